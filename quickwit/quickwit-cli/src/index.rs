@@ -43,7 +43,7 @@ use quickwit_config::{ConfigFormat, IndexConfig};
 use quickwit_indexing::models::IndexingStatistics;
 use quickwit_indexing::IndexingPipeline;
 use quickwit_metastore::{IndexMetadata, Split, SplitState};
-use quickwit_proto::search::{CountHits, SortField, SortOrder};
+use quickwit_proto::search::{CountHits, SortField, SortMissing, SortOrder};
 use quickwit_proto::types::IndexId;
 use quickwit_rest_client::models::IngestSource;
 use quickwit_rest_client::rest_client::{CommitType, IngestEvent};
@@ -203,6 +203,20 @@ pub fn build_index_command() -> Command {
                         .required(false),
                 ])
             )
+        .subcommand(
+            Command::new("sql")
+                .display_order(9)
+                .about("Runs a SQL query against an index.")
+                .args(&[
+                    arg!(--query <SQL> "SQL `SELECT` statement, e.g. \"SELECT service, COUNT(*) FROM logs GROUP BY service\". Learn more on https://quickwit.io/docs/reference/rest-api.")
+                        .display_order(1)
+                        .required(true),
+                    arg!(--format <FORMAT> "Output format of the result set.")
+                        .value_parser(["json", "csv"])
+                        .default_value("json")
+                        .required(false),
+                ])
+            )
         .arg_required_else_help(true)
 }
 
@@ -259,6 +273,13 @@ pub struct SearchIndexArgs {
     pub sort_by_score: bool,
 }
 
+#[derive(Debug, Eq, PartialEq)]
+pub struct SqlQueryArgs {
+    pub client_args: ClientArgs,
+    pub query: String,
+    pub csv: bool,
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub struct DeleteIndexArgs {
     pub client_args: ClientArgs,
@@ -282,12 +303,13 @@ pub enum IndexCliCommand {
     Ingest(IngestDocsArgs),
     List(ListIndexesArgs),
     Search(SearchIndexArgs),
+    Sql(SqlQueryArgs),
 }
 
 impl IndexCliCommand {
     pub fn default_log_level(&self) -> Level {
         match self {
-            Self::Search(_) => Level::ERROR,
+            Self::Search(_) | Self::Sql(_) => Level::ERROR,
             _ => Level::INFO,
         }
     }
@@ -304,6 +326,7 @@ impl IndexCliCommand {
             "ingest" => Self::parse_ingest_args(submatches),
             "list" => Self::parse_list_args(submatches),
             "search" => Self::parse_search_args(submatches),
+            "sql" => Self::parse_sql_args(submatches),
             "update" => Self::parse_update_args(submatches),
             _ => bail!("unknown index subcommand `{subcommand}`"),
         }
@@ -460,6 +483,22 @@ impl IndexCliCommand {
         }))
     }
 
+    fn parse_sql_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
+        let query = matches
+            .remove_one::<String>("query")
+            .context("`query` should be a required arg")?;
+        let csv = matches
+            .remove_one::<String>("format")
+            .expect("`format` should have a default value.")
+            == "csv";
+        let client_args = ClientArgs::parse(&mut matches)?;
+        Ok(Self::Sql(SqlQueryArgs {
+            client_args,
+            query,
+            csv,
+        }))
+    }
+
     fn parse_delete_args(mut matches: ArgMatches) -> anyhow::Result<Self> {
         let client_args = ClientArgs::parse(&mut matches)?;
         let index_id = matches
@@ -484,6 +523,7 @@ impl IndexCliCommand {
             Self::Ingest(args) => ingest_docs_cli(args).await,
             Self::List(args) => list_index_cli(args).await,
             Self::Search(args) => search_index_cli(args).await,
+            Self::Sql(args) => sql_query_cli(args).await,
             Self::Update(args) => update_index_cli(args).await,
         }
     }
@@ -1080,6 +1120,7 @@ pub async fn search_index(args: SearchIndexArgs) -> anyhow::Result<SearchRespons
                 field_name: "_score".to_string(),
                 sort_order: SortOrder::Desc as i32,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             }],
         })
         .unwrap_or_default();
@@ -1109,6 +1150,20 @@ pub async fn search_index_cli(args: SearchIndexArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+pub async fn sql_query_cli(args: SqlQueryArgs) -> anyhow::Result<()> {
+    debug!(args=?args, "sql-query");
+    let qw_client = args.client_args.client();
+    let sql_response = qw_client.sql(&args.query).await?;
+
+    if args.csv {
+        print!("{}", sql_response.to_csv());
+    } else {
+        let sql_response_json = serde_json::to_string_pretty(&sql_response)?;
+        println!("{sql_response_json}");
+    }
+    Ok(())
+}
+
 pub async fn delete_index_cli(args: DeleteIndexArgs) -> anyhow::Result<()> {
     debug!(args=?args, "delete-index");
     if !args.dry_run && !args.assume_yes {
@@ -136,7 +136,7 @@ mod tests {
     use quickwit_cli::cli::{build_cli, CliCommand};
     use quickwit_cli::index::{
         ClearIndexArgs, CreateIndexArgs, DeleteIndexArgs, DescribeIndexArgs, IndexCliCommand,
-        IngestDocsArgs, SearchIndexArgs,
+        IngestDocsArgs, SearchIndexArgs, SqlQueryArgs,
     };
     use quickwit_cli::split::{DescribeSplitArgs, SplitCliCommand};
     use quickwit_cli::tool::{
@@ -523,6 +523,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_sql_args() -> anyhow::Result<()> {
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "index",
+            "sql",
+            "--query",
+            "SELECT COUNT(*) FROM wikipedia",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Sql(SqlQueryArgs {
+                query,
+                csv: false,
+                ..
+            })) if query == "SELECT COUNT(*) FROM wikipedia"
+        ));
+
+        let app = build_cli().no_binary_name(true);
+        let matches = app.try_get_matches_from([
+            "index",
+            "sql",
+            "--query",
+            "SELECT title FROM wikipedia",
+            "--format",
+            "csv",
+        ])?;
+        let command = CliCommand::parse_cli_args(matches)?;
+        assert!(matches!(
+            command,
+            CliCommand::Index(IndexCliCommand::Sql(SqlQueryArgs { csv: true, .. }))
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_parse_local_search_args() {
         let app = build_cli().no_binary_name(true);
@@ -544,12 +544,24 @@ pub async fn local_search_cli(args: LocalSearchArgs) -> anyhow::Result<()> {
         max_hits: args.max_hits as u64,
         search_fields: args.search_fields,
         snippet_fields: args.snippet_fields,
+        snippet_pre_tag: None,
+        snippet_post_tag: None,
+        snippet_fragment_size: None,
         start_timestamp: args.start_timestamp,
         end_timestamp: args.end_timestamp,
         aggs,
         format: BodyFormat::Json,
+        csv_fields: None,
         sort_by,
+        search_after: None,
+        collapse_field: None,
+        collapse_inner_hits: None,
+        timeout: None,
+        priority: None,
         count_all: CountHits::CountAll,
+        allow_leading_wildcard: false,
+        explain: false,
+        profile: false,
     };
     let search_request =
         search_request_from_api_request(vec![args.index_id], search_request_query_string)?;
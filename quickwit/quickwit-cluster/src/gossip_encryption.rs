@@ -0,0 +1,490 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Encryption and authentication of the gossip messages exchanged over UDP.
+//!
+//! Encrypted datagrams are laid out as follows:
+//! `magic (3 bytes) | format (1 byte) | nonce (12 bytes) | ciphertext | tag (16 bytes)`.
+//! The payload is a serialized chitchat message, compressed with zstd when it would not fit in a
+//! UDP datagram otherwise, and sealed with ChaCha20-Poly1305. The magic and the format are
+//! authenticated along with the payload. Serialized chitchat messages never start with the magic
+//! bytes, which tells the encrypted datagrams apart from the plaintext ones.
+
+use std::borrow::Cow;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, ensure, Context};
+use async_trait::async_trait;
+use chitchat::transport::{Socket, UdpSocket};
+use chitchat::{ChitchatMessage, Serializable};
+use quickwit_common::rate_limited_tracing::rate_limited_warn;
+use quickwit_config::{GossipConfig, GossipEncryptionMode};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+const MAGIC: &[u8; 3] = b"QWG";
+
+const HEADER_LEN: usize = MAGIC.len() + 1;
+
+const TAG_LEN: usize = 16;
+
+const ENCRYPTION_OVERHEAD: usize = HEADER_LEN + NONCE_LEN + TAG_LEN;
+
+/// The payload is the serialized chitchat message.
+const RAW_FORMAT: u8 = 1;
+
+/// The payload is the serialized chitchat message compressed with zstd.
+const ZSTD_FORMAT: u8 = 2;
+
+/// Maximum payload size of a UDP datagram over IPv4, which is also the maximum size of the
+/// chitchat messages.
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Maximum time spent deserializing a message on the loopback interface.
+const DECODE_TIMEOUT: Duration = Duration::from_secs(1);
+
+pub(crate) struct GossipCipher {
+    mode: GossipEncryptionMode,
+    // The first key encrypts the outgoing messages, all of them are tried to decrypt the incoming
+    // ones.
+    keys: Vec<LessSafeKey>,
+    rng: SystemRandom,
+}
+
+impl GossipCipher {
+    /// Returns `None` when gossip encryption is disabled.
+    pub fn from_config(gossip_config: &GossipConfig) -> anyhow::Result<Option<Self>> {
+        if gossip_config.encryption == GossipEncryptionMode::Disabled {
+            return Ok(None);
+        }
+        let keys: Vec<LessSafeKey> = gossip_config
+            .decode_secret_keys()?
+            .iter()
+            .map(|key_bytes| {
+                UnboundKey::new(&CHACHA20_POLY1305, key_bytes)
+                    .map(LessSafeKey::new)
+                    .map_err(|_| anyhow!("invalid gossip secret key"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        ensure!(
+            !keys.is_empty(),
+            "gossip encryption requires at least one secret key"
+        );
+        let cipher = Self {
+            mode: gossip_config.encryption,
+            keys,
+            rng: SystemRandom::new(),
+        };
+        Ok(Some(cipher))
+    }
+
+    fn encrypts_outgoing_messages(&self) -> bool {
+        matches!(
+            self.mode,
+            GossipEncryptionMode::Permissive | GossipEncryptionMode::Strict
+        )
+    }
+
+    fn accepts_plaintext_messages(&self) -> bool {
+        self.mode != GossipEncryptionMode::Strict
+    }
+
+    fn is_encrypted(datagram: &[u8]) -> bool {
+        datagram.starts_with(MAGIC)
+    }
+
+    /// Encrypts and authenticates a serialized chitchat message into `datagram`.
+    fn seal(&self, plaintext: &[u8], datagram: &mut Vec<u8>) -> anyhow::Result<()> {
+        let (format, payload) = if plaintext.len() + ENCRYPTION_OVERHEAD <= MAX_DATAGRAM_SIZE {
+            (RAW_FORMAT, Cow::Borrowed(plaintext))
+        } else {
+            let compressed_plaintext =
+                zstd::bulk::compress(plaintext, 1).context("failed to compress gossip message")?;
+            (ZSTD_FORMAT, Cow::Owned(compressed_plaintext))
+        };
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce_bytes)
+            .map_err(|_| anyhow!("failed to generate gossip message nonce"))?;
+
+        datagram.clear();
+        datagram.extend_from_slice(MAGIC);
+        datagram.push(format);
+        datagram.extend_from_slice(&nonce_bytes);
+        datagram.extend_from_slice(&payload);
+
+        let (header_and_nonce, in_out) = datagram.split_at_mut(HEADER_LEN + NONCE_LEN);
+        let tag = self.keys[0]
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(nonce_bytes),
+                Aad::from(&header_and_nonce[..HEADER_LEN]),
+                in_out,
+            )
+            .map_err(|_| anyhow!("failed to encrypt gossip message"))?;
+        datagram.extend_from_slice(tag.as_ref());
+        Ok(())
+    }
+
+    /// Authenticates and decrypts an encrypted datagram into a serialized chitchat message.
+    fn open(&self, datagram: &[u8]) -> anyhow::Result<Vec<u8>> {
+        ensure!(
+            datagram.len() >= ENCRYPTION_OVERHEAD,
+            "encrypted gossip message is truncated"
+        );
+        let (header, nonce_and_ciphertext) = datagram.split_at(HEADER_LEN);
+        let format = header[MAGIC.len()];
+
+        if format != RAW_FORMAT && format != ZSTD_FORMAT {
+            bail!("unknown encrypted gossip message format `{format}`");
+        }
+        let (nonce_slice, ciphertext) = nonce_and_ciphertext.split_at(NONCE_LEN);
+        let nonce_bytes: [u8; NONCE_LEN] = nonce_slice
+            .try_into()
+            .expect("nonce slice should be `NONCE_LEN` bytes long");
+
+        for key in &self.keys {
+            // `open_in_place` leaves the buffer in an unspecified state on failure.
+            let mut in_out = ciphertext.to_vec();
+
+            let Ok(payload) = key.open_in_place(
+                Nonce::assume_unique_for_key(nonce_bytes),
+                Aad::from(header),
+                &mut in_out,
+            ) else {
+                continue;
+            };
+            let payload_len = payload.len();
+            in_out.truncate(payload_len);
+
+            if format == RAW_FORMAT {
+                return Ok(in_out);
+            }
+            return zstd::bulk::decompress(&in_out, MAX_DATAGRAM_SIZE)
+                .context("failed to decompress gossip message");
+        }
+        bail!("gossip message is not authenticated by any of the secret keys")
+    }
+}
+
+/// Deserializes chitchat messages.
+///
+/// chitchat does not expose the deserialization of its messages, so the payloads are forwarded to
+/// a chitchat UDP socket listening on the loopback interface, which deserializes them.
+struct LoopbackDecoder {
+    forwarder: tokio::net::UdpSocket,
+    forwarder_addr: SocketAddr,
+    decoder: UdpSocket,
+    decoder_addr: SocketAddr,
+}
+
+impl LoopbackDecoder {
+    async fn open() -> anyhow::Result<Self> {
+        let forwarder = tokio::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let forwarder_addr = forwarder.local_addr()?;
+        // The chitchat socket does not expose its local address, so we have to pick its port.
+        let decoder_addr = std::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))?.local_addr()?;
+        let decoder = UdpSocket::open(decoder_addr).await?;
+        let loopback_decoder = Self {
+            forwarder,
+            forwarder_addr,
+            decoder,
+            decoder_addr,
+        };
+        Ok(loopback_decoder)
+    }
+
+    async fn decode(&mut self, payload: &[u8]) -> anyhow::Result<ChitchatMessage> {
+        self.forwarder.send_to(payload, self.decoder_addr).await?;
+
+        let decode_future = async {
+            loop {
+                let (from_addr, message) = self.decoder.recv().await?;
+                // Other processes of the host may send datagrams to the decoder.
+                if from_addr == self.forwarder_addr {
+                    return anyhow::Ok(message);
+                }
+            }
+        };
+        // The chitchat socket silently drops the payloads it fails to deserialize.
+        tokio::time::timeout(DECODE_TIMEOUT, decode_future)
+            .await
+            .context("failed to deserialize gossip message")?
+    }
+}
+
+/// UDP socket encrypting and authenticating the gossip messages according to the gossip
+/// encryption mode of the node.
+pub(crate) struct EncryptedUdpSocket {
+    socket: tokio::net::UdpSocket,
+    cipher: Arc<GossipCipher>,
+    loopback_decoder: LoopbackDecoder,
+    send_buffer: Vec<u8>,
+    datagram_buffer: Vec<u8>,
+    recv_buffer: Box<[u8]>,
+}
+
+impl EncryptedUdpSocket {
+    pub async fn open(listen_addr: SocketAddr, cipher: Arc<GossipCipher>) -> anyhow::Result<Self> {
+        let socket = tokio::net::UdpSocket::bind(listen_addr)
+            .await
+            .with_context(|| format!("failed to bind gossip socket to `{listen_addr}`"))?;
+        let loopback_decoder = LoopbackDecoder::open().await?;
+        let encrypted_socket = Self {
+            socket,
+            cipher,
+            loopback_decoder,
+            send_buffer: Vec::with_capacity(MAX_DATAGRAM_SIZE),
+            datagram_buffer: Vec::with_capacity(MAX_DATAGRAM_SIZE),
+            recv_buffer: vec![0u8; u16::MAX as usize].into_boxed_slice(),
+        };
+        Ok(encrypted_socket)
+    }
+}
+
+#[async_trait]
+impl Socket for EncryptedUdpSocket {
+    async fn send(&mut self, to: SocketAddr, msg: ChitchatMessage) -> anyhow::Result<()> {
+        self.send_buffer.clear();
+        msg.serialize(&mut self.send_buffer);
+
+        let datagram = if self.cipher.encrypts_outgoing_messages() {
+            self.cipher
+                .seal(&self.send_buffer, &mut self.datagram_buffer)?;
+            &self.datagram_buffer
+        } else {
+            &self.send_buffer
+        };
+        self.socket
+            .send_to(datagram, to)
+            .await
+            .with_context(|| format!("failed to send gossip message to `{to}`"))?;
+        Ok(())
+    }
+
+    async fn recv(&mut self) -> anyhow::Result<(SocketAddr, ChitchatMessage)> {
+        loop {
+            let (datagram_len, from_addr) = self.socket.recv_from(&mut self.recv_buffer).await?;
+            let datagram = &self.recv_buffer[..datagram_len];
+
+            let payload = if GossipCipher::is_encrypted(datagram) {
+                match self.cipher.open(datagram) {
+                    Ok(payload) => Cow::Owned(payload),
+                    Err(error) => {
+                        rate_limited_warn!(
+                            limit_per_min = 10,
+                            from_addr=%from_addr,
+                            "dropped gossip message: {error}"
+                        );
+                        continue;
+                    }
+                }
+            } else if self.cipher.accepts_plaintext_messages() {
+                Cow::Borrowed(datagram)
+            } else {
+                rate_limited_warn!(
+                    limit_per_min = 10,
+                    from_addr=%from_addr,
+                    "dropped plaintext gossip message: gossip encryption mode is `strict`"
+                );
+                continue;
+            };
+            match self.loopback_decoder.decode(&payload).await {
+                Ok(message) => return Ok((from_addr, message)),
+                Err(error) => {
+                    rate_limited_warn!(
+                        limit_per_min = 10,
+                        from_addr=%from_addr,
+                        "dropped gossip message: {error}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::prelude::{Engine, BASE64_STANDARD};
+
+    use super::*;
+
+    fn gossip_cipher(mode: GossipEncryptionMode, keys: &[[u8; 32]]) -> Arc<GossipCipher> {
+        let gossip_config = GossipConfig {
+            encryption: mode,
+            secret_keys: keys.iter().map(|key| BASE64_STANDARD.encode(key)).collect(),
+        };
+        let cipher = GossipCipher::from_config(&gossip_config).unwrap().unwrap();
+        Arc::new(cipher)
+    }
+
+    fn find_available_loopback_addr() -> SocketAddr {
+        std::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_gossip_cipher_from_config() {
+        let gossip_config = GossipConfig::default();
+        assert!(GossipCipher::from_config(&gossip_config).unwrap().is_none());
+
+        let cipher = gossip_cipher(GossipEncryptionMode::Passive, &[[1u8; 32]]);
+        assert!(!cipher.encrypts_outgoing_messages());
+        assert!(cipher.accepts_plaintext_messages());
+
+        let cipher = gossip_cipher(GossipEncryptionMode::Permissive, &[[1u8; 32]]);
+        assert!(cipher.encrypts_outgoing_messages());
+        assert!(cipher.accepts_plaintext_messages());
+
+        let cipher = gossip_cipher(GossipEncryptionMode::Strict, &[[1u8; 32]]);
+        assert!(cipher.encrypts_outgoing_messages());
+        assert!(!cipher.accepts_plaintext_messages());
+    }
+
+    #[test]
+    fn test_gossip_cipher_seal_open() {
+        let cipher = gossip_cipher(GossipEncryptionMode::Strict, &[[1u8; 32]]);
+        let plaintext = b"\x00hello";
+
+        let mut datagram = Vec::new();
+        cipher.seal(plaintext, &mut datagram).unwrap();
+        assert!(GossipCipher::is_encrypted(&datagram));
+        assert_eq!(datagram.len(), plaintext.len() + ENCRYPTION_OVERHEAD);
+        assert!(!datagram
+            .windows(plaintext.len())
+            .any(|window| window == plaintext));
+        assert_eq!(cipher.open(&datagram).unwrap(), plaintext);
+
+        // The nonces are random, so sealing the same message twice yields different datagrams.
+        let mut other_datagram = Vec::new();
+        cipher.seal(plaintext, &mut other_datagram).unwrap();
+        assert_ne!(datagram, other_datagram);
+
+        // Tampering with the header, the nonce, the ciphertext, or the tag is detected.
+        for pos in [
+            MAGIC.len(),
+            HEADER_LEN,
+            HEADER_LEN + NONCE_LEN,
+            datagram.len() - 1,
+        ] {
+            let mut tampered_datagram = datagram.clone();
+            tampered_datagram[pos] ^= 1;
+            cipher.open(&tampered_datagram).unwrap_err();
+        }
+        cipher
+            .open(&datagram[..ENCRYPTION_OVERHEAD - 1])
+            .unwrap_err();
+
+        let other_cipher = gossip_cipher(GossipEncryptionMode::Strict, &[[2u8; 32]]);
+        let error = other_cipher.open(&datagram).unwrap_err();
+        assert!(error.to_string().contains("not authenticated"));
+    }
+
+    #[test]
+    fn test_gossip_cipher_key_rotation() {
+        let old_cipher = gossip_cipher(GossipEncryptionMode::Strict, &[[1u8; 32]]);
+        let rotating_cipher = gossip_cipher(GossipEncryptionMode::Strict, &[[2u8; 32], [1u8; 32]]);
+        let new_cipher = gossip_cipher(GossipEncryptionMode::Strict, &[[2u8; 32]]);
+
+        let mut datagram = Vec::new();
+        old_cipher.seal(b"old", &mut datagram).unwrap();
+        assert_eq!(rotating_cipher.open(&datagram).unwrap(), b"old");
+        new_cipher.open(&datagram).unwrap_err();
+
+        rotating_cipher.seal(b"new", &mut datagram).unwrap();
+        assert_eq!(new_cipher.open(&datagram).unwrap(), b"new");
+        old_cipher.open(&datagram).unwrap_err();
+    }
+
+    #[test]
+    fn test_gossip_cipher_compresses_large_messages() {
+        let cipher = gossip_cipher(GossipEncryptionMode::Strict, &[[1u8; 32]]);
+        let plaintext = vec![3u8; MAX_DATAGRAM_SIZE];
+
+        let mut datagram = Vec::new();
+        cipher.seal(&plaintext, &mut datagram).unwrap();
+        assert_eq!(datagram[MAGIC.len()], ZSTD_FORMAT);
+        assert!(datagram.len() <= MAX_DATAGRAM_SIZE);
+        assert_eq!(cipher.open(&datagram).unwrap(), plaintext);
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_udp_socket() {
+        let keys = [[1u8; 32]];
+
+        let strict_addr = find_available_loopback_addr();
+        let strict_cipher = gossip_cipher(GossipEncryptionMode::Strict, &keys);
+        let mut strict_socket = EncryptedUdpSocket::open(strict_addr, strict_cipher)
+            .await
+            .unwrap();
+
+        let permissive_addr = find_available_loopback_addr();
+        let permissive_cipher = gossip_cipher(GossipEncryptionMode::Permissive, &keys);
+        let mut permissive_socket = EncryptedUdpSocket::open(permissive_addr, permissive_cipher)
+            .await
+            .unwrap();
+
+        let plaintext_addr = find_available_loopback_addr();
+        let mut plaintext_socket = UdpSocket::open(plaintext_addr).await.unwrap();
+
+        let intruder_addr = find_available_loopback_addr();
+        let intruder_cipher = gossip_cipher(GossipEncryptionMode::Strict, &[[2u8; 32]]);
+        let mut intruder_socket = EncryptedUdpSocket::open(intruder_addr, intruder_cipher)
+            .await
+            .unwrap();
+
+        // The permissive socket accepts both plaintext and encrypted messages.
+        plaintext_socket
+            .send(permissive_addr, ChitchatMessage::BadCluster)
+            .await
+            .unwrap();
+        let (from_addr, message) = permissive_socket.recv().await.unwrap();
+        assert_eq!(from_addr, plaintext_addr);
+        assert!(matches!(message, ChitchatMessage::BadCluster));
+
+        strict_socket
+            .send(permissive_addr, ChitchatMessage::BadCluster)
+            .await
+            .unwrap();
+        let (from_addr, message) = permissive_socket.recv().await.unwrap();
+        assert_eq!(from_addr, strict_addr);
+        assert!(matches!(message, ChitchatMessage::BadCluster));
+
+        // The strict socket drops the plaintext messages and the messages encrypted with another
+        // key.
+        plaintext_socket
+            .send(strict_addr, ChitchatMessage::BadCluster)
+            .await
+            .unwrap();
+        intruder_socket
+            .send(strict_addr, ChitchatMessage::BadCluster)
+            .await
+            .unwrap();
+        permissive_socket
+            .send(strict_addr, ChitchatMessage::BadCluster)
+            .await
+            .unwrap();
+        let (from_addr, message) = strict_socket.recv().await.unwrap();
+        assert_eq!(from_addr, permissive_addr);
+        assert!(matches!(message, ChitchatMessage::BadCluster));
+    }
+}
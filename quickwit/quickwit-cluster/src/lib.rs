@@ -21,6 +21,7 @@
 
 mod change;
 mod cluster;
+mod gossip_encryption;
 mod grpc_gossip;
 mod grpc_service;
 mod member;
@@ -28,6 +29,7 @@ mod metrics;
 mod node;
 
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -50,6 +52,7 @@ pub use crate::cluster::{
     create_cluster_for_test, create_cluster_for_test_with_id, grpc_addr_from_listen_addr_for_test,
 };
 pub use crate::cluster::{Cluster, ClusterSnapshot, NodeIdSchema};
+use crate::gossip_encryption::{EncryptedUdpSocket, GossipCipher};
 pub use crate::member::{ClusterMember, INDEXING_CPU_CAPACITY_KEY};
 pub use crate::node::ClusterNode;
 
@@ -72,10 +75,13 @@ impl From<u64> for GenerationId {
     }
 }
 
-struct CountingUdpTransport;
+struct CountingUdpTransport {
+    // `None` when gossip encryption is disabled.
+    gossip_cipher_opt: Option<Arc<GossipCipher>>,
+}
 
 struct CountingUdpSocket {
-    socket: UdpSocket,
+    socket: Box<dyn Socket>,
     gossip_recv: IntCounter,
     gossip_recv_bytes: IntCounter,
     gossip_send: IntCounter,
@@ -104,7 +110,11 @@ impl Socket for CountingUdpSocket {
 #[async_trait]
 impl Transport for CountingUdpTransport {
     async fn open(&self, listen_addr: SocketAddr) -> anyhow::Result<Box<dyn Socket>> {
-        let socket = UdpSocket::open(listen_addr).await?;
+        let socket: Box<dyn Socket> = if let Some(gossip_cipher) = &self.gossip_cipher_opt {
+            Box::new(EncryptedUdpSocket::open(listen_addr, gossip_cipher.clone()).await?)
+        } else {
+            Box::new(UdpSocket::open(listen_addr).await?)
+        };
         Ok(Box::new(CountingUdpSocket {
             socket,
             gossip_recv: crate::metrics::CLUSTER_METRICS
@@ -151,6 +161,9 @@ pub async fn start_cluster_service(node_config: &NodeConfig) -> anyhow::Result<C
         dead_node_grace_period: Duration::from_secs(2 * 60 * 60), // 2 hours
         ..Default::default()
     };
+    let transport = CountingUdpTransport {
+        gossip_cipher_opt: GossipCipher::from_config(&node_config.gossip_config)?.map(Arc::new),
+    };
     let cluster = Cluster::join(
         cluster_id,
         self_node,
@@ -158,7 +171,7 @@ pub async fn start_cluster_service(node_config: &NodeConfig) -> anyhow::Result<C
         peer_seed_addrs,
         node_config.gossip_interval,
         failure_detector_config,
-        &CountingUdpTransport,
+        &transport,
     )
     .await?;
     if node_config
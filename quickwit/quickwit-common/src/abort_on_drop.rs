@@ -0,0 +1,76 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::task::{JoinError, JoinHandle};
+
+/// A [`JoinHandle`] that aborts its task when it is dropped.
+///
+/// Dropping a regular `JoinHandle` detaches the task, which keeps running in the background.
+/// This wrapper is useful when the spawned work is only meaningful to the spawner, e.g. to stop
+/// searching splits as soon as the request that triggered the search is cancelled.
+pub struct AbortOnDropJoinHandle<T>(JoinHandle<T>);
+
+impl<T> AbortOnDropJoinHandle<T> {
+    pub fn new(join_handle: JoinHandle<T>) -> Self {
+        AbortOnDropJoinHandle(join_handle)
+    }
+}
+
+impl<T> Drop for AbortOnDropJoinHandle<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl<T> Future for AbortOnDropJoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_abort_on_drop_join_handle() {
+        let join_handle = AbortOnDropJoinHandle::new(tokio::spawn(async { 42 }));
+        assert_eq!(join_handle.await.unwrap(), 42);
+
+        let completed = Arc::new(AtomicBool::new(false));
+        let completed_clone = completed.clone();
+        let join_handle = AbortOnDropJoinHandle::new(tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            completed_clone.store(true, Ordering::Relaxed);
+        }));
+        drop(join_handle);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!completed.load(Ordering::Relaxed));
+    }
+}
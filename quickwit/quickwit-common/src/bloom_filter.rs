@@ -0,0 +1,245 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+use std::hash::Hasher;
+use std::io;
+
+use siphasher::sip::SipHasher;
+
+const MAX_NUM_HASHES: u32 = 16;
+
+/// Version of the serialization format of [`SplitBloomFilters`].
+const SPLIT_BLOOM_FILTERS_FORMAT_VERSION: u8 = 1;
+
+/// A Bloom filter over byte strings.
+///
+/// The hash function is fixed, so that a filter serialized by a node can be read by any other
+/// node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BloomFilter {
+    num_hashes: u32,
+    words: Vec<u64>,
+}
+
+impl BloomFilter {
+    /// Creates an empty filter sized so that, once `num_items` items are inserted, the
+    /// probability for `contains` to return a false positive is about `false_positive_rate`.
+    pub fn with_num_items(num_items: usize, false_positive_rate: f64) -> BloomFilter {
+        let num_items = num_items.max(1) as f64;
+        let ln_2 = std::f64::consts::LN_2;
+        let num_bits = (-num_items * false_positive_rate.ln() / (ln_2 * ln_2)).ceil();
+        let num_words = ((num_bits / 64.0).ceil() as usize).max(1);
+        let num_hashes = ((num_words * 64) as f64 / num_items * ln_2).round() as u32;
+        BloomFilter {
+            num_hashes: num_hashes.clamp(1, MAX_NUM_HASHES),
+            words: vec![0; num_words],
+        }
+    }
+
+    fn num_bits(&self) -> u64 {
+        self.words.len() as u64 * 64
+    }
+
+    /// Returns the positions of the bits of an item, using double hashing.
+    fn bit_positions(&self, item: &[u8]) -> impl Iterator<Item = u64> {
+        let mut hasher = SipHasher::new();
+        hasher.write(item);
+        let hash = hasher.finish();
+        let hash1 = hash & u32::MAX as u64;
+        let hash2 = hash >> 32;
+        let num_bits = self.num_bits();
+        (0..self.num_hashes as u64)
+            .map(move |index| hash1.wrapping_add(index.wrapping_mul(hash2)) % num_bits)
+    }
+
+    /// Inserts an item into the filter.
+    pub fn insert(&mut self, item: &[u8]) {
+        for bit_position in self.bit_positions(item) {
+            self.words[(bit_position / 64) as usize] |= 1 << (bit_position % 64);
+        }
+    }
+
+    /// Returns false if the item was never inserted. Returns true if the item was probably
+    /// inserted.
+    pub fn contains(&self, item: &[u8]) -> bool {
+        self.bit_positions(item).all(|bit_position| {
+            self.words[(bit_position / 64) as usize] & (1 << (bit_position % 64)) != 0
+        })
+    }
+
+    /// Returns the size of the filter in bytes.
+    pub fn num_bytes(&self) -> usize {
+        self.words.len() * 8
+    }
+}
+
+/// The Bloom filters of the fields of a split, built over the terms of each field.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SplitBloomFilters {
+    bloom_filters: BTreeMap<String, BloomFilter>,
+}
+
+impl SplitBloomFilters {
+    /// Adds the Bloom filter of a field.
+    pub fn insert(&mut self, field_name: String, bloom_filter: BloomFilter) {
+        self.bloom_filters.insert(field_name, bloom_filter);
+    }
+
+    /// Returns whether the split has a Bloom filter for the field.
+    pub fn has_field(&self, field_name: &str) -> bool {
+        self.bloom_filters.contains_key(field_name)
+    }
+
+    /// Returns false if the field has a Bloom filter and the term is not in the filter.
+    pub fn may_contain(&self, field_name: &str, term: &[u8]) -> bool {
+        self.bloom_filters
+            .get(field_name)
+            .map(|bloom_filter| bloom_filter.contains(term))
+            .unwrap_or(true)
+    }
+
+    /// Returns true if the split has no Bloom filters.
+    pub fn is_empty(&self) -> bool {
+        self.bloom_filters.is_empty()
+    }
+
+    /// Serializes the Bloom filters.
+    pub fn serialize(&self) -> Vec<u8> {
+        let num_bytes: usize = self
+            .bloom_filters
+            .iter()
+            .map(|(field_name, bloom_filter)| 12 + field_name.len() + bloom_filter.num_bytes())
+            .sum();
+        let mut buffer = Vec::with_capacity(5 + num_bytes);
+        buffer.push(SPLIT_BLOOM_FILTERS_FORMAT_VERSION);
+        buffer.extend_from_slice(&(self.bloom_filters.len() as u32).to_le_bytes());
+        for (field_name, bloom_filter) in &self.bloom_filters {
+            buffer.extend_from_slice(&(field_name.len() as u32).to_le_bytes());
+            buffer.extend_from_slice(field_name.as_bytes());
+            buffer.extend_from_slice(&bloom_filter.num_hashes.to_le_bytes());
+            buffer.extend_from_slice(&(bloom_filter.words.len() as u32).to_le_bytes());
+            for word in &bloom_filter.words {
+                buffer.extend_from_slice(&word.to_le_bytes());
+            }
+        }
+        buffer
+    }
+
+    /// Deserializes Bloom filters serialized with [`SplitBloomFilters::serialize`].
+    pub fn deserialize(mut bytes: &[u8]) -> io::Result<SplitBloomFilters> {
+        let format_version = read_bytes::<1>(&mut bytes)?[0];
+        if format_version != SPLIT_BLOOM_FILTERS_FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported split Bloom filters format version `{format_version}`"),
+            ));
+        }
+        let num_bloom_filters = read_u32(&mut bytes)?;
+        let mut bloom_filters = BTreeMap::new();
+        for _ in 0..num_bloom_filters {
+            let field_name_len = read_u32(&mut bytes)? as usize;
+            let field_name = String::from_utf8(read_slice(&mut bytes, field_name_len)?.to_vec())
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            let num_hashes = read_u32(&mut bytes)?;
+            let num_words = read_u32(&mut bytes)? as usize;
+            let words = read_slice(&mut bytes, num_words * 8)?
+                .chunks_exact(8)
+                .map(|word_bytes| u64::from_le_bytes(word_bytes.try_into().unwrap()))
+                .collect();
+            bloom_filters.insert(field_name, BloomFilter { num_hashes, words });
+        }
+        Ok(SplitBloomFilters { bloom_filters })
+    }
+}
+
+fn read_slice<'a>(bytes: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+    if bytes.len() < len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "split Bloom filters are truncated",
+        ));
+    }
+    let (slice, remaining_bytes) = bytes.split_at(len);
+    *bytes = remaining_bytes;
+    Ok(slice)
+}
+
+fn read_bytes<const N: usize>(bytes: &mut &[u8]) -> io::Result<[u8; N]> {
+    Ok(read_slice(bytes, N)?.try_into().unwrap())
+}
+
+fn read_u32(bytes: &mut &[u8]) -> io::Result<u32> {
+    read_bytes::<4>(bytes).map(u32::from_le_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_filter() {
+        let mut bloom_filter = BloomFilter::with_num_items(1_000, 0.01);
+        for item in 0..1_000u32 {
+            bloom_filter.insert(format!("trace-{item}").as_bytes());
+        }
+        for item in 0..1_000u32 {
+            assert!(bloom_filter.contains(format!("trace-{item}").as_bytes()));
+        }
+        let num_false_positives = (1_000..11_000u32)
+            .filter(|item| bloom_filter.contains(format!("trace-{item}").as_bytes()))
+            .count();
+        // The expected number of false positives is 100.
+        assert!(num_false_positives < 200, "{num_false_positives}");
+    }
+
+    #[test]
+    fn test_bloom_filter_empty() {
+        let bloom_filter = BloomFilter::with_num_items(0, 0.01);
+        assert!(!bloom_filter.contains(b"trace-1"));
+    }
+
+    #[test]
+    fn test_split_bloom_filters_serialization() {
+        let mut trace_id_bloom_filter = BloomFilter::with_num_items(2, 0.01);
+        trace_id_bloom_filter.insert(b"trace-1");
+        trace_id_bloom_filter.insert(b"trace-2");
+
+        let mut split_bloom_filters = SplitBloomFilters::default();
+        split_bloom_filters.insert("trace_id".to_string(), trace_id_bloom_filter);
+        split_bloom_filters.insert(
+            "request_id".to_string(),
+            BloomFilter::with_num_items(0, 0.01),
+        );
+        let serialized_split_bloom_filters = split_bloom_filters.serialize();
+        let deserialized_split_bloom_filters =
+            SplitBloomFilters::deserialize(&serialized_split_bloom_filters).unwrap();
+        assert_eq!(deserialized_split_bloom_filters, split_bloom_filters);
+
+        assert!(deserialized_split_bloom_filters.has_field("trace_id"));
+        assert!(!deserialized_split_bloom_filters.has_field("span_id"));
+        assert!(deserialized_split_bloom_filters.may_contain("trace_id", b"trace-1"));
+        assert!(!deserialized_split_bloom_filters.may_contain("trace_id", b"trace-3"));
+        assert!(!deserialized_split_bloom_filters.may_contain("request_id", b"request-1"));
+        // Without a Bloom filter, the field may contain any term.
+        assert!(deserialized_split_bloom_filters.may_contain("span_id", b"span-1"));
+
+        SplitBloomFilters::deserialize(&serialized_split_bloom_filters[..10]).unwrap_err();
+    }
+}
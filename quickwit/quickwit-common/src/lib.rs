@@ -19,9 +19,11 @@
 
 #![deny(clippy::disallowed_methods)]
 
+mod abort_on_drop;
 mod coolid;
 
 pub mod binary_heap;
+pub mod bloom_filter;
 pub mod fs;
 pub mod io;
 mod kill_switch;
@@ -44,6 +46,7 @@ pub mod temp_dir;
 #[cfg(any(test, feature = "testsuite"))]
 pub mod test_utils;
 pub mod thread_pool;
+pub mod tls;
 pub mod tower;
 pub mod type_map;
 pub mod uri;
@@ -54,6 +57,7 @@ use std::future::Future;
 use std::ops::{Range, RangeInclusive};
 use std::str::FromStr;
 
+pub use abort_on_drop::AbortOnDropJoinHandle;
 pub use coolid::new_coolid;
 pub use kill_switch::KillSwitch;
 pub use path_hasher::PathHasher;
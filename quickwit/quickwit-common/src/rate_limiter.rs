@@ -104,8 +104,6 @@ impl RateLimiter {
 
     /// Acquires some permits from the rate limiter.
     /// If the permits are not available, returns the duration to wait before trying again.
-    ///
-    /// This method is currently only used in simian.
     pub fn acquire_with_duration(&mut self, num_permits: u64) -> Result<(), Duration> {
         if self.acquire_inner(num_permits) {
             return Ok(());
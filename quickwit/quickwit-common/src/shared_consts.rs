@@ -71,6 +71,9 @@ pub const INGESTER_PRIMARY_SHARDS_PREFIX: &str = "ingester.primary_shards:";
 /// File name for the encoded list of fields in the split
 pub const SPLIT_FIELDS_FILE_NAME: &str = "split_fields";
 
+/// File name for the encoded Bloom filters of the split
+pub const SPLIT_BLOOM_FILTERS_FILE_NAME: &str = "split_bloom_filters";
+
 pub const DEFAULT_SHARD_THROUGHPUT_LIMIT: ByteSize = ByteSize::mib(5);
 
 // (Just a reexport).
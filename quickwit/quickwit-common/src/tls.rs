@@ -0,0 +1,662 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! TLS utilities shared by the REST and gRPC servers, and by the gRPC clients the nodes use to
+//! talk to each other.
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, RwLock, Weak};
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{bail, Context as _};
+use http::Uri;
+use rustls::client::ResolvesClientCert;
+use rustls::server::{
+    AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient, ClientCertVerifier,
+    ClientHello, ResolvesServerCert,
+};
+use rustls::sign::CertifiedKey;
+use rustls::{
+    Certificate, ClientConfig, PrivateKey, RootCertStore, ServerConfig, ServerName, SignatureScheme,
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::TlsConnector;
+use tower::Service;
+use tracing::{debug, error, info, warn};
+
+use crate::tower::BoxFuture;
+
+/// Interval at which the certificate and private key files are checked for changes.
+const CERTIFICATE_RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Loads the PEM-encoded certificates of a file.
+pub fn load_certificates(path: &Path) -> anyhow::Result<Vec<Certificate>> {
+    let file = File::open(path)
+        .with_context(|| format!("failed to open certificate file `{}`", path.display()))?;
+    let certificates = rustls_pemfile::certs(&mut BufReader::new(file))
+        .with_context(|| format!("failed to parse certificate file `{}`", path.display()))?;
+    if certificates.is_empty() {
+        bail!("no certificate found in `{}`", path.display());
+    }
+    Ok(certificates.into_iter().map(Certificate).collect())
+}
+
+/// Loads the first PEM-encoded private key of a file. PKCS#8, PKCS#1 (RSA), and SEC1 (EC) keys
+/// are supported.
+pub fn load_private_key(path: &Path) -> anyhow::Result<PrivateKey> {
+    let file = File::open(path)
+        .with_context(|| format!("failed to open private key file `{}`", path.display()))?;
+    let mut reader = BufReader::new(file);
+    loop {
+        let item_opt = rustls_pemfile::read_one(&mut reader)
+            .with_context(|| format!("failed to parse private key file `{}`", path.display()))?;
+        match item_opt {
+            Some(rustls_pemfile::Item::PKCS8Key(key))
+            | Some(rustls_pemfile::Item::RSAKey(key))
+            | Some(rustls_pemfile::Item::ECKey(key)) => return Ok(PrivateKey(key)),
+            Some(_) => continue,
+            None => bail!("no private key found in `{}`", path.display()),
+        }
+    }
+}
+
+fn load_certified_key(cert_path: &Path, key_path: &Path) -> anyhow::Result<CertifiedKey> {
+    let certificates = load_certificates(cert_path)?;
+    let private_key = load_private_key(key_path)?;
+    let signing_key = rustls::sign::any_supported_type(&private_key)
+        .with_context(|| format!("unsupported private key type in `{}`", key_path.display()))?;
+    Ok(CertifiedKey::new(certificates, signing_key))
+}
+
+/// Returns the most recent modification time of the files, or `None` if one of them cannot be
+/// read.
+fn last_modified_at(paths: &[&Path]) -> Option<SystemTime> {
+    paths
+        .iter()
+        .map(|path| {
+            path.metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .try_fold(SystemTime::UNIX_EPOCH, |acc, modified_at_opt| {
+            modified_at_opt.map(|modified_at| acc.max(modified_at))
+        })
+}
+
+struct LoadedCertificate {
+    certified_key: Arc<CertifiedKey>,
+    modified_at_opt: Option<SystemTime>,
+}
+
+/// Resolves the certificate presented by a TLS server, or by a TLS client when mutual TLS is
+/// enabled, and reloads it when the certificate or private key files change on disk, so that
+/// rotated certificates are used without restarting the node.
+pub struct ReloadingCertResolver {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    loaded_certificate: RwLock<LoadedCertificate>,
+}
+
+impl ReloadingCertResolver {
+    /// Loads the certificate and private key. Fails if they cannot be loaded.
+    pub fn load(cert_path: &Path, key_path: &Path) -> anyhow::Result<Arc<Self>> {
+        let modified_at_opt = last_modified_at(&[cert_path, key_path]);
+        let certified_key = load_certified_key(cert_path, key_path)?;
+        let loaded_certificate = LoadedCertificate {
+            certified_key: Arc::new(certified_key),
+            modified_at_opt,
+        };
+        let cert_resolver = Self {
+            cert_path: cert_path.to_path_buf(),
+            key_path: key_path.to_path_buf(),
+            loaded_certificate: RwLock::new(loaded_certificate),
+        };
+        Ok(Arc::new(cert_resolver))
+    }
+
+    /// Reloads the certificate and private key if one of the files changed since they were last
+    /// loaded. Returns whether they were reloaded. On error, the previous certificate is kept.
+    pub fn reload_if_modified(&self) -> anyhow::Result<bool> {
+        let modified_at_opt = last_modified_at(&[&self.cert_path, &self.key_path]);
+
+        if modified_at_opt.is_none()
+            || modified_at_opt == self.loaded_certificate.read().unwrap().modified_at_opt
+        {
+            return Ok(false);
+        }
+        let certified_key = load_certified_key(&self.cert_path, &self.key_path)?;
+        *self.loaded_certificate.write().unwrap() = LoadedCertificate {
+            certified_key: Arc::new(certified_key),
+            modified_at_opt,
+        };
+        Ok(true)
+    }
+
+    /// Spawns a task that periodically reloads the certificate if it changed on disk. The task
+    /// stops when the resolver is dropped.
+    pub fn spawn_reload_task(self: &Arc<Self>) {
+        let cert_resolver_weak: Weak<Self> = Arc::downgrade(self);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CERTIFICATE_RELOAD_INTERVAL);
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+
+                let Some(cert_resolver) = cert_resolver_weak.upgrade() else {
+                    return;
+                };
+                match cert_resolver.reload_if_modified() {
+                    Ok(true) => info!(
+                        cert_path=%cert_resolver.cert_path.display(),
+                        "reloaded TLS certificate"
+                    ),
+                    Ok(false) => {}
+                    Err(error) => error!(
+                        cert_path=%cert_resolver.cert_path.display(),
+                        error=?error,
+                        "failed to reload TLS certificate, keeping the previous one"
+                    ),
+                }
+            }
+        });
+    }
+
+    fn certified_key(&self) -> Arc<CertifiedKey> {
+        self.loaded_certificate
+            .read()
+            .unwrap()
+            .certified_key
+            .clone()
+    }
+}
+
+impl ResolvesServerCert for ReloadingCertResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.certified_key())
+    }
+}
+
+impl ResolvesClientCert for ReloadingCertResolver {
+    fn resolve(
+        &self,
+        _acceptable_issuers: &[&[u8]],
+        _sigschemes: &[SignatureScheme],
+    ) -> Option<Arc<CertifiedKey>> {
+        Some(self.certified_key())
+    }
+
+    fn has_certs(&self) -> bool {
+        true
+    }
+}
+
+fn load_root_cert_store(ca_path: &Path) -> anyhow::Result<RootCertStore> {
+    let mut root_cert_store = RootCertStore::empty();
+
+    for certificate in load_certificates(ca_path)? {
+        root_cert_store
+            .add(&certificate)
+            .with_context(|| format!("invalid CA certificate in `{}`", ca_path.display()))?;
+    }
+    Ok(root_cert_store)
+}
+
+/// Builds a verifier of the certificates presented by TLS clients, which must be signed by one
+/// of the CA certificates of `ca_path`. If `allow_unauthenticated` is true, clients presenting no
+/// certificate are accepted as well, but clients presenting an invalid one are still rejected.
+pub fn make_client_cert_verifier(
+    ca_path: &Path,
+    allow_unauthenticated: bool,
+) -> anyhow::Result<Arc<dyn ClientCertVerifier>> {
+    let root_cert_store = load_root_cert_store(ca_path)?;
+
+    let client_cert_verifier = if allow_unauthenticated {
+        AllowAnyAnonymousOrAuthenticatedClient::new(root_cert_store).boxed()
+    } else {
+        AllowAnyAuthenticatedClient::new(root_cert_store).boxed()
+    };
+    Ok(client_cert_verifier)
+}
+
+/// Builds the config of a TLS server presenting the certificate of `cert_resolver` and
+/// negotiating one of the `alpn_protocols`. Client certificates are requested and verified only
+/// if `client_cert_verifier_opt` is set.
+pub fn make_server_config(
+    cert_resolver: Arc<ReloadingCertResolver>,
+    client_cert_verifier_opt: Option<Arc<dyn ClientCertVerifier>>,
+    alpn_protocols: &[&[u8]],
+) -> Arc<ServerConfig> {
+    let server_config_builder = ServerConfig::builder().with_safe_defaults();
+    let mut server_config = if let Some(client_cert_verifier) = client_cert_verifier_opt {
+        server_config_builder
+            .with_client_cert_verifier(client_cert_verifier)
+            .with_cert_resolver(cert_resolver)
+    } else {
+        server_config_builder
+            .with_no_client_auth()
+            .with_cert_resolver(cert_resolver)
+    };
+    server_config.alpn_protocols = alpn_protocols
+        .iter()
+        .map(|alpn_protocol| alpn_protocol.to_vec())
+        .collect();
+    Arc::new(server_config)
+}
+
+/// Builds the config of a gRPC TLS client trusting the certificates of `ca_path_opt`, or the
+/// system's root certificates if `None`. The client presents the certificate of
+/// `client_cert_resolver_opt` to the servers requesting one.
+pub fn make_grpc_client_config(
+    ca_path_opt: Option<&Path>,
+    client_cert_resolver_opt: Option<Arc<ReloadingCertResolver>>,
+) -> anyhow::Result<ClientConfig> {
+    let mut root_cert_store = RootCertStore::empty();
+
+    if let Some(ca_path) = ca_path_opt {
+        root_cert_store = load_root_cert_store(ca_path)?;
+    } else {
+        let native_certificates = rustls_native_certs::load_native_certs()
+            .context("failed to load the system's root certificates")?;
+        for native_certificate in native_certificates {
+            if let Err(error) = root_cert_store.add(&Certificate(native_certificate.0)) {
+                warn!(error=?error, "skipping invalid system root certificate");
+            }
+        }
+    }
+    let client_config_builder = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(root_cert_store);
+    let mut client_config = if let Some(client_cert_resolver) = client_cert_resolver_opt {
+        client_config_builder.with_client_cert_resolver(client_cert_resolver)
+    } else {
+        client_config_builder.with_no_client_auth()
+    };
+    client_config.alpn_protocols = vec![b"h2".to_vec()];
+    Ok(client_config)
+}
+
+/// Connection established by a [`GrpcTlsConnector`].
+pub enum GrpcStream {
+    Plain(TcpStream),
+    Tls(TlsStream<TcpStream>),
+}
+
+impl AsyncRead for GrpcStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(tcp_stream) => Pin::new(tcp_stream).poll_read(cx, buf),
+            Self::Tls(tls_stream) => Pin::new(tls_stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for GrpcStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Plain(tcp_stream) => Pin::new(tcp_stream).poll_write(cx, buf),
+            Self::Tls(tls_stream) => Pin::new(tls_stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Plain(tcp_stream) => Pin::new(tcp_stream).poll_write_vectored(cx, bufs),
+            Self::Tls(tls_stream) => Pin::new(tls_stream).poll_write_vectored(cx, bufs),
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Self::Plain(tcp_stream) => tcp_stream.is_write_vectored(),
+            Self::Tls(tls_stream) => tls_stream.is_write_vectored(),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(tcp_stream) => Pin::new(tcp_stream).poll_flush(cx),
+            Self::Tls(tls_stream) => Pin::new(tls_stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(tcp_stream) => Pin::new(tcp_stream).poll_shutdown(cx),
+            Self::Tls(tls_stream) => Pin::new(tls_stream).poll_shutdown(cx),
+        }
+    }
+}
+
+/// Connector establishing the TLS connections of the gRPC channels.
+#[derive(Clone)]
+pub struct GrpcTlsConnector {
+    tls_connector: TlsConnector,
+    server_name_opt: Option<ServerName>,
+    plaintext_fallback: bool,
+}
+
+impl GrpcTlsConnector {
+    /// The certificates of the servers are verified against `server_name_opt` if set, and
+    /// against the host of the URI otherwise, which is usually the IP address of the node.
+    pub fn new(client_config: ClientConfig, server_name_opt: Option<&str>) -> anyhow::Result<Self> {
+        let server_name_opt = server_name_opt
+            .map(|server_name| {
+                ServerName::try_from(server_name)
+                    .with_context(|| format!("invalid TLS server name `{server_name}`"))
+            })
+            .transpose()?;
+        let tls_connector = TlsConnector::from(Arc::new(client_config));
+        Ok(Self {
+            tls_connector,
+            server_name_opt,
+            plaintext_fallback: false,
+        })
+    }
+
+    /// When enabled, the connector falls back to a plaintext connection if the TLS handshake
+    /// fails, which lets the node reach the nodes that do not serve gRPC over TLS yet while TLS
+    /// is being rolled out across the cluster.
+    pub fn with_plaintext_fallback(mut self, plaintext_fallback: bool) -> Self {
+        self.plaintext_fallback = plaintext_fallback;
+        self
+    }
+}
+
+impl Service<Uri> for GrpcTlsConnector {
+    type Response = GrpcStream;
+    type Error = io::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let tls_connector = self.tls_connector.clone();
+        let server_name_opt = self.server_name_opt.clone();
+        let plaintext_fallback = self.plaintext_fallback;
+
+        Box::pin(async move {
+            let Some(authority) = uri.authority() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("URI `{uri}` has no authority"),
+                ));
+            };
+            let tcp_stream = TcpStream::connect(authority.as_str()).await?;
+            tcp_stream.set_nodelay(true)?;
+
+            let server_name = if let Some(server_name) = server_name_opt {
+                server_name
+            } else {
+                let host = authority
+                    .host()
+                    .trim_start_matches('[')
+                    .trim_end_matches(']');
+                ServerName::try_from(host)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?
+            };
+            match tls_connector.connect(server_name, tcp_stream).await {
+                Ok(tls_stream) => Ok(GrpcStream::Tls(tls_stream)),
+                Err(error) if plaintext_fallback => {
+                    debug!(
+                        error=?error,
+                        uri=%uri,
+                        "TLS handshake failed, falling back to plaintext"
+                    );
+                    let tcp_stream = TcpStream::connect(authority.as_str()).await?;
+                    tcp_stream.set_nodelay(true)?;
+                    Ok(GrpcStream::Plain(tcp_stream))
+                }
+                Err(error) => Err(error),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsAcceptor;
+
+    use super::*;
+
+    fn test_file_path(filename: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("resources/tests/tls")
+            .join(filename)
+    }
+
+    #[test]
+    fn test_load_certificates_and_private_key() {
+        let certificates = load_certificates(&test_file_path("server.crt")).unwrap();
+        assert_eq!(certificates.len(), 1);
+        load_private_key(&test_file_path("server.key")).unwrap();
+
+        let error = load_certificates(&test_file_path("server.key")).unwrap_err();
+        assert!(error.to_string().contains("no certificate found"));
+
+        let error = load_private_key(&test_file_path("server.crt")).unwrap_err();
+        assert!(error.to_string().contains("no private key found"));
+
+        load_certificates(&test_file_path("does-not-exist.crt")).unwrap_err();
+    }
+
+    #[test]
+    fn test_reloading_cert_resolver() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cert_path = temp_dir.path().join("server.crt");
+        let key_path = temp_dir.path().join("server.key");
+        std::fs::copy(test_file_path("server.crt"), &cert_path).unwrap();
+        std::fs::copy(test_file_path("server.key"), &key_path).unwrap();
+
+        let cert_resolver = ReloadingCertResolver::load(&cert_path, &key_path).unwrap();
+        let initial_certificate = cert_resolver.certified_key().cert[0].clone();
+        assert!(!cert_resolver.reload_if_modified().unwrap());
+
+        // An invalid certificate is not loaded.
+        std::fs::write(&cert_path, "invalid").unwrap();
+        File::options()
+            .write(true)
+            .open(&cert_path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(1))
+            .unwrap();
+        cert_resolver.reload_if_modified().unwrap_err();
+        assert_eq!(cert_resolver.certified_key().cert[0], initial_certificate);
+
+        std::fs::copy(test_file_path("server-rotated.crt"), &cert_path).unwrap();
+        std::fs::copy(test_file_path("server-rotated.key"), &key_path).unwrap();
+        File::options()
+            .write(true)
+            .open(&cert_path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(2))
+            .unwrap();
+        assert!(cert_resolver.reload_if_modified().unwrap());
+
+        let rotated_certificate = load_certificates(&test_file_path("server-rotated.crt"))
+            .unwrap()
+            .remove(0);
+        assert_eq!(cert_resolver.certified_key().cert[0], rotated_certificate);
+    }
+
+    /// Spawns a TLS server that greets its clients with "hello" and returns its URI.
+    async fn spawn_tls_server(server_config: Arc<ServerConfig>) -> Uri {
+        let tls_acceptor = TlsAcceptor::from(server_config);
+        let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listen_addr = tcp_listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            loop {
+                let (tcp_stream, _) = tcp_listener.accept().await.unwrap();
+                let Ok(mut tls_stream) = tls_acceptor.accept(tcp_stream).await else {
+                    continue;
+                };
+                let _ = tls_stream.write_all(b"hello").await;
+                let _ = tls_stream.shutdown().await;
+            }
+        });
+        format!("https://{listen_addr}").parse().unwrap()
+    }
+
+    async fn read_greeting(
+        tls_connector: &mut GrpcTlsConnector,
+        uri: Uri,
+    ) -> io::Result<(GrpcStream, String)> {
+        let mut grpc_stream = tls_connector.call(uri).await?;
+        let mut message = String::new();
+        grpc_stream.read_to_string(&mut message).await?;
+        Ok((grpc_stream, message))
+    }
+
+    fn server_cert_resolver() -> Arc<ReloadingCertResolver> {
+        ReloadingCertResolver::load(&test_file_path("server.crt"), &test_file_path("server.key"))
+            .unwrap()
+    }
+
+    fn client_cert_resolver() -> Arc<ReloadingCertResolver> {
+        ReloadingCertResolver::load(&test_file_path("client.crt"), &test_file_path("client.key"))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_grpc_tls_connector() {
+        let server_config = make_server_config(server_cert_resolver(), None, &[b"h2"]);
+        let uri = spawn_tls_server(server_config).await;
+
+        let client_config = make_grpc_client_config(Some(&test_file_path("ca.crt")), None).unwrap();
+        let mut tls_connector = GrpcTlsConnector::new(client_config, None).unwrap();
+        let (grpc_stream, message) = read_greeting(&mut tls_connector, uri.clone())
+            .await
+            .unwrap();
+        assert_eq!(message, "hello");
+
+        let GrpcStream::Tls(tls_stream) = grpc_stream else {
+            panic!("expected a TLS stream");
+        };
+        assert_eq!(tls_stream.get_ref().1.alpn_protocol(), Some(&b"h2"[..]));
+
+        let client_config = make_grpc_client_config(Some(&test_file_path("ca.crt")), None).unwrap();
+        let mut tls_connector = GrpcTlsConnector::new(client_config, Some("quickwit")).unwrap();
+        tls_connector.call(uri.clone()).await.unwrap();
+
+        let client_config = make_grpc_client_config(Some(&test_file_path("ca.crt")), None).unwrap();
+        let mut tls_connector = GrpcTlsConnector::new(client_config, Some("unknown")).unwrap();
+        tls_connector.call(uri).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_grpc_tls_connector_with_client_cert() {
+        let client_cert_verifier =
+            make_client_cert_verifier(&test_file_path("client-ca.crt"), false).unwrap();
+        let server_config =
+            make_server_config(server_cert_resolver(), Some(client_cert_verifier), &[b"h2"]);
+        let uri = spawn_tls_server(server_config).await;
+
+        // The server requires a client certificate.
+        let client_config = make_grpc_client_config(Some(&test_file_path("ca.crt")), None).unwrap();
+        let mut tls_connector = GrpcTlsConnector::new(client_config, None).unwrap();
+        read_greeting(&mut tls_connector, uri.clone())
+            .await
+            .unwrap_err();
+
+        let client_config = make_grpc_client_config(
+            Some(&test_file_path("ca.crt")),
+            Some(client_cert_resolver()),
+        )
+        .unwrap();
+        let mut tls_connector = GrpcTlsConnector::new(client_config, None).unwrap();
+        let (_, message) = read_greeting(&mut tls_connector, uri.clone())
+            .await
+            .unwrap();
+        assert_eq!(message, "hello");
+
+        // The client certificate must be signed by the CA trusted by the server.
+        let client_config = make_grpc_client_config(
+            Some(&test_file_path("ca.crt")),
+            Some(server_cert_resolver()),
+        )
+        .unwrap();
+        let mut tls_connector = GrpcTlsConnector::new(client_config, None).unwrap();
+        read_greeting(&mut tls_connector, uri).await.unwrap_err();
+
+        // Anonymous clients are accepted when unauthenticated clients are allowed.
+        let client_cert_verifier =
+            make_client_cert_verifier(&test_file_path("client-ca.crt"), true).unwrap();
+        let server_config =
+            make_server_config(server_cert_resolver(), Some(client_cert_verifier), &[b"h2"]);
+        let uri = spawn_tls_server(server_config).await;
+
+        let client_config = make_grpc_client_config(Some(&test_file_path("ca.crt")), None).unwrap();
+        let mut tls_connector = GrpcTlsConnector::new(client_config, None).unwrap();
+        let (_, message) = read_greeting(&mut tls_connector, uri.clone())
+            .await
+            .unwrap();
+        assert_eq!(message, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_grpc_tls_connector_plaintext_fallback() {
+        let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listen_addr = tcp_listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut tcp_stream, _) = tcp_listener.accept().await.unwrap();
+                let _ = tcp_stream.write_all(b"hello").await;
+                let _ = tcp_stream.shutdown().await;
+            }
+        });
+        let uri: Uri = format!("https://{listen_addr}").parse().unwrap();
+
+        let client_config = make_grpc_client_config(Some(&test_file_path("ca.crt")), None).unwrap();
+        let mut tls_connector = GrpcTlsConnector::new(client_config, None).unwrap();
+        tls_connector.call(uri.clone()).await.unwrap_err();
+
+        let client_config = make_grpc_client_config(Some(&test_file_path("ca.crt")), None).unwrap();
+        let mut tls_connector = GrpcTlsConnector::new(client_config, None)
+            .unwrap()
+            .with_plaintext_fallback(true);
+        let (grpc_stream, message) = read_greeting(&mut tls_connector, uri).await.unwrap();
+        assert!(matches!(grpc_stream, GrpcStream::Plain(_)));
+        assert_eq!(message, "hello");
+    }
+}
@@ -57,7 +57,7 @@ pub use rate_estimator::{RateEstimator, SmaRateEstimator};
 pub use rate_limit::{RateLimit, RateLimitLayer};
 pub use retry::{RetryLayer, RetryPolicy};
 pub use timeout::{Timeout, TimeoutExceeded, TimeoutLayer};
-pub use transport::{make_channel, warmup_channel, BalanceChannel};
+pub use transport::{make_channel, set_grpc_tls_connector, warmup_channel, BalanceChannel};
 
 pub type BoxError = Box<dyn error::Error + Send + Sync + 'static>;
 
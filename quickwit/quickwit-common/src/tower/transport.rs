@@ -23,12 +23,14 @@ use std::fmt;
 use std::hash::Hash;
 use std::net::SocketAddr;
 use std::pin::Pin;
+use std::sync::RwLock;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
 use futures::stream::once;
 use futures::{Stream, StreamExt};
 use http::Uri;
+use once_cell::sync::Lazy;
 use tokio::sync::{mpsc, watch};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tonic::transport::{Channel, Endpoint};
@@ -39,8 +41,19 @@ use tower::load::{CompleteOnResponse, PendingRequestsDiscover};
 use tower::{BoxError, Service, ServiceExt};
 
 use super::{BoxFuture, Change};
+use crate::tls::GrpcTlsConnector;
 use crate::BoxStream;
 
+/// Connector used by the channels created by [`make_channel`] when gRPC TLS is enabled.
+static GRPC_TLS_CONNECTOR: Lazy<RwLock<Option<GrpcTlsConnector>>> = Lazy::new(RwLock::default);
+
+/// Sets the connector of the channels created by [`make_channel`]: with `Some`, the channels
+/// connect over TLS, otherwise they connect over plain TCP. It must be called before the node
+/// creates its first channel and applies to the whole process.
+pub fn set_grpc_tls_connector(grpc_tls_connector_opt: Option<GrpcTlsConnector>) {
+    *GRPC_TLS_CONNECTOR.write().unwrap() = grpc_tls_connector_opt;
+}
+
 // Transforms a boxed stream of `Change<K, Channel>` into a stream of `Result<TowerChange<K,
 // Channel>, Infallible>>` while keeping track of the number of connections.
 struct ChangeStreamAdapter<K> {
@@ -201,15 +214,25 @@ where K: Hash + Eq + Clone + Send + Sync + 'static
 ///
 /// The function is marked as `async` because it requires an executor (`connect_lazy`).
 pub async fn make_channel(socket_addr: SocketAddr) -> Channel {
+    let grpc_tls_connector_opt = GRPC_TLS_CONNECTOR.read().unwrap().clone();
+    let scheme = if grpc_tls_connector_opt.is_some() {
+        "https"
+    } else {
+        "http"
+    };
     let uri = Uri::builder()
-        .scheme("http")
+        .scheme(scheme)
         .authority(socket_addr.to_string())
         .path_and_query("/")
         .build()
         .expect("provided arguments should be valid");
-    Endpoint::from(uri)
-        .connect_timeout(Duration::from_secs(5))
-        .connect_lazy()
+    let endpoint = Endpoint::from(uri).connect_timeout(Duration::from_secs(5));
+
+    if let Some(grpc_tls_connector) = grpc_tls_connector_opt {
+        endpoint.connect_with_connector_lazy(grpc_tls_connector)
+    } else {
+        endpoint.connect_lazy()
+    }
 }
 
 /// Forces a channel to initiate the underlying HTTP connection. Calling this function only makes
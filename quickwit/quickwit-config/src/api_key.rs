@@ -0,0 +1,189 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeSet;
+
+use anyhow::ensure;
+use serde::{Deserialize, Serialize};
+
+use crate::{validate_identifier, RoleId};
+
+pub type ApiKeyId = String;
+
+/// Set of actions granted to an API key.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    Hash,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// Ingesting documents.
+    Ingest,
+    /// Searching and reading index metadata.
+    Search,
+    /// Managing the indexes, sources, templates, API keys, and roles. Grants the other scopes as
+    /// well.
+    Admin,
+}
+
+impl ApiKeyScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::Ingest => "ingest",
+            ApiKeyScope::Search => "search",
+            ApiKeyScope::Admin => "admin",
+        }
+    }
+}
+
+/// An API key used to authenticate the requests sent to the REST API. Only the SHA-256 digest of
+/// the secret is stored: the secret itself is returned once, when the key is created.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKey {
+    pub api_key_id: ApiKeyId,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub scopes: BTreeSet<ApiKeyScope>,
+    /// Roles granting privileges on some indexes in addition to the scopes.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub roles: BTreeSet<RoleId>,
+    /// Hex-encoded SHA-256 digest of the secret.
+    pub secret_hash: String,
+    /// Creation timestamp in seconds.
+    pub create_timestamp: i64,
+}
+
+impl ApiKey {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        validate_identifier("API key", &self.api_key_id)?;
+
+        ensure!(
+            !self.scopes.is_empty() || !self.roles.is_empty(),
+            "API key `{}` must have at least one scope or role",
+            self.api_key_id
+        );
+        ensure!(
+            self.secret_hash.len() == 64
+                && self
+                    .secret_hash
+                    .bytes()
+                    .all(|byte| byte.is_ascii_hexdigit()),
+            "secret hash of API key `{}` must be a hex-encoded SHA-256 digest",
+            self.api_key_id
+        );
+        Ok(())
+    }
+
+    /// Returns whether the key grants the `scope`, either directly or through the `admin` scope.
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes.contains(&ApiKeyScope::Admin) || self.scopes.contains(&scope)
+    }
+
+    #[cfg(any(test, feature = "testsuite"))]
+    pub fn for_test(api_key_id: &str, scopes: &[ApiKeyScope]) -> Self {
+        ApiKey {
+            api_key_id: api_key_id.to_string(),
+            description: None,
+            scopes: scopes.iter().copied().collect(),
+            roles: BTreeSet::new(),
+            secret_hash: "0".repeat(64),
+            create_timestamp: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_serde() {
+        let api_key_json = r#"{
+            "api_key_id": "ingest-key",
+            "scopes": ["ingest", "search"],
+            "secret_hash": "0000000000000000000000000000000000000000000000000000000000000000",
+            "create_timestamp": 1
+        }"#;
+        let api_key: ApiKey = serde_json::from_str(api_key_json).unwrap();
+        assert_eq!(api_key.api_key_id, "ingest-key");
+        assert!(api_key.description.is_none());
+        assert_eq!(
+            api_key.scopes,
+            BTreeSet::from([ApiKeyScope::Ingest, ApiKeyScope::Search])
+        );
+        api_key.validate().unwrap();
+
+        let api_key_json = r#"{
+            "api_key_id": "ingest-key",
+            "scopes": ["write"],
+            "secret_hash": "0000000000000000000000000000000000000000000000000000000000000000",
+            "create_timestamp": 1
+        }"#;
+        serde_json::from_str::<ApiKey>(api_key_json).unwrap_err();
+    }
+
+    #[test]
+    fn test_api_key_validate() {
+        ApiKey::for_test("test-key", &[ApiKeyScope::Search])
+            .validate()
+            .unwrap();
+
+        let mut api_key = ApiKey::for_test("-", &[ApiKeyScope::Search]);
+        api_key.validate().unwrap_err();
+
+        api_key.api_key_id = "test-key".to_string();
+        api_key.scopes.clear();
+        let error = api_key.validate().unwrap_err();
+        assert!(error.to_string().contains("at least one scope or role"));
+
+        api_key.roles.insert("analyst".to_string());
+        api_key.validate().unwrap();
+
+        api_key.scopes.insert(ApiKeyScope::Ingest);
+        api_key.secret_hash = "secret".to_string();
+        let error = api_key.validate().unwrap_err();
+        assert!(error.to_string().contains("SHA-256 digest"));
+    }
+
+    #[test]
+    fn test_api_key_has_scope() {
+        let api_key = ApiKey::for_test("test-key", &[ApiKeyScope::Search]);
+        assert!(api_key.has_scope(ApiKeyScope::Search));
+        assert!(!api_key.has_scope(ApiKeyScope::Ingest));
+        assert!(!api_key.has_scope(ApiKeyScope::Admin));
+
+        let api_key = ApiKey::for_test("test-key", &[ApiKeyScope::Admin]);
+        assert!(api_key.has_scope(ApiKeyScope::Search));
+        assert!(api_key.has_scope(ApiKeyScope::Ingest));
+        assert!(api_key.has_scope(ApiKeyScope::Admin));
+    }
+}
@@ -0,0 +1,238 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::{bail, ensure};
+use serde::{Deserialize, Serialize};
+
+use crate::validate_identifier;
+
+/// A detection rule is a query registered on an index. The documents matching the query are
+/// emitted to the sink of the rule as they are indexed.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DetectionRuleConfig {
+    pub rule_id: String,
+    /// Query, expressed in the query language, that the documents must match to trigger the
+    /// rule. Terms without a field target the default search fields of the index.
+    pub query: String,
+    pub sink: DetectionRuleSinkConfig,
+}
+
+/// Destination of the documents matching a detection rule.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum DetectionRuleSinkConfig {
+    /// Ingests the matches into an index through the ingest API.
+    Index { index_id: String },
+    /// Posts the matches as a JSON array to an HTTP endpoint.
+    Webhook {
+        url: String,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        headers: BTreeMap<String, String>,
+    },
+}
+
+impl DetectionRuleConfig {
+    fn validate(&self) -> anyhow::Result<()> {
+        validate_identifier("detection rule", &self.rule_id)?;
+        ensure!(
+            !self.query.trim().is_empty(),
+            "query of detection rule `{}` is empty",
+            self.rule_id
+        );
+        match &self.sink {
+            DetectionRuleSinkConfig::Index { index_id } => {
+                validate_identifier("index", index_id)?;
+            }
+            DetectionRuleSinkConfig::Webhook { url, headers } => {
+                let uri: http::Uri = url.parse().map_err(|error| {
+                    anyhow::anyhow!(
+                        "webhook URL `{url}` of detection rule `{}` is invalid: {error}",
+                        self.rule_id
+                    )
+                })?;
+                if !matches!(uri.scheme_str(), Some("http" | "https")) || uri.host().is_none() {
+                    bail!(
+                        "webhook URL `{url}` of detection rule `{}` must be an absolute HTTP or \
+                         HTTPS URL",
+                        self.rule_id
+                    );
+                }
+                for (header_name, header_value) in headers {
+                    http::HeaderName::try_from(header_name.as_str()).map_err(|_| {
+                        anyhow::anyhow!(
+                            "webhook header name `{header_name}` of detection rule `{}` is invalid",
+                            self.rule_id
+                        )
+                    })?;
+                    http::HeaderValue::try_from(header_value.as_str()).map_err(|_| {
+                        anyhow::anyhow!(
+                            "webhook header `{header_name}` of detection rule `{}` has an invalid \
+                             value",
+                            self.rule_id
+                        )
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validates the detection rules of an index, except their queries, which are validated against
+/// the doc mapping of the index.
+pub(crate) fn validate_detection_rules(
+    detection_rules: &[DetectionRuleConfig],
+) -> anyhow::Result<()> {
+    let mut rule_ids: HashSet<&str> = HashSet::with_capacity(detection_rules.len());
+
+    for detection_rule in detection_rules {
+        detection_rule.validate()?;
+
+        if !rule_ids.insert(&detection_rule.rule_id) {
+            bail!(
+                "detection rule ID `{}` is not unique",
+                detection_rule.rule_id
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Checks that no detection rule emits its matches to the index it is registered on: the
+/// matches would be indexed and matched again.
+pub(crate) fn validate_detection_rule_sinks(
+    index_id: &str,
+    detection_rules: &[DetectionRuleConfig],
+) -> anyhow::Result<()> {
+    for detection_rule in detection_rules {
+        if let DetectionRuleSinkConfig::Index {
+            index_id: sink_index_id,
+        } = &detection_rule.sink
+        {
+            ensure!(
+                index_id != sink_index_id,
+                "detection rule `{}` cannot emit its matches to its own index",
+                detection_rule.rule_id
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detection_rule_config_serde() {
+        let detection_rules_yaml = r#"
+            - rule_id: failed-ssh-logins
+              query: 'process:sshd AND "Failed password"'
+              sink:
+                type: webhook
+                url: https://alerts.example.com/hooks/quickwit
+                headers:
+                  Authorization: Bearer my-token
+            - rule_id: errors
+              query: severity_text:ERROR
+              sink:
+                type: index
+                index_id: alerts
+        "#;
+        let detection_rules: Vec<DetectionRuleConfig> =
+            serde_yaml::from_str(detection_rules_yaml).unwrap();
+        assert_eq!(detection_rules.len(), 2);
+        assert_eq!(
+            detection_rules[0].sink,
+            DetectionRuleSinkConfig::Webhook {
+                url: "https://alerts.example.com/hooks/quickwit".to_string(),
+                headers: BTreeMap::from([(
+                    "Authorization".to_string(),
+                    "Bearer my-token".to_string()
+                )]),
+            }
+        );
+        assert_eq!(
+            detection_rules[1].sink,
+            DetectionRuleSinkConfig::Index {
+                index_id: "alerts".to_string()
+            }
+        );
+        validate_detection_rules(&detection_rules).unwrap();
+
+        let serialized_json = serde_json::to_value(&detection_rules[1]).unwrap();
+        assert_eq!(
+            serialized_json,
+            serde_json::json!({
+                "rule_id": "errors",
+                "query": "severity_text:ERROR",
+                "sink": {"type": "index", "index_id": "alerts"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_detection_rules() {
+        let detection_rule =
+            |rule_id: &str, query: &str, sink: DetectionRuleSinkConfig| DetectionRuleConfig {
+                rule_id: rule_id.to_string(),
+                query: query.to_string(),
+                sink,
+            };
+        let index_sink = |index_id: &str| DetectionRuleSinkConfig::Index {
+            index_id: index_id.to_string(),
+        };
+        let webhook_sink = |url: &str| DetectionRuleSinkConfig::Webhook {
+            url: url.to_string(),
+            headers: BTreeMap::new(),
+        };
+        let validate = |detection_rules: &[DetectionRuleConfig]| {
+            validate_detection_rules(detection_rules)
+                .and_then(|_| validate_detection_rule_sinks("logs", detection_rules))
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(
+            validate(&[detection_rule("errors", " ", index_sink("alerts"))])
+                .contains("query of detection rule `errors` is empty")
+        );
+        assert!(validate(&[
+            detection_rule("errors", "level:error", index_sink("alerts")),
+            detection_rule("errors", "level:warn", index_sink("alerts")),
+        ])
+        .contains("detection rule ID `errors` is not unique"));
+        assert!(
+            validate(&[detection_rule("errors", "level:error", index_sink("logs"))])
+                .contains("cannot emit its matches to its own index")
+        );
+        assert!(validate(&[detection_rule(
+            "errors",
+            "level:error",
+            webhook_sink("ftp://example.com")
+        )])
+        .contains("must be an absolute HTTP or HTTPS URL"));
+        assert!(
+            validate(&[detection_rule("e", "level:error", index_sink("alerts"))])
+                .contains("detection rule ID `e` is invalid")
+        );
+    }
+}
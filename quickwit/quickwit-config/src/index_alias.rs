@@ -0,0 +1,369 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use itertools::Itertools;
+use quickwit_proto::types::IndexId;
+use quickwit_query::query_ast::QueryAst;
+use quickwit_query::ElasticQueryDsl;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::validate_identifier;
+
+/// Properties of an alias for one of its indexes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexAliasConfig {
+    /// Query, in the Elasticsearch query DSL, applied to the searches targeting the alias.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<JsonValue>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_write_index: Option<bool>,
+}
+
+/// Adds the indexes to the aliases, or removes them from the aliases.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexAliasAction {
+    Add {
+        index_ids: Vec<IndexId>,
+        aliases: Vec<String>,
+        config: IndexAliasConfig,
+    },
+    Remove {
+        index_ids: Vec<IndexId>,
+        aliases: Vec<String>,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IndexAliasError {
+    /// A `remove` action targets an alias that does not exist or does not contain the index.
+    AliasNotFound(String),
+    InvalidAction(String),
+}
+
+impl fmt::Display for IndexAliasError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndexAliasError::AliasNotFound(alias) => write!(formatter, "aliases [{alias}] missing"),
+            IndexAliasError::InvalidAction(reason) => write!(formatter, "{reason}"),
+        }
+    }
+}
+
+impl std::error::Error for IndexAliasError {}
+
+/// Index aliases, keyed by alias name and then by index ID.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct IndexAliases {
+    aliases: BTreeMap<String, BTreeMap<IndexId, IndexAliasConfig>>,
+}
+
+impl IndexAliases {
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// Returns the indexes of the alias named `alias`, if it exists.
+    pub fn get(&self, alias: &str) -> Option<&BTreeMap<IndexId, IndexAliasConfig>> {
+        self.aliases.get(alias)
+    }
+
+    /// Iterates over the aliases, sorted by name, and their indexes.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &BTreeMap<IndexId, IndexAliasConfig>)> {
+        self.aliases.iter()
+    }
+
+    /// Applies the actions in order. The actions are applied atomically: if one of them is
+    /// invalid, the aliases are left untouched.
+    pub fn apply_actions(&mut self, actions: Vec<IndexAliasAction>) -> Result<(), IndexAliasError> {
+        let mut aliases = self.aliases.clone();
+
+        for action in actions {
+            match action {
+                IndexAliasAction::Add {
+                    index_ids,
+                    aliases: alias_names,
+                    config,
+                } => {
+                    validate_action(&index_ids, &alias_names)?;
+
+                    if let Some(filter) = &config.filter {
+                        parse_alias_filter(filter).map_err(|error| {
+                            IndexAliasError::InvalidAction(format!(
+                                "failed to parse alias filter: {error}"
+                            ))
+                        })?;
+                    }
+                    for index_id in &index_ids {
+                        validate_identifier("index", index_id)
+                            .map_err(|error| IndexAliasError::InvalidAction(error.to_string()))?;
+                    }
+                    for alias in alias_names {
+                        validate_identifier("alias", &alias)
+                            .map_err(|error| IndexAliasError::InvalidAction(error.to_string()))?;
+                        let alias_indexes = aliases.entry(alias).or_default();
+
+                        for index_id in &index_ids {
+                            alias_indexes.insert(index_id.clone(), config.clone());
+                        }
+                    }
+                }
+                IndexAliasAction::Remove {
+                    index_ids,
+                    aliases: alias_names,
+                } => {
+                    validate_action(&index_ids, &alias_names)?;
+
+                    for alias in &alias_names {
+                        for index_id in &index_ids {
+                            let is_removed = aliases
+                                .get_mut(alias)
+                                .and_then(|alias_indexes| alias_indexes.remove(index_id))
+                                .is_some();
+                            if !is_removed {
+                                return Err(IndexAliasError::AliasNotFound(alias.clone()));
+                            }
+                        }
+                    }
+                    aliases.retain(|_, alias_indexes| !alias_indexes.is_empty());
+                }
+            }
+        }
+        for (alias, alias_indexes) in &aliases {
+            let write_index_ids: Vec<&IndexId> = alias_indexes
+                .iter()
+                .filter(|(_, alias_config)| alias_config.is_write_index == Some(true))
+                .map(|(index_id, _)| index_id)
+                .collect();
+            if write_index_ids.len() > 1 {
+                return Err(IndexAliasError::InvalidAction(format!(
+                    "alias [{alias}] has more than one write index [{}]",
+                    write_index_ids.iter().join(",")
+                )));
+            }
+        }
+        self.aliases = aliases;
+        Ok(())
+    }
+
+    /// Removes the given indexes from all the aliases. Returns whether any alias was modified.
+    pub fn remove_indexes(&mut self, index_ids: &[IndexId]) -> bool {
+        let mut is_modified = false;
+
+        for alias_indexes in self.aliases.values_mut() {
+            for index_id in index_ids {
+                is_modified |= alias_indexes.remove(index_id).is_some();
+            }
+        }
+        self.aliases
+            .retain(|_, alias_indexes| !alias_indexes.is_empty());
+        is_modified
+    }
+
+    /// Returns the index documents should be written to when they target `index_id`, which is
+    /// returned as is if it is not an alias.
+    ///
+    /// An alias with a single index writes to it unless `is_write_index` is explicitly `false`.
+    pub fn resolve_write_index(&self, index_id: &str) -> Result<IndexId, String> {
+        let Some(alias_indexes) = self.aliases.get(index_id) else {
+            return Ok(index_id.to_string());
+        };
+        let write_index_id_opt = alias_indexes
+            .iter()
+            .find(|(_, alias_config)| alias_config.is_write_index == Some(true))
+            .or_else(|| {
+                alias_indexes
+                    .iter()
+                    .exactly_one()
+                    .ok()
+                    .filter(|(_, alias_config)| alias_config.is_write_index.is_none())
+            })
+            .map(|(write_index_id, _)| write_index_id.clone());
+        write_index_id_opt.ok_or_else(|| {
+            format!(
+                "no write index is defined for alias [{index_id}]. The write index may be \
+                 explicitly disabled using is_write_index=false or the alias points to multiple \
+                 indices without one being designated as a write index"
+            )
+        })
+    }
+}
+
+impl FromIterator<(String, BTreeMap<IndexId, IndexAliasConfig>)> for IndexAliases {
+    fn from_iter<I>(iter: I) -> Self
+    where I: IntoIterator<Item = (String, BTreeMap<IndexId, IndexAliasConfig>)> {
+        let aliases = iter
+            .into_iter()
+            .filter(|(_, alias_indexes)| !alias_indexes.is_empty())
+            .collect();
+        Self { aliases }
+    }
+}
+
+fn validate_action(index_ids: &[IndexId], aliases: &[String]) -> Result<(), IndexAliasError> {
+    if index_ids.is_empty() || aliases.is_empty() {
+        return Err(IndexAliasError::InvalidAction(
+            "alias actions require at least one index and one alias".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Parses an alias filter, expressed in the Elasticsearch query DSL.
+pub fn parse_alias_filter(filter: &JsonValue) -> anyhow::Result<QueryAst> {
+    let query_dsl: ElasticQueryDsl = serde_json::from_value(filter.clone())?;
+    query_dsl.try_into()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn add_action(
+        index_ids: &[&str],
+        alias: &str,
+        is_write_index: Option<bool>,
+    ) -> IndexAliasAction {
+        IndexAliasAction::Add {
+            index_ids: index_ids
+                .iter()
+                .map(|index_id| index_id.to_string())
+                .collect(),
+            aliases: vec![alias.to_string()],
+            config: IndexAliasConfig {
+                filter: None,
+                is_write_index,
+            },
+        }
+    }
+
+    fn remove_action(index_ids: &[&str], alias: &str) -> IndexAliasAction {
+        IndexAliasAction::Remove {
+            index_ids: index_ids
+                .iter()
+                .map(|index_id| index_id.to_string())
+                .collect(),
+            aliases: vec![alias.to_string()],
+        }
+    }
+
+    #[test]
+    fn test_index_aliases_apply_actions() {
+        let mut index_aliases = IndexAliases::default();
+        index_aliases
+            .apply_actions(vec![
+                add_action(&["logs-1", "logs-2"], "logs", None),
+                add_action(&["logs-2"], "logs-write", Some(true)),
+            ])
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&index_aliases).unwrap(),
+            json!({
+                "logs": {"logs-1": {}, "logs-2": {}},
+                "logs-write": {"logs-2": {"is_write_index": true}},
+            })
+        );
+        // Rollover: the write index changes atomically.
+        index_aliases
+            .apply_actions(vec![
+                add_action(&["logs-2"], "logs-write", Some(false)),
+                add_action(&["logs-3"], "logs-write", Some(true)),
+                remove_action(&["logs-1"], "logs"),
+            ])
+            .unwrap();
+        assert_eq!(
+            index_aliases.resolve_write_index("logs-write").unwrap(),
+            "logs-3"
+        );
+        let error = index_aliases
+            .apply_actions(vec![add_action(&["logs-4"], "logs-write", Some(true))])
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "alias [logs-write] has more than one write index [logs-3,logs-4]"
+        );
+        let error = index_aliases
+            .apply_actions(vec![
+                remove_action(&["logs-2"], "logs"),
+                remove_action(&["logs-1"], "logs"),
+            ])
+            .unwrap_err();
+        assert_eq!(error, IndexAliasError::AliasNotFound("logs".to_string()));
+        // Failed actions leave the aliases untouched.
+        assert!(index_aliases.get("logs").is_some());
+
+        let error = index_aliases
+            .apply_actions(vec![IndexAliasAction::Add {
+                index_ids: vec!["logs-1".to_string()],
+                aliases: vec!["logs".to_string()],
+                config: IndexAliasConfig {
+                    filter: Some(json!({"foo": {}})),
+                    is_write_index: None,
+                },
+            }])
+            .unwrap_err();
+        assert!(matches!(error, IndexAliasError::InvalidAction(_)));
+
+        let error = index_aliases
+            .apply_actions(vec![add_action(&["logs-1"], "_logs", None)])
+            .unwrap_err();
+        assert!(matches!(error, IndexAliasError::InvalidAction(_)));
+
+        assert!(index_aliases.remove_indexes(&["logs-2".to_string()]));
+        assert!(!index_aliases.remove_indexes(&["logs-2".to_string()]));
+        assert!(index_aliases.get("logs").is_none());
+    }
+
+    #[test]
+    fn test_index_aliases_resolve_write_index() {
+        let mut index_aliases = IndexAliases::default();
+        index_aliases
+            .apply_actions(vec![
+                add_action(&["logs-1"], "single", None),
+                add_action(&["logs-1"], "disabled", Some(false)),
+                add_action(&["logs-1", "logs-2"], "multiple", None),
+                add_action(&["logs-1"], "rollover", Some(false)),
+                add_action(&["logs-2"], "rollover", Some(true)),
+            ])
+            .unwrap();
+        assert_eq!(
+            index_aliases.resolve_write_index("logs-3").unwrap(),
+            "logs-3"
+        );
+        assert_eq!(
+            index_aliases.resolve_write_index("single").unwrap(),
+            "logs-1"
+        );
+        assert_eq!(
+            index_aliases.resolve_write_index("rollover").unwrap(),
+            "logs-2"
+        );
+        assert!(index_aliases.resolve_write_index("disabled").is_err());
+        assert!(index_aliases.resolve_write_index("multiple").is_err());
+    }
+}
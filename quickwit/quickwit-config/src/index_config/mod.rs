@@ -29,14 +29,17 @@ use bytesize::ByteSize;
 use chrono::Utc;
 use cron::Schedule;
 use humantime::parse_duration;
+use quickwit_common::is_false;
 use quickwit_common::uri::Uri;
 use quickwit_doc_mapper::{DefaultDocMapperBuilder, DocMapper, DocMapping};
 use quickwit_proto::types::IndexId;
+use quickwit_query::query_ast::query_ast_from_user_text;
 use serde::{Deserialize, Serialize};
 pub use serialize::{load_index_config_from_user_config, load_index_config_update};
 use siphasher::sip::SipHasher;
 use tracing::warn;
 
+use crate::detection_rule_config::{validate_detection_rules, DetectionRuleConfig};
 use crate::index_config::serialize::VersionedIndexConfig;
 use crate::merge_policy_config::MergePolicyConfig;
 
@@ -121,6 +124,10 @@ pub struct IndexingSettings {
     pub merge_policy: MergePolicyConfig,
     #[serde(default)]
     pub resources: IndexingResources,
+    /// Queries the documents are matched against as they are indexed. The matching documents
+    /// are emitted to the sink of each rule.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub detection_rules: Vec<DetectionRuleConfig>,
 }
 
 impl IndexingSettings {
@@ -162,6 +169,7 @@ impl Default for IndexingSettings {
             split_num_docs_target: Self::default_split_num_docs_target(),
             merge_policy: MergePolicyConfig::default(),
             resources: IndexingResources::default(),
+            detection_rules: Vec::new(),
         }
     }
 }
@@ -171,6 +179,10 @@ impl Default for IndexingSettings {
 pub struct SearchSettings {
     #[serde(default)]
     pub default_search_fields: Vec<String>,
+    /// Allows wildcard queries starting with a wildcard (`*bond`, `*ond*`). They are run as a
+    /// bounded scan of the term dictionary of the targeted fields.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub allow_leading_wildcard: bool,
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
@@ -344,6 +356,7 @@ impl IndexConfig {
                 r#"attributes.server"#.to_string(),
                 r"attributes.server\.status".to_string(),
             ],
+            ..Default::default()
         };
         IndexConfig {
             index_id: index_id.to_string(),
@@ -359,7 +372,7 @@ impl IndexConfig {
 #[cfg(any(test, feature = "testsuite"))]
 impl crate::TestableForRegression for IndexConfig {
     fn sample_for_regression() -> Self {
-        use std::collections::BTreeSet;
+        use std::collections::{BTreeMap, BTreeSet};
         use std::num::NonZeroU32;
 
         use quickwit_doc_mapper::Mode;
@@ -419,12 +432,14 @@ impl crate::TestableForRegression for IndexConfig {
             ],
             timestamp_field: Some("timestamp".to_string()),
             tag_fields: BTreeSet::from_iter(["tenant_id".to_string(), "log_level".to_string()]),
+            bloom_filter_fields: BTreeSet::new(),
             partition_key: Some("tenant_id".to_string()),
             max_num_partitions: NonZeroU32::new(100).unwrap(),
             index_field_presence: true,
             store_document_size: false,
             store_source: true,
             tokenizers: vec![tokenizer],
+            field_aliases: BTreeMap::new(),
         };
         let retention_policy = Some(RetentionPolicy {
             retention_period: "90 days".to_string(),
@@ -449,6 +464,7 @@ impl crate::TestableForRegression for IndexConfig {
         };
         let search_settings = SearchSettings {
             default_search_fields: vec!["message".to_string()],
+            ..Default::default()
         };
         IndexConfig {
             index_id: "my-index".to_string(),
@@ -477,6 +493,7 @@ pub fn build_doc_mapper(
     let builder = DefaultDocMapperBuilder {
         doc_mapping: doc_mapping.clone(),
         default_search_fields: search_settings.default_search_fields.clone(),
+        allow_leading_wildcard: search_settings.allow_leading_wildcard,
     };
     Ok(Arc::new(builder.try_build()?))
 }
@@ -492,11 +509,25 @@ pub(super) fn validate_index_config(
     // Note: this needs a deep refactoring to separate the doc mapping configuration,
     // and doc mapper implementations.
     // TODO see if we should store the byproducton the IndexConfig.
-    build_doc_mapper(doc_mapping, search_settings)?;
+    let doc_mapper = build_doc_mapper(doc_mapping, search_settings)?;
 
     indexing_settings.merge_policy.validate()?;
     indexing_settings.resources.validate()?;
 
+    validate_detection_rules(&indexing_settings.detection_rules)?;
+
+    for detection_rule in &indexing_settings.detection_rules {
+        let query_ast = query_ast_from_user_text(&detection_rule.query, None);
+        doc_mapper
+            .query(doc_mapper.schema(), &query_ast, true)
+            .with_context(|| {
+                format!(
+                    "invalid query `{}` for detection rule `{}`",
+                    detection_rule.query, detection_rule.rule_id
+                )
+            })?;
+    }
+
     if let Some(retention_policy) = retention_policy_opt {
         retention_policy.validate()?;
 
@@ -592,6 +623,7 @@ mod tests {
             index_config.search_settings,
             SearchSettings {
                 default_search_fields: vec!["severity_text".to_string(), "body".to_string()],
+                ..Default::default()
             }
         );
     }
@@ -634,6 +666,7 @@ mod tests {
                 index_config.search_settings,
                 SearchSettings {
                     default_search_fields: vec!["body".to_string()],
+                    ..Default::default()
                 }
             );
         }
@@ -668,6 +701,7 @@ mod tests {
                 index_config.search_settings,
                 SearchSettings {
                     default_search_fields: vec!["body".to_string()],
+                    ..Default::default()
                 }
             );
         }
@@ -705,6 +739,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_index_config_with_detection_rules() {
+        let config_yaml = |query: &str, sink_index_id: &str| {
+            format!(
+                r#"
+            version: 0.8
+            index_id: hdfs-logs
+            index_uri: "s3://my-index"
+            doc_mapping:
+              mode: strict
+              field_mappings:
+                - name: severity_text
+                  type: text
+                  tokenizer: raw
+            indexing_settings:
+              detection_rules:
+                - rule_id: errors
+                  query: '{query}'
+                  sink:
+                    type: index
+                    index_id: {sink_index_id}
+            "#
+            )
+        };
+        let load_index_config = |config_yaml: String| {
+            load_index_config_from_user_config(
+                ConfigFormat::Yaml,
+                config_yaml.as_bytes(),
+                &Uri::for_test("s3://my-index"),
+            )
+        };
+        let index_config = load_index_config(config_yaml("severity_text:ERROR", "alerts")).unwrap();
+        let detection_rules = &index_config.indexing_settings.detection_rules;
+        assert_eq!(detection_rules.len(), 1);
+        assert_eq!(detection_rules[0].rule_id, "errors");
+
+        let error = load_index_config(config_yaml("unknown_field:ERROR", "alerts")).unwrap_err();
+        assert!(format!("{error:#}")
+            .contains("invalid query `unknown_field:ERROR` for detection rule `errors`"));
+
+        let error = load_index_config(config_yaml("severity_text:ERROR", "hdfs-logs")).unwrap_err();
+        assert!(format!("{error:#}").contains("cannot emit its matches to its own index"));
+    }
+
     #[test]
     fn test_index_config_with_malformed_maturation_duration() {
         let config_yaml = r#"
@@ -27,6 +27,7 @@ use serde::{Deserialize, Serialize};
 use tracing::info;
 
 use super::validate_index_config;
+use crate::detection_rule_config::validate_detection_rule_sinks;
 use crate::{
     validate_identifier, ConfigFormat, DocMapping, IndexConfig, IndexingSettings, RetentionPolicy,
     SearchSettings,
@@ -109,6 +110,7 @@ pub fn load_index_config_update(
             .search_settings
             .default_search_fields
             .clone(),
+        allow_leading_wildcard: new_index_config.search_settings.allow_leading_wildcard,
     };
     doc_mapper_builder
         .try_build()
@@ -202,6 +204,10 @@ impl IndexConfigForSerialization {
             &index_config.search_settings,
             &index_config.retention_policy_opt,
         )?;
+        validate_detection_rule_sinks(
+            &index_config.index_id,
+            &index_config.indexing_settings.detection_rules,
+        )?;
         Ok(index_config)
     }
 }
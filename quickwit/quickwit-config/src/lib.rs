@@ -30,20 +30,30 @@ use quickwit_common::uri::Uri;
 use quickwit_proto::types::NodeIdRef;
 use regex::Regex;
 
+mod api_key;
 mod cluster_config;
 mod config_value;
+mod detection_rule_config;
+mod index_alias;
 mod index_config;
 mod index_template;
+mod lifecycle_policy;
 pub mod merge_policy_config;
 mod metastore_config;
 mod node_config;
 mod qw_env_vars;
+mod role;
 pub mod service;
 mod source_config;
 mod storage_config;
 mod templating;
 
+pub use api_key::{ApiKey, ApiKeyId, ApiKeyScope};
 pub use cluster_config::ClusterConfig;
+pub use detection_rule_config::{DetectionRuleConfig, DetectionRuleSinkConfig};
+pub use index_alias::{
+    parse_alias_filter, IndexAliasAction, IndexAliasConfig, IndexAliasError, IndexAliases,
+};
 // We export that one for backward compatibility.
 // See #2048
 use index_config::serialize::{IndexConfigV0_8, VersionedIndexConfig};
@@ -52,6 +62,7 @@ pub use index_config::{
     IndexingResources, IndexingSettings, RetentionPolicy, SearchSettings,
 };
 pub use quickwit_doc_mapper::DocMapping;
+pub use role::{IndexGrant, IndexPrivilege, Role, RoleId};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::Value as JsonValue;
@@ -67,6 +78,9 @@ use tracing::warn;
 
 use crate::index_template::IndexTemplateV0_8;
 pub use crate::index_template::{IndexTemplate, IndexTemplateId, VersionedIndexTemplate};
+pub use crate::lifecycle_policy::{
+    DeletePhase, ForceMergePhase, LifecyclePhases, LifecyclePolicy, LifecyclePolicyId,
+};
 use crate::merge_policy_config::{
     ConstWriteAmplificationMergePolicyConfig, MergePolicyConfig, StableLogMergePolicyConfig,
 };
@@ -74,7 +88,10 @@ pub use crate::metastore_config::{
     MetastoreBackend, MetastoreConfig, MetastoreConfigs, PostgresMetastoreConfig,
 };
 pub use crate::node_config::{
-    IndexerConfig, IngestApiConfig, JaegerConfig, NodeConfig, SearcherConfig, SplitCacheLimits,
+    AuditSinkConfig, GossipConfig, GossipEncryptionMode, IndexerConfig, IngestApiConfig,
+    JaegerConfig, LeafSearchHedgingConfig, MtlsMode, NodeConfig, PgWireConfig, RequestRateLimits,
+    RestAuditConfig, RestAuthConfig, RestConfig, RestRateLimitsConfig,
+    SearchAdmissionControlConfig, SearcherConfig, SplitCacheLimits, TlsConfig,
     DEFAULT_QW_CONFIG_PATH,
 };
 use crate::source_config::serialize::{SourceConfigV0_7, SourceConfigV0_8, VersionedSourceConfig};
@@ -101,6 +118,8 @@ pub fn disable_ingest_v1() -> bool {
 #[openapi(components(schemas(
     IndexingResources,
     IndexingSettings,
+    DetectionRuleConfig,
+    DetectionRuleSinkConfig,
     SearchSettings,
     RetentionPolicy,
     MergePolicyConfig,
@@ -112,6 +131,15 @@ pub fn disable_ingest_v1() -> bool {
     IndexConfigV0_8,
     VersionedIndexTemplate,
     IndexTemplateV0_8,
+    LifecyclePolicy,
+    LifecyclePhases,
+    ForceMergePhase,
+    DeletePhase,
+    ApiKey,
+    ApiKeyScope,
+    Role,
+    IndexGrant,
+    IndexPrivilege,
     SourceInputFormat,
     SourceParams,
     FileSourceMessageType,
@@ -0,0 +1,317 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+use anyhow::{bail, ensure, Context};
+use humantime::parse_duration;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{validate_identifier, validate_index_id_pattern};
+
+pub type LifecyclePolicyId = String;
+
+/// A lifecycle policy defines the phases that the splits of the indexes matching its index ID
+/// patterns go through as they age. Splits are in the hot phase until they reach the age of the
+/// first configured phase.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LifecyclePolicy {
+    pub policy_id: LifecyclePolicyId,
+    pub index_id_patterns: Vec<String>,
+    /// When several policies match an index, the policy with the highest priority applies.
+    #[serde(default)]
+    pub priority: usize,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub phases: LifecyclePhases,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LifecyclePhases {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force_merge: Option<ForceMergePhase>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<DeletePhase>,
+}
+
+/// Merges the splits older than `min_age` into splits of up to `target_num_docs` documents. The
+/// merged splits are never merged again by the indexers.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ForceMergePhase {
+    /// Age, expressed in a human-friendly way (`1 day`, `12 hours`, ...), from which the splits
+    /// are merged. The age of a split is computed from the end of its time range, or from its
+    /// creation date if the index has no timestamp field.
+    pub min_age: String,
+    #[schema(default = 10_000_000)]
+    #[serde(default = "ForceMergePhase::default_target_num_docs")]
+    pub target_num_docs: usize,
+    /// Maximum number of splits merged together in a single merge operation.
+    #[schema(default = 50)]
+    #[serde(default = "ForceMergePhase::default_max_merge_factor")]
+    pub max_merge_factor: usize,
+}
+
+impl ForceMergePhase {
+    fn default_target_num_docs() -> usize {
+        10_000_000
+    }
+
+    fn default_max_merge_factor() -> usize {
+        50
+    }
+
+    pub fn min_age(&self) -> anyhow::Result<Duration> {
+        parse_min_age(&self.min_age, "force_merge")
+    }
+}
+
+/// Marks the splits older than `min_age` for deletion.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DeletePhase {
+    /// Age, expressed in a human-friendly way (`30 days`, `1 week`, ...), from which the splits
+    /// are deleted. The age of a split is computed from the end of its time range, or from its
+    /// creation date if the index has no timestamp field.
+    pub min_age: String,
+}
+
+impl DeletePhase {
+    pub fn min_age(&self) -> anyhow::Result<Duration> {
+        parse_min_age(&self.min_age, "delete")
+    }
+}
+
+fn parse_min_age(min_age: &str, phase_name: &str) -> anyhow::Result<Duration> {
+    parse_duration(min_age)
+        .with_context(|| format!("failed to parse `min_age` `{min_age}` of phase `{phase_name}`"))
+}
+
+impl LifecyclePolicy {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        validate_identifier("lifecycle policy", &self.policy_id)?;
+
+        ensure!(
+            !self.index_id_patterns.is_empty(),
+            "`index_id_patterns` must not be empty"
+        );
+        for index_id_pattern in &self.index_id_patterns {
+            validate_index_id_pattern(index_id_pattern, true)?;
+        }
+        let force_merge_min_age_opt = self
+            .phases
+            .force_merge
+            .as_ref()
+            .map(|force_merge_phase| {
+                ensure!(
+                    force_merge_phase.target_num_docs > 0,
+                    "`target_num_docs` must be strictly positive"
+                );
+                ensure!(
+                    force_merge_phase.max_merge_factor >= 2,
+                    "`max_merge_factor` must be greater than or equal to 2"
+                );
+                force_merge_phase.min_age()
+            })
+            .transpose()?;
+        let delete_min_age_opt = self
+            .phases
+            .delete
+            .as_ref()
+            .map(DeletePhase::min_age)
+            .transpose()?;
+
+        match (force_merge_min_age_opt, delete_min_age_opt) {
+            (None, None) => bail!("lifecycle policy must define at least one phase"),
+            (Some(force_merge_min_age), Some(delete_min_age)) => ensure!(
+                force_merge_min_age < delete_min_age,
+                "`min_age` of phase `force_merge` must be less than `min_age` of phase `delete`"
+            ),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Returns whether the policy applies to the index. Index ID patterns follow the same rules
+    /// as the ones of index templates: `*` matches any sequence of characters and patterns
+    /// starting with `-` exclude the matching indexes.
+    pub fn matches_index_id(&self, index_id: &str) -> bool {
+        let mut is_match = false;
+
+        for index_id_pattern in &self.index_id_patterns {
+            if let Some(negative_pattern) = index_id_pattern.strip_prefix('-') {
+                if pattern_regex(negative_pattern).is_match(index_id) {
+                    return false;
+                }
+            } else {
+                is_match |= pattern_regex(index_id_pattern).is_match(index_id);
+            }
+        }
+        is_match
+    }
+
+    /// Returns the policy with the highest priority among the policies matching the index. Ties
+    /// are broken by policy ID.
+    pub fn find_matching_policy<'a>(
+        lifecycle_policies: impl IntoIterator<Item = &'a LifecyclePolicy>,
+        index_id: &str,
+    ) -> Option<&'a LifecyclePolicy> {
+        lifecycle_policies
+            .into_iter()
+            .filter(|lifecycle_policy| lifecycle_policy.matches_index_id(index_id))
+            .min_by(|left, right| {
+                right
+                    .priority
+                    .cmp(&left.priority)
+                    .then_with(|| left.policy_id.cmp(&right.policy_id))
+            })
+    }
+
+    #[cfg(any(test, feature = "testsuite"))]
+    pub fn for_test(policy_id: &str, index_id_patterns: &[&str]) -> Self {
+        LifecyclePolicy {
+            policy_id: policy_id.to_string(),
+            index_id_patterns: index_id_patterns
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+            priority: 0,
+            description: None,
+            phases: LifecyclePhases {
+                force_merge: Some(ForceMergePhase {
+                    min_age: "1 day".to_string(),
+                    target_num_docs: ForceMergePhase::default_target_num_docs(),
+                    max_merge_factor: ForceMergePhase::default_max_merge_factor(),
+                }),
+                delete: Some(DeletePhase {
+                    min_age: "30 days".to_string(),
+                }),
+            },
+        }
+    }
+}
+
+pub(crate) fn pattern_regex(index_id_pattern: &str) -> Regex {
+    let regex_str = index_id_pattern
+        .split('*')
+        .map(regex::escape)
+        .collect::<Vec<String>>()
+        .join(".*");
+    Regex::new(&format!("^{regex_str}$")).expect("regular expression should compile")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lifecycle_policy_serde() {
+        let lifecycle_policy_yaml = r#"
+            policy_id: logs
+            index_id_patterns:
+              - logs-*
+            phases:
+              force_merge:
+                min_age: 1 day
+              delete:
+                min_age: 30 days
+        "#;
+        let lifecycle_policy: LifecyclePolicy =
+            serde_yaml::from_str(lifecycle_policy_yaml).unwrap();
+        lifecycle_policy.validate().unwrap();
+        assert_eq!(
+            lifecycle_policy,
+            LifecyclePolicy::for_test("logs", &["logs-*"])
+        );
+
+        let force_merge_phase = lifecycle_policy.phases.force_merge.as_ref().unwrap();
+        assert_eq!(
+            force_merge_phase.min_age().unwrap(),
+            Duration::from_secs(24 * 3600)
+        );
+        assert_eq!(force_merge_phase.target_num_docs, 10_000_000);
+        assert_eq!(force_merge_phase.max_merge_factor, 50);
+
+        let delete_phase = lifecycle_policy.phases.delete.as_ref().unwrap();
+        assert_eq!(
+            delete_phase.min_age().unwrap(),
+            Duration::from_secs(30 * 24 * 3600)
+        );
+    }
+
+    #[test]
+    fn test_lifecycle_policy_validate() {
+        let mut lifecycle_policy = LifecyclePolicy::for_test("logs", &["logs-*"]);
+        lifecycle_policy.validate().unwrap();
+
+        lifecycle_policy.index_id_patterns.clear();
+        let error = lifecycle_policy.validate().unwrap_err();
+        assert!(error.to_string().contains("must not be empty"));
+
+        let mut lifecycle_policy = LifecyclePolicy::for_test("logs", &["logs-*"]);
+        lifecycle_policy.phases.delete.as_mut().unwrap().min_age = "12 hours".to_string();
+        let error = lifecycle_policy.validate().unwrap_err();
+        assert!(error.to_string().contains("must be less than"));
+
+        lifecycle_policy.phases.delete.as_mut().unwrap().min_age = "forever".to_string();
+        let error = lifecycle_policy.validate().unwrap_err();
+        assert!(error.to_string().contains("failed to parse `min_age`"));
+
+        let mut lifecycle_policy = LifecyclePolicy::for_test("logs", &["logs-*"]);
+        lifecycle_policy.phases = LifecyclePhases::default();
+        let error = lifecycle_policy.validate().unwrap_err();
+        assert!(error.to_string().contains("at least one phase"));
+
+        let mut lifecycle_policy = LifecyclePolicy::for_test("logs", &["logs-*"]);
+        lifecycle_policy
+            .phases
+            .force_merge
+            .as_mut()
+            .unwrap()
+            .max_merge_factor = 1;
+        let error = lifecycle_policy.validate().unwrap_err();
+        assert!(error.to_string().contains("`max_merge_factor`"));
+    }
+
+    #[test]
+    fn test_lifecycle_policy_find_matching_policy() {
+        let logs_policy = LifecyclePolicy::for_test("logs", &["logs-*", "-logs-audit-*"]);
+        let mut audit_policy = LifecyclePolicy::for_test("audit", &["*-audit-*"]);
+        audit_policy.priority = 1;
+        let mut app_policy = LifecyclePolicy::for_test("app", &["logs-app-*"]);
+        app_policy.priority = 1;
+        let lifecycle_policies = [logs_policy, audit_policy, app_policy];
+
+        let find_matching_policy_id = |index_id: &str| {
+            LifecyclePolicy::find_matching_policy(&lifecycle_policies, index_id)
+                .map(|lifecycle_policy| lifecycle_policy.policy_id.as_str())
+        };
+        assert_eq!(find_matching_policy_id("logs-web-1"), Some("logs"));
+        assert_eq!(find_matching_policy_id("logs-audit-1"), Some("audit"));
+        assert_eq!(find_matching_policy_id("logs-app-1"), Some("app"));
+        assert_eq!(find_matching_policy_id("metrics-1"), None);
+        assert_eq!(find_matching_policy_id("logs"), None);
+    }
+}
@@ -19,14 +19,15 @@
 
 mod serialize;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
 use std::net::SocketAddr;
 use std::num::{NonZeroU32, NonZeroU64, NonZeroUsize};
 use std::path::PathBuf;
 use std::time::Duration;
 
-use anyhow::{bail, ensure};
+use anyhow::{bail, ensure, Context};
+use base64::prelude::{Engine, BASE64_STANDARD};
 use bytesize::ByteSize;
 use http::HeaderMap;
 use quickwit_common::net::HostAddr;
@@ -37,6 +38,7 @@ use quickwit_proto::types::NodeId;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
+use crate::lifecycle_policy::pattern_regex;
 use crate::node_config::serialize::load_node_config_with_env;
 use crate::service::QuickwitService;
 use crate::storage_config::StorageConfigs;
@@ -51,6 +53,208 @@ pub struct RestConfig {
     pub cors_allow_origins: Vec<String>,
     #[serde(with = "http_serde::header_map")]
     pub extra_headers: HeaderMap,
+    /// Minimum size of the responses compressed with gzip or zstd, if the client accepts it.
+    /// `None` disables the compression of the responses.
+    pub compression_min_size: Option<ByteSize>,
+    #[serde(default)]
+    pub auth: RestAuthConfig,
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+    #[serde(default)]
+    pub audit: RestAuditConfig,
+    #[serde(default)]
+    pub rate_limits: RestRateLimitsConfig,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RestAuthConfig {
+    /// When enabled, the requests sent to the REST API must carry an API key granting the scope
+    /// required by the endpoint.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Secret of the built-in `root` API key, which has the `admin` scope. It is meant to create
+    /// the first API keys of the cluster.
+    #[serde(default)]
+    pub root_api_key: Option<String>,
+}
+
+impl RestAuthConfig {
+    /// ID of the built-in API key whose secret is defined by `root_api_key`.
+    pub const ROOT_API_KEY_ID: &'static str = "root";
+
+    /// Redacts the secret of the root API key.
+    pub fn redact(&mut self) {
+        if let Some(root_api_key) = self.root_api_key.as_mut() {
+            *root_api_key = "***redacted***".to_string();
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RestAuditConfig {
+    /// When enabled, an audit event recording who called which API on which indexes, and the
+    /// outcome of the call, is emitted for each ingest, search, and admin request.
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub sink: AuditSinkConfig,
+    /// Fields of the audit events whose value is redacted: `client_ip`, `user_agent`, `query` for
+    /// the whole query string, or `query.<name>` for a single query string parameter.
+    #[serde(default)]
+    pub redact: Vec<String>,
+}
+
+impl RestAuditConfig {
+    /// Default ID of the index receiving the audit events.
+    pub const DEFAULT_INDEX_ID: &'static str = "quickwit-audit-v0_1";
+}
+
+/// Rate limits of the ingest and search requests sent to the REST API. Each node enforces them
+/// on the requests it receives.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RestRateLimitsConfig {
+    /// Limits of each index without limits of its own in `indexes`.
+    #[serde(default)]
+    pub default_index: RequestRateLimits,
+    /// Limits of specific indexes, keyed by index ID. The unset limits fall back to
+    /// `default_index`.
+    #[serde(default)]
+    pub indexes: BTreeMap<String, RequestRateLimits>,
+    /// Limits of each API key without limits of its own in `api_keys`.
+    #[serde(default)]
+    pub default_api_key: RequestRateLimits,
+    /// Limits of specific API keys, keyed by API key ID. The unset limits fall back to
+    /// `default_api_key`.
+    #[serde(default)]
+    pub api_keys: BTreeMap<String, RequestRateLimits>,
+}
+
+impl RestRateLimitsConfig {
+    /// Returns whether at least one limit is set.
+    pub fn is_enabled(&self) -> bool {
+        [&self.default_index, &self.default_api_key]
+            .into_iter()
+            .chain(self.indexes.values())
+            .chain(self.api_keys.values())
+            .any(|limits| limits.ingest_bytes_per_sec.is_some() || limits.search_qps.is_some())
+    }
+
+    /// Returns the limits of an index.
+    pub fn index_limits(&self, index_id: &str) -> RequestRateLimits {
+        self.indexes
+            .get(index_id)
+            .map_or(self.default_index, |limits| limits.or(&self.default_index))
+    }
+
+    /// Returns the IDs of the indexes with dedicated limits matching an index ID pattern.
+    pub fn limited_index_ids_matching<'a>(
+        &'a self,
+        index_id_pattern: &str,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        let pattern_regex = pattern_regex(index_id_pattern);
+        self.indexes
+            .keys()
+            .map(String::as_str)
+            .filter(move |index_id| pattern_regex.is_match(index_id))
+    }
+
+    /// Returns the limits of an API key.
+    pub fn api_key_limits(&self, api_key_id: &str) -> RequestRateLimits {
+        self.api_keys
+            .get(api_key_id)
+            .map_or(self.default_api_key, |limits| {
+                limits.or(&self.default_api_key)
+            })
+    }
+}
+
+/// Token bucket limits of the requests of an index or of an API key. `None` means unlimited.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestRateLimits {
+    /// Maximum number of bytes ingested per second, measured on the request bodies.
+    #[serde(default)]
+    pub ingest_bytes_per_sec: Option<ByteSize>,
+    /// Maximum number of search requests per second.
+    #[serde(default)]
+    pub search_qps: Option<NonZeroU32>,
+}
+
+impl RequestRateLimits {
+    fn or(&self, default_limits: &RequestRateLimits) -> RequestRateLimits {
+        RequestRateLimits {
+            ingest_bytes_per_sec: self
+                .ingest_bytes_per_sec
+                .or(default_limits.ingest_bytes_per_sec),
+            search_qps: self.search_qps.or(default_limits.search_qps),
+        }
+    }
+}
+
+/// Destination of the audit events.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuditSinkConfig {
+    /// Writes the audit events to the standard output, one JSON object per line.
+    #[default]
+    Stdout,
+    /// Appends the audit events to a file, one JSON object per line.
+    File { path: PathBuf },
+    /// Ingests the audit events into a dedicated index, created on startup if it does not exist.
+    Index {
+        #[serde(default = "AuditSinkConfig::default_index_id")]
+        index_id: String,
+    },
+}
+
+impl AuditSinkConfig {
+    fn default_index_id() -> String {
+        RestAuditConfig::DEFAULT_INDEX_ID.to_string()
+    }
+}
+
+/// Certificate and private key of a TLS server. The files are reloaded when they change on disk, so
+/// certificates can be rotated without restarting the node.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// Path to the PEM-encoded certificate chain presented by the server.
+    pub cert_path: PathBuf,
+    /// Path to the PEM-encoded private key of the certificate.
+    pub key_path: PathBuf,
+    /// Path to the PEM-encoded CA certificates trusted by the node when it connects to the other
+    /// nodes over gRPC. Defaults to the system's root certificates. gRPC only.
+    #[serde(default)]
+    pub ca_path: Option<PathBuf>,
+    /// Name against which the certificates of the other nodes are verified when the node
+    /// connects to them over gRPC. Defaults to their IP address. gRPC only.
+    #[serde(default)]
+    pub server_name: Option<String>,
+    /// Mutual TLS enforcement mode between the nodes of the cluster. When enabled, the node
+    /// presents its certificate to the other nodes, and the gRPC server verifies the
+    /// certificates of its clients against `ca_path`. gRPC only.
+    #[serde(default)]
+    pub mtls: MtlsMode,
+}
+
+/// Mutual TLS enforcement modes, meant to be enabled in steps across a running cluster:
+/// `disabled` -> `permissive` on every node, then `permissive` -> `strict` on every node.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MtlsMode {
+    /// Client certificates are neither presented nor verified.
+    #[default]
+    Disabled,
+    /// The gRPC server also accepts plaintext connections and TLS connections without client
+    /// certificate, but rejects invalid client certificates. The node presents its certificate to
+    /// the other nodes and falls back to plaintext to reach the nodes that do not serve TLS yet.
+    Permissive,
+    /// The gRPC server only accepts TLS connections presenting a valid client certificate, and
+    /// the node only connects to the other nodes over mutual TLS.
+    Strict,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -58,6 +262,10 @@ pub struct RestConfig {
 pub struct GrpcConfig {
     #[serde(default = "GrpcConfig::default_max_message_size")]
     pub max_message_size: ByteSize,
+    /// When set, the gRPC server only accepts TLS connections, and the node connects to the other
+    /// nodes of the cluster over TLS.
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
 }
 
 impl GrpcConfig {
@@ -71,6 +279,12 @@ impl GrpcConfig {
             "max gRPC message size (`grpc.max_message_size`) must be at least 1MB, got `{}`",
             self.max_message_size
         );
+        if let Some(tls_config) = &self.tls {
+            ensure!(
+                tls_config.mtls == MtlsMode::Disabled || tls_config.ca_path.is_some(),
+                "`grpc.tls.ca_path` must be set when mutual TLS (`grpc.tls.mtls`) is enabled"
+            );
+        }
         Ok(())
     }
 }
@@ -79,10 +293,87 @@ impl Default for GrpcConfig {
     fn default() -> Self {
         Self {
             max_message_size: Self::default_max_message_size(),
+            tls: None,
         }
     }
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GossipConfig {
+    /// Encryption enforcement mode of the gossip messages exchanged over UDP.
+    #[serde(default)]
+    pub encryption: GossipEncryptionMode,
+    /// Base64-encoded 32-byte keys shared by the nodes of the cluster. The first key encrypts the
+    /// messages sent by the node, and all the keys are tried to decrypt the messages it receives,
+    /// so keys can be rotated without downtime.
+    #[serde(default)]
+    pub secret_keys: Vec<String>,
+}
+
+impl GossipConfig {
+    /// Length in bytes of the gossip secret keys.
+    pub const SECRET_KEY_LEN: usize = 32;
+
+    /// Decodes the secret keys, in the order in which they are configured.
+    pub fn decode_secret_keys(&self) -> anyhow::Result<Vec<[u8; Self::SECRET_KEY_LEN]>> {
+        self.secret_keys
+            .iter()
+            .enumerate()
+            .map(|(key_ord, secret_key)| {
+                let key_bytes = BASE64_STANDARD.decode(secret_key).with_context(|| {
+                    format!(
+                        "gossip secret key #{key_ord} (`gossip.secret_keys`) is not valid base64"
+                    )
+                })?;
+                key_bytes.try_into().map_err(|key_bytes: Vec<u8>| {
+                    anyhow::anyhow!(
+                        "gossip secret key #{key_ord} (`gossip.secret_keys`) must be {} bytes \
+                         long, got {} bytes",
+                        Self::SECRET_KEY_LEN,
+                        key_bytes.len()
+                    )
+                })
+            })
+            .collect()
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.encryption == GossipEncryptionMode::Disabled || !self.secret_keys.is_empty(),
+            "at least one secret key (`gossip.secret_keys`) must be set when gossip encryption \
+             (`gossip.encryption`) is enabled"
+        );
+        self.decode_secret_keys()?;
+        Ok(())
+    }
+
+    /// Redacts the secret keys.
+    pub fn redact(&mut self) {
+        for secret_key in &mut self.secret_keys {
+            *secret_key = "***redacted***".to_string();
+        }
+    }
+}
+
+/// Gossip encryption enforcement modes, meant to be enabled in steps across a running cluster:
+/// `disabled` -> `passive` on every node, then `passive` -> `permissive` on every node, then
+/// `permissive` -> `strict` on every node.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GossipEncryptionMode {
+    /// Gossip messages are sent and accepted in plaintext.
+    #[default]
+    Disabled,
+    /// The node accepts both plaintext and encrypted messages, but sends plaintext messages.
+    Passive,
+    /// The node accepts both plaintext and encrypted messages, and sends encrypted messages.
+    Permissive,
+    /// The node sends encrypted messages and drops the messages that are not encrypted and
+    /// authenticated with one of its secret keys.
+    Strict,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct IndexerConfig {
@@ -206,6 +497,124 @@ impl SplitCacheLimits {
     }
 }
 
+/// Hedging of the leaf search requests: when a leaf search request is slower than most of the
+/// recent ones, a duplicate request is sent to another searcher and the first response wins.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LeafSearchHedgingConfig {
+    /// Percentile of the latencies of the recent leaf search requests after which a request is
+    /// hedged.
+    #[serde(default = "LeafSearchHedgingConfig::default_latency_percentile")]
+    pub latency_percentile: u8,
+    /// Upper bound of the delay after which a request is hedged. It is also the delay used until
+    /// enough latencies have been observed.
+    #[serde(default = "LeafSearchHedgingConfig::default_max_delay_millis")]
+    pub max_delay_millis: NonZeroU64,
+    /// Maximum percentage of the leaf search requests that can be hedged, so that hedging cannot
+    /// multiply the load when all the searchers are slow.
+    #[serde(default = "LeafSearchHedgingConfig::default_max_hedged_requests_percent")]
+    pub max_hedged_requests_percent: u8,
+}
+
+impl LeafSearchHedgingConfig {
+    fn default_latency_percentile() -> u8 {
+        95
+    }
+
+    fn default_max_delay_millis() -> NonZeroU64 {
+        NonZeroU64::new(2_000).unwrap()
+    }
+
+    fn default_max_hedged_requests_percent() -> u8 {
+        10
+    }
+
+    /// The upper bound of the hedging delay.
+    pub fn max_delay(&self) -> Duration {
+        Duration::from_millis(self.max_delay_millis.get())
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.latency_percentile > 0 && self.latency_percentile < 100,
+            "leaf_search_hedging.latency_percentile must be between 1 and 99, got `{}`",
+            self.latency_percentile
+        );
+        ensure!(
+            self.max_hedged_requests_percent > 0 && self.max_hedged_requests_percent <= 100,
+            "leaf_search_hedging.max_hedged_requests_percent must be between 1 and 100, got `{}`",
+            self.max_hedged_requests_percent
+        );
+        Ok(())
+    }
+}
+
+impl Default for LeafSearchHedgingConfig {
+    fn default() -> Self {
+        Self {
+            latency_percentile: Self::default_latency_percentile(),
+            max_delay_millis: Self::default_max_delay_millis(),
+            max_hedged_requests_percent: Self::default_max_hedged_requests_percent(),
+        }
+    }
+}
+
+/// Admission control of the root searches: limits the number of searches running concurrently,
+/// per priority, per index and per API key. The searches over a limit are queued until they can
+/// run or until `max_queue_duration_millis` elapses.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SearchAdmissionControlConfig {
+    /// Maximum number of interactive searches running concurrently.
+    #[serde(default = "SearchAdmissionControlConfig::default_max_concurrent_interactive_searches")]
+    pub max_concurrent_interactive_searches: NonZeroUsize,
+    /// Maximum number of batch searches running concurrently. Batch searches do not use the slots
+    /// of the interactive searches, so they cannot starve them.
+    #[serde(default = "SearchAdmissionControlConfig::default_max_concurrent_batch_searches")]
+    pub max_concurrent_batch_searches: NonZeroUsize,
+    /// Maximum number of searches running concurrently on the same indexes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_searches_per_index: Option<NonZeroUsize>,
+    /// Maximum number of searches running concurrently for the same API key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_searches_per_api_key: Option<NonZeroUsize>,
+    /// Maximum time a search waits in the queue before being rejected.
+    #[serde(default = "SearchAdmissionControlConfig::default_max_queue_duration_millis")]
+    pub max_queue_duration_millis: NonZeroU64,
+}
+
+impl SearchAdmissionControlConfig {
+    fn default_max_concurrent_interactive_searches() -> NonZeroUsize {
+        NonZeroUsize::new(100).unwrap()
+    }
+
+    fn default_max_concurrent_batch_searches() -> NonZeroUsize {
+        NonZeroUsize::new(4).unwrap()
+    }
+
+    fn default_max_queue_duration_millis() -> NonZeroU64 {
+        NonZeroU64::new(5_000).unwrap()
+    }
+
+    /// The maximum time a search waits in the queue before being rejected.
+    pub fn max_queue_duration(&self) -> Duration {
+        Duration::from_millis(self.max_queue_duration_millis.get())
+    }
+}
+
+impl Default for SearchAdmissionControlConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_interactive_searches: Self::default_max_concurrent_interactive_searches(
+            ),
+            max_concurrent_batch_searches: Self::default_max_concurrent_batch_searches(),
+            max_concurrent_searches_per_index: None,
+            max_concurrent_searches_per_api_key: None,
+            max_queue_duration_millis: Self::default_max_queue_duration_millis(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields, default)]
 pub struct SearcherConfig {
@@ -214,6 +623,7 @@ pub struct SearcherConfig {
     pub fast_field_cache_capacity: ByteSize,
     pub split_footer_cache_capacity: ByteSize,
     pub partial_request_cache_capacity: ByteSize,
+    pub search_result_cache_capacity: ByteSize,
     pub max_num_concurrent_split_searches: usize,
     pub max_num_concurrent_split_streams: usize,
     // Strangely, if None, this will also have the effect of not forwarding
@@ -221,6 +631,10 @@ pub struct SearcherConfig {
     // TODO document and fix if necessary.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub split_cache: Option<SplitCacheLimits>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leaf_search_hedging: Option<LeafSearchHedgingConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admission_control: Option<SearchAdmissionControlConfig>,
     #[serde(default = "SearcherConfig::default_request_timeout_secs")]
     request_timeout_secs: NonZeroU64,
 }
@@ -231,11 +645,14 @@ impl Default for SearcherConfig {
             fast_field_cache_capacity: ByteSize::gb(1),
             split_footer_cache_capacity: ByteSize::mb(500),
             partial_request_cache_capacity: ByteSize::mb(64),
+            search_result_cache_capacity: ByteSize::mb(64),
             max_num_concurrent_split_streams: 100,
             max_num_concurrent_split_searches: 100,
             aggregation_memory_limit: ByteSize::mb(500),
             aggregation_bucket_limit: 65000,
             split_cache: None,
+            leaf_search_hedging: None,
+            admission_control: None,
             request_timeout_secs: Self::default_request_timeout_secs(),
         }
     }
@@ -250,6 +667,9 @@ impl SearcherConfig {
         NonZeroU64::new(30).unwrap()
     }
     fn validate(&self) -> anyhow::Result<()> {
+        if let Some(leaf_search_hedging) = self.leaf_search_hedging {
+            leaf_search_hedging.validate()?;
+        }
         if let Some(split_cache_limits) = self.split_cache {
             if self.max_num_concurrent_split_searches
                 > split_cache_limits.max_file_descriptors.get() as usize
@@ -418,6 +838,33 @@ impl Default for JaegerConfig {
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PgWireConfig {
+    /// Enables the PostgreSQL wire protocol listener that allows PostgreSQL clients to run
+    /// read-only SQL queries.
+    #[serde(default)]
+    pub enable_endpoint: bool,
+    /// Port the PostgreSQL wire protocol listener binds to, on the node listen address.
+    #[serde(default = "PgWireConfig::default_listen_port")]
+    pub listen_port: u16,
+}
+
+impl PgWireConfig {
+    fn default_listen_port() -> u16 {
+        7282
+    }
+}
+
+impl Default for PgWireConfig {
+    fn default() -> Self {
+        Self {
+            enable_endpoint: false,
+            listen_port: Self::default_listen_port(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct NodeConfig {
     pub cluster_id: String,
@@ -434,12 +881,14 @@ pub struct NodeConfig {
     pub default_index_root_uri: Uri,
     pub rest_config: RestConfig,
     pub grpc_config: GrpcConfig,
+    pub gossip_config: GossipConfig,
     pub storage_configs: StorageConfigs,
     pub metastore_configs: MetastoreConfigs,
     pub indexer_config: IndexerConfig,
     pub searcher_config: SearcherConfig,
     pub ingest_api_config: IngestApiConfig,
     pub jaeger_config: JaegerConfig,
+    pub pg_wire_config: PgWireConfig,
 }
 
 impl NodeConfig {
@@ -488,6 +937,8 @@ impl NodeConfig {
         self.metastore_configs.redact();
         self.metastore_uri.redact();
         self.storage_configs.redact();
+        self.rest_config.auth.redact();
+        self.gossip_config.redact();
     }
 
     /// Creates a config with defaults suitable for testing.
@@ -572,6 +1023,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_leaf_search_hedging_config_serialization() {
+        let searcher_config_yaml = r#"
+            leaf_search_hedging:
+              latency_percentile: 99
+        "#;
+        let searcher_config: SearcherConfig = serde_yaml::from_str(searcher_config_yaml).unwrap();
+        assert_eq!(
+            searcher_config.leaf_search_hedging,
+            Some(LeafSearchHedgingConfig {
+                latency_percentile: 99,
+                max_delay_millis: NonZeroU64::new(2_000).unwrap(),
+                max_hedged_requests_percent: 10,
+            })
+        );
+        searcher_config.validate().unwrap();
+
+        let searcher_config_yaml = r#"
+            leaf_search_hedging:
+              max_hedged_requests_percent: 0
+        "#;
+        let searcher_config: SearcherConfig = serde_yaml::from_str(searcher_config_yaml).unwrap();
+        searcher_config.validate().unwrap_err();
+
+        let searcher_config_yaml = r#"
+            leaf_search_hedging:
+              latency_percentile: 100
+              max_delay_millis: 500
+        "#;
+        let searcher_config: SearcherConfig = serde_yaml::from_str(searcher_config_yaml).unwrap();
+        searcher_config.validate().unwrap_err();
+    }
+
+    #[test]
+    fn test_search_admission_control_config_serialization() {
+        let searcher_config_yaml = r#"
+            admission_control:
+              max_concurrent_batch_searches: 2
+              max_concurrent_searches_per_api_key: 10
+        "#;
+        let searcher_config: SearcherConfig = serde_yaml::from_str(searcher_config_yaml).unwrap();
+        assert_eq!(
+            searcher_config.admission_control,
+            Some(SearchAdmissionControlConfig {
+                max_concurrent_interactive_searches: NonZeroUsize::new(100).unwrap(),
+                max_concurrent_batch_searches: NonZeroUsize::new(2).unwrap(),
+                max_concurrent_searches_per_index: None,
+                max_concurrent_searches_per_api_key: Some(NonZeroUsize::new(10).unwrap()),
+                max_queue_duration_millis: NonZeroU64::new(5_000).unwrap(),
+            })
+        );
+        let searcher_config_yaml = r#"
+            admission_control:
+              max_concurrent_batch_searches: 0
+        "#;
+        serde_yaml::from_str::<SearcherConfig>(searcher_config_yaml).unwrap_err();
+    }
+
     #[test]
     fn test_validate_ingest_api_default() {
         let ingest_api_config: IngestApiConfig = serde_yaml::from_str("").unwrap();
@@ -642,12 +1151,60 @@ mod tests {
     fn test_grpc_config_validate() {
         let grpc_config = GrpcConfig {
             max_message_size: ByteSize::mb(1),
+            tls: None,
         };
         assert!(grpc_config.validate().is_ok());
 
         let grpc_config = GrpcConfig {
             max_message_size: ByteSize::kb(1),
+            tls: None,
         };
         assert!(grpc_config.validate().is_err());
     }
+
+    #[test]
+    fn test_gossip_config_validate() {
+        let secret_key = BASE64_STANDARD.encode([7u8; GossipConfig::SECRET_KEY_LEN]);
+
+        let gossip_config = GossipConfig::default();
+        assert!(gossip_config.validate().is_ok());
+
+        let gossip_config = GossipConfig {
+            encryption: GossipEncryptionMode::Strict,
+            secret_keys: Vec::new(),
+        };
+        let error = gossip_config.validate().unwrap_err();
+        assert!(error.to_string().contains("`gossip.secret_keys`"));
+
+        let gossip_config = GossipConfig {
+            encryption: GossipEncryptionMode::Strict,
+            secret_keys: vec![secret_key.clone(), "not-base64!".to_string()],
+        };
+        let error = gossip_config.validate().unwrap_err();
+        assert!(error.to_string().contains("#1"));
+
+        let gossip_config = GossipConfig {
+            encryption: GossipEncryptionMode::Passive,
+            secret_keys: vec![BASE64_STANDARD.encode([7u8; 16])],
+        };
+        let error = gossip_config.validate().unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("must be 32 bytes long, got 16 bytes"));
+
+        let mut gossip_config = GossipConfig {
+            encryption: GossipEncryptionMode::Permissive,
+            secret_keys: vec![secret_key],
+        };
+        gossip_config.validate().unwrap();
+        assert_eq!(
+            gossip_config.decode_secret_keys().unwrap(),
+            vec![[7u8; GossipConfig::SECRET_KEY_LEN]]
+        );
+        gossip_config.redact();
+        assert_eq!(
+            gossip_config.secret_keys,
+            vec!["***redacted***".to_string()]
+        );
+    }
 }
@@ -22,7 +22,8 @@ use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use std::time::Duration;
 
-use anyhow::{bail, Context};
+use anyhow::{bail, ensure, Context};
+use bytesize::ByteSize;
 use http::HeaderMap;
 use quickwit_common::net::{find_private_ip, get_short_hostname, Host};
 use quickwit_common::new_coolid;
@@ -31,7 +32,10 @@ use quickwit_proto::types::NodeId;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
-use super::{GrpcConfig, RestConfig};
+use super::{
+    AuditSinkConfig, GossipConfig, GrpcConfig, MtlsMode, RestAuditConfig, RestAuthConfig,
+    RestConfig, RestRateLimitsConfig, TlsConfig,
+};
 use crate::config_value::ConfigValue;
 use crate::qw_env_vars::*;
 use crate::service::QuickwitService;
@@ -39,7 +43,7 @@ use crate::storage_config::StorageConfigs;
 use crate::templating::render_config;
 use crate::{
     validate_identifier, validate_node_id, ConfigFormat, IndexerConfig, IngestApiConfig,
-    JaegerConfig, MetastoreConfigs, NodeConfig, SearcherConfig,
+    JaegerConfig, MetastoreConfigs, NodeConfig, PgWireConfig, SearcherConfig,
 };
 
 pub const DEFAULT_CLUSTER_ID: &str = "quickwit-default-cluster";
@@ -102,6 +106,10 @@ fn default_rest_listen_port() -> u16 {
     7280
 }
 
+fn default_rest_compression_min_size() -> ByteSize {
+    ByteSize::kib(1)
+}
+
 fn default_data_dir_uri() -> ConfigValue<Uri, QW_DATA_DIR> {
     ConfigValue::with_default(Uri::from_str(DEFAULT_DATA_DIR_PATH).unwrap())
 }
@@ -195,6 +203,9 @@ struct NodeConfigBuilder {
     #[serde(rename = "grpc")]
     #[serde(default)]
     grpc_config: GrpcConfig,
+    #[serde(rename = "gossip")]
+    #[serde(default)]
+    gossip_config: GossipConfig,
     #[serde(rename = "storage")]
     #[serde(default)]
     storage_configs: StorageConfigs,
@@ -213,6 +224,9 @@ struct NodeConfigBuilder {
     #[serde(rename = "jaeger")]
     #[serde(default)]
     jaeger_config: JaegerConfig,
+    #[serde(rename = "pg_wire")]
+    #[serde(default)]
+    pg_wire_config: PgWireConfig,
 }
 
 impl NodeConfigBuilder {
@@ -251,6 +265,7 @@ impl NodeConfigBuilder {
             .build_and_validate(listen_ip, env_vars)?;
 
         self.grpc_config.validate()?;
+        self.gossip_config.validate()?;
 
         let gossip_listen_port = self
             .gossip_listen_port
@@ -320,12 +335,14 @@ impl NodeConfigBuilder {
             default_index_root_uri,
             rest_config,
             grpc_config: self.grpc_config,
+            gossip_config: self.gossip_config,
             metastore_configs: self.metastore_configs,
             storage_configs: self.storage_configs,
             indexer_config: self.indexer_config,
             searcher_config: self.searcher_config,
             ingest_api_config: self.ingest_api_config,
             jaeger_config: self.jaeger_config,
+            pg_wire_config: self.pg_wire_config,
         };
 
         validate(&node_config)?;
@@ -343,6 +360,28 @@ fn validate(node_config: &NodeConfig) -> anyhow::Result<()> {
     if node_config.peer_seeds.is_empty() {
         warn!("peer seeds are empty");
     }
+    if node_config.rest_config.auth.enabled {
+        // The gRPC services used by the nodes to communicate with each other, including the
+        // metastore service that manages the API keys and the roles, do not check API keys, so
+        // only the nodes of the cluster must be able to reach them.
+        let mtls_mode = node_config
+            .grpc_config
+            .tls
+            .as_ref()
+            .map(|tls_config| tls_config.mtls)
+            .unwrap_or_default();
+        ensure!(
+            mtls_mode == MtlsMode::Strict,
+            "API key authentication (`rest.auth.enabled`) requires strict mutual TLS between the \
+             nodes (`grpc.tls.mtls: strict`)"
+        );
+        // The PostgreSQL wire protocol server does not authenticate its clients.
+        ensure!(
+            !node_config.pg_wire_config.enable_endpoint,
+            "the PostgreSQL wire protocol endpoint (`pg_wire.enable_endpoint`) cannot be enabled \
+             along with API key authentication (`rest.auth.enabled`)"
+        );
+    }
     Ok(())
 }
 
@@ -365,12 +404,14 @@ impl Default for NodeConfigBuilder {
             default_index_root_uri: ConfigValue::none(),
             rest_config_builder: RestConfigBuilder::default(),
             grpc_config: GrpcConfig::default(),
+            gossip_config: GossipConfig::default(),
             storage_configs: StorageConfigs::default(),
             metastore_configs: MetastoreConfigs::default(),
             indexer_config: IndexerConfig::default(),
             searcher_config: SearcherConfig::default(),
             ingest_api_config: IngestApiConfig::default(),
             jaeger_config: JaegerConfig::default(),
+            pg_wire_config: PgWireConfig::default(),
         }
     }
 }
@@ -387,6 +428,18 @@ struct RestConfigBuilder {
     #[serde(with = "http_serde::header_map")]
     #[serde(default)]
     pub extra_headers: HeaderMap,
+    #[serde(default)]
+    compression_enabled: Option<bool>,
+    #[serde(default)]
+    compression_min_size: Option<ByteSize>,
+    #[serde(default)]
+    auth: RestAuthConfig,
+    #[serde(default)]
+    tls: Option<TlsConfig>,
+    #[serde(default)]
+    audit: RestAuditConfig,
+    #[serde(default)]
+    rate_limits: RestRateLimitsConfig,
 }
 
 impl RestConfigBuilder {
@@ -401,10 +454,67 @@ impl RestConfigBuilder {
             listen_port_from_config_or_default,
         )
         .resolve(env_vars)?;
+        let compression_min_size = if self.compression_enabled.unwrap_or(true) {
+            Some(
+                self.compression_min_size
+                    .unwrap_or(default_rest_compression_min_size()),
+            )
+        } else {
+            None
+        };
+        if let Some(root_api_key) = &self.auth.root_api_key {
+            ensure!(
+                root_api_key.len() >= 16,
+                "root API key (`rest.auth.root_api_key`) must be at least 16 characters long"
+            );
+        }
+        if let Some(tls_config) = &self.tls {
+            ensure!(
+                tls_config.ca_path.is_none()
+                    && tls_config.server_name.is_none()
+                    && tls_config.mtls == MtlsMode::Disabled,
+                "`rest.tls.ca_path`, `rest.tls.server_name`, and `rest.tls.mtls` are not \
+                 supported, they only apply to `grpc.tls`"
+            );
+        }
+        for redaction_rule in &self.audit.redact {
+            let is_valid = match redaction_rule.as_str() {
+                "client_ip" | "user_agent" | "query" => true,
+                _ => redaction_rule
+                    .strip_prefix("query.")
+                    .is_some_and(|param_name| !param_name.is_empty()),
+            };
+            ensure!(
+                is_valid,
+                "invalid audit redaction rule `{redaction_rule}` (`rest.audit.redact`), expected \
+                 `client_ip`, `user_agent`, `query`, or `query.<name>`"
+            );
+        }
+        if let AuditSinkConfig::Index { index_id } = &self.audit.sink {
+            validate_identifier("audit index ID", index_id)?;
+        }
+        let rate_limits = &self.rate_limits;
+
+        for limits in [&rate_limits.default_index, &rate_limits.default_api_key]
+            .into_iter()
+            .chain(rate_limits.indexes.values())
+            .chain(rate_limits.api_keys.values())
+        {
+            ensure!(
+                limits.ingest_bytes_per_sec != Some(ByteSize(0)),
+                "ingest rate limit (`rest.rate_limits.*.ingest_bytes_per_sec`) must be greater \
+                 than 0"
+            );
+        }
         let rest_config = RestConfig {
             listen_addr: SocketAddr::new(listen_ip, listen_port),
             cors_allow_origins: self.cors_allow_origins,
             extra_headers: self.extra_headers,
+            compression_min_size,
+            auth: self.auth,
+            tls: self.tls,
+            audit: self.audit,
+            rate_limits: self.rate_limits,
         };
         Ok(rest_config)
     }
@@ -442,6 +552,11 @@ pub fn node_config_for_tests_from_ports(
         listen_addr: rest_listen_addr,
         cors_allow_origins: Vec::new(),
         extra_headers: HeaderMap::new(),
+        compression_min_size: Some(default_rest_compression_min_size()),
+        auth: RestAuthConfig::default(),
+        tls: None,
+        audit: RestAuditConfig::default(),
+        rate_limits: RestRateLimitsConfig::default(),
     };
     NodeConfig {
         cluster_id: default_cluster_id().unwrap(),
@@ -458,12 +573,14 @@ pub fn node_config_for_tests_from_ports(
         default_index_root_uri,
         rest_config,
         grpc_config: GrpcConfig::default(),
+        gossip_config: GossipConfig::default(),
         storage_configs: StorageConfigs::default(),
         metastore_configs: MetastoreConfigs::default(),
         indexer_config: IndexerConfig::default(),
         searcher_config: SearcherConfig::default(),
         ingest_api_config: IngestApiConfig::default(),
         jaeger_config: JaegerConfig::default(),
+        pg_wire_config: PgWireConfig::default(),
     }
 }
 
@@ -471,13 +588,13 @@ pub fn node_config_for_tests_from_ports(
 mod tests {
     use std::env;
     use std::net::Ipv4Addr;
-    use std::num::{NonZeroU64, NonZeroUsize};
-    use std::path::Path;
+    use std::num::{NonZeroU32, NonZeroU64, NonZeroUsize};
+    use std::path::{Path, PathBuf};
 
-    use bytesize::ByteSize;
     use itertools::Itertools;
 
     use super::*;
+    use crate::node_config::GossipEncryptionMode;
     use crate::storage_config::StorageBackendFlavor;
 
     fn get_config_filepath(config_filename: &str) -> String {
@@ -608,9 +725,12 @@ mod tests {
                 fast_field_cache_capacity: ByteSize::gb(10),
                 split_footer_cache_capacity: ByteSize::gb(1),
                 partial_request_cache_capacity: ByteSize::mb(64),
+                search_result_cache_capacity: ByteSize::mb(64),
                 max_num_concurrent_split_searches: 150,
                 max_num_concurrent_split_streams: 120,
                 split_cache: None,
+                leaf_search_hedging: None,
+                admission_control: None,
                 request_timeout_secs: NonZeroU64::new(30).unwrap(),
             }
         );
@@ -623,6 +743,13 @@ mod tests {
                 max_fetch_spans: NonZeroU64::new(1_000).unwrap(),
             }
         );
+        assert_eq!(
+            config.pg_wire_config,
+            PgWireConfig {
+                enable_endpoint: true,
+                listen_port: 5433,
+            }
+        );
         Ok(())
     }
 
@@ -836,6 +963,7 @@ mod tests {
         assert_eq!(config.searcher_config, SearcherConfig::default());
         assert_eq!(config.ingest_api_config, IngestApiConfig::default());
         assert_eq!(config.jaeger_config, JaegerConfig::default());
+        assert_eq!(config.pg_wire_config, PgWireConfig::default());
     }
 
     #[tokio::test]
@@ -1101,6 +1229,456 @@ mod tests {
             .contains("max_trace_duration_secs: invalid value: integer `0`"))
     }
 
+    #[tokio::test]
+    async fn test_rest_config_compression() {
+        let rest_config_yaml = r#"
+            version: 0.8
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            config.rest_config.compression_min_size,
+            Some(ByteSize::kib(1))
+        );
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              compression_min_size: 4KiB
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            config.rest_config.compression_min_size,
+            Some(ByteSize::kib(4))
+        );
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              compression_enabled: false
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(config.rest_config.compression_min_size, None);
+    }
+
+    #[tokio::test]
+    async fn test_rest_config_auth() {
+        let rest_config_yaml = r#"
+            version: 0.8
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(config.rest_config.auth, RestAuthConfig::default());
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              auth:
+                enabled: true
+                root_api_key: 0123456789abcdef
+            grpc:
+              tls:
+                cert_path: /etc/quickwit/grpc.crt
+                key_path: /etc/quickwit/grpc.key
+                ca_path: /etc/quickwit/ca.crt
+                mtls: strict
+        "#;
+        let mut config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert!(config.rest_config.auth.enabled);
+        assert_eq!(
+            config.rest_config.auth.root_api_key.as_deref(),
+            Some("0123456789abcdef")
+        );
+        config.redact();
+        assert_eq!(
+            config.rest_config.auth.root_api_key.as_deref(),
+            Some("***redacted***")
+        );
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              auth:
+                enabled: true
+                root_api_key: secret
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("at least 16 characters"));
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              auth:
+                enabled: true
+            grpc:
+              tls:
+                cert_path: /etc/quickwit/grpc.crt
+                key_path: /etc/quickwit/grpc.key
+                ca_path: /etc/quickwit/ca.crt
+                mtls: permissive
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("requires strict mutual TLS"));
+
+        let rest_config_yaml = r#"
+            version: 0.8
+            rest:
+              auth:
+                enabled: true
+            grpc:
+              tls:
+                cert_path: /etc/quickwit/grpc.crt
+                key_path: /etc/quickwit/grpc.key
+                ca_path: /etc/quickwit/ca.crt
+                mtls: strict
+            pg_wire:
+              enable_endpoint: true
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rest_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("`pg_wire.enable_endpoint`"));
+    }
+
+    #[tokio::test]
+    async fn test_gossip_config() {
+        let gossip_config_yaml = r#"
+            version: 0.8
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            gossip_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(config.gossip_config, GossipConfig::default());
+
+        let gossip_config_yaml = r#"
+            version: 0.8
+            gossip:
+              encryption: strict
+              secret_keys:
+                - AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=
+        "#;
+        let mut config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            gossip_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            config.gossip_config.encryption,
+            GossipEncryptionMode::Strict
+        );
+        let secret_keys = config.gossip_config.decode_secret_keys().unwrap();
+        assert_eq!(secret_keys.len(), 1);
+        assert_eq!(secret_keys[0][31], 31);
+        config.redact();
+        assert_eq!(config.gossip_config.secret_keys, vec!["***redacted***"]);
+
+        let gossip_config_yaml = r#"
+            version: 0.8
+            gossip:
+              encryption: permissive
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            gossip_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("`gossip.secret_keys`"));
+    }
+
+    #[tokio::test]
+    async fn test_tls_config() {
+        let tls_config_yaml = r#"
+            version: 0.8
+            rest:
+              tls:
+                cert_path: /etc/quickwit/rest.crt
+                key_path: /etc/quickwit/rest.key
+            grpc:
+              tls:
+                cert_path: /etc/quickwit/grpc.crt
+                key_path: /etc/quickwit/grpc.key
+                ca_path: /etc/quickwit/ca.crt
+                server_name: quickwit.internal
+                mtls: strict
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            tls_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            config.rest_config.tls,
+            Some(TlsConfig {
+                cert_path: PathBuf::from("/etc/quickwit/rest.crt"),
+                key_path: PathBuf::from("/etc/quickwit/rest.key"),
+                ca_path: None,
+                server_name: None,
+                mtls: MtlsMode::Disabled,
+            })
+        );
+        assert_eq!(
+            config.grpc_config.tls,
+            Some(TlsConfig {
+                cert_path: PathBuf::from("/etc/quickwit/grpc.crt"),
+                key_path: PathBuf::from("/etc/quickwit/grpc.key"),
+                ca_path: Some(PathBuf::from("/etc/quickwit/ca.crt")),
+                server_name: Some("quickwit.internal".to_string()),
+                mtls: MtlsMode::Strict,
+            })
+        );
+
+        let tls_config_yaml = r#"
+            version: 0.8
+            rest:
+              tls:
+                cert_path: /etc/quickwit/rest.crt
+                key_path: /etc/quickwit/rest.key
+                ca_path: /etc/quickwit/ca.crt
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            tls_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("only apply to `grpc.tls`"));
+
+        let tls_config_yaml = r#"
+            version: 0.8
+            grpc:
+              tls:
+                cert_path: /etc/quickwit/grpc.crt
+                key_path: /etc/quickwit/grpc.key
+                mtls: permissive
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            tls_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("`grpc.tls.ca_path` must be set"));
+    }
+
+    #[tokio::test]
+    async fn test_rest_audit_config() {
+        let config =
+            load_node_config_with_env(ConfigFormat::Yaml, b"version: 0.8", &Default::default())
+                .await
+                .unwrap();
+        assert_eq!(config.rest_config.audit, RestAuditConfig::default());
+        assert_eq!(config.rest_config.audit.sink, AuditSinkConfig::Stdout);
+
+        let audit_config_yaml = r#"
+            version: 0.8
+            rest:
+              audit:
+                enabled: true
+                sink:
+                  type: file
+                  path: /var/log/quickwit/audit.log
+                redact:
+                  - client_ip
+                  - query.q
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            audit_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            config.rest_config.audit,
+            RestAuditConfig {
+                enabled: true,
+                sink: AuditSinkConfig::File {
+                    path: PathBuf::from("/var/log/quickwit/audit.log"),
+                },
+                redact: vec!["client_ip".to_string(), "query.q".to_string()],
+            }
+        );
+
+        let audit_config_yaml = r#"
+            version: 0.8
+            rest:
+              audit:
+                enabled: true
+                sink:
+                  type: index
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            audit_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            config.rest_config.audit.sink,
+            AuditSinkConfig::Index {
+                index_id: RestAuditConfig::DEFAULT_INDEX_ID.to_string(),
+            }
+        );
+
+        let audit_config_yaml = r#"
+            version: 0.8
+            rest:
+              audit:
+                enabled: true
+                redact:
+                  - authorization
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            audit_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("invalid audit redaction rule"));
+    }
+
+    #[tokio::test]
+    async fn test_rest_rate_limits_config() {
+        let config =
+            load_node_config_with_env(ConfigFormat::Yaml, b"version: 0.8", &Default::default())
+                .await
+                .unwrap();
+        assert!(!config.rest_config.rate_limits.is_enabled());
+
+        let rate_limits_config_yaml = r#"
+            version: 0.8
+            rest:
+              rate_limits:
+                default_index:
+                  ingest_bytes_per_sec: 10MB
+                  search_qps: 100
+                indexes:
+                  noisy-index:
+                    search_qps: 10
+                api_keys:
+                  my-key:
+                    ingest_bytes_per_sec: 1MB
+        "#;
+        let config = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rate_limits_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        let rate_limits = &config.rest_config.rate_limits;
+        assert!(rate_limits.is_enabled());
+
+        let index_limits = rate_limits.index_limits("my-index");
+        assert_eq!(index_limits.ingest_bytes_per_sec, Some(ByteSize::mb(10)));
+        assert_eq!(index_limits.search_qps, NonZeroU32::new(100));
+
+        let index_limits = rate_limits.index_limits("noisy-index");
+        assert_eq!(index_limits.ingest_bytes_per_sec, Some(ByteSize::mb(10)));
+        assert_eq!(index_limits.search_qps, NonZeroU32::new(10));
+
+        let limited_index_ids: Vec<&str> =
+            rate_limits.limited_index_ids_matching("noisy-*").collect();
+        assert_eq!(limited_index_ids, ["noisy-index"]);
+        assert_eq!(rate_limits.limited_index_ids_matching("quiet-*").count(), 0);
+
+        let api_key_limits = rate_limits.api_key_limits("my-key");
+        assert_eq!(api_key_limits.ingest_bytes_per_sec, Some(ByteSize::mb(1)));
+        assert_eq!(api_key_limits.search_qps, None);
+
+        let api_key_limits = rate_limits.api_key_limits("other-key");
+        assert_eq!(api_key_limits, crate::RequestRateLimits::default());
+
+        let rate_limits_config_yaml = r#"
+            version: 0.8
+            rest:
+              rate_limits:
+                default_api_key:
+                  search_qps: 0
+        "#;
+        load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rate_limits_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+
+        let rate_limits_config_yaml = r#"
+            version: 0.8
+            rest:
+              rate_limits:
+                default_index:
+                  ingest_bytes_per_sec: 0
+        "#;
+        let error = load_node_config_with_env(
+            ConfigFormat::Yaml,
+            rate_limits_config_yaml.as_bytes(),
+            &Default::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("must be greater than 0"));
+    }
+
     #[tokio::test]
     async fn test_rest_config_accepts_wildcard() {
         let rest_config_yaml = r#"
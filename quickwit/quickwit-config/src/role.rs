@@ -0,0 +1,220 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeSet;
+
+use anyhow::ensure;
+use serde::{Deserialize, Serialize};
+
+use crate::lifecycle_policy::pattern_regex;
+use crate::{validate_identifier, validate_index_id_pattern, ApiKeyScope};
+
+pub type RoleId = String;
+
+/// Privilege granted by a role on a set of indexes.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    Hash,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexPrivilege {
+    /// Searching the indexes and reading their metadata.
+    Read,
+    /// Ingesting documents into the indexes.
+    Write,
+    /// Managing the indexes and their sources. Grants the other privileges as well.
+    Admin,
+}
+
+impl IndexPrivilege {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IndexPrivilege::Read => "read",
+            IndexPrivilege::Write => "write",
+            IndexPrivilege::Admin => "admin",
+        }
+    }
+}
+
+impl From<ApiKeyScope> for IndexPrivilege {
+    fn from(scope: ApiKeyScope) -> Self {
+        match scope {
+            ApiKeyScope::Ingest => IndexPrivilege::Write,
+            ApiKeyScope::Search => IndexPrivilege::Read,
+            ApiKeyScope::Admin => IndexPrivilege::Admin,
+        }
+    }
+}
+
+/// Grants some privileges on the indexes matching the index ID patterns.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct IndexGrant {
+    pub index_id_patterns: Vec<String>,
+    #[schema(value_type = Vec<IndexPrivilege>)]
+    pub privileges: BTreeSet<IndexPrivilege>,
+}
+
+/// A role groups index grants. Roles are assigned to API keys, which are then allowed to access
+/// the indexes covered by the grants of their roles in addition to their scopes.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Role {
+    pub role_id: RoleId,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub grants: Vec<IndexGrant>,
+}
+
+impl Role {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        validate_identifier("role", &self.role_id)?;
+
+        ensure!(
+            !self.grants.is_empty(),
+            "role `{}` must have at least one grant",
+            self.role_id
+        );
+        for grant in &self.grants {
+            ensure!(
+                !grant.index_id_patterns.is_empty(),
+                "`index_id_patterns` must not be empty"
+            );
+            // Negative patterns are not supported: a request targeting `logs-*` could not be
+            // authorized by a grant on `logs-*` excluding `-logs-secret`.
+            for index_id_pattern in &grant.index_id_patterns {
+                validate_index_id_pattern(index_id_pattern, false)?;
+            }
+            ensure!(
+                !grant.privileges.is_empty(),
+                "`privileges` must not be empty"
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns whether the role grants the `privilege` on all the indexes matching
+    /// `index_id_pattern`, which may itself contain `*` wildcards: the grant on `logs-*` covers
+    /// `logs-2024` and `logs-2024-*`, but not `*`.
+    pub fn grants_privilege(&self, index_id_pattern: &str, privilege: IndexPrivilege) -> bool {
+        self.grants.iter().any(|grant| {
+            grant_has_privilege(grant, privilege)
+                && grant.index_id_patterns.iter().any(|granted_pattern| {
+                    // The `*` of the granted pattern also matches the `*` of the requested pattern
+                    // literally, so every index matching the latter also matches the former.
+                    pattern_regex(granted_pattern).is_match(index_id_pattern)
+                })
+        })
+    }
+
+    /// Returns whether the role grants the `privilege` on at least one index pattern.
+    pub fn has_privilege(&self, privilege: IndexPrivilege) -> bool {
+        self.grants
+            .iter()
+            .any(|grant| grant_has_privilege(grant, privilege))
+    }
+
+    #[cfg(any(test, feature = "testsuite"))]
+    pub fn for_test(role_id: &str, index_id_patterns: &[&str], privilege: IndexPrivilege) -> Self {
+        Role {
+            role_id: role_id.to_string(),
+            description: None,
+            grants: vec![IndexGrant {
+                index_id_patterns: index_id_patterns
+                    .iter()
+                    .map(|pattern| pattern.to_string())
+                    .collect(),
+                privileges: BTreeSet::from([privilege]),
+            }],
+        }
+    }
+}
+
+fn grant_has_privilege(grant: &IndexGrant, privilege: IndexPrivilege) -> bool {
+    grant.privileges.contains(&IndexPrivilege::Admin) || grant.privileges.contains(&privilege)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_serde() {
+        let role_json = r#"{
+            "role_id": "analyst",
+            "grants": [
+                {
+                    "index_id_patterns": ["logs-*"],
+                    "privileges": ["read"]
+                }
+            ]
+        }"#;
+        let role: Role = serde_json::from_str(role_json).unwrap();
+        assert_eq!(
+            role,
+            Role::for_test("analyst", &["logs-*"], IndexPrivilege::Read)
+        );
+        role.validate().unwrap();
+    }
+
+    #[test]
+    fn test_role_validate() {
+        let mut role = Role::for_test("analyst", &["logs-*"], IndexPrivilege::Read);
+        role.grants[0].index_id_patterns = vec!["-logs-secret".to_string()];
+        role.validate().unwrap_err();
+
+        role.grants[0].index_id_patterns.clear();
+        let error = role.validate().unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`index_id_patterns` must not be empty"));
+
+        role.grants.clear();
+        let error = role.validate().unwrap_err();
+        assert!(error.to_string().contains("at least one grant"));
+    }
+
+    #[test]
+    fn test_role_grants_privilege() {
+        let role = Role::for_test("analyst", &["logs-*"], IndexPrivilege::Read);
+        assert!(role.grants_privilege("logs-2024", IndexPrivilege::Read));
+        assert!(role.grants_privilege("logs-2024-*", IndexPrivilege::Read));
+        assert!(role.grants_privilege("logs-*", IndexPrivilege::Read));
+        assert!(!role.grants_privilege("logs-2024", IndexPrivilege::Write));
+        assert!(!role.grants_privilege("metrics", IndexPrivilege::Read));
+        assert!(!role.grants_privilege("*", IndexPrivilege::Read));
+        assert!(!role.grants_privilege("log*", IndexPrivilege::Read));
+
+        let role = Role::for_test("logs-admin", &["logs-*"], IndexPrivilege::Admin);
+        assert!(role.grants_privilege("logs-2024", IndexPrivilege::Read));
+        assert!(role.grants_privilege("logs-2024", IndexPrivilege::Write));
+        assert!(role.grants_privilege("logs-2024", IndexPrivilege::Admin));
+        assert!(role.has_privilege(IndexPrivilege::Write));
+    }
+}
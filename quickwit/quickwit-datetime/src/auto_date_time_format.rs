@@ -0,0 +1,208 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use once_cell::sync::Lazy;
+
+use crate::date_time_parsing::parse_date_time_str_with_format;
+use crate::{DateTimeInputFormat, StrptimeParser, TantivyDateTime};
+
+/// Well-known datetime formats tried by the `auto` input format, by order of priority.
+const AUTO_STRPTIME_FORMATS: &[&str] = &[
+    // Common SQL and application log formats.
+    "%Y-%m-%d %H:%M:%S.%f",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y/%m/%d %H:%M:%S",
+    // Apache and NGINX access log format.
+    "%d/%b/%Y:%H:%M:%S %z",
+    // Syslog (RFC 3164) format.
+    "%b %d %H:%M:%S",
+];
+
+static AUTO_DATE_TIME_FORMATS: Lazy<Vec<DateTimeInputFormat>> = Lazy::new(|| {
+    let mut date_time_formats = vec![
+        DateTimeInputFormat::Rfc3339,
+        DateTimeInputFormat::Iso8601,
+        DateTimeInputFormat::Rfc2822,
+    ];
+    for strptime_format in AUTO_STRPTIME_FORMATS {
+        let parser = StrptimeParser::from_str(strptime_format)
+            .expect("auto strptime formats should be valid");
+        date_time_formats.push(DateTimeInputFormat::Strptime(parser));
+    }
+    date_time_formats.push(DateTimeInputFormat::Timestamp);
+    date_time_formats
+});
+
+/// A datetime parser that detects the format of datetime strings among a prioritized list of
+/// well-known formats.
+///
+/// Datetime strings of a given source usually share the same format, so the parser remembers the
+/// last format that succeeded and tries it first. Clones get their own cache, so that each
+/// indexing pipeline converges on its own format.
+#[derive(Default)]
+pub struct AutoDateTimeParser {
+    last_matched_format_idx: AtomicUsize,
+}
+
+impl AutoDateTimeParser {
+    pub(crate) fn parse_date_time_str(
+        &self,
+        date_time_str: &str,
+    ) -> Result<Option<TantivyDateTime>, String> {
+        let date_time_formats: &[DateTimeInputFormat] = &AUTO_DATE_TIME_FORMATS;
+        let last_matched_format_idx = self.last_matched_format_idx.load(Ordering::Relaxed);
+
+        if let Some(date_time) = parse_date_time_str_with_format(
+            date_time_str,
+            &date_time_formats[last_matched_format_idx],
+        )? {
+            return Ok(Some(date_time));
+        }
+        for (format_idx, date_time_format) in date_time_formats.iter().enumerate() {
+            if format_idx == last_matched_format_idx {
+                continue;
+            }
+            if let Some(date_time) =
+                parse_date_time_str_with_format(date_time_str, date_time_format)?
+            {
+                self.last_matched_format_idx
+                    .store(format_idx, Ordering::Relaxed);
+                return Ok(Some(date_time));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Clone for AutoDateTimeParser {
+    fn clone(&self) -> Self {
+        AutoDateTimeParser::default()
+    }
+}
+
+impl PartialEq for AutoDateTimeParser {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for AutoDateTimeParser {}
+
+impl std::fmt::Debug for AutoDateTimeParser {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.debug_struct("AutoDateTimeParser").finish()
+    }
+}
+
+impl std::hash::Hash for AutoDateTimeParser {
+    fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::{parse_date_time_str, parse_timestamp_float, parse_timestamp_int};
+
+    #[test]
+    fn test_auto_date_time_parser() {
+        let parser = AutoDateTimeParser::default();
+        let test_data = [
+            ("2024-02-14T18:40:19Z", datetime!(2024-02-14 18:40:19 UTC)),
+            ("20240214T184019Z", datetime!(2024-02-14 18:40:19 UTC)),
+            (
+                "Wed, 14 Feb 2024 18:40:19 +0100",
+                datetime!(2024-02-14 18:40:19 +01:00),
+            ),
+            (
+                "2024-02-14 18:40:19.950",
+                datetime!(2024-02-14 18:40:19.950 UTC),
+            ),
+            ("2024-02-14 18:40:19", datetime!(2024-02-14 18:40:19 UTC)),
+            ("2024/02/14 18:40:19", datetime!(2024-02-14 18:40:19 UTC)),
+            (
+                "14/Feb/2024:18:40:19 -0500",
+                datetime!(2024-02-14 18:40:19 -05:00),
+            ),
+            ("1707936019", datetime!(2024-02-14 18:40:19 UTC)),
+            ("1707936019950", datetime!(2024-02-14 18:40:19.950 UTC)),
+        ];
+        for (date_time_str, expected) in test_data {
+            let date_time = parser.parse_date_time_str(date_time_str).unwrap().unwrap();
+            assert_eq!(
+                date_time.into_timestamp_nanos(),
+                expected.unix_timestamp_nanos() as i64,
+                "failed to parse `{date_time_str}`"
+            );
+        }
+        parser
+            .parse_date_time_str("Mar  6 17:40:02")
+            .unwrap()
+            .unwrap();
+        assert!(parser.parse_date_time_str("foo").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_auto_date_time_input_format() {
+        let date_time_format = DateTimeInputFormat::from_str("auto").unwrap();
+        assert_eq!(date_time_format.as_str(), "auto");
+
+        let date_time =
+            parse_date_time_str("2024-02-14 18:40:19", &[date_time_format.clone()]).unwrap();
+        assert_eq!(date_time.into_timestamp_secs(), 1_707_936_019);
+
+        let date_time = parse_timestamp_int(1_707_936_019, &[date_time_format.clone()]).unwrap();
+        assert_eq!(date_time.into_timestamp_secs(), 1_707_936_019);
+
+        let date_time = parse_timestamp_float(1_707_936_019.5, &[date_time_format]).unwrap();
+        assert_eq!(date_time.into_timestamp_millis(), 1_707_936_019_500);
+    }
+
+    #[test]
+    fn test_auto_date_time_parser_caches_last_matched_format() {
+        let parser = AutoDateTimeParser::default();
+        assert_eq!(parser.last_matched_format_idx.load(Ordering::Relaxed), 0);
+
+        parser
+            .parse_date_time_str("2024/02/14 18:40:19")
+            .unwrap()
+            .unwrap();
+        let last_matched_format_idx = parser.last_matched_format_idx.load(Ordering::Relaxed);
+        assert_eq!(
+            AUTO_DATE_TIME_FORMATS[last_matched_format_idx].as_str(),
+            "%Y/%m/%d %H:%M:%S"
+        );
+        // A failed parse leaves the cache untouched.
+        assert!(parser.parse_date_time_str("foo").unwrap().is_none());
+        assert_eq!(
+            parser.last_matched_format_idx.load(Ordering::Relaxed),
+            last_matched_format_idx
+        );
+        // Clones start with a fresh cache.
+        let parser_clone = parser.clone();
+        assert_eq!(
+            parser_clone.last_matched_format_idx.load(Ordering::Relaxed),
+            0
+        );
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Compatibility layer for the chrono strftime dialect, used by many Rust tools (e.g. Vector).
+//!
+//! Chrono-specific specifiers are translated into format items directly while the remaining
+//! chunks of the format are handed over to `time-fmt`.
+
+use time::format_description::modifier::{OffsetHour, OffsetMinute, Subsecond, SubsecondDigits};
+use time::format_description::{Component, OwnedFormatItem};
+use time_fmt::parse::time_format_item::parse_to_format_item;
+
+/// Chrono specifiers that are not supported by `time-fmt`.
+///
+/// Specifiers are matched in order, so a specifier must appear before any of its prefixes.
+const CHRONO_SPECIFIERS: &[&str] = &["%.3f", "%.6f", "%.9f", "%.f", "%3f", "%6f", "%9f", "%:z"];
+
+fn literal(literal: &str) -> OwnedFormatItem {
+    OwnedFormatItem::Literal(literal.as_bytes().into())
+}
+
+fn build_subsecond_item(digits: SubsecondDigits) -> OwnedFormatItem {
+    let mut subsecond = Subsecond::default();
+    subsecond.digits = digits;
+    OwnedFormatItem::Component(Component::Subsecond(subsecond))
+}
+
+fn build_chrono_item(specifier: &str) -> OwnedFormatItem {
+    match specifier {
+        // `%.f` is the only specifier that matches an absent fractional part.
+        "%.f" => OwnedFormatItem::Optional(Box::new(OwnedFormatItem::Compound(Box::new([
+            literal("."),
+            build_subsecond_item(SubsecondDigits::OneOrMore),
+        ])))),
+        "%.3f" => OwnedFormatItem::Compound(Box::new([
+            literal("."),
+            build_subsecond_item(SubsecondDigits::Three),
+        ])),
+        "%.6f" => OwnedFormatItem::Compound(Box::new([
+            literal("."),
+            build_subsecond_item(SubsecondDigits::Six),
+        ])),
+        "%.9f" => OwnedFormatItem::Compound(Box::new([
+            literal("."),
+            build_subsecond_item(SubsecondDigits::Nine),
+        ])),
+        "%3f" => build_subsecond_item(SubsecondDigits::Three),
+        "%6f" => build_subsecond_item(SubsecondDigits::Six),
+        "%9f" => build_subsecond_item(SubsecondDigits::Nine),
+        "%:z" => {
+            let mut offset_hour = OffsetHour::default();
+            offset_hour.sign_is_mandatory = true;
+            OwnedFormatItem::Compound(Box::new([
+                OwnedFormatItem::Component(Component::OffsetHour(offset_hour)),
+                literal(":"),
+                OwnedFormatItem::Component(Component::OffsetMinute(OffsetMinute::default())),
+            ]))
+        }
+        _ => unreachable!("specifier `{specifier}` is missing from `CHRONO_SPECIFIERS`"),
+    }
+}
+
+fn push_time_fmt_items(
+    time_fmt_format: &str,
+    items: &mut Vec<OwnedFormatItem>,
+) -> Result<(), String> {
+    if time_fmt_format.is_empty() {
+        return Ok(());
+    }
+    let time_fmt_items =
+        parse_to_format_item(time_fmt_format).map_err(|error| error.to_string())?;
+    items.extend(time_fmt_items.into_iter().map(OwnedFormatItem::from));
+    Ok(())
+}
+
+/// Builds the format items of a strptime format that may contain chrono-specific specifiers.
+///
+/// Returns the format items and whether the format contains a UTC offset.
+pub(crate) fn build_strptime_format_items(
+    strptime_format: &str,
+) -> Result<(Vec<OwnedFormatItem>, bool), String> {
+    let mut items = Vec::new();
+    let mut with_timezone = false;
+    let mut chunk_start = 0;
+    let mut pos = 0;
+
+    'scanner: while pos < strptime_format.len() {
+        let remaining = &strptime_format[pos..];
+
+        if !remaining.starts_with('%') {
+            pos += remaining.chars().next().map(char::len_utf8).unwrap_or(1);
+            continue;
+        }
+        for specifier in CHRONO_SPECIFIERS {
+            if remaining.starts_with(specifier) {
+                push_time_fmt_items(&strptime_format[chunk_start..pos], &mut items)?;
+                items.push(build_chrono_item(specifier));
+                with_timezone |= specifier.ends_with('z');
+                pos += specifier.len();
+                chunk_start = pos;
+                continue 'scanner;
+            }
+        }
+        // Skips the specifier character, so that `%%` is never mistaken for the start of a
+        // specifier.
+        let specifier_len = remaining[1..]
+            .chars()
+            .next()
+            .map(|specifier_char| 1 + specifier_char.len_utf8())
+            .unwrap_or(1);
+        with_timezone |= remaining[1..].starts_with(['z', 'Z']);
+        pos += specifier_len;
+    }
+    push_time_fmt_items(&strptime_format[chunk_start..], &mut items)?;
+    Ok((items, with_timezone))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use time::macros::datetime;
+
+    use crate::StrptimeParser;
+
+    #[test]
+    fn test_parse_chrono_strptime_format() {
+        let test_data = [
+            (
+                "%Y-%m-%dT%H:%M:%S%.f%:z",
+                "2024-02-14T18:40:19.950188+01:00",
+                datetime!(2024-02-14 18:40:19.950188 +01:00),
+            ),
+            (
+                "%Y-%m-%dT%H:%M:%S%.f%:z",
+                "2024-02-14T18:40:19+01:00",
+                datetime!(2024-02-14 18:40:19 +01:00),
+            ),
+            (
+                "%Y-%m-%d %H:%M:%S%.3f",
+                "2024-02-14 18:40:19.950",
+                datetime!(2024-02-14 18:40:19.950 UTC),
+            ),
+            (
+                "%Y-%m-%d %H:%M:%S%.6f",
+                "2024-02-14 18:40:19.950188",
+                datetime!(2024-02-14 18:40:19.950188 UTC),
+            ),
+            (
+                "%Y-%m-%d %H:%M:%S%.9f",
+                "2024-02-14 18:40:19.950188123",
+                datetime!(2024-02-14 18:40:19.950188123 UTC),
+            ),
+            (
+                "%Y-%m-%d %H:%M:%S,%3f",
+                "2024-02-14 18:40:19,950",
+                datetime!(2024-02-14 18:40:19.950 UTC),
+            ),
+            (
+                "%Y-%m-%d %H:%M:%S %:z",
+                "2024-02-14 18:40:19 -05:00",
+                datetime!(2024-02-14 18:40:19 -05:00),
+            ),
+            (
+                "%Y-%m-%d %H:%M:%S %z",
+                "2024-02-14 18:40:19 -0500",
+                datetime!(2024-02-14 18:40:19 -05:00),
+            ),
+            (
+                "100%% %Y-%m-%d",
+                "100% 2024-02-14",
+                datetime!(2024-02-14 00:00:00 UTC),
+            ),
+        ];
+        for (strptime_format, date_time_str, expected) in test_data {
+            let parser = StrptimeParser::from_strptime(strptime_format).unwrap();
+            let date_time = parser.parse_date_time(date_time_str).unwrap();
+            assert_eq!(date_time, expected, "failed to parse `{date_time_str}`");
+        }
+        // The fixed-precision specifiers require the exact number of digits.
+        let parser = StrptimeParser::from_strptime("%Y-%m-%d %H:%M:%S%.3f").unwrap();
+        parser.parse_date_time("2024-02-14 18:40:19").unwrap_err();
+        parser
+            .parse_date_time("2024-02-14 18:40:19.9501")
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_format_chrono_strptime_format() {
+        let parser = StrptimeParser::from_str("%Y-%m-%dT%H:%M:%S%.3f%:z").unwrap();
+        let date_time = datetime!(2024-02-14 18:40:19.950188 +01:00);
+        assert_eq!(
+            parser.format_date_time(&date_time).unwrap(),
+            "2024-02-14T18:40:19.950+01:00"
+        );
+    }
+}
@@ -0,0 +1,388 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use time::{Duration, Month, OffsetDateTime, Time};
+
+use crate::{
+    parse_date_time_str, try_into_tantivy_date_time, DateTimeInputFormat, TantivyDateTime,
+};
+
+/// Specifies in which direction `/unit` rounding operations round the datetime.
+///
+/// Following Elasticsearch semantics, lower bounds of inclusive ranges and upper bounds of
+/// exclusive ranges round down, while upper bounds of inclusive ranges and lower bounds of
+/// exclusive ranges round up.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DateMathRounding {
+    /// Rounds to the first nanosecond of the unit, e.g. `2024-02-14T00:00:00Z` for `/d`.
+    Down,
+    /// Rounds to the last nanosecond of the unit, e.g. `2024-02-14T23:59:59.999999999Z` for
+    /// `/d`.
+    Up,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum DateMathUnit {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl DateMathUnit {
+    fn from_char(unit_char: char) -> Option<Self> {
+        let unit = match unit_char {
+            'y' => DateMathUnit::Year,
+            'M' => DateMathUnit::Month,
+            'w' => DateMathUnit::Week,
+            'd' => DateMathUnit::Day,
+            'h' | 'H' => DateMathUnit::Hour,
+            'm' => DateMathUnit::Minute,
+            's' => DateMathUnit::Second,
+            _ => return None,
+        };
+        Some(unit)
+    }
+}
+
+/// Resolves Elasticsearch date math expressions, such as `now-1h`, `now/d`, or
+/// `2024-02-14||+1M/M`, relative to a given `now`.
+///
+/// An expression starts with an anchor, either `now` or a date followed by `||`, and is followed
+/// by any number of `+<amount><unit>`, `-<amount><unit>`, and `/<unit>` operations, applied from
+/// left to right. The supported units are `y` (years), `M` (months), `w` (weeks), `d` (days), `h`
+/// or `H` (hours), `m` (minutes), and `s` (seconds).
+#[derive(Clone, Debug)]
+pub struct DateMathParser {
+    now: OffsetDateTime,
+}
+
+impl DateMathParser {
+    pub fn new(now: OffsetDateTime) -> Self {
+        DateMathParser { now }
+    }
+
+    /// Returns whether the string is a date math expression rather than a plain date.
+    pub fn is_date_math_expression(date_math_str: &str) -> bool {
+        date_math_str.starts_with("now") || date_math_str.contains("||")
+    }
+
+    /// Resolves a date math expression. The formats are used to parse the anchor date when the
+    /// expression does not start with `now`.
+    pub fn parse(
+        &self,
+        date_math_str: &str,
+        anchor_date_time_formats: &[DateTimeInputFormat],
+        rounding: DateMathRounding,
+    ) -> Result<TantivyDateTime, String> {
+        let (anchor, operations) = if let Some(operations) = date_math_str.strip_prefix("now") {
+            (self.now, operations)
+        } else if let Some((anchor_str, operations)) = date_math_str.split_once("||") {
+            let anchor = parse_date_time_str(anchor_str, anchor_date_time_formats)?.into_utc();
+            (anchor, operations)
+        } else {
+            return Err(format!(
+                "invalid date math expression `{date_math_str}`: expressions must start with \
+                 `now` or a date followed by `||`"
+            ));
+        };
+        let date_time = apply_operations(anchor, operations, rounding)
+            .map_err(|error| format!("invalid date math expression `{date_math_str}`: {error}"))?;
+        try_into_tantivy_date_time(date_time)
+    }
+}
+
+fn apply_operations(
+    mut date_time: OffsetDateTime,
+    operations: &str,
+    rounding: DateMathRounding,
+) -> Result<OffsetDateTime, String> {
+    let mut chars = operations.chars().peekable();
+
+    while let Some(operator) = chars.next() {
+        match operator {
+            '+' | '-' => {
+                let mut amount: i64 = 0;
+                let mut num_digits = 0;
+                while let Some(digit) = chars.peek().and_then(|next_char| next_char.to_digit(10)) {
+                    amount = amount
+                        .checked_mul(10)
+                        .and_then(|amount| amount.checked_add(digit as i64))
+                        .ok_or("amount is too large")?;
+                    num_digits += 1;
+                    chars.next();
+                }
+                if num_digits == 0 {
+                    amount = 1;
+                }
+                if operator == '-' {
+                    amount = -amount;
+                }
+                let unit = parse_unit(chars.next())?;
+                date_time = add_units(date_time, amount, unit)?;
+            }
+            '/' => {
+                let unit = parse_unit(chars.next())?;
+                date_time = round(date_time, unit, rounding)?;
+            }
+            _ => return Err(format!("unexpected character `{operator}`")),
+        }
+    }
+    Ok(date_time)
+}
+
+fn parse_unit(unit_char_opt: Option<char>) -> Result<DateMathUnit, String> {
+    let Some(unit_char) = unit_char_opt else {
+        return Err("missing unit".to_string());
+    };
+    DateMathUnit::from_char(unit_char).ok_or_else(|| format!("unknown unit `{unit_char}`"))
+}
+
+fn add_units(
+    date_time: OffsetDateTime,
+    amount: i64,
+    unit: DateMathUnit,
+) -> Result<OffsetDateTime, String> {
+    let date_time_opt = match unit {
+        DateMathUnit::Year => amount
+            .checked_mul(12)
+            .and_then(|num_months| add_months(date_time, num_months)),
+        DateMathUnit::Month => add_months(date_time, amount),
+        DateMathUnit::Week => add_seconds(date_time, amount, 7 * 24 * 3600),
+        DateMathUnit::Day => add_seconds(date_time, amount, 24 * 3600),
+        DateMathUnit::Hour => add_seconds(date_time, amount, 3600),
+        DateMathUnit::Minute => add_seconds(date_time, amount, 60),
+        DateMathUnit::Second => add_seconds(date_time, amount, 1),
+    };
+    date_time_opt.ok_or_else(|| "datetime is out of range".to_string())
+}
+
+fn add_seconds(
+    date_time: OffsetDateTime,
+    amount: i64,
+    unit_num_seconds: i64,
+) -> Option<OffsetDateTime> {
+    let num_seconds = amount.checked_mul(unit_num_seconds)?;
+    date_time.checked_add(Duration::seconds(num_seconds))
+}
+
+/// Adds calendar months, clamping the day of the month if necessary (e.g. January 31st plus one
+/// month is February 28th or 29th).
+fn add_months(date_time: OffsetDateTime, num_months: i64) -> Option<OffsetDateTime> {
+    let month_index = (date_time.year() as i64)
+        .checked_mul(12)?
+        .checked_add(date_time.month() as i64 - 1)?
+        .checked_add(num_months)?;
+    let year = i32::try_from(month_index.div_euclid(12)).ok()?;
+    let month = Month::try_from(month_index.rem_euclid(12) as u8 + 1).ok()?;
+    let day = date_time
+        .day()
+        .min(time::util::days_in_year_month(year, month));
+    let date = time::Date::from_calendar_date(year, month, day).ok()?;
+    Some(date_time.replace_date(date))
+}
+
+fn round(
+    date_time: OffsetDateTime,
+    unit: DateMathUnit,
+    rounding: DateMathRounding,
+) -> Result<OffsetDateTime, String> {
+    let date = date_time.date();
+    let time = date_time.time();
+    let rounded_down = match unit {
+        DateMathUnit::Year => date
+            .replace_month(Month::January)
+            .and_then(|date| date.replace_day(1))
+            .map(|date| date_time.replace_date(date).replace_time(Time::MIDNIGHT))
+            .map_err(|error| error.to_string())?,
+        DateMathUnit::Month => date
+            .replace_day(1)
+            .map(|date| date_time.replace_date(date).replace_time(Time::MIDNIGHT))
+            .map_err(|error| error.to_string())?,
+        DateMathUnit::Week => {
+            let num_days_from_monday = date.weekday().number_days_from_monday() as i64;
+            date_time.replace_time(Time::MIDNIGHT) - Duration::days(num_days_from_monday)
+        }
+        DateMathUnit::Day => date_time.replace_time(Time::MIDNIGHT),
+        DateMathUnit::Hour => {
+            date_time.replace_time(Time::from_hms(time.hour(), 0, 0).expect("hour should be valid"))
+        }
+        DateMathUnit::Minute => date_time.replace_time(
+            Time::from_hms(time.hour(), time.minute(), 0).expect("hour and minute should be valid"),
+        ),
+        DateMathUnit::Second => date_time
+            .replace_nanosecond(0)
+            .expect("0 is a valid nanosecond"),
+    };
+    match rounding {
+        DateMathRounding::Down => Ok(rounded_down),
+        DateMathRounding::Up => {
+            let next = add_units(rounded_down, 1, unit)?;
+            Ok(next - Duration::NANOSECOND)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn parse_date_math(date_math_str: &str, rounding: DateMathRounding) -> OffsetDateTime {
+        let now = datetime!(2024-02-14 18:40:19.950 UTC);
+        let date_time_formats = [
+            DateTimeInputFormat::Rfc3339,
+            DateTimeInputFormat::from_str("%Y-%m-%d").unwrap(),
+        ];
+        DateMathParser::new(now)
+            .parse(date_math_str, &date_time_formats, rounding)
+            .unwrap()
+            .into_utc()
+    }
+
+    #[test]
+    fn test_is_date_math_expression() {
+        assert!(DateMathParser::is_date_math_expression("now"));
+        assert!(DateMathParser::is_date_math_expression("now-1h"));
+        assert!(DateMathParser::is_date_math_expression("2024-02-14||+1d"));
+        assert!(!DateMathParser::is_date_math_expression("2024-02-14"));
+    }
+
+    #[test]
+    fn test_date_math_add_and_subtract() {
+        let test_data = [
+            ("now", datetime!(2024-02-14 18:40:19.950 UTC)),
+            ("now-15m", datetime!(2024-02-14 18:25:19.950 UTC)),
+            ("now+1h", datetime!(2024-02-14 19:40:19.950 UTC)),
+            ("now+1H", datetime!(2024-02-14 19:40:19.950 UTC)),
+            ("now-30s", datetime!(2024-02-14 18:39:49.950 UTC)),
+            ("now-1d", datetime!(2024-02-13 18:40:19.950 UTC)),
+            ("now-d", datetime!(2024-02-13 18:40:19.950 UTC)),
+            ("now-2w", datetime!(2024-01-31 18:40:19.950 UTC)),
+            ("now+1M", datetime!(2024-03-14 18:40:19.950 UTC)),
+            ("now-2M", datetime!(2023-12-14 18:40:19.950 UTC)),
+            ("now-1y", datetime!(2023-02-14 18:40:19.950 UTC)),
+            ("now+1d-12h", datetime!(2024-02-15 06:40:19.950 UTC)),
+            ("2024-01-31||+1M", datetime!(2024-02-29 00:00:00 UTC)),
+            ("2024-02-29||+1y", datetime!(2025-02-28 00:00:00 UTC)),
+            (
+                "2024-01-01T10:00:00Z||-10m",
+                datetime!(2024-01-01 09:50:00 UTC),
+            ),
+        ];
+        for (date_math_str, expected) in test_data {
+            assert_eq!(
+                parse_date_math(date_math_str, DateMathRounding::Down),
+                expected,
+                "{date_math_str}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_date_math_rounding() {
+        let test_data = [
+            (
+                "now/d",
+                datetime!(2024-02-14 00:00:00 UTC),
+                datetime!(2024-02-14 23:59:59.999999999 UTC),
+            ),
+            (
+                "now/h",
+                datetime!(2024-02-14 18:00:00 UTC),
+                datetime!(2024-02-14 18:59:59.999999999 UTC),
+            ),
+            (
+                "now/m",
+                datetime!(2024-02-14 18:40:00 UTC),
+                datetime!(2024-02-14 18:40:59.999999999 UTC),
+            ),
+            (
+                "now/s",
+                datetime!(2024-02-14 18:40:19 UTC),
+                datetime!(2024-02-14 18:40:19.999999999 UTC),
+            ),
+            (
+                "now/w",
+                datetime!(2024-02-12 00:00:00 UTC),
+                datetime!(2024-02-18 23:59:59.999999999 UTC),
+            ),
+            (
+                "now/M",
+                datetime!(2024-02-01 00:00:00 UTC),
+                datetime!(2024-02-29 23:59:59.999999999 UTC),
+            ),
+            (
+                "now/y",
+                datetime!(2024-01-01 00:00:00 UTC),
+                datetime!(2024-12-31 23:59:59.999999999 UTC),
+            ),
+            (
+                "now-1d/d",
+                datetime!(2024-02-13 00:00:00 UTC),
+                datetime!(2024-02-13 23:59:59.999999999 UTC),
+            ),
+            (
+                "2024-02-14||+1M/M",
+                datetime!(2024-03-01 00:00:00 UTC),
+                datetime!(2024-03-31 23:59:59.999999999 UTC),
+            ),
+        ];
+        for (date_math_str, expected_down, expected_up) in test_data {
+            assert_eq!(
+                parse_date_math(date_math_str, DateMathRounding::Down),
+                expected_down,
+                "{date_math_str}"
+            );
+            assert_eq!(
+                parse_date_math(date_math_str, DateMathRounding::Up),
+                expected_up,
+                "{date_math_str}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_date_math_invalid_expressions() {
+        let parser = DateMathParser::new(OffsetDateTime::now_utc());
+        let date_time_formats = [DateTimeInputFormat::Rfc3339];
+        for (date_math_str, expected_error) in [
+            ("2024-02-14", "expressions must start with `now`"),
+            ("now-1", "missing unit"),
+            ("now-1q", "unknown unit `q`"),
+            ("now*2d", "unexpected character `*`"),
+            ("now/", "missing unit"),
+            ("yesterday||-1d", "failed to parse datetime `yesterday`"),
+            ("now+9223372036854775807M", "datetime is out of range"),
+            ("now-9223372036854775807M", "datetime is out of range"),
+            ("now+9223372036854775807y", "datetime is out of range"),
+        ] {
+            let error = parser
+                .parse(date_math_str, &date_time_formats, DateMathRounding::Down)
+                .unwrap_err();
+            assert!(error.contains(expected_error), "{error}");
+        }
+    }
+}
@@ -20,59 +20,164 @@
 use std::fmt::Display;
 use std::str::FromStr;
 
-use ouroboros::self_referencing;
+use chrono::{Offset, TimeZone};
+use chrono_tz::Tz;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value as JsonValue;
 use time::error::Format;
 use time::format_description::well_known::{Iso8601, Rfc2822, Rfc3339};
-use time::format_description::FormatItem;
+use time::format_description::OwnedFormatItem;
 use time::parsing::Parsed;
-use time::{Month, OffsetDateTime, PrimitiveDateTime};
-use time_fmt::parse::time_format_item::parse_to_format_item;
+use time::{Month, OffsetDateTime, PrimitiveDateTime, UtcOffset, Weekday};
 
+use crate::auto_date_time_format::AutoDateTimeParser;
+use crate::chrono_date_time_format::build_strptime_format_items;
 use crate::TantivyDateTime;
 
-/// A date time parser that holds the format specification `Vec<FormatItem>`.
-#[self_referencing]
+/// Number of months in the future a date without a year can be before it is assumed to belong
+/// to the previous year.
+const DEFAULT_YEAR_INFERENCE_WINDOW_MONTHS: u8 = 3;
+
+/// Specifies how the year of a datetime string that does not contain one is inferred.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum YearInference {
+    /// Datetime strings without a year are rejected.
+    Disabled,
+    /// The year is inferred from the current date, tolerating dates up to the given number of
+    /// months in the future.
+    Window(u8),
+}
+
+impl Default for YearInference {
+    fn default() -> Self {
+        YearInference::Window(DEFAULT_YEAR_INFERENCE_WINDOW_MONTHS)
+    }
+}
+
+/// Splits a strptime format from the options that follow it. The options are introduced by `%|`,
+/// which is not a valid specifier, so that formats containing a literal `|` are left untouched,
+/// e.g. `%b %d %H:%M:%S %| infer_year=disabled` or `%b %d %H:%M:%S %| infer_year_window=6`.
+fn split_strptime_options(strptime_format: &str) -> (&str, Option<&str>) {
+    let mut char_indices = strptime_format.char_indices();
+
+    while let Some((_, c)) = char_indices.next() {
+        if c != '%' {
+            continue;
+        }
+        // Skips the specifier, so that `%%|` is a literal `%` followed by a literal `|`.
+        if let Some((pos, '|')) = char_indices.next() {
+            let format_str = strptime_format[..pos - 1].trim_end();
+            return (format_str, Some(&strptime_format[pos + 1..]));
+        }
+    }
+    (strptime_format, None)
+}
+
+/// Parses the options that follow a strptime format, separated by `|`, e.g.
+/// `infer_year=disabled` or `infer_year=enabled | infer_year_window=6`.
+fn parse_year_inference_options(options_str: &str) -> Result<YearInference, String> {
+    let mut year_inference = YearInference::default();
+
+    for option_str in options_str.split('|') {
+        let Some((key, value)) = option_str.split_once('=') else {
+            return Err(format!(
+                "invalid strptime format option `{}`: expected `key=value`",
+                option_str.trim()
+            ));
+        };
+        match (key.trim(), value.trim()) {
+            ("infer_year", "enabled") => {}
+            ("infer_year", "disabled") => year_inference = YearInference::Disabled,
+            ("infer_year_window", window_str) => {
+                let window = window_str
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|window| *window < 12)
+                    .ok_or_else(|| {
+                        format!(
+                            "invalid year inference window `{window_str}`: expected a number of \
+                             months between 0 and 11"
+                        )
+                    })?;
+                year_inference = YearInference::Window(window);
+            }
+            (key, value) => {
+                return Err(format!(
+                    "unknown strptime format option `{key}={value}`. supported options are \
+                     `infer_year=enabled|disabled` and `infer_year_window=<months>`"
+                ));
+            }
+        }
+    }
+    Ok(year_inference)
+}
+
+/// A date time parser that holds the format specification `Vec<OwnedFormatItem>`.
+#[derive(Clone)]
 pub struct StrptimeParser {
     strptime_format: String,
     with_timezone: bool,
-    #[borrows(strptime_format)]
-    #[covariant]
-    items: Vec<FormatItem<'this>>,
+    items: Box<[OwnedFormatItem]>,
+    year_inference: YearInference,
 }
 
 impl FromStr for StrptimeParser {
     type Err = String;
 
     fn from_str(strptime_format: &str) -> Result<Self, Self::Err> {
-        StrptimeParser::try_new(
-            strptime_format.to_string(),
-            strptime_format.to_lowercase().contains("%z"),
-            |strptime_format: &String| {
-                parse_to_format_item(strptime_format).map_err(|error| {
-                    format!("invalid strptime format `{strptime_format}`: {error}")
-                })
-            },
-        )
+        StrptimeParser::from_strptime(strptime_format)
     }
 }
 
 impl StrptimeParser {
+    /// Creates a parser from a strptime format. On top of the `time-fmt` dialect, the
+    /// chrono-specific specifiers `%.f`, `%.3f`, `%.6f`, `%.9f`, `%3f`, `%6f`, `%9f`, and `%:z`
+    /// are accepted.
+    pub fn from_strptime(strptime_format: &str) -> Result<Self, String> {
+        let (format_str, year_inference) = match split_strptime_options(strptime_format) {
+            (format_str, Some(options_str)) => {
+                (format_str, parse_year_inference_options(options_str)?)
+            }
+            (format_str, None) => (format_str, YearInference::default()),
+        };
+        let (items, with_timezone) = build_strptime_format_items(format_str)
+            .map_err(|error| format!("invalid strptime format `{strptime_format}`: {error}"))?;
+        let mut parser = StrptimeParser::new(
+            strptime_format.to_string(),
+            with_timezone,
+            items.into_boxed_slice(),
+        );
+        parser.year_inference = year_inference;
+        Ok(parser)
+    }
+
+    pub(crate) fn new(
+        strptime_format: String,
+        with_timezone: bool,
+        items: Box<[OwnedFormatItem]>,
+    ) -> Self {
+        StrptimeParser {
+            strptime_format,
+            with_timezone,
+            items,
+            year_inference: YearInference::default(),
+        }
+    }
+
     /// Parse a given date according to the datetime format specified during the StrptimeParser
     /// creation. If the date format does not provide a specific a time, the time will be set to
     /// 00:00:00.
-    fn parse_primitive_date_time(&self, date_time_str: &str) -> anyhow::Result<PrimitiveDateTime> {
+    fn parse_items(&self, date_time_str: &str) -> anyhow::Result<Parsed> {
         let mut parsed = Parsed::new();
         if !parsed
-            .parse_items(date_time_str.as_bytes(), self.borrow_items())?
+            .parse_items(date_time_str.as_bytes(), &self.items[..])?
             .is_empty()
         {
             anyhow::bail!(
                 "datetime string `{}` does not match strptime format `{}`",
                 date_time_str,
-                self.borrow_strptime_format()
+                self.strptime_format
             );
         }
         // The parsed datetime contains a date but seems to be missing "time".
@@ -84,40 +189,56 @@ impl StrptimeParser {
             parsed.set_minute(0u8);
             parsed.set_second(0u8);
         }
-        if parsed.year().is_none() {
+        // Dates expressed as a week of a week-based year (e.g. `2024-W07`) do not carry a day of
+        // the week. We default to the first day of the week.
+        if parsed.iso_week_number().is_some() && parsed.weekday().is_none() {
+            parsed.set_weekday(Weekday::Monday);
+        }
+        if parsed.year().is_none() && parsed.iso_year().is_none() {
+            let YearInference::Window(window_months) = self.year_inference else {
+                anyhow::bail!(
+                    "datetime string `{}` does not contain a year and year inference is disabled \
+                     for strptime format `{}`",
+                    date_time_str,
+                    self.strptime_format
+                );
+            };
             let now = OffsetDateTime::now_utc();
-            let year = infer_year(parsed.month(), now.month(), now.year());
+            let year = infer_year(parsed.month(), now.month(), now.year(), window_months);
             parsed.set_year(year);
         }
-        let date_time = parsed.try_into()?;
-        Ok(date_time)
+        Ok(parsed)
     }
 
     pub fn parse_date_time(&self, date_time_str: &str) -> Result<OffsetDateTime, String> {
-        if *self.borrow_with_timezone() {
-            OffsetDateTime::parse(date_time_str, self.borrow_items()).map_err(|err| err.to_string())
+        let mut parsed = self
+            .parse_items(date_time_str)
+            .map_err(|err| err.to_string())?;
+        if self.with_timezone {
+            // A UTC offset written as the `Z` designator does not set any offset component.
+            if parsed.offset_hour().is_none() {
+                parsed.set_offset_hour(0i8);
+            }
+            OffsetDateTime::try_from(parsed).map_err(|err| err.to_string())
         } else {
-            self.parse_primitive_date_time(date_time_str)
+            PrimitiveDateTime::try_from(parsed)
                 .map(|date_time| date_time.assume_utc())
                 .map_err(|err| err.to_string())
         }
     }
 
     pub fn format_date_time(&self, date_time: &OffsetDateTime) -> Result<String, Format> {
-        date_time.format(self.borrow_items())
+        date_time.format(&self.items[..])
     }
-}
 
-impl Clone for StrptimeParser {
-    fn clone(&self) -> Self {
-        // `self.format` is already known to be a valid format.
-        Self::from_str(self.borrow_strptime_format().as_str()).unwrap()
+    pub fn strptime_format(&self) -> &str {
+        &self.strptime_format
     }
 }
 
 impl PartialEq for StrptimeParser {
     fn eq(&self, other: &Self) -> bool {
-        self.borrow_strptime_format() == other.borrow_strptime_format()
+        self.strptime_format == other.strptime_format
     }
 }
 
@@ -127,14 +248,14 @@ impl std::fmt::Debug for StrptimeParser {
     fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         formatter
             .debug_struct("StrptimeParser")
-            .field("format", &self.borrow_strptime_format())
+            .field("format", &self.strptime_format)
             .finish()
     }
 }
 
 impl std::hash::Hash for StrptimeParser {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.borrow_strptime_format().hash(state);
+        self.strptime_format.hash(state);
     }
 }
 
@@ -156,24 +277,50 @@ fn is_strftime_formatting(format_str: &str) -> bool {
 /// Specifies the datetime and unix timestamp formats to use when parsing date strings.
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Default)]
 pub enum DateTimeInputFormat {
+    /// Detects the format among a prioritized list of well-known formats.
+    Auto(AutoDateTimeParser),
     Iso8601,
     Rfc2822,
     #[default]
     Rfc3339,
     Strptime(StrptimeParser),
+    /// Unix timestamp whose unit (seconds, milliseconds, microseconds, or nanoseconds) is
+    /// inferred from its magnitude.
     Timestamp,
+    TimestampSecs,
+    TimestampMillis,
+    TimestampMicros,
+    TimestampNanos,
 }
 
 impl DateTimeInputFormat {
     pub fn as_str(&self) -> &str {
         match self {
+            DateTimeInputFormat::Auto(_) => "auto",
             DateTimeInputFormat::Iso8601 => "iso8601",
             DateTimeInputFormat::Rfc2822 => "rfc2822",
             DateTimeInputFormat::Rfc3339 => "rfc3339",
-            DateTimeInputFormat::Strptime(parser) => parser.borrow_strptime_format(),
+            DateTimeInputFormat::Strptime(parser) => parser.strptime_format(),
             DateTimeInputFormat::Timestamp => "unix_timestamp",
+            DateTimeInputFormat::TimestampSecs => "unix_timestamp_secs",
+            DateTimeInputFormat::TimestampMillis => "unix_timestamp_millis",
+            DateTimeInputFormat::TimestampMicros => "unix_timestamp_micros",
+            DateTimeInputFormat::TimestampNanos => "unix_timestamp_nanos",
         }
     }
+
+    /// Returns whether the format parses unix timestamps.
+    pub fn is_timestamp(&self) -> bool {
+        matches!(
+            self,
+            DateTimeInputFormat::Auto(_)
+                | DateTimeInputFormat::Timestamp
+                | DateTimeInputFormat::TimestampSecs
+                | DateTimeInputFormat::TimestampMillis
+                | DateTimeInputFormat::TimestampMicros
+                | DateTimeInputFormat::TimestampNanos
+        )
+    }
 }
 
 impl Display for DateTimeInputFormat {
@@ -187,10 +334,15 @@ impl FromStr for DateTimeInputFormat {
 
     fn from_str(date_time_format_str: &str) -> Result<Self, Self::Err> {
         let date_time_format = match date_time_format_str.to_lowercase().as_str() {
+            "auto" => DateTimeInputFormat::Auto(AutoDateTimeParser::default()),
             "iso8601" => DateTimeInputFormat::Iso8601,
             "rfc2822" => DateTimeInputFormat::Rfc2822,
             "rfc3339" => DateTimeInputFormat::Rfc3339,
             "unix_timestamp" => DateTimeInputFormat::Timestamp,
+            "unix_timestamp_secs" => DateTimeInputFormat::TimestampSecs,
+            "unix_timestamp_millis" => DateTimeInputFormat::TimestampMillis,
+            "unix_timestamp_micros" => DateTimeInputFormat::TimestampMicros,
+            "unix_timestamp_nanos" => DateTimeInputFormat::TimestampNanos,
             _ => {
                 if !is_strftime_formatting(date_time_format_str) {
                     return Err(format!(
@@ -241,7 +393,7 @@ impl DateTimeOutputFormat {
             DateTimeOutputFormat::Iso8601 => "iso8601",
             DateTimeOutputFormat::Rfc2822 => "rfc2822",
             DateTimeOutputFormat::Rfc3339 => "rfc3339",
-            DateTimeOutputFormat::Strptime(parser) => parser.borrow_strptime_format(),
+            DateTimeOutputFormat::Strptime(parser) => parser.strptime_format(),
             DateTimeOutputFormat::TimestampSecs => "unix_timestamp_secs",
             DateTimeOutputFormat::TimestampMillis => "unix_timestamp_millis",
             DateTimeOutputFormat::TimestampMicros => "unix_timestamp_micros",
@@ -250,7 +402,17 @@ impl DateTimeOutputFormat {
     }
 
     pub fn format_to_json(&self, date_time: TantivyDateTime) -> Result<JsonValue, String> {
-        let date = date_time.into_utc();
+        self.format_to_json_with_timezone(date_time, &OutputTimezone::default())
+    }
+
+    /// Formats a datetime, rendering it in the given time zone. Unix timestamp formats are
+    /// unaffected by the time zone.
+    pub fn format_to_json_with_timezone(
+        &self,
+        date_time: TantivyDateTime,
+        output_timezone: &OutputTimezone,
+    ) -> Result<JsonValue, String> {
+        let date = output_timezone.to_offset_date_time(date_time.into_utc())?;
         let format_result = match &self {
             DateTimeOutputFormat::Rfc3339 => date.format(&Rfc3339).map(JsonValue::String),
             DateTimeOutputFormat::Iso8601 => date.format(&Iso8601::DEFAULT).map(JsonValue::String),
@@ -323,17 +485,94 @@ impl<'de> Deserialize<'de> for DateTimeOutputFormat {
     }
 }
 
+/// Specifies the time zone in which datetime values are displayed: `UTC` or an IANA time zone name
+/// such as `Europe/Paris`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct OutputTimezone(Tz);
+
+impl Default for OutputTimezone {
+    fn default() -> Self {
+        OutputTimezone(Tz::UTC)
+    }
+}
+
+impl OutputTimezone {
+    pub fn is_utc(&self) -> bool {
+        self.0 == Tz::UTC
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.0.name()
+    }
+
+    /// Converts a UTC datetime into this time zone. The UTC offset is resolved for the instant
+    /// being converted, so daylight saving time transitions are taken into account.
+    fn to_offset_date_time(&self, date_time: OffsetDateTime) -> Result<OffsetDateTime, String> {
+        if self.is_utc() {
+            return Ok(date_time);
+        }
+        let chrono_date_time = chrono::DateTime::from_timestamp(date_time.unix_timestamp(), 0)
+            .ok_or_else(|| format!("datetime `{date_time}` is out of range"))?;
+        let offset_secs = self
+            .0
+            .offset_from_utc_datetime(&chrono_date_time.naive_utc())
+            .fix()
+            .local_minus_utc();
+        let utc_offset =
+            UtcOffset::from_whole_seconds(offset_secs).map_err(|error| error.to_string())?;
+        Ok(date_time.to_offset(utc_offset))
+    }
+}
+
+impl Display for OutputTimezone {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OutputTimezone {
+    type Err = String;
+
+    fn from_str(timezone_str: &str) -> Result<Self, Self::Err> {
+        let timezone = Tz::from_str(timezone_str).map_err(|_| {
+            format!(
+                "unknown time zone: `{timezone_str}`. time zones must be `UTC` or a IANA time \
+                 zone name, e.g. `Europe/Paris`"
+            )
+        })?;
+        Ok(OutputTimezone(timezone))
+    }
+}
+
+impl Serialize for OutputTimezone {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: serde::Serializer {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for OutputTimezone {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        let timezone_str: String = Deserialize::deserialize(deserializer)?;
+        let timezone = timezone_str.parse().map_err(D::Error::custom)?;
+        Ok(timezone)
+    }
+}
+
 /// Infers the year of a parsed date time. It assumes that events appear more often delayed than in
-/// the future and, as a result, skews towards the past year.
+/// the future and, as a result, skews towards the past year: months more than `window_months`
+/// ahead of the current month are assumed to belong to the previous year.
 pub(super) fn infer_year(
     parsed_month_opt: Option<Month>,
     this_month: Month,
     this_year: i32,
+    window_months: u8,
 ) -> i32 {
     let Some(parsed_month) = parsed_month_opt else {
         return this_year;
     };
-    if parsed_month as u8 > this_month as u8 + 3 {
+    if parsed_month as u8 > this_month as u8 + window_months {
         return this_year - 1;
     }
     this_year
@@ -349,15 +588,29 @@ mod tests {
     #[test]
     fn test_date_time_input_format_ser() {
         let date_time_formats_json = serde_json::to_value(&[
+            DateTimeInputFormat::Auto(AutoDateTimeParser::default()),
             DateTimeInputFormat::Iso8601,
             DateTimeInputFormat::Rfc2822,
             DateTimeInputFormat::Rfc3339,
             DateTimeInputFormat::Timestamp,
+            DateTimeInputFormat::TimestampSecs,
+            DateTimeInputFormat::TimestampMillis,
+            DateTimeInputFormat::TimestampMicros,
+            DateTimeInputFormat::TimestampNanos,
         ])
         .unwrap();
 
-        let expected_date_time_formats =
-            serde_json::json!(["iso8601", "rfc2822", "rfc3339", "unix_timestamp",]);
+        let expected_date_time_formats = serde_json::json!([
+            "auto",
+            "iso8601",
+            "rfc2822",
+            "rfc3339",
+            "unix_timestamp",
+            "unix_timestamp_secs",
+            "unix_timestamp_millis",
+            "unix_timestamp_micros",
+            "unix_timestamp_nanos",
+        ]);
         assert_eq!(date_time_formats_json, expected_date_time_formats);
     }
 
@@ -365,19 +618,29 @@ mod tests {
     fn test_date_time_input_format_deser() {
         let date_time_formats_json = r#"
             [
+                "auto",
                 "iso8601",
                 "rfc2822",
                 "rfc3339",
-                "unix_timestamp"
+                "unix_timestamp",
+                "unix_timestamp_secs",
+                "unix_timestamp_millis",
+                "unix_timestamp_micros",
+                "unix_timestamp_nanos"
             ]
             "#;
         let date_time_formats: Vec<DateTimeInputFormat> =
             serde_json::from_str(date_time_formats_json).unwrap();
         let expected_date_time_formats = [
+            DateTimeInputFormat::Auto(AutoDateTimeParser::default()),
             DateTimeInputFormat::Iso8601,
             DateTimeInputFormat::Rfc2822,
             DateTimeInputFormat::Rfc3339,
             DateTimeInputFormat::Timestamp,
+            DateTimeInputFormat::TimestampSecs,
+            DateTimeInputFormat::TimestampMillis,
+            DateTimeInputFormat::TimestampMicros,
+            DateTimeInputFormat::TimestampNanos,
         ];
         assert_eq!(date_time_formats, &expected_date_time_formats);
     }
@@ -436,13 +699,7 @@ mod tests {
 
     #[test]
     fn test_fail_date_time_input_format_from_str_with_unknown_format() {
-        let formats = vec![
-            "test%",
-            "test-%v",
-            "test-%q",
-            "unix_timestamp_secs",
-            "unix_timestamp_seconds",
-        ];
+        let formats = vec!["test%", "test-%v", "test-%q", "unix_timestamp_seconds"];
         for format in formats {
             let error_str = DateTimeInputFormat::from_str(format)
                 .unwrap_err()
@@ -476,27 +733,153 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_output_timezone_deser() {
+        let output_timezone: OutputTimezone = serde_json::from_str(r#""Europe/Paris""#).unwrap();
+        assert_eq!(output_timezone.as_str(), "Europe/Paris");
+        assert_eq!(
+            serde_json::to_string(&output_timezone).unwrap(),
+            r#""Europe/Paris""#
+        );
+        let output_timezone: OutputTimezone = serde_json::from_str(r#""UTC""#).unwrap();
+        assert!(output_timezone.is_utc());
+
+        let error = serde_json::from_str::<OutputTimezone>(r#""Mars/Olympus_Mons""#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("unknown time zone: `Mars/Olympus_Mons`"));
+    }
+
+    #[test]
+    fn test_format_to_json_with_timezone() {
+        let output_timezone = OutputTimezone::from_str("Europe/Paris").unwrap();
+        // Winter time, UTC+1.
+        let date_time = TantivyDateTime::from_utc(datetime!(2024-01-15 12:30:00 UTC));
+        let json_value = DateTimeOutputFormat::Rfc3339
+            .format_to_json_with_timezone(date_time, &output_timezone)
+            .unwrap();
+        assert_eq!(json_value, JsonValue::from("2024-01-15T13:30:00+01:00"));
+
+        // Summer time, UTC+2.
+        let date_time = TantivyDateTime::from_utc(datetime!(2024-07-15 12:30:00 UTC));
+        let json_value = DateTimeOutputFormat::Rfc3339
+            .format_to_json_with_timezone(date_time, &output_timezone)
+            .unwrap();
+        assert_eq!(json_value, JsonValue::from("2024-07-15T14:30:00+02:00"));
+
+        // Right after the switch to summer time.
+        let date_time = TantivyDateTime::from_utc(datetime!(2024-03-31 01:00:00 UTC));
+        let json_value = DateTimeOutputFormat::Strptime(
+            StrptimeParser::from_str("%Y-%m-%d %H:%M:%S %z").unwrap(),
+        )
+        .format_to_json_with_timezone(date_time, &output_timezone)
+        .unwrap();
+        assert_eq!(json_value, JsonValue::from("2024-03-31 03:00:00 +0200"));
+
+        // Timestamps do not depend on the time zone.
+        let json_value = DateTimeOutputFormat::TimestampSecs
+            .format_to_json_with_timezone(date_time, &output_timezone)
+            .unwrap();
+        assert_eq!(json_value, JsonValue::from(1_711_846_800));
+    }
+
     #[test]
     fn test_infer_year() {
-        let inferred_year = infer_year(None, Month::January, 2024);
+        let inferred_year = infer_year(None, Month::January, 2024, 3);
         assert_eq!(inferred_year, 2024);
 
-        let inferred_year = infer_year(Some(Month::December), Month::January, 2024);
+        let inferred_year = infer_year(Some(Month::December), Month::January, 2024, 3);
         assert_eq!(inferred_year, 2023);
 
-        let inferred_year = infer_year(Some(Month::January), Month::January, 2024);
+        let inferred_year = infer_year(Some(Month::January), Month::January, 2024, 3);
         assert_eq!(inferred_year, 2024);
 
-        let inferred_year = infer_year(Some(Month::February), Month::January, 2024);
+        let inferred_year = infer_year(Some(Month::February), Month::January, 2024, 3);
         assert_eq!(inferred_year, 2024);
 
-        let inferred_year = infer_year(Some(Month::March), Month::January, 2024);
+        let inferred_year = infer_year(Some(Month::March), Month::January, 2024, 3);
         assert_eq!(inferred_year, 2024);
 
-        let inferred_year = infer_year(Some(Month::April), Month::January, 2024);
+        let inferred_year = infer_year(Some(Month::April), Month::January, 2024, 3);
         assert_eq!(inferred_year, 2024);
 
-        let inferred_year = infer_year(Some(Month::May), Month::January, 2024);
+        let inferred_year = infer_year(Some(Month::May), Month::January, 2024, 3);
         assert_eq!(inferred_year, 2023);
+
+        let inferred_year = infer_year(Some(Month::February), Month::January, 2024, 0);
+        assert_eq!(inferred_year, 2023);
+
+        let inferred_year = infer_year(Some(Month::July), Month::January, 2024, 6);
+        assert_eq!(inferred_year, 2024);
+
+        let inferred_year = infer_year(Some(Month::August), Month::January, 2024, 6);
+        assert_eq!(inferred_year, 2023);
+    }
+
+    #[test]
+    fn test_strptime_parser_year_inference_options() {
+        let parser = StrptimeParser::from_str("%b %d %H:%M:%S %| infer_year=disabled").unwrap();
+        assert_eq!(
+            parser.strptime_format(),
+            "%b %d %H:%M:%S %| infer_year=disabled"
+        );
+        let error = parser.parse_date_time("Mar  6 17:40:02").unwrap_err();
+        assert!(error.contains("year inference is disabled"));
+
+        let parser = StrptimeParser::from_str("%Y %b %d %H:%M:%S %| infer_year=disabled").unwrap();
+        assert_eq!(
+            parser.parse_date_time("2023 Mar  6 17:40:02").unwrap(),
+            datetime!(2023-03-06 17:40:02 UTC)
+        );
+        let parser = StrptimeParser::from_str("%b %d %H:%M:%S %|infer_year_window=11").unwrap();
+        let date_time = parser.parse_date_time("Mar  6 17:40:02").unwrap();
+        let now = OffsetDateTime::now_utc();
+        assert_eq!(
+            date_time.year(),
+            infer_year(Some(Month::March), now.month(), now.year(), 11)
+        );
+        StrptimeParser::from_str("%b %d %H:%M:%S %| infer_year=enabled | infer_year_window=6")
+            .unwrap();
+
+        for invalid_format in [
+            "%b %d %| infer_year=maybe",
+            "%b %d %| infer_year_window=12",
+            "%b %d %| infer_year_window=-1",
+            "%b %d %| foo",
+        ] {
+            StrptimeParser::from_str(invalid_format).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_strptime_parser_literal_pipe() {
+        let parser = StrptimeParser::from_str("%Y-%m-%d|%H:%M:%S").unwrap();
+        assert_eq!(
+            parser.parse_date_time("2024-03-06|17:40:02").unwrap(),
+            datetime!(2024-03-06 17:40:02 UTC)
+        );
+        let parser = StrptimeParser::from_str("%Y-%m-%d %%|%H:%M:%S").unwrap();
+        assert_eq!(
+            parser.parse_date_time("2024-03-06 %|17:40:02").unwrap(),
+            datetime!(2024-03-06 17:40:02 UTC)
+        );
+        let parser = StrptimeParser::from_str("%b %d|%H:%M:%S %| infer_year=disabled").unwrap();
+        let error = parser.parse_date_time("Mar  6|17:40:02").unwrap_err();
+        assert!(error.contains("year inference is disabled"));
+    }
+
+    #[test]
+    fn test_split_strptime_options() {
+        assert_eq!(split_strptime_options("%b %d"), ("%b %d", None));
+        assert_eq!(split_strptime_options("%b|%d"), ("%b|%d", None));
+        assert_eq!(split_strptime_options("%b %%|%d"), ("%b %%|%d", None));
+        assert_eq!(
+            split_strptime_options("%b %d %| infer_year=disabled"),
+            ("%b %d", Some(" infer_year=disabled"))
+        );
+        assert_eq!(
+            split_strptime_options("%b|%d%|infer_year_window=6"),
+            ("%b|%d", Some("infer_year_window=6"))
+        );
     }
 }
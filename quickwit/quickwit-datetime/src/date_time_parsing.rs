@@ -32,28 +32,27 @@ const MIN_TIMESTAMP_SECONDS: i64 = 72_057_595;
 // Maximum supported timestamp value in seconds (16 Mar 2242 12:56:31 GMT).
 const MAX_TIMESTAMP_SECONDS: i64 = 8_589_934_591;
 
+const OUT_OF_RANGE_ERROR_MESSAGE: &str = "value is out of range. Quickwit only supports datetimes \
+                                          ranging from `1677-09-21T00:12:43.145224192Z` to \
+                                          `2262-04-11T23:47:16.854775807Z`";
+
+/// Converts an [`OffsetDateTime`] into a [`TantivyDateTime`], which holds the number of
+/// nanoseconds since the Unix epoch in an `i64`. Unlike [`TantivyDateTime::from_utc`], this
+/// function returns an error instead of silently wrapping around when the datetime is out of the
+/// representable range.
+pub fn try_into_tantivy_date_time(date_time: OffsetDateTime) -> Result<TantivyDateTime, String> {
+    let timestamp_nanos = i64::try_from(date_time.unix_timestamp_nanos()).map_err(|_| {
+        format!("failed to convert datetime `{date_time}`: {OUT_OF_RANGE_ERROR_MESSAGE}")
+    })?;
+    Ok(TantivyDateTime::from_timestamp_nanos(timestamp_nanos))
+}
+
 pub fn parse_date_time_str(
     date_time_str: &str,
     date_time_formats: &[DateTimeInputFormat],
 ) -> Result<TantivyDateTime, String> {
     for date_time_format in date_time_formats {
-        let date_time_opt = match date_time_format {
-            DateTimeInputFormat::Iso8601 => parse_iso8601(date_time_str)
-                .map(TantivyDateTime::from_utc)
-                .ok(),
-            DateTimeInputFormat::Rfc2822 => parse_rfc2822(date_time_str)
-                .map(TantivyDateTime::from_utc)
-                .ok(),
-            DateTimeInputFormat::Rfc3339 => parse_rfc3339(date_time_str)
-                .map(TantivyDateTime::from_utc)
-                .ok(),
-            DateTimeInputFormat::Strptime(parser) => parser
-                .parse_date_time(date_time_str)
-                .map(TantivyDateTime::from_utc)
-                .ok(),
-            DateTimeInputFormat::Timestamp => parse_timestamp_str(date_time_str),
-        };
-        if let Some(date_time) = date_time_opt {
+        if let Some(date_time) = parse_date_time_str_with_format(date_time_str, date_time_format)? {
             return Ok(date_time);
         }
     }
@@ -66,11 +65,44 @@ pub fn parse_date_time_str(
     ))
 }
 
+/// Parses a datetime string using a single format. Returns `Ok(None)` if the string does not
+/// match the format and an error if it matches but the datetime is out of the supported range.
+pub(crate) fn parse_date_time_str_with_format(
+    date_time_str: &str,
+    date_time_format: &DateTimeInputFormat,
+) -> Result<Option<TantivyDateTime>, String> {
+    let date_time_opt = match date_time_format {
+        DateTimeInputFormat::Auto(parser) => return parser.parse_date_time_str(date_time_str),
+        DateTimeInputFormat::Iso8601 => parse_iso8601(date_time_str).ok(),
+        DateTimeInputFormat::Rfc2822 => parse_rfc2822(date_time_str).ok(),
+        DateTimeInputFormat::Rfc3339 => parse_rfc3339(date_time_str).ok(),
+        DateTimeInputFormat::Strptime(parser) => parser.parse_date_time(date_time_str).ok(),
+        DateTimeInputFormat::Timestamp => return Ok(parse_timestamp_str(date_time_str)),
+        DateTimeInputFormat::TimestampSecs
+        | DateTimeInputFormat::TimestampMillis
+        | DateTimeInputFormat::TimestampMicros
+        | DateTimeInputFormat::TimestampNanos => {
+            let Some(num_nanos_per_unit) = timestamp_unit_num_nanos(date_time_format) else {
+                return Ok(None);
+            };
+            return parse_timestamp_str_with_unit(date_time_str, num_nanos_per_unit).map_err(
+                |error| {
+                    format!(
+                        "failed to parse unix timestamp `{date_time_str}` using format \
+                         `{date_time_format}`: {error}"
+                    )
+                },
+            );
+        }
+    };
+    date_time_opt.map(try_into_tantivy_date_time).transpose()
+}
+
 pub fn parse_timestamp_float(
     timestamp: f64,
     date_time_formats: &[DateTimeInputFormat],
 ) -> Result<TantivyDateTime, String> {
-    if !date_time_formats.contains(&DateTimeInputFormat::Timestamp) {
+    let Some(timestamp_format) = find_timestamp_format(date_time_formats) else {
         return Err(format!(
             "failed to parse datetime `{timestamp}` using the following formats: `{}`",
             date_time_formats
@@ -78,18 +110,34 @@ pub fn parse_timestamp_float(
                 .map(|date_time_format| date_time_format.as_str())
                 .join("`, `")
         ));
+    };
+    let Some(num_nanos_per_unit) = timestamp_unit_num_nanos(timestamp_format) else {
+        let duration_since_epoch = Duration::try_from_secs_f64(timestamp)
+            .map_err(|error| format!("Failed to parse datetime `{timestamp}`: {error}"))?;
+        let timestamp_nanos = duration_since_epoch.as_nanos() as i64;
+        return Ok(TantivyDateTime::from_timestamp_nanos(timestamp_nanos));
+    };
+    let timestamp_nanos = timestamp * num_nanos_per_unit as f64;
+
+    if !timestamp_nanos.is_finite()
+        || timestamp_nanos < i64::MIN as f64
+        || timestamp_nanos > i64::MAX as f64
+    {
+        return Err(format!(
+            "failed to parse unix timestamp `{timestamp}` using format `{timestamp_format}`: \
+             {OUT_OF_RANGE_ERROR_MESSAGE}"
+        ));
     }
-    let duration_since_epoch = Duration::try_from_secs_f64(timestamp)
-        .map_err(|error| format!("Failed to parse datetime `{timestamp}`: {error}"))?;
-    let timestamp_nanos = duration_since_epoch.as_nanos() as i64;
-    Ok(TantivyDateTime::from_timestamp_nanos(timestamp_nanos))
+    Ok(TantivyDateTime::from_timestamp_nanos(
+        timestamp_nanos as i64,
+    ))
 }
 
 pub fn parse_timestamp_int(
     timestamp: i64,
     date_time_formats: &[DateTimeInputFormat],
 ) -> Result<TantivyDateTime, String> {
-    if !date_time_formats.contains(&DateTimeInputFormat::Timestamp) {
+    let Some(timestamp_format) = find_timestamp_format(date_time_formats) else {
         return Err(format!(
             "failed to parse datetime `{timestamp}` using the following formats: `{}`",
             date_time_formats
@@ -97,8 +145,87 @@ pub fn parse_timestamp_int(
                 .map(|date_time_format| date_time_format.as_str())
                 .join("`, `")
         ));
+    };
+    let Some(num_nanos_per_unit) = timestamp_unit_num_nanos(timestamp_format) else {
+        return parse_timestamp(timestamp);
+    };
+    timestamp
+        .checked_mul(num_nanos_per_unit)
+        .map(TantivyDateTime::from_timestamp_nanos)
+        .ok_or_else(|| {
+            format!(
+                "failed to parse unix timestamp `{timestamp}` using format `{timestamp_format}`: \
+                 {OUT_OF_RANGE_ERROR_MESSAGE}"
+            )
+        })
+}
+
+/// Returns the first unix timestamp format of the list, if any.
+fn find_timestamp_format(
+    date_time_formats: &[DateTimeInputFormat],
+) -> Option<&DateTimeInputFormat> {
+    date_time_formats
+        .iter()
+        .find(|date_time_format| date_time_format.is_timestamp())
+}
+
+/// Returns the number of nanoseconds in one unit of a unix timestamp format with an explicit
+/// unit, or `None` if the unit must be inferred from the timestamp value.
+fn timestamp_unit_num_nanos(date_time_format: &DateTimeInputFormat) -> Option<i64> {
+    match date_time_format {
+        DateTimeInputFormat::TimestampSecs => Some(1_000_000_000),
+        DateTimeInputFormat::TimestampMillis => Some(1_000_000),
+        DateTimeInputFormat::TimestampMicros => Some(1_000),
+        DateTimeInputFormat::TimestampNanos => Some(1),
+        _ => None,
     }
-    parse_timestamp(timestamp)
+}
+
+/// Parses a unix timestamp string expressed in the unit specified by `num_nanos_per_unit`.
+/// Unlike [`parse_timestamp_str`], negative values are accepted and the supported range is only
+/// bounded by the nanosecond precision of [`TantivyDateTime`]. Returns `Ok(None)` if the string
+/// is not a number and an error if the timestamp is out of range.
+fn parse_timestamp_str_with_unit(
+    timestamp_str: &str,
+    num_nanos_per_unit: i64,
+) -> Result<Option<TantivyDateTime>, String> {
+    let (integer_part_str, fractional_part_str) =
+        timestamp_str.split_once('.').unwrap_or((timestamp_str, ""));
+    let Ok(integer_part) = integer_part_str.parse::<i64>() else {
+        return Ok(None);
+    };
+    if !fractional_part_str
+        .bytes()
+        .all(|byte| byte.is_ascii_digit())
+    {
+        return Ok(None);
+    }
+    let max_num_fractional_digits = num_nanos_per_unit.ilog10() as usize;
+    let num_fractional_digits = fractional_part_str.len().min(max_num_fractional_digits);
+
+    let fractional_nanos = if num_fractional_digits > 0 {
+        let Ok(fractional_part) = fractional_part_str[..num_fractional_digits].parse::<i64>()
+        else {
+            return Ok(None);
+        };
+        fractional_part * 10i64.pow((max_num_fractional_digits - num_fractional_digits) as u32)
+    } else {
+        0
+    };
+    let timestamp_nanos_opt =
+        integer_part
+            .checked_mul(num_nanos_per_unit)
+            .and_then(|timestamp_nanos| {
+                if integer_part_str.starts_with('-') {
+                    timestamp_nanos.checked_sub(fractional_nanos)
+                } else {
+                    timestamp_nanos.checked_add(fractional_nanos)
+                }
+            });
+    let Some(timestamp_nanos) = timestamp_nanos_opt else {
+        return Err(OUT_OF_RANGE_ERROR_MESSAGE.to_string());
+    };
+    Ok(Some(TantivyDateTime::from_timestamp_nanos(timestamp_nanos)))
 }
 
 pub fn parse_timestamp_str(timestamp_str: &str) -> Option<TantivyDateTime> {
@@ -252,7 +379,7 @@ mod tests {
             ("%b %d %H:%M:%S", "Mar  6 17:40:02", {
                 let dt = datetime!(1900-03-06 17:40:02 UTC);
                 let now = OffsetDateTime::now_utc();
-                let year = infer_year(Some(Month::March), now.month(), now.year());
+                let year = infer_year(Some(Month::March), now.month(), now.year(), 3);
                 dt.replace_year(year).unwrap()
             }),
             (
@@ -418,6 +545,147 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_timestamp_int_with_explicit_unit() {
+        // Small millisecond values would be interpreted as seconds by the heuristic.
+        let date_time =
+            parse_timestamp_int(86_400_000, &[DateTimeInputFormat::TimestampMillis]).unwrap();
+        assert_eq!(date_time.into_timestamp_secs(), 86_400);
+
+        // Pre-1973 second timestamps are rejected by the heuristic.
+        let date_time =
+            parse_timestamp_int(-86_400, &[DateTimeInputFormat::TimestampSecs]).unwrap();
+        assert_eq!(
+            date_time.into_timestamp_secs(),
+            datetime!(1969-12-31 00:00:00 UTC).unix_timestamp()
+        );
+        let date_time =
+            parse_timestamp_int(1_500, &[DateTimeInputFormat::TimestampMicros]).unwrap();
+        assert_eq!(date_time.into_timestamp_nanos(), 1_500_000);
+
+        let date_time = parse_timestamp_int(42, &[DateTimeInputFormat::TimestampNanos]).unwrap();
+        assert_eq!(date_time.into_timestamp_nanos(), 42);
+
+        // The first timestamp format wins.
+        let date_time = parse_timestamp_int(
+            1_000,
+            &[
+                DateTimeInputFormat::Rfc3339,
+                DateTimeInputFormat::TimestampMillis,
+                DateTimeInputFormat::Timestamp,
+            ],
+        )
+        .unwrap();
+        assert_eq!(date_time.into_timestamp_secs(), 1);
+
+        let error =
+            parse_timestamp_int(i64::MAX, &[DateTimeInputFormat::TimestampSecs]).unwrap_err();
+        assert_eq!(
+            error,
+            "failed to parse unix timestamp `9223372036854775807` using format \
+             `unix_timestamp_secs`: value is out of range. Quickwit only supports datetimes \
+             ranging from `1677-09-21T00:12:43.145224192Z` to `2262-04-11T23:47:16.854775807Z`"
+        );
+    }
+
+    #[test]
+    fn test_parse_date_time_wide_range() {
+        // Dates prior to 1970 are supported by all formats but `unix_timestamp`, which infers the
+        // timestamp unit from its magnitude.
+        let date_time_formats = [
+            DateTimeInputFormat::Rfc3339,
+            DateTimeInputFormat::Strptime(StrptimeParser::from_str("%Y-%m-%d").unwrap()),
+            DateTimeInputFormat::TimestampSecs,
+        ];
+        let expected_timestamp_secs = datetime!(1900-01-01 00:00:00 UTC).unix_timestamp();
+
+        for date_time_str in ["1900-01-01T00:00:00Z", "1900-01-01", "-2208988800"] {
+            let date_time = parse_date_time_str(date_time_str, &date_time_formats).unwrap();
+            assert_eq!(date_time.into_timestamp_secs(), expected_timestamp_secs);
+        }
+        let date_time =
+            parse_timestamp_int(-2_208_988_800, &[DateTimeInputFormat::TimestampSecs]).unwrap();
+        assert_eq!(date_time.into_timestamp_secs(), expected_timestamp_secs);
+
+        // Datetimes that do not fit in the nanosecond precision of the index are rejected instead
+        // of wrapping around.
+        for date_time_str in [
+            "1600-01-01T00:00:00Z",
+            "2300-01-01T00:00:00Z",
+            "9999-12-31",
+            "253402300800",
+        ] {
+            let error = parse_date_time_str(date_time_str, &date_time_formats).unwrap_err();
+            assert!(
+                error.contains("value is out of range"),
+                "unexpected error for `{date_time_str}`: {error}"
+            );
+        }
+        // Year 10000.
+        let error = parse_timestamp_int(253_402_300_800, &[DateTimeInputFormat::TimestampSecs])
+            .unwrap_err();
+        assert!(error.contains("value is out of range"));
+
+        let error = parse_timestamp_float(253_402_300_800.0, &[DateTimeInputFormat::TimestampSecs])
+            .unwrap_err();
+        assert!(error.contains("value is out of range"));
+    }
+
+    #[test]
+    fn test_try_into_tantivy_date_time() {
+        let date_time = try_into_tantivy_date_time(datetime!(1900-01-01 00:00:00 UTC)).unwrap();
+        assert_eq!(date_time.into_utc(), datetime!(1900-01-01 00:00:00 UTC));
+
+        let date_time =
+            try_into_tantivy_date_time(datetime!(2262-04-11 23:47:16.854775807 UTC)).unwrap();
+        assert_eq!(date_time.into_timestamp_nanos(), i64::MAX);
+
+        let error =
+            try_into_tantivy_date_time(datetime!(2262-04-11 23:47:16.854775808 UTC)).unwrap_err();
+        assert!(error.contains("value is out of range"));
+    }
+
+    #[test]
+    fn test_parse_timestamp_float_with_explicit_unit() {
+        let date_time =
+            parse_timestamp_float(1_500.5, &[DateTimeInputFormat::TimestampMillis]).unwrap();
+        assert_eq!(date_time.into_timestamp_micros(), 1_500_500);
+
+        let date_time = parse_timestamp_float(-1.5, &[DateTimeInputFormat::TimestampSecs]).unwrap();
+        assert_eq!(date_time.into_timestamp_millis(), -1_500);
+
+        let error =
+            parse_timestamp_float(f64::MAX, &[DateTimeInputFormat::TimestampSecs]).unwrap_err();
+        assert!(error.contains("value is out of range"));
+    }
+
+    #[test]
+    fn test_parse_date_time_str_with_explicit_unit() {
+        let date_time =
+            parse_date_time_str("1000", &[DateTimeInputFormat::TimestampMillis]).unwrap();
+        assert_eq!(date_time.into_timestamp_secs(), 1);
+
+        let date_time =
+            parse_date_time_str("-1.25", &[DateTimeInputFormat::TimestampSecs]).unwrap();
+        assert_eq!(date_time.into_timestamp_millis(), -1_250);
+
+        let date_time =
+            parse_date_time_str("1.123456789", &[DateTimeInputFormat::TimestampMillis]).unwrap();
+        assert_eq!(date_time.into_timestamp_nanos(), 1_123_456);
+
+        let date_time = parse_date_time_str("1.", &[DateTimeInputFormat::TimestampMicros]).unwrap();
+        assert_eq!(date_time.into_timestamp_nanos(), 1_000);
+
+        let date_time =
+            parse_date_time_str("12.9", &[DateTimeInputFormat::TimestampNanos]).unwrap();
+        assert_eq!(date_time.into_timestamp_nanos(), 12);
+
+        for invalid_timestamp_str in ["", "foo", "1.2e3", "1.-5", "9223372036854775807"] {
+            parse_date_time_str(invalid_timestamp_str, &[DateTimeInputFormat::TimestampSecs])
+                .unwrap_err();
+        }
+    }
+
     #[test]
     fn test_parse_timestamp_str() {
         let date_time = parse_timestamp_str("123456789").unwrap();
@@ -0,0 +1,730 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use time::format_description::modifier::{
+    Day, Hour, Minute, Month as MonthModifier, OffsetHour, OffsetMinute, Padding, Period, Second,
+    Subsecond, SubsecondDigits, WeekNumber, WeekNumberRepr, Year, YearRepr,
+};
+use time::format_description::{Component, OwnedFormatItem};
+
+use crate::{DateTimeInputFormat, StrptimeParser};
+
+/// Java date format tokens supported by the tokenizer.
+///
+/// Tokens are matched greedily in order, so a token must appear before any of its prefixes.
+const JAVA_DATE_FORMAT_TOKENS: &[&str] = &[
+    "yyyy",
+    "xxxx",
+    "SSSSSSSSS",
+    "SSSSSSSS",
+    "SSSSSSS",
+    "SSSSSS",
+    "SSSSS",
+    "SSSS",
+    "SSS",
+    "SS",
+    "XXX",
+    "ZZZ",
+    "MM",
+    "dd",
+    "ww",
+    "HH",
+    "hh",
+    "mm",
+    "ss",
+    "XX",
+    "ZZ",
+    "M",
+    "d",
+    "w",
+    "H",
+    "h",
+    "m",
+    "s",
+    "S",
+    "a",
+    "X",
+    "Z",
+];
+
+/// A token of a Java date format.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum JavaDateFormatToken<'a> {
+    /// A sequence of characters matched as is.
+    Literal(&'a str),
+    /// A pattern letter sequence from [`JAVA_DATE_FORMAT_TOKENS`].
+    Pattern(&'static str),
+    /// The start of an optional section (`[`).
+    OptionalStart,
+    /// The end of an optional section (`]`).
+    OptionalEnd,
+}
+
+impl JavaDateFormatToken<'_> {
+    fn is_zone_offset(&self) -> bool {
+        matches!(self, JavaDateFormatToken::Pattern(pattern) if pattern.starts_with(['X', 'Z']))
+    }
+}
+
+fn literal(literal: &str) -> OwnedFormatItem {
+    OwnedFormatItem::Literal(literal.as_bytes().into())
+}
+
+fn get_padding(pattern: &str) -> Padding {
+    if pattern.len() == 1 {
+        Padding::None
+    } else {
+        Padding::Zero
+    }
+}
+
+fn build_year_item(_pattern: &str) -> OwnedFormatItem {
+    let mut year = Year::default();
+    year.repr = YearRepr::Full;
+    OwnedFormatItem::Component(Component::Year(year))
+}
+
+/// The week-based year is the year the ISO week belongs to, which differs from the calendar year
+/// for the first and last days of the year (e.g. 2024-12-30 belongs to `2025-W01`).
+fn build_week_based_year_item(_pattern: &str) -> OwnedFormatItem {
+    let mut year = Year::default();
+    year.repr = YearRepr::Full;
+    year.iso_week_based = true;
+    OwnedFormatItem::Component(Component::Year(year))
+}
+
+fn build_month_item(pattern: &str) -> OwnedFormatItem {
+    let mut month = MonthModifier::default();
+    month.padding = get_padding(pattern);
+    OwnedFormatItem::Component(Component::Month(month))
+}
+
+fn build_day_item(pattern: &str) -> OwnedFormatItem {
+    let mut day = Day::default();
+    day.padding = get_padding(pattern);
+    OwnedFormatItem::Component(Component::Day(day))
+}
+
+fn build_week_of_year_item(pattern: &str) -> OwnedFormatItem {
+    let mut week_number = WeekNumber::default();
+    week_number.repr = WeekNumberRepr::Iso;
+    week_number.padding = get_padding(pattern);
+    OwnedFormatItem::Component(Component::WeekNumber(week_number))
+}
+
+fn build_hour_item(pattern: &str) -> OwnedFormatItem {
+    let mut hour = Hour::default();
+    hour.padding = get_padding(pattern);
+    hour.is_12_hour_clock = pattern.starts_with('h');
+    OwnedFormatItem::Component(Component::Hour(hour))
+}
+
+fn build_period_item(_pattern: &str) -> OwnedFormatItem {
+    let mut period = Period::default();
+    period.case_sensitive = false;
+    OwnedFormatItem::Component(Component::Period(period))
+}
+
+fn build_minute_item(pattern: &str) -> OwnedFormatItem {
+    let mut minute = Minute::default();
+    minute.padding = get_padding(pattern);
+    OwnedFormatItem::Component(Component::Minute(minute))
+}
+
+fn build_second_item(pattern: &str) -> OwnedFormatItem {
+    let mut second = Second::default();
+    second.padding = get_padding(pattern);
+    OwnedFormatItem::Component(Component::Second(second))
+}
+
+/// The number of `S` letters is the exact number of fractional digits.
+fn build_fraction_of_second_item(pattern: &str) -> OwnedFormatItem {
+    let mut subsecond = Subsecond::default();
+    subsecond.digits = match pattern.len() {
+        1 => SubsecondDigits::One,
+        2 => SubsecondDigits::Two,
+        3 => SubsecondDigits::Three,
+        4 => SubsecondDigits::Four,
+        5 => SubsecondDigits::Five,
+        6 => SubsecondDigits::Six,
+        7 => SubsecondDigits::Seven,
+        8 => SubsecondDigits::Eight,
+        _ => SubsecondDigits::Nine,
+    };
+    OwnedFormatItem::Component(Component::Subsecond(subsecond))
+}
+
+/// Builds a UTC offset item.
+///
+/// When parsing, `+HH:MM`, `+HHMM`, `+HH` and the `Z` designator for UTC are all accepted
+/// regardless of the pattern. When formatting, `XXX` renders the offset as `+HH:MM` and the other
+/// patterns render it as `+HHMM`.
+fn build_zone_offset_item(pattern: &str) -> OwnedFormatItem {
+    let mut offset_hour = OffsetHour::default();
+    offset_hour.sign_is_mandatory = true;
+    let offset_hour_item = OwnedFormatItem::Component(Component::OffsetHour(offset_hour));
+    let offset_minute_item =
+        OwnedFormatItem::Component(Component::OffsetMinute(OffsetMinute::default()));
+
+    let with_delimiter = OwnedFormatItem::Compound(Box::new([
+        offset_hour_item.clone(),
+        literal(":"),
+        offset_minute_item.clone(),
+    ]));
+    let without_delimiter =
+        OwnedFormatItem::Compound(Box::new([offset_hour_item.clone(), offset_minute_item]));
+
+    // `First` formats using its first item and parses using the first item that matches, so
+    // `+HH` must come after the representations it is a prefix of.
+    let offset_items = if pattern == "XXX" {
+        [
+            with_delimiter,
+            without_delimiter,
+            offset_hour_item,
+            literal("Z"),
+        ]
+    } else {
+        [
+            without_delimiter,
+            with_delimiter,
+            offset_hour_item,
+            literal("Z"),
+        ]
+    };
+    OwnedFormatItem::First(Box::new(offset_items))
+}
+
+fn build_item(pattern: &str) -> OwnedFormatItem {
+    match pattern {
+        "yyyy" => build_year_item(pattern),
+        "xxxx" => build_week_based_year_item(pattern),
+        "MM" | "M" => build_month_item(pattern),
+        "dd" | "d" => build_day_item(pattern),
+        "ww" | "w" => build_week_of_year_item(pattern),
+        "HH" | "H" | "hh" | "h" => build_hour_item(pattern),
+        "a" => build_period_item(pattern),
+        "mm" | "m" => build_minute_item(pattern),
+        "ss" | "s" => build_second_item(pattern),
+        _ if pattern.starts_with('S') => build_fraction_of_second_item(pattern),
+        _ if pattern.starts_with(['X', 'Z']) => build_zone_offset_item(pattern),
+        _ => unreachable!("pattern `{pattern}` is missing from `JAVA_DATE_FORMAT_TOKENS`"),
+    }
+}
+
+/// Splits a Java date format into a sequence of tokens.
+///
+/// Text enclosed in single quotes is treated as a literal, `''` being an escaped single quote.
+/// Square brackets delimit optional sections, which can be nested. Other non-letter characters are
+/// treated as literals as well.
+pub(crate) fn java_date_format_tokenizer(
+    java_date_format: &str,
+) -> Result<Vec<JavaDateFormatToken<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut remaining = java_date_format;
+    let mut optional_depth = 0usize;
+
+    'tokenizer: while let Some(next_char) = remaining.chars().next() {
+        if next_char == '[' {
+            tokens.push(JavaDateFormatToken::OptionalStart);
+            optional_depth += 1;
+            remaining = &remaining[1..];
+            continue;
+        }
+        if next_char == ']' {
+            if optional_depth == 0 {
+                return Err(format!(
+                    "invalid java date format `{java_date_format}`: unmatched `]`"
+                ));
+            }
+            tokens.push(JavaDateFormatToken::OptionalEnd);
+            optional_depth -= 1;
+            remaining = &remaining[1..];
+            continue;
+        }
+        if next_char == '\'' {
+            if let Some(rest) = remaining.strip_prefix("''") {
+                tokens.push(JavaDateFormatToken::Literal(&remaining[..1]));
+                remaining = rest;
+                continue;
+            }
+            // Within a quoted literal, `''` is an escaped single quote as well.
+            remaining = &remaining[1..];
+            loop {
+                let Some(quote_pos) = remaining.find('\'') else {
+                    return Err(format!(
+                        "invalid java date format `{java_date_format}`: unterminated quoted \
+                         literal"
+                    ));
+                };
+                if quote_pos > 0 {
+                    tokens.push(JavaDateFormatToken::Literal(&remaining[..quote_pos]));
+                }
+                remaining = &remaining[quote_pos + 1..];
+
+                if remaining.starts_with('\'') {
+                    tokens.push(JavaDateFormatToken::Literal(&remaining[..1]));
+                    remaining = &remaining[1..];
+                } else {
+                    break;
+                }
+            }
+            continue;
+        }
+        for pattern in JAVA_DATE_FORMAT_TOKENS {
+            if let Some(rest) = remaining.strip_prefix(pattern) {
+                tokens.push(JavaDateFormatToken::Pattern(pattern));
+                remaining = rest;
+                continue 'tokenizer;
+            }
+        }
+        if next_char.is_ascii_alphabetic() {
+            return Err(format!(
+                "invalid java date format `{java_date_format}`: unsupported pattern letter \
+                 `{next_char}`"
+            ));
+        }
+        let char_len = next_char.len_utf8();
+        tokens.push(JavaDateFormatToken::Literal(&remaining[..char_len]));
+        remaining = &remaining[char_len..];
+    }
+    if optional_depth > 0 {
+        return Err(format!(
+            "invalid java date format `{java_date_format}`: unterminated optional section"
+        ));
+    }
+    Ok(tokens)
+}
+
+/// Builds the format items of a sequence of tokens whose optional sections are balanced.
+///
+/// Optional sections are parsed only if they match as a whole and are always formatted.
+fn build_format_items(tokens: &[JavaDateFormatToken]) -> Vec<OwnedFormatItem> {
+    let mut sections: Vec<Vec<OwnedFormatItem>> = vec![Vec::new()];
+
+    for token in tokens {
+        match token {
+            JavaDateFormatToken::Literal(literal_str) => sections
+                .last_mut()
+                .expect("there should be at least one section")
+                .push(literal(literal_str)),
+            JavaDateFormatToken::Pattern(pattern) => sections
+                .last_mut()
+                .expect("there should be at least one section")
+                .push(build_item(pattern)),
+            JavaDateFormatToken::OptionalStart => sections.push(Vec::new()),
+            JavaDateFormatToken::OptionalEnd => {
+                let optional_items = sections
+                    .pop()
+                    .expect("optional sections should be balanced");
+                let optional_item = OwnedFormatItem::Optional(Box::new(OwnedFormatItem::Compound(
+                    optional_items.into_boxed_slice(),
+                )));
+                sections
+                    .last_mut()
+                    .expect("optional sections should be balanced")
+                    .push(optional_item);
+            }
+        }
+    }
+    debug_assert_eq!(sections.len(), 1);
+    sections.pop().unwrap_or_default()
+}
+
+impl StrptimeParser {
+    /// Creates a parser from a Java `DateTimeFormatter` pattern, as used in Elasticsearch
+    /// mappings and queries (e.g. `yyyy-MM-dd'T'HH:mm:ss.SSSZ` or `xxxx-'W'ww`).
+    ///
+    /// Dates parsed with a pattern that does not contain a UTC offset are assumed to be UTC.
+    pub fn from_java_datetime_format(java_datetime_format: &str) -> Result<Self, String> {
+        let tokens = java_date_format_tokenizer(java_datetime_format)?;
+        let with_timezone = tokens.iter().any(JavaDateFormatToken::is_zone_offset);
+        let items = build_format_items(&tokens);
+        Ok(StrptimeParser::new(
+            java_datetime_format.to_string(),
+            with_timezone,
+            items.into_boxed_slice(),
+        ))
+    }
+}
+
+/// Elasticsearch built-in formats that only have a time of day, and therefore cannot parse a date.
+const TIME_ONLY_FORMAT_ALIASES: &[&str] = &[
+    "basic_t_time",
+    "basic_t_time_no_millis",
+    "basic_time",
+    "basic_time_no_millis",
+    "hour",
+    "hour_minute",
+    "hour_minute_second",
+    "hour_minute_second_fraction",
+    "hour_minute_second_millis",
+    "t_time",
+    "t_time_no_millis",
+    "time",
+    "time_no_millis",
+];
+
+/// Resolves an Elasticsearch date format, which is either the name of a built-in format (e.g.
+/// `strict_date` or `epoch_millis`) or a Java date format pattern.
+///
+/// Epoch formats resolve to unix timestamp formats with an explicit unit, which parse integer and
+/// fractional timestamps. Time-only formats (e.g. `hour_minute`) are rejected.
+pub fn resolve_java_datetime_format_alias(
+    java_datetime_format: &str,
+) -> Result<DateTimeInputFormat, String> {
+    let alias = java_datetime_format
+        .strip_prefix("strict_")
+        .unwrap_or(java_datetime_format);
+    if TIME_ONLY_FORMAT_ALIASES.contains(&alias) {
+        return Err(format!(
+            "date format `{java_datetime_format}` only has a time of day and cannot be used to \
+             parse dates"
+        ));
+    }
+    let java_datetime_pattern = match java_datetime_format {
+        "epoch_millis" => return Ok(DateTimeInputFormat::TimestampMillis),
+        "epoch_second" => return Ok(DateTimeInputFormat::TimestampSecs),
+        "basic_date" | "strict_basic_date" => "yyyyMMdd",
+        "basic_date_time" | "strict_basic_date_time" => "yyyyMMdd'T'HHmmss.SSSZ",
+        "basic_date_time_no_millis" | "strict_basic_date_time_no_millis" => "yyyyMMdd'T'HHmmssZ",
+        "date" | "strict_date" | "year_month_day" | "strict_year_month_day" => "yyyy-MM-dd",
+        "date_optional_time" | "strict_date_optional_time" => {
+            "yyyy-MM-dd['T'HH:mm[:ss[.SSS]][XXX]]"
+        }
+        "date_hour_minute" | "strict_date_hour_minute" => "yyyy-MM-dd'T'HH:mm",
+        "date_hour_minute_second" | "strict_date_hour_minute_second" => "yyyy-MM-dd'T'HH:mm:ss",
+        "date_hour_minute_second_millis" | "strict_date_hour_minute_second_millis" => {
+            "yyyy-MM-dd'T'HH:mm:ss.SSS"
+        }
+        "date_time" | "strict_date_time" => "yyyy-MM-dd'T'HH:mm:ss.SSSZ",
+        "date_time_no_millis" | "strict_date_time_no_millis" => "yyyy-MM-dd'T'HH:mm:ssZ",
+        "weekyear_week" | "strict_weekyear_week" => "xxxx-'W'ww",
+        _ => java_datetime_format,
+    };
+    StrptimeParser::from_java_datetime_format(java_datetime_pattern)
+        .map(DateTimeInputFormat::Strptime)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::parse_date_time_str;
+
+    #[test]
+    fn test_java_date_format_tokenizer() {
+        use JavaDateFormatToken::{Literal, Pattern};
+
+        let tokens = java_date_format_tokenizer("yyyy-MM-dd").unwrap();
+        assert_eq!(
+            tokens,
+            [
+                Pattern("yyyy"),
+                Literal("-"),
+                Pattern("MM"),
+                Literal("-"),
+                Pattern("dd")
+            ]
+        );
+        let tokens = java_date_format_tokenizer("xxxx-'W'ww").unwrap();
+        assert_eq!(
+            tokens,
+            [Pattern("xxxx"), Literal("-"), Literal("W"), Pattern("ww")]
+        );
+        let tokens = java_date_format_tokenizer("HH:mm:ss.SSSXXX").unwrap();
+        assert_eq!(
+            tokens,
+            [
+                Pattern("HH"),
+                Literal(":"),
+                Pattern("mm"),
+                Literal(":"),
+                Pattern("ss"),
+                Literal("."),
+                Pattern("SSS"),
+                Pattern("XXX")
+            ]
+        );
+        let tokens = java_date_format_tokenizer("hh 'o''clock' a").unwrap();
+        assert_eq!(
+            tokens,
+            [
+                Pattern("hh"),
+                Literal(" "),
+                Literal("o"),
+                Literal("'"),
+                Literal("clock"),
+                Literal(" "),
+                Pattern("a")
+            ]
+        );
+        let tokens = java_date_format_tokenizer("'''T'''yyyy").unwrap();
+        assert_eq!(
+            tokens,
+            [Literal("'"), Literal("T"), Literal("'"), Pattern("yyyy")]
+        );
+        let error = java_date_format_tokenizer("yyyy-'W").unwrap_err();
+        assert!(error.contains("unterminated quoted literal"));
+
+        let error = java_date_format_tokenizer("yyyy-QQ").unwrap_err();
+        assert!(error.contains("unsupported pattern letter `Q`"));
+    }
+
+    #[test]
+    fn test_java_date_format_tokenizer_optional_sections() {
+        use JavaDateFormatToken::{Literal, OptionalEnd, OptionalStart, Pattern};
+
+        let tokens = java_date_format_tokenizer("yyyy['T'HH[:mm]]'['").unwrap();
+        assert_eq!(
+            tokens,
+            [
+                Pattern("yyyy"),
+                OptionalStart,
+                Literal("T"),
+                Pattern("HH"),
+                OptionalStart,
+                Literal(":"),
+                Pattern("mm"),
+                OptionalEnd,
+                OptionalEnd,
+                Literal("[")
+            ]
+        );
+        let error = java_date_format_tokenizer("yyyy[-MM").unwrap_err();
+        assert!(error.contains("unterminated optional section"));
+
+        let error = java_date_format_tokenizer("yyyy]-MM").unwrap_err();
+        assert!(error.contains("unmatched `]`"));
+    }
+
+    #[test]
+    fn test_parse_java_date_format_with_optional_sections() {
+        let parser =
+            StrptimeParser::from_java_datetime_format("yyyy-MM-dd['T'HH:mm:ss[.SSS][Z]]").unwrap();
+        let test_data = [
+            ("2024-02-14", datetime!(2024-02-14 00:00:00 UTC)),
+            ("2024-02-14T18:40:19", datetime!(2024-02-14 18:40:19 UTC)),
+            (
+                "2024-02-14T18:40:19.950",
+                datetime!(2024-02-14 18:40:19.950 UTC),
+            ),
+            (
+                "2024-02-14T18:40:19+0100",
+                datetime!(2024-02-14 18:40:19 +01:00),
+            ),
+            (
+                "2024-02-14T18:40:19.950Z",
+                datetime!(2024-02-14 18:40:19.950 UTC),
+            ),
+        ];
+        for (date_time_str, expected) in test_data {
+            assert_eq!(
+                parser.parse_date_time(date_time_str).unwrap(),
+                expected,
+                "failed to parse `{date_time_str}`"
+            );
+        }
+        // An optional section is only parsed if it matches as a whole.
+        parser.parse_date_time("2024-02-14T18:40").unwrap_err();
+        parser.parse_date_time("2024-02-14T").unwrap_err();
+
+        let date_time_format =
+            resolve_java_datetime_format_alias("strict_date_optional_time").unwrap();
+        for date_time_str in [
+            "2024-02-14",
+            "2024-02-14T18:40",
+            "2024-02-14T18:40:19",
+            "2024-02-14T18:40:19.950",
+            "2024-02-14T18:40:19.950+01:00",
+        ] {
+            parse_date_time_str(date_time_str, &[date_time_format.clone()]).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parse_java_date_format() {
+        let test_data = [
+            (
+                "yyyy-MM-dd",
+                "2024-02-14",
+                datetime!(2024-02-14 00:00:00 UTC),
+            ),
+            ("yyyy/M/d", "2024/2/4", datetime!(2024-02-04 00:00:00 UTC)),
+            (
+                "dd.MM.yyyy",
+                "14.02.2024",
+                datetime!(2024-02-14 00:00:00 UTC),
+            ),
+            ("xxxx-'W'ww", "2024-W07", datetime!(2024-02-12 00:00:00 UTC)),
+            ("xxxx-'W'ww", "2025-W01", datetime!(2024-12-30 00:00:00 UTC)),
+            ("xxxx'W'w", "2020W53", datetime!(2020-12-28 00:00:00 UTC)),
+            (
+                "yyyy-MM-dd HH:mm:ss",
+                "2024-02-14 18:40:19",
+                datetime!(2024-02-14 18:40:19 UTC),
+            ),
+            (
+                "yyyy-MM-dd HH:mm",
+                "2024-02-14 18:40",
+                datetime!(2024-02-14 18:40:00 UTC),
+            ),
+            (
+                "yyyy-MM-dd'T'HH:mm:ss.SSS",
+                "2024-02-14T18:40:19.950",
+                datetime!(2024-02-14 18:40:19.950 UTC),
+            ),
+            (
+                "yyyy-MM-dd'T'HH:mm:ss.SSSSSS",
+                "2024-02-14T18:40:19.950188",
+                datetime!(2024-02-14 18:40:19.950188 UTC),
+            ),
+            (
+                "yyyy-MM-dd'T'HH:mm:ss.SSSSSSSSS",
+                "2024-02-14T18:40:19.950188123",
+                datetime!(2024-02-14 18:40:19.950188123 UTC),
+            ),
+            (
+                "yyyy-MM-dd hh:mm a",
+                "2024-02-14 06:40 PM",
+                datetime!(2024-02-14 18:40:00 UTC),
+            ),
+            (
+                "yyyy-MM-dd HH:mm:ss.SSSZ",
+                "2024-02-14 18:40:19.950+0200",
+                datetime!(2024-02-14 16:40:19.950 UTC),
+            ),
+            (
+                "yyyy-MM-dd HH:mm:ss.SSSZ",
+                "2024-02-14 18:40:19.950-03:30",
+                datetime!(2024-02-14 22:10:19.950 UTC),
+            ),
+            (
+                "yyyy-MM-dd'T'HH:mm:ssXXX",
+                "2024-02-14T18:40:19+02:00",
+                datetime!(2024-02-14 16:40:19 UTC),
+            ),
+            (
+                "yyyy-MM-dd'T'HH:mm:ssXXX",
+                "2024-02-14T18:40:19Z",
+                datetime!(2024-02-14 18:40:19 UTC),
+            ),
+            (
+                "yyyy-MM-dd'T'HH:mm:ssX",
+                "2024-02-14T18:40:19+02",
+                datetime!(2024-02-14 16:40:19 UTC),
+            ),
+        ];
+        for (java_format, date_time_str, expected) in test_data {
+            let parser = StrptimeParser::from_java_datetime_format(java_format).unwrap();
+            let date_time = parser
+                .parse_date_time(date_time_str)
+                .unwrap_or_else(|error| {
+                    panic!(
+                        "failed to parse `{date_time_str}` using the java date format \
+                         `{java_format}`: {error}"
+                    )
+                });
+            assert_eq!(date_time, expected);
+        }
+    }
+
+    #[test]
+    fn test_java_date_format_with_timezone() {
+        let parser = StrptimeParser::from_java_datetime_format("yyyy-MM-dd HH:mm'Z'").unwrap();
+        let date_time = parser.parse_date_time("2024-02-14 18:40Z").unwrap();
+        assert_eq!(date_time, datetime!(2024-02-14 18:40:00 UTC));
+
+        let parser = StrptimeParser::from_java_datetime_format("yyyy-MM-dd HH:mmZ").unwrap();
+        let date_time = parser.parse_date_time("2024-02-14 18:40+0200").unwrap();
+        assert_eq!(date_time, datetime!(2024-02-14 18:40:00 +02:00));
+        assert_eq!(date_time.offset().whole_hours(), 2);
+
+        let date_time = parser.parse_date_time("2024-02-14 18:40Z").unwrap();
+        assert_eq!(date_time, datetime!(2024-02-14 18:40:00 UTC));
+
+        let error = parser.parse_date_time("2024-02-14 18:40").unwrap_err();
+        assert!(!error.is_empty());
+    }
+
+    #[test]
+    fn test_format_java_date_format_round_trip() {
+        let test_data = [
+            ("yyyy-MM-dd", "2024-02-14"),
+            ("xxxx-'W'ww", "2024-W07"),
+            ("xxxx-'W'ww", "2025-W01"),
+            ("yyyy-MM-dd'T'HH:mm:ss.SSS", "2024-02-14T18:40:19.950"),
+            ("yyyy-MM-dd hh:mm a", "2024-02-14 06:40 PM"),
+            ("yyyy-MM-dd HH:mm:ssZ", "2024-02-14 18:40:19+0200"),
+            ("yyyy-MM-dd HH:mm:ssXXX", "2024-02-14 18:40:19-03:30"),
+        ];
+        for (java_format, date_time_str) in test_data {
+            let parser = StrptimeParser::from_java_datetime_format(java_format).unwrap();
+            let date_time = parser.parse_date_time(date_time_str).unwrap();
+            assert_eq!(parser.format_date_time(&date_time).unwrap(), date_time_str);
+        }
+    }
+
+    #[test]
+    fn test_resolve_java_datetime_format_alias() {
+        assert_eq!(
+            resolve_java_datetime_format_alias("epoch_millis").unwrap(),
+            DateTimeInputFormat::TimestampMillis
+        );
+        assert_eq!(
+            resolve_java_datetime_format_alias("epoch_second").unwrap(),
+            DateTimeInputFormat::TimestampSecs
+        );
+        let date_time_format = resolve_java_datetime_format_alias("strict_date").unwrap();
+        assert_eq!(date_time_format.as_str(), "yyyy-MM-dd");
+
+        let date_time_format = resolve_java_datetime_format_alias("dd/MM/yyyy").unwrap();
+        assert_eq!(date_time_format.as_str(), "dd/MM/yyyy");
+
+        resolve_java_datetime_format_alias("unknown_format").unwrap_err();
+
+        for time_only_format in ["hour_minute", "strict_hour_minute_second", "time"] {
+            let error = resolve_java_datetime_format_alias(time_only_format).unwrap_err();
+            assert_eq!(
+                error,
+                format!(
+                    "date format `{time_only_format}` only has a time of day and cannot be used \
+                     to parse dates"
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_epoch_aliases() {
+        let epoch_millis = resolve_java_datetime_format_alias("epoch_millis").unwrap();
+        let date_time = parse_date_time_str("1707936019950", &[epoch_millis.clone()]).unwrap();
+        assert_eq!(date_time.into_timestamp_millis(), 1_707_936_019_950);
+
+        let date_time = parse_date_time_str("1000", &[epoch_millis]).unwrap();
+        assert_eq!(date_time.into_timestamp_secs(), 1);
+
+        let epoch_second = resolve_java_datetime_format_alias("epoch_second").unwrap();
+        let date_time = parse_date_time_str("1707936019", &[epoch_second.clone()]).unwrap();
+        assert_eq!(date_time.into_timestamp_secs(), 1_707_936_019);
+
+        let date_time = parse_date_time_str("1707936019.95", &[epoch_second]).unwrap();
+        assert_eq!(date_time.into_timestamp_millis(), 1_707_936_019_950);
+    }
+}
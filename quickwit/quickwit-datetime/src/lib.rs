@@ -17,11 +17,21 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+mod auto_date_time_format;
+mod chrono_date_time_format;
+mod date_math;
 mod date_time_format;
 mod date_time_parsing;
+mod java_date_time_format;
 
-pub use date_time_format::{DateTimeInputFormat, DateTimeOutputFormat, StrptimeParser};
+pub use auto_date_time_format::AutoDateTimeParser;
+pub use date_math::{DateMathParser, DateMathRounding};
+pub use date_time_format::{
+    DateTimeInputFormat, DateTimeOutputFormat, OutputTimezone, StrptimeParser,
+};
 pub use date_time_parsing::{
     parse_date_time_str, parse_timestamp, parse_timestamp_float, parse_timestamp_int,
+    try_into_tantivy_date_time,
 };
+pub use java_date_time_format::resolve_java_datetime_format_alias;
 pub use tantivy::DateTime as TantivyDateTime;
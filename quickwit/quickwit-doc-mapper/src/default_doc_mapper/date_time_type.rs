@@ -18,7 +18,9 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use indexmap::IndexSet;
-use quickwit_datetime::{DateTimeInputFormat, DateTimeOutputFormat, TantivyDateTime};
+use quickwit_datetime::{
+    DateTimeInputFormat, DateTimeOutputFormat, OutputTimezone, TantivyDateTime,
+};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value as JsonValue;
 use tantivy::schema::{DateTimePrecision, OwnedValue as TantivyValue};
@@ -41,6 +43,11 @@ pub struct QuickwitDateTimeOptions {
     #[serde(default)]
     pub output_format: DateTimeOutputFormat,
 
+    /// Time zone in which dates are displayed.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "OutputTimezone::is_utc")]
+    pub output_timezone: OutputTimezone,
+
     /// Internal storage precision.
     #[serde(default)]
     #[serde(alias = "precision")]
@@ -62,6 +69,7 @@ impl Default for QuickwitDateTimeOptions {
             description: None,
             input_formats: InputFormats::default(),
             output_format: DateTimeOutputFormat::default(),
+            output_timezone: OutputTimezone::default(),
             fast_precision: DateTimePrecision::default(),
             indexed: true,
             stored: true,
@@ -221,6 +229,7 @@ mod tests {
             description: Some("When the record was last updated.".to_string()),
             input_formats: expected_input_formats,
             output_format: DateTimeOutputFormat::Rfc3339,
+            output_timezone: OutputTimezone::default(),
             fast_precision: DateTimePrecision::Milliseconds,
             indexed: true,
             fast: true,
@@ -291,6 +300,7 @@ mod tests {
             description: Some("When the record was last updated.".to_string()),
             input_formats: expected_input_formats,
             output_format: DateTimeOutputFormat::TimestampSecs,
+            output_timezone: OutputTimezone::default(),
             fast_precision: DateTimePrecision::Milliseconds,
             indexed: true,
             fast: true,
@@ -311,6 +321,7 @@ mod tests {
             date_time_options.output_format,
             DateTimeOutputFormat::Rfc3339
         );
+        assert!(date_time_options.output_timezone.is_utc());
         assert_eq!(date_time_options.fast_precision, DateTimePrecision::Seconds);
         assert!(date_time_options.indexed);
         assert!(date_time_options.stored);
@@ -372,6 +383,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_date_time_options_output_timezone_ser_deser() {
+        let field_mapping_entry = serde_json::from_str::<FieldMappingEntry>(
+            r#"
+            {
+                "name": "updated_at",
+                "type": "datetime",
+                "output_timezone": "Europe/Paris"
+            }"#,
+        )
+        .unwrap();
+
+        let entry_json = serde_json::to_value(&field_mapping_entry).unwrap();
+        assert_eq!(entry_json["output_timezone"], "Europe/Paris");
+
+        let error = serde_json::from_str::<QuickwitDateTimeOptions>(
+            r#"
+            {
+                "output_timezone": "Europe/Pariss"
+            }
+            "#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("unknown time zone: `Europe/Pariss`"));
+    }
+
     #[test]
     fn test_deserialize_input_formats_deser() {
         {
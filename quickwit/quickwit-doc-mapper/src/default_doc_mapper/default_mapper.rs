@@ -35,22 +35,23 @@ use tantivy::schema::document::{ReferenceValue, ReferenceValueLeaf};
 use tantivy::schema::{
     Field, FieldType, OwnedValue as TantivyValue, Schema, Value, INDEXED, STORED,
 };
+use tantivy::tokenizer::TokenStream;
 use tantivy::TantivyDocument as Document;
 
 use super::field_mapping_entry::RAW_TOKENIZER_NAME;
 use super::DefaultDocMapperBuilder;
 use crate::default_doc_mapper::mapping_tree::{
     build_field_path_from_str, build_mapping_tree, map_primitive_json_to_tantivy,
-    JsonValueIterator, MappingNode, MappingNodeRoot,
+    JsonValueIterator, MappingNode, MappingNodeRoot, TokenCountField,
 };
-use crate::default_doc_mapper::FieldMappingType;
+use crate::default_doc_mapper::{validate_field_mapping_name, FieldMappingType};
 use crate::doc_mapper::{JsonObject, Partition};
-use crate::query_builder::build_query;
+use crate::query_builder::{build_query, enable_leading_wildcards, resolve_field_aliases};
 use crate::routing_expression::RoutingExpr;
 use crate::{
-    Cardinality, DocMapper, DocMapping, DocParsingError, Mode, ModeType, QueryParserError,
-    TokenizerEntry, WarmupInfo, DOCUMENT_SIZE_FIELD_NAME, DYNAMIC_FIELD_NAME,
-    FIELD_PRESENCE_FIELD_NAME, SOURCE_FIELD_NAME,
+    Cardinality, DocMapper, DocMapping, DocParsingError, Mode, QueryParserError, TokenizerEntry,
+    WarmupInfo, DOCUMENT_SIZE_FIELD_NAME, DYNAMIC_FIELD_NAME, FIELD_PRESENCE_FIELD_NAME,
+    SOURCE_FIELD_NAME,
 };
 
 const FIELD_PRESENCE_FIELD: Field = Field::from_field_id(0u32);
@@ -79,6 +80,8 @@ pub struct DefaultDocMapper {
     document_size_field: Option<Field>,
     /// Default list of field names used for search.
     default_search_field_names: Vec<String>,
+    /// Allows wildcard queries starting with a wildcard.
+    allow_leading_wildcard: bool,
     /// Timestamp field name.
     timestamp_field_name: Option<String>,
     /// Timestamp field path (name parsed)
@@ -92,6 +95,8 @@ pub struct DefaultDocMapper {
     schema: Schema,
     /// List of field names used for tagging.
     tag_field_names: BTreeSet<String>,
+    /// List of field names with a per-split Bloom filter.
+    bloom_filter_field_names: BTreeSet<String>,
     /// The partition key is a DSL used to route documents
     /// into specific splits.
     partition_key: RoutingExpr,
@@ -103,6 +108,10 @@ pub struct DefaultDocMapper {
     tokenizer_entries: Vec<TokenizerEntry>,
     /// Tokenizer manager.
     tokenizer_manager: TokenizerManager,
+    /// Alias field names resolved to a concrete field at query time.
+    field_aliases: BTreeMap<String, String>,
+    /// Text fields whose number of tokens is recorded in a fast field.
+    token_count_fields: Vec<TokenCountField>,
 }
 
 fn validate_timestamp_field(
@@ -147,16 +156,19 @@ impl From<DefaultDocMapper> for DefaultDocMapperBuilder {
             field_mappings: default_doc_mapper.field_mappings.into(),
             timestamp_field: default_doc_mapper.timestamp_field_name,
             tag_fields: default_doc_mapper.tag_field_names,
+            bloom_filter_fields: default_doc_mapper.bloom_filter_field_names,
             partition_key: partition_key_opt,
             max_num_partitions: default_doc_mapper.max_num_partitions,
             index_field_presence: default_doc_mapper.index_field_presence,
             store_document_size: default_doc_mapper.document_size_field.is_some(),
             store_source: default_doc_mapper.source_field.is_some(),
             tokenizers: default_doc_mapper.tokenizer_entries,
+            field_aliases: default_doc_mapper.field_aliases,
         };
         Self {
             doc_mapping,
             default_search_fields: default_doc_mapper.default_search_field_names,
+            allow_leading_wildcard: default_doc_mapper.allow_leading_wildcard,
         }
     }
 }
@@ -247,6 +259,22 @@ impl TryFrom<DefaultDocMapperBuilder> for DefaultDocMapper {
         }
         validate_fields_tokenizers(&schema, &tokenizer_manager)?;
 
+        // Resolve field aliases
+        for (alias, target_field_name) in &doc_mapping.field_aliases {
+            validate_field_alias(
+                alias,
+                target_field_name,
+                &doc_mapping.field_aliases,
+                &schema,
+            )?;
+            if schema
+                .find_field_with_default(target_field_name, dynamic_field)
+                .is_none()
+            {
+                bail!("unknown target field `{target_field_name}` for field alias `{alias}`");
+            }
+        }
+
         // Resolve default search fields
         let mut default_search_field_names = Vec::new();
         for default_search_field_name in &builder.default_search_fields {
@@ -256,8 +284,12 @@ impl TryFrom<DefaultDocMapperBuilder> for DefaultDocMapper {
                     default_search_field_name
                 )
             }
+            let resolved_default_search_field_name = doc_mapping
+                .field_aliases
+                .get(default_search_field_name)
+                .unwrap_or(default_search_field_name);
             let (default_search_field, _json_path) = schema
-                .find_field_with_default(default_search_field_name, dynamic_field)
+                .find_field_with_default(resolved_default_search_field_name, dynamic_field)
                 .with_context(|| {
                     format!("unknown default search field `{default_search_field_name}`")
                 })?;
@@ -272,6 +304,11 @@ impl TryFrom<DefaultDocMapperBuilder> for DefaultDocMapper {
             validate_tag(tag_field_name, &schema)?;
         }
 
+        // Resolve Bloom filter fields
+        for bloom_filter_field_name in &doc_mapping.bloom_filter_fields {
+            validate_bloom_filter_field(bloom_filter_field_name, &schema)?;
+        }
+
         let partition_key_expr: &str = doc_mapping.partition_key.as_deref().unwrap_or("");
         let partition_key = RoutingExpr::new(partition_key_expr).with_context(|| {
             format!("failed to interpret the partition key: `{partition_key_expr}`")
@@ -285,6 +322,7 @@ impl TryFrom<DefaultDocMapperBuilder> for DefaultDocMapper {
                 tag_field_names.insert(partition_key);
             }
         }
+        let token_count_fields = field_mappings.token_count_fields();
         Ok(DefaultDocMapper {
             doc_mapping_uid: doc_mapping.doc_mapping_uid,
             schema,
@@ -293,20 +331,42 @@ impl TryFrom<DefaultDocMapperBuilder> for DefaultDocMapper {
             dynamic_field,
             document_size_field,
             default_search_field_names,
+            allow_leading_wildcard: builder.allow_leading_wildcard,
             timestamp_field_name: doc_mapping.timestamp_field,
             timestamp_field_path,
             field_mappings,
             concatenate_dynamic_fields,
             tag_field_names,
+            bloom_filter_field_names: doc_mapping.bloom_filter_fields,
             partition_key,
             max_num_partitions: doc_mapping.max_num_partitions,
             mode: doc_mapping.mode,
             tokenizer_entries: doc_mapping.tokenizers,
             tokenizer_manager,
+            field_aliases: doc_mapping.field_aliases,
+            token_count_fields,
         })
     }
 }
 
+/// Checks that a field alias does not shadow a field of the schema and does not target another
+/// alias.
+fn validate_field_alias(
+    alias: &str,
+    target_field_name: &str,
+    field_aliases: &BTreeMap<String, String>,
+    schema: &Schema,
+) -> anyhow::Result<()> {
+    validate_field_mapping_name(alias).with_context(|| format!("invalid field alias `{alias}`"))?;
+    if schema.get_field(alias).is_ok() {
+        bail!("field alias `{alias}` conflicts with an existing field");
+    }
+    if field_aliases.contains_key(target_field_name) {
+        bail!("field alias `{alias}` cannot target another field alias `{target_field_name}`");
+    }
+    Ok(())
+}
+
 /// Checks that a given field name is a valid candidate for a tag.
 ///
 /// The conditions are:
@@ -359,6 +419,30 @@ fn validate_tag(tag_field_name: &str, schema: &Schema) -> Result<(), anyhow::Err
     Ok(())
 }
 
+fn validate_bloom_filter_field(
+    bloom_filter_field_name: &str,
+    schema: &Schema,
+) -> Result<(), anyhow::Error> {
+    let field = schema
+        .get_field(bloom_filter_field_name)
+        .with_context(|| format!("unknown Bloom filter field: `{bloom_filter_field_name}`"))?;
+    let FieldType::Str(options) = schema.get_field_entry(field).field_type() else {
+        bail!("Bloom filters are only allowed on text fields (`{bloom_filter_field_name}`)");
+    };
+    // The Bloom filters are built over the terms of the field, and probed with the searched
+    // values: both must be equal, which is only guaranteed with the `raw` tokenizer.
+    let tokenizer_opt = options
+        .get_indexing_options()
+        .map(|text_options: &tantivy::schema::TextFieldIndexing| text_options.tokenizer());
+    if tokenizer_opt != Some(RAW_TOKENIZER_NAME) {
+        bail!(
+            "Bloom filters are only allowed on indexed text fields with the `raw` tokenizer \
+             (`{bloom_filter_field_name}`)"
+        );
+    }
+    Ok(())
+}
+
 /// Checks that a given text/json field name has a registered tokenizer.
 fn validate_fields_tokenizers(
     schema: &Schema,
@@ -452,60 +536,121 @@ fn tantivy_value_to_json(val: TantivyValue) -> JsonValue {
     }
 }
 
+/// Records the presence hashes of a JSON value. Returns true if the value holds at least one
+/// non-null leaf.
+///
+/// Leaf paths are only recorded if `include_leaves` is true. Fast fields do not need them as
+/// tantivy's `ExistsQuery` relies on the column index instead.
 #[inline]
 fn populate_field_presence_for_json_value<'a>(
     json_value: impl Value<'a>,
     path_hasher: &PathHasher,
     is_expand_dots_enabled: bool,
+    include_leaves: bool,
     output: &mut FnvHashSet<u64>,
-) {
+) -> bool {
     match json_value.as_value() {
-        ReferenceValue::Leaf(ReferenceValueLeaf::Null) => {}
+        ReferenceValue::Leaf(ReferenceValueLeaf::Null) => false,
         ReferenceValue::Leaf(_) => {
-            output.insert(path_hasher.finish());
+            if include_leaves {
+                output.insert(path_hasher.finish());
+            }
+            true
         }
         ReferenceValue::Array(items) => {
+            let mut has_value = false;
             for item in items {
-                populate_field_presence_for_json_value(
+                has_value |= populate_field_presence_for_json_value(
                     item,
                     path_hasher,
                     is_expand_dots_enabled,
+                    include_leaves,
                     output,
                 );
             }
+            has_value
         }
-        ReferenceValue::Object(json_obj) => {
-            populate_field_presence_for_json_obj(
-                json_obj,
-                path_hasher.clone(),
-                is_expand_dots_enabled,
-                output,
-            );
-        }
+        ReferenceValue::Object(json_obj) => populate_field_presence_for_json_obj(
+            json_obj,
+            path_hasher.clone(),
+            is_expand_dots_enabled,
+            include_leaves,
+            output,
+        ),
     }
 }
 
+/// Records the presence hashes of a JSON object, including the hash of the object itself and of
+/// its nested objects, so that `exists` queries also match intermediate paths such as
+/// `attributes.http`. Objects that only hold null values or empty arrays are not recorded.
 fn populate_field_presence_for_json_obj<'a, Iter: Iterator<Item = (&'a str, impl Value<'a>)>>(
     json_obj: Iter,
     path_hasher: PathHasher,
     is_expand_dots_enabled: bool,
+    include_leaves: bool,
     output: &mut FnvHashSet<u64>,
-) {
+) -> bool {
+    let mut has_value = false;
     for (field_key, field_value) in json_obj {
         let mut child_path_hasher = path_hasher.clone();
+        // With expand dots, the key `a.b` also implies the presence of the object `a`.
+        let mut intermediate_path_hashes: Vec<u64> = Vec::new();
         if is_expand_dots_enabled {
-            for segment in field_key.split('.') {
+            let mut segments = field_key.split('.').peekable();
+            while let Some(segment) = segments.next() {
                 child_path_hasher.append(segment.as_bytes());
+                if segments.peek().is_some() {
+                    intermediate_path_hashes.push(child_path_hasher.finish());
+                }
             }
         } else {
             child_path_hasher.append(field_key.as_bytes());
         };
-        populate_field_presence_for_json_value(
+        let child_has_value = populate_field_presence_for_json_value(
             field_value,
             &child_path_hasher,
             is_expand_dots_enabled,
+            include_leaves,
             output,
         );
+        if child_has_value {
+            output.extend(intermediate_path_hashes);
+            has_value = true;
+        }
+    }
+    if has_value {
+        output.insert(path_hasher.finish());
+    }
+    has_value
+}
+
+/// Records the number of tokens of each value of the text fields configured with `token_count`.
+fn add_token_counts(
+    token_count_fields: &[TokenCountField],
+    tokenizer_manager: &TokenizerManager,
+    document: &mut Document,
+) {
+    for token_count_field in token_count_fields {
+        let Some(mut tokenizer) =
+            tokenizer_manager.get_tokenizer(&token_count_field.tokenizer_name)
+        else {
+            continue;
+        };
+        let token_counts: Vec<u64> = document
+            .get_all(token_count_field.text_field)
+            .filter_map(|value| value.as_str())
+            .map(|text| {
+                let mut token_stream = tokenizer.token_stream(text);
+                let mut num_tokens = 0;
+                while token_stream.advance() {
+                    num_tokens += 1;
+                }
+                num_tokens
+            })
+            .collect();
+        for token_count in token_counts {
+            document.add_u64(token_count_field.token_count_field, token_count);
+        }
     }
 }
 
@@ -516,10 +661,17 @@ impl DocMapper for DefaultDocMapper {
     }
 
     fn validate_json_obj(&self, json_obj: &BorrowedJsonMap) -> Result<(), DocParsingError> {
-        let is_strict = self.mode.mode_type() == ModeType::Strict;
         let mut field_path = Vec::new();
-        self.field_mappings
-            .validate_from_json(json_obj, is_strict, &mut field_path)?;
+        let mut unmapped_field_paths = Vec::new();
+        self.field_mappings.validate_from_json(
+            json_obj,
+            self.mode.mode_type(),
+            &mut field_path,
+            &mut unmapped_field_paths,
+        )?;
+        if !unmapped_field_paths.is_empty() {
+            return Err(DocParsingError::NoSuchFieldsInSchema(unmapped_field_paths));
+        }
         if let Some(timestamp_field_path) = &self.timestamp_field_path {
             let missing_ts_field =
                 || DocParsingError::RequiredField("timestamp field is required".to_string());
@@ -572,13 +724,23 @@ impl DocMapper for DefaultDocMapper {
         }
 
         let mode = self.mode.mode_type();
+        let mut unmapped_field_paths = Vec::new();
         self.field_mappings.doc_from_json(
             json_obj,
             mode,
             &mut document,
             &mut field_path,
             &mut dynamic_json_obj,
+            &mut unmapped_field_paths,
         )?;
+        if !unmapped_field_paths.is_empty() {
+            return Err(DocParsingError::NoSuchFieldsInSchema(unmapped_field_paths));
+        }
+        add_token_counts(
+            &self.token_count_fields,
+            &self.tokenizer_manager,
+            &mut document,
+        );
 
         if let Some(dynamic_field) = self.dynamic_field {
             if !dynamic_json_obj.is_empty() {
@@ -614,26 +776,26 @@ impl DocMapper for DefaultDocMapper {
                 FnvHashSet::with_capacity_and_hasher(document.len(), Default::default());
             for (field, value) in document.field_values() {
                 let field_entry = self.schema.get_field_entry(field);
-                if !field_entry.is_indexed() || field_entry.is_fast() {
-                    // We are using an tantivy's ExistsQuery for fast fields.
-                    continue;
-                }
                 let mut path_hasher: PathHasher = PathHasher::default();
                 path_hasher.append(&field.field_id().to_le_bytes()[..]);
-                if let Some(json_obj) = value.as_object() {
-                    let is_expand_dots_enabled: bool =
-                        if let FieldType::JsonObject(json_options) = field_entry.field_type() {
-                            json_options.is_expand_dots_enabled()
-                        } else {
-                            false
-                        };
+                if let FieldType::JsonObject(json_options) = field_entry.field_type() {
+                    let Some(json_obj) = value.as_object() else {
+                        continue;
+                    };
+                    if !field_entry.is_indexed() && !field_entry.is_fast() {
+                        continue;
+                    }
+                    // We are using tantivy's ExistsQuery for the leaves of fast fields, but
+                    // objects have no column, so their presence is always recorded.
                     populate_field_presence_for_json_obj(
                         json_obj,
                         path_hasher,
-                        is_expand_dots_enabled,
+                        json_options.is_expand_dots_enabled(),
+                        !field_entry.is_fast(),
                         &mut field_presence_hashes,
                     );
-                } else {
+                } else if field_entry.is_indexed() && !field_entry.is_fast() {
+                    // We are using tantivy's ExistsQuery for fast fields.
                     field_presence_hashes.insert(path_hasher.finish());
                 }
             }
@@ -709,8 +871,12 @@ impl DocMapper for DefaultDocMapper {
         query_ast: &QueryAst,
         with_validation: bool,
     ) -> Result<(Box<dyn Query>, WarmupInfo), QueryParserError> {
+        let mut resolved_query_ast = resolve_field_aliases(query_ast.clone(), &self.field_aliases);
+        if self.allow_leading_wildcard {
+            resolved_query_ast = enable_leading_wildcards(resolved_query_ast);
+        }
         build_query(
-            query_ast,
+            &resolved_query_ast,
             split_schema,
             self.tokenizer_manager(),
             &self.default_search_field_names[..],
@@ -734,6 +900,10 @@ impl DocMapper for DefaultDocMapper {
         self.tag_field_names.clone()
     }
 
+    fn bloom_filter_field_names(&self) -> BTreeSet<String> {
+        self.bloom_filter_field_names.clone()
+    }
+
     fn max_num_partitions(&self) -> NonZeroU32 {
         self.max_num_partitions
     }
@@ -745,7 +915,7 @@ impl DocMapper for DefaultDocMapper {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::{HashMap, HashSet};
+    use std::collections::{BTreeSet, HashMap, HashSet};
     use std::iter::zip;
 
     use itertools::Itertools;
@@ -821,8 +991,8 @@ mod tests {
         let schema = doc_mapper.schema();
         // 9 property entry + 1 field "_source" + 2 fields values for "tags" field
         // + 2 values inf "server.status" field + 2 values in "server.payload" field
-        // + 7 values for field presence
-        assert_eq!(document.len(), 23);
+        // + 8 values for field presence
+        assert_eq!(document.len(), 24);
         let expected_json_paths_and_values: HashMap<String, JsonValue> =
             serde_json::from_str(EXPECTED_JSON_PATHS_AND_VALUES).unwrap();
         let mut field_presences: HashSet<u64> = HashSet::new();
@@ -867,8 +1037,15 @@ mod tests {
                 }
             }
         }
-        assert_eq!(field_presences.len(), 7);
+        assert_eq!(field_presences.len(), 8);
         let timestamp_field = schema.get_field("timestamp").unwrap();
+        let dynamic_field = schema.get_field(DYNAMIC_FIELD_NAME).unwrap();
+        // The dynamic field is fast: only its object paths are recorded.
+        assert!(
+            field_presences.contains(&PathHasher::hash_path(&[&dynamic_field
+                .field_id()
+                .to_le_bytes()[..]]))
+        );
         let body_field = schema.get_field("body").unwrap();
         let attributes_field = schema.get_field("attributes.server").unwrap();
         assert!(
@@ -1358,6 +1535,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_field_presence_for_nested_json_paths() {
+        let doc_mapper = r#"{
+            "index_field_presence": true,
+            "field_mappings": [
+                {
+                    "name": "attributes",
+                    "type": "json",
+                    "fast": false
+                },
+                {
+                    "name": "fast_attributes",
+                    "type": "json",
+                    "fast": true
+                }
+            ]
+        }"#;
+        let doc_mapper = serde_json::from_str::<DefaultDocMapper>(doc_mapper).unwrap();
+        let schema = doc_mapper.schema();
+        let json_doc = serde_json::json!({
+            "attributes": {
+                "http": {"status_code": 200, "headers": [{"name": "host"}]},
+                "tls.version": "1.3",
+                "empty": {"value": null, "items": []}
+            },
+            "fast_attributes": {
+                "http": {"status_code": 200}
+            }
+        });
+        let (_, document) = doc_mapper
+            .doc_from_json_obj(json_doc.as_object().unwrap().clone(), 0)
+            .unwrap();
+        let field_presences: HashSet<u64> = document
+            .get_all(schema.get_field(FIELD_PRESENCE_FIELD_NAME).unwrap())
+            .map(|value| value.as_u64().unwrap())
+            .collect();
+
+        let attributes_field_id = schema.get_field("attributes").unwrap().field_id();
+        let attributes_hash = |path: &[&[u8]]| -> u64 {
+            let mut full_path: Vec<&[u8]> = vec![&attributes_field_id.to_le_bytes()[..]];
+            full_path.extend_from_slice(path);
+            PathHasher::hash_path(&full_path)
+        };
+        let fast_attributes_field_id = schema.get_field("fast_attributes").unwrap().field_id();
+        let fast_attributes_hash = |path: &[&[u8]]| -> u64 {
+            let mut full_path: Vec<&[u8]> = vec![&fast_attributes_field_id.to_le_bytes()[..]];
+            full_path.extend_from_slice(path);
+            PathHasher::hash_path(&full_path)
+        };
+        let expected_field_presences: HashSet<u64> = [
+            attributes_hash(&[]),
+            attributes_hash(&[b"http"]),
+            attributes_hash(&[b"http", b"status_code"]),
+            attributes_hash(&[b"http", b"headers"]),
+            attributes_hash(&[b"http", b"headers", b"name"]),
+            attributes_hash(&[b"tls"]),
+            attributes_hash(&[b"tls", b"version"]),
+            // Leaves of fast fields are not recorded.
+            fast_attributes_hash(&[]),
+            fast_attributes_hash(&[b"http"]),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(field_presences, expected_field_presences);
+    }
+
     #[test]
     fn test_partition_key_in_tags() {
         let doc_mapper = r#"{
@@ -1505,6 +1748,56 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_build_doc_mapper_with_bloom_filter_fields() -> anyhow::Result<()> {
+        let doc_mapper = r#"{
+            "default_search_fields": [],
+            "bloom_filter_fields": ["trace_id"],
+            "field_mappings": [
+                {
+                    "name": "trace_id",
+                    "type": "text",
+                    "tokenizer": "raw"
+                },
+                {
+                    "name": "message",
+                    "type": "text"
+                },
+                {
+                    "name": "status",
+                    "type": "u64"
+                }
+            ]
+        }"#;
+        let doc_mapper = serde_json::from_str::<DefaultDocMapper>(doc_mapper)?;
+        assert_eq!(
+            doc_mapper.bloom_filter_field_names(),
+            BTreeSet::from_iter(["trace_id".to_string()])
+        );
+        let bloom_filter_named_fields = doc_mapper.bloom_filter_named_fields()?;
+        assert_eq!(bloom_filter_named_fields.len(), 1);
+        assert_eq!(bloom_filter_named_fields[0].name, "trace_id");
+
+        let mut builder = DefaultDocMapperBuilder::from(doc_mapper);
+        builder.doc_mapping.bloom_filter_fields = BTreeSet::from_iter(["message".to_string()]);
+        assert_eq!(
+            builder.clone().try_build().unwrap_err().to_string(),
+            "Bloom filters are only allowed on indexed text fields with the `raw` tokenizer \
+             (`message`)"
+        );
+        builder.doc_mapping.bloom_filter_fields = BTreeSet::from_iter(["status".to_string()]);
+        assert_eq!(
+            builder.clone().try_build().unwrap_err().to_string(),
+            "Bloom filters are only allowed on text fields (`status`)"
+        );
+        builder.doc_mapping.bloom_filter_fields = BTreeSet::from_iter(["request_id".to_string()]);
+        assert_eq!(
+            builder.try_build().unwrap_err().to_string(),
+            "unknown Bloom filter field: `request_id`"
+        );
+        Ok(())
+    }
+
     // See #1132
     #[test]
     fn test_by_default_store_source_is_false_and_fields_are_stored_individually() {
@@ -1617,6 +1910,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_strict_verbose_mode() {
+        let default_doc_mapper: DefaultDocMapper = serde_json::from_str(
+            r#"{
+            "field_mappings": [
+                {
+                    "name": "some_obj",
+                    "type": "object",
+                    "field_mappings": [
+                        {
+                            "name": "child_a",
+                            "type": "text"
+                        }
+                    ]
+                }
+            ],
+            "mode": "strict_verbose"
+        }"#,
+        )
+        .unwrap();
+        assert!(default_doc_mapper
+            .doc_from_json_str(r#"{ "some_obj": { "child_a": "hello" } }"#)
+            .is_ok());
+        let parsing_err = default_doc_mapper
+            .doc_from_json_str(
+                r#"{ "a": { "b": 5 }, "some_obj": { "child_a": "hello", "child_b": 6 } }"#,
+            )
+            .err()
+            .unwrap();
+        assert!(matches!(
+            &parsing_err,
+            DocParsingError::NoSuchFieldsInSchema(field_paths) if field_paths == &["a", "some_obj.child_b"]
+        ));
+        assert_eq!(
+            parsing_err.to_string(),
+            "the document contains fields that are not declared in the schema: [\"a\", \
+             \"some_obj.child_b\"]"
+        );
+    }
+
     #[test]
     fn test_lenient_mode_simple() {
         let default_doc_mapper: DefaultDocMapper =
@@ -1813,6 +2146,205 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_text_token_count_in_mapping() {
+        test_doc_from_json_test_aux(
+            r#"{
+                "field_mappings": [
+                    {
+                        "name": "message",
+                        "type": "array<text>",
+                        "tokenizer": "default",
+                        "token_count": true
+                    }
+                ],
+                "mode": "strict"
+            }"#,
+            "message.token_count",
+            r#"{"message": ["Hello big World", "Bye"]}"#,
+            vec![3_u64.into(), 1_u64.into()],
+        );
+        let error = serde_json::from_str::<DefaultDocMapper>(
+            r#"{
+                "field_mappings": [
+                    {
+                        "name": "message",
+                        "type": "text",
+                        "indexed": false,
+                        "token_count": true
+                    }
+                ]
+            }"#,
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`token_count` requires the text field to be indexed"));
+    }
+
+    #[test]
+    fn test_text_sub_fields_in_mapping() {
+        let doc_mapper_json = r#"{
+            "field_mappings": [
+                {
+                    "name": "message",
+                    "type": "array<text>",
+                    "fields": [
+                        {
+                            "name": "raw",
+                            "type": "text",
+                            "tokenizer": "raw",
+                            "fast": true
+                        }
+                    ]
+                }
+            ],
+            "mode": "strict"
+        }"#;
+        let document_json = r#"{"message": ["Hello World", "Bye"]}"#;
+        test_doc_from_json_test_aux(
+            doc_mapper_json,
+            "message",
+            document_json,
+            vec!["Hello World".into(), "Bye".into()],
+        );
+        test_doc_from_json_test_aux(
+            doc_mapper_json,
+            "message.raw",
+            document_json,
+            vec!["Hello World".into(), "Bye".into()],
+        );
+        let doc_mapper: DefaultDocMapper = serde_json::from_str(doc_mapper_json).unwrap();
+        let schema = doc_mapper.schema();
+        let sub_field_entry = schema.get_field_entry(schema.get_field("message.raw").unwrap());
+        assert!(!sub_field_entry.is_stored());
+        assert!(sub_field_entry.is_fast());
+
+        // Sub-fields are not part of the document returned in search results.
+        let (_, doc) = doc_mapper.doc_from_json_str(document_json).unwrap();
+        let named_doc = doc.to_named_doc(&schema);
+        let json_doc = doc_mapper.doc_to_json(named_doc.0).unwrap();
+        assert_eq!(
+            JsonValue::Object(json_doc),
+            json!({"message": ["Hello World", "Bye"]})
+        );
+    }
+
+    #[test]
+    fn test_completion_field_in_mapping() {
+        let doc_mapper_json = r#"{
+            "field_mappings": [
+                {
+                    "name": "suggest",
+                    "type": "completion"
+                }
+            ],
+            "mode": "strict"
+        }"#;
+        test_doc_from_json_test_aux(
+            doc_mapper_json,
+            "suggest",
+            r#"{"suggest": "Nirvana"}"#,
+            vec!["Nirvana".into()],
+        );
+        test_doc_from_json_test_aux(
+            doc_mapper_json,
+            "suggest",
+            r#"{"suggest": ["Nevermind", "Nirvana"]}"#,
+            vec!["Nevermind".into(), "Nirvana".into()],
+        );
+        let doc_mapper: DefaultDocMapper = serde_json::from_str(doc_mapper_json).unwrap();
+        let schema = doc_mapper.schema();
+        let field_entry = schema.get_field_entry(schema.get_field("suggest").unwrap());
+        let FieldType::Str(text_options) = field_entry.field_type() else {
+            panic!("expected a text field");
+        };
+        assert_eq!(
+            text_options.get_indexing_options().unwrap().tokenizer(),
+            "raw_lowercase"
+        );
+        // The completion field mapping survives a serialization round trip.
+        let doc_mapper_ser = serde_json::to_value(&doc_mapper).unwrap();
+        assert_eq!(
+            doc_mapper_ser["field_mappings"][0],
+            json!({"name": "suggest", "type": "completion", "stored": true})
+        );
+    }
+
+    #[test]
+    fn test_copy_to_concatenate_field_in_mapping() {
+        test_doc_from_json_test_aux(
+            r#"{
+                "field_mappings": [
+                    {
+                        "name": "some_text",
+                        "type": "text",
+                        "copy_to": ["concat"]
+                    },
+                    {
+                        "name": "some_obj",
+                        "type": "object",
+                        "field_mappings": [
+                            {
+                                "name": "some_int",
+                                "type": "u64",
+                                "copy_to": ["concat"]
+                            }
+                        ]
+                    },
+                    {
+                        "name": "concat",
+                        "type": "concatenate"
+                    }
+                ],
+                "mode": "strict"
+            }"#,
+            "concat",
+            r#"{"some_obj": {"some_int": 25}, "some_text": "this is a text"}"#,
+            vec![25_u64.into(), "this is a text".into()],
+        );
+        // `copy_to` and `concatenate_fields` targeting the same field don't duplicate values.
+        test_doc_from_json_test_aux(
+            r#"{
+                "field_mappings": [
+                    {
+                        "name": "some_text",
+                        "type": "text",
+                        "copy_to": ["concat"]
+                    },
+                    {
+                        "name": "concat",
+                        "type": "concatenate",
+                        "concatenate_fields": ["some_text"]
+                    }
+                ],
+                "mode": "strict"
+            }"#,
+            "concat",
+            r#"{"some_text": "this is a text"}"#,
+            vec!["this is a text".into()],
+        );
+    }
+
+    #[test]
+    fn test_copy_to_unknown_concatenate_field() {
+        let error = serde_json::from_str::<DefaultDocMapper>(
+            r#"{
+                "field_mappings": [
+                    {
+                        "name": "some_text",
+                        "type": "text",
+                        "copy_to": ["concat"]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`copy_to` target `concat` is not a concatenate field of the doc mapping"));
+    }
+
     #[test]
     fn test_concatenate_field_in_mapping_dynamic() {
         test_doc_from_json_test_aux(
@@ -1847,6 +2379,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_concatenate_field_in_mapping_with_patterns() {
+        test_doc_from_json_test_aux(
+            r#"{
+                "field_mappings": [
+                    {
+                        "name": "attributes",
+                        "type": "object",
+                        "field_mappings": [
+                            {"name": "color", "type": "text"},
+                            {"name": "count", "type": "u64"},
+                            {"name": "ip", "type": "ip"},
+                            {"name": "password", "type": "text"}
+                        ]
+                    },
+                    {
+                        "name": "message",
+                        "type": "text"
+                    },
+                    {
+                        "name": "concat",
+                        "type": "concatenate",
+                        "concatenate_fields": ["message", "attributes.*"],
+                        "exclude_fields": ["*.password"]
+                    }
+                ],
+                "mode": "strict"
+            }"#,
+            "concat",
+            r#"{
+                "attributes": {"color": "red", "count": 3, "ip": "127.0.0.1", "password": "1234"},
+                "message": "hello"
+            }"#,
+            vec!["red".into(), 3_u64.into(), "hello".into()],
+        );
+    }
+
     #[test]
     fn test_concatenate_field_in_mapping_integer() {
         test_doc_from_json_test_aux(
@@ -2166,6 +2735,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_doc_mapper_field_aliases() {
+        let doc_mapper: DefaultDocMapper = serde_json::from_str(
+            r#"{
+            "field_mappings": [
+                {
+                    "name": "host",
+                    "type": "object",
+                    "field_mappings": [{"type": "text", "name": "name", "tokenizer": "raw"}]
+                }
+            ],
+            "field_aliases": {"hostname": "host.name"},
+            "default_search_fields": ["hostname"],
+            "mode": "strict"
+        }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            default_doc_mapper_query_aux(&doc_mapper, "hostname:toto").unwrap(),
+            r#"TermQuery(Term(field=1, type=Str, "toto"))"#
+        );
+        assert_eq!(
+            default_doc_mapper_query_aux(&doc_mapper, "toto").unwrap(),
+            r#"TermQuery(Term(field=1, type=Str, "toto"))"#
+        );
+        assert_eq!(
+            default_doc_mapper_query_aux(&doc_mapper, "host.name:toto").unwrap(),
+            r#"TermQuery(Term(field=1, type=Str, "toto"))"#
+        );
+    }
+
+    #[test]
+    fn test_doc_mapper_invalid_field_aliases() {
+        let error = serde_json::from_str::<DefaultDocMapper>(
+            r#"{
+            "field_mappings": [{"type": "text", "name": "body"}],
+            "field_aliases": {"message": "msg"},
+            "mode": "strict"
+        }"#,
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("unknown target field `msg` for field alias `message`"));
+
+        let error = serde_json::from_str::<DefaultDocMapper>(
+            r#"{
+            "field_mappings": [{"type": "text", "name": "body"}],
+            "field_aliases": {"body": "body"}
+        }"#,
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("field alias `body` conflicts with an existing field"));
+
+        let error = serde_json::from_str::<DefaultDocMapper>(
+            r#"{
+            "field_mappings": [{"type": "text", "name": "body"}],
+            "field_aliases": {"message": "body", "msg": "message"}
+        }"#,
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("field alias `msg` cannot target another field alias `message`"));
+    }
+
     #[test]
     fn test_doc_mapper_object_dot_collision_with_json_field() {
         let doc_mapper: DefaultDocMapper = serde_json::from_str(
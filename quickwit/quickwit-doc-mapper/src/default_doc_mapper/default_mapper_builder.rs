@@ -17,6 +17,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use quickwit_common::is_false;
 use serde::{Deserialize, Serialize};
 
 use crate::{DefaultDocMapper, DocMapping};
@@ -36,6 +37,9 @@ pub struct DefaultDocMapperBuilder {
     /// Default search field names.
     #[serde(default)]
     pub default_search_fields: Vec<String>,
+    /// Allows wildcard queries starting with a wildcard.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub allow_leading_wildcard: bool,
 }
 
 #[cfg(test)]
@@ -22,6 +22,7 @@ use std::convert::TryFrom;
 
 use anyhow::bail;
 use base64::prelude::{Engine, BASE64_STANDARD};
+use quickwit_common::is_false;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use tantivy::schema::{
@@ -99,6 +100,10 @@ pub struct QuickwitNumericOptions {
     pub coerce: bool,
     #[serde(default)]
     pub output_format: NumericOutputFormat,
+    /// Concatenate fields the value of this field is copied to.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub copy_to: Vec<String>,
 }
 
 impl Default for QuickwitNumericOptions {
@@ -110,6 +115,7 @@ impl Default for QuickwitNumericOptions {
             fast: false,
             coerce: true,
             output_format: NumericOutputFormat::default(),
+            copy_to: Vec::new(),
         }
     }
 }
@@ -126,6 +132,10 @@ pub struct QuickwitBoolOptions {
     pub indexed: bool,
     #[serde(default)]
     pub fast: bool,
+    /// Concatenate fields the value of this field is copied to.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub copy_to: Vec<String>,
 }
 
 impl Default for QuickwitBoolOptions {
@@ -135,6 +145,7 @@ impl Default for QuickwitBoolOptions {
             indexed: true,
             stored: true,
             fast: false,
+            copy_to: Vec::new(),
         }
     }
 }
@@ -275,6 +286,12 @@ pub(crate) const DEFAULT_TOKENIZER_NAME: &str = "default";
 
 pub(crate) const RAW_TOKENIZER_NAME: &str = "raw";
 
+/// Tokenizer of the completion fields: each value is indexed as a single lowercased term.
+pub(crate) const COMPLETION_TOKENIZER_NAME: &str = "raw_lowercase";
+
+/// Name of the sub-field recording the number of tokens of a text field.
+pub(crate) const TOKEN_COUNT_SUB_FIELD_NAME: &str = "token_count";
+
 impl Default for QuickwitTextTokenizer {
     fn default() -> Self {
         Self::from_static(DEFAULT_TOKENIZER_NAME)
@@ -455,6 +472,21 @@ pub struct QuickwitTextOptions {
     pub stored: bool,
     #[serde(default)]
     pub fast: FastFieldOptions,
+    /// Concatenate fields the value of this field is copied to.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub copy_to: Vec<String>,
+    /// Text sub-fields indexing the value of this field with a different configuration.
+    /// A sub-field `raw` of the field `message` is exposed as `message.raw`.
+    #[schema(value_type = Vec<Object>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldMappingEntry>,
+    /// If true, the number of tokens of the field values is indexed in the fast field
+    /// `<field_name>.token_count`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub token_count: bool,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
@@ -520,6 +552,9 @@ impl Default for QuickwitTextOptions {
             indexing_options: Some(TextIndexingOptions::default()),
             stored: true,
             fast: FastFieldOptions::default(),
+            copy_to: Vec::new(),
+            fields: Vec::new(),
+            token_count: false,
         }
     }
 }
@@ -670,6 +705,10 @@ pub struct QuickwitConcatenateOptions {
     /// Fields to concatenate
     #[serde(default)]
     pub concatenate_fields: Vec<String>,
+    /// Fields excluded from the concatenate field, even if they match `concatenate_fields`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude_fields: Vec<String>,
     #[serde(default)]
     pub include_dynamic_fields: bool,
     #[serde_multikey(
@@ -698,6 +737,7 @@ impl Default for QuickwitConcatenateOptions {
         QuickwitConcatenateOptions {
             description: None,
             concatenate_fields: Vec::new(),
+            exclude_fields: Vec::new(),
             include_dynamic_fields: false,
             indexing_options: TextIndexingOptions {
                 tokenizer: QuickwitTextTokenizer::default(),
@@ -721,6 +761,44 @@ impl From<QuickwitConcatenateOptions> for JsonObjectOptions {
     }
 }
 
+/// Options associated to a completion field.
+///
+/// The values of a completion field are indexed as a single lowercased term each, so that the
+/// values starting with a given prefix can be read directly from the term dictionary of a split.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QuickwitCompletionOptions {
+    /// Optional description of the completion field.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default = "default_as_true")]
+    pub stored: bool,
+}
+
+impl Default for QuickwitCompletionOptions {
+    fn default() -> Self {
+        QuickwitCompletionOptions {
+            description: None,
+            stored: true,
+        }
+    }
+}
+
+impl From<QuickwitCompletionOptions> for TextOptions {
+    fn from(quickwit_completion_options: QuickwitCompletionOptions) -> Self {
+        let mut text_options = TextOptions::default();
+        if quickwit_completion_options.stored {
+            text_options = text_options.set_stored();
+        }
+        let text_field_indexing = TextFieldIndexing::default()
+            .set_index_option(IndexRecordOption::Basic)
+            .set_fieldnorms(false)
+            .set_tokenizer(COMPLETION_TOKENIZER_NAME);
+        text_options.set_indexing_options(text_field_indexing)
+    }
+}
+
 fn deserialize_mapping_type(
     quickwit_field_type: QuickwitFieldType,
     json: JsonValue,
@@ -737,17 +815,38 @@ fn deserialize_mapping_type(
         }
         QuickwitFieldType::Concatenate => {
             let concatenate_options: QuickwitConcatenateOptions = serde_json::from_value(json)?;
-            if concatenate_options.concatenate_fields.is_empty()
-                && !concatenate_options.include_dynamic_fields
-            {
-                anyhow::bail!("concatenate type must have at least one sub-field");
-            }
             return Ok(FieldMappingType::Concatenate(concatenate_options));
         }
+        QuickwitFieldType::Completion => {
+            let completion_options: QuickwitCompletionOptions = serde_json::from_value(json)?;
+            return Ok(FieldMappingType::Completion(completion_options));
+        }
     };
     match typ {
         Type::Str => {
             let text_options: QuickwitTextOptions = serde_json::from_value(json)?;
+            if text_options.token_count && text_options.indexing_options.is_none() {
+                anyhow::bail!("`token_count` requires the text field to be indexed");
+            }
+            for sub_field in &text_options.fields {
+                if text_options.token_count && sub_field.name == TOKEN_COUNT_SUB_FIELD_NAME {
+                    anyhow::bail!(
+                        "sub-field `{TOKEN_COUNT_SUB_FIELD_NAME}` conflicts with the \
+                         `token_count` field"
+                    );
+                }
+                let FieldMappingType::Text(sub_field_options, Cardinality::SingleValued) =
+                    &sub_field.mapping_type
+                else {
+                    anyhow::bail!(
+                        "sub-field `{}` must be of type `text`, only text sub-fields are supported",
+                        sub_field.name
+                    );
+                };
+                if !sub_field_options.fields.is_empty() {
+                    anyhow::bail!("sub-field `{}` cannot have sub-fields", sub_field.name);
+                }
+            }
             Ok(FieldMappingType::Text(text_options, cardinality))
         }
         Type::U64 => {
@@ -840,6 +939,7 @@ fn typed_mapping_to_json_params(
         FieldMappingType::Concatenate(concatenate_options) => {
             serialize_to_map(&concatenate_options)
         }
+        FieldMappingType::Completion(completion_options) => serialize_to_map(&completion_options),
     }
     .unwrap()
 }
@@ -1062,6 +1162,65 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_deserialize_text_mapping_entry_with_sub_fields() {
+        let mapping_entry = serde_json::from_str::<FieldMappingEntry>(
+            r#"
+        {
+            "name": "message",
+            "type": "text",
+            "tokenizer": "default",
+            "fields": [
+                {
+                    "name": "raw",
+                    "type": "text",
+                    "tokenizer": "raw",
+                    "fast": true
+                }
+            ]
+        }
+        "#,
+        )
+        .unwrap();
+        let FieldMappingType::Text(options, _) = mapping_entry.mapping_type else {
+            panic!("wrong property type");
+        };
+        assert_eq!(options.fields.len(), 1);
+        assert_eq!(options.fields[0].name, "raw");
+        let FieldMappingType::Text(sub_field_options, _) = &options.fields[0].mapping_type else {
+            panic!("wrong property type");
+        };
+        assert_eq!(
+            sub_field_options
+                .indexing_options
+                .as_ref()
+                .unwrap()
+                .tokenizer
+                .name(),
+            "raw"
+        );
+        assert_eq!(sub_field_options.fast, FastFieldOptions::default_enabled());
+
+        let error = serde_json::from_str::<FieldMappingEntry>(
+            r#"
+        {
+            "name": "message",
+            "type": "text",
+            "fields": [
+                {
+                    "name": "length",
+                    "type": "u64"
+                }
+            ]
+        }
+        "#,
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("sub-field `length` must be of type `text`"));
+    }
+
     #[test]
     fn test_deserialize_valid_fieldnorms() -> anyhow::Result<()> {
         let result = serde_json::from_str::<FieldMappingEntry>(
@@ -1675,6 +1834,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_completion_mapping() {
+        let entry = serde_json::from_str::<FieldMappingEntry>(
+            r#"
+            {
+                "name": "my_field_name",
+                "type": "completion",
+                "stored": false
+            }
+            "#,
+        )
+        .unwrap();
+        let FieldMappingType::Completion(completion_options) = &entry.mapping_type else {
+            panic!("expected a completion field mapping");
+        };
+        let tantivy_text_options = TextOptions::from(completion_options.clone());
+        assert!(!tantivy_text_options.is_stored());
+        let text_field_indexing = tantivy_text_options.get_indexing_options().unwrap();
+        assert_eq!(text_field_indexing.tokenizer(), "raw_lowercase");
+        assert_eq!(text_field_indexing.index_option(), IndexRecordOption::Basic);
+
+        let entry_deserser = serde_json::to_value(&entry).unwrap();
+        assert_eq!(
+            entry_deserser,
+            json!({
+                "name": "my_field_name",
+                "type": "completion",
+                "stored": false,
+            })
+        );
+
+        let error = serde_json::from_str::<FieldMappingEntry>(
+            r#"
+            {
+                "name": "my_field_name",
+                "type": "completion",
+                "tokenizer": "default"
+            }
+            "#,
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("error while parsing field `my_field_name`: unknown field `tokenizer`"));
+    }
+
     #[test]
     fn test_parse_json_mapping_singlevalue() {
         let field_mapping_entry = serde_json::from_str::<FieldMappingEntry>(
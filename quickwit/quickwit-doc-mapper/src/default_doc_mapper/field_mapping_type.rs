@@ -22,8 +22,9 @@ use tantivy::schema::Type;
 use super::date_time_type::QuickwitDateTimeOptions;
 use super::field_mapping_entry::QuickwitBoolOptions;
 use crate::default_doc_mapper::field_mapping_entry::{
-    QuickwitBytesOptions, QuickwitConcatenateOptions, QuickwitIpAddrOptions, QuickwitJsonOptions,
-    QuickwitNumericOptions, QuickwitObjectOptions, QuickwitTextOptions,
+    QuickwitBytesOptions, QuickwitCompletionOptions, QuickwitConcatenateOptions,
+    QuickwitIpAddrOptions, QuickwitJsonOptions, QuickwitNumericOptions, QuickwitObjectOptions,
+    QuickwitTextOptions,
 };
 use crate::Cardinality;
 
@@ -53,6 +54,8 @@ pub enum FieldMappingType {
     Object(QuickwitObjectOptions),
     /// Concatenate field mapping type configuration.
     Concatenate(QuickwitConcatenateOptions),
+    /// Completion field mapping type configuration.
+    Completion(QuickwitCompletionOptions),
 }
 
 impl FieldMappingType {
@@ -72,6 +75,7 @@ impl FieldMappingType {
                 return QuickwitFieldType::Object;
             }
             FieldMappingType::Concatenate(_) => return QuickwitFieldType::Concatenate,
+            FieldMappingType::Completion(_) => return QuickwitFieldType::Completion,
         };
         match cardinality {
             Cardinality::SingleValued => QuickwitFieldType::Simple(primitive_type),
@@ -85,6 +89,7 @@ pub enum QuickwitFieldType {
     Simple(Type),
     Object,
     Concatenate,
+    Completion,
     Array(Type),
 }
 
@@ -95,6 +100,7 @@ impl QuickwitFieldType {
             QuickwitFieldType::Object => "object".to_string(),
             QuickwitFieldType::Array(typ) => format!("array<{}>", primitive_type_to_str(typ)),
             QuickwitFieldType::Concatenate => "concatenate".to_string(),
+            QuickwitFieldType::Completion => "completion".to_string(),
         }
     }
 
@@ -105,6 +111,9 @@ impl QuickwitFieldType {
         if type_str == "concatenate" {
             return Some(QuickwitFieldType::Concatenate);
         }
+        if type_str == "completion" {
+            return Some(QuickwitFieldType::Completion);
+        }
         if type_str.starts_with("array<") && type_str.ends_with('>') {
             let parsed_type_str = parse_primitive_type(&type_str[6..type_str.len() - 1])?;
             return Some(QuickwitFieldType::Array(parsed_type_str));
@@ -168,5 +177,7 @@ mod tests {
         test_parse_type_aux("object2", None);
         test_parse_type_aux("bool", Some(QuickwitFieldType::Simple(Type::Bool)));
         test_parse_type_aux("ip", Some(QuickwitFieldType::Simple(Type::IpAddr)));
+        test_parse_type_aux("completion", Some(QuickwitFieldType::Completion));
+        test_parse_type_aux("array<completion>", None);
     }
 }
@@ -22,8 +22,9 @@ use std::collections::BTreeMap;
 use std::net::IpAddr;
 use std::str::FromStr;
 
-use anyhow::bail;
+use anyhow::{bail, Context};
 use itertools::Itertools;
+use regex::Regex;
 use serde_json::Value as JsonValue;
 use serde_json_borrow::{Map as BorrowedJsonMap, Value as BorrowedJsonValue};
 use tantivy::schema::{
@@ -33,10 +34,12 @@ use tantivy::schema::{
 use tantivy::TantivyDocument as Document;
 
 use super::date_time_type::QuickwitDateTimeOptions;
-use super::field_mapping_entry::{NumericOutputFormat, QuickwitBoolOptions};
+use super::field_mapping_entry::{
+    NumericOutputFormat, QuickwitBoolOptions, TOKEN_COUNT_SUB_FIELD_NAME,
+};
 use crate::default_doc_mapper::field_mapping_entry::{
-    QuickwitBytesOptions, QuickwitIpAddrOptions, QuickwitNumericOptions, QuickwitObjectOptions,
-    QuickwitTextOptions,
+    QuickwitBytesOptions, QuickwitCompletionOptions, QuickwitIpAddrOptions, QuickwitNumericOptions,
+    QuickwitObjectOptions, QuickwitTextOptions,
 };
 use crate::default_doc_mapper::{FieldMappingType, QuickwitJsonOptions};
 use crate::{Cardinality, DocParsingError, FieldMappingEntry, ModeType};
@@ -45,6 +48,7 @@ use crate::{Cardinality, DocParsingError, FieldMappingEntry, ModeType};
 pub enum LeafType {
     Bool(QuickwitBoolOptions),
     Bytes(QuickwitBytesOptions),
+    Completion(QuickwitCompletionOptions),
     DateTime(QuickwitDateTimeOptions),
     F64(QuickwitNumericOptions),
     I64(QuickwitNumericOptions),
@@ -161,7 +165,7 @@ pub(crate) fn map_primitive_json_to_tantivy(value: JsonValue) -> Option<TantivyV
 impl LeafType {
     fn validate_from_json(&self, json_val: &BorrowedJsonValue) -> Result<(), String> {
         match self {
-            LeafType::Text(_) => {
+            LeafType::Text(_) | LeafType::Completion(_) => {
                 if json_val.is_string() {
                     Ok(())
                 } else {
@@ -218,7 +222,7 @@ impl LeafType {
 
     fn value_from_json(&self, json_val: JsonValue) -> Result<TantivyValue, String> {
         match self {
-            LeafType::Text(_) => {
+            LeafType::Text(_) | LeafType::Completion(_) => {
                 if let JsonValue::String(text) = json_val {
                     Ok(TantivyValue::Str(text))
                 } else {
@@ -298,6 +302,7 @@ impl LeafType {
                 Err("unsupported concat type: DateTime".to_string())
             }
             LeafType::Bytes(_binary_options) => Err("unsupported concat type: Bytes".to_string()),
+            LeafType::Completion(_) => Err("unsupported concat type: Completion".to_string()),
             LeafType::Json(_) => {
                 if let JsonValue::Object(json_obj) = json_val {
                     Ok(OneOrIter::Iter(
@@ -322,8 +327,25 @@ impl LeafType {
             IpAddr(_),
             // won't be supported
             Bytes(_),
+            Completion(_),
         */
     }
+
+    /// Returns the concatenate fields the value of this field is copied to.
+    fn copy_to(&self) -> &[String] {
+        match self {
+            LeafType::Text(options) => &options.copy_to,
+            LeafType::I64(options) | LeafType::U64(options) | LeafType::F64(options) => {
+                &options.copy_to
+            }
+            LeafType::Bool(options) => &options.copy_to,
+            LeafType::IpAddr(_)
+            | LeafType::DateTime(_)
+            | LeafType::Bytes(_)
+            | LeafType::Completion(_)
+            | LeafType::Json(_) => &[],
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -333,6 +355,10 @@ pub(crate) struct MappingLeaf {
     cardinality: Cardinality,
     // concatenate fields this field is part of
     concatenate: Vec<Field>,
+    // text sub-fields indexing the same value with a different configuration
+    sub_fields: Vec<Field>,
+    // fast field recording the number of tokens of the values of this text field
+    token_count_field: Option<Field>,
 }
 
 impl MappingLeaf {
@@ -402,6 +428,9 @@ impl MappingLeaf {
                     .typ
                     .value_from_json(el_json_val)
                     .map_err(|err_msg| DocParsingError::ValueError(path.join("."), err_msg))?;
+                for sub_field in &self.sub_fields {
+                    document.add_field_value(*sub_field, &value);
+                }
                 document.add_field_value(self.field, &value);
             }
             return Ok(());
@@ -422,6 +451,9 @@ impl MappingLeaf {
             .typ
             .value_from_json(json_val)
             .map_err(|err_msg| DocParsingError::ValueError(path.join("."), err_msg))?;
+        for sub_field in &self.sub_fields {
+            document.add_field_value(*sub_field, &value);
+        }
         document.add_field_value(self.field, &value);
         Ok(())
     }
@@ -780,7 +812,7 @@ fn tantivy_value_to_json_value_nocopy(value: TantivyValue) -> JsonValue {
 /// For certain LeafType, we use the type options to format the output.
 fn value_to_json(value: TantivyValue, leaf_type: &LeafType) -> Option<JsonValue> {
     let res = match leaf_type {
-        LeafType::Text(_) => value_to_string(value),
+        LeafType::Text(_) | LeafType::Completion(_) => value_to_string(value),
         LeafType::Bool(_) => value_to_bool(value),
         LeafType::IpAddr(_) => value_to_ip(value),
         LeafType::F64(numeric_options) => value_to_float(value, numeric_options),
@@ -808,7 +840,7 @@ fn value_to_json(value: TantivyValue, leaf_type: &LeafType) -> Option<JsonValue>
             .map(|date_time| {
                 date_time_options
                     .output_format
-                    .format_to_json(date_time)
+                    .format_to_json_with_timezone(date_time, &date_time_options.output_timezone)
                     .expect("Invalid datetime is not allowed.")
             })
             .ok_or(value),
@@ -1046,6 +1078,51 @@ impl MappingNode {
         }
     }
 
+    /// Returns the text fields whose number of tokens is recorded in a fast field.
+    pub fn token_count_fields(&self) -> Vec<TokenCountField> {
+        let mut token_count_fields = Vec::new();
+        for child_tree in self.branches.values() {
+            match child_tree {
+                MappingTree::Leaf(leaf) => {
+                    let (Some(token_count_field), LeafType::Text(text_options)) =
+                        (leaf.token_count_field, &leaf.typ)
+                    else {
+                        continue;
+                    };
+                    let Some(indexing_options) = &text_options.indexing_options else {
+                        continue;
+                    };
+                    token_count_fields.push(TokenCountField {
+                        text_field: leaf.field,
+                        token_count_field,
+                        tokenizer_name: indexing_options.tokenizer.name().to_string(),
+                    });
+                }
+                MappingTree::Node(child_node) => {
+                    token_count_fields.extend(child_node.token_count_fields());
+                }
+            }
+        }
+        token_count_fields
+    }
+
+    /// Calls `leaf_fn` on all the leaves of the mapping tree, along with the path of the leaf
+    /// relative to this node.
+    fn for_each_leaf_mut(
+        &mut self,
+        field_path: &mut Vec<String>,
+        leaf_fn: &mut impl FnMut(&[String], &mut MappingLeaf),
+    ) {
+        for (field_name, child_tree) in self.branches.iter_mut() {
+            field_path.push(field_name.clone());
+            match child_tree {
+                MappingTree::Leaf(leaf) => leaf_fn(field_path, leaf),
+                MappingTree::Node(child_node) => child_node.for_each_leaf_mut(field_path, leaf_fn),
+            }
+            field_path.pop();
+        }
+    }
+
     #[cfg(test)]
     pub fn num_fields(&self) -> usize {
         self.branches.len()
@@ -1070,26 +1147,45 @@ impl MappingNode {
         field_mapping_entries
     }
 
+    /// Validates a JSON object against the mapping.
+    ///
+    /// In `strict_verbose` mode, unmapped fields are pushed into `unmapped_field_paths` instead
+    /// of returning an error.
     pub fn validate_from_json<'a>(
         &self,
         json_obj: &'a BorrowedJsonMap,
-        strict_mode: bool,
+        mode: ModeType,
         path: &mut Vec<&'a str>,
+        unmapped_field_paths: &mut Vec<String>,
     ) -> Result<(), DocParsingError> {
         for (field_name, json_val) in json_obj.iter() {
             if let Some(child_tree) = self.branches.get(field_name) {
                 path.push(field_name);
-                child_tree.validate_from_json(json_val, path, strict_mode)?;
+                child_tree.validate_from_json(json_val, path, mode, unmapped_field_paths)?;
                 path.pop();
-            } else if strict_mode {
-                path.push(field_name);
-                let field_path = path.join(".");
-                return Err(DocParsingError::NoSuchFieldInSchema(field_path));
+                continue;
+            }
+            match mode {
+                ModeType::Lenient | ModeType::Dynamic => {}
+                ModeType::Strict => {
+                    path.push(field_name);
+                    let field_path = path.join(".");
+                    return Err(DocParsingError::NoSuchFieldInSchema(field_path));
+                }
+                ModeType::StrictVerbose => {
+                    path.push(field_name);
+                    unmapped_field_paths.push(path.join("."));
+                    path.pop();
+                }
             }
         }
         Ok(())
     }
 
+    /// Adds the values of a JSON object to the document.
+    ///
+    /// In `strict_verbose` mode, unmapped fields are pushed into `unmapped_field_paths` instead
+    /// of returning an error.
     pub fn doc_from_json(
         &self,
         json_obj: serde_json::Map<String, JsonValue>,
@@ -1097,11 +1193,19 @@ impl MappingNode {
         document: &mut Document,
         path: &mut Vec<String>,
         dynamic_json_obj: &mut serde_json::Map<String, JsonValue>,
+        unmapped_field_paths: &mut Vec<String>,
     ) -> Result<(), DocParsingError> {
         for (field_name, val) in json_obj {
             if let Some(child_tree) = self.branches.get(&field_name) {
                 path.push(field_name);
-                child_tree.doc_from_json(val, mode, document, path, dynamic_json_obj)?;
+                child_tree.doc_from_json(
+                    val,
+                    mode,
+                    document,
+                    path,
+                    dynamic_json_obj,
+                    unmapped_field_paths,
+                )?;
                 path.pop();
             } else {
                 match mode {
@@ -1118,6 +1222,11 @@ impl MappingNode {
                         let field_path = path.join(".");
                         return Err(DocParsingError::NoSuchFieldInSchema(field_path));
                     }
+                    ModeType::StrictVerbose => {
+                        path.push(field_name);
+                        unmapped_field_paths.push(path.join("."));
+                        path.pop();
+                    }
                 }
             }
         }
@@ -1161,6 +1270,7 @@ impl From<MappingLeaf> for FieldMappingType {
             LeafType::DateTime(opt) => FieldMappingType::DateTime(opt, leaf.cardinality),
             LeafType::Bytes(opt) => FieldMappingType::Bytes(opt, leaf.cardinality),
             LeafType::Json(opt) => FieldMappingType::Json(opt, leaf.cardinality),
+            LeafType::Completion(opt) => FieldMappingType::Completion(opt),
         }
     }
 }
@@ -1171,6 +1281,14 @@ impl From<MappingNode> for Vec<FieldMappingEntry> {
     }
 }
 
+/// A text field whose number of tokens is recorded in a fast field.
+#[derive(Clone)]
+pub(crate) struct TokenCountField {
+    pub text_field: Field,
+    pub token_count_field: Field,
+    pub tokenizer_name: String,
+}
+
 #[derive(Clone)]
 pub(crate) enum MappingTree {
     Leaf(MappingLeaf),
@@ -1182,7 +1300,8 @@ impl MappingTree {
         &self,
         json_value: &'a BorrowedJsonValue<'a>,
         field_path: &mut Vec<&'a str>,
-        strict_mode: bool,
+        mode: ModeType,
+        unmapped_field_paths: &mut Vec<String>,
     ) -> Result<(), DocParsingError> {
         match self {
             MappingTree::Leaf(mapping_leaf) => {
@@ -1190,7 +1309,12 @@ impl MappingTree {
             }
             MappingTree::Node(mapping_node) => {
                 if let Some(json_obj) = json_value.as_object() {
-                    mapping_node.validate_from_json(json_obj, strict_mode, field_path)
+                    mapping_node.validate_from_json(
+                        json_obj,
+                        mode,
+                        field_path,
+                        unmapped_field_paths,
+                    )
                 } else {
                     Err(DocParsingError::ValueError(
                         field_path.join("."),
@@ -1208,6 +1332,7 @@ impl MappingTree {
         document: &mut Document,
         path: &mut Vec<String>,
         dynamic_json_obj: &mut serde_json::Map<String, JsonValue>,
+        unmapped_field_paths: &mut Vec<String>,
     ) -> Result<(), DocParsingError> {
         match self {
             MappingTree::Leaf(mapping_leaf) => {
@@ -1215,7 +1340,14 @@ impl MappingTree {
             }
             MappingTree::Node(mapping_node) => {
                 if let JsonValue::Object(json_obj) = json_value {
-                    mapping_node.doc_from_json(json_obj, mode, document, path, dynamic_json_obj)
+                    mapping_node.doc_from_json(
+                        json_obj,
+                        mode,
+                        document,
+                        path,
+                        dynamic_json_obj,
+                        unmapped_field_paths,
+                    )
                 } else {
                     Err(DocParsingError::ValueError(
                         path.join("."),
@@ -1281,7 +1413,7 @@ fn build_mapping_tree_from_entries<'a>(
             concatenate_dynamic_fields.append(&mut dynamic_fields);
         }
     }
-    for concatenate_field_entry in concatenate_fields {
+    for concatenate_field_entry in &concatenate_fields {
         let FieldMappingType::Concatenate(options) = &concatenate_field_entry.mapping_type else {
             // we only pushed Concatenate fields in `concatenate_fields`
             unreachable!();
@@ -1292,7 +1424,56 @@ fn build_mapping_tree_from_entries<'a>(
         }
         let text_options: JsonObjectOptions = options.clone().into();
         let field = schema.add_json_field(name, text_options);
+        let mut num_copied_fields = 0;
+
+        // `copy_to` can only target concatenate fields declared at the root of the doc mapping.
+        if field_path.is_empty() {
+            mapping_node.for_each_leaf_mut(&mut Vec::new(), &mut |_, leaf| {
+                if leaf.typ.copy_to().contains(name) && !leaf.concatenate.contains(&field) {
+                    leaf.concatenate.push(field);
+                    num_copied_fields += 1;
+                }
+            });
+        }
+        if options.concatenate_fields.is_empty()
+            && !options.include_dynamic_fields
+            && num_copied_fields == 0
+        {
+            bail!("concatenate type must have at least one sub-field");
+        }
+        let exclude_field_patterns = options
+            .exclude_fields
+            .iter()
+            .map(|exclude_field| field_pattern_regex(exclude_field))
+            .collect::<anyhow::Result<Vec<Regex>>>()?;
+        let is_excluded = |field_path_as_str: &str| {
+            exclude_field_patterns
+                .iter()
+                .any(|exclude_field_pattern| exclude_field_pattern.is_match(field_path_as_str))
+        };
         for sub_field in &options.concatenate_fields {
+            if sub_field.contains('*') {
+                // Wildcard patterns silently skip the fields that can't be concatenated.
+                let sub_field_pattern = field_pattern_regex(sub_field)?;
+                mapping_node.for_each_leaf_mut(&mut Vec::new(), &mut |leaf_path, leaf| {
+                    let leaf_path_as_str = leaf_path
+                        .iter()
+                        .map(String::as_str)
+                        .map(escape_dots)
+                        .join(".");
+                    if sub_field_pattern.is_match(&leaf_path_as_str)
+                        && !is_excluded(&leaf_path_as_str)
+                        && leaf.typ.supported_for_concat()
+                        && !leaf.concatenate.contains(&field)
+                    {
+                        leaf.concatenate.push(field);
+                    }
+                });
+                continue;
+            }
+            if is_excluded(sub_field) {
+                continue;
+            }
             for matched_field in
                 mapping_node
                     .find_field_mapping_leaf(sub_field)
@@ -1306,13 +1487,34 @@ fn build_mapping_tree_from_entries<'a>(
                         sub_field
                     );
                 }
-                matched_field.concatenate.push(field);
+                if !matched_field.concatenate.contains(&field) {
+                    matched_field.concatenate.push(field);
+                }
             }
         }
         if options.include_dynamic_fields {
             concatenate_dynamic_fields.push(field);
         }
     }
+    if field_path.is_empty() {
+        let mut unknown_copy_to_targets = Vec::new();
+
+        mapping_node.for_each_leaf_mut(&mut Vec::new(), &mut |_, leaf| {
+            for copy_to_target in leaf.typ.copy_to() {
+                let is_concatenate_field = concatenate_fields
+                    .iter()
+                    .any(|concatenate_field_entry| concatenate_field_entry.name == *copy_to_target);
+                if !is_concatenate_field {
+                    unknown_copy_to_targets.push(copy_to_target.clone());
+                }
+            }
+        });
+        if let Some(copy_to_target) = unknown_copy_to_targets.first() {
+            bail!(
+                "`copy_to` target `{copy_to_target}` is not a concatenate field of the doc mapping"
+            );
+        }
+    }
     Ok(MappingNodeRoot {
         field_mappings: mapping_node,
         concatenate_dynamic_fields,
@@ -1402,6 +1604,20 @@ fn get_ip_address_options(quickwit_ip_address_options: &QuickwitIpAddrOptions) -
 /// We simply concatenate these field names, interleaving them with '.'.
 /// If a fieldname itself contains a '.', we escape it with '\'.
 /// ('\' itself is forbidden).
+/// Builds a regex matching the field paths described by a pattern where `*` matches any sequence
+/// of characters.
+fn field_pattern_regex(field_pattern: &str) -> anyhow::Result<Regex> {
+    let mut regex = String::from("^");
+    for (idx, part) in field_pattern.split('*').enumerate() {
+        if idx > 0 {
+            regex.push_str(".*");
+        }
+        regex.push_str(&regex::escape(part));
+    }
+    regex.push('$');
+    Regex::new(&regex).with_context(|| format!("invalid field pattern `{field_pattern}`"))
+}
+
 fn field_name_for_field_path(field_path: &[&str]) -> String {
     field_path.iter().cloned().map(escape_dots).join(".")
 }
@@ -1457,11 +1673,42 @@ fn build_mapping_from_field_type<'a>(
         FieldMappingType::Text(options, cardinality) => {
             let text_options: TextOptions = options.clone().into();
             let field = schema_builder.add_text_field(&field_name, text_options);
+            let mut sub_fields = Vec::with_capacity(options.fields.len());
+            for sub_field_entry in &options.fields {
+                let FieldMappingType::Text(sub_field_options, _) = &sub_field_entry.mapping_type
+                else {
+                    // only text sub-fields pass the field mapping validation
+                    unreachable!();
+                };
+                // Sub-fields are never stored: their value is the one of the parent field.
+                let sub_field_text_options: TextOptions = QuickwitTextOptions {
+                    stored: false,
+                    ..sub_field_options.clone()
+                }
+                .into();
+                field_path.push(&sub_field_entry.name);
+                let sub_field_name = field_name_for_field_path(field_path);
+                field_path.pop();
+                let sub_field =
+                    schema_builder.add_text_field(&sub_field_name, sub_field_text_options);
+                sub_fields.push(sub_field);
+            }
+            let token_count_field = if options.token_count {
+                field_path.push(TOKEN_COUNT_SUB_FIELD_NAME);
+                let token_count_field_name = field_name_for_field_path(field_path);
+                field_path.pop();
+                let token_count_options = NumericOptions::default().set_indexed().set_fast();
+                Some(schema_builder.add_u64_field(&token_count_field_name, token_count_options))
+            } else {
+                None
+            };
             let mapping_leaf = MappingLeaf {
                 field,
                 typ: LeafType::Text(options.clone()),
                 cardinality: *cardinality,
                 concatenate: Vec::new(),
+                sub_fields,
+                token_count_field,
             };
             Ok((MappingTree::Leaf(mapping_leaf), Vec::new()))
         }
@@ -1473,6 +1720,8 @@ fn build_mapping_from_field_type<'a>(
                 typ: LeafType::I64(options.clone()),
                 cardinality: *cardinality,
                 concatenate: Vec::new(),
+                sub_fields: Vec::new(),
+                token_count_field: None,
             };
             Ok((MappingTree::Leaf(mapping_leaf), Vec::new()))
         }
@@ -1484,6 +1733,8 @@ fn build_mapping_from_field_type<'a>(
                 typ: LeafType::U64(options.clone()),
                 cardinality: *cardinality,
                 concatenate: Vec::new(),
+                sub_fields: Vec::new(),
+                token_count_field: None,
             };
             Ok((MappingTree::Leaf(mapping_leaf), Vec::new()))
         }
@@ -1495,6 +1746,8 @@ fn build_mapping_from_field_type<'a>(
                 typ: LeafType::F64(options.clone()),
                 cardinality: *cardinality,
                 concatenate: Vec::new(),
+                sub_fields: Vec::new(),
+                token_count_field: None,
             };
             Ok((MappingTree::Leaf(mapping_leaf), Vec::new()))
         }
@@ -1506,6 +1759,8 @@ fn build_mapping_from_field_type<'a>(
                 typ: LeafType::Bool(options.clone()),
                 cardinality: *cardinality,
                 concatenate: Vec::new(),
+                sub_fields: Vec::new(),
+                token_count_field: None,
             };
             Ok((MappingTree::Leaf(mapping_leaf), Vec::new()))
         }
@@ -1517,6 +1772,8 @@ fn build_mapping_from_field_type<'a>(
                 typ: LeafType::IpAddr(options.clone()),
                 cardinality: *cardinality,
                 concatenate: Vec::new(),
+                sub_fields: Vec::new(),
+                token_count_field: None,
             };
             Ok((MappingTree::Leaf(mapping_leaf), Vec::new()))
         }
@@ -1528,6 +1785,8 @@ fn build_mapping_from_field_type<'a>(
                 typ: LeafType::DateTime(options.clone()),
                 cardinality: *cardinality,
                 concatenate: Vec::new(),
+                sub_fields: Vec::new(),
+                token_count_field: None,
             };
             Ok((MappingTree::Leaf(mapping_leaf), Vec::new()))
         }
@@ -1539,6 +1798,8 @@ fn build_mapping_from_field_type<'a>(
                 typ: LeafType::Bytes(options.clone()),
                 cardinality: *cardinality,
                 concatenate: Vec::new(),
+                sub_fields: Vec::new(),
+                token_count_field: None,
             };
             Ok((MappingTree::Leaf(mapping_leaf), Vec::new()))
         }
@@ -1550,6 +1811,22 @@ fn build_mapping_from_field_type<'a>(
                 typ: LeafType::Json(options.clone()),
                 cardinality: *cardinality,
                 concatenate: Vec::new(),
+                sub_fields: Vec::new(),
+                token_count_field: None,
+            };
+            Ok((MappingTree::Leaf(mapping_leaf), Vec::new()))
+        }
+        FieldMappingType::Completion(options) => {
+            let text_options: TextOptions = options.clone().into();
+            let field = schema_builder.add_text_field(&field_name, text_options);
+            let mapping_leaf = MappingLeaf {
+                field,
+                typ: LeafType::Completion(options.clone()),
+                // A completion field accepts a single value or an array of values.
+                cardinality: Cardinality::MultiValued,
+                concatenate: Vec::new(),
+                sub_fields: Vec::new(),
+                token_count_field: None,
             };
             Ok((MappingTree::Leaf(mapping_leaf), Vec::new()))
         }
@@ -1769,6 +2046,8 @@ mod tests {
             typ,
             cardinality: Cardinality::MultiValued,
             concatenate: Vec::new(),
+            sub_fields: Vec::new(),
+            token_count_field: None,
         };
         let mut document = Document::default();
         let mut path = Vec::new();
@@ -1821,6 +2100,8 @@ mod tests {
             typ,
             cardinality: Cardinality::MultiValued,
             concatenate: Vec::new(),
+            sub_fields: Vec::new(),
+            token_count_field: None,
         };
         let mut document = Document::default();
         let mut path = Vec::new();
@@ -1844,6 +2125,8 @@ mod tests {
             typ,
             cardinality: Cardinality::MultiValued,
             concatenate: Vec::new(),
+            sub_fields: Vec::new(),
+            token_count_field: None,
         };
         let mut document = Document::default();
         let mut path = Vec::new();
@@ -1862,6 +2145,8 @@ mod tests {
             typ,
             cardinality: Cardinality::MultiValued,
             concatenate: Vec::new(),
+            sub_fields: Vec::new(),
+            token_count_field: None,
         };
         let mut document = Document::default();
         let mut path = Vec::new();
@@ -1881,6 +2166,8 @@ mod tests {
             typ,
             cardinality: Cardinality::MultiValued,
             concatenate: Vec::new(),
+            sub_fields: Vec::new(),
+            token_count_field: None,
         };
         let mut document = Document::default();
         let mut path = vec!["root".to_string(), "my_field".to_string()];
@@ -2027,6 +2314,8 @@ mod tests {
             typ,
             cardinality: Cardinality::MultiValued,
             concatenate: Vec::new(),
+            sub_fields: Vec::new(),
+            token_count_field: None,
         };
         let mut document = Document::default();
         let mut path = vec!["root".to_string(), "my_field".to_string()];
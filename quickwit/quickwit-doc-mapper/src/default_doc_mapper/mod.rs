@@ -44,7 +44,10 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 pub use tokenizer_entry::{analyze_text, TokenizerConfig, TokenizerEntry};
 pub(crate) use tokenizer_entry::{
-    NgramTokenizerOption, RegexTokenizerOption, TokenFilterType, TokenizerType,
+    CharFilterType, EdgeNgramOption, MultilangTokenizerOption, NgramTokenizerOption,
+    PathHierarchyTokenizerOption, PatternReplaceCharFilterOption, PatternTokenizerOption,
+    RegexTokenizerOption, SnowballFilterOption, StopWordsFilterOption, SynonymFilterOption,
+    TokenFilterType, TokenizerType,
 };
 
 use crate::QW_RESERVED_FIELD_NAMES;
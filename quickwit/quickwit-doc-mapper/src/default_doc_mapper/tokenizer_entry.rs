@@ -17,12 +17,15 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::Context;
-use quickwit_query::{CodeTokenizer, DEFAULT_REMOVE_TOKEN_LENGTH};
+use anyhow::{bail, Context};
+use quickwit_query::{
+    CharFilter, CharFilterTokenizer, CodeTokenizer, EdgeNgramFilter, PathHierarchyTokenizer,
+    PatternTokenizer, SynonymFilter, DEFAULT_REMOVE_TOKEN_LENGTH,
+};
 use serde::{Deserialize, Serialize};
 use tantivy::tokenizer::{
-    AsciiFoldingFilter, LowerCaser, NgramTokenizer, RegexTokenizer, RemoveLongFilter,
-    SimpleTokenizer, TextAnalyzer, Token,
+    AsciiFoldingFilter, Language, LowerCaser, NgramTokenizer, RegexTokenizer, RemoveLongFilter,
+    SimpleTokenizer, Stemmer, StopWordFilter, TextAnalyzer, Token,
 };
 
 /// A `TokenizerEntry` defines a custom tokenizer with its name and configuration.
@@ -40,6 +43,10 @@ pub struct TokenizerEntry {
 pub struct TokenizerConfig {
     #[serde(flatten)]
     pub(crate) tokenizer_type: TokenizerType,
+    /// Character filters applied to the text before tokenization.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) char_filters: Vec<CharFilterType>,
     #[serde(default)]
     pub(crate) filters: Vec<TokenFilterType>,
 }
@@ -47,27 +54,68 @@ pub struct TokenizerConfig {
 impl TokenizerConfig {
     /// Build a `TextAnalyzer` from a `TokenizerConfig`.
     pub fn text_analyzer(&self) -> anyhow::Result<TextAnalyzer> {
+        let char_filters = self
+            .char_filters
+            .iter()
+            .map(CharFilterType::char_filter)
+            .collect::<anyhow::Result<Vec<CharFilter>>>()?;
         let mut text_analyzer_builder = match &self.tokenizer_type {
-            TokenizerType::Simple => TextAnalyzer::builder(SimpleTokenizer::default()).dynamic(),
+            TokenizerType::Simple => TextAnalyzer::builder(CharFilterTokenizer::new(
+                char_filters,
+                SimpleTokenizer::default(),
+            ))
+            .dynamic(),
             #[cfg(any(test, feature = "multilang"))]
-            TokenizerType::Multilang => {
-                TextAnalyzer::builder(quickwit_query::MultiLangTokenizer::default()).dynamic()
+            TokenizerType::Multilang(options) => {
+                let config = quickwit_query::MultiLangTokenizerConfig {
+                    language: options.language.clone(),
+                    japanese_dictionary: options.japanese_dictionary.clone(),
+                    cmn_user_dictionary: options.cmn_user_dictionary.clone(),
+                    jpn_user_dictionary: options.jpn_user_dictionary.clone(),
+                    kor_user_dictionary: options.kor_user_dictionary.clone(),
+                };
+                let tokenizer = quickwit_query::MultiLangTokenizer::from_config(&config)
+                    .with_context(|| "invalid multilang tokenizer".to_string())?;
+                TextAnalyzer::builder(CharFilterTokenizer::new(char_filters, tokenizer)).dynamic()
+            }
+            TokenizerType::SourceCode => TextAnalyzer::builder(CharFilterTokenizer::new(
+                char_filters,
+                CodeTokenizer::default(),
+            ))
+            .dynamic(),
+            TokenizerType::EdgeNgram(options) => {
+                let token_filter = EdgeNgramFilter::new(options.min_gram, options.max_gram)
+                    .with_context(|| "invalid edge_ngram tokenizer".to_string())?;
+                TextAnalyzer::builder(CharFilterTokenizer::new(
+                    char_filters,
+                    SimpleTokenizer::default(),
+                ))
+                .filter(token_filter)
+                .dynamic()
             }
-            TokenizerType::SourceCode => TextAnalyzer::builder(CodeTokenizer::default()).dynamic(),
             TokenizerType::Ngram(options) => {
                 let tokenizer =
                     NgramTokenizer::new(options.min_gram, options.max_gram, options.prefix_only)
                         .with_context(|| "invalid ngram tokenizer".to_string())?;
-                TextAnalyzer::builder(tokenizer).dynamic()
+                TextAnalyzer::builder(CharFilterTokenizer::new(char_filters, tokenizer)).dynamic()
+            }
+            TokenizerType::PathHierarchy(options) => {
+                let tokenizer = PathHierarchyTokenizer::new(options.delimiter);
+                TextAnalyzer::builder(CharFilterTokenizer::new(char_filters, tokenizer)).dynamic()
+            }
+            TokenizerType::Pattern(options) => {
+                let tokenizer = PatternTokenizer::new(&options.pattern, options.group)
+                    .with_context(|| "invalid pattern tokenizer".to_string())?;
+                TextAnalyzer::builder(CharFilterTokenizer::new(char_filters, tokenizer)).dynamic()
             }
             TokenizerType::Regex(options) => {
                 let tokenizer = RegexTokenizer::new(&options.pattern)
                     .with_context(|| "invalid regex tokenizer".to_string())?;
-                TextAnalyzer::builder(tokenizer).dynamic()
+                TextAnalyzer::builder(CharFilterTokenizer::new(char_filters, tokenizer)).dynamic()
             }
         };
         for filter in &self.filters {
-            match filter.tantivy_token_filter_enum() {
+            match filter.tantivy_token_filter_enum()? {
                 TantivyTokenFilterEnum::RemoveLong(token_filter) => {
                     text_analyzer_builder = text_analyzer_builder.filter_dynamic(token_filter);
                 }
@@ -77,6 +125,20 @@ impl TokenizerConfig {
                 TantivyTokenFilterEnum::AsciiFolding(token_filter) => {
                     text_analyzer_builder = text_analyzer_builder.filter_dynamic(token_filter);
                 }
+                TantivyTokenFilterEnum::Stemmer(token_filter) => {
+                    text_analyzer_builder = text_analyzer_builder.filter_dynamic(token_filter);
+                }
+                TantivyTokenFilterEnum::Synonym(token_filter) => {
+                    text_analyzer_builder = text_analyzer_builder.filter_dynamic(token_filter);
+                }
+                TantivyTokenFilterEnum::EdgeNgram(token_filter) => {
+                    text_analyzer_builder = text_analyzer_builder.filter_dynamic(token_filter);
+                }
+                TantivyTokenFilterEnum::StopWords(token_filters) => {
+                    for token_filter in token_filters {
+                        text_analyzer_builder = text_analyzer_builder.filter_dynamic(token_filter);
+                    }
+                }
             }
         }
         Ok(text_analyzer_builder.build())
@@ -94,12 +156,101 @@ pub fn analyze_text(text: &str, tokenizer: &TokenizerConfig) -> anyhow::Result<V
     Ok(tokens)
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CharFilterType {
+    HtmlStrip,
+    PatternReplace(PatternReplaceCharFilterOption),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PatternReplaceCharFilterOption {
+    /// Regular expression matching the text to replace.
+    pub pattern: String,
+    /// Replacement text, which can reference capture groups with `$1` or `${name}`.
+    #[serde(default)]
+    pub replacement: String,
+}
+
+impl CharFilterType {
+    fn char_filter(&self) -> anyhow::Result<CharFilter> {
+        match self {
+            Self::HtmlStrip => Ok(CharFilter::HtmlStrip),
+            Self::PatternReplace(options) => {
+                CharFilter::pattern_replace(&options.pattern, &options.replacement)
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum TokenFilterType {
     RemoveLong,
+    #[serde(alias = "lowercase")]
     LowerCaser,
     AsciiFolding,
+    /// English stemmer, as used by the `en_stem` tokenizer.
+    Stemmer,
+    Snowball(SnowballFilterOption),
+    StopWords(StopWordsFilterOption),
+    Synonym(SynonymFilterOption),
+    EdgeNgram(EdgeNgramOption),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SnowballFilterOption {
+    /// Language of the Snowball stemmer, e.g. `french` or `german`.
+    pub language: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StopWordsFilterOption {
+    /// Language of the built-in stop word list to use, e.g. `english` or `french`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Additional stop words. They are stored in the index config, so that all the nodes build
+    /// the same filter.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SynonymFilterOption {
+    /// Synonym rules, e.g. `tv, television` or `tv => television`.
+    pub synonyms: Vec<String>,
+}
+
+/// Parses a language name (e.g. `english`, case-insensitive) into a tantivy [`Language`].
+fn parse_language(language_str: &str) -> anyhow::Result<Language> {
+    let language = match language_str.to_ascii_lowercase().as_str() {
+        "arabic" => Language::Arabic,
+        "danish" => Language::Danish,
+        "dutch" => Language::Dutch,
+        "english" => Language::English,
+        "finnish" => Language::Finnish,
+        "french" => Language::French,
+        "german" => Language::German,
+        "greek" => Language::Greek,
+        "hungarian" => Language::Hungarian,
+        "italian" => Language::Italian,
+        "norwegian" => Language::Norwegian,
+        "portuguese" => Language::Portuguese,
+        "romanian" => Language::Romanian,
+        "russian" => Language::Russian,
+        "spanish" => Language::Spanish,
+        "swedish" => Language::Swedish,
+        "tamil" => Language::Tamil,
+        "turkish" => Language::Turkish,
+        _ => bail!("unsupported language `{language_str}`"),
+    };
+    Ok(language)
 }
 
 /// Tantivy token filter enum to build
@@ -108,17 +259,52 @@ enum TantivyTokenFilterEnum {
     RemoveLong(RemoveLongFilter),
     LowerCaser(LowerCaser),
     AsciiFolding(AsciiFoldingFilter),
+    Stemmer(Stemmer),
+    StopWords(Vec<StopWordFilter>),
+    Synonym(SynonymFilter),
+    EdgeNgram(EdgeNgramFilter),
 }
 
 impl TokenFilterType {
-    fn tantivy_token_filter_enum(&self) -> TantivyTokenFilterEnum {
-        match &self {
+    fn tantivy_token_filter_enum(&self) -> anyhow::Result<TantivyTokenFilterEnum> {
+        let token_filter = match &self {
             Self::RemoveLong => TantivyTokenFilterEnum::RemoveLong(RemoveLongFilter::limit(
                 DEFAULT_REMOVE_TOKEN_LENGTH,
             )),
             Self::LowerCaser => TantivyTokenFilterEnum::LowerCaser(LowerCaser),
             Self::AsciiFolding => TantivyTokenFilterEnum::AsciiFolding(AsciiFoldingFilter),
-        }
+            Self::Stemmer => TantivyTokenFilterEnum::Stemmer(Stemmer::new(Language::English)),
+            Self::Snowball(options) => {
+                let language = parse_language(&options.language)?;
+                TantivyTokenFilterEnum::Stemmer(Stemmer::new(language))
+            }
+            Self::StopWords(options) => {
+                if options.language.is_none() && options.words.is_empty() {
+                    bail!("`stop_words` filter requires a `language` or a list of `words`");
+                }
+                let mut token_filters = Vec::new();
+
+                if let Some(language_str) = &options.language {
+                    let language = parse_language(language_str)?;
+                    let Some(token_filter) = StopWordFilter::new(language) else {
+                        bail!("no stop word list is available for language `{language_str}`");
+                    };
+                    token_filters.push(token_filter);
+                }
+                if !options.words.is_empty() {
+                    token_filters.push(StopWordFilter::remove(options.words.clone()));
+                }
+                TantivyTokenFilterEnum::StopWords(token_filters)
+            }
+            Self::Synonym(options) => {
+                TantivyTokenFilterEnum::Synonym(SynonymFilter::new(&options.synonyms)?)
+            }
+            Self::EdgeNgram(options) => TantivyTokenFilterEnum::EdgeNgram(
+                EdgeNgramFilter::new(options.min_gram, options.max_gram)
+                    .with_context(|| "invalid edge_ngram filter".to_string())?,
+            ),
+        };
+        Ok(token_filter)
     }
 }
 
@@ -126,8 +312,14 @@ impl TokenFilterType {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TokenizerType {
     #[cfg(any(test, feature = "multilang"))]
-    Multilang,
+    Multilang(MultilangTokenizerOption),
+    /// Splits the text like `simple` and emits the prefixes of each word.
+    EdgeNgram(EdgeNgramOption),
     Ngram(NgramTokenizerOption),
+    PathHierarchy(PathHierarchyTokenizerOption),
+    /// Splits the text on the matches of a regular expression, or emits the captures of one of
+    /// its groups.
+    Pattern(PatternTokenizerOption),
     Regex(RegexTokenizerOption),
     Simple,
     SourceCode,
@@ -142,6 +334,69 @@ pub struct NgramTokenizerOption {
     pub prefix_only: bool,
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EdgeNgramOption {
+    pub min_gram: usize,
+    pub max_gram: usize,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MultilangTokenizerOption {
+    /// 3-letter code of the language of the texts (`cmn`, `eng`, `jpn`, or `kor`). When set,
+    /// the language detection is skipped.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Dictionary used for Japanese: `ipadic` (default) or `unidic`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub japanese_dictionary: Option<String>,
+    /// Entries of a user dictionary extending the Chinese dictionary, in the simple CSV user
+    /// dictionary format of lindera, e.g. `surface,part_of_speech,reading`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cmn_user_dictionary: Vec<String>,
+    /// Entries of a user dictionary extending the Japanese dictionary.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub jpn_user_dictionary: Vec<String>,
+    /// Entries of a user dictionary extending the Korean dictionary.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub kor_user_dictionary: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PathHierarchyTokenizerOption {
+    /// Character separating the levels of the hierarchy.
+    #[schema(value_type = String)]
+    #[serde(default = "default_path_delimiter")]
+    pub delimiter: char,
+}
+
+fn default_path_delimiter() -> char {
+    '/'
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PatternTokenizerOption {
+    /// Regular expression matching the separators, or the tokens when `group` is set.
+    #[serde(default = "default_tokenizer_pattern")]
+    pub pattern: String,
+    /// Capture group to emit as tokens. When unset, the text is split on the pattern matches.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<usize>,
+}
+
+fn default_tokenizer_pattern() -> String {
+    r"\W+".to_string()
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, utoipa::ToSchema)]
 #[serde(deny_unknown_fields)]
 pub struct RegexTokenizerOption {
@@ -150,7 +405,12 @@ pub struct RegexTokenizerOption {
 
 #[cfg(test)]
 mod tests {
-    use super::{NgramTokenizerOption, TokenizerType};
+    use super::{
+        CharFilterType, EdgeNgramOption, MultilangTokenizerOption, NgramTokenizerOption,
+        PathHierarchyTokenizerOption, PatternReplaceCharFilterOption, PatternTokenizerOption,
+        SnowballFilterOption, StopWordsFilterOption, SynonymFilterOption, TokenFilterType,
+        TokenizerType,
+    };
     use crate::default_doc_mapper::RegexTokenizerOption;
     use crate::TokenizerEntry;
 
@@ -243,4 +503,431 @@ mod tests {
             _ => panic!("Unexpected tokenizer type"),
         }
     }
+
+    #[test]
+    fn test_tokenizer_entry_multilang() {
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "multilang"
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            tokenizer_config_entry.config.tokenizer_type,
+            TokenizerType::Multilang(MultilangTokenizerOption::default())
+        );
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "multilang",
+                "language": "jpn"
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            tokenizer_config_entry.config.tokenizer_type,
+            TokenizerType::Multilang(MultilangTokenizerOption {
+                language: Some("jpn".to_string()),
+                ..Default::default()
+            })
+        );
+        let tokens =
+            super::analyze_text("すもももももももものうち", &tokenizer_config_entry.config)
+                .unwrap();
+        assert_eq!(tokens.len(), 7);
+
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "multilang",
+                "language": "klingon"
+            }
+            "#,
+        )
+        .unwrap();
+        let error = tokenizer_config_entry.config.text_analyzer().err().unwrap();
+        assert_eq!(error.to_string(), "invalid multilang tokenizer");
+
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "multilang",
+                "japanese_dictionary": "unidic",
+                "jpn_user_dictionary": ["東京スカイツリー"]
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            tokenizer_config_entry.config.tokenizer_type,
+            TokenizerType::Multilang(MultilangTokenizerOption {
+                japanese_dictionary: Some("unidic".to_string()),
+                jpn_user_dictionary: vec!["東京スカイツリー".to_string()],
+                ..Default::default()
+            })
+        );
+        let error = tokenizer_config_entry.config.text_analyzer().err().unwrap();
+        assert!(format!("{error:#}").contains("failed to load user dictionary"));
+
+        // Tokenizers must not read local files.
+        let error = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "multilang",
+                "jpn_user_dictionary_path": "/etc/passwd"
+            }
+            "#,
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("unknown field `jpn_user_dictionary_path`"));
+    }
+
+    #[test]
+    fn test_tokenizer_entry_filter_chain() {
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "simple",
+                "filters": [
+                    "lowercase",
+                    {"stop_words": {"language": "english", "words": ["quick"]}},
+                    "stemmer"
+                ]
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            tokenizer_config_entry.config.filters,
+            vec![
+                TokenFilterType::LowerCaser,
+                TokenFilterType::StopWords(StopWordsFilterOption {
+                    language: Some("english".to_string()),
+                    words: vec!["quick".to_string()],
+                }),
+                TokenFilterType::Stemmer,
+            ]
+        );
+        let tokens = super::analyze_text(
+            "The Quick foxes are Jumping",
+            &tokenizer_config_entry.config,
+        )
+        .unwrap();
+        let token_texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(token_texts, ["fox", "jump"]);
+    }
+
+    #[test]
+    fn test_tokenizer_entry_invalid_stop_words_filter() {
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "simple",
+                "filters": [{"stop_words": {}}]
+            }
+            "#,
+        )
+        .unwrap();
+        let error = tokenizer_config_entry.config.text_analyzer().err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "`stop_words` filter requires a `language` or a list of `words`"
+        );
+
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "simple",
+                "filters": [{"stop_words": {"language": "klingon"}}]
+            }
+            "#,
+        )
+        .unwrap();
+        let error = tokenizer_config_entry.config.text_analyzer().err().unwrap();
+        assert_eq!(error.to_string(), "unsupported language `klingon`");
+    }
+
+    #[test]
+    fn test_tokenizer_entry_snowball_filter() {
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "simple",
+                "filters": ["lowercase", {"snowball": {"language": "French"}}]
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            tokenizer_config_entry.config.filters[1],
+            TokenFilterType::Snowball(SnowballFilterOption {
+                language: "French".to_string(),
+            })
+        );
+        let tokens =
+            super::analyze_text("Chevaux mangeaient", &tokenizer_config_entry.config).unwrap();
+        let token_texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(token_texts, ["cheval", "mang"]);
+
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "simple",
+                "filters": [{"snowball": {"language": "klingon"}}]
+            }
+            "#,
+        )
+        .unwrap();
+        let error = tokenizer_config_entry.config.text_analyzer().err().unwrap();
+        assert_eq!(error.to_string(), "unsupported language `klingon`");
+    }
+
+    #[test]
+    fn test_tokenizer_entry_synonym_filter() {
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "simple",
+                "filters": ["lowercase", {"synonym": {"synonyms": ["tv, television"]}}]
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            tokenizer_config_entry.config.filters[1],
+            TokenFilterType::Synonym(SynonymFilterOption {
+                synonyms: vec!["tv, television".to_string()],
+            })
+        );
+        let tokens = super::analyze_text("Smart TV", &tokenizer_config_entry.config).unwrap();
+        let token_texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(token_texts, ["smart", "tv", "television"]);
+    }
+
+    #[test]
+    fn test_tokenizer_entry_char_filters() {
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "simple",
+                "char_filters": [
+                    "html_strip",
+                    {"pattern_replace": {"pattern": "(\\d+)-(\\d+)", "replacement": "$1$2"}}
+                ],
+                "filters": ["lowercase"]
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            tokenizer_config_entry.config.char_filters,
+            vec![
+                CharFilterType::HtmlStrip,
+                CharFilterType::PatternReplace(PatternReplaceCharFilterOption {
+                    pattern: "(\\d+)-(\\d+)".to_string(),
+                    replacement: "$1$2".to_string(),
+                }),
+            ]
+        );
+        let tokens = super::analyze_text(
+            "<div>Call <b>555-1234</b></div>",
+            &tokenizer_config_entry.config,
+        )
+        .unwrap();
+        let token_texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(token_texts, ["call", "5551234"]);
+
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "simple",
+                "char_filters": [{"pattern_replace": {"pattern": "("}}]
+            }
+            "#,
+        )
+        .unwrap();
+        let error = tokenizer_config_entry.config.text_analyzer().err().unwrap();
+        assert_eq!(error.to_string(), "invalid `pattern_replace` pattern `(`");
+    }
+
+    #[test]
+    fn test_tokenizer_entry_edge_ngram() {
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "edge_ngram",
+                "min_gram": 2,
+                "max_gram": 3,
+                "filters": ["lowercase"]
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            tokenizer_config_entry.config.tokenizer_type,
+            TokenizerType::EdgeNgram(EdgeNgramOption {
+                min_gram: 2,
+                max_gram: 3,
+            })
+        );
+        let tokens = super::analyze_text("Quick Fox", &tokenizer_config_entry.config).unwrap();
+        let token_texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(token_texts, ["qu", "qui", "fo", "fox"]);
+
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "simple",
+                "filters": ["lowercase", {"edge_ngram": {"min_gram": 1, "max_gram": 2}}]
+            }
+            "#,
+        )
+        .unwrap();
+        let tokens = super::analyze_text("Quick Fox", &tokenizer_config_entry.config).unwrap();
+        let token_texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(token_texts, ["q", "qu", "f", "fo"]);
+
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "edge_ngram",
+                "min_gram": 3,
+                "max_gram": 2
+            }
+            "#,
+        )
+        .unwrap();
+        let error = tokenizer_config_entry.config.text_analyzer().err().unwrap();
+        assert_eq!(error.to_string(), "invalid edge_ngram tokenizer");
+    }
+
+    #[test]
+    fn test_tokenizer_entry_path_hierarchy() {
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "path_hierarchy"
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            tokenizer_config_entry.config.tokenizer_type,
+            TokenizerType::PathHierarchy(PathHierarchyTokenizerOption { delimiter: '/' })
+        );
+        let tokens =
+            super::analyze_text("/var/log/nginx/access.log", &tokenizer_config_entry.config)
+                .unwrap();
+        let token_texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(
+            token_texts,
+            [
+                "/var",
+                "/var/log",
+                "/var/log/nginx",
+                "/var/log/nginx/access.log"
+            ]
+        );
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "path_hierarchy",
+                "delimiter": "."
+            }
+            "#,
+        )
+        .unwrap();
+        let tokens = super::analyze_text("a.b", &tokenizer_config_entry.config).unwrap();
+        let token_texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(token_texts, ["a", "a.b"]);
+    }
+
+    #[test]
+    fn test_tokenizer_entry_pattern() {
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "pattern"
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            tokenizer_config_entry.config.tokenizer_type,
+            TokenizerType::Pattern(PatternTokenizerOption {
+                pattern: r"\W+".to_string(),
+                group: None,
+            })
+        );
+        let tokens = super::analyze_text("foo-bar baz", &tokenizer_config_entry.config).unwrap();
+        let token_texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(token_texts, ["foo", "bar", "baz"]);
+
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "pattern",
+                "pattern": "(\\w+)=",
+                "group": 1
+            }
+            "#,
+        )
+        .unwrap();
+        let tokens =
+            super::analyze_text("level=error status=500", &tokenizer_config_entry.config).unwrap();
+        let token_texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(token_texts, ["level", "status"]);
+
+        let tokenizer_config_entry = serde_json::from_str::<TokenizerEntry>(
+            r#"
+            {
+                "name": "my_tokenizer",
+                "type": "pattern",
+                "pattern": "\\w+",
+                "group": 1
+            }
+            "#,
+        )
+        .unwrap();
+        let error = tokenizer_config_entry.config.text_analyzer().err().unwrap();
+        assert_eq!(error.to_string(), "invalid pattern tokenizer");
+    }
+
+    #[test]
+    fn test_tokenizer_entry_stop_words_rejects_file_paths() {
+        // Tokenizers are built on every node and from the configs of the analyze APIs, so they
+        // must not read local files.
+        let tokenizer_config_json = serde_json::json!({
+            "name": "my_tokenizer",
+            "type": "simple",
+            "filters": [{"stop_words": {"words_path": "/etc/passwd"}}]
+        });
+        let error = serde_json::from_value::<TokenizerEntry>(tokenizer_config_json).unwrap_err();
+        assert!(error.to_string().contains("unknown field `words_path`"));
+    }
 }
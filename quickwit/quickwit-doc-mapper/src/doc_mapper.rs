@@ -137,20 +137,19 @@ pub trait DocMapper: Send + Sync + Debug + DynClone + 'static {
     /// Returns the tag `NameField`s on the current schema.
     /// Returns an error if a tag field is not found in this schema.
     fn tag_named_fields(&self) -> anyhow::Result<Vec<NamedField>> {
-        let index_schema = self.schema();
-        self.tag_field_names()
-            .iter()
-            .map(|field_name| {
-                index_schema
-                    .get_field(field_name)
-                    .context(format!("field `{field_name}` must exist in the schema"))
-                    .map(|field| NamedField {
-                        name: field_name.clone(),
-                        field,
-                        field_type: index_schema.get_field_entry(field).field_type().clone(),
-                    })
-            })
-            .collect::<Result<Vec<_>, _>>()
+        named_fields(&self.schema(), &self.tag_field_names())
+    }
+
+    /// Returns the names of the fields for which a Bloom filter of their terms is stored in each
+    /// split.
+    fn bloom_filter_field_names(&self) -> BTreeSet<String> {
+        Default::default()
+    }
+
+    /// Returns the `NamedField`s of the Bloom filter fields on the current schema.
+    /// Returns an error if a Bloom filter field is not found in this schema.
+    fn bloom_filter_named_fields(&self) -> anyhow::Result<Vec<NamedField>> {
+        named_fields(&self.schema(), &self.bloom_filter_field_names())
     }
 
     /// Returns the maximum number of partitions.
@@ -171,6 +170,26 @@ pub struct NamedField {
     pub field_type: FieldType,
 }
 
+/// Resolves the given field names on the schema.
+fn named_fields(
+    schema: &Schema,
+    field_names: &BTreeSet<String>,
+) -> anyhow::Result<Vec<NamedField>> {
+    field_names
+        .iter()
+        .map(|field_name| {
+            schema
+                .get_field(field_name)
+                .context(format!("field `{field_name}` must exist in the schema"))
+                .map(|field| NamedField {
+                    name: field_name.clone(),
+                    field,
+                    field_type: schema.get_field_entry(field).field_type().clone(),
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()
+}
+
 clone_trait_object!(DocMapper);
 
 /// Bounds for a range of terms, with an optional max count of terms being matched.
@@ -362,6 +381,7 @@ mod tests {
             default_fields: None,
             default_operator: BooleanOperand::And,
             lenient: false,
+            allow_leading_wildcard: false,
         }
         .parse_user_query(&[])
         .unwrap();
@@ -658,6 +678,15 @@ mod tests {
                 DocParsingError::NoSuchFieldInSchema(_)
             ));
         }
+        {
+            const JSON_CONFIG_VALUE: &str = r#"{ "mode": "strict_verbose", "field_mappings": [] }"#;
+            let doc_mapper = serde_json::from_str::<DefaultDocMapper>(JSON_CONFIG_VALUE).unwrap();
+            assert!(matches!(
+                test_validate_doc_aux(&doc_mapper, r#"{ "whatever": "blop", "other": 1 }"#)
+                    .unwrap_err(),
+                DocParsingError::NoSuchFieldsInSchema(field_paths) if field_paths.len() == 2
+            ));
+        }
         {
             const JSON_CONFIG_VALUE: &str = r#"{ "mode": "lenient", "field_mappings": [] }"#;
             let doc_mapper = serde_json::from_str::<DefaultDocMapper>(JSON_CONFIG_VALUE).unwrap();
@@ -859,7 +888,8 @@ mod tests {
             .push(TokenizerEntry {
                 name: "multilang".to_string(),
                 config: TokenizerConfig {
-                    tokenizer_type: TokenizerType::Multilang,
+                    tokenizer_type: TokenizerType::Multilang(Default::default()),
+                    char_filters: Vec::new(),
                     filters: Vec::new(),
                 },
             });
@@ -17,7 +17,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::num::NonZeroU32;
 
 use quickwit_proto::types::DocMappingUid;
@@ -33,6 +33,9 @@ pub enum ModeType {
     Lenient,
     /// Strict mode: returns an error when an unmapped field is encountered.
     Strict,
+    /// Strict verbose mode: returns an error listing all the unmapped fields of the document.
+    #[serde(rename = "strict_verbose")]
+    StrictVerbose,
     /// Dynamic mode: captures and handles unmapped fields according to the dynamic field
     /// configuration.
     #[default]
@@ -46,6 +49,8 @@ pub enum Mode {
     Lenient,
     /// Strict mode: returns an error when an unmapped field is encountered.
     Strict,
+    /// Strict verbose mode: returns an error listing all the unmapped fields of the document.
+    StrictVerbose,
     /// Dynamic mode: captures and handles unmapped fields according to the dynamic field
     /// configuration.
     Dynamic(QuickwitJsonOptions),
@@ -57,6 +62,7 @@ impl Mode {
         match self {
             Self::Lenient => ModeType::Lenient,
             Self::Strict => ModeType::Strict,
+            Self::StrictVerbose => ModeType::StrictVerbose,
             Self::Dynamic(_) => ModeType::Dynamic,
         }
     }
@@ -69,6 +75,7 @@ impl Mode {
         Ok(match (mode, dynamic_mapping) {
             (ModeType::Lenient, None) => Self::Lenient,
             (ModeType::Strict, None) => Self::Strict,
+            (ModeType::StrictVerbose, None) => Self::StrictVerbose,
             (ModeType::Dynamic, Some(dynamic_mapping)) => Self::Dynamic(dynamic_mapping),
             (ModeType::Dynamic, None) => Self::default(), // Dynamic with default options
             (_, Some(_)) => anyhow::bail!(
@@ -83,6 +90,7 @@ impl Mode {
         match self {
             Self::Lenient => (ModeType::Lenient, None),
             Self::Strict => (ModeType::Strict, None),
+            Self::StrictVerbose => (ModeType::StrictVerbose, None),
             Self::Dynamic(json_options) => (ModeType::Dynamic, Some(json_options)),
         }
     }
@@ -137,6 +145,14 @@ pub struct DocMapping {
     #[serde(default)]
     pub tag_fields: BTreeSet<String>,
 
+    /// Declares the high cardinality fields, like trace IDs, for which a Bloom filter of the
+    /// values is stored in each split. The Bloom filters allow skipping the splits that do not
+    /// contain the searched value.
+    #[schema(value_type = Vec<String>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub bloom_filter_fields: BTreeSet<String>,
+
     /// Expresses via a "mini-DSL" how to route documents to split partitions.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -164,6 +180,11 @@ pub struct DocMapping {
     /// A set of additional user-defined tokenizers to be used during indexing.
     #[serde(default)]
     pub tokenizers: Vec<TokenizerEntry>,
+
+    /// Alias field names resolved to a concrete field at query time.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub field_aliases: BTreeMap<String, String>,
 }
 
 impl DocMapping {
@@ -219,6 +240,7 @@ mod tests {
             ],
             timestamp_field: Some("timestamp".to_string()),
             tag_fields: BTreeSet::from_iter(["level".to_string()]),
+            bloom_filter_fields: BTreeSet::from_iter(["trace_id".to_string()]),
             partition_key: Some("tenant_id".to_string()),
             max_num_partitions: NonZeroU32::new(100).unwrap(),
             index_field_presence: true,
@@ -230,9 +252,11 @@ mod tests {
                     tokenizer_type: TokenizerType::Regex(RegexTokenizerOption {
                         pattern: r"\s+".to_string(),
                     }),
+                    char_filters: Vec::new(),
                     filters: vec![TokenFilterType::LowerCaser],
                 },
             }],
+            field_aliases: BTreeMap::from_iter([("hostname".to_string(), "host.name".to_string())]),
         };
         let serialized = serde_json::to_string(&doc_mapping).unwrap();
         let deserialized: DocMapping = serde_json::from_str(&serialized).unwrap();
@@ -257,5 +281,6 @@ mod tests {
         assert_eq!(doc_mapping.index_field_presence, false);
         assert_eq!(doc_mapping.store_document_size, false);
         assert_eq!(doc_mapping.store_source, false);
+        assert!(doc_mapping.field_aliases.is_empty());
     }
 }
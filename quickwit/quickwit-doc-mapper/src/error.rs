@@ -51,6 +51,9 @@ pub enum DocParsingError {
     /// The json-document contains a field that is not declared in the schema.
     #[error("the document contains a field that is not declared in the schema: {0:?}")]
     NoSuchFieldInSchema(String),
+    /// The json-document contains several fields that are not declared in the schema.
+    #[error("the document contains fields that are not declared in the schema: {0:?}")]
+    NoSuchFieldsInSchema(Vec<String>),
     /// The document contains a array of values but a single value is expected.
     #[error("the document contains an array of values but a single value is expected: {0:?}")]
     MultiValuesNotSupported(String),
@@ -40,9 +40,11 @@ pub use default_doc_mapper::{
     FieldMappingType, QuickwitBytesOptions, QuickwitJsonOptions, TokenizerConfig, TokenizerEntry,
 };
 use default_doc_mapper::{
-    FastFieldOptions, FieldMappingEntryForSerialization, IndexRecordOptionSchema,
-    NgramTokenizerOption, QuickwitTextNormalizer, QuickwitTextTokenizer, RegexTokenizerOption,
-    TokenFilterType, TokenizerType,
+    CharFilterType, EdgeNgramOption, FastFieldOptions, FieldMappingEntryForSerialization,
+    IndexRecordOptionSchema, MultilangTokenizerOption, NgramTokenizerOption,
+    PathHierarchyTokenizerOption, PatternReplaceCharFilterOption, PatternTokenizerOption,
+    QuickwitTextNormalizer, QuickwitTextTokenizer, RegexTokenizerOption, SnowballFilterOption,
+    StopWordsFilterOption, SynonymFilterOption, TokenFilterType, TokenizerType,
 };
 pub use doc_mapper::{DocMapper, JsonObject, NamedField, TermRange, WarmupInfo};
 pub use doc_mapping::{DocMapping, Mode, ModeType};
@@ -79,16 +81,25 @@ pub enum Cardinality {
 
 #[derive(utoipa::OpenApi)]
 #[openapi(components(schemas(
+    CharFilterType,
     DocMappingUid,
+    EdgeNgramOption,
     FastFieldOptions,
     FieldMappingEntryForSerialization,
     IndexRecordOptionSchema,
     ModeType,
+    MultilangTokenizerOption,
     NgramTokenizerOption,
+    PathHierarchyTokenizerOption,
+    PatternReplaceCharFilterOption,
+    PatternTokenizerOption,
     QuickwitJsonOptions,
     QuickwitTextNormalizer,
     QuickwitTextTokenizer,
     RegexTokenizerOption,
+    SnowballFilterOption,
+    StopWordsFilterOption,
+    SynonymFilterOption,
     TokenFilterType,
     TokenizerConfig,
     TokenizerEntry,
@@ -17,13 +17,14 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::Infallible;
 use std::ops::Bound;
 
 use quickwit_query::query_ast::{
-    FieldPresenceQuery, FullTextQuery, PhrasePrefixQuery, QueryAst, QueryAstVisitor, RangeQuery,
-    TermSetQuery, WildcardQuery,
+    FieldPresenceQuery, FullTextMode, FullTextQuery, PhrasePrefixQuery, QueryAst,
+    QueryAstTransformer, QueryAstVisitor, RangeQuery, RegexQuery, TermQuery, TermSetQuery,
+    UserInputQuery, WildcardQuery,
 };
 use quickwit_query::tokenizers::TokenizerManager;
 use quickwit_query::{find_field_or_hit_dynamic, InvalidQuery};
@@ -68,6 +69,148 @@ impl<'a> QueryAstVisitor<'a> for ExistsQueryFields {
     }
 }
 
+/// Replaces the alias field names of a query by the field they resolve to.
+struct FieldAliasResolver<'a> {
+    field_aliases: &'a BTreeMap<String, String>,
+}
+
+impl<'a> FieldAliasResolver<'a> {
+    fn resolve(&self, field: &mut String) {
+        if let Some(target_field) = self.field_aliases.get(field) {
+            *field = target_field.clone();
+        }
+    }
+}
+
+impl<'a> QueryAstTransformer for FieldAliasResolver<'a> {
+    type Err = Infallible;
+
+    fn transform_term(
+        &mut self,
+        mut term_query: TermQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        self.resolve(&mut term_query.field);
+        Ok(Some(term_query.into()))
+    }
+
+    fn transform_term_set(
+        &mut self,
+        term_set_query: TermSetQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        let mut terms_per_field: HashMap<String, _> = HashMap::new();
+        for (mut field, terms) in term_set_query.terms_per_field {
+            self.resolve(&mut field);
+            terms_per_field
+                .entry(field)
+                .or_insert_with(Default::default)
+                .extend(terms);
+        }
+        Ok(Some(TermSetQuery { terms_per_field }.into()))
+    }
+
+    fn transform_full_text(
+        &mut self,
+        mut full_text_query: FullTextQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        self.resolve(&mut full_text_query.field);
+        Ok(Some(full_text_query.into()))
+    }
+
+    fn transform_phrase_prefix(
+        &mut self,
+        mut phrase_prefix_query: PhrasePrefixQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        self.resolve(&mut phrase_prefix_query.field);
+        Ok(Some(phrase_prefix_query.into()))
+    }
+
+    fn transform_range(
+        &mut self,
+        mut range_query: RangeQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        self.resolve(&mut range_query.field);
+        Ok(Some(range_query.into()))
+    }
+
+    fn transform_user_text(
+        &mut self,
+        mut user_input_query: UserInputQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        if let Some(default_fields) = &mut user_input_query.default_fields {
+            for default_field in default_fields {
+                self.resolve(default_field);
+            }
+        }
+        Ok(Some(user_input_query.into()))
+    }
+
+    fn transform_exists(
+        &mut self,
+        mut exists_query: FieldPresenceQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        self.resolve(&mut exists_query.field);
+        Ok(Some(exists_query.into()))
+    }
+
+    fn transform_wildcard(
+        &mut self,
+        mut wildcard_query: WildcardQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        self.resolve(&mut wildcard_query.field);
+        Ok(Some(wildcard_query.into()))
+    }
+
+    fn transform_regex(
+        &mut self,
+        mut regex_query: RegexQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        self.resolve(&mut regex_query.field);
+        Ok(Some(regex_query.into()))
+    }
+}
+
+/// Rewrites the query so that alias field names are replaced by the field they resolve to.
+pub(crate) fn resolve_field_aliases(
+    query_ast: QueryAst,
+    field_aliases: &BTreeMap<String, String>,
+) -> QueryAst {
+    if field_aliases.is_empty() {
+        return query_ast;
+    }
+    let mut field_alias_resolver = FieldAliasResolver { field_aliases };
+    // This cannot fail. The error type is Infallible.
+    match field_alias_resolver.transform(query_ast) {
+        Ok(Some(resolved_query_ast)) => resolved_query_ast,
+        Ok(None) => QueryAst::MatchNone,
+        Err(infallible) => match infallible {},
+    }
+}
+
+/// Enables leading wildcards on all of the wildcard queries of a query.
+struct LeadingWildcardEnabler;
+
+impl QueryAstTransformer for LeadingWildcardEnabler {
+    type Err = Infallible;
+
+    fn transform_wildcard(
+        &mut self,
+        mut wildcard_query: WildcardQuery,
+    ) -> Result<Option<QueryAst>, Infallible> {
+        wildcard_query.allow_leading_wildcard = true;
+        Ok(Some(wildcard_query.into()))
+    }
+}
+
+/// Rewrites the query so that its wildcard queries are allowed to start with a wildcard.
+pub(crate) fn enable_leading_wildcards(query_ast: QueryAst) -> QueryAst {
+    // This cannot fail. The error type is Infallible.
+    match LeadingWildcardEnabler.transform(query_ast) {
+        Ok(Some(query_ast)) => query_ast,
+        Ok(None) => QueryAst::MatchNone,
+        Err(infallible) => match infallible {},
+    }
+}
+
 /// Build a `Query` with field resolution & forbidding range clauses.
 pub(crate) fn build_query(
     query_ast: &QueryAst,
@@ -160,6 +303,44 @@ impl<'a, 'b> QueryAstVisitor<'a> for ExtractTermSetFields<'b> {
         }
         Ok(())
     }
+
+    fn visit_full_text(&mut self, full_text_query: &'a FullTextQuery) -> anyhow::Result<()> {
+        // Fuzzy terms are matched by running an automaton against the term dictionary.
+        if !matches!(full_text_query.params.mode, FullTextMode::Fuzzy { .. }) {
+            return Ok(());
+        }
+        if let Ok((field, _field_entry, _path)) =
+            find_field_or_hit_dynamic(&full_text_query.field, self.schema)
+        {
+            self.term_dict_fields_to_warm_up.insert(field);
+        }
+        Ok(())
+    }
+
+    fn visit_wildcard(&mut self, wildcard_query: &'a WildcardQuery) -> anyhow::Result<()> {
+        // Wildcard queries that are not prefix queries are run as an automaton against the
+        // whole term dictionary of the field.
+        if !wildcard_query.requires_term_dict_scan() {
+            return Ok(());
+        }
+        if let Ok((field, _field_entry, _path)) =
+            find_field_or_hit_dynamic(&wildcard_query.field, self.schema)
+        {
+            self.term_dict_fields_to_warm_up.insert(field);
+        }
+        Ok(())
+    }
+
+    fn visit_regex(&mut self, regex_query: &'a RegexQuery) -> anyhow::Result<()> {
+        // The regex automaton is run against the whole term dictionary of the field.
+        // Missing fields are not an error here: the query matches no documents.
+        if let Ok((field, _field_entry, _path)) =
+            find_field_or_hit_dynamic(&regex_query.field, self.schema)
+        {
+            self.term_dict_fields_to_warm_up.insert(field);
+        }
+        Ok(())
+    }
 }
 
 fn extract_term_set_query_fields(
@@ -258,6 +439,10 @@ impl<'a, 'b: 'a> QueryAstVisitor<'a> for ExtractPrefixTermRanges<'b> {
     }
 
     fn visit_wildcard(&mut self, wildcard_query: &'a WildcardQuery) -> Result<(), Self::Err> {
+        if wildcard_query.requires_term_dict_scan() {
+            // The whole term dictionary is warmed up, see `ExtractTermSetFields`.
+            return Ok(());
+        }
         let (_, term) = wildcard_query.extract_prefix_term(self.schema, self.tokenizer_manager)?;
         self.add_prefix_term(term, u32::MAX, false);
         Ok(())
@@ -285,7 +470,7 @@ mod test {
     use tantivy::schema::{DateOptions, DateTimePrecision, Schema, FAST, INDEXED, STORED, TEXT};
     use tantivy::Term;
 
-    use super::{build_query, ExtractPrefixTermRanges};
+    use super::{build_query, enable_leading_wildcards, ExtractPrefixTermRanges};
     use crate::{TermRange, DYNAMIC_FIELD_NAME, SOURCE_FIELD_NAME};
 
     enum TestExpectation<'a> {
@@ -680,6 +865,113 @@ mod test {
         assert!(warmup_info.term_dict_fields.is_empty());
     }
 
+    #[test]
+    fn test_build_query_fuzzy() {
+        check_build_query_static_mode(
+            "title:helo~1",
+            Vec::new(),
+            TestExpectation::Ok("FuzzyTermQuery"),
+        );
+        check_build_query_static_mode(
+            "title:helo~3",
+            Vec::new(),
+            TestExpectation::Err("fuzzy distance must be at most 2, got 3"),
+        );
+
+        let query_with_fuzzy = query_ast_from_user_text("desc:helo~1", None)
+            .parse_user_query(&[])
+            .unwrap();
+        let (_, warmup_info) = build_query(
+            &query_with_fuzzy,
+            make_schema(true),
+            &create_default_quickwit_tokenizer_manager(),
+            &[],
+            true,
+        )
+        .unwrap();
+        assert_eq!(warmup_info.term_dict_fields.len(), 1);
+        assert!(warmup_info
+            .term_dict_fields
+            .contains(&tantivy::schema::Field::from_field_id(1)));
+    }
+
+    #[test]
+    fn test_build_query_regex() {
+        check_build_query_static_mode(
+            "title:/hel.o/",
+            Vec::new(),
+            TestExpectation::Ok("RegexQuery"),
+        );
+        check_build_query_static_mode(
+            "title:/hel(o/",
+            Vec::new(),
+            TestExpectation::Err("invalid regex `hel(o`"),
+        );
+        check_build_query_static_mode(
+            "u64_fast:/1.3/",
+            Vec::new(),
+            TestExpectation::Err("trying to run a Regex query on a non-text field"),
+        );
+        check_build_query_dynamic_mode(
+            "product.color:/bl.e/",
+            Vec::new(),
+            TestExpectation::Ok("RegexQuery"),
+        );
+
+        let query_with_regex = query_ast_from_user_text("desc:/hel.o/", None)
+            .parse_user_query(&[])
+            .unwrap();
+        let (_, warmup_info) = build_query(
+            &query_with_regex,
+            make_schema(true),
+            &create_default_quickwit_tokenizer_manager(),
+            &[],
+            true,
+        )
+        .unwrap();
+        assert_eq!(warmup_info.term_dict_fields.len(), 1);
+        assert!(warmup_info
+            .term_dict_fields
+            .contains(&tantivy::schema::Field::from_field_id(1)));
+    }
+
+    #[test]
+    fn test_build_query_wildcard() {
+        check_build_query_static_mode(
+            "title:hel*",
+            Vec::new(),
+            TestExpectation::Ok("PhrasePrefixQuery"),
+        );
+        check_build_query_static_mode(
+            "title:h*l?o",
+            Vec::new(),
+            TestExpectation::Ok("TermDictScanBudgetQuery"),
+        );
+        check_build_query_static_mode(
+            "title:*llo",
+            Vec::new(),
+            TestExpectation::Err("must be enabled with `allow_leading_wildcard`"),
+        );
+
+        let query_with_wildcard = query_ast_from_user_text("desc:?ello", None)
+            .parse_user_query(&[])
+            .unwrap();
+        let query_with_wildcard = enable_leading_wildcards(query_with_wildcard);
+        let (_, warmup_info) = build_query(
+            &query_with_wildcard,
+            make_schema(true),
+            &create_default_quickwit_tokenizer_manager(),
+            &[],
+            true,
+        )
+        .unwrap();
+        assert_eq!(warmup_info.term_dict_fields.len(), 1);
+        assert!(warmup_info
+            .term_dict_fields
+            .contains(&tantivy::schema::Field::from_field_id(1)));
+        assert!(warmup_info.term_ranges_grouped_by_field.is_empty());
+    }
+
     #[test]
     fn test_extract_phrase_prefix_position_required() {
         let schema = make_schema(false);
@@ -61,6 +61,21 @@ fn extract_unsimplified_tags_filter_ast(query_ast: QueryAst) -> UnsimplifiedTagF
             }
             collect_tag_filters_for_clause(clause_with_resolved_occur)
         }
+        QueryAst::DisjunctionMax(disjunction_max_query) => {
+            // A document matches a disjunction max query if it matches any of its disjuncts.
+            let clause_with_resolved_occur: Vec<(Occur, UnsimplifiedTagFilterAst)> =
+                disjunction_max_query
+                    .disjuncts
+                    .into_iter()
+                    .map(|child_ast| {
+                        (
+                            Occur::Should,
+                            extract_unsimplified_tags_filter_ast(child_ast),
+                        )
+                    })
+                    .collect();
+            collect_tag_filters_for_clause(clause_with_resolved_occur)
+        }
         QueryAst::Term(term_query) => UnsimplifiedTagFilterAst::Tag {
             is_present: true,
             field: term_query.field,
@@ -105,6 +120,9 @@ fn extract_unsimplified_tags_filter_ast(query_ast: QueryAst) -> UnsimplifiedTagF
                 value: phrase_prefix_query.phrase,
             }
         }
+        QueryAst::Wildcard(wildcard_query) if wildcard_query.requires_term_dict_scan() => {
+            UnsimplifiedTagFilterAst::Uninformative
+        }
         QueryAst::Wildcard(wildcard_query) => {
             // TODO same as FullText above.
             UnsimplifiedTagFilterAst::Tag {
@@ -117,7 +135,7 @@ fn extract_unsimplified_tags_filter_ast(query_ast: QueryAst) -> UnsimplifiedTagF
         QueryAst::UserInput(_user_text_query) => {
             panic!("Extract unsimplified should only be called on AST without UserInputQuery.");
         }
-        QueryAst::FieldPresence(_) => UnsimplifiedTagFilterAst::Uninformative,
+        QueryAst::FieldPresence(_) | QueryAst::Regex(_) => UnsimplifiedTagFilterAst::Uninformative,
     }
 }
 
@@ -217,14 +235,19 @@ impl Display for TagFilterAst {
 impl TagFilterAst {
     /// Evaluates the tag filter predicate over a set of tags.
     pub fn evaluate(&self, tag_set: &BTreeSet<String>) -> bool {
+        self.evaluate_with(&|tag| tag_set.contains(tag))
+    }
+
+    /// Evaluates the tag filter predicate, given a function telling whether a tag is present.
+    pub fn evaluate_with(&self, tag_is_present: &impl Fn(&str) -> bool) -> bool {
         match self {
-            TagFilterAst::And(children) => {
-                children.iter().all(|child_ast| child_ast.evaluate(tag_set))
-            }
-            TagFilterAst::Or(children) => {
-                children.iter().any(|child_ast| child_ast.evaluate(tag_set))
-            }
-            TagFilterAst::Tag { is_present, tag } => tag_set.contains(tag) == *is_present,
+            TagFilterAst::And(children) => children
+                .iter()
+                .all(|child_ast| child_ast.evaluate_with(tag_is_present)),
+            TagFilterAst::Or(children) => children
+                .iter()
+                .any(|child_ast| child_ast.evaluate_with(tag_is_present)),
+            TagFilterAst::Tag { is_present, tag } => tag_is_present(tag) == *is_present,
         }
     }
 }
@@ -399,6 +422,7 @@ mod test {
             default_fields: None,
             default_operator: BooleanOperand::Or,
             lenient: false,
+            allow_leading_wildcard: false,
         }
         .into();
         let parsed_query_ast = query_ast.parse_user_query(&[]).unwrap();
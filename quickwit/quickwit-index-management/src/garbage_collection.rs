@@ -28,18 +28,19 @@ use itertools::Itertools;
 use quickwit_common::pretty::PrettySample;
 use quickwit_common::Progress;
 use quickwit_metastore::{
-    ListSplitsQuery, ListSplitsRequestExt, MetastoreServiceStreamSplitsExt, SplitInfo,
-    SplitMetadata, SplitState,
+    pinned_split_ids, ListSplitsQuery, ListSplitsRequestExt, MetastoreServiceStreamSplitsExt,
+    PointInTime, SplitInfo, SplitMetadata, SplitState,
 };
 use quickwit_proto::metastore::{
-    DeleteSplitsRequest, ListSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError,
+    serde_utils, DeletePointInTimesRequest, DeleteSplitsRequest, ListPointInTimesRequest,
+    ListSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError, MetastoreResult,
     MetastoreService, MetastoreServiceClient,
 };
 use quickwit_proto::types::{IndexUid, SplitId};
 use quickwit_storage::{BulkDeleteError, Storage};
 use thiserror::Error;
 use time::OffsetDateTime;
-use tracing::{error, instrument};
+use tracing::{error, info, instrument};
 
 /// The maximum number of splits that the GC should delete per attempt.
 const DELETE_SPLITS_BATCH_SIZE: usize = 10_000;
@@ -76,8 +77,69 @@ pub struct SplitRemovalInfo {
     pub failed_splits: Vec<SplitInfo>,
 }
 
+/// Loads the IDs of the splits pinned by the points in time that have not expired yet, per index.
+/// Unless `dry_run` is set, expired points in time are removed along the way.
+async fn load_pinned_split_ids(
+    metastore: &MetastoreServiceClient,
+    dry_run: bool,
+    progress_opt: Option<&Progress>,
+) -> anyhow::Result<HashMap<IndexUid, HashSet<SplitId>>> {
+    let list_point_in_times_response = protect_future(
+        progress_opt,
+        metastore.list_point_in_times(ListPointInTimesRequest::default()),
+    )
+    .await
+    .context("failed to list points in time")?;
+    let point_in_times: Vec<PointInTime> = list_point_in_times_response
+        .point_in_times_json
+        .iter()
+        .map(|point_in_time_json| serde_utils::from_json_str(point_in_time_json))
+        .collect::<MetastoreResult<_>>()?;
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+
+    if !dry_run {
+        let expired_point_in_time_keys: Vec<String> = point_in_times
+            .iter()
+            .filter(|point_in_time| point_in_time.is_expired(now))
+            .map(|point_in_time| point_in_time.point_in_time_key.clone())
+            .collect();
+
+        if !expired_point_in_time_keys.is_empty() {
+            info!(
+                "removing {} expired point(s) in time",
+                expired_point_in_time_keys.len()
+            );
+            let delete_point_in_times_request = DeletePointInTimesRequest {
+                point_in_time_keys: expired_point_in_time_keys,
+            };
+            if let Err(error) = protect_future(
+                progress_opt,
+                metastore.delete_point_in_times(delete_point_in_times_request),
+            )
+            .await
+            {
+                error!(%error, "failed to remove expired points in time");
+            }
+        }
+    }
+    Ok(pinned_split_ids(&point_in_times, now))
+}
+
+/// Returns `true` if the split is not pinned by a point in time.
+fn is_split_deletable(
+    split_metadata: &SplitMetadata,
+    pinned_split_ids_per_index: &HashMap<IndexUid, HashSet<SplitId>>,
+) -> bool {
+    pinned_split_ids_per_index
+        .get(&split_metadata.index_uid)
+        .map(|pinned_split_ids| !pinned_split_ids.contains(&split_metadata.split_id))
+        .unwrap_or(true)
+}
+
 /// Detect all dangling splits and associated files from the index and removes them.
 ///
+/// Splits pinned by a point in time are not deleted until the point in time expires.
+///
 /// * `indexes` - The target index uids and storages.
 /// * `storage - The storage managing the target index.
 /// * `metastore` - The metastore managing the target index.
@@ -120,6 +182,9 @@ pub async fn run_garbage_collect(
     .collect_splits_metadata()
     .await?;
 
+    let pinned_split_ids_per_index =
+        load_pinned_split_ids(&metastore, dry_run, progress_opt).await?;
+
     if dry_run {
         let marked_for_deletion_query =
             list_splits_query_for_index_uids.with_split_state(SplitState::MarkedForDeletion);
@@ -136,6 +201,7 @@ pub async fn run_garbage_collect(
 
         let candidate_entries: Vec<SplitInfo> = splits_marked_for_deletion
             .into_iter()
+            .filter(|split| is_split_deletable(split, &pinned_split_ids_per_index))
             .map(|split| split.as_split_info())
             .collect();
         return Ok(SplitRemovalInfo {
@@ -169,6 +235,7 @@ pub async fn run_garbage_collect(
         updated_before_timestamp,
         metastore,
         indexes,
+        &pinned_split_ids_per_index,
         progress_opt,
     )
     .await)
@@ -262,15 +329,17 @@ async fn list_splits_metadata(
 
 /// Removes any splits marked for deletion which haven't been
 /// updated after `updated_before_timestamp` in batches of 1000 splits.
+/// Splits pinned by a point in time are skipped.
 ///
 /// The aim of this is to spread the load out across a longer period
 /// rather than short, heavy bursts on the metastore and storage system itself.
-#[instrument(skip(index_uids, storages, metastore, progress_opt), fields(num_indexes=%index_uids.len()))]
+#[instrument(skip(index_uids, storages, metastore, pinned_split_ids_per_index, progress_opt), fields(num_indexes=%index_uids.len()))]
 async fn delete_splits_marked_for_deletion_several_indexes(
     index_uids: Vec<IndexUid>,
     updated_before_timestamp: i64,
     metastore: MetastoreServiceClient,
     storages: HashMap<IndexUid, Arc<dyn Storage>>,
+    pinned_split_ids_per_index: &HashMap<IndexUid, HashSet<SplitId>>,
     progress_opt: Option<&Progress>,
 ) -> SplitRemovalInfo {
     let mut split_removal_info = SplitRemovalInfo::default();
@@ -305,9 +374,18 @@ async fn delete_splits_marked_for_deletion_several_indexes(
         if num_splits_to_delete == 0 {
             break;
         }
+        let deletable_splits_metadata: Vec<SplitMetadata> = splits_metadata_to_delete
+            .into_iter()
+            .filter(|split| is_split_deletable(split, pinned_split_ids_per_index))
+            .collect();
 
+        if deletable_splits_metadata.is_empty() {
+            // The whole batch is pinned by points in time: listing again would return the same
+            // splits, so we leave the remaining splits for the next run.
+            break;
+        }
         let splits_metadata_to_delete_per_index: HashMap<IndexUid, Vec<SplitMetadata>> =
-            splits_metadata_to_delete
+            deletable_splits_metadata
                 .into_iter()
                 .map(|meta| (meta.index_uid.clone(), meta))
                 .into_group_map();
@@ -434,6 +512,7 @@ pub async fn delete_splits_from_storage_and_metastore(
 
 #[cfg(test)]
 mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
     use std::time::Duration;
 
     use itertools::Itertools;
@@ -441,10 +520,12 @@ mod tests {
     use quickwit_config::IndexConfig;
     use quickwit_metastore::{
         metastore_for_test, CreateIndexRequestExt, ListSplitsQuery,
-        MetastoreServiceStreamSplitsExt, SplitMetadata, SplitState, StageSplitsRequestExt,
+        MetastoreServiceStreamSplitsExt, PointInTime, SplitMetadata, SplitState,
+        StageSplitsRequestExt,
     };
     use quickwit_proto::metastore::{
-        CreateIndexRequest, EntityKind, MockMetastoreService, StageSplitsRequest,
+        CreateIndexRequest, CreatePointInTimeRequest, EntityKind, ListPointInTimesResponse,
+        MockMetastoreService, StageSplitsRequest,
     };
     use quickwit_proto::types::IndexUid;
     use quickwit_storage::{
@@ -663,13 +744,17 @@ mod tests {
 
     #[tokio::test]
     async fn test_run_gc_deletes_splits_with_no_split() {
-        // Test that we make only 2 calls to the metastore.
+        // Test that we make only 2 calls to the metastore to list splits.
         let storage = storage_for_test();
         let mut mock_metastore = MockMetastoreService::new();
         mock_metastore
             .expect_list_splits()
             .times(2)
             .returning(|_| Ok(ServiceStream::empty()));
+        mock_metastore
+            .expect_list_point_in_times()
+            .times(1)
+            .returning(|_| Ok(ListPointInTimesResponse::default()));
         run_garbage_collect(
             hashmap(
                 IndexUid::new_with_random_ulid("index-test-gc-deletes"),
@@ -685,6 +770,112 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_run_gc_skips_splits_pinned_by_point_in_time() {
+        let storage = storage_for_test();
+        let metastore = metastore_for_test();
+
+        let index_id = "test-run-gc-pit--index";
+        let index_uri = format!("ram:///indexes/{index_id}");
+        let index_config = IndexConfig::for_test(index_id, &index_uri);
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let index_uid: IndexUid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid()
+            .clone();
+
+        let split_ids = ["pinned-split", "expired-split", "unpinned-split"];
+        let splits_metadata = split_ids.map(|split_id| SplitMetadata {
+            split_id: split_id.to_string(),
+            index_uid: index_uid.clone(),
+            ..Default::default()
+        });
+        let stage_splits_request =
+            StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), splits_metadata)
+                .unwrap();
+        metastore.stage_splits(stage_splits_request).await.unwrap();
+        let mark_splits_for_deletion_request = MarkSplitsForDeletionRequest::new(
+            index_uid.clone(),
+            split_ids.map(|split_id| split_id.to_string()).to_vec(),
+        );
+        metastore
+            .mark_splits_for_deletion(mark_splits_for_deletion_request)
+            .await
+            .unwrap();
+
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let point_in_times = [
+            ("live-pit", "pinned-split", now + 3600),
+            ("expired-pit", "expired-split", now - 1),
+        ];
+        for (point_in_time_key, split_id, expire_at) in point_in_times {
+            let point_in_time = PointInTime {
+                point_in_time_key: point_in_time_key.to_string(),
+                split_ids: BTreeMap::from_iter([(
+                    index_uid.clone(),
+                    BTreeSet::from_iter([split_id.to_string()]),
+                )]),
+                expire_at,
+            };
+            let create_point_in_time_request = CreatePointInTimeRequest {
+                point_in_time_json: serde_utils::to_json_str(&point_in_time).unwrap(),
+                overwrite: false,
+            };
+            metastore
+                .create_point_in_time(create_point_in_time_request)
+                .await
+                .unwrap();
+        }
+
+        let split_removal_info = run_garbage_collect(
+            hashmap(index_uid.clone(), storage.clone()),
+            metastore.clone(),
+            Duration::from_secs(30),
+            Duration::from_secs(0),
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+        let removed_split_ids: Vec<String> = split_removal_info
+            .removed_split_entries
+            .into_iter()
+            .map(|split_info| split_info.split_id)
+            .sorted()
+            .collect();
+        assert_eq!(removed_split_ids, ["expired-split", "unpinned-split"]);
+
+        let query = ListSplitsQuery::for_index(index_uid);
+        let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query).unwrap();
+        let remaining_splits = metastore
+            .list_splits(list_splits_request)
+            .await
+            .unwrap()
+            .collect_splits()
+            .await
+            .unwrap();
+        assert_eq!(remaining_splits.len(), 1);
+        assert_eq!(remaining_splits[0].split_metadata.split_id, "pinned-split");
+
+        // The expired point in time was removed from the metastore.
+        let point_in_time_keys: Vec<String> = metastore
+            .list_point_in_times(ListPointInTimesRequest::default())
+            .await
+            .unwrap()
+            .point_in_times_json
+            .iter()
+            .map(|point_in_time_json| {
+                serde_utils::from_json_str::<PointInTime>(point_in_time_json)
+                    .unwrap()
+                    .point_in_time_key
+            })
+            .collect();
+        assert_eq!(point_in_time_keys, ["live-pit"]);
+    }
+
     #[tokio::test]
     async fn test_delete_splits_from_storage_and_metastore_happy_path() {
         let storage = storage_for_test();
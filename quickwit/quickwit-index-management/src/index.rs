@@ -34,10 +34,10 @@ use quickwit_metastore::{
     MetastoreServiceStreamSplitsExt, SplitInfo, SplitMetadata, SplitState,
 };
 use quickwit_proto::metastore::{
-    serde_utils, AddSourceRequest, CreateIndexRequest, DeleteIndexRequest, EntityKind,
-    IndexMetadataRequest, ListIndexesMetadataRequest, ListSplitsRequest,
-    MarkSplitsForDeletionRequest, MetastoreError, MetastoreService, MetastoreServiceClient,
-    ResetSourceCheckpointRequest,
+    serde_utils, AddSourceRequest, CreateIndexRequest, DeleteIndexAliasesRequest,
+    DeleteIndexRequest, EntityKind, IndexMetadataRequest, ListIndexesMetadataRequest,
+    ListSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError, MetastoreService,
+    MetastoreServiceClient, ResetSourceCheckpointRequest,
 };
 use quickwit_proto::types::{IndexUid, SplitId};
 use quickwit_proto::{ServiceError, ServiceErrorCode};
@@ -117,6 +117,10 @@ impl IndexService {
         self.metastore.clone()
     }
 
+    pub fn storage_resolver(&self) -> StorageResolver {
+        self.storage_resolver.clone()
+    }
+
     /// Creates an index from `IndexConfig`.
     pub async fn create_index(
         &mut self,
@@ -234,6 +238,22 @@ impl IndexService {
         };
         self.metastore.delete_index(delete_index_request).await?;
 
+        // The index is gone at this point, so failing to clean up its aliases does not fail the
+        // deletion. The aliases still pointing to it then target a missing index.
+        let delete_index_aliases_request = DeleteIndexAliasesRequest {
+            index_ids: vec![index_id.to_string()],
+        };
+        if let Err(error) = self
+            .metastore
+            .delete_index_aliases(delete_index_aliases_request)
+            .await
+        {
+            error!(
+                index_id=%index_id,
+                error=?error,
+                "failed to remove deleted index from its aliases"
+            );
+        }
         Ok(deleted_splits)
     }
 
@@ -529,11 +549,17 @@ pub async fn validate_storage_uri(
 mod tests {
 
     use quickwit_common::uri::Uri;
-    use quickwit_config::{IndexConfig, CLI_SOURCE_ID, INGEST_API_SOURCE_ID, INGEST_V2_SOURCE_ID};
+    use quickwit_config::{
+        IndexAliasAction, IndexAliasConfig, IndexConfig, CLI_SOURCE_ID, INGEST_API_SOURCE_ID,
+        INGEST_V2_SOURCE_ID,
+    };
     use quickwit_metastore::{
-        metastore_for_test, MetastoreServiceExt, SplitMetadata, StageSplitsRequestExt,
+        metastore_for_test, ListIndexAliasesResponseExt, MetastoreServiceExt, SplitMetadata,
+        StageSplitsRequestExt, UpdateIndexAliasesRequestExt,
+    };
+    use quickwit_proto::metastore::{
+        ListIndexAliasesRequest, StageSplitsRequest, UpdateIndexAliasesRequest,
     };
-    use quickwit_proto::metastore::StageSplitsRequest;
     use quickwit_storage::PutPayload;
 
     use super::*;
@@ -629,6 +655,18 @@ mod tests {
         storage.put(split_path, payload).await.unwrap();
         assert!(storage.exists(split_path).await.unwrap());
 
+        let update_index_aliases_request =
+            UpdateIndexAliasesRequest::try_from_actions(&[IndexAliasAction::Add {
+                index_ids: vec![index_id.to_string()],
+                aliases: vec!["test-alias".to_string()],
+                config: IndexAliasConfig::default(),
+            }])
+            .unwrap();
+        metastore
+            .update_index_aliases(update_index_aliases_request)
+            .await
+            .unwrap();
+
         let split_infos = index_service.delete_index(index_id, false).await.unwrap();
         assert_eq!(split_infos.len(), 1);
 
@@ -642,5 +680,13 @@ mod tests {
             .unwrap();
         assert!(splits.is_empty());
         assert!(!storage.exists(split_path).await.unwrap());
+
+        let index_aliases = metastore
+            .list_index_aliases(ListIndexAliasesRequest {})
+            .await
+            .unwrap()
+            .deserialize_index_aliases()
+            .unwrap();
+        assert!(index_aliases.is_empty());
     }
 }
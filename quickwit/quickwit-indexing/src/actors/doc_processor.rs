@@ -45,6 +45,7 @@ use tokio::runtime::Handle;
 #[cfg(feature = "vrl")]
 use super::vrl_processing::*;
 use crate::actors::Indexer;
+use crate::detection_rules::DetectionRuleMatcher;
 use crate::models::{
     NewPublishLock, NewPublishToken, ProcessedDoc, ProcessedDocBatch, PublishLock, RawDocBatch,
 };
@@ -417,6 +418,7 @@ pub struct DocProcessor {
     #[cfg(feature = "vrl")]
     transform_opt: Option<VrlProgram>,
     input_format: SourceInputFormat,
+    detection_rule_matcher_opt: Option<DetectionRuleMatcher>,
 }
 
 impl DocProcessor {
@@ -443,9 +445,18 @@ impl DocProcessor {
                 .map(VrlProgram::try_from_transform_config)
                 .transpose()?,
             input_format,
+            detection_rule_matcher_opt: None,
         })
     }
 
+    /// Sets the matcher the processed documents are matched against before being indexed.
+    pub(crate) fn set_detection_rule_matcher(
+        &mut self,
+        detection_rule_matcher: DetectionRuleMatcher,
+    ) {
+        self.detection_rule_matcher_opt = Some(detection_rule_matcher);
+    }
+
     // Extract a timestamp from a tantivy document.
     //
     // If the timestamp is set up in the docmapper and the timestamp is missing,
@@ -466,7 +477,15 @@ impl DocProcessor {
         Ok(Some(timestamp))
     }
 
-    fn process_raw_doc(&mut self, raw_doc: Bytes, processed_docs: &mut Vec<ProcessedDoc>) {
+    /// Processes a raw document and appends the resulting documents to `processed_docs`. When
+    /// detection rules are set, the JSON objects the documents are built from are appended to
+    /// `json_docs` as well.
+    fn process_raw_doc(
+        &mut self,
+        raw_doc: Bytes,
+        processed_docs: &mut Vec<ProcessedDoc>,
+        json_docs: &mut Vec<JsonObject>,
+    ) {
         let num_bytes = raw_doc.len();
 
         #[cfg(feature = "vrl")]
@@ -475,13 +494,23 @@ impl DocProcessor {
         let transform_opt: Option<&mut VrlProgram> = None;
 
         for json_doc_result in parse_raw_doc(self.input_format, raw_doc, num_bytes, transform_opt) {
-            let processed_doc_result =
-                json_doc_result.and_then(|json_doc| self.process_json_doc(json_doc));
+            let processed_doc_result = json_doc_result.and_then(|json_doc| {
+                let json_obj_opt = self
+                    .detection_rule_matcher_opt
+                    .as_ref()
+                    .map(|_| json_doc.json_obj.clone());
+                self.process_json_doc(json_doc)
+                    .map(|processed_doc| (processed_doc, json_obj_opt))
+            });
 
             match processed_doc_result {
-                Ok(processed_doc) => {
+                Ok((processed_doc, json_obj_opt)) => {
                     self.counters.record_valid(processed_doc.num_bytes as u64);
                     processed_docs.push(processed_doc);
+
+                    if let Some(json_obj) = json_obj_opt {
+                        json_docs.push(json_obj);
+                    }
                 }
                 Err(error) => {
                     rate_limited_warn!(
@@ -578,12 +607,17 @@ impl Handler<RawDocBatch> for DocProcessor {
             return Ok(());
         }
         let mut processed_docs: Vec<ProcessedDoc> = Vec::with_capacity(raw_doc_batch.docs.len());
+        let mut json_docs: Vec<JsonObject> = Vec::new();
 
         for raw_doc in raw_doc_batch.docs {
             let _protected_zone_guard = ctx.protect_zone();
-            self.process_raw_doc(raw_doc, &mut processed_docs);
+            self.process_raw_doc(raw_doc, &mut processed_docs, &mut json_docs);
             ctx.record_progress();
         }
+        if let Some(detection_rule_matcher) = &self.detection_rule_matcher_opt {
+            let _protected_zone_guard = ctx.protect_zone();
+            detection_rule_matcher.match_docs(&processed_docs, &json_docs);
+        }
         let processed_doc_batch = ProcessedDocBatch::new(
             processed_docs,
             raw_doc_batch.checkpoint_delta,
@@ -47,6 +47,7 @@ use crate::actors::publisher::PublisherType;
 use crate::actors::sequencer::Sequencer;
 use crate::actors::uploader::UploaderType;
 use crate::actors::{Indexer, Packager, Publisher, Uploader};
+use crate::detection_rules::{spawn_detection_rule_sinks, DetectionRuleMatcher};
 use crate::merge_policy::MergePolicy;
 use crate::models::IndexingStatistics;
 use crate::source::{
@@ -378,7 +379,13 @@ impl IndexingPipeline {
 
         // Packager
         let tag_fields = self.params.doc_mapper.tag_named_fields()?;
-        let packager = Packager::new("Packager", tag_fields, uploader_mailbox);
+        let bloom_filter_fields = self.params.doc_mapper.bloom_filter_named_fields()?;
+        let packager = Packager::new(
+            "Packager",
+            tag_fields,
+            bloom_filter_fields,
+            uploader_mailbox,
+        );
         let (packager_mailbox, packager_handle) = ctx
             .spawn_actor()
             .set_kill_switch(self.kill_switch.clone())
@@ -411,7 +418,7 @@ impl IndexingPipeline {
             .set_kill_switch(self.kill_switch.clone())
             .spawn(indexer);
 
-        let doc_processor = DocProcessor::try_new(
+        let mut doc_processor = DocProcessor::try_new(
             index_id.to_string(),
             source_id.to_string(),
             self.params.doc_mapper.clone(),
@@ -419,6 +426,18 @@ impl IndexingPipeline {
             self.params.source_config.transform_config.clone(),
             self.params.source_config.input_format,
         )?;
+        let detection_rules = &self.params.indexing_settings.detection_rules;
+
+        if !detection_rules.is_empty() {
+            let match_batch_tx = spawn_detection_rule_sinks(self.params.queues_dir_path.clone());
+            let detection_rule_matcher = DetectionRuleMatcher::try_new(
+                index_id.to_string(),
+                &*self.params.doc_mapper,
+                detection_rules,
+                match_batch_tx,
+            )?;
+            doc_processor.set_detection_rule_matcher(detection_rule_matcher);
+        }
         let (doc_processor_mailbox, doc_processor_handle) = ctx
             .spawn_actor()
             .set_backpressure_micros_counter(
@@ -278,7 +278,13 @@ impl MergePipeline {
 
         // Merge Packager
         let tag_fields = self.params.doc_mapper.tag_named_fields()?;
-        let merge_packager = Packager::new("MergePackager", tag_fields, merge_uploader_mailbox);
+        let bloom_filter_fields = self.params.doc_mapper.bloom_filter_named_fields()?;
+        let merge_packager = Packager::new(
+            "MergePackager",
+            tag_fields,
+            bloom_filter_fields,
+            merge_uploader_mailbox,
+        );
         let (merge_packager_mailbox, merge_packager_handler) = ctx
             .spawn_actor()
             .set_kill_switch(self.kill_switch.clone())
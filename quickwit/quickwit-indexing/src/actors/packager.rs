@@ -17,8 +17,9 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io;
+use std::net::Ipv6Addr;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -27,17 +28,20 @@ use async_trait::async_trait;
 use fail::fail_point;
 use itertools::Itertools;
 use quickwit_actors::{Actor, ActorContext, ActorExitStatus, Handler, Mailbox, QueueCapacity};
+use quickwit_common::bloom_filter::{BloomFilter, SplitBloomFilters};
 use quickwit_common::runtimes::RuntimeType;
 use quickwit_common::temp_dir::TempDirectory;
 use quickwit_directories::write_hotcache;
 use quickwit_doc_mapper::tag_pruning::append_to_tag_set;
 use quickwit_doc_mapper::NamedField;
+use quickwit_metastore::FieldValueRange;
 use quickwit_proto::search::{
     serialize_split_fields, ListFieldType, ListFields, ListFieldsEntryResponse,
 };
+use tantivy::columnar::{Column, DynamicColumn, HasAssociatedColumnType};
 use tantivy::index::FieldMetadata;
 use tantivy::schema::{FieldType, Type};
-use tantivy::{InvertedIndexReader, ReloadPolicy, SegmentMeta};
+use tantivy::{InvertedIndexReader, ReloadPolicy, Searcher, SegmentMeta};
 use tokio::runtime::Handle;
 use tracing::{debug, info, instrument, warn};
 
@@ -48,6 +52,10 @@ const MAX_VALUES_PER_TAG_FIELD: usize = if cfg!(any(test, feature = "testsuite")
     1000
 };
 
+/// Probability for the Bloom filter of a field to report a value absent from the split as
+/// present. About 10 bits are used per distinct value.
+const BLOOM_FILTER_FALSE_POSITIVE_RATE: f64 = 0.01;
+
 use crate::actors::Uploader;
 use crate::models::{
     EmptySplit, IndexedSplit, IndexedSplitBatch, PackagedSplit, PackagedSplitBatch,
@@ -70,18 +78,22 @@ pub struct Packager {
     uploader_mailbox: Mailbox<Uploader>,
     /// List of tag fields ([`Vec<NamedField>`]) defined in the index config.
     tag_fields: Vec<NamedField>,
+    /// List of the fields with a Bloom filter defined in the index config.
+    bloom_filter_fields: Vec<NamedField>,
 }
 
 impl Packager {
     pub fn new(
         actor_name: &'static str,
         tag_fields: Vec<NamedField>,
+        bloom_filter_fields: Vec<NamedField>,
         uploader_mailbox: Mailbox<Uploader>,
     ) -> Packager {
         Packager {
             actor_name,
             uploader_mailbox,
             tag_fields,
+            bloom_filter_fields,
         }
     }
 
@@ -92,8 +104,13 @@ impl Packager {
     ) -> anyhow::Result<PackagedSplit> {
         let segment_metas = split.index.searchable_segment_metas()?;
         assert_eq!(segment_metas.len(), 1);
-        let packaged_split =
-            create_packaged_split(&segment_metas[..], split, &self.tag_fields, ctx)?;
+        let packaged_split = create_packaged_split(
+            &segment_metas[..],
+            split,
+            &self.tag_fields,
+            &self.bloom_filter_fields,
+            ctx,
+        )?;
         Ok(packaged_split)
     }
 }
@@ -277,6 +294,7 @@ fn create_packaged_split(
     segment_metas: &[SegmentMeta],
     split: IndexedSplit,
     tag_fields: &[NamedField],
+    bloom_filter_fields: &[NamedField],
     ctx: &ActorContext<Packager>,
 ) -> anyhow::Result<PackagedSplit> {
     debug!(split_id = split.split_id(), "create-packaged-split");
@@ -314,6 +332,20 @@ fn create_packaged_split(
 
     ctx.record_progress();
 
+    debug!(split_id = split.split_id(), bloom_filter_fields =? bloom_filter_fields, "build-bloom-filters");
+    let split_bloom_filters =
+        build_split_bloom_filters(bloom_filter_fields, &index_reader.searcher())?;
+    let serialized_split_bloom_filters = if split_bloom_filters.is_empty() {
+        Vec::new()
+    } else {
+        split_bloom_filters.serialize()
+    };
+    ctx.record_progress();
+
+    debug!(split_id = split.split_id(), "compute-field-value-ranges");
+    let field_value_ranges = compute_field_value_ranges(&index_reader.searcher())?;
+    ctx.record_progress();
+
     debug!(split_id = split.split_id(), "build-hotcache");
     let mut hotcache_bytes = Vec::new();
     build_hotcache(split.split_scratch_directory.path(), &mut hotcache_bytes)?;
@@ -323,15 +355,107 @@ fn create_packaged_split(
 
     let packaged_split = PackagedSplit {
         serialized_split_fields,
+        serialized_split_bloom_filters,
         split_attrs: split.split_attrs,
         split_scratch_directory: split.split_scratch_directory,
         tags,
+        field_value_ranges,
         split_files,
         hotcache_bytes,
     };
     Ok(packaged_split)
 }
 
+/// Computes the min and max values of the numeric and IP fast fields.
+///
+/// Fields without any value, and `f64` fields containing NaN, are skipped.
+fn compute_field_value_ranges(
+    searcher: &Searcher,
+) -> anyhow::Result<BTreeMap<String, FieldValueRange>> {
+    let schema = searcher.schema();
+    let mut field_value_ranges = BTreeMap::new();
+
+    for (_field, field_entry) in schema.fields() {
+        if !field_entry.is_fast() {
+            continue;
+        }
+        let field_name = field_entry.name();
+        let field_value_range_opt = match field_entry.field_type().value_type() {
+            Type::U64 => column_min_max::<u64>(searcher, field_name)?
+                .map(|(min, max)| FieldValueRange::U64 { min, max }),
+            Type::I64 => column_min_max::<i64>(searcher, field_name)?
+                .map(|(min, max)| FieldValueRange::I64 { min, max }),
+            Type::F64 => column_min_max::<f64>(searcher, field_name)?
+                .filter(|(min, max)| !min.is_nan() && !max.is_nan())
+                .map(|(min, max)| FieldValueRange::F64 { min, max }),
+            Type::IpAddr => column_min_max::<Ipv6Addr>(searcher, field_name)?
+                .map(|(min, max)| FieldValueRange::Ip { min, max }),
+            _ => None,
+        };
+        if let Some(field_value_range) = field_value_range_opt {
+            field_value_ranges.insert(field_name.to_string(), field_value_range);
+        }
+    }
+    Ok(field_value_ranges)
+}
+
+/// Returns the min and max values of a fast field over all segments, or `None` if the field does
+/// not have any value.
+fn column_min_max<T>(searcher: &Searcher, field_name: &str) -> anyhow::Result<Option<(T, T)>>
+where
+    T: HasAssociatedColumnType,
+    DynamicColumn: Into<Option<Column<T>>>,
+{
+    let mut min_max_opt: Option<(T, T)> = None;
+
+    for segment_reader in searcher.segment_readers() {
+        let Some(column) = segment_reader.fast_fields().column_opt::<T>(field_name)? else {
+            continue;
+        };
+        if column.values.num_vals() == 0 {
+            continue;
+        }
+        let (column_min, column_max) = (column.min_value(), column.max_value());
+        min_max_opt = Some(match min_max_opt {
+            Some((min, max)) => (
+                if column_min < min { column_min } else { min },
+                if column_max > max { column_max } else { max },
+            ),
+            None => (column_min, column_max),
+        });
+    }
+    Ok(min_max_opt)
+}
+
+/// Builds the Bloom filters of the terms of the given fields.
+fn build_split_bloom_filters(
+    bloom_filter_fields: &[NamedField],
+    searcher: &Searcher,
+) -> anyhow::Result<SplitBloomFilters> {
+    let mut split_bloom_filters = SplitBloomFilters::default();
+    for named_field in bloom_filter_fields {
+        let inverted_indexes = searcher
+            .segment_readers()
+            .iter()
+            .map(|segment| segment.inverted_index(named_field.field))
+            .collect::<Result<Vec<_>, _>>()?;
+        let num_terms = inverted_indexes
+            .iter()
+            .map(|inverted_index| inverted_index.terms().num_terms())
+            .sum::<usize>();
+        let mut bloom_filter =
+            BloomFilter::with_num_items(num_terms, BLOOM_FILTER_FALSE_POSITIVE_RATE);
+        for inverted_index in &inverted_indexes {
+            let mut terms_streamer = inverted_index.terms().stream()?;
+            while let Some((term_data, _)) = terms_streamer.next() {
+                bloom_filter.insert(term_data);
+            }
+        }
+        split_bloom_filters.insert(named_field.name.clone(), bloom_filter);
+    }
+    Ok(split_bloom_filters)
+}
+
 /// Serializes the Split fields.
 ///
 /// `fields_metadata` has to be sorted.
@@ -456,8 +580,10 @@ mod tests {
         let tag_many = schema_builder.add_text_field("tag_many", STRING);
         let tag_u64 =
             schema_builder.add_u64_field("tag_u64", NumericOptions::default().set_indexed());
-        let tag_i64 =
-            schema_builder.add_i64_field("tag_i64", NumericOptions::default().set_indexed());
+        let tag_i64 = schema_builder.add_i64_field(
+            "tag_i64",
+            NumericOptions::default().set_indexed().set_fast(),
+        );
         let tag_f64 =
             schema_builder.add_f64_field("tag_f64", NumericOptions::default().set_indexed());
         let tag_bool =
@@ -566,7 +692,8 @@ mod tests {
                 "tag_str", "tag_many", "tag_u64", "tag_i64", "tag_f64", "tag_bool",
             ],
         );
-        let packager = Packager::new("TestPackager", tag_fields, mailbox);
+        let bloom_filter_fields = get_tag_fields(indexed_split.index.schema(), &["tag_str"]);
+        let packager = Packager::new("TestPackager", tag_fields, bloom_filter_fields, mailbox);
         let (packager_mailbox, packager_handle) = universe.spawn_builder().spawn(packager);
         packager_mailbox
             .send_message(IndexedSplitBatch {
@@ -603,6 +730,16 @@ mod tests {
                 "tag_u64:42"
             ]
         );
+        let split_bloom_filters =
+            SplitBloomFilters::deserialize(&split.serialized_split_bloom_filters)?;
+        assert!(split_bloom_filters.has_field("tag_str"));
+        assert!(split_bloom_filters.may_contain("tag_str", b"value"));
+        assert!(!split_bloom_filters.may_contain("tag_str", b"other-value"));
+        assert_eq!(
+            split.field_value_ranges.get("tag_i64"),
+            Some(&FieldValueRange::I64 { min: -42, max: -42 })
+        );
+        assert!(!split.field_value_ranges.contains_key("tag_u64"));
         assert_eq!(
             split.split_attrs.time_range,
             Some(
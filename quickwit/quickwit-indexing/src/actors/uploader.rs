@@ -319,12 +319,14 @@ impl Handler<PackagedSplitBatch> for Uploader {
                     let split_streamer = SplitPayloadBuilder::get_split_payload(
                         &packaged_split.split_files,
                         &packaged_split.serialized_split_fields,
+                        &packaged_split.serialized_split_bloom_filters,
                         &packaged_split.hotcache_bytes,
                     )?;
                     let split_metadata = create_split_metadata(
                         &merge_policy,
                         &packaged_split.split_attrs,
                         packaged_split.tags.clone(),
+                        packaged_split.field_value_ranges.clone(),
                         split_streamer.footer_range.start..split_streamer.footer_range.end,
                     );
 
@@ -468,6 +470,7 @@ async fn upload_split(
     let split_streamer = SplitPayloadBuilder::get_split_payload(
         &packaged_split.split_files,
         &packaged_split.serialized_split_fields,
+        &packaged_split.serialized_split_bloom_filters,
         &packaged_split.hotcache_bytes,
     )?;
 
@@ -566,8 +569,10 @@ mod tests {
                         num_merge_ops: 0,
                     },
                     serialized_split_fields: Vec::new(),
+                    serialized_split_bloom_filters: Vec::new(),
                     split_scratch_directory,
                     tags: Default::default(),
+                    field_value_ranges: Default::default(),
                     hotcache_bytes: Vec::new(),
                     split_files: Vec::new(),
                 }],
@@ -679,8 +684,10 @@ mod tests {
                 num_merge_ops: 0,
             },
             serialized_split_fields: Vec::new(),
+            serialized_split_bloom_filters: Vec::new(),
             split_scratch_directory: split_scratch_directory_1,
             tags: Default::default(),
+            field_value_ranges: Default::default(),
             split_files: Vec::new(),
             hotcache_bytes: Vec::new(),
         };
@@ -706,8 +713,10 @@ mod tests {
                 num_merge_ops: 0,
             },
             serialized_split_fields: Vec::new(),
+            serialized_split_bloom_filters: Vec::new(),
             split_scratch_directory: split_scratch_directory_2,
             tags: Default::default(),
+            field_value_ranges: Default::default(),
             split_files: Vec::new(),
             hotcache_bytes: Vec::new(),
         };
@@ -825,8 +834,10 @@ mod tests {
                         num_merge_ops: 0,
                     },
                     serialized_split_fields: Vec::new(),
+                    serialized_split_bloom_filters: Vec::new(),
                     split_scratch_directory,
                     tags: Default::default(),
+                    field_value_ranges: Default::default(),
                     hotcache_bytes: Vec::new(),
                     split_files: Vec::new(),
                 }],
@@ -1005,8 +1016,10 @@ mod tests {
                         num_merge_ops: 0,
                     },
                     serialized_split_fields: Vec::new(),
+                    serialized_split_bloom_filters: Vec::new(),
                     split_scratch_directory,
                     tags: Default::default(),
+                    field_value_ranges: Default::default(),
                     hotcache_bytes: Vec::new(),
                     split_files: Vec::new(),
                 }],
@@ -0,0 +1,410 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Detection rules are queries registered on an index. The documents are matched against them
+//! as they are indexed, in the fashion of a percolator, and the matching documents are emitted to
+//! the sinks of the rules. This is the building block of log alerting.
+//!
+//! Emitting matches is best effort: matches are dropped if the sinks cannot keep up with the
+//! indexing throughput, and are not emitted again if the indexing pipeline restarts.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Context;
+use quickwit_common::rate_limited_tracing::rate_limited_warn;
+use quickwit_config::{DetectionRuleConfig, DetectionRuleSinkConfig};
+use quickwit_doc_mapper::{DocMapper, JsonObject};
+use quickwit_ingest::{get_ingest_api_service, CommitType, DocBatchBuilder, IngestRequest};
+use quickwit_proto::types::IndexId;
+use quickwit_query::get_quickwit_fastfield_normalizer_manager;
+use quickwit_query::query_ast::query_ast_from_user_text;
+use serde::Serialize;
+use tantivy::collector::DocSetCollector;
+use tantivy::directory::RamDirectory;
+use tantivy::query::Query;
+use tantivy::schema::Schema;
+use tantivy::tokenizer::TokenizerManager;
+use tantivy::{DocId, IndexBuilder, IndexReader, ReloadPolicy};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+
+use crate::metrics::INDEXER_METRICS;
+use crate::models::ProcessedDoc;
+
+/// Number of batches of matches that can wait for their sink before new matches are dropped.
+const MATCH_BATCH_QUEUE_CAPACITY: usize = 100;
+
+/// Memory budget of the in-memory index the batches of documents are matched against.
+const MATCHER_MEMORY_BUDGET_IN_BYTES: usize = 15_000_000;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Document matching a detection rule, as emitted to the sink of the rule.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct DetectionRuleMatch {
+    pub rule_id: String,
+    pub index_id: IndexId,
+    /// Time at which the document was matched, in seconds since the Unix epoch.
+    pub matched_at: i64,
+    pub doc: JsonObject,
+}
+
+/// Matches of a detection rule found in a batch of documents.
+#[derive(Debug)]
+pub(crate) struct DetectionRuleMatchBatch {
+    pub sink: DetectionRuleSinkConfig,
+    pub matches: Vec<DetectionRuleMatch>,
+}
+
+struct DetectionRule {
+    rule_id: String,
+    query: Box<dyn Query>,
+    sink: DetectionRuleSinkConfig,
+}
+
+pub(crate) struct DetectionRuleMatcher {
+    index_id: IndexId,
+    schema: Schema,
+    tokenizer_manager: TokenizerManager,
+    detection_rules: Vec<DetectionRule>,
+    match_batch_tx: mpsc::Sender<DetectionRuleMatchBatch>,
+}
+
+impl DetectionRuleMatcher {
+    pub fn try_new(
+        index_id: IndexId,
+        doc_mapper: &dyn DocMapper,
+        detection_rule_configs: &[DetectionRuleConfig],
+        match_batch_tx: mpsc::Sender<DetectionRuleMatchBatch>,
+    ) -> anyhow::Result<Self> {
+        let schema = doc_mapper.schema();
+        let mut detection_rules = Vec::with_capacity(detection_rule_configs.len());
+
+        for detection_rule_config in detection_rule_configs {
+            let query_ast = query_ast_from_user_text(&detection_rule_config.query, None);
+            let (query, _) = doc_mapper
+                .query(schema.clone(), &query_ast, true)
+                .with_context(|| {
+                    format!(
+                        "invalid query for detection rule `{}`",
+                        detection_rule_config.rule_id
+                    )
+                })?;
+            detection_rules.push(DetectionRule {
+                rule_id: detection_rule_config.rule_id.clone(),
+                query,
+                sink: detection_rule_config.sink.clone(),
+            });
+        }
+        Ok(Self {
+            index_id,
+            schema,
+            tokenizer_manager: doc_mapper.tokenizer_manager().tantivy_manager().clone(),
+            detection_rules,
+            match_batch_tx,
+        })
+    }
+
+    /// Indexes the documents in an in-memory index and returns the IDs of the documents matching
+    /// each detection rule. Document IDs are the positions of the documents in the batch.
+    fn search_docs(&self, docs: &[ProcessedDoc]) -> tantivy::Result<Vec<Vec<DocId>>> {
+        let mut index_writer = IndexBuilder::new()
+            .schema(self.schema.clone())
+            .tokenizers(self.tokenizer_manager.clone())
+            .fast_field_tokenizers(
+                get_quickwit_fastfield_normalizer_manager()
+                    .tantivy_manager()
+                    .clone(),
+            )
+            .single_segment_index_writer(RamDirectory::create(), MATCHER_MEMORY_BUDGET_IN_BYTES)?;
+
+        for processed_doc in docs {
+            index_writer.add_document(processed_doc.doc.clone())?;
+        }
+        let index = index_writer.finalize()?;
+        let index_reader: IndexReader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let searcher = index_reader.searcher();
+
+        self.detection_rules
+            .iter()
+            .map(|detection_rule| {
+                let mut doc_ids: Vec<DocId> = searcher
+                    .search(&*detection_rule.query, &DocSetCollector)?
+                    .into_iter()
+                    .map(|doc_address| doc_address.doc_id)
+                    .collect();
+                doc_ids.sort_unstable();
+                Ok(doc_ids)
+            })
+            .collect()
+    }
+
+    /// Matches a batch of documents against the detection rules and hands the matches over to
+    /// the sinks. `json_docs` holds the JSON objects the documents were built from.
+    ///
+    /// Failures are logged but never interrupt indexing.
+    pub fn match_docs(&self, docs: &[ProcessedDoc], json_docs: &[JsonObject]) {
+        if docs.is_empty() {
+            return;
+        }
+        let matched_doc_ids_per_rule = match self.search_docs(docs) {
+            Ok(matched_doc_ids_per_rule) => matched_doc_ids_per_rule,
+            Err(error) => {
+                rate_limited_warn!(
+                    limit_per_min = 10,
+                    index_id = self.index_id,
+                    "failed to match documents against detection rules: {error}"
+                );
+                return;
+            }
+        };
+        let matched_at = time::OffsetDateTime::now_utc().unix_timestamp();
+
+        for (detection_rule, matched_doc_ids) in
+            self.detection_rules.iter().zip(matched_doc_ids_per_rule)
+        {
+            if matched_doc_ids.is_empty() {
+                continue;
+            }
+            let matches: Vec<DetectionRuleMatch> = matched_doc_ids
+                .into_iter()
+                .filter_map(|doc_id| json_docs.get(doc_id as usize))
+                .map(|json_doc| DetectionRuleMatch {
+                    rule_id: detection_rule.rule_id.clone(),
+                    index_id: self.index_id.clone(),
+                    matched_at,
+                    doc: json_doc.clone(),
+                })
+                .collect();
+            let num_matches = matches.len() as u64;
+            let match_batch = DetectionRuleMatchBatch {
+                sink: detection_rule.sink.clone(),
+                matches,
+            };
+            match self.match_batch_tx.try_send(match_batch) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    rate_limited_warn!(
+                        limit_per_min = 10,
+                        index_id = self.index_id,
+                        rule_id = detection_rule.rule_id,
+                        "detection rule sinks are lagging behind: dropping {num_matches} matches"
+                    );
+                    record_matches(&self.index_id, "dropped", num_matches);
+                }
+                Err(TrySendError::Closed(_)) => {
+                    record_matches(&self.index_id, "dropped", num_matches);
+                }
+            }
+        }
+    }
+}
+
+fn record_matches(index_id: &str, outcome: &str, num_matches: u64) {
+    let index_label = quickwit_common::metrics::index_label(index_id);
+    INDEXER_METRICS
+        .detection_rule_matches_total
+        .with_label_values([index_label, outcome])
+        .inc_by(num_matches);
+}
+
+/// Spawns the task emitting the matches of the detection rules to their sinks. The task
+/// terminates once all the senders are dropped.
+pub(crate) fn spawn_detection_rule_sinks(
+    queues_dir_path: PathBuf,
+) -> mpsc::Sender<DetectionRuleMatchBatch> {
+    let (match_batch_tx, mut match_batch_rx) = mpsc::channel(MATCH_BATCH_QUEUE_CAPACITY);
+
+    tokio::spawn(async move {
+        let http_client = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+
+        while let Some(match_batch) = match_batch_rx.recv().await {
+            let DetectionRuleMatchBatch { sink, matches } = match_batch;
+            let Some(first_match) = matches.first() else {
+                continue;
+            };
+            let index_id = first_match.index_id.clone();
+            let rule_id = first_match.rule_id.clone();
+            let num_matches = matches.len() as u64;
+
+            let emit_result = match &sink {
+                DetectionRuleSinkConfig::Index {
+                    index_id: sink_index_id,
+                } => emit_to_index(&queues_dir_path, sink_index_id, &matches).await,
+                DetectionRuleSinkConfig::Webhook { url, headers } => {
+                    emit_to_webhook(&http_client, url, headers, &matches).await
+                }
+            };
+            match emit_result {
+                Ok(()) => record_matches(&index_id, "emitted", num_matches),
+                Err(error) => {
+                    rate_limited_warn!(
+                        limit_per_min = 10,
+                        index_id = index_id,
+                        rule_id = rule_id,
+                        "failed to emit {num_matches} detection rule matches: {error:#}"
+                    );
+                    record_matches(&index_id, "failed", num_matches);
+                }
+            }
+        }
+    });
+    match_batch_tx
+}
+
+async fn emit_to_index(
+    queues_dir_path: &Path,
+    index_id: &str,
+    matches: &[DetectionRuleMatch],
+) -> anyhow::Result<()> {
+    let ingest_api_service = get_ingest_api_service(queues_dir_path).await?;
+    let mut doc_batch_builder = DocBatchBuilder::new(index_id.to_string()).json_writer();
+
+    for detection_rule_match in matches {
+        doc_batch_builder.ingest_doc(detection_rule_match)?;
+    }
+    let ingest_request = IngestRequest {
+        doc_batches: vec![doc_batch_builder.build()],
+        commit: CommitType::Auto.into(),
+    };
+    ingest_api_service
+        .ask_for_res(ingest_request)
+        .await
+        .with_context(|| format!("failed to ingest matches into index `{index_id}`"))?;
+    Ok(())
+}
+
+async fn emit_to_webhook(
+    http_client: &reqwest::Client,
+    url: &str,
+    headers: &BTreeMap<String, String>,
+    matches: &[DetectionRuleMatch],
+) -> anyhow::Result<()> {
+    let mut request = http_client.post(url).json(matches);
+
+    for (header_name, header_value) in headers {
+        request = request.header(header_name, header_value);
+    }
+    request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("failed to post matches to webhook `{url}`"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_doc_mapper::default_doc_mapper_for_test;
+    use serde_json::{json, Value as JsonValue};
+
+    use super::*;
+
+    fn detection_rule_config(rule_id: &str, query: &str) -> DetectionRuleConfig {
+        DetectionRuleConfig {
+            rule_id: rule_id.to_string(),
+            query: query.to_string(),
+            sink: DetectionRuleSinkConfig::Index {
+                index_id: "alerts".to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detection_rule_matcher() {
+        let doc_mapper = default_doc_mapper_for_test();
+        let (match_batch_tx, mut match_batch_rx) = mpsc::channel(10);
+        let detection_rule_matcher = DetectionRuleMatcher::try_new(
+            "my-index".to_string(),
+            &doc_mapper,
+            &[
+                detection_rule_config("failed-logins", "body:\"failed password\""),
+                detection_rule_config("important", "isImportant:true AND owner:security"),
+                detection_rule_config("never", "body:unmatched"),
+            ],
+            match_batch_tx,
+        )
+        .unwrap();
+
+        let json_docs: Vec<JsonObject> = [
+            json!({"timestamp": 1, "body": "Failed password for root", "owner": "security", "isImportant": true}),
+            json!({"timestamp": 2, "body": "Accepted password for root", "owner": "security"}),
+            json!({"timestamp": 3, "body": "failed password for admin", "owner": "ops", "isImportant": true}),
+        ]
+        .into_iter()
+        .map(|json_doc| serde_json::from_value(json_doc).unwrap())
+        .collect();
+        let processed_docs: Vec<ProcessedDoc> = json_docs
+            .iter()
+            .map(|json_doc| {
+                let (partition, doc) = doc_mapper.doc_from_json_obj(json_doc.clone(), 0).unwrap();
+                ProcessedDoc {
+                    doc,
+                    timestamp_opt: None,
+                    partition,
+                    num_bytes: 0,
+                }
+            })
+            .collect();
+        detection_rule_matcher.match_docs(&processed_docs, &json_docs);
+
+        let match_batch = match_batch_rx.try_recv().unwrap();
+        let matched_timestamps: Vec<&JsonValue> = match_batch
+            .matches
+            .iter()
+            .map(|detection_rule_match| &detection_rule_match.doc["timestamp"])
+            .collect();
+        assert_eq!(matched_timestamps, [&json!(1), &json!(3)]);
+        assert_eq!(match_batch.matches[0].rule_id, "failed-logins");
+        assert_eq!(match_batch.matches[0].index_id, "my-index");
+
+        let match_batch = match_batch_rx.try_recv().unwrap();
+        assert_eq!(match_batch.matches.len(), 1);
+        assert_eq!(match_batch.matches[0].rule_id, "important");
+        assert_eq!(match_batch.matches[0].doc["timestamp"], json!(1));
+
+        assert!(match_batch_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_detection_rule_matcher_invalid_query() {
+        let doc_mapper = default_doc_mapper_for_test();
+        let (match_batch_tx, _match_batch_rx) = mpsc::channel(10);
+        let error = DetectionRuleMatcher::try_new(
+            "my-index".to_string(),
+            &doc_mapper,
+            &[detection_rule_config("invalid", "response_time:abc")],
+            match_batch_tx,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "invalid query for detection rule `invalid`"
+        );
+    }
+}
@@ -40,6 +40,7 @@ pub use crate::split_store::{get_tantivy_directory_from_split_bundle, IndexingSp
 
 pub mod actors;
 mod controlled_directory;
+mod detection_rules;
 pub mod merge_policy;
 mod metrics;
 pub mod models;
@@ -205,7 +205,7 @@ fn splits_short_debug(splits: &[SplitMetadata]) -> Vec<SplitShortDebug> {
 pub mod tests {
 
     use std::collections::hash_map::DefaultHasher;
-    use std::collections::{BTreeSet, HashMap};
+    use std::collections::{BTreeMap, BTreeSet, HashMap};
     use std::hash::Hasher;
     use std::ops::RangeInclusive;
 
@@ -380,7 +380,7 @@ pub mod tests {
             source_id: "test_source".to_string(),
         };
         let split_attrs = merge_split_attrs(pipeline_id, merged_split_id, splits).unwrap();
-        create_split_metadata(merge_policy, &split_attrs, tags, 0..0)
+        create_split_metadata(merge_policy, &split_attrs, tags, BTreeMap::new(), 0..0)
     }
 
     fn apply_merge(
@@ -32,6 +32,7 @@ pub struct IndexerMetrics {
     pub ongoing_merge_operations: IntGauge,
     pub pending_merge_operations: IntGauge,
     pub pending_merge_bytes: IntGauge,
+    pub detection_rule_matches_total: IntCounterVec<2>,
     // We use a lazy counter, as most users do not use Kafka.
     #[cfg_attr(not(feature = "kafka"), allow(dead_code))]
     pub kafka_rebalance_total: Lazy<IntCounter>,
@@ -95,6 +96,14 @@ impl Default for IndexerMetrics {
                 "indexing",
                 &[],
             ),
+            detection_rule_matches_total: new_counter_vec(
+                "detection_rule_matches_total",
+                "Number of documents matching a detection rule by index and outcome in [emitted, \
+                 failed, dropped]",
+                "indexing",
+                &[],
+                ["index", "outcome"],
+            ),
             kafka_rebalance_total: Lazy::new(|| {
                 new_counter(
                     "kafka_rebalance_total",
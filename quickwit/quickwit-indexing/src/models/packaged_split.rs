@@ -17,13 +17,14 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::path::PathBuf;
 
 use itertools::Itertools;
 use quickwit_common::temp_dir::TempDirectory;
 use quickwit_metastore::checkpoint::IndexCheckpointDelta;
+use quickwit_metastore::FieldValueRange;
 use quickwit_proto::types::{IndexUid, PublishToken, SplitId};
 use tracing::Span;
 
@@ -32,9 +33,11 @@ use crate::models::{PublishLock, SplitAttrs};
 
 pub struct PackagedSplit {
     pub serialized_split_fields: Vec<u8>,
+    pub serialized_split_bloom_filters: Vec<u8>,
     pub split_attrs: SplitAttrs,
     pub split_scratch_directory: TempDirectory,
     pub tags: BTreeSet<String>,
+    pub field_value_ranges: BTreeMap<String, FieldValueRange>,
     pub split_files: Vec<PathBuf>,
     pub hotcache_bytes: Vec<u8>,
 }
@@ -341,6 +341,7 @@ mod tests {
                     &split_metadata1,
                     &split_path,
                     Box::new(SplitPayloadBuilder::get_split_payload(
+                        &[],
                         &[],
                         &[],
                         &[5, 5, 5],
@@ -370,6 +371,7 @@ mod tests {
                     &split_metadata2,
                     &split_path,
                     Box::new(SplitPayloadBuilder::get_split_payload(
+                        &[],
                         &[],
                         &[],
                         &[5, 5, 5],
@@ -329,6 +329,7 @@ impl JaegerService {
                 default_fields: None,
                 default_operator: BooleanOperand::And,
                 lenient: true,
+                allow_leading_wildcard: false,
             };
             let mut new_query = BoolQuery::default();
             new_query.must.push(query.into());
@@ -1190,6 +1191,7 @@ mod tests {
                     default_fields: None,
                     default_operator: quickwit_query::BooleanOperand::And,
                     lenient: false,
+                    allow_leading_wildcard: false,
                 }
                 .into()
             );
@@ -0,0 +1,286 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use quickwit_actors::{
+    Actor, ActorContext, ActorExitStatus, ActorHandle, Handler, Mailbox, Supervisor,
+};
+use quickwit_common::io::IoControls;
+use quickwit_common::pubsub::EventBroker;
+use quickwit_common::temp_dir;
+use quickwit_config::{build_doc_mapper, ForceMergePhase, IndexConfig};
+use quickwit_indexing::actors::{
+    schedule_merge, MergeExecutor, MergeSchedulerService, MergeSplitDownloader, Packager,
+    Publisher, Uploader, UploaderType,
+};
+use quickwit_indexing::merge_policy::{MergeOperation, NopMergePolicy};
+use quickwit_indexing::{IndexingSplitStore, PublisherType, SplitsUpdateMailbox};
+use quickwit_metastore::SplitMetadata;
+use quickwit_proto::indexing::MergePipelineId;
+use quickwit_proto::metastore::MetastoreServiceClient;
+use quickwit_proto::types::{IndexUid, NodeId};
+use quickwit_storage::Storage;
+use serde::Serialize;
+use tantivy::Inventory;
+use tokio::join;
+use tracing::info;
+
+use crate::lifecycle_policy_execution::plan_force_merge_operations;
+
+struct ForceMergePipelineHandles {
+    downloader: ActorHandle<Supervisor<MergeSplitDownloader>>,
+    merge_executor: ActorHandle<Supervisor<MergeExecutor>>,
+    packager: ActorHandle<Supervisor<Packager>>,
+    uploader: ActorHandle<Supervisor<Uploader>>,
+    publisher: ActorHandle<Supervisor<Publisher>>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ForceMergePipelineState {
+    /// The number of merge operations planned since the pipeline started.
+    pub num_planned_merge_operations: usize,
+    /// The number of merge operations that have not been published yet.
+    pub num_ongoing_merge_operations: usize,
+}
+
+/// Merges the splits of an index that reached the `force_merge` phase of its lifecycle policy.
+/// The pipeline is made of the same actors as a merge pipeline, but the merge operations are
+/// planned from the lifecycle policy instead of the merge policy of the index. The merged splits
+/// are mature, so the indexers never merge them again.
+pub struct ForceMergePipeline {
+    index_uid: IndexUid,
+    index_config: IndexConfig,
+    metastore: MetastoreServiceClient,
+    index_storage: Arc<dyn Storage>,
+    force_merge_dir: PathBuf,
+    max_concurrent_split_uploads: usize,
+    merge_scheduler_service: Mailbox<MergeSchedulerService>,
+    event_broker: EventBroker,
+    handles_opt: Option<ForceMergePipelineHandles>,
+    downloader_mailbox_opt: Option<Mailbox<MergeSplitDownloader>>,
+    /// Inventory of the ongoing merge operations, which are dropped once the merged split is
+    /// published. It prevents merging the same splits twice.
+    ongoing_merge_operations_inventory: Inventory<MergeOperation>,
+    num_planned_merge_operations: usize,
+}
+
+impl ForceMergePipeline {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        index_uid: IndexUid,
+        index_config: IndexConfig,
+        metastore: MetastoreServiceClient,
+        index_storage: Arc<dyn Storage>,
+        force_merge_dir: PathBuf,
+        max_concurrent_split_uploads: usize,
+        merge_scheduler_service: Mailbox<MergeSchedulerService>,
+        event_broker: EventBroker,
+    ) -> Self {
+        Self {
+            index_uid,
+            index_config,
+            metastore,
+            index_storage,
+            force_merge_dir,
+            max_concurrent_split_uploads,
+            merge_scheduler_service,
+            event_broker,
+            handles_opt: None,
+            downloader_mailbox_opt: None,
+            ongoing_merge_operations_inventory: Inventory::new(),
+            num_planned_merge_operations: 0,
+        }
+    }
+
+    fn spawn_pipeline(&mut self, ctx: &ActorContext<Self>) -> anyhow::Result<()> {
+        info!(index_uid=%self.index_uid, "spawning force merge pipeline");
+
+        let publisher = Publisher::new(
+            PublisherType::MergePublisher,
+            self.metastore.clone(),
+            None,
+            None,
+        );
+        let (publisher_mailbox, publisher_handle) = ctx.spawn_actor().supervise(publisher);
+        let split_store =
+            IndexingSplitStore::create_without_local_store_for_test(self.index_storage.clone());
+        let uploader = Uploader::new(
+            UploaderType::MergeUploader,
+            self.metastore.clone(),
+            Arc::new(NopMergePolicy),
+            split_store.clone(),
+            SplitsUpdateMailbox::Publisher(publisher_mailbox),
+            self.max_concurrent_split_uploads,
+            self.event_broker.clone(),
+        );
+        let (uploader_mailbox, uploader_handle) = ctx.spawn_actor().supervise(uploader);
+
+        let doc_mapper = build_doc_mapper(
+            &self.index_config.doc_mapping,
+            &self.index_config.search_settings,
+        )?;
+        let tag_fields = doc_mapper.tag_named_fields()?;
+        let bloom_filter_fields = doc_mapper.bloom_filter_named_fields()?;
+        let packager = Packager::new(
+            "ForceMergePackager",
+            tag_fields,
+            bloom_filter_fields,
+            uploader_mailbox,
+        );
+        let (packager_mailbox, packager_handle) = ctx.spawn_actor().supervise(packager);
+
+        let pipeline_id = MergePipelineId {
+            node_id: NodeId::from("unknown"),
+            index_uid: self.index_uid.clone(),
+            source_id: "unknown".to_string(),
+        };
+        let merge_executor_io_controls = IoControls::default().set_component("force_merger");
+        let split_download_io_controls = merge_executor_io_controls
+            .clone()
+            .set_component("split_downloader_force_merge");
+        let merge_executor = MergeExecutor::new(
+            pipeline_id,
+            self.metastore.clone(),
+            doc_mapper,
+            merge_executor_io_controls,
+            packager_mailbox,
+        );
+        let (merge_executor_mailbox, merge_executor_handle) =
+            ctx.spawn_actor().supervise(merge_executor);
+
+        let scratch_directory = temp_dir::Builder::default()
+            .join(&self.index_uid.index_id)
+            .join(&self.index_uid.incarnation_id.to_string())
+            .tempdir_in(&self.force_merge_dir)?;
+        let merge_split_downloader = MergeSplitDownloader {
+            scratch_directory,
+            split_store,
+            executor_mailbox: merge_executor_mailbox,
+            io_controls: split_download_io_controls,
+        };
+        let (downloader_mailbox, downloader_handle) =
+            ctx.spawn_actor().supervise(merge_split_downloader);
+
+        self.downloader_mailbox_opt = Some(downloader_mailbox);
+        self.handles_opt = Some(ForceMergePipelineHandles {
+            downloader: downloader_handle,
+            merge_executor: merge_executor_handle,
+            packager: packager_handle,
+            uploader: uploader_handle,
+            publisher: publisher_handle,
+        });
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Actor for ForceMergePipeline {
+    type ObservableState = ForceMergePipelineState;
+
+    fn observable_state(&self) -> Self::ObservableState {
+        ForceMergePipelineState {
+            num_planned_merge_operations: self.num_planned_merge_operations,
+            num_ongoing_merge_operations: self.ongoing_merge_operations_inventory.list().len(),
+        }
+    }
+
+    fn name(&self) -> String {
+        "ForceMergePipeline".to_string()
+    }
+
+    async fn initialize(&mut self, ctx: &ActorContext<Self>) -> Result<(), ActorExitStatus> {
+        self.spawn_pipeline(ctx)?;
+        Ok(())
+    }
+
+    async fn finalize(
+        &mut self,
+        _exit_status: &ActorExitStatus,
+        _ctx: &ActorContext<Self>,
+    ) -> anyhow::Result<()> {
+        if let Some(handles) = self.handles_opt.take() {
+            join!(
+                handles.downloader.quit(),
+                handles.merge_executor.quit(),
+                handles.packager.quit(),
+                handles.uploader.quit(),
+                handles.publisher.quit(),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Plans and schedules the merge operations of the splits that reached the `force_merge` phase.
+#[derive(Debug)]
+pub(crate) struct ForceMerge {
+    pub splits: Vec<SplitMetadata>,
+    pub force_merge_phase: ForceMergePhase,
+}
+
+#[async_trait]
+impl Handler<ForceMerge> for ForceMergePipeline {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        message: ForceMerge,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        let Some(downloader_mailbox) = &self.downloader_mailbox_opt else {
+            return Ok(());
+        };
+        let ongoing_split_ids: HashSet<String> = self
+            .ongoing_merge_operations_inventory
+            .list()
+            .iter()
+            .flat_map(|merge_operation| merge_operation.splits_as_slice())
+            .map(|split| split.split_id.clone())
+            .collect();
+        let splits: Vec<SplitMetadata> = message
+            .splits
+            .into_iter()
+            .filter(|split| !ongoing_split_ids.contains(&split.split_id))
+            .collect();
+
+        for splits in plan_force_merge_operations(splits, &message.force_merge_phase) {
+            let merge_operation = MergeOperation::new_merge_operation(splits);
+            info!(
+                index_uid=%self.index_uid,
+                merge_operation=?merge_operation,
+                "planned force merge operation"
+            );
+            let tracked_merge_operation = self
+                .ongoing_merge_operations_inventory
+                .track(merge_operation);
+            ctx.protect_future(schedule_merge(
+                &self.merge_scheduler_service,
+                tracked_merge_operation,
+                downloader_mailbox.clone(),
+            ))
+            .await?;
+            self.num_planned_merge_operations += 1;
+        }
+        Ok(())
+    }
+}
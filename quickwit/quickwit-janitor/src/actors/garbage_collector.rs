@@ -222,8 +222,8 @@ mod tests {
         SplitState,
     };
     use quickwit_proto::metastore::{
-        EmptyResponse, ListIndexesMetadataResponse, ListSplitsResponse, MetastoreError,
-        MockMetastoreService,
+        EmptyResponse, ListIndexesMetadataResponse, ListPointInTimesResponse, ListSplitsResponse,
+        MetastoreError, MockMetastoreService,
     };
     use quickwit_proto::types::IndexUid;
     use quickwit_storage::MockStorage;
@@ -311,6 +311,10 @@ mod tests {
                 let splits = ListSplitsResponse::try_from_splits(splits).unwrap();
                 Ok(ServiceStream::from(vec![Ok(splits)]))
             });
+        mock_metastore
+            .expect_list_point_in_times()
+            .times(1)
+            .returning(|_| Ok(ListPointInTimesResponse::default()));
         let index_uid_clone = index_uid.clone();
         mock_metastore
             .expect_mark_splits_for_deletion()
@@ -385,6 +389,10 @@ mod tests {
                 let splits = ListSplitsResponse::try_from_splits(splits).unwrap();
                 Ok(ServiceStream::from(vec![Ok(splits)]))
             });
+        mock_metastore
+            .expect_list_point_in_times()
+            .times(1)
+            .returning(|_| Ok(ListPointInTimesResponse::default()));
         mock_metastore
             .expect_mark_splits_for_deletion()
             .times(1)
@@ -458,6 +466,10 @@ mod tests {
                 let splits = ListSplitsResponse::try_from_splits(splits).unwrap();
                 Ok(ServiceStream::from(vec![Ok(splits)]))
             });
+        mock_metastore
+            .expect_list_point_in_times()
+            .times(3)
+            .returning(|_| Ok(ListPointInTimesResponse::default()));
         mock_metastore
             .expect_mark_splits_for_deletion()
             .times(3)
@@ -637,6 +649,10 @@ mod tests {
                 let splits = ListSplitsResponse::try_from_splits(splits).unwrap();
                 Ok(ServiceStream::from(vec![Ok(splits)]))
             });
+        mock_metastore
+            .expect_list_point_in_times()
+            .times(1)
+            .returning(|_| Ok(ListPointInTimesResponse::default()));
         mock_metastore
             .expect_mark_splits_for_deletion()
             .times(2)
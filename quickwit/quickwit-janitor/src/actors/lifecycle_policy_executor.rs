@@ -0,0 +1,418 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use quickwit_actors::{Actor, ActorContext, ActorExitStatus, ActorHandle, Handler, Mailbox};
+use quickwit_common::pretty::PrettySample;
+use quickwit_common::pubsub::EventBroker;
+use quickwit_common::temp_dir;
+use quickwit_config::LifecyclePolicy;
+use quickwit_indexing::actors::MergeSchedulerService;
+use quickwit_metastore::{
+    IndexMetadata, ListIndexesMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt,
+    MetastoreServiceStreamSplitsExt, SplitMetadata, SplitState,
+};
+use quickwit_proto::metastore::{
+    ListIndexesMetadataRequest, ListSplitsRequest, MarkSplitsForDeletionRequest, MetastoreService,
+    MetastoreServiceClient,
+};
+use quickwit_proto::types::{DocMappingUid, IndexUid, SplitId};
+use quickwit_storage::StorageResolver;
+use serde::Serialize;
+use time::OffsetDateTime;
+use tracing::{debug, error, info};
+
+use super::force_merge_pipeline::{ForceMerge, ForceMergePipeline};
+use crate::lifecycle_policy_execution::{split_phase, LifecyclePhaseName};
+use crate::lifecycle_policy_store::LifecyclePolicyStore;
+
+pub const LIFECYCLE_POLICY_EXECUTOR_DIR_NAME: &str = "lifecycle_policy_executor";
+
+const RUN_INTERVAL: Duration = if cfg!(any(test, feature = "testsuite")) {
+    Duration::from_millis(500)
+} else {
+    Duration::from_secs(10 * 60) // 10 minutes
+};
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct LifecyclePolicyExecutorCounters {
+    /// The number of execution passes.
+    pub num_execution_passes: usize,
+
+    /// The number of splits marked for deletion by the `delete` phase.
+    pub num_deleted_splits: usize,
+
+    /// The number of running force merge pipelines.
+    pub num_running_force_merge_pipelines: usize,
+}
+
+#[derive(Debug)]
+struct Loop;
+
+struct ForceMergePipelineHandle {
+    doc_mapping_uid: DocMappingUid,
+    mailbox: Mailbox<ForceMergePipeline>,
+    handle: ActorHandle<ForceMergePipeline>,
+}
+
+/// An actor that periodically applies the lifecycle policies to the indexes they match:
+/// - the splits of the `delete` phase are marked for deletion, the garbage collector takes care of
+///   the actual deletion;
+/// - the splits of the `force_merge` phase are sent to the force merge pipeline of the index.
+pub struct LifecyclePolicyExecutor {
+    metastore: MetastoreServiceClient,
+    storage_resolver: StorageResolver,
+    lifecycle_policy_store: LifecyclePolicyStore,
+    force_merge_dir: PathBuf,
+    max_concurrent_split_uploads: usize,
+    merge_scheduler_service: Mailbox<MergeSchedulerService>,
+    event_broker: EventBroker,
+    force_merge_pipelines: HashMap<IndexUid, ForceMergePipelineHandle>,
+    counters: LifecyclePolicyExecutorCounters,
+}
+
+impl LifecyclePolicyExecutor {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new(
+        metastore: MetastoreServiceClient,
+        storage_resolver: StorageResolver,
+        lifecycle_policy_store: LifecyclePolicyStore,
+        data_dir_path: PathBuf,
+        max_concurrent_split_uploads: usize,
+        merge_scheduler_service: Mailbox<MergeSchedulerService>,
+        event_broker: EventBroker,
+    ) -> anyhow::Result<Self> {
+        let force_merge_path = data_dir_path.join(LIFECYCLE_POLICY_EXECUTOR_DIR_NAME);
+        let force_merge_dir = temp_dir::create_or_purge_directory(&force_merge_path).await?;
+        Ok(Self {
+            metastore,
+            storage_resolver,
+            lifecycle_policy_store,
+            force_merge_dir,
+            max_concurrent_split_uploads,
+            merge_scheduler_service,
+            event_broker,
+            force_merge_pipelines: HashMap::new(),
+            counters: LifecyclePolicyExecutorCounters::default(),
+        })
+    }
+
+    async fn execute_lifecycle_policies(&mut self, ctx: &ActorContext<Self>) -> anyhow::Result<()> {
+        self.counters.num_execution_passes += 1;
+
+        let lifecycle_policies = ctx
+            .protect_future(self.lifecycle_policy_store.list_policies())
+            .await?;
+        let indexes_metadata = ctx
+            .protect_future(
+                self.metastore
+                    .list_indexes_metadata(ListIndexesMetadataRequest::all()),
+            )
+            .await?
+            .deserialize_indexes_metadata()
+            .await?;
+        let mut force_merged_index_uids: HashSet<IndexUid> = HashSet::new();
+
+        for index_metadata in indexes_metadata {
+            let Some(lifecycle_policy) = LifecyclePolicy::find_matching_policy(
+                &lifecycle_policies,
+                index_metadata.index_id(),
+            ) else {
+                continue;
+            };
+            if lifecycle_policy.phases.force_merge.is_some() {
+                force_merged_index_uids.insert(index_metadata.index_uid.clone());
+            }
+            let index_uid = index_metadata.index_uid.clone();
+
+            if let Err(error) = self
+                .execute_lifecycle_policy(index_metadata, lifecycle_policy, ctx)
+                .await
+            {
+                error!(
+                    index_id=%index_uid.index_id,
+                    policy_id=%lifecycle_policy.policy_id,
+                    error=?error,
+                    "failed to execute lifecycle policy"
+                );
+            }
+        }
+        // Stops the pipelines of the indexes that were deleted or no longer have a `force_merge`
+        // phase.
+        let stale_index_uids: Vec<IndexUid> = self
+            .force_merge_pipelines
+            .keys()
+            .filter(|index_uid| !force_merged_index_uids.contains(*index_uid))
+            .cloned()
+            .collect();
+        for index_uid in stale_index_uids {
+            if let Some(pipeline_handle) = self.force_merge_pipelines.remove(&index_uid) {
+                info!(index_id=%index_uid.index_id, "stopping force merge pipeline");
+                pipeline_handle.handle.kill().await;
+            }
+        }
+        self.counters.num_running_force_merge_pipelines = self.force_merge_pipelines.len();
+        Ok(())
+    }
+
+    async fn execute_lifecycle_policy(
+        &mut self,
+        index_metadata: IndexMetadata,
+        lifecycle_policy: &LifecyclePolicy,
+        ctx: &ActorContext<Self>,
+    ) -> anyhow::Result<()> {
+        let index_uid = index_metadata.index_uid.clone();
+        let query =
+            ListSplitsQuery::for_index(index_uid.clone()).with_split_state(SplitState::Published);
+        let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+        let published_splits = ctx
+            .protect_future(self.metastore.list_splits(list_splits_request))
+            .await?
+            .collect_splits_metadata()
+            .await?;
+
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let mut force_merge_splits: Vec<SplitMetadata> = Vec::new();
+        let mut expired_split_ids: Vec<SplitId> = Vec::new();
+
+        for split in published_splits {
+            match split_phase(&split, lifecycle_policy, now_timestamp)? {
+                LifecyclePhaseName::Hot => {}
+                LifecyclePhaseName::ForceMerge => force_merge_splits.push(split),
+                LifecyclePhaseName::Delete => expired_split_ids.push(split.split_id),
+            }
+        }
+        if !expired_split_ids.is_empty() {
+            info!(
+                index_id=%index_uid.index_id,
+                policy_id=%lifecycle_policy.policy_id,
+                split_ids=?PrettySample::new(&expired_split_ids, 5),
+                "marking {} splits for deletion based on lifecycle policy",
+                expired_split_ids.len()
+            );
+            let num_expired_splits = expired_split_ids.len();
+            let mark_splits_for_deletion_request =
+                MarkSplitsForDeletionRequest::new(index_uid.clone(), expired_split_ids);
+            ctx.protect_future(
+                self.metastore
+                    .mark_splits_for_deletion(mark_splits_for_deletion_request),
+            )
+            .await?;
+            self.counters.num_deleted_splits += num_expired_splits;
+        }
+        let Some(force_merge_phase) = &lifecycle_policy.phases.force_merge else {
+            return Ok(());
+        };
+        let index_config = index_metadata.into_index_config();
+        let doc_mapping_uid = index_config.doc_mapping.doc_mapping_uid;
+
+        // Splits indexed with a previous doc mapping are not merged with the current doc mapper.
+        force_merge_splits.retain(|split| split.doc_mapping_uid == doc_mapping_uid);
+
+        if self
+            .force_merge_pipelines
+            .get(&index_uid)
+            .is_some_and(|pipeline_handle| pipeline_handle.doc_mapping_uid != doc_mapping_uid)
+        {
+            debug!(
+                index_id=%index_uid.index_id,
+                "restarting force merge pipeline after doc mapping update"
+            );
+            let pipeline_handle = self
+                .force_merge_pipelines
+                .remove(&index_uid)
+                .expect("force merge pipeline should be present");
+            pipeline_handle.handle.kill().await;
+        }
+        if !self.force_merge_pipelines.contains_key(&index_uid) {
+            let index_storage = self
+                .storage_resolver
+                .resolve(&index_config.index_uri)
+                .await?;
+            let pipeline = ForceMergePipeline::new(
+                index_uid.clone(),
+                index_config,
+                self.metastore.clone(),
+                index_storage,
+                self.force_merge_dir.clone(),
+                self.max_concurrent_split_uploads,
+                self.merge_scheduler_service.clone(),
+                self.event_broker.clone(),
+            );
+            let (mailbox, handle) = ctx.spawn_actor().spawn(pipeline);
+            let pipeline_handle = ForceMergePipelineHandle {
+                doc_mapping_uid,
+                mailbox,
+                handle,
+            };
+            self.force_merge_pipelines
+                .insert(index_uid.clone(), pipeline_handle);
+        }
+        if force_merge_splits.len() < 2 {
+            return Ok(());
+        }
+        let force_merge = ForceMerge {
+            splits: force_merge_splits,
+            force_merge_phase: force_merge_phase.clone(),
+        };
+        let pipeline_mailbox = &self.force_merge_pipelines[&index_uid].mailbox;
+        ctx.send_message(pipeline_mailbox, force_merge).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Actor for LifecyclePolicyExecutor {
+    type ObservableState = LifecyclePolicyExecutorCounters;
+
+    fn observable_state(&self) -> Self::ObservableState {
+        self.counters.clone()
+    }
+
+    fn name(&self) -> String {
+        "LifecyclePolicyExecutor".to_string()
+    }
+
+    async fn initialize(&mut self, ctx: &ActorContext<Self>) -> Result<(), ActorExitStatus> {
+        self.handle(Loop, ctx).await
+    }
+}
+
+#[async_trait]
+impl Handler<Loop> for LifecyclePolicyExecutor {
+    type Reply = ();
+
+    async fn handle(&mut self, _: Loop, ctx: &ActorContext<Self>) -> Result<(), ActorExitStatus> {
+        if let Err(error) = self.execute_lifecycle_policies(ctx).await {
+            error!(error=?error, "failed to execute lifecycle policies");
+        }
+        ctx.schedule_self_msg(RUN_INTERVAL, Loop);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_actors::Universe;
+    use quickwit_common::ServiceStream;
+    use quickwit_config::{DeletePhase, IndexConfig, LifecyclePhases};
+    use quickwit_metastore::{metastore_for_test, ListSplitsResponseExt, Split};
+    use quickwit_proto::metastore::{
+        EmptyResponse, ListIndexesMetadataResponse, ListSplitsResponse, MockMetastoreService,
+    };
+
+    use super::*;
+
+    fn make_split(split_id: &str, end_timestamp: i64) -> Split {
+        Split {
+            split_metadata: SplitMetadata {
+                split_id: split_id.to_string(),
+                footer_offsets: 5..20,
+                time_range: Some(end_timestamp - 60..=end_timestamp),
+                ..Default::default()
+            },
+            split_state: SplitState::Published,
+            update_timestamp: 0,
+            publish_timestamp: Some(100),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_policy_executor_delete_phase() {
+        let universe = Universe::with_accelerated_time();
+        let storage_resolver = StorageResolver::for_test();
+        let lifecycle_policy_store = LifecyclePolicyStore::new(metastore_for_test());
+        let mut lifecycle_policy = LifecyclePolicy::for_test("logs", &["logs-*"]);
+        lifecycle_policy.phases = LifecyclePhases {
+            force_merge: None,
+            delete: Some(DeletePhase {
+                min_age: "1 day".to_string(),
+            }),
+        };
+        lifecycle_policy_store
+            .put_policy(lifecycle_policy, false)
+            .await
+            .unwrap();
+
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .returning(|_| {
+                let indexes_metadata = ["logs-1", "metrics-1"]
+                    .into_iter()
+                    .map(|index_id| {
+                        IndexMetadata::new(IndexConfig::for_test(
+                            index_id,
+                            &format!("ram:///indexes/{index_id}"),
+                        ))
+                    })
+                    .collect();
+                Ok(ListIndexesMetadataResponse::for_test(indexes_metadata))
+            });
+        mock_metastore
+            .expect_list_splits()
+            .returning(|list_splits_request| {
+                let query = list_splits_request.deserialize_list_splits_query().unwrap();
+                assert_eq!(query.index_uids[0].index_id, "logs-1");
+
+                let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+                let splits = vec![
+                    make_split("split-1", now_timestamp - 3600),
+                    make_split("split-2", now_timestamp - 2 * 24 * 3600),
+                ];
+                let splits_response = ListSplitsResponse::try_from_splits(splits).unwrap();
+                Ok(ServiceStream::from(vec![Ok(splits_response)]))
+            });
+        mock_metastore
+            .expect_mark_splits_for_deletion()
+            .times(1..)
+            .returning(|mark_splits_for_deletion_request| {
+                assert_eq!(
+                    mark_splits_for_deletion_request.index_uid().index_id,
+                    "logs-1"
+                );
+                assert_eq!(mark_splits_for_deletion_request.split_ids, ["split-2"]);
+                Ok(EmptyResponse {})
+            });
+        let data_dir = tempfile::tempdir().unwrap();
+        let lifecycle_policy_executor = LifecyclePolicyExecutor::new(
+            MetastoreServiceClient::from_mock(mock_metastore),
+            storage_resolver,
+            lifecycle_policy_store,
+            data_dir.path().to_path_buf(),
+            1,
+            universe.get_or_spawn_one(),
+            EventBroker::default(),
+        )
+        .await
+        .unwrap();
+        let (_mailbox, handle) = universe.spawn_builder().spawn(lifecycle_policy_executor);
+
+        let counters = handle.process_pending_and_observe().await.state;
+        assert_eq!(counters.num_execution_passes, 1);
+        assert_eq!(counters.num_deleted_splits, 1);
+        assert_eq!(counters.num_running_force_merge_pipelines, 0);
+
+        universe.assert_quit().await;
+    }
+}
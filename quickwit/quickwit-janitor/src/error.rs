@@ -31,6 +31,12 @@ pub enum JanitorError {
     Internal(String),
     #[error("invalid delete query: `{0}`")]
     InvalidDeleteQuery(String),
+    #[error("invalid lifecycle policy: `{0}`")]
+    InvalidLifecyclePolicy(String),
+    #[error("lifecycle policy `{0}` already exists")]
+    LifecyclePolicyAlreadyExists(String),
+    #[error("lifecycle policy `{0}` not found")]
+    LifecyclePolicyNotFound(String),
     #[error("metastore error: `{0}`")]
     Metastore(#[from] MetastoreError),
 }
@@ -43,6 +49,9 @@ impl ServiceError for JanitorError {
                 ServiceErrorCode::Internal
             }
             Self::InvalidDeleteQuery(_) => ServiceErrorCode::BadRequest,
+            Self::InvalidLifecyclePolicy(_) => ServiceErrorCode::BadRequest,
+            Self::LifecyclePolicyAlreadyExists(_) => ServiceErrorCode::AlreadyExists,
+            Self::LifecyclePolicyNotFound(_) => ServiceErrorCode::NotFound,
             Self::Metastore(metastore_error) => metastore_error.error_code(),
         }
     }
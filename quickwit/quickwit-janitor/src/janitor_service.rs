@@ -23,12 +23,15 @@ use quickwit_actors::{
 };
 use serde_json::{json, Value as JsonValue};
 
-use crate::actors::{DeleteTaskService, GarbageCollector, RetentionPolicyExecutor};
+use crate::actors::{
+    DeleteTaskService, GarbageCollector, LifecyclePolicyExecutor, RetentionPolicyExecutor,
+};
 
 pub struct JanitorService {
     delete_task_service_handle: Option<ActorHandle<DeleteTaskService>>,
     garbage_collector_handle: ActorHandle<GarbageCollector>,
     retention_policy_executor_handle: ActorHandle<RetentionPolicyExecutor>,
+    lifecycle_policy_executor_handle: ActorHandle<LifecyclePolicyExecutor>,
 }
 
 impl JanitorService {
@@ -36,11 +39,13 @@ impl JanitorService {
         delete_task_service_handle: Option<ActorHandle<DeleteTaskService>>,
         garbage_collector_handle: ActorHandle<GarbageCollector>,
         retention_policy_executor_handle: ActorHandle<RetentionPolicyExecutor>,
+        lifecycle_policy_executor_handle: ActorHandle<LifecyclePolicyExecutor>,
     ) -> Self {
         Self {
             delete_task_service_handle,
             garbage_collector_handle,
             retention_policy_executor_handle,
+            lifecycle_policy_executor_handle,
         }
     }
 
@@ -52,6 +57,7 @@ impl JanitorService {
             })
             && self.garbage_collector_handle.state() != ActorState::Failure
             && self.retention_policy_executor_handle.state() != ActorState::Failure
+            && self.lifecycle_policy_executor_handle.state() != ActorState::Failure
     }
 }
 
@@ -32,15 +32,28 @@ use tracing::info;
 pub mod actors;
 pub mod error;
 mod janitor_service;
+mod lifecycle_policy_execution;
+mod lifecycle_policy_store;
 mod metrics;
 mod retention_policy_execution;
 
 pub use janitor_service::JanitorService;
+pub use lifecycle_policy_execution::{
+    compute_index_lifecycle_status, IndexLifecycleStatus, LifecyclePhaseName, LifecyclePhaseStatus,
+};
+pub use lifecycle_policy_store::LifecyclePolicyStore;
 
-use crate::actors::{DeleteTaskService, GarbageCollector, RetentionPolicyExecutor};
+use crate::actors::{
+    DeleteTaskService, GarbageCollector, LifecyclePolicyExecutor, RetentionPolicyExecutor,
+};
 
 #[derive(utoipa::OpenApi)]
-#[openapi(components(schemas(SplitInfo)))]
+#[openapi(components(schemas(
+    SplitInfo,
+    IndexLifecycleStatus,
+    LifecyclePhaseName,
+    LifecyclePhaseStatus
+)))]
 /// Schema used for the OpenAPI generation which are apart of this crate.
 pub struct JanitorApiSchemas;
 
@@ -60,6 +73,21 @@ pub async fn start_janitor_service(
     let retention_policy_executor = RetentionPolicyExecutor::new(metastore.clone());
     let (_, retention_policy_executor_handle) =
         universe.spawn_builder().spawn(retention_policy_executor);
+
+    let lifecycle_policy_store = LifecyclePolicyStore::new(metastore.clone());
+    let lifecycle_policy_executor = LifecyclePolicyExecutor::new(
+        metastore.clone(),
+        storage_resolver.clone(),
+        lifecycle_policy_store,
+        config.data_dir_path.clone(),
+        config.indexer_config.max_concurrent_split_uploads,
+        universe.get_or_spawn_one::<MergeSchedulerService>(),
+        event_broker.clone(),
+    )
+    .await?;
+    let (_, lifecycle_policy_executor_handle) =
+        universe.spawn_builder().spawn(lifecycle_policy_executor);
+
     let delete_task_service_handle = if run_delete_task_service {
         let delete_task_service = DeleteTaskService::new(
             metastore,
@@ -82,6 +110,7 @@ pub async fn start_janitor_service(
         delete_task_service_handle,
         garbage_collector_handle,
         retention_policy_executor_handle,
+        lifecycle_policy_executor_handle,
     );
     let (janitor_service_mailbox, _janitor_service_handle) =
         universe.spawn_builder().spawn(janitor_service);
@@ -0,0 +1,330 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use quickwit_config::{ForceMergePhase, LifecyclePolicy, LifecyclePolicyId};
+use quickwit_metastore::SplitMetadata;
+use quickwit_proto::types::{DocMappingUid, IndexId};
+use serde::{Deserialize, Serialize};
+
+/// Lifecycle status of an index, as returned by the REST API.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct IndexLifecycleStatus {
+    pub index_id: IndexId,
+    /// ID of the lifecycle policy that applies to the index, if any.
+    pub policy_id: Option<LifecyclePolicyId>,
+    /// Published splits of the index grouped by phase.
+    pub phases: Vec<LifecyclePhaseStatus>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecyclePhaseName {
+    Hot,
+    ForceMerge,
+    Delete,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct LifecyclePhaseStatus {
+    pub phase: LifecyclePhaseName,
+    pub num_splits: usize,
+    pub num_docs: usize,
+    pub size_in_bytes: u64,
+    /// Number of merge operations that remain to be executed for the splits of the
+    /// `force_merge` phase.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_pending_merge_operations: Option<usize>,
+}
+
+impl LifecyclePhaseStatus {
+    fn new(phase: LifecyclePhaseName, splits: &[&SplitMetadata]) -> Self {
+        LifecyclePhaseStatus {
+            phase,
+            num_splits: splits.len(),
+            num_docs: splits.iter().map(|split| split.num_docs).sum(),
+            size_in_bytes: splits.iter().map(|split| split.footer_offsets.end).sum(),
+            num_pending_merge_operations: None,
+        }
+    }
+}
+
+/// Returns the timestamp from which the age of a split is computed: the end of its time range,
+/// or its creation timestamp if the index has no timestamp field.
+fn split_age_timestamp(split_metadata: &SplitMetadata) -> i64 {
+    split_metadata
+        .time_range
+        .as_ref()
+        .map(|time_range| *time_range.end())
+        .unwrap_or(split_metadata.create_timestamp)
+}
+
+/// Returns whether the split is at least `min_age` old at `now_timestamp`.
+fn is_older_than(split_metadata: &SplitMetadata, min_age: Duration, now_timestamp: i64) -> bool {
+    split_age_timestamp(split_metadata) <= now_timestamp - min_age.as_secs() as i64
+}
+
+/// Assigns each split to the last phase of the lifecycle policy whose `min_age` it has reached.
+pub(crate) fn split_phase(
+    split_metadata: &SplitMetadata,
+    lifecycle_policy: &LifecyclePolicy,
+    now_timestamp: i64,
+) -> anyhow::Result<LifecyclePhaseName> {
+    if let Some(delete_phase) = &lifecycle_policy.phases.delete {
+        if is_older_than(split_metadata, delete_phase.min_age()?, now_timestamp) {
+            return Ok(LifecyclePhaseName::Delete);
+        }
+    }
+    if let Some(force_merge_phase) = &lifecycle_policy.phases.force_merge {
+        if is_older_than(split_metadata, force_merge_phase.min_age()?, now_timestamp) {
+            return Ok(LifecyclePhaseName::ForceMerge);
+        }
+    }
+    Ok(LifecyclePhaseName::Hot)
+}
+
+/// Groups the splits of the `force_merge` phase into merge operations. Splits are merged with
+/// the splits of the same partition and doc mapping, in chronological order, so that the merged
+/// splits contain at most `target_num_docs` documents and cover contiguous time ranges. Splits
+/// that cannot be merged with any other split are left untouched.
+pub(crate) fn plan_force_merge_operations(
+    splits: Vec<SplitMetadata>,
+    force_merge_phase: &ForceMergePhase,
+) -> Vec<Vec<SplitMetadata>> {
+    let mut splits_by_group: BTreeMap<(u64, DocMappingUid), Vec<SplitMetadata>> = BTreeMap::new();
+
+    for split in splits {
+        splits_by_group
+            .entry((split.partition_id, split.doc_mapping_uid))
+            .or_default()
+            .push(split);
+    }
+    let mut merge_operations = Vec::new();
+
+    for mut group_splits in splits_by_group.into_values() {
+        group_splits.sort_by_key(|split| {
+            let start_timestamp = split
+                .time_range
+                .as_ref()
+                .map(|time_range| *time_range.start())
+                .unwrap_or(split.create_timestamp);
+            (start_timestamp, split.split_id.clone())
+        });
+        let mut merge_operation_splits: Vec<SplitMetadata> = Vec::new();
+        let mut merge_operation_num_docs = 0;
+
+        for split in group_splits {
+            if split.num_docs >= force_merge_phase.target_num_docs {
+                continue;
+            }
+            if merge_operation_num_docs + split.num_docs > force_merge_phase.target_num_docs
+                || merge_operation_splits.len() == force_merge_phase.max_merge_factor
+            {
+                let splits = std::mem::take(&mut merge_operation_splits);
+                if splits.len() >= 2 {
+                    merge_operations.push(splits);
+                }
+                merge_operation_num_docs = 0;
+            }
+            merge_operation_num_docs += split.num_docs;
+            merge_operation_splits.push(split);
+        }
+        if merge_operation_splits.len() >= 2 {
+            merge_operations.push(merge_operation_splits);
+        }
+    }
+    merge_operations
+}
+
+/// Computes the lifecycle status of an index from its published splits.
+pub fn compute_index_lifecycle_status(
+    index_id: IndexId,
+    lifecycle_policy_opt: Option<&LifecyclePolicy>,
+    published_splits: &[SplitMetadata],
+    now_timestamp: i64,
+) -> anyhow::Result<IndexLifecycleStatus> {
+    let Some(lifecycle_policy) = lifecycle_policy_opt else {
+        let all_splits: Vec<&SplitMetadata> = published_splits.iter().collect();
+        return Ok(IndexLifecycleStatus {
+            index_id,
+            policy_id: None,
+            phases: vec![LifecyclePhaseStatus::new(
+                LifecyclePhaseName::Hot,
+                &all_splits,
+            )],
+        });
+    };
+    let mut hot_splits = Vec::new();
+    let mut force_merge_splits = Vec::new();
+    let mut delete_splits = Vec::new();
+
+    for split in published_splits {
+        match split_phase(split, lifecycle_policy, now_timestamp)? {
+            LifecyclePhaseName::Hot => hot_splits.push(split),
+            LifecyclePhaseName::ForceMerge => force_merge_splits.push(split),
+            LifecyclePhaseName::Delete => delete_splits.push(split),
+        }
+    }
+    let mut phases = vec![LifecyclePhaseStatus::new(
+        LifecyclePhaseName::Hot,
+        &hot_splits,
+    )];
+    if let Some(force_merge_phase) = &lifecycle_policy.phases.force_merge {
+        let mut phase_status =
+            LifecyclePhaseStatus::new(LifecyclePhaseName::ForceMerge, &force_merge_splits);
+        let merge_operations = plan_force_merge_operations(
+            force_merge_splits.into_iter().cloned().collect(),
+            force_merge_phase,
+        );
+        phase_status.num_pending_merge_operations = Some(merge_operations.len());
+        phases.push(phase_status);
+    }
+    if lifecycle_policy.phases.delete.is_some() {
+        phases.push(LifecyclePhaseStatus::new(
+            LifecyclePhaseName::Delete,
+            &delete_splits,
+        ));
+    }
+    Ok(IndexLifecycleStatus {
+        index_id,
+        policy_id: Some(lifecycle_policy.policy_id.clone()),
+        phases,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 3600;
+    const NOW: i64 = 100 * DAY;
+
+    fn split_for_test(
+        split_id: &str,
+        partition_id: u64,
+        num_docs: usize,
+        end_timestamp: i64,
+    ) -> SplitMetadata {
+        SplitMetadata {
+            split_id: split_id.to_string(),
+            partition_id,
+            num_docs,
+            time_range: Some(end_timestamp - 3600..=end_timestamp),
+            footer_offsets: 0..1_000,
+            ..Default::default()
+        }
+    }
+
+    fn split_ids(merge_operations: &[Vec<SplitMetadata>]) -> Vec<Vec<&str>> {
+        merge_operations
+            .iter()
+            .map(|splits| splits.iter().map(|split| split.split_id()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_split_phase() {
+        let lifecycle_policy = LifecyclePolicy::for_test("logs", &["logs-*"]);
+
+        let hot_split = split_for_test("hot", 0, 10, NOW - 3600);
+        let force_merge_split = split_for_test("force-merge", 0, 10, NOW - 2 * DAY);
+        let delete_split = split_for_test("delete", 0, 10, NOW - 31 * DAY);
+        let mut split_without_time_range = split_for_test("no-time-range", 0, 10, 0);
+        split_without_time_range.time_range = None;
+        split_without_time_range.create_timestamp = NOW - 2 * DAY;
+
+        let phase = |split| split_phase(split, &lifecycle_policy, NOW).unwrap();
+        assert_eq!(phase(&hot_split), LifecyclePhaseName::Hot);
+        assert_eq!(phase(&force_merge_split), LifecyclePhaseName::ForceMerge);
+        assert_eq!(phase(&delete_split), LifecyclePhaseName::Delete);
+        assert_eq!(
+            phase(&split_without_time_range),
+            LifecyclePhaseName::ForceMerge
+        );
+    }
+
+    #[test]
+    fn test_plan_force_merge_operations() {
+        let force_merge_phase = ForceMergePhase {
+            min_age: "1 day".to_string(),
+            target_num_docs: 100,
+            max_merge_factor: 3,
+        };
+        let splits = vec![
+            split_for_test("a", 0, 40, NOW - 10 * DAY),
+            split_for_test("b", 0, 40, NOW - 9 * DAY),
+            // Would exceed the target number of docs.
+            split_for_test("c", 0, 40, NOW - 8 * DAY),
+            split_for_test("d", 0, 10, NOW - 7 * DAY),
+            split_for_test("e", 0, 10, NOW - 6 * DAY),
+            // Would exceed the max merge factor.
+            split_for_test("f", 0, 10, NOW - 5 * DAY),
+            // Already large enough.
+            split_for_test("g", 0, 100, NOW - 4 * DAY),
+            // Alone in its partition.
+            split_for_test("h", 1, 10, NOW - 3 * DAY),
+        ];
+        let merge_operations = plan_force_merge_operations(splits, &force_merge_phase);
+        assert_eq!(
+            split_ids(&merge_operations),
+            [vec!["a", "b"], vec!["c", "d", "e"]]
+        );
+    }
+
+    #[test]
+    fn test_compute_index_lifecycle_status() {
+        let lifecycle_policy = LifecyclePolicy::for_test("logs", &["logs-*"]);
+        let splits = vec![
+            split_for_test("hot", 0, 10, NOW - 3600),
+            split_for_test("force-merge-1", 0, 20, NOW - 2 * DAY),
+            split_for_test("force-merge-2", 0, 30, NOW - 3 * DAY),
+            split_for_test("delete", 0, 40, NOW - 31 * DAY),
+        ];
+        let index_lifecycle_status = compute_index_lifecycle_status(
+            "logs-1".to_string(),
+            Some(&lifecycle_policy),
+            &splits,
+            NOW,
+        )
+        .unwrap();
+        assert_eq!(index_lifecycle_status.policy_id.as_deref(), Some("logs"));
+        assert_eq!(
+            serde_json::to_value(&index_lifecycle_status.phases).unwrap(),
+            serde_json::json!([
+                {"phase": "hot", "num_splits": 1, "num_docs": 10, "size_in_bytes": 1_000},
+                {
+                    "phase": "force_merge",
+                    "num_splits": 2,
+                    "num_docs": 50,
+                    "size_in_bytes": 2_000,
+                    "num_pending_merge_operations": 1
+                },
+                {"phase": "delete", "num_splits": 1, "num_docs": 40, "size_in_bytes": 1_000},
+            ])
+        );
+        let index_lifecycle_status =
+            compute_index_lifecycle_status("metrics-1".to_string(), None, &splits, NOW).unwrap();
+        assert!(index_lifecycle_status.policy_id.is_none());
+        assert_eq!(index_lifecycle_status.phases.len(), 1);
+        assert_eq!(index_lifecycle_status.phases[0].num_splits, 4);
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use quickwit_config::LifecyclePolicy;
+use quickwit_proto::metastore::{
+    serde_utils, CreateLifecyclePolicyRequest, DeleteLifecyclePolicyRequest,
+    ListLifecyclePoliciesRequest, MetastoreError, MetastoreService, MetastoreServiceClient,
+};
+
+use crate::error::JanitorError;
+
+/// Stores the lifecycle policies in the metastore, which serializes concurrent updates issued by
+/// the nodes of a cluster.
+#[derive(Clone)]
+pub struct LifecyclePolicyStore {
+    metastore: MetastoreServiceClient,
+}
+
+impl LifecyclePolicyStore {
+    pub fn new(metastore: MetastoreServiceClient) -> Self {
+        Self { metastore }
+    }
+
+    /// Returns the lifecycle policies sorted by policy ID.
+    pub async fn list_policies(&self) -> Result<Vec<LifecyclePolicy>, JanitorError> {
+        let list_lifecycle_policies_response = self
+            .metastore
+            .clone()
+            .list_lifecycle_policies(ListLifecyclePoliciesRequest {})
+            .await?;
+        let mut lifecycle_policies = list_lifecycle_policies_response
+            .lifecycle_policies_json
+            .iter()
+            .map(|lifecycle_policy_json| serde_utils::from_json_str(lifecycle_policy_json))
+            .collect::<Result<Vec<LifecyclePolicy>, MetastoreError>>()?;
+        lifecycle_policies.sort_by(|left, right| left.policy_id.cmp(&right.policy_id));
+        Ok(lifecycle_policies)
+    }
+
+    pub async fn get_policy(&self, policy_id: &str) -> Result<LifecyclePolicy, JanitorError> {
+        self.list_policies()
+            .await?
+            .into_iter()
+            .find(|lifecycle_policy| lifecycle_policy.policy_id == policy_id)
+            .ok_or_else(|| JanitorError::LifecyclePolicyNotFound(policy_id.to_string()))
+    }
+
+    /// Validates and stores the policy. If `overwrite` is false, the request fails if a policy
+    /// with the same ID already exists.
+    pub async fn put_policy(
+        &self,
+        lifecycle_policy: LifecyclePolicy,
+        overwrite: bool,
+    ) -> Result<(), JanitorError> {
+        lifecycle_policy
+            .validate()
+            .map_err(|error| JanitorError::InvalidLifecyclePolicy(error.to_string()))?;
+
+        let create_lifecycle_policy_request = CreateLifecyclePolicyRequest {
+            lifecycle_policy_json: serde_utils::to_json_str(&lifecycle_policy)?,
+            overwrite,
+        };
+        match self
+            .metastore
+            .clone()
+            .create_lifecycle_policy(create_lifecycle_policy_request)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(MetastoreError::AlreadyExists(_)) => Err(
+                JanitorError::LifecyclePolicyAlreadyExists(lifecycle_policy.policy_id),
+            ),
+            Err(MetastoreError::InvalidArgument { message }) => {
+                Err(JanitorError::InvalidLifecyclePolicy(message))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub async fn delete_policy(&self, policy_id: &str) -> Result<(), JanitorError> {
+        let delete_lifecycle_policy_request = DeleteLifecyclePolicyRequest {
+            policy_id: policy_id.to_string(),
+        };
+        match self
+            .metastore
+            .clone()
+            .delete_lifecycle_policy(delete_lifecycle_policy_request)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(MetastoreError::NotFound(_)) => {
+                Err(JanitorError::LifecyclePolicyNotFound(policy_id.to_string()))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_metastore::metastore_for_test;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_lifecycle_policy_store() {
+        let lifecycle_policy_store = LifecyclePolicyStore::new(metastore_for_test());
+        assert!(lifecycle_policy_store
+            .list_policies()
+            .await
+            .unwrap()
+            .is_empty());
+
+        let logs_policy = LifecyclePolicy::for_test("logs", &["logs-*"]);
+        lifecycle_policy_store
+            .put_policy(logs_policy.clone(), false)
+            .await
+            .unwrap();
+        let error = lifecycle_policy_store
+            .put_policy(logs_policy.clone(), false)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            JanitorError::LifecyclePolicyAlreadyExists(_)
+        ));
+
+        let mut updated_logs_policy = logs_policy.clone();
+        updated_logs_policy.priority = 1;
+        lifecycle_policy_store
+            .put_policy(updated_logs_policy.clone(), true)
+            .await
+            .unwrap();
+        assert_eq!(
+            lifecycle_policy_store.get_policy("logs").await.unwrap(),
+            updated_logs_policy
+        );
+        let mut invalid_policy = LifecyclePolicy::for_test("metrics", &["metrics-*"]);
+        invalid_policy.index_id_patterns.clear();
+        let error = lifecycle_policy_store
+            .put_policy(invalid_policy, false)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, JanitorError::InvalidLifecyclePolicy(_)));
+
+        lifecycle_policy_store.delete_policy("logs").await.unwrap();
+        let error = lifecycle_policy_store
+            .delete_policy("logs")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, JanitorError::LifecyclePolicyNotFound(_)));
+        assert!(lifecycle_policy_store
+            .list_policies()
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
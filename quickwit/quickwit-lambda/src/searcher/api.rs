@@ -73,20 +73,37 @@ fn native_api(
 fn es_compat_api(
     search_service: Arc<dyn SearchService>,
     metastore: MetastoreServiceClient,
+    index_alias_store: IndexAliasStore,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     es_compat_search_handler(search_service.clone())
-        .or(es_compat_index_search_handler(search_service.clone()))
-        .or(es_compat_index_count_handler(search_service.clone()))
+        .or(es_compat_index_search_handler(
+            search_service.clone(),
+            index_alias_store.clone(),
+        ))
+        .or(es_compat_index_count_handler(
+            search_service.clone(),
+            index_alias_store.clone(),
+        ))
         .or(es_compat_scroll_handler(search_service.clone()))
-        .or(es_compat_index_multi_search_handler(search_service.clone()))
+        .or(es_compat_index_multi_search_handler(
+            search_service.clone(),
+            index_alias_store.clone(),
+        ))
         .or(es_compat_index_field_capabilities_handler(
             search_service.clone(),
         ))
         .or(es_compat_index_stats_handler(metastore.clone()))
         .or(es_compat_stats_handler(metastore.clone()))
+        .or(es_compat_index_mapping_handler(metastore.clone()))
+        .or(es_compat_mapping_handler(metastore.clone()))
+        .or(es_compat_analyze_handler())
+        .or(es_compat_index_analyze_handler(metastore.clone()))
         .or(es_compat_index_cat_indices_handler(metastore.clone()))
         .or(es_compat_cat_indices_handler(metastore.clone()))
+        .or(es_compat_index_cat_count_handler(metastore.clone()))
+        .or(es_compat_cat_count_handler(metastore.clone()))
         .or(es_compat_resolve_index_handler(metastore.clone()))
+        .or(es_compat_get_aliases_handler(index_alias_store))
 }
 
 fn index_api(
@@ -98,11 +115,16 @@ fn index_api(
 fn v1_searcher_api(
     search_service: Arc<dyn SearchService>,
     metastore: MetastoreServiceClient,
+    index_alias_store: IndexAliasStore,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     warp::path!("api" / "v1" / ..)
         .and(
             native_api(search_service.clone())
-                .or(es_compat_api(search_service, metastore.clone()))
+                .or(es_compat_api(
+                    search_service,
+                    metastore.clone(),
+                    index_alias_store,
+                ))
                 .or(index_api(metastore)),
         )
         .with(warp::filters::compression::gzip())
@@ -123,6 +145,7 @@ pub async fn setup_searcher_api(
     );
     let _telemetry_handle_opt = quickwit_telemetry::start_telemetry_loop(telemetry_info);
 
+    let index_alias_store = IndexAliasStore::new(metastore.clone());
     let search_service = create_local_search_service(
         node_config.searcher_config,
         metastore.clone(),
@@ -149,7 +172,11 @@ pub async fn setup_searcher_api(
 
     let api = warp::any()
         .and(before_hook)
-        .and(v1_searcher_api(search_service, metastore))
+        .and(v1_searcher_api(
+            search_service,
+            metastore,
+            index_alias_store,
+        ))
         .with(after_hook);
 
     Ok(api)
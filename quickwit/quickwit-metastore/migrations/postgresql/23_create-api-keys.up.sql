@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS api_keys (
+    api_key_id VARCHAR(255) NOT NULL,
+    api_key_json TEXT NOT NULL,
+    PRIMARY KEY (api_key_id)
+);
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS roles (
+    role_id VARCHAR(255) NOT NULL,
+    role_json TEXT NOT NULL,
+    PRIMARY KEY (role_id)
+);
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS point_in_times (
+    point_in_time_key VARCHAR(255) NOT NULL,
+    point_in_time_json TEXT NOT NULL,
+    PRIMARY KEY (point_in_time_key)
+);
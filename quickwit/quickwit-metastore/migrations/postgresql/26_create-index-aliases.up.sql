@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS index_aliases (
+    alias VARCHAR(255) NOT NULL,
+    alias_indexes_json TEXT NOT NULL,
+    PRIMARY KEY (alias)
+);
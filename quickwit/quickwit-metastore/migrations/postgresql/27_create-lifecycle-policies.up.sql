@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS lifecycle_policies (
+    policy_id VARCHAR(255) NOT NULL,
+    lifecycle_policy_json TEXT NOT NULL,
+    PRIMARY KEY (policy_id)
+);
@@ -34,6 +34,7 @@ mod error;
 mod metastore;
 mod metastore_factory;
 mod metastore_resolver;
+mod point_in_time;
 mod split_metadata;
 mod split_metadata_version;
 #[cfg(test)]
@@ -49,16 +50,19 @@ pub(crate) use metastore::index_metadata::serialize::{IndexMetadataV0_8, Version
 pub use metastore::postgres::PostgresqlMetastore;
 pub use metastore::{
     file_backed, AddSourceRequestExt, CreateIndexRequestExt, CreateIndexResponseExt, IndexMetadata,
-    IndexMetadataResponseExt, IndexesMetadataResponseExt, ListIndexesMetadataResponseExt,
-    ListSplitsQuery, ListSplitsRequestExt, ListSplitsResponseExt, MetastoreServiceExt,
-    MetastoreServiceStreamSplitsExt, PublishSplitsRequestExt, StageSplitsRequestExt,
-    UpdateIndexRequestExt,
+    IndexMetadataResponseExt, IndexesMetadataResponseExt, ListIndexAliasesResponseExt,
+    ListIndexesMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt, ListSplitsResponseExt,
+    MetastoreServiceExt, MetastoreServiceStreamSplitsExt, PublishSplitsRequestExt,
+    StageSplitsRequestExt, UpdateIndexAliasesRequestExt, UpdateIndexRequestExt,
 };
 pub use metastore_factory::{MetastoreFactory, UnsupportedMetastore};
 pub use metastore_resolver::MetastoreResolver;
+pub use point_in_time::{pinned_split_ids, PointInTime};
 use quickwit_common::is_disjoint;
 use quickwit_doc_mapper::tag_pruning::TagFilterAst;
-pub use split_metadata::{Split, SplitInfo, SplitMaturity, SplitMetadata, SplitState};
+pub use split_metadata::{
+    FieldValueRange, Split, SplitInfo, SplitMaturity, SplitMetadata, SplitState,
+};
 pub(crate) use split_metadata_version::{SplitMetadataV0_8, VersionedSplitMetadata};
 
 #[derive(utoipa::OpenApi)]
@@ -23,21 +23,26 @@ use async_trait::async_trait;
 use quickwit_common::uri::Uri;
 use quickwit_proto::control_plane::{ControlPlaneService, ControlPlaneServiceClient};
 use quickwit_proto::metastore::{
-    AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest, CreateIndexRequest,
-    CreateIndexResponse, CreateIndexTemplateRequest, DeleteIndexRequest,
-    DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest, DeleteShardsResponse,
-    DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse,
-    FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse, GetIndexTemplateRequest,
-    GetIndexTemplateResponse, IndexMetadataRequest, IndexMetadataResponse, IndexesMetadataRequest,
-    IndexesMetadataResponse, LastDeleteOpstampRequest, LastDeleteOpstampResponse,
-    ListDeleteTasksRequest, ListDeleteTasksResponse, ListIndexTemplatesRequest,
-    ListIndexTemplatesResponse, ListIndexesMetadataRequest, ListIndexesMetadataResponse,
+    AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest, CreateApiKeyRequest,
+    CreateIndexRequest, CreateIndexResponse, CreateIndexTemplateRequest,
+    CreateLifecyclePolicyRequest, CreatePointInTimeRequest, CreateRoleRequest, DeleteApiKeyRequest,
+    DeleteIndexAliasesRequest, DeleteIndexRequest, DeleteIndexTemplatesRequest,
+    DeleteLifecyclePolicyRequest, DeletePointInTimesRequest, DeleteQuery, DeleteRoleRequest,
+    DeleteShardsRequest, DeleteShardsResponse, DeleteSourceRequest, DeleteSplitsRequest,
+    DeleteTask, EmptyResponse, FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse,
+    GetIndexTemplateRequest, GetIndexTemplateResponse, IndexMetadataRequest, IndexMetadataResponse,
+    IndexesMetadataRequest, IndexesMetadataResponse, LastDeleteOpstampRequest,
+    LastDeleteOpstampResponse, ListApiKeysRequest, ListApiKeysResponse, ListDeleteTasksRequest,
+    ListDeleteTasksResponse, ListIndexAliasesRequest, ListIndexAliasesResponse,
+    ListIndexTemplatesRequest, ListIndexTemplatesResponse, ListIndexesMetadataRequest,
+    ListIndexesMetadataResponse, ListLifecyclePoliciesRequest, ListLifecyclePoliciesResponse,
+    ListPointInTimesRequest, ListPointInTimesResponse, ListRolesRequest, ListRolesResponse,
     ListShardsRequest, ListShardsResponse, ListSplitsRequest, ListSplitsResponse,
     ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreResult, MetastoreService,
     MetastoreServiceClient, MetastoreServiceStream, OpenShardsRequest, OpenShardsResponse,
     PruneShardsRequest, PruneShardsResponse, PublishSplitsRequest, ResetSourceCheckpointRequest,
-    StageSplitsRequest, ToggleSourceRequest, UpdateIndexRequest, UpdateSplitsDeleteOpstampRequest,
-    UpdateSplitsDeleteOpstampResponse,
+    StageSplitsRequest, ToggleSourceRequest, UpdateIndexAliasesRequest, UpdateIndexRequest,
+    UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
 };
 
 /// A [`MetastoreService`] implementation that proxies some requests to the control plane so it can
@@ -281,4 +286,104 @@ impl MetastoreService for ControlPlaneMetastore {
     ) -> MetastoreResult<EmptyResponse> {
         self.metastore.delete_index_templates(request).await
     }
+
+    // API Key API
+
+    async fn create_api_key(&self, request: CreateApiKeyRequest) -> MetastoreResult<EmptyResponse> {
+        self.metastore.create_api_key(request).await
+    }
+
+    async fn list_api_keys(
+        &self,
+        request: ListApiKeysRequest,
+    ) -> MetastoreResult<ListApiKeysResponse> {
+        self.metastore.list_api_keys(request).await
+    }
+
+    async fn delete_api_key(&self, request: DeleteApiKeyRequest) -> MetastoreResult<EmptyResponse> {
+        self.metastore.delete_api_key(request).await
+    }
+
+    // Role API
+
+    async fn create_role(&self, request: CreateRoleRequest) -> MetastoreResult<EmptyResponse> {
+        self.metastore.create_role(request).await
+    }
+
+    async fn list_roles(&self, request: ListRolesRequest) -> MetastoreResult<ListRolesResponse> {
+        self.metastore.list_roles(request).await
+    }
+
+    async fn delete_role(&self, request: DeleteRoleRequest) -> MetastoreResult<EmptyResponse> {
+        self.metastore.delete_role(request).await
+    }
+
+    // Point-in-time API
+
+    async fn create_point_in_time(
+        &self,
+        request: CreatePointInTimeRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.create_point_in_time(request).await
+    }
+
+    async fn list_point_in_times(
+        &self,
+        request: ListPointInTimesRequest,
+    ) -> MetastoreResult<ListPointInTimesResponse> {
+        self.metastore.list_point_in_times(request).await
+    }
+
+    async fn delete_point_in_times(
+        &self,
+        request: DeletePointInTimesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.delete_point_in_times(request).await
+    }
+
+    // Index alias API
+
+    async fn update_index_aliases(
+        &self,
+        request: UpdateIndexAliasesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.update_index_aliases(request).await
+    }
+
+    async fn list_index_aliases(
+        &self,
+        request: ListIndexAliasesRequest,
+    ) -> MetastoreResult<ListIndexAliasesResponse> {
+        self.metastore.list_index_aliases(request).await
+    }
+
+    async fn delete_index_aliases(
+        &self,
+        request: DeleteIndexAliasesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.delete_index_aliases(request).await
+    }
+
+    // Lifecycle policy API
+
+    async fn create_lifecycle_policy(
+        &self,
+        request: CreateLifecyclePolicyRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.create_lifecycle_policy(request).await
+    }
+
+    async fn list_lifecycle_policies(
+        &self,
+        request: ListLifecyclePoliciesRequest,
+    ) -> MetastoreResult<ListLifecyclePoliciesResponse> {
+        self.metastore.list_lifecycle_policies(request).await
+    }
+
+    async fn delete_lifecycle_policy(
+        &self,
+        request: DeleteLifecyclePolicyRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.delete_lifecycle_policy(request).await
+    }
 }
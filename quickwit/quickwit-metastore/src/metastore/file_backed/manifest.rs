@@ -22,13 +22,18 @@ use std::path::Path;
 
 use itertools::Itertools;
 use quickwit_common::uri::Uri;
-use quickwit_config::{IndexTemplate, IndexTemplateId};
+use quickwit_config::{
+    ApiKey, ApiKeyId, IndexAliases, IndexTemplate, IndexTemplateId, LifecyclePolicy,
+    LifecyclePolicyId, Role, RoleId,
+};
 use quickwit_proto::metastore::{serde_utils, MetastoreError, MetastoreResult};
 use quickwit_proto::types::{DocMappingUid, IndexId};
 use quickwit_storage::{OwnedBytes, Storage, StorageError, StorageErrorKind, StorageResult};
 use serde::{Deserialize, Serialize};
 use tracing::error;
 
+use crate::PointInTime;
+
 pub(super) const MANIFEST_FILE_NAME: &str = "manifest.json";
 
 // The legacy manifest file was deprecated in 0.8.0, we can drop support for it in 0.10.0 or 0.11.0.
@@ -45,6 +50,11 @@ impl LegacyManifest {
         Manifest {
             indexes: self.indexes,
             templates: HashMap::new(),
+            api_keys: HashMap::new(),
+            roles: HashMap::new(),
+            point_in_times: HashMap::new(),
+            index_aliases: IndexAliases::default(),
+            lifecycle_policies: HashMap::new(),
         }
     }
 }
@@ -69,6 +79,11 @@ pub(crate) struct Manifest {
     // The templates are serialized as a sorted `Vec<IndexTemplate>` so the btree map is
     // unnecessary here and we can pass the hash map as is to the `MetastoreState`
     pub templates: HashMap<IndexTemplateId, IndexTemplate>,
+    pub api_keys: HashMap<ApiKeyId, ApiKey>,
+    pub roles: HashMap<RoleId, Role>,
+    pub point_in_times: HashMap<String, PointInTime>,
+    pub index_aliases: IndexAliases,
+    pub lifecycle_policies: HashMap<LifecyclePolicyId, LifecyclePolicy>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -108,6 +123,21 @@ impl From<VersionedManifest> for Manifest {
 struct ManifestV0_8 {
     indexes: BTreeMap<IndexId, IndexStatus>,
     templates: Vec<IndexTemplate>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    api_keys: Vec<ApiKey>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    roles: Vec<Role>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    point_in_times: Vec<PointInTime>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "IndexAliases::is_empty")]
+    index_aliases: IndexAliases,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    lifecycle_policies: Vec<LifecyclePolicy>,
 }
 
 impl From<Manifest> for ManifestV0_8 {
@@ -117,9 +147,34 @@ impl From<Manifest> for ManifestV0_8 {
             .into_values()
             .sorted_unstable_by(|left, right| left.template_id.cmp(&right.template_id))
             .collect();
+        let api_keys = manifest
+            .api_keys
+            .into_values()
+            .sorted_unstable_by(|left, right| left.api_key_id.cmp(&right.api_key_id))
+            .collect();
+        let roles = manifest
+            .roles
+            .into_values()
+            .sorted_unstable_by(|left, right| left.role_id.cmp(&right.role_id))
+            .collect();
+        let point_in_times = manifest
+            .point_in_times
+            .into_values()
+            .sorted_unstable_by(|left, right| left.point_in_time_key.cmp(&right.point_in_time_key))
+            .collect();
+        let lifecycle_policies = manifest
+            .lifecycle_policies
+            .into_values()
+            .sorted_unstable_by(|left, right| left.policy_id.cmp(&right.policy_id))
+            .collect();
         ManifestV0_8 {
             indexes: manifest.indexes,
             templates,
+            api_keys,
+            roles,
+            point_in_times,
+            index_aliases: manifest.index_aliases,
+            lifecycle_policies,
         }
     }
 }
@@ -132,7 +187,35 @@ impl From<ManifestV0_8> for Manifest {
             .into_iter()
             .map(|template| (template.template_id.clone(), template))
             .collect();
-        Manifest { indexes, templates }
+        let api_keys = manifest
+            .api_keys
+            .into_iter()
+            .map(|api_key| (api_key.api_key_id.clone(), api_key))
+            .collect();
+        let roles = manifest
+            .roles
+            .into_iter()
+            .map(|role| (role.role_id.clone(), role))
+            .collect();
+        let point_in_times = manifest
+            .point_in_times
+            .into_iter()
+            .map(|point_in_time| (point_in_time.point_in_time_key.clone(), point_in_time))
+            .collect();
+        let lifecycle_policies = manifest
+            .lifecycle_policies
+            .into_iter()
+            .map(|lifecycle_policy| (lifecycle_policy.policy_id.clone(), lifecycle_policy))
+            .collect();
+        Manifest {
+            indexes,
+            templates,
+            api_keys,
+            roles,
+            point_in_times,
+            index_aliases: manifest.index_aliases,
+            lifecycle_policies,
+        }
     }
 }
 
@@ -149,12 +232,25 @@ impl quickwit_config::TestableForRegression for Manifest {
             "test-template-1".to_string(),
             IndexTemplate::sample_for_regression(),
         );
-        Manifest { indexes, templates }
+        Manifest {
+            indexes,
+            templates,
+            api_keys: HashMap::new(),
+            roles: HashMap::new(),
+            point_in_times: HashMap::new(),
+            index_aliases: IndexAliases::default(),
+            lifecycle_policies: HashMap::new(),
+        }
     }
 
     fn assert_equality(&self, other: &Self) {
         assert_eq!(self.indexes, other.indexes);
         assert_eq!(self.templates, other.templates);
+        assert_eq!(self.api_keys, other.api_keys);
+        assert_eq!(self.roles, other.roles);
+        assert_eq!(self.point_in_times, other.point_in_times);
+        assert_eq!(self.index_aliases, other.index_aliases);
+        assert_eq!(self.lifecycle_policies, other.lifecycle_policies);
     }
 }
 
@@ -249,6 +345,10 @@ fn into_metastore_error(
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
+
+    use quickwit_config::{ApiKeyScope, IndexAliasAction, IndexAliasConfig, IndexPrivilege};
+    use quickwit_proto::types::IndexUid;
     use serde_json::json;
 
     use super::*;
@@ -325,7 +425,46 @@ mod tests {
                 IndexTemplate::for_test("test-template-2", &["test-index-bar*"], 200),
             ),
         ]);
-        let manifest = Manifest { indexes, templates };
+        let api_keys = HashMap::from_iter([(
+            "test-api-key".to_string(),
+            ApiKey::for_test("test-api-key", &[ApiKeyScope::Search]),
+        )]);
+        let roles = HashMap::from_iter([(
+            "test-role".to_string(),
+            Role::for_test("test-role", &["test-index-*"], IndexPrivilege::Read),
+        )]);
+        let point_in_times = HashMap::from_iter([(
+            "test-point-in-time".to_string(),
+            PointInTime {
+                point_in_time_key: "test-point-in-time".to_string(),
+                split_ids: BTreeMap::from_iter([(
+                    IndexUid::for_test("test-index-1", 0),
+                    BTreeSet::from_iter(["test-split".to_string()]),
+                )]),
+                expire_at: 1_700_000_000,
+            },
+        )]);
+        let mut index_aliases = IndexAliases::default();
+        index_aliases
+            .apply_actions(vec![IndexAliasAction::Add {
+                index_ids: vec!["test-index-1".to_string()],
+                aliases: vec!["test-alias".to_string()],
+                config: IndexAliasConfig::default(),
+            }])
+            .unwrap();
+        let lifecycle_policies = HashMap::from_iter([(
+            "test-lifecycle-policy".to_string(),
+            LifecyclePolicy::for_test("test-lifecycle-policy", &["test-index-*"]),
+        )]);
+        let manifest = Manifest {
+            indexes,
+            templates,
+            api_keys,
+            roles,
+            point_in_times,
+            index_aliases,
+            lifecycle_policies,
+        };
         let manifest_json = serde_json::to_string_pretty(&manifest).unwrap();
         let manifest_deserialized: Manifest = serde_json::from_str(&manifest_json).unwrap();
         assert_eq!(manifest, manifest_deserialized);
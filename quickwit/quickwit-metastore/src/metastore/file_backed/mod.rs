@@ -43,24 +43,30 @@ use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use itertools::Itertools;
 use quickwit_common::ServiceStream;
-use quickwit_config::IndexTemplate;
+use quickwit_config::{ApiKey, IndexTemplate, LifecyclePolicy, Role};
 use quickwit_proto::metastore::{
-    serde_utils, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest, CreateIndexRequest,
-    CreateIndexResponse, CreateIndexTemplateRequest, DeleteIndexRequest,
-    DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest, DeleteShardsResponse,
-    DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse, EntityKind,
-    FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse, GetIndexTemplateRequest,
-    GetIndexTemplateResponse, IndexMetadataFailure, IndexMetadataFailureReason,
-    IndexMetadataRequest, IndexMetadataResponse, IndexTemplateMatch, IndexesMetadataRequest,
-    IndexesMetadataResponse, LastDeleteOpstampRequest, LastDeleteOpstampResponse,
-    ListDeleteTasksRequest, ListDeleteTasksResponse, ListIndexTemplatesRequest,
-    ListIndexTemplatesResponse, ListIndexesMetadataRequest, ListIndexesMetadataResponse,
+    serde_utils, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest,
+    CreateApiKeyRequest, CreateIndexRequest, CreateIndexResponse, CreateIndexTemplateRequest,
+    CreateLifecyclePolicyRequest, CreatePointInTimeRequest, CreateRoleRequest, DeleteApiKeyRequest,
+    DeleteIndexAliasesRequest, DeleteIndexRequest, DeleteIndexTemplatesRequest,
+    DeleteLifecyclePolicyRequest, DeletePointInTimesRequest, DeleteQuery, DeleteRoleRequest,
+    DeleteShardsRequest, DeleteShardsResponse, DeleteSourceRequest, DeleteSplitsRequest,
+    DeleteTask, EmptyResponse, EntityKind, FindIndexTemplateMatchesRequest,
+    FindIndexTemplateMatchesResponse, GetIndexTemplateRequest, GetIndexTemplateResponse,
+    IndexMetadataFailure, IndexMetadataFailureReason, IndexMetadataRequest, IndexMetadataResponse,
+    IndexTemplateMatch, IndexesMetadataRequest, IndexesMetadataResponse, LastDeleteOpstampRequest,
+    LastDeleteOpstampResponse, ListApiKeysRequest, ListApiKeysResponse, ListDeleteTasksRequest,
+    ListDeleteTasksResponse, ListIndexAliasesRequest, ListIndexAliasesResponse,
+    ListIndexTemplatesRequest, ListIndexTemplatesResponse, ListIndexesMetadataRequest,
+    ListIndexesMetadataResponse, ListLifecyclePoliciesRequest, ListLifecyclePoliciesResponse,
+    ListPointInTimesRequest, ListPointInTimesResponse, ListRolesRequest, ListRolesResponse,
     ListShardsRequest, ListShardsResponse, ListSplitsRequest, ListSplitsResponse,
     ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError, MetastoreResult,
     MetastoreService, MetastoreServiceStream, OpenShardSubrequest, OpenShardsRequest,
     OpenShardsResponse, PruneShardsRequest, PruneShardsResponse, PublishSplitsRequest,
-    ResetSourceCheckpointRequest, StageSplitsRequest, ToggleSourceRequest, UpdateIndexRequest,
-    UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
+    ResetSourceCheckpointRequest, StageSplitsRequest, ToggleSourceRequest,
+    UpdateIndexAliasesRequest, UpdateIndexRequest, UpdateSplitsDeleteOpstampRequest,
+    UpdateSplitsDeleteOpstampResponse,
 };
 use quickwit_proto::types::{IndexId, IndexUid};
 use quickwit_storage::Storage;
@@ -75,13 +81,14 @@ use self::manifest::{load_or_create_manifest, save_manifest, MANIFEST_FILE_NAME}
 use self::state::MetastoreState;
 use self::store_operations::{delete_index, index_exists, load_index, put_index};
 use super::{
-    AddSourceRequestExt, CreateIndexRequestExt, IndexMetadataResponseExt,
-    IndexesMetadataResponseExt, ListIndexesMetadataResponseExt, ListSplitsRequestExt,
-    ListSplitsResponseExt, PublishSplitsRequestExt, StageSplitsRequestExt, UpdateIndexRequestExt,
+    apply_index_alias_actions, AddSourceRequestExt, CreateIndexRequestExt,
+    IndexMetadataResponseExt, IndexesMetadataResponseExt, ListIndexAliasesResponseExt,
+    ListIndexesMetadataResponseExt, ListSplitsRequestExt, ListSplitsResponseExt,
+    PublishSplitsRequestExt, StageSplitsRequestExt, UpdateIndexRequestExt,
     STREAM_SPLITS_CHUNK_SIZE,
 };
 use crate::checkpoint::IndexCheckpointDelta;
-use crate::{IndexMetadata, ListSplitsQuery, MetastoreServiceExt, Split, SplitState};
+use crate::{IndexMetadata, ListSplitsQuery, MetastoreServiceExt, PointInTime, Split, SplitState};
 
 /// Status of an index tracked by the metastore.
 pub(crate) enum LazyIndexStatus {
@@ -1135,6 +1142,412 @@ impl MetastoreService for FileBackedMetastore {
         }
         Ok(EmptyResponse {})
     }
+
+    // API Key API
+
+    async fn create_api_key(&self, request: CreateApiKeyRequest) -> MetastoreResult<EmptyResponse> {
+        let api_key: ApiKey = serde_utils::from_json_str(&request.api_key_json)?;
+
+        api_key
+            .validate()
+            .map_err(|error| MetastoreError::InvalidArgument {
+                message: format!("invalid API key `{}`: `{error}`", api_key.api_key_id),
+            })?;
+        let api_key_id = api_key.api_key_id.clone();
+
+        let mut state_wlock_guard = self.state.write().await;
+
+        let evicted_api_key_opt = match state_wlock_guard.api_keys.entry(api_key_id.clone()) {
+            Entry::Vacant(entry) => {
+                entry.insert(api_key);
+                None
+            }
+            Entry::Occupied(mut entry) if request.overwrite => Some(entry.insert(api_key)),
+            Entry::Occupied(_) => {
+                return Err(MetastoreError::AlreadyExists(EntityKind::ApiKey {
+                    api_key_id,
+                }));
+            }
+        };
+        let manifest = state_wlock_guard.as_manifest();
+        let save_result = save_manifest(&*self.storage, &manifest).await;
+
+        // Rollback on error.
+        if let Err(error) = save_result {
+            if let Some(evicted_api_key) = evicted_api_key_opt {
+                state_wlock_guard
+                    .api_keys
+                    .insert(api_key_id, evicted_api_key);
+            } else {
+                state_wlock_guard.api_keys.remove(&api_key_id);
+            }
+            return Err(error);
+        }
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_api_keys(
+        &self,
+        _request: ListApiKeysRequest,
+    ) -> MetastoreResult<ListApiKeysResponse> {
+        let inner_rlock_guard = self.state.read().await;
+
+        let api_keys_json: Vec<String> = inner_rlock_guard
+            .api_keys
+            .values()
+            .sorted_unstable_by(|left, right| left.api_key_id.cmp(&right.api_key_id))
+            .map(serde_utils::to_json_str)
+            .collect::<MetastoreResult<_>>()?;
+        let response = ListApiKeysResponse { api_keys_json };
+        Ok(response)
+    }
+
+    async fn delete_api_key(&self, request: DeleteApiKeyRequest) -> MetastoreResult<EmptyResponse> {
+        let mut state_wlock_guard = self.state.write().await;
+
+        let Some(evicted_api_key) = state_wlock_guard.api_keys.remove(&request.api_key_id) else {
+            return Err(MetastoreError::NotFound(EntityKind::ApiKey {
+                api_key_id: request.api_key_id,
+            }));
+        };
+        let manifest = state_wlock_guard.as_manifest();
+        let save_result = save_manifest(&*self.storage, &manifest).await;
+
+        // Rollback on error.
+        if let Err(error) = save_result {
+            state_wlock_guard
+                .api_keys
+                .insert(request.api_key_id, evicted_api_key);
+            return Err(error);
+        }
+        Ok(EmptyResponse {})
+    }
+
+    // Role API
+
+    async fn create_role(&self, request: CreateRoleRequest) -> MetastoreResult<EmptyResponse> {
+        let role: Role = serde_utils::from_json_str(&request.role_json)?;
+
+        role.validate()
+            .map_err(|error| MetastoreError::InvalidArgument {
+                message: format!("invalid role `{}`: `{error}`", role.role_id),
+            })?;
+        let role_id = role.role_id.clone();
+
+        let mut state_wlock_guard = self.state.write().await;
+
+        let evicted_role_opt = match state_wlock_guard.roles.entry(role_id.clone()) {
+            Entry::Vacant(entry) => {
+                entry.insert(role);
+                None
+            }
+            Entry::Occupied(mut entry) if request.overwrite => Some(entry.insert(role)),
+            Entry::Occupied(_) => {
+                return Err(MetastoreError::AlreadyExists(EntityKind::Role { role_id }));
+            }
+        };
+        let manifest = state_wlock_guard.as_manifest();
+        let save_result = save_manifest(&*self.storage, &manifest).await;
+
+        // Rollback on error.
+        if let Err(error) = save_result {
+            if let Some(evicted_role) = evicted_role_opt {
+                state_wlock_guard.roles.insert(role_id, evicted_role);
+            } else {
+                state_wlock_guard.roles.remove(&role_id);
+            }
+            return Err(error);
+        }
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_roles(&self, _request: ListRolesRequest) -> MetastoreResult<ListRolesResponse> {
+        let inner_rlock_guard = self.state.read().await;
+
+        let roles_json: Vec<String> = inner_rlock_guard
+            .roles
+            .values()
+            .sorted_unstable_by(|left, right| left.role_id.cmp(&right.role_id))
+            .map(serde_utils::to_json_str)
+            .collect::<MetastoreResult<_>>()?;
+        let response = ListRolesResponse { roles_json };
+        Ok(response)
+    }
+
+    async fn delete_role(&self, request: DeleteRoleRequest) -> MetastoreResult<EmptyResponse> {
+        let mut state_wlock_guard = self.state.write().await;
+
+        let Some(evicted_role) = state_wlock_guard.roles.remove(&request.role_id) else {
+            return Err(MetastoreError::NotFound(EntityKind::Role {
+                role_id: request.role_id,
+            }));
+        };
+        let manifest = state_wlock_guard.as_manifest();
+        let save_result = save_manifest(&*self.storage, &manifest).await;
+
+        // Rollback on error.
+        if let Err(error) = save_result {
+            state_wlock_guard
+                .roles
+                .insert(request.role_id, evicted_role);
+            return Err(error);
+        }
+        Ok(EmptyResponse {})
+    }
+
+    // Point-in-time API
+
+    async fn create_point_in_time(
+        &self,
+        request: CreatePointInTimeRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let point_in_time: PointInTime = serde_utils::from_json_str(&request.point_in_time_json)?;
+        let point_in_time_key = point_in_time.point_in_time_key.clone();
+
+        let mut state_wlock_guard = self.state.write().await;
+
+        let evicted_point_in_time_opt = match state_wlock_guard
+            .point_in_times
+            .entry(point_in_time_key.clone())
+        {
+            Entry::Vacant(entry) => {
+                entry.insert(point_in_time);
+                None
+            }
+            Entry::Occupied(mut entry) if request.overwrite => Some(entry.insert(point_in_time)),
+            Entry::Occupied(_) => {
+                return Err(MetastoreError::AlreadyExists(EntityKind::PointInTime {
+                    point_in_time_key,
+                }));
+            }
+        };
+        let manifest = state_wlock_guard.as_manifest();
+        let save_result = save_manifest(&*self.storage, &manifest).await;
+
+        // Rollback on error.
+        if let Err(error) = save_result {
+            if let Some(evicted_point_in_time) = evicted_point_in_time_opt {
+                state_wlock_guard
+                    .point_in_times
+                    .insert(point_in_time_key, evicted_point_in_time);
+            } else {
+                state_wlock_guard.point_in_times.remove(&point_in_time_key);
+            }
+            return Err(error);
+        }
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_point_in_times(
+        &self,
+        request: ListPointInTimesRequest,
+    ) -> MetastoreResult<ListPointInTimesResponse> {
+        let inner_rlock_guard = self.state.read().await;
+
+        let point_in_times_json: Vec<String> = inner_rlock_guard
+            .point_in_times
+            .values()
+            .filter(|point_in_time| {
+                request.point_in_time_keys.is_empty()
+                    || request
+                        .point_in_time_keys
+                        .contains(&point_in_time.point_in_time_key)
+            })
+            .sorted_unstable_by(|left, right| left.point_in_time_key.cmp(&right.point_in_time_key))
+            .map(serde_utils::to_json_str)
+            .collect::<MetastoreResult<_>>()?;
+        let response = ListPointInTimesResponse {
+            point_in_times_json,
+        };
+        Ok(response)
+    }
+
+    async fn delete_point_in_times(
+        &self,
+        request: DeletePointInTimesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let mut state_wlock_guard = self.state.write().await;
+
+        let evicted_point_in_times: Vec<PointInTime> = request
+            .point_in_time_keys
+            .iter()
+            .filter_map(|point_in_time_key| {
+                state_wlock_guard.point_in_times.remove(point_in_time_key)
+            })
+            .collect();
+
+        if evicted_point_in_times.is_empty() {
+            return Ok(EmptyResponse {});
+        }
+        let manifest = state_wlock_guard.as_manifest();
+        let save_result = save_manifest(&*self.storage, &manifest).await;
+
+        // Rollback on error.
+        if let Err(error) = save_result {
+            for evicted_point_in_time in evicted_point_in_times {
+                state_wlock_guard.point_in_times.insert(
+                    evicted_point_in_time.point_in_time_key.clone(),
+                    evicted_point_in_time,
+                );
+            }
+            return Err(error);
+        }
+        Ok(EmptyResponse {})
+    }
+
+    // Index alias API
+
+    async fn update_index_aliases(
+        &self,
+        request: UpdateIndexAliasesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let mut state_wlock_guard = self.state.write().await;
+
+        let mut index_aliases = state_wlock_guard.index_aliases.clone();
+        apply_index_alias_actions(&mut index_aliases, &request)?;
+
+        let previous_index_aliases =
+            std::mem::replace(&mut state_wlock_guard.index_aliases, index_aliases);
+        let manifest = state_wlock_guard.as_manifest();
+        let save_result = save_manifest(&*self.storage, &manifest).await;
+
+        // Rollback on error.
+        if let Err(error) = save_result {
+            state_wlock_guard.index_aliases = previous_index_aliases;
+            return Err(error);
+        }
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_index_aliases(
+        &self,
+        _request: ListIndexAliasesRequest,
+    ) -> MetastoreResult<ListIndexAliasesResponse> {
+        let inner_rlock_guard = self.state.read().await;
+        ListIndexAliasesResponse::try_from_index_aliases(&inner_rlock_guard.index_aliases)
+    }
+
+    async fn delete_index_aliases(
+        &self,
+        request: DeleteIndexAliasesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let mut state_wlock_guard = self.state.write().await;
+
+        let mut index_aliases = state_wlock_guard.index_aliases.clone();
+
+        if !index_aliases.remove_indexes(&request.index_ids) {
+            return Ok(EmptyResponse {});
+        }
+        let previous_index_aliases =
+            std::mem::replace(&mut state_wlock_guard.index_aliases, index_aliases);
+        let manifest = state_wlock_guard.as_manifest();
+        let save_result = save_manifest(&*self.storage, &manifest).await;
+
+        // Rollback on error.
+        if let Err(error) = save_result {
+            state_wlock_guard.index_aliases = previous_index_aliases;
+            return Err(error);
+        }
+        Ok(EmptyResponse {})
+    }
+
+    // Lifecycle policy API
+
+    async fn create_lifecycle_policy(
+        &self,
+        request: CreateLifecyclePolicyRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let lifecycle_policy: LifecyclePolicy =
+            serde_utils::from_json_str(&request.lifecycle_policy_json)?;
+
+        lifecycle_policy
+            .validate()
+            .map_err(|error| MetastoreError::InvalidArgument {
+                message: format!(
+                    "invalid lifecycle policy `{}`: `{error}`",
+                    lifecycle_policy.policy_id
+                ),
+            })?;
+        let policy_id = lifecycle_policy.policy_id.clone();
+
+        let mut state_wlock_guard = self.state.write().await;
+
+        let evicted_lifecycle_policy_opt = match state_wlock_guard
+            .lifecycle_policies
+            .entry(policy_id.clone())
+        {
+            Entry::Vacant(entry) => {
+                entry.insert(lifecycle_policy);
+                None
+            }
+            Entry::Occupied(mut entry) if request.overwrite => Some(entry.insert(lifecycle_policy)),
+            Entry::Occupied(_) => {
+                return Err(MetastoreError::AlreadyExists(EntityKind::LifecyclePolicy {
+                    policy_id,
+                }));
+            }
+        };
+        let manifest = state_wlock_guard.as_manifest();
+        let save_result = save_manifest(&*self.storage, &manifest).await;
+
+        // Rollback on error.
+        if let Err(error) = save_result {
+            if let Some(evicted_lifecycle_policy) = evicted_lifecycle_policy_opt {
+                state_wlock_guard
+                    .lifecycle_policies
+                    .insert(policy_id, evicted_lifecycle_policy);
+            } else {
+                state_wlock_guard.lifecycle_policies.remove(&policy_id);
+            }
+            return Err(error);
+        }
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_lifecycle_policies(
+        &self,
+        _request: ListLifecyclePoliciesRequest,
+    ) -> MetastoreResult<ListLifecyclePoliciesResponse> {
+        let inner_rlock_guard = self.state.read().await;
+
+        let lifecycle_policies_json: Vec<String> = inner_rlock_guard
+            .lifecycle_policies
+            .values()
+            .sorted_unstable_by(|left, right| left.policy_id.cmp(&right.policy_id))
+            .map(serde_utils::to_json_str)
+            .collect::<MetastoreResult<_>>()?;
+        let response = ListLifecyclePoliciesResponse {
+            lifecycle_policies_json,
+        };
+        Ok(response)
+    }
+
+    async fn delete_lifecycle_policy(
+        &self,
+        request: DeleteLifecyclePolicyRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let mut state_wlock_guard = self.state.write().await;
+
+        let Some(evicted_lifecycle_policy) = state_wlock_guard
+            .lifecycle_policies
+            .remove(&request.policy_id)
+        else {
+            return Err(MetastoreError::NotFound(EntityKind::LifecyclePolicy {
+                policy_id: request.policy_id,
+            }));
+        };
+        let manifest = state_wlock_guard.as_manifest();
+        let save_result = save_manifest(&*self.storage, &manifest).await;
+
+        // Rollback on error.
+        if let Err(error) = save_result {
+            state_wlock_guard
+                .lifecycle_policies
+                .insert(request.policy_id, evicted_lifecycle_policy);
+            return Err(error);
+        }
+        Ok(EmptyResponse {})
+    }
 }
 
 impl MetastoreServiceExt for FileBackedMetastore {}
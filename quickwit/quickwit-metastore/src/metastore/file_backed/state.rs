@@ -21,7 +21,10 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
-use quickwit_config::{IndexTemplate, IndexTemplateId};
+use quickwit_config::{
+    ApiKey, ApiKeyId, IndexAliases, IndexTemplate, IndexTemplateId, LifecyclePolicy,
+    LifecyclePolicyId, Role, RoleId,
+};
 use quickwit_proto::metastore::MetastoreResult;
 use quickwit_proto::types::IndexId;
 use quickwit_storage::Storage;
@@ -30,12 +33,18 @@ use super::index_template_matcher::IndexTemplateMatcher;
 use super::lazy_file_backed_index::LazyFileBackedIndex;
 use super::manifest::{IndexStatus, Manifest};
 use super::LazyIndexStatus;
+use crate::PointInTime;
 
 #[derive(Default)]
 pub(super) struct MetastoreState {
     pub indexes: HashMap<IndexId, LazyIndexStatus>,
     pub templates: HashMap<IndexTemplateId, IndexTemplate>,
     pub template_matcher: IndexTemplateMatcher,
+    pub api_keys: HashMap<ApiKeyId, ApiKey>,
+    pub roles: HashMap<RoleId, Role>,
+    pub point_in_times: HashMap<String, PointInTime>,
+    pub index_aliases: IndexAliases,
+    pub lifecycle_policies: HashMap<LifecyclePolicyId, LifecyclePolicy>,
 }
 
 impl MetastoreState {
@@ -69,6 +78,11 @@ impl MetastoreState {
             indexes,
             templates: manifest.templates,
             template_matcher,
+            api_keys: manifest.api_keys,
+            roles: manifest.roles,
+            point_in_times: manifest.point_in_times,
+            index_aliases: manifest.index_aliases,
+            lifecycle_policies: manifest.lifecycle_policies,
         };
         Ok(state)
     }
@@ -87,6 +101,19 @@ impl MetastoreState {
             })
             .collect();
         let templates = self.templates.clone();
-        Manifest { indexes, templates }
+        let api_keys = self.api_keys.clone();
+        let roles = self.roles.clone();
+        let point_in_times = self.point_in_times.clone();
+        let index_aliases = self.index_aliases.clone();
+        let lifecycle_policies = self.lifecycle_policies.clone();
+        Manifest {
+            indexes,
+            templates,
+            api_keys,
+            roles,
+            point_in_times,
+            index_aliases,
+            lifecycle_policies,
+        }
     }
 }
@@ -33,16 +33,17 @@ pub use index_metadata::IndexMetadata;
 use itertools::Itertools;
 use quickwit_common::thread_pool::run_cpu_intensive;
 use quickwit_config::{
-    DocMapping, FileSourceParams, IndexConfig, IndexingSettings, RetentionPolicy, SearchSettings,
-    SourceConfig, SourceParams,
+    DocMapping, FileSourceParams, IndexAliasAction, IndexAliasError, IndexAliases, IndexConfig,
+    IndexingSettings, RetentionPolicy, SearchSettings, SourceConfig, SourceParams,
 };
 use quickwit_doc_mapper::tag_pruning::TagFilterAst;
 use quickwit_proto::metastore::{
-    serde_utils, AddSourceRequest, CreateIndexRequest, CreateIndexResponse, DeleteTask,
+    serde_utils, AddSourceRequest, CreateIndexRequest, CreateIndexResponse, DeleteTask, EntityKind,
     IndexMetadataFailure, IndexMetadataRequest, IndexMetadataResponse, IndexesMetadataResponse,
-    ListIndexesMetadataResponse, ListSplitsRequest, ListSplitsResponse, MetastoreError,
-    MetastoreResult, MetastoreService, MetastoreServiceClient, MetastoreServiceStream,
-    PublishSplitsRequest, StageSplitsRequest, UpdateIndexRequest,
+    ListIndexAliasesResponse, ListIndexesMetadataResponse, ListSplitsRequest, ListSplitsResponse,
+    MetastoreError, MetastoreResult, MetastoreService, MetastoreServiceClient,
+    MetastoreServiceStream, PublishSplitsRequest, StageSplitsRequest, UpdateIndexAliasesRequest,
+    UpdateIndexRequest,
 };
 use quickwit_proto::types::{IndexUid, NodeId, SplitId};
 use time::OffsetDateTime;
@@ -927,6 +928,72 @@ impl<T> Default for FilterRange<T> {
     }
 }
 
+/// Helper trait to build a [`UpdateIndexAliasesRequest`] and deserialize its payload.
+pub trait UpdateIndexAliasesRequestExt {
+    /// Creates a new [`UpdateIndexAliasesRequest`] from a list of [`IndexAliasAction`].
+    fn try_from_actions(actions: &[IndexAliasAction])
+        -> MetastoreResult<UpdateIndexAliasesRequest>;
+
+    /// Deserializes the `index_alias_actions_json` field of an [`UpdateIndexAliasesRequest`] into
+    /// a list of [`IndexAliasAction`].
+    fn deserialize_actions(&self) -> MetastoreResult<Vec<IndexAliasAction>>;
+}
+
+impl UpdateIndexAliasesRequestExt for UpdateIndexAliasesRequest {
+    fn try_from_actions(actions: &[IndexAliasAction]) -> MetastoreResult<Self> {
+        let index_alias_actions_json = serde_utils::to_json_str(&actions)?;
+        let request = Self {
+            index_alias_actions_json,
+        };
+        Ok(request)
+    }
+
+    fn deserialize_actions(&self) -> MetastoreResult<Vec<IndexAliasAction>> {
+        serde_utils::from_json_str(&self.index_alias_actions_json)
+    }
+}
+
+/// Helper trait to build a [`ListIndexAliasesResponse`] and deserialize its payload.
+pub trait ListIndexAliasesResponseExt {
+    /// Creates a new [`ListIndexAliasesResponse`] from [`IndexAliases`].
+    fn try_from_index_aliases(
+        index_aliases: &IndexAliases,
+    ) -> MetastoreResult<ListIndexAliasesResponse>;
+
+    /// Deserializes the `index_aliases_json` field of a [`ListIndexAliasesResponse`] into
+    /// [`IndexAliases`].
+    fn deserialize_index_aliases(&self) -> MetastoreResult<IndexAliases>;
+}
+
+impl ListIndexAliasesResponseExt for ListIndexAliasesResponse {
+    fn try_from_index_aliases(index_aliases: &IndexAliases) -> MetastoreResult<Self> {
+        let index_aliases_json = serde_utils::to_json_str(index_aliases)?;
+        let response = Self { index_aliases_json };
+        Ok(response)
+    }
+
+    fn deserialize_index_aliases(&self) -> MetastoreResult<IndexAliases> {
+        serde_utils::from_json_str(&self.index_aliases_json)
+    }
+}
+
+/// Applies the actions of an [`UpdateIndexAliasesRequest`] to `index_aliases`.
+fn apply_index_alias_actions(
+    index_aliases: &mut IndexAliases,
+    request: &UpdateIndexAliasesRequest,
+) -> MetastoreResult<()> {
+    let actions = request.deserialize_actions()?;
+
+    index_aliases
+        .apply_actions(actions)
+        .map_err(|error| match error {
+            IndexAliasError::AliasNotFound(alias) => {
+                MetastoreError::NotFound(EntityKind::IndexAlias { alias })
+            }
+            IndexAliasError::InvalidAction(message) => MetastoreError::InvalidArgument { message },
+        })
+}
+
 /// Maps the given source params to whether checkpoints should be stored in the index metadata
 /// (false) or the shard table (true)
 fn use_shard_api(params: &SourceParams) -> bool {
@@ -17,7 +17,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{self, Write};
 use std::time::Duration;
 
@@ -28,27 +28,33 @@ use quickwit_common::pretty::PrettySample;
 use quickwit_common::uri::Uri;
 use quickwit_common::{get_bool_from_env, ServiceStream};
 use quickwit_config::{
-    validate_index_id_pattern, IndexTemplate, IndexTemplateId, PostgresMetastoreConfig,
+    validate_index_id_pattern, ApiKey, IndexAliasConfig, IndexAliases, IndexTemplate,
+    IndexTemplateId, LifecyclePolicy, PostgresMetastoreConfig, Role,
 };
 use quickwit_proto::ingest::{Shard, ShardState};
 use quickwit_proto::metastore::{
-    serde_utils, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest, CreateIndexRequest,
-    CreateIndexResponse, CreateIndexTemplateRequest, DeleteIndexRequest,
-    DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest, DeleteShardsResponse,
-    DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse, EntityKind,
-    FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse, GetIndexTemplateRequest,
-    GetIndexTemplateResponse, IndexMetadataFailure, IndexMetadataFailureReason,
-    IndexMetadataRequest, IndexMetadataResponse, IndexTemplateMatch, IndexesMetadataRequest,
-    IndexesMetadataResponse, LastDeleteOpstampRequest, LastDeleteOpstampResponse,
-    ListDeleteTasksRequest, ListDeleteTasksResponse, ListIndexTemplatesRequest,
-    ListIndexTemplatesResponse, ListIndexesMetadataRequest, ListIndexesMetadataResponse,
+    serde_utils, AcquireShardsRequest, AcquireShardsResponse, AddSourceRequest,
+    CreateApiKeyRequest, CreateIndexRequest, CreateIndexResponse, CreateIndexTemplateRequest,
+    CreateLifecyclePolicyRequest, CreatePointInTimeRequest, CreateRoleRequest, DeleteApiKeyRequest,
+    DeleteIndexAliasesRequest, DeleteIndexRequest, DeleteIndexTemplatesRequest,
+    DeleteLifecyclePolicyRequest, DeletePointInTimesRequest, DeleteQuery, DeleteRoleRequest,
+    DeleteShardsRequest, DeleteShardsResponse, DeleteSourceRequest, DeleteSplitsRequest,
+    DeleteTask, EmptyResponse, EntityKind, FindIndexTemplateMatchesRequest,
+    FindIndexTemplateMatchesResponse, GetIndexTemplateRequest, GetIndexTemplateResponse,
+    IndexMetadataFailure, IndexMetadataFailureReason, IndexMetadataRequest, IndexMetadataResponse,
+    IndexTemplateMatch, IndexesMetadataRequest, IndexesMetadataResponse, LastDeleteOpstampRequest,
+    LastDeleteOpstampResponse, ListApiKeysRequest, ListApiKeysResponse, ListDeleteTasksRequest,
+    ListDeleteTasksResponse, ListIndexAliasesRequest, ListIndexAliasesResponse,
+    ListIndexTemplatesRequest, ListIndexTemplatesResponse, ListIndexesMetadataRequest,
+    ListIndexesMetadataResponse, ListLifecyclePoliciesRequest, ListLifecyclePoliciesResponse,
+    ListPointInTimesRequest, ListPointInTimesResponse, ListRolesRequest, ListRolesResponse,
     ListShardsRequest, ListShardsResponse, ListShardsSubresponse, ListSplitsRequest,
     ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError,
     MetastoreResult, MetastoreService, MetastoreServiceStream, OpenShardSubrequest,
     OpenShardSubresponse, OpenShardsRequest, OpenShardsResponse, PruneShardsRequest,
     PruneShardsResponse, PublishSplitsRequest, ResetSourceCheckpointRequest, StageSplitsRequest,
-    ToggleSourceRequest, UpdateIndexRequest, UpdateSplitsDeleteOpstampRequest,
-    UpdateSplitsDeleteOpstampResponse,
+    ToggleSourceRequest, UpdateIndexAliasesRequest, UpdateIndexRequest,
+    UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
 };
 use quickwit_proto::types::{IndexId, IndexUid, Position, PublishToken, ShardId, SourceId};
 use sea_query::{Alias, Asterisk, Expr, Func, PostgresQueryBuilder, Query, UnionType};
@@ -74,12 +80,14 @@ use crate::file_backed::MutationOccurred;
 use crate::metastore::postgres::model::Shards;
 use crate::metastore::postgres::utils::split_maturity_timestamp;
 use crate::metastore::{
-    use_shard_api, IndexesMetadataResponseExt, PublishSplitsRequestExt, STREAM_SPLITS_CHUNK_SIZE,
+    apply_index_alias_actions, use_shard_api, IndexesMetadataResponseExt,
+    ListIndexAliasesResponseExt, PublishSplitsRequestExt, STREAM_SPLITS_CHUNK_SIZE,
 };
 use crate::{
     AddSourceRequestExt, CreateIndexRequestExt, IndexMetadata, IndexMetadataResponseExt,
     ListIndexesMetadataResponseExt, ListSplitsRequestExt, ListSplitsResponseExt,
-    MetastoreServiceExt, Split, SplitState, StageSplitsRequestExt, UpdateIndexRequestExt,
+    MetastoreServiceExt, PointInTime, Split, SplitState, StageSplitsRequestExt,
+    UpdateIndexRequestExt,
 };
 
 /// PostgreSQL metastore implementation.
@@ -1671,6 +1679,407 @@ impl MetastoreService for PostgresqlMetastore {
             .await?;
         Ok(EmptyResponse {})
     }
+
+    // API Key API
+
+    async fn create_api_key(&self, request: CreateApiKeyRequest) -> MetastoreResult<EmptyResponse> {
+        const INSERT_API_KEY_QUERY: &str = include_str!("queries/api_keys/insert.sql");
+        const UPSERT_API_KEY_QUERY: &str = include_str!("queries/api_keys/upsert.sql");
+
+        let api_key: ApiKey = serde_utils::from_json_str(&request.api_key_json)?;
+
+        api_key
+            .validate()
+            .map_err(|error| MetastoreError::InvalidArgument {
+                message: format!("invalid API key `{}`: `{error}`", api_key.api_key_id),
+            })?;
+        if request.overwrite {
+            sqlx::query(UPSERT_API_KEY_QUERY)
+                .bind(&api_key.api_key_id)
+                .bind(&request.api_key_json)
+                .execute(&self.connection_pool)
+                .await?;
+
+            return Ok(EmptyResponse {});
+        }
+        let pg_query_result = sqlx::query(INSERT_API_KEY_QUERY)
+            .bind(&api_key.api_key_id)
+            .bind(&request.api_key_json)
+            .execute(&self.connection_pool)
+            .await?;
+
+        if pg_query_result.rows_affected() == 0 {
+            return Err(MetastoreError::AlreadyExists(EntityKind::ApiKey {
+                api_key_id: api_key.api_key_id,
+            }));
+        }
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_api_keys(
+        &self,
+        _request: ListApiKeysRequest,
+    ) -> MetastoreResult<ListApiKeysResponse> {
+        let pg_api_keys_json: Vec<(String,)> =
+            sqlx::query_as("SELECT api_key_json FROM api_keys ORDER BY api_key_id ASC")
+                .fetch_all(&self.connection_pool)
+                .await?;
+        let api_keys_json: Vec<String> = pg_api_keys_json
+            .into_iter()
+            .map(|(api_key_json,)| api_key_json)
+            .collect();
+        let response = ListApiKeysResponse { api_keys_json };
+        Ok(response)
+    }
+
+    async fn delete_api_key(&self, request: DeleteApiKeyRequest) -> MetastoreResult<EmptyResponse> {
+        let pg_query_result = sqlx::query("DELETE FROM api_keys WHERE api_key_id = $1")
+            .bind(&request.api_key_id)
+            .execute(&self.connection_pool)
+            .await?;
+
+        if pg_query_result.rows_affected() == 0 {
+            return Err(MetastoreError::NotFound(EntityKind::ApiKey {
+                api_key_id: request.api_key_id,
+            }));
+        }
+        Ok(EmptyResponse {})
+    }
+
+    // Role API
+
+    async fn create_role(&self, request: CreateRoleRequest) -> MetastoreResult<EmptyResponse> {
+        const INSERT_ROLE_QUERY: &str = include_str!("queries/roles/insert.sql");
+        const UPSERT_ROLE_QUERY: &str = include_str!("queries/roles/upsert.sql");
+
+        let role: Role = serde_utils::from_json_str(&request.role_json)?;
+
+        role.validate()
+            .map_err(|error| MetastoreError::InvalidArgument {
+                message: format!("invalid role `{}`: `{error}`", role.role_id),
+            })?;
+        if request.overwrite {
+            sqlx::query(UPSERT_ROLE_QUERY)
+                .bind(&role.role_id)
+                .bind(&request.role_json)
+                .execute(&self.connection_pool)
+                .await?;
+
+            return Ok(EmptyResponse {});
+        }
+        let pg_query_result = sqlx::query(INSERT_ROLE_QUERY)
+            .bind(&role.role_id)
+            .bind(&request.role_json)
+            .execute(&self.connection_pool)
+            .await?;
+
+        if pg_query_result.rows_affected() == 0 {
+            return Err(MetastoreError::AlreadyExists(EntityKind::Role {
+                role_id: role.role_id,
+            }));
+        }
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_roles(&self, _request: ListRolesRequest) -> MetastoreResult<ListRolesResponse> {
+        let pg_roles_json: Vec<(String,)> =
+            sqlx::query_as("SELECT role_json FROM roles ORDER BY role_id ASC")
+                .fetch_all(&self.connection_pool)
+                .await?;
+        let roles_json: Vec<String> = pg_roles_json
+            .into_iter()
+            .map(|(role_json,)| role_json)
+            .collect();
+        let response = ListRolesResponse { roles_json };
+        Ok(response)
+    }
+
+    async fn delete_role(&self, request: DeleteRoleRequest) -> MetastoreResult<EmptyResponse> {
+        let pg_query_result = sqlx::query("DELETE FROM roles WHERE role_id = $1")
+            .bind(&request.role_id)
+            .execute(&self.connection_pool)
+            .await?;
+
+        if pg_query_result.rows_affected() == 0 {
+            return Err(MetastoreError::NotFound(EntityKind::Role {
+                role_id: request.role_id,
+            }));
+        }
+        Ok(EmptyResponse {})
+    }
+
+    // Point-in-time API
+
+    async fn create_point_in_time(
+        &self,
+        request: CreatePointInTimeRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        const INSERT_POINT_IN_TIME_QUERY: &str = include_str!("queries/point_in_times/insert.sql");
+        const UPSERT_POINT_IN_TIME_QUERY: &str = include_str!("queries/point_in_times/upsert.sql");
+
+        let point_in_time: PointInTime = serde_utils::from_json_str(&request.point_in_time_json)?;
+
+        if request.overwrite {
+            sqlx::query(UPSERT_POINT_IN_TIME_QUERY)
+                .bind(&point_in_time.point_in_time_key)
+                .bind(&request.point_in_time_json)
+                .execute(&self.connection_pool)
+                .await?;
+
+            return Ok(EmptyResponse {});
+        }
+        let pg_query_result = sqlx::query(INSERT_POINT_IN_TIME_QUERY)
+            .bind(&point_in_time.point_in_time_key)
+            .bind(&request.point_in_time_json)
+            .execute(&self.connection_pool)
+            .await?;
+
+        if pg_query_result.rows_affected() == 0 {
+            return Err(MetastoreError::AlreadyExists(EntityKind::PointInTime {
+                point_in_time_key: point_in_time.point_in_time_key,
+            }));
+        }
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_point_in_times(
+        &self,
+        request: ListPointInTimesRequest,
+    ) -> MetastoreResult<ListPointInTimesResponse> {
+        let pg_point_in_times_json: Vec<(String,)> = if request.point_in_time_keys.is_empty() {
+            sqlx::query_as(
+                "SELECT point_in_time_json FROM point_in_times ORDER BY point_in_time_key ASC",
+            )
+            .fetch_all(&self.connection_pool)
+            .await?
+        } else {
+            sqlx::query_as(
+                r#"
+                SELECT point_in_time_json
+                FROM point_in_times
+                WHERE point_in_time_key = ANY($1)
+                ORDER BY point_in_time_key ASC
+                "#,
+            )
+            .bind(&request.point_in_time_keys)
+            .fetch_all(&self.connection_pool)
+            .await?
+        };
+        let point_in_times_json: Vec<String> = pg_point_in_times_json
+            .into_iter()
+            .map(|(point_in_time_json,)| point_in_time_json)
+            .collect();
+        let response = ListPointInTimesResponse {
+            point_in_times_json,
+        };
+        Ok(response)
+    }
+
+    async fn delete_point_in_times(
+        &self,
+        request: DeletePointInTimesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        if request.point_in_time_keys.is_empty() {
+            return Ok(EmptyResponse {});
+        }
+        sqlx::query("DELETE FROM point_in_times WHERE point_in_time_key = ANY($1)")
+            .bind(&request.point_in_time_keys)
+            .execute(&self.connection_pool)
+            .await?;
+        Ok(EmptyResponse {})
+    }
+
+    // Index alias API
+
+    async fn update_index_aliases(
+        &self,
+        request: UpdateIndexAliasesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        run_with_tx!(self.connection_pool, tx, {
+            let previous_index_aliases = lock_and_fetch_index_aliases(tx).await?;
+            let mut index_aliases = previous_index_aliases.clone();
+            apply_index_alias_actions(&mut index_aliases, &request)?;
+            save_index_aliases(tx, &previous_index_aliases, &index_aliases).await
+        })?;
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_index_aliases(
+        &self,
+        _request: ListIndexAliasesRequest,
+    ) -> MetastoreResult<ListIndexAliasesResponse> {
+        let pg_index_aliases: Vec<(String, String)> =
+            sqlx::query_as("SELECT alias, alias_indexes_json FROM index_aliases")
+                .fetch_all(&self.connection_pool)
+                .await?;
+        let index_aliases = deserialize_index_aliases(pg_index_aliases)?;
+        ListIndexAliasesResponse::try_from_index_aliases(&index_aliases)
+    }
+
+    async fn delete_index_aliases(
+        &self,
+        request: DeleteIndexAliasesRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        if request.index_ids.is_empty() {
+            return Ok(EmptyResponse {});
+        }
+        run_with_tx!(self.connection_pool, tx, {
+            let previous_index_aliases = lock_and_fetch_index_aliases(tx).await?;
+            let mut index_aliases = previous_index_aliases.clone();
+
+            if !index_aliases.remove_indexes(&request.index_ids) {
+                return Ok(());
+            }
+            save_index_aliases(tx, &previous_index_aliases, &index_aliases).await
+        })?;
+        Ok(EmptyResponse {})
+    }
+
+    // Lifecycle policy API
+
+    async fn create_lifecycle_policy(
+        &self,
+        request: CreateLifecyclePolicyRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        const INSERT_LIFECYCLE_POLICY_QUERY: &str =
+            include_str!("queries/lifecycle_policies/insert.sql");
+        const UPSERT_LIFECYCLE_POLICY_QUERY: &str =
+            include_str!("queries/lifecycle_policies/upsert.sql");
+
+        let lifecycle_policy: LifecyclePolicy =
+            serde_utils::from_json_str(&request.lifecycle_policy_json)?;
+
+        lifecycle_policy
+            .validate()
+            .map_err(|error| MetastoreError::InvalidArgument {
+                message: format!(
+                    "invalid lifecycle policy `{}`: `{error}`",
+                    lifecycle_policy.policy_id
+                ),
+            })?;
+        if request.overwrite {
+            sqlx::query(UPSERT_LIFECYCLE_POLICY_QUERY)
+                .bind(&lifecycle_policy.policy_id)
+                .bind(&request.lifecycle_policy_json)
+                .execute(&self.connection_pool)
+                .await?;
+
+            return Ok(EmptyResponse {});
+        }
+        let pg_query_result = sqlx::query(INSERT_LIFECYCLE_POLICY_QUERY)
+            .bind(&lifecycle_policy.policy_id)
+            .bind(&request.lifecycle_policy_json)
+            .execute(&self.connection_pool)
+            .await?;
+
+        if pg_query_result.rows_affected() == 0 {
+            return Err(MetastoreError::AlreadyExists(EntityKind::LifecyclePolicy {
+                policy_id: lifecycle_policy.policy_id,
+            }));
+        }
+        Ok(EmptyResponse {})
+    }
+
+    async fn list_lifecycle_policies(
+        &self,
+        _request: ListLifecyclePoliciesRequest,
+    ) -> MetastoreResult<ListLifecyclePoliciesResponse> {
+        let pg_lifecycle_policies_json: Vec<(String,)> = sqlx::query_as(
+            "SELECT lifecycle_policy_json FROM lifecycle_policies ORDER BY policy_id ASC",
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+        let lifecycle_policies_json: Vec<String> = pg_lifecycle_policies_json
+            .into_iter()
+            .map(|(lifecycle_policy_json,)| lifecycle_policy_json)
+            .collect();
+        let response = ListLifecyclePoliciesResponse {
+            lifecycle_policies_json,
+        };
+        Ok(response)
+    }
+
+    async fn delete_lifecycle_policy(
+        &self,
+        request: DeleteLifecyclePolicyRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let pg_query_result = sqlx::query("DELETE FROM lifecycle_policies WHERE policy_id = $1")
+            .bind(&request.policy_id)
+            .execute(&self.connection_pool)
+            .await?;
+
+        if pg_query_result.rows_affected() == 0 {
+            return Err(MetastoreError::NotFound(EntityKind::LifecyclePolicy {
+                policy_id: request.policy_id,
+            }));
+        }
+        Ok(EmptyResponse {})
+    }
+}
+
+/// Locks the `index_aliases` table until the end of the transaction and returns its content.
+///
+/// Alias actions may modify several aliases at once and must see a consistent state, so row
+/// locks are not enough: a concurrent transaction could create an alias in the meantime.
+async fn lock_and_fetch_index_aliases(
+    tx: &mut Transaction<'_, Postgres>,
+) -> MetastoreResult<IndexAliases> {
+    sqlx::query("LOCK TABLE index_aliases IN EXCLUSIVE MODE")
+        .execute(tx.as_mut())
+        .await?;
+    let pg_index_aliases: Vec<(String, String)> =
+        sqlx::query_as("SELECT alias, alias_indexes_json FROM index_aliases")
+            .fetch_all(tx.as_mut())
+            .await?;
+    deserialize_index_aliases(pg_index_aliases)
+}
+
+fn deserialize_index_aliases(
+    pg_index_aliases: Vec<(String, String)>,
+) -> MetastoreResult<IndexAliases> {
+    pg_index_aliases
+        .into_iter()
+        .map(|(alias, alias_indexes_json)| {
+            let alias_indexes: BTreeMap<IndexId, IndexAliasConfig> =
+                serde_utils::from_json_str(&alias_indexes_json)?;
+            Ok((alias, alias_indexes))
+        })
+        .collect()
+}
+
+/// Writes the aliases that differ between `previous_index_aliases` and `index_aliases`.
+async fn save_index_aliases(
+    tx: &mut Transaction<'_, Postgres>,
+    previous_index_aliases: &IndexAliases,
+    index_aliases: &IndexAliases,
+) -> MetastoreResult<()> {
+    const UPSERT_INDEX_ALIAS_QUERY: &str = include_str!("queries/index_aliases/upsert.sql");
+
+    let removed_aliases: Vec<&str> = previous_index_aliases
+        .iter()
+        .map(|(alias, _)| alias.as_str())
+        .filter(|alias| index_aliases.get(alias).is_none())
+        .collect();
+
+    if !removed_aliases.is_empty() {
+        sqlx::query("DELETE FROM index_aliases WHERE alias = ANY($1)")
+            .bind(&removed_aliases)
+            .execute(tx.as_mut())
+            .await?;
+    }
+    for (alias, alias_indexes) in index_aliases.iter() {
+        if previous_index_aliases.get(alias) == Some(alias_indexes) {
+            continue;
+        }
+        let alias_indexes_json = serde_utils::to_json_str(alias_indexes)?;
+
+        sqlx::query(UPSERT_INDEX_ALIAS_QUERY)
+            .bind(alias)
+            .bind(&alias_indexes_json)
+            .execute(tx.as_mut())
+            .await?;
+    }
+    Ok(())
 }
 
 async fn open_or_fetch_shard<'e>(
@@ -0,0 +1,4 @@
+INSERT INTO api_keys(api_key_id, api_key_json)
+    VALUES ($1, $2)
+ON CONFLICT (api_key_id)
+    DO NOTHING
@@ -0,0 +1,5 @@
+INSERT INTO index_aliases(alias, alias_indexes_json)
+    VALUES ($1, $2)
+ON CONFLICT (alias)
+    DO UPDATE SET
+        alias_indexes_json = $2
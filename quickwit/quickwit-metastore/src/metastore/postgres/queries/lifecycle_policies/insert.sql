@@ -0,0 +1,4 @@
+INSERT INTO lifecycle_policies(policy_id, lifecycle_policy_json)
+    VALUES ($1, $2)
+ON CONFLICT (policy_id)
+    DO NOTHING
@@ -0,0 +1,5 @@
+INSERT INTO lifecycle_policies(policy_id, lifecycle_policy_json)
+    VALUES ($1, $2)
+ON CONFLICT (policy_id)
+    DO UPDATE SET
+        lifecycle_policy_json = $2
@@ -0,0 +1,5 @@
+INSERT INTO point_in_times(point_in_time_key, point_in_time_json)
+    VALUES ($1, $2)
+ON CONFLICT (point_in_time_key)
+    DO UPDATE SET
+        point_in_time_json = $2
@@ -0,0 +1,4 @@
+INSERT INTO roles(role_id, role_json)
+    VALUES ($1, $2)
+ON CONFLICT (role_id)
+    DO NOTHING
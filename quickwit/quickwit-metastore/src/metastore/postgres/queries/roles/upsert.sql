@@ -0,0 +1,5 @@
+INSERT INTO roles(role_id, role_json)
+    VALUES ($1, $2)
+ON CONFLICT (role_id)
+    DO UPDATE SET
+        role_json = $2
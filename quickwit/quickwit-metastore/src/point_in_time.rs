@@ -0,0 +1,125 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Point-in-time records.
+//!
+//! A point in time (PIT) pins the set of splits visible to a query at the time it was created, so
+//! that paginated searches are not affected by concurrent merges and publishes. The records are
+//! stored in the metastore. Searchers create and extend them, and the janitor relies on them to
+//! hold off the deletion of pinned splits until the point in time expires.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use quickwit_proto::types::{IndexUid, SplitId};
+use serde::{Deserialize, Serialize};
+
+/// Splits pinned by a point in time.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PointInTime {
+    /// Unique key of the point in time.
+    pub point_in_time_key: String,
+    /// IDs of the splits visible to the point in time, per index.
+    pub split_ids: BTreeMap<IndexUid, BTreeSet<SplitId>>,
+    /// Unix timestamp (in seconds) at which the point in time expires.
+    pub expire_at: i64,
+}
+
+impl PointInTime {
+    /// Returns `true` if the point in time has expired at timestamp `now`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expire_at <= now
+    }
+}
+
+/// Returns the IDs of the splits pinned by the points in time that have not expired yet, per
+/// index.
+pub fn pinned_split_ids<'a>(
+    point_in_times: impl IntoIterator<Item = &'a PointInTime>,
+    now: i64,
+) -> HashMap<IndexUid, HashSet<SplitId>> {
+    let mut pinned_split_ids: HashMap<IndexUid, HashSet<SplitId>> = HashMap::new();
+
+    for point_in_time in point_in_times {
+        if point_in_time.is_expired(now) {
+            continue;
+        }
+        for (index_uid, split_ids) in &point_in_time.split_ids {
+            pinned_split_ids
+                .entry(index_uid.clone())
+                .or_default()
+                .extend(split_ids.iter().cloned());
+        }
+    }
+    pinned_split_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point_in_time(
+        point_in_time_key: &str,
+        index_uid: &IndexUid,
+        split_ids: &[&str],
+        expire_at: i64,
+    ) -> PointInTime {
+        let split_ids = split_ids
+            .iter()
+            .map(|split_id| split_id.to_string())
+            .collect();
+        PointInTime {
+            point_in_time_key: point_in_time_key.to_string(),
+            split_ids: BTreeMap::from_iter([(index_uid.clone(), split_ids)]),
+            expire_at,
+        }
+    }
+
+    #[test]
+    fn test_point_in_time_expiration() {
+        let index_uid = IndexUid::for_test("test-index", 0);
+        let point_in_time = point_in_time("pit-1", &index_uid, &["split-1"], 100);
+        assert!(!point_in_time.is_expired(99));
+        assert!(point_in_time.is_expired(100));
+    }
+
+    #[test]
+    fn test_pinned_split_ids() {
+        let index_uid_1 = IndexUid::for_test("test-index-1", 0);
+        let index_uid_2 = IndexUid::for_test("test-index-2", 0);
+        let point_in_times = [
+            point_in_time("pit-1", &index_uid_1, &["split-1", "split-2"], 100),
+            point_in_time("pit-2", &index_uid_1, &["split-2", "split-3"], 200),
+            point_in_time("pit-3", &index_uid_2, &["split-4"], 200),
+        ];
+        let pinned_split_ids = pinned_split_ids(&point_in_times, 150);
+        assert_eq!(pinned_split_ids.len(), 2);
+
+        let expected_split_ids: HashSet<SplitId> = ["split-2".to_string(), "split-3".to_string()]
+            .into_iter()
+            .collect();
+        assert_eq!(pinned_split_ids[&index_uid_1], expected_split_ids);
+
+        let expected_split_ids: HashSet<SplitId> = ["split-4".to_string()].into_iter().collect();
+        assert_eq!(pinned_split_ids[&index_uid_2], expected_split_ids);
+
+        let point_in_time_json = serde_json::to_string(&point_in_times[0]).unwrap();
+        let point_in_time: PointInTime = serde_json::from_str(&point_in_time_json).unwrap();
+        assert_eq!(point_in_time, point_in_times[0]);
+    }
+}
@@ -17,8 +17,9 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
+use std::net::Ipv6Addr;
 use std::ops::{Range, RangeInclusive};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -120,6 +121,11 @@ pub struct SplitMetadata {
     /// [`MAX_VALUES_PER_TAG_FIELD`]: https://github.com/quickwit-oss/quickwit/blob/main/quickwit-indexing/src/actors/packager.rs#L36
     pub tags: BTreeSet<String>,
 
+    /// Min and max values of the numeric and IP fast fields of the split, keyed by field name.
+    /// Used to prune splits on range and term queries. Fields without any value are absent.
+    #[schema(value_type = Object)]
+    pub field_value_ranges: BTreeMap<String, FieldValueRange>,
+
     /// Contains the range of bytes of the footer that needs to be downloaded
     /// in order to open a split.
     ///
@@ -178,6 +184,9 @@ impl fmt::Debug for SplitMetadata {
             tags_str.push('}');
             debug_struct.field("tags", &tags_str);
         }
+        if !self.field_value_ranges.is_empty() {
+            debug_struct.field("field_value_ranges", &self.field_value_ranges);
+        }
         debug_struct.field("footer_offsets", &self.footer_offsets);
         debug_struct.field("delete_opstamp", &self.delete_opstamp);
         debug_struct.field("num_merge_ops", &self.num_merge_ops);
@@ -283,6 +292,10 @@ impl quickwit_config::TestableForRegression for SplitMetadata {
                 maturation_period: Duration::from_secs(4),
             },
             tags: ["234".to_string(), "aaa".to_string()].into_iter().collect(),
+            field_value_ranges: BTreeMap::from_iter([(
+                "status_code".to_string(),
+                FieldValueRange::U64 { min: 200, max: 503 },
+            )]),
             footer_offsets: 1000..2000,
             num_merge_ops: 3,
             doc_mapping_uid: DocMappingUid::default(),
@@ -294,6 +307,45 @@ impl quickwit_config::TestableForRegression for SplitMetadata {
     }
 }
 
+/// Min and max values of a numeric or IP fast field in a split.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum FieldValueRange {
+    /// Range of a `u64` field.
+    U64 {
+        /// Min value.
+        min: u64,
+        /// Max value.
+        max: u64,
+    },
+    /// Range of an `i64` field.
+    I64 {
+        /// Min value.
+        min: i64,
+        /// Max value.
+        max: i64,
+    },
+    /// Range of an `f64` field.
+    F64 {
+        /// Min value.
+        min: f64,
+        /// Max value.
+        max: f64,
+    },
+    /// Range of an `ip` field. IPv4 addresses are mapped to IPv6.
+    Ip {
+        /// Min value.
+        min: Ipv6Addr,
+        /// Max value.
+        max: Ipv6Addr,
+    },
+}
+
+// The bounds of an `f64` range are never NaN: the ranges of fields containing NaN values are not
+// recorded.
+impl Eq for FieldValueRange {}
+
 /// A split state.
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, utoipa::ToSchema)]
 pub enum SplitState {
@@ -422,6 +474,7 @@ mod tests {
                 tags.insert("😿".to_string());
                 tags
             },
+            field_value_ranges: BTreeMap::new(),
             footer_offsets: 0..1024,
             delete_opstamp: 0,
             num_merge_ops: 0,
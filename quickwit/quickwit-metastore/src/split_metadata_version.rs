@@ -17,13 +17,13 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ops::{Range, RangeInclusive};
 
 use quickwit_proto::types::{DocMappingUid, IndexUid, SplitId};
 use serde::{Deserialize, Serialize};
 
-use crate::split_metadata::{utc_now_timestamp, SplitMaturity};
+use crate::split_metadata::{utc_now_timestamp, FieldValueRange, SplitMaturity};
 use crate::SplitMetadata;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
@@ -78,6 +78,12 @@ pub(crate) struct SplitMetadataV0_8 {
     /// A set of tags for categorizing and searching group of splits.
     pub tags: BTreeSet<String>,
 
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[schema(value_type = Object)]
+    /// Min and max values of the numeric and IP fast fields of the split.
+    pub field_value_ranges: BTreeMap<String, FieldValueRange>,
+
     #[schema(value_type = Object)]
     /// Contains the range of bytes of the footer that needs to be downloaded
     /// in order to open a split.
@@ -130,6 +136,7 @@ impl From<SplitMetadataV0_8> for SplitMetadata {
             create_timestamp: v8.create_timestamp,
             maturity: v8.maturity,
             tags: v8.tags,
+            field_value_ranges: v8.field_value_ranges,
             footer_offsets: v8.footer_offsets,
             num_merge_ops: v8.num_merge_ops,
             doc_mapping_uid: v8.doc_mapping_uid,
@@ -152,6 +159,7 @@ impl From<SplitMetadata> for SplitMetadataV0_8 {
             create_timestamp: split.create_timestamp,
             maturity: split.maturity,
             tags: split.tags,
+            field_value_ranges: split.field_value_ranges,
             footer_offsets: split.footer_offsets,
             num_merge_ops: split.num_merge_ops,
             doc_mapping_uid: split.doc_mapping_uid,
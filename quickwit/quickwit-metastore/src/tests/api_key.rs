@@ -0,0 +1,156 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use quickwit_common::rand::append_random_suffix;
+use quickwit_config::{ApiKey, ApiKeyScope};
+use quickwit_proto::metastore::{
+    serde_utils, CreateApiKeyRequest, DeleteApiKeyRequest, EntityKind, ListApiKeysRequest,
+    MetastoreError, MetastoreResult, MetastoreService,
+};
+
+use super::DefaultForTest;
+use crate::MetastoreServiceExt;
+
+async fn list_all_api_keys(metastore: &mut dyn MetastoreService) -> MetastoreResult<Vec<ApiKey>> {
+    let list_api_keys_response = metastore.list_api_keys(ListApiKeysRequest {}).await?;
+    list_api_keys_response
+        .api_keys_json
+        .into_iter()
+        .map(|api_key_json| serde_utils::from_json_str(&api_key_json))
+        .collect()
+}
+
+async fn cleanup_api_keys(metastore: &mut dyn MetastoreService) {
+    for api_key in list_all_api_keys(metastore).await.unwrap() {
+        let delete_api_key_request = DeleteApiKeyRequest {
+            api_key_id: api_key.api_key_id,
+        };
+        metastore
+            .delete_api_key(delete_api_key_request)
+            .await
+            .unwrap();
+    }
+}
+
+pub async fn test_metastore_create_api_key<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+    cleanup_api_keys(&mut metastore).await;
+
+    let api_key_id = append_random_suffix("test-create-api-key");
+    let api_key = ApiKey::for_test(&api_key_id, &[ApiKeyScope::Search]);
+    let api_key_json = serde_json::to_string(&api_key).unwrap();
+
+    let create_api_key_request = CreateApiKeyRequest {
+        api_key_json: api_key_json.clone(),
+        overwrite: false,
+    };
+    metastore
+        .create_api_key(create_api_key_request.clone())
+        .await
+        .unwrap();
+
+    let api_keys = list_all_api_keys(&mut metastore).await.unwrap();
+    assert_eq!(api_keys, vec![api_key]);
+
+    let error = metastore
+        .create_api_key(create_api_key_request)
+        .await
+        .unwrap_err();
+    let expected_error = MetastoreError::AlreadyExists(EntityKind::ApiKey {
+        api_key_id: api_key_id.clone(),
+    });
+    assert_eq!(error, expected_error);
+
+    let mut api_key = ApiKey::for_test(&api_key_id, &[ApiKeyScope::Search]);
+    api_key.roles.insert("test-role".to_string());
+
+    let create_api_key_request = CreateApiKeyRequest {
+        api_key_json: serde_json::to_string(&api_key).unwrap(),
+        overwrite: true,
+    };
+    metastore
+        .create_api_key(create_api_key_request)
+        .await
+        .unwrap();
+
+    let api_keys = list_all_api_keys(&mut metastore).await.unwrap();
+    assert_eq!(api_keys, vec![api_key]);
+
+    let mut invalid_api_key = ApiKey::for_test(&api_key_id, &[ApiKeyScope::Search]);
+    invalid_api_key.scopes.clear();
+
+    let create_api_key_request = CreateApiKeyRequest {
+        api_key_json: serde_json::to_string(&invalid_api_key).unwrap(),
+        overwrite: false,
+    };
+    let error = metastore
+        .create_api_key(create_api_key_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    cleanup_api_keys(&mut metastore).await;
+}
+
+pub async fn test_metastore_delete_api_key<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+    cleanup_api_keys(&mut metastore).await;
+
+    let foo_api_key_id = append_random_suffix("test-api-key-foo");
+    let foo_api_key = ApiKey::for_test(&foo_api_key_id, &[ApiKeyScope::Ingest]);
+
+    let bar_api_key_id = append_random_suffix("test-api-key-bar");
+    let bar_api_key = ApiKey::for_test(&bar_api_key_id, &[ApiKeyScope::Admin]);
+
+    for api_key in [&foo_api_key, &bar_api_key] {
+        let create_api_key_request = CreateApiKeyRequest {
+            api_key_json: serde_json::to_string(api_key).unwrap(),
+            overwrite: false,
+        };
+        metastore
+            .create_api_key(create_api_key_request)
+            .await
+            .unwrap();
+    }
+    let delete_api_key_request = DeleteApiKeyRequest {
+        api_key_id: foo_api_key_id.clone(),
+    };
+    metastore
+        .delete_api_key(delete_api_key_request.clone())
+        .await
+        .unwrap();
+
+    let api_keys = list_all_api_keys(&mut metastore).await.unwrap();
+    assert_eq!(api_keys, vec![bar_api_key]);
+
+    let error = metastore
+        .delete_api_key(delete_api_key_request)
+        .await
+        .unwrap_err();
+    let expected_error = MetastoreError::NotFound(EntityKind::ApiKey {
+        api_key_id: foo_api_key_id,
+    });
+    assert_eq!(error, expected_error);
+
+    cleanup_api_keys(&mut metastore).await;
+}
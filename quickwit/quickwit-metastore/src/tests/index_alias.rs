@@ -0,0 +1,205 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use quickwit_config::{IndexAliasAction, IndexAliasConfig, IndexAliases};
+use quickwit_proto::metastore::{
+    DeleteIndexAliasesRequest, EntityKind, ListIndexAliasesRequest, MetastoreError,
+    MetastoreService, UpdateIndexAliasesRequest,
+};
+use quickwit_proto::types::IndexId;
+
+use super::DefaultForTest;
+use crate::{ListIndexAliasesResponseExt, MetastoreServiceExt, UpdateIndexAliasesRequestExt};
+
+async fn list_index_aliases(metastore: &mut dyn MetastoreService) -> IndexAliases {
+    metastore
+        .list_index_aliases(ListIndexAliasesRequest {})
+        .await
+        .unwrap()
+        .deserialize_index_aliases()
+        .unwrap()
+}
+
+async fn cleanup_index_aliases(metastore: &mut dyn MetastoreService) {
+    let index_ids: Vec<IndexId> = list_index_aliases(metastore)
+        .await
+        .iter()
+        .flat_map(|(_, alias_indexes)| alias_indexes.keys().cloned())
+        .collect();
+    let delete_index_aliases_request = DeleteIndexAliasesRequest { index_ids };
+    metastore
+        .delete_index_aliases(delete_index_aliases_request)
+        .await
+        .unwrap();
+}
+
+fn add_action(
+    index_ids: &[&str],
+    aliases: &[&str],
+    is_write_index: Option<bool>,
+) -> IndexAliasAction {
+    IndexAliasAction::Add {
+        index_ids: index_ids
+            .iter()
+            .map(|index_id| index_id.to_string())
+            .collect(),
+        aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+        config: IndexAliasConfig {
+            filter: None,
+            is_write_index,
+        },
+    }
+}
+
+fn remove_action(index_ids: &[&str], aliases: &[&str]) -> IndexAliasAction {
+    IndexAliasAction::Remove {
+        index_ids: index_ids
+            .iter()
+            .map(|index_id| index_id.to_string())
+            .collect(),
+        aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+    }
+}
+
+pub async fn test_metastore_update_index_aliases<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+    cleanup_index_aliases(&mut metastore).await;
+
+    let update_index_aliases_request = UpdateIndexAliasesRequest::try_from_actions(&[
+        add_action(
+            &["test-index-foo", "test-index-bar"],
+            &["test-alias-foo"],
+            None,
+        ),
+        add_action(&["test-index-bar"], &["test-alias-bar"], Some(true)),
+    ])
+    .unwrap();
+    metastore
+        .update_index_aliases(update_index_aliases_request)
+        .await
+        .unwrap();
+
+    let index_aliases = list_index_aliases(&mut metastore).await;
+    let alias_foo_index_ids: Vec<&IndexId> = index_aliases
+        .get("test-alias-foo")
+        .unwrap()
+        .keys()
+        .collect();
+    assert_eq!(alias_foo_index_ids, ["test-index-bar", "test-index-foo"]);
+    assert_eq!(
+        index_aliases.resolve_write_index("test-alias-bar").unwrap(),
+        "test-index-bar"
+    );
+
+    // The actions are applied atomically: the `add` action is discarded because the `remove`
+    // action fails.
+    let update_index_aliases_request = UpdateIndexAliasesRequest::try_from_actions(&[
+        add_action(&["test-index-qux"], &["test-alias-qux"], None),
+        remove_action(&["test-index-foo"], &["test-alias-unknown"]),
+    ])
+    .unwrap();
+    let error = metastore
+        .update_index_aliases(update_index_aliases_request)
+        .await
+        .unwrap_err();
+    let expected_error = MetastoreError::NotFound(EntityKind::IndexAlias {
+        alias: "test-alias-unknown".to_string(),
+    });
+    assert_eq!(error, expected_error);
+    assert_eq!(list_index_aliases(&mut metastore).await, index_aliases);
+
+    let update_index_aliases_request = UpdateIndexAliasesRequest::try_from_actions(&[
+        add_action(&["test-index-foo"], &["test-alias-qux"], Some(true)),
+        add_action(&["test-index-bar"], &["test-alias-qux"], Some(true)),
+    ])
+    .unwrap();
+    let error = metastore
+        .update_index_aliases(update_index_aliases_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    let update_index_aliases_request =
+        UpdateIndexAliasesRequest::try_from_actions(&[remove_action(
+            &["test-index-bar"],
+            &["test-alias-bar"],
+        )])
+        .unwrap();
+    metastore
+        .update_index_aliases(update_index_aliases_request)
+        .await
+        .unwrap();
+
+    let index_aliases = list_index_aliases(&mut metastore).await;
+    assert!(index_aliases.get("test-alias-foo").is_some());
+    assert!(index_aliases.get("test-alias-bar").is_none());
+
+    cleanup_index_aliases(&mut metastore).await;
+}
+
+pub async fn test_metastore_delete_index_aliases<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+    cleanup_index_aliases(&mut metastore).await;
+
+    let update_index_aliases_request = UpdateIndexAliasesRequest::try_from_actions(&[
+        add_action(
+            &["test-index-foo", "test-index-bar"],
+            &["test-alias-foo"],
+            None,
+        ),
+        add_action(&["test-index-bar"], &["test-alias-bar"], None),
+    ])
+    .unwrap();
+    metastore
+        .update_index_aliases(update_index_aliases_request)
+        .await
+        .unwrap();
+
+    let delete_index_aliases_request = DeleteIndexAliasesRequest {
+        index_ids: vec![
+            "test-index-bar".to_string(),
+            "test-index-unknown".to_string(),
+        ],
+    };
+    metastore
+        .delete_index_aliases(delete_index_aliases_request.clone())
+        .await
+        .unwrap();
+
+    let index_aliases = list_index_aliases(&mut metastore).await;
+    let alias_foo_index_ids: Vec<&IndexId> = index_aliases
+        .get("test-alias-foo")
+        .unwrap()
+        .keys()
+        .collect();
+    assert_eq!(alias_foo_index_ids, ["test-index-foo"]);
+    assert!(index_aliases.get("test-alias-bar").is_none());
+
+    // Removing indexes that no alias contains is a no-op.
+    metastore
+        .delete_index_aliases(delete_index_aliases_request)
+        .await
+        .unwrap();
+
+    cleanup_index_aliases(&mut metastore).await;
+}
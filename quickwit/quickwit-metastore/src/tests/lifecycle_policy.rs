@@ -0,0 +1,159 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use quickwit_common::rand::append_random_suffix;
+use quickwit_config::LifecyclePolicy;
+use quickwit_proto::metastore::{
+    serde_utils, CreateLifecyclePolicyRequest, DeleteLifecyclePolicyRequest, EntityKind,
+    ListLifecyclePoliciesRequest, MetastoreError, MetastoreResult, MetastoreService,
+};
+
+use super::DefaultForTest;
+use crate::MetastoreServiceExt;
+
+async fn list_all_lifecycle_policies(
+    metastore: &mut dyn MetastoreService,
+) -> MetastoreResult<Vec<LifecyclePolicy>> {
+    let list_lifecycle_policies_response = metastore
+        .list_lifecycle_policies(ListLifecyclePoliciesRequest {})
+        .await?;
+    list_lifecycle_policies_response
+        .lifecycle_policies_json
+        .into_iter()
+        .map(|lifecycle_policy_json| serde_utils::from_json_str(&lifecycle_policy_json))
+        .collect()
+}
+
+async fn cleanup_lifecycle_policies(metastore: &mut dyn MetastoreService) {
+    for lifecycle_policy in list_all_lifecycle_policies(metastore).await.unwrap() {
+        let delete_lifecycle_policy_request = DeleteLifecyclePolicyRequest {
+            policy_id: lifecycle_policy.policy_id,
+        };
+        metastore
+            .delete_lifecycle_policy(delete_lifecycle_policy_request)
+            .await
+            .unwrap();
+    }
+}
+
+pub async fn test_metastore_create_lifecycle_policy<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+    cleanup_lifecycle_policies(&mut metastore).await;
+
+    let policy_id = append_random_suffix("test-create-lifecycle-policy");
+    let lifecycle_policy = LifecyclePolicy::for_test(&policy_id, &["test-index-*"]);
+
+    let create_lifecycle_policy_request = CreateLifecyclePolicyRequest {
+        lifecycle_policy_json: serde_json::to_string(&lifecycle_policy).unwrap(),
+        overwrite: false,
+    };
+    metastore
+        .create_lifecycle_policy(create_lifecycle_policy_request.clone())
+        .await
+        .unwrap();
+
+    let lifecycle_policies = list_all_lifecycle_policies(&mut metastore).await.unwrap();
+    assert_eq!(lifecycle_policies, vec![lifecycle_policy]);
+
+    let error = metastore
+        .create_lifecycle_policy(create_lifecycle_policy_request)
+        .await
+        .unwrap_err();
+    let expected_error = MetastoreError::AlreadyExists(EntityKind::LifecyclePolicy {
+        policy_id: policy_id.clone(),
+    });
+    assert_eq!(error, expected_error);
+
+    let mut lifecycle_policy = LifecyclePolicy::for_test(&policy_id, &["test-index-*"]);
+    lifecycle_policy.priority = 1;
+
+    let create_lifecycle_policy_request = CreateLifecyclePolicyRequest {
+        lifecycle_policy_json: serde_json::to_string(&lifecycle_policy).unwrap(),
+        overwrite: true,
+    };
+    metastore
+        .create_lifecycle_policy(create_lifecycle_policy_request)
+        .await
+        .unwrap();
+
+    let lifecycle_policies = list_all_lifecycle_policies(&mut metastore).await.unwrap();
+    assert_eq!(lifecycle_policies, vec![lifecycle_policy]);
+
+    let mut invalid_lifecycle_policy = LifecyclePolicy::for_test(&policy_id, &["test-index-*"]);
+    invalid_lifecycle_policy.index_id_patterns.clear();
+
+    let create_lifecycle_policy_request = CreateLifecyclePolicyRequest {
+        lifecycle_policy_json: serde_json::to_string(&invalid_lifecycle_policy).unwrap(),
+        overwrite: true,
+    };
+    let error = metastore
+        .create_lifecycle_policy(create_lifecycle_policy_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    cleanup_lifecycle_policies(&mut metastore).await;
+}
+
+pub async fn test_metastore_delete_lifecycle_policy<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+    cleanup_lifecycle_policies(&mut metastore).await;
+
+    let foo_policy_id = append_random_suffix("test-lifecycle-policy-foo");
+    let foo_lifecycle_policy = LifecyclePolicy::for_test(&foo_policy_id, &["test-index-foo*"]);
+
+    let bar_policy_id = append_random_suffix("test-lifecycle-policy-bar");
+    let bar_lifecycle_policy = LifecyclePolicy::for_test(&bar_policy_id, &["test-index-bar*"]);
+
+    for lifecycle_policy in [&foo_lifecycle_policy, &bar_lifecycle_policy] {
+        let create_lifecycle_policy_request = CreateLifecyclePolicyRequest {
+            lifecycle_policy_json: serde_json::to_string(lifecycle_policy).unwrap(),
+            overwrite: false,
+        };
+        metastore
+            .create_lifecycle_policy(create_lifecycle_policy_request)
+            .await
+            .unwrap();
+    }
+    let delete_lifecycle_policy_request = DeleteLifecyclePolicyRequest {
+        policy_id: foo_policy_id.clone(),
+    };
+    metastore
+        .delete_lifecycle_policy(delete_lifecycle_policy_request.clone())
+        .await
+        .unwrap();
+
+    let lifecycle_policies = list_all_lifecycle_policies(&mut metastore).await.unwrap();
+    assert_eq!(lifecycle_policies, vec![bar_lifecycle_policy]);
+
+    let error = metastore
+        .delete_lifecycle_policy(delete_lifecycle_policy_request)
+        .await
+        .unwrap_err();
+    let expected_error = MetastoreError::NotFound(EntityKind::LifecyclePolicy {
+        policy_id: foo_policy_id,
+    });
+    assert_eq!(error, expected_error);
+
+    cleanup_lifecycle_policies(&mut metastore).await;
+}
@@ -30,9 +30,14 @@ use quickwit_proto::metastore::{
 use quickwit_proto::tonic::transport::Channel;
 use quickwit_proto::types::IndexUid;
 
+pub(crate) mod api_key;
 pub(crate) mod delete_task;
 pub(crate) mod index;
+pub(crate) mod index_alias;
+pub(crate) mod lifecycle_policy;
 pub(crate) mod list_splits;
+pub(crate) mod point_in_time;
+pub(crate) mod role;
 pub(crate) mod shard;
 pub(crate) mod source;
 pub(crate) mod split;
@@ -521,6 +526,76 @@ macro_rules! metastore_test_suite {
             async fn test_metastore_delete_index_templates() {
                 $crate::tests::template::test_metastore_delete_index_templates::<$metastore_type>().await;
             }
+
+            /// API Key API tests
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_create_api_key() {
+                $crate::tests::api_key::test_metastore_create_api_key::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_delete_api_key() {
+                $crate::tests::api_key::test_metastore_delete_api_key::<$metastore_type>().await;
+            }
+
+            /// Role API tests
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_create_role() {
+                $crate::tests::role::test_metastore_create_role::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_delete_role() {
+                $crate::tests::role::test_metastore_delete_role::<$metastore_type>().await;
+            }
+
+            /// Point-in-time API tests
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_create_point_in_time() {
+                $crate::tests::point_in_time::test_metastore_create_point_in_time::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_list_and_delete_point_in_times() {
+                $crate::tests::point_in_time::test_metastore_list_and_delete_point_in_times::<$metastore_type>().await;
+            }
+
+            /// Index alias API tests
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_update_index_aliases() {
+                $crate::tests::index_alias::test_metastore_update_index_aliases::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_delete_index_aliases() {
+                $crate::tests::index_alias::test_metastore_delete_index_aliases::<$metastore_type>().await;
+            }
+
+            /// Lifecycle policy API tests
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_create_lifecycle_policy() {
+                $crate::tests::lifecycle_policy::test_metastore_create_lifecycle_policy::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            #[serial_test::file_serial]
+            async fn test_metastore_delete_lifecycle_policy() {
+                $crate::tests::lifecycle_policy::test_metastore_delete_lifecycle_policy::<$metastore_type>().await;
+            }
         }
     };
 }
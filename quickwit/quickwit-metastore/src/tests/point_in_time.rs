@@ -0,0 +1,178 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use quickwit_common::rand::append_random_suffix;
+use quickwit_proto::metastore::{
+    serde_utils, CreatePointInTimeRequest, DeletePointInTimesRequest, EntityKind,
+    ListPointInTimesRequest, MetastoreError, MetastoreResult, MetastoreService,
+};
+use quickwit_proto::types::IndexUid;
+
+use super::DefaultForTest;
+use crate::{MetastoreServiceExt, PointInTime};
+
+async fn list_point_in_times(
+    metastore: &mut dyn MetastoreService,
+    point_in_time_keys: Vec<String>,
+) -> MetastoreResult<Vec<PointInTime>> {
+    let list_point_in_times_request = ListPointInTimesRequest { point_in_time_keys };
+    let list_point_in_times_response = metastore
+        .list_point_in_times(list_point_in_times_request)
+        .await?;
+    list_point_in_times_response
+        .point_in_times_json
+        .into_iter()
+        .map(|point_in_time_json| serde_utils::from_json_str(&point_in_time_json))
+        .collect()
+}
+
+async fn cleanup_point_in_times(metastore: &mut dyn MetastoreService) {
+    let point_in_time_keys = list_point_in_times(metastore, Vec::new())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|point_in_time| point_in_time.point_in_time_key)
+        .collect();
+    let delete_point_in_times_request = DeletePointInTimesRequest { point_in_time_keys };
+    metastore
+        .delete_point_in_times(delete_point_in_times_request)
+        .await
+        .unwrap();
+}
+
+fn point_in_time_for_test(point_in_time_key: &str, split_ids: &[&str]) -> PointInTime {
+    let index_uid = IndexUid::for_test("test-index", 0);
+    let split_ids = split_ids
+        .iter()
+        .map(|split_id| split_id.to_string())
+        .collect::<BTreeSet<_>>();
+    PointInTime {
+        point_in_time_key: point_in_time_key.to_string(),
+        split_ids: BTreeMap::from_iter([(index_uid, split_ids)]),
+        expire_at: 1_700_000_000,
+    }
+}
+
+pub async fn test_metastore_create_point_in_time<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+    cleanup_point_in_times(&mut metastore).await;
+
+    let point_in_time_key = append_random_suffix("test-create-point-in-time");
+    let point_in_time = point_in_time_for_test(&point_in_time_key, &["split-1"]);
+
+    let create_point_in_time_request = CreatePointInTimeRequest {
+        point_in_time_json: serde_json::to_string(&point_in_time).unwrap(),
+        overwrite: false,
+    };
+    metastore
+        .create_point_in_time(create_point_in_time_request.clone())
+        .await
+        .unwrap();
+
+    let point_in_times = list_point_in_times(&mut metastore, Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(point_in_times, vec![point_in_time]);
+
+    let error = metastore
+        .create_point_in_time(create_point_in_time_request)
+        .await
+        .unwrap_err();
+    let expected_error = MetastoreError::AlreadyExists(EntityKind::PointInTime {
+        point_in_time_key: point_in_time_key.clone(),
+    });
+    assert_eq!(error, expected_error);
+
+    let mut point_in_time = point_in_time_for_test(&point_in_time_key, &["split-1"]);
+    point_in_time.expire_at += 60;
+
+    let create_point_in_time_request = CreatePointInTimeRequest {
+        point_in_time_json: serde_json::to_string(&point_in_time).unwrap(),
+        overwrite: true,
+    };
+    metastore
+        .create_point_in_time(create_point_in_time_request)
+        .await
+        .unwrap();
+
+    let point_in_times = list_point_in_times(&mut metastore, Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(point_in_times, vec![point_in_time]);
+
+    cleanup_point_in_times(&mut metastore).await;
+}
+
+pub async fn test_metastore_list_and_delete_point_in_times<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+    cleanup_point_in_times(&mut metastore).await;
+
+    let foo_point_in_time_key = append_random_suffix("test-point-in-time-foo");
+    let foo_point_in_time = point_in_time_for_test(&foo_point_in_time_key, &["split-1"]);
+
+    let bar_point_in_time_key = append_random_suffix("test-point-in-time-bar");
+    let bar_point_in_time = point_in_time_for_test(&bar_point_in_time_key, &["split-2"]);
+
+    for point_in_time in [&foo_point_in_time, &bar_point_in_time] {
+        let create_point_in_time_request = CreatePointInTimeRequest {
+            point_in_time_json: serde_json::to_string(point_in_time).unwrap(),
+            overwrite: false,
+        };
+        metastore
+            .create_point_in_time(create_point_in_time_request)
+            .await
+            .unwrap();
+    }
+    let point_in_times = list_point_in_times(&mut metastore, vec![foo_point_in_time_key.clone()])
+        .await
+        .unwrap();
+    assert_eq!(point_in_times, vec![foo_point_in_time]);
+
+    let point_in_times = list_point_in_times(&mut metastore, vec!["unknown-key".to_string()])
+        .await
+        .unwrap();
+    assert!(point_in_times.is_empty());
+
+    let delete_point_in_times_request = DeletePointInTimesRequest {
+        point_in_time_keys: vec![foo_point_in_time_key, "unknown-key".to_string()],
+    };
+    metastore
+        .delete_point_in_times(delete_point_in_times_request.clone())
+        .await
+        .unwrap();
+
+    let point_in_times = list_point_in_times(&mut metastore, Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(point_in_times, vec![bar_point_in_time]);
+
+    // Deleting unknown points in time is a no-op.
+    metastore
+        .delete_point_in_times(delete_point_in_times_request)
+        .await
+        .unwrap();
+
+    cleanup_point_in_times(&mut metastore).await;
+}
@@ -0,0 +1,145 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use quickwit_common::rand::append_random_suffix;
+use quickwit_config::{IndexPrivilege, Role};
+use quickwit_proto::metastore::{
+    serde_utils, CreateRoleRequest, DeleteRoleRequest, EntityKind, ListRolesRequest,
+    MetastoreError, MetastoreResult, MetastoreService,
+};
+
+use super::DefaultForTest;
+use crate::MetastoreServiceExt;
+
+async fn list_all_roles(metastore: &mut dyn MetastoreService) -> MetastoreResult<Vec<Role>> {
+    let list_roles_response = metastore.list_roles(ListRolesRequest {}).await?;
+    list_roles_response
+        .roles_json
+        .into_iter()
+        .map(|role_json| serde_utils::from_json_str(&role_json))
+        .collect()
+}
+
+async fn cleanup_roles(metastore: &mut dyn MetastoreService) {
+    for role in list_all_roles(metastore).await.unwrap() {
+        let delete_role_request = DeleteRoleRequest {
+            role_id: role.role_id,
+        };
+        metastore.delete_role(delete_role_request).await.unwrap();
+    }
+}
+
+pub async fn test_metastore_create_role<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+    cleanup_roles(&mut metastore).await;
+
+    let role_id = append_random_suffix("test-create-role");
+    let role = Role::for_test(&role_id, &["test-index-*"], IndexPrivilege::Read);
+
+    let create_role_request = CreateRoleRequest {
+        role_json: serde_json::to_string(&role).unwrap(),
+        overwrite: false,
+    };
+    metastore
+        .create_role(create_role_request.clone())
+        .await
+        .unwrap();
+
+    let roles = list_all_roles(&mut metastore).await.unwrap();
+    assert_eq!(roles, vec![role]);
+
+    let error = metastore
+        .create_role(create_role_request)
+        .await
+        .unwrap_err();
+    let expected_error = MetastoreError::AlreadyExists(EntityKind::Role {
+        role_id: role_id.clone(),
+    });
+    assert_eq!(error, expected_error);
+
+    let role = Role::for_test(&role_id, &["test-index-*"], IndexPrivilege::Write);
+
+    let create_role_request = CreateRoleRequest {
+        role_json: serde_json::to_string(&role).unwrap(),
+        overwrite: true,
+    };
+    metastore.create_role(create_role_request).await.unwrap();
+
+    let roles = list_all_roles(&mut metastore).await.unwrap();
+    assert_eq!(roles, vec![role]);
+
+    let mut invalid_role = Role::for_test(&role_id, &["test-index-*"], IndexPrivilege::Read);
+    invalid_role.grants.clear();
+
+    let create_role_request = CreateRoleRequest {
+        role_json: serde_json::to_string(&invalid_role).unwrap(),
+        overwrite: true,
+    };
+    let error = metastore
+        .create_role(create_role_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    cleanup_roles(&mut metastore).await;
+}
+
+pub async fn test_metastore_delete_role<
+    MetastoreUnderTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreUnderTest::default_for_test().await;
+    cleanup_roles(&mut metastore).await;
+
+    let foo_role_id = append_random_suffix("test-role-foo");
+    let foo_role = Role::for_test(&foo_role_id, &["test-index-foo*"], IndexPrivilege::Read);
+
+    let bar_role_id = append_random_suffix("test-role-bar");
+    let bar_role = Role::for_test(&bar_role_id, &["test-index-bar*"], IndexPrivilege::Admin);
+
+    for role in [&foo_role, &bar_role] {
+        let create_role_request = CreateRoleRequest {
+            role_json: serde_json::to_string(role).unwrap(),
+            overwrite: false,
+        };
+        metastore.create_role(create_role_request).await.unwrap();
+    }
+    let delete_role_request = DeleteRoleRequest {
+        role_id: foo_role_id.clone(),
+    };
+    metastore
+        .delete_role(delete_role_request.clone())
+        .await
+        .unwrap();
+
+    let roles = list_all_roles(&mut metastore).await.unwrap();
+    assert_eq!(roles, vec![bar_role]);
+
+    let error = metastore
+        .delete_role(delete_role_request)
+        .await
+        .unwrap_err();
+    let expected_error = MetastoreError::NotFound(EntityKind::Role {
+        role_id: foo_role_id,
+    });
+    assert_eq!(error, expected_error);
+
+    cleanup_roles(&mut metastore).await;
+}
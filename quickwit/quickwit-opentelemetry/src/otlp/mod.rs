@@ -201,7 +201,7 @@ pub fn extract_otel_traces_index_id_patterns_from_metadata(
     Ok(index_id_patterns)
 }
 
-pub(crate) fn extract_otel_index_id_from_metadata(
+pub fn extract_otel_index_id_from_metadata(
     metadata: &tonic::metadata::MetadataMap,
     otel_signal: OtelSignal,
 ) -> Result<String, Status> {
@@ -535,6 +535,142 @@ pub struct DeleteIndexTemplatesRequest {
     pub template_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateApiKeyRequest {
+    #[prost(string, tag = "1")]
+    pub api_key_json: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub overwrite: bool,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListApiKeysRequest {}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListApiKeysResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub api_keys_json: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteApiKeyRequest {
+    #[prost(string, tag = "1")]
+    pub api_key_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateRoleRequest {
+    #[prost(string, tag = "1")]
+    pub role_json: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub overwrite: bool,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListRolesRequest {}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListRolesResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub roles_json: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteRoleRequest {
+    #[prost(string, tag = "1")]
+    pub role_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreatePointInTimeRequest {
+    #[prost(string, tag = "1")]
+    pub point_in_time_json: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub overwrite: bool,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListPointInTimesRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub point_in_time_keys: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListPointInTimesResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub point_in_times_json: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeletePointInTimesRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub point_in_time_keys: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateIndexAliasesRequest {
+    #[prost(string, tag = "1")]
+    pub index_alias_actions_json: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListIndexAliasesRequest {}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListIndexAliasesResponse {
+    #[prost(string, tag = "1")]
+    pub index_aliases_json: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteIndexAliasesRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub index_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateLifecyclePolicyRequest {
+    #[prost(string, tag = "1")]
+    pub lifecycle_policy_json: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub overwrite: bool,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListLifecyclePoliciesRequest {}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListLifecyclePoliciesResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub lifecycle_policies_json: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteLifecyclePolicyRequest {
+    #[prost(string, tag = "1")]
+    pub policy_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -791,6 +927,81 @@ impl RpcName for DeleteIndexTemplatesRequest {
         "delete_index_templates"
     }
 }
+impl RpcName for CreateApiKeyRequest {
+    fn rpc_name() -> &'static str {
+        "create_api_key"
+    }
+}
+impl RpcName for ListApiKeysRequest {
+    fn rpc_name() -> &'static str {
+        "list_api_keys"
+    }
+}
+impl RpcName for DeleteApiKeyRequest {
+    fn rpc_name() -> &'static str {
+        "delete_api_key"
+    }
+}
+impl RpcName for CreateRoleRequest {
+    fn rpc_name() -> &'static str {
+        "create_role"
+    }
+}
+impl RpcName for ListRolesRequest {
+    fn rpc_name() -> &'static str {
+        "list_roles"
+    }
+}
+impl RpcName for DeleteRoleRequest {
+    fn rpc_name() -> &'static str {
+        "delete_role"
+    }
+}
+impl RpcName for CreatePointInTimeRequest {
+    fn rpc_name() -> &'static str {
+        "create_point_in_time"
+    }
+}
+impl RpcName for ListPointInTimesRequest {
+    fn rpc_name() -> &'static str {
+        "list_point_in_times"
+    }
+}
+impl RpcName for DeletePointInTimesRequest {
+    fn rpc_name() -> &'static str {
+        "delete_point_in_times"
+    }
+}
+impl RpcName for UpdateIndexAliasesRequest {
+    fn rpc_name() -> &'static str {
+        "update_index_aliases"
+    }
+}
+impl RpcName for ListIndexAliasesRequest {
+    fn rpc_name() -> &'static str {
+        "list_index_aliases"
+    }
+}
+impl RpcName for DeleteIndexAliasesRequest {
+    fn rpc_name() -> &'static str {
+        "delete_index_aliases"
+    }
+}
+impl RpcName for CreateLifecyclePolicyRequest {
+    fn rpc_name() -> &'static str {
+        "create_lifecycle_policy"
+    }
+}
+impl RpcName for ListLifecyclePoliciesRequest {
+    fn rpc_name() -> &'static str {
+        "list_lifecycle_policies"
+    }
+}
+impl RpcName for DeleteLifecyclePolicyRequest {
+    fn rpc_name() -> &'static str {
+        "delete_lifecycle_policy"
+    }
+}
 pub type MetastoreServiceStream<T> = quickwit_common::ServiceStream<
     crate::metastore::MetastoreResult<T>,
 >;
@@ -963,6 +1174,81 @@ pub trait MetastoreService: std::fmt::Debug + Send + Sync + 'static {
         &self,
         request: DeleteIndexTemplatesRequest,
     ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Creates an API key, or replaces it if `overwrite` is set.
+    async fn create_api_key(
+        &self,
+        request: CreateApiKeyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Returns all the API keys.
+    async fn list_api_keys(
+        &self,
+        request: ListApiKeysRequest,
+    ) -> crate::metastore::MetastoreResult<ListApiKeysResponse>;
+    /// Deletes an API key.
+    async fn delete_api_key(
+        &self,
+        request: DeleteApiKeyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Creates a role, or replaces it if `overwrite` is set.
+    async fn create_role(
+        &self,
+        request: CreateRoleRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Returns all the roles.
+    async fn list_roles(
+        &self,
+        request: ListRolesRequest,
+    ) -> crate::metastore::MetastoreResult<ListRolesResponse>;
+    /// Deletes a role.
+    async fn delete_role(
+        &self,
+        request: DeleteRoleRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Creates a point in time, or replaces it if `overwrite` is set.
+    async fn create_point_in_time(
+        &self,
+        request: CreatePointInTimeRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Returns the points in time with the given keys, or all of them if no keys are given.
+    async fn list_point_in_times(
+        &self,
+        request: ListPointInTimesRequest,
+    ) -> crate::metastore::MetastoreResult<ListPointInTimesResponse>;
+    /// Deletes the points in time with the given keys. Unknown keys are ignored.
+    async fn delete_point_in_times(
+        &self,
+        request: DeletePointInTimesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Applies a list of alias actions atomically: if one of them fails, none is applied.
+    async fn update_index_aliases(
+        &self,
+        request: UpdateIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Returns the index aliases.
+    async fn list_index_aliases(
+        &self,
+        request: ListIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<ListIndexAliasesResponse>;
+    /// Removes the given indexes from all the aliases.
+    async fn delete_index_aliases(
+        &self,
+        request: DeleteIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Creates a lifecycle policy, or replaces it if `overwrite` is set.
+    async fn create_lifecycle_policy(
+        &self,
+        request: CreateLifecyclePolicyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Returns all the lifecycle policies.
+    async fn list_lifecycle_policies(
+        &self,
+        request: ListLifecyclePoliciesRequest,
+    ) -> crate::metastore::MetastoreResult<ListLifecyclePoliciesResponse>;
+    /// Deletes a lifecycle policy.
+    async fn delete_lifecycle_policy(
+        &self,
+        request: DeleteLifecyclePolicyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
     async fn check_connectivity(&self) -> anyhow::Result<()>;
     fn endpoints(&self) -> Vec<quickwit_common::uri::Uri>;
 }
@@ -1237,6 +1523,96 @@ impl MetastoreService for MetastoreServiceClient {
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner.0.delete_index_templates(request).await
     }
+    async fn create_api_key(
+        &self,
+        request: CreateApiKeyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.create_api_key(request).await
+    }
+    async fn list_api_keys(
+        &self,
+        request: ListApiKeysRequest,
+    ) -> crate::metastore::MetastoreResult<ListApiKeysResponse> {
+        self.inner.0.list_api_keys(request).await
+    }
+    async fn delete_api_key(
+        &self,
+        request: DeleteApiKeyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.delete_api_key(request).await
+    }
+    async fn create_role(
+        &self,
+        request: CreateRoleRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.create_role(request).await
+    }
+    async fn list_roles(
+        &self,
+        request: ListRolesRequest,
+    ) -> crate::metastore::MetastoreResult<ListRolesResponse> {
+        self.inner.0.list_roles(request).await
+    }
+    async fn delete_role(
+        &self,
+        request: DeleteRoleRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.delete_role(request).await
+    }
+    async fn create_point_in_time(
+        &self,
+        request: CreatePointInTimeRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.create_point_in_time(request).await
+    }
+    async fn list_point_in_times(
+        &self,
+        request: ListPointInTimesRequest,
+    ) -> crate::metastore::MetastoreResult<ListPointInTimesResponse> {
+        self.inner.0.list_point_in_times(request).await
+    }
+    async fn delete_point_in_times(
+        &self,
+        request: DeletePointInTimesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.delete_point_in_times(request).await
+    }
+    async fn update_index_aliases(
+        &self,
+        request: UpdateIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.update_index_aliases(request).await
+    }
+    async fn list_index_aliases(
+        &self,
+        request: ListIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<ListIndexAliasesResponse> {
+        self.inner.0.list_index_aliases(request).await
+    }
+    async fn delete_index_aliases(
+        &self,
+        request: DeleteIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.delete_index_aliases(request).await
+    }
+    async fn create_lifecycle_policy(
+        &self,
+        request: CreateLifecyclePolicyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.create_lifecycle_policy(request).await
+    }
+    async fn list_lifecycle_policies(
+        &self,
+        request: ListLifecyclePoliciesRequest,
+    ) -> crate::metastore::MetastoreResult<ListLifecyclePoliciesResponse> {
+        self.inner.0.list_lifecycle_policies(request).await
+    }
+    async fn delete_lifecycle_policy(
+        &self,
+        request: DeleteLifecyclePolicyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.0.delete_lifecycle_policy(request).await
+    }
     async fn check_connectivity(&self) -> anyhow::Result<()> {
         self.inner.0.check_connectivity().await
     }
@@ -1437,6 +1813,96 @@ pub mod mock_metastore_service {
         ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
             self.inner.lock().await.delete_index_templates(request).await
         }
+        async fn create_api_key(
+            &self,
+            request: super::CreateApiKeyRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.create_api_key(request).await
+        }
+        async fn list_api_keys(
+            &self,
+            request: super::ListApiKeysRequest,
+        ) -> crate::metastore::MetastoreResult<super::ListApiKeysResponse> {
+            self.inner.lock().await.list_api_keys(request).await
+        }
+        async fn delete_api_key(
+            &self,
+            request: super::DeleteApiKeyRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.delete_api_key(request).await
+        }
+        async fn create_role(
+            &self,
+            request: super::CreateRoleRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.create_role(request).await
+        }
+        async fn list_roles(
+            &self,
+            request: super::ListRolesRequest,
+        ) -> crate::metastore::MetastoreResult<super::ListRolesResponse> {
+            self.inner.lock().await.list_roles(request).await
+        }
+        async fn delete_role(
+            &self,
+            request: super::DeleteRoleRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.delete_role(request).await
+        }
+        async fn create_point_in_time(
+            &self,
+            request: super::CreatePointInTimeRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.create_point_in_time(request).await
+        }
+        async fn list_point_in_times(
+            &self,
+            request: super::ListPointInTimesRequest,
+        ) -> crate::metastore::MetastoreResult<super::ListPointInTimesResponse> {
+            self.inner.lock().await.list_point_in_times(request).await
+        }
+        async fn delete_point_in_times(
+            &self,
+            request: super::DeletePointInTimesRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.delete_point_in_times(request).await
+        }
+        async fn update_index_aliases(
+            &self,
+            request: super::UpdateIndexAliasesRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.update_index_aliases(request).await
+        }
+        async fn list_index_aliases(
+            &self,
+            request: super::ListIndexAliasesRequest,
+        ) -> crate::metastore::MetastoreResult<super::ListIndexAliasesResponse> {
+            self.inner.lock().await.list_index_aliases(request).await
+        }
+        async fn delete_index_aliases(
+            &self,
+            request: super::DeleteIndexAliasesRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.delete_index_aliases(request).await
+        }
+        async fn create_lifecycle_policy(
+            &self,
+            request: super::CreateLifecyclePolicyRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.create_lifecycle_policy(request).await
+        }
+        async fn list_lifecycle_policies(
+            &self,
+            request: super::ListLifecyclePoliciesRequest,
+        ) -> crate::metastore::MetastoreResult<super::ListLifecyclePoliciesResponse> {
+            self.inner.lock().await.list_lifecycle_policies(request).await
+        }
+        async fn delete_lifecycle_policy(
+            &self,
+            request: super::DeleteLifecyclePolicyRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.delete_lifecycle_policy(request).await
+        }
         async fn check_connectivity(&self) -> anyhow::Result<()> {
             self.inner.lock().await.check_connectivity().await
         }
@@ -1928,11 +2394,251 @@ impl tower::Service<DeleteIndexTemplatesRequest> for InnerMetastoreServiceClient
         Box::pin(fut)
     }
 }
-/// A tower service stack is a set of tower services.
-#[derive(Debug)]
-struct MetastoreServiceTowerServiceStack {
-    #[allow(dead_code)]
-    inner: InnerMetastoreServiceClient,
+impl tower::Service<CreateApiKeyRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: CreateApiKeyRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.create_api_key(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<ListApiKeysRequest> for InnerMetastoreServiceClient {
+    type Response = ListApiKeysResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: ListApiKeysRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.list_api_keys(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<DeleteApiKeyRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: DeleteApiKeyRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.delete_api_key(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<CreateRoleRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: CreateRoleRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.create_role(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<ListRolesRequest> for InnerMetastoreServiceClient {
+    type Response = ListRolesResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: ListRolesRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.list_roles(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<DeleteRoleRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: DeleteRoleRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.delete_role(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<CreatePointInTimeRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: CreatePointInTimeRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.create_point_in_time(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<ListPointInTimesRequest> for InnerMetastoreServiceClient {
+    type Response = ListPointInTimesResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: ListPointInTimesRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.list_point_in_times(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<DeletePointInTimesRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: DeletePointInTimesRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.delete_point_in_times(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<UpdateIndexAliasesRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: UpdateIndexAliasesRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.update_index_aliases(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<ListIndexAliasesRequest> for InnerMetastoreServiceClient {
+    type Response = ListIndexAliasesResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: ListIndexAliasesRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.list_index_aliases(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<DeleteIndexAliasesRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: DeleteIndexAliasesRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.delete_index_aliases(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<CreateLifecyclePolicyRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: CreateLifecyclePolicyRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.create_lifecycle_policy(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<ListLifecyclePoliciesRequest> for InnerMetastoreServiceClient {
+    type Response = ListLifecyclePoliciesResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: ListLifecyclePoliciesRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.list_lifecycle_policies(request).await };
+        Box::pin(fut)
+    }
+}
+impl tower::Service<DeleteLifecyclePolicyRequest> for InnerMetastoreServiceClient {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: DeleteLifecyclePolicyRequest) -> Self::Future {
+        let svc = self.clone();
+        let fut = async move { svc.0.delete_lifecycle_policy(request).await };
+        Box::pin(fut)
+    }
+}
+/// A tower service stack is a set of tower services.
+#[derive(Debug)]
+struct MetastoreServiceTowerServiceStack {
+    #[allow(dead_code)]
+    inner: InnerMetastoreServiceClient,
     create_index_svc: quickwit_common::tower::BoxService<
         CreateIndexRequest,
         CreateIndexResponse,
@@ -2083,6 +2789,81 @@ struct MetastoreServiceTowerServiceStack {
         EmptyResponse,
         crate::metastore::MetastoreError,
     >,
+    create_api_key_svc: quickwit_common::tower::BoxService<
+        CreateApiKeyRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    list_api_keys_svc: quickwit_common::tower::BoxService<
+        ListApiKeysRequest,
+        ListApiKeysResponse,
+        crate::metastore::MetastoreError,
+    >,
+    delete_api_key_svc: quickwit_common::tower::BoxService<
+        DeleteApiKeyRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    create_role_svc: quickwit_common::tower::BoxService<
+        CreateRoleRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    list_roles_svc: quickwit_common::tower::BoxService<
+        ListRolesRequest,
+        ListRolesResponse,
+        crate::metastore::MetastoreError,
+    >,
+    delete_role_svc: quickwit_common::tower::BoxService<
+        DeleteRoleRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    create_point_in_time_svc: quickwit_common::tower::BoxService<
+        CreatePointInTimeRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    list_point_in_times_svc: quickwit_common::tower::BoxService<
+        ListPointInTimesRequest,
+        ListPointInTimesResponse,
+        crate::metastore::MetastoreError,
+    >,
+    delete_point_in_times_svc: quickwit_common::tower::BoxService<
+        DeletePointInTimesRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    update_index_aliases_svc: quickwit_common::tower::BoxService<
+        UpdateIndexAliasesRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    list_index_aliases_svc: quickwit_common::tower::BoxService<
+        ListIndexAliasesRequest,
+        ListIndexAliasesResponse,
+        crate::metastore::MetastoreError,
+    >,
+    delete_index_aliases_svc: quickwit_common::tower::BoxService<
+        DeleteIndexAliasesRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    create_lifecycle_policy_svc: quickwit_common::tower::BoxService<
+        CreateLifecyclePolicyRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    list_lifecycle_policies_svc: quickwit_common::tower::BoxService<
+        ListLifecyclePoliciesRequest,
+        ListLifecyclePoliciesResponse,
+        crate::metastore::MetastoreError,
+    >,
+    delete_lifecycle_policy_svc: quickwit_common::tower::BoxService<
+        DeleteLifecyclePolicyRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
 }
 #[async_trait::async_trait]
 impl MetastoreService for MetastoreServiceTowerServiceStack {
@@ -2266,26 +3047,116 @@ impl MetastoreService for MetastoreServiceTowerServiceStack {
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.delete_index_templates_svc.clone().ready().await?.call(request).await
     }
-    async fn check_connectivity(&self) -> anyhow::Result<()> {
-        self.inner.0.check_connectivity().await
+    async fn create_api_key(
+        &self,
+        request: CreateApiKeyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.create_api_key_svc.clone().ready().await?.call(request).await
     }
-    fn endpoints(&self) -> Vec<quickwit_common::uri::Uri> {
-        self.inner.0.endpoints()
+    async fn list_api_keys(
+        &self,
+        request: ListApiKeysRequest,
+    ) -> crate::metastore::MetastoreResult<ListApiKeysResponse> {
+        self.list_api_keys_svc.clone().ready().await?.call(request).await
     }
-}
-type CreateIndexLayer = quickwit_common::tower::BoxLayer<
-    quickwit_common::tower::BoxService<
-        CreateIndexRequest,
-        CreateIndexResponse,
-        crate::metastore::MetastoreError,
-    >,
-    CreateIndexRequest,
-    CreateIndexResponse,
-    crate::metastore::MetastoreError,
->;
-type UpdateIndexLayer = quickwit_common::tower::BoxLayer<
-    quickwit_common::tower::BoxService<
-        UpdateIndexRequest,
+    async fn delete_api_key(
+        &self,
+        request: DeleteApiKeyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.delete_api_key_svc.clone().ready().await?.call(request).await
+    }
+    async fn create_role(
+        &self,
+        request: CreateRoleRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.create_role_svc.clone().ready().await?.call(request).await
+    }
+    async fn list_roles(
+        &self,
+        request: ListRolesRequest,
+    ) -> crate::metastore::MetastoreResult<ListRolesResponse> {
+        self.list_roles_svc.clone().ready().await?.call(request).await
+    }
+    async fn delete_role(
+        &self,
+        request: DeleteRoleRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.delete_role_svc.clone().ready().await?.call(request).await
+    }
+    async fn create_point_in_time(
+        &self,
+        request: CreatePointInTimeRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.create_point_in_time_svc.clone().ready().await?.call(request).await
+    }
+    async fn list_point_in_times(
+        &self,
+        request: ListPointInTimesRequest,
+    ) -> crate::metastore::MetastoreResult<ListPointInTimesResponse> {
+        self.list_point_in_times_svc.clone().ready().await?.call(request).await
+    }
+    async fn delete_point_in_times(
+        &self,
+        request: DeletePointInTimesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.delete_point_in_times_svc.clone().ready().await?.call(request).await
+    }
+    async fn update_index_aliases(
+        &self,
+        request: UpdateIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.update_index_aliases_svc.clone().ready().await?.call(request).await
+    }
+    async fn list_index_aliases(
+        &self,
+        request: ListIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<ListIndexAliasesResponse> {
+        self.list_index_aliases_svc.clone().ready().await?.call(request).await
+    }
+    async fn delete_index_aliases(
+        &self,
+        request: DeleteIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.delete_index_aliases_svc.clone().ready().await?.call(request).await
+    }
+    async fn create_lifecycle_policy(
+        &self,
+        request: CreateLifecyclePolicyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.create_lifecycle_policy_svc.clone().ready().await?.call(request).await
+    }
+    async fn list_lifecycle_policies(
+        &self,
+        request: ListLifecyclePoliciesRequest,
+    ) -> crate::metastore::MetastoreResult<ListLifecyclePoliciesResponse> {
+        self.list_lifecycle_policies_svc.clone().ready().await?.call(request).await
+    }
+    async fn delete_lifecycle_policy(
+        &self,
+        request: DeleteLifecyclePolicyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.delete_lifecycle_policy_svc.clone().ready().await?.call(request).await
+    }
+    async fn check_connectivity(&self) -> anyhow::Result<()> {
+        self.inner.0.check_connectivity().await
+    }
+    fn endpoints(&self) -> Vec<quickwit_common::uri::Uri> {
+        self.inner.0.endpoints()
+    }
+}
+type CreateIndexLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        CreateIndexRequest,
+        CreateIndexResponse,
+        crate::metastore::MetastoreError,
+    >,
+    CreateIndexRequest,
+    CreateIndexResponse,
+    crate::metastore::MetastoreError,
+>;
+type UpdateIndexLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        UpdateIndexRequest,
         IndexMetadataResponse,
         crate::metastore::MetastoreError,
     >,
@@ -2573,6 +3444,156 @@ type DeleteIndexTemplatesLayer = quickwit_common::tower::BoxLayer<
     EmptyResponse,
     crate::metastore::MetastoreError,
 >;
+type CreateApiKeyLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        CreateApiKeyRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    CreateApiKeyRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type ListApiKeysLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        ListApiKeysRequest,
+        ListApiKeysResponse,
+        crate::metastore::MetastoreError,
+    >,
+    ListApiKeysRequest,
+    ListApiKeysResponse,
+    crate::metastore::MetastoreError,
+>;
+type DeleteApiKeyLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        DeleteApiKeyRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    DeleteApiKeyRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type CreateRoleLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        CreateRoleRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    CreateRoleRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type ListRolesLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        ListRolesRequest,
+        ListRolesResponse,
+        crate::metastore::MetastoreError,
+    >,
+    ListRolesRequest,
+    ListRolesResponse,
+    crate::metastore::MetastoreError,
+>;
+type DeleteRoleLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        DeleteRoleRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    DeleteRoleRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type CreatePointInTimeLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        CreatePointInTimeRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    CreatePointInTimeRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type ListPointInTimesLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        ListPointInTimesRequest,
+        ListPointInTimesResponse,
+        crate::metastore::MetastoreError,
+    >,
+    ListPointInTimesRequest,
+    ListPointInTimesResponse,
+    crate::metastore::MetastoreError,
+>;
+type DeletePointInTimesLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        DeletePointInTimesRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    DeletePointInTimesRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type UpdateIndexAliasesLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        UpdateIndexAliasesRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    UpdateIndexAliasesRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type ListIndexAliasesLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        ListIndexAliasesRequest,
+        ListIndexAliasesResponse,
+        crate::metastore::MetastoreError,
+    >,
+    ListIndexAliasesRequest,
+    ListIndexAliasesResponse,
+    crate::metastore::MetastoreError,
+>;
+type DeleteIndexAliasesLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        DeleteIndexAliasesRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    DeleteIndexAliasesRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type CreateLifecyclePolicyLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        CreateLifecyclePolicyRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    CreateLifecyclePolicyRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
+type ListLifecyclePoliciesLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        ListLifecyclePoliciesRequest,
+        ListLifecyclePoliciesResponse,
+        crate::metastore::MetastoreError,
+    >,
+    ListLifecyclePoliciesRequest,
+    ListLifecyclePoliciesResponse,
+    crate::metastore::MetastoreError,
+>;
+type DeleteLifecyclePolicyLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        DeleteLifecyclePolicyRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    DeleteLifecyclePolicyRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
 #[derive(Debug, Default)]
 pub struct MetastoreServiceTowerLayerStack {
     create_index_layers: Vec<CreateIndexLayer>,
@@ -2605,6 +3626,21 @@ pub struct MetastoreServiceTowerLayerStack {
     find_index_template_matches_layers: Vec<FindIndexTemplateMatchesLayer>,
     list_index_templates_layers: Vec<ListIndexTemplatesLayer>,
     delete_index_templates_layers: Vec<DeleteIndexTemplatesLayer>,
+    create_api_key_layers: Vec<CreateApiKeyLayer>,
+    list_api_keys_layers: Vec<ListApiKeysLayer>,
+    delete_api_key_layers: Vec<DeleteApiKeyLayer>,
+    create_role_layers: Vec<CreateRoleLayer>,
+    list_roles_layers: Vec<ListRolesLayer>,
+    delete_role_layers: Vec<DeleteRoleLayer>,
+    create_point_in_time_layers: Vec<CreatePointInTimeLayer>,
+    list_point_in_times_layers: Vec<ListPointInTimesLayer>,
+    delete_point_in_times_layers: Vec<DeletePointInTimesLayer>,
+    update_index_aliases_layers: Vec<UpdateIndexAliasesLayer>,
+    list_index_aliases_layers: Vec<ListIndexAliasesLayer>,
+    delete_index_aliases_layers: Vec<DeleteIndexAliasesLayer>,
+    create_lifecycle_policy_layers: Vec<CreateLifecyclePolicyLayer>,
+    list_lifecycle_policies_layers: Vec<ListLifecyclePoliciesLayer>,
+    delete_lifecycle_policy_layers: Vec<DeleteLifecyclePolicyLayer>,
 }
 impl MetastoreServiceTowerLayerStack {
     pub fn stack_layer<L>(mut self, layer: L) -> Self
@@ -3375,725 +4411,1490 @@ impl MetastoreServiceTowerLayerStack {
         >>::Service as tower::Service<
             DeleteIndexTemplatesRequest,
         >>::Future: Send + 'static,
-    {
-        self.create_index_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.update_index_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.index_metadata_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.indexes_metadata_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.list_indexes_metadata_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.delete_index_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.list_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.stage_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.publish_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.mark_splits_for_deletion_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.delete_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.add_source_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.toggle_source_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.delete_source_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.reset_source_checkpoint_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.last_delete_opstamp_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.create_delete_task_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.update_splits_delete_opstamp_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.list_delete_tasks_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.list_stale_splits_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.open_shards_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.acquire_shards_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.delete_shards_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.prune_shards_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.list_shards_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.create_index_template_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.get_index_template_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.find_index_template_matches_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.list_index_templates_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self.delete_index_templates_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
-        self
-    }
-    pub fn stack_create_index_layer<L>(mut self, layer: L) -> Self
-    where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    CreateIndexRequest,
-                    CreateIndexResponse,
+                    CreateApiKeyRequest,
+                    EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                CreateIndexRequest,
-                Response = CreateIndexResponse,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                CreateApiKeyRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                CreateApiKeyRequest,
+                Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<CreateIndexRequest>>::Future: Send + 'static,
-    {
-        self.create_index_layers.push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_update_index_layer<L>(mut self, layer: L) -> Self
-    where
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                CreateApiKeyRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            CreateApiKeyRequest,
+        >>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    UpdateIndexRequest,
-                    IndexMetadataResponse,
+                    ListApiKeysRequest,
+                    ListApiKeysResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                UpdateIndexRequest,
-                Response = IndexMetadataResponse,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                ListApiKeysRequest,
+                ListApiKeysResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                ListApiKeysRequest,
+                Response = ListApiKeysResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<UpdateIndexRequest>>::Future: Send + 'static,
-    {
-        self.update_index_layers.push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_index_metadata_layer<L>(mut self, layer: L) -> Self
-    where
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                ListApiKeysRequest,
+                ListApiKeysResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            ListApiKeysRequest,
+        >>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    IndexMetadataRequest,
-                    IndexMetadataResponse,
+                    DeleteApiKeyRequest,
+                    EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                IndexMetadataRequest,
-                Response = IndexMetadataResponse,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                DeleteApiKeyRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                DeleteApiKeyRequest,
+                Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<IndexMetadataRequest>>::Future: Send + 'static,
-    {
-        self.index_metadata_layers.push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_indexes_metadata_layer<L>(mut self, layer: L) -> Self
-    where
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                DeleteApiKeyRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            DeleteApiKeyRequest,
+        >>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    IndexesMetadataRequest,
-                    IndexesMetadataResponse,
+                    CreateRoleRequest,
+                    EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                IndexesMetadataRequest,
-                Response = IndexesMetadataResponse,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                CreateRoleRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                CreateRoleRequest,
+                Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<IndexesMetadataRequest>>::Future: Send + 'static,
-    {
-        self.indexes_metadata_layers.push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_list_indexes_metadata_layer<L>(mut self, layer: L) -> Self
-    where
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                CreateRoleRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            CreateRoleRequest,
+        >>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    ListIndexesMetadataRequest,
-                    ListIndexesMetadataResponse,
+                    ListRolesRequest,
+                    ListRolesResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                ListIndexesMetadataRequest,
-                Response = ListIndexesMetadataResponse,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                ListRolesRequest,
+                ListRolesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                ListRolesRequest,
+                Response = ListRolesResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<
-            ListIndexesMetadataRequest,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                ListRolesRequest,
+                ListRolesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            ListRolesRequest,
         >>::Future: Send + 'static,
-    {
-        self.list_indexes_metadata_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_delete_index_layer<L>(mut self, layer: L) -> Self
-    where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    DeleteIndexRequest,
+                    DeleteRoleRequest,
                     EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                DeleteIndexRequest,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                DeleteRoleRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                DeleteRoleRequest,
                 Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<DeleteIndexRequest>>::Future: Send + 'static,
-    {
-        self.delete_index_layers.push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_list_splits_layer<L>(mut self, layer: L) -> Self
-    where
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                DeleteRoleRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            DeleteRoleRequest,
+        >>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    ListSplitsRequest,
-                    MetastoreServiceStream<ListSplitsResponse>,
+                    CreatePointInTimeRequest,
+                    EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                ListSplitsRequest,
-                Response = MetastoreServiceStream<ListSplitsResponse>,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                CreatePointInTimeRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                CreatePointInTimeRequest,
+                Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<ListSplitsRequest>>::Future: Send + 'static,
-    {
-        self.list_splits_layers.push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_stage_splits_layer<L>(mut self, layer: L) -> Self
-    where
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                CreatePointInTimeRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            CreatePointInTimeRequest,
+        >>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    StageSplitsRequest,
-                    EmptyResponse,
+                    ListPointInTimesRequest,
+                    ListPointInTimesResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                StageSplitsRequest,
-                Response = EmptyResponse,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                ListPointInTimesRequest,
+                ListPointInTimesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                ListPointInTimesRequest,
+                Response = ListPointInTimesResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<StageSplitsRequest>>::Future: Send + 'static,
-    {
-        self.stage_splits_layers.push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_publish_splits_layer<L>(mut self, layer: L) -> Self
-    where
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                ListPointInTimesRequest,
+                ListPointInTimesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            ListPointInTimesRequest,
+        >>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    PublishSplitsRequest,
+                    DeletePointInTimesRequest,
                     EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                PublishSplitsRequest,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                DeletePointInTimesRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                DeletePointInTimesRequest,
                 Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<PublishSplitsRequest>>::Future: Send + 'static,
-    {
-        self.publish_splits_layers.push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_mark_splits_for_deletion_layer<L>(mut self, layer: L) -> Self
-    where
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                DeletePointInTimesRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            DeletePointInTimesRequest,
+        >>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    MarkSplitsForDeletionRequest,
+                    UpdateIndexAliasesRequest,
                     EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                MarkSplitsForDeletionRequest,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                UpdateIndexAliasesRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                UpdateIndexAliasesRequest,
                 Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<
-            MarkSplitsForDeletionRequest,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                UpdateIndexAliasesRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            UpdateIndexAliasesRequest,
         >>::Future: Send + 'static,
-    {
-        self.mark_splits_for_deletion_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_delete_splits_layer<L>(mut self, layer: L) -> Self
-    where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    DeleteSplitsRequest,
-                    EmptyResponse,
+                    ListIndexAliasesRequest,
+                    ListIndexAliasesResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                DeleteSplitsRequest,
-                Response = EmptyResponse,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                ListIndexAliasesRequest,
+                ListIndexAliasesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                ListIndexAliasesRequest,
+                Response = ListIndexAliasesResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<DeleteSplitsRequest>>::Future: Send + 'static,
-    {
-        self.delete_splits_layers.push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_add_source_layer<L>(mut self, layer: L) -> Self
-    where
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                ListIndexAliasesRequest,
+                ListIndexAliasesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            ListIndexAliasesRequest,
+        >>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    AddSourceRequest,
+                    DeleteIndexAliasesRequest,
                     EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                AddSourceRequest,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                DeleteIndexAliasesRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                DeleteIndexAliasesRequest,
                 Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<AddSourceRequest>>::Future: Send + 'static,
-    {
-        self.add_source_layers.push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_toggle_source_layer<L>(mut self, layer: L) -> Self
-    where
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                DeleteIndexAliasesRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            DeleteIndexAliasesRequest,
+        >>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    ToggleSourceRequest,
+                    CreateLifecyclePolicyRequest,
                     EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                ToggleSourceRequest,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                CreateLifecyclePolicyRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                CreateLifecyclePolicyRequest,
                 Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<ToggleSourceRequest>>::Future: Send + 'static,
-    {
-        self.toggle_source_layers.push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_delete_source_layer<L>(mut self, layer: L) -> Self
-    where
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                CreateLifecyclePolicyRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            CreateLifecyclePolicyRequest,
+        >>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    DeleteSourceRequest,
-                    EmptyResponse,
+                    ListLifecyclePoliciesRequest,
+                    ListLifecyclePoliciesResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                DeleteSourceRequest,
-                Response = EmptyResponse,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                ListLifecyclePoliciesRequest,
+                ListLifecyclePoliciesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                ListLifecyclePoliciesRequest,
+                Response = ListLifecyclePoliciesResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<DeleteSourceRequest>>::Future: Send + 'static,
-    {
-        self.delete_source_layers.push(quickwit_common::tower::BoxLayer::new(layer));
-        self
-    }
-    pub fn stack_reset_source_checkpoint_layer<L>(mut self, layer: L) -> Self
-    where
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                ListLifecyclePoliciesRequest,
+                ListLifecyclePoliciesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            ListLifecyclePoliciesRequest,
+        >>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    ResetSourceCheckpointRequest,
+                    DeleteLifecyclePolicyRequest,
                     EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
-            > + Send + Sync + 'static,
-        L::Service: tower::Service<
-                ResetSourceCheckpointRequest,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                DeleteLifecyclePolicyRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                DeleteLifecyclePolicyRequest,
                 Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<
-            ResetSourceCheckpointRequest,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                DeleteLifecyclePolicyRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<
+            DeleteLifecyclePolicyRequest,
         >>::Future: Send + 'static,
     {
+        self.create_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.update_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.index_metadata_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.indexes_metadata_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_indexes_metadata_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_index_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.stage_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.publish_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.mark_splits_for_deletion_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.add_source_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.toggle_source_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_source_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self.reset_source_checkpoint_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer));
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.last_delete_opstamp_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.create_delete_task_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.update_splits_delete_opstamp_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_delete_tasks_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_stale_splits_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.open_shards_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.acquire_shards_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_shards_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.prune_shards_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_shards_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.create_index_template_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.get_index_template_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.find_index_template_matches_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_index_templates_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_index_templates_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.create_api_key_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_api_keys_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_api_key_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.create_role_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_roles_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_role_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.create_point_in_time_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_point_in_times_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_point_in_times_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.update_index_aliases_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_index_aliases_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_index_aliases_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.create_lifecycle_policy_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.list_lifecycle_policies_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.delete_lifecycle_policy_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self
     }
-    pub fn stack_last_delete_opstamp_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_create_index_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    LastDeleteOpstampRequest,
-                    LastDeleteOpstampResponse,
+                    CreateIndexRequest,
+                    CreateIndexResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                LastDeleteOpstampRequest,
-                Response = LastDeleteOpstampResponse,
+                CreateIndexRequest,
+                Response = CreateIndexResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<LastDeleteOpstampRequest>>::Future: Send + 'static,
+        <L::Service as tower::Service<CreateIndexRequest>>::Future: Send + 'static,
     {
-        self.last_delete_opstamp_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self.create_index_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_create_delete_task_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_update_index_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    DeleteQuery,
-                    DeleteTask,
+                    UpdateIndexRequest,
+                    IndexMetadataResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                DeleteQuery,
-                Response = DeleteTask,
+                UpdateIndexRequest,
+                Response = IndexMetadataResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<DeleteQuery>>::Future: Send + 'static,
+        <L::Service as tower::Service<UpdateIndexRequest>>::Future: Send + 'static,
     {
-        self.create_delete_task_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self.update_index_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_update_splits_delete_opstamp_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_index_metadata_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    UpdateSplitsDeleteOpstampRequest,
-                    UpdateSplitsDeleteOpstampResponse,
+                    IndexMetadataRequest,
+                    IndexMetadataResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                UpdateSplitsDeleteOpstampRequest,
-                Response = UpdateSplitsDeleteOpstampResponse,
+                IndexMetadataRequest,
+                Response = IndexMetadataResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<
-            UpdateSplitsDeleteOpstampRequest,
-        >>::Future: Send + 'static,
+        <L::Service as tower::Service<IndexMetadataRequest>>::Future: Send + 'static,
     {
-        self.update_splits_delete_opstamp_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self.index_metadata_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_list_delete_tasks_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_indexes_metadata_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    ListDeleteTasksRequest,
-                    ListDeleteTasksResponse,
+                    IndexesMetadataRequest,
+                    IndexesMetadataResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                ListDeleteTasksRequest,
-                Response = ListDeleteTasksResponse,
+                IndexesMetadataRequest,
+                Response = IndexesMetadataResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<ListDeleteTasksRequest>>::Future: Send + 'static,
+        <L::Service as tower::Service<IndexesMetadataRequest>>::Future: Send + 'static,
     {
-        self.list_delete_tasks_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self.indexes_metadata_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_list_stale_splits_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_list_indexes_metadata_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    ListStaleSplitsRequest,
-                    ListSplitsResponse,
+                    ListIndexesMetadataRequest,
+                    ListIndexesMetadataResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                ListStaleSplitsRequest,
-                Response = ListSplitsResponse,
+                ListIndexesMetadataRequest,
+                Response = ListIndexesMetadataResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<ListStaleSplitsRequest>>::Future: Send + 'static,
+        <L::Service as tower::Service<
+            ListIndexesMetadataRequest,
+        >>::Future: Send + 'static,
     {
-        self.list_stale_splits_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self.list_indexes_metadata_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_open_shards_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_delete_index_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    OpenShardsRequest,
-                    OpenShardsResponse,
+                    DeleteIndexRequest,
+                    EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                OpenShardsRequest,
-                Response = OpenShardsResponse,
+                DeleteIndexRequest,
+                Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<OpenShardsRequest>>::Future: Send + 'static,
+        <L::Service as tower::Service<DeleteIndexRequest>>::Future: Send + 'static,
     {
-        self.open_shards_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self.delete_index_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_acquire_shards_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_list_splits_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    AcquireShardsRequest,
-                    AcquireShardsResponse,
+                    ListSplitsRequest,
+                    MetastoreServiceStream<ListSplitsResponse>,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                AcquireShardsRequest,
-                Response = AcquireShardsResponse,
+                ListSplitsRequest,
+                Response = MetastoreServiceStream<ListSplitsResponse>,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<AcquireShardsRequest>>::Future: Send + 'static,
+        <L::Service as tower::Service<ListSplitsRequest>>::Future: Send + 'static,
     {
-        self.acquire_shards_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self.list_splits_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_delete_shards_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_stage_splits_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    DeleteShardsRequest,
-                    DeleteShardsResponse,
+                    StageSplitsRequest,
+                    EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                DeleteShardsRequest,
-                Response = DeleteShardsResponse,
+                StageSplitsRequest,
+                Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<DeleteShardsRequest>>::Future: Send + 'static,
+        <L::Service as tower::Service<StageSplitsRequest>>::Future: Send + 'static,
     {
-        self.delete_shards_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self.stage_splits_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_prune_shards_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_publish_splits_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    PruneShardsRequest,
-                    PruneShardsResponse,
+                    PublishSplitsRequest,
+                    EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                PruneShardsRequest,
-                Response = PruneShardsResponse,
+                PublishSplitsRequest,
+                Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<PruneShardsRequest>>::Future: Send + 'static,
+        <L::Service as tower::Service<PublishSplitsRequest>>::Future: Send + 'static,
     {
-        self.prune_shards_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self.publish_splits_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_list_shards_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_mark_splits_for_deletion_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    ListShardsRequest,
-                    ListShardsResponse,
+                    MarkSplitsForDeletionRequest,
+                    EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                ListShardsRequest,
-                Response = ListShardsResponse,
+                MarkSplitsForDeletionRequest,
+                Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<ListShardsRequest>>::Future: Send + 'static,
+        <L::Service as tower::Service<
+            MarkSplitsForDeletionRequest,
+        >>::Future: Send + 'static,
     {
-        self.list_shards_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self.mark_splits_for_deletion_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_create_index_template_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_delete_splits_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    CreateIndexTemplateRequest,
+                    DeleteSplitsRequest,
                     EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                CreateIndexTemplateRequest,
+                DeleteSplitsRequest,
                 Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<
-            CreateIndexTemplateRequest,
-        >>::Future: Send + 'static,
+        <L::Service as tower::Service<DeleteSplitsRequest>>::Future: Send + 'static,
     {
-        self.create_index_template_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self.delete_splits_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_get_index_template_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_add_source_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    GetIndexTemplateRequest,
-                    GetIndexTemplateResponse,
+                    AddSourceRequest,
+                    EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                GetIndexTemplateRequest,
-                Response = GetIndexTemplateResponse,
+                AddSourceRequest,
+                Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<GetIndexTemplateRequest>>::Future: Send + 'static,
+        <L::Service as tower::Service<AddSourceRequest>>::Future: Send + 'static,
     {
-        self.get_index_template_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self.add_source_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_find_index_template_matches_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_toggle_source_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    FindIndexTemplateMatchesRequest,
-                    FindIndexTemplateMatchesResponse,
+                    ToggleSourceRequest,
+                    EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                FindIndexTemplateMatchesRequest,
-                Response = FindIndexTemplateMatchesResponse,
+                ToggleSourceRequest,
+                Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<
-            FindIndexTemplateMatchesRequest,
-        >>::Future: Send + 'static,
+        <L::Service as tower::Service<ToggleSourceRequest>>::Future: Send + 'static,
     {
-        self.find_index_template_matches_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self.toggle_source_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_list_index_templates_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_delete_source_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    ListIndexTemplatesRequest,
-                    ListIndexTemplatesResponse,
+                    DeleteSourceRequest,
+                    EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                ListIndexTemplatesRequest,
-                Response = ListIndexTemplatesResponse,
+                DeleteSourceRequest,
+                Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
-        <L::Service as tower::Service<
-            ListIndexTemplatesRequest,
-        >>::Future: Send + 'static,
+        <L::Service as tower::Service<DeleteSourceRequest>>::Future: Send + 'static,
     {
-        self.list_index_templates_layers
-            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self.delete_source_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn stack_delete_index_templates_layer<L>(mut self, layer: L) -> Self
+    pub fn stack_reset_source_checkpoint_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
-                    DeleteIndexTemplatesRequest,
+                    ResetSourceCheckpointRequest,
                     EmptyResponse,
                     crate::metastore::MetastoreError,
                 >,
             > + Send + Sync + 'static,
         L::Service: tower::Service<
-                DeleteIndexTemplatesRequest,
+                ResetSourceCheckpointRequest,
                 Response = EmptyResponse,
                 Error = crate::metastore::MetastoreError,
             > + Clone + Send + Sync + 'static,
         <L::Service as tower::Service<
-            DeleteIndexTemplatesRequest,
+            ResetSourceCheckpointRequest,
         >>::Future: Send + 'static,
     {
-        self.delete_index_templates_layers
+        self.reset_source_checkpoint_layers
             .push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
-    pub fn build<T>(self, instance: T) -> MetastoreServiceClient
+    pub fn stack_last_delete_opstamp_layer<L>(mut self, layer: L) -> Self
     where
-        T: MetastoreService,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    LastDeleteOpstampRequest,
+                    LastDeleteOpstampResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                LastDeleteOpstampRequest,
+                Response = LastDeleteOpstampResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<LastDeleteOpstampRequest>>::Future: Send + 'static,
     {
-        let inner_client = InnerMetastoreServiceClient(std::sync::Arc::new(instance));
-        self.build_from_inner_client(inner_client)
-    }
-    pub fn build_from_channel(
-        self,
-        addr: std::net::SocketAddr,
-        channel: tonic::transport::Channel,
-        max_message_size: bytesize::ByteSize,
-    ) -> MetastoreServiceClient {
-        let client = MetastoreServiceClient::from_channel(
-            addr,
-            channel,
-            max_message_size,
-        );
-        let inner_client = client.inner;
-        self.build_from_inner_client(inner_client)
+        self.last_delete_opstamp_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
     }
-    pub fn build_from_balance_channel(
-        self,
-        balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
-        max_message_size: bytesize::ByteSize,
-    ) -> MetastoreServiceClient {
-        let client = MetastoreServiceClient::from_balance_channel(
-            balance_channel,
-            max_message_size,
-        );
-        let inner_client = client.inner;
-        self.build_from_inner_client(inner_client)
+    pub fn stack_create_delete_task_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    DeleteQuery,
+                    DeleteTask,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                DeleteQuery,
+                Response = DeleteTask,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<DeleteQuery>>::Future: Send + 'static,
+    {
+        self.create_delete_task_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
     }
-    pub fn build_from_mailbox<A>(
-        self,
-        mailbox: quickwit_actors::Mailbox<A>,
-    ) -> MetastoreServiceClient
+    pub fn stack_update_splits_delete_opstamp_layer<L>(mut self, layer: L) -> Self
     where
-        A: quickwit_actors::Actor + std::fmt::Debug + Send + 'static,
-        MetastoreServiceMailbox<A>: MetastoreService,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    UpdateSplitsDeleteOpstampRequest,
+                    UpdateSplitsDeleteOpstampResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                UpdateSplitsDeleteOpstampRequest,
+                Response = UpdateSplitsDeleteOpstampResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            UpdateSplitsDeleteOpstampRequest,
+        >>::Future: Send + 'static,
     {
-        let inner_client = InnerMetastoreServiceClient(
-            std::sync::Arc::new(MetastoreServiceMailbox::new(mailbox)),
-        );
-        self.build_from_inner_client(inner_client)
+        self.update_splits_delete_opstamp_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
     }
-    #[cfg(any(test, feature = "testsuite"))]
-    pub fn build_from_mock(self, mock: MockMetastoreService) -> MetastoreServiceClient {
-        let client = MetastoreServiceClient::from_mock(mock);
-        let inner_client = client.inner;
-        self.build_from_inner_client(inner_client)
+    pub fn stack_list_delete_tasks_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    ListDeleteTasksRequest,
+                    ListDeleteTasksResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                ListDeleteTasksRequest,
+                Response = ListDeleteTasksResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<ListDeleteTasksRequest>>::Future: Send + 'static,
+    {
+        self.list_delete_tasks_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self
     }
-    fn build_from_inner_client(
-        self,
-        inner_client: InnerMetastoreServiceClient,
-    ) -> MetastoreServiceClient {
-        let create_index_svc = self
-            .create_index_layers
-            .into_iter()
+    pub fn stack_list_stale_splits_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    ListStaleSplitsRequest,
+                    ListSplitsResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                ListStaleSplitsRequest,
+                Response = ListSplitsResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<ListStaleSplitsRequest>>::Future: Send + 'static,
+    {
+        self.list_stale_splits_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_open_shards_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    OpenShardsRequest,
+                    OpenShardsResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                OpenShardsRequest,
+                Response = OpenShardsResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<OpenShardsRequest>>::Future: Send + 'static,
+    {
+        self.open_shards_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_acquire_shards_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    AcquireShardsRequest,
+                    AcquireShardsResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                AcquireShardsRequest,
+                Response = AcquireShardsResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<AcquireShardsRequest>>::Future: Send + 'static,
+    {
+        self.acquire_shards_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_delete_shards_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    DeleteShardsRequest,
+                    DeleteShardsResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                DeleteShardsRequest,
+                Response = DeleteShardsResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<DeleteShardsRequest>>::Future: Send + 'static,
+    {
+        self.delete_shards_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_prune_shards_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    PruneShardsRequest,
+                    PruneShardsResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                PruneShardsRequest,
+                Response = PruneShardsResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<PruneShardsRequest>>::Future: Send + 'static,
+    {
+        self.prune_shards_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_list_shards_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    ListShardsRequest,
+                    ListShardsResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                ListShardsRequest,
+                Response = ListShardsResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<ListShardsRequest>>::Future: Send + 'static,
+    {
+        self.list_shards_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_create_index_template_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    CreateIndexTemplateRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                CreateIndexTemplateRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            CreateIndexTemplateRequest,
+        >>::Future: Send + 'static,
+    {
+        self.create_index_template_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_get_index_template_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    GetIndexTemplateRequest,
+                    GetIndexTemplateResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                GetIndexTemplateRequest,
+                Response = GetIndexTemplateResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<GetIndexTemplateRequest>>::Future: Send + 'static,
+    {
+        self.get_index_template_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_find_index_template_matches_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    FindIndexTemplateMatchesRequest,
+                    FindIndexTemplateMatchesResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                FindIndexTemplateMatchesRequest,
+                Response = FindIndexTemplateMatchesResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            FindIndexTemplateMatchesRequest,
+        >>::Future: Send + 'static,
+    {
+        self.find_index_template_matches_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_list_index_templates_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    ListIndexTemplatesRequest,
+                    ListIndexTemplatesResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                ListIndexTemplatesRequest,
+                Response = ListIndexTemplatesResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            ListIndexTemplatesRequest,
+        >>::Future: Send + 'static,
+    {
+        self.list_index_templates_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_delete_index_templates_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    DeleteIndexTemplatesRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                DeleteIndexTemplatesRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            DeleteIndexTemplatesRequest,
+        >>::Future: Send + 'static,
+    {
+        self.delete_index_templates_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_create_api_key_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    CreateApiKeyRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                CreateApiKeyRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            CreateApiKeyRequest,
+        >>::Future: Send + 'static,
+    {
+        self.create_api_key_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_list_api_keys_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    ListApiKeysRequest,
+                    ListApiKeysResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                ListApiKeysRequest,
+                Response = ListApiKeysResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            ListApiKeysRequest,
+        >>::Future: Send + 'static,
+    {
+        self.list_api_keys_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_delete_api_key_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    DeleteApiKeyRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                DeleteApiKeyRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            DeleteApiKeyRequest,
+        >>::Future: Send + 'static,
+    {
+        self.delete_api_key_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_create_role_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    CreateRoleRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                CreateRoleRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            CreateRoleRequest,
+        >>::Future: Send + 'static,
+    {
+        self.create_role_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_list_roles_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    ListRolesRequest,
+                    ListRolesResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                ListRolesRequest,
+                Response = ListRolesResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            ListRolesRequest,
+        >>::Future: Send + 'static,
+    {
+        self.list_roles_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_delete_role_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    DeleteRoleRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                DeleteRoleRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            DeleteRoleRequest,
+        >>::Future: Send + 'static,
+    {
+        self.delete_role_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_create_point_in_time_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    CreatePointInTimeRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                CreatePointInTimeRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            CreatePointInTimeRequest,
+        >>::Future: Send + 'static,
+    {
+        self.create_point_in_time_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_list_point_in_times_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    ListPointInTimesRequest,
+                    ListPointInTimesResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                ListPointInTimesRequest,
+                Response = ListPointInTimesResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            ListPointInTimesRequest,
+        >>::Future: Send + 'static,
+    {
+        self.list_point_in_times_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_delete_point_in_times_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    DeletePointInTimesRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                DeletePointInTimesRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            DeletePointInTimesRequest,
+        >>::Future: Send + 'static,
+    {
+        self.delete_point_in_times_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_update_index_aliases_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    UpdateIndexAliasesRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                UpdateIndexAliasesRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            UpdateIndexAliasesRequest,
+        >>::Future: Send + 'static,
+    {
+        self.update_index_aliases_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_list_index_aliases_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    ListIndexAliasesRequest,
+                    ListIndexAliasesResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                ListIndexAliasesRequest,
+                Response = ListIndexAliasesResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            ListIndexAliasesRequest,
+        >>::Future: Send + 'static,
+    {
+        self.list_index_aliases_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_delete_index_aliases_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    DeleteIndexAliasesRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                DeleteIndexAliasesRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            DeleteIndexAliasesRequest,
+        >>::Future: Send + 'static,
+    {
+        self.delete_index_aliases_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_create_lifecycle_policy_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    CreateLifecyclePolicyRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                CreateLifecyclePolicyRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            CreateLifecyclePolicyRequest,
+        >>::Future: Send + 'static,
+    {
+        self.create_lifecycle_policy_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_list_lifecycle_policies_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    ListLifecyclePoliciesRequest,
+                    ListLifecyclePoliciesResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                ListLifecyclePoliciesRequest,
+                Response = ListLifecyclePoliciesResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            ListLifecyclePoliciesRequest,
+        >>::Future: Send + 'static,
+    {
+        self.list_lifecycle_policies_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn stack_delete_lifecycle_policy_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    DeleteLifecyclePolicyRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                DeleteLifecyclePolicyRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<
+            DeleteLifecyclePolicyRequest,
+        >>::Future: Send + 'static,
+    {
+        self.delete_lifecycle_policy_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
+    pub fn build<T>(self, instance: T) -> MetastoreServiceClient
+    where
+        T: MetastoreService,
+    {
+        let inner_client = InnerMetastoreServiceClient(std::sync::Arc::new(instance));
+        self.build_from_inner_client(inner_client)
+    }
+    pub fn build_from_channel(
+        self,
+        addr: std::net::SocketAddr,
+        channel: tonic::transport::Channel,
+        max_message_size: bytesize::ByteSize,
+    ) -> MetastoreServiceClient {
+        let client = MetastoreServiceClient::from_channel(
+            addr,
+            channel,
+            max_message_size,
+        );
+        let inner_client = client.inner;
+        self.build_from_inner_client(inner_client)
+    }
+    pub fn build_from_balance_channel(
+        self,
+        balance_channel: quickwit_common::tower::BalanceChannel<std::net::SocketAddr>,
+        max_message_size: bytesize::ByteSize,
+    ) -> MetastoreServiceClient {
+        let client = MetastoreServiceClient::from_balance_channel(
+            balance_channel,
+            max_message_size,
+        );
+        let inner_client = client.inner;
+        self.build_from_inner_client(inner_client)
+    }
+    pub fn build_from_mailbox<A>(
+        self,
+        mailbox: quickwit_actors::Mailbox<A>,
+    ) -> MetastoreServiceClient
+    where
+        A: quickwit_actors::Actor + std::fmt::Debug + Send + 'static,
+        MetastoreServiceMailbox<A>: MetastoreService,
+    {
+        let inner_client = InnerMetastoreServiceClient(
+            std::sync::Arc::new(MetastoreServiceMailbox::new(mailbox)),
+        );
+        self.build_from_inner_client(inner_client)
+    }
+    #[cfg(any(test, feature = "testsuite"))]
+    pub fn build_from_mock(self, mock: MockMetastoreService) -> MetastoreServiceClient {
+        let client = MetastoreServiceClient::from_mock(mock);
+        let inner_client = client.inner;
+        self.build_from_inner_client(inner_client)
+    }
+    fn build_from_inner_client(
+        self,
+        inner_client: InnerMetastoreServiceClient,
+    ) -> MetastoreServiceClient {
+        let create_index_svc = self
+            .create_index_layers
+            .into_iter()
             .rev()
             .fold(
                 quickwit_common::tower::BoxService::new(inner_client.clone()),
@@ -4331,6 +6132,126 @@ impl MetastoreServiceTowerLayerStack {
                 quickwit_common::tower::BoxService::new(inner_client.clone()),
                 |svc, layer| layer.layer(svc),
             );
+        let create_api_key_svc = self
+            .create_api_key_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let list_api_keys_svc = self
+            .list_api_keys_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let delete_api_key_svc = self
+            .delete_api_key_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let create_role_svc = self
+            .create_role_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let list_roles_svc = self
+            .list_roles_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let delete_role_svc = self
+            .delete_role_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let create_point_in_time_svc = self
+            .create_point_in_time_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let list_point_in_times_svc = self
+            .list_point_in_times_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let delete_point_in_times_svc = self
+            .delete_point_in_times_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let update_index_aliases_svc = self
+            .update_index_aliases_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let list_index_aliases_svc = self
+            .list_index_aliases_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let delete_index_aliases_svc = self
+            .delete_index_aliases_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let create_lifecycle_policy_svc = self
+            .create_lifecycle_policy_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let list_lifecycle_policies_svc = self
+            .list_lifecycle_policies_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
+        let delete_lifecycle_policy_svc = self
+            .delete_lifecycle_policy_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(inner_client.clone()),
+                |svc, layer| layer.layer(svc),
+            );
         let tower_svc_stack = MetastoreServiceTowerServiceStack {
             inner: inner_client,
             create_index_svc,
@@ -4363,6 +6284,21 @@ impl MetastoreServiceTowerLayerStack {
             find_index_template_matches_svc,
             list_index_templates_svc,
             delete_index_templates_svc,
+            create_api_key_svc,
+            list_api_keys_svc,
+            delete_api_key_svc,
+            create_role_svc,
+            list_roles_svc,
+            delete_role_svc,
+            create_point_in_time_svc,
+            list_point_in_times_svc,
+            delete_point_in_times_svc,
+            update_index_aliases_svc,
+            list_index_aliases_svc,
+            delete_index_aliases_svc,
+            create_lifecycle_policy_svc,
+            list_lifecycle_policies_svc,
+            delete_lifecycle_policy_svc,
         };
         MetastoreServiceClient::new(tower_svc_stack)
     }
@@ -4639,6 +6575,111 @@ where
             Response = EmptyResponse,
             Error = crate::metastore::MetastoreError,
             Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            CreateApiKeyRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            ListApiKeysRequest,
+            Response = ListApiKeysResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<
+                ListApiKeysResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >
+        + tower::Service<
+            DeleteApiKeyRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            CreateRoleRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            ListRolesRequest,
+            Response = ListRolesResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<
+                ListRolesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >
+        + tower::Service<
+            DeleteRoleRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            CreatePointInTimeRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            ListPointInTimesRequest,
+            Response = ListPointInTimesResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<
+                ListPointInTimesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >
+        + tower::Service<
+            DeletePointInTimesRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            UpdateIndexAliasesRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            ListIndexAliasesRequest,
+            Response = ListIndexAliasesResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<
+                ListIndexAliasesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >
+        + tower::Service<
+            DeleteIndexAliasesRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            CreateLifecyclePolicyRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
+        + tower::Service<
+            ListLifecyclePoliciesRequest,
+            Response = ListLifecyclePoliciesResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<
+                ListLifecyclePoliciesResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >
+        + tower::Service<
+            DeleteLifecyclePolicyRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
         >,
 {
     async fn create_index(
@@ -4743,81 +6784,171 @@ where
     ) -> crate::metastore::MetastoreResult<DeleteTask> {
         self.clone().call(request).await
     }
-    async fn update_splits_delete_opstamp(
+    async fn update_splits_delete_opstamp(
+        &self,
+        request: UpdateSplitsDeleteOpstampRequest,
+    ) -> crate::metastore::MetastoreResult<UpdateSplitsDeleteOpstampResponse> {
+        self.clone().call(request).await
+    }
+    async fn list_delete_tasks(
+        &self,
+        request: ListDeleteTasksRequest,
+    ) -> crate::metastore::MetastoreResult<ListDeleteTasksResponse> {
+        self.clone().call(request).await
+    }
+    async fn list_stale_splits(
+        &self,
+        request: ListStaleSplitsRequest,
+    ) -> crate::metastore::MetastoreResult<ListSplitsResponse> {
+        self.clone().call(request).await
+    }
+    async fn open_shards(
+        &self,
+        request: OpenShardsRequest,
+    ) -> crate::metastore::MetastoreResult<OpenShardsResponse> {
+        self.clone().call(request).await
+    }
+    async fn acquire_shards(
+        &self,
+        request: AcquireShardsRequest,
+    ) -> crate::metastore::MetastoreResult<AcquireShardsResponse> {
+        self.clone().call(request).await
+    }
+    async fn delete_shards(
+        &self,
+        request: DeleteShardsRequest,
+    ) -> crate::metastore::MetastoreResult<DeleteShardsResponse> {
+        self.clone().call(request).await
+    }
+    async fn prune_shards(
+        &self,
+        request: PruneShardsRequest,
+    ) -> crate::metastore::MetastoreResult<PruneShardsResponse> {
+        self.clone().call(request).await
+    }
+    async fn list_shards(
+        &self,
+        request: ListShardsRequest,
+    ) -> crate::metastore::MetastoreResult<ListShardsResponse> {
+        self.clone().call(request).await
+    }
+    async fn create_index_template(
+        &self,
+        request: CreateIndexTemplateRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.clone().call(request).await
+    }
+    async fn get_index_template(
+        &self,
+        request: GetIndexTemplateRequest,
+    ) -> crate::metastore::MetastoreResult<GetIndexTemplateResponse> {
+        self.clone().call(request).await
+    }
+    async fn find_index_template_matches(
+        &self,
+        request: FindIndexTemplateMatchesRequest,
+    ) -> crate::metastore::MetastoreResult<FindIndexTemplateMatchesResponse> {
+        self.clone().call(request).await
+    }
+    async fn list_index_templates(
+        &self,
+        request: ListIndexTemplatesRequest,
+    ) -> crate::metastore::MetastoreResult<ListIndexTemplatesResponse> {
+        self.clone().call(request).await
+    }
+    async fn delete_index_templates(
+        &self,
+        request: DeleteIndexTemplatesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.clone().call(request).await
+    }
+    async fn create_api_key(
+        &self,
+        request: CreateApiKeyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.clone().call(request).await
+    }
+    async fn list_api_keys(
         &self,
-        request: UpdateSplitsDeleteOpstampRequest,
-    ) -> crate::metastore::MetastoreResult<UpdateSplitsDeleteOpstampResponse> {
+        request: ListApiKeysRequest,
+    ) -> crate::metastore::MetastoreResult<ListApiKeysResponse> {
         self.clone().call(request).await
     }
-    async fn list_delete_tasks(
+    async fn delete_api_key(
         &self,
-        request: ListDeleteTasksRequest,
-    ) -> crate::metastore::MetastoreResult<ListDeleteTasksResponse> {
+        request: DeleteApiKeyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.clone().call(request).await
     }
-    async fn list_stale_splits(
+    async fn create_role(
         &self,
-        request: ListStaleSplitsRequest,
-    ) -> crate::metastore::MetastoreResult<ListSplitsResponse> {
+        request: CreateRoleRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.clone().call(request).await
     }
-    async fn open_shards(
+    async fn list_roles(
         &self,
-        request: OpenShardsRequest,
-    ) -> crate::metastore::MetastoreResult<OpenShardsResponse> {
+        request: ListRolesRequest,
+    ) -> crate::metastore::MetastoreResult<ListRolesResponse> {
         self.clone().call(request).await
     }
-    async fn acquire_shards(
+    async fn delete_role(
         &self,
-        request: AcquireShardsRequest,
-    ) -> crate::metastore::MetastoreResult<AcquireShardsResponse> {
+        request: DeleteRoleRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.clone().call(request).await
     }
-    async fn delete_shards(
+    async fn create_point_in_time(
         &self,
-        request: DeleteShardsRequest,
-    ) -> crate::metastore::MetastoreResult<DeleteShardsResponse> {
+        request: CreatePointInTimeRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.clone().call(request).await
     }
-    async fn prune_shards(
+    async fn list_point_in_times(
         &self,
-        request: PruneShardsRequest,
-    ) -> crate::metastore::MetastoreResult<PruneShardsResponse> {
+        request: ListPointInTimesRequest,
+    ) -> crate::metastore::MetastoreResult<ListPointInTimesResponse> {
         self.clone().call(request).await
     }
-    async fn list_shards(
+    async fn delete_point_in_times(
         &self,
-        request: ListShardsRequest,
-    ) -> crate::metastore::MetastoreResult<ListShardsResponse> {
+        request: DeletePointInTimesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.clone().call(request).await
     }
-    async fn create_index_template(
+    async fn update_index_aliases(
         &self,
-        request: CreateIndexTemplateRequest,
+        request: UpdateIndexAliasesRequest,
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.clone().call(request).await
     }
-    async fn get_index_template(
+    async fn list_index_aliases(
         &self,
-        request: GetIndexTemplateRequest,
-    ) -> crate::metastore::MetastoreResult<GetIndexTemplateResponse> {
+        request: ListIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<ListIndexAliasesResponse> {
         self.clone().call(request).await
     }
-    async fn find_index_template_matches(
+    async fn delete_index_aliases(
         &self,
-        request: FindIndexTemplateMatchesRequest,
-    ) -> crate::metastore::MetastoreResult<FindIndexTemplateMatchesResponse> {
+        request: DeleteIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.clone().call(request).await
     }
-    async fn list_index_templates(
+    async fn create_lifecycle_policy(
         &self,
-        request: ListIndexTemplatesRequest,
-    ) -> crate::metastore::MetastoreResult<ListIndexTemplatesResponse> {
+        request: CreateLifecyclePolicyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.clone().call(request).await
     }
-    async fn delete_index_templates(
+    async fn list_lifecycle_policies(
         &self,
-        request: DeleteIndexTemplatesRequest,
+        request: ListLifecyclePoliciesRequest,
+    ) -> crate::metastore::MetastoreResult<ListLifecyclePoliciesResponse> {
+        self.clone().call(request).await
+    }
+    async fn delete_lifecycle_policy(
+        &self,
+        request: DeleteLifecyclePolicyRequest,
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.clone().call(request).await
     }
@@ -5018,282 +7149,492 @@ where
     }
     async fn delete_splits(
         &self,
-        request: DeleteSplitsRequest,
+        request: DeleteSplitsRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner
+            .clone()
+            .delete_splits(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                DeleteSplitsRequest::rpc_name(),
+            ))
+    }
+    async fn add_source(
+        &self,
+        request: AddSourceRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner
+            .clone()
+            .add_source(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                AddSourceRequest::rpc_name(),
+            ))
+    }
+    async fn toggle_source(
+        &self,
+        request: ToggleSourceRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner
+            .clone()
+            .toggle_source(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                ToggleSourceRequest::rpc_name(),
+            ))
+    }
+    async fn delete_source(
+        &self,
+        request: DeleteSourceRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner
+            .clone()
+            .delete_source(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                DeleteSourceRequest::rpc_name(),
+            ))
+    }
+    async fn reset_source_checkpoint(
+        &self,
+        request: ResetSourceCheckpointRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner
+            .clone()
+            .reset_source_checkpoint(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                ResetSourceCheckpointRequest::rpc_name(),
+            ))
+    }
+    async fn last_delete_opstamp(
+        &self,
+        request: LastDeleteOpstampRequest,
+    ) -> crate::metastore::MetastoreResult<LastDeleteOpstampResponse> {
+        self.inner
+            .clone()
+            .last_delete_opstamp(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                LastDeleteOpstampRequest::rpc_name(),
+            ))
+    }
+    async fn create_delete_task(
+        &self,
+        request: DeleteQuery,
+    ) -> crate::metastore::MetastoreResult<DeleteTask> {
+        self.inner
+            .clone()
+            .create_delete_task(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                DeleteQuery::rpc_name(),
+            ))
+    }
+    async fn update_splits_delete_opstamp(
+        &self,
+        request: UpdateSplitsDeleteOpstampRequest,
+    ) -> crate::metastore::MetastoreResult<UpdateSplitsDeleteOpstampResponse> {
+        self.inner
+            .clone()
+            .update_splits_delete_opstamp(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                UpdateSplitsDeleteOpstampRequest::rpc_name(),
+            ))
+    }
+    async fn list_delete_tasks(
+        &self,
+        request: ListDeleteTasksRequest,
+    ) -> crate::metastore::MetastoreResult<ListDeleteTasksResponse> {
+        self.inner
+            .clone()
+            .list_delete_tasks(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                ListDeleteTasksRequest::rpc_name(),
+            ))
+    }
+    async fn list_stale_splits(
+        &self,
+        request: ListStaleSplitsRequest,
+    ) -> crate::metastore::MetastoreResult<ListSplitsResponse> {
+        self.inner
+            .clone()
+            .list_stale_splits(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                ListStaleSplitsRequest::rpc_name(),
+            ))
+    }
+    async fn open_shards(
+        &self,
+        request: OpenShardsRequest,
+    ) -> crate::metastore::MetastoreResult<OpenShardsResponse> {
+        self.inner
+            .clone()
+            .open_shards(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                OpenShardsRequest::rpc_name(),
+            ))
+    }
+    async fn acquire_shards(
+        &self,
+        request: AcquireShardsRequest,
+    ) -> crate::metastore::MetastoreResult<AcquireShardsResponse> {
+        self.inner
+            .clone()
+            .acquire_shards(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                AcquireShardsRequest::rpc_name(),
+            ))
+    }
+    async fn delete_shards(
+        &self,
+        request: DeleteShardsRequest,
+    ) -> crate::metastore::MetastoreResult<DeleteShardsResponse> {
+        self.inner
+            .clone()
+            .delete_shards(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                DeleteShardsRequest::rpc_name(),
+            ))
+    }
+    async fn prune_shards(
+        &self,
+        request: PruneShardsRequest,
+    ) -> crate::metastore::MetastoreResult<PruneShardsResponse> {
+        self.inner
+            .clone()
+            .prune_shards(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                PruneShardsRequest::rpc_name(),
+            ))
+    }
+    async fn list_shards(
+        &self,
+        request: ListShardsRequest,
+    ) -> crate::metastore::MetastoreResult<ListShardsResponse> {
+        self.inner
+            .clone()
+            .list_shards(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|status| crate::error::grpc_status_to_service_error(
+                status,
+                ListShardsRequest::rpc_name(),
+            ))
+    }
+    async fn create_index_template(
+        &self,
+        request: CreateIndexTemplateRequest,
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner
             .clone()
-            .delete_splits(request)
+            .create_index_template(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                DeleteSplitsRequest::rpc_name(),
+                CreateIndexTemplateRequest::rpc_name(),
             ))
     }
-    async fn add_source(
+    async fn get_index_template(
         &self,
-        request: AddSourceRequest,
-    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        request: GetIndexTemplateRequest,
+    ) -> crate::metastore::MetastoreResult<GetIndexTemplateResponse> {
         self.inner
             .clone()
-            .add_source(request)
+            .get_index_template(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                AddSourceRequest::rpc_name(),
+                GetIndexTemplateRequest::rpc_name(),
             ))
     }
-    async fn toggle_source(
+    async fn find_index_template_matches(
         &self,
-        request: ToggleSourceRequest,
-    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        request: FindIndexTemplateMatchesRequest,
+    ) -> crate::metastore::MetastoreResult<FindIndexTemplateMatchesResponse> {
         self.inner
             .clone()
-            .toggle_source(request)
+            .find_index_template_matches(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                ToggleSourceRequest::rpc_name(),
+                FindIndexTemplateMatchesRequest::rpc_name(),
             ))
     }
-    async fn delete_source(
+    async fn list_index_templates(
         &self,
-        request: DeleteSourceRequest,
-    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        request: ListIndexTemplatesRequest,
+    ) -> crate::metastore::MetastoreResult<ListIndexTemplatesResponse> {
         self.inner
             .clone()
-            .delete_source(request)
+            .list_index_templates(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                DeleteSourceRequest::rpc_name(),
+                ListIndexTemplatesRequest::rpc_name(),
             ))
     }
-    async fn reset_source_checkpoint(
+    async fn delete_index_templates(
         &self,
-        request: ResetSourceCheckpointRequest,
+        request: DeleteIndexTemplatesRequest,
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner
             .clone()
-            .reset_source_checkpoint(request)
+            .delete_index_templates(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                ResetSourceCheckpointRequest::rpc_name(),
+                DeleteIndexTemplatesRequest::rpc_name(),
             ))
     }
-    async fn last_delete_opstamp(
+    async fn create_api_key(
         &self,
-        request: LastDeleteOpstampRequest,
-    ) -> crate::metastore::MetastoreResult<LastDeleteOpstampResponse> {
+        request: CreateApiKeyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner
             .clone()
-            .last_delete_opstamp(request)
+            .create_api_key(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                LastDeleteOpstampRequest::rpc_name(),
+                CreateApiKeyRequest::rpc_name(),
             ))
     }
-    async fn create_delete_task(
+    async fn list_api_keys(
         &self,
-        request: DeleteQuery,
-    ) -> crate::metastore::MetastoreResult<DeleteTask> {
+        request: ListApiKeysRequest,
+    ) -> crate::metastore::MetastoreResult<ListApiKeysResponse> {
         self.inner
             .clone()
-            .create_delete_task(request)
+            .list_api_keys(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                DeleteQuery::rpc_name(),
+                ListApiKeysRequest::rpc_name(),
             ))
     }
-    async fn update_splits_delete_opstamp(
+    async fn delete_api_key(
         &self,
-        request: UpdateSplitsDeleteOpstampRequest,
-    ) -> crate::metastore::MetastoreResult<UpdateSplitsDeleteOpstampResponse> {
+        request: DeleteApiKeyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner
             .clone()
-            .update_splits_delete_opstamp(request)
+            .delete_api_key(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                UpdateSplitsDeleteOpstampRequest::rpc_name(),
+                DeleteApiKeyRequest::rpc_name(),
             ))
     }
-    async fn list_delete_tasks(
+    async fn create_role(
         &self,
-        request: ListDeleteTasksRequest,
-    ) -> crate::metastore::MetastoreResult<ListDeleteTasksResponse> {
+        request: CreateRoleRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner
             .clone()
-            .list_delete_tasks(request)
+            .create_role(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                ListDeleteTasksRequest::rpc_name(),
+                CreateRoleRequest::rpc_name(),
             ))
     }
-    async fn list_stale_splits(
+    async fn list_roles(
         &self,
-        request: ListStaleSplitsRequest,
-    ) -> crate::metastore::MetastoreResult<ListSplitsResponse> {
+        request: ListRolesRequest,
+    ) -> crate::metastore::MetastoreResult<ListRolesResponse> {
         self.inner
             .clone()
-            .list_stale_splits(request)
+            .list_roles(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                ListStaleSplitsRequest::rpc_name(),
+                ListRolesRequest::rpc_name(),
             ))
     }
-    async fn open_shards(
+    async fn delete_role(
         &self,
-        request: OpenShardsRequest,
-    ) -> crate::metastore::MetastoreResult<OpenShardsResponse> {
+        request: DeleteRoleRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner
             .clone()
-            .open_shards(request)
+            .delete_role(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                OpenShardsRequest::rpc_name(),
+                DeleteRoleRequest::rpc_name(),
             ))
     }
-    async fn acquire_shards(
+    async fn create_point_in_time(
         &self,
-        request: AcquireShardsRequest,
-    ) -> crate::metastore::MetastoreResult<AcquireShardsResponse> {
+        request: CreatePointInTimeRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner
             .clone()
-            .acquire_shards(request)
+            .create_point_in_time(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                AcquireShardsRequest::rpc_name(),
+                CreatePointInTimeRequest::rpc_name(),
             ))
     }
-    async fn delete_shards(
+    async fn list_point_in_times(
         &self,
-        request: DeleteShardsRequest,
-    ) -> crate::metastore::MetastoreResult<DeleteShardsResponse> {
+        request: ListPointInTimesRequest,
+    ) -> crate::metastore::MetastoreResult<ListPointInTimesResponse> {
         self.inner
             .clone()
-            .delete_shards(request)
+            .list_point_in_times(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                DeleteShardsRequest::rpc_name(),
+                ListPointInTimesRequest::rpc_name(),
             ))
     }
-    async fn prune_shards(
+    async fn delete_point_in_times(
         &self,
-        request: PruneShardsRequest,
-    ) -> crate::metastore::MetastoreResult<PruneShardsResponse> {
+        request: DeletePointInTimesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner
             .clone()
-            .prune_shards(request)
+            .delete_point_in_times(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                PruneShardsRequest::rpc_name(),
+                DeletePointInTimesRequest::rpc_name(),
             ))
     }
-    async fn list_shards(
+    async fn update_index_aliases(
         &self,
-        request: ListShardsRequest,
-    ) -> crate::metastore::MetastoreResult<ListShardsResponse> {
+        request: UpdateIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner
             .clone()
-            .list_shards(request)
+            .update_index_aliases(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                ListShardsRequest::rpc_name(),
+                UpdateIndexAliasesRequest::rpc_name(),
             ))
     }
-    async fn create_index_template(
+    async fn list_index_aliases(
         &self,
-        request: CreateIndexTemplateRequest,
-    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        request: ListIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<ListIndexAliasesResponse> {
         self.inner
             .clone()
-            .create_index_template(request)
+            .list_index_aliases(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                CreateIndexTemplateRequest::rpc_name(),
+                ListIndexAliasesRequest::rpc_name(),
             ))
     }
-    async fn get_index_template(
+    async fn delete_index_aliases(
         &self,
-        request: GetIndexTemplateRequest,
-    ) -> crate::metastore::MetastoreResult<GetIndexTemplateResponse> {
+        request: DeleteIndexAliasesRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner
             .clone()
-            .get_index_template(request)
+            .delete_index_aliases(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                GetIndexTemplateRequest::rpc_name(),
+                DeleteIndexAliasesRequest::rpc_name(),
             ))
     }
-    async fn find_index_template_matches(
+    async fn create_lifecycle_policy(
         &self,
-        request: FindIndexTemplateMatchesRequest,
-    ) -> crate::metastore::MetastoreResult<FindIndexTemplateMatchesResponse> {
+        request: CreateLifecyclePolicyRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner
             .clone()
-            .find_index_template_matches(request)
+            .create_lifecycle_policy(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                FindIndexTemplateMatchesRequest::rpc_name(),
+                CreateLifecyclePolicyRequest::rpc_name(),
             ))
     }
-    async fn list_index_templates(
+    async fn list_lifecycle_policies(
         &self,
-        request: ListIndexTemplatesRequest,
-    ) -> crate::metastore::MetastoreResult<ListIndexTemplatesResponse> {
+        request: ListLifecyclePoliciesRequest,
+    ) -> crate::metastore::MetastoreResult<ListLifecyclePoliciesResponse> {
         self.inner
             .clone()
-            .list_index_templates(request)
+            .list_lifecycle_policies(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                ListIndexTemplatesRequest::rpc_name(),
+                ListLifecyclePoliciesRequest::rpc_name(),
             ))
     }
-    async fn delete_index_templates(
+    async fn delete_lifecycle_policy(
         &self,
-        request: DeleteIndexTemplatesRequest,
+        request: DeleteLifecyclePolicyRequest,
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner
             .clone()
-            .delete_index_templates(request)
+            .delete_lifecycle_policy(request)
             .await
             .map(|response| response.into_inner())
             .map_err(|status| crate::error::grpc_status_to_service_error(
                 status,
-                DeleteIndexTemplatesRequest::rpc_name(),
+                DeleteLifecyclePolicyRequest::rpc_name(),
             ))
     }
     async fn check_connectivity(&self) -> anyhow::Result<()> {
@@ -5664,6 +8005,171 @@ for MetastoreServiceGrpcServerAdapter {
             .map(tonic::Response::new)
             .map_err(crate::error::grpc_error_to_grpc_status)
     }
+    async fn create_api_key(
+        &self,
+        request: tonic::Request<CreateApiKeyRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .create_api_key(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn list_api_keys(
+        &self,
+        request: tonic::Request<ListApiKeysRequest>,
+    ) -> Result<tonic::Response<ListApiKeysResponse>, tonic::Status> {
+        self.inner
+            .0
+            .list_api_keys(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn delete_api_key(
+        &self,
+        request: tonic::Request<DeleteApiKeyRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .delete_api_key(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn create_role(
+        &self,
+        request: tonic::Request<CreateRoleRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .create_role(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn list_roles(
+        &self,
+        request: tonic::Request<ListRolesRequest>,
+    ) -> Result<tonic::Response<ListRolesResponse>, tonic::Status> {
+        self.inner
+            .0
+            .list_roles(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn delete_role(
+        &self,
+        request: tonic::Request<DeleteRoleRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .delete_role(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn create_point_in_time(
+        &self,
+        request: tonic::Request<CreatePointInTimeRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .create_point_in_time(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn list_point_in_times(
+        &self,
+        request: tonic::Request<ListPointInTimesRequest>,
+    ) -> Result<tonic::Response<ListPointInTimesResponse>, tonic::Status> {
+        self.inner
+            .0
+            .list_point_in_times(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn delete_point_in_times(
+        &self,
+        request: tonic::Request<DeletePointInTimesRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .delete_point_in_times(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn update_index_aliases(
+        &self,
+        request: tonic::Request<UpdateIndexAliasesRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .update_index_aliases(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn list_index_aliases(
+        &self,
+        request: tonic::Request<ListIndexAliasesRequest>,
+    ) -> Result<tonic::Response<ListIndexAliasesResponse>, tonic::Status> {
+        self.inner
+            .0
+            .list_index_aliases(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn delete_index_aliases(
+        &self,
+        request: tonic::Request<DeleteIndexAliasesRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .delete_index_aliases(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn create_lifecycle_policy(
+        &self,
+        request: tonic::Request<CreateLifecyclePolicyRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .create_lifecycle_policy(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn list_lifecycle_policies(
+        &self,
+        request: tonic::Request<ListLifecyclePoliciesRequest>,
+    ) -> Result<tonic::Response<ListLifecyclePoliciesResponse>, tonic::Status> {
+        self.inner
+            .0
+            .list_lifecycle_policies(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
+    async fn delete_lifecycle_policy(
+        &self,
+        request: tonic::Request<DeleteLifecyclePolicyRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .0
+            .delete_lifecycle_policy(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(crate::error::grpc_error_to_grpc_status)
+    }
 }
 /// Generated client implementations.
 pub mod metastore_service_grpc_client {
@@ -5724,87 +8230,507 @@ pub mod metastore_service_grpc_client {
         /// Attempt to create a new client by connecting to a given endpoint.
         pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
         where
-            D: TryInto<tonic::transport::Endpoint>,
-            D::Error: Into<StdError>,
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> MetastoreServiceGrpcClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> MetastoreServiceGrpcClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
         {
-            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
-            Ok(Self::new(conn))
+            MetastoreServiceGrpcClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Creates an index.
+        ///
+        /// This API creates a new index in the metastore.
+        /// An error will occur if an index that already exists in the storage is specified.
+        pub async fn create_index(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateIndexResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/CreateIndex",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.metastore.MetastoreService", "CreateIndex"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Update an index.
+        pub async fn update_index(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::IndexMetadataResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/UpdateIndex",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.metastore.MetastoreService", "UpdateIndex"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Returns the `IndexMetadata` of an index identified by its IndexID or its IndexUID.
+        pub async fn index_metadata(
+            &mut self,
+            request: impl tonic::IntoRequest<super::IndexMetadataRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::IndexMetadataResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/IndexMetadata",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "IndexMetadata",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Fetches the metadata of a list of indexes identified by their Index IDs or UIDs.
+        pub async fn indexes_metadata(
+            &mut self,
+            request: impl tonic::IntoRequest<super::IndexesMetadataRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::IndexesMetadataResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/IndexesMetadata",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "IndexesMetadata",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Gets an indexes metadatas.
+        pub async fn list_indexes_metadata(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListIndexesMetadataRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIndexesMetadataResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/ListIndexesMetadata",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "ListIndexesMetadata",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Deletes an index
+        pub async fn delete_index(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteIndexRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/DeleteIndex",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.metastore.MetastoreService", "DeleteIndex"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Streams splits from index.
+        pub async fn list_splits(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListSplitsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ListSplitsResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/ListSplits",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.metastore.MetastoreService", "ListSplits"),
+                );
+            self.inner.server_streaming(req, path, codec).await
         }
-    }
-    impl<T> MetastoreServiceGrpcClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::BoxBody>,
-        T::Error: Into<StdError>,
-        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
-        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
-    {
-        pub fn new(inner: T) -> Self {
-            let inner = tonic::client::Grpc::new(inner);
-            Self { inner }
+        /// Stages several splits.
+        pub async fn stage_splits(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StageSplitsRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/StageSplits",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.metastore.MetastoreService", "StageSplits"),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        pub fn with_origin(inner: T, origin: Uri) -> Self {
-            let inner = tonic::client::Grpc::with_origin(inner, origin);
-            Self { inner }
+        /// Publishes split.
+        pub async fn publish_splits(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PublishSplitsRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/PublishSplits",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "PublishSplits",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> MetastoreServiceGrpcClient<InterceptedService<T, F>>
-        where
-            F: tonic::service::Interceptor,
-            T::ResponseBody: Default,
-            T: tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-                Response = http::Response<
-                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
-                >,
-            >,
-            <T as tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-            >>::Error: Into<StdError> + Send + Sync,
-        {
-            MetastoreServiceGrpcClient::new(InterceptedService::new(inner, interceptor))
+        /// Marks splits for deletion.
+        pub async fn mark_splits_for_deletion(
+            &mut self,
+            request: impl tonic::IntoRequest<super::MarkSplitsForDeletionRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/MarkSplitsForDeletion",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "MarkSplitsForDeletion",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Compress requests with the given encoding.
-        ///
-        /// This requires the server to support it otherwise it might respond with an
-        /// error.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.send_compressed(encoding);
-            self
+        /// Deletes splits.
+        pub async fn delete_splits(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteSplitsRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/DeleteSplits",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "DeleteSplits",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Enable decompressing responses.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.accept_compressed(encoding);
-            self
+        /// Adds source.
+        pub async fn add_source(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AddSourceRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/AddSource",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("quickwit.metastore.MetastoreService", "AddSource"),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Limits the maximum size of a decoded message.
-        ///
-        /// Default: `4MB`
-        #[must_use]
-        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_decoding_message_size(limit);
-            self
+        /// Toggles source.
+        pub async fn toggle_source(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ToggleSourceRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/ToggleSource",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "ToggleSource",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Limits the maximum size of an encoded message.
-        ///
-        /// Default: `usize::MAX`
-        #[must_use]
-        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_encoding_message_size(limit);
-            self
+        /// Removes source.
+        pub async fn delete_source(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteSourceRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/DeleteSource",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "DeleteSource",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Resets source checkpoint.
+        pub async fn reset_source_checkpoint(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ResetSourceCheckpointRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/ResetSourceCheckpoint",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "ResetSourceCheckpoint",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Creates an index.
-        ///
-        /// This API creates a new index in the metastore.
-        /// An error will occur if an index that already exists in the storage is specified.
-        pub async fn create_index(
+        /// Gets last opstamp for a given `index_id`.
+        pub async fn last_delete_opstamp(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateIndexRequest>,
+            request: impl tonic::IntoRequest<super::LastDeleteOpstampRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateIndexResponse>,
+            tonic::Response<super::LastDeleteOpstampResponse>,
             tonic::Status,
         > {
             self.inner
@@ -5818,23 +8744,23 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/CreateIndex",
+                "/quickwit.metastore.MetastoreService/LastDeleteOpstamp",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("quickwit.metastore.MetastoreService", "CreateIndex"),
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "LastDeleteOpstamp",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Update an index.
-        pub async fn update_index(
+        /// Creates a delete task.
+        pub async fn create_delete_task(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateIndexRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::IndexMetadataResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::DeleteQuery>,
+        ) -> std::result::Result<tonic::Response<super::DeleteTask>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -5846,21 +8772,24 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/UpdateIndex",
+                "/quickwit.metastore.MetastoreService/CreateDeleteTask",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("quickwit.metastore.MetastoreService", "UpdateIndex"),
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "CreateDeleteTask",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Returns the `IndexMetadata` of an index identified by its IndexID or its IndexUID.
-        pub async fn index_metadata(
+        /// Updates splits `delete_opstamp`.
+        pub async fn update_splits_delete_opstamp(
             &mut self,
-            request: impl tonic::IntoRequest<super::IndexMetadataRequest>,
+            request: impl tonic::IntoRequest<super::UpdateSplitsDeleteOpstampRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::IndexMetadataResponse>,
+            tonic::Response<super::UpdateSplitsDeleteOpstampResponse>,
             tonic::Status,
         > {
             self.inner
@@ -5874,24 +8803,24 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/IndexMetadata",
+                "/quickwit.metastore.MetastoreService/UpdateSplitsDeleteOpstamp",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "IndexMetadata",
+                        "UpdateSplitsDeleteOpstamp",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Fetches the metadata of a list of indexes identified by their Index IDs or UIDs.
-        pub async fn indexes_metadata(
+        /// Lists delete tasks with `delete_task.opstamp` > `opstamp_start` for a given `index_id`.
+        pub async fn list_delete_tasks(
             &mut self,
-            request: impl tonic::IntoRequest<super::IndexesMetadataRequest>,
+            request: impl tonic::IntoRequest<super::ListDeleteTasksRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::IndexesMetadataResponse>,
+            tonic::Response<super::ListDeleteTasksResponse>,
             tonic::Status,
         > {
             self.inner
@@ -5905,24 +8834,24 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/IndexesMetadata",
+                "/quickwit.metastore.MetastoreService/ListDeleteTasks",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "IndexesMetadata",
+                        "ListDeleteTasks",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Gets an indexes metadatas.
-        pub async fn list_indexes_metadata(
+        /// Lists splits with `split.delete_opstamp` < `delete_opstamp` for a given `index_id`.
+        pub async fn list_stale_splits(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListIndexesMetadataRequest>,
+            request: impl tonic::IntoRequest<super::ListStaleSplitsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListIndexesMetadataResponse>,
+            tonic::Response<super::ListSplitsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -5936,23 +8865,31 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/ListIndexesMetadata",
+                "/quickwit.metastore.MetastoreService/ListStaleSplits",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "ListIndexesMetadata",
+                        "ListStaleSplits",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Deletes an index
-        pub async fn delete_index(
+        /// Shard API
+        ///
+        /// Note that for the file-backed metastore implementation, the requests are not processed atomically.
+        /// Indeed, each request comprises one or more subrequests that target different indexes and sources processed
+        /// independently. Responses list the requests that succeeded or failed in the fields `successes` and
+        /// `failures`.
+        pub async fn open_shards(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeleteIndexRequest>,
-        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::OpenShardsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OpenShardsResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -5964,21 +8901,29 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/DeleteIndex",
+                "/quickwit.metastore.MetastoreService/OpenShards",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("quickwit.metastore.MetastoreService", "DeleteIndex"),
+                    GrpcMethod::new("quickwit.metastore.MetastoreService", "OpenShards"),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Streams splits from index.
-        pub async fn list_splits(
+        /// Acquires a set of shards for indexing. This RPC locks the shards for publishing thanks to a publish token and only
+        /// the last indexer that has acquired the shards is allowed to publish. The response returns for each subrequest the
+        /// list of acquired shards along with the positions to index from.
+        ///
+        /// If a requested shard is missing, this method does not return an error. It should simply return the list of
+        /// shards that were actually acquired.
+        ///
+        /// For this reason, AcquireShards.acquire_shards may return less subresponse than there was in the request.
+        /// Also they may be returned in any order.
+        pub async fn acquire_shards(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListSplitsRequest>,
+            request: impl tonic::IntoRequest<super::AcquireShardsRequest>,
         ) -> std::result::Result<
-            tonic::Response<tonic::codec::Streaming<super::ListSplitsResponse>>,
+            tonic::Response<super::AcquireShardsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -5992,20 +8937,27 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/ListSplits",
+                "/quickwit.metastore.MetastoreService/AcquireShards",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("quickwit.metastore.MetastoreService", "ListSplits"),
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "AcquireShards",
+                    ),
                 );
-            self.inner.server_streaming(req, path, codec).await
+            self.inner.unary(req, path, codec).await
         }
-        /// Stages several splits.
-        pub async fn stage_splits(
+        /// Deletes a set of shards. This RPC deletes the shards from the metastore.
+        /// If the shard did not exist to begin with, the operation is successful and does not return any error.
+        pub async fn delete_shards(
             &mut self,
-            request: impl tonic::IntoRequest<super::StageSplitsRequest>,
-        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::DeleteShardsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeleteShardsResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -6017,20 +8969,26 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/StageSplits",
+                "/quickwit.metastore.MetastoreService/DeleteShards",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("quickwit.metastore.MetastoreService", "StageSplits"),
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "DeleteShards",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Publishes split.
-        pub async fn publish_splits(
+        /// Deletes outdated shards. This RPC deletes the shards from the metastore.
+        pub async fn prune_shards(
             &mut self,
-            request: impl tonic::IntoRequest<super::PublishSplitsRequest>,
-        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::PruneShardsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PruneShardsResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -6042,23 +9000,22 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/PublishSplits",
+                "/quickwit.metastore.MetastoreService/PruneShards",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new(
-                        "quickwit.metastore.MetastoreService",
-                        "PublishSplits",
-                    ),
+                    GrpcMethod::new("quickwit.metastore.MetastoreService", "PruneShards"),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Marks splits for deletion.
-        pub async fn mark_splits_for_deletion(
+        pub async fn list_shards(
             &mut self,
-            request: impl tonic::IntoRequest<super::MarkSplitsForDeletionRequest>,
-        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::ListShardsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListShardsResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -6070,22 +9027,19 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/MarkSplitsForDeletion",
+                "/quickwit.metastore.MetastoreService/ListShards",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new(
-                        "quickwit.metastore.MetastoreService",
-                        "MarkSplitsForDeletion",
-                    ),
+                    GrpcMethod::new("quickwit.metastore.MetastoreService", "ListShards"),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Deletes splits.
-        pub async fn delete_splits(
+        /// Creates an index template.
+        pub async fn create_index_template(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeleteSplitsRequest>,
+            request: impl tonic::IntoRequest<super::CreateIndexTemplateRequest>,
         ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
@@ -6098,23 +9052,26 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/DeleteSplits",
+                "/quickwit.metastore.MetastoreService/CreateIndexTemplate",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "DeleteSplits",
+                        "CreateIndexTemplate",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Adds source.
-        pub async fn add_source(
+        /// Fetches an index template.
+        pub async fn get_index_template(
             &mut self,
-            request: impl tonic::IntoRequest<super::AddSourceRequest>,
-        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::GetIndexTemplateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetIndexTemplateResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -6126,20 +9083,26 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/AddSource",
+                "/quickwit.metastore.MetastoreService/GetIndexTemplate",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("quickwit.metastore.MetastoreService", "AddSource"),
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "GetIndexTemplate",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Toggles source.
-        pub async fn toggle_source(
+        /// Finds matching index templates.
+        pub async fn find_index_template_matches(
             &mut self,
-            request: impl tonic::IntoRequest<super::ToggleSourceRequest>,
-        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::FindIndexTemplateMatchesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::FindIndexTemplateMatchesResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -6151,23 +9114,26 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/ToggleSource",
+                "/quickwit.metastore.MetastoreService/FindIndexTemplateMatches",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "ToggleSource",
+                        "FindIndexTemplateMatches",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Removes source.
-        pub async fn delete_source(
+        /// Returns all the index templates.
+        pub async fn list_index_templates(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeleteSourceRequest>,
-        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::ListIndexTemplatesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIndexTemplatesResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -6179,22 +9145,22 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/DeleteSource",
+                "/quickwit.metastore.MetastoreService/ListIndexTemplates",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "DeleteSource",
+                        "ListIndexTemplates",
                     ),
                 );
             self.inner.unary(req, path, codec).await
-        }
-        /// Resets source checkpoint.
-        pub async fn reset_source_checkpoint(
+        }
+        /// Deletes index templates.
+        pub async fn delete_index_templates(
             &mut self,
-            request: impl tonic::IntoRequest<super::ResetSourceCheckpointRequest>,
+            request: impl tonic::IntoRequest<super::DeleteIndexTemplatesRequest>,
         ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
@@ -6207,26 +9173,23 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/ResetSourceCheckpoint",
+                "/quickwit.metastore.MetastoreService/DeleteIndexTemplates",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "ResetSourceCheckpoint",
+                        "DeleteIndexTemplates",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Gets last opstamp for a given `index_id`.
-        pub async fn last_delete_opstamp(
+        /// Creates an API key, or replaces it if `overwrite` is set.
+        pub async fn create_api_key(
             &mut self,
-            request: impl tonic::IntoRequest<super::LastDeleteOpstampRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::LastDeleteOpstampResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::CreateApiKeyRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6238,23 +9201,26 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/LastDeleteOpstamp",
+                "/quickwit.metastore.MetastoreService/CreateApiKey",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "LastDeleteOpstamp",
+                        "CreateApiKey",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Creates a delete task.
-        pub async fn create_delete_task(
+        /// Returns all the API keys.
+        pub async fn list_api_keys(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeleteQuery>,
-        ) -> std::result::Result<tonic::Response<super::DeleteTask>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::ListApiKeysRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListApiKeysResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -6266,26 +9232,23 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/CreateDeleteTask",
+                "/quickwit.metastore.MetastoreService/ListApiKeys",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "CreateDeleteTask",
+                        "ListApiKeys",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Updates splits `delete_opstamp`.
-        pub async fn update_splits_delete_opstamp(
+        /// Deletes an API key.
+        pub async fn delete_api_key(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateSplitsDeleteOpstampRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::UpdateSplitsDeleteOpstampResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::DeleteApiKeyRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6297,26 +9260,23 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/UpdateSplitsDeleteOpstamp",
+                "/quickwit.metastore.MetastoreService/DeleteApiKey",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "UpdateSplitsDeleteOpstamp",
+                        "DeleteApiKey",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Lists delete tasks with `delete_task.opstamp` > `opstamp_start` for a given `index_id`.
-        pub async fn list_delete_tasks(
+        /// Creates a role, or replaces it if `overwrite` is set.
+        pub async fn create_role(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListDeleteTasksRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::ListDeleteTasksResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::CreateRoleRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6328,24 +9288,24 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/ListDeleteTasks",
+                "/quickwit.metastore.MetastoreService/CreateRole",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "ListDeleteTasks",
+                        "CreateRole",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Lists splits with `split.delete_opstamp` < `delete_opstamp` for a given `index_id`.
-        pub async fn list_stale_splits(
+        /// Returns all the roles.
+        pub async fn list_roles(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListStaleSplitsRequest>,
+            request: impl tonic::IntoRequest<super::ListRolesRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListSplitsResponse>,
+            tonic::Response<super::ListRolesResponse>,
             tonic::Status,
         > {
             self.inner
@@ -6359,31 +9319,23 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/ListStaleSplits",
+                "/quickwit.metastore.MetastoreService/ListRoles",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "ListStaleSplits",
+                        "ListRoles",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Shard API
-        ///
-        /// Note that for the file-backed metastore implementation, the requests are not processed atomically.
-        /// Indeed, each request comprises one or more subrequests that target different indexes and sources processed
-        /// independently. Responses list the requests that succeeded or failed in the fields `successes` and
-        /// `failures`.
-        pub async fn open_shards(
+        /// Deletes a role.
+        pub async fn delete_role(
             &mut self,
-            request: impl tonic::IntoRequest<super::OpenShardsRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::OpenShardsResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::DeleteRoleRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6395,31 +9347,23 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/OpenShards",
+                "/quickwit.metastore.MetastoreService/DeleteRole",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("quickwit.metastore.MetastoreService", "OpenShards"),
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "DeleteRole",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Acquires a set of shards for indexing. This RPC locks the shards for publishing thanks to a publish token and only
-        /// the last indexer that has acquired the shards is allowed to publish. The response returns for each subrequest the
-        /// list of acquired shards along with the positions to index from.
-        ///
-        /// If a requested shard is missing, this method does not return an error. It should simply return the list of
-        /// shards that were actually acquired.
-        ///
-        /// For this reason, AcquireShards.acquire_shards may return less subresponse than there was in the request.
-        /// Also they may be returned in any order.
-        pub async fn acquire_shards(
+        /// Creates a point in time, or replaces it if `overwrite` is set.
+        pub async fn create_point_in_time(
             &mut self,
-            request: impl tonic::IntoRequest<super::AcquireShardsRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::AcquireShardsResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::CreatePointInTimeRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6431,25 +9375,24 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/AcquireShards",
+                "/quickwit.metastore.MetastoreService/CreatePointInTime",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "AcquireShards",
+                        "CreatePointInTime",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Deletes a set of shards. This RPC deletes the shards from the metastore.
-        /// If the shard did not exist to begin with, the operation is successful and does not return any error.
-        pub async fn delete_shards(
+        /// Returns the points in time with the given keys, or all of them if no keys are given.
+        pub async fn list_point_in_times(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeleteShardsRequest>,
+            request: impl tonic::IntoRequest<super::ListPointInTimesRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::DeleteShardsResponse>,
+            tonic::Response<super::ListPointInTimesResponse>,
             tonic::Status,
         > {
             self.inner
@@ -6463,26 +9406,23 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/DeleteShards",
+                "/quickwit.metastore.MetastoreService/ListPointInTimes",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "DeleteShards",
+                        "ListPointInTimes",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Deletes outdated shards. This RPC deletes the shards from the metastore.
-        pub async fn prune_shards(
+        /// Deletes the points in time with the given keys. Unknown keys are ignored.
+        pub async fn delete_point_in_times(
             &mut self,
-            request: impl tonic::IntoRequest<super::PruneShardsRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::PruneShardsResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::DeletePointInTimesRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6494,22 +9434,23 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/PruneShards",
+                "/quickwit.metastore.MetastoreService/DeletePointInTimes",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("quickwit.metastore.MetastoreService", "PruneShards"),
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "DeletePointInTimes",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_shards(
+        /// Applies a list of alias actions atomically: if one of them fails, none is applied.
+        pub async fn update_index_aliases(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListShardsRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::ListShardsResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::UpdateIndexAliasesRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6521,20 +9462,26 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/ListShards",
+                "/quickwit.metastore.MetastoreService/UpdateIndexAliases",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("quickwit.metastore.MetastoreService", "ListShards"),
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "UpdateIndexAliases",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Creates an index template.
-        pub async fn create_index_template(
+        /// Returns the index aliases.
+        pub async fn list_index_aliases(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateIndexTemplateRequest>,
-        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::ListIndexAliasesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIndexAliasesResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -6546,26 +9493,23 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/CreateIndexTemplate",
+                "/quickwit.metastore.MetastoreService/ListIndexAliases",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "CreateIndexTemplate",
+                        "ListIndexAliases",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Fetches an index template.
-        pub async fn get_index_template(
+        /// Removes the given indexes from all the aliases.
+        pub async fn delete_index_aliases(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetIndexTemplateRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::GetIndexTemplateResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::DeleteIndexAliasesRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6577,26 +9521,23 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/GetIndexTemplate",
+                "/quickwit.metastore.MetastoreService/DeleteIndexAliases",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "GetIndexTemplate",
+                        "DeleteIndexAliases",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Finds matching index templates.
-        pub async fn find_index_template_matches(
+        /// Creates a lifecycle policy, or replaces it if `overwrite` is set.
+        pub async fn create_lifecycle_policy(
             &mut self,
-            request: impl tonic::IntoRequest<super::FindIndexTemplateMatchesRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::FindIndexTemplateMatchesResponse>,
-            tonic::Status,
-        > {
+            request: impl tonic::IntoRequest<super::CreateLifecyclePolicyRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
                 .await
@@ -6608,24 +9549,24 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/FindIndexTemplateMatches",
+                "/quickwit.metastore.MetastoreService/CreateLifecyclePolicy",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "FindIndexTemplateMatches",
+                        "CreateLifecyclePolicy",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Returns all the index templates.
-        pub async fn list_index_templates(
+        /// Returns all the lifecycle policies.
+        pub async fn list_lifecycle_policies(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListIndexTemplatesRequest>,
+            request: impl tonic::IntoRequest<super::ListLifecyclePoliciesRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListIndexTemplatesResponse>,
+            tonic::Response<super::ListLifecyclePoliciesResponse>,
             tonic::Status,
         > {
             self.inner
@@ -6639,22 +9580,22 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/ListIndexTemplates",
+                "/quickwit.metastore.MetastoreService/ListLifecyclePolicies",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "ListIndexTemplates",
+                        "ListLifecyclePolicies",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Deletes index templates.
-        pub async fn delete_index_templates(
+        /// Deletes a lifecycle policy.
+        pub async fn delete_lifecycle_policy(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeleteIndexTemplatesRequest>,
+            request: impl tonic::IntoRequest<super::DeleteLifecyclePolicyRequest>,
         ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
             self.inner
                 .ready()
@@ -6667,14 +9608,14 @@ pub mod metastore_service_grpc_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/quickwit.metastore.MetastoreService/DeleteIndexTemplates",
+                "/quickwit.metastore.MetastoreService/DeleteLifecyclePolicy",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "quickwit.metastore.MetastoreService",
-                        "DeleteIndexTemplates",
+                        "DeleteLifecyclePolicy",
                     ),
                 );
             self.inner.unary(req, path, codec).await
@@ -6911,6 +9852,96 @@ pub mod metastore_service_grpc_server {
             &self,
             request: tonic::Request<super::DeleteIndexTemplatesRequest>,
         ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Creates an API key, or replaces it if `overwrite` is set.
+        async fn create_api_key(
+            &self,
+            request: tonic::Request<super::CreateApiKeyRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Returns all the API keys.
+        async fn list_api_keys(
+            &self,
+            request: tonic::Request<super::ListApiKeysRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListApiKeysResponse>,
+            tonic::Status,
+        >;
+        /// Deletes an API key.
+        async fn delete_api_key(
+            &self,
+            request: tonic::Request<super::DeleteApiKeyRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Creates a role, or replaces it if `overwrite` is set.
+        async fn create_role(
+            &self,
+            request: tonic::Request<super::CreateRoleRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Returns all the roles.
+        async fn list_roles(
+            &self,
+            request: tonic::Request<super::ListRolesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListRolesResponse>,
+            tonic::Status,
+        >;
+        /// Deletes a role.
+        async fn delete_role(
+            &self,
+            request: tonic::Request<super::DeleteRoleRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Creates a point in time, or replaces it if `overwrite` is set.
+        async fn create_point_in_time(
+            &self,
+            request: tonic::Request<super::CreatePointInTimeRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Returns the points in time with the given keys, or all of them if no keys are given.
+        async fn list_point_in_times(
+            &self,
+            request: tonic::Request<super::ListPointInTimesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListPointInTimesResponse>,
+            tonic::Status,
+        >;
+        /// Deletes the points in time with the given keys. Unknown keys are ignored.
+        async fn delete_point_in_times(
+            &self,
+            request: tonic::Request<super::DeletePointInTimesRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Applies a list of alias actions atomically: if one of them fails, none is applied.
+        async fn update_index_aliases(
+            &self,
+            request: tonic::Request<super::UpdateIndexAliasesRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Returns the index aliases.
+        async fn list_index_aliases(
+            &self,
+            request: tonic::Request<super::ListIndexAliasesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIndexAliasesResponse>,
+            tonic::Status,
+        >;
+        /// Removes the given indexes from all the aliases.
+        async fn delete_index_aliases(
+            &self,
+            request: tonic::Request<super::DeleteIndexAliasesRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Creates a lifecycle policy, or replaces it if `overwrite` is set.
+        async fn create_lifecycle_policy(
+            &self,
+            request: tonic::Request<super::CreateLifecyclePolicyRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Returns all the lifecycle policies.
+        async fn list_lifecycle_policies(
+            &self,
+            request: tonic::Request<super::ListLifecyclePoliciesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListLifecyclePoliciesResponse>,
+            tonic::Status,
+        >;
+        /// Deletes a lifecycle policy.
+        async fn delete_lifecycle_policy(
+            &self,
+            request: tonic::Request<super::DeleteLifecyclePolicyRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
     }
     /// Metastore meant to manage Quickwit's indexes, their splits and delete tasks.
     ///
@@ -7040,23 +10071,618 @@ pub mod metastore_service_grpc_server {
             match req.uri().path() {
                 "/quickwit.metastore.MetastoreService/CreateIndex" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateIndexSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct CreateIndexSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::CreateIndexRequest>
+                    for CreateIndexSvc<T> {
+                        type Response = super::CreateIndexResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateIndexRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).create_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/UpdateIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateIndexSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::UpdateIndexRequest>
+                    for UpdateIndexSvc<T> {
+                        type Response = super::IndexMetadataResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateIndexRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).update_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/IndexMetadata" => {
+                    #[allow(non_camel_case_types)]
+                    struct IndexMetadataSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::IndexMetadataRequest>
+                    for IndexMetadataSvc<T> {
+                        type Response = super::IndexMetadataResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::IndexMetadataRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).index_metadata(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = IndexMetadataSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/IndexesMetadata" => {
+                    #[allow(non_camel_case_types)]
+                    struct IndexesMetadataSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::IndexesMetadataRequest>
+                    for IndexesMetadataSvc<T> {
+                        type Response = super::IndexesMetadataResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::IndexesMetadataRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).indexes_metadata(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = IndexesMetadataSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/ListIndexesMetadata" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListIndexesMetadataSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::ListIndexesMetadataRequest>
+                    for ListIndexesMetadataSvc<T> {
+                        type Response = super::ListIndexesMetadataResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListIndexesMetadataRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).list_indexes_metadata(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListIndexesMetadataSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/DeleteIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteIndexSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::DeleteIndexRequest>
+                    for DeleteIndexSvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteIndexRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).delete_index(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/ListSplits" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListSplitsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::ServerStreamingService<super::ListSplitsRequest>
+                    for ListSplitsSvc<T> {
+                        type Response = super::ListSplitsResponse;
+                        type ResponseStream = T::ListSplitsStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListSplitsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move { (*inner).list_splits(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListSplitsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/StageSplits" => {
+                    #[allow(non_camel_case_types)]
+                    struct StageSplitsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::StageSplitsRequest>
+                    for StageSplitsSvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::StageSplitsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).stage_splits(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = StageSplitsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/PublishSplits" => {
+                    #[allow(non_camel_case_types)]
+                    struct PublishSplitsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::PublishSplitsRequest>
+                    for PublishSplitsSvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PublishSplitsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).publish_splits(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = PublishSplitsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/MarkSplitsForDeletion" => {
+                    #[allow(non_camel_case_types)]
+                    struct MarkSplitsForDeletionSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::MarkSplitsForDeletionRequest>
+                    for MarkSplitsForDeletionSvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::MarkSplitsForDeletionRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).mark_splits_for_deletion(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = MarkSplitsForDeletionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/DeleteSplits" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteSplitsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::DeleteSplitsRequest>
+                    for DeleteSplitsSvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteSplitsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).delete_splits(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteSplitsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/AddSource" => {
+                    #[allow(non_camel_case_types)]
+                    struct AddSourceSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::AddSourceRequest>
+                    for AddSourceSvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AddSourceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move { (*inner).add_source(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = AddSourceSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/ToggleSource" => {
+                    #[allow(non_camel_case_types)]
+                    struct ToggleSourceSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::ToggleSourceRequest>
+                    for ToggleSourceSvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ToggleSourceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).toggle_source(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ToggleSourceSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/DeleteSource" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteSourceSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::CreateIndexRequest>
-                    for CreateIndexSvc<T> {
-                        type Response = super::CreateIndexResponse;
+                    > tonic::server::UnaryService<super::DeleteSourceRequest>
+                    for DeleteSourceSvc<T> {
+                        type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateIndexRequest>,
+                            request: tonic::Request<super::DeleteSourceRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).create_index(request).await
+                                (*inner).delete_source(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7068,7 +10694,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateIndexSvc(inner);
+                        let method = DeleteSourceSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7084,25 +10710,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/UpdateIndex" => {
+                "/quickwit.metastore.MetastoreService/ResetSourceCheckpoint" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateIndexSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct ResetSourceCheckpointSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::UpdateIndexRequest>
-                    for UpdateIndexSvc<T> {
-                        type Response = super::IndexMetadataResponse;
+                    > tonic::server::UnaryService<super::ResetSourceCheckpointRequest>
+                    for ResetSourceCheckpointSvc<T> {
+                        type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdateIndexRequest>,
+                            request: tonic::Request<super::ResetSourceCheckpointRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).update_index(request).await
+                                (*inner).reset_source_checkpoint(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7114,7 +10740,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = UpdateIndexSvc(inner);
+                        let method = ResetSourceCheckpointSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7130,25 +10756,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/IndexMetadata" => {
+                "/quickwit.metastore.MetastoreService/LastDeleteOpstamp" => {
                     #[allow(non_camel_case_types)]
-                    struct IndexMetadataSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct LastDeleteOpstampSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::IndexMetadataRequest>
-                    for IndexMetadataSvc<T> {
-                        type Response = super::IndexMetadataResponse;
+                    > tonic::server::UnaryService<super::LastDeleteOpstampRequest>
+                    for LastDeleteOpstampSvc<T> {
+                        type Response = super::LastDeleteOpstampResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::IndexMetadataRequest>,
+                            request: tonic::Request<super::LastDeleteOpstampRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).index_metadata(request).await
+                                (*inner).last_delete_opstamp(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7160,7 +10786,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = IndexMetadataSvc(inner);
+                        let method = LastDeleteOpstampSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7176,25 +10802,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/IndexesMetadata" => {
+                "/quickwit.metastore.MetastoreService/CreateDeleteTask" => {
                     #[allow(non_camel_case_types)]
-                    struct IndexesMetadataSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct CreateDeleteTaskSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::IndexesMetadataRequest>
-                    for IndexesMetadataSvc<T> {
-                        type Response = super::IndexesMetadataResponse;
+                    > tonic::server::UnaryService<super::DeleteQuery>
+                    for CreateDeleteTaskSvc<T> {
+                        type Response = super::DeleteTask;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::IndexesMetadataRequest>,
+                            request: tonic::Request<super::DeleteQuery>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).indexes_metadata(request).await
+                                (*inner).create_delete_task(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7206,7 +10832,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = IndexesMetadataSvc(inner);
+                        let method = CreateDeleteTaskSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7222,25 +10848,30 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/ListIndexesMetadata" => {
+                "/quickwit.metastore.MetastoreService/UpdateSplitsDeleteOpstamp" => {
                     #[allow(non_camel_case_types)]
-                    struct ListIndexesMetadataSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct UpdateSplitsDeleteOpstampSvc<T: MetastoreServiceGrpc>(
+                        pub Arc<T>,
+                    );
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::ListIndexesMetadataRequest>
-                    for ListIndexesMetadataSvc<T> {
-                        type Response = super::ListIndexesMetadataResponse;
+                    > tonic::server::UnaryService<
+                        super::UpdateSplitsDeleteOpstampRequest,
+                    > for UpdateSplitsDeleteOpstampSvc<T> {
+                        type Response = super::UpdateSplitsDeleteOpstampResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListIndexesMetadataRequest>,
+                            request: tonic::Request<
+                                super::UpdateSplitsDeleteOpstampRequest,
+                            >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).list_indexes_metadata(request).await
+                                (*inner).update_splits_delete_opstamp(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7252,7 +10883,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListIndexesMetadataSvc(inner);
+                        let method = UpdateSplitsDeleteOpstampSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7268,25 +10899,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/DeleteIndex" => {
+                "/quickwit.metastore.MetastoreService/ListDeleteTasks" => {
                     #[allow(non_camel_case_types)]
-                    struct DeleteIndexSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct ListDeleteTasksSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::DeleteIndexRequest>
-                    for DeleteIndexSvc<T> {
-                        type Response = super::EmptyResponse;
+                    > tonic::server::UnaryService<super::ListDeleteTasksRequest>
+                    for ListDeleteTasksSvc<T> {
+                        type Response = super::ListDeleteTasksResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeleteIndexRequest>,
+                            request: tonic::Request<super::ListDeleteTasksRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).delete_index(request).await
+                                (*inner).list_delete_tasks(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7298,7 +10929,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = DeleteIndexSvc(inner);
+                        let method = ListDeleteTasksSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7314,25 +10945,26 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/ListSplits" => {
+                "/quickwit.metastore.MetastoreService/ListStaleSplits" => {
                     #[allow(non_camel_case_types)]
-                    struct ListSplitsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct ListStaleSplitsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::ServerStreamingService<super::ListSplitsRequest>
-                    for ListSplitsSvc<T> {
+                    > tonic::server::UnaryService<super::ListStaleSplitsRequest>
+                    for ListStaleSplitsSvc<T> {
                         type Response = super::ListSplitsResponse;
-                        type ResponseStream = T::ListSplitsStream;
                         type Future = BoxFuture<
-                            tonic::Response<Self::ResponseStream>,
+                            tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListSplitsRequest>,
+                            request: tonic::Request<super::ListStaleSplitsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
-                            let fut = async move { (*inner).list_splits(request).await };
+                            let fut = async move {
+                                (*inner).list_stale_splits(request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -7343,7 +10975,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListSplitsSvc(inner);
+                        let method = ListStaleSplitsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7354,30 +10986,74 @@ pub mod metastore_service_grpc_server {
                                 max_decoding_message_size,
                                 max_encoding_message_size,
                             );
-                        let res = grpc.server_streaming(method, req).await;
+                        let res = grpc.unary(method, req).await;
                         Ok(res)
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/StageSplits" => {
+                "/quickwit.metastore.MetastoreService/OpenShards" => {
                     #[allow(non_camel_case_types)]
-                    struct StageSplitsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct OpenShardsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::StageSplitsRequest>
-                    for StageSplitsSvc<T> {
-                        type Response = super::EmptyResponse;
+                    > tonic::server::UnaryService<super::OpenShardsRequest>
+                    for OpenShardsSvc<T> {
+                        type Response = super::OpenShardsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::StageSplitsRequest>,
+                            request: tonic::Request<super::OpenShardsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move { (*inner).open_shards(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = OpenShardsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/AcquireShards" => {
+                    #[allow(non_camel_case_types)]
+                    struct AcquireShardsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::AcquireShardsRequest>
+                    for AcquireShardsSvc<T> {
+                        type Response = super::AcquireShardsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AcquireShardsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).stage_splits(request).await
+                                (*inner).acquire_shards(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7389,7 +11065,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = StageSplitsSvc(inner);
+                        let method = AcquireShardsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7405,25 +11081,71 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/PublishSplits" => {
+                "/quickwit.metastore.MetastoreService/DeleteShards" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteShardsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::DeleteShardsRequest>
+                    for DeleteShardsSvc<T> {
+                        type Response = super::DeleteShardsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteShardsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).delete_shards(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteShardsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/quickwit.metastore.MetastoreService/PruneShards" => {
                     #[allow(non_camel_case_types)]
-                    struct PublishSplitsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct PruneShardsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::PublishSplitsRequest>
-                    for PublishSplitsSvc<T> {
-                        type Response = super::EmptyResponse;
+                    > tonic::server::UnaryService<super::PruneShardsRequest>
+                    for PruneShardsSvc<T> {
+                        type Response = super::PruneShardsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::PublishSplitsRequest>,
+                            request: tonic::Request<super::PruneShardsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).publish_splits(request).await
+                                (*inner).prune_shards(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7435,7 +11157,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = PublishSplitsSvc(inner);
+                        let method = PruneShardsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7451,26 +11173,24 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/MarkSplitsForDeletion" => {
+                "/quickwit.metastore.MetastoreService/ListShards" => {
                     #[allow(non_camel_case_types)]
-                    struct MarkSplitsForDeletionSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct ListShardsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::MarkSplitsForDeletionRequest>
-                    for MarkSplitsForDeletionSvc<T> {
-                        type Response = super::EmptyResponse;
+                    > tonic::server::UnaryService<super::ListShardsRequest>
+                    for ListShardsSvc<T> {
+                        type Response = super::ListShardsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::MarkSplitsForDeletionRequest>,
+                            request: tonic::Request<super::ListShardsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
-                            let fut = async move {
-                                (*inner).mark_splits_for_deletion(request).await
-                            };
+                            let fut = async move { (*inner).list_shards(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -7481,7 +11201,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = MarkSplitsForDeletionSvc(inner);
+                        let method = ListShardsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7497,13 +11217,13 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/DeleteSplits" => {
+                "/quickwit.metastore.MetastoreService/CreateIndexTemplate" => {
                     #[allow(non_camel_case_types)]
-                    struct DeleteSplitsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct CreateIndexTemplateSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::DeleteSplitsRequest>
-                    for DeleteSplitsSvc<T> {
+                    > tonic::server::UnaryService<super::CreateIndexTemplateRequest>
+                    for CreateIndexTemplateSvc<T> {
                         type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
@@ -7511,11 +11231,11 @@ pub mod metastore_service_grpc_server {
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeleteSplitsRequest>,
+                            request: tonic::Request<super::CreateIndexTemplateRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).delete_splits(request).await
+                                (*inner).create_index_template(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7527,7 +11247,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = DeleteSplitsSvc(inner);
+                        let method = CreateIndexTemplateSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7543,24 +11263,26 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/AddSource" => {
+                "/quickwit.metastore.MetastoreService/GetIndexTemplate" => {
                     #[allow(non_camel_case_types)]
-                    struct AddSourceSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct GetIndexTemplateSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::AddSourceRequest>
-                    for AddSourceSvc<T> {
-                        type Response = super::EmptyResponse;
+                    > tonic::server::UnaryService<super::GetIndexTemplateRequest>
+                    for GetIndexTemplateSvc<T> {
+                        type Response = super::GetIndexTemplateResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::AddSourceRequest>,
+                            request: tonic::Request<super::GetIndexTemplateRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
-                            let fut = async move { (*inner).add_source(request).await };
+                            let fut = async move {
+                                (*inner).get_index_template(request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -7571,7 +11293,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = AddSourceSvc(inner);
+                        let method = GetIndexTemplateSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7587,25 +11309,29 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/ToggleSource" => {
+                "/quickwit.metastore.MetastoreService/FindIndexTemplateMatches" => {
                     #[allow(non_camel_case_types)]
-                    struct ToggleSourceSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct FindIndexTemplateMatchesSvc<T: MetastoreServiceGrpc>(
+                        pub Arc<T>,
+                    );
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::ToggleSourceRequest>
-                    for ToggleSourceSvc<T> {
-                        type Response = super::EmptyResponse;
+                    > tonic::server::UnaryService<super::FindIndexTemplateMatchesRequest>
+                    for FindIndexTemplateMatchesSvc<T> {
+                        type Response = super::FindIndexTemplateMatchesResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ToggleSourceRequest>,
+                            request: tonic::Request<
+                                super::FindIndexTemplateMatchesRequest,
+                            >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).toggle_source(request).await
+                                (*inner).find_index_template_matches(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7617,7 +11343,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ToggleSourceSvc(inner);
+                        let method = FindIndexTemplateMatchesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7633,25 +11359,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/DeleteSource" => {
+                "/quickwit.metastore.MetastoreService/ListIndexTemplates" => {
                     #[allow(non_camel_case_types)]
-                    struct DeleteSourceSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct ListIndexTemplatesSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::DeleteSourceRequest>
-                    for DeleteSourceSvc<T> {
-                        type Response = super::EmptyResponse;
+                    > tonic::server::UnaryService<super::ListIndexTemplatesRequest>
+                    for ListIndexTemplatesSvc<T> {
+                        type Response = super::ListIndexTemplatesResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeleteSourceRequest>,
+                            request: tonic::Request<super::ListIndexTemplatesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).delete_source(request).await
+                                (*inner).list_index_templates(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7663,7 +11389,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = DeleteSourceSvc(inner);
+                        let method = ListIndexTemplatesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7679,13 +11405,13 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/ResetSourceCheckpoint" => {
+                "/quickwit.metastore.MetastoreService/DeleteIndexTemplates" => {
                     #[allow(non_camel_case_types)]
-                    struct ResetSourceCheckpointSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct DeleteIndexTemplatesSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::ResetSourceCheckpointRequest>
-                    for ResetSourceCheckpointSvc<T> {
+                    > tonic::server::UnaryService<super::DeleteIndexTemplatesRequest>
+                    for DeleteIndexTemplatesSvc<T> {
                         type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
@@ -7693,11 +11419,11 @@ pub mod metastore_service_grpc_server {
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ResetSourceCheckpointRequest>,
+                            request: tonic::Request<super::DeleteIndexTemplatesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).reset_source_checkpoint(request).await
+                                (*inner).delete_index_templates(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7709,7 +11435,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ResetSourceCheckpointSvc(inner);
+                        let method = DeleteIndexTemplatesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7725,25 +11451,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/LastDeleteOpstamp" => {
+                "/quickwit.metastore.MetastoreService/CreateApiKey" => {
                     #[allow(non_camel_case_types)]
-                    struct LastDeleteOpstampSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct CreateApiKeySvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::LastDeleteOpstampRequest>
-                    for LastDeleteOpstampSvc<T> {
-                        type Response = super::LastDeleteOpstampResponse;
+                    > tonic::server::UnaryService<super::CreateApiKeyRequest>
+                    for CreateApiKeySvc<T> {
+                        type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::LastDeleteOpstampRequest>,
+                            request: tonic::Request<super::CreateApiKeyRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).last_delete_opstamp(request).await
+                                (*inner).create_api_key(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7755,7 +11481,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = LastDeleteOpstampSvc(inner);
+                        let method = CreateApiKeySvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7771,25 +11497,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/CreateDeleteTask" => {
+                "/quickwit.metastore.MetastoreService/ListApiKeys" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateDeleteTaskSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct ListApiKeysSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::DeleteQuery>
-                    for CreateDeleteTaskSvc<T> {
-                        type Response = super::DeleteTask;
+                    > tonic::server::UnaryService<super::ListApiKeysRequest>
+                    for ListApiKeysSvc<T> {
+                        type Response = super::ListApiKeysResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeleteQuery>,
+                            request: tonic::Request<super::ListApiKeysRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).create_delete_task(request).await
+                                (*inner).list_api_keys(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7801,7 +11527,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateDeleteTaskSvc(inner);
+                        let method = ListApiKeysSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7817,30 +11543,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/UpdateSplitsDeleteOpstamp" => {
+                "/quickwit.metastore.MetastoreService/DeleteApiKey" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateSplitsDeleteOpstampSvc<T: MetastoreServiceGrpc>(
-                        pub Arc<T>,
-                    );
+                    struct DeleteApiKeySvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<
-                        super::UpdateSplitsDeleteOpstampRequest,
-                    > for UpdateSplitsDeleteOpstampSvc<T> {
-                        type Response = super::UpdateSplitsDeleteOpstampResponse;
+                    > tonic::server::UnaryService<super::DeleteApiKeyRequest>
+                    for DeleteApiKeySvc<T> {
+                        type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<
-                                super::UpdateSplitsDeleteOpstampRequest,
-                            >,
+                            request: tonic::Request<super::DeleteApiKeyRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).update_splits_delete_opstamp(request).await
+                                (*inner).delete_api_key(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7852,7 +11573,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = UpdateSplitsDeleteOpstampSvc(inner);
+                        let method = DeleteApiKeySvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7868,25 +11589,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/ListDeleteTasks" => {
+                "/quickwit.metastore.MetastoreService/CreateRole" => {
                     #[allow(non_camel_case_types)]
-                    struct ListDeleteTasksSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct CreateRoleSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::ListDeleteTasksRequest>
-                    for ListDeleteTasksSvc<T> {
-                        type Response = super::ListDeleteTasksResponse;
+                    > tonic::server::UnaryService<super::CreateRoleRequest>
+                    for CreateRoleSvc<T> {
+                        type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListDeleteTasksRequest>,
+                            request: tonic::Request<super::CreateRoleRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).list_delete_tasks(request).await
+                                (*inner).create_role(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7898,7 +11619,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListDeleteTasksSvc(inner);
+                        let method = CreateRoleSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7914,25 +11635,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/ListStaleSplits" => {
+                "/quickwit.metastore.MetastoreService/ListRoles" => {
                     #[allow(non_camel_case_types)]
-                    struct ListStaleSplitsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct ListRolesSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::ListStaleSplitsRequest>
-                    for ListStaleSplitsSvc<T> {
-                        type Response = super::ListSplitsResponse;
+                    > tonic::server::UnaryService<super::ListRolesRequest>
+                    for ListRolesSvc<T> {
+                        type Response = super::ListRolesResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListStaleSplitsRequest>,
+                            request: tonic::Request<super::ListRolesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).list_stale_splits(request).await
+                                (*inner).list_roles(request).await
                             };
                             Box::pin(fut)
                         }
@@ -7944,7 +11665,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListStaleSplitsSvc(inner);
+                        let method = ListRolesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -7960,24 +11681,26 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/OpenShards" => {
+                "/quickwit.metastore.MetastoreService/DeleteRole" => {
                     #[allow(non_camel_case_types)]
-                    struct OpenShardsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct DeleteRoleSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::OpenShardsRequest>
-                    for OpenShardsSvc<T> {
-                        type Response = super::OpenShardsResponse;
+                    > tonic::server::UnaryService<super::DeleteRoleRequest>
+                    for DeleteRoleSvc<T> {
+                        type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::OpenShardsRequest>,
+                            request: tonic::Request<super::DeleteRoleRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
-                            let fut = async move { (*inner).open_shards(request).await };
+                            let fut = async move {
+                                (*inner).delete_role(request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -7988,7 +11711,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = OpenShardsSvc(inner);
+                        let method = DeleteRoleSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8004,25 +11727,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/AcquireShards" => {
+                "/quickwit.metastore.MetastoreService/CreatePointInTime" => {
                     #[allow(non_camel_case_types)]
-                    struct AcquireShardsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct CreatePointInTimeSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::AcquireShardsRequest>
-                    for AcquireShardsSvc<T> {
-                        type Response = super::AcquireShardsResponse;
+                    > tonic::server::UnaryService<super::CreatePointInTimeRequest>
+                    for CreatePointInTimeSvc<T> {
+                        type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::AcquireShardsRequest>,
+                            request: tonic::Request<super::CreatePointInTimeRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).acquire_shards(request).await
+                                (*inner).create_point_in_time(request).await
                             };
                             Box::pin(fut)
                         }
@@ -8034,7 +11757,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = AcquireShardsSvc(inner);
+                        let method = CreatePointInTimeSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8050,25 +11773,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/DeleteShards" => {
+                "/quickwit.metastore.MetastoreService/ListPointInTimes" => {
                     #[allow(non_camel_case_types)]
-                    struct DeleteShardsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct ListPointInTimesSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::DeleteShardsRequest>
-                    for DeleteShardsSvc<T> {
-                        type Response = super::DeleteShardsResponse;
+                    > tonic::server::UnaryService<super::ListPointInTimesRequest>
+                    for ListPointInTimesSvc<T> {
+                        type Response = super::ListPointInTimesResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeleteShardsRequest>,
+                            request: tonic::Request<super::ListPointInTimesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).delete_shards(request).await
+                                (*inner).list_point_in_times(request).await
                             };
                             Box::pin(fut)
                         }
@@ -8080,7 +11803,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = DeleteShardsSvc(inner);
+                        let method = ListPointInTimesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8096,25 +11819,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/PruneShards" => {
+                "/quickwit.metastore.MetastoreService/DeletePointInTimes" => {
                     #[allow(non_camel_case_types)]
-                    struct PruneShardsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct DeletePointInTimesSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::PruneShardsRequest>
-                    for PruneShardsSvc<T> {
-                        type Response = super::PruneShardsResponse;
+                    > tonic::server::UnaryService<super::DeletePointInTimesRequest>
+                    for DeletePointInTimesSvc<T> {
+                        type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::PruneShardsRequest>,
+                            request: tonic::Request<super::DeletePointInTimesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).prune_shards(request).await
+                                (*inner).delete_point_in_times(request).await
                             };
                             Box::pin(fut)
                         }
@@ -8126,7 +11849,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = PruneShardsSvc(inner);
+                        let method = DeletePointInTimesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8142,24 +11865,26 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/ListShards" => {
+                "/quickwit.metastore.MetastoreService/UpdateIndexAliases" => {
                     #[allow(non_camel_case_types)]
-                    struct ListShardsSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct UpdateIndexAliasesSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::ListShardsRequest>
-                    for ListShardsSvc<T> {
-                        type Response = super::ListShardsResponse;
+                    > tonic::server::UnaryService<super::UpdateIndexAliasesRequest>
+                    for UpdateIndexAliasesSvc<T> {
+                        type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListShardsRequest>,
+                            request: tonic::Request<super::UpdateIndexAliasesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
-                            let fut = async move { (*inner).list_shards(request).await };
+                            let fut = async move {
+                                (*inner).update_index_aliases(request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -8170,7 +11895,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListShardsSvc(inner);
+                        let method = UpdateIndexAliasesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8186,25 +11911,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/CreateIndexTemplate" => {
+                "/quickwit.metastore.MetastoreService/ListIndexAliases" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateIndexTemplateSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct ListIndexAliasesSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::CreateIndexTemplateRequest>
-                    for CreateIndexTemplateSvc<T> {
-                        type Response = super::EmptyResponse;
+                    > tonic::server::UnaryService<super::ListIndexAliasesRequest>
+                    for ListIndexAliasesSvc<T> {
+                        type Response = super::ListIndexAliasesResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateIndexTemplateRequest>,
+                            request: tonic::Request<super::ListIndexAliasesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).create_index_template(request).await
+                                (*inner).list_index_aliases(request).await
                             };
                             Box::pin(fut)
                         }
@@ -8216,7 +11941,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateIndexTemplateSvc(inner);
+                        let method = ListIndexAliasesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8232,25 +11957,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/GetIndexTemplate" => {
+                "/quickwit.metastore.MetastoreService/DeleteIndexAliases" => {
                     #[allow(non_camel_case_types)]
-                    struct GetIndexTemplateSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct DeleteIndexAliasesSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::GetIndexTemplateRequest>
-                    for GetIndexTemplateSvc<T> {
-                        type Response = super::GetIndexTemplateResponse;
+                    > tonic::server::UnaryService<super::DeleteIndexAliasesRequest>
+                    for DeleteIndexAliasesSvc<T> {
+                        type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetIndexTemplateRequest>,
+                            request: tonic::Request<super::DeleteIndexAliasesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).get_index_template(request).await
+                                (*inner).delete_index_aliases(request).await
                             };
                             Box::pin(fut)
                         }
@@ -8262,7 +11987,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetIndexTemplateSvc(inner);
+                        let method = DeleteIndexAliasesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8278,29 +12003,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/FindIndexTemplateMatches" => {
+                "/quickwit.metastore.MetastoreService/CreateLifecyclePolicy" => {
                     #[allow(non_camel_case_types)]
-                    struct FindIndexTemplateMatchesSvc<T: MetastoreServiceGrpc>(
-                        pub Arc<T>,
-                    );
+                    struct CreateLifecyclePolicySvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::FindIndexTemplateMatchesRequest>
-                    for FindIndexTemplateMatchesSvc<T> {
-                        type Response = super::FindIndexTemplateMatchesResponse;
+                    > tonic::server::UnaryService<super::CreateLifecyclePolicyRequest>
+                    for CreateLifecyclePolicySvc<T> {
+                        type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<
-                                super::FindIndexTemplateMatchesRequest,
-                            >,
+                            request: tonic::Request<super::CreateLifecyclePolicyRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).find_index_template_matches(request).await
+                                (*inner).create_lifecycle_policy(request).await
                             };
                             Box::pin(fut)
                         }
@@ -8312,7 +12033,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = FindIndexTemplateMatchesSvc(inner);
+                        let method = CreateLifecyclePolicySvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8328,25 +12049,25 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/ListIndexTemplates" => {
+                "/quickwit.metastore.MetastoreService/ListLifecyclePolicies" => {
                     #[allow(non_camel_case_types)]
-                    struct ListIndexTemplatesSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct ListLifecyclePoliciesSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::ListIndexTemplatesRequest>
-                    for ListIndexTemplatesSvc<T> {
-                        type Response = super::ListIndexTemplatesResponse;
+                    > tonic::server::UnaryService<super::ListLifecyclePoliciesRequest>
+                    for ListLifecyclePoliciesSvc<T> {
+                        type Response = super::ListLifecyclePoliciesResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListIndexTemplatesRequest>,
+                            request: tonic::Request<super::ListLifecyclePoliciesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).list_index_templates(request).await
+                                (*inner).list_lifecycle_policies(request).await
                             };
                             Box::pin(fut)
                         }
@@ -8358,7 +12079,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListIndexTemplatesSvc(inner);
+                        let method = ListLifecyclePoliciesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -8374,13 +12095,13 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/quickwit.metastore.MetastoreService/DeleteIndexTemplates" => {
+                "/quickwit.metastore.MetastoreService/DeleteLifecyclePolicy" => {
                     #[allow(non_camel_case_types)]
-                    struct DeleteIndexTemplatesSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    struct DeleteLifecyclePolicySvc<T: MetastoreServiceGrpc>(pub Arc<T>);
                     impl<
                         T: MetastoreServiceGrpc,
-                    > tonic::server::UnaryService<super::DeleteIndexTemplatesRequest>
-                    for DeleteIndexTemplatesSvc<T> {
+                    > tonic::server::UnaryService<super::DeleteLifecyclePolicyRequest>
+                    for DeleteLifecyclePolicySvc<T> {
                         type Response = super::EmptyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
@@ -8388,11 +12109,11 @@ pub mod metastore_service_grpc_server {
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeleteIndexTemplatesRequest>,
+                            request: tonic::Request<super::DeleteLifecyclePolicyRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).delete_index_templates(request).await
+                                (*inner).delete_lifecycle_policy(request).await
                             };
                             Box::pin(fut)
                         }
@@ -8404,7 +12125,7 @@ pub mod metastore_service_grpc_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = DeleteIndexTemplatesSvc(inner);
+                        let method = DeleteLifecyclePolicySvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
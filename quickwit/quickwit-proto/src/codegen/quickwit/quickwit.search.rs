@@ -191,6 +191,139 @@ pub struct SearchRequest {
     pub search_after: ::core::option::Option<PartialHit>,
     #[prost(enumeration = "CountHits", tag = "17")]
     pub count_hits: i32,
+    /// Queries the hits are matched against. The names of the queries matching
+    /// a hit are returned in the `matched_queries` of the hit.
+    #[prost(message, repeated, tag = "18")]
+    pub named_queries: ::prost::alloc::vec::Vec<NamedQuery>,
+    /// Options of the snippets extracted on the `snippet_fields`.
+    #[prost(message, optional, tag = "19")]
+    pub snippet_options: ::core::option::Option<SnippetOptions>,
+    /// If set, the search only runs on the splits pinned by this point in time.
+    #[prost(message, optional, tag = "20")]
+    pub point_in_time: ::core::option::Option<PointInTime>,
+    /// If set, only the top hit of each distinct value of the collapse field is returned.
+    #[prost(message, optional, tag = "21")]
+    pub collapse: ::core::option::Option<Collapse>,
+    /// If set, the search returns the hits and aggregations collected on the splits that
+    /// could be searched within this duration, and reports the other splits as timed out.
+    /// The root sends the leaves the time left before its own deadline.
+    #[prost(uint32, optional, tag = "22")]
+    pub timeout_millis: ::core::option::Option<u32>,
+    /// Priority of the search, used by the admission control of the root searcher.
+    #[prost(enumeration = "SearchPriority", tag = "23")]
+    pub priority: i32,
+    /// ID of the API key of the client issuing the search, used by the admission control of the
+    /// root searcher to limit the number of concurrent searches per API key.
+    #[prost(string, optional, tag = "24")]
+    pub api_key_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Suggesters returning the values of completion fields starting with a given prefix.
+    #[prost(message, repeated, tag = "25")]
+    pub suggesters: ::prost::alloc::vec::Vec<CompletionSuggester>,
+    /// Whether to return the explanation of the score of each hit.
+    #[prost(bool, tag = "26")]
+    pub explain: bool,
+    /// Whether to return the time spent and the bytes downloaded searching each split.
+    #[prost(bool, tag = "27")]
+    pub profile: bool,
+    /// Fast fields whose values are read from the columnar store and returned with each hit.
+    #[prost(string, repeated, tag = "28")]
+    pub docvalue_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Whether to skip reading the documents from the doc store. The hits then carry an empty
+    /// JSON document.
+    #[prost(bool, tag = "29")]
+    pub skip_source: bool,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[derive(Eq, Hash)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Collapse {
+    /// Fast field the hits are collapsed on.
+    #[prost(string, tag = "1")]
+    pub field: ::prost::alloc::string::String,
+    /// Number of best hits returned in the `inner_hits` of each top hit, including the top hit itself.
+    /// No inner hits are returned if 0.
+    #[prost(uint32, tag = "2")]
+    pub inner_hits: u32,
+    /// Name of the inner hits, as set in the `inner_hits` of an Elasticsearch `collapse`.
+    #[prost(string, optional, tag = "3")]
+    pub inner_hits_name: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[derive(Eq, Hash)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CompletionSuggester {
+    /// Name of the suggestion in the response.
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// Field the suggestions are read from, usually a `completion` field.
+    #[prost(string, tag = "2")]
+    pub field: ::prost::alloc::string::String,
+    /// Prefix of the suggested values. It is normalized like the values of the field.
+    #[prost(string, tag = "3")]
+    pub prefix: ::prost::alloc::string::String,
+    /// Maximum number of suggestions returned.
+    #[prost(uint32, tag = "4")]
+    pub size: u32,
+}
+/// Time spent and bytes downloaded searching a split.
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SplitSearchProfile {
+    #[prost(string, tag = "1")]
+    pub split_id: ::prost::alloc::string::String,
+    /// Time spent downloading the data required by the search.
+    #[prost(uint64, tag = "2")]
+    pub warmup_micros: u64,
+    /// Time spent evaluating the query and collecting the hits and the aggregations.
+    #[prost(uint64, tag = "3")]
+    pub search_micros: u64,
+    /// Part of `search_micros` spent collecting the aggregations.
+    #[prost(uint64, tag = "4")]
+    pub aggregation_micros: u64,
+    /// Number of bytes downloaded from the storage. The bytes read from the caches are not counted.
+    #[prost(uint64, tag = "5")]
+    pub num_bytes_downloaded: u64,
+    /// Whether the response was read from the leaf search cache.
+    #[prost(bool, tag = "6")]
+    pub leaf_cache_hit: bool,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CompletionSuggestion {
+    /// Name of the suggester this suggestion answers.
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// Indexed values starting with the suggester prefix, in lexicographical order.
+    #[prost(string, repeated, tag = "2")]
+    pub options: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[derive(Eq, Hash)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PointInTime {
+    /// Opaque point-in-time ID returned when the point in time was created.
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    /// If set, extends the expiration of the point in time to now + `keep_alive_secs`.
+    #[prost(uint32, optional, tag = "2")]
+    pub keep_alive_secs: ::core::option::Option<u32>,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[derive(Eq, Hash)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NamedQuery {
+    /// Name of the query, as set with `_name` in the Elasticsearch query DSL.
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// Json object representing Quickwit's QueryAst.
+    #[prost(string, tag = "2")]
+    pub query_ast: ::prost::alloc::string::String,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[derive(Eq, Hash)]
@@ -206,6 +339,9 @@ pub struct SortField {
     /// unix_timestamp_nanos.
     #[prost(enumeration = "SortDatetimeFormat", optional, tag = "3")]
     pub sort_datetime_format: ::core::option::Option<i32>,
+    /// Placement of the documents that have no value for the sort field.
+    #[prost(enumeration = "SortMissing", tag = "4")]
+    pub missing: i32,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -230,6 +366,19 @@ pub struct SearchResponse {
     /// Scroll Id (only set if scroll_secs was set in the request)
     #[prost(string, optional, tag = "6")]
     pub scroll_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// True if the search timed out before all the splits were searched. In that case,
+    /// the response only contains the results of the splits searched within the timeout.
+    #[prost(bool, tag = "7")]
+    pub timed_out: bool,
+    /// Ids of the splits skipped because the search timed out.
+    #[prost(string, repeated, tag = "8")]
+    pub timed_out_splits: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Suggestions of the suggesters of the request.
+    #[prost(message, repeated, tag = "9")]
+    pub suggestions: ::prost::alloc::vec::Vec<CompletionSuggestion>,
+    /// Profiles of the searched splits, if the search request has `profile` set.
+    #[prost(message, repeated, tag = "10")]
+    pub split_profiles: ::prost::alloc::vec::Vec<SplitSearchProfile>,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -251,6 +400,9 @@ pub struct SplitSearchError {
     /// Flag to indicate if the error can be considered a retryable error
     #[prost(bool, tag = "3")]
     pub retryable_error: bool,
+    /// Flag to indicate that the split was skipped because the search timed out.
+    #[prost(bool, tag = "4")]
+    pub timed_out: bool,
 }
 /// / A LeafSearchRequest can span multiple indices.
 /// /
@@ -341,6 +493,15 @@ pub struct LeafHit {
     /// A snippet of the matching content
     #[prost(string, optional, tag = "3")]
     pub leaf_snippet_json: ::core::option::Option<::prost::alloc::string::String>,
+    /// The names of the named queries matching the hit
+    #[prost(string, repeated, tag = "4")]
+    pub matched_queries: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// The explanation of the score of the hit, serialized as JSON
+    #[prost(string, optional, tag = "5")]
+    pub explanation_json: ::core::option::Option<::prost::alloc::string::String>,
+    /// The values of the docvalue fields of the hit, serialized as JSON
+    #[prost(string, optional, tag = "6")]
+    pub leaf_docvalue_fields_json: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -358,6 +519,19 @@ pub struct Hit {
     /// The index id of the hit
     #[prost(string, tag = "4")]
     pub index_id: ::prost::alloc::string::String,
+    /// The names of the named queries matching the hit
+    #[prost(string, repeated, tag = "5")]
+    pub matched_queries: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// The best hits sharing the collapse value of the hit, including the hit itself, if the
+    /// search request has a `collapse` with `inner_hits`.
+    #[prost(message, repeated, tag = "6")]
+    pub inner_hits: ::prost::alloc::vec::Vec<Hit>,
+    /// The explanation of the score of the hit, serialized as JSON
+    #[prost(string, optional, tag = "7")]
+    pub explanation_json: ::core::option::Option<::prost::alloc::string::String>,
+    /// The values of the docvalue fields of the hit, serialized as JSON
+    #[prost(string, optional, tag = "8")]
+    pub docvalue_fields_json: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// A partial hit, is a hit for which we have not fetch the content yet.
 /// Instead, it holds a document_uri which is enough information to
@@ -392,6 +566,10 @@ pub struct PartialHit {
     /// The DocId identifies a unique document at the scale of a tantivy segment.
     #[prost(uint32, tag = "4")]
     pub doc_id: u32,
+    /// Value of the collapse field of the document, if the search request has a `collapse`.
+    /// Unset if the document has no value for this field.
+    #[prost(string, optional, tag = "5")]
+    pub collapse_value: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[derive(Ord, PartialOrd)]
@@ -440,6 +618,12 @@ pub struct LeafSearchResponse {
     pub intermediate_aggregation_result: ::core::option::Option<
         ::prost::alloc::vec::Vec<u8>,
     >,
+    /// Suggestions of the suggesters of the request, merged over the searched splits.
+    #[prost(message, repeated, tag = "7")]
+    pub suggestions: ::prost::alloc::vec::Vec<CompletionSuggestion>,
+    /// Profiles of the searched splits, if the search request has `profile` set.
+    #[prost(message, repeated, tag = "8")]
+    pub split_profiles: ::prost::alloc::vec::Vec<SplitSearchProfile>,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -449,6 +633,23 @@ pub struct SnippetRequest {
     pub snippet_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
     #[prost(string, tag = "2")]
     pub query_ast_resolved: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub snippet_options: ::core::option::Option<SnippetOptions>,
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[derive(Eq, Hash)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SnippetOptions {
+    /// Tag inserted before the highlighted terms. Defaults to `<b>`.
+    #[prost(string, optional, tag = "1")]
+    pub pre_tag: ::core::option::Option<::prost::alloc::string::String>,
+    /// Tag inserted after the highlighted terms. Defaults to `</b>`.
+    #[prost(string, optional, tag = "2")]
+    pub post_tag: ::core::option::Option<::prost::alloc::string::String>,
+    /// Maximum number of characters of a snippet. Defaults to 150.
+    #[prost(uint32, optional, tag = "3")]
+    pub fragment_size: ::core::option::Option<u32>,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -471,6 +672,19 @@ pub struct FetchDocsRequest {
     /// `DocMapper` as json serialized trait.
     #[prost(string, tag = "6")]
     pub doc_mapper: ::prost::alloc::string::String,
+    /// Named queries, with their QueryAst resolved, the fetched documents
+    /// are matched against.
+    #[prost(message, repeated, tag = "8")]
+    pub named_queries: ::prost::alloc::vec::Vec<NamedQuery>,
+    /// QueryAst, resolved, whose score of the fetched documents is explained.
+    #[prost(string, optional, tag = "9")]
+    pub explain_query_ast: ::core::option::Option<::prost::alloc::string::String>,
+    /// Fast fields whose values are read from the columnar store and returned with each hit.
+    #[prost(string, repeated, tag = "10")]
+    pub docvalue_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Whether to skip reading the documents from the doc store.
+    #[prost(bool, tag = "11")]
+    pub skip_source: bool,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -671,6 +885,36 @@ impl ListFieldType {
 #[serde(rename_all = "snake_case")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum SearchPriority {
+    /// Searches backing user-facing queries, e.g. dashboards.
+    Interactive = 0,
+    /// Long-running searches, e.g. exports, that must not delay the interactive ones.
+    Batch = 1,
+}
+impl SearchPriority {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SearchPriority::Interactive => "INTERACTIVE",
+            SearchPriority::Batch => "BATCH",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "INTERACTIVE" => Some(Self::Interactive),
+            "BATCH" => Some(Self::Batch),
+            _ => None,
+        }
+    }
+}
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum CountHits {
     /// Count all hits, querying all splits.
     CountAll = 0,
@@ -730,6 +974,38 @@ impl SortOrder {
         }
     }
 }
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SortMissing {
+    /// Documents missing the sort value come after the others.
+    ///
+    /// < This will be the default value;
+    Last = 0,
+    /// Documents missing the sort value come before the others.
+    First = 1,
+}
+impl SortMissing {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SortMissing::Last => "SORT_MISSING_LAST",
+            SortMissing::First => "SORT_MISSING_FIRST",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SORT_MISSING_LAST" => Some(Self::Last),
+            "SORT_MISSING_FIRST" => Some(Self::First),
+            _ => None,
+        }
+    }
+}
 /// Sort value format for datetime field.
 /// We keep an enum with only one format
 /// for future extension.
@@ -774,6 +1050,9 @@ pub enum OutputFormat {
     /// Format data by row in ClickHouse binary format.
     /// <https://clickhouse.tech/docs/en/interfaces/formats/#rowbinary>
     ClickHouseRowBinary = 1,
+    /// Arrow IPC streaming format, with a record batch per split.
+    /// <https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format>
+    ArrowIpc = 2,
 }
 impl OutputFormat {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -784,6 +1063,7 @@ impl OutputFormat {
         match self {
             OutputFormat::Csv => "CSV",
             OutputFormat::ClickHouseRowBinary => "CLICK_HOUSE_ROW_BINARY",
+            OutputFormat::ArrowIpc => "ARROW_IPC",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -791,6 +1071,7 @@ impl OutputFormat {
         match value {
             "CSV" => Some(Self::Csv),
             "CLICK_HOUSE_ROW_BINARY" => Some(Self::ClickHouseRowBinary),
+            "ARROW_IPC" => Some(Self::ArrowIpc),
             _ => None,
         }
     }
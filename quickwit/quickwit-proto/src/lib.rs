@@ -232,6 +232,22 @@ impl search::SortOrder {
         }
     }
 
+    /// Same as [`Self::compare_opt`], but `None` values are ranked according to `missing`.
+    #[inline(always)]
+    pub fn compare_opt_with_missing<T: Ord>(
+        &self,
+        this: &Option<T>,
+        other: &Option<T>,
+        missing: search::SortMissing,
+    ) -> Ordering {
+        let ordering = self.compare_opt(this, other);
+        if missing == search::SortMissing::First && this.is_some() != other.is_some() {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
     pub fn compare<T: Ord>(&self, this: &T, other: &T) -> Ordering {
         if self == &search::SortOrder::Desc {
             this.cmp(other)
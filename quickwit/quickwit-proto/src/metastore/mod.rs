@@ -81,6 +81,31 @@ pub enum EntityKind {
         /// Index template ID.
         template_id: String,
     },
+    /// An API key.
+    ApiKey {
+        /// API key ID.
+        api_key_id: String,
+    },
+    /// A role.
+    Role {
+        /// Role ID.
+        role_id: String,
+    },
+    /// A point in time.
+    PointInTime {
+        /// Point-in-time key.
+        point_in_time_key: String,
+    },
+    /// An index alias.
+    IndexAlias {
+        /// Alias name.
+        alias: String,
+    },
+    /// A lifecycle policy.
+    LifecyclePolicy {
+        /// Lifecycle policy ID.
+        policy_id: String,
+    },
 }
 
 impl fmt::Display for EntityKind {
@@ -102,6 +127,15 @@ impl fmt::Display for EntityKind {
             EntityKind::IndexTemplate { template_id } => {
                 write!(f, "index template `{}`", template_id)
             }
+            EntityKind::ApiKey { api_key_id } => write!(f, "API key `{api_key_id}`"),
+            EntityKind::Role { role_id } => write!(f, "role `{role_id}`"),
+            EntityKind::PointInTime { point_in_time_key } => {
+                write!(f, "point in time `{point_in_time_key}`")
+            }
+            EntityKind::IndexAlias { alias } => write!(f, "alias `{alias}`"),
+            EntityKind::LifecyclePolicy { policy_id } => {
+                write!(f, "lifecycle policy `{policy_id}`")
+            }
         }
     }
 }
@@ -17,17 +17,51 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use serde_with::formats::PreferMany;
 use serde_with::{serde_as, DefaultOnNull, OneOrMany};
 
 use crate::elastic_query_dsl::{ConvertibleToQueryAst, ElasticQueryDslInner};
 use crate::not_nan_f32::NotNaNf32;
-use crate::query_ast::{self, QueryAst};
+use crate::query_ast::{self, MinimumShouldMatch, QueryAst};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MinimumShouldMatchValue {
+    Int(isize),
+    Str(String),
+}
+
+/// Deserializes the Elasticsearch `minimum_should_match` parameter.
+///
+/// Supports integers (`2`, `-1`) and percentages (`75%`, `-25%`).
+/// Combinations (`3<90%`) are not supported.
+pub(crate) fn deserialize_minimum_should_match<'de, D>(
+    deserializer: D,
+) -> Result<Option<MinimumShouldMatch>, D::Error>
+where D: Deserializer<'de> {
+    let minimum_should_match_str = match MinimumShouldMatchValue::deserialize(deserializer)? {
+        MinimumShouldMatchValue::Int(count) => return Ok(Some(MinimumShouldMatch::Count(count))),
+        MinimumShouldMatchValue::Str(minimum_should_match_str) => minimum_should_match_str,
+    };
+    let minimum_should_match_trimmed = minimum_should_match_str.trim();
+    let minimum_should_match_res =
+        if let Some(percentage_str) = minimum_should_match_trimmed.strip_suffix('%') {
+            percentage_str.parse().map(MinimumShouldMatch::Percentage)
+        } else {
+            minimum_should_match_trimmed
+                .parse()
+                .map(MinimumShouldMatch::Count)
+        };
+    let minimum_should_match = minimum_should_match_res.map_err(|_| {
+        serde::de::Error::custom(format!(
+            "unsupported minimum_should_match `{minimum_should_match_str}`, expected an integer \
+             or a percentage"
+        ))
+    })?;
+    Ok(Some(minimum_should_match))
+}
 
-/// # Unsupported features
-/// - minimum_should_match
-/// - named queries
 #[serde_as]
 #[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
 #[serde(deny_unknown_fields)]
@@ -44,6 +78,8 @@ pub struct BoolQuery {
     #[serde_as(deserialize_as = "DefaultOnNull<OneOrMany<_, PreferMany>>")]
     #[serde(default)]
     filter: Vec<ElasticQueryDslInner>,
+    #[serde(default, deserialize_with = "deserialize_minimum_should_match")]
+    minimum_should_match: Option<MinimumShouldMatch>,
     #[serde(default)]
     pub boost: Option<NotNaNf32>,
 }
@@ -56,9 +92,22 @@ impl BoolQuery {
             must_not: Vec::new(),
             should: children,
             filter: Vec::new(),
+            minimum_should_match: None,
             boost: None,
         }
     }
+
+    // Wraps a single query into a boolean query, in order to boost its score.
+    pub(crate) fn boosted(child: ElasticQueryDslInner, boost: NotNaNf32) -> BoolQuery {
+        BoolQuery {
+            must: vec![child],
+            must_not: Vec::new(),
+            should: Vec::new(),
+            filter: Vec::new(),
+            minimum_should_match: None,
+            boost: Some(boost),
+        }
+    }
 }
 
 fn convert_vec(query_dsls: Vec<ElasticQueryDslInner>) -> anyhow::Result<Vec<QueryAst>> {
@@ -70,13 +119,19 @@ fn convert_vec(query_dsls: Vec<ElasticQueryDslInner>) -> anyhow::Result<Vec<Quer
 
 impl ConvertibleToQueryAst for BoolQuery {
     fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        let should = convert_vec(self.should)?;
+        let minimum_should_match = self
+            .minimum_should_match
+            .map(|minimum_should_match| minimum_should_match.resolve(should.len()));
         let bool_query_ast = query_ast::BoolQuery {
             must: convert_vec(self.must)?,
             must_not: convert_vec(self.must_not)?,
-            should: convert_vec(self.should)?,
+            should,
             filter: convert_vec(self.filter)?,
+            minimum_should_match,
         };
-        Ok(bool_query_ast.into())
+        let query_ast: QueryAst = bool_query_ast.into();
+        Ok(query_ast.boost(self.boost))
     }
 }
 
@@ -90,6 +145,9 @@ impl From<BoolQuery> for ElasticQueryDslInner {
 mod tests {
     use crate::elastic_query_dsl::bool_query::BoolQuery;
     use crate::elastic_query_dsl::term_query::term_query_from_field_value;
+    use crate::elastic_query_dsl::ConvertibleToQueryAst;
+    use crate::not_nan_f32::NotNaNf32;
+    use crate::query_ast::{MinimumShouldMatch, QueryAst};
 
     #[test]
     fn test_dsl_bool_query_deserialize_simple() {
@@ -110,6 +168,7 @@ mod tests {
                 must_not: Vec::new(),
                 should: Vec::new(),
                 filter: Vec::new(),
+                minimum_should_match: None,
                 boost: None,
             }
         );
@@ -129,6 +188,7 @@ mod tests {
                 must_not: Vec::new(),
                 should: Vec::new(),
                 filter: vec![term_query_from_field_value("product_id", "2").into(),],
+                minimum_should_match: None,
                 boost: None,
             }
         );
@@ -151,8 +211,60 @@ mod tests {
                 must_not: Vec::new(),
                 should: Vec::new(),
                 filter: Vec::new(),
+                minimum_should_match: None,
                 boost: None,
             }
         );
     }
+
+    #[test]
+    fn test_dsl_bool_query_minimum_should_match() {
+        let bool_query_json = r#"{
+            "should": [
+                { "term": {"product_id": {"value": "1" }} },
+                { "term": {"product_id": {"value": "2" }} },
+                { "term": {"product_id": {"value": "3" }} },
+                { "term": {"product_id": {"value": "4" }} }
+            ],
+            "minimum_should_match": "-25%"
+        }"#;
+        let bool_query: BoolQuery = serde_json::from_str(bool_query_json).unwrap();
+        assert_eq!(
+            bool_query.minimum_should_match,
+            Some(MinimumShouldMatch::Percentage(-25))
+        );
+        let QueryAst::Bool(bool_query_ast) = bool_query.convert_to_query_ast().unwrap() else {
+            panic!()
+        };
+        assert_eq!(bool_query_ast.minimum_should_match, Some(3));
+
+        let bool_query: BoolQuery =
+            serde_json::from_str(r#"{ "should": [], "minimum_should_match": 2 }"#).unwrap();
+        assert_eq!(
+            bool_query.minimum_should_match,
+            Some(MinimumShouldMatch::Count(2))
+        );
+
+        let deser_error = serde_json::from_str::<BoolQuery>(
+            r#"{ "should": [], "minimum_should_match": "3<90%" }"#,
+        )
+        .unwrap_err();
+        assert!(deser_error
+            .to_string()
+            .contains("unsupported minimum_should_match `3<90%`"));
+    }
+
+    #[test]
+    fn test_dsl_bool_query_boost() {
+        let bool_query: BoolQuery = serde_json::from_str(
+            r#"{ "must": { "term": {"product_id": {"value": "1" }} }, "boost": 2.0 }"#,
+        )
+        .unwrap();
+        let query_ast = bool_query.convert_to_query_ast().unwrap();
+        let QueryAst::Boost { underlying, boost } = query_ast else {
+            panic!("expected a boost query, got {query_ast:?}");
+        };
+        assert_eq!(boost, NotNaNf32::try_from(2.0).unwrap());
+        assert!(matches!(*underlying, QueryAst::Bool(_)));
+    }
 }
@@ -0,0 +1,111 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::Deserialize;
+
+use crate::elastic_query_dsl::{ConvertibleToQueryAst, ElasticQueryDslInner};
+use crate::not_nan_f32::NotNaNf32;
+use crate::query_ast::{self, QueryAst};
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DisMaxQuery {
+    pub(crate) queries: Vec<ElasticQueryDslInner>,
+    #[serde(default)]
+    pub(crate) tie_breaker: Option<NotNaNf32>,
+    #[serde(default)]
+    pub(crate) boost: Option<NotNaNf32>,
+}
+
+impl ConvertibleToQueryAst for DisMaxQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        let disjuncts = self
+            .queries
+            .into_iter()
+            .map(|query| query.convert_to_query_ast())
+            .collect::<anyhow::Result<Vec<QueryAst>>>()?;
+        let disjunction_max_ast: QueryAst = query_ast::DisjunctionMaxQuery {
+            disjuncts,
+            tie_breaker: self.tie_breaker.unwrap_or(NotNaNf32::ZERO),
+        }
+        .into();
+        Ok(disjunction_max_ast.boost(self.boost))
+    }
+}
+
+impl From<DisMaxQuery> for ElasticQueryDslInner {
+    fn from(dis_max_query: DisMaxQuery) -> Self {
+        ElasticQueryDslInner::DisMax(dis_max_query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elastic_query_dsl::ElasticQueryDsl;
+
+    #[test]
+    fn test_dis_max_query() {
+        let dis_max_query: ElasticQueryDsl = serde_json::from_str(
+            r#"{
+                "dis_max": {
+                    "queries": [
+                        { "term": { "title": "quickwit" } },
+                        { "term": { "body": "quickwit" } }
+                    ],
+                    "tie_breaker": 0.7,
+                    "boost": 1.5
+                }
+            }"#,
+        )
+        .unwrap();
+        let query_ast = QueryAst::try_from(dis_max_query).unwrap();
+        let QueryAst::Boost { underlying, boost } = query_ast else {
+            panic!("expected a boost query, got {query_ast:?}");
+        };
+        assert_eq!(boost, NotNaNf32::try_from(1.5).unwrap());
+        let QueryAst::DisjunctionMax(disjunction_max_query) = *underlying else {
+            panic!("expected a disjunction max query");
+        };
+        assert_eq!(disjunction_max_query.disjuncts.len(), 2);
+        assert_eq!(
+            disjunction_max_query.tie_breaker,
+            NotNaNf32::try_from(0.7).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_dis_max_query_default_tie_breaker() {
+        let dis_max_query: DisMaxQuery =
+            serde_json::from_str(r#"{ "queries": [{ "match_all": {} }] }"#).unwrap();
+        let query_ast = dis_max_query.convert_to_query_ast().unwrap();
+        let QueryAst::DisjunctionMax(disjunction_max_query) = query_ast else {
+            panic!("expected a disjunction max query, got {query_ast:?}");
+        };
+        assert_eq!(disjunction_max_query.disjuncts, vec![QueryAst::MatchAll]);
+        assert_eq!(disjunction_max_query.tie_breaker, NotNaNf32::ZERO);
+    }
+
+    #[test]
+    fn test_dis_max_query_unknown_field() {
+        let err = serde_json::from_str::<DisMaxQuery>(r#"{ "queries": [], "_name": "named" }"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown field `_name`"));
+    }
+}
@@ -20,7 +20,7 @@
 use serde::Deserialize;
 
 use crate::elastic_query_dsl::ConvertibleToQueryAst;
-use crate::query_ast::{self, QueryAst};
+use crate::query_ast::{self, BoolQuery, QueryAst};
 
 #[derive(Deserialize, Clone, Eq, PartialEq, Debug)]
 pub struct ExistsQuery {
@@ -35,9 +35,28 @@ impl ConvertibleToQueryAst for ExistsQuery {
     }
 }
 
+/// Matches the documents that do not hold any value for the field. This is the negation of
+/// [`ExistsQuery`], as supported by older versions of Elasticsearch.
+#[derive(Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct MissingQuery {
+    field: String,
+}
+
+impl ConvertibleToQueryAst for MissingQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        let exists_query_ast = ExistsQuery { field: self.field }.convert_to_query_ast()?;
+        Ok(QueryAst::Bool(BoolQuery {
+            must_not: vec![exists_query_ast],
+            ..Default::default()
+        }))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::elastic_query_dsl::exists_query::ExistsQuery;
+    use crate::elastic_query_dsl::exists_query::{ExistsQuery, MissingQuery};
+    use crate::elastic_query_dsl::ConvertibleToQueryAst;
+    use crate::query_ast::{BoolQuery, FieldPresenceQuery, QueryAst};
 
     #[test]
     fn test_dsl_exists_query_deserialize_simple() {
@@ -52,4 +71,20 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_dsl_missing_query_convert_to_query_ast() {
+        let missing_query: MissingQuery =
+            serde_json::from_str(r#"{"field": "attributes.http.status_code"}"#).unwrap();
+        let query_ast = missing_query.convert_to_query_ast().unwrap();
+        assert_eq!(
+            query_ast,
+            QueryAst::Bool(BoolQuery {
+                must_not: vec![QueryAst::FieldPresence(FieldPresenceQuery {
+                    field: "attributes.http.status_code".to_string(),
+                })],
+                ..Default::default()
+            })
+        );
+    }
 }
@@ -0,0 +1,379 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::ops::Bound;
+
+use anyhow::Context;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map as JsonMap, Number as JsonNumber, Value as JsonValue};
+
+use crate::elastic_query_dsl::ConvertibleToQueryAst;
+use crate::not_nan_f32::NotNaNf32;
+use crate::query_ast::{BoolQuery, QueryAst, RangeQuery};
+use crate::JsonLiteral;
+
+/// Matches the documents whose geo point falls within a bounding box.
+///
+/// Quickwit does not have a geo point field type: a geo point field is an object field with `lat`
+/// and `lon` numeric fast fields, so the query is converted into range queries on these fields.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub(crate) struct GeoBoundingBoxQuery {
+    field: String,
+    // The bounding box is validated when the query is converted into a query AST.
+    bounding_box: JsonMap<String, JsonValue>,
+    boost: Option<NotNaNf32>,
+}
+
+impl<'de> Deserialize<'de> for GeoBoundingBoxQuery {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        let mut params = JsonMap::deserialize(deserializer)?;
+        let boost = params
+            .remove("boost")
+            .map(NotNaNf32::deserialize)
+            .transpose()
+            .map_err(D::Error::custom)?;
+        // Geo points are validated strictly and unmapped fields match no documents.
+        for ignored_param in ["validation_method", "ignore_unmapped"] {
+            params.remove(ignored_param);
+        }
+        let mut params_iter = params.into_iter();
+        let Some((field, bounding_box)) = params_iter.next() else {
+            return Err(D::Error::custom(
+                "geo_bounding_box query should have a field",
+            ));
+        };
+        if let Some((other_field, _)) = params_iter.next() {
+            return Err(D::Error::custom(format!(
+                "geo_bounding_box query should have a single field. got several ({field}, \
+                 {other_field}, ...)"
+            )));
+        }
+        let JsonValue::Object(bounding_box) = bounding_box else {
+            return Err(D::Error::custom(format!(
+                "bounding box of field `{field}` should be an object"
+            )));
+        };
+        Ok(GeoBoundingBoxQuery {
+            field,
+            bounding_box,
+            boost,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct BoundingBox {
+    top: f64,
+    left: f64,
+    bottom: f64,
+    right: f64,
+}
+
+impl BoundingBox {
+    fn parse(bounding_box: &JsonMap<String, JsonValue>) -> anyhow::Result<Self> {
+        let get_point = |key: &str| bounding_box.get(key).map(parse_geo_point).transpose();
+        let get_coordinate = |key: &str| -> anyhow::Result<Option<f64>> {
+            bounding_box
+                .get(key)
+                .map(|value| {
+                    value
+                        .as_f64()
+                        .with_context(|| format!("`{key}` should be a number"))
+                })
+                .transpose()
+        };
+        let unknown_keys: Vec<&str> = bounding_box
+            .keys()
+            .map(String::as_str)
+            .filter(|key| {
+                ![
+                    "top_left",
+                    "bottom_right",
+                    "top_right",
+                    "bottom_left",
+                    "top",
+                    "left",
+                    "bottom",
+                    "right",
+                    "wkt",
+                ]
+                .contains(key)
+            })
+            .collect();
+        if !unknown_keys.is_empty() {
+            anyhow::bail!(
+                "unknown bounding box parameters: {}",
+                unknown_keys.join(", ")
+            );
+        }
+        let (top, left, bottom, right) = if let Some(wkt_value) = bounding_box.get("wkt") {
+            if bounding_box.len() > 1 {
+                anyhow::bail!("`wkt` cannot be combined with other bounding box parameters");
+            }
+            let wkt = wkt_value.as_str().context("`wkt` should be a string")?;
+            let [left, right, top, bottom] = parse_wkt_bbox(wkt)?;
+            (top, left, bottom, right)
+        } else if let (Some((top, left)), Some((bottom, right))) =
+            (get_point("top_left")?, get_point("bottom_right")?)
+        {
+            (top, left, bottom, right)
+        } else if let (Some((top, right)), Some((bottom, left))) =
+            (get_point("top_right")?, get_point("bottom_left")?)
+        {
+            (top, left, bottom, right)
+        } else if let (Some(top), Some(left), Some(bottom), Some(right)) = (
+            get_coordinate("top")?,
+            get_coordinate("left")?,
+            get_coordinate("bottom")?,
+            get_coordinate("right")?,
+        ) {
+            (top, left, bottom, right)
+        } else {
+            anyhow::bail!(
+                "bounding box should be defined by `top_left` and `bottom_right`, `top_right` and \
+                 `bottom_left`, `top`, `left`, `bottom`, and `right`, or `wkt`"
+            );
+        };
+        for lat in [top, bottom] {
+            if !(-90.0..=90.0).contains(&lat) {
+                anyhow::bail!("invalid latitude `{lat}`: must be between -90 and 90");
+            }
+        }
+        for lon in [left, right] {
+            if !(-180.0..=180.0).contains(&lon) {
+                anyhow::bail!("invalid longitude `{lon}`: must be between -180 and 180");
+            }
+        }
+        if top < bottom {
+            anyhow::bail!("top latitude `{top}` is below bottom latitude `{bottom}`");
+        }
+        Ok(BoundingBox {
+            top,
+            left,
+            bottom,
+            right,
+        })
+    }
+}
+
+/// Parses a geo point expressed as an object `{"lat": 40.7, "lon": -74.0}`, an array
+/// `[-74.0, 40.7]` (longitude first), a string `"40.7,-74.0"` (latitude first), or a WKT point
+/// `"POINT (-74.0 40.7)"` (longitude first), into a `(lat, lon)` pair.
+fn parse_geo_point(geo_point: &JsonValue) -> anyhow::Result<(f64, f64)> {
+    let parse_number =
+        |number_str: &str| -> anyhow::Result<f64> { Ok(number_str.trim().parse::<f64>()?) };
+    let lat_lon_opt = match geo_point {
+        JsonValue::Object(geo_point_obj) if geo_point_obj.len() == 2 => geo_point_obj
+            .get("lat")
+            .and_then(JsonValue::as_f64)
+            .zip(geo_point_obj.get("lon").and_then(JsonValue::as_f64)),
+        JsonValue::Array(coordinates) if coordinates.len() == 2 => {
+            coordinates[1].as_f64().zip(coordinates[0].as_f64())
+        }
+        JsonValue::String(geo_point_str) => {
+            if let Some(wkt_coordinates) = strip_wkt_keyword(geo_point_str, "POINT") {
+                let coordinates: Vec<&str> = wkt_coordinates.split_whitespace().collect();
+                let [lon, lat] = coordinates[..] else {
+                    anyhow::bail!("invalid WKT point `{geo_point_str}`");
+                };
+                Some((parse_number(lat)?, parse_number(lon)?))
+            } else if let Some((lat, lon)) = geo_point_str.split_once(',') {
+                Some((parse_number(lat)?, parse_number(lon)?))
+            } else {
+                anyhow::bail!(
+                    "invalid geo point `{geo_point_str}`: geohashes are not supported, use \
+                     `lat,lon` instead"
+                );
+            }
+        }
+        _ => None,
+    };
+    lat_lon_opt.with_context(|| format!("invalid geo point `{geo_point}`"))
+}
+
+/// Returns the content of the parentheses of a WKT shape such as `POINT (-74.0 40.7)`.
+fn strip_wkt_keyword<'a>(wkt: &'a str, keyword: &str) -> Option<&'a str> {
+    let wkt = wkt.trim();
+    let keyword_len = keyword.len();
+    if !wkt.get(..keyword_len)?.eq_ignore_ascii_case(keyword) {
+        return None;
+    }
+    wkt[keyword_len..]
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Parses a WKT bounding box `BBOX (left, right, top, bottom)`.
+fn parse_wkt_bbox(wkt: &str) -> anyhow::Result<[f64; 4]> {
+    let invalid_wkt = || anyhow::anyhow!("invalid WKT bounding box `{wkt}`");
+    let coordinates = strip_wkt_keyword(wkt, "BBOX").ok_or_else(invalid_wkt)?;
+    let coordinates: Vec<f64> = coordinates
+        .split(',')
+        .map(|coordinate| coordinate.trim().parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| invalid_wkt())?;
+    coordinates.try_into().map_err(|_| invalid_wkt())
+}
+
+fn coordinate_range_query(field: String, min: f64, max: f64) -> QueryAst {
+    let to_literal = |coordinate: f64| {
+        JsonLiteral::Number(JsonNumber::from_f64(coordinate).expect("coordinates should be finite"))
+    };
+    RangeQuery {
+        field,
+        lower_bound: Bound::Included(to_literal(min)),
+        upper_bound: Bound::Included(to_literal(max)),
+    }
+    .into()
+}
+
+impl ConvertibleToQueryAst for GeoBoundingBoxQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        let BoundingBox {
+            top,
+            left,
+            bottom,
+            right,
+        } = BoundingBox::parse(&self.bounding_box)
+            .with_context(|| format!("invalid geo_bounding_box query on field `{}`", self.field))?;
+        let lat_field = format!("{}.lat", self.field);
+        let lon_field = format!("{}.lon", self.field);
+
+        let lat_query_ast = coordinate_range_query(lat_field, bottom, top);
+        let lon_query_ast = if left <= right {
+            coordinate_range_query(lon_field, left, right)
+        } else {
+            // The bounding box crosses the antimeridian.
+            QueryAst::Bool(BoolQuery {
+                should: vec![
+                    coordinate_range_query(lon_field.clone(), left, 180.0),
+                    coordinate_range_query(lon_field, -180.0, right),
+                ],
+                ..Default::default()
+            })
+        };
+        let query_ast = QueryAst::Bool(BoolQuery {
+            filter: vec![lat_query_ast, lon_query_ast],
+            ..Default::default()
+        });
+        Ok(query_ast.boost(self.boost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn lat_lon_query_ast(lat: (f64, f64), lon_query_ast: QueryAst) -> QueryAst {
+        QueryAst::Bool(BoolQuery {
+            filter: vec![
+                coordinate_range_query("location.lat".to_string(), lat.0, lat.1),
+                lon_query_ast,
+            ],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_geo_bounding_box_query_convert_to_query_ast() {
+        let expected_query_ast = lat_lon_query_ast(
+            (40.01, 40.73),
+            coordinate_range_query("location.lon".to_string(), -74.1, -71.12),
+        );
+        for bounding_box_json in [
+            json!({
+                "top_left": { "lat": 40.73, "lon": -74.1 },
+                "bottom_right": { "lat": 40.01, "lon": -71.12 }
+            }),
+            json!({
+                "top_right": [-71.12, 40.73],
+                "bottom_left": "40.01, -74.1"
+            }),
+            json!({
+                "top_left": "POINT (-74.1 40.73)",
+                "bottom_right": "POINT (-71.12 40.01)"
+            }),
+            json!({ "top": 40.73, "left": -74.1, "bottom": 40.01, "right": -71.12 }),
+            json!({ "wkt": "BBOX (-74.1, -71.12, 40.73, 40.01)" }),
+        ] {
+            let geo_bounding_box_query: GeoBoundingBoxQuery = serde_json::from_value(json!({
+                "location": bounding_box_json,
+                "validation_method": "STRICT"
+            }))
+            .unwrap();
+            assert_eq!(
+                geo_bounding_box_query.convert_to_query_ast().unwrap(),
+                expected_query_ast
+            );
+        }
+    }
+
+    #[test]
+    fn test_geo_bounding_box_query_crossing_antimeridian() {
+        let geo_bounding_box_query: GeoBoundingBoxQuery = serde_json::from_value(json!({
+            "location": { "top": 10.0, "left": 170.0, "bottom": -10.0, "right": -170.0 },
+            "boost": 2.0
+        }))
+        .unwrap();
+        let expected_query_ast = lat_lon_query_ast(
+            (-10.0, 10.0),
+            QueryAst::Bool(BoolQuery {
+                should: vec![
+                    coordinate_range_query("location.lon".to_string(), 170.0, 180.0),
+                    coordinate_range_query("location.lon".to_string(), -180.0, -170.0),
+                ],
+                ..Default::default()
+            }),
+        );
+        assert_eq!(
+            geo_bounding_box_query.convert_to_query_ast().unwrap(),
+            expected_query_ast.boost(Some(NotNaNf32::try_from(2.0).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_geo_bounding_box_query_invalid() {
+        serde_json::from_value::<GeoBoundingBoxQuery>(json!({})).unwrap_err();
+        serde_json::from_value::<GeoBoundingBoxQuery>(json!({
+            "location": { "top": 1.0, "left": 1.0, "bottom": 0.0, "right": 2.0 },
+            "other_location": { "top": 1.0, "left": 1.0, "bottom": 0.0, "right": 2.0 }
+        }))
+        .unwrap_err();
+
+        for invalid_bounding_box_json in [
+            json!({ "top": 1.0, "left": 1.0, "bottom": 0.0 }),
+            json!({ "top": 0.0, "left": 1.0, "bottom": 1.0, "right": 2.0 }),
+            json!({ "top": 91.0, "left": 1.0, "bottom": 0.0, "right": 2.0 }),
+            json!({ "top": 1.0, "left": -181.0, "bottom": 0.0, "right": 2.0 }),
+            json!({ "top_left": "drm3btev3e86", "bottom_right": "drm3btev3e87" }),
+            json!({ "wkt": "BBOX (1.0, 2.0, 1.0)" }),
+            json!({ "wkt": "BBOX (1.0, 2.0, 1.0, 0.0)", "top": 1.0 }),
+            json!({ "top": 1.0, "left": 1.0, "bottom": 0.0, "right": 2.0, "middle": 0.5 }),
+        ] {
+            let geo_bounding_box_query: GeoBoundingBoxQuery =
+                serde_json::from_value(json!({ "location": invalid_bounding_box_json })).unwrap();
+            geo_bounding_box_query.convert_to_query_ast().unwrap_err();
+        }
+    }
+}
@@ -36,6 +36,12 @@ pub(crate) struct MatchBoolPrefixQuery {
 
 impl ConvertibleToQueryAst for MatchBoolPrefixQuery {
     fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        if self.params.fuzziness.is_some() {
+            anyhow::bail!("`fuzziness` is not supported in `match_bool_prefix` queries");
+        }
+        if self.params.minimum_should_match.is_some() {
+            anyhow::bail!("`minimum_should_match` is not supported in `match_bool_prefix` queries");
+        }
         let full_text_params = FullTextParams {
             tokenizer: None,
             mode: crate::query_ast::FullTextMode::BoolPrefix {
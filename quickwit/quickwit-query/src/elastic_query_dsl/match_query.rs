@@ -17,12 +17,15 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
+use crate::elastic_query_dsl::bool_query::deserialize_minimum_should_match;
 use crate::elastic_query_dsl::{
     ConvertibleToQueryAst, ElasticQueryDslInner, StringOrStructForSerialization,
 };
-use crate::query_ast::{FullTextParams, FullTextQuery, QueryAst};
+use crate::query_ast::{
+    FullTextMode, FullTextParams, FullTextQuery, Fuzziness, MinimumShouldMatch, QueryAst,
+};
 use crate::{BooleanOperand, MatchAllOrNone, OneFieldMap};
 
 /// `MatchQuery` as defined in
@@ -47,13 +50,68 @@ pub(crate) struct MatchQueryParams {
     // default). For Elastic, it covers type errors (searching text in an integer field).
     #[serde(default)]
     pub(crate) lenient: bool,
+    #[serde(default, deserialize_with = "deserialize_fuzziness")]
+    pub(crate) fuzziness: Option<Fuzziness>,
+    #[serde(default = "default_fuzzy_transpositions")]
+    pub(crate) fuzzy_transpositions: bool,
+    #[serde(default, deserialize_with = "deserialize_minimum_should_match")]
+    pub(crate) minimum_should_match: Option<MinimumShouldMatch>,
+}
+
+fn default_fuzzy_transpositions() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FuzzinessValue {
+    Distance(u8),
+    Str(String),
+}
+
+/// Deserializes the Elasticsearch `fuzziness` parameter: `AUTO`, or an edit distance
+/// expressed as an integer or a string.
+fn deserialize_fuzziness<'de, D>(deserializer: D) -> Result<Option<Fuzziness>, D::Error>
+where D: Deserializer<'de> {
+    let fuzziness = match FuzzinessValue::deserialize(deserializer)? {
+        FuzzinessValue::Distance(distance) => Fuzziness::Distance(distance),
+        FuzzinessValue::Str(fuzziness_str) if fuzziness_str.eq_ignore_ascii_case("auto") => {
+            Fuzziness::Auto
+        }
+        FuzzinessValue::Str(fuzziness_str) => {
+            let distance: u8 = fuzziness_str.parse().map_err(|_| {
+                serde::de::Error::custom(format!(
+                    "invalid fuzziness `{fuzziness_str}`, expected `AUTO` or an edit distance"
+                ))
+            })?;
+            Fuzziness::Distance(distance)
+        }
+    };
+    Ok(Some(fuzziness))
 }
 
 impl ConvertibleToQueryAst for MatchQuery {
     fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        let mode = if let Some(fuzziness) = self.params.fuzziness {
+            if self.params.minimum_should_match.is_some() {
+                anyhow::bail!(
+                    "`minimum_should_match` is not supported in `match` queries with `fuzziness`"
+                );
+            }
+            FullTextMode::Fuzzy {
+                operator: self.params.operator,
+                fuzziness,
+                transpositions: self.params.fuzzy_transpositions,
+            }
+        } else {
+            FullTextMode::Bool {
+                operator: self.params.operator,
+                minimum_should_match: self.params.minimum_should_match,
+            }
+        };
         let full_text_params = FullTextParams {
             tokenizer: None,
-            mode: self.params.operator.into(),
+            mode,
             zero_terms_query: self.params.zero_terms_query,
         };
         Ok(QueryAst::FullText(FullTextQuery {
@@ -90,6 +148,9 @@ impl From<String> for MatchQueryParams {
             zero_terms_query: Default::default(),
             operator: Default::default(),
             lenient: false,
+            fuzziness: None,
+            fuzzy_transpositions: true,
+            minimum_should_match: None,
         }
     }
 }
@@ -97,7 +158,6 @@ impl From<String> for MatchQueryParams {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::query_ast::FullTextMode;
 
     #[test]
     fn test_deserialize_match_query_string() {
@@ -139,6 +199,9 @@ mod tests {
                 operator: BooleanOperand::And,
                 zero_terms_query: crate::MatchAllOrNone::MatchAll,
                 lenient: false,
+                fuzziness: None,
+                fuzzy_transpositions: true,
+                minimum_should_match: None,
             },
         };
         let ast = match_query.convert_to_query_ast().unwrap();
@@ -156,9 +219,66 @@ mod tests {
         assert_eq!(
             params.mode,
             FullTextMode::Bool {
-                operator: BooleanOperand::And
+                operator: BooleanOperand::And,
+                minimum_should_match: None,
             }
         );
         assert_eq!(params.zero_terms_query, MatchAllOrNone::MatchAll);
     }
+
+    #[test]
+    fn test_match_query_fuzziness() {
+        let match_query: MatchQuery = serde_json::from_str(
+            r#"{"body": {"query": "quikwit", "fuzziness": "AUTO", "fuzzy_transpositions": false}}"#,
+        )
+        .unwrap();
+        let ast = match_query.convert_to_query_ast().unwrap();
+        let QueryAst::FullText(FullTextQuery { params, .. }) = ast else {
+            panic!()
+        };
+        assert_eq!(
+            params.mode,
+            FullTextMode::Fuzzy {
+                operator: BooleanOperand::Or,
+                fuzziness: Fuzziness::Auto,
+                transpositions: false,
+            }
+        );
+
+        for fuzziness_json in ["1", r#""1""#] {
+            let match_query: MatchQuery = serde_json::from_str(&format!(
+                r#"{{"body": {{"query": "quikwit", "fuzziness": {fuzziness_json}}}}}"#
+            ))
+            .unwrap();
+            assert_eq!(match_query.params.fuzziness, Some(Fuzziness::Distance(1)));
+            assert!(match_query.params.fuzzy_transpositions);
+        }
+
+        let deser_error = serde_json::from_str::<MatchQuery>(
+            r#"{"body": {"query": "quikwit", "fuzziness": "AUTO:3,6"}}"#,
+        )
+        .unwrap_err();
+        assert!(deser_error
+            .to_string()
+            .contains("invalid fuzziness `AUTO:3,6`"));
+    }
+
+    #[test]
+    fn test_match_query_minimum_should_match() {
+        let match_query: MatchQuery = serde_json::from_str(
+            r#"{"body": {"query": "quick brown fox", "minimum_should_match": "75%"}}"#,
+        )
+        .unwrap();
+        let ast = match_query.convert_to_query_ast().unwrap();
+        let QueryAst::FullText(FullTextQuery { params, .. }) = ast else {
+            panic!()
+        };
+        assert_eq!(
+            params.mode,
+            FullTextMode::Bool {
+                operator: BooleanOperand::Or,
+                minimum_should_match: Some(MinimumShouldMatch::Percentage(75)),
+            }
+        );
+    }
 }
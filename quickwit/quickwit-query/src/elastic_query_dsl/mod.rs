@@ -17,31 +17,45 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use serde::{Deserialize, Serialize};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value as JsonValue;
 
 mod bool_query;
+mod dis_max_query;
 mod exists_query;
+mod geo_bounding_box_query;
 mod match_bool_prefix;
 mod match_phrase_query;
 mod match_query;
+mod more_like_this_query;
 mod multi_match;
+mod named_query;
 mod one_field_map;
 mod phrase_prefix_query;
 mod query_string_query;
 mod range_query;
+mod regexp_query;
+mod span_near_query;
 mod string_or_struct;
 mod term_query;
 mod terms_query;
 
 use bool_query::BoolQuery;
+use dis_max_query::DisMaxQuery;
+use geo_bounding_box_query::GeoBoundingBoxQuery;
+use more_like_this_query::MoreLikeThisQuery;
+use named_query::extract_named_clauses;
 pub use one_field_map::OneFieldMap;
 use phrase_prefix_query::MatchPhrasePrefixQuery;
 pub(crate) use query_string_query::QueryStringQuery;
 use range_query::RangeQuery;
+use regexp_query::RegexpQuery;
+use span_near_query::SpanNearQuery;
 pub(crate) use string_or_struct::StringOrStructForSerialization;
 use term_query::TermQuery;
 
-use crate::elastic_query_dsl::exists_query::ExistsQuery;
+use crate::elastic_query_dsl::exists_query::{ExistsQuery, MissingQuery};
 use crate::elastic_query_dsl::match_bool_prefix::MatchBoolPrefixQuery;
 use crate::elastic_query_dsl::match_phrase_query::MatchPhraseQuery;
 use crate::elastic_query_dsl::match_query::MatchQuery;
@@ -68,6 +82,7 @@ pub(crate) struct MatchNoneQuery;
 pub(crate) enum ElasticQueryDslInner {
     QueryString(QueryStringQuery),
     Bool(BoolQuery),
+    DisMax(DisMaxQuery),
     Term(TermQuery),
     Terms(TermsQuery),
     MatchAll(MatchAllQuery),
@@ -77,19 +92,62 @@ pub(crate) enum ElasticQueryDslInner {
     MatchPhrase(MatchPhraseQuery),
     MatchPhrasePrefix(MatchPhrasePrefixQuery),
     MultiMatch(MultiMatchQuery),
+    MoreLikeThis(MoreLikeThisQuery),
     Range(RangeQuery),
+    Regexp(RegexpQuery),
+    SpanNear(SpanNearQuery),
     Exists(ExistsQuery),
+    Missing(MissingQuery),
+    GeoBoundingBox(GeoBoundingBoxQuery),
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
-#[serde(transparent)]
-pub struct ElasticQueryDsl(ElasticQueryDslInner);
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ElasticQueryDsl {
+    query: ElasticQueryDslInner,
+    // Clauses named with the `_name` parameter.
+    named_queries: Vec<(String, ElasticQueryDslInner)>,
+}
+
+impl ElasticQueryDsl {
+    /// Returns the clauses of the query named with the `_name` parameter, converted into query
+    /// ASTs.
+    pub fn named_queries(&self) -> anyhow::Result<Vec<(String, QueryAst)>> {
+        self.named_queries
+            .iter()
+            .map(|(name, named_query)| {
+                let query_ast = named_query.clone().convert_to_query_ast()?;
+                Ok((name.clone(), query_ast))
+            })
+            .collect()
+    }
+}
+
+impl<'de> Deserialize<'de> for ElasticQueryDsl {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        let mut query_json = JsonValue::deserialize(deserializer)?;
+        let named_clauses = extract_named_clauses(&mut query_json).map_err(D::Error::custom)?;
+        let query = ElasticQueryDslInner::deserialize(query_json).map_err(D::Error::custom)?;
+        let named_queries = named_clauses
+            .into_iter()
+            .map(|(name, named_clause_json)| {
+                ElasticQueryDslInner::deserialize(named_clause_json)
+                    .map(|named_query| (name, named_query))
+            })
+            .collect::<Result<_, _>>()
+            .map_err(D::Error::custom)?;
+        Ok(ElasticQueryDsl {
+            query,
+            named_queries,
+        })
+    }
+}
 
 impl TryFrom<ElasticQueryDsl> for QueryAst {
     type Error = anyhow::Error;
 
     fn try_from(es_dsl: ElasticQueryDsl) -> anyhow::Result<Self> {
-        es_dsl.0.convert_to_query_ast()
+        es_dsl.query.convert_to_query_ast()
     }
 }
 
@@ -102,6 +160,7 @@ impl ConvertibleToQueryAst for ElasticQueryDslInner {
         match self {
             Self::QueryString(query_string_query) => query_string_query.convert_to_query_ast(),
             Self::Bool(bool_query) => bool_query.convert_to_query_ast(),
+            Self::DisMax(dis_max_query) => dis_max_query.convert_to_query_ast(),
             Self::Term(term_query) => term_query.convert_to_query_ast(),
             Self::Terms(terms_query) => terms_query.convert_to_query_ast(),
             Self::MatchAll(match_all_query) => {
@@ -123,9 +182,16 @@ impl ConvertibleToQueryAst for ElasticQueryDslInner {
                 match_phrase_prefix.convert_to_query_ast()
             }
             Self::Range(range_query) => range_query.convert_to_query_ast(),
+            Self::Regexp(regexp_query) => regexp_query.convert_to_query_ast(),
+            Self::SpanNear(span_near_query) => span_near_query.convert_to_query_ast(),
             Self::Match(match_query) => match_query.convert_to_query_ast(),
             Self::Exists(exists_query) => exists_query.convert_to_query_ast(),
+            Self::Missing(missing_query) => missing_query.convert_to_query_ast(),
             Self::MultiMatch(multi_match_query) => multi_match_query.convert_to_query_ast(),
+            Self::MoreLikeThis(more_like_this_query) => more_like_this_query.convert_to_query_ast(),
+            Self::GeoBoundingBox(geo_bounding_box_query) => {
+                geo_bounding_box_query.convert_to_query_ast()
+            }
         }
     }
 }
@@ -142,13 +208,51 @@ mod tests {
                 "product_id": { "value": "61809" }
             }
         }"#;
-        let query_dsl = serde_json::from_str(term_query_json).unwrap();
-        let ElasticQueryDsl(ElasticQueryDslInner::Term(term_query)) = query_dsl else {
+        let query_dsl: ElasticQueryDsl = serde_json::from_str(term_query_json).unwrap();
+        let ElasticQueryDslInner::Term(term_query) = query_dsl.query else {
             panic!()
         };
         assert_eq!(
             &term_query,
             &term_query_from_field_value("product_id", "61809")
         );
+        assert!(query_dsl.named_queries.is_empty());
+    }
+
+    #[test]
+    fn test_query_dsl_deserialize_named_queries() {
+        let query_json = r#"{
+            "bool": {
+                "should": [
+                    { "term": { "severity": { "value": "ERROR", "_name": "errors" } } },
+                    { "match": { "body": { "query": "timeout", "_name": "timeouts" } } }
+                ]
+            }
+        }"#;
+        let query_dsl: ElasticQueryDsl = serde_json::from_str(query_json).unwrap();
+        let named_queries = query_dsl.named_queries().unwrap();
+        assert_eq!(named_queries.len(), 2);
+        assert_eq!(named_queries[0].0, "errors");
+        assert_eq!(
+            named_queries[0].1,
+            term_query_from_field_value("severity", "ERROR")
+                .convert_to_query_ast()
+                .unwrap()
+        );
+        assert_eq!(named_queries[1].0, "timeouts");
+
+        let QueryAst::Bool(bool_query) = QueryAst::try_from(query_dsl).unwrap() else {
+            panic!()
+        };
+        assert_eq!(bool_query.should.len(), 2);
+    }
+
+    #[test]
+    fn test_query_dsl_deserialize_named_queries_invalid() {
+        let query_json = r#"{ "match_all": { "_name": ["all"] } }"#;
+        let error = serde_json::from_str::<ElasticQueryDsl>(query_json).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("query name must be a string, got `[\"all\"]`"));
     }
 }
@@ -0,0 +1,406 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use serde_with::formats::PreferMany;
+use serde_with::{serde_as, OneOrMany};
+
+use crate::elastic_query_dsl::bool_query::deserialize_minimum_should_match;
+use crate::elastic_query_dsl::{ConvertibleToQueryAst, ElasticQueryDslInner};
+use crate::not_nan_f32::NotNaNf32;
+use crate::query_ast::{
+    self, FullTextMode, FullTextParams, FullTextQuery, MinimumShouldMatch, QueryAst,
+};
+use crate::{BooleanOperand, MatchAllOrNone};
+
+/// Text or document the returned documents should be similar to.
+#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(untagged)]
+pub(crate) enum LikeItem {
+    Text(String),
+    Document(LikeDocument),
+}
+
+/// Document referenced in the `like` or `unlike` parameters.
+///
+/// Only artificial documents are supported: Quickwit documents do not have an id that could be
+/// used to fetch them.
+#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LikeDocument {
+    #[serde(rename = "_index", default)]
+    index: Option<String>,
+    #[serde(rename = "_id", default)]
+    id: Option<String>,
+    #[serde(default)]
+    doc: Option<serde_json::Value>,
+}
+
+fn default_max_query_terms() -> usize {
+    25
+}
+
+fn default_min_term_freq() -> usize {
+    2
+}
+
+fn default_minimum_should_match() -> Option<MinimumShouldMatch> {
+    Some(MinimumShouldMatch::Percentage(30))
+}
+
+/// `MoreLikeThisQuery` as defined in
+/// <https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl-mlt-query.html>
+///
+/// The most frequent terms of the `like` texts and documents are selected, and the query
+/// matches the documents containing a sufficient number of them. Unlike Elasticsearch, the
+/// terms are not weighted by their document frequency in the index.
+#[serde_as]
+#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct MoreLikeThisQuery {
+    fields: Vec<String>,
+    #[serde_as(deserialize_as = "OneOrMany<_, PreferMany>")]
+    like: Vec<LikeItem>,
+    #[serde_as(deserialize_as = "OneOrMany<_, PreferMany>")]
+    #[serde(default)]
+    unlike: Vec<LikeItem>,
+    #[serde(default = "default_max_query_terms")]
+    max_query_terms: usize,
+    #[serde(default = "default_min_term_freq")]
+    min_term_freq: usize,
+    #[serde(default)]
+    min_word_length: usize,
+    // 0 means that the length of the words is not bounded.
+    #[serde(default)]
+    max_word_length: usize,
+    #[serde(default)]
+    stop_words: Vec<String>,
+    #[serde(
+        default = "default_minimum_should_match",
+        deserialize_with = "deserialize_minimum_should_match"
+    )]
+    minimum_should_match: Option<MinimumShouldMatch>,
+    #[serde(default)]
+    boost: Option<NotNaNf32>,
+}
+
+impl From<MoreLikeThisQuery> for ElasticQueryDslInner {
+    fn from(more_like_this_query: MoreLikeThisQuery) -> Self {
+        Self::MoreLikeThis(more_like_this_query)
+    }
+}
+
+/// Appends the text values found at `path` in the document, which can be either a key of the
+/// document or a dot-separated path of nested keys.
+fn collect_texts<'a>(json_value: &'a serde_json::Value, path: &str, texts: &mut Vec<&'a str>) {
+    match json_value {
+        serde_json::Value::String(text) if path.is_empty() => texts.push(text),
+        serde_json::Value::Array(json_values) => {
+            for json_value in json_values {
+                collect_texts(json_value, path, texts);
+            }
+        }
+        serde_json::Value::Object(json_object) if !path.is_empty() => {
+            if let Some(json_value) = json_object.get(path) {
+                collect_texts(json_value, "", texts);
+            }
+            let mut split_idx = 0;
+            while let Some(dot_idx) = path[split_idx..].find('.') {
+                let (key, sub_path) = (
+                    &path[..split_idx + dot_idx],
+                    &path[split_idx + dot_idx + 1..],
+                );
+                if let Some(json_value) = json_object.get(key) {
+                    collect_texts(json_value, sub_path, texts);
+                }
+                split_idx += dot_idx + 1;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the texts of the like items for the given field.
+fn like_texts<'a>(like_items: &'a [LikeItem], field: &str) -> anyhow::Result<Vec<&'a str>> {
+    let mut texts = Vec::new();
+
+    for like_item in like_items {
+        match like_item {
+            LikeItem::Text(text) => texts.push(text.as_str()),
+            LikeItem::Document(LikeDocument { doc: Some(doc), .. }) => {
+                collect_texts(doc, field, &mut texts)
+            }
+            LikeItem::Document(LikeDocument { id: Some(id), .. }) => {
+                anyhow::bail!(
+                    "`more_like_this` query cannot reference document `{id}` by id: documents \
+                     must be provided with the `doc` parameter"
+                );
+            }
+            LikeItem::Document(LikeDocument { index, .. }) => {
+                let index_id = index.as_deref().unwrap_or_default();
+                anyhow::bail!(
+                    "`more_like_this` query document `{index_id}` requires a `doc` parameter"
+                );
+            }
+        }
+    }
+    Ok(texts)
+}
+
+/// Splits the text into lowercased alphanumeric words, like the `default` tokenizer does.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+struct TermCandidate {
+    field_ord: usize,
+    term: String,
+    term_freq: usize,
+    first_position: usize,
+}
+
+impl MoreLikeThisQuery {
+    fn is_valid_word(&self, word: &str, stop_words: &HashSet<String>) -> bool {
+        let num_chars = word.chars().count();
+        num_chars >= self.min_word_length
+            && (self.max_word_length == 0 || num_chars <= self.max_word_length)
+            && !stop_words.contains(word)
+    }
+
+    /// Returns the most frequent terms of the like items, as `(field, term)` pairs.
+    fn select_terms(&self) -> anyhow::Result<Vec<(String, String)>> {
+        let stop_words: HashSet<String> = self
+            .stop_words
+            .iter()
+            .map(|stop_word| stop_word.to_lowercase())
+            .collect();
+        let mut candidates: Vec<TermCandidate> = Vec::new();
+
+        for (field_ord, field) in self.fields.iter().enumerate() {
+            let unlike_terms: HashSet<String> = like_texts(&self.unlike, field)?
+                .into_iter()
+                .flat_map(words)
+                .collect();
+            let mut term_freqs: HashMap<String, (usize, usize)> = HashMap::new();
+
+            for (position, word) in like_texts(&self.like, field)?
+                .into_iter()
+                .flat_map(words)
+                .enumerate()
+            {
+                if !self.is_valid_word(&word, &stop_words) || unlike_terms.contains(&word) {
+                    continue;
+                }
+                term_freqs.entry(word).or_insert((0, position)).0 += 1;
+            }
+            candidates.extend(
+                term_freqs
+                    .into_iter()
+                    .filter(|(_, (term_freq, _))| *term_freq >= self.min_term_freq)
+                    .map(|(term, (term_freq, first_position))| TermCandidate {
+                        field_ord,
+                        term,
+                        term_freq,
+                        first_position,
+                    }),
+            );
+        }
+        candidates.sort_by_key(|candidate| {
+            (
+                std::cmp::Reverse(candidate.term_freq),
+                candidate.field_ord,
+                candidate.first_position,
+            )
+        });
+        let selected_terms = candidates
+            .into_iter()
+            .take(self.max_query_terms)
+            .map(|candidate| (self.fields[candidate.field_ord].clone(), candidate.term))
+            .collect();
+        Ok(selected_terms)
+    }
+}
+
+impl ConvertibleToQueryAst for MoreLikeThisQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        if self.fields.is_empty() {
+            anyhow::bail!("`more_like_this` query requires at least one field");
+        }
+        if self.like.is_empty() {
+            anyhow::bail!("`more_like_this` query requires at least one `like` item");
+        }
+        let selected_terms = self.select_terms()?;
+
+        if selected_terms.is_empty() {
+            return Ok(QueryAst::MatchNone);
+        }
+        let minimum_should_match = self
+            .minimum_should_match
+            .map(|minimum_should_match| minimum_should_match.resolve(selected_terms.len()))
+            .unwrap_or(1)
+            .max(1);
+        let should: Vec<QueryAst> = selected_terms
+            .into_iter()
+            .map(|(field, term)| {
+                FullTextQuery {
+                    field,
+                    text: term,
+                    params: FullTextParams {
+                        tokenizer: None,
+                        mode: FullTextMode::Bool {
+                            operator: BooleanOperand::Or,
+                            minimum_should_match: None,
+                        },
+                        zero_terms_query: MatchAllOrNone::MatchNone,
+                    },
+                    lenient: false,
+                }
+                .into()
+            })
+            .collect();
+        let bool_query = query_ast::BoolQuery {
+            should,
+            minimum_should_match: Some(minimum_should_match),
+            ..Default::default()
+        };
+        Ok(QueryAst::Bool(bool_query).boost(self.boost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selected_terms(query_ast: &QueryAst) -> Vec<(&str, &str)> {
+        let QueryAst::Bool(bool_query) = query_ast else {
+            panic!("expected a bool query, got {query_ast:?}");
+        };
+        bool_query
+            .should
+            .iter()
+            .map(|clause| {
+                let QueryAst::FullText(full_text_query) = clause else {
+                    panic!("expected a full text query, got {clause:?}");
+                };
+                (
+                    full_text_query.field.as_str(),
+                    full_text_query.text.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_more_like_this_query_from_text() {
+        let more_like_this_query: MoreLikeThisQuery = serde_json::from_str(
+            r#"{
+                "fields": ["body"],
+                "like": "Connection timeout to db-1. Retrying: connection timeout, connection refused.",
+                "min_word_length": 3,
+                "stop_words": ["to"]
+            }"#,
+        )
+        .unwrap();
+        let query_ast = more_like_this_query.convert_to_query_ast().unwrap();
+        assert_eq!(
+            selected_terms(&query_ast),
+            [("body", "connection"), ("body", "timeout")]
+        );
+        let QueryAst::Bool(bool_query) = query_ast else {
+            panic!()
+        };
+        assert_eq!(bool_query.minimum_should_match, Some(1));
+    }
+
+    #[test]
+    fn test_more_like_this_query_from_documents() {
+        let more_like_this_query: MoreLikeThisQuery = serde_json::from_str(
+            r#"{
+                "fields": ["title", "resource.service"],
+                "like": [
+                    { "_index": "incidents", "doc": { "title": "Disk full on node", "resource": { "service": "api" } } },
+                    { "doc": { "title": "Disk full on ingest node", "resource.service": "api" } }
+                ],
+                "unlike": "ingest node",
+                "min_term_freq": 2,
+                "max_query_terms": 2,
+                "minimum_should_match": "100%",
+                "boost": 2.0
+            }"#,
+        )
+        .unwrap();
+        let query_ast = more_like_this_query.convert_to_query_ast().unwrap();
+        let QueryAst::Boost { underlying, boost } = query_ast else {
+            panic!()
+        };
+        assert_eq!(boost, NotNaNf32::try_from(2.0).unwrap());
+        assert_eq!(
+            selected_terms(&underlying),
+            [("title", "disk"), ("title", "full")]
+        );
+        let QueryAst::Bool(bool_query) = *underlying else {
+            panic!()
+        };
+        assert_eq!(bool_query.minimum_should_match, Some(2));
+    }
+
+    #[test]
+    fn test_more_like_this_query_no_terms() {
+        let more_like_this_query: MoreLikeThisQuery =
+            serde_json::from_str(r#"{ "fields": ["body"], "like": "all words are unique" }"#)
+                .unwrap();
+        let query_ast = more_like_this_query.convert_to_query_ast().unwrap();
+        assert_eq!(query_ast, QueryAst::MatchNone);
+    }
+
+    #[test]
+    fn test_more_like_this_query_invalid() {
+        let convert_more_like_this = |more_like_this_json: &str| -> String {
+            serde_json::from_str::<MoreLikeThisQuery>(more_like_this_json)
+                .unwrap()
+                .convert_to_query_ast()
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(convert_more_like_this(
+            r#"{ "fields": ["body"], "like": [{ "_index": "logs", "_id": "1" }] }"#
+        )
+        .contains("cannot reference document `1` by id"));
+        assert!(
+            convert_more_like_this(r#"{ "fields": [], "like": "timeout" }"#)
+                .contains("requires at least one field")
+        );
+        assert!(
+            convert_more_like_this(r#"{ "fields": ["body"], "like": [] }"#)
+                .contains("requires at least one `like` item")
+        );
+
+        let deser_error = serde_json::from_str::<MoreLikeThisQuery>(
+            r#"{ "fields": ["body"], "like": "timeout", "min_doc_freq": 5 }"#,
+        )
+        .unwrap_err();
+        assert!(deser_error
+            .to_string()
+            .contains("unknown field `min_doc_freq`"));
+    }
+}
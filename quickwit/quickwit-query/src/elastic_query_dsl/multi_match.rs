@@ -22,6 +22,7 @@ use serde_with::formats::PreferMany;
 use serde_with::{serde_as, OneOrMany};
 
 use crate::elastic_query_dsl::bool_query::BoolQuery;
+use crate::elastic_query_dsl::dis_max_query::DisMaxQuery;
 use crate::elastic_query_dsl::match_bool_prefix::MatchBoolPrefixQuery;
 use crate::elastic_query_dsl::match_phrase_query::{MatchPhraseQuery, MatchPhraseQueryParams};
 use crate::elastic_query_dsl::match_query::{MatchQuery, MatchQueryParams};
@@ -29,9 +30,14 @@ use crate::elastic_query_dsl::phrase_prefix_query::{
     MatchPhrasePrefixQuery, MatchPhrasePrefixQueryParams,
 };
 use crate::elastic_query_dsl::{ConvertibleToQueryAst, ElasticQueryDslInner};
+use crate::not_nan_f32::NotNaNf32;
 
 /// Multi match queries are a bit odd. They end up being expanded into another type of query.
 /// In Quickwit, we operate this expansion in generic way at the time of deserialization.
+///
+/// `best_fields`, `cross_fields`, `phrase` and `phrase_prefix` are expanded into a `dis_max`
+/// query over the per-field queries, while `most_fields` and `bool_prefix` are expanded into a
+/// boolean union.
 #[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
 #[serde(try_from = "MultiMatchQueryForDeserialization")]
 pub struct MultiMatchQuery(Box<ElasticQueryDslInner>);
@@ -53,6 +59,8 @@ struct MultiMatchQueryForDeserialization {
     // default). For Elastic, it covers type errors (searching text in an integer field).
     #[serde(default)]
     lenient: bool,
+    #[serde(default)]
+    tie_breaker: Option<NotNaNf32>,
 }
 
 fn deserialize_match_query_for_one_field(
@@ -98,20 +106,29 @@ fn deserialize_match_query_for_one_field(
     }
 }
 
-fn validate_field_name(field_name: &str) -> Result<(), String> {
-    if field_name.contains('^') {
-        return Err(format!(
-            "Quickwit does not support field boosting in the multi match query fields (got `{}`)",
-            field_name
-        ));
-    }
-    if field_name.contains('*') {
+/// Splits a multi match field of the form `field^boost` into its field name and boost.
+fn parse_field_and_boost(field: &str) -> Result<(&str, Option<NotNaNf32>), String> {
+    if field.contains('*') {
         return Err(format!(
             "Quickwit does not support wildcards in the multi match query fields (got `{}`)",
-            field_name
+            field
         ));
     }
-    Ok(())
+    let Some((field_name, boost_str)) = field.split_once('^') else {
+        return Ok((field, None));
+    };
+    let boost = boost_str
+        .parse::<f32>()
+        .ok()
+        .filter(|boost| *boost >= 0.0)
+        .and_then(|boost| NotNaNf32::try_from(boost).ok())
+        .ok_or_else(|| {
+            format!(
+                "invalid boost in multi match query field `{field}`, expected a non-negative \
+                 number"
+            )
+        })?;
+    Ok((field_name, Some(boost)))
 }
 
 impl TryFrom<MultiMatchQueryForDeserialization> for MultiMatchQuery {
@@ -125,22 +142,31 @@ impl TryFrom<MultiMatchQueryForDeserialization> for MultiMatchQuery {
                  must have at least one field.",
             ));
         }
-        for field in &multi_match_query.fields {
-            validate_field_name(field).map_err(serde::de::Error::custom)?;
-        }
         let mut children = Vec::new();
-        for field in multi_match_query.fields {
+        for field in &multi_match_query.fields {
+            let (field_name, boost_opt) =
+                parse_field_and_boost(field).map_err(serde::de::Error::custom)?;
             let child = deserialize_match_query_for_one_field(
                 multi_match_query.match_type,
-                &field,
+                field_name,
                 multi_match_query.other_parameters.clone(),
             )?;
-            children.push(child);
+            if let Some(boost) = boost_opt {
+                children.push(BoolQuery::boosted(child, boost).into());
+            } else {
+                children.push(child);
+            }
         }
-        let bool_query = BoolQuery::union(children);
-        Ok(MultiMatchQuery(Box::new(ElasticQueryDslInner::Bool(
-            bool_query,
-        ))))
+        let query = if multi_match_query.match_type.is_field_centric_disjunction() {
+            ElasticQueryDslInner::DisMax(DisMaxQuery {
+                queries: children,
+                tie_breaker: multi_match_query.tie_breaker,
+                boost: None,
+            })
+        } else {
+            ElasticQueryDslInner::Bool(BoolQuery::union(children))
+        };
+        Ok(MultiMatchQuery(Box::new(query)))
     }
 }
 
@@ -149,13 +175,28 @@ impl TryFrom<MultiMatchQueryForDeserialization> for MultiMatchQuery {
 pub enum MatchType {
     #[default]
     MostFields,
-    BestFields,  // Not implemented will be converted to MostFields
-    CrossFields, // Not implemented will be converted to MostFields
+    BestFields,
+    // Term-centric blending is not implemented, cross fields is handled like best fields.
+    CrossFields,
     Phrase,
     PhrasePrefix,
     BoolPrefix,
 }
 
+impl MatchType {
+    /// Returns true if the score of a document should be the one of its best matching field
+    /// rather than the sum of the scores of all matching fields.
+    fn is_field_centric_disjunction(self) -> bool {
+        match self {
+            MatchType::BestFields
+            | MatchType::CrossFields
+            | MatchType::Phrase
+            | MatchType::PhrasePrefix => true,
+            MatchType::MostFields | MatchType::BoolPrefix => false,
+        }
+    }
+}
+
 impl ConvertibleToQueryAst for MultiMatchQuery {
     fn convert_to_query_ast(self) -> anyhow::Result<crate::query_ast::QueryAst> {
         self.0.convert_to_query_ast()
@@ -166,6 +207,7 @@ impl ConvertibleToQueryAst for MultiMatchQuery {
 mod tests {
     use super::*;
     use crate::elastic_query_dsl::default_max_expansions;
+    use crate::query_ast::QueryAst;
 
     #[track_caller]
     fn test_multimatch_query_ok_aux<T: Into<ElasticQueryDslInner>>(json: &str, expected: T) {
@@ -199,6 +241,9 @@ mod tests {
                         operator: crate::BooleanOperand::Or,
                         zero_terms_query: Default::default(),
                         lenient: false,
+                        fuzziness: None,
+                        fuzzy_transpositions: true,
+                        minimum_should_match: None,
                     },
                 }
                 .into(),
@@ -209,6 +254,9 @@ mod tests {
                         operator: crate::BooleanOperand::Or,
                         zero_terms_query: Default::default(),
                         lenient: false,
+                        fuzziness: None,
+                        fuzzy_transpositions: true,
+                        minimum_should_match: None,
                     },
                 }
                 .into(),
@@ -221,28 +269,38 @@ mod tests {
             "type": "best_fields",
             "fields": ["title", "body"]
         }"#,
-            BoolQuery::union(vec![
-                MatchQuery {
-                    field: "title".to_string(),
-                    params: MatchQueryParams {
-                        query: "quick brown fox".to_string(),
-                        operator: crate::BooleanOperand::Or,
-                        zero_terms_query: Default::default(),
-                        lenient: false,
-                    },
-                }
-                .into(),
-                MatchQuery {
-                    field: "body".to_string(),
-                    params: MatchQueryParams {
-                        query: "quick brown fox".to_string(),
-                        operator: crate::BooleanOperand::Or,
-                        zero_terms_query: Default::default(),
-                        lenient: false,
-                    },
-                }
-                .into(),
-            ]),
+            DisMaxQuery {
+                queries: vec![
+                    MatchQuery {
+                        field: "title".to_string(),
+                        params: MatchQueryParams {
+                            query: "quick brown fox".to_string(),
+                            operator: crate::BooleanOperand::Or,
+                            zero_terms_query: Default::default(),
+                            lenient: false,
+                            fuzziness: None,
+                            fuzzy_transpositions: true,
+                            minimum_should_match: None,
+                        },
+                    }
+                    .into(),
+                    MatchQuery {
+                        field: "body".to_string(),
+                        params: MatchQueryParams {
+                            query: "quick brown fox".to_string(),
+                            operator: crate::BooleanOperand::Or,
+                            zero_terms_query: Default::default(),
+                            lenient: false,
+                            fuzziness: None,
+                            fuzzy_transpositions: true,
+                            minimum_should_match: None,
+                        },
+                    }
+                    .into(),
+                ],
+                tie_breaker: None,
+                boost: None,
+            },
         );
 
         test_multimatch_query_ok_aux(
@@ -251,28 +309,38 @@ mod tests {
             "type": "cross_fields",
             "fields": ["title", "body"]
         }"#,
-            BoolQuery::union(vec![
-                MatchQuery {
-                    field: "title".to_string(),
-                    params: MatchQueryParams {
-                        query: "quick brown fox".to_string(),
-                        operator: crate::BooleanOperand::Or,
-                        zero_terms_query: Default::default(),
-                        lenient: false,
-                    },
-                }
-                .into(),
-                MatchQuery {
-                    field: "body".to_string(),
-                    params: MatchQueryParams {
-                        query: "quick brown fox".to_string(),
-                        operator: crate::BooleanOperand::Or,
-                        zero_terms_query: Default::default(),
-                        lenient: false,
-                    },
-                }
-                .into(),
-            ]),
+            DisMaxQuery {
+                queries: vec![
+                    MatchQuery {
+                        field: "title".to_string(),
+                        params: MatchQueryParams {
+                            query: "quick brown fox".to_string(),
+                            operator: crate::BooleanOperand::Or,
+                            zero_terms_query: Default::default(),
+                            lenient: false,
+                            fuzziness: None,
+                            fuzzy_transpositions: true,
+                            minimum_should_match: None,
+                        },
+                    }
+                    .into(),
+                    MatchQuery {
+                        field: "body".to_string(),
+                        params: MatchQueryParams {
+                            query: "quick brown fox".to_string(),
+                            operator: crate::BooleanOperand::Or,
+                            zero_terms_query: Default::default(),
+                            lenient: false,
+                            fuzziness: None,
+                            fuzzy_transpositions: true,
+                            minimum_should_match: None,
+                        },
+                    }
+                    .into(),
+                ],
+                tie_breaker: None,
+                boost: None,
+            },
         );
 
         test_multimatch_query_ok_aux(
@@ -281,28 +349,32 @@ mod tests {
             "type": "phrase",
             "fields": ["title", "body"]
         }"#,
-            BoolQuery::union(vec![
-                MatchPhraseQuery {
-                    field: "title".to_string(),
-                    params: MatchPhraseQueryParams {
-                        query: "quick brown fox".to_string(),
-                        zero_terms_query: Default::default(),
-                        analyzer: None,
-                        slop: Default::default(),
-                    },
-                }
-                .into(),
-                MatchPhraseQuery {
-                    field: "body".to_string(),
-                    params: MatchPhraseQueryParams {
-                        query: "quick brown fox".to_string(),
-                        zero_terms_query: Default::default(),
-                        analyzer: None,
-                        slop: Default::default(),
-                    },
-                }
-                .into(),
-            ]),
+            DisMaxQuery {
+                queries: vec![
+                    MatchPhraseQuery {
+                        field: "title".to_string(),
+                        params: MatchPhraseQueryParams {
+                            query: "quick brown fox".to_string(),
+                            zero_terms_query: Default::default(),
+                            analyzer: None,
+                            slop: Default::default(),
+                        },
+                    }
+                    .into(),
+                    MatchPhraseQuery {
+                        field: "body".to_string(),
+                        params: MatchPhraseQueryParams {
+                            query: "quick brown fox".to_string(),
+                            zero_terms_query: Default::default(),
+                            analyzer: None,
+                            slop: Default::default(),
+                        },
+                    }
+                    .into(),
+                ],
+                tie_breaker: None,
+                boost: None,
+            },
         );
 
         test_multimatch_query_ok_aux(
@@ -311,30 +383,34 @@ mod tests {
             "type": "phrase_prefix",
             "fields": ["title", "body"]
         }"#,
-            BoolQuery::union(vec![
-                MatchPhrasePrefixQuery {
-                    field: "title".to_string(),
-                    value: MatchPhrasePrefixQueryParams {
-                        query: "quick brown fox".to_string(),
-                        analyzer: Default::default(),
-                        max_expansions: default_max_expansions(),
-                        slop: Default::default(),
-                        zero_terms_query: Default::default(),
-                    },
-                }
-                .into(),
-                MatchPhrasePrefixQuery {
-                    field: "body".to_string(),
-                    value: MatchPhrasePrefixQueryParams {
-                        query: "quick brown fox".to_string(),
-                        analyzer: Default::default(),
-                        max_expansions: default_max_expansions(),
-                        slop: Default::default(),
-                        zero_terms_query: Default::default(),
-                    },
-                }
-                .into(),
-            ]),
+            DisMaxQuery {
+                queries: vec![
+                    MatchPhrasePrefixQuery {
+                        field: "title".to_string(),
+                        value: MatchPhrasePrefixQueryParams {
+                            query: "quick brown fox".to_string(),
+                            analyzer: Default::default(),
+                            max_expansions: default_max_expansions(),
+                            slop: Default::default(),
+                            zero_terms_query: Default::default(),
+                        },
+                    }
+                    .into(),
+                    MatchPhrasePrefixQuery {
+                        field: "body".to_string(),
+                        value: MatchPhrasePrefixQueryParams {
+                            query: "quick brown fox".to_string(),
+                            analyzer: Default::default(),
+                            max_expansions: default_max_expansions(),
+                            slop: Default::default(),
+                            zero_terms_query: Default::default(),
+                        },
+                    }
+                    .into(),
+                ],
+                tie_breaker: None,
+                boost: None,
+            },
         );
 
         test_multimatch_query_ok_aux(
@@ -351,6 +427,9 @@ mod tests {
                         operator: crate::BooleanOperand::Or,
                         zero_terms_query: Default::default(),
                         lenient: false,
+                        fuzziness: None,
+                        fuzzy_transpositions: true,
+                        minimum_should_match: None,
                     },
                 }
                 .into(),
@@ -361,6 +440,9 @@ mod tests {
                         operator: crate::BooleanOperand::Or,
                         zero_terms_query: Default::default(),
                         lenient: false,
+                        fuzziness: None,
+                        fuzzy_transpositions: true,
+                        minimum_should_match: None,
                     },
                 }
                 .into(),
@@ -382,9 +464,48 @@ mod tests {
             r#"{
                 "query": "quick brown fox",
                 "type": "most_fields",
-                "fields": ["body", "title^3"]
+                "fields": ["body", "title^high"]
+            }"#,
+            "invalid boost in multi match query field `title^high`",
+        );
+    }
+
+    #[test]
+    fn test_multimatch_query_field_boost_and_tie_breaker() {
+        let multi_match_query: MultiMatchQuery = serde_json::from_str(
+            r#"{
+                "query": "quickwit",
+                "type": "best_fields",
+                "fields": ["title^3", "body"],
+                "tie_breaker": 0.3
             }"#,
-            "Quickwit does not support field boosting",
+        )
+        .unwrap();
+        let query_ast = multi_match_query.convert_to_query_ast().unwrap();
+        let QueryAst::DisjunctionMax(disjunction_max_query) = query_ast else {
+            panic!("expected a disjunction max query, got {query_ast:?}");
+        };
+        assert_eq!(
+            disjunction_max_query.tie_breaker,
+            NotNaNf32::try_from(0.3).unwrap()
         );
+        let [title_ast, body_ast] = &disjunction_max_query.disjuncts[..] else {
+            panic!("expected two disjuncts");
+        };
+        let QueryAst::Boost { underlying, boost } = title_ast else {
+            panic!("expected a boost query, got {title_ast:?}");
+        };
+        assert_eq!(*boost, NotNaNf32::try_from(3.0).unwrap());
+        let QueryAst::Bool(bool_query) = &**underlying else {
+            panic!("expected a bool query, got {underlying:?}");
+        };
+        let [QueryAst::FullText(title_full_text_query)] = &bool_query.must[..] else {
+            panic!("expected a full text query, got {:?}", bool_query.must);
+        };
+        assert_eq!(title_full_text_query.field, "title");
+        let QueryAst::FullText(body_full_text_query) = body_ast else {
+            panic!("expected a full text query, got {body_ast:?}");
+        };
+        assert_eq!(body_full_text_query.field, "body");
     }
 }
@@ -0,0 +1,161 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::bail;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+const NAME_PARAM: &str = "_name";
+
+/// Removes the `_name` parameters from a query clause and its sub-clauses, and returns the named
+/// clauses along with their names, in the order they appear in the query.
+///
+/// The named clauses returned are stripped of their `_name` parameters and of those of their
+/// sub-clauses, so they can be deserialized on their own.
+pub(crate) fn extract_named_clauses(
+    clause_json: &mut JsonValue,
+) -> anyhow::Result<Vec<(String, JsonValue)>> {
+    let mut named_clauses = Vec::new();
+    extract_named_clauses_aux(clause_json, &mut named_clauses)?;
+    Ok(named_clauses)
+}
+
+fn extract_named_clauses_aux(
+    clause_json: &mut JsonValue,
+    named_clauses: &mut Vec<(String, JsonValue)>,
+) -> anyhow::Result<()> {
+    let position = named_clauses.len();
+    let name_opt = {
+        // A clause is an object with a single key: the type of the query.
+        let JsonValue::Object(clause_obj) = clause_json else {
+            return Ok(());
+        };
+        if clause_obj.len() != 1 {
+            return Ok(());
+        }
+        let Some((query_type, JsonValue::Object(params_obj))) = clause_obj.iter_mut().next() else {
+            return Ok(());
+        };
+        let mut name_opt = take_name(params_obj)?;
+
+        // Single field queries, such as `term` or `match`, may nest their parameters under the
+        // name of the field.
+        if name_opt.is_none() && params_obj.len() == 1 {
+            if let Some(JsonValue::Object(field_params_obj)) = params_obj.values_mut().next() {
+                name_opt = take_name(field_params_obj)?;
+            }
+        }
+        let sub_clauses_keys: &[&str] = match query_type.as_str() {
+            "bool" => &["must", "must_not", "should", "filter"],
+            "dis_max" => &["queries"],
+            _ => &[],
+        };
+        for sub_clauses_key in sub_clauses_keys {
+            match params_obj.get_mut(*sub_clauses_key) {
+                Some(JsonValue::Array(sub_clauses)) => {
+                    for sub_clause in sub_clauses {
+                        extract_named_clauses_aux(sub_clause, named_clauses)?;
+                    }
+                }
+                Some(sub_clause) => extract_named_clauses_aux(sub_clause, named_clauses)?,
+                None => {}
+            }
+        }
+        name_opt
+    };
+    if let Some(name) = name_opt {
+        named_clauses.insert(position, (name, clause_json.clone()));
+    }
+    Ok(())
+}
+
+fn take_name(params_obj: &mut JsonMap<String, JsonValue>) -> anyhow::Result<Option<String>> {
+    match params_obj.remove(NAME_PARAM) {
+        Some(JsonValue::String(name)) => Ok(Some(name)),
+        Some(name_json) => bail!("query name must be a string, got `{name_json}`"),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_extract_named_clauses() {
+        let mut query_json = json!({
+            "bool": {
+                "must": {"match": {"body": {"query": "failed", "_name": "failed"}}},
+                "should": [
+                    {"term": {"severity": {"value": "ERROR", "_name": "error"}}},
+                    {"terms": {"host": ["a", "b"], "_name": "hosts"}},
+                    {"match_all": {}}
+                ],
+                "_name": "all"
+            }
+        });
+        let named_clauses = extract_named_clauses(&mut query_json).unwrap();
+        let expected_query_json = json!({
+            "bool": {
+                "must": {"match": {"body": {"query": "failed"}}},
+                "should": [
+                    {"term": {"severity": {"value": "ERROR"}}},
+                    {"terms": {"host": ["a", "b"]}},
+                    {"match_all": {}}
+                ]
+            }
+        });
+        assert_eq!(query_json, expected_query_json);
+        assert_eq!(
+            named_clauses,
+            [
+                ("all".to_string(), expected_query_json),
+                (
+                    "failed".to_string(),
+                    json!({"match": {"body": {"query": "failed"}}})
+                ),
+                (
+                    "error".to_string(),
+                    json!({"term": {"severity": {"value": "ERROR"}}})
+                ),
+                ("hosts".to_string(), json!({"terms": {"host": ["a", "b"]}})),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_named_clauses_none() {
+        let mut query_json = json!({"term": {"severity": "ERROR"}});
+        let named_clauses = extract_named_clauses(&mut query_json).unwrap();
+        assert!(named_clauses.is_empty());
+
+        let mut query_json = json!({"match": {"body": "foo"}});
+        let named_clauses = extract_named_clauses(&mut query_json).unwrap();
+        assert!(named_clauses.is_empty());
+        assert_eq!(query_json, json!({"match": {"body": "foo"}}));
+    }
+
+    #[test]
+    fn test_extract_named_clauses_invalid_name() {
+        let mut query_json = json!({"match_all": {"_name": 1}});
+        let error = extract_named_clauses(&mut query_json).unwrap_err();
+        assert_eq!(error.to_string(), "query name must be a string, got `1`");
+    }
+}
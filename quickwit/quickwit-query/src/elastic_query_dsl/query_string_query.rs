@@ -45,6 +45,10 @@ pub(crate) struct QueryStringQuery {
     // this option.
     #[serde(default)]
     lenient: bool,
+    // Contrary to Elasticsearch, leading wildcards are disabled by default, as they require
+    // scanning the whole term dictionary of the targeted fields.
+    #[serde(default)]
+    allow_leading_wildcard: Option<bool>,
 }
 
 impl ConvertibleToQueryAst for QueryStringQuery {
@@ -61,6 +65,7 @@ impl ConvertibleToQueryAst for QueryStringQuery {
             default_fields,
             default_operator: self.default_operator,
             lenient: self.lenient,
+            allow_leading_wildcard: self.allow_leading_wildcard.unwrap_or(false),
         };
         Ok(user_text_query.into())
     }
@@ -81,6 +86,7 @@ mod tests {
             default_field: None,
             boost: None,
             lenient: false,
+            allow_leading_wildcard: None,
         };
         let QueryAst::UserInput(user_input_query) =
             query_string_query.convert_to_query_ast().unwrap()
@@ -103,6 +109,7 @@ mod tests {
             default_field: Some("hello".to_string()),
             boost: None,
             lenient: false,
+            allow_leading_wildcard: None,
         };
         let QueryAst::UserInput(user_input_query) =
             query_string_query.convert_to_query_ast().unwrap()
@@ -125,6 +132,7 @@ mod tests {
             default_field: Some("hello".to_string()),
             boost: None,
             lenient: false,
+            allow_leading_wildcard: None,
         };
         let err_msg = query_string_query
             .convert_to_query_ast()
@@ -142,6 +150,7 @@ mod tests {
             default_operator: crate::BooleanOperand::And,
             boost: None,
             lenient: false,
+            allow_leading_wildcard: None,
         };
         let QueryAst::UserInput(user_input_query) =
             query_string_query.convert_to_query_ast().unwrap()
@@ -160,6 +169,7 @@ mod tests {
             default_operator: crate::BooleanOperand::Or,
             boost: None,
             lenient: false,
+            allow_leading_wildcard: None,
         };
         let QueryAst::UserInput(user_input_query) =
             query_string_query.convert_to_query_ast().unwrap()
@@ -179,6 +189,7 @@ mod tests {
             default_operator: crate::BooleanOperand::Or,
             boost: None,
             lenient: false,
+            allow_leading_wildcard: None,
         };
         let QueryAst::UserInput(user_input_query) =
             query_string_query.convert_to_query_ast().unwrap()
@@ -203,8 +214,23 @@ mod tests {
             default_fields,
             default_operator,
             lenient: _,
+            allow_leading_wildcard: _,
         }) if user_text == "hello world"
             && default_operator == BooleanOperand::Or
             && default_fields == Some(vec!["text".to_string()])));
     }
+
+    #[test]
+    fn test_build_query_string_allow_leading_wildcard() {
+        let query_string_query: QueryStringQuery = serde_json::from_str(
+            r#"{ "query": "*ond", "default_field": "title", "allow_leading_wildcard": true }"#,
+        )
+        .unwrap();
+        let QueryAst::UserInput(user_input_query) =
+            query_string_query.convert_to_query_ast().unwrap()
+        else {
+            panic!();
+        };
+        assert!(user_input_query.allow_leading_wildcard);
+    }
 }
@@ -18,10 +18,14 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::ops::Bound;
-use std::str::FromStr;
 
-use quickwit_datetime::StrptimeParser;
+use quickwit_datetime::{
+    parse_date_time_str, resolve_java_datetime_format_alias, DateMathParser, DateMathRounding,
+    DateTimeInputFormat, DateTimeOutputFormat,
+};
 use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use time::OffsetDateTime;
 
 use crate::elastic_query_dsl::one_field_map::OneFieldMap;
 use crate::elastic_query_dsl::ConvertibleToQueryAst;
@@ -59,19 +63,46 @@ impl ConvertibleToQueryAst for RangeQuery {
             boost,
             format,
         } = self.value;
-        let (gt, gte, lt, lte) = if let Some(JsonLiteral::String(fmt)) = format {
-            let parser = StrptimeParser::from_str(&fmt).map_err(|reason| {
-                anyhow::anyhow!("failed to create parser from : {}; reason: {}", fmt, reason)
-            })?;
-            (
-                gt.map(|v| parse_and_convert(v, &parser)).transpose()?,
-                gte.map(|v| parse_and_convert(v, &parser)).transpose()?,
-                lt.map(|v| parse_and_convert(v, &parser)).transpose()?,
-                lte.map(|v| parse_and_convert(v, &parser)).transpose()?,
-            )
-        } else {
-            (gt, gte, lt, lte)
+        let date_time_formats_opt: Option<Vec<DateTimeInputFormat>> =
+            if let Some(JsonLiteral::String(java_date_format)) = format {
+                // Elasticsearch accepts several formats separated by `||`.
+                let date_time_formats = java_date_format
+                    .split("||")
+                    .map(|java_date_format| {
+                        resolve_java_datetime_format_alias(java_date_format.trim()).map_err(
+                            |reason| {
+                                anyhow::anyhow!(
+                                    "failed to create parser from : {}; reason: {}",
+                                    java_date_format,
+                                    reason
+                                )
+                            },
+                        )
+                    })
+                    .collect::<anyhow::Result<_>>()?;
+                Some(date_time_formats)
+            } else {
+                None
+            };
+        let date_math_parser = DateMathParser::new(OffsetDateTime::now_utc());
+        // Without a format, the type of the field is unknown here, so the bounds are passed
+        // as is and date math expressions are resolved when the query is built for a date field.
+        let convert = |literal: JsonLiteral, rounding: DateMathRounding| {
+            let Some(date_time_formats) = date_time_formats_opt.as_deref() else {
+                return Ok(literal);
+            };
+            parse_and_convert(literal, date_time_formats, &date_math_parser, rounding)
         };
+        let gt = gt.map(|gt| convert(gt, DateMathRounding::Up)).transpose()?;
+        let gte = gte
+            .map(|gte| convert(gte, DateMathRounding::Down))
+            .transpose()?;
+        let lt = lt
+            .map(|lt| convert(lt, DateMathRounding::Down))
+            .transpose()?;
+        let lte = lte
+            .map(|lte| convert(lte, DateMathRounding::Up))
+            .transpose()?;
 
         let range_query_ast = crate::query_ast::RangeQuery {
             field,
@@ -97,41 +128,69 @@ impl ConvertibleToQueryAst for RangeQuery {
     }
 }
 
-fn parse_and_convert(literal: JsonLiteral, parser: &StrptimeParser) -> anyhow::Result<JsonLiteral> {
-    if let JsonLiteral::String(date_time_str) = literal {
-        let parsed_date_time = parser
-            .parse_date_time(&date_time_str)
-            .map_err(|reason| anyhow::anyhow!("Failed to parse date time: {}", reason))?;
-        Ok(JsonLiteral::String(parsed_date_time.to_string()))
+/// Parses a date time string, which can be a date math expression such as `now-1h/d`, using the
+/// formats of the range query, and converts it into a RFC 3339 date time string, which is
+/// understood regardless of the input formats of the date field.
+fn parse_and_convert(
+    literal: JsonLiteral,
+    date_time_formats: &[DateTimeInputFormat],
+    date_math_parser: &DateMathParser,
+    rounding: DateMathRounding,
+) -> anyhow::Result<JsonLiteral> {
+    let JsonLiteral::String(date_time_str) = literal else {
+        return Ok(literal);
+    };
+    let parsed_date_time = if DateMathParser::is_date_math_expression(&date_time_str) {
+        date_math_parser
+            .parse(&date_time_str, date_time_formats, rounding)
+            .map_err(|reason| anyhow::anyhow!("Failed to parse date math: {}", reason))?
     } else {
-        Ok(literal)
-    }
+        parse_date_time_str(&date_time_str, date_time_formats)
+            .map_err(|reason| anyhow::anyhow!("Failed to parse date time: {}", reason))?
+    };
+    let JsonValue::String(rfc3339_date_time_str) = DateTimeOutputFormat::Rfc3339
+        .format_to_json(parsed_date_time)
+        .map_err(|reason| anyhow::anyhow!("Failed to format date time: {}", reason))?
+    else {
+        unreachable!("RFC 3339 date times are formatted as strings");
+    };
+    Ok(JsonLiteral::String(rfc3339_date_time_str))
 }
 
 #[cfg(test)]
 mod tests {
+    use std::ops::Bound;
     use std::str::FromStr;
 
-    use quickwit_datetime::StrptimeParser;
+    use quickwit_datetime::{
+        DateMathParser, DateMathRounding, DateTimeInputFormat, StrptimeParser,
+    };
+    use time::macros::datetime;
 
+    use super::{RangeQuery, RangeQueryParams};
     use crate::elastic_query_dsl::range_query::parse_and_convert;
+    use crate::elastic_query_dsl::ConvertibleToQueryAst;
+    use crate::query_ast::QueryAst;
     use crate::JsonLiteral;
 
     #[test]
     fn test_parse_and_convert() -> anyhow::Result<()> {
-        let parser = StrptimeParser::from_str("%Y-%m-%d %H:%M:%S").unwrap();
+        let parser = [DateTimeInputFormat::Strptime(
+            StrptimeParser::from_str("%Y-%m-%d %H:%M:%S").unwrap(),
+        )];
+        let date_math_parser = DateMathParser::new(datetime!(2024-02-14 18:40:19 UTC));
 
         // valid datetime
         let input = JsonLiteral::String("2022-12-30 05:45:00".to_string());
-        let result = parse_and_convert(input, &parser)?;
+        let result = parse_and_convert(input, &parser, &date_math_parser, DateMathRounding::Down)?;
         assert_eq!(
             result,
-            JsonLiteral::String("2022-12-30 5:45:00.0 +00:00:00".to_string())
+            JsonLiteral::String("2022-12-30T05:45:00Z".to_string())
         );
 
         // invalid datetime
         let input = JsonLiteral::String("invalid datetime".to_string());
-        let result = parse_and_convert(input, &parser);
+        let result = parse_and_convert(input, &parser, &date_math_parser, DateMathRounding::Down);
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -140,9 +199,81 @@ mod tests {
 
         // non_string(number) input
         let input = JsonLiteral::Number(27.into());
-        let result = parse_and_convert(input.clone(), &parser)?;
+        let result = parse_and_convert(
+            input.clone(),
+            &parser,
+            &date_math_parser,
+            DateMathRounding::Down,
+        )?;
         assert_eq!(result, input);
 
+        // date math
+        let input = JsonLiteral::String("now-1d/d".to_string());
+        let result = parse_and_convert(input, &parser, &date_math_parser, DateMathRounding::Up)?;
+        assert_eq!(
+            result,
+            JsonLiteral::String("2024-02-13T23:59:59.999999999Z".to_string())
+        );
+
+        // date math with an anchor date
+        let input = JsonLiteral::String("2022-12-30 05:45:00||+1M/M".to_string());
+        let result = parse_and_convert(input, &parser, &date_math_parser, DateMathRounding::Down)?;
+        assert_eq!(
+            result,
+            JsonLiteral::String("2023-01-01T00:00:00Z".to_string())
+        );
+
         Ok(())
     }
+
+    #[test]
+    fn test_range_query_with_java_date_formats() {
+        let range_query = RangeQuery {
+            field: "timestamp".to_string(),
+            value: RangeQueryParams {
+                gte: Some(JsonLiteral::String("2022-12-30".to_string())),
+                lt: Some(JsonLiteral::String("1672531200000".to_string())),
+                format: Some(JsonLiteral::String(
+                    "strict_date_time||yyyy-MM-dd||epoch_millis".to_string(),
+                )),
+                ..Default::default()
+            },
+        };
+        let QueryAst::Range(range_query_ast) = range_query.convert_to_query_ast().unwrap() else {
+            panic!("expected a range query");
+        };
+        assert_eq!(
+            range_query_ast.lower_bound,
+            Bound::Included(JsonLiteral::String("2022-12-30T00:00:00Z".to_string()))
+        );
+        assert_eq!(
+            range_query_ast.upper_bound,
+            Bound::Excluded(JsonLiteral::String("2023-01-01T00:00:00Z".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_range_query_without_format_leaves_bounds_untouched() {
+        // Without a format, the field may not be a date field, so date math is only resolved when
+        // the query is built against the schema.
+        let range_query = RangeQuery {
+            field: "host".to_string(),
+            value: RangeQueryParams {
+                gte: Some(JsonLiteral::String("nowhere".to_string())),
+                lt: Some(JsonLiteral::String("a||b".to_string())),
+                ..Default::default()
+            },
+        };
+        let QueryAst::Range(range_query_ast) = range_query.convert_to_query_ast().unwrap() else {
+            panic!("expected a range query");
+        };
+        assert_eq!(
+            range_query_ast.lower_bound,
+            Bound::Included(JsonLiteral::String("nowhere".to_string()))
+        );
+        assert_eq!(
+            range_query_ast.upper_bound,
+            Bound::Excluded(JsonLiteral::String("a||b".to_string()))
+        );
+    }
 }
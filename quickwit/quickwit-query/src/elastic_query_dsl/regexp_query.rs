@@ -0,0 +1,134 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::Deserialize;
+
+use super::StringOrStructForSerialization;
+use crate::elastic_query_dsl::one_field_map::OneFieldMap;
+use crate::elastic_query_dsl::{ConvertibleToQueryAst, ElasticQueryDslInner};
+use crate::not_nan_f32::NotNaNf32;
+use crate::query_ast::{self, QueryAst};
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(from = "OneFieldMap<StringOrStructForSerialization<RegexpQueryParams>>")]
+pub(crate) struct RegexpQuery {
+    pub(crate) field: String,
+    pub(crate) params: RegexpQueryParams,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RegexpQueryParams {
+    value: String,
+    #[serde(default)]
+    case_insensitive: bool,
+    #[serde(default)]
+    boost: Option<NotNaNf32>,
+}
+
+impl From<String> for RegexpQueryParams {
+    fn from(value: String) -> RegexpQueryParams {
+        RegexpQueryParams {
+            value,
+            case_insensitive: false,
+            boost: None,
+        }
+    }
+}
+
+impl From<OneFieldMap<StringOrStructForSerialization<RegexpQueryParams>>> for RegexpQuery {
+    fn from(one_field_map: OneFieldMap<StringOrStructForSerialization<RegexpQueryParams>>) -> Self {
+        RegexpQuery {
+            field: one_field_map.field,
+            params: one_field_map.value.inner,
+        }
+    }
+}
+
+impl From<RegexpQuery> for ElasticQueryDslInner {
+    fn from(regexp_query: RegexpQuery) -> Self {
+        Self::Regexp(regexp_query)
+    }
+}
+
+impl ConvertibleToQueryAst for RegexpQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        let RegexpQueryParams {
+            value,
+            case_insensitive,
+            boost,
+        } = self.params;
+        let regex_ast: QueryAst = query_ast::RegexQuery {
+            field: self.field,
+            regex: value,
+            case_insensitive,
+        }
+        .into();
+        Ok(regex_ast.boost(boost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regexp_query_deserialization() {
+        let regexp_query: RegexpQuery = serde_json::from_str(
+            r#"{ "user.id": { "value": "k.*y", "case_insensitive": true, "boost": 2.0 } }"#,
+        )
+        .unwrap();
+        let query_ast = regexp_query.convert_to_query_ast().unwrap();
+        let QueryAst::Boost { underlying, boost } = query_ast else {
+            panic!()
+        };
+        assert_eq!(boost, NotNaNf32::try_from(2.0).unwrap());
+        assert_eq!(
+            *underlying,
+            QueryAst::Regex(query_ast::RegexQuery {
+                field: "user.id".to_string(),
+                regex: "k.*y".to_string(),
+                case_insensitive: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_regexp_query_deserialization_in_short_format() {
+        let regexp_query: RegexpQuery = serde_json::from_str(r#"{ "user.id": "k.*y" }"#).unwrap();
+        let query_ast = regexp_query.convert_to_query_ast().unwrap();
+        assert_eq!(
+            query_ast,
+            QueryAst::Regex(query_ast::RegexQuery {
+                field: "user.id".to_string(),
+                regex: "k.*y".to_string(),
+                case_insensitive: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_regexp_query_deserialization_unsupported_parameter() {
+        let error = serde_json::from_str::<RegexpQuery>(
+            r#"{ "user.id": { "value": "k.*y", "rewrite": "constant_score" } }"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("unknown field `rewrite`"));
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::Deserialize;
+
+use crate::elastic_query_dsl::term_query::{TermQuery, TermQueryParams};
+use crate::elastic_query_dsl::{ConvertibleToQueryAst, ElasticQueryDslInner};
+use crate::not_nan_f32::NotNaNf32;
+use crate::query_ast::{FullTextMode, FullTextParams, FullTextQuery, QueryAst};
+use crate::MatchAllOrNone;
+
+/// Span queries accepted as clauses of a `span_near` query.
+#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum SpanQuery {
+    SpanTerm(TermQuery),
+}
+
+fn default_in_order() -> bool {
+    true
+}
+
+/// `SpanNearQuery` as defined in
+/// <https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl-span-near-query.html>
+///
+/// The query is executed as a phrase query with slop, so the clauses must target
+/// the same field and match in order.
+#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct SpanNearQuery {
+    clauses: Vec<SpanQuery>,
+    #[serde(default)]
+    slop: u32,
+    #[serde(default = "default_in_order")]
+    in_order: bool,
+    #[serde(default)]
+    boost: Option<NotNaNf32>,
+}
+
+impl From<SpanNearQuery> for ElasticQueryDslInner {
+    fn from(span_near_query: SpanNearQuery) -> Self {
+        Self::SpanNear(span_near_query)
+    }
+}
+
+impl ConvertibleToQueryAst for SpanNearQuery {
+    fn convert_to_query_ast(self) -> anyhow::Result<QueryAst> {
+        if !self.in_order {
+            anyhow::bail!("`span_near` queries with `in_order` set to false are not supported");
+        }
+        let mut field_opt: Option<String> = None;
+        let mut values: Vec<String> = Vec::with_capacity(self.clauses.len());
+        for clause in self.clauses {
+            let SpanQuery::SpanTerm(TermQuery {
+                field,
+                value: TermQueryParams { value, boost },
+            }) = clause;
+            if boost.is_some() {
+                anyhow::bail!(
+                    "boosting `span_term` clauses of a `span_near` query is not supported"
+                );
+            }
+            match &field_opt {
+                Some(previous_field) if previous_field != &field => {
+                    anyhow::bail!(
+                        "all the clauses of a `span_near` query must target the same field (got \
+                         `{previous_field}` and `{field}`)"
+                    );
+                }
+                Some(_) => {}
+                None => field_opt = Some(field),
+            }
+            values.push(value);
+        }
+        let Some(field) = field_opt else {
+            anyhow::bail!("`span_near` query requires at least one clause");
+        };
+        let full_text_params = FullTextParams {
+            tokenizer: None,
+            mode: FullTextMode::Phrase { slop: self.slop },
+            zero_terms_query: MatchAllOrNone::MatchNone,
+        };
+        let phrase_ast: QueryAst = FullTextQuery {
+            field,
+            text: values.join(" "),
+            params: full_text_params,
+            lenient: false,
+        }
+        .into();
+        Ok(phrase_ast.boost(self.boost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_near_query() {
+        let span_near_query: SpanNearQuery = serde_json::from_str(
+            r#"{
+                "clauses": [
+                    { "span_term": { "message": "error" } },
+                    { "span_term": { "message": { "value": "timeout" } } }
+                ],
+                "slop": 5
+            }"#,
+        )
+        .unwrap();
+        let query_ast = span_near_query.convert_to_query_ast().unwrap();
+        let QueryAst::FullText(full_text_query) = query_ast else {
+            panic!()
+        };
+        assert_eq!(full_text_query.field, "message");
+        assert_eq!(full_text_query.text, "error timeout");
+        assert_eq!(
+            full_text_query.params.mode,
+            FullTextMode::Phrase { slop: 5 }
+        );
+    }
+
+    #[test]
+    fn test_span_near_query_invalid() {
+        let convert_span_near = |span_near_json: &str| -> String {
+            serde_json::from_str::<SpanNearQuery>(span_near_json)
+                .unwrap()
+                .convert_to_query_ast()
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(convert_span_near(
+            r#"{
+                "clauses": [
+                    { "span_term": { "message": "error" } },
+                    { "span_term": { "title": "timeout" } }
+                ]
+            }"#
+        )
+        .contains("must target the same field"));
+        assert!(convert_span_near(
+            r#"{
+                "clauses": [
+                    { "span_term": { "message": "error" } },
+                    { "span_term": { "message": "timeout" } }
+                ],
+                "in_order": false
+            }"#
+        )
+        .contains("`in_order` set to false are not supported"));
+        assert!(convert_span_near(r#"{ "clauses": [] }"#).contains("at least one clause"));
+
+        let deser_error = serde_json::from_str::<SpanNearQuery>(
+            r#"{ "clauses": [ { "span_or": { "clauses": [] } } ] }"#,
+        )
+        .unwrap_err();
+        assert!(deser_error
+            .to_string()
+            .contains("unknown variant `span_or`"));
+    }
+}
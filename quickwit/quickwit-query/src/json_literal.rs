@@ -26,7 +26,7 @@ use quickwit_datetime::{parse_date_time_str, parse_timestamp, DateTimeInputForma
 use serde::{Deserialize, Serialize};
 use tantivy::schema::IntoIpv6Addr;
 
-fn get_default_date_time_format() -> &'static [DateTimeInputFormat] {
+pub(crate) fn get_default_date_time_format() -> &'static [DateTimeInputFormat] {
     static DEFAULT_DATE_TIME_FORMATS: OnceCell<Vec<DateTimeInputFormat>> = OnceCell::new();
     DEFAULT_DATE_TIME_FORMATS
         .get_or_init(|| {
@@ -42,12 +42,13 @@ pub(crate) use not_nan_f32::NotNaNf32;
 pub use query_ast::utils::find_field_or_hit_dynamic;
 use serde::{Deserialize, Serialize};
 pub use tantivy::query::Query as TantivyQuery;
-#[cfg(feature = "multilang")]
-pub use tokenizers::MultiLangTokenizer;
 pub use tokenizers::{
     create_default_quickwit_tokenizer_manager, get_quickwit_fastfield_normalizer_manager,
-    CodeTokenizer, DEFAULT_REMOVE_TOKEN_LENGTH,
+    CharFilter, CharFilterTokenizer, CodeTokenizer, EdgeNgramFilter, PathHierarchyTokenizer,
+    PatternTokenizer, SynonymFilter, DEFAULT_REMOVE_TOKEN_LENGTH,
 };
+#[cfg(feature = "multilang")]
+pub use tokenizers::{MultiLangTokenizer, MultiLangTokenizerConfig};
 
 #[derive(Serialize, Deserialize, Debug, Default, Copy, Clone, Eq, PartialEq)]
 pub enum BooleanOperand {
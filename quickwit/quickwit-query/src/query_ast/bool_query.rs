@@ -26,7 +26,6 @@ use crate::tokenizers::TokenizerManager;
 use crate::InvalidQuery;
 
 /// # Unsupported features
-/// - minimum_should_match
 /// - named queries
 ///
 /// Edge cases of BooleanQuery are not obvious,
@@ -48,6 +47,43 @@ pub struct BoolQuery {
     pub should: Vec<QueryAst>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub filter: Vec<QueryAst>,
+    /// Minimum number of should clauses that must match.
+    ///
+    /// If not set, should clauses are optional when the query has must or filter
+    /// clauses, and at least one of them must match otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum_should_match: Option<usize>,
+}
+
+/// Number of should clauses that must match, expressed relatively to the number of
+/// should clauses, as in Elasticsearch.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum MinimumShouldMatch {
+    // A positive count is the number of clauses that must match.
+    // A negative count is the number of clauses that are allowed not to match.
+    Count(isize),
+    // Same as `Count`, expressed as a percentage of the number of clauses, rounded down.
+    Percentage(isize),
+}
+
+impl MinimumShouldMatch {
+    /// Returns the number of should clauses that must match, given the number of should
+    /// clauses of the query.
+    pub fn resolve(self, num_should_clauses: usize) -> usize {
+        let num_should_clauses = num_should_clauses as isize;
+        let minimum_should_match = match self {
+            MinimumShouldMatch::Count(count) if count >= 0 => count,
+            MinimumShouldMatch::Count(count) => num_should_clauses + count,
+            MinimumShouldMatch::Percentage(percentage) if percentage >= 0 => {
+                num_should_clauses * percentage / 100
+            }
+            MinimumShouldMatch::Percentage(percentage) => {
+                num_should_clauses - num_should_clauses * -percentage / 100
+            }
+        };
+        minimum_should_match.max(0) as usize
+    }
 }
 
 impl From<BoolQuery> for QueryAst {
@@ -101,6 +137,25 @@ impl BuildTantivyAst for BoolQuery {
             )?;
             boolean_query.filter.push(filter_leaf);
         }
+        boolean_query.minimum_should_match = self.minimum_should_match;
         Ok(TantivyQueryAst::Bool(boolean_query))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::MinimumShouldMatch;
+
+    #[test]
+    fn test_minimum_should_match_resolve() {
+        assert_eq!(MinimumShouldMatch::Count(2).resolve(4), 2);
+        assert_eq!(MinimumShouldMatch::Count(5).resolve(4), 5);
+        assert_eq!(MinimumShouldMatch::Count(-1).resolve(4), 3);
+        assert_eq!(MinimumShouldMatch::Count(-5).resolve(4), 0);
+        assert_eq!(MinimumShouldMatch::Percentage(75).resolve(4), 3);
+        assert_eq!(MinimumShouldMatch::Percentage(70).resolve(4), 2);
+        assert_eq!(MinimumShouldMatch::Percentage(-25).resolve(4), 3);
+        assert_eq!(MinimumShouldMatch::Percentage(-30).resolve(4), 3);
+        assert_eq!(MinimumShouldMatch::Percentage(100).resolve(0), 0);
+    }
+}
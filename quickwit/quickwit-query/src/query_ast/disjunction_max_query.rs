@@ -0,0 +1,160 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+use tantivy::query::DisjunctionMaxQuery as TantivyDisjunctionMaxQuery;
+use tantivy::schema::Schema as TantivySchema;
+
+use super::{BuildTantivyAst, TantivyQueryAst};
+use crate::query_ast::QueryAst;
+use crate::tokenizers::TokenizerManager;
+use crate::{InvalidQuery, MatchAllOrNone, NotNaNf32};
+
+fn is_zero(tie_breaker: &NotNaNf32) -> bool {
+    *tie_breaker == NotNaNf32::ZERO
+}
+
+fn default_tie_breaker() -> NotNaNf32 {
+    NotNaNf32::ZERO
+}
+
+/// Matches the documents matching any of the disjuncts.
+///
+/// Contrary to a disjunction expressed as a boolean query, the score of a document is the
+/// maximum score of the disjuncts it matches, plus `tie_breaker` times the scores of the
+/// other matching disjuncts.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct DisjunctionMaxQuery {
+    pub disjuncts: Vec<QueryAst>,
+    #[serde(default = "default_tie_breaker", skip_serializing_if = "is_zero")]
+    pub tie_breaker: NotNaNf32,
+}
+
+impl From<DisjunctionMaxQuery> for QueryAst {
+    fn from(disjunction_max_query: DisjunctionMaxQuery) -> Self {
+        QueryAst::DisjunctionMax(disjunction_max_query)
+    }
+}
+
+impl BuildTantivyAst for DisjunctionMaxQuery {
+    fn build_tantivy_ast_impl(
+        &self,
+        schema: &TantivySchema,
+        tokenizer_manager: &TokenizerManager,
+        search_fields: &[String],
+        with_validation: bool,
+    ) -> Result<TantivyQueryAst, InvalidQuery> {
+        let mut disjuncts: Vec<TantivyQueryAst> = Vec::with_capacity(self.disjuncts.len());
+        for disjunct in &self.disjuncts {
+            let disjunct_ast = disjunct
+                .build_tantivy_ast_call(schema, tokenizer_manager, search_fields, with_validation)?
+                .simplify();
+            if disjunct_ast.const_predicate() == Some(MatchAllOrNone::MatchNone) {
+                continue;
+            }
+            disjuncts.push(disjunct_ast);
+        }
+        if disjuncts.len() <= 1 {
+            return Ok(disjuncts.pop().unwrap_or_else(TantivyQueryAst::match_none));
+        }
+        let disjunction_max_query = TantivyDisjunctionMaxQuery::with_tie_breaker(
+            disjuncts.into_iter().map(Into::into).collect(),
+            self.tie_breaker.into(),
+        );
+        Ok(disjunction_max_query.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::schema::TEXT;
+
+    use super::*;
+    use crate::create_default_quickwit_tokenizer_manager;
+    use crate::query_ast::TermQuery;
+
+    fn build_tantivy_ast(disjunction_max_query: DisjunctionMaxQuery) -> TantivyQueryAst {
+        let mut schema_builder = TantivySchema::builder();
+        schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_text_field("body", TEXT);
+        let schema = schema_builder.build();
+        disjunction_max_query
+            .build_tantivy_ast_call(
+                &schema,
+                &create_default_quickwit_tokenizer_manager(),
+                &[],
+                true,
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_disjunction_max_query() {
+        let disjunction_max_query = DisjunctionMaxQuery {
+            disjuncts: vec![
+                TermQuery::from_field_value("title", "quickwit").into(),
+                TermQuery::from_field_value("body", "quickwit").into(),
+            ],
+            tie_breaker: NotNaNf32::try_from(0.3).unwrap(),
+        };
+        let tantivy_ast = build_tantivy_ast(disjunction_max_query);
+        let leaf_str = format!("{:?}", tantivy_ast.as_leaf().unwrap());
+        assert!(leaf_str.starts_with("DisjunctionMaxQuery"));
+        assert!(leaf_str.contains("tie_breaker: 0.3"));
+    }
+
+    #[test]
+    fn test_disjunction_max_query_simplification() {
+        let tantivy_ast = build_tantivy_ast(DisjunctionMaxQuery {
+            disjuncts: Vec::new(),
+            tie_breaker: NotNaNf32::ZERO,
+        });
+        assert_eq!(
+            tantivy_ast.const_predicate(),
+            Some(MatchAllOrNone::MatchNone)
+        );
+
+        let tantivy_ast = build_tantivy_ast(DisjunctionMaxQuery {
+            disjuncts: vec![
+                QueryAst::MatchNone,
+                TermQuery::from_field_value("title", "quickwit").into(),
+            ],
+            tie_breaker: NotNaNf32::ZERO,
+        });
+        let leaf_str = format!("{:?}", tantivy_ast.as_leaf().unwrap());
+        assert!(leaf_str.starts_with("TermQuery"));
+    }
+
+    #[test]
+    fn test_disjunction_max_query_serialization() {
+        let disjunction_max_query: QueryAst = DisjunctionMaxQuery {
+            disjuncts: vec![QueryAst::MatchAll],
+            tie_breaker: NotNaNf32::ZERO,
+        }
+        .into();
+        let disjunction_max_query_json = serde_json::to_string(&disjunction_max_query).unwrap();
+        assert_eq!(
+            disjunction_max_query_json,
+            r#"{"type":"disjunction_max","disjuncts":[{"type":"match_all"}]}"#
+        );
+        let disjunction_max_query_deser: QueryAst =
+            serde_json::from_str(&disjunction_max_query_json).unwrap();
+        assert_eq!(disjunction_max_query_deser, disjunction_max_query);
+    }
+}
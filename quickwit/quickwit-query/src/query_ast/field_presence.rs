@@ -20,10 +20,10 @@
 use quickwit_common::shared_consts::FIELD_PRESENCE_FIELD_NAME;
 use quickwit_common::PathHasher;
 use serde::{Deserialize, Serialize};
-use tantivy::schema::{Field, IndexRecordOption, Schema as TantivySchema};
+use tantivy::schema::{Field, IndexRecordOption, Schema as TantivySchema, Type};
 use tantivy::Term;
 
-use crate::query_ast::tantivy_query_ast::TantivyQueryAst;
+use crate::query_ast::tantivy_query_ast::{TantivyBoolQuery, TantivyQueryAst};
 use crate::query_ast::{BuildTantivyAst, QueryAst};
 use crate::tokenizers::TokenizerManager;
 use crate::{find_field_or_hit_dynamic, InvalidQuery};
@@ -77,34 +77,98 @@ impl BuildTantivyAst for FieldPresenceQuery {
         _search_fields: &[String],
         _with_validation: bool,
     ) -> Result<TantivyQueryAst, InvalidQuery> {
-        let field_presence_field = schema.get_field(FIELD_PRESENCE_FIELD_NAME).map_err(|_| {
-            InvalidQuery::SchemaError("field presence is not available for this split".to_string())
-        })?;
-        let (field, field_entry, path) = find_field_or_hit_dynamic(&self.field, schema)?;
-        if field_entry.is_fast() {
-            let full_path = if path.is_empty() {
-                field_entry.name().to_string()
-            } else {
-                format!("{}.{}", field_entry.name(), path)
-            };
-            let exists_query = tantivy::query::ExistsQuery::new_exists_query(full_path);
-            Ok(TantivyQueryAst::from(exists_query))
-        } else {
-            // fallback to the presence field
+        let (field, field_entry, path) = match find_field_or_hit_dynamic(&self.field, schema) {
+            Ok(field_and_path) => field_and_path,
+            // No document can hold a field that is absent from the schema. Returning a constant
+            // predicate lets negations (`missing`) simplify into a match-all query.
+            Err(InvalidQuery::FieldDoesNotExist { .. }) => {
+                return Ok(TantivyQueryAst::match_none());
+            }
+            Err(invalid_query) => return Err(invalid_query),
+        };
+        let is_json_field = field_entry.field_type().value_type() == Type::Json;
+        let field_presence_field_opt = schema.get_field(FIELD_PRESENCE_FIELD_NAME).ok();
+        let field_presence_term_query_opt = field_presence_field_opt.map(|field_presence_field| {
             let field_presence_hash = compute_field_presence_hash(field, path);
             let field_presence_term: Term =
                 Term::from_field_u64(field_presence_field, field_presence_hash);
-            let field_presence_term_query =
-                tantivy::query::TermQuery::new(field_presence_term, IndexRecordOption::Basic);
-            Ok(TantivyQueryAst::from(field_presence_term_query))
+            tantivy::query::TermQuery::new(field_presence_term, IndexRecordOption::Basic)
+        });
+        if !field_entry.is_fast() {
+            // fallback to the presence field
+            let field_presence_term_query = field_presence_term_query_opt.ok_or_else(|| {
+                InvalidQuery::SchemaError(
+                    "field presence is not available for this split".to_string(),
+                )
+            })?;
+            return Ok(TantivyQueryAst::from(field_presence_term_query));
+        }
+        let full_path = if path.is_empty() {
+            field_entry.name().to_string()
+        } else {
+            format!("{}.{}", field_entry.name(), path)
+        };
+        let exists_query = tantivy::query::ExistsQuery::new_exists_query(full_path);
+        match field_presence_term_query_opt {
+            // The columns of a fast JSON field only cover its leaves. The presence of objects is
+            // recorded in the presence field.
+            Some(field_presence_term_query) if is_json_field => Ok(TantivyBoolQuery {
+                should: vec![exists_query.into(), field_presence_term_query.into()],
+                ..Default::default()
+            }
+            .into()),
+            _ => Ok(TantivyQueryAst::from(exists_query)),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use tantivy::schema::{FAST, INDEXED, STRING, TEXT};
 
     use super::*;
+    use crate::{create_default_quickwit_tokenizer_manager, MatchAllOrNone};
+
+    fn build_field_presence_query(field: &str, schema: &TantivySchema) -> TantivyQueryAst {
+        FieldPresenceQuery {
+            field: field.to_string(),
+        }
+        .build_tantivy_ast_call(
+            schema,
+            &create_default_quickwit_tokenizer_manager(),
+            &[],
+            true,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_field_presence_query_build() {
+        let mut schema_builder = TantivySchema::builder();
+        schema_builder.add_u64_field(FIELD_PRESENCE_FIELD_NAME, INDEXED);
+        schema_builder.add_text_field("body", TEXT);
+        schema_builder.add_u64_field("status_code", FAST);
+        schema_builder.add_json_field("attributes", STRING | FAST);
+        let schema = schema_builder.build();
+
+        let tantivy_ast = build_field_presence_query("body", &schema);
+        assert!(format!("{:?}", tantivy_ast.as_leaf().unwrap()).starts_with("TermQuery"));
+
+        let tantivy_ast = build_field_presence_query("status_code", &schema);
+        assert!(format!("{:?}", tantivy_ast.as_leaf().unwrap()).starts_with("ExistsQuery"));
+
+        // Fast JSON fields rely on the presence field for object paths.
+        let tantivy_ast = build_field_presence_query("attributes.http", &schema);
+        let bool_query = tantivy_ast.as_bool_query().unwrap();
+        assert_eq!(bool_query.should.len(), 2);
+
+        // Fields absent from the schema cannot be present.
+        let tantivy_ast = build_field_presence_query("unknown", &schema);
+        assert_eq!(
+            tantivy_ast.const_predicate(),
+            Some(MatchAllOrNone::MatchNone)
+        );
+    }
 
     #[test]
     fn test_field_presence_single() {
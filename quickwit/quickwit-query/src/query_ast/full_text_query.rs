@@ -20,19 +20,19 @@
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use tantivy::query::{
-    PhrasePrefixQuery as TantivyPhrasePrefixQuery, PhraseQuery as TantivyPhraseQuery,
-    TermQuery as TantivyTermQuery,
+    FuzzyTermQuery as TantivyFuzzyTermQuery, PhrasePrefixQuery as TantivyPhrasePrefixQuery,
+    PhraseQuery as TantivyPhraseQuery, TermQuery as TantivyTermQuery,
 };
 use tantivy::schema::{
     Field, FieldType, IndexRecordOption, JsonObjectOptions, Schema as TantivySchema,
-    TextFieldIndexing,
+    TextFieldIndexing, Type,
 };
 use tantivy::tokenizer::{TextAnalyzer, TokenStream};
 use tantivy::Term;
 
 use crate::query_ast::tantivy_query_ast::{TantivyBoolQuery, TantivyQueryAst};
 use crate::query_ast::utils::full_text_query;
-use crate::query_ast::{BuildTantivyAst, QueryAst};
+use crate::query_ast::{BuildTantivyAst, MinimumShouldMatch, QueryAst};
 use crate::tokenizers::TokenizerManager;
 use crate::{find_field_or_hit_dynamic, BooleanOperand, InvalidQuery, MatchAllOrNone};
 
@@ -113,17 +113,48 @@ impl FullTextParams {
         if terms.is_empty() {
             return Ok(self.zero_terms_query.into());
         }
-        if terms.len() == 1 {
+        if let FullTextMode::Fuzzy {
+            operator,
+            fuzziness,
+            transpositions,
+        } = self.mode
+        {
+            let mut leaf_queries: Vec<TantivyQueryAst> = terms
+                .into_iter()
+                .map(|(_, term)| fuzzy_term_query(term, fuzziness, transpositions))
+                .collect::<Result<_, _>>()?;
+            if leaf_queries.len() == 1 {
+                return Ok(leaf_queries.pop().unwrap());
+            }
+            return Ok(TantivyBoolQuery::build_clause(operator, leaf_queries).into());
+        }
+        let has_minimum_should_match = matches!(
+            self.mode,
+            FullTextMode::Bool {
+                minimum_should_match: Some(_),
+                ..
+            }
+        );
+        if terms.len() == 1 && !has_minimum_should_match {
             let term = terms.pop().unwrap().1;
             return Ok(TantivyTermQuery::new(term, IndexRecordOption::WithFreqs).into());
         }
         match self.mode {
-            FullTextMode::Bool { operator } => {
+            FullTextMode::Bool {
+                operator,
+                minimum_should_match,
+            } => {
+                let num_terms = terms.len();
                 let leaf_queries: Vec<TantivyQueryAst> = terms
                     .into_iter()
                     .map(|(_, term)| TantivyTermQuery::new(term, index_record_option).into())
                     .collect();
-                Ok(TantivyBoolQuery::build_clause(operator, leaf_queries).into())
+                let mut bool_query = TantivyBoolQuery::build_clause(operator, leaf_queries);
+                if operator == BooleanOperand::Or {
+                    bool_query.minimum_should_match = minimum_should_match
+                        .map(|minimum_should_match| minimum_should_match.resolve(num_terms));
+                }
+                Ok(bool_query.into())
             }
             FullTextMode::BoolPrefix {
                 operator,
@@ -164,14 +195,84 @@ impl FullTextParams {
                     Ok(TantivyBoolQuery::build_clause(BooleanOperand::And, term_query).into())
                 }
             }
+            FullTextMode::Fuzzy { .. } => unreachable!("fuzzy queries are handled above"),
         }
     }
 }
 
+/// Maximum edit distance supported by fuzzy queries.
+pub const MAX_FUZZY_DISTANCE: u8 = 2;
+
+/// Returns the text of a term built from a text or a JSON field.
+fn term_text(term: &Term) -> Option<&str> {
+    let value_bytes = term.serialized_value_bytes();
+    let text_bytes = if term.typ() == Type::Json {
+        // JSON terms are serialized as `<json path>\0<type code><value>`.
+        let end_of_path_pos = value_bytes.iter().position(|byte| *byte == 0u8)?;
+        value_bytes.get(end_of_path_pos + 2..)?
+    } else {
+        value_bytes
+    };
+    std::str::from_utf8(text_bytes).ok()
+}
+
+fn fuzzy_term_query(
+    term: Term,
+    fuzziness: Fuzziness,
+    transpositions: bool,
+) -> Result<TantivyQueryAst, InvalidQuery> {
+    let distance = match fuzziness {
+        Fuzziness::Auto => {
+            let num_chars = term_text(&term)
+                .map(|text| text.chars().count())
+                .unwrap_or(0);
+            Fuzziness::auto_distance(num_chars)
+        }
+        Fuzziness::Distance(distance) => {
+            if distance > MAX_FUZZY_DISTANCE {
+                return Err(anyhow::anyhow!(
+                    "fuzzy distance must be at most {MAX_FUZZY_DISTANCE}, got {distance}"
+                )
+                .into());
+            }
+            distance
+        }
+    };
+    if distance == 0 {
+        return Ok(TantivyTermQuery::new(term, IndexRecordOption::WithFreqs).into());
+    }
+    Ok(TantivyFuzzyTermQuery::new(term, distance, transpositions).into())
+}
+
 fn is_zero(val: &u32) -> bool {
     *val == 0u32
 }
 
+fn default_transpositions() -> bool {
+    true
+}
+
+/// Maximum number of edits (insertions, deletions, substitutions and optionally
+/// transpositions) allowed between a query term and the terms it matches.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fuzziness {
+    // The distance is derived from the length of the term: exact match under 3 characters,
+    // 1 edit under 6 characters, 2 edits otherwise.
+    Auto,
+    Distance(u8),
+}
+
+impl Fuzziness {
+    fn auto_distance(num_chars: usize) -> u8 {
+        match num_chars {
+            0..=2 => 0,
+            3..=5 => 1,
+            _ => 2,
+        }
+    }
+}
+
 /// `FullTextMode` describe how we should derive a query from a user sequence of tokens.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -180,6 +281,9 @@ pub enum FullTextMode {
     // create a boolean clause (conjunction or disjunction based on the operator).
     Bool {
         operator: BooleanOperand,
+        // Only applies to the `Or` operator.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        minimum_should_match: Option<MinimumShouldMatch>,
     },
     BoolPrefix {
         operator: BooleanOperand,
@@ -199,11 +303,22 @@ pub enum FullTextMode {
         #[serde(default, skip_serializing_if = "is_zero")]
         slop: u32,
     },
+    // After tokenization, each token matches the terms within the given edit distance.
+    // The resulting queries are combined into a boolean clause.
+    Fuzzy {
+        operator: BooleanOperand,
+        fuzziness: Fuzziness,
+        #[serde(default = "default_transpositions")]
+        transpositions: bool,
+    },
 }
 
 impl From<BooleanOperand> for FullTextMode {
     fn from(operator: BooleanOperand) -> Self {
-        FullTextMode::Bool { operator }
+        FullTextMode::Bool {
+            operator,
+            minimum_should_match: None,
+        }
     }
 }
 
@@ -312,8 +427,8 @@ mod tests {
     use tantivy::schema::{Schema, TEXT};
 
     use crate::query_ast::tantivy_query_ast::TantivyQueryAst;
-    use crate::query_ast::{BuildTantivyAst, FullTextMode, FullTextQuery};
-    use crate::{create_default_quickwit_tokenizer_manager, BooleanOperand};
+    use crate::query_ast::{BuildTantivyAst, FullTextMode, FullTextQuery, Fuzziness};
+    use crate::{create_default_quickwit_tokenizer_manager, BooleanOperand, InvalidQuery};
 
     #[test]
     fn test_zero_terms() {
@@ -428,4 +543,69 @@ mod tests {
         let bool_query = ast.as_bool_query().unwrap();
         assert_eq!(bool_query.must.len(), 2);
     }
+
+    fn build_fuzzy_query(
+        text: &str,
+        fuzziness: Fuzziness,
+    ) -> Result<TantivyQueryAst, InvalidQuery> {
+        let full_text_query = FullTextQuery {
+            field: "body".to_string(),
+            text: text.to_string(),
+            params: super::FullTextParams {
+                tokenizer: None,
+                mode: FullTextMode::Fuzzy {
+                    operator: BooleanOperand::And,
+                    fuzziness,
+                    transpositions: true,
+                },
+                zero_terms_query: crate::MatchAllOrNone::MatchNone,
+            },
+            lenient: false,
+        };
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("body", TEXT);
+        let schema = schema_builder.build();
+        full_text_query.build_tantivy_ast_call(
+            &schema,
+            &create_default_quickwit_tokenizer_manager(),
+            &[],
+            true,
+        )
+    }
+
+    #[test]
+    fn test_full_text_fuzzy_mode() {
+        let ast = build_fuzzy_query("Quikwit", Fuzziness::Distance(1)).unwrap();
+        let leaf = ast.as_leaf().unwrap();
+        let leaf_str = format!("{:?}", leaf);
+        assert!(leaf_str.contains("FuzzyTermQuery"));
+        assert!(leaf_str.contains(r#""quikwit""#));
+        assert!(leaf_str.contains("distance: 1"));
+
+        let ast = build_fuzzy_query("Quikwit serch", Fuzziness::Distance(2)).unwrap();
+        let bool_query = ast.as_bool_query().unwrap();
+        assert_eq!(bool_query.must.len(), 2);
+
+        let error = build_fuzzy_query("Quikwit", Fuzziness::Distance(3)).unwrap_err();
+        assert_eq!(error.to_string(), "fuzzy distance must be at most 2, got 3");
+    }
+
+    #[test]
+    fn test_full_text_fuzzy_mode_auto() {
+        // Short terms must match exactly.
+        let ast = build_fuzzy_query("qw", Fuzziness::Auto).unwrap();
+        let leaf = ast.as_leaf().unwrap();
+        assert_eq!(
+            &format!("{:?}", leaf),
+            r#"TermQuery(Term(field=0, type=Str, "qw"))"#
+        );
+
+        let ast = build_fuzzy_query("serch", Fuzziness::Auto).unwrap();
+        let leaf_str = format!("{:?}", ast.as_leaf().unwrap());
+        assert!(leaf_str.contains("distance: 1"));
+
+        let ast = build_fuzzy_query("Quikwit", Fuzziness::Auto).unwrap();
+        let leaf_str = format!("{:?}", ast.as_leaf().unwrap());
+        assert!(leaf_str.contains("distance: 2"));
+    }
 }
@@ -24,10 +24,12 @@ use tantivy::schema::Schema as TantivySchema;
 use crate::tokenizers::TokenizerManager;
 
 mod bool_query;
+mod disjunction_max_query;
 mod field_presence;
 mod full_text_query;
 mod phrase_prefix_query;
 mod range_query;
+mod regex_query;
 mod tantivy_query_ast;
 mod term_query;
 mod term_set_query;
@@ -36,17 +38,21 @@ pub(crate) mod utils;
 mod visitor;
 mod wildcard_query;
 
-pub use bool_query::BoolQuery;
+pub use bool_query::{BoolQuery, MinimumShouldMatch};
+pub use disjunction_max_query::DisjunctionMaxQuery;
 pub use field_presence::FieldPresenceQuery;
-pub use full_text_query::{FullTextMode, FullTextParams, FullTextQuery};
+pub use full_text_query::{
+    FullTextMode, FullTextParams, FullTextQuery, Fuzziness, MAX_FUZZY_DISTANCE,
+};
 pub use phrase_prefix_query::PhrasePrefixQuery;
 pub use range_query::RangeQuery;
+pub use regex_query::{RegexQuery, MAX_REGEX_LENGTH};
 use tantivy_query_ast::TantivyQueryAst;
 pub use term_query::TermQuery;
 pub use term_set_query::TermSetQuery;
 pub use user_input_query::UserInputQuery;
 pub use visitor::{QueryAstTransformer, QueryAstVisitor};
-pub use wildcard_query::WildcardQuery;
+pub use wildcard_query::{WildcardQuery, MAX_WILDCARD_SCANNED_TERMS};
 
 use crate::{BooleanOperand, InvalidQuery, NotNaNf32};
 
@@ -55,6 +61,7 @@ use crate::{BooleanOperand, InvalidQuery, NotNaNf32};
 #[serde(rename_all = "snake_case")]
 pub enum QueryAst {
     Bool(BoolQuery),
+    DisjunctionMax(DisjunctionMaxQuery),
     Term(TermQuery),
     TermSet(TermSetQuery),
     FieldPresence(FieldPresenceQuery),
@@ -63,6 +70,7 @@ pub enum QueryAst {
     Range(RangeQuery),
     UserInput(UserInputQuery),
     Wildcard(WildcardQuery),
+    Regex(RegexQuery),
     MatchAll,
     MatchNone,
     Boost {
@@ -82,6 +90,7 @@ impl QueryAst {
                 must_not,
                 should,
                 filter,
+                minimum_should_match,
             }) => {
                 let must = parse_user_query_in_asts(must, default_search_fields)?;
                 let must_not = parse_user_query_in_asts(must_not, default_search_fields)?;
@@ -92,6 +101,18 @@ impl QueryAst {
                     must_not,
                     should,
                     filter,
+                    minimum_should_match,
+                }
+                .into())
+            }
+            QueryAst::DisjunctionMax(DisjunctionMaxQuery {
+                disjuncts,
+                tie_breaker,
+            }) => {
+                let disjuncts = parse_user_query_in_asts(disjuncts, default_search_fields)?;
+                Ok(DisjunctionMaxQuery {
+                    disjuncts,
+                    tie_breaker,
                 }
                 .into())
             }
@@ -103,7 +124,8 @@ impl QueryAst {
             | ast @ QueryAst::MatchNone
             | ast @ QueryAst::FieldPresence(_)
             | ast @ QueryAst::Range(_)
-            | ast @ QueryAst::Wildcard(_) => Ok(ast),
+            | ast @ QueryAst::Wildcard(_)
+            | ast @ QueryAst::Regex(_) => Ok(ast),
             QueryAst::UserInput(user_text_query) => {
                 user_text_query.parse_user_query(default_search_fields)
             }
@@ -191,6 +213,8 @@ impl BuildTantivyAst for QueryAst {
                 search_fields,
                 with_validation,
             ),
+            QueryAst::DisjunctionMax(disjunction_max_query) => disjunction_max_query
+                .build_tantivy_ast_call(schema, tokenizer_manager, search_fields, with_validation),
             QueryAst::Term(term_query) => term_query.build_tantivy_ast_call(
                 schema,
                 tokenizer_manager,
@@ -247,6 +271,12 @@ impl BuildTantivyAst for QueryAst {
                 search_fields,
                 with_validation,
             ),
+            QueryAst::Regex(regex) => regex.build_tantivy_ast_call(
+                schema,
+                tokenizer_manager,
+                search_fields,
+                with_validation,
+            ),
         }
     }
 }
@@ -317,6 +347,7 @@ pub fn query_ast_from_user_text(user_text: &str, default_fields: Option<Vec<Stri
         default_fields,
         default_operator: BooleanOperand::And,
         lenient: false,
+        allow_leading_wildcard: false,
     }
     .into()
 }
@@ -336,6 +367,7 @@ mod tests {
             default_fields: Default::default(),
             default_operator: Default::default(),
             lenient: false,
+            allow_leading_wildcard: false,
         }
         .into();
         let schema = tantivy::schema::Schema::builder().build();
@@ -360,6 +392,7 @@ mod tests {
             default_fields: Default::default(),
             default_operator: Default::default(),
             lenient: false,
+            allow_leading_wildcard: false,
         }
         .into();
         let query_ast_with_parsed_user_query: QueryAst = query_ast.parse_user_query(&[]).unwrap();
@@ -382,6 +415,7 @@ mod tests {
             default_fields: Default::default(),
             default_operator: Default::default(),
             lenient: false,
+            allow_leading_wildcard: false,
         }
         .into();
         let bool_query_ast: QueryAst = BoolQuery {
@@ -417,6 +451,7 @@ mod tests {
             default_fields: None,
             default_operator: crate::BooleanOperand::And,
             lenient: false,
+            allow_leading_wildcard: false,
         }
         .parse_user_query(&[])
         .unwrap();
@@ -433,6 +468,7 @@ mod tests {
             default_fields: None,
             default_operator: crate::BooleanOperand::Or,
             lenient: false,
+            allow_leading_wildcard: false,
         }
         .parse_user_query(&[])
         .unwrap();
@@ -19,16 +19,18 @@
 
 use std::ops::Bound;
 
+use quickwit_datetime::{DateMathParser, DateMathRounding};
 use serde::{Deserialize, Serialize};
 use tantivy::fastfield::FastValue;
 use tantivy::query::FastFieldRangeQuery;
 use tantivy::schema::Schema as TantivySchema;
 use tantivy::tokenizer::TextAnalyzer;
 use tantivy::{DateTime, Term};
+use time::OffsetDateTime;
 
 use super::tantivy_query_ast::TantivyBoolQuery;
 use super::QueryAst;
-use crate::json_literal::InterpretUserInput;
+use crate::json_literal::{get_default_date_time_format, InterpretUserInput};
 use crate::query_ast::tantivy_query_ast::TantivyQueryAst;
 use crate::query_ast::BuildTantivyAst;
 use crate::tokenizers::TokenizerManager;
@@ -75,7 +77,52 @@ where
     Ok((lower_bound, upper_bound))
 }
 
-/// Converts a given bound JsonLiteral bound into a bound of type T.
+/// Converts a bound of a range query on a date field into a date bound, resolving the date math
+/// expressions such as `now-1h/d` or `2024-01-01||+1M`. Like Elasticsearch, rounded dates are
+/// rounded so that the range includes the whole rounded unit for inclusive bounds and excludes
+/// it for exclusive bounds.
+fn convert_date_bound(
+    bound: &Bound<JsonLiteral>,
+    is_lower_bound: bool,
+    date_math_parser: &DateMathParser,
+    field_name: &str,
+) -> Result<Bound<DateTime>, InvalidQuery> {
+    let convert = |literal: &JsonLiteral, rounding: DateMathRounding| {
+        if let JsonLiteral::String(date_time_str) = literal {
+            if DateMathParser::is_date_math_expression(date_time_str) {
+                return date_math_parser
+                    .parse(date_time_str, get_default_date_time_format(), rounding)
+                    .map_err(|reason| {
+                        InvalidQuery::Other(anyhow::anyhow!(
+                            "failed to parse date math `{date_time_str}` for field \
+                             `{field_name}`: {reason}"
+                        ))
+                    });
+            }
+        }
+        DateTime::interpret_json(literal).ok_or_else(|| InvalidQuery::InvalidBoundary {
+            expected_value_type: DateTime::name(),
+            field_name: field_name.to_string(),
+        })
+    };
+    let converted_bound = match (bound, is_lower_bound) {
+        (Bound::Included(literal), true) => {
+            Bound::Included(convert(literal, DateMathRounding::Down)?)
+        }
+        (Bound::Excluded(literal), true) => {
+            Bound::Excluded(convert(literal, DateMathRounding::Up)?)
+        }
+        (Bound::Included(literal), false) => {
+            Bound::Included(convert(literal, DateMathRounding::Up)?)
+        }
+        (Bound::Excluded(literal), false) => {
+            Bound::Excluded(convert(literal, DateMathRounding::Down)?)
+        }
+        (Bound::Unbounded, _) => Bound::Unbounded,
+    };
+    Ok(converted_bound)
+}
+
 impl From<RangeQuery> for QueryAst {
     fn from(range_query: RangeQuery) -> Self {
         QueryAst::Range(range_query)
@@ -181,8 +228,19 @@ impl BuildTantivyAst for RangeQuery {
                 });
             }
             tantivy::schema::FieldType::Date(date_options) => {
-                let (lower_bound, upper_bound) =
-                    convert_bounds(&self.lower_bound, &self.upper_bound, field_entry.name())?;
+                let date_math_parser = DateMathParser::new(OffsetDateTime::now_utc());
+                let lower_bound = convert_date_bound(
+                    &self.lower_bound,
+                    true,
+                    &date_math_parser,
+                    field_entry.name(),
+                )?;
+                let upper_bound = convert_date_bound(
+                    &self.upper_bound,
+                    false,
+                    &date_math_parser,
+                    field_entry.name(),
+                )?;
                 let truncate_datetime =
                     |date: &DateTime| date.truncate(date_options.get_precision());
                 let lower_bound = map_bound(&lower_bound, truncate_datetime);
@@ -286,9 +344,12 @@ fn map_bound<TFrom, TTo>(bound: &Bound<TFrom>, transform: impl Fn(&TFrom) -> TTo
 mod tests {
     use std::ops::Bound;
 
+    use quickwit_datetime::DateMathParser;
     use tantivy::schema::{DateOptions, DateTimePrecision, Schema, FAST, STORED, TEXT};
+    use tantivy::DateTime;
+    use time::macros::datetime;
 
-    use super::RangeQuery;
+    use super::{convert_date_bound, RangeQuery};
     use crate::query_ast::BuildTantivyAst;
     use crate::{
         create_default_quickwit_tokenizer_manager, InvalidQuery, JsonLiteral, MatchAllOrNone,
@@ -362,6 +423,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_convert_date_bound() {
+        let date_math_parser = DateMathParser::new(datetime!(2024-02-14 18:40:19 UTC));
+        let convert = |bound: Bound<&str>, is_lower_bound: bool| {
+            let bound = bound.map(|date_time_str| JsonLiteral::String(date_time_str.to_string()));
+            convert_date_bound(&bound, is_lower_bound, &date_math_parser, "my_date_field")
+        };
+        let start_of_day = DateTime::from_utc(datetime!(2024-02-14 00:00:00 UTC));
+        let end_of_day = DateTime::from_utc(datetime!(2024-02-14 23:59:59.999999999 UTC));
+
+        assert_eq!(
+            convert(Bound::Included("now/d"), true).unwrap(),
+            Bound::Included(start_of_day)
+        );
+        assert_eq!(
+            convert(Bound::Excluded("now/d"), true).unwrap(),
+            Bound::Excluded(end_of_day)
+        );
+        assert_eq!(
+            convert(Bound::Included("now/d"), false).unwrap(),
+            Bound::Included(end_of_day)
+        );
+        assert_eq!(
+            convert(Bound::Excluded("now/d"), false).unwrap(),
+            Bound::Excluded(start_of_day)
+        );
+        assert_eq!(
+            convert(Bound::Included("2024-02-13T12:00:00Z||+1d/d"), true).unwrap(),
+            Bound::Included(start_of_day)
+        );
+        assert_eq!(
+            convert(Bound::Included("2024-02-14T00:00:00Z"), true).unwrap(),
+            Bound::Included(start_of_day)
+        );
+        assert_eq!(convert(Bound::Unbounded, true).unwrap(), Bound::Unbounded);
+
+        let error = convert(Bound::Included("now+1x"), true).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("failed to parse date math `now+1x`"));
+
+        let error = convert(Bound::Included("nowhere"), true).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("failed to parse date math `nowhere`"));
+    }
+
+    #[test]
+    fn test_range_query_str_field_ignores_date_math() {
+        test_range_query_typed_field_util(
+            "my_str_field",
+            JsonLiteral::String("a||b".to_string()),
+            JsonLiteral::String("nowhere".to_string()),
+            "FastFieldRangeQuery { bounds: BoundsRange { lower_bound: Included(Term(field=3, \
+             type=Str, \"a||b\")), upper_bound: Included(Term(field=3, type=Str, \"nowhere\")) } }",
+        );
+    }
+
     #[test]
     fn test_range_query_missing_field() {
         let schema = make_schema(false);
@@ -420,8 +539,8 @@ mod tests {
              type=I64, 1980)), upper_bound: Included(Term(field=6, type=Json, path=hello, \
              type=I64, 1989)) } }), Leaf(FastFieldRangeQuery { bounds: BoundsRange { lower_bound: \
              Included(Term(field=6, type=Json, path=hello, type=Str, \"1980\")), upper_bound: \
-             Included(Term(field=6, type=Json, path=hello, type=Str, \"1989\")) } })], filter: [] \
-             })"
+             Included(Term(field=6, type=Json, path=hello, type=Str, \"1989\")) } })], filter: \
+             [], minimum_should_match: None })"
         );
     }
 
@@ -0,0 +1,259 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Context;
+use quickwit_common::is_false;
+use serde::{Deserialize, Serialize};
+use tantivy::schema::{Field, FieldType, Schema as TantivySchema};
+use tantivy::Term;
+
+use super::{BuildTantivyAst, QueryAst};
+use crate::query_ast::TantivyQueryAst;
+use crate::tokenizers::TokenizerManager;
+use crate::{find_field_or_hit_dynamic, InvalidQuery};
+
+/// Maximum length of a regular expression, in bytes.
+pub const MAX_REGEX_LENGTH: usize = 1_000;
+
+/// Maximum size of the compiled regular expression, in bytes.
+///
+/// Regular expressions are compiled into an automaton that is run against
+/// the term dictionary of every split. This limit protects searchers against
+/// patterns that would blow up into huge automata.
+const MAX_REGEX_COMPILED_SIZE: usize = 1 << 20;
+
+/// A Regex query matches the terms of a field that match a regular expression.
+///
+/// The regular expression must match the entire term, e.g. `b.*d` matches `bond`
+/// but `on` does not. It is applied to the terms as they are stored in the index,
+/// i.e. after tokenization and normalization.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+pub struct RegexQuery {
+    pub field: String,
+    pub regex: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub case_insensitive: bool,
+}
+
+impl From<RegexQuery> for QueryAst {
+    fn from(regex_query: RegexQuery) -> Self {
+        Self::Regex(regex_query)
+    }
+}
+
+impl RegexQuery {
+    #[cfg(test)]
+    pub fn from_field_value(field: impl ToString, regex: impl ToString) -> Self {
+        Self {
+            field: field.to_string(),
+            regex: regex.to_string(),
+            case_insensitive: false,
+        }
+    }
+
+    fn validate_regex(&self) -> Result<(), InvalidQuery> {
+        if self.regex.len() > MAX_REGEX_LENGTH {
+            return Err(anyhow::anyhow!(
+                "regex of length {} exceeds the maximum allowed length of {MAX_REGEX_LENGTH}",
+                self.regex.len()
+            )
+            .into());
+        }
+        regex::RegexBuilder::new(&self.regex)
+            .size_limit(MAX_REGEX_COMPILED_SIZE)
+            .build()
+            .with_context(|| format!("invalid regex `{}`", self.regex))?;
+        Ok(())
+    }
+
+    /// Returns the field targeted by the query and the regular expression to run
+    /// against its term dictionary.
+    ///
+    /// For JSON fields, the regular expression is prefixed with the JSON path of the
+    /// targeted value, so that it only matches the terms of that path.
+    pub fn to_field_and_regex(
+        &self,
+        schema: &TantivySchema,
+    ) -> Result<(Field, String), InvalidQuery> {
+        self.validate_regex()?;
+        let (field, field_entry, json_path) = find_field_or_hit_dynamic(&self.field, schema)?;
+        let regex = if self.case_insensitive {
+            format!("(?i:{})", self.regex)
+        } else {
+            self.regex.clone()
+        };
+        match field_entry.field_type() {
+            FieldType::Str(text_options) => {
+                if text_options.get_indexing_options().is_none() {
+                    return Err(InvalidQuery::SchemaError(format!(
+                        "field {} is not full-text searchable",
+                        field_entry.name()
+                    )));
+                }
+                Ok((field, regex))
+            }
+            FieldType::JsonObject(json_options) => {
+                if json_options.get_text_indexing_options().is_none() {
+                    return Err(InvalidQuery::SchemaError(format!(
+                        "field {} is not full-text searchable",
+                        field_entry.name()
+                    )));
+                }
+                let mut term = Term::from_field_json_path(
+                    field,
+                    json_path,
+                    json_options.is_expand_dots_enabled(),
+                );
+                term.append_type_and_str("");
+                // The JSON path and type markers are ASCII/UTF-8, so this cannot fail.
+                let json_path_prefix = std::str::from_utf8(term.serialized_value_bytes())
+                    .context("JSON path is not valid UTF-8")?;
+                let json_regex = format!("{}(?:{regex})", regex::escape(json_path_prefix));
+                Ok((field, json_regex))
+            }
+            _ => Err(InvalidQuery::SchemaError(
+                "trying to run a Regex query on a non-text field".to_string(),
+            )),
+        }
+    }
+}
+
+impl BuildTantivyAst for RegexQuery {
+    fn build_tantivy_ast_impl(
+        &self,
+        schema: &TantivySchema,
+        _tokenizer_manager: &TokenizerManager,
+        _search_fields: &[String],
+        _with_validation: bool,
+    ) -> Result<TantivyQueryAst, InvalidQuery> {
+        let (field, regex) = self.to_field_and_regex(schema)?;
+        let regex_query = tantivy::query::RegexQuery::from_pattern(&regex, field)
+            .with_context(|| format!("failed to compile regex `{}`", self.regex))?;
+        Ok(regex_query.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::schema::{TextFieldIndexing, TextOptions, STORED, TEXT};
+
+    use super::*;
+    use crate::create_default_quickwit_tokenizer_manager;
+
+    fn build_schema() -> TantivySchema {
+        let mut schema_builder = TantivySchema::builder();
+        schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_text_field("stored_only", STORED);
+        schema_builder.add_u64_field("count", STORED);
+        schema_builder.add_json_field("attributes", TEXT);
+        schema_builder.build()
+    }
+
+    #[test]
+    fn test_regex_query_on_text_field() {
+        let schema = build_schema();
+        let regex_query = RegexQuery::from_field_value("title", "qu.*t");
+        let (field, regex) = regex_query.to_field_and_regex(&schema).unwrap();
+        assert_eq!(field, schema.get_field("title").unwrap());
+        assert_eq!(regex, "qu.*t");
+        regex_query
+            .build_tantivy_ast_call(
+                &schema,
+                &create_default_quickwit_tokenizer_manager(),
+                &[],
+                true,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_regex_query_case_insensitive() {
+        let schema = build_schema();
+        let regex_query = RegexQuery {
+            field: "title".to_string(),
+            regex: "qu.*t".to_string(),
+            case_insensitive: true,
+        };
+        let (_field, regex) = regex_query.to_field_and_regex(&schema).unwrap();
+        assert_eq!(regex, "(?i:qu.*t)");
+    }
+
+    #[test]
+    fn test_regex_query_on_json_field() {
+        let schema = build_schema();
+        let regex_query = RegexQuery::from_field_value("attributes.color", "bl.*");
+        let (field, regex) = regex_query.to_field_and_regex(&schema).unwrap();
+        assert_eq!(field, schema.get_field("attributes").unwrap());
+        assert_eq!(regex, "color\u{0}s(?:bl.*)");
+        regex_query
+            .build_tantivy_ast_call(
+                &schema,
+                &create_default_quickwit_tokenizer_manager(),
+                &[],
+                true,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_regex_query_invalid() {
+        let schema = build_schema();
+        let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+        let error = RegexQuery::from_field_value("title", "qu(ick")
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap_err();
+        assert!(error.to_string().contains("invalid regex `qu(ick`"));
+
+        let error = RegexQuery::from_field_value("title", "a".repeat(MAX_REGEX_LENGTH + 1))
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("exceeds the maximum allowed length"));
+
+        let error = RegexQuery::from_field_value("title", "\\w{1000}\\w{1000}\\w{1000}")
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap_err();
+        assert!(error.to_string().contains("invalid regex"));
+
+        let error = RegexQuery::from_field_value("count", "1.*")
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap_err();
+        assert!(matches!(error, InvalidQuery::SchemaError(_)));
+
+        let error = RegexQuery::from_field_value("stored_only", "a.*")
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap_err();
+        assert!(matches!(error, InvalidQuery::SchemaError(_)));
+    }
+
+    #[test]
+    fn test_regex_query_with_custom_tokenizer_field() {
+        let mut schema_builder = TantivySchema::builder();
+        let text_options = TextOptions::default()
+            .set_indexing_options(TextFieldIndexing::default().set_tokenizer("raw"));
+        schema_builder.add_text_field("path", text_options);
+        let schema = schema_builder.build();
+        let (_field, regex) = RegexQuery::from_field_value("path", "/var/log/.*\\.log")
+            .to_field_and_regex(&schema)
+            .unwrap();
+        assert_eq!(regex, "/var/log/.*\\.log");
+    }
+}
@@ -160,6 +160,10 @@ pub(crate) struct TantivyBoolQuery {
     pub must_not: Vec<TantivyQueryAst>,
     pub should: Vec<TantivyQueryAst>,
     pub filter: Vec<TantivyQueryAst>,
+    // Minimum number of should clauses that must match. When `None`, should clauses are
+    // optional if there is at least one must or filter clause, and at least one should
+    // clause must match otherwise.
+    pub minimum_should_match: Option<usize>,
 }
 
 fn simplify_asts(asts: Vec<TantivyQueryAst>) -> Vec<TantivyQueryAst> {
@@ -193,6 +197,25 @@ impl TantivyBoolQuery {
                 }
             }
         }
+        if let Some(minimum_should_match) = self.minimum_should_match {
+            self.should
+                .retain(|should| should.const_predicate() != Some(MatchAllOrNone::MatchNone));
+            if minimum_should_match > self.should.len() {
+                return TantivyQueryAst::ConstPredicate(MatchAllOrNone::MatchNone);
+            }
+            if minimum_should_match == 0 {
+                // Should clauses are optional and only contribute to the score.
+                self.must.push(TantivyQueryAst::match_all());
+                self.minimum_should_match = None;
+            } else if minimum_should_match == self.should.len() {
+                // All should clauses are required.
+                self.must.append(&mut self.should);
+                self.minimum_should_match = None;
+            } else if minimum_should_match == 1 && self.must.is_empty() && self.filter.is_empty() {
+                // This is the default behavior.
+                self.minimum_should_match = None;
+            }
+        }
         if self.should.is_empty()
             && self.must.is_empty()
             && self.filter.is_empty()
@@ -251,7 +274,10 @@ impl TantivyBoolQuery {
                     continue;
                 }
             };
-            if should_bool.must.is_empty()
+            // Flattening should clauses would change the number of should clauses matching.
+            if self.minimum_should_match.is_none()
+                && should_bool.minimum_should_match.is_none()
+                && should_bool.must.is_empty()
                 && should_bool.filter.is_empty()
                 && should_bool.must_not.is_empty()
             {
@@ -360,6 +386,12 @@ impl From<TantivyBoolQuery> for Box<dyn TantivyQuery> {
                 Box::new(TantivyConstScoreQuery::new(filter_query, 0.0f32)),
             ));
         }
+        if let Some(minimum_should_match) = bool_query.minimum_should_match {
+            return Box::new(tantivy::query::BooleanQuery::with_minimum_required_clauses(
+                clause,
+                minimum_should_match,
+            ));
+        }
         Box::new(tantivy::query::BooleanQuery::from(clause))
     }
 }
@@ -563,6 +595,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_simplify_bool_query_with_minimum_should_match() {
+        let bool_query = TantivyBoolQuery {
+            should: vec![term("abc"), term("def"), term("ghi")],
+            minimum_should_match: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(bool_query.clone().simplify(), bool_query.into());
+
+        let bool_query = TantivyBoolQuery {
+            should: vec![term("abc"), term("def"), TantivyQueryAst::match_none()],
+            minimum_should_match: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            bool_query.simplify().const_predicate(),
+            Some(MatchAllOrNone::MatchNone)
+        );
+
+        let bool_query = TantivyBoolQuery {
+            should: vec![term("abc"), term("def")],
+            minimum_should_match: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            bool_query.simplify(),
+            TantivyBoolQuery {
+                must: vec![term("abc"), term("def")],
+                ..Default::default()
+            }
+            .into()
+        );
+    }
+
     #[test]
     fn test_simplify_bool_query_with_match_none_must_clauses() {
         let tantivy_query = EmptyQuery.into();
@@ -835,6 +901,25 @@ mod tests {
                 .iter()
                 .filter_map(|should| should.evaluate_test())
                 .sum();
+            if let Some(minimum_should_match) = self.minimum_should_match {
+                let num_matching_should = self
+                    .should
+                    .iter()
+                    .filter(|should| should.evaluate_test().is_some())
+                    .count();
+                if num_matching_should < minimum_should_match {
+                    return None;
+                }
+                let must_scores: Option<Vec<u32>> =
+                    self.must.iter().map(|must| must.evaluate_test()).collect();
+                let all_filters_match = self
+                    .filter
+                    .iter()
+                    .all(|filter| filter.evaluate_test().is_some());
+                return must_scores
+                    .filter(|_| all_filters_match)
+                    .map(|must_scores| must_scores.iter().sum::<u32>() + should_score);
+            }
             if self.must.len() + self.filter.len() > 0 {
                 if self
                     .must
@@ -881,14 +966,18 @@ mod tests {
             let filter = proptest::collection::vec(element.clone(), 0..4);
             let should = proptest::collection::vec(element.clone(), 0..4);
             let must_not = proptest::collection::vec(element.clone(), 0..4);
-            (must, filter, should, must_not).prop_map(|(must, filter, should, must_not)| {
-                TantivyQueryAst::Bool(TantivyBoolQuery {
-                    must,
-                    filter,
-                    should,
-                    must_not,
-                })
-            })
+            let minimum_should_match = proptest::option::of(0usize..5);
+            (must, filter, should, must_not, minimum_should_match).prop_map(
+                |(must, filter, should, must_not, minimum_should_match)| {
+                    TantivyQueryAst::Bool(TantivyBoolQuery {
+                        must,
+                        filter,
+                        should,
+                        must_not,
+                        minimum_should_match,
+                    })
+                },
+            )
         })
     }
 
@@ -126,7 +126,7 @@ impl From<TermQuery> for HashMap<String, TermQueryValue> {
 
 #[cfg(test)]
 mod tests {
-    use tantivy::schema::{Schema, INDEXED};
+    use tantivy::schema::{Schema, FAST, INDEXED};
 
     use crate::create_default_quickwit_tokenizer_manager;
     use crate::query_ast::{BuildTantivyAst, TermQuery};
@@ -179,6 +179,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_term_query_with_ipaddr_cidr() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_ip_addr_field("ip", INDEXED | FAST);
+        schema_builder.add_ip_addr_field("ip_not_fast", INDEXED);
+        let schema = schema_builder.build();
+        let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+
+        let build_query_debug = |field: &str, value: &str| {
+            TermQuery::from_field_value(field, value)
+                .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+                .map(|tantivy_query_ast| format!("{:?}", tantivy_query_ast.as_leaf().unwrap()))
+        };
+        let query_debug = build_query_debug("ip", "10.0.0.0/8").unwrap();
+        assert!(query_debug.contains("Included(Term(field=0, type=IpAddr, ::ffff:10.0.0.0))"));
+        assert!(query_debug.contains("Included(Term(field=0, type=IpAddr, ::ffff:10.255.255.255))"));
+
+        let query_debug = build_query_debug("ip", "2001:db8::1/32").unwrap();
+        assert!(query_debug.contains("Included(Term(field=0, type=IpAddr, 2001:db8::))"));
+        assert!(query_debug.contains(
+            "Included(Term(field=0, type=IpAddr, 2001:db8:ffff:ffff:ffff:ffff:ffff:ffff))"
+        ));
+
+        let query_debug = build_query_debug("ip", "192.168.1.1/32").unwrap();
+        assert!(query_debug.contains("Included(Term(field=0, type=IpAddr, ::ffff:192.168.1.1))"));
+
+        let error = build_query_debug("ip", "10.0.0.0/33").unwrap_err();
+        assert!(matches!(
+            error,
+            crate::InvalidQuery::InvalidSearchTerm { .. }
+        ));
+
+        let error = build_query_debug("ip_not_fast", "10.0.0.0/8").unwrap_err();
+        assert!(error.to_string().contains(
+            "CIDR queries are only supported for fast fields. (`ip_not_fast` is not a fast field)"
+        ));
+    }
+
     #[test]
     fn test_term_query_bytes_with_padding() {
         let term_query = TermQuery {
@@ -21,6 +21,7 @@ use std::collections::{BTreeSet, HashMap};
 use std::ops::Bound;
 
 use anyhow::bail;
+use quickwit_common::is_false;
 use serde::{Deserialize, Serialize};
 use tantivy::query_grammar::{
     Delimiter, Occur, UserInputAst, UserInputBound, UserInputLeaf, UserInputLiteral,
@@ -30,7 +31,7 @@ use tantivy::schema::Schema as TantivySchema;
 use crate::not_nan_f32::NotNaNf32;
 use crate::query_ast::tantivy_query_ast::TantivyQueryAst;
 use crate::query_ast::{
-    self, BuildTantivyAst, FieldPresenceQuery, FullTextMode, FullTextParams, QueryAst,
+    self, BuildTantivyAst, FieldPresenceQuery, FullTextMode, FullTextParams, Fuzziness, QueryAst,
 };
 use crate::tokenizers::TokenizerManager;
 use crate::{BooleanOperand, InvalidQuery, JsonLiteral};
@@ -50,6 +51,9 @@ pub struct UserInputQuery {
     pub default_fields: Option<Vec<String>>,
     pub default_operator: BooleanOperand,
     pub lenient: bool,
+    // Allows wildcard queries starting with a wildcard, like `*bond`.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub allow_leading_wildcard: bool,
 }
 
 impl UserInputQuery {
@@ -79,6 +83,7 @@ impl UserInputQuery {
             default_occur,
             search_fields,
             self.lenient,
+            self.allow_leading_wildcard,
         )
     }
 }
@@ -108,6 +113,7 @@ fn convert_user_input_ast_to_query_ast(
     default_occur: Occur,
     default_search_fields: &[String],
     lenient: bool,
+    allow_leading_wildcard: bool,
 ) -> anyhow::Result<QueryAst> {
     match user_input_ast {
         UserInputAst::Clause(clause) => {
@@ -118,6 +124,7 @@ fn convert_user_input_ast_to_query_ast(
                     default_occur,
                     default_search_fields,
                     lenient,
+                    allow_leading_wildcard,
                 )?;
                 let children_ast_for_occur: &mut Vec<QueryAst> =
                     match occur_opt.unwrap_or(default_occur) {
@@ -130,9 +137,12 @@ fn convert_user_input_ast_to_query_ast(
             Ok(bool_query.into())
         }
         UserInputAst::Leaf(leaf) => match *leaf {
-            UserInputLeaf::Literal(literal) => {
-                convert_user_input_literal(literal, default_search_fields, lenient)
-            }
+            UserInputLeaf::Literal(literal) => convert_user_input_literal(
+                literal,
+                default_search_fields,
+                lenient,
+                allow_leading_wildcard,
+            ),
             UserInputLeaf::All => Ok(QueryAst::MatchAll),
             UserInputLeaf::Range {
                 field,
@@ -189,6 +199,7 @@ fn convert_user_input_ast_to_query_ast(
                 default_occur,
                 default_search_fields,
                 lenient,
+                allow_leading_wildcard,
             )?;
             let boost: NotNaNf32 = (boost as f32)
                 .try_into()
@@ -226,12 +237,41 @@ fn is_wildcard(phrase: &str) -> bool {
         .is_break()
 }
 
+/// Parses a regular expression delimited by slashes, such as `/qu.ck/`.
+///
+/// Slashes inside the regular expression must be escaped, as in `/var\/log/`, so that terms
+/// containing unescaped slashes, such as the path `/var/log/`, are not regular expressions. The
+/// escaped slashes are unescaped, the other escape sequences are left to the regex syntax.
+fn parse_regex(phrase: &str) -> Option<String> {
+    let escaped_regex = phrase.strip_prefix('/')?.strip_suffix('/')?;
+    let mut regex = String::with_capacity(escaped_regex.len());
+    let mut chars = escaped_regex.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '/' => return None,
+            '\\' => match chars.next() {
+                Some('/') => regex.push('/'),
+                Some(escaped_char) => {
+                    regex.push('\\');
+                    regex.push(escaped_char);
+                }
+                // The closing slash is escaped.
+                None => return None,
+            },
+            _ => regex.push(c),
+        }
+    }
+    Some(regex)
+}
+
 /// Convert a leaf of a text query AST to a QueryAst.
 /// This may generate more than a single leaf if there are multiple default fields.
 fn convert_user_input_literal(
     user_input_literal: UserInputLiteral,
     default_search_fields: &[String],
     lenient: bool,
+    allow_leading_wildcard: bool,
 ) -> anyhow::Result<QueryAst> {
     let UserInputLiteral {
         field_name,
@@ -252,9 +292,16 @@ fn convert_user_input_literal(
         anyhow::bail!("query requires a default search field and none was supplied");
     }
     let mode = match delimiter {
+        // `field:value~2` matches terms within an edit distance of 2 of `value`.
+        Delimiter::None if slop > 0 => FullTextMode::Fuzzy {
+            operator: BooleanOperand::And,
+            fuzziness: Fuzziness::Distance(u8::try_from(slop).unwrap_or(u8::MAX)),
+            transpositions: true,
+        },
         Delimiter::None => FullTextMode::PhraseFallbackToIntersection,
         Delimiter::SingleQuotes => FullTextMode::Bool {
             operator: BooleanOperand::And,
+            minimum_should_match: None,
         },
         Delimiter::DoubleQuotes => FullTextMode::Phrase { slop },
     };
@@ -263,11 +310,23 @@ fn convert_user_input_literal(
         mode,
         zero_terms_query: crate::MatchAllOrNone::MatchNone,
     };
+    let regex_opt: Option<String> = if delimiter == Delimiter::None && !prefix {
+        parse_regex(&phrase)
+    } else {
+        None
+    };
     let wildcard = delimiter == Delimiter::None && is_wildcard(&phrase);
     let mut phrase_queries: Vec<QueryAst> = field_names
         .into_iter()
         .map(|field_name| {
-            if prefix {
+            if let Some(regex) = regex_opt.as_ref() {
+                query_ast::RegexQuery {
+                    field: field_name,
+                    regex: regex.clone(),
+                    case_insensitive: false,
+                }
+                .into()
+            } else if prefix {
                 query_ast::PhrasePrefixQuery {
                     field: field_name,
                     phrase: phrase.clone(),
@@ -279,6 +338,7 @@ fn convert_user_input_literal(
                 query_ast::WildcardQuery {
                     field: field_name,
                     value: phrase.clone(),
+                    allow_leading_wildcard,
                 }
                 .into()
             } else {
@@ -308,7 +368,8 @@ fn convert_user_input_literal(
 #[cfg(test)]
 mod tests {
     use crate::query_ast::{
-        BoolQuery, BuildTantivyAst, FullTextMode, FullTextQuery, QueryAst, UserInputQuery,
+        BoolQuery, BuildTantivyAst, FullTextMode, FullTextQuery, Fuzziness, QueryAst,
+        UserInputQuery,
     };
     use crate::{create_default_quickwit_tokenizer_manager, BooleanOperand, InvalidQuery};
 
@@ -319,6 +380,7 @@ mod tests {
             default_fields: None,
             default_operator: BooleanOperand::And,
             lenient: false,
+            allow_leading_wildcard: false,
         };
         let schema = tantivy::schema::Schema::builder().build();
         {
@@ -353,6 +415,7 @@ mod tests {
                 default_fields: None,
                 default_operator: BooleanOperand::And,
                 lenient: false,
+                allow_leading_wildcard: false,
             }
             .parse_user_query(&[])
             .unwrap_err();
@@ -367,6 +430,7 @@ mod tests {
                 default_fields: Some(Vec::new()),
                 default_operator: BooleanOperand::And,
                 lenient: false,
+                allow_leading_wildcard: false,
             }
             .parse_user_query(&[])
             .unwrap_err();
@@ -384,6 +448,7 @@ mod tests {
             default_fields: None,
             default_operator: BooleanOperand::And,
             lenient: false,
+            allow_leading_wildcard: false,
         }
         .parse_user_query(&["defaultfield".to_string()])
         .unwrap();
@@ -405,6 +470,7 @@ mod tests {
             default_fields: None,
             default_operator: BooleanOperand::And,
             lenient: false,
+            allow_leading_wildcard: false,
         }
         .parse_user_query(&[])
         .unwrap();
@@ -420,6 +486,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_user_input_query_fuzzy() {
+        let ast = UserInputQuery {
+            user_text: "field:quikwit~1".to_string(),
+            default_fields: None,
+            default_operator: BooleanOperand::And,
+            lenient: false,
+            allow_leading_wildcard: false,
+        }
+        .parse_user_query(&[])
+        .unwrap();
+        let QueryAst::FullText(full_text_query) = ast else {
+            panic!()
+        };
+        assert_eq!(&full_text_query.field, "field");
+        assert_eq!(&full_text_query.text, "quikwit");
+        assert_eq!(
+            full_text_query.params.mode,
+            FullTextMode::Fuzzy {
+                operator: BooleanOperand::And,
+                fuzziness: Fuzziness::Distance(1),
+                transpositions: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_user_input_query_regex() {
+        let ast = UserInputQuery {
+            user_text: "field:/qu.ck/".to_string(),
+            default_fields: None,
+            default_operator: BooleanOperand::And,
+            lenient: false,
+            allow_leading_wildcard: false,
+        }
+        .parse_user_query(&[])
+        .unwrap();
+        let QueryAst::Regex(regex_query) = ast else {
+            panic!()
+        };
+        assert_eq!(&regex_query.field, "field");
+        assert_eq!(&regex_query.regex, "qu.ck");
+        assert!(!regex_query.case_insensitive);
+    }
+
+    #[test]
+    fn test_user_input_query_regex_escaped_slashes() {
+        let parse_user_query = |user_text: &str| {
+            UserInputQuery {
+                user_text: user_text.to_string(),
+                default_fields: None,
+                default_operator: BooleanOperand::And,
+                lenient: false,
+                allow_leading_wildcard: false,
+            }
+            .parse_user_query(&[])
+            .unwrap()
+        };
+        let QueryAst::Regex(regex_query) = parse_user_query(r"path:/var\/log\/.*/") else {
+            panic!()
+        };
+        assert_eq!(&regex_query.regex, "var/log/.*");
+
+        // A path with unescaped slashes is not a regular expression.
+        let QueryAst::FullText(full_text_query) = parse_user_query("path:/var/log/") else {
+            panic!()
+        };
+        assert_eq!(&full_text_query.text, "/var/log/");
+
+        // Escaped slashes are literal.
+        let QueryAst::FullText(full_text_query) = parse_user_query(r"path:\/qu.ck\/") else {
+            panic!()
+        };
+        assert_eq!(&full_text_query.text, r"\/qu.ck\/");
+    }
+
+    #[test]
+    fn test_parse_regex() {
+        assert_eq!(parse_regex("/qu.ck/").unwrap(), "qu.ck");
+        assert_eq!(parse_regex(r"/a\/b/").unwrap(), "a/b");
+        assert_eq!(parse_regex(r"/\d+\.\d+/").unwrap(), r"\d+\.\d+");
+        assert_eq!(parse_regex("//").unwrap(), "");
+        assert!(parse_regex("qu.ck").is_none());
+        assert!(parse_regex("/").is_none());
+        assert!(parse_regex("/var/log/").is_none());
+        assert!(parse_regex(r"/qu.ck\/").is_none());
+        assert!(parse_regex(r"\/qu.ck\/").is_none());
+    }
+
+    #[test]
+    fn test_user_input_query_leading_wildcard() {
+        let ast = UserInputQuery {
+            user_text: "field:*ond*".to_string(),
+            default_fields: None,
+            default_operator: BooleanOperand::And,
+            lenient: false,
+            allow_leading_wildcard: true,
+        }
+        .parse_user_query(&[])
+        .unwrap();
+        let QueryAst::Wildcard(wildcard_query) = ast else {
+            panic!()
+        };
+        assert_eq!(&wildcard_query.field, "field");
+        assert_eq!(&wildcard_query.value, "*ond*");
+        assert!(wildcard_query.allow_leading_wildcard);
+    }
+
     #[test]
     fn test_user_input_query_override_default_fields() {
         let ast = UserInputQuery {
@@ -427,6 +601,7 @@ mod tests {
             default_fields: Some(vec!["defaultfield".to_string()]),
             default_operator: BooleanOperand::And,
             lenient: false,
+            allow_leading_wildcard: false,
         }
         .parse_user_query(&["defaultfieldweshouldignore".to_string()])
         .unwrap();
@@ -448,6 +623,7 @@ mod tests {
             default_fields: Some(vec!["fielda".to_string(), "fieldb".to_string()]),
             default_operator: BooleanOperand::And,
             lenient: false,
+            allow_leading_wildcard: false,
         }
         .parse_user_query(&["defaultfieldweshouldignore".to_string()])
         .unwrap();
@@ -464,6 +640,7 @@ mod tests {
             default_fields: Some(vec!["fieldtoignore".to_string()]),
             default_operator: BooleanOperand::And,
             lenient: false,
+            allow_leading_wildcard: false,
         }
         .parse_user_query(&["fieldtoignore".to_string()])
         .unwrap();
@@ -486,6 +663,7 @@ mod tests {
                 default_fields: None,
                 default_operator: BooleanOperand::Or,
                 lenient: false,
+                allow_leading_wildcard: false,
             }
             .parse_user_query(&[])
             .unwrap();
@@ -522,7 +700,8 @@ mod tests {
             assert_eq!(
                 double_quote_query.params.mode,
                 FullTextMode::Bool {
-                    operator: BooleanOperand::And
+                    operator: BooleanOperand::And,
+                    minimum_should_match: None,
                 }
             );
         }
@@ -17,8 +17,11 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::net::{IpAddr, Ipv6Addr};
+use std::ops::{Bound, RangeInclusive};
+
 use tantivy::json_utils::convert_to_fast_value_and_append_to_json_term;
-use tantivy::query::TermQuery as TantivyTermQuery;
+use tantivy::query::{FastFieldRangeQuery, TermQuery as TantivyTermQuery};
 use tantivy::schema::{
     Field, FieldEntry, FieldType, IndexRecordOption, JsonObjectOptions, Schema as TantivySchema,
     Type,
@@ -105,6 +108,25 @@ fn parse_value_from_user_text<'a, T: InterpretUserInput<'a>>(
     })
 }
 
+/// Parses a CIDR block such as `10.0.0.0/8` or `2001:db8::/32` into the range of addresses it
+/// covers. IPv4 addresses are mapped to IPv6, like in IP fields.
+fn parse_ip_cidr(text: &str) -> Option<RangeInclusive<Ipv6Addr>> {
+    let (ip_str, prefix_len_str) = text.split_once('/')?;
+    let ip_addr: IpAddr = ip_str.parse().ok()?;
+    let prefix_len: u32 = prefix_len_str.parse().ok()?;
+
+    let (ip_v6, prefix_len) = match ip_addr {
+        IpAddr::V4(ip_v4) if prefix_len <= 32 => (ip_v4.to_ipv6_mapped(), prefix_len + 96),
+        IpAddr::V6(ip_v6) if prefix_len <= 128 => (ip_v6, prefix_len),
+        _ => return None,
+    };
+    let host_mask = u128::MAX.checked_shr(prefix_len).unwrap_or(0);
+    let ip_u128 = u128::from(ip_v6);
+    let first_ip = Ipv6Addr::from(ip_u128 & !host_mask);
+    let last_ip = Ipv6Addr::from(ip_u128 | host_mask);
+    Some(first_ip..=last_ip)
+}
+
 fn compute_query_with_field(
     field: Field,
     field_entry: &FieldEntry,
@@ -156,6 +178,21 @@ fn compute_query_with_field(
             full_text_params.make_query(terms, text_field_indexing.index_option())
         }
         FieldType::IpAddr(_) => {
+            if let Some(ip_range) = parse_ip_cidr(value) {
+                if !field_entry.is_fast() {
+                    return Err(InvalidQuery::SchemaError(format!(
+                        "CIDR queries are only supported for fast fields. (`{}` is not a fast \
+                         field)",
+                        field_entry.name()
+                    )));
+                }
+                let (first_ip, last_ip) = ip_range.into_inner();
+                return Ok(FastFieldRangeQuery::new(
+                    Bound::Included(Term::from_field_ip_addr(field, first_ip)),
+                    Bound::Included(Term::from_field_ip_addr(field, last_ip)),
+                )
+                .into());
+            }
             let ip_v6 = parse_value_from_user_text(value, field_entry.name())?;
             let term = Term::from_field_ip_addr(field, ip_v6);
             Ok(make_term_query(term))
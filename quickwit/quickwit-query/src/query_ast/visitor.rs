@@ -21,8 +21,8 @@ use crate::not_nan_f32::NotNaNf32;
 use crate::query_ast::field_presence::FieldPresenceQuery;
 use crate::query_ast::user_input_query::UserInputQuery;
 use crate::query_ast::{
-    BoolQuery, FullTextQuery, PhrasePrefixQuery, QueryAst, RangeQuery, TermQuery, TermSetQuery,
-    WildcardQuery,
+    BoolQuery, DisjunctionMaxQuery, FullTextQuery, PhrasePrefixQuery, QueryAst, RangeQuery,
+    RegexQuery, TermQuery, TermSetQuery, WildcardQuery,
 };
 
 /// Simple trait to implement a Visitor over the QueryAst.
@@ -32,6 +32,9 @@ pub trait QueryAstVisitor<'a> {
     fn visit(&mut self, query_ast: &'a QueryAst) -> Result<(), Self::Err> {
         match query_ast {
             QueryAst::Bool(bool_query) => self.visit_bool(bool_query),
+            QueryAst::DisjunctionMax(disjunction_max_query) => {
+                self.visit_disjunction_max(disjunction_max_query)
+            }
             QueryAst::Term(term_query) => self.visit_term(term_query),
             QueryAst::TermSet(term_set_query) => self.visit_term_set(term_set_query),
             QueryAst::FullText(full_text_query) => self.visit_full_text(full_text_query),
@@ -45,6 +48,7 @@ pub trait QueryAstVisitor<'a> {
             QueryAst::UserInput(user_text_query) => self.visit_user_text(user_text_query),
             QueryAst::FieldPresence(exists) => self.visit_exists(exists),
             QueryAst::Wildcard(wildcard) => self.visit_wildcard(wildcard),
+            QueryAst::Regex(regex) => self.visit_regex(regex),
         }
     }
 
@@ -61,6 +65,16 @@ pub trait QueryAstVisitor<'a> {
         Ok(())
     }
 
+    fn visit_disjunction_max(
+        &mut self,
+        disjunction_max_query: &'a DisjunctionMaxQuery,
+    ) -> Result<(), Self::Err> {
+        for ast in &disjunction_max_query.disjuncts {
+            self.visit(ast)?;
+        }
+        Ok(())
+    }
+
     fn visit_term(&mut self, _term_query: &'a TermQuery) -> Result<(), Self::Err> {
         Ok(())
     }
@@ -111,6 +125,10 @@ pub trait QueryAstVisitor<'a> {
     fn visit_wildcard(&mut self, _wildcard_query: &'a WildcardQuery) -> Result<(), Self::Err> {
         Ok(())
     }
+
+    fn visit_regex(&mut self, _regex_query: &'a RegexQuery) -> Result<(), Self::Err> {
+        Ok(())
+    }
 }
 
 /// Simple trait to implement a Visitor over the QueryAst.
@@ -120,6 +138,9 @@ pub trait QueryAstTransformer {
     fn transform(&mut self, query_ast: QueryAst) -> Result<Option<QueryAst>, Self::Err> {
         match query_ast {
             QueryAst::Bool(bool_query) => self.transform_bool(bool_query),
+            QueryAst::DisjunctionMax(disjunction_max_query) => {
+                self.transform_disjunction_max(disjunction_max_query)
+            }
             QueryAst::Term(term_query) => self.transform_term(term_query),
             QueryAst::TermSet(term_set_query) => self.transform_term_set(term_set_query),
             QueryAst::FullText(full_text_query) => self.transform_full_text(full_text_query),
@@ -133,6 +154,7 @@ pub trait QueryAstTransformer {
             QueryAst::UserInput(user_text_query) => self.transform_user_text(user_text_query),
             QueryAst::FieldPresence(exists) => self.transform_exists(exists),
             QueryAst::Wildcard(wildcard) => self.transform_wildcard(wildcard),
+            QueryAst::Regex(regex) => self.transform_regex(regex),
         }
     }
 
@@ -161,6 +183,18 @@ pub trait QueryAstTransformer {
         Ok(Some(QueryAst::Bool(bool_query)))
     }
 
+    fn transform_disjunction_max(
+        &mut self,
+        mut disjunction_max_query: DisjunctionMaxQuery,
+    ) -> Result<Option<QueryAst>, Self::Err> {
+        disjunction_max_query.disjuncts = disjunction_max_query
+            .disjuncts
+            .into_iter()
+            .filter_map(|query_ast| self.transform(query_ast).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(QueryAst::DisjunctionMax(disjunction_max_query)))
+    }
+
     fn transform_term(&mut self, term_query: TermQuery) -> Result<Option<QueryAst>, Self::Err> {
         Ok(Some(QueryAst::Term(term_query)))
     }
@@ -231,4 +265,8 @@ pub trait QueryAstTransformer {
     ) -> Result<Option<QueryAst>, Self::Err> {
         Ok(Some(QueryAst::Wildcard(wildcard_query)))
     }
+
+    fn transform_regex(&mut self, regex_query: RegexQuery) -> Result<Option<QueryAst>, Self::Err> {
+        Ok(Some(QueryAst::Regex(regex_query)))
+    }
 }
@@ -18,22 +18,42 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use anyhow::{anyhow, bail, Context};
+use quickwit_common::is_false;
 use serde::{Deserialize, Serialize};
+use tantivy::query::{EnableScoring, Explanation, Query, Scorer, Weight};
 use tantivy::schema::{Field, FieldType, Schema as TantivySchema};
-use tantivy::Term;
+use tantivy::tokenizer::TextAnalyzer;
+use tantivy::{DocId, Score, SegmentReader, TantivyError, Term};
 
-use super::{BuildTantivyAst, QueryAst};
+use super::{BuildTantivyAst, QueryAst, RegexQuery};
 use crate::query_ast::TantivyQueryAst;
 use crate::tokenizers::TokenizerManager;
 use crate::{find_field_or_hit_dynamic, InvalidQuery};
 
+/// Maximum number of terms of a split term dictionary that a wildcard query is allowed to
+/// scan.
+///
+/// Wildcard queries that are not a simple prefix query are run as an automaton against the
+/// whole term dictionary of the targeted field. Splits whose term dictionary for that field
+/// is larger than this budget fail the query rather than running an unbounded scan.
+pub const MAX_WILDCARD_SCANNED_TERMS: u64 = 10_000_000;
+
 /// A Wildcard query allows to match 'bond' with a query like 'b*d'.
 ///
-/// At the moment, only wildcard at end of term is supported.
+/// `*` matches any sequence of characters and `?` matches a single character. They can be
+/// escaped with `\`.
+///
+/// Patterns ending with a single `*` are run as a prefix query. Other patterns are run
+/// against the term dictionary of the field. Patterns starting with a wildcard, like `*bond`,
+/// require a scan of the whole term dictionary and are rejected unless
+/// `allow_leading_wildcard` is set.
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
 pub struct WildcardQuery {
     pub field: String,
     pub value: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub allow_leading_wildcard: bool,
 }
 
 impl From<WildcardQuery> for QueryAst {
@@ -48,10 +68,71 @@ impl WildcardQuery {
         Self {
             field: field.to_string(),
             value: value.to_string(),
+            allow_leading_wildcard: false,
         }
     }
 }
 
+#[derive(Debug, Eq, PartialEq)]
+enum WildcardToken {
+    Literal(String),
+    // `*`
+    AnyChars,
+    // `?`
+    AnyChar,
+}
+
+fn tokenize_wildcard_pattern(pattern: &str) -> anyhow::Result<Vec<WildcardToken>> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        let wildcard_token = match c {
+            '\\' => {
+                let escaped_char = chars
+                    .next()
+                    .ok_or_else(|| anyhow!("wildcard query ends with an escape character"))?;
+                literal.push(escaped_char);
+                continue;
+            }
+            '*' => WildcardToken::AnyChars,
+            '?' => WildcardToken::AnyChar,
+            _ => {
+                literal.push(c);
+                continue;
+            }
+        };
+        if !literal.is_empty() {
+            tokens.push(WildcardToken::Literal(std::mem::take(&mut literal)));
+        }
+        // `**` is equivalent to `*`.
+        if wildcard_token == WildcardToken::AnyChars
+            && tokens.last() == Some(&WildcardToken::AnyChars)
+        {
+            continue;
+        }
+        tokens.push(wildcard_token);
+    }
+    if !literal.is_empty() {
+        tokens.push(WildcardToken::Literal(literal));
+    }
+    if !tokens
+        .iter()
+        .any(|token| !matches!(token, WildcardToken::Literal(_)))
+    {
+        bail!("Wildcard query doesn't contain a wildcard");
+    }
+    Ok(tokens)
+}
+
+/// Returns the prefix of patterns of the form `prefix*`.
+fn as_prefix_pattern(tokens: &[WildcardToken]) -> Option<&str> {
+    match tokens {
+        [WildcardToken::Literal(prefix), WildcardToken::AnyChars] => Some(prefix),
+        _ => None,
+    }
+}
+
 fn extract_unique_token(mut tokens: Vec<Term>) -> anyhow::Result<Term> {
     let term = tokens
         .pop()
@@ -62,57 +143,46 @@ fn extract_unique_token(mut tokens: Vec<Term>) -> anyhow::Result<Term> {
     Ok(term)
 }
 
-fn unescape_with_final_wildcard(phrase: &str) -> anyhow::Result<String> {
-    enum State {
-        Normal,
-        Escaped,
-    }
+fn get_normalizer(
+    field_type: &FieldType,
+    field_name: &str,
+    tokenizer_manager: &TokenizerManager,
+) -> Result<TextAnalyzer, InvalidQuery> {
+    let text_field_indexing_opt = match field_type {
+        FieldType::Str(text_options) => text_options.get_indexing_options(),
+        FieldType::JsonObject(json_options) => json_options.get_text_indexing_options(),
+        _ => {
+            return Err(InvalidQuery::SchemaError(
+                "trying to run a Wildcard query on a non-text field".to_string(),
+            ))
+        }
+    };
+    let text_field_indexing = text_field_indexing_opt.ok_or_else(|| {
+        InvalidQuery::SchemaError(format!("field {field_name} is not full-text searchable"))
+    })?;
+    let tokenizer_name = text_field_indexing.tokenizer();
+    let normalizer = tokenizer_manager
+        .get_normalizer(tokenizer_name)
+        .with_context(|| format!("no tokenizer named `{}` is registered", tokenizer_name))?;
+    Ok(normalizer)
+}
 
-    // we keep this state outside of scan because we want to query if after
-    let mut saw_wildcard = false;
-    let saw_wildcard = &mut saw_wildcard;
+impl WildcardQuery {
+    /// Returns true if the pattern starts with a wildcard, e.g. `*bond`.
+    pub fn has_leading_wildcard(&self) -> bool {
+        tokenize_wildcard_pattern(&self.value)
+            .map(|tokens| !matches!(tokens.first(), Some(WildcardToken::Literal(_))))
+            .unwrap_or(false)
+    }
 
-    let phrase = phrase
-        .chars()
-        .scan(State::Normal, |state, c| {
-            if *saw_wildcard {
-                return Some(Some(Err(anyhow!(
-                    "Wildcard iquery contains wildcard in non final position"
-                ))));
-            }
-            match state {
-                State::Escaped => {
-                    *state = State::Normal;
-                    Some(Some(Ok(c)))
-                }
-                State::Normal => {
-                    if c == '*' {
-                        *saw_wildcard = true;
-                        Some(None)
-                    } else if c == '\\' {
-                        *state = State::Escaped;
-                        Some(None)
-                    } else if c == '?' {
-                        Some(Some(Err(anyhow!("Wildcard query contains `?`"))))
-                    } else {
-                        Some(Some(Ok(c)))
-                    }
-                }
-            }
-        })
-        // we have an iterator of Option<Result<char, anyhow::Error>>
-        .flatten()
-        // we have an iterator of Result<char, anyhow::Error>
-        .collect::<Result<String, _>>()?;
-    if !*saw_wildcard {
-        bail!("Wildcard query doesn't contain a wildcard");
+    /// Returns true if the query cannot be run as a prefix query and requires running an
+    /// automaton against the term dictionary of the field.
+    pub fn requires_term_dict_scan(&self) -> bool {
+        tokenize_wildcard_pattern(&self.value)
+            .map(|tokens| as_prefix_pattern(&tokens).is_none())
+            .unwrap_or(false)
     }
-    Ok(phrase)
-}
 
-impl WildcardQuery {
-    // TODO this method will probably disappear once we support the full semantic of
-    // wildcard queries
     pub fn extract_prefix_term(
         &self,
         schema: &TantivySchema,
@@ -121,48 +191,18 @@ impl WildcardQuery {
         let (field, field_entry, json_path) = find_field_or_hit_dynamic(&self.field, schema)?;
         let field_type = field_entry.field_type();
 
-        let prefix = unescape_with_final_wildcard(&self.value)?;
-
+        let tokens = tokenize_wildcard_pattern(&self.value)?;
+        let prefix = as_prefix_pattern(&tokens).ok_or_else(|| {
+            anyhow!(
+                "wildcard query `{}` is not a prefix query (wildcard in non final position)",
+                self.value
+            )
+        })?;
+        let mut normalizer = get_normalizer(field_type, field_entry.name(), tokenizer_manager)?;
+        let mut token_stream = normalizer.token_stream(prefix);
+        let mut tokens = Vec::new();
         match field_type {
-            FieldType::Str(ref text_options) => {
-                let text_field_indexing = text_options.get_indexing_options().ok_or_else(|| {
-                    InvalidQuery::SchemaError(format!(
-                        "field {} is not full-text searchable",
-                        field_entry.name()
-                    ))
-                })?;
-                let tokenizer_name = text_field_indexing.tokenizer();
-                let mut normalizer = tokenizer_manager
-                    .get_normalizer(tokenizer_name)
-                    .with_context(|| {
-                        format!("no tokenizer named `{}` is registered", tokenizer_name)
-                    })?;
-                let mut token_stream = normalizer.token_stream(&prefix);
-                let mut tokens = Vec::new();
-                token_stream.process(&mut |token| {
-                    let term: Term = Term::from_field_text(field, &token.text);
-                    tokens.push(term);
-                });
-                let term = extract_unique_token(tokens)?;
-                Ok((field, term))
-            }
             FieldType::JsonObject(json_options) => {
-                let text_field_indexing =
-                    json_options.get_text_indexing_options().ok_or_else(|| {
-                        InvalidQuery::SchemaError(format!(
-                            "field {} is not full-text searchable",
-                            field_entry.name()
-                        ))
-                    })?;
-                let tokenizer_name = text_field_indexing.tokenizer();
-                let mut normalizer = tokenizer_manager
-                    .get_normalizer(tokenizer_name)
-                    .with_context(|| {
-                        format!("no tokenizer named `{}` is registered", tokenizer_name)
-                    })?;
-                let mut token_stream = normalizer.token_stream(&prefix);
-                let mut tokens = Vec::new();
-
                 token_stream.process(&mut |token| {
                     let mut term = Term::from_field_json_path(
                         field,
@@ -172,13 +212,49 @@ impl WildcardQuery {
                     term.append_type_and_str(&token.text);
                     tokens.push(term);
                 });
-                let term = extract_unique_token(tokens)?;
-                Ok((field, term))
             }
-            _ => Err(InvalidQuery::SchemaError(
-                "trying to run a Wildcard query on a non-text field".to_string(),
-            )),
+            _ => {
+                token_stream.process(&mut |token| {
+                    let term: Term = Term::from_field_text(field, &token.text);
+                    tokens.push(term);
+                });
+            }
         }
+        let term = extract_unique_token(tokens)?;
+        Ok((field, term))
+    }
+
+    /// Converts the wildcard pattern into a regular expression matching the normalized terms
+    /// of the field.
+    fn to_regex_query(
+        &self,
+        schema: &TantivySchema,
+        tokenizer_manager: &TokenizerManager,
+    ) -> Result<RegexQuery, InvalidQuery> {
+        let (_field, field_entry, _json_path) = find_field_or_hit_dynamic(&self.field, schema)?;
+        let mut normalizer = get_normalizer(
+            field_entry.field_type(),
+            field_entry.name(),
+            tokenizer_manager,
+        )?;
+        let mut regex = String::new();
+        for token in tokenize_wildcard_pattern(&self.value)? {
+            match token {
+                WildcardToken::Literal(literal) => {
+                    let mut token_stream = normalizer.token_stream(&literal);
+                    token_stream.process(&mut |token| {
+                        regex.push_str(&regex::escape(&token.text));
+                    });
+                }
+                WildcardToken::AnyChars => regex.push_str(".*"),
+                WildcardToken::AnyChar => regex.push('.'),
+            }
+        }
+        Ok(RegexQuery {
+            field: self.field.clone(),
+            regex,
+            case_insensitive: false,
+        })
     }
 }
 
@@ -190,12 +266,91 @@ impl BuildTantivyAst for WildcardQuery {
         _search_fields: &[String],
         _with_validation: bool,
     ) -> Result<TantivyQueryAst, InvalidQuery> {
-        let (_, term) = self.extract_prefix_term(schema, tokenizer_manager)?;
+        if !self.requires_term_dict_scan() {
+            let (_, term) = self.extract_prefix_term(schema, tokenizer_manager)?;
+
+            let mut phrase_prefix_query =
+                tantivy::query::PhrasePrefixQuery::new_with_offset(vec![(0, term)]);
+            phrase_prefix_query.set_max_expansions(u32::MAX);
+            return Ok(phrase_prefix_query.into());
+        }
+        if self.has_leading_wildcard() && !self.allow_leading_wildcard {
+            return Err(anyhow!(
+                "wildcard query `{}` starts with a wildcard. leading wildcards require a scan of \
+                 the whole term dictionary and must be enabled with `allow_leading_wildcard`",
+                self.value
+            )
+            .into());
+        }
+        let regex_query = self.to_regex_query(schema, tokenizer_manager)?;
+        let (field, regex) = regex_query.to_field_and_regex(schema)?;
+        let automaton_query = tantivy::query::RegexQuery::from_pattern(&regex, field)
+            .map_err(|err| anyhow!("invalid wildcard query `{}`: {err}", self.value))?;
+        let bounded_query = TermDictScanBudgetQuery {
+            underlying: Box::new(automaton_query),
+            field,
+            max_scanned_terms: MAX_WILDCARD_SCANNED_TERMS,
+        };
+        Ok(bounded_query.into())
+    }
+}
+
+/// Wraps a query running an automaton against the term dictionary of a field, and fails
+/// on segments whose term dictionary exceeds the scan budget.
+#[derive(Debug, Clone)]
+struct TermDictScanBudgetQuery {
+    underlying: Box<dyn Query>,
+    field: Field,
+    max_scanned_terms: u64,
+}
+
+impl Query for TermDictScanBudgetQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        let underlying = self.underlying.weight(enable_scoring)?;
+        Ok(Box::new(TermDictScanBudgetWeight {
+            underlying,
+            field: self.field,
+            max_scanned_terms: self.max_scanned_terms,
+        }))
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        self.underlying.query_terms(visitor);
+    }
+}
+
+struct TermDictScanBudgetWeight {
+    underlying: Box<dyn Weight>,
+    field: Field,
+    max_scanned_terms: u64,
+}
 
-        let mut phrase_prefix_query =
-            tantivy::query::PhrasePrefixQuery::new_with_offset(vec![(0, term)]);
-        phrase_prefix_query.set_max_expansions(u32::MAX);
-        Ok(phrase_prefix_query.into())
+impl TermDictScanBudgetWeight {
+    fn check_budget(&self, reader: &SegmentReader) -> tantivy::Result<()> {
+        let num_terms = reader.inverted_index(self.field)?.terms().num_terms() as u64;
+        if num_terms > self.max_scanned_terms {
+            return Err(TantivyError::InvalidArgument(format!(
+                "wildcard query would scan {num_terms} terms, exceeding the budget of {} terms",
+                self.max_scanned_terms
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Weight for TermDictScanBudgetWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        self.check_budget(reader)?;
+        self.underlying.scorer(reader, boost)
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        self.underlying.explain(reader, doc)
+    }
+
+    fn count(&self, reader: &SegmentReader) -> tantivy::Result<u32> {
+        self.check_budget(reader)?;
+        self.underlying.count(reader)
     }
 }
 
@@ -208,10 +363,10 @@ mod tests {
 
     #[test]
     fn test_extract_term_for_wildcard() {
-        let query = WildcardQuery {
-            field: "my_field".to_string(),
-            value: "MyString Wh1ch a nOrMal Tokenizer would cut*".to_string(),
-        };
+        let query = WildcardQuery::from_field_value(
+            "my_field",
+            "MyString Wh1ch a nOrMal Tokenizer would cut*",
+        );
         let tokenizer_manager = create_default_quickwit_tokenizer_manager();
         for tokenizer in ["raw", "whitespace"] {
             let mut schema_builder = TantivySchema::builder();
@@ -252,4 +407,94 @@ mod tests {
             assert_eq!(text, &query.value.trim_end_matches('*').to_lowercase());
         }
     }
+
+    #[test]
+    fn test_tokenize_wildcard_pattern() {
+        assert_eq!(
+            tokenize_wildcard_pattern("b*d").unwrap(),
+            vec![
+                WildcardToken::Literal("b".to_string()),
+                WildcardToken::AnyChars,
+                WildcardToken::Literal("d".to_string()),
+            ]
+        );
+        assert_eq!(
+            tokenize_wildcard_pattern(r"**b\*?").unwrap(),
+            vec![
+                WildcardToken::AnyChars,
+                WildcardToken::Literal("b*".to_string()),
+                WildcardToken::AnyChar,
+            ]
+        );
+        assert!(tokenize_wildcard_pattern(r"bond\*").is_err());
+        assert!(tokenize_wildcard_pattern(r"bond\").is_err());
+    }
+
+    #[test]
+    fn test_wildcard_query_requires_term_dict_scan() {
+        let prefix_query = WildcardQuery::from_field_value("my_field", "bon*");
+        assert!(!prefix_query.requires_term_dict_scan());
+        assert!(!prefix_query.has_leading_wildcard());
+
+        let infix_query = WildcardQuery::from_field_value("my_field", "b?n*d");
+        assert!(infix_query.requires_term_dict_scan());
+        assert!(!infix_query.has_leading_wildcard());
+
+        let substring_query = WildcardQuery::from_field_value("my_field", "*ond*");
+        assert!(substring_query.requires_term_dict_scan());
+        assert!(substring_query.has_leading_wildcard());
+    }
+
+    #[test]
+    fn test_wildcard_query_to_regex() {
+        let mut schema_builder = TantivySchema::builder();
+        schema_builder.add_text_field("my_field", tantivy::schema::TEXT);
+        let schema = schema_builder.build();
+        let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+        let query = WildcardQuery::from_field_value("my_field", "*Bo?d.*");
+        let regex_query = query.to_regex_query(&schema, &tokenizer_manager).unwrap();
+        assert_eq!(regex_query.regex, r".*bo.d\..*");
+    }
+
+    #[test]
+    fn test_wildcard_query_leading_wildcard_opt_in() {
+        let mut schema_builder = TantivySchema::builder();
+        schema_builder.add_text_field("my_field", tantivy::schema::TEXT);
+        let schema = schema_builder.build();
+        let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+
+        let mut query = WildcardQuery::from_field_value("my_field", "*ond");
+        let error = query
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap_err();
+        assert!(error.to_string().contains("allow_leading_wildcard"));
+
+        query.allow_leading_wildcard = true;
+        let tantivy_ast = query
+            .build_tantivy_ast_call(&schema, &tokenizer_manager, &[], true)
+            .unwrap();
+        let leaf_str = format!("{:?}", tantivy_ast.as_leaf().unwrap());
+        assert!(leaf_str.starts_with("TermDictScanBudgetQuery"));
+        assert!(leaf_str.contains("max_scanned_terms: 10000000"));
+    }
+
+    #[test]
+    fn test_wildcard_query_serialization() {
+        let query: QueryAst = WildcardQuery::from_field_value("my_field", "b*d").into();
+        let query_json = serde_json::to_string(&query).unwrap();
+        assert_eq!(
+            query_json,
+            r#"{"type":"wildcard","field":"my_field","value":"b*d"}"#
+        );
+        let query = WildcardQuery {
+            field: "my_field".to_string(),
+            value: "*b*".to_string(),
+            allow_leading_wildcard: true,
+        };
+        let query_json = serde_json::to_string(&query).unwrap();
+        assert_eq!(
+            query_json,
+            r#"{"field":"my_field","value":"*b*","allow_leading_wildcard":true}"#
+        );
+    }
 }
@@ -0,0 +1,434 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::ops::Range;
+
+use anyhow::Context;
+use regex::Regex;
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+/// HTML elements that are replaced by a line break instead of being removed, so that the words
+/// they separate are not glued together.
+const HTML_BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+
+/// A character filter transforms the text before it is tokenized.
+#[derive(Clone, Debug)]
+pub enum CharFilter {
+    /// Strips HTML tags, comments, and the content of `script` and `style` elements, and decodes
+    /// HTML entities.
+    HtmlStrip,
+    /// Replaces the matches of a regular expression. The replacement can reference capture groups
+    /// with `$1` or `${name}`.
+    PatternReplace { regex: Regex, replacement: String },
+}
+
+impl CharFilter {
+    pub fn pattern_replace(pattern: &str, replacement: &str) -> anyhow::Result<Self> {
+        let regex = Regex::new(pattern)
+            .with_context(|| format!("invalid `pattern_replace` pattern `{pattern}`"))?;
+        Ok(CharFilter::PatternReplace {
+            regex,
+            replacement: replacement.to_string(),
+        })
+    }
+
+    fn apply(&self, output: &mut CharFilterOutput) {
+        match self {
+            CharFilter::HtmlStrip => strip_html(output),
+            CharFilter::PatternReplace { regex, replacement } => {
+                let input = output.input;
+                let mut copy_start = 0;
+                let mut expanded_replacement = String::new();
+
+                for captures in regex.captures_iter(input) {
+                    let matched = captures.get(0).expect("group 0 should always be present");
+                    output.copy(copy_start..matched.start());
+                    expanded_replacement.clear();
+                    captures.expand(replacement, &mut expanded_replacement);
+                    output.replace(matched.range(), &expanded_replacement);
+                    copy_start = matched.end();
+                }
+                output.copy(copy_start..input.len());
+            }
+        }
+    }
+}
+
+fn strip_html(output: &mut CharFilterOutput) {
+    let input = output.input;
+    let mut copy_start = 0;
+    let mut pos = 0;
+    let mut char_buffer = [0u8; 4];
+
+    while pos < input.len() {
+        let markup_opt: Option<(usize, &str)> = match input.as_bytes()[pos] {
+            b'<' => parse_html_markup(input, pos),
+            b'&' => match parse_html_entity(input, pos) {
+                Some((entity_end, ch)) => Some((entity_end, &*ch.encode_utf8(&mut char_buffer))),
+                None => None,
+            },
+            _ => None,
+        };
+        let Some((markup_end, replacement)) = markup_opt else {
+            pos += 1;
+            continue;
+        };
+        output.copy(copy_start..pos);
+        output.replace(pos..markup_end, replacement);
+        pos = markup_end;
+        copy_start = pos;
+    }
+    output.copy(copy_start..input.len());
+}
+
+/// Parses the HTML tag, comment, or declaration starting at `start` and returns its end offset
+/// along with the text it should be replaced with.
+fn parse_html_markup(input: &str, start: usize) -> Option<(usize, &'static str)> {
+    let markup = &input[start..];
+
+    if markup.starts_with("<!--") {
+        let comment_len = markup.find("-->")? + 3;
+        return Some((start + comment_len, ""));
+    }
+    let tag_end = start + markup.find('>')? + 1;
+    let tag = &input[start + 1..tag_end - 1];
+
+    if tag.starts_with('!') || tag.starts_with('?') {
+        return Some((tag_end, ""));
+    }
+    let (is_closing_tag, tag) = match tag.strip_prefix('/') {
+        Some(tag) => (true, tag),
+        None => (false, tag),
+    };
+    if !tag.starts_with(|ch: char| ch.is_ascii_alphabetic()) {
+        return None;
+    }
+    let tag_name = tag
+        .split(|ch: char| ch.is_ascii_whitespace() || ch == '/')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    if !is_closing_tag && (tag_name == "script" || tag_name == "style") {
+        let closing_tag = format!("</{tag_name}");
+        let content = input[tag_end..].to_ascii_lowercase();
+        let element_end = content
+            .find(&closing_tag)
+            .and_then(|closing_tag_start| {
+                content[closing_tag_start..]
+                    .find('>')
+                    .map(|closing_tag_len| tag_end + closing_tag_start + closing_tag_len + 1)
+            })
+            .unwrap_or(input.len());
+        return Some((element_end, ""));
+    }
+    if HTML_BLOCK_ELEMENTS.contains(&tag_name.as_str()) {
+        Some((tag_end, "\n"))
+    } else {
+        Some((tag_end, ""))
+    }
+}
+
+/// Parses the HTML entity starting at `start` and returns its end offset along with the character
+/// it represents.
+fn parse_html_entity(input: &str, start: usize) -> Option<(usize, char)> {
+    let entity = &input[start + 1..];
+    let entity_len = entity.find(';').filter(|len| (1..=10).contains(len))?;
+    let entity_name = &entity[..entity_len];
+
+    let ch = if let Some(code_point) = entity_name.strip_prefix('#') {
+        let code_point = if let Some(hex_code_point) = code_point.strip_prefix(['x', 'X']) {
+            u32::from_str_radix(hex_code_point, 16).ok()?
+        } else {
+            code_point.parse::<u32>().ok()?
+        };
+        char::from_u32(code_point)?
+    } else {
+        match entity_name {
+            "amp" => '&',
+            "apos" => '\'',
+            "gt" => '>',
+            "lt" => '<',
+            "nbsp" => ' ',
+            "quot" => '"',
+            _ => return None,
+        }
+    };
+    Some((start + entity_len + 2, ch))
+}
+
+/// Text produced by a chain of character filters, along with the range of the original text
+/// each of its bytes originates from.
+#[derive(Clone, Default)]
+struct FilteredText {
+    text: String,
+    original_starts: Vec<usize>,
+    original_ends: Vec<usize>,
+}
+
+impl FilteredText {
+    fn clear(&mut self) {
+        self.text.clear();
+        self.original_starts.clear();
+        self.original_ends.clear();
+    }
+
+    fn original_range(&self, range: Range<usize>, original_len: usize) -> Range<usize> {
+        if range.is_empty() {
+            let original_offset = self
+                .original_starts
+                .get(range.start)
+                .copied()
+                .unwrap_or(original_len);
+            return original_offset..original_offset;
+        }
+        self.original_starts[range.start]..self.original_ends[range.end - 1]
+    }
+}
+
+/// Output of a character filter.
+struct CharFilterOutput<'a> {
+    input: &'a str,
+    /// Mapping of the input to the original text. `None` when the input is the original text.
+    input_mapping_opt: Option<&'a FilteredText>,
+    original_len: usize,
+    output: &'a mut FilteredText,
+}
+
+impl CharFilterOutput<'_> {
+    fn original_range(&self, range: Range<usize>) -> Range<usize> {
+        match self.input_mapping_opt {
+            Some(input_mapping) => input_mapping.original_range(range, self.original_len),
+            None => range,
+        }
+    }
+
+    /// Appends a range of the input, unchanged.
+    fn copy(&mut self, range: Range<usize>) {
+        self.output.text.push_str(&self.input[range.clone()]);
+
+        for offset in range {
+            let original_range = self.original_range(offset..offset + 1);
+            self.output.original_starts.push(original_range.start);
+            self.output.original_ends.push(original_range.end);
+        }
+    }
+
+    /// Appends the replacement of a range of the input.
+    fn replace(&mut self, range: Range<usize>, replacement: &str) {
+        let original_range = self.original_range(range);
+        self.output.text.push_str(replacement);
+
+        for _ in 0..replacement.len() {
+            self.output.original_starts.push(original_range.start);
+            self.output.original_ends.push(original_range.end);
+        }
+    }
+}
+
+/// A tokenizer applying a chain of [`CharFilter`] to the text before handing it over to the
+/// wrapped tokenizer.
+///
+/// The offsets of the emitted tokens refer to the original text.
+#[derive(Clone)]
+pub struct CharFilterTokenizer<T> {
+    char_filters: Vec<CharFilter>,
+    inner: T,
+    filtered_text: FilteredText,
+    filtered_text_buffer: FilteredText,
+}
+
+impl<T: Tokenizer> CharFilterTokenizer<T> {
+    pub fn new(char_filters: Vec<CharFilter>, tokenizer: T) -> Self {
+        Self {
+            char_filters,
+            inner: tokenizer,
+            filtered_text: FilteredText::default(),
+            filtered_text_buffer: FilteredText::default(),
+        }
+    }
+}
+
+impl<T: Tokenizer> Tokenizer for CharFilterTokenizer<T> {
+    type TokenStream<'a> = CharFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        if self.char_filters.is_empty() {
+            return CharFilterTokenStream {
+                tail: self.inner.token_stream(text),
+                filtered_text_opt: None,
+                original_len: text.len(),
+            };
+        }
+        for (char_filter_idx, char_filter) in self.char_filters.iter().enumerate() {
+            std::mem::swap(&mut self.filtered_text, &mut self.filtered_text_buffer);
+            self.filtered_text.clear();
+
+            let mut output = if char_filter_idx == 0 {
+                CharFilterOutput {
+                    input: text,
+                    input_mapping_opt: None,
+                    original_len: text.len(),
+                    output: &mut self.filtered_text,
+                }
+            } else {
+                CharFilterOutput {
+                    input: &self.filtered_text_buffer.text,
+                    input_mapping_opt: Some(&self.filtered_text_buffer),
+                    original_len: text.len(),
+                    output: &mut self.filtered_text,
+                }
+            };
+            char_filter.apply(&mut output);
+        }
+        CharFilterTokenStream {
+            tail: self.inner.token_stream(&self.filtered_text.text),
+            filtered_text_opt: Some(&self.filtered_text),
+            original_len: text.len(),
+        }
+    }
+}
+
+pub struct CharFilterTokenStream<'a, T> {
+    tail: T,
+    filtered_text_opt: Option<&'a FilteredText>,
+    original_len: usize,
+}
+
+impl<'a, T: TokenStream> TokenStream for CharFilterTokenStream<'a, T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        if let Some(filtered_text) = self.filtered_text_opt {
+            let token = self.tail.token_mut();
+            let original_range =
+                filtered_text.original_range(token.offset_from..token.offset_to, self.original_len);
+            token.offset_from = original_range.start;
+            token.offset_to = original_range.end;
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::tokenizer::{SimpleTokenizer, TokenStream, Tokenizer};
+
+    use super::{CharFilter, CharFilterTokenizer};
+
+    fn tokenize(char_filters: Vec<CharFilter>, text: &str) -> Vec<(String, usize, usize)> {
+        let mut tokenizer = CharFilterTokenizer::new(char_filters, SimpleTokenizer::default());
+        let mut token_stream = tokenizer.token_stream(text);
+        let mut tokens = Vec::new();
+        while let Some(token) = token_stream.next() {
+            tokens.push((token.text.clone(), token.offset_from, token.offset_to));
+        }
+        tokens
+    }
+
+    #[test]
+    fn test_html_strip_char_filter() {
+        let text = "<p>Hello<br/>W&amp;rld</p><script>var x = 1;</script>&#x41;<!-- hidden -->b";
+        let tokens = tokenize(vec![CharFilter::HtmlStrip], text);
+        assert_eq!(
+            tokens,
+            [
+                ("Hello".to_string(), 3, 8),
+                ("W".to_string(), 13, 14),
+                ("rld".to_string(), 19, 22),
+                ("Ab".to_string(), 53, 75),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_html_strip_char_filter_keeps_text_that_is_not_markup() {
+        let text = "1 < 2 && 3 > 2 &unknown; <3";
+        let tokens = tokenize(vec![CharFilter::HtmlStrip], text);
+        let token_texts: Vec<&str> = tokens.iter().map(|(text, _, _)| text.as_str()).collect();
+        assert_eq!(token_texts, ["1", "2", "3", "2", "unknown", "3"]);
+    }
+
+    #[test]
+    fn test_pattern_replace_char_filter() {
+        let char_filter = CharFilter::pattern_replace(r"(\w+)\.(\w+)", "${2}${1}").unwrap();
+        let tokens = tokenize(vec![char_filter], "call foo.bar now");
+        assert_eq!(
+            tokens,
+            [
+                ("call".to_string(), 0, 4),
+                ("barfoo".to_string(), 5, 12),
+                ("now".to_string(), 13, 16),
+            ]
+        );
+        let error = CharFilter::pattern_replace("(", "").unwrap_err();
+        assert_eq!(error.to_string(), "invalid `pattern_replace` pattern `(`");
+    }
+
+    #[test]
+    fn test_char_filter_chain() {
+        let char_filters = vec![
+            CharFilter::HtmlStrip,
+            CharFilter::pattern_replace("-", "").unwrap(),
+        ];
+        let tokens = tokenize(char_filters, "<b>e-mail</b>");
+        assert_eq!(tokens, [("email".to_string(), 3, 9)]);
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::bail;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// A token filter emitting the prefixes of each token, from `min_gram` to `max_gram` characters.
+///
+/// The prefixes are emitted at the position of the original token, which makes it possible to
+/// build search-as-you-type fields. Tokens shorter than `min_gram` characters are removed.
+#[derive(Clone, Debug)]
+pub struct EdgeNgramFilter {
+    min_gram: usize,
+    max_gram: usize,
+}
+
+impl EdgeNgramFilter {
+    pub fn new(min_gram: usize, max_gram: usize) -> anyhow::Result<Self> {
+        if min_gram == 0 {
+            bail!("`min_gram` must be greater than 0");
+        }
+        if min_gram > max_gram {
+            bail!("`min_gram` must be lower than or equal to `max_gram`");
+        }
+        Ok(Self { min_gram, max_gram })
+    }
+}
+
+impl TokenFilter for EdgeNgramFilter {
+    type Tokenizer<T: Tokenizer> = EdgeNgramFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> EdgeNgramFilterWrapper<T> {
+        EdgeNgramFilterWrapper {
+            min_gram: self.min_gram,
+            max_gram: self.max_gram,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct EdgeNgramFilterWrapper<T> {
+    min_gram: usize,
+    max_gram: usize,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for EdgeNgramFilterWrapper<T> {
+    type TokenStream<'a> = EdgeNgramTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        EdgeNgramTokenStream {
+            min_gram: self.min_gram,
+            max_gram: self.max_gram,
+            tail: self.inner.token_stream(text),
+            original_text: String::new(),
+            gram_ends: Vec::new(),
+            gram_idx: 0,
+        }
+    }
+}
+
+pub struct EdgeNgramTokenStream<T> {
+    min_gram: usize,
+    max_gram: usize,
+    tail: T,
+    /// Text of the token currently being split into prefixes.
+    original_text: String,
+    /// Byte offsets of the ends of the prefixes of `original_text` to emit.
+    gram_ends: Vec<usize>,
+    gram_idx: usize,
+}
+
+impl<T: TokenStream> TokenStream for EdgeNgramTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        loop {
+            if let Some(&gram_end) = self.gram_ends.get(self.gram_idx) {
+                self.gram_idx += 1;
+                let token = self.tail.token_mut();
+                token.text.clear();
+                token.text.push_str(&self.original_text[..gram_end]);
+                return true;
+            }
+            if !self.tail.advance() {
+                return false;
+            }
+            self.original_text.clear();
+            self.original_text.push_str(&self.tail.token().text);
+            self.gram_ends.clear();
+            self.gram_idx = 0;
+
+            let char_ends = self
+                .original_text
+                .char_indices()
+                .map(|(char_offset, ch)| char_offset + ch.len_utf8());
+            self.gram_ends.extend(
+                char_ends
+                    .skip(self.min_gram - 1)
+                    .take(self.max_gram - self.min_gram + 1),
+            );
+        }
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::tokenizer::{SimpleTokenizer, TextAnalyzer};
+
+    use super::EdgeNgramFilter;
+
+    fn analyze(min_gram: usize, max_gram: usize, text: &str) -> Vec<(String, usize)> {
+        let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(EdgeNgramFilter::new(min_gram, max_gram).unwrap())
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        while let Some(token) = token_stream.next() {
+            tokens.push((token.text.clone(), token.position));
+        }
+        tokens
+    }
+
+    #[test]
+    fn test_edge_ngram_filter() {
+        let tokens = analyze(2, 3, "a quick été");
+        assert_eq!(
+            tokens,
+            [
+                ("qu".to_string(), 1),
+                ("qui".to_string(), 1),
+                ("ét".to_string(), 2),
+                ("été".to_string(), 2),
+            ]
+        );
+        let tokens = analyze(1, 10, "fox");
+        assert_eq!(
+            tokens,
+            [
+                ("f".to_string(), 0),
+                ("fo".to_string(), 0),
+                ("fox".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_edge_ngram_filter_invalid_settings() {
+        let error = EdgeNgramFilter::new(0, 3).unwrap_err();
+        assert_eq!(error.to_string(), "`min_gram` must be greater than 0");
+
+        let error = EdgeNgramFilter::new(3, 2).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`min_gram` must be lower than or equal to `max_gram`"
+        );
+    }
+}
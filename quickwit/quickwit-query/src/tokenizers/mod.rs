@@ -17,10 +17,15 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+mod char_filters;
 mod chinese_compatible;
 mod code_tokenizer;
+mod edge_ngram_filter;
 #[cfg(feature = "multilang")]
 mod multilang;
+mod path_hierarchy;
+mod pattern_tokenizer;
+mod synonym_filter;
 mod tokenizer_manager;
 
 use once_cell::sync::Lazy;
@@ -29,10 +34,15 @@ use tantivy::tokenizer::{
     Stemmer, TextAnalyzer, WhitespaceTokenizer,
 };
 
+pub use self::char_filters::{CharFilter, CharFilterTokenizer};
 use self::chinese_compatible::ChineseTokenizer;
 pub use self::code_tokenizer::CodeTokenizer;
+pub use self::edge_ngram_filter::EdgeNgramFilter;
 #[cfg(feature = "multilang")]
-pub use self::multilang::MultiLangTokenizer;
+pub use self::multilang::{MultiLangTokenizer, MultiLangTokenizerConfig};
+pub use self::path_hierarchy::PathHierarchyTokenizer;
+pub use self::pattern_tokenizer::PatternTokenizer;
+pub use self::synonym_filter::SynonymFilter;
 pub use self::tokenizer_manager::TokenizerManager;
 
 pub const DEFAULT_REMOVE_TOKEN_LENGTH: usize = 255;
@@ -17,8 +17,14 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::io::Write;
+use std::sync::Arc;
+
 use lindera_core::mode::Mode;
-use lindera_dictionary::{load_dictionary_from_config, DictionaryConfig, DictionaryKind};
+use lindera_dictionary::{
+    load_dictionary_from_config, load_user_dictionary_from_config, DictionaryConfig,
+    DictionaryKind, UserDictionaryConfig,
+};
 use lindera_tokenizer::token::Token as LinderaToken;
 use lindera_tokenizer::tokenizer::Tokenizer as LinderaTokenizer;
 use once_cell::sync::Lazy;
@@ -62,6 +68,71 @@ static KOR_TOKENIZER: Lazy<LinderaTokenizer> = Lazy::new(|| {
     LinderaTokenizer::new(kor_dictionary, None, Mode::Normal)
 });
 
+/// Builds a lindera tokenizer, optionally extended with a user dictionary.
+///
+/// User dictionary entries are rows in the simple CSV user dictionary format of lindera, e.g.
+/// `東京スカイツリー,カスタム名詞,トウキョウスカイツリー`. Lindera only loads user dictionaries
+/// from files, so the entries are written to a temporary file first.
+fn build_lindera_tokenizer(
+    dictionary_kind: DictionaryKind,
+    user_dictionary_entries: &[String],
+) -> anyhow::Result<LinderaTokenizer> {
+    let dictionary_config = DictionaryConfig {
+        kind: Some(dictionary_kind.clone()),
+        path: None,
+    };
+    let dictionary = load_dictionary_from_config(dictionary_config).map_err(|error| {
+        anyhow::anyhow!("failed to load lindera `{dictionary_kind:?}` dictionary: {error}")
+    })?;
+    let user_dictionary_opt = if user_dictionary_entries.is_empty() {
+        None
+    } else {
+        if let Some(entry) = user_dictionary_entries
+            .iter()
+            .find(|entry| entry.contains(['\n', '\r']))
+        {
+            anyhow::bail!(
+                "user dictionary entry `{}` spans several lines",
+                entry.escape_debug()
+            );
+        }
+        // The `.csv` extension tells lindera to parse the file as a CSV user dictionary.
+        let mut user_dictionary_file = tempfile::Builder::new().suffix(".csv").tempfile()?;
+        for entry in user_dictionary_entries {
+            writeln!(user_dictionary_file, "{entry}")?;
+        }
+        user_dictionary_file.flush()?;
+
+        let user_dictionary_config = UserDictionaryConfig {
+            kind: Some(dictionary_kind),
+            path: user_dictionary_file.path().to_path_buf(),
+        };
+        let user_dictionary = load_user_dictionary_from_config(user_dictionary_config)
+            .map_err(|error| anyhow::anyhow!("failed to load user dictionary: {error}"))?;
+        Some(user_dictionary)
+    };
+    Ok(LinderaTokenizer::new(
+        dictionary,
+        user_dictionary_opt,
+        Mode::Normal,
+    ))
+}
+
+/// Configuration of a [`MultiLangTokenizer`].
+#[derive(Clone, Debug, Default)]
+pub struct MultiLangTokenizerConfig {
+    /// 3-letter code of the language of the texts. When set, the language detection is skipped.
+    pub language: Option<String>,
+    /// Dictionary used for Japanese: `ipadic` (default) or `unidic`.
+    pub japanese_dictionary: Option<String>,
+    /// Entries of a user dictionary extending the Chinese dictionary.
+    pub cmn_user_dictionary: Vec<String>,
+    /// Entries of a user dictionary extending the Japanese dictionary.
+    pub jpn_user_dictionary: Vec<String>,
+    /// Entries of a user dictionary extending the Korean dictionary.
+    pub kor_user_dictionary: Vec<String>,
+}
+
 /// Multilanguage tokenizer that uses the `whichlang` to detect the language of the text
 /// and uses the appropriate tokenizer for the detected language:
 /// - lindera for Chinese, Japanese, and Korean.
@@ -69,17 +140,97 @@ static KOR_TOKENIZER: Lazy<LinderaTokenizer> = Lazy::new(|| {
 ///
 /// It is possible to bypass the language detection by prefixing the text with the language code
 /// followed by a colon. For example, `KOR:일본입니다` will be tokenized by the korean tokenizer.
-/// Current supported prefix are:
+/// Prefixes are case-insensitive. Current supported prefix are:
 /// - `KOR:` for Korean tokenizer
 /// - `JPN:` for Japanese tokenizer
 /// - `CMN:` for Chinese tokenizer
 /// - `ENG:` for Quickwit's default tokenizer
+///
+/// When the language of the texts is known in advance, the tokenizer can be built with
+/// [`MultiLangTokenizer::with_language`] to skip the language detection altogether. Prefixes still
+/// take precedence over the language of the tokenizer.
+///
+/// The lindera dictionaries can be customized with [`MultiLangTokenizer::from_config`]. Custom
+/// lindera tokenizers are shared between clones.
 #[derive(Clone, Default)]
 pub struct MultiLangTokenizer {
     default_tokenizer: SimpleTokenizer,
+    language_opt: Option<Lang>,
+    cmn_tokenizer_opt: Option<Arc<LinderaTokenizer>>,
+    jpn_tokenizer_opt: Option<Arc<LinderaTokenizer>>,
+    kor_tokenizer_opt: Option<Arc<LinderaTokenizer>>,
     token: Token,
 }
 
+impl MultiLangTokenizer {
+    /// Creates a tokenizer that tokenizes all texts as the language identified by the given
+    /// 3-letter code (`cmn`, `eng`, `jpn`, or `kor`) instead of detecting it.
+    pub fn with_language(language_code: &str) -> anyhow::Result<Self> {
+        let config = MultiLangTokenizerConfig {
+            language: Some(language_code.to_string()),
+            ..Default::default()
+        };
+        MultiLangTokenizer::from_config(&config)
+    }
+
+    /// Creates a tokenizer from a configuration. Dictionaries are loaded eagerly, so that invalid
+    /// user dictionaries are reported right away.
+    pub fn from_config(config: &MultiLangTokenizerConfig) -> anyhow::Result<Self> {
+        let language_opt = if let Some(language_code) = &config.language {
+            let Some(language) = get_language_from_code(language_code) else {
+                anyhow::bail!(
+                    "unsupported language `{language_code}`: supported languages are `cmn`, \
+                     `eng`, `jpn`, and `kor`"
+                );
+            };
+            Some(language)
+        } else {
+            None
+        };
+        let jpn_dictionary_kind = match config.japanese_dictionary.as_deref() {
+            None | Some("ipadic") => DictionaryKind::IPADIC,
+            Some("unidic") => DictionaryKind::UniDic,
+            Some(japanese_dictionary) => {
+                anyhow::bail!(
+                    "unsupported Japanese dictionary `{japanese_dictionary}`: supported \
+                     dictionaries are `ipadic` and `unidic`"
+                );
+            }
+        };
+        let cmn_tokenizer_opt = if !config.cmn_user_dictionary.is_empty() {
+            let cmn_tokenizer =
+                build_lindera_tokenizer(DictionaryKind::CcCedict, &config.cmn_user_dictionary)?;
+            Some(Arc::new(cmn_tokenizer))
+        } else {
+            None
+        };
+        let jpn_tokenizer_opt = if !config.jpn_user_dictionary.is_empty()
+            || matches!(jpn_dictionary_kind, DictionaryKind::UniDic)
+        {
+            let jpn_tokenizer =
+                build_lindera_tokenizer(jpn_dictionary_kind, &config.jpn_user_dictionary)?;
+            Some(Arc::new(jpn_tokenizer))
+        } else {
+            None
+        };
+        let kor_tokenizer_opt = if !config.kor_user_dictionary.is_empty() {
+            let kor_tokenizer =
+                build_lindera_tokenizer(DictionaryKind::KoDic, &config.kor_user_dictionary)?;
+            Some(Arc::new(kor_tokenizer))
+        } else {
+            None
+        };
+
+        Ok(MultiLangTokenizer {
+            language_opt,
+            cmn_tokenizer_opt,
+            jpn_tokenizer_opt,
+            kor_tokenizer_opt,
+            ..Default::default()
+        })
+    }
+}
+
 impl Tokenizer for MultiLangTokenizer {
     type TokenStream<'a> = MultiLanguageTokenStream<'a>;
     fn token_stream<'a>(&'a mut self, text: &'a str) -> MultiLanguageTokenStream<'a> {
@@ -87,14 +238,17 @@ impl Tokenizer for MultiLangTokenizer {
         let (language_prefix, text_to_tokenize) = get_language_from_prefix(text);
         // If the text is empty, we return an empty token stream.
         // `whichlang::detect_language` panicks if the text is empty.
-        if text.trim().is_empty() {
+        if text_to_tokenize.trim().is_empty() {
             return MultiLanguageTokenStream::Empty;
         }
-        let language = language_prefix.unwrap_or_else(|| detect_language(text_to_tokenize));
+        let language = language_prefix
+            .or(self.language_opt)
+            .unwrap_or_else(|| detect_language(text_to_tokenize));
         match language {
             Lang::Cmn => {
+                let cmn_tokenizer = self.cmn_tokenizer_opt.as_deref().unwrap_or(&CMN_TOKENIZER);
                 let lindera_token_stream = LinderaTokenStream {
-                    tokens: CMN_TOKENIZER
+                    tokens: cmn_tokenizer
                         .tokenize(text_to_tokenize)
                         .expect("tokenize method should never fail"),
                     token: &mut self.token,
@@ -102,8 +256,9 @@ impl Tokenizer for MultiLangTokenizer {
                 MultiLanguageTokenStream::Lindera(lindera_token_stream)
             }
             Lang::Jpn => {
+                let jpn_tokenizer = self.jpn_tokenizer_opt.as_deref().unwrap_or(&JPN_TOKENIZER);
                 let lindera_token_stream = LinderaTokenStream {
-                    tokens: JPN_TOKENIZER
+                    tokens: jpn_tokenizer
                         .tokenize(text_to_tokenize)
                         .expect("tokenize method should never fail"),
                     token: &mut self.token,
@@ -111,8 +266,9 @@ impl Tokenizer for MultiLangTokenizer {
                 MultiLanguageTokenStream::Lindera(lindera_token_stream)
             }
             Lang::Kor => {
+                let kor_tokenizer = self.kor_tokenizer_opt.as_deref().unwrap_or(&KOR_TOKENIZER);
                 let lindera_token_stream = LinderaTokenStream {
-                    tokens: KOR_TOKENIZER
+                    tokens: kor_tokenizer
                         .tokenize(text_to_tokenize)
                         .expect("tokenize method should never fail"),
                     token: &mut self.token,
@@ -126,27 +282,42 @@ impl Tokenizer for MultiLangTokenizer {
     }
 }
 
+/// Languages that can be forced with a prefix or set on the tokenizer, with their 3-letter code.
+const LANGUAGE_CODES: [(&str, Lang); 4] = [
+    ("cmn", Lang::Cmn),
+    ("eng", Lang::Eng),
+    ("jpn", Lang::Jpn),
+    ("kor", Lang::Kor),
+];
+
+/// Gets the language identified by a case-insensitive 3-letter code.
+fn get_language_from_code(language_code: &str) -> Option<Lang> {
+    LANGUAGE_CODES
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(language_code))
+        .map(|(_, language)| *language)
+}
+
 /// Gets the language defined by a prefix `{ID}:text` where ID being the 3-letter language used by
-/// whichlang) and returns the language and the text without the prefix. If the prefix is not
-/// recognized, the language is `None` and the text is the original.
+/// whichlang) and returns the language and the text without the prefix. The prefix is
+/// case-insensitive. If the prefix is not recognized, the language is `None` and the text is the
+/// original.
 fn get_language_from_prefix(text: &str) -> (Option<Lang>, &str) {
-    let prefix_bytes = &text.as_bytes()[0..std::cmp::min(4, text.len())];
-    // TODO: refactor.
-    let prefix_language = match prefix_bytes {
-        b"CMN:" => Some(Lang::Cmn),
-        b"ENG:" => Some(Lang::Eng),
-        b"JPN:" => Some(Lang::Jpn),
-        b"KOR:" => Some(Lang::Kor),
-        _ => None,
-    };
-    let text_without_prefix = if prefix_language.is_some() {
-        // This is safe as we know that the prefix is made of 4 ascii characters.
-        &text[4..]
-    } else {
-        text
+    let Some(prefix_bytes) = text.as_bytes().get(..4) else {
+        return (None, text);
     };
-    (prefix_language, text_without_prefix)
+    if prefix_bytes[3] != b':' {
+        return (None, text);
+    }
+    for (code, language) in LANGUAGE_CODES {
+        if prefix_bytes[..3].eq_ignore_ascii_case(code.as_bytes()) {
+            // This is safe as we know that the prefix is made of 4 ascii characters.
+            return (Some(language), &text[4..]);
+        }
+    }
+    (None, text)
 }
+
 pub enum MultiLanguageTokenStream<'a> {
     Empty,
     Lindera(LinderaTokenStream<'a>),
@@ -216,7 +387,10 @@ impl<'a> TokenStream for LinderaTokenStream<'a> {
 mod tests {
     use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
 
-    use super::{get_language_from_prefix, MultiLangTokenizer, MultiLanguageTokenStream};
+    use super::{
+        get_language_from_prefix, MultiLangTokenizer, MultiLangTokenizerConfig,
+        MultiLanguageTokenStream,
+    };
 
     fn test_helper(mut tokenizer: MultiLanguageTokenStream) -> Vec<Token> {
         let mut tokens: Vec<Token> = Vec::new();
@@ -267,6 +441,11 @@ mod tests {
             let tokens = test_helper(tokenizer.token_stream("ENG:すもももももももものうち"));
             assert_eq!(tokens.len(), 1);
         }
+        {
+            // Prefixes are case-insensitive.
+            let tokens = test_helper(tokenizer.token_stream("jpn:すもももももももものうち"));
+            assert_eq!(tokens.len(), 7);
+        }
     }
 
     #[test]
@@ -295,6 +474,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_multilanguage_tokenizer_with_language() {
+        // Short texts are easily misdetected.
+        let mut tokenizer = MultiLangTokenizer::with_language("jpn").unwrap();
+        let tokens = test_helper(tokenizer.token_stream("すもももももももものうち"));
+        assert_eq!(tokens.len(), 7);
+
+        // Prefixes take precedence over the language of the tokenizer.
+        let tokens = test_helper(tokenizer.token_stream("ENG:すもももももももものうち"));
+        assert_eq!(tokens.len(), 1);
+
+        let mut tokenizer = MultiLangTokenizer::with_language("ENG").unwrap();
+        let tokens = test_helper(tokenizer.token_stream("すもももももももものうち"));
+        assert_eq!(tokens.len(), 1);
+
+        let error = MultiLangTokenizer::with_language("fra").err().unwrap();
+        assert!(error.to_string().contains("unsupported language `fra`"));
+    }
+
+    #[test]
+    fn test_multilanguage_tokenizer_with_user_dictionary() {
+        let mut tokenizer = MultiLangTokenizer::with_language("jpn").unwrap();
+        let tokens = test_helper(tokenizer.token_stream("東京スカイツリーの最寄り駅"));
+        assert_ne!(tokens[0].text, "東京スカイツリー");
+
+        let config = MultiLangTokenizerConfig {
+            language: Some("jpn".to_string()),
+            jpn_user_dictionary: vec![
+                "東京スカイツリー,カスタム名詞,トウキョウスカイツリー".to_string()
+            ],
+            ..Default::default()
+        };
+        let mut tokenizer = MultiLangTokenizer::from_config(&config).unwrap();
+        let tokens = test_helper(tokenizer.token_stream("東京スカイツリーの最寄り駅"));
+        assert_eq!(tokens[0].text, "東京スカイツリー");
+
+        // Clones share the custom lindera tokenizers.
+        let mut tokenizer_clone = tokenizer.clone();
+        let tokens = test_helper(tokenizer_clone.token_stream("東京スカイツリーの最寄り駅"));
+        assert_eq!(tokens[0].text, "東京スカイツリー");
+
+        let config = MultiLangTokenizerConfig {
+            jpn_user_dictionary: vec!["東京スカイツリー".to_string()],
+            ..Default::default()
+        };
+        let error = MultiLangTokenizer::from_config(&config).err().unwrap();
+        assert!(error.to_string().contains("failed to load user dictionary"));
+
+        let config = MultiLangTokenizerConfig {
+            jpn_user_dictionary: vec!["東京,カスタム名詞,トウキョウ\n駅,名詞,エキ".to_string()],
+            ..Default::default()
+        };
+        let error = MultiLangTokenizer::from_config(&config).err().unwrap();
+        assert!(error.to_string().contains("spans several lines"));
+    }
+
+    #[test]
+    fn test_multilanguage_tokenizer_with_unidic() {
+        let config = MultiLangTokenizerConfig {
+            language: Some("jpn".to_string()),
+            japanese_dictionary: Some("unidic".to_string()),
+            ..Default::default()
+        };
+        let mut tokenizer = MultiLangTokenizer::from_config(&config).unwrap();
+        let tokens = test_helper(tokenizer.token_stream("すもももももももものうち"));
+        assert_eq!(tokens[0].text, "すもも");
+
+        let config = MultiLangTokenizerConfig {
+            japanese_dictionary: Some("jumandic".to_string()),
+            ..Default::default()
+        };
+        let error = MultiLangTokenizer::from_config(&config).err().unwrap();
+        assert!(error
+            .to_string()
+            .contains("unsupported Japanese dictionary `jumandic`"));
+    }
+
     #[test]
     fn test_multilanguage_tokenizer_with_empty_string() {
         let mut tokenizer = MultiLangTokenizer::default();
@@ -306,6 +562,10 @@ mod tests {
             let tokens = test_helper(tokenizer.token_stream("   "));
             assert_eq!(tokens.len(), 0);
         }
+        {
+            let tokens = test_helper(tokenizer.token_stream("JPN:  "));
+            assert_eq!(tokens.len(), 0);
+        }
     }
 
     #[test]
@@ -325,6 +585,26 @@ mod tests {
             assert_eq!(lang, Some(whichlang::Lang::Eng));
             assert_eq!(text, "my address");
         }
+        {
+            let (lang, text) = get_language_from_prefix("jpn:こんにちは");
+            assert_eq!(lang, Some(whichlang::Lang::Jpn));
+            assert_eq!(text, "こんにちは");
+        }
+        {
+            let (lang, text) = get_language_from_prefix("eng:hello");
+            assert_eq!(lang, Some(whichlang::Lang::Eng));
+            assert_eq!(text, "hello");
+        }
+        {
+            let (lang, text) = get_language_from_prefix("Kor:일본입니다");
+            assert_eq!(lang, Some(whichlang::Lang::Kor));
+            assert_eq!(text, "일본입니다");
+        }
+        {
+            let (lang, text) = get_language_from_prefix("ENG");
+            assert!(lang.is_none());
+            assert_eq!(text, "ENG");
+        }
         {
             let (lang, text) = get_language_from_prefix("UNK:my address");
             assert!(lang.is_none());
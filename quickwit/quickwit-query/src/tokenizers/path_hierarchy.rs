@@ -0,0 +1,147 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::str::MatchIndices;
+
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+/// A tokenizer emitting each level of a path-like hierarchy.
+///
+/// For instance, it splits `/var/log/nginx/access.log` as
+/// `[/var, /var/log, /var/log/nginx, /var/log/nginx/access.log]`. All the tokens are emitted at
+/// position 0, so that filtering on a path prefix becomes a term query.
+#[derive(Clone)]
+pub struct PathHierarchyTokenizer {
+    delimiter: char,
+    token: Token,
+}
+
+impl Default for PathHierarchyTokenizer {
+    fn default() -> Self {
+        Self::new('/')
+    }
+}
+
+impl PathHierarchyTokenizer {
+    pub fn new(delimiter: char) -> Self {
+        Self {
+            delimiter,
+            token: Token::default(),
+        }
+    }
+}
+
+impl Tokenizer for PathHierarchyTokenizer {
+    type TokenStream<'a> = PathHierarchyTokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        self.token.reset();
+        PathHierarchyTokenStream {
+            text,
+            delimiters: text.match_indices(self.delimiter),
+            done: text.is_empty(),
+            token: &mut self.token,
+        }
+    }
+}
+
+pub struct PathHierarchyTokenStream<'a> {
+    text: &'a str,
+    delimiters: MatchIndices<'a, char>,
+    done: bool,
+    token: &'a mut Token,
+}
+
+impl<'a> TokenStream for PathHierarchyTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        if self.done {
+            return false;
+        }
+        let level_end = loop {
+            match self.delimiters.next() {
+                // A leading delimiter does not delimit a level.
+                Some((0, _)) => continue,
+                Some((delimiter_offset, _)) => break delimiter_offset,
+                None => {
+                    self.done = true;
+                    break self.text.len();
+                }
+            }
+        };
+        self.token.text.clear();
+        self.token.text.push_str(&self.text[..level_end]);
+        self.token.offset_from = 0;
+        self.token.offset_to = level_end;
+        self.token.position = 0;
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::tokenizer::{TokenStream, Tokenizer};
+
+    use super::PathHierarchyTokenizer;
+
+    fn tokenize(tokenizer: &mut PathHierarchyTokenizer, text: &str) -> Vec<String> {
+        let mut token_stream = tokenizer.token_stream(text);
+        let mut tokens = Vec::new();
+        while let Some(token) = token_stream.next() {
+            assert_eq!(token.position, 0);
+            assert_eq!(token.offset_from, 0);
+            assert_eq!(token.offset_to, token.text.len());
+            tokens.push(token.text.clone());
+        }
+        tokens
+    }
+
+    #[test]
+    fn test_path_hierarchy_tokenizer() {
+        let mut tokenizer = PathHierarchyTokenizer::default();
+        assert_eq!(
+            tokenize(&mut tokenizer, "/var/log/nginx/access.log"),
+            [
+                "/var",
+                "/var/log",
+                "/var/log/nginx",
+                "/var/log/nginx/access.log"
+            ]
+        );
+        assert_eq!(tokenize(&mut tokenizer, "var/log"), ["var", "var/log"]);
+        assert_eq!(tokenize(&mut tokenizer, "/"), ["/"]);
+        assert!(tokenize(&mut tokenizer, "").is_empty());
+    }
+
+    #[test]
+    fn test_path_hierarchy_tokenizer_with_delimiter() {
+        let mut tokenizer = PathHierarchyTokenizer::new('.');
+        assert_eq!(
+            tokenize(&mut tokenizer, "com.quickwit.search"),
+            ["com", "com.quickwit", "com.quickwit.search"]
+        );
+    }
+}
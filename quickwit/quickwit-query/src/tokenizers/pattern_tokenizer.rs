@@ -0,0 +1,176 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::ops::Range;
+
+use anyhow::{bail, Context};
+use regex::Regex;
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+/// A tokenizer based on a regular expression.
+///
+/// By default, the text is split on the matches of the regular expression, like the
+/// Elasticsearch `pattern` tokenizer. When a capture group is specified, the tokens are the
+/// captures of that group instead, group 0 being the whole match.
+#[derive(Clone)]
+pub struct PatternTokenizer {
+    regex: Regex,
+    group_opt: Option<usize>,
+    token_ranges: Vec<Range<usize>>,
+    token: Token,
+}
+
+impl PatternTokenizer {
+    pub fn new(pattern: &str, group_opt: Option<usize>) -> anyhow::Result<Self> {
+        let regex = Regex::new(pattern).with_context(|| format!("invalid pattern `{pattern}`"))?;
+
+        if let Some(group) = group_opt {
+            if group >= regex.captures_len() {
+                bail!("pattern `{pattern}` has no capture group {group}");
+            }
+        }
+        Ok(Self {
+            regex,
+            group_opt,
+            token_ranges: Vec::new(),
+            token: Token::default(),
+        })
+    }
+}
+
+impl Tokenizer for PatternTokenizer {
+    type TokenStream<'a> = PatternTokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        self.token.reset();
+        self.token_ranges.clear();
+
+        if let Some(group) = self.group_opt {
+            let captured_ranges = self
+                .regex
+                .captures_iter(text)
+                .filter_map(|captures| captures.get(group))
+                .map(|capture| capture.range())
+                .filter(|range| !range.is_empty());
+            self.token_ranges.extend(captured_ranges);
+        } else {
+            let mut token_start = 0;
+
+            for separator in self.regex.find_iter(text) {
+                if token_start < separator.start() {
+                    self.token_ranges.push(token_start..separator.start());
+                }
+                token_start = separator.end();
+            }
+            if token_start < text.len() {
+                self.token_ranges.push(token_start..text.len());
+            }
+        }
+        PatternTokenStream {
+            text,
+            token_ranges: self.token_ranges.iter(),
+            token: &mut self.token,
+        }
+    }
+}
+
+pub struct PatternTokenStream<'a> {
+    text: &'a str,
+    token_ranges: std::slice::Iter<'a, Range<usize>>,
+    token: &'a mut Token,
+}
+
+impl<'a> TokenStream for PatternTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        let Some(token_range) = self.token_ranges.next() else {
+            return false;
+        };
+        self.token.text.clear();
+        self.token.text.push_str(&self.text[token_range.clone()]);
+        self.token.offset_from = token_range.start;
+        self.token.offset_to = token_range.end;
+        self.token.position = self.token.position.wrapping_add(1);
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::tokenizer::{TokenStream, Tokenizer};
+
+    use super::PatternTokenizer;
+
+    fn tokenize(tokenizer: &mut PatternTokenizer, text: &str) -> Vec<(String, usize)> {
+        let mut token_stream = tokenizer.token_stream(text);
+        let mut tokens = Vec::new();
+        while let Some(token) = token_stream.next() {
+            assert_eq!(&text[token.offset_from..token.offset_to], token.text);
+            tokens.push((token.text.clone(), token.position));
+        }
+        tokens
+    }
+
+    #[test]
+    fn test_pattern_tokenizer_split() {
+        let mut tokenizer = PatternTokenizer::new(r"[\s,;]+", None).unwrap();
+        assert_eq!(
+            tokenize(&mut tokenizer, " com.quickwit.search, quickwit.io ;"),
+            [
+                ("com.quickwit.search".to_string(), 0),
+                ("quickwit.io".to_string(), 1),
+            ]
+        );
+        assert!(tokenize(&mut tokenizer, "").is_empty());
+    }
+
+    #[test]
+    fn test_pattern_tokenizer_capture_group() {
+        let mut tokenizer = PatternTokenizer::new(r"(\w+)=(\w*)", Some(1)).unwrap();
+        assert_eq!(
+            tokenize(&mut tokenizer, "level=error status=500 empty="),
+            [
+                ("level".to_string(), 0),
+                ("status".to_string(), 1),
+                ("empty".to_string(), 2),
+            ]
+        );
+        let mut tokenizer = PatternTokenizer::new(r"(\w+)=(\w*)", Some(2)).unwrap();
+        assert_eq!(
+            tokenize(&mut tokenizer, "level=error status=500 empty="),
+            [("error".to_string(), 0), ("500".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_pattern_tokenizer_invalid() {
+        let error = PatternTokenizer::new("(", None).err().unwrap();
+        assert_eq!(error.to_string(), "invalid pattern `(`");
+
+        let error = PatternTokenizer::new(r"(\w+)", Some(2)).err().unwrap();
+        assert_eq!(error.to_string(), r"pattern `(\w+)` has no capture group 2");
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::bail;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// A token filter expanding or replacing tokens with their synonyms.
+///
+/// Synonyms are declared with the Solr syntax, one rule per entry:
+/// - `tv, television` declares equivalent terms: each of them is expanded into all of them.
+/// - `tv => television` declares an explicit mapping: `tv` is replaced by `television`.
+///
+/// Synonyms are emitted at the position of the original token. Only single-token synonyms are
+/// supported, and they are matched against the output of the previous filters, so rules are
+/// typically written in lowercase and the filter is placed after a lowercasing filter.
+#[derive(Clone)]
+pub struct SynonymFilter {
+    synonyms: Arc<HashMap<String, Vec<String>>>,
+}
+
+impl SynonymFilter {
+    /// Creates a new synonym filter from a list of rules.
+    pub fn new<S: AsRef<str>>(rules: &[S]) -> anyhow::Result<Self> {
+        let mut synonyms: HashMap<String, Vec<String>> = HashMap::new();
+
+        for rule in rules {
+            let rule = rule.as_ref();
+
+            let (terms, replacements) = match rule.split_once("=>") {
+                Some((terms, replacements)) => (
+                    parse_synonym_terms(terms)?,
+                    parse_synonym_terms(replacements)?,
+                ),
+                None => {
+                    let terms = parse_synonym_terms(rule)?;
+                    (terms.clone(), terms)
+                }
+            };
+            if terms.is_empty() || replacements.is_empty() {
+                bail!("invalid synonym rule `{rule}`");
+            }
+            for term in terms {
+                let term_synonyms = synonyms.entry(term).or_default();
+
+                for replacement in &replacements {
+                    if !term_synonyms.contains(replacement) {
+                        term_synonyms.push(replacement.clone());
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            synonyms: Arc::new(synonyms),
+        })
+    }
+}
+
+fn parse_synonym_terms(terms: &str) -> anyhow::Result<Vec<String>> {
+    let mut parsed_terms = Vec::new();
+
+    for term in terms.split(',') {
+        let term = term.trim();
+
+        if term.is_empty() {
+            continue;
+        }
+        if term.contains(char::is_whitespace) {
+            bail!("multi-word synonym `{term}` is not supported");
+        }
+        parsed_terms.push(term.to_string());
+    }
+    Ok(parsed_terms)
+}
+
+impl TokenFilter for SynonymFilter {
+    type Tokenizer<T: Tokenizer> = SynonymFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> SynonymFilterWrapper<T> {
+        SynonymFilterWrapper {
+            synonyms: self.synonyms,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SynonymFilterWrapper<T> {
+    synonyms: Arc<HashMap<String, Vec<String>>>,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for SynonymFilterWrapper<T> {
+    type TokenStream<'a> = SynonymTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        SynonymTokenStream {
+            synonyms: &self.synonyms,
+            tail: self.inner.token_stream(text),
+            pending_synonyms: Default::default(),
+        }
+    }
+}
+
+pub struct SynonymTokenStream<'a, T> {
+    synonyms: &'a HashMap<String, Vec<String>>,
+    tail: T,
+    pending_synonyms: std::slice::Iter<'a, String>,
+}
+
+impl<'a, T: TokenStream> TokenStream for SynonymTokenStream<'a, T> {
+    fn advance(&mut self) -> bool {
+        if let Some(synonym) = self.pending_synonyms.next() {
+            set_token_text(self.tail.token_mut(), synonym);
+            return true;
+        }
+        if !self.tail.advance() {
+            return false;
+        }
+        if let Some(synonyms) = self.synonyms.get(&self.tail.token().text) {
+            let mut synonyms_iter = synonyms.iter();
+
+            if let Some(synonym) = synonyms_iter.next() {
+                set_token_text(self.tail.token_mut(), synonym);
+            }
+            self.pending_synonyms = synonyms_iter;
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+fn set_token_text(token: &mut Token, text: &str) {
+    token.text.clear();
+    token.text.push_str(text);
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::tokenizer::{LowerCaser, SimpleTokenizer, TextAnalyzer};
+
+    use super::SynonymFilter;
+
+    fn analyze(rules: &[&str], text: &str) -> Vec<(String, usize)> {
+        let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .filter(SynonymFilter::new(rules).unwrap())
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        while let Some(token) = token_stream.next() {
+            tokens.push((token.text.clone(), token.position));
+        }
+        tokens
+    }
+
+    #[test]
+    fn test_synonym_filter_equivalent_terms() {
+        let tokens = analyze(&["tv, television"], "Cheap TV stand");
+        assert_eq!(
+            tokens,
+            [
+                ("cheap".to_string(), 0),
+                ("tv".to_string(), 1),
+                ("television".to_string(), 1),
+                ("stand".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_synonym_filter_explicit_mapping() {
+        let tokens = analyze(&["tv, telly => television"], "telly tv");
+        assert_eq!(
+            tokens,
+            [("television".to_string(), 0), ("television".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_synonym_filter_invalid_rules() {
+        let error = SynonymFilter::new(&["=> television"]).err().unwrap();
+        assert_eq!(error.to_string(), "invalid synonym rule `=> television`");
+
+        let error = SynonymFilter::new(&["tv, flat screen"]).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "multi-word synonym `flat screen` is not supported"
+        );
+    }
+}
@@ -26,7 +26,10 @@ use quickwit_indexing::actors::IndexingServiceCounters;
 pub use quickwit_ingest::CommitType;
 use quickwit_metastore::{IndexMetadata, Split, SplitInfo};
 use quickwit_search::SearchResponseRest;
-use quickwit_serve::{ListSplitsQueryParams, ListSplitsResponse, SearchRequestQueryString};
+use quickwit_serve::{
+    ListSplitsQueryParams, ListSplitsResponse, SearchRequestQueryString, SqlRequest, SqlResponse,
+    SqlResponseFormat,
+};
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use reqwest::{Client, ClientBuilder, Method, StatusCode, Url};
 use serde::Serialize;
@@ -228,6 +231,28 @@ impl QuickwitClient {
         Ok(search_response)
     }
 
+    /// Runs a SQL query. The result set is always requested as JSON.
+    pub async fn sql(&self, query: &str) -> Result<SqlResponse, Error> {
+        let sql_request = SqlRequest {
+            query: query.to_string(),
+            format: SqlResponseFormat::Json,
+        };
+        let body = Bytes::from(serde_json::to_vec(&sql_request).unwrap());
+        let response = self
+            .transport
+            .send::<()>(
+                Method::POST,
+                "_sql",
+                None,
+                None,
+                Some(body),
+                self.search_timeout,
+            )
+            .await?;
+        let sql_response = response.deserialize().await?;
+        Ok(sql_response)
+    }
+
     pub fn indexes(&self) -> IndexClient {
         IndexClient::new(&self.transport, self.timeout)
     }
@@ -684,7 +709,10 @@ mod test {
     use quickwit_ingest::CommitType;
     use quickwit_metastore::IndexMetadata;
     use quickwit_search::SearchResponseRest;
-    use quickwit_serve::{ListSplitsQueryParams, ListSplitsResponse, SearchRequestQueryString};
+    use quickwit_serve::{
+        ListSplitsQueryParams, ListSplitsResponse, SearchRequestQueryString, SqlRequest,
+        SqlResponse, SqlResponseFormat,
+    };
     use reqwest::header::CONTENT_TYPE;
     use reqwest::{StatusCode, Url};
     use serde_json::json;
@@ -723,9 +751,15 @@ mod test {
             num_hits: 0,
             hits: Vec::new(),
             snippets: None,
+            inner_hits: None,
+            explanations: None,
             aggregations: None,
             elapsed_time_micros: 100,
             errors: Vec::new(),
+            search_after: None,
+            timed_out: false,
+            timed_out_splits: Vec::new(),
+            split_profiles: Vec::new(),
         };
         Mock::given(method("POST"))
             .and(path("/api/v1/my-index/search"))
@@ -744,6 +778,32 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_sql_endpoint() {
+        let mock_server = MockServer::start().await;
+        let server_url = Url::parse(&mock_server.uri()).unwrap();
+        let qw_client = QuickwitClientBuilder::new(server_url).build();
+        Mock::given(method("POST"))
+            .and(path("/api/v1/_sql"))
+            .and(body_json(json!({
+                "query": "SELECT COUNT(*) FROM my-index",
+                "format": "json",
+            })))
+            .respond_with(ResponseTemplate::new(StatusCode::OK).set_body_json(json!({
+                "columns": [{"name": "count(*)"}],
+                "rows": [[42]],
+            })))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        let sql_response = qw_client
+            .sql("SELECT COUNT(*) FROM my-index")
+            .await
+            .unwrap();
+        assert_eq!(sql_response.columns[0].name, "count(*)");
+        assert_eq!(sql_response.rows, vec![vec![json!(42)]]);
+    }
+
     fn get_ndjson_filepath(ndjson_dataset_filename: &str) -> String {
         format!(
             "{}/resources/tests/{}",
@@ -0,0 +1,291 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use quickwit_common::metrics::OwnedGaugeGuard;
+use quickwit_config::SearchAdmissionControlConfig;
+use quickwit_proto::search::SearchPriority;
+use quickwit_proto::types::IndexUid;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{SearchError, SEARCH_METRICS};
+
+/// Limits the number of root searches running concurrently, per priority, per index and per API
+/// key.
+///
+/// The searches over a limit wait in a FIFO queue, and are rejected with
+/// [`SearchError::TooManyRequests`] if they cannot start within the maximum queue duration.
+/// Interactive and batch searches are queued separately and do not share their slots, so a burst
+/// of batch searches does not delay the interactive ones.
+pub struct SearchAdmissionController {
+    interactive_semaphore: Arc<Semaphore>,
+    batch_semaphore: Arc<Semaphore>,
+    per_index_semaphores_opt: Option<KeyedSemaphores>,
+    per_api_key_semaphores_opt: Option<KeyedSemaphores>,
+    max_queue_duration: Duration,
+}
+
+/// Admits a search until it is dropped.
+#[derive(Debug)]
+pub struct SearchAdmissionPermit {
+    _permits: Vec<OwnedSemaphorePermit>,
+}
+
+/// Semaphores created on demand for each key, e.g. each index.
+struct KeyedSemaphores {
+    max_permits: usize,
+    semaphores: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl KeyedSemaphores {
+    fn new(max_permits: usize) -> Self {
+        KeyedSemaphores {
+            max_permits,
+            semaphores: Mutex::default(),
+        }
+    }
+
+    fn get(&self, key: &str) -> Arc<Semaphore> {
+        let mut semaphores = self.semaphores.lock().unwrap();
+        if let Some(semaphore) = semaphores.get(key) {
+            return semaphore.clone();
+        }
+        // The semaphores that are neither held by a permit nor awaited by a search are dropped,
+        // so that the map does not grow with every key ever seen.
+        semaphores.retain(|_, semaphore| Arc::strong_count(semaphore) > 1);
+        let semaphore = Arc::new(Semaphore::new(self.max_permits));
+        semaphores.insert(key.to_string(), semaphore.clone());
+        semaphore
+    }
+}
+
+async fn acquire_permit(semaphore: Arc<Semaphore>) -> OwnedSemaphorePermit {
+    semaphore
+        .acquire_owned()
+        .await
+        .expect("the admission control semaphores should never be closed")
+}
+
+impl SearchAdmissionController {
+    /// Creates an admission controller with the limits of the given config.
+    pub fn new(config: &SearchAdmissionControlConfig) -> Self {
+        SearchAdmissionController {
+            interactive_semaphore: Arc::new(Semaphore::new(
+                config.max_concurrent_interactive_searches.get(),
+            )),
+            batch_semaphore: Arc::new(Semaphore::new(config.max_concurrent_batch_searches.get())),
+            per_index_semaphores_opt: config
+                .max_concurrent_searches_per_index
+                .map(|max_permits| KeyedSemaphores::new(max_permits.get())),
+            per_api_key_semaphores_opt: config
+                .max_concurrent_searches_per_api_key
+                .map(|max_permits| KeyedSemaphores::new(max_permits.get())),
+            max_queue_duration: config.max_queue_duration(),
+        }
+    }
+
+    /// Waits for a search to be admitted, or returns [`SearchError::TooManyRequests`] after the
+    /// maximum queue duration.
+    ///
+    /// The indexes are the indexes resolved from the index ID patterns of the search, so that
+    /// searches targeting the same index through different patterns share its slots. A search
+    /// targeting several indexes takes a slot of each of them.
+    pub async fn acquire(
+        &self,
+        priority: SearchPriority,
+        index_uids: &[IndexUid],
+        api_key_id_opt: Option<&str>,
+    ) -> crate::Result<SearchAdmissionPermit> {
+        let priority_label = match priority {
+            SearchPriority::Interactive => "interactive",
+            SearchPriority::Batch => "batch",
+        };
+        let mut queued_searches_guard = OwnedGaugeGuard::from_gauge(
+            SEARCH_METRICS
+                .admission_queued_searches
+                .with_label_values([priority_label]),
+        );
+        queued_searches_guard.add(1);
+
+        let acquire_permits_fut = async {
+            let mut permits = Vec::with_capacity(index_uids.len() + 2);
+            // The API key and index slots are acquired first, so that a search waiting for them
+            // does not hold a slot of its priority.
+            if let (Some(per_api_key_semaphores), Some(api_key_id)) =
+                (&self.per_api_key_semaphores_opt, api_key_id_opt)
+            {
+                permits.push(acquire_permit(per_api_key_semaphores.get(api_key_id)).await);
+            }
+            if let Some(per_index_semaphores) = &self.per_index_semaphores_opt {
+                // The index slots are always acquired in the same order, so that two searches
+                // targeting the same indexes cannot each hold a slot awaited by the other.
+                let mut index_keys: Vec<String> =
+                    index_uids.iter().map(IndexUid::to_string).collect();
+                index_keys.sort_unstable();
+                index_keys.dedup();
+
+                for index_key in &index_keys {
+                    permits.push(acquire_permit(per_index_semaphores.get(index_key)).await);
+                }
+            }
+            let priority_semaphore = match priority {
+                SearchPriority::Interactive => &self.interactive_semaphore,
+                SearchPriority::Batch => &self.batch_semaphore,
+            };
+            permits.push(acquire_permit(priority_semaphore.clone()).await);
+            SearchAdmissionPermit { _permits: permits }
+        };
+        tokio::time::timeout(self.max_queue_duration, acquire_permits_fut)
+            .await
+            .map_err(|_| {
+                SEARCH_METRICS
+                    .admission_rejected_searches_total
+                    .with_label_values([priority_label])
+                    .inc();
+                SearchError::TooManyRequests
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::{NonZeroU64, NonZeroUsize};
+
+    use super::*;
+
+    fn admission_controller_for_test() -> SearchAdmissionController {
+        SearchAdmissionController::new(&SearchAdmissionControlConfig {
+            max_concurrent_interactive_searches: NonZeroUsize::new(2).unwrap(),
+            max_concurrent_batch_searches: NonZeroUsize::new(1).unwrap(),
+            max_concurrent_searches_per_index: None,
+            max_concurrent_searches_per_api_key: Some(NonZeroUsize::new(1).unwrap()),
+            max_queue_duration_millis: NonZeroU64::new(50).unwrap(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_search_admission_controller_priorities() {
+        let admission_controller = admission_controller_for_test();
+        let index_1 = [IndexUid::for_test("index-1", 0)];
+        let index_2 = [IndexUid::for_test("index-2", 0)];
+        let _batch_permit = admission_controller
+            .acquire(SearchPriority::Batch, &index_1, None)
+            .await
+            .unwrap();
+        // The batch searches are limited separately.
+        let search_error = admission_controller
+            .acquire(SearchPriority::Batch, &index_1, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(search_error, SearchError::TooManyRequests));
+
+        // The interactive searches are not delayed by the batch searches.
+        let interactive_permit = admission_controller
+            .acquire(SearchPriority::Interactive, &index_1, None)
+            .await
+            .unwrap();
+        let _interactive_permit_2 = admission_controller
+            .acquire(SearchPriority::Interactive, &index_2, None)
+            .await
+            .unwrap();
+        let interactive_permit_fut =
+            admission_controller.acquire(SearchPriority::Interactive, &index_1, None);
+        tokio::pin!(interactive_permit_fut);
+        tokio::time::timeout(Duration::from_millis(10), &mut interactive_permit_fut)
+            .await
+            .unwrap_err();
+        // Releasing a permit admits the queued search.
+        drop(interactive_permit);
+        interactive_permit_fut.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_search_admission_controller_per_api_key() {
+        let admission_controller = admission_controller_for_test();
+        let index_1 = [IndexUid::for_test("index-1", 0)];
+        let index_2 = [IndexUid::for_test("index-2", 0)];
+        let api_key_permit = admission_controller
+            .acquire(SearchPriority::Interactive, &index_1, Some("key-1"))
+            .await
+            .unwrap();
+        let search_error = admission_controller
+            .acquire(SearchPriority::Interactive, &index_2, Some("key-1"))
+            .await
+            .unwrap_err();
+        assert!(matches!(search_error, SearchError::TooManyRequests));
+        // The other API keys are not limited.
+        admission_controller
+            .acquire(SearchPriority::Interactive, &index_1, Some("key-2"))
+            .await
+            .unwrap();
+        drop(api_key_permit);
+        admission_controller
+            .acquire(SearchPriority::Interactive, &index_2, Some("key-1"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_search_admission_controller_per_index() {
+        let admission_controller = SearchAdmissionController::new(&SearchAdmissionControlConfig {
+            max_concurrent_searches_per_index: Some(NonZeroUsize::new(1).unwrap()),
+            max_queue_duration_millis: NonZeroU64::new(50).unwrap(),
+            ..Default::default()
+        });
+        let index_uid_1 = IndexUid::for_test("index-1", 0);
+        let index_uid_2 = IndexUid::for_test("index-2", 0);
+
+        let permit = admission_controller
+            .acquire(
+                SearchPriority::Interactive,
+                &[index_uid_1.clone(), index_uid_2.clone()],
+                None,
+            )
+            .await
+            .unwrap();
+        // A search targeting any of the indexes waits, whatever the order of the indexes.
+        for index_uids in [
+            vec![index_uid_2.clone(), index_uid_1.clone()],
+            vec![index_uid_2.clone()],
+        ] {
+            let search_error = admission_controller
+                .acquire(SearchPriority::Interactive, &index_uids, None)
+                .await
+                .unwrap_err();
+            assert!(matches!(search_error, SearchError::TooManyRequests));
+        }
+        // A recreated index is a different index.
+        admission_controller
+            .acquire(
+                SearchPriority::Interactive,
+                &[IndexUid::for_test("index-1", 1)],
+                None,
+            )
+            .await
+            .unwrap();
+        drop(permit);
+        admission_controller
+            .acquire(SearchPriority::Interactive, &[index_uid_2], None)
+            .await
+            .unwrap();
+    }
+}
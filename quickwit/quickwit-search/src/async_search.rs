@@ -0,0 +1,531 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use itertools::Itertools;
+use quickwit_metastore::SplitMetadata;
+use quickwit_proto::metastore::MetastoreServiceClient;
+use quickwit_proto::search::{LeafSearchResponse, SearchRequest, SearchResponse};
+use quickwit_storage::StorageResolver;
+use serde::{Deserialize, Serialize};
+use tantivy::time::OffsetDateTime;
+use tokio::time::Instant;
+use tracing::{error, info};
+use ulid::Ulid;
+
+use crate::bloom_filter_pruning::prune_splits_with_bloom_filters;
+use crate::pipeline_aggregations::PipelineAggregations;
+use crate::point_in_time::PinnedSplits;
+use crate::root::{
+    apply_pipeline_aggregations, fetch_docs_phase, finalize_aggregation_if_any,
+    get_count_from_metadata, is_metadata_count_request, jobs_to_leaf_request,
+    merge_leaf_search_responses, prepare_root_search, timed_out_split_ids,
+    IndexesMetasForLeafSearch, PreparedRootSearch, SearchJob,
+};
+use crate::service::SearcherContext;
+use crate::{ClusterClient, SearchError};
+
+/// Maximum accepted async search keep alive.
+const MAX_ASYNC_SEARCH_KEEP_ALIVE: Duration = Duration::from_secs(24 * 60 * 60); // 24 hours
+
+/// The splits assigned to a searcher are sent in several leaf requests of at most this many
+/// splits, so that the progress of the search can be reported as the leaf responses come back.
+const MAX_SPLITS_PER_LEAF_REQUEST: usize = 32;
+
+/// Interval at which the state of an async search is polled while waiting for its completion.
+const WAIT_FOR_COMPLETION_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// State of an async search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AsyncSearchResponse {
+    /// ID of the async search.
+    pub id: String,
+    /// Whether the search is still running.
+    pub is_running: bool,
+    /// Whether `response` only covers part of the splits, either because the search is still
+    /// running or because it failed.
+    pub is_partial: bool,
+    pub start_time_millis: i64,
+    /// Time after which the async search and its results are discarded.
+    pub expiration_time_millis: i64,
+    pub completion_time_millis: Option<i64>,
+    /// The submitted search request.
+    pub search_request: SearchRequest,
+    /// Number of splits targeted by the search.
+    pub num_splits: u64,
+    /// Number of splits searched so far.
+    pub num_searched_splits: u64,
+    /// While the search is running, the response only holds the number of hits and the
+    /// aggregations of the splits searched so far: the hits are fetched once all the splits have
+    /// been searched.
+    pub response: SearchResponse,
+    /// Error that interrupted the search, if any.
+    pub error: Option<SearchError>,
+}
+
+/// Async searches are stored in the KV store of the searchers, which replicates them across the
+/// cluster, so any node can serve them.
+#[derive(Serialize, Deserialize)]
+enum AsyncSearchEntry {
+    Search(AsyncSearchResponse),
+    /// Deleting an async search replaces it with a tombstone, which tells the node running it to
+    /// stop.
+    Deleted,
+}
+
+fn now_millis() -> i64 {
+    (OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as i64
+}
+
+fn validate_keep_alive(keep_alive: Duration) -> crate::Result<()> {
+    if keep_alive.is_zero() || keep_alive > MAX_ASYNC_SEARCH_KEEP_ALIVE {
+        return Err(SearchError::InvalidArgument(format!(
+            "async search keep alive must be between 1 second and {} seconds",
+            MAX_ASYNC_SEARCH_KEEP_ALIVE.as_secs()
+        )));
+    }
+    Ok(())
+}
+
+fn parse_async_search_id(async_search_id: &str) -> crate::Result<Ulid> {
+    Ulid::from_string(async_search_id).map_err(|_| {
+        SearchError::InvalidArgument(format!("invalid async search ID `{async_search_id}`"))
+    })
+}
+
+/// Returns the time to live of an entry expiring at `expiration_time_millis`, or `None` if it
+/// has already expired.
+fn time_to_live(expiration_time_millis: i64) -> Option<Duration> {
+    let time_to_live_secs = (expiration_time_millis - now_millis()) / 1_000;
+    if time_to_live_secs <= 0 {
+        return None;
+    }
+    Some(Duration::from_secs(time_to_live_secs as u64))
+}
+
+async fn load_entry(
+    async_search_key: Ulid,
+    cluster_client: &ClusterClient,
+) -> crate::Result<Option<AsyncSearchEntry>> {
+    let Some(payload) = cluster_client
+        .get_kv(&async_search_key.to_bytes()[..])
+        .await
+    else {
+        return Ok(None);
+    };
+    let entry: AsyncSearchEntry = serde_json::from_slice(&payload)?;
+    Ok(Some(entry))
+}
+
+async fn save_entry(
+    async_search_key: Ulid,
+    entry: &AsyncSearchEntry,
+    time_to_live: Duration,
+    cluster_client: &ClusterClient,
+) -> crate::Result<()> {
+    let payload = serde_json::to_vec(entry)?;
+    cluster_client
+        .put_kv(&async_search_key.to_bytes()[..], &payload, time_to_live)
+        .await;
+    Ok(())
+}
+
+/// Submits a search running in the background, and waits at most `wait_for_completion_timeout`
+/// for it to complete.
+///
+/// The request is validated and its splits are listed before returning, so invalid requests are
+/// rejected right away.
+pub(crate) async fn submit_async_search(
+    search_request: SearchRequest,
+    pinned_splits_opt: Option<&PinnedSplits>,
+    wait_for_completion_timeout: Duration,
+    keep_alive: Duration,
+    searcher_context: Arc<SearcherContext>,
+    mut metastore: MetastoreServiceClient,
+    storage_resolver: &StorageResolver,
+    cluster_client: ClusterClient,
+) -> crate::Result<AsyncSearchResponse> {
+    validate_keep_alive(keep_alive)?;
+
+    if search_request.scroll_ttl_secs.is_some() {
+        return Err(SearchError::InvalidArgument(
+            "scroll cannot be used with an async search".to_string(),
+        ));
+    }
+    let mut prepared_search =
+        prepare_root_search(search_request.clone(), &mut metastore, pinned_splits_opt).await?;
+    prepared_search.split_metadatas = prune_splits_with_bloom_filters(
+        &searcher_context,
+        storage_resolver,
+        &prepared_search.indexes_metas_for_leaf_search,
+        &prepared_search.search_request,
+        std::mem::take(&mut prepared_search.split_metadatas),
+    )
+    .await?;
+
+    let async_search_key = Ulid::new();
+    let start_time_millis = now_millis();
+    let async_search_response = AsyncSearchResponse {
+        id: async_search_key.to_string(),
+        is_running: true,
+        is_partial: true,
+        start_time_millis,
+        expiration_time_millis: start_time_millis + keep_alive.as_millis() as i64,
+        completion_time_millis: None,
+        search_request,
+        num_splits: prepared_search.split_metadatas.len() as u64,
+        num_searched_splits: 0,
+        response: SearchResponse::default(),
+        error: None,
+    };
+    // The async search is saved before returning so that it can be polled right away.
+    save_entry(
+        async_search_key,
+        &AsyncSearchEntry::Search(async_search_response.clone()),
+        keep_alive,
+        &cluster_client,
+    )
+    .await?;
+
+    let async_search_task = AsyncSearchTask {
+        async_search_key,
+        async_search_response,
+        searcher_context,
+        cluster_client: cluster_client.clone(),
+    };
+    let mut join_handle = tokio::spawn(async_search_task.run(prepared_search));
+
+    match tokio::time::timeout(wait_for_completion_timeout, &mut join_handle).await {
+        Ok(Ok(Some(async_search_response))) => Ok(async_search_response),
+        Ok(Ok(None)) => Err(SearchError::AsyncSearchNotFound(
+            async_search_key.to_string(),
+        )),
+        Ok(Err(join_error)) => Err(SearchError::Internal(format!(
+            "async search task failed: {join_error}"
+        ))),
+        // The search keeps running in the background.
+        Err(_) => {
+            get_async_search(
+                &async_search_key.to_string(),
+                Duration::ZERO,
+                &cluster_client,
+            )
+            .await
+        }
+    }
+}
+
+/// Returns the state of an async search, waiting at most `wait_for_completion_timeout` for it to
+/// complete.
+pub(crate) async fn get_async_search(
+    async_search_id: &str,
+    wait_for_completion_timeout: Duration,
+    cluster_client: &ClusterClient,
+) -> crate::Result<AsyncSearchResponse> {
+    let async_search_key = parse_async_search_id(async_search_id)?;
+    let deadline = Instant::now() + wait_for_completion_timeout;
+    loop {
+        let Some(AsyncSearchEntry::Search(async_search_response)) =
+            load_entry(async_search_key, cluster_client).await?
+        else {
+            return Err(SearchError::AsyncSearchNotFound(
+                async_search_id.to_string(),
+            ));
+        };
+        if !async_search_response.is_running || Instant::now() >= deadline {
+            return Ok(async_search_response);
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        tokio::time::sleep(WAIT_FOR_COMPLETION_POLL_INTERVAL.min(remaining)).await;
+    }
+}
+
+/// Deletes an async search, stopping it if it is still running. Returns `true` if the async
+/// search existed and had not expired yet.
+pub(crate) async fn delete_async_search(
+    async_search_id: &str,
+    cluster_client: &ClusterClient,
+) -> crate::Result<bool> {
+    let async_search_key = parse_async_search_id(async_search_id)?;
+    let Some(AsyncSearchEntry::Search(async_search_response)) =
+        load_entry(async_search_key, cluster_client).await?
+    else {
+        return Ok(false);
+    };
+    let Some(time_to_live) = time_to_live(async_search_response.expiration_time_millis) else {
+        return Ok(false);
+    };
+    save_entry(
+        async_search_key,
+        &AsyncSearchEntry::Deleted,
+        time_to_live,
+        cluster_client,
+    )
+    .await?;
+    Ok(true)
+}
+
+struct AsyncSearchTask {
+    async_search_key: Ulid,
+    async_search_response: AsyncSearchResponse,
+    searcher_context: Arc<SearcherContext>,
+    cluster_client: ClusterClient,
+}
+
+impl AsyncSearchTask {
+    /// Runs the search to completion and returns its final state, or `None` if the async search
+    /// was deleted or expired in the meantime.
+    async fn run(mut self, prepared_search: PreparedRootSearch) -> Option<AsyncSearchResponse> {
+        let start_instant = Instant::now();
+        let search_result = self.search(prepared_search).await;
+        match search_result {
+            Ok(Some(search_response)) => {
+                self.async_search_response.response = search_response;
+                self.async_search_response.is_partial = false;
+            }
+            Ok(None) => {
+                info!(async_search_id=%self.async_search_key, "async search stopped");
+                return None;
+            }
+            Err(search_error) => {
+                error!(async_search_id=%self.async_search_key, error=?search_error, "async search failed");
+                self.async_search_response.error = Some(search_error);
+            }
+        }
+        self.async_search_response.is_running = false;
+        self.async_search_response.completion_time_millis = Some(now_millis());
+        self.async_search_response.response.elapsed_time_micros =
+            start_instant.elapsed().as_micros() as u64;
+
+        if !self.save_progress().await {
+            return None;
+        }
+        Some(self.async_search_response)
+    }
+
+    /// Searches the splits, saving the partial results as the leaf responses come back. Returns
+    /// `None` if the async search was deleted or expired in the meantime.
+    async fn search(
+        &mut self,
+        prepared_search: PreparedRootSearch,
+    ) -> crate::Result<Option<SearchResponse>> {
+        let PreparedRootSearch {
+            search_request,
+            indexes_metas_for_leaf_search,
+            split_metadatas,
+            pipeline_aggregations_opt,
+        } = prepared_search;
+
+        let merged_leaf_search_response = if is_metadata_count_request(&search_request) {
+            let leaf_search_responses = get_count_from_metadata(&split_metadatas);
+            self.async_search_response.num_searched_splits = split_metadatas.len() as u64;
+            merge_leaf_search_responses(
+                &self.searcher_context,
+                &search_request,
+                leaf_search_responses,
+            )
+            .await?
+        } else {
+            let Some(merged_leaf_search_response) = self
+                .leaf_search(
+                    &search_request,
+                    &indexes_metas_for_leaf_search,
+                    &split_metadatas,
+                    pipeline_aggregations_opt.as_ref(),
+                )
+                .await?
+            else {
+                return Ok(None);
+            };
+            merged_leaf_search_response
+        };
+        let timed_out_splits = timed_out_split_ids(&merged_leaf_search_response.failed_splits)?;
+        let hits = fetch_docs_phase(
+            &indexes_metas_for_leaf_search,
+            &merged_leaf_search_response.partial_hits,
+            &split_metadatas[..],
+            &search_request,
+            &self.cluster_client,
+        )
+        .await?;
+
+        // In case there is no index, we don't want the response to contain any aggregation
+        // structure.
+        let aggregation = if indexes_metas_for_leaf_search.is_empty() {
+            None
+        } else {
+            finalize_aggregation_if_any(
+                &search_request,
+                merged_leaf_search_response.intermediate_aggregation_result,
+                &self.searcher_context,
+            )?
+        };
+        let mut search_response = SearchResponse {
+            aggregation,
+            num_hits: merged_leaf_search_response.num_hits,
+            hits,
+            elapsed_time_micros: 0u64,
+            errors: Vec::new(),
+            scroll_id: None,
+            timed_out: !timed_out_splits.is_empty(),
+            timed_out_splits,
+            suggestions: merged_leaf_search_response.suggestions,
+            split_profiles: merged_leaf_search_response.split_profiles,
+        };
+        apply_pipeline_aggregations(pipeline_aggregations_opt.as_ref(), &mut search_response)?;
+        Ok(Some(search_response))
+    }
+
+    /// Dispatches the splits to the leaves and merges the leaf responses as they come back,
+    /// saving the partial results along the way. Returns `None` if the async search was deleted
+    /// or expired in the meantime.
+    async fn leaf_search(
+        &mut self,
+        search_request: &SearchRequest,
+        indexes_metas_for_leaf_search: &IndexesMetasForLeafSearch,
+        split_metadatas: &[SplitMetadata],
+        pipeline_aggregations_opt: Option<&PipelineAggregations>,
+    ) -> crate::Result<Option<LeafSearchResponse>> {
+        let cluster_client = self.cluster_client.clone();
+        let jobs: Vec<SearchJob> = split_metadatas.iter().map(SearchJob::from).collect();
+        let assigned_leaf_search_jobs = cluster_client
+            .search_job_placer
+            .assign_jobs(jobs, &HashSet::default())
+            .await?;
+        let mut leaf_search_futures = FuturesUnordered::new();
+
+        for (client, client_jobs) in assigned_leaf_search_jobs {
+            for jobs_chunk in &client_jobs.into_iter().chunks(MAX_SPLITS_PER_LEAF_REQUEST) {
+                let jobs_chunk: Vec<SearchJob> = jobs_chunk.collect();
+                let num_splits = jobs_chunk.len() as u64;
+                let leaf_request = jobs_to_leaf_request(
+                    search_request,
+                    indexes_metas_for_leaf_search,
+                    jobs_chunk,
+                )?;
+                let leaf_search_future = cluster_client.leaf_search(leaf_request, client.clone());
+                leaf_search_futures.push(async move { (num_splits, leaf_search_future.await) });
+            }
+        }
+        let mut merged_leaf_search_response_opt: Option<LeafSearchResponse> = None;
+
+        while let Some((num_splits, leaf_search_result)) = leaf_search_futures.next().await {
+            let mut leaf_search_responses = vec![leaf_search_result?];
+            leaf_search_responses.extend(merged_leaf_search_response_opt.take());
+            let merged_leaf_search_response = merge_leaf_search_responses(
+                &self.searcher_context,
+                search_request,
+                leaf_search_responses,
+            )
+            .await?;
+            self.async_search_response.num_searched_splits += num_splits;
+
+            if !leaf_search_futures.is_empty() {
+                let partial_response = &mut self.async_search_response.response;
+                partial_response.num_hits = merged_leaf_search_response.num_hits;
+                partial_response.aggregation = finalize_aggregation_if_any(
+                    search_request,
+                    merged_leaf_search_response
+                        .intermediate_aggregation_result
+                        .clone(),
+                    &self.searcher_context,
+                )?;
+                apply_pipeline_aggregations(pipeline_aggregations_opt, partial_response)?;
+
+                if !self.save_progress().await {
+                    return Ok(None);
+                }
+            }
+            merged_leaf_search_response_opt = Some(merged_leaf_search_response);
+        }
+        let merged_leaf_search_response = match merged_leaf_search_response_opt {
+            Some(merged_leaf_search_response) => merged_leaf_search_response,
+            // There was no split to search.
+            None => {
+                merge_leaf_search_responses(&self.searcher_context, search_request, Vec::new())
+                    .await?
+            }
+        };
+        Ok(Some(merged_leaf_search_response))
+    }
+
+    /// Saves the current state of the async search. Returns `false` if the async search was
+    /// deleted or expired, in which case it should stop.
+    async fn save_progress(&self) -> bool {
+        let Some(time_to_live) = time_to_live(self.async_search_response.expiration_time_millis)
+        else {
+            return false;
+        };
+        match load_entry(self.async_search_key, &self.cluster_client).await {
+            Ok(Some(AsyncSearchEntry::Deleted)) => return false,
+            Ok(_) => {}
+            Err(load_error) => {
+                error!(async_search_id=%self.async_search_key, error=?load_error, "failed to load async search");
+            }
+        }
+        let entry = AsyncSearchEntry::Search(self.async_search_response.clone());
+
+        if let Err(save_error) = save_entry(
+            self.async_search_key,
+            &entry,
+            time_to_live,
+            &self.cluster_client,
+        )
+        .await
+        {
+            error!(async_search_id=%self.async_search_key, error=?save_error, "failed to save async search");
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_keep_alive() {
+        validate_keep_alive(Duration::from_secs(60)).unwrap();
+        validate_keep_alive(Duration::ZERO).unwrap_err();
+        validate_keep_alive(MAX_ASYNC_SEARCH_KEEP_ALIVE + Duration::from_secs(1)).unwrap_err();
+    }
+
+    #[test]
+    fn test_parse_async_search_id() {
+        let async_search_key = Ulid::new();
+        assert_eq!(
+            parse_async_search_id(&async_search_key.to_string()).unwrap(),
+            async_search_key
+        );
+        let error = parse_async_search_id("not-an-async-search-id").unwrap_err();
+        assert!(matches!(error, SearchError::InvalidArgument(_)));
+    }
+
+    #[test]
+    fn test_time_to_live() {
+        assert!(time_to_live(now_millis() - 1_000).is_none());
+        let time_to_live = time_to_live(now_millis() + 60_000).unwrap();
+        assert!(time_to_live <= Duration::from_secs(60));
+        assert!(time_to_live >= Duration::from_secs(59));
+    }
+}
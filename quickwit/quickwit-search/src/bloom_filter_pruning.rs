@@ -0,0 +1,268 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+use anyhow::Context;
+use futures::stream::{self, StreamExt};
+use quickwit_common::bloom_filter::SplitBloomFilters;
+use quickwit_common::shared_consts::SPLIT_BLOOM_FILTERS_FILE_NAME;
+use quickwit_common::uri::Uri;
+use quickwit_doc_mapper::tag_pruning::{extract_tags_from_query, TagFilterAst};
+use quickwit_metastore::SplitMetadata;
+use quickwit_proto::search::SearchRequest;
+use quickwit_proto::types::IndexUid;
+use quickwit_query::query_ast::{
+    FullTextQuery, PhrasePrefixQuery, QueryAst, QueryAstTransformer, WildcardQuery,
+};
+use quickwit_storage::{OwnedBytes, Storage, StorageResolver};
+use tracing::{debug, warn};
+
+use crate::extract_split_and_footer_offsets;
+use crate::leaf::open_split_bundle;
+use crate::root::IndexesMetasForLeafSearch;
+use crate::service::{deserialize_doc_mapper, SearcherContext};
+
+/// Maximum number of splits whose Bloom filters are fetched concurrently.
+const MAX_CONCURRENT_BLOOM_FILTERS_FETCHES: usize = 100;
+
+/// Removes the splits that cannot match the query according to their Bloom filters.
+///
+/// The query is turned into the same predicate as the one used for tag pruning. The tags of the
+/// fields with a Bloom filter are then evaluated against the Bloom filters of each split: a split
+/// is discarded if the predicate evaluates to false. Since a Bloom filter never reports a term of
+/// the split as absent, no matching split can be discarded.
+///
+/// Splits without Bloom filters, or for which the Bloom filters cannot be fetched, are kept.
+pub(crate) async fn prune_splits_with_bloom_filters(
+    searcher_context: &SearcherContext,
+    storage_resolver: &StorageResolver,
+    indexes_metas_for_leaf_search: &IndexesMetasForLeafSearch,
+    search_request: &SearchRequest,
+    split_metadatas: Vec<SplitMetadata>,
+) -> crate::Result<Vec<SplitMetadata>> {
+    if split_metadatas.is_empty() {
+        return Ok(split_metadatas);
+    }
+    let query_ast: QueryAst = serde_json::from_str(&search_request.query_ast)?;
+    let Some(query_ast) = ExactTermQueriesOnly.transform(query_ast)? else {
+        return Ok(split_metadatas);
+    };
+    let Some(tag_filter_ast) = extract_tags_from_query(query_ast) else {
+        return Ok(split_metadatas);
+    };
+    let mut prunable_index_uids: HashSet<&IndexUid> = HashSet::new();
+
+    for (index_uid, index_metas) in indexes_metas_for_leaf_search {
+        let bloom_filter_field_names =
+            deserialize_doc_mapper(&index_metas.doc_mapper_str)?.bloom_filter_field_names();
+        if references_any_field(&tag_filter_ast, &bloom_filter_field_names) {
+            prunable_index_uids.insert(index_uid);
+        }
+    }
+    if prunable_index_uids.is_empty() {
+        return Ok(split_metadatas);
+    }
+    let (split_metadatas_to_check, mut kept_split_metadatas): (Vec<SplitMetadata>, Vec<_>) =
+        split_metadatas
+            .into_iter()
+            .partition(|split_metadata| prunable_index_uids.contains(&split_metadata.index_uid));
+    let num_splits_to_check = split_metadatas_to_check.len();
+    let split_may_match_futures = split_metadatas_to_check.iter().map(|split_metadata| {
+        split_may_match(
+            searcher_context,
+            storage_resolver,
+            indexes_metas_for_leaf_search,
+            &tag_filter_ast,
+            split_metadata,
+        )
+    });
+    let split_may_match_results: Vec<bool> = stream::iter(split_may_match_futures)
+        .buffered(MAX_CONCURRENT_BLOOM_FILTERS_FETCHES)
+        .collect()
+        .await;
+
+    for (split_metadata, split_may_match) in split_metadatas_to_check
+        .into_iter()
+        .zip(split_may_match_results)
+    {
+        if split_may_match {
+            kept_split_metadatas.push(split_metadata);
+        }
+    }
+    debug!(
+        num_splits_checked = num_splits_to_check,
+        num_splits_kept = kept_split_metadatas.len(),
+        "bloom-filter-pruning"
+    );
+    Ok(kept_split_metadatas)
+}
+
+/// Replaces the queries whose tags do not match the terms of the Bloom filters by `MatchAll`, which
+/// is uninformative for split pruning.
+///
+/// The tags of prefix and wildcard queries are patterns rather than terms, and a full-text query
+/// with a custom tokenizer may not search the query text as is.
+struct ExactTermQueriesOnly;
+
+impl QueryAstTransformer for ExactTermQueriesOnly {
+    type Err = std::convert::Infallible;
+
+    fn transform_full_text(
+        &mut self,
+        full_text_query: FullTextQuery,
+    ) -> Result<Option<QueryAst>, Self::Err> {
+        if full_text_query.params.tokenizer.is_some() {
+            return Ok(Some(QueryAst::MatchAll));
+        }
+        Ok(Some(QueryAst::FullText(full_text_query)))
+    }
+
+    fn transform_phrase_prefix(
+        &mut self,
+        _phrase_prefix_query: PhrasePrefixQuery,
+    ) -> Result<Option<QueryAst>, Self::Err> {
+        Ok(Some(QueryAst::MatchAll))
+    }
+
+    fn transform_wildcard(
+        &mut self,
+        _wildcard_query: WildcardQuery,
+    ) -> Result<Option<QueryAst>, Self::Err> {
+        Ok(Some(QueryAst::MatchAll))
+    }
+}
+
+/// Returns true if one of the tags of the tag filter is about one of the given fields.
+fn references_any_field(tag_filter_ast: &TagFilterAst, field_names: &BTreeSet<String>) -> bool {
+    match tag_filter_ast {
+        TagFilterAst::And(children) | TagFilterAst::Or(children) => children
+            .iter()
+            .any(|child_ast| references_any_field(child_ast, field_names)),
+        TagFilterAst::Tag { tag, .. } => field_names.contains(parse_tag(tag).0),
+    }
+}
+
+/// Splits a tag into its field name and, for a term tag, its value.
+///
+/// See `tag_pruning` for the format of the tags.
+fn parse_tag(tag: &str) -> (&str, Option<&str>) {
+    if let Some((field_name, value)) = tag.split_once(':') {
+        (field_name, Some(value))
+    } else {
+        (tag.strip_suffix('!').unwrap_or(tag), None)
+    }
+}
+
+async fn split_may_match(
+    searcher_context: &SearcherContext,
+    storage_resolver: &StorageResolver,
+    indexes_metas_for_leaf_search: &IndexesMetasForLeafSearch,
+    tag_filter_ast: &TagFilterAst,
+    split_metadata: &SplitMetadata,
+) -> bool {
+    let index_uri = &indexes_metas_for_leaf_search[&split_metadata.index_uid].index_uri;
+    let split_bloom_filters = match get_split_bloom_filters(
+        searcher_context,
+        storage_resolver,
+        index_uri,
+        split_metadata,
+    )
+    .await
+    {
+        Ok(split_bloom_filters) => split_bloom_filters,
+        Err(error) => {
+            warn!(
+                split_id=%split_metadata.split_id,
+                error=?error,
+                "failed to read Bloom filters of split"
+            );
+            return true;
+        }
+    };
+    tag_filter_ast.evaluate_with(&|tag| match parse_tag(tag) {
+        (field_name, Some(value)) => split_bloom_filters.may_contain(field_name, value.as_bytes()),
+        (field_name, None) => split_bloom_filters.has_field(field_name),
+    })
+}
+
+async fn get_split_bloom_filters(
+    searcher_context: &SearcherContext,
+    storage_resolver: &StorageResolver,
+    index_uri: &Uri,
+    split_metadata: &SplitMetadata,
+) -> anyhow::Result<SplitBloomFilters> {
+    let split_id = &split_metadata.split_id;
+    let serialized_split_bloom_filters = if let Some(serialized_split_bloom_filters) =
+        searcher_context.split_bloom_filters_cache.get(split_id)
+    {
+        serialized_split_bloom_filters
+    } else {
+        let index_storage = storage_resolver.resolve(index_uri).await?;
+        let split_and_footer_offsets = extract_split_and_footer_offsets(split_metadata);
+        let (_, split_bundle) =
+            open_split_bundle(searcher_context, index_storage, &split_and_footer_offsets).await?;
+        let bloom_filters_path = Path::new(SPLIT_BLOOM_FILTERS_FILE_NAME);
+        // Splits created before the Bloom filter fields were set do not have the file. We cache
+        // an empty payload so that we do not open their bundle again.
+        let serialized_split_bloom_filters = if split_bundle.exists(bloom_filters_path).await? {
+            split_bundle.get_all(bloom_filters_path).await?
+        } else {
+            OwnedBytes::empty()
+        };
+        searcher_context
+            .split_bloom_filters_cache
+            .put(split_id.clone(), serialized_split_bloom_filters.clone());
+        serialized_split_bloom_filters
+    };
+    if serialized_split_bloom_filters.is_empty() {
+        return Ok(SplitBloomFilters::default());
+    }
+    SplitBloomFilters::deserialize(serialized_split_bloom_filters.as_slice())
+        .context("failed to deserialize split Bloom filters")
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_query::query_ast::qast_helper;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_tag() {
+        assert_eq!(parse_tag("trace_id!"), ("trace_id", None));
+        assert_eq!(parse_tag("trace_id:abc"), ("trace_id", Some("abc")));
+        assert_eq!(parse_tag("trace_id:a:b!"), ("trace_id", Some("a:b!")));
+    }
+
+    #[test]
+    fn test_exact_term_queries_only() {
+        let query_ast = qast_helper("trace_id:abc* OR trace_id:def", &[]);
+        let query_ast = ExactTermQueriesOnly.transform(query_ast).unwrap().unwrap();
+        let tag_filter_ast = extract_tags_from_query(query_ast);
+        // The prefix query is uninformative, so nothing can be pruned.
+        assert!(tag_filter_ast.is_none());
+
+        let query_ast = qast_helper("trace_id:abc* AND trace_id:def", &[]);
+        let query_ast = ExactTermQueriesOnly.transform(query_ast).unwrap().unwrap();
+        let tag_filter_ast = extract_tags_from_query(query_ast).unwrap();
+        assert_eq!(tag_filter_ast.to_string(), "(¬trace_id! ∨ trace_id:def)");
+    }
+}
@@ -17,11 +17,14 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
 
 use base64::Engine;
 use futures::future::ready;
 use futures::{Future, StreamExt};
+use quickwit_config::LeafSearchHedgingConfig;
 use quickwit_proto::search::{
     FetchDocsRequest, FetchDocsResponse, GetKvRequest, LeafListFieldsRequest, LeafListTermsRequest,
     LeafListTermsResponse, LeafSearchRequest, LeafSearchResponse, LeafSearchStreamRequest,
@@ -30,9 +33,12 @@ use quickwit_proto::search::{
 use tantivy::aggregation::intermediate_agg_result::IntermediateAggregationResults;
 use tokio::sync::mpsc::error::SendError;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::time::Instant;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{debug, error, info, warn};
 
+use crate::hedging::LeafSearchHedging;
+use crate::metrics::SEARCH_METRICS;
 use crate::retry::search::LeafSearchRetryPolicy;
 use crate::retry::search_stream::{LeafSearchStreamRetryPolicy, SuccessfulSplitIds};
 use crate::retry::{retry_client, DefaultRetryPolicy, RetryPolicy};
@@ -53,12 +59,23 @@ const TARGET_NUM_REPLICATION: usize = 2;
 #[derive(Clone)]
 pub struct ClusterClient {
     pub(crate) search_job_placer: SearchJobPlacer,
+    leaf_search_hedging_opt: Option<Arc<LeafSearchHedging>>,
 }
 
 impl ClusterClient {
     /// Instantiates [`ClusterClient`].
     pub fn new(search_job_placer: SearchJobPlacer) -> Self {
-        Self { search_job_placer }
+        Self {
+            search_job_placer,
+            leaf_search_hedging_opt: None,
+        }
+    }
+
+    /// Enables the hedging of the leaf search requests: a leaf search request slower than most
+    /// recent ones is duplicated to another node, and the first response is kept.
+    pub fn with_leaf_search_hedging(mut self, hedging_config: LeafSearchHedgingConfig) -> Self {
+        self.leaf_search_hedging_opt = Some(Arc::new(LeafSearchHedging::new(hedging_config)));
+        self
     }
 
     /// Fetches docs with retry on another node client.
@@ -92,9 +109,13 @@ impl ClusterClient {
         request: LeafSearchRequest,
         mut client: SearchServiceClient,
     ) -> crate::Result<LeafSearchResponse> {
-        let mut response_res = client.leaf_search(request.clone()).await;
+        let start_instant = Instant::now();
+        let mut response_res = self
+            .hedged_leaf_search(request.clone(), client.clone())
+            .await;
         let retry_policy = LeafSearchRetryPolicy {};
-        if let Some(retry_request) = retry_policy.retry_request(request, &response_res) {
+        if let Some(mut retry_request) = retry_policy.retry_request(request, &response_res) {
+            deduct_elapsed_time_from_timeout(&mut retry_request, start_instant.elapsed());
             assert!(!retry_request.leaf_requests.is_empty());
             client = retry_client(
                 &self.search_job_placer,
@@ -112,6 +133,90 @@ impl ClusterClient {
         response_res
     }
 
+    /// Executes a leaf search request. If hedging is enabled and the request does not complete
+    /// within the hedging delay, a duplicate request is sent to another node and the first
+    /// successful response wins.
+    async fn hedged_leaf_search(
+        &self,
+        request: LeafSearchRequest,
+        mut client: SearchServiceClient,
+    ) -> crate::Result<LeafSearchResponse> {
+        let Some(leaf_search_hedging) = &self.leaf_search_hedging_opt else {
+            return client.leaf_search(request).await;
+        };
+        leaf_search_hedging.record_request();
+        let start_instant = Instant::now();
+        let hedging_delay = leaf_search_hedging.hedging_delay();
+        let primary_addr = client.grpc_addr();
+        let primary_response_fut = client.leaf_search(request.clone());
+        tokio::pin!(primary_response_fut);
+
+        let Ok(primary_response_res) =
+            tokio::time::timeout(hedging_delay, &mut primary_response_fut).await
+        else {
+            return self
+                .race_hedged_leaf_search(
+                    request,
+                    primary_addr,
+                    primary_response_fut,
+                    leaf_search_hedging,
+                    start_instant,
+                )
+                .await;
+        };
+        leaf_search_hedging.record_latency(start_instant.elapsed());
+        primary_response_res
+    }
+
+    async fn race_hedged_leaf_search(
+        &self,
+        mut request: LeafSearchRequest,
+        primary_addr: SocketAddr,
+        primary_response_fut: impl Future<Output = crate::Result<LeafSearchResponse>>,
+        leaf_search_hedging: &LeafSearchHedging,
+        start_instant: Instant,
+    ) -> crate::Result<LeafSearchResponse> {
+        let Some(first_split_id) = request
+            .leaf_requests
+            .first()
+            .and_then(|leaf_request| leaf_request.split_offsets.first())
+            .map(|split_offsets| split_offsets.split_id.clone())
+        else {
+            return primary_response_fut.await;
+        };
+        let hedge_client_res =
+            retry_client(&self.search_job_placer, primary_addr, &first_split_id).await;
+        let mut hedge_client = match hedge_client_res {
+            // Hedging on the same node would only add load to it.
+            Ok(hedge_client) if hedge_client.grpc_addr() != primary_addr => hedge_client,
+            _ => return primary_response_fut.await,
+        };
+        if !leaf_search_hedging.try_acquire_hedge() {
+            // Past the budget, hedging would multiply the load exactly when searchers are
+            // overloaded.
+            SEARCH_METRICS.leaf_search_hedges_throttled_total.inc();
+            let primary_response_res = primary_response_fut.await;
+            leaf_search_hedging.record_latency(start_instant.elapsed());
+            return primary_response_res;
+        }
+        debug!(
+            "leaf search on {primary_addr} did not respond within the hedging delay, sending a \
+             duplicate request to {}",
+            hedge_client.grpc_addr()
+        );
+        SEARCH_METRICS.leaf_search_hedged_requests_total.inc();
+        deduct_elapsed_time_from_timeout(&mut request, start_instant.elapsed());
+        let hedge_response_fut = hedge_client.leaf_search(request);
+        let (response_res, hedge_won) =
+            first_successful_response(primary_response_fut, hedge_response_fut).await;
+        // If the hedge won, the latency of the primary request is at least the elapsed time.
+        leaf_search_hedging.record_latency(start_instant.elapsed());
+        if hedge_won {
+            SEARCH_METRICS.leaf_search_hedge_wins_total.inc();
+        }
+        response_res
+    }
+
     /// Leaf search with retry on another node client.
     pub async fn leaf_list_fields(
         &self,
@@ -299,11 +404,60 @@ fn merge_leaf_search_response(
         num_hits: left_response.num_hits + right_response.num_hits,
         num_attempted_splits: left_response.num_attempted_splits
             + right_response.num_attempted_splits,
-        failed_splits: right_response.failed_splits,
+        // The splits that failed in the left response were retried, except the ones that timed
+        // out.
+        failed_splits: left_response
+            .failed_splits
+            .into_iter()
+            .filter(|failed_split| failed_split.timed_out)
+            .chain(right_response.failed_splits)
+            .collect(),
         partial_hits: left_response.partial_hits,
+        suggestions: Vec::new(),
+        split_profiles: Vec::new(),
     })
 }
 
+/// Runs a request and its hedge concurrently, and returns the first successful response. If
+/// one of them fails, the response of the other one is returned. The returned boolean is true if
+/// the response comes from the hedge.
+async fn first_successful_response<T>(
+    primary_response_fut: impl Future<Output = crate::Result<T>>,
+    hedge_response_fut: impl Future<Output = crate::Result<T>>,
+) -> (crate::Result<T>, bool) {
+    tokio::pin!(primary_response_fut);
+    tokio::pin!(hedge_response_fut);
+
+    tokio::select! {
+        primary_response_res = &mut primary_response_fut => {
+            if primary_response_res.is_ok() {
+                return (primary_response_res, false);
+            }
+            (hedge_response_fut.await, true)
+        }
+        hedge_response_res = &mut hedge_response_fut => {
+            if hedge_response_res.is_ok() {
+                return (hedge_response_res, true);
+            }
+            (primary_response_fut.await, false)
+        }
+    }
+}
+
+/// Deducts the time elapsed since a leaf search request was first sent from its timeout, so that
+/// its retries and hedges do not outlive the deadline of the root.
+fn deduct_elapsed_time_from_timeout(request: &mut LeafSearchRequest, elapsed: Duration) {
+    let Some(timeout_millis) = request
+        .search_request
+        .as_mut()
+        .and_then(|search_request| search_request.timeout_millis.as_mut())
+    else {
+        return;
+    };
+    let elapsed_millis = u32::try_from(elapsed.as_millis()).unwrap_or(u32::MAX);
+    *timeout_millis = timeout_millis.saturating_sub(elapsed_millis);
+}
+
 // Merge initial leaf search results with results obtained from a retry.
 fn merge_leaf_search_results(
     left_search_response_result: crate::Result<LeafSearchResponse>,
@@ -367,6 +521,7 @@ mod tests {
             split_id: split_id.to_string(),
             segment_ord: 1,
             doc_id,
+            collapse_value: None,
         }
     }
 
@@ -572,6 +727,7 @@ mod tests {
                         error: "mock_error".to_string(),
                         split_id: "split_2".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 1,
                     ..Default::default()
@@ -588,6 +744,7 @@ mod tests {
                         error: "mock_error".to_string(),
                         split_id: "split_3".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 1,
                     ..Default::default()
@@ -605,12 +762,114 @@ mod tests {
         assert_eq!(result.unwrap().num_hits, 2);
     }
 
+    #[tokio::test]
+    async fn test_cluster_client_leaf_search_hedging_fast_response() {
+        let request = mock_leaf_search_request();
+        let mut mock_search_service_1 = MockSearchService::new();
+        mock_search_service_1
+            .expect_leaf_search()
+            .return_once(|_: LeafSearchRequest| {
+                Ok(LeafSearchResponse {
+                    num_attempted_splits: 1,
+                    ..Default::default()
+                })
+            });
+        // The second searcher must not receive any request.
+        let mock_search_service_2 = MockSearchService::new();
+        let searcher_pool = searcher_pool_for_test([
+            ("127.0.0.1:1001", mock_search_service_1),
+            ("127.0.0.1:1002", mock_search_service_2),
+        ]);
+        let first_client_addr: SocketAddr = "127.0.0.1:1001".parse().unwrap();
+        let first_client = searcher_pool.get(&first_client_addr).unwrap();
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+        let cluster_client = ClusterClient::new(search_job_placer)
+            .with_leaf_search_hedging(LeafSearchHedgingConfig::default());
+        let leaf_search_response = cluster_client
+            .leaf_search(request, first_client)
+            .await
+            .unwrap();
+        assert_eq!(leaf_search_response.num_attempted_splits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_first_successful_response() {
+        tokio::time::pause();
+        let delayed_response = |delay_millis: u64, response_res: crate::Result<u32>| async move {
+            tokio::time::sleep(Duration::from_millis(delay_millis)).await;
+            response_res
+        };
+        let error = || SearchError::Internal("error".to_string());
+
+        let (response_res, hedge_won) =
+            first_successful_response(delayed_response(10, Ok(1)), delayed_response(20, Ok(2)))
+                .await;
+        assert_eq!(response_res.unwrap(), 1);
+        assert!(!hedge_won);
+
+        let (response_res, hedge_won) =
+            first_successful_response(delayed_response(20, Ok(1)), delayed_response(10, Ok(2)))
+                .await;
+        assert_eq!(response_res.unwrap(), 2);
+        assert!(hedge_won);
+
+        let (response_res, hedge_won) = first_successful_response(
+            delayed_response(10, Err(error())),
+            delayed_response(20, Ok(2)),
+        )
+        .await;
+        assert_eq!(response_res.unwrap(), 2);
+        assert!(hedge_won);
+
+        let (response_res, hedge_won) = first_successful_response(
+            delayed_response(20, Ok(1)),
+            delayed_response(10, Err(error())),
+        )
+        .await;
+        assert_eq!(response_res.unwrap(), 1);
+        assert!(!hedge_won);
+
+        let (response_res, _) = first_successful_response(
+            delayed_response(10, Err(error())),
+            delayed_response(20, Err(error())),
+        )
+        .await;
+        assert!(response_res.is_err());
+    }
+
+    #[test]
+    fn test_deduct_elapsed_time_from_timeout() {
+        let mut request = LeafSearchRequest {
+            search_request: Some(SearchRequest {
+                timeout_millis: Some(1_000),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        deduct_elapsed_time_from_timeout(&mut request, Duration::from_millis(300));
+        let timeout_millis = request.search_request.as_ref().unwrap().timeout_millis;
+        assert_eq!(timeout_millis, Some(700));
+
+        deduct_elapsed_time_from_timeout(&mut request, Duration::from_secs(1));
+        let timeout_millis = request.search_request.as_ref().unwrap().timeout_millis;
+        assert_eq!(timeout_millis, Some(0));
+
+        let mut request = LeafSearchRequest {
+            search_request: Some(SearchRequest::default()),
+            ..Default::default()
+        };
+        deduct_elapsed_time_from_timeout(&mut request, Duration::from_secs(1));
+        let timeout_millis = request.search_request.as_ref().unwrap().timeout_millis;
+        assert_eq!(timeout_millis, None);
+    }
+
     #[test]
     fn test_merge_leaf_search_retry_on_partial_success() -> anyhow::Result<()> {
         let split_error = SplitSearchError {
             error: "error".to_string(),
             split_id: "split_2".to_string(),
             retryable_error: true,
+            timed_out: false,
         };
         let leaf_response = LeafSearchResponse {
             num_hits: 1,
@@ -641,6 +900,7 @@ mod tests {
             error: "error".to_string(),
             split_id: "split_2".to_string(),
             retryable_error: true,
+            timed_out: false,
         };
         let leaf_response = LeafSearchResponse {
             num_hits: 1,
@@ -0,0 +1,493 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use quickwit_common::binary_heap::SortKeyMapper;
+use quickwit_proto::search::{Collapse, PartialHit};
+use quickwit_proto::types::SplitId;
+use tantivy::columnar::{ColumnType, MonotonicallyMappableToU64, StrColumn};
+use tantivy::fastfield::Column;
+use tantivy::{DocId, Score, SegmentOrdinal, SegmentReader};
+
+use crate::collector::{HitSortingMapper, SegmentPartialHit, SortingFieldExtractorPair};
+use crate::top_k_collector::{
+    precompute_search_after_order, QuickwitSegmentTopKCollector, SearchAfterSegment,
+};
+
+/// Column types of the numerical fast fields hits can be collapsed on.
+const NUMERIC_COLLAPSE_COLUMN_TYPES: [ColumnType; 5] = [
+    ColumnType::U64,
+    ColumnType::I64,
+    ColumnType::F64,
+    ColumnType::Bool,
+    ColumnType::DateTime,
+];
+
+/// Returns the number of hits collected for each collapsed value.
+pub(crate) fn collapse_group_size(collapse: &Collapse) -> usize {
+    (collapse.inner_hits as usize).max(1)
+}
+
+/// Computes top-k of groups of items sharing the same collapse value.
+///
+/// Groups are ranked by their best item, and only the `group_size` best items of each group are
+/// kept.
+#[derive(Clone)]
+pub(crate) struct CollapsedTopK<G, T, S> {
+    groups: HashMap<G, Vec<T>>,
+    num_groups: usize,
+    group_size: usize,
+    sort_key_mapper: S,
+}
+
+impl<G, T, S> CollapsedTopK<G, T, S>
+where
+    G: Eq + Hash,
+    S: SortKeyMapper<T>,
+    S::Key: Ord,
+{
+    pub fn new(num_groups: usize, group_size: usize, sort_key_mapper: S) -> Self {
+        CollapsedTopK {
+            groups: HashMap::new(),
+            num_groups,
+            group_size,
+            sort_key_mapper,
+        }
+    }
+
+    pub fn add_entries(&mut self, entries: impl Iterator<Item = (G, T)>) {
+        for (group, item) in entries {
+            self.add_entry(group, item);
+        }
+    }
+
+    pub fn add_entry(&mut self, group: G, item: T) {
+        if self.num_groups == 0 || self.group_size == 0 {
+            return;
+        }
+        let sort_key = self.sort_key_mapper.get_sort_key(&item);
+        let group_items = self.groups.entry(group).or_default();
+
+        if group_items.len() >= self.group_size {
+            if let Some(worst_item) = group_items.last() {
+                if self.sort_key_mapper.get_sort_key(worst_item) >= sort_key {
+                    return;
+                }
+            }
+        }
+        let position = group_items
+            .partition_point(|group_item| self.sort_key_mapper.get_sort_key(group_item) > sort_key);
+        group_items.insert(position, item);
+        group_items.truncate(self.group_size);
+
+        // Groups outside of the top-k are only evicted once in a while to amortize the cost of
+        // ranking the groups.
+        if self.groups.len() >= 2 * self.num_groups {
+            self.evict_worst_groups();
+        }
+    }
+
+    /// Returns the best item of the worst group of the top-k, if k groups were collected already.
+    pub fn peek_worst_group_top_item(&self) -> Option<&T> {
+        if self.num_groups == 0 || self.groups.len() < self.num_groups {
+            return None;
+        }
+        let mut top_items: Vec<&T> = self.groups.values().map(|items| &items[0]).collect();
+        let (_, worst_top_item, _) = top_items
+            .select_nth_unstable_by_key(self.num_groups - 1, |item| {
+                Reverse(self.sort_key_mapper.get_sort_key(item))
+            });
+        Some(*worst_top_item)
+    }
+
+    /// Returns the top-k groups, best group first. The items of each group are sorted, best
+    /// item first.
+    pub fn finalize(mut self) -> Vec<(G, Vec<T>)> {
+        self.evict_worst_groups();
+        let sort_key_mapper = self.sort_key_mapper;
+        let mut groups: Vec<(G, Vec<T>)> = self.groups.into_iter().collect();
+        groups.sort_by_cached_key(|(_, items)| Reverse(sort_key_mapper.get_sort_key(&items[0])));
+        groups
+    }
+
+    fn evict_worst_groups(&mut self) {
+        if self.groups.len() <= self.num_groups {
+            return;
+        }
+        let mut groups: Vec<(G, Vec<T>)> = self.groups.drain().collect();
+        let sort_key_mapper = &self.sort_key_mapper;
+        groups.select_nth_unstable_by_key(self.num_groups - 1, |(_, items)| {
+            Reverse(sort_key_mapper.get_sort_key(&items[0]))
+        });
+        groups.truncate(self.num_groups);
+        self.groups.extend(groups);
+    }
+}
+
+/// Merges collapsed partial hits, keeping the `num_groups` best groups of hits sharing the same
+/// collapse value and the `group_size` best hits of each group.
+///
+/// The hits of a group are contiguous in the returned vector.
+pub(crate) fn top_k_collapsed_partial_hits(
+    partial_hits: impl Iterator<Item = PartialHit>,
+    sort_key_mapper: HitSortingMapper,
+    num_groups: usize,
+    group_size: usize,
+) -> Vec<PartialHit> {
+    let mut top_k_groups = CollapsedTopK::new(num_groups, group_size, sort_key_mapper);
+    top_k_groups.add_entries(
+        partial_hits.map(|partial_hit| (partial_hit.collapse_value.clone(), partial_hit)),
+    );
+    top_k_groups
+        .finalize()
+        .into_iter()
+        .flat_map(|(_, partial_hits)| partial_hits)
+        .collect()
+}
+
+/// Drops the first `start_offset` groups of a list of collapsed partial hits, and only keeps the
+/// `max_groups` following ones.
+pub(crate) fn drain_collapsed_groups(
+    partial_hits: &mut Vec<PartialHit>,
+    start_offset: usize,
+    max_groups: usize,
+) {
+    let group_starts: Vec<usize> = (0..partial_hits.len())
+        .filter(|&position| {
+            position == 0
+                || partial_hits[position - 1].collapse_value
+                    != partial_hits[position].collapse_value
+        })
+        .collect();
+    let group_start = |group_ord: usize| {
+        group_starts
+            .get(group_ord)
+            .copied()
+            .unwrap_or(partial_hits.len())
+    };
+    let end = group_start(start_offset.saturating_add(max_groups));
+    let start = group_start(start_offset);
+    partial_hits.truncate(end);
+    partial_hits.drain(..start);
+}
+
+/// Value of the collapse field of a document, at the scale of a segment.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum SegmentCollapseValue {
+    TermOrd(u64),
+    Numeric(u64),
+}
+
+/// Extracts the value of the collapse field of the documents of a segment.
+///
+/// For multivalued fields, only the first value of the document is considered.
+pub(crate) struct CollapseValueExtractor {
+    str_column_opt: Option<StrColumn>,
+    numeric_column_opt: Option<(Column<u64>, ColumnType)>,
+}
+
+impl CollapseValueExtractor {
+    pub fn new(field_name: &str, segment_reader: &SegmentReader) -> tantivy::Result<Self> {
+        let fast_fields = segment_reader.fast_fields();
+        let str_column_opt = fast_fields.str(field_name)?;
+        let numeric_column_opt =
+            fast_fields.u64_lenient_for_type(Some(&NUMERIC_COLLAPSE_COLUMN_TYPES), field_name)?;
+        Ok(CollapseValueExtractor {
+            str_column_opt,
+            numeric_column_opt,
+        })
+    }
+
+    #[inline]
+    fn extract(&self, doc_id: DocId) -> Option<SegmentCollapseValue> {
+        if let Some(str_column) = &self.str_column_opt {
+            if let Some(term_ord) = str_column.term_ords(doc_id).next() {
+                return Some(SegmentCollapseValue::TermOrd(term_ord));
+            }
+        }
+        let (numeric_column, _) = self.numeric_column_opt.as_ref()?;
+        numeric_column
+            .first(doc_id)
+            .map(SegmentCollapseValue::Numeric)
+    }
+
+    /// Converts a collapse value to its string representation, which is comparable across
+    /// segments and splits.
+    fn to_collapse_value(&self, segment_collapse_value: SegmentCollapseValue) -> Option<String> {
+        match segment_collapse_value {
+            SegmentCollapseValue::TermOrd(term_ord) => {
+                let str_column = self.str_column_opt.as_ref()?;
+                let mut term = String::new();
+                // The dictionary is warmed up with the fast field, so this is not expected to
+                // fail.
+                match str_column.ord_to_str(term_ord, &mut term) {
+                    Ok(true) => Some(term),
+                    _ => None,
+                }
+            }
+            SegmentCollapseValue::Numeric(value) => {
+                let (_, column_type) = self.numeric_column_opt.as_ref()?;
+                let collapse_value = match column_type {
+                    ColumnType::I64 | ColumnType::DateTime => i64::from_u64(value).to_string(),
+                    ColumnType::F64 => f64::from_u64(value).to_string(),
+                    ColumnType::Bool => (value != 0).to_string(),
+                    _ => value.to_string(),
+                };
+                Some(collapse_value)
+            }
+        }
+    }
+}
+
+/// Segment top-k collector keeping the best hits per value of the collapse field.
+pub(crate) struct CollapsingSegmentTopKCollector {
+    split_id: SplitId,
+    segment_ord: SegmentOrdinal,
+    score_extractor: SortingFieldExtractorPair,
+    collapse_value_extractor: CollapseValueExtractor,
+    search_after: Option<SearchAfterSegment>,
+    // Precomputed order for search_after for split_id and segment_ord
+    precomp_search_after_order: Ordering,
+    sort_key_mapper: HitSortingMapper,
+    top_k_groups: CollapsedTopK<Option<SegmentCollapseValue>, SegmentPartialHit, HitSortingMapper>,
+}
+
+impl CollapsingSegmentTopKCollector {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        split_id: SplitId,
+        score_extractor: SortingFieldExtractorPair,
+        collapse_value_extractor: CollapseValueExtractor,
+        num_groups: usize,
+        group_size: usize,
+        segment_ord: SegmentOrdinal,
+        search_after_option: Option<PartialHit>,
+        sort_key_mapper: HitSortingMapper,
+    ) -> Self {
+        let HitSortingMapper { order1, order2, .. } = sort_key_mapper;
+        let precomp_search_after_order =
+            precompute_search_after_order(&split_id, segment_ord, &search_after_option, order1);
+        let search_after =
+            SearchAfterSegment::new(search_after_option, order1, order2, &score_extractor);
+        CollapsingSegmentTopKCollector {
+            split_id,
+            segment_ord,
+            score_extractor,
+            collapse_value_extractor,
+            search_after,
+            precomp_search_after_order,
+            sort_key_mapper: sort_key_mapper.clone(),
+            top_k_groups: CollapsedTopK::new(num_groups, group_size, sort_key_mapper),
+        }
+    }
+}
+
+impl QuickwitSegmentTopKCollector for CollapsingSegmentTopKCollector {
+    fn collect_top_k_block(&mut self, docs: &[DocId]) {
+        // Scores are not computed when collecting blocks.
+        for doc_id in docs.iter().cloned() {
+            self.collect_top_k(doc_id, 0.0);
+        }
+    }
+
+    #[inline]
+    fn collect_top_k(&mut self, doc_id: DocId, score: Score) {
+        let (sort_value, sort_value2) =
+            self.score_extractor.extract_typed_sort_value(doc_id, score);
+
+        if let Some(search_after) = &self.search_after {
+            if !search_after.should_collect(
+                doc_id,
+                sort_value,
+                sort_value2,
+                &self.sort_key_mapper,
+                self.precomp_search_after_order,
+            ) {
+                return;
+            }
+        }
+        let hit = SegmentPartialHit {
+            sort_value,
+            sort_value2,
+            doc_id,
+        };
+        let collapse_value_opt = self.collapse_value_extractor.extract(doc_id);
+        self.top_k_groups.add_entry(collapse_value_opt, hit);
+    }
+
+    fn get_top_k(&self) -> Vec<PartialHit> {
+        let mut partial_hits = Vec::new();
+
+        for (segment_collapse_value_opt, hits) in self.top_k_groups.clone().finalize() {
+            let collapse_value_opt =
+                segment_collapse_value_opt.and_then(|segment_collapse_value| {
+                    self.collapse_value_extractor
+                        .to_collapse_value(segment_collapse_value)
+                });
+            for hit in hits {
+                let mut partial_hit = hit.into_partial_hit(
+                    self.split_id.clone(),
+                    self.segment_ord,
+                    &self.score_extractor.first,
+                    &self.score_extractor.second,
+                );
+                partial_hit.collapse_value.clone_from(&collapse_value_opt);
+                partial_hits.push(partial_hit);
+            }
+        }
+        partial_hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_proto::search::{SortByValue, SortMissing, SortOrder, SortValue};
+
+    use super::*;
+
+    fn sort_key_mapper(order: SortOrder) -> HitSortingMapper {
+        HitSortingMapper {
+            order1: order,
+            order2: order,
+            missing1: SortMissing::Last,
+            missing2: SortMissing::Last,
+        }
+    }
+
+    struct Identity;
+
+    impl SortKeyMapper<u32> for Identity {
+        type Key = u32;
+
+        fn get_sort_key(&self, value: &u32) -> u32 {
+            *value
+        }
+    }
+
+    fn make_partial_hit(collapse_value: &str, sort_value: u64) -> PartialHit {
+        PartialHit {
+            sort_value: Some(SortByValue {
+                sort_value: Some(SortValue::U64(sort_value)),
+            }),
+            split_id: "split".to_string(),
+            doc_id: sort_value as u32,
+            collapse_value: Some(collapse_value.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn collapse_values_and_sort_values(partial_hits: &[PartialHit]) -> Vec<(&str, u32)> {
+        partial_hits
+            .iter()
+            .map(|partial_hit| {
+                (
+                    partial_hit.collapse_value.as_deref().unwrap(),
+                    partial_hit.doc_id,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_collapsed_top_k() {
+        let mut top_k = CollapsedTopK::new(2, 2, Identity);
+        assert!(top_k.peek_worst_group_top_item().is_none());
+        top_k.add_entries(
+            [
+                ("a", 1),
+                ("b", 5),
+                ("a", 7),
+                ("c", 6),
+                ("a", 3),
+                ("d", 2),
+                ("b", 4),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(top_k.peek_worst_group_top_item(), Some(&6));
+        let groups = top_k.finalize();
+        assert_eq!(groups, vec![("a", vec![7, 3]), ("c", vec![6])]);
+
+        let mut top_k = CollapsedTopK::new(0, 2, Identity);
+        top_k.add_entry("a", 1);
+        assert!(top_k.peek_worst_group_top_item().is_none());
+        assert!(top_k.finalize().is_empty());
+    }
+
+    #[test]
+    fn test_top_k_collapsed_partial_hits() {
+        let partial_hits = vec![
+            make_partial_hit("a", 1),
+            make_partial_hit("b", 5),
+            make_partial_hit("a", 7),
+            make_partial_hit("c", 6),
+            make_partial_hit("b", 4),
+            make_partial_hit("b", 3),
+        ];
+        let top_k = top_k_collapsed_partial_hits(
+            partial_hits.clone().into_iter(),
+            sort_key_mapper(SortOrder::Desc),
+            2,
+            2,
+        );
+        assert_eq!(
+            collapse_values_and_sort_values(&top_k),
+            [("a", 7), ("a", 1), ("c", 6)]
+        );
+        let top_k = top_k_collapsed_partial_hits(
+            partial_hits.into_iter(),
+            sort_key_mapper(SortOrder::Asc),
+            3,
+            1,
+        );
+        assert_eq!(
+            collapse_values_and_sort_values(&top_k),
+            [("a", 1), ("b", 3), ("c", 6)]
+        );
+    }
+
+    #[test]
+    fn test_drain_collapsed_groups() {
+        let partial_hits = vec![
+            make_partial_hit("a", 7),
+            make_partial_hit("a", 6),
+            make_partial_hit("b", 5),
+            make_partial_hit("c", 4),
+            make_partial_hit("c", 3),
+        ];
+        let mut drained_partial_hits = partial_hits.clone();
+        drain_collapsed_groups(&mut drained_partial_hits, 1, 1);
+        assert_eq!(
+            collapse_values_and_sort_values(&drained_partial_hits),
+            [("b", 5)]
+        );
+        let mut drained_partial_hits = partial_hits.clone();
+        drain_collapsed_groups(&mut drained_partial_hits, 1, 10);
+        assert_eq!(
+            collapse_values_and_sort_values(&drained_partial_hits),
+            [("b", 5), ("c", 4), ("c", 3)]
+        );
+        let mut drained_partial_hits = partial_hits;
+        drain_collapsed_groups(&mut drained_partial_hits, 3, 1);
+        assert!(drained_partial_hits.is_empty());
+    }
+}
@@ -20,13 +20,17 @@
 use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use itertools::Itertools;
 use quickwit_common::binary_heap::{SortKeyMapper, TopK};
-use quickwit_doc_mapper::WarmupInfo;
+use quickwit_doc_mapper::{DocMapper, WarmupInfo};
 use quickwit_proto::search::{
-    LeafSearchResponse, PartialHit, SearchRequest, SortByValue, SortOrder, SortValue,
-    SplitSearchError,
+    Collapse, CompletionSuggester, CompletionSuggestion, LeafSearchResponse, PartialHit,
+    SearchRequest, SortByValue, SortField, SortMissing, SortOrder, SortValue, SplitSearchError,
+    SplitSearchProfile,
 };
 use quickwit_proto::types::SplitId;
 use serde::Deserialize;
@@ -36,8 +40,19 @@ use tantivy::aggregation::{AggregationLimitsGuard, AggregationSegmentCollector};
 use tantivy::collector::{Collector, SegmentCollector};
 use tantivy::columnar::{ColumnType, MonotonicallyMappableToU64};
 use tantivy::fastfield::Column;
+use tantivy::query::Weight;
+use tantivy::schema::Schema;
 use tantivy::{DateTime, DocId, Score, SegmentOrdinal, SegmentReader, TantivyError};
 
+use crate::collapse::{
+    collapse_group_size, drain_collapsed_groups, top_k_collapsed_partial_hits,
+    CollapseValueExtractor, CollapsedTopK, CollapsingSegmentTopKCollector,
+};
+use crate::completion_suggest::merge_completion_suggestions;
+use crate::extended_aggregations::{
+    ExtendedAggregations, ExtendedAggregationsSegmentCollector,
+    IntermediateExtendedAggregationResults,
+};
 use crate::find_trace_ids_collector::{FindTraceIdsCollector, FindTraceIdsSegmentCollector, Span};
 use crate::top_k_collector::{specialized_top_k_segment_collector, QuickwitSegmentTopKCollector};
 use crate::GlobalDocAddress;
@@ -50,6 +65,7 @@ pub(crate) enum SortByComponent {
     FastField {
         field_name: String,
         order: SortOrder,
+        missing: SortMissing,
     },
     Score {
         order: SortOrder,
@@ -69,14 +85,18 @@ pub(crate) struct SortByPair {
     second: Option<SortByComponent>,
 }
 impl SortByPair {
-    pub fn sort_orders(&self) -> (SortOrder, SortOrder) {
-        (
-            self.first.sort_order(),
-            self.second
-                .as_ref()
-                .map(|sort_by| sort_by.sort_order())
-                .unwrap_or(SortOrder::Desc),
-        )
+    pub fn sort_key_mapper(&self) -> HitSortingMapper {
+        let (order2, missing2) = self
+            .second
+            .as_ref()
+            .map(|sort_by| (sort_by.sort_order(), sort_by.sort_missing()))
+            .unwrap_or((SortOrder::Desc, SortMissing::Last));
+        HitSortingMapper {
+            order1: self.first.sort_order(),
+            order2,
+            missing1: self.first.sort_missing(),
+            missing2,
+        }
     }
 }
 impl SortByComponent {
@@ -112,11 +132,7 @@ impl SortByComponent {
         }
     }
     pub fn add_fast_field(&self, set: &mut HashSet<String>) {
-        if let SortByComponent::FastField {
-            field_name,
-            order: _,
-        } = self
-        {
+        if let SortByComponent::FastField { field_name, .. } = self {
             set.insert(field_name.clone());
         }
     }
@@ -127,6 +143,13 @@ impl SortByComponent {
             SortByComponent::Score { order } => *order,
         }
     }
+    /// Only fast fields can be missing a value: scores and doc ids are always defined.
+    pub fn sort_missing(&self) -> SortMissing {
+        match self {
+            SortByComponent::FastField { missing, .. } => *missing,
+            SortByComponent::DocId { .. } | SortByComponent::Score { .. } => SortMissing::Last,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -474,6 +497,7 @@ fn get_score_extractor(
 enum AggregationSegmentCollectors {
     FindTraceIdsSegmentCollector(Box<FindTraceIdsSegmentCollector>),
     TantivyAggregationSegmentCollector(AggregationSegmentCollector),
+    ExtendedAggregationSegmentCollector(Box<ExtendedAggregationsSegmentCollector>),
 }
 
 /// Quickwit collector working at the scale of the segment.
@@ -481,6 +505,33 @@ pub struct QuickwitSegmentCollector {
     segment_top_k_collector: Option<Box<dyn QuickwitSegmentTopKCollector>>,
     aggregation: Option<AggregationSegmentCollectors>,
     num_hits: u64,
+    aggregation_timer_opt: Option<AggregationTimer>,
+}
+
+/// Measures the time spent collecting the aggregations of a segment, when the search is profiled.
+struct AggregationTimer {
+    elapsed: Duration,
+    total_nanos: Arc<AtomicU64>,
+}
+
+impl QuickwitSegmentCollector {
+    #[inline]
+    fn start_aggregation_timer(&self) -> Option<Instant> {
+        if self.aggregation.is_some() && self.aggregation_timer_opt.is_some() {
+            Some(Instant::now())
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn stop_aggregation_timer(&mut self, start_opt: Option<Instant>) {
+        if let (Some(start), Some(aggregation_timer)) =
+            (start_opt, self.aggregation_timer_opt.as_mut())
+        {
+            aggregation_timer.elapsed += start.elapsed();
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -521,6 +572,7 @@ impl SegmentPartialHit {
             doc_id: self.doc_id,
             split_id,
             segment_ord,
+            collapse_value: None,
         }
     }
 }
@@ -537,6 +589,7 @@ impl SegmentCollector for QuickwitSegmentCollector {
             segment_top_k_collector.collect_top_k_block(filtered_docs);
         }
 
+        let aggregation_start_opt = self.start_aggregation_timer();
         match self.aggregation.as_mut() {
             Some(AggregationSegmentCollectors::FindTraceIdsSegmentCollector(collector)) => {
                 collector.collect_block(filtered_docs)
@@ -544,8 +597,12 @@ impl SegmentCollector for QuickwitSegmentCollector {
             Some(AggregationSegmentCollectors::TantivyAggregationSegmentCollector(collector)) => {
                 collector.collect_block(filtered_docs)
             }
+            Some(AggregationSegmentCollectors::ExtendedAggregationSegmentCollector(collector)) => {
+                collector.collect_block(filtered_docs)
+            }
             None => (),
         }
+        self.stop_aggregation_timer(aggregation_start_opt);
     }
 
     #[inline]
@@ -555,6 +612,7 @@ impl SegmentCollector for QuickwitSegmentCollector {
             segment_top_k_collector.collect_top_k(doc_id, score);
         }
 
+        let aggregation_start_opt = self.start_aggregation_timer();
         match self.aggregation.as_mut() {
             Some(AggregationSegmentCollectors::FindTraceIdsSegmentCollector(collector)) => {
                 collector.collect(doc_id, score)
@@ -562,8 +620,12 @@ impl SegmentCollector for QuickwitSegmentCollector {
             Some(AggregationSegmentCollectors::TantivyAggregationSegmentCollector(collector)) => {
                 collector.collect(doc_id, score)
             }
+            Some(AggregationSegmentCollectors::ExtendedAggregationSegmentCollector(collector)) => {
+                collector.collect(doc_id)
+            }
             None => (),
         }
+        self.stop_aggregation_timer(aggregation_start_opt);
     }
 
     fn harvest(self) -> Self::Fruit {
@@ -572,6 +634,7 @@ impl SegmentCollector for QuickwitSegmentCollector {
             partial_hits = segment_top_k_collector.get_top_k();
         }
 
+        let aggregation_start_opt = self.start_aggregation_timer();
         let intermediate_aggregation_result = match self.aggregation {
             Some(AggregationSegmentCollectors::FindTraceIdsSegmentCollector(collector)) => {
                 let fruit: Vec<Span> = collector.harvest();
@@ -584,14 +647,29 @@ impl SegmentCollector for QuickwitSegmentCollector {
                     .expect("Collector fruit should be serializable.");
                 Some(serialized)
             }
+            Some(AggregationSegmentCollectors::ExtendedAggregationSegmentCollector(collector)) => {
+                let serialized = postcard::to_allocvec(&collector.harvest()?)
+                    .expect("Collector fruit should be serializable.");
+                Some(serialized)
+            }
             None => None,
         };
+        if let (Some(aggregation_start), Some(aggregation_timer)) =
+            (aggregation_start_opt, self.aggregation_timer_opt)
+        {
+            let elapsed = aggregation_timer.elapsed + aggregation_start.elapsed();
+            aggregation_timer
+                .total_nanos
+                .fetch_add(elapsed.as_nanos() as u64, AtomicOrdering::Relaxed);
+        }
         Ok(LeafSearchResponse {
             intermediate_aggregation_result,
             num_hits: self.num_hits,
             partial_hits,
             failed_splits: Vec::new(),
             num_attempted_splits: 1,
+            suggestions: Vec::new(),
+            split_profiles: Vec::new(),
         })
     }
 }
@@ -603,6 +681,9 @@ pub enum QuickwitAggregations {
     /// Aggregation used by the Jaeger service to find trace IDs that match a
     /// [`quickwit_proto::jaeger::storage::v1::FindTraceIDsRequest`].
     FindTraceIdsAggregation(FindTraceIdsCollector),
+    /// Tantivy aggregations along with aggregations implemented by Quickwit, such as the
+    /// `filters` and `significant_terms` aggregations.
+    ExtendedAggregations(ExtendedAggregations),
     /// Your classic Tantivy aggregation.
     TantivyAggregations(Aggregations),
 }
@@ -613,6 +694,9 @@ impl QuickwitAggregations {
             QuickwitAggregations::FindTraceIdsAggregation(collector) => {
                 collector.fast_field_names()
             }
+            QuickwitAggregations::ExtendedAggregations(aggregations) => {
+                aggregations.fast_field_names()
+            }
             QuickwitAggregations::TantivyAggregations(aggregations) => {
                 get_fast_field_names(aggregations)
             }
@@ -624,6 +708,9 @@ impl QuickwitAggregations {
             QuickwitAggregations::FindTraceIdsAggregation(aggreg) => {
                 QuickwitIncrementalAggregations::FindTraceIdsAggregation(aggreg.clone(), Vec::new())
             }
+            QuickwitAggregations::ExtendedAggregations(aggreg) => {
+                QuickwitIncrementalAggregations::ExtendedAggregations(aggreg.clone(), Vec::new())
+            }
             QuickwitAggregations::TantivyAggregations(aggreg) => {
                 QuickwitIncrementalAggregations::TantivyAggregations(aggreg.clone(), Vec::new())
             }
@@ -634,6 +721,7 @@ impl QuickwitAggregations {
 #[derive(Clone)]
 enum QuickwitIncrementalAggregations {
     FindTraceIdsAggregation(FindTraceIdsCollector, Vec<Vec<Span>>),
+    ExtendedAggregations(ExtendedAggregations, Vec<Vec<u8>>),
     TantivyAggregations(Aggregations, Vec<Vec<u8>>),
     NoAggregation,
 }
@@ -650,7 +738,8 @@ impl QuickwitIncrementalAggregations {
                     state.push(new_state);
                 }
             }
-            QuickwitIncrementalAggregations::TantivyAggregations(_, state) => {
+            QuickwitIncrementalAggregations::ExtendedAggregations(_, state)
+            | QuickwitIncrementalAggregations::TantivyAggregations(_, state) => {
                 state.push(intermediate_result);
             }
             QuickwitIncrementalAggregations::NoAggregation => (),
@@ -673,12 +762,14 @@ impl QuickwitIncrementalAggregations {
                                 split_id: SplitId::new(),
                                 segment_ord: 0,
                                 doc_id: 0,
+                                collapse_value: None,
                             });
                         }
                     }
                 }
                 None
             }
+            QuickwitIncrementalAggregations::ExtendedAggregations(_, _) => None,
             QuickwitIncrementalAggregations::TantivyAggregations(_, _) => None,
             QuickwitIncrementalAggregations::NoAggregation => None,
         }
@@ -695,6 +786,12 @@ impl QuickwitIncrementalAggregations {
                 let serialized = postcard::to_allocvec(&merged_fruit).map_err(map_error)?;
                 Ok(Some(serialized))
             }
+            QuickwitIncrementalAggregations::ExtendedAggregations(aggregation, state) => {
+                merge_intermediate_aggregation_result(
+                    &Some(QuickwitAggregations::ExtendedAggregations(aggregation)),
+                    state.iter().map(|vec| vec.as_slice()),
+                )
+            }
             QuickwitIncrementalAggregations::TantivyAggregations(aggregation, state) => {
                 merge_intermediate_aggregation_result(
                     &Some(QuickwitAggregations::TantivyAggregations(aggregation)),
@@ -719,6 +816,17 @@ pub(crate) struct QuickwitCollector {
     pub aggregation: Option<QuickwitAggregations>,
     pub aggregation_limits: AggregationLimitsGuard,
     search_after: Option<PartialHit>,
+    /// If set, only the best hits of each value of the collapse field are collected.
+    pub collapse: Option<Collapse>,
+    /// Suggesters whose suggestions are merged with the hits. The suggestions of a split are not
+    /// collected by the collector, they are read from the term dictionaries of the split.
+    pub suggesters: Vec<CompletionSuggester>,
+    /// Weights of the filter queries of the `filters` aggregations, built against the split
+    /// schema.
+    filters_weights: Vec<Vec<Arc<dyn Weight>>>,
+    /// If set, the time spent collecting the aggregations is added to this counter, in
+    /// nanoseconds.
+    pub aggregation_collection_nanos: Option<Arc<AtomicU64>>,
 }
 
 impl QuickwitCollector {
@@ -733,6 +841,7 @@ impl QuickwitCollector {
         self.max_hits = search_request.max_hits as usize;
         self.start_offset = search_request.start_offset as usize;
         self.search_after.clone_from(&search_request.search_after);
+        self.collapse.clone_from(&search_request.collapse);
     }
     pub fn fast_field_names(&self) -> HashSet<String> {
         let mut fast_field_names = HashSet::default();
@@ -743,6 +852,9 @@ impl QuickwitCollector {
         if let Some(aggregations) = &self.aggregation {
             fast_field_names.extend(aggregations.fast_field_names());
         }
+        if let Some(collapse) = &self.collapse {
+            fast_field_names.insert(collapse.field.clone());
+        }
         fast_field_names
     }
 
@@ -753,6 +865,23 @@ impl QuickwitCollector {
             ..WarmupInfo::default()
         }
     }
+
+    /// Builds the weights of the filter queries of the `filters` aggregations, if any, against
+    /// the split schema. Returns the warmup info required by the filter queries.
+    pub fn build_filters_weights(
+        &mut self,
+        doc_mapper: &dyn DocMapper,
+        split_schema: &Schema,
+    ) -> crate::Result<WarmupInfo> {
+        let Some(QuickwitAggregations::ExtendedAggregations(aggregations)) = &self.aggregation
+        else {
+            return Ok(WarmupInfo::default());
+        };
+        let (filters_weights, warmup_info) =
+            aggregations.build_weights(doc_mapper, split_schema)?;
+        self.filters_weights = filters_weights;
+        Ok(warmup_info)
+    }
 }
 
 impl Collector for QuickwitCollector {
@@ -784,13 +913,38 @@ impl Collector for QuickwitCollector {
                     )?,
                 ),
             ),
+            Some(QuickwitAggregations::ExtendedAggregations(aggs)) => Some(
+                AggregationSegmentCollectors::ExtendedAggregationSegmentCollector(Box::new(
+                    aggs.for_segment(
+                        &self.filters_weights,
+                        segment_reader,
+                        segment_ord,
+                        &self.aggregation_limits,
+                    )?,
+                )),
+            ),
             None => None,
         };
         let score_extractor = get_score_extractor(&self.sort_by, segment_reader)?;
-        let (order1, order2) = self.sort_by.sort_orders();
+        let sort_key_mapper = self.sort_by.sort_key_mapper();
 
         let segment_top_k_collector = if leaf_max_hits == 0 {
             None
+        } else if let Some(collapse) = &self.collapse {
+            let collapse_value_extractor =
+                CollapseValueExtractor::new(&collapse.field, segment_reader)?;
+            let coll: Box<dyn QuickwitSegmentTopKCollector> =
+                Box::new(CollapsingSegmentTopKCollector::new(
+                    self.split_id.clone(),
+                    score_extractor,
+                    collapse_value_extractor,
+                    leaf_max_hits,
+                    collapse_group_size(collapse),
+                    segment_ord,
+                    self.search_after.clone(),
+                    sort_key_mapper,
+                ));
+            Some(coll)
         } else {
             let coll: Box<dyn QuickwitSegmentTopKCollector> = specialized_top_k_segment_collector(
                 self.split_id.clone(),
@@ -798,16 +952,23 @@ impl Collector for QuickwitCollector {
                 leaf_max_hits,
                 segment_ord,
                 self.search_after.clone(),
-                order1,
-                order2,
+                sort_key_mapper,
             );
             Some(coll)
         };
 
+        let aggregation_timer_opt = self
+            .aggregation_collection_nanos
+            .clone()
+            .map(|total_nanos| AggregationTimer {
+                elapsed: Duration::ZERO,
+                total_nanos,
+            });
         Ok(QuickwitSegmentCollector {
             num_hits: 0,
             segment_top_k_collector,
             aggregation,
+            aggregation_timer_opt,
         })
     }
 
@@ -834,17 +995,26 @@ impl Collector for QuickwitCollector {
         // All leaves will return their top [0..start_offset + max_hits) documents.
         // We compute the overall [0..start_offset + max_hits) documents ...
         let num_hits = self.start_offset + self.max_hits;
-        let (sort_order1, sort_order2) = self.sort_by.sort_orders();
         let mut merged_leaf_response = merge_leaf_responses(
             &self.aggregation,
+            self.collapse.as_ref(),
+            &self.suggesters,
             segment_fruits?,
-            sort_order1,
-            sort_order2,
+            self.sort_by.sort_key_mapper(),
             num_hits,
         )?;
         // ... and drop the first [..start_offsets) hits.
         // note that self.start_offset is 0 when merging from leaf_search, and is only set when
         // merging from root_search, so as to remove the firsts elements only once.
+        if self.collapse.is_some() {
+            // Collapsed hits are paginated by group.
+            drain_collapsed_groups(
+                &mut merged_leaf_response.partial_hits,
+                self.start_offset,
+                self.max_hits,
+            );
+            return Ok(merged_leaf_response);
+        }
         merged_leaf_response.partial_hits.drain(
             0..self
                 .start_offset
@@ -897,6 +1067,27 @@ fn merge_intermediate_aggregation_result<'a>(
                 None
             }
         }
+        Some(QuickwitAggregations::ExtendedAggregations(_)) => {
+            let fruits: Vec<IntermediateExtendedAggregationResults> =
+                intermediate_aggregation_results
+                    .map(|intermediate_aggregation_result| {
+                        postcard::from_bytes(intermediate_aggregation_result).map_err(map_error)
+                    })
+                    .collect::<Result<_, _>>()?;
+
+            let mut fruit_iter = fruits.into_iter();
+            if let Some(first_fruit) = fruit_iter.next() {
+                let mut merged_fruit = first_fruit;
+                for fruit in fruit_iter {
+                    merged_fruit.merge_fruits(fruit)?;
+                }
+                let serialized = postcard::to_allocvec(&merged_fruit).map_err(map_error)?;
+
+                Some(serialized)
+            } else {
+                None
+            }
+        }
         None => None,
     };
 
@@ -906,9 +1097,10 @@ fn merge_intermediate_aggregation_result<'a>(
 /// Merges a set of Leaf Results.
 fn merge_leaf_responses(
     aggregations_opt: &Option<QuickwitAggregations>,
+    collapse_opt: Option<&Collapse>,
+    suggesters: &[CompletionSuggester],
     mut leaf_responses: Vec<LeafSearchResponse>,
-    sort_order1: SortOrder,
-    sort_order2: SortOrder,
+    sort_key_mapper: HitSortingMapper,
     max_hits: usize,
 ) -> tantivy::Result<LeafSearchResponse> {
     // Optimization: No merging needed if there is only one result.
@@ -936,22 +1128,38 @@ fn merge_leaf_responses(
         .flat_map(|leaf_response| leaf_response.failed_splits.iter())
         .cloned()
         .collect_vec();
+    let suggestions = merge_completion_suggestions(
+        suggesters,
+        leaf_responses
+            .iter_mut()
+            .flat_map(|leaf_response| std::mem::take(&mut leaf_response.suggestions)),
+    );
+    let split_profiles = leaf_responses
+        .iter_mut()
+        .flat_map(|leaf_response| std::mem::take(&mut leaf_response.split_profiles))
+        .collect_vec();
     let all_partial_hits: Vec<PartialHit> = leaf_responses
         .into_iter()
         .flat_map(|leaf_response| leaf_response.partial_hits)
         .collect();
-    let top_k_partial_hits: Vec<PartialHit> = top_k_partial_hits(
-        all_partial_hits.into_iter(),
-        sort_order1,
-        sort_order2,
-        max_hits,
-    );
+    let top_k_partial_hits: Vec<PartialHit> = if let Some(collapse) = collapse_opt {
+        top_k_collapsed_partial_hits(
+            all_partial_hits.into_iter(),
+            sort_key_mapper,
+            max_hits,
+            collapse_group_size(collapse),
+        )
+    } else {
+        top_k_partial_hits(all_partial_hits.into_iter(), sort_key_mapper, max_hits)
+    };
     Ok(LeafSearchResponse {
         intermediate_aggregation_result: merged_intermediate_aggregation_result,
         num_hits,
         partial_hits: top_k_partial_hits,
         failed_splits,
         num_attempted_splits,
+        suggestions,
+        split_profiles,
     })
 }
 
@@ -961,11 +1169,9 @@ fn merge_leaf_responses(
 /// TODO we could possibly optimize the sort away (but I doubt it matters).
 fn top_k_partial_hits(
     partial_hits: impl Iterator<Item = PartialHit>,
-    order1: SortOrder,
-    order2: SortOrder,
+    sort_key_mapper: HitSortingMapper,
     num_hits: usize,
 ) -> Vec<PartialHit> {
-    let sort_key_mapper = HitSortingMapper { order1, order2 };
     let mut top_k_hits = TopK::new(num_hits, sort_key_mapper);
 
     partial_hits.for_each(|hit| top_k_hits.add_entry(hit));
@@ -974,7 +1180,8 @@ fn top_k_partial_hits(
 }
 
 pub(crate) fn sort_by_from_request(search_request: &SearchRequest) -> SortByPair {
-    let to_sort_by_component = |field_name: &str, order| {
+    let to_sort_by_component = |sort_field: &SortField, order| {
+        let field_name = &sort_field.field_name;
         if field_name == "_score" {
             SortByComponent::Score { order }
         } else if field_name == "_shard_doc" || field_name == "_doc" {
@@ -983,6 +1190,7 @@ pub(crate) fn sort_by_from_request(search_request: &SearchRequest) -> SortByPair
             SortByComponent::FastField {
                 field_name: field_name.to_string(),
                 order,
+                missing: sort_field.missing(),
             }
         }
     };
@@ -996,15 +1204,15 @@ pub(crate) fn sort_by_from_request(search_request: &SearchRequest) -> SortByPair
     } else if num_sort_fields == 1 {
         let sort_field = &search_request.sort_fields[0];
         let order = SortOrder::from_i32(sort_field.sort_order).unwrap_or(SortOrder::Desc);
-        to_sort_by_component(&sort_field.field_name, order).into()
+        to_sort_by_component(sort_field, order).into()
     } else if num_sort_fields == 2 {
         let sort_field1 = &search_request.sort_fields[0];
         let order1 = SortOrder::from_i32(sort_field1.sort_order).unwrap_or(SortOrder::Desc);
         let sort_field2 = &search_request.sort_fields[1];
         let order2 = SortOrder::from_i32(sort_field2.sort_order).unwrap_or(SortOrder::Desc);
         SortByPair {
-            first: to_sort_by_component(&sort_field1.field_name, order1),
-            second: Some(to_sort_by_component(&sort_field2.field_name, order2)),
+            first: to_sort_by_component(sort_field1, order1),
+            second: Some(to_sort_by_component(sort_field2, order2)),
         }
     } else {
         panic!("Sort by more than 2 fields is not supported yet.")
@@ -1030,6 +1238,10 @@ pub(crate) fn make_collector_for_split(
         aggregation,
         aggregation_limits,
         search_after: search_request.search_after.clone(),
+        collapse: search_request.collapse.clone(),
+        suggesters: search_request.suggesters.clone(),
+        filters_weights: Vec::new(),
+        aggregation_collection_nanos: None,
     })
 }
 
@@ -1051,6 +1263,10 @@ pub(crate) fn make_merge_collector(
         aggregation,
         aggregation_limits: aggregation_limits.clone(),
         search_after: search_request.search_after.clone(),
+        collapse: search_request.collapse.clone(),
+        suggesters: search_request.suggesters.clone(),
+        filters_weights: Vec::new(),
+        aggregation_collection_nanos: None,
     })
 }
 
@@ -1063,6 +1279,8 @@ pub struct SegmentPartialHitSortingKey {
     sort_order: SortOrder,
     // TODO This should not be there.
     sort_order2: SortOrder,
+    missing: SortMissing,
+    missing2: SortMissing,
 }
 
 impl Ord for SegmentPartialHitSortingKey {
@@ -1075,12 +1293,16 @@ impl Ord for SegmentPartialHitSortingKey {
             self.sort_order2, other.sort_order2,
             "comparing two PartialHitSortingKey of different ordering"
         );
-        let order = self
-            .sort_order
-            .compare_opt(&self.sort_value, &other.sort_value);
-        let order2 = self
-            .sort_order2
-            .compare_opt(&self.sort_value2, &other.sort_value2);
+        let order = self.sort_order.compare_opt_with_missing(
+            &self.sort_value,
+            &other.sort_value,
+            self.missing,
+        );
+        let order2 = self.sort_order2.compare_opt_with_missing(
+            &self.sort_value2,
+            &other.sort_value2,
+            self.missing2,
+        );
         let order_addr = self.sort_order.compare(&self.doc_id, &other.doc_id);
         order.then(order2).then(order_addr)
     }
@@ -1100,6 +1322,8 @@ pub(crate) struct PartialHitSortingKey {
     // TODO remove this
     sort_order: SortOrder,
     sort_order2: SortOrder,
+    missing: SortMissing,
+    missing2: SortMissing,
 }
 
 impl Ord for PartialHitSortingKey {
@@ -1113,13 +1337,17 @@ impl Ord for PartialHitSortingKey {
             "comparing two PartialHitSortingKey of different ordering"
         );
 
-        let order = self
-            .sort_order
-            .compare_opt(&self.sort_value, &other.sort_value);
+        let order = self.sort_order.compare_opt_with_missing(
+            &self.sort_value,
+            &other.sort_value,
+            self.missing,
+        );
 
-        let order2 = self
-            .sort_order2
-            .compare_opt(&self.sort_value2, &other.sort_value2);
+        let order2 = self.sort_order2.compare_opt_with_missing(
+            &self.sort_value2,
+            &other.sort_value2,
+            self.missing2,
+        );
 
         let order_addr = self.sort_order.compare(&self.address, &other.address);
 
@@ -1137,6 +1365,16 @@ impl PartialOrd for PartialHitSortingKey {
 pub(crate) struct HitSortingMapper {
     pub order1: SortOrder,
     pub order2: SortOrder,
+    pub missing1: SortMissing,
+    pub missing2: SortMissing,
+}
+
+impl HitSortingMapper {
+    /// Returns true if the hits missing a sort value are ranked before the others for one of the
+    /// sort fields.
+    pub fn has_missing_first(&self) -> bool {
+        self.missing1 == SortMissing::First || self.missing2 == SortMissing::First
+    }
 }
 
 impl SortKeyMapper<PartialHit> for HitSortingMapper {
@@ -1148,6 +1386,8 @@ impl SortKeyMapper<PartialHit> for HitSortingMapper {
             address: GlobalDocAddress::from_partial_hit(partial_hit),
             sort_order: self.order1,
             sort_order2: self.order2,
+            missing: self.missing1,
+            missing2: self.missing2,
         }
     }
 }
@@ -1161,6 +1401,8 @@ impl SortKeyMapper<SegmentPartialHit> for HitSortingMapper {
             doc_id: partial_hit.doc_id,
             sort_order: self.order1,
             sort_order2: self.order2,
+            missing: self.missing1,
+            missing2: self.missing2,
         }
     }
 }
@@ -1169,11 +1411,16 @@ impl SortKeyMapper<SegmentPartialHit> for HitSortingMapper {
 #[derive(Clone)]
 pub(crate) struct IncrementalCollector {
     top_k_hits: TopK<PartialHit, PartialHitSortingKey, HitSortingMapper>,
+    /// Used instead of `top_k_hits` if the hits are collapsed.
+    collapsed_top_k_hits: Option<CollapsedTopK<Option<String>, PartialHit, HitSortingMapper>>,
     incremental_aggregation: QuickwitIncrementalAggregations,
     num_hits: u64,
     failed_splits: Vec<SplitSearchError>,
     num_attempted_splits: u64,
     start_offset: usize,
+    suggesters: Vec<CompletionSuggester>,
+    suggestions: Vec<CompletionSuggestion>,
+    split_profiles: Vec<SplitSearchProfile>,
 }
 
 impl IncrementalCollector {
@@ -1184,15 +1431,26 @@ impl IncrementalCollector {
             .as_ref()
             .map(QuickwitAggregations::maybe_incremental_aggregator)
             .unwrap_or(QuickwitIncrementalAggregations::NoAggregation);
-        let (order1, order2) = collector.sort_by.sort_orders();
-        let sort_key_mapper = HitSortingMapper { order1, order2 };
+        let sort_key_mapper = collector.sort_by.sort_key_mapper();
+        let num_hits = collector.max_hits + collector.start_offset;
+        let collapsed_top_k_hits = collector.collapse.as_ref().map(|collapse| {
+            CollapsedTopK::new(
+                num_hits,
+                collapse_group_size(collapse),
+                sort_key_mapper.clone(),
+            )
+        });
         IncrementalCollector {
-            top_k_hits: TopK::new(collector.max_hits + collector.start_offset, sort_key_mapper),
+            top_k_hits: TopK::new(num_hits, sort_key_mapper),
+            collapsed_top_k_hits,
             start_offset: collector.start_offset,
             incremental_aggregation,
             num_hits: 0,
             failed_splits: Vec::new(),
             num_attempted_splits: 0,
+            suggesters: collector.suggesters,
+            suggestions: Vec::new(),
+            split_profiles: Vec::new(),
         }
     }
 
@@ -1204,16 +1462,33 @@ impl IncrementalCollector {
             failed_splits,
             num_attempted_splits,
             intermediate_aggregation_result,
+            suggestions,
+            split_profiles,
         } = leaf_response;
 
         self.num_hits += num_hits;
-        self.top_k_hits.add_entries(partial_hits.into_iter());
+        if let Some(collapsed_top_k_hits) = &mut self.collapsed_top_k_hits {
+            collapsed_top_k_hits.add_entries(
+                partial_hits
+                    .into_iter()
+                    .map(|partial_hit| (partial_hit.collapse_value.clone(), partial_hit)),
+            );
+        } else {
+            self.top_k_hits.add_entries(partial_hits.into_iter());
+        }
         self.failed_splits.extend(failed_splits);
         self.num_attempted_splits += num_attempted_splits;
         if let Some(intermediate_aggregation_result) = intermediate_aggregation_result {
             self.incremental_aggregation
                 .add(intermediate_aggregation_result)?;
         }
+        if !suggestions.is_empty() {
+            self.suggestions = merge_completion_suggestions(
+                &self.suggesters,
+                self.suggestions.drain(..).chain(suggestions),
+            );
+        }
+        self.split_profiles.extend(split_profiles);
         Ok(())
     }
 
@@ -1232,6 +1507,13 @@ impl IncrementalCollector {
                 .virtual_worst_hit()
                 .map(Cow::Owned);
         }
+        if let Some(collapsed_top_k_hits) = &self.collapsed_top_k_hits {
+            // The top hit of the worst group: a split can only add a group to the top-k with
+            // better hits.
+            return collapsed_top_k_hits
+                .peek_worst_group_top_item()
+                .map(Cow::Borrowed);
+        }
 
         if self.top_k_hits.at_capacity() {
             self.top_k_hits.peek_worst().map(Cow::Borrowed)
@@ -1243,16 +1525,29 @@ impl IncrementalCollector {
     /// Finalize the merge, creating a LeafSearchResponse.
     pub(crate) fn finalize(self) -> tantivy::Result<LeafSearchResponse> {
         let intermediate_aggregation_result = self.incremental_aggregation.finalize()?;
-        let mut partial_hits = self.top_k_hits.finalize();
-        if self.start_offset != 0 {
-            partial_hits.drain(0..self.start_offset.min(partial_hits.len()));
-        }
+        let partial_hits = if let Some(collapsed_top_k_hits) = self.collapsed_top_k_hits {
+            let mut partial_hits: Vec<PartialHit> = collapsed_top_k_hits
+                .finalize()
+                .into_iter()
+                .flat_map(|(_, partial_hits)| partial_hits)
+                .collect();
+            drain_collapsed_groups(&mut partial_hits, self.start_offset, usize::MAX);
+            partial_hits
+        } else {
+            let mut partial_hits = self.top_k_hits.finalize();
+            if self.start_offset != 0 {
+                partial_hits.drain(0..self.start_offset.min(partial_hits.len()));
+            }
+            partial_hits
+        };
         Ok(LeafSearchResponse {
             num_hits: self.num_hits,
             partial_hits,
             failed_splits: self.failed_splits,
             num_attempted_splits: self.num_attempted_splits,
             intermediate_aggregation_result,
+            suggestions: self.suggestions,
+            split_profiles: self.split_profiles,
         })
     }
 }
@@ -1262,16 +1557,25 @@ mod tests {
     use std::cmp::Ordering;
 
     use quickwit_proto::search::{
-        LeafSearchResponse, PartialHit, SearchRequest, SortByValue, SortField, SortOrder,
-        SortValue, SplitSearchError,
+        LeafSearchResponse, PartialHit, SearchRequest, SortByValue, SortField, SortMissing,
+        SortOrder, SortValue, SplitSearchError,
     };
     use quickwit_proto::types::DocMappingUid;
     use tantivy::collector::Collector;
     use tantivy::TantivyDocument;
 
-    use super::{make_merge_collector, IncrementalCollector};
+    use super::{make_merge_collector, HitSortingMapper, IncrementalCollector};
     use crate::collector::top_k_partial_hits;
 
+    fn sort_key_mapper(order: SortOrder, missing: SortMissing) -> HitSortingMapper {
+        HitSortingMapper {
+            order1: order,
+            order2: order,
+            missing1: missing,
+            missing2: missing,
+        }
+    }
+
     #[test]
     fn test_merge_partial_hits_no_tie() {
         let make_doc = |sort_value: u64| PartialHit {
@@ -1280,18 +1584,67 @@ mod tests {
             split_id: "split1".to_string(),
             segment_ord: 0u32,
             doc_id: 0u32,
+            collapse_value: None,
         };
         assert_eq!(
             top_k_partial_hits(
                 vec![make_doc(1u64), make_doc(3u64), make_doc(2u64),].into_iter(),
-                SortOrder::Asc,
-                SortOrder::Asc,
+                sort_key_mapper(SortOrder::Asc, SortMissing::Last),
                 2
             ),
             vec![make_doc(1), make_doc(2)]
         );
     }
 
+    #[test]
+    fn test_merge_partial_hits_missing_values() {
+        let make_doc = |sort_value_opt: Option<u64>, doc_id: u32| PartialHit {
+            sort_value: sort_value_opt.map(|sort_value| SortValue::U64(sort_value).into()),
+            sort_value2: None,
+            split_id: "split1".to_string(),
+            segment_ord: 0u32,
+            doc_id,
+            collapse_value: None,
+        };
+        let partial_hits = vec![
+            make_doc(Some(1), 0),
+            make_doc(None, 1),
+            make_doc(Some(2), 2),
+        ];
+        assert_eq!(
+            top_k_partial_hits(
+                partial_hits.clone().into_iter(),
+                sort_key_mapper(SortOrder::Asc, SortMissing::Last),
+                3
+            ),
+            vec![
+                make_doc(Some(1), 0),
+                make_doc(Some(2), 2),
+                make_doc(None, 1)
+            ]
+        );
+        assert_eq!(
+            top_k_partial_hits(
+                partial_hits.clone().into_iter(),
+                sort_key_mapper(SortOrder::Asc, SortMissing::First),
+                2
+            ),
+            vec![make_doc(None, 1), make_doc(Some(1), 0)]
+        );
+        assert_eq!(
+            top_k_partial_hits(
+                partial_hits.into_iter(),
+                sort_key_mapper(SortOrder::Desc, SortMissing::First),
+                3
+            ),
+            vec![
+                make_doc(None, 1),
+                make_doc(Some(2), 2),
+                make_doc(Some(1), 0)
+            ]
+        );
+    }
+
     #[test]
     fn test_merge_partial_hits_with_tie() {
         let make_hit_given_split_id = |split_id: u64| PartialHit {
@@ -1300,6 +1653,7 @@ mod tests {
             split_id: format!("split_{split_id}"),
             segment_ord: 0u32,
             doc_id: 0u32,
+            collapse_value: None,
         };
         assert_eq!(
             &top_k_partial_hits(
@@ -1309,8 +1663,7 @@ mod tests {
                     make_hit_given_split_id(2u64),
                 ]
                 .into_iter(),
-                SortOrder::Desc,
-                SortOrder::Desc,
+                sort_key_mapper(SortOrder::Desc, SortMissing::Last),
                 2
             ),
             &[make_hit_given_split_id(3), make_hit_given_split_id(2)]
@@ -1323,8 +1676,7 @@ mod tests {
                     make_hit_given_split_id(2u64),
                 ]
                 .into_iter(),
-                SortOrder::Asc,
-                SortOrder::Asc,
+                sort_key_mapper(SortOrder::Asc, SortMissing::Last),
                 2
             ),
             &[make_hit_given_split_id(1), make_hit_given_split_id(2)]
@@ -1418,17 +1770,23 @@ mod tests {
                 .split(',')
                 .filter(|field| !field.is_empty())
                 .map(|field| {
+                    let (field, missing) = match field.strip_suffix(":_first") {
+                        Some(field) => (field, SortMissing::First),
+                        None => (field, SortMissing::Last),
+                    };
                     if let Some(field) = field.strip_prefix('-') {
                         SortField {
                             field_name: field.to_string(),
                             sort_order: SortOrder::Asc.into(),
                             sort_datetime_format: None,
+                            missing: missing as i32,
                         }
                     } else {
                         SortField {
                             field_name: field.to_string(),
                             sort_order: SortOrder::Desc.into(),
                             sort_datetime_format: None,
+                            missing: missing as i32,
                         }
                     }
                 })
@@ -1498,6 +1856,8 @@ mod tests {
         let cmp_1_asc = |a: &Doc, b: &Doc| reverse_int(&b.1 .0).cmp(&reverse_int(&a.1 .0));
         let cmp_2_desc = |a: &Doc, b: &Doc| b.1 .1.cmp(&a.1 .1);
         let cmp_2_asc = |a: &Doc, b: &Doc| reverse_int(&b.1 .1).cmp(&reverse_int(&a.1 .1));
+        let cmp_1_missing_first = |a: &Doc, b: &Doc| a.1 .0.is_some().cmp(&b.1 .0.is_some());
+        let cmp_2_missing_first = |a: &Doc, b: &Doc| a.1 .1.is_some().cmp(&b.1 .1.is_some());
 
         {
             // the logic for sorting isn't easy to wrap one's head around. These simple tests are
@@ -1586,6 +1946,32 @@ mod tests {
                         .then(cmp_doc_id_asc(a, b))
                 }),
             ),
+            (
+                "sort1:_first",
+                Box::new(|a, b| {
+                    cmp_1_missing_first(a, b)
+                        .then(cmp_1_desc(a, b))
+                        .then(cmp_doc_id_desc(a, b))
+                }),
+            ),
+            (
+                "-sort1:_first,sort2",
+                Box::new(|a, b| {
+                    cmp_1_missing_first(a, b)
+                        .then(cmp_1_asc(a, b))
+                        .then(cmp_2_desc(a, b))
+                        .then(cmp_doc_id_asc(a, b))
+                }),
+            ),
+            (
+                "sort1,-sort2:_first",
+                Box::new(|a, b| {
+                    cmp_1_desc(a, b)
+                        .then(cmp_2_missing_first(a, b))
+                        .then(cmp_2_asc(a, b))
+                        .then(cmp_doc_id_desc(a, b))
+                }),
+            ),
         ];
 
         for (sort_str, sort_function) in sort_orders {
@@ -1667,6 +2053,7 @@ mod tests {
                 sort_value2: Some(SortByValue {
                     sort_value: val2.map(SortValue::U64),
                 }),
+                collapse_value: None,
             })
             .collect::<Vec<_>>();
         // we eliminate based on sort value
@@ -1678,11 +2065,13 @@ mod tests {
                         field_name: "sort1".to_string(),
                         sort_order: SortOrder::Desc.into(),
                         sort_datetime_format: None,
+                        missing: SortMissing::Last as i32,
                     },
                     SortField {
                         field_name: "sort2".to_string(),
                         sort_order: SortOrder::Asc.into(),
                         sort_datetime_format: None,
+                        missing: SortMissing::Last as i32,
                     },
                 ],
                 search_after: Some(search_after),
@@ -1714,6 +2103,7 @@ mod tests {
                 doc_id: 5,
                 sort_value: None,
                 sort_value2: None,
+                collapse_value: None,
             };
             let request = SearchRequest {
                 max_hits: 1000,
@@ -1721,6 +2111,7 @@ mod tests {
                     field_name: "_shard_doc".to_string(),
                     sort_order: SortOrder::Desc.into(),
                     sort_datetime_format: None,
+                    missing: SortMissing::Last as i32,
                 }],
                 search_after: Some(search_after),
                 ..SearchRequest::default()
@@ -1799,6 +2190,7 @@ mod tests {
                     field_name: "timestamp".to_string(),
                     sort_order: SortOrder::Desc as i32,
                     sort_datetime_format: None,
+                    missing: SortMissing::Last as i32,
                 }],
                 aggregation_request: None,
                 ..Default::default()
@@ -1811,10 +2203,13 @@ mod tests {
                     doc_id: 123,
                     sort_value: Some(SortValue::I64(1234).into()),
                     sort_value2: None,
+                    collapse_value: None,
                 }],
                 failed_splits: Vec::new(),
                 num_attempted_splits: 3,
                 intermediate_aggregation_result: None,
+                suggestions: Vec::new(),
+                split_profiles: Vec::new(),
             }],
         );
 
@@ -1828,10 +2223,13 @@ mod tests {
                     doc_id: 123,
                     sort_value: Some(SortValue::I64(1234).into()),
                     sort_value2: None,
+                    collapse_value: None,
                 }],
                 failed_splits: Vec::new(),
                 num_attempted_splits: 3,
-                intermediate_aggregation_result: None
+                intermediate_aggregation_result: None,
+                suggestions: Vec::new(),
+                split_profiles: Vec::new(),
             }
         );
 
@@ -1843,6 +2241,7 @@ mod tests {
                     field_name: "timestamp".to_string(),
                     sort_order: SortOrder::Desc as i32,
                     sort_datetime_format: None,
+                    missing: SortMissing::Last as i32,
                 }],
                 aggregation_request: None,
                 ..Default::default()
@@ -1857,6 +2256,7 @@ mod tests {
                             doc_id: 123,
                             sort_value: Some(SortValue::I64(1234).into()),
                             sort_value2: None,
+                            collapse_value: None,
                         },
                         PartialHit {
                             split_id: "1".to_string(),
@@ -1864,11 +2264,14 @@ mod tests {
                             doc_id: 125,
                             sort_value: Some(SortValue::I64(1236).into()),
                             sort_value2: None,
+                            collapse_value: None,
                         },
                     ],
                     failed_splits: Vec::new(),
                     num_attempted_splits: 3,
                     intermediate_aggregation_result: None,
+                    suggestions: Vec::new(),
+                    split_profiles: Vec::new(),
                 },
                 LeafSearchResponse {
                     num_hits: 10,
@@ -1878,14 +2281,18 @@ mod tests {
                         doc_id: 3,
                         sort_value: Some(SortValue::I64(1235).into()),
                         sort_value2: None,
+                        collapse_value: None,
                     }],
                     failed_splits: vec![SplitSearchError {
                         error: "fake error".to_string(),
                         split_id: "3".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 2,
                     intermediate_aggregation_result: None,
+                    suggestions: Vec::new(),
+                    split_profiles: Vec::new(),
                 },
             ],
         );
@@ -1901,6 +2308,7 @@ mod tests {
                         doc_id: 125,
                         sort_value: Some(SortValue::I64(1236).into()),
                         sort_value2: None,
+                        collapse_value: None,
                     },
                     PartialHit {
                         split_id: "2".to_string(),
@@ -1908,15 +2316,19 @@ mod tests {
                         doc_id: 3,
                         sort_value: Some(SortValue::I64(1235).into()),
                         sort_value2: None,
+                        collapse_value: None,
                     },
                 ],
                 failed_splits: vec![SplitSearchError {
                     error: "fake error".to_string(),
                     split_id: "3".to_string(),
                     retryable_error: true,
+                    timed_out: false,
                 }],
                 num_attempted_splits: 5,
-                intermediate_aggregation_result: None
+                intermediate_aggregation_result: None,
+                suggestions: Vec::new(),
+                split_profiles: Vec::new(),
             }
         );
 
@@ -1929,6 +2341,7 @@ mod tests {
                     field_name: "timestamp".to_string(),
                     sort_order: SortOrder::Asc as i32,
                     sort_datetime_format: None,
+                    missing: SortMissing::Last as i32,
                 }],
                 aggregation_request: None,
                 ..Default::default()
@@ -1943,6 +2356,7 @@ mod tests {
                             doc_id: 123,
                             sort_value: Some(SortValue::I64(1234).into()),
                             sort_value2: None,
+                            collapse_value: None,
                         },
                         PartialHit {
                             split_id: "1".to_string(),
@@ -1950,11 +2364,14 @@ mod tests {
                             doc_id: 125,
                             sort_value: Some(SortValue::I64(1236).into()),
                             sort_value2: None,
+                            collapse_value: None,
                         },
                     ],
                     failed_splits: Vec::new(),
                     num_attempted_splits: 3,
                     intermediate_aggregation_result: None,
+                    suggestions: Vec::new(),
+                    split_profiles: Vec::new(),
                 },
                 LeafSearchResponse {
                     num_hits: 10,
@@ -1964,14 +2381,18 @@ mod tests {
                         doc_id: 3,
                         sort_value: Some(SortValue::I64(1235).into()),
                         sort_value2: None,
+                        collapse_value: None,
                     }],
                     failed_splits: vec![SplitSearchError {
                         error: "fake error".to_string(),
                         split_id: "3".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 2,
                     intermediate_aggregation_result: None,
+                    suggestions: Vec::new(),
+                    split_profiles: Vec::new(),
                 },
             ],
         );
@@ -1987,6 +2408,7 @@ mod tests {
                         doc_id: 123,
                         sort_value: Some(SortValue::I64(1234).into()),
                         sort_value2: None,
+                        collapse_value: None,
                     },
                     PartialHit {
                         split_id: "2".to_string(),
@@ -1994,15 +2416,19 @@ mod tests {
                         doc_id: 3,
                         sort_value: Some(SortValue::I64(1235).into()),
                         sort_value2: None,
+                        collapse_value: None,
                     },
                 ],
                 failed_splits: vec![SplitSearchError {
                     error: "fake error".to_string(),
                     split_id: "3".to_string(),
                     retryable_error: true,
+                    timed_out: false,
                 }],
                 num_attempted_splits: 5,
-                intermediate_aggregation_result: None
+                intermediate_aggregation_result: None,
+                suggestions: Vec::new(),
+                split_profiles: Vec::new(),
             }
         );
         // TODO would be nice to test aggregation too.
@@ -0,0 +1,328 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::Bound;
+
+use anyhow::Context;
+use itertools::Itertools;
+use quickwit_proto::search::{CompletionSuggester, CompletionSuggestion};
+use tantivy::schema::{Field, FieldType, Schema};
+use tantivy::tokenizer::TokenStream;
+use tantivy::{Index, Searcher};
+
+use crate::SearchError;
+
+/// Maximum number of suggestions returned by a suggester.
+const MAX_SUGGESTION_SIZE: u32 = 10_000;
+
+/// Validates the suggesters of a search request against the schema of the searched indexes.
+pub(crate) fn validate_suggesters(
+    schema: &Schema,
+    suggesters: &[CompletionSuggester],
+) -> crate::Result<()> {
+    let mut suggester_names: HashSet<&str> = HashSet::with_capacity(suggesters.len());
+    for suggester in suggesters {
+        if !suggester_names.insert(&suggester.name) {
+            return Err(SearchError::InvalidArgument(format!(
+                "duplicate suggester name `{}`",
+                suggester.name
+            )));
+        }
+        if suggester.size > MAX_SUGGESTION_SIZE {
+            return Err(SearchError::InvalidArgument(format!(
+                "max value for suggester size is {MAX_SUGGESTION_SIZE}, but got {}",
+                suggester.size
+            )));
+        }
+        let field = schema.get_field(&suggester.field).map_err(|_| {
+            SearchError::InvalidArgument(format!(
+                "unknown field used in suggester `{}`: {}",
+                suggester.name, suggester.field
+            ))
+        })?;
+        let field_entry = schema.get_field_entry(field);
+        let is_indexed_text_field = matches!(
+            field_entry.field_type(),
+            FieldType::Str(text_options) if text_options.get_indexing_options().is_some()
+        );
+        if !is_indexed_text_field {
+            return Err(SearchError::InvalidArgument(format!(
+                "suggester field `{}` must be an indexed `completion` or `text` field",
+                suggester.field
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the smallest key greater than all the keys starting with `prefix`, or `None` if there
+/// is no such key, e.g. for an empty prefix.
+fn prefix_end_key(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut end_key = prefix.to_vec();
+    while let Some(last_byte) = end_key.pop() {
+        if last_byte != u8::MAX {
+            end_key.push(last_byte + 1);
+            return Some(end_key);
+        }
+    }
+    None
+}
+
+/// Normalizes the prefix with the tokenizer of the field, so that it matches the indexed terms.
+///
+/// Returns `None` if the prefix is split into several tokens: no term of the field can start
+/// with it.
+fn normalize_prefix(index: &Index, field: Field, prefix: &str) -> tantivy::Result<Option<String>> {
+    let mut tokenizer = index.tokenizer_for_field(field)?;
+    let mut token_stream = tokenizer.token_stream(prefix);
+    let mut tokens: Vec<String> = Vec::new();
+    token_stream.process(&mut |token| tokens.push(token.text.clone()));
+    let normalized_prefix_opt = match tokens.as_slice() {
+        [] if prefix.is_empty() => Some(String::new()),
+        [token] => Some(token.clone()),
+        _ => None,
+    };
+    Ok(normalized_prefix_opt)
+}
+
+/// Returns the terms of the field starting with the prefix of the suggester, in lexicographical
+/// order, read directly from the term dictionaries of the split.
+async fn split_completion_options(
+    index: &Index,
+    searcher: &Searcher,
+    field: Field,
+    suggester: &CompletionSuggester,
+) -> anyhow::Result<Vec<String>> {
+    let Some(prefix) = normalize_prefix(index, field, &suggester.prefix)? else {
+        return Ok(Vec::new());
+    };
+    let prefix_end_opt = prefix_end_key(prefix.as_bytes());
+    let limit = suggester.size as u64;
+
+    let mut segment_options: Vec<Vec<String>> =
+        Vec::with_capacity(searcher.segment_readers().len());
+    for segment_reader in searcher.segment_readers() {
+        let inverted_index = segment_reader.inverted_index(field)?.clone();
+        let dict = inverted_index.terms();
+        let end_bound = prefix_end_opt
+            .as_deref()
+            .map(Bound::Excluded)
+            .unwrap_or(Bound::Unbounded);
+        dict.file_slice_for_range((Bound::Included(prefix.as_bytes()), end_bound), Some(limit))
+            .read_bytes_async()
+            .await
+            .with_context(|| "failed to load sstable range")?;
+
+        let mut range = dict.range().ge(prefix.as_bytes()).limit(limit);
+        if let Some(prefix_end) = &prefix_end_opt {
+            range = range.lt(prefix_end);
+        }
+        let mut stream = range
+            .into_stream()
+            .with_context(|| "failed to create stream over sstable")?;
+        let mut options: Vec<String> = Vec::with_capacity(suggester.size as usize);
+        while stream.advance() {
+            // The terms of a text field are valid UTF-8.
+            options.push(String::from_utf8_lossy(stream.key()).into_owned());
+        }
+        segment_options.push(options);
+    }
+    let options = segment_options
+        .into_iter()
+        .kmerge()
+        .dedup()
+        .take(suggester.size as usize)
+        .collect();
+    Ok(options)
+}
+
+/// Computes the suggestions of the suggesters on a split.
+pub(crate) async fn leaf_completion_suggestions(
+    index: &Index,
+    searcher: &Searcher,
+    suggesters: &[CompletionSuggester],
+) -> anyhow::Result<Vec<CompletionSuggestion>> {
+    let schema = index.schema();
+    let mut suggestions = Vec::with_capacity(suggesters.len());
+    for suggester in suggesters {
+        // The field is missing from the splits indexed before it was added to the doc mapping.
+        let options = if let Ok(field) = schema.get_field(&suggester.field) {
+            split_completion_options(index, searcher, field, suggester).await?
+        } else {
+            Vec::new()
+        };
+        suggestions.push(CompletionSuggestion {
+            name: suggester.name.clone(),
+            options,
+        });
+    }
+    Ok(suggestions)
+}
+
+/// Merges the suggestions computed on different splits: the options of each suggester are
+/// deduplicated, and only the `size` first ones in lexicographical order are kept.
+pub(crate) fn merge_completion_suggestions(
+    suggesters: &[CompletionSuggester],
+    suggestions: impl IntoIterator<Item = CompletionSuggestion>,
+) -> Vec<CompletionSuggestion> {
+    if suggesters.is_empty() {
+        return Vec::new();
+    }
+    let mut options_per_name: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for suggestion in suggestions {
+        options_per_name
+            .entry(suggestion.name)
+            .or_default()
+            .extend(suggestion.options);
+    }
+    suggesters
+        .iter()
+        .map(|suggester| {
+            let options = options_per_name
+                .remove(&suggester.name)
+                .unwrap_or_default()
+                .into_iter()
+                .take(suggester.size as usize)
+                .collect();
+            CompletionSuggestion {
+                name: suggester.name.clone(),
+                options,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::schema::{TextFieldIndexing, TextOptions, STORED};
+
+    use super::*;
+
+    #[test]
+    fn test_prefix_end_key() {
+        assert_eq!(prefix_end_key(b""), None);
+        assert_eq!(prefix_end_key(b"nirv"), Some(b"nirw".to_vec()));
+        assert_eq!(prefix_end_key(b"ab\xFF"), Some(b"ac".to_vec()));
+        assert_eq!(prefix_end_key(b"\xFF\xFF"), None);
+    }
+
+    #[test]
+    fn test_merge_completion_suggestions() {
+        let suggesters = vec![
+            CompletionSuggester {
+                name: "artist".to_string(),
+                field: "artist_suggest".to_string(),
+                prefix: "n".to_string(),
+                size: 3,
+            },
+            CompletionSuggester {
+                name: "album".to_string(),
+                field: "album_suggest".to_string(),
+                prefix: "z".to_string(),
+                size: 3,
+            },
+        ];
+        let suggestions = vec![
+            CompletionSuggestion {
+                name: "artist".to_string(),
+                options: vec!["nas".to_string(), "nirvana".to_string()],
+            },
+            CompletionSuggestion {
+                name: "album".to_string(),
+                options: Vec::new(),
+            },
+            CompletionSuggestion {
+                name: "artist".to_string(),
+                options: vec![
+                    "nena".to_string(),
+                    "nirvana".to_string(),
+                    "nofx".to_string(),
+                ],
+            },
+        ];
+        let merged_suggestions = merge_completion_suggestions(&suggesters, suggestions);
+        assert_eq!(
+            merged_suggestions,
+            vec![
+                CompletionSuggestion {
+                    name: "artist".to_string(),
+                    options: vec!["nas".to_string(), "nena".to_string(), "nirvana".to_string()],
+                },
+                CompletionSuggestion {
+                    name: "album".to_string(),
+                    options: Vec::new(),
+                },
+            ]
+        );
+        assert!(merge_completion_suggestions(&[], Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_validate_suggesters() {
+        let mut schema_builder = Schema::builder();
+        let text_indexing = TextFieldIndexing::default().set_tokenizer("raw_lowercase");
+        schema_builder.add_text_field(
+            "suggest",
+            TextOptions::default().set_indexing_options(text_indexing),
+        );
+        schema_builder.add_text_field("body", STORED);
+        let schema = schema_builder.build();
+
+        let suggester = CompletionSuggester {
+            name: "my-suggestion".to_string(),
+            field: "suggest".to_string(),
+            prefix: "nir".to_string(),
+            size: 5,
+        };
+        validate_suggesters(&schema, &[suggester.clone()]).unwrap();
+
+        let error = validate_suggesters(&schema, &[suggester.clone(), suggester.clone()])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("duplicate suggester name `my-suggestion`"));
+
+        let too_large_suggester = CompletionSuggester {
+            size: 10_001,
+            ..suggester.clone()
+        };
+        let error = validate_suggesters(&schema, &[too_large_suggester])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("max value for suggester size is 10000"));
+
+        let unknown_field_suggester = CompletionSuggester {
+            field: "unknown".to_string(),
+            ..suggester.clone()
+        };
+        let error = validate_suggesters(&schema, &[unknown_field_suggester])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("unknown field used in suggester `my-suggestion`: unknown"));
+
+        let not_indexed_suggester = CompletionSuggester {
+            field: "body".to_string(),
+            ..suggester
+        };
+        let error = validate_suggesters(&schema, &[not_indexed_suggester])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("suggester field `body` must be an indexed"));
+    }
+}
@@ -33,6 +33,8 @@ use tokio::task::JoinError;
 #[derive(Error, Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum SearchError {
+    #[error("async search `{0}` not found or expired")]
+    AsyncSearchNotFound(String),
     #[error("could not find indexes matching the IDs `{index_ids:?}`")]
     IndexesNotFound { index_ids: Vec<String> },
     #[error("internal error: `{0}`")]
@@ -43,6 +45,8 @@ pub enum SearchError {
     InvalidArgument(String),
     #[error("{0}")]
     InvalidQuery(String),
+    #[error("point in time `{0}` not found or expired")]
+    PointInTimeNotFound(String),
     #[error("storage not found: `{0}`)")]
     StorageResolver(#[from] StorageResolverError),
     #[error("request timed out: {0}")]
@@ -56,6 +60,7 @@ pub enum SearchError {
 impl ServiceError for SearchError {
     fn error_code(&self) -> ServiceErrorCode {
         match self {
+            Self::AsyncSearchNotFound(_) => ServiceErrorCode::NotFound,
             Self::IndexesNotFound { .. } => ServiceErrorCode::NotFound,
             Self::Internal(error_msg) => {
                 rate_limited_error!(limit_per_min = 6, "search internal error: {error_msg}");
@@ -64,6 +69,7 @@ impl ServiceError for SearchError {
             Self::InvalidAggregationRequest(_) => ServiceErrorCode::BadRequest,
             Self::InvalidArgument(_) => ServiceErrorCode::BadRequest,
             Self::InvalidQuery(_) => ServiceErrorCode::BadRequest,
+            Self::PointInTimeNotFound(_) => ServiceErrorCode::NotFound,
             Self::StorageResolver(storage_err) => {
                 rate_limited_error!(
                     limit_per_min = 6,
@@ -0,0 +1,587 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Aggregations implemented by Quickwit on top of tantivy, such as the `filters`,
+//! `significant_terms`, geo grid, and `ip_range` aggregations.
+//!
+//! They are collected by dedicated segment collectors running alongside the tantivy aggregation
+//! collector, and are only supported at the top level of the aggregation request.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use quickwit_doc_mapper::{DocMapper, WarmupInfo};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use tantivy::aggregation::agg_req::{get_fast_field_names, Aggregations};
+use tantivy::aggregation::intermediate_agg_result::IntermediateAggregationResults;
+use tantivy::aggregation::{AggregationLimitsGuard, AggregationSegmentCollector};
+use tantivy::collector::SegmentCollector;
+use tantivy::query::Weight;
+use tantivy::schema::Schema;
+use tantivy::{DocId, SegmentOrdinal, SegmentReader};
+
+use crate::filters_aggregation::{
+    is_filters_aggregation, FiltersAggregation, FiltersSegmentCollector,
+    IntermediateFiltersAggregationResult,
+};
+use crate::geo_grid_aggregation::{
+    is_geo_grid_aggregation, GeoGridAggregation, GeoGridSegmentCollector, IntermediateGeoGridResult,
+};
+use crate::ip_range_aggregation::{
+    is_ip_range_aggregation, IntermediateIpRangeResult, IpRangeAggregation, IpRangeSegmentCollector,
+};
+use crate::significant_terms_aggregation::{
+    is_significant_terms_aggregation, IntermediateSignificantTermsResult,
+    SignificantTermsAggregation, SignificantTermsSegmentCollector,
+};
+
+fn is_extended_aggregation(aggregation: &JsonValue) -> bool {
+    is_filters_aggregation(aggregation)
+        || is_significant_terms_aggregation(aggregation)
+        || is_geo_grid_aggregation(aggregation)
+        || is_ip_range_aggregation(aggregation)
+}
+
+/// Returns true if the aggregation request contains a top-level aggregation implemented by
+/// Quickwit.
+pub(crate) fn has_extended_aggregation(aggregation_request: &str) -> bool {
+    let Ok(JsonValue::Object(aggregations)) =
+        serde_json::from_str::<JsonValue>(aggregation_request)
+    else {
+        return false;
+    };
+    aggregations.values().any(is_extended_aggregation)
+}
+
+/// Tantivy aggregations along with one or several aggregations implemented by Quickwit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtendedAggregations {
+    aggregations: Aggregations,
+    filters_aggregations: Vec<FiltersAggregation>,
+    significant_terms_aggregations: Vec<SignificantTermsAggregation>,
+    geo_grid_aggregations: Vec<GeoGridAggregation>,
+    ip_range_aggregations: Vec<IpRangeAggregation>,
+}
+
+impl<'de> Deserialize<'de> for ExtendedAggregations {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        let aggregations_json = JsonMap::<String, JsonValue>::deserialize(deserializer)?;
+        Self::from_json(aggregations_json).map_err(D::Error::custom)
+    }
+}
+
+impl ExtendedAggregations {
+    fn from_json(aggregations_json: JsonMap<String, JsonValue>) -> Result<Self, String> {
+        let mut tantivy_aggregations_json = JsonMap::new();
+        let mut filters_aggregations = Vec::new();
+        let mut significant_terms_aggregations = Vec::new();
+        let mut geo_grid_aggregations = Vec::new();
+        let mut ip_range_aggregations = Vec::new();
+
+        for (name, aggregation_json) in aggregations_json {
+            if is_filters_aggregation(&aggregation_json) {
+                filters_aggregations.push(FiltersAggregation::parse(name, aggregation_json)?);
+            } else if is_significant_terms_aggregation(&aggregation_json) {
+                significant_terms_aggregations
+                    .push(SignificantTermsAggregation::parse(name, aggregation_json)?);
+            } else if is_geo_grid_aggregation(&aggregation_json) {
+                geo_grid_aggregations.push(GeoGridAggregation::parse(name, aggregation_json)?);
+            } else if is_ip_range_aggregation(&aggregation_json) {
+                ip_range_aggregations.push(IpRangeAggregation::parse(name, aggregation_json)?);
+            } else {
+                tantivy_aggregations_json.insert(name, aggregation_json);
+            }
+        }
+        if filters_aggregations.is_empty()
+            && significant_terms_aggregations.is_empty()
+            && geo_grid_aggregations.is_empty()
+            && ip_range_aggregations.is_empty()
+        {
+            return Err("no aggregation implemented by Quickwit found".to_string());
+        }
+        let aggregations: Aggregations =
+            serde_json::from_value(JsonValue::Object(tantivy_aggregations_json))
+                .map_err(|error| error.to_string())?;
+        Ok(Self {
+            aggregations,
+            filters_aggregations,
+            significant_terms_aggregations,
+            geo_grid_aggregations,
+            ip_range_aggregations,
+        })
+    }
+
+    pub(crate) fn fast_field_names(&self) -> HashSet<String> {
+        let mut fast_field_names = get_fast_field_names(&self.aggregations);
+        for filters_aggregation in &self.filters_aggregations {
+            fast_field_names.extend(get_fast_field_names(filters_aggregation.sub_aggregations()));
+        }
+        for significant_terms_aggregation in &self.significant_terms_aggregations {
+            fast_field_names.insert(significant_terms_aggregation.field().to_string());
+        }
+        for geo_grid_aggregation in &self.geo_grid_aggregations {
+            fast_field_names.extend(geo_grid_aggregation.fast_field_names());
+        }
+        for ip_range_aggregation in &self.ip_range_aggregations {
+            fast_field_names.insert(ip_range_aggregation.field().to_string());
+            fast_field_names.extend(get_fast_field_names(
+                ip_range_aggregation.sub_aggregations(),
+            ));
+        }
+        fast_field_names
+    }
+
+    /// Builds the weights of the filter queries of every filters aggregation against the split
+    /// schema.
+    pub(crate) fn build_weights(
+        &self,
+        doc_mapper: &dyn DocMapper,
+        split_schema: &Schema,
+    ) -> crate::Result<(Vec<Vec<Arc<dyn Weight>>>, WarmupInfo)> {
+        let mut weights = Vec::with_capacity(self.filters_aggregations.len());
+        let mut warmup_info = WarmupInfo::default();
+
+        for filters_aggregation in &self.filters_aggregations {
+            let (filters_weights, filters_warmup_info) =
+                filters_aggregation.build_weights(doc_mapper, split_schema)?;
+            weights.push(filters_weights);
+            warmup_info.merge(filters_warmup_info);
+        }
+        Ok((weights, warmup_info))
+    }
+
+    pub(crate) fn for_segment(
+        &self,
+        weights: &[Vec<Arc<dyn Weight>>],
+        segment_reader: &SegmentReader,
+        segment_ord: SegmentOrdinal,
+        aggregation_limits: &AggregationLimitsGuard,
+    ) -> tantivy::Result<ExtendedAggregationsSegmentCollector> {
+        if weights.len() != self.filters_aggregations.len() {
+            return Err(tantivy::TantivyError::InternalError(
+                "the weights of the filters aggregations must be built before collecting"
+                    .to_string(),
+            ));
+        }
+        let aggregation_collector = AggregationSegmentCollector::from_agg_req_and_reader(
+            &self.aggregations,
+            segment_reader,
+            segment_ord,
+            aggregation_limits,
+        )?;
+        let filters_collectors = self
+            .filters_aggregations
+            .iter()
+            .zip(weights)
+            .map(|(filters_aggregation, filters_weights)| {
+                FiltersSegmentCollector::new(
+                    filters_aggregation,
+                    filters_weights,
+                    segment_reader,
+                    segment_ord,
+                    aggregation_limits,
+                )
+            })
+            .collect::<tantivy::Result<_>>()?;
+        let significant_terms_collectors = self
+            .significant_terms_aggregations
+            .iter()
+            .map(|significant_terms_aggregation| {
+                significant_terms_aggregation.segment_collector(segment_reader)
+            })
+            .collect::<tantivy::Result<_>>()?;
+        let geo_grid_collectors = self
+            .geo_grid_aggregations
+            .iter()
+            .map(|geo_grid_aggregation| geo_grid_aggregation.segment_collector(segment_reader))
+            .collect::<tantivy::Result<_>>()?;
+        let ip_range_collectors = self
+            .ip_range_aggregations
+            .iter()
+            .map(|ip_range_aggregation| {
+                ip_range_aggregation.segment_collector(
+                    segment_reader,
+                    segment_ord,
+                    aggregation_limits,
+                )
+            })
+            .collect::<tantivy::Result<_>>()?;
+        Ok(ExtendedAggregationsSegmentCollector {
+            aggregation_collector,
+            filters_collectors,
+            significant_terms_collectors,
+            geo_grid_collectors,
+            ip_range_collectors,
+        })
+    }
+}
+
+pub(crate) struct ExtendedAggregationsSegmentCollector {
+    aggregation_collector: AggregationSegmentCollector,
+    filters_collectors: Vec<FiltersSegmentCollector>,
+    significant_terms_collectors: Vec<SignificantTermsSegmentCollector>,
+    geo_grid_collectors: Vec<GeoGridSegmentCollector>,
+    ip_range_collectors: Vec<IpRangeSegmentCollector>,
+}
+
+impl ExtendedAggregationsSegmentCollector {
+    pub fn collect(&mut self, doc: DocId) {
+        self.collect_block(&[doc]);
+    }
+
+    pub fn collect_block(&mut self, docs: &[DocId]) {
+        self.aggregation_collector.collect_block(docs);
+
+        for filters_collector in &mut self.filters_collectors {
+            filters_collector.collect_block(docs);
+        }
+        for significant_terms_collector in &mut self.significant_terms_collectors {
+            significant_terms_collector.collect_block(docs);
+        }
+        for geo_grid_collector in &mut self.geo_grid_collectors {
+            geo_grid_collector.collect_block(docs);
+        }
+        for ip_range_collector in &mut self.ip_range_collectors {
+            ip_range_collector.collect_block(docs);
+        }
+    }
+
+    pub fn harvest(self) -> tantivy::Result<IntermediateExtendedAggregationResults> {
+        let aggregations = self.aggregation_collector.harvest()?;
+        let filters_aggregations = self
+            .filters_collectors
+            .into_iter()
+            .map(FiltersSegmentCollector::harvest)
+            .collect::<tantivy::Result<_>>()?;
+        let significant_terms_aggregations = self
+            .significant_terms_collectors
+            .into_iter()
+            .map(SignificantTermsSegmentCollector::harvest)
+            .collect::<tantivy::Result<_>>()?;
+        let geo_grid_aggregations = self
+            .geo_grid_collectors
+            .into_iter()
+            .map(GeoGridSegmentCollector::harvest)
+            .collect::<tantivy::Result<_>>()?;
+        let ip_range_aggregations = self
+            .ip_range_collectors
+            .into_iter()
+            .map(IpRangeSegmentCollector::harvest)
+            .collect::<tantivy::Result<_>>()?;
+        Ok(IntermediateExtendedAggregationResults {
+            aggregations,
+            filters_aggregations,
+            significant_terms_aggregations,
+            geo_grid_aggregations,
+            ip_range_aggregations,
+        })
+    }
+}
+
+/// Intermediate results of tantivy aggregations along with aggregations implemented by Quickwit.
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct IntermediateExtendedAggregationResults {
+    aggregations: IntermediateAggregationResults,
+    filters_aggregations: Vec<IntermediateFiltersAggregationResult>,
+    significant_terms_aggregations: Vec<IntermediateSignificantTermsResult>,
+    geo_grid_aggregations: Vec<IntermediateGeoGridResult>,
+    ip_range_aggregations: Vec<IntermediateIpRangeResult>,
+}
+
+impl IntermediateExtendedAggregationResults {
+    pub fn merge_fruits(
+        &mut self,
+        other: IntermediateExtendedAggregationResults,
+    ) -> tantivy::Result<()> {
+        self.aggregations.merge_fruits(other.aggregations)?;
+
+        if self.filters_aggregations.is_empty() {
+            self.filters_aggregations = other.filters_aggregations;
+        } else {
+            for (filters_aggregation, other_filters_aggregation) in self
+                .filters_aggregations
+                .iter_mut()
+                .zip(other.filters_aggregations)
+            {
+                filters_aggregation.merge_fruits(other_filters_aggregation)?;
+            }
+        }
+        if self.significant_terms_aggregations.is_empty() {
+            self.significant_terms_aggregations = other.significant_terms_aggregations;
+        } else {
+            for (significant_terms_aggregation, other_significant_terms_aggregation) in self
+                .significant_terms_aggregations
+                .iter_mut()
+                .zip(other.significant_terms_aggregations)
+            {
+                significant_terms_aggregation.merge_fruits(other_significant_terms_aggregation);
+            }
+        }
+        if self.geo_grid_aggregations.is_empty() {
+            self.geo_grid_aggregations = other.geo_grid_aggregations;
+        } else {
+            for (geo_grid_aggregation, other_geo_grid_aggregation) in self
+                .geo_grid_aggregations
+                .iter_mut()
+                .zip(other.geo_grid_aggregations)
+            {
+                geo_grid_aggregation.merge_fruits(other_geo_grid_aggregation);
+            }
+        }
+        if self.ip_range_aggregations.is_empty() {
+            self.ip_range_aggregations = other.ip_range_aggregations;
+        } else {
+            for (ip_range_aggregation, other_ip_range_aggregation) in self
+                .ip_range_aggregations
+                .iter_mut()
+                .zip(other.ip_range_aggregations)
+            {
+                ip_range_aggregation.merge_fruits(other_ip_range_aggregation)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts the intermediate results into the final JSON aggregation results.
+    pub fn into_final_result(
+        mut self,
+        aggregations: ExtendedAggregations,
+        aggregation_limits: AggregationLimitsGuard,
+    ) -> crate::Result<JsonValue> {
+        let aggregation_results = self
+            .aggregations
+            .into_final_result(aggregations.aggregations, aggregation_limits.clone())?;
+        let mut aggregation_results_json = serde_json::to_value(aggregation_results)?;
+
+        let Some(aggregation_results_obj) = aggregation_results_json.as_object_mut() else {
+            return Ok(aggregation_results_json);
+        };
+        // No split was searched: we still return the (empty) results.
+        self.filters_aggregations
+            .resize_with(aggregations.filters_aggregations.len(), Default::default);
+        self.significant_terms_aggregations.resize_with(
+            aggregations.significant_terms_aggregations.len(),
+            Default::default,
+        );
+        self.geo_grid_aggregations
+            .resize_with(aggregations.geo_grid_aggregations.len(), Default::default);
+        self.ip_range_aggregations
+            .resize_with(aggregations.ip_range_aggregations.len(), Default::default);
+
+        for (filters_aggregation, intermediate_result) in aggregations
+            .filters_aggregations
+            .into_iter()
+            .zip(self.filters_aggregations)
+        {
+            let name = filters_aggregation.name().to_string();
+            let filters_aggregation_json =
+                intermediate_result.into_final_result(filters_aggregation, &aggregation_limits)?;
+            aggregation_results_obj.insert(name, filters_aggregation_json);
+        }
+        for (significant_terms_aggregation, intermediate_result) in aggregations
+            .significant_terms_aggregations
+            .iter()
+            .zip(self.significant_terms_aggregations)
+        {
+            let significant_terms_aggregation_json =
+                intermediate_result.into_final_result(significant_terms_aggregation);
+            aggregation_results_obj.insert(
+                significant_terms_aggregation.name().to_string(),
+                significant_terms_aggregation_json,
+            );
+        }
+        for (geo_grid_aggregation, intermediate_result) in aggregations
+            .geo_grid_aggregations
+            .iter()
+            .zip(self.geo_grid_aggregations)
+        {
+            let geo_grid_aggregation_json =
+                intermediate_result.into_final_result(geo_grid_aggregation);
+            aggregation_results_obj.insert(
+                geo_grid_aggregation.name().to_string(),
+                geo_grid_aggregation_json,
+            );
+        }
+        for (ip_range_aggregation, intermediate_result) in aggregations
+            .ip_range_aggregations
+            .iter()
+            .zip(self.ip_range_aggregations)
+        {
+            let ip_range_aggregation_json =
+                intermediate_result.into_final_result(ip_range_aggregation, &aggregation_limits)?;
+            aggregation_results_obj.insert(
+                ip_range_aggregation.name().to_string(),
+                ip_range_aggregation_json,
+            );
+        }
+        Ok(aggregation_results_json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::collector::QuickwitAggregations;
+
+    #[test]
+    fn test_parse_extended_aggregations() {
+        let aggregations_json = json!({
+            "statuses": {
+                "filters": {
+                    "filters": {"errors": "status:5xx"}
+                },
+                "aggs": {
+                    "avg_latency": {"avg": {"field": "latency"}}
+                }
+            },
+            "codes": {
+                "significant_terms": {"field": "code"}
+            },
+            "tiles": {
+                "geotile_grid": {"field": "location"}
+            },
+            "networks": {
+                "ip_range": {"field": "host", "ranges": [{"mask": "10.0.0.0/8"}]},
+                "aggs": {
+                    "max_status": {"max": {"field": "status"}}
+                }
+            },
+            "max_latency": {"max": {"field": "latency"}}
+        });
+        assert!(has_extended_aggregation(&aggregations_json.to_string()));
+
+        let aggregations: QuickwitAggregations = serde_json::from_value(aggregations_json).unwrap();
+        let QuickwitAggregations::ExtendedAggregations(aggregations) = aggregations else {
+            panic!("expected extended aggregations, got {aggregations:?}");
+        };
+        assert_eq!(aggregations.aggregations.len(), 1);
+        assert!(aggregations.aggregations.contains_key("max_latency"));
+        assert_eq!(aggregations.filters_aggregations.len(), 1);
+        assert_eq!(aggregations.filters_aggregations[0].name(), "statuses");
+        assert_eq!(aggregations.significant_terms_aggregations.len(), 1);
+        assert_eq!(
+            aggregations.significant_terms_aggregations[0].name(),
+            "codes"
+        );
+        assert_eq!(aggregations.geo_grid_aggregations.len(), 1);
+        assert_eq!(aggregations.geo_grid_aggregations[0].name(), "tiles");
+        assert_eq!(aggregations.ip_range_aggregations.len(), 1);
+        assert_eq!(aggregations.ip_range_aggregations[0].name(), "networks");
+
+        let fast_field_names = aggregations.fast_field_names();
+        assert_eq!(fast_field_names.len(), 6);
+        assert!(fast_field_names.contains("latency"));
+        assert!(fast_field_names.contains("code"));
+        assert!(fast_field_names.contains("location.lat"));
+        assert!(fast_field_names.contains("location.lon"));
+        assert!(fast_field_names.contains("host"));
+        assert!(fast_field_names.contains("status"));
+    }
+
+    #[test]
+    fn test_parse_tantivy_aggregations() {
+        let aggregations_json = json!({
+            "max_latency": {"max": {"field": "latency"}}
+        });
+        assert!(!has_extended_aggregation(&aggregations_json.to_string()));
+
+        let aggregations: QuickwitAggregations = serde_json::from_value(aggregations_json).unwrap();
+        assert!(matches!(
+            aggregations,
+            QuickwitAggregations::TantivyAggregations(_)
+        ));
+    }
+
+    #[test]
+    fn test_parse_invalid_extended_aggregations() {
+        // Aggregations implemented by Quickwit are only supported at the top level.
+        let aggregations_json = json!({
+            "statuses": {
+                "filters": {
+                    "filters": {"errors": "status:5xx"},
+                },
+                "aggs": {
+                    "nested_statuses": {
+                        "filters": {
+                            "filters": {"errors": "status:5xx"},
+                        }
+                    }
+                }
+            }
+        });
+        serde_json::from_value::<ExtendedAggregations>(aggregations_json).unwrap_err();
+
+        let aggregations_json = json!({
+            "per_host": {
+                "terms": {"field": "host"},
+                "aggs": {
+                    "codes": {
+                        "significant_terms": {"field": "code"}
+                    }
+                }
+            }
+        });
+        assert!(!has_extended_aggregation(&aggregations_json.to_string()));
+        serde_json::from_value::<QuickwitAggregations>(aggregations_json).unwrap_err();
+    }
+
+    #[test]
+    fn test_extended_aggregations_final_result_without_splits() {
+        let aggregations: ExtendedAggregations = serde_json::from_value(json!({
+            "statuses": {
+                "filters": {
+                    "filters": {"errors": {"type": "term", "field": "status", "value": "5xx"}}
+                }
+            },
+            "codes": {
+                "significant_terms": {"field": "code"}
+            },
+            "hashes": {
+                "geohash_grid": {"field": "location"}
+            }
+        }))
+        .unwrap();
+        let final_result = IntermediateExtendedAggregationResults::default()
+            .into_final_result(aggregations, AggregationLimitsGuard::new(None, None))
+            .unwrap();
+        assert_eq!(
+            final_result,
+            json!({
+                "statuses": {
+                    "buckets": {
+                        "errors": {"doc_count": 0}
+                    }
+                },
+                "codes": {
+                    "doc_count": 0,
+                    "bg_count": 0,
+                    "buckets": []
+                },
+                "hashes": {
+                    "buckets": []
+                }
+            })
+        );
+    }
+}
@@ -18,28 +18,54 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
 use std::sync::Arc;
 
 use anyhow::{Context, Ok};
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
 use futures::{StreamExt, TryStreamExt};
 use itertools::Itertools;
-use quickwit_doc_mapper::DocMapper;
+use quickwit_doc_mapper::{DocMapper, WarmupInfo};
 use quickwit_proto::search::{
-    FetchDocsResponse, PartialHit, SnippetRequest, SplitIdAndFooterOffsets,
+    FetchDocsResponse, NamedQuery, PartialHit, SnippetRequest, SplitIdAndFooterOffsets,
 };
+use quickwit_query::query_ast::QueryAst;
 use quickwit_storage::Storage;
-use tantivy::query::Query;
+use serde_json::Value as JsonValue;
+use tantivy::columnar::DynamicColumn;
+use tantivy::query::{EnableScoring, Query};
 use tantivy::schema::document::CompactDocValue;
-use tantivy::schema::{Document as DocumentTrait, Field, TantivyDocument, Value};
+use tantivy::schema::{
+    Document as DocumentTrait, Field, FieldEntry, FieldType, TantivyDocument, Value,
+};
 use tantivy::snippet::SnippetGenerator;
-use tantivy::{ReloadPolicy, Score, Searcher, Term};
+use tantivy::time::format_description::well_known::Rfc3339;
+use tantivy::{DocAddress, DocId, DocSet, ReloadPolicy, Score, Searcher, Term};
 use tracing::{error, Instrument};
 
-use crate::leaf::open_index_with_caches;
+use crate::leaf::{open_index_with_caches, warmup};
 use crate::service::SearcherContext;
 use crate::{convert_document_to_json_string, GlobalDocAddress};
 
 const SNIPPET_MAX_NUM_CHARS: usize = 150;
+const SNIPPET_DEFAULT_PRE_TAG: &str = "<b>";
+const SNIPPET_DEFAULT_POST_TAG: &str = "</b>";
+
+/// What is retrieved for each fetched document, besides its content.
+#[derive(Clone, Copy, Default)]
+pub struct FetchDocsOptions<'a> {
+    /// Snippets of the fields of the documents.
+    pub snippet_request_opt: Option<&'a SnippetRequest>,
+    /// Named queries the documents are matched against.
+    pub named_queries: &'a [NamedQuery],
+    /// Query, resolved, whose score of the documents is explained.
+    pub explain_query_ast_opt: Option<&'a str>,
+    /// Fast fields whose values are read from the columnar store.
+    pub docvalue_fields: &'a [String],
+    /// Whether to skip reading the documents from the doc store.
+    pub skip_source: bool,
+}
 
 /// Given a list of global doc address, fetches all the documents and
 /// returns them as a hashmap.
@@ -49,7 +75,7 @@ async fn fetch_docs_to_map(
     index_storage: Arc<dyn Storage>,
     splits: &[SplitIdAndFooterOffsets],
     doc_mapper: Arc<dyn DocMapper>,
-    snippet_request_opt: Option<&SnippetRequest>,
+    fetch_docs_options: FetchDocsOptions<'_>,
 ) -> anyhow::Result<HashMap<GlobalDocAddress, Document>> {
     let mut split_fetch_docs_futures = Vec::new();
 
@@ -76,7 +102,7 @@ async fn fetch_docs_to_map(
             index_storage.clone(),
             split_and_offset,
             doc_mapper.clone(),
-            snippet_request_opt,
+            fetch_docs_options,
         ));
     }
 
@@ -116,7 +142,7 @@ pub async fn fetch_docs(
     index_storage: Arc<dyn Storage>,
     splits: &[SplitIdAndFooterOffsets],
     doc_mapper: Arc<dyn DocMapper>,
-    snippet_request_opt: Option<&SnippetRequest>,
+    fetch_docs_options: FetchDocsOptions<'_>,
 ) -> anyhow::Result<FetchDocsResponse> {
     let global_doc_addrs: Vec<GlobalDocAddress> = partial_hits
         .iter()
@@ -129,7 +155,7 @@ pub async fn fetch_docs(
         index_storage,
         splits,
         doc_mapper,
-        snippet_request_opt,
+        fetch_docs_options,
     )
     .await?;
 
@@ -143,6 +169,9 @@ pub async fn fetch_docs(
                     leaf_json: document.content_json,
                     partial_hit: Some(partial_hit.clone()),
                     leaf_snippet_json: document.snippet_json,
+                    matched_queries: document.matched_queries,
+                    explanation_json: document.explanation_json,
+                    leaf_docvalue_fields_json: document.docvalue_fields_json,
                 })
             } else {
                 None
@@ -160,6 +189,9 @@ const NUM_CONCURRENT_REQUESTS: usize = 30;
 struct Document {
     content_json: String,
     snippet_json: Option<String>,
+    matched_queries: Vec<String>,
+    explanation_json: Option<String>,
+    docvalue_fields_json: Option<String>,
 }
 
 /// Fetching docs from a specific split.
@@ -169,17 +201,26 @@ async fn fetch_docs_in_split(
     index_storage: Arc<dyn Storage>,
     split: &SplitIdAndFooterOffsets,
     doc_mapper: Arc<dyn DocMapper>,
-    snippet_request_opt: Option<&SnippetRequest>,
+    fetch_docs_options: FetchDocsOptions<'_>,
 ) -> anyhow::Result<Vec<(GlobalDocAddress, Document)>> {
+    let FetchDocsOptions {
+        snippet_request_opt,
+        named_queries,
+        explain_query_ast_opt,
+        docvalue_fields,
+        skip_source,
+    } = fetch_docs_options;
     global_doc_addrs.sort_by_key(|doc| doc.doc_addr);
     // Opens the index without the ephemeral unbounded cache, this cache is indeed not useful
-    // when fetching docs as we will fetch them only once.
+    // when fetching docs as we will fetch them only once. It is however required to warm up the
+    // named queries the docs are matched against, the query whose score is explained, and the
+    // docvalue fields.
     let mut index = open_index_with_caches(
         &searcher_context,
         index_storage,
         split,
         Some(doc_mapper.tokenizer_manager()),
-        false,
+        !named_queries.is_empty() || explain_query_ast_opt.is_some() || !docvalue_fields.is_empty(),
     )
     .await
     .context("open-index-for-split")?;
@@ -201,26 +242,60 @@ async fn fetch_docs_in_split(
     } else {
         None
     };
+    let doc_addrs: Vec<DocAddress> = global_doc_addrs
+        .iter()
+        .map(|global_doc_addr| global_doc_addr.doc_addr)
+        .collect();
+    let mut matched_queries_per_doc =
+        match_named_queries(&searcher, &*doc_mapper, named_queries, &doc_addrs).await?;
+    let mut explanation_per_doc =
+        explain_docs(&searcher, &*doc_mapper, explain_query_ast_opt, &doc_addrs).await?;
+    let mut docvalue_fields_per_doc =
+        read_docvalue_fields(&searcher, docvalue_fields, &doc_addrs).await?;
 
     let doc_futures = global_doc_addrs.into_iter().map(|global_doc_addr| {
         let moved_searcher = searcher.clone();
         let moved_doc_mapper = doc_mapper.clone();
         let fields_snippet_generator_opt_clone = fields_snippet_generator_opt.clone();
+        let matched_queries = matched_queries_per_doc
+            .remove(&global_doc_addr.doc_addr)
+            .unwrap_or_default();
+        let explanation_json = explanation_per_doc.remove(&global_doc_addr.doc_addr);
+        let docvalue_fields_json = docvalue_fields_per_doc.remove(&global_doc_addr.doc_addr);
         async move {
+            // The doc store is only read if the content of the document or snippets are needed.
+            if skip_source && fields_snippet_generator_opt_clone.is_none() {
+                return Ok((
+                    global_doc_addr,
+                    Document {
+                        content_json: "{}".to_string(),
+                        snippet_json: None,
+                        matched_queries,
+                        explanation_json,
+                        docvalue_fields_json,
+                    },
+                ));
+            }
             let doc: TantivyDocument = moved_searcher
                 .doc_async(global_doc_addr.doc_addr)
                 .await
                 .context("searcher-doc-async")?;
 
-            let named_field_doc = doc.to_named_doc(moved_searcher.schema());
-            let content_json =
-                convert_document_to_json_string(named_field_doc, &*moved_doc_mapper)?;
+            let content_json = if skip_source {
+                "{}".to_string()
+            } else {
+                let named_field_doc = doc.to_named_doc(moved_searcher.schema());
+                convert_document_to_json_string(named_field_doc, &*moved_doc_mapper)?
+            };
             if fields_snippet_generator_opt_clone.is_none() {
                 return Ok((
                     global_doc_addr,
                     Document {
                         content_json,
                         snippet_json: None,
+                        matched_queries,
+                        explanation_json,
+                        docvalue_fields_json,
                     },
                 ));
             }
@@ -232,6 +307,9 @@ async fn fetch_docs_in_split(
                     Document {
                         content_json,
                         snippet_json: None,
+                        matched_queries,
+                        explanation_json,
+                        docvalue_fields_json,
                     },
                 ));
             }
@@ -251,6 +329,9 @@ async fn fetch_docs_in_split(
                 Document {
                     content_json,
                     snippet_json: Some(snippet_json),
+                    matched_queries,
+                    explanation_json,
+                    docvalue_fields_json,
                 },
             ))
         }
@@ -263,11 +344,200 @@ async fn fetch_docs_in_split(
         .await
 }
 
+/// Matches the documents against the named queries and returns the names of the queries matching
+/// each document. The document addresses must be sorted.
+async fn match_named_queries(
+    searcher: &Searcher,
+    doc_mapper: &dyn DocMapper,
+    named_queries: &[NamedQuery],
+    doc_addrs: &[DocAddress],
+) -> anyhow::Result<HashMap<DocAddress, Vec<String>>> {
+    let mut matched_queries_per_doc: HashMap<DocAddress, Vec<String>> = HashMap::new();
+
+    if named_queries.is_empty() || doc_addrs.is_empty() {
+        return Ok(matched_queries_per_doc);
+    }
+    let mut queries: Vec<(&str, Box<dyn Query>)> = Vec::with_capacity(named_queries.len());
+    let mut warmup_info = WarmupInfo::default();
+
+    for named_query in named_queries {
+        let query_ast: QueryAst = serde_json::from_str(&named_query.query_ast)
+            .context("failed to deserialize QueryAst")?;
+        let (query, query_warmup_info) =
+            doc_mapper.query(searcher.schema().clone(), &query_ast, false)?;
+        warmup_info.merge(query_warmup_info);
+        queries.push((named_query.name.as_str(), query));
+    }
+    warmup(searcher, &warmup_info).await?;
+
+    for (name, query) in queries {
+        let weight = query.weight(EnableScoring::disabled_from_searcher(searcher))?;
+
+        for (segment_ord, segment_doc_addrs) in
+            &doc_addrs.iter().chunk_by(|doc_addr| doc_addr.segment_ord)
+        {
+            let segment_reader = searcher.segment_reader(segment_ord);
+            let mut scorer = weight.scorer(segment_reader, 1.0)?;
+
+            for doc_addr in segment_doc_addrs {
+                if scorer.doc() < doc_addr.doc_id {
+                    scorer.seek(doc_addr.doc_id);
+                }
+                if scorer.doc() != doc_addr.doc_id {
+                    continue;
+                }
+                let matched_queries = matched_queries_per_doc.entry(*doc_addr).or_default();
+
+                // The same name may be given to several queries.
+                if !matched_queries
+                    .iter()
+                    .any(|matched_query| matched_query == name)
+                {
+                    matched_queries.push(name.to_string());
+                }
+            }
+        }
+    }
+    Ok(matched_queries_per_doc)
+}
+
+/// Explains the score of the documents for the query and returns the explanations serialized as
+/// JSON.
+async fn explain_docs(
+    searcher: &Searcher,
+    doc_mapper: &dyn DocMapper,
+    explain_query_ast_opt: Option<&str>,
+    doc_addrs: &[DocAddress],
+) -> anyhow::Result<HashMap<DocAddress, String>> {
+    let mut explanation_per_doc: HashMap<DocAddress, String> = HashMap::new();
+
+    let Some(explain_query_ast) = explain_query_ast_opt else {
+        return Ok(explanation_per_doc);
+    };
+    if doc_addrs.is_empty() {
+        return Ok(explanation_per_doc);
+    }
+    let query_ast: QueryAst =
+        serde_json::from_str(explain_query_ast).context("failed to deserialize QueryAst")?;
+    let (query, mut warmup_info) =
+        doc_mapper.query(searcher.schema().clone(), &query_ast, false)?;
+    // The BM25 score depends on the field norms of the documents.
+    warmup_info.field_norms = true;
+    warmup(searcher, &warmup_info).await?;
+
+    let weight = query.weight(EnableScoring::enabled_from_searcher(searcher))?;
+
+    for doc_addr in doc_addrs {
+        let segment_reader = searcher.segment_reader(doc_addr.segment_ord);
+        let explanation = weight.explain(segment_reader, doc_addr.doc_id)?;
+        explanation_per_doc.insert(*doc_addr, serde_json::to_string(&explanation)?);
+    }
+    Ok(explanation_per_doc)
+}
+
+/// Reads the values of the docvalue fields of the documents from the fast fields, and returns them
+/// serialized as JSON objects mapping each field to the array of its values. The document
+/// addresses must be sorted.
+async fn read_docvalue_fields(
+    searcher: &Searcher,
+    docvalue_fields: &[String],
+    doc_addrs: &[DocAddress],
+) -> anyhow::Result<HashMap<DocAddress, String>> {
+    let mut docvalue_fields_per_doc: HashMap<DocAddress, String> = HashMap::new();
+
+    if docvalue_fields.is_empty() || doc_addrs.is_empty() {
+        return Ok(docvalue_fields_per_doc);
+    }
+    let warmup_info = WarmupInfo {
+        fast_field_names: docvalue_fields.iter().cloned().collect(),
+        ..Default::default()
+    };
+    warmup(searcher, &warmup_info).await?;
+
+    for (segment_ord, segment_doc_addrs) in
+        &doc_addrs.iter().chunk_by(|doc_addr| doc_addr.segment_ord)
+    {
+        let fast_fields = searcher.segment_reader(segment_ord).fast_fields();
+        let mut columns: Vec<(&str, DynamicColumn)> = Vec::new();
+
+        for docvalue_field in docvalue_fields {
+            // A field of a JSON object may be stored in several columns of different types.
+            for dynamic_column_handle in fast_fields.dynamic_column_handles(docvalue_field)? {
+                columns.push((docvalue_field.as_str(), dynamic_column_handle.open()?));
+            }
+        }
+        for doc_addr in segment_doc_addrs {
+            let mut values_per_field: BTreeMap<&str, Vec<JsonValue>> = BTreeMap::new();
+
+            for (field_name, column) in &columns {
+                let values = column_values_for_doc(column, doc_addr.doc_id)?;
+
+                if !values.is_empty() {
+                    values_per_field
+                        .entry(*field_name)
+                        .or_default()
+                        .extend(values);
+                }
+            }
+            docvalue_fields_per_doc.insert(*doc_addr, serde_json::to_string(&values_per_field)?);
+        }
+    }
+    Ok(docvalue_fields_per_doc)
+}
+
+/// Returns the values of a document in a fast field column, as JSON values. Dates are formatted in
+/// RFC 3339, IP addresses in their textual representation, and bytes in base64.
+fn column_values_for_doc(column: &DynamicColumn, doc_id: DocId) -> anyhow::Result<Vec<JsonValue>> {
+    let values = match column {
+        DynamicColumn::Bool(column) => column.values_for_doc(doc_id).map(JsonValue::from).collect(),
+        DynamicColumn::I64(column) => column.values_for_doc(doc_id).map(JsonValue::from).collect(),
+        DynamicColumn::U64(column) => column.values_for_doc(doc_id).map(JsonValue::from).collect(),
+        DynamicColumn::F64(column) => column.values_for_doc(doc_id).map(JsonValue::from).collect(),
+        DynamicColumn::DateTime(column) => column
+            .values_for_doc(doc_id)
+            .map(|date_time| Ok(JsonValue::String(date_time.into_utc().format(&Rfc3339)?)))
+            .collect::<anyhow::Result<_>>()?,
+        DynamicColumn::IpAddr(column) => column
+            .values_for_doc(doc_id)
+            .map(|ip_addr| {
+                let ip_addr = ip_addr
+                    .to_ipv4_mapped()
+                    .map(IpAddr::V4)
+                    .unwrap_or(IpAddr::V6(ip_addr));
+                JsonValue::String(ip_addr.to_string())
+            })
+            .collect(),
+        DynamicColumn::Str(str_column) => {
+            let mut values = Vec::new();
+            let mut value = String::new();
+            for term_ord in str_column.term_ords(doc_id) {
+                value.clear();
+                str_column.ord_to_str(term_ord, &mut value)?;
+                values.push(JsonValue::String(value.clone()));
+            }
+            values
+        }
+        DynamicColumn::Bytes(bytes_column) => {
+            let mut values = Vec::new();
+            let mut value = Vec::new();
+            for term_ord in bytes_column.term_ords(doc_id) {
+                value.clear();
+                bytes_column.ord_to_bytes(term_ord, &mut value)?;
+                values.push(JsonValue::String(BASE64_STANDARD.encode(&value)));
+            }
+            values
+        }
+    };
+    Ok(values)
+}
+
 // A struct to hold the snippet generators associated to
 // the snippet fields from a search request.
 #[derive(Clone)]
 struct FieldsSnippetGenerator {
     field_generators: Arc<HashMap<String, SnippetGenerator>>,
+    pre_tag: Arc<str>,
+    post_tag: Arc<str>,
 }
 
 impl FieldsSnippetGenerator {
@@ -282,11 +552,12 @@ impl FieldsSnippetGenerator {
                 .into_iter()
                 .filter_map(|value| {
                     value.as_str().and_then(|text| {
-                        let snippet = snippet_generator.snippet(text);
-                        match snippet.is_empty() {
-                            false => Some(snippet.to_html()),
-                            _ => None,
+                        let mut snippet = snippet_generator.snippet(text);
+                        if snippet.is_empty() {
+                            return None;
                         }
+                        snippet.set_snippet_prefix_postfix(&self.pre_tag, &self.post_tag);
+                        Some(snippet.to_html())
                     })
                 })
                 .collect();
@@ -311,23 +582,85 @@ async fn create_fields_snippet_generator(
     let query_ast_resolved = serde_json::from_str(&snippet_request.query_ast_resolved)
         .context("failed to deserialize QueryAst")?;
     let (query, _) = doc_mapper.query(schema.clone(), &query_ast_resolved, false)?;
-    let mut snippet_generators = HashMap::new();
+    let snippet_options = snippet_request.snippet_options.clone().unwrap_or_default();
+    let max_num_chars = snippet_options
+        .fragment_size
+        .map(|fragment_size| fragment_size as usize)
+        .unwrap_or(SNIPPET_MAX_NUM_CHARS);
+
+    let mut snippet_fields: Vec<(String, Field)> = Vec::new();
     for field_name in &snippet_request.snippet_fields {
-        let field = schema.get_field(field_name)?;
-        let snippet_generator = create_snippet_generator(searcher, &query, field).await?;
-        snippet_generators.insert(field_name.clone(), snippet_generator);
+        if field_name.contains('*') {
+            for (field, field_entry) in schema.fields() {
+                if is_snippet_field(field_entry)
+                    && field_name_matches_pattern(field_entry.name(), field_name)
+                {
+                    snippet_fields.push((field_entry.name().to_string(), field));
+                }
+            }
+        } else {
+            let field = schema.get_field(field_name)?;
+            snippet_fields.push((field_name.clone(), field));
+        }
+    }
+    let mut snippet_generators = HashMap::new();
+    for (field_name, field) in snippet_fields {
+        if snippet_generators.contains_key(&field_name) {
+            continue;
+        }
+        let snippet_generator =
+            create_snippet_generator(searcher, &query, field, max_num_chars).await?;
+        snippet_generators.insert(field_name, snippet_generator);
     }
 
     Ok(FieldsSnippetGenerator {
         field_generators: Arc::new(snippet_generators),
+        pre_tag: snippet_options
+            .pre_tag
+            .as_deref()
+            .unwrap_or(SNIPPET_DEFAULT_PRE_TAG)
+            .into(),
+        post_tag: snippet_options
+            .post_tag
+            .as_deref()
+            .unwrap_or(SNIPPET_DEFAULT_POST_TAG)
+            .into(),
     })
 }
 
+// Returns true if snippets can be extracted from the field, i.e. if it is a stored text field.
+fn is_snippet_field(field_entry: &FieldEntry) -> bool {
+    matches!(field_entry.field_type(), FieldType::Str(text_options) if text_options.is_stored())
+}
+
+// Returns true if the field name matches the pattern, in which `*` matches any sequence of
+// characters.
+fn field_name_matches_pattern(field_name: &str, pattern: &str) -> bool {
+    let mut pattern_parts = pattern.split('*');
+    let first_part = pattern_parts.next().unwrap_or_default();
+    let Some(mut remaining) = field_name.strip_prefix(first_part) else {
+        return false;
+    };
+    let mut pattern_parts: Vec<&str> = pattern_parts.collect();
+    let Some(last_part) = pattern_parts.pop() else {
+        // The pattern does not contain any wildcard.
+        return remaining.is_empty();
+    };
+    for pattern_part in pattern_parts {
+        let Some(pos) = remaining.find(pattern_part) else {
+            return false;
+        };
+        remaining = &remaining[pos + pattern_part.len()..];
+    }
+    remaining.ends_with(last_part)
+}
+
 // Creates a snippet generator associated to a field.
 async fn create_snippet_generator(
     searcher: &Searcher,
     query: &dyn Query,
     field: Field,
+    max_num_chars: usize,
 ) -> anyhow::Result<SnippetGenerator> {
     let mut terms: Vec<&Term> = Vec::new();
     // TODO ok with termset?
@@ -353,6 +686,6 @@ async fn create_snippet_generator(
         terms_text,
         tokenizer,
         field,
-        SNIPPET_MAX_NUM_CHARS,
+        max_num_chars,
     ))
 }
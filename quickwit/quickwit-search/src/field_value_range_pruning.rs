@@ -0,0 +1,260 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+use std::net::Ipv6Addr;
+use std::ops::Bound;
+
+use quickwit_metastore::{FieldValueRange, SplitMetadata};
+use quickwit_query::query_ast::{BoolQuery, QueryAst, RangeQuery};
+use quickwit_query::{InterpretUserInput, JsonLiteral};
+
+/// Removes the splits that cannot match the query according to the min and max values of their
+/// numeric and IP fast fields.
+pub(crate) fn prune_splits_with_field_value_ranges(
+    query_ast: &QueryAst,
+    split_metadatas: &mut Vec<SplitMetadata>,
+) {
+    split_metadatas.retain(|split_metadata| {
+        split_metadata.field_value_ranges.is_empty()
+            || may_match(query_ast, &split_metadata.field_value_ranges)
+    });
+}
+
+/// Returns false if no document with field values within the given ranges can match the query.
+///
+/// The evaluation is conservative: any query that is not a term or range query over a field with
+/// a known range is assumed to possibly match.
+fn may_match(query_ast: &QueryAst, field_value_ranges: &BTreeMap<String, FieldValueRange>) -> bool {
+    match query_ast {
+        QueryAst::Bool(bool_query) => bool_query_may_match(bool_query, field_value_ranges),
+        QueryAst::DisjunctionMax(disjunction_max_query) => disjunction_max_query
+            .disjuncts
+            .iter()
+            .any(|disjunct| may_match(disjunct, field_value_ranges)),
+        QueryAst::Term(term_query) => {
+            term_may_match(&term_query.field, &term_query.value, field_value_ranges)
+        }
+        QueryAst::TermSet(term_set_query) => {
+            term_set_query.terms_per_field.iter().any(|(field, terms)| {
+                terms
+                    .iter()
+                    .any(|term| term_may_match(field, term, field_value_ranges))
+            })
+        }
+        // On numeric and IP fields, a full-text query is a term query.
+        QueryAst::FullText(full_text_query) => term_may_match(
+            &full_text_query.field,
+            &full_text_query.text,
+            field_value_ranges,
+        ),
+        QueryAst::Range(range_query) => range_may_match(range_query, field_value_ranges),
+        QueryAst::Boost { underlying, .. } => may_match(underlying, field_value_ranges),
+        QueryAst::MatchNone => false,
+        QueryAst::MatchAll
+        | QueryAst::PhrasePrefix(_)
+        | QueryAst::UserInput(_)
+        | QueryAst::FieldPresence(_)
+        | QueryAst::Wildcard(_)
+        | QueryAst::Regex(_) => true,
+    }
+}
+
+fn bool_query_may_match(
+    bool_query: &BoolQuery,
+    field_value_ranges: &BTreeMap<String, FieldValueRange>,
+) -> bool {
+    let mut required_clauses = bool_query
+        .must
+        .iter()
+        .chain(bool_query.filter.iter())
+        .peekable();
+
+    if required_clauses.peek().is_some() {
+        if !required_clauses.all(|clause| may_match(clause, field_value_ranges)) {
+            return false;
+        }
+        if bool_query.minimum_should_match.unwrap_or(0) == 0 {
+            return true;
+        }
+    } else if bool_query.minimum_should_match == Some(0) {
+        return true;
+    }
+    // At least one should clause has to match. The must not clauses can only remove documents.
+    bool_query.should.is_empty()
+        || bool_query
+            .should
+            .iter()
+            .any(|clause| may_match(clause, field_value_ranges))
+}
+
+fn term_may_match(
+    field: &str,
+    value: &str,
+    field_value_ranges: &BTreeMap<String, FieldValueRange>,
+) -> bool {
+    let Some(field_value_range) = field_value_ranges.get(field) else {
+        return true;
+    };
+    let value = JsonLiteral::String(value.to_string());
+    let bound = Bound::Included(value);
+    field_value_range_overlaps(field_value_range, &bound, &bound)
+}
+
+fn range_may_match(
+    range_query: &RangeQuery,
+    field_value_ranges: &BTreeMap<String, FieldValueRange>,
+) -> bool {
+    let Some(field_value_range) = field_value_ranges.get(&range_query.field) else {
+        return true;
+    };
+    field_value_range_overlaps(
+        field_value_range,
+        &range_query.lower_bound,
+        &range_query.upper_bound,
+    )
+}
+
+fn field_value_range_overlaps(
+    field_value_range: &FieldValueRange,
+    lower_bound: &Bound<JsonLiteral>,
+    upper_bound: &Bound<JsonLiteral>,
+) -> bool {
+    match *field_value_range {
+        FieldValueRange::U64 { min, max } => overlaps::<u64>(min, max, lower_bound, upper_bound),
+        FieldValueRange::I64 { min, max } => overlaps::<i64>(min, max, lower_bound, upper_bound),
+        FieldValueRange::F64 { min, max } => overlaps::<f64>(min, max, lower_bound, upper_bound),
+        FieldValueRange::Ip { min, max } => {
+            overlaps::<Ipv6Addr>(min, max, lower_bound, upper_bound)
+        }
+    }
+}
+
+/// Returns true if `[min, max]` and the range given by the bounds overlap, or if one of the
+/// bounds cannot be interpreted as a value of type `T`.
+fn overlaps<'a, T>(
+    min: T,
+    max: T,
+    lower_bound: &'a Bound<JsonLiteral>,
+    upper_bound: &'a Bound<JsonLiteral>,
+) -> bool
+where
+    T: InterpretUserInput<'a> + PartialOrd,
+{
+    let above_lower_bound = match lower_bound {
+        Bound::Included(value) => T::interpret_json(value).map(|value| max >= value),
+        Bound::Excluded(value) => T::interpret_json(value).map(|value| max > value),
+        Bound::Unbounded => Some(true),
+    };
+    let below_upper_bound = match upper_bound {
+        Bound::Included(value) => T::interpret_json(value).map(|value| min <= value),
+        Bound::Excluded(value) => T::interpret_json(value).map(|value| min < value),
+        Bound::Unbounded => Some(true),
+    };
+    above_lower_bound.unwrap_or(true) && below_upper_bound.unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_query::query_ast::qast_helper;
+
+    use super::*;
+
+    fn field_value_ranges() -> BTreeMap<String, FieldValueRange> {
+        BTreeMap::from_iter([
+            (
+                "status_code".to_string(),
+                FieldValueRange::U64 { min: 200, max: 404 },
+            ),
+            (
+                "latency".to_string(),
+                FieldValueRange::F64 {
+                    min: 0.5,
+                    max: 12.5,
+                },
+            ),
+            (
+                "ip".to_string(),
+                FieldValueRange::Ip {
+                    min: "10.0.0.1"
+                        .parse::<std::net::Ipv4Addr>()
+                        .unwrap()
+                        .to_ipv6_mapped(),
+                    max: "10.0.0.9"
+                        .parse::<std::net::Ipv4Addr>()
+                        .unwrap()
+                        .to_ipv6_mapped(),
+                },
+            ),
+        ])
+    }
+
+    fn test_may_match_aux(user_query: &str, expected: bool) {
+        let query_ast = qast_helper(user_query, &["body"]);
+        assert_eq!(
+            may_match(&query_ast, &field_value_ranges()),
+            expected,
+            "{user_query}"
+        );
+    }
+
+    #[test]
+    fn test_may_match() {
+        test_may_match_aux("status_code:>=500", false);
+        test_may_match_aux("status_code:>=404", true);
+        test_may_match_aux("status_code:>404", false);
+        test_may_match_aux("status_code:<200", false);
+        test_may_match_aux("status_code:[300 TO 310]", true);
+        test_may_match_aux("status_code:500", false);
+        test_may_match_aux("status_code:302", true);
+        test_may_match_aux("status_code:IN [500 503]", false);
+        test_may_match_aux("status_code:IN [500 302]", true);
+        test_may_match_aux("latency:>20", false);
+        test_may_match_aux("latency:<=0.5", true);
+        test_may_match_aux("ip:10.0.0.22", false);
+        test_may_match_aux("ip:10.0.0.2", true);
+        test_may_match_aux("status_code:500 AND body:hello", false);
+        test_may_match_aux("status_code:500 OR body:hello", true);
+        test_may_match_aux("status_code:500 OR latency:>20", false);
+        test_may_match_aux("NOT status_code:302", true);
+        test_may_match_aux("-status_code:500", true);
+        test_may_match_aux("unknown_field:>5", true);
+        // Values that cannot be interpreted never prune splits.
+        test_may_match_aux("status_code:>-5", true);
+        test_may_match_aux("status_code:abc", true);
+    }
+
+    #[test]
+    fn test_prune_splits_with_field_value_ranges() {
+        let split_with_ranges = SplitMetadata {
+            split_id: "split-with-ranges".to_string(),
+            field_value_ranges: field_value_ranges(),
+            ..Default::default()
+        };
+        let split_without_ranges = SplitMetadata {
+            split_id: "split-without-ranges".to_string(),
+            ..Default::default()
+        };
+        let mut split_metadatas = vec![split_with_ranges, split_without_ranges];
+        let query_ast = qast_helper("status_code:>=500", &[]);
+        prune_splits_with_field_value_ranges(&query_ast, &mut split_metadatas);
+        assert_eq!(split_metadatas.len(), 1);
+        assert_eq!(split_metadatas[0].split_id, "split-without-ranges");
+    }
+}
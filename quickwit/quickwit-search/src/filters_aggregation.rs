@@ -0,0 +1,547 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! The `filters` aggregation, which computes metrics for several named filter queries in a single
+//! pass over the documents matching the search query.
+//!
+//! The buckets are collected by running the filter queries alongside the tantivy aggregation
+//! collector, and their sub-aggregations are regular tantivy aggregations.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use quickwit_doc_mapper::{DocMapper, WarmupInfo};
+use quickwit_query::query_ast::{query_ast_from_user_text, QueryAst};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use tantivy::aggregation::agg_req::Aggregations;
+use tantivy::aggregation::intermediate_agg_result::IntermediateAggregationResults;
+use tantivy::aggregation::{AggregationLimitsGuard, AggregationSegmentCollector};
+use tantivy::collector::SegmentCollector;
+use tantivy::query::{EnableScoring, Scorer, Weight};
+use tantivy::schema::Schema;
+use tantivy::{DocId, DocSet, SegmentOrdinal, SegmentReader};
+
+use crate::SearchError;
+
+const DEFAULT_OTHER_BUCKET_KEY: &str = "_other_";
+
+/// Query of a filter bucket, expressed either in the query language or as a query AST.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum FilterQuery {
+    UserText(String),
+    QueryAst(QueryAst),
+}
+
+impl FilterQuery {
+    fn to_query_ast(&self) -> QueryAst {
+        match self {
+            FilterQuery::UserText(user_text) => query_ast_from_user_text(user_text, None),
+            FilterQuery::QueryAst(query_ast) => query_ast.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FiltersAggregationParams {
+    filters: BTreeMap<String, FilterQuery>,
+    #[serde(default)]
+    other_bucket: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    other_bucket_key: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FiltersAggregationRequest {
+    filters: FiltersAggregationParams,
+    #[serde(
+        default,
+        alias = "aggregations",
+        skip_serializing_if = "Aggregations::is_empty"
+    )]
+    aggs: Aggregations,
+}
+
+pub(crate) fn is_filters_aggregation(aggregation: &JsonValue) -> bool {
+    aggregation
+        .as_object()
+        .map(|aggregation_obj| aggregation_obj.contains_key("filters"))
+        .unwrap_or(false)
+}
+
+/// A filters aggregation, with one bucket per filter query, and optionally a bucket for the
+/// documents matching none of them.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FiltersAggregation {
+    name: String,
+    /// Filter queries, sorted by bucket key.
+    filters: Vec<(String, QueryAst)>,
+    other_bucket_key_opt: Option<String>,
+    sub_aggregations: Aggregations,
+}
+
+impl FiltersAggregation {
+    pub fn parse(name: String, aggregation_json: JsonValue) -> Result<Self, String> {
+        let request: FiltersAggregationRequest = serde_json::from_value(aggregation_json)
+            .map_err(|error| format!("invalid filters aggregation `{name}`: {error}"))?;
+        Self::new(name, request)
+    }
+
+    fn new(name: String, request: FiltersAggregationRequest) -> Result<Self, String> {
+        let params = request.filters;
+        if params.filters.is_empty() {
+            return Err(format!(
+                "filters aggregation `{name}` must define at least one filter"
+            ));
+        }
+        let other_bucket_key_opt = if params.other_bucket || params.other_bucket_key.is_some() {
+            let other_bucket_key = params
+                .other_bucket_key
+                .unwrap_or_else(|| DEFAULT_OTHER_BUCKET_KEY.to_string());
+            if params.filters.contains_key(&other_bucket_key) {
+                return Err(format!(
+                    "other bucket key `{other_bucket_key}` of filters aggregation `{name}` \
+                     conflicts with a filter key"
+                ));
+            }
+            Some(other_bucket_key)
+        } else {
+            None
+        };
+        let filters = params
+            .filters
+            .into_iter()
+            .map(|(key, filter_query)| {
+                let query_ast = filter_query.to_query_ast();
+                (key, query_ast)
+            })
+            .collect();
+        Ok(Self {
+            name,
+            filters,
+            other_bucket_key_opt,
+            sub_aggregations: request.aggs,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn sub_aggregations(&self) -> &Aggregations {
+        &self.sub_aggregations
+    }
+
+    fn num_buckets(&self) -> usize {
+        self.filters.len() + usize::from(self.other_bucket_key_opt.is_some())
+    }
+
+    /// Builds the weights of the filter queries against the split schema.
+    pub fn build_weights(
+        &self,
+        doc_mapper: &dyn DocMapper,
+        split_schema: &Schema,
+    ) -> crate::Result<(Vec<Arc<dyn Weight>>, WarmupInfo)> {
+        let mut weights: Vec<Arc<dyn Weight>> = Vec::with_capacity(self.filters.len());
+        let mut warmup_info = WarmupInfo::default();
+
+        for (_key, query_ast) in &self.filters {
+            let (query, query_warmup_info) =
+                doc_mapper.query(split_schema.clone(), query_ast, false)?;
+            warmup_info.merge(query_warmup_info);
+            let weight = query.weight(EnableScoring::disabled_from_schema(split_schema))?;
+            weights.push(Arc::from(weight));
+        }
+        Ok((weights, warmup_info))
+    }
+}
+
+/// Resolves the user input queries of the filters aggregations with the default search fields of
+/// the doc mapper, and validates the resolved queries against its schema.
+pub(crate) fn resolve_filters_aggregations(
+    aggregation_request: &str,
+    doc_mapper: &dyn DocMapper,
+) -> crate::Result<String> {
+    let Ok(JsonValue::Object(mut aggregations_json)) =
+        serde_json::from_str::<JsonValue>(aggregation_request)
+    else {
+        return Ok(aggregation_request.to_string());
+    };
+    let mut resolved_any = false;
+
+    for (name, aggregation_json) in aggregations_json.iter_mut() {
+        if !is_filters_aggregation(aggregation_json) {
+            continue;
+        }
+        let mut request: FiltersAggregationRequest =
+            serde_json::from_value(aggregation_json.take()).map_err(|error| {
+                SearchError::InvalidAggregationRequest(format!(
+                    "invalid filters aggregation `{name}`: {error}"
+                ))
+            })?;
+        for filter_query in request.filters.filters.values_mut() {
+            let filter_query_ast_resolved = filter_query
+                .to_query_ast()
+                .parse_user_query(doc_mapper.default_search_fields())
+                .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
+            doc_mapper.query(doc_mapper.schema(), &filter_query_ast_resolved, true)?;
+            *filter_query = FilterQuery::QueryAst(filter_query_ast_resolved);
+        }
+        *aggregation_json = serde_json::to_value(&request)?;
+        resolved_any = true;
+    }
+    if !resolved_any {
+        return Ok(aggregation_request.to_string());
+    }
+    Ok(serde_json::to_string(&aggregations_json)?)
+}
+
+struct FilterBucketSegmentCollector {
+    /// `None` for the other bucket.
+    scorer_opt: Option<Box<dyn Scorer>>,
+    doc_count: u64,
+    sub_aggregation_collector: AggregationSegmentCollector,
+}
+
+pub(crate) struct FiltersSegmentCollector {
+    buckets: Vec<FilterBucketSegmentCollector>,
+    /// Per doc of the current block, whether it matched any filter.
+    matched_any: Vec<bool>,
+    bucket_docs: Vec<DocId>,
+}
+
+impl FiltersSegmentCollector {
+    pub fn new(
+        filters_aggregation: &FiltersAggregation,
+        weights: &[Arc<dyn Weight>],
+        segment_reader: &SegmentReader,
+        segment_ord: SegmentOrdinal,
+        aggregation_limits: &AggregationLimitsGuard,
+    ) -> tantivy::Result<Self> {
+        let mut scorers: Vec<Option<Box<dyn Scorer>>> = weights
+            .iter()
+            .map(|weight| weight.scorer(segment_reader, 1.0).map(Some))
+            .collect::<tantivy::Result<_>>()?;
+        if filters_aggregation.other_bucket_key_opt.is_some() {
+            scorers.push(None);
+        }
+        let buckets = scorers
+            .into_iter()
+            .map(|scorer_opt| {
+                let sub_aggregation_collector =
+                    AggregationSegmentCollector::from_agg_req_and_reader(
+                        &filters_aggregation.sub_aggregations,
+                        segment_reader,
+                        segment_ord,
+                        aggregation_limits,
+                    )?;
+                Ok(FilterBucketSegmentCollector {
+                    scorer_opt,
+                    doc_count: 0,
+                    sub_aggregation_collector,
+                })
+            })
+            .collect::<tantivy::Result<_>>()?;
+        Ok(Self {
+            buckets,
+            matched_any: Vec::new(),
+            bucket_docs: Vec::new(),
+        })
+    }
+
+    /// Collects a block of docs, which must be sorted by doc ID and follow the docs of the
+    /// previous blocks.
+    pub fn collect_block(&mut self, docs: &[DocId]) {
+        self.matched_any.clear();
+        self.matched_any.resize(docs.len(), false);
+
+        for bucket in &mut self.buckets {
+            self.bucket_docs.clear();
+
+            if let Some(scorer) = &mut bucket.scorer_opt {
+                for (doc, matched_any) in docs.iter().zip(self.matched_any.iter_mut()) {
+                    if scorer.doc() < *doc {
+                        scorer.seek(*doc);
+                    }
+                    if scorer.doc() == *doc {
+                        self.bucket_docs.push(*doc);
+                        *matched_any = true;
+                    }
+                }
+            } else {
+                // The other bucket comes last, after all the filters have been evaluated.
+                self.bucket_docs.extend(
+                    docs.iter()
+                        .zip(&self.matched_any)
+                        .filter(|(_, matched_any)| !**matched_any)
+                        .map(|(doc, _)| *doc),
+                );
+            }
+            if self.bucket_docs.is_empty() {
+                continue;
+            }
+            bucket.doc_count += self.bucket_docs.len() as u64;
+            bucket
+                .sub_aggregation_collector
+                .collect_block(&self.bucket_docs);
+        }
+    }
+
+    pub fn harvest(self) -> tantivy::Result<IntermediateFiltersAggregationResult> {
+        let buckets = self
+            .buckets
+            .into_iter()
+            .map(|bucket| {
+                Ok(IntermediateFilterBucket {
+                    doc_count: bucket.doc_count,
+                    sub_aggregations: bucket.sub_aggregation_collector.harvest()?,
+                })
+            })
+            .collect::<tantivy::Result<_>>()?;
+        Ok(IntermediateFiltersAggregationResult { buckets })
+    }
+}
+
+/// Intermediate result of a bucket defined by a filter, also used by the `ip_range` aggregation.
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct IntermediateFilterBucket {
+    pub doc_count: u64,
+    pub sub_aggregations: IntermediateAggregationResults,
+}
+
+impl IntermediateFilterBucket {
+    pub fn merge_fruits(&mut self, other: IntermediateFilterBucket) -> tantivy::Result<()> {
+        self.doc_count += other.doc_count;
+        self.sub_aggregations.merge_fruits(other.sub_aggregations)
+    }
+
+    /// Returns the bucket JSON object: the results of the sub-aggregations along with the
+    /// `doc_count`.
+    pub fn into_final_result(
+        self,
+        sub_aggregations: Aggregations,
+        aggregation_limits: AggregationLimitsGuard,
+    ) -> crate::Result<JsonValue> {
+        let sub_aggregation_results = self
+            .sub_aggregations
+            .into_final_result(sub_aggregations, aggregation_limits)?;
+        let mut bucket_json = serde_json::to_value(sub_aggregation_results)?;
+
+        if let Some(bucket_obj) = bucket_json.as_object_mut() {
+            bucket_obj.insert("doc_count".to_string(), JsonValue::from(self.doc_count));
+        }
+        Ok(bucket_json)
+    }
+}
+
+/// Intermediate result of a filters aggregation: the filter buckets sorted by key, followed by the
+/// other bucket if requested.
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct IntermediateFiltersAggregationResult {
+    buckets: Vec<IntermediateFilterBucket>,
+}
+
+impl IntermediateFiltersAggregationResult {
+    pub fn merge_fruits(
+        &mut self,
+        other: IntermediateFiltersAggregationResult,
+    ) -> tantivy::Result<()> {
+        if self.buckets.is_empty() {
+            self.buckets = other.buckets;
+            return Ok(());
+        }
+        for (bucket, other_bucket) in self.buckets.iter_mut().zip(other.buckets) {
+            bucket.merge_fruits(other_bucket)?;
+        }
+        Ok(())
+    }
+
+    pub fn into_final_result(
+        mut self,
+        filters_aggregation: FiltersAggregation,
+        aggregation_limits: &AggregationLimitsGuard,
+    ) -> crate::Result<JsonValue> {
+        if self.buckets.is_empty() {
+            // No split was searched: we still return the (empty) buckets.
+            self.buckets
+                .resize_with(filters_aggregation.num_buckets(), Default::default);
+        }
+        let bucket_keys = filters_aggregation
+            .filters
+            .into_iter()
+            .map(|(key, _)| key)
+            .chain(filters_aggregation.other_bucket_key_opt);
+        let mut buckets_json = JsonMap::new();
+
+        for (key, bucket) in bucket_keys.zip(self.buckets) {
+            let bucket_json = bucket.into_final_result(
+                filters_aggregation.sub_aggregations.clone(),
+                aggregation_limits.clone(),
+            )?;
+            buckets_json.insert(key, bucket_json);
+        }
+        let mut aggregation_json = JsonMap::new();
+        aggregation_json.insert("buckets".to_string(), JsonValue::Object(buckets_json));
+        Ok(JsonValue::Object(aggregation_json))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_filters_aggregation() {
+        let aggregation_json = json!({
+            "filters": {
+                "filters": {
+                    "errors": {"type": "term", "field": "status", "value": "5xx"},
+                    "warnings": "status:4xx"
+                },
+                "other_bucket": true
+            },
+            "aggs": {
+                "avg_latency": {"avg": {"field": "latency"}}
+            }
+        });
+        let filters_aggregation =
+            FiltersAggregation::parse("statuses".to_string(), aggregation_json).unwrap();
+        assert_eq!(filters_aggregation.name(), "statuses");
+        assert_eq!(filters_aggregation.filters.len(), 2);
+        assert_eq!(filters_aggregation.filters[0].0, "errors");
+        assert_eq!(filters_aggregation.filters[1].0, "warnings");
+        assert_eq!(
+            filters_aggregation.filters[1].1,
+            query_ast_from_user_text("status:4xx", None)
+        );
+        assert_eq!(
+            filters_aggregation.other_bucket_key_opt.as_deref(),
+            Some("_other_")
+        );
+        assert_eq!(filters_aggregation.num_buckets(), 3);
+        assert!(filters_aggregation
+            .sub_aggregations()
+            .contains_key("avg_latency"));
+    }
+
+    #[test]
+    fn test_parse_invalid_filters_aggregation() {
+        let error =
+            FiltersAggregation::parse("statuses".to_string(), json!({"filters": {"filters": {}}}))
+                .unwrap_err();
+        assert_eq!(
+            error,
+            "filters aggregation `statuses` must define at least one filter"
+        );
+        let error = FiltersAggregation::parse(
+            "statuses".to_string(),
+            json!({
+                "filters": {
+                    "filters": {"errors": "status:5xx"},
+                    "other_bucket_key": "errors"
+                }
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "other bucket key `errors` of filters aggregation `statuses` conflicts with a filter \
+             key"
+        );
+        let error = FiltersAggregation::parse(
+            "statuses".to_string(),
+            json!({
+                "filters": {
+                    "filters": {"errors": "status:5xx"},
+                    "keyed": false
+                }
+            }),
+        )
+        .unwrap_err();
+        assert!(error.starts_with("invalid filters aggregation `statuses`: unknown field `keyed`"));
+    }
+
+    #[test]
+    fn test_filters_aggregation_final_result() {
+        let filters_aggregation = FiltersAggregation::parse(
+            "statuses".to_string(),
+            json!({
+                "filters": {
+                    "filters": {
+                        "errors": {"type": "term", "field": "status", "value": "5xx"},
+                        "warnings": {"type": "term", "field": "status", "value": "4xx"}
+                    },
+                    "other_bucket_key": "others"
+                }
+            }),
+        )
+        .unwrap();
+        let intermediate_result = |doc_counts: &[u64]| IntermediateFiltersAggregationResult {
+            buckets: doc_counts
+                .iter()
+                .map(|doc_count| IntermediateFilterBucket {
+                    doc_count: *doc_count,
+                    sub_aggregations: IntermediateAggregationResults::default(),
+                })
+                .collect(),
+        };
+        let mut merged_result = IntermediateFiltersAggregationResult::default();
+        merged_result
+            .merge_fruits(intermediate_result(&[1, 2, 3]))
+            .unwrap();
+        merged_result
+            .merge_fruits(intermediate_result(&[10, 0, 5]))
+            .unwrap();
+
+        let aggregation_limits = AggregationLimitsGuard::new(None, None);
+        let final_result = merged_result
+            .into_final_result(filters_aggregation.clone(), &aggregation_limits)
+            .unwrap();
+        assert_eq!(
+            final_result,
+            json!({
+                "buckets": {
+                    "errors": {"doc_count": 11},
+                    "warnings": {"doc_count": 2},
+                    "others": {"doc_count": 8}
+                }
+            })
+        );
+
+        // No split was searched.
+        let final_result = IntermediateFiltersAggregationResult::default()
+            .into_final_result(filters_aggregation, &aggregation_limits)
+            .unwrap();
+        assert_eq!(
+            final_result,
+            json!({
+                "buckets": {
+                    "errors": {"doc_count": 0},
+                    "warnings": {"doc_count": 0},
+                    "others": {"doc_count": 0}
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,445 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! The `geotile_grid` and `geohash_grid` aggregations, which group geo points into the cells of a
+//! grid, e.g. to render map heatmaps.
+//!
+//! Quickwit has no dedicated geo point field type: a geo point field is an object field with `lat`
+//! and `lon` numeric fast fields.
+
+use std::collections::{BTreeMap, HashMap};
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use tantivy::columnar::{Column, NumericalType};
+use tantivy::{DocId, SegmentReader};
+
+const DEFAULT_SIZE: usize = 10_000;
+
+const DEFAULT_GEOTILE_PRECISION: u8 = 7;
+
+const MAX_GEOTILE_PRECISION: u8 = 29;
+
+const DEFAULT_GEOHASH_PRECISION: u8 = 5;
+
+const MAX_GEOHASH_PRECISION: u8 = 12;
+
+/// Latitude bounds of the Web Mercator projection used by map tiles.
+const MAX_GEOTILE_LATITUDE: f64 = 85.051_128_779_806_59;
+
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GeoGridAggregationParams {
+    field: String,
+    #[serde(default)]
+    precision: Option<u8>,
+    #[serde(default)]
+    size: Option<usize>,
+    #[serde(default)]
+    shard_size: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum GeoGridAggregationRequest {
+    GeotileGrid(GeoGridAggregationParams),
+    GeohashGrid(GeoGridAggregationParams),
+}
+
+pub(crate) fn is_geo_grid_aggregation(aggregation: &JsonValue) -> bool {
+    aggregation
+        .as_object()
+        .map(|aggregation_obj| {
+            aggregation_obj.contains_key("geotile_grid")
+                || aggregation_obj.contains_key("geohash_grid")
+        })
+        .unwrap_or(false)
+}
+
+/// A grid dividing the surface of the earth into cells, at a given precision.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GeoGrid {
+    /// Web Mercator map tiles at zoom level `precision`, keyed `{zoom}/{x}/{y}`.
+    Geotile { precision: u8 },
+    /// Geohashes of `precision` characters.
+    Geohash { precision: u8 },
+}
+
+impl GeoGrid {
+    /// Returns the identifier of the cell containing the point, or `None` if the point is not a
+    /// valid geo point.
+    fn cell_id(&self, lat: f64, lon: f64) -> Option<u64> {
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return None;
+        }
+        match *self {
+            GeoGrid::Geotile { precision } => {
+                let num_tiles = 1u64 << precision;
+                let lat_rad = lat
+                    .clamp(-MAX_GEOTILE_LATITUDE, MAX_GEOTILE_LATITUDE)
+                    .to_radians();
+                let x = (lon + 180.0) / 360.0 * num_tiles as f64;
+                let y = (1.0 - (lat_rad.tan() + 1.0 / lat_rad.cos()).ln() / PI) / 2.0
+                    * num_tiles as f64;
+                let x = (x.max(0.0) as u64).min(num_tiles - 1);
+                let y = (y.max(0.0) as u64).min(num_tiles - 1);
+                Some((x << 32) | y)
+            }
+            GeoGrid::Geohash { precision } => {
+                let mut lat_range = (-90.0, 90.0);
+                let mut lon_range = (-180.0, 180.0);
+                let mut cell_id = 0u64;
+
+                // Bits alternate between longitude and latitude, starting with longitude.
+                for bit_ord in 0..precision as usize * 5 {
+                    let (range, value) = if bit_ord % 2 == 0 {
+                        (&mut lon_range, lon)
+                    } else {
+                        (&mut lat_range, lat)
+                    };
+                    let mid = (range.0 + range.1) / 2.0;
+                    cell_id <<= 1;
+
+                    if value >= mid {
+                        cell_id |= 1;
+                        range.0 = mid;
+                    } else {
+                        range.1 = mid;
+                    }
+                }
+                Some(cell_id)
+            }
+        }
+    }
+
+    fn cell_key(&self, cell_id: u64) -> String {
+        match *self {
+            GeoGrid::Geotile { precision } => {
+                format!(
+                    "{precision}/{}/{}",
+                    cell_id >> 32,
+                    cell_id & u32::MAX as u64
+                )
+            }
+            GeoGrid::Geohash { precision } => (0..precision as usize)
+                .rev()
+                .map(|char_ord| {
+                    GEOHASH_ALPHABET[((cell_id >> (char_ord * 5)) & 0b11111) as usize] as char
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GeoGridAggregation {
+    name: String,
+    field: String,
+    grid: GeoGrid,
+    size: usize,
+    shard_size: usize,
+}
+
+impl GeoGridAggregation {
+    pub fn parse(name: String, aggregation_json: JsonValue) -> Result<Self, String> {
+        let request: GeoGridAggregationRequest = serde_json::from_value(aggregation_json)
+            .map_err(|error| format!("invalid geo grid aggregation `{name}`: {error}"))?;
+        let (params, grid) = match request {
+            GeoGridAggregationRequest::GeotileGrid(params) => {
+                // Precisions above the maximum are capped rather than rejected.
+                let precision = params
+                    .precision
+                    .unwrap_or(DEFAULT_GEOTILE_PRECISION)
+                    .min(MAX_GEOTILE_PRECISION);
+                (params, GeoGrid::Geotile { precision })
+            }
+            GeoGridAggregationRequest::GeohashGrid(params) => {
+                let precision = params
+                    .precision
+                    .unwrap_or(DEFAULT_GEOHASH_PRECISION)
+                    .min(MAX_GEOHASH_PRECISION);
+                if precision == 0 {
+                    return Err(format!(
+                        "precision of geohash_grid aggregation `{name}` must be strictly positive"
+                    ));
+                }
+                (params, GeoGrid::Geohash { precision })
+            }
+        };
+        let size = params.size.unwrap_or(DEFAULT_SIZE);
+        if size == 0 {
+            return Err(format!(
+                "size of geo grid aggregation `{name}` must be strictly positive"
+            ));
+        }
+        let shard_size = params.shard_size.unwrap_or(size).max(size);
+        Ok(Self {
+            name,
+            field: params.field,
+            grid,
+            size,
+            shard_size,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the names of the latitude and longitude fast fields.
+    pub fn fast_field_names(&self) -> [String; 2] {
+        [format!("{}.lat", self.field), format!("{}.lon", self.field)]
+    }
+
+    pub fn segment_collector(
+        &self,
+        segment_reader: &SegmentReader,
+    ) -> tantivy::Result<GeoGridSegmentCollector> {
+        let [lat_field_name, lon_field_name] = self.fast_field_names();
+        let lat_column_opt = open_f64_column(segment_reader, &lat_field_name)?;
+        let lon_column_opt = open_f64_column(segment_reader, &lon_field_name)?;
+        Ok(GeoGridSegmentCollector {
+            columns_opt: lat_column_opt.zip(lon_column_opt),
+            grid: self.grid,
+            shard_size: self.shard_size,
+            cell_doc_counts: HashMap::new(),
+        })
+    }
+}
+
+/// Opens a numeric fast field as a `f64` column, whatever its numerical type. This matters for
+/// JSON fields, where coordinates that are all integers are stored in an integer column.
+fn open_f64_column(
+    segment_reader: &SegmentReader,
+    field_name: &str,
+) -> tantivy::Result<Option<Column<f64>>> {
+    let dynamic_column_handles = segment_reader
+        .fast_fields()
+        .dynamic_column_handles(field_name)?;
+
+    for dynamic_column_handle in dynamic_column_handles {
+        if dynamic_column_handle
+            .column_type()
+            .numerical_type()
+            .is_none()
+        {
+            continue;
+        }
+        let Some(dynamic_column) = dynamic_column_handle
+            .open()?
+            .coerce_numerical(NumericalType::F64)
+        else {
+            continue;
+        };
+        return Ok(dynamic_column.into());
+    }
+    Ok(None)
+}
+
+/// Counts the documents per grid cell. Only the `shard_size` most populated cells of the segment
+/// are returned.
+pub(crate) struct GeoGridSegmentCollector {
+    columns_opt: Option<(Column<f64>, Column<f64>)>,
+    grid: GeoGrid,
+    shard_size: usize,
+    cell_doc_counts: HashMap<u64, u64>,
+}
+
+impl GeoGridSegmentCollector {
+    pub fn collect_block(&mut self, docs: &[DocId]) {
+        let Some((lat_column, lon_column)) = &self.columns_opt else {
+            return;
+        };
+        for doc in docs {
+            let (Some(lat), Some(lon)) = (lat_column.first(*doc), lon_column.first(*doc)) else {
+                continue;
+            };
+            if let Some(cell_id) = self.grid.cell_id(lat, lon) {
+                *self.cell_doc_counts.entry(cell_id).or_default() += 1;
+            }
+        }
+    }
+
+    pub fn harvest(self) -> tantivy::Result<IntermediateGeoGridResult> {
+        let mut cell_doc_counts: Vec<(u64, u64)> = self.cell_doc_counts.into_iter().collect();
+
+        if cell_doc_counts.len() > self.shard_size {
+            cell_doc_counts.sort_unstable_by(|(left_cell_id, left), (right_cell_id, right)| {
+                right
+                    .cmp(left)
+                    .then_with(|| left_cell_id.cmp(right_cell_id))
+            });
+            cell_doc_counts.truncate(self.shard_size);
+        }
+        let buckets = cell_doc_counts
+            .into_iter()
+            .map(|(cell_id, doc_count)| (self.grid.cell_key(cell_id), doc_count))
+            .collect();
+        Ok(IntermediateGeoGridResult { buckets })
+    }
+}
+
+/// Document counts per cell key, merged across splits.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct IntermediateGeoGridResult {
+    buckets: BTreeMap<String, u64>,
+}
+
+impl IntermediateGeoGridResult {
+    pub fn merge_fruits(&mut self, other: IntermediateGeoGridResult) {
+        for (cell_key, doc_count) in other.buckets {
+            *self.buckets.entry(cell_key).or_default() += doc_count;
+        }
+    }
+
+    pub fn into_final_result(self, aggregation: &GeoGridAggregation) -> JsonValue {
+        let mut buckets: Vec<(String, u64)> = self.buckets.into_iter().collect();
+        // Cells are sorted by decreasing document count, and then by key.
+        buckets.sort_by(|(left_key, left), (right_key, right)| {
+            right.cmp(left).then_with(|| left_key.cmp(right_key))
+        });
+        buckets.truncate(aggregation.size);
+
+        let buckets: Vec<JsonValue> = buckets
+            .into_iter()
+            .map(|(cell_key, doc_count)| json!({"key": cell_key, "doc_count": doc_count}))
+            .collect();
+        json!({ "buckets": buckets })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_geo_grid_aggregation() {
+        let aggregation = GeoGridAggregation::parse(
+            "tiles".to_string(),
+            json!({"geotile_grid": {"field": "location"}}),
+        )
+        .unwrap();
+        assert_eq!(aggregation.name(), "tiles");
+        assert_eq!(aggregation.grid, GeoGrid::Geotile { precision: 7 });
+        assert_eq!(aggregation.size, 10_000);
+        assert_eq!(aggregation.shard_size, 10_000);
+        assert_eq!(
+            aggregation.fast_field_names(),
+            ["location.lat".to_string(), "location.lon".to_string()]
+        );
+
+        let aggregation = GeoGridAggregation::parse(
+            "hashes".to_string(),
+            json!({
+                "geohash_grid": {
+                    "field": "location",
+                    "precision": 20,
+                    "size": 100,
+                    "shard_size": 10
+                }
+            }),
+        )
+        .unwrap();
+        assert_eq!(aggregation.grid, GeoGrid::Geohash { precision: 12 });
+        assert_eq!(aggregation.size, 100);
+        assert_eq!(aggregation.shard_size, 100);
+
+        let aggregation = GeoGridAggregation::parse(
+            "tiles".to_string(),
+            json!({"geotile_grid": {"field": "location", "precision": 42}}),
+        )
+        .unwrap();
+        assert_eq!(aggregation.grid, GeoGrid::Geotile { precision: 29 });
+
+        for invalid_aggregation_json in [
+            json!({"geohash_grid": {"field": "location", "precision": 0}}),
+            json!({"geotile_grid": {"field": "location", "size": 0}}),
+            json!({"geotile_grid": {"field": "location", "bounds": {}}}),
+            json!({"geotile_grid": {"field": "location"}, "aggs": {}}),
+        ] {
+            GeoGridAggregation::parse("tiles".to_string(), invalid_aggregation_json).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_geotile_grid_cells() {
+        let grid = GeoGrid::Geotile { precision: 8 };
+        let cell_id = grid.cell_id(52.374, 4.9).unwrap();
+        assert_eq!(grid.cell_key(cell_id), "8/131/84");
+
+        let grid = GeoGrid::Geotile { precision: 0 };
+        let cell_id = grid.cell_id(-90.0, 180.0).unwrap();
+        assert_eq!(grid.cell_key(cell_id), "0/0/0");
+
+        let grid = GeoGrid::Geotile { precision: 1 };
+        let cell_id = grid.cell_id(90.0, -180.0).unwrap();
+        assert_eq!(grid.cell_key(cell_id), "1/0/0");
+        let cell_id = grid.cell_id(-90.0, 180.0).unwrap();
+        assert_eq!(grid.cell_key(cell_id), "1/1/1");
+
+        assert!(grid.cell_id(91.0, 0.0).is_none());
+        assert!(grid.cell_id(0.0, f64::NAN).is_none());
+    }
+
+    #[test]
+    fn test_geohash_grid_cells() {
+        let grid = GeoGrid::Geohash { precision: 11 };
+        let cell_id = grid.cell_id(57.64911, 10.40744).unwrap();
+        assert_eq!(grid.cell_key(cell_id), "u4pruydqqvj");
+
+        let grid = GeoGrid::Geohash { precision: 5 };
+        let cell_id = grid.cell_id(48.8566, 2.3522).unwrap();
+        assert_eq!(grid.cell_key(cell_id), "u09tv");
+
+        let grid = GeoGrid::Geohash { precision: 12 };
+        let cell_id = grid.cell_id(-90.0, -180.0).unwrap();
+        assert_eq!(grid.cell_key(cell_id), "000000000000");
+    }
+
+    #[test]
+    fn test_geo_grid_final_result() {
+        let aggregation = GeoGridAggregation::parse(
+            "hashes".to_string(),
+            json!({"geohash_grid": {"field": "location", "size": 2}}),
+        )
+        .unwrap();
+        let mut intermediate_result = IntermediateGeoGridResult {
+            buckets: BTreeMap::from_iter([("u09tv".to_string(), 3), ("u4pru".to_string(), 1)]),
+        };
+        intermediate_result.merge_fruits(IntermediateGeoGridResult {
+            buckets: BTreeMap::from_iter([
+                ("u4pru".to_string(), 3),
+                ("gcpvj".to_string(), 3),
+                ("dr5re".to_string(), 1),
+            ]),
+        });
+        assert_eq!(
+            intermediate_result.into_final_result(&aggregation),
+            json!({
+                "buckets": [
+                    {"key": "u4pru", "doc_count": 4},
+                    {"key": "gcpvj", "doc_count": 3},
+                ]
+            })
+        );
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use quickwit_config::LeafSearchHedgingConfig;
+
+/// Number of recent leaf search latencies the hedging delay is computed from.
+const LATENCY_WINDOW_LEN: usize = 1_000;
+
+/// Minimum number of leaf search latencies to observe before the hedging delay adapts to them.
+const MIN_NUM_LATENCIES: usize = 20;
+
+/// Maximum number of hedges that can be sent in a burst, once the hedge budget is full.
+const MAX_HEDGE_TOKENS: f64 = 10.0;
+
+/// Computes the delay after which a leaf search request is hedged, from the latencies of the
+/// recent leaf search requests, and caps the number of hedges with a token bucket: every leaf
+/// search request earns a fraction of a token and every hedge spends a full one.
+pub(crate) struct LeafSearchHedging {
+    config: LeafSearchHedgingConfig,
+    latencies: Mutex<VecDeque<Duration>>,
+    hedge_tokens: Mutex<f64>,
+}
+
+impl LeafSearchHedging {
+    pub fn new(config: LeafSearchHedgingConfig) -> Self {
+        LeafSearchHedging {
+            config,
+            latencies: Mutex::new(VecDeque::with_capacity(LATENCY_WINDOW_LEN)),
+            hedge_tokens: Mutex::new(MAX_HEDGE_TOKENS),
+        }
+    }
+
+    /// Adds the share of a hedge earned by a leaf search request to the hedge budget.
+    pub fn record_request(&self) {
+        let request_share = self.config.max_hedged_requests_percent as f64 / 100.0;
+        let mut hedge_tokens = self.hedge_tokens.lock().unwrap();
+        *hedge_tokens = (*hedge_tokens + request_share).min(MAX_HEDGE_TOKENS);
+    }
+
+    /// Spends a token of the hedge budget. Returns `false` if the budget is exhausted, in which
+    /// case the request must not be hedged.
+    pub fn try_acquire_hedge(&self) -> bool {
+        let mut hedge_tokens = self.hedge_tokens.lock().unwrap();
+        if *hedge_tokens < 1.0 {
+            return false;
+        }
+        *hedge_tokens -= 1.0;
+        true
+    }
+
+    /// Returns the configured percentile of the recent latencies, capped by the maximum delay.
+    pub fn hedging_delay(&self) -> Duration {
+        let max_delay = self.config.max_delay();
+        let mut latencies: Vec<Duration> = {
+            let latencies = self.latencies.lock().unwrap();
+            if latencies.len() < MIN_NUM_LATENCIES {
+                return max_delay;
+            }
+            latencies.iter().copied().collect()
+        };
+        let rank = (latencies.len() - 1) * self.config.latency_percentile as usize / 100;
+        let (_, latency, _) = latencies.select_nth_unstable(rank);
+        (*latency).min(max_delay)
+    }
+
+    pub fn record_latency(&self, latency: Duration) {
+        let mut latencies = self.latencies.lock().unwrap();
+        if latencies.len() == LATENCY_WINDOW_LEN {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use super::*;
+
+    #[test]
+    fn test_leaf_search_hedging_delay() {
+        let hedging = LeafSearchHedging::new(LeafSearchHedgingConfig {
+            latency_percentile: 90,
+            max_delay_millis: NonZeroU64::new(500).unwrap(),
+            max_hedged_requests_percent: 10,
+        });
+        assert_eq!(hedging.hedging_delay(), Duration::from_millis(500));
+
+        for latency_millis in 1..=100 {
+            hedging.record_latency(Duration::from_millis(latency_millis));
+        }
+        assert_eq!(hedging.hedging_delay(), Duration::from_millis(90));
+
+        for _ in 0..LATENCY_WINDOW_LEN {
+            hedging.record_latency(Duration::from_secs(1));
+        }
+        assert_eq!(hedging.hedging_delay(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_leaf_search_hedging_budget() {
+        let hedging = LeafSearchHedging::new(LeafSearchHedgingConfig {
+            latency_percentile: 90,
+            max_delay_millis: NonZeroU64::new(500).unwrap(),
+            max_hedged_requests_percent: 10,
+        });
+        // The budget starts full and allows a burst of hedges.
+        for _ in 0..10 {
+            assert!(hedging.try_acquire_hedge());
+        }
+        assert!(!hedging.try_acquire_hedge());
+
+        for _ in 0..9 {
+            hedging.record_request();
+        }
+        assert!(!hedging.try_acquire_hedge());
+
+        // 10 requests earn one hedge (floating point rounding aside).
+        for _ in 0..2 {
+            hedging.record_request();
+        }
+        assert!(hedging.try_acquire_hedge());
+        assert!(!hedging.try_acquire_hedge());
+
+        // The budget does not grow beyond the burst size.
+        for _ in 0..1_000 {
+            hedging.record_request();
+        }
+        let num_hedges = (0..100).filter(|_| hedging.try_acquire_hedge()).count();
+        assert_eq!(num_hedges, 10);
+    }
+}
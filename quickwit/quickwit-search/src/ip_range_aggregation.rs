@@ -0,0 +1,557 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! The `ip_range` aggregation, which buckets documents by ranges of IP addresses, expressed either
+//! with bounds or as CIDR masks.
+//!
+//! IPv4 addresses are stored as IPv4-mapped IPv6 addresses, so IPv4 and IPv6 ranges are handled
+//! the same way.
+
+use std::net::{IpAddr, Ipv6Addr};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use tantivy::aggregation::agg_req::Aggregations;
+use tantivy::aggregation::{AggregationLimitsGuard, AggregationSegmentCollector};
+use tantivy::collector::SegmentCollector;
+use tantivy::columnar::Column;
+use tantivy::{DocId, SegmentOrdinal, SegmentReader};
+
+use crate::filters_aggregation::IntermediateFilterBucket;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IpRangeParams {
+    #[serde(default)]
+    key: Option<String>,
+    #[serde(default)]
+    from: Option<IpAddr>,
+    #[serde(default)]
+    to: Option<IpAddr>,
+    #[serde(default)]
+    mask: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IpRangeAggregationParams {
+    field: String,
+    ranges: Vec<IpRangeParams>,
+    #[serde(default)]
+    keyed: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IpRangeAggregationRequest {
+    ip_range: IpRangeAggregationParams,
+    #[serde(default, alias = "aggregations")]
+    aggs: Aggregations,
+}
+
+pub(crate) fn is_ip_range_aggregation(aggregation: &JsonValue) -> bool {
+    aggregation
+        .as_object()
+        .map(|aggregation_obj| aggregation_obj.contains_key("ip_range"))
+        .unwrap_or(false)
+}
+
+fn to_ipv6(ip_addr: IpAddr) -> Ipv6Addr {
+    match ip_addr {
+        IpAddr::V4(ipv4_addr) => ipv4_addr.to_ipv6_mapped(),
+        IpAddr::V6(ipv6_addr) => ipv6_addr,
+    }
+}
+
+/// Formats IPv4-mapped addresses as IPv4 addresses.
+fn format_ip(ipv6_addr: Ipv6Addr) -> String {
+    match ipv6_addr.to_ipv4_mapped() {
+        Some(ipv4_addr) => ipv4_addr.to_string(),
+        None => ipv6_addr.to_string(),
+    }
+}
+
+/// Parses a CIDR mask such as `10.0.0.0/8` into the first address of the range and the first
+/// address following it, if any.
+fn parse_cidr_mask(mask: &str) -> Result<(Ipv6Addr, Option<Ipv6Addr>), String> {
+    let invalid_mask = || format!("invalid CIDR mask `{mask}`");
+
+    let (ip_addr_str, prefix_len_str) = mask.split_once('/').ok_or_else(invalid_mask)?;
+    let ip_addr: IpAddr = ip_addr_str.parse().map_err(|_| invalid_mask())?;
+    let prefix_len: u32 = prefix_len_str.parse().map_err(|_| invalid_mask())?;
+
+    let prefix_len = match ip_addr {
+        IpAddr::V4(_) if prefix_len <= 32 => prefix_len + 96,
+        IpAddr::V6(_) if prefix_len <= 128 => prefix_len,
+        _ => return Err(invalid_mask()),
+    };
+    if prefix_len == 0 {
+        return Ok((Ipv6Addr::UNSPECIFIED, None));
+    }
+    let num_addrs_log2 = 128 - prefix_len;
+    let network = u128::from(to_ipv6(ip_addr)) & (u128::MAX << num_addrs_log2);
+    let next_network_opt = network
+        .checked_add(1u128 << num_addrs_log2)
+        .map(Ipv6Addr::from);
+    Ok((Ipv6Addr::from(network), next_network_opt))
+}
+
+/// A range of IP addresses, including `from` and excluding `to`.
+#[derive(Debug, Clone, PartialEq)]
+struct IpRange {
+    key: String,
+    from_opt: Option<Ipv6Addr>,
+    to_opt: Option<Ipv6Addr>,
+}
+
+impl IpRange {
+    fn parse(params: IpRangeParams) -> Result<Self, String> {
+        let (from_opt, to_opt, default_key) = if let Some(mask) = params.mask {
+            if params.from.is_some() || params.to.is_some() {
+                return Err(format!(
+                    "range `{mask}` must define either a mask or bounds, not both"
+                ));
+            }
+            let (from, to_opt) = parse_cidr_mask(&mask)?;
+            (Some(from), to_opt, mask)
+        } else {
+            let from_opt = params.from.map(to_ipv6);
+            let to_opt = params.to.map(to_ipv6);
+            let format_bound = |bound_opt: Option<Ipv6Addr>| {
+                bound_opt.map(format_ip).unwrap_or_else(|| "*".to_string())
+            };
+            let default_key = format!("{}-{}", format_bound(from_opt), format_bound(to_opt));
+            (from_opt, to_opt, default_key)
+        };
+        Ok(Self {
+            key: params.key.unwrap_or(default_key),
+            from_opt,
+            to_opt,
+        })
+    }
+
+    fn contains(&self, ipv6_addr: Ipv6Addr) -> bool {
+        if let Some(from) = self.from_opt {
+            if ipv6_addr < from {
+                return false;
+            }
+        }
+        if let Some(to) = self.to_opt {
+            if ipv6_addr >= to {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IpRangeAggregation {
+    name: String,
+    field: String,
+    /// Ranges, in the order of the request.
+    ranges: Vec<IpRange>,
+    keyed: bool,
+    sub_aggregations: Aggregations,
+}
+
+impl IpRangeAggregation {
+    pub fn parse(name: String, aggregation_json: JsonValue) -> Result<Self, String> {
+        let request: IpRangeAggregationRequest = serde_json::from_value(aggregation_json)
+            .map_err(|error| format!("invalid ip_range aggregation `{name}`: {error}"))?;
+        let params = request.ip_range;
+
+        if params.ranges.is_empty() {
+            return Err(format!(
+                "ip_range aggregation `{name}` must define at least one range"
+            ));
+        }
+        let ranges: Vec<IpRange> = params
+            .ranges
+            .into_iter()
+            .map(IpRange::parse)
+            .collect::<Result<_, _>>()
+            .map_err(|error| format!("invalid ip_range aggregation `{name}`: {error}"))?;
+
+        if params.keyed {
+            for (range_ord, range) in ranges.iter().enumerate() {
+                if ranges[..range_ord]
+                    .iter()
+                    .any(|other_range| other_range.key == range.key)
+                {
+                    return Err(format!(
+                        "key `{}` of ip_range aggregation `{name}` is not unique",
+                        range.key
+                    ));
+                }
+            }
+        }
+        Ok(Self {
+            name,
+            field: params.field,
+            ranges,
+            keyed: params.keyed,
+            sub_aggregations: request.aggs,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    pub fn sub_aggregations(&self) -> &Aggregations {
+        &self.sub_aggregations
+    }
+
+    pub fn segment_collector(
+        &self,
+        segment_reader: &SegmentReader,
+        segment_ord: SegmentOrdinal,
+        aggregation_limits: &AggregationLimitsGuard,
+    ) -> tantivy::Result<IpRangeSegmentCollector> {
+        let ip_column_opt = segment_reader
+            .fast_fields()
+            .column_opt::<Ipv6Addr>(&self.field)?;
+        let buckets = self
+            .ranges
+            .iter()
+            .map(|_| {
+                let sub_aggregation_collector =
+                    AggregationSegmentCollector::from_agg_req_and_reader(
+                        &self.sub_aggregations,
+                        segment_reader,
+                        segment_ord,
+                        aggregation_limits,
+                    )?;
+                Ok(IpRangeBucketSegmentCollector {
+                    doc_count: 0,
+                    sub_aggregation_collector,
+                })
+            })
+            .collect::<tantivy::Result<_>>()?;
+        Ok(IpRangeSegmentCollector {
+            ip_column_opt,
+            ranges: self.ranges.clone(),
+            buckets,
+            bucket_docs: Vec::new(),
+        })
+    }
+}
+
+struct IpRangeBucketSegmentCollector {
+    doc_count: u64,
+    sub_aggregation_collector: AggregationSegmentCollector,
+}
+
+/// Collects the documents of every range. A document with several IP addresses falls into a range
+/// if any of its addresses does.
+pub(crate) struct IpRangeSegmentCollector {
+    ip_column_opt: Option<Column<Ipv6Addr>>,
+    ranges: Vec<IpRange>,
+    buckets: Vec<IpRangeBucketSegmentCollector>,
+    bucket_docs: Vec<DocId>,
+}
+
+impl IpRangeSegmentCollector {
+    pub fn collect_block(&mut self, docs: &[DocId]) {
+        let Some(ip_column) = &self.ip_column_opt else {
+            return;
+        };
+        for (range, bucket) in self.ranges.iter().zip(&mut self.buckets) {
+            self.bucket_docs.clear();
+            self.bucket_docs.extend(docs.iter().copied().filter(|doc| {
+                ip_column
+                    .values_for_doc(*doc)
+                    .any(|ipv6_addr| range.contains(ipv6_addr))
+            }));
+            if self.bucket_docs.is_empty() {
+                continue;
+            }
+            bucket.doc_count += self.bucket_docs.len() as u64;
+            bucket
+                .sub_aggregation_collector
+                .collect_block(&self.bucket_docs);
+        }
+    }
+
+    pub fn harvest(self) -> tantivy::Result<IntermediateIpRangeResult> {
+        let buckets = self
+            .buckets
+            .into_iter()
+            .map(|bucket| {
+                Ok(IntermediateFilterBucket {
+                    doc_count: bucket.doc_count,
+                    sub_aggregations: bucket.sub_aggregation_collector.harvest()?,
+                })
+            })
+            .collect::<tantivy::Result<_>>()?;
+        Ok(IntermediateIpRangeResult { buckets })
+    }
+}
+
+/// Intermediate result of an ip_range aggregation: the buckets in the order of the ranges.
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct IntermediateIpRangeResult {
+    buckets: Vec<IntermediateFilterBucket>,
+}
+
+impl IntermediateIpRangeResult {
+    pub fn merge_fruits(&mut self, other: IntermediateIpRangeResult) -> tantivy::Result<()> {
+        if self.buckets.is_empty() {
+            self.buckets = other.buckets;
+            return Ok(());
+        }
+        for (bucket, other_bucket) in self.buckets.iter_mut().zip(other.buckets) {
+            bucket.merge_fruits(other_bucket)?;
+        }
+        Ok(())
+    }
+
+    pub fn into_final_result(
+        mut self,
+        ip_range_aggregation: &IpRangeAggregation,
+        aggregation_limits: &AggregationLimitsGuard,
+    ) -> crate::Result<JsonValue> {
+        if self.buckets.is_empty() {
+            // No split was searched: we still return the (empty) buckets.
+            self.buckets
+                .resize_with(ip_range_aggregation.ranges.len(), Default::default);
+        }
+        let mut keyed_buckets_json = JsonMap::new();
+        let mut buckets_json = Vec::new();
+
+        for (range, bucket) in ip_range_aggregation.ranges.iter().zip(self.buckets) {
+            let mut bucket_json = bucket.into_final_result(
+                ip_range_aggregation.sub_aggregations.clone(),
+                aggregation_limits.clone(),
+            )?;
+            let Some(bucket_obj) = bucket_json.as_object_mut() else {
+                continue;
+            };
+            if let Some(from) = range.from_opt {
+                bucket_obj.insert("from".to_string(), JsonValue::String(format_ip(from)));
+            }
+            if let Some(to) = range.to_opt {
+                bucket_obj.insert("to".to_string(), JsonValue::String(format_ip(to)));
+            }
+            if ip_range_aggregation.keyed {
+                keyed_buckets_json.insert(range.key.clone(), bucket_json);
+            } else {
+                bucket_obj.insert("key".to_string(), JsonValue::String(range.key.clone()));
+                buckets_json.push(bucket_json);
+            }
+        }
+        let buckets_json = if ip_range_aggregation.keyed {
+            JsonValue::Object(keyed_buckets_json)
+        } else {
+            JsonValue::Array(buckets_json)
+        };
+        let mut aggregation_json = JsonMap::new();
+        aggregation_json.insert("buckets".to_string(), buckets_json);
+        Ok(JsonValue::Object(aggregation_json))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tantivy::aggregation::intermediate_agg_result::IntermediateAggregationResults;
+
+    use super::*;
+
+    fn ip(ip_addr_str: &str) -> Ipv6Addr {
+        to_ipv6(ip_addr_str.parse().unwrap())
+    }
+
+    #[test]
+    fn test_parse_ip_range_aggregation() {
+        let aggregation = IpRangeAggregation::parse(
+            "networks".to_string(),
+            json!({
+                "ip_range": {
+                    "field": "host",
+                    "ranges": [
+                        {"to": "10.0.0.5"},
+                        {"from": "10.0.0.5", "key": "upper"},
+                        {"mask": "192.168.0.0/16"},
+                        {"mask": "2001:db8::/32"}
+                    ]
+                },
+                "aggs": {
+                    "avg_latency": {"avg": {"field": "latency"}}
+                }
+            }),
+        )
+        .unwrap();
+        assert_eq!(aggregation.name(), "networks");
+        assert_eq!(aggregation.field(), "host");
+        assert!(!aggregation.keyed);
+        assert_eq!(aggregation.sub_aggregations().len(), 1);
+        assert_eq!(
+            aggregation.ranges,
+            vec![
+                IpRange {
+                    key: "*-10.0.0.5".to_string(),
+                    from_opt: None,
+                    to_opt: Some(ip("10.0.0.5")),
+                },
+                IpRange {
+                    key: "upper".to_string(),
+                    from_opt: Some(ip("10.0.0.5")),
+                    to_opt: None,
+                },
+                IpRange {
+                    key: "192.168.0.0/16".to_string(),
+                    from_opt: Some(ip("192.168.0.0")),
+                    to_opt: Some(ip("192.169.0.0")),
+                },
+                IpRange {
+                    key: "2001:db8::/32".to_string(),
+                    from_opt: Some(ip("2001:db8::")),
+                    to_opt: Some(ip("2001:db9::")),
+                },
+            ]
+        );
+        for invalid_aggregation_json in [
+            json!({"ip_range": {"field": "host", "ranges": []}}),
+            json!({"ip_range": {"field": "host", "ranges": [{"from": "not-an-ip"}]}}),
+            json!({"ip_range": {"field": "host", "ranges": [{"mask": "10.0.0.0/33"}]}}),
+            json!({"ip_range": {"field": "host", "ranges": [{"mask": "10.0.0.0"}]}}),
+            json!({
+                "ip_range": {"field": "host", "ranges": [{"mask": "10.0.0.0/8", "to": "10.0.0.1"}]}
+            }),
+            json!({
+                "ip_range": {
+                    "field": "host",
+                    "ranges": [{"to": "10.0.0.1"}, {"to": "10.0.0.1"}],
+                    "keyed": true
+                }
+            }),
+            json!({
+                "ip_range": {"field": "host", "ranges": [{"to": "10.0.0.1"}], "missing": "0.0.0.0"}
+            }),
+        ] {
+            IpRangeAggregation::parse("networks".to_string(), invalid_aggregation_json)
+                .unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_ip_range_contains() {
+        let range = IpRange::parse(IpRangeParams {
+            key: None,
+            from: None,
+            to: None,
+            mask: Some("10.1.2.3/24".to_string()),
+        })
+        .unwrap();
+        assert_eq!(range.from_opt, Some(ip("10.1.2.0")));
+        assert_eq!(range.to_opt, Some(ip("10.1.3.0")));
+        assert!(!range.contains(ip("10.1.1.255")));
+        assert!(range.contains(ip("10.1.2.0")));
+        assert!(range.contains(ip("10.1.2.255")));
+        assert!(!range.contains(ip("10.1.3.0")));
+        assert!(!range.contains(ip("::1")));
+
+        let range = IpRange::parse(IpRangeParams {
+            key: None,
+            from: None,
+            to: None,
+            mask: Some("::/0".to_string()),
+        })
+        .unwrap();
+        assert_eq!(range.from_opt, Some(Ipv6Addr::UNSPECIFIED));
+        assert_eq!(range.to_opt, None);
+        assert!(range.contains(ip("255.255.255.255")));
+        assert!(range.contains(ip("ffff::1")));
+    }
+
+    #[test]
+    fn test_ip_range_aggregation_final_result() {
+        let aggregation_json = json!({
+            "ip_range": {
+                "field": "host",
+                "ranges": [
+                    {"to": "10.0.0.5"},
+                    {"mask": "10.0.0.0/24"}
+                ]
+            }
+        });
+        let aggregation =
+            IpRangeAggregation::parse("networks".to_string(), aggregation_json.clone()).unwrap();
+        let intermediate_result = |doc_counts: &[u64]| IntermediateIpRangeResult {
+            buckets: doc_counts
+                .iter()
+                .map(|doc_count| IntermediateFilterBucket {
+                    doc_count: *doc_count,
+                    sub_aggregations: IntermediateAggregationResults::default(),
+                })
+                .collect(),
+        };
+        let mut merged_result = IntermediateIpRangeResult::default();
+        merged_result
+            .merge_fruits(intermediate_result(&[1, 2]))
+            .unwrap();
+        merged_result
+            .merge_fruits(intermediate_result(&[3, 0]))
+            .unwrap();
+
+        let aggregation_limits = AggregationLimitsGuard::new(None, None);
+        let final_result = merged_result
+            .into_final_result(&aggregation, &aggregation_limits)
+            .unwrap();
+        assert_eq!(
+            final_result,
+            json!({
+                "buckets": [
+                    {"key": "*-10.0.0.5", "to": "10.0.0.5", "doc_count": 4},
+                    {
+                        "key": "10.0.0.0/24",
+                        "from": "10.0.0.0",
+                        "to": "10.0.1.0",
+                        "doc_count": 2
+                    }
+                ]
+            })
+        );
+
+        let mut aggregation_json = aggregation_json;
+        aggregation_json["ip_range"]["keyed"] = json!(true);
+        let aggregation =
+            IpRangeAggregation::parse("networks".to_string(), aggregation_json).unwrap();
+
+        // No split was searched.
+        let final_result = IntermediateIpRangeResult::default()
+            .into_final_result(&aggregation, &aggregation_limits)
+            .unwrap();
+        assert_eq!(
+            final_result,
+            json!({
+                "buckets": {
+                    "*-10.0.0.5": {"to": "10.0.0.5", "doc_count": 0},
+                    "10.0.0.0/24": {"from": "10.0.0.0", "to": "10.0.1.0", "doc_count": 0}
+                }
+            })
+        );
+    }
+}
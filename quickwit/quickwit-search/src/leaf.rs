@@ -18,25 +18,29 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::ops::Bound;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 use anyhow::Context;
 use futures::future::try_join_all;
 use quickwit_common::pretty::PrettySample;
+use quickwit_common::AbortOnDropJoinHandle;
 use quickwit_directories::{CachingDirectory, HotDirectory, StorageDirectory};
 use quickwit_doc_mapper::{DocMapper, TermRange, WarmupInfo};
 use quickwit_proto::search::{
     CountHits, LeafSearchRequest, LeafSearchResponse, PartialHit, SearchRequest, SortOrder,
-    SortValue, SplitIdAndFooterOffsets, SplitSearchError,
+    SortValue, SplitIdAndFooterOffsets, SplitSearchError, SplitSearchProfile,
 };
 use quickwit_query::query_ast::{BoolQuery, QueryAst, QueryAstTransformer, RangeQuery, TermQuery};
 use quickwit_query::tokenizers::TokenizerManager;
 use quickwit_storage::{
-    wrap_storage_with_cache, BundleStorage, MemorySizedCache, OwnedBytes, SplitCache, Storage,
-    StorageResolver,
+    wrap_storage_with_cache, wrap_storage_with_download_counter, BundleStorage, MemorySizedCache,
+    OwnedBytes, SplitCache, Storage, StorageResolver,
 };
 use tantivy::aggregation::agg_req::{AggregationVariants, Aggregations};
 use tantivy::aggregation::AggregationLimitsGuard;
@@ -45,9 +49,11 @@ use tantivy::fastfield::FastFieldReaders;
 use tantivy::schema::Field;
 use tantivy::{DateTime, Index, ReloadPolicy, Searcher, Term};
 use tokio::task::JoinError;
+use tokio::time::Instant;
 use tracing::*;
 
 use crate::collector::{make_collector_for_split, make_merge_collector, IncrementalCollector};
+use crate::completion_suggest::leaf_completion_suggestions;
 use crate::root::is_metadata_count_request_with_ast;
 use crate::service::{deserialize_doc_mapper, SearcherContext};
 use crate::{QuickwitAggregations, SearchError};
@@ -339,6 +345,8 @@ fn get_leaf_resp_from_count(count: u64) -> LeafSearchResponse {
         failed_splits: Vec::new(),
         num_attempted_splits: 1,
         intermediate_aggregation_result: None,
+        suggestions: Vec::new(),
+        split_profiles: Vec::new(),
     }
 }
 
@@ -357,10 +365,20 @@ async fn leaf_search_single_split(
         &split,
         doc_mapper.timestamp_field_name(),
     );
-    if let Some(cached_answer) = searcher_context
+    // The profiling flag is removed from the request so that profiled and regular searches share
+    // the cached responses.
+    let profile = std::mem::take(&mut search_request.profile);
+    if let Some(mut cached_answer) = searcher_context
         .leaf_search_cache
         .get(split.clone(), search_request.clone())
     {
+        if profile {
+            cached_answer.split_profiles = vec![SplitSearchProfile {
+                split_id: split.split_id.clone(),
+                leaf_cache_hit: true,
+                ..Default::default()
+            }];
+        }
         return Ok(cached_answer);
     }
 
@@ -376,6 +394,12 @@ async fn leaf_search_single_split(
     }
 
     let split_id = split.split_id.to_string();
+    let num_bytes_downloaded = Arc::new(AtomicU64::new(0));
+    let storage = if profile {
+        wrap_storage_with_download_counter(storage, num_bytes_downloaded.clone())
+    } else {
+        storage
+    };
     let index = open_index_with_caches(
         searcher_context,
         storage,
@@ -394,22 +418,33 @@ async fn leaf_search_single_split(
 
     let mut collector =
         make_collector_for_split(split_id.clone(), &search_request, aggregations_limits)?;
+    let aggregation_collection_nanos = Arc::new(AtomicU64::new(0));
+    if profile {
+        collector.aggregation_collection_nanos = Some(aggregation_collection_nanos.clone());
+    }
 
     let (query, mut warmup_info) = doc_mapper.query(split_schema.clone(), &query_ast, false)?;
+    let filters_warmup_info = collector.build_filters_weights(&*doc_mapper, &split_schema)?;
 
     let collector_warmup_info = collector.warmup_info();
     warmup_info.merge(collector_warmup_info);
+    warmup_info.merge(filters_warmup_info);
     warmup_info.simplify();
 
+    let warmup_start = Instant::now();
     warmup(&searcher, &warmup_info).await?;
+    let warmup_elapsed = warmup_start.elapsed();
+    let suggestions =
+        leaf_completion_suggestions(&index, &searcher, &search_request.suggesters).await?;
     let span = info_span!("tantivy_search");
 
-    let (search_request, leaf_search_response) = {
+    let (search_request, mut leaf_search_response, search_elapsed) = {
         let split = split.clone();
 
         crate::search_thread_pool()
             .run_cpu_intensive(move || {
                 let _span_guard = span.enter();
+                let search_start = Instant::now();
                 // Our search execution has been scheduled, let's check if we can improve the
                 // request based on the results of the preceding searches
                 check_optimize_search_request(&mut search_request, &split, &split_filter);
@@ -418,15 +453,20 @@ async fn leaf_search_single_split(
                     return Ok((
                         search_request,
                         get_leaf_resp_from_count(searcher.num_docs() as u64),
+                        search_start.elapsed(),
                     ));
                 }
                 if collector.is_count_only() {
                     let count = query.count(&searcher)? as u64;
-                    Ok((search_request, get_leaf_resp_from_count(count)))
+                    Ok((
+                        search_request,
+                        get_leaf_resp_from_count(count),
+                        search_start.elapsed(),
+                    ))
                 } else {
                     searcher
                         .search(&query, &collector)
-                        .map(|resp| (search_request, resp))
+                        .map(|resp| (search_request, resp, search_start.elapsed()))
                 }
             })
             .await
@@ -434,10 +474,22 @@ async fn leaf_search_single_split(
                 crate::SearchError::Internal(format!("leaf search panicked. split={split_id}"))
             })??
     };
+    leaf_search_response.suggestions = suggestions;
 
     searcher_context
         .leaf_search_cache
         .put(split, search_request, leaf_search_response.clone());
+    // The profile is added after caching the response, as it is only valid for this search.
+    if profile {
+        leaf_search_response.split_profiles = vec![SplitSearchProfile {
+            split_id,
+            warmup_micros: warmup_elapsed.as_micros() as u64,
+            search_micros: search_elapsed.as_micros() as u64,
+            aggregation_micros: aggregation_collection_nanos.load(Ordering::Relaxed) / 1_000,
+            num_bytes_downloaded: num_bytes_downloaded.load(Ordering::Relaxed),
+            leaf_cache_hit: false,
+        }];
+    }
     Ok(leaf_search_response)
 }
 
@@ -818,6 +870,11 @@ fn is_simple_all_query(search_request: &SearchRequest) -> bool {
         return false;
     }
 
+    // Collapsed hits are counted by group, not by document.
+    if search_request.collapse.is_some() {
+        return false;
+    }
+
     // TODO: Update the logic to handle start_timestamp end_timestamp ranges
     if search_request.start_timestamp.is_some() || search_request.end_timestamp.is_some() {
         return false;
@@ -857,6 +914,15 @@ impl CanSplitDoBetter {
             }
         }
 
+        // The inner hits of a collapsed group can be worse than the worst top hit.
+        if request
+            .collapse
+            .as_ref()
+            .is_some_and(|collapse| collapse.inner_hits > 1)
+        {
+            return CanSplitDoBetter::Uninformative;
+        }
+
         if request.sort_fields.is_empty() {
             CanSplitDoBetter::SplitIdHigher(None)
         } else if let Some((sort_by, timestamp_field)) =
@@ -1065,6 +1131,11 @@ pub async fn multi_leaf_search(
         .search_request
         .ok_or_else(|| SearchError::Internal("no search request".to_string()))?
         .into();
+    // The root sets the timeout to the time left before its own deadline, so the deadline is
+    // computed once for all the indexes, as soon as the request is received.
+    let deadline_opt: Option<Instant> = search_request
+        .timeout_millis
+        .map(|timeout_millis| Instant::now() + Duration::from_millis(timeout_millis as u64));
 
     let doc_mappers: Vec<Arc<dyn DocMapper>> = leaf_search_request
         .doc_mappers
@@ -1104,7 +1175,9 @@ pub async fn multi_leaf_search(
             })?
             .clone();
 
-        let leaf_request_future = tokio::spawn(
+        // The spawned tasks are aborted if the leaf search request is cancelled, e.g. because the
+        // client of the root search disconnected.
+        let leaf_request_future = AbortOnDropJoinHandle::new(tokio::spawn(
             resolve_storage_and_leaf_search(
                 searcher_context.clone(),
                 search_request.clone(),
@@ -1113,9 +1186,10 @@ pub async fn multi_leaf_search(
                 leaf_search_request_ref.split_offsets,
                 doc_mapper,
                 aggregation_limits.clone(),
+                deadline_opt,
             )
             .in_current_span(),
-        );
+        ));
         leaf_request_tasks.push(leaf_request_future);
     }
 
@@ -1136,6 +1210,7 @@ pub async fn multi_leaf_search(
                     split_id: "unknown".to_string(),
                     error: format!("{}", err),
                     retryable_error: true,
+                    timed_out: false,
                 });
             }
         }
@@ -1158,6 +1233,7 @@ async fn resolve_storage_and_leaf_search(
     splits: Vec<SplitIdAndFooterOffsets>,
     doc_mapper: Arc<dyn DocMapper>,
     aggregations_limits: AggregationLimitsGuard,
+    deadline_opt: Option<Instant>,
 ) -> crate::Result<LeafSearchResponse> {
     let storage = storage_resolver.resolve(&index_uri).await?;
 
@@ -1168,6 +1244,7 @@ async fn resolve_storage_and_leaf_search(
         splits,
         doc_mapper,
         aggregations_limits,
+        deadline_opt,
     )
     .await
 }
@@ -1201,6 +1278,8 @@ fn disable_search_request_hits(search_request: &mut SearchRequest) {
 /// [PartialHit](quickwit_proto::search::PartialHit) candidates. The root will be in
 /// charge to consolidate, identify the actual final top hits to display, and
 /// fetch the actual documents to convert the partial hits into actual Hits.
+///
+/// The splits that cannot be searched before `deadline_opt` are reported as timed out.
 #[instrument(skip_all, fields(index = ?request.index_id_patterns))]
 pub async fn leaf_search(
     searcher_context: Arc<SearcherContext>,
@@ -1209,6 +1288,7 @@ pub async fn leaf_search(
     splits: Vec<SplitIdAndFooterOffsets>,
     doc_mapper: Arc<dyn DocMapper>,
     aggregations_limits: AggregationLimitsGuard,
+    deadline_opt: Option<Instant>,
 ) -> Result<LeafSearchResponse, SearchError> {
     info!(splits_num = splits.len(), split_offsets = ?PrettySample::new(&splits, 5));
 
@@ -1223,29 +1303,42 @@ pub async fn leaf_search(
 
     let split_filter = Arc::new(RwLock::new(split_filter));
 
-    let mut leaf_search_single_split_join_handles: Vec<(String, tokio::task::JoinHandle<()>)> =
+    let mut leaf_search_single_split_join_handles: Vec<(String, AbortOnDropJoinHandle<()>)> =
         Vec::with_capacity(split_with_req.len());
 
     let merge_collector = make_merge_collector(&request, &aggregations_limits)?;
     let incremental_merge_collector = IncrementalCollector::new(merge_collector);
     let incremental_merge_collector = Arc::new(Mutex::new(incremental_merge_collector));
 
+    // Splits that could not be searched before the deadline.
+    let mut timed_out_splits: Vec<String> = Vec::new();
+
     for (split, mut request) in split_with_req {
-        let leaf_split_search_permit = searcher_context.leaf_search_split_semaphore
-            .clone()
-            .acquire_owned()
-            .instrument(info_span!("waiting_for_leaf_search_split_semaphore"))
-            .await
-            .expect("Failed to acquire permit. This should never happen! Please, report on https://github.com/quickwit-oss/quickwit/issues.");
+        let leaf_split_search_permit_opt = if is_deadline_reached(deadline_opt) {
+            None
+        } else {
+            let leaf_split_search_permit_fut = searcher_context
+                .leaf_search_split_semaphore
+                .clone()
+                .acquire_owned()
+                .instrument(info_span!("waiting_for_leaf_search_split_semaphore"));
+            run_until_deadline(deadline_opt, leaf_split_search_permit_fut)
+                .await
+                .map(|permit_res| permit_res.expect("Failed to acquire permit. This should never happen! Please, report on https://github.com/quickwit-oss/quickwit/issues."))
+        };
 
         let can_be_better = check_optimize_search_request(&mut request, &split, &split_filter);
         if !can_be_better && !run_all_splits {
             continue;
         }
+        let Some(leaf_split_search_permit) = leaf_split_search_permit_opt else {
+            timed_out_splits.push(split.split_id);
+            continue;
+        };
 
         leaf_search_single_split_join_handles.push((
             split.split_id.clone(),
-            tokio::spawn(
+            AbortOnDropJoinHandle::new(tokio::spawn(
                 leaf_search_single_split_wrapper(
                     request,
                     searcher_context.clone(),
@@ -1258,7 +1351,7 @@ pub async fn leaf_search(
                     aggregations_limits.clone(),
                 )
                 .in_current_span(),
-            ),
+            )),
         ));
     }
 
@@ -1268,8 +1361,13 @@ pub async fn leaf_search(
 
     // There is no need to use `join_all`, as these are spawned tasks.
     for (split, leaf_search_join_handle) in leaf_search_single_split_join_handles {
+        // Dropping the join handle of a split that is still running at the deadline aborts it.
+        let Some(join_res) = run_until_deadline(deadline_opt, leaf_search_join_handle).await else {
+            timed_out_splits.push(split);
+            continue;
+        };
         // splits that did not panic were already added to the collector
-        if let Err(join_error) = leaf_search_join_handle.await {
+        if let Err(join_error) = join_res {
             if join_error.is_cancelled() {
                 // An explicit task cancellation is not an error.
                 continue;
@@ -1294,6 +1392,21 @@ pub async fn leaf_search(
             split_id,
             error: SearchError::from(split_search_join_error).to_string(),
             retryable_error: true,
+            timed_out: false,
+        });
+    }
+    if !timed_out_splits.is_empty() {
+        warn!(
+            num_timed_out_splits = timed_out_splits.len(),
+            "leaf search timed out before all the splits were searched"
+        );
+    }
+    for split_id in timed_out_splits {
+        incremental_merge_collector.add_failed_split(SplitSearchError {
+            split_id,
+            error: "search timed out".to_string(),
+            retryable_error: false,
+            timed_out: true,
         });
     }
 
@@ -1306,6 +1419,20 @@ pub async fn leaf_search(
     Ok(result)
 }
 
+fn is_deadline_reached(deadline_opt: Option<Instant>) -> bool {
+    deadline_opt.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// Runs the future until completion or until the deadline, if any, is reached. Returns `None`
+/// and drops the future in the latter case.
+async fn run_until_deadline<F: Future>(deadline_opt: Option<Instant>, fut: F) -> Option<F::Output> {
+    if let Some(deadline) = deadline_opt {
+        tokio::time::timeout_at(deadline, fut).await.ok()
+    } else {
+        Some(fut.await)
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 #[instrument(skip_all, fields(split_id = split.split_id))]
 async fn leaf_search_single_split_wrapper(
@@ -1349,6 +1476,7 @@ async fn leaf_search_single_split_wrapper(
                     split_id: split.split_id.clone(),
                     error: format!("Error parsing aggregation result: {err}"),
                     retryable_error: true,
+                    timed_out: false,
                 });
             }
         }
@@ -1356,6 +1484,7 @@ async fn leaf_search_single_split_wrapper(
             split_id: split.split_id.clone(),
             error: format!("{err}"),
             retryable_error: true,
+            timed_out: false,
         }),
     }
     if let Some(last_hit) = locked_incremental_merge_collector.peek_worst_hit() {
@@ -250,7 +250,10 @@ mod tests {
                 sort_value: Some(SortValue::U64(0u64).into()),
                 sort_value2: None,
                 split_id: "split_1".to_string(),
+                collapse_value: None,
             }],
+            suggestions: Vec::new(),
+            split_profiles: Vec::new(),
         };
 
         assert!(cache.get(split_1.clone(), query_1.clone()).is_none());
@@ -339,7 +342,10 @@ mod tests {
                 sort_value: Some(SortValue::U64(0).into()),
                 sort_value2: None,
                 split_id: "split_1".to_string(),
+                collapse_value: None,
             }],
+            suggestions: Vec::new(),
+            split_profiles: Vec::new(),
         };
 
         // for split_1, 1 and 1bis cover different timestamp ranges
@@ -22,25 +22,40 @@
 #![allow(clippy::bool_assert_comparison)]
 #![deny(clippy::disallowed_methods)]
 
+mod admission_control;
+mod async_search;
+mod bloom_filter_pruning;
 mod client;
 mod cluster_client;
+mod collapse;
 mod collector;
+mod completion_suggest;
 mod error;
+mod extended_aggregations;
 mod fetch_docs;
+mod field_value_range_pruning;
 mod filters;
+mod filters_aggregation;
 mod find_trace_ids_collector;
+mod geo_grid_aggregation;
+mod hedging;
+mod ip_range_aggregation;
 mod leaf;
 mod leaf_cache;
 mod list_fields;
 mod list_fields_cache;
 mod list_terms;
+mod pipeline_aggregations;
+mod point_in_time;
 mod retry;
 mod root;
 mod scroll_context;
 mod search_job_placer;
 mod search_response_rest;
+mod search_result_cache;
 mod search_stream;
 mod service;
+mod significant_terms_aggregation;
 pub(crate) mod top_k_collector;
 
 mod metrics;
@@ -77,6 +92,7 @@ use quickwit_storage::StorageResolver;
 pub use service::SearcherContext;
 use tantivy::DocAddress;
 
+pub use crate::async_search::AsyncSearchResponse;
 pub use crate::client::{
     create_search_client_from_channel, create_search_client_from_grpc_addr, SearchServiceClient,
 };
@@ -88,7 +104,10 @@ pub use crate::root::{
     IndexMetasForLeafSearch, SearchJob,
 };
 pub use crate::search_job_placer::{Job, SearchJobPlacer};
-pub use crate::search_response_rest::{SearchPlanResponseRest, SearchResponseRest};
+pub use crate::search_response_rest::{
+    decode_search_after_cursor, encode_search_after_cursor, SearchPlanResponseRest,
+    SearchResponseRest,
+};
 pub use crate::search_stream::root_search_stream;
 pub use crate::service::{MockSearchService, SearchService, SearchServiceImpl};
 
@@ -185,18 +204,15 @@ pub async fn list_all_splits(
     list_relevant_splits(index_uids, None, None, None, metastore).await
 }
 
-/// Extract the list of relevant splits for a given request.
-pub async fn list_relevant_splits(
+/// Builds the query listing the splits of the given indexes that match the time range and tags
+/// filter. Returns `None` if `index_uids` is empty.
+pub(crate) fn relevant_splits_query(
     index_uids: Vec<IndexUid>,
     start_timestamp: Option<i64>,
     end_timestamp: Option<i64>,
     tags_filter_opt: Option<TagFilterAst>,
-    metastore: &mut MetastoreServiceClient,
-) -> crate::Result<Vec<SplitMetadata>> {
-    let Some(mut query) = ListSplitsQuery::try_from_index_uids(index_uids) else {
-        return Ok(Vec::new());
-    };
-    query = query.with_split_state(SplitState::Published);
+) -> Option<ListSplitsQuery> {
+    let mut query = ListSplitsQuery::try_from_index_uids(index_uids)?;
 
     if let Some(start_ts) = start_timestamp {
         query = query.with_time_range_start_gte(start_ts);
@@ -207,6 +223,23 @@ pub async fn list_relevant_splits(
     if let Some(tags_filter) = tags_filter_opt {
         query = query.with_tags_filter(tags_filter);
     }
+    Some(query)
+}
+
+/// Extract the list of relevant splits for a given request.
+pub async fn list_relevant_splits(
+    index_uids: Vec<IndexUid>,
+    start_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+    tags_filter_opt: Option<TagFilterAst>,
+    metastore: &mut MetastoreServiceClient,
+) -> crate::Result<Vec<SplitMetadata>> {
+    let Some(query) =
+        relevant_splits_query(index_uids, start_timestamp, end_timestamp, tags_filter_opt)
+    else {
+        return Ok(Vec::new());
+    };
+    let query = query.with_split_state(SplitState::Published);
     let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
     let splits_metadata: Vec<SplitMetadata> = metastore
         .list_splits(list_splits_request)
@@ -263,7 +296,10 @@ pub async fn start_searcher_service(
     search_job_placer: SearchJobPlacer,
     searcher_context: Arc<SearcherContext>,
 ) -> anyhow::Result<Arc<dyn SearchService>> {
-    let cluster_client = ClusterClient::new(search_job_placer);
+    let mut cluster_client = ClusterClient::new(search_job_placer);
+    if let Some(hedging_config) = searcher_context.searcher_config.leaf_search_hedging {
+        cluster_client = cluster_client.with_leaf_search_hedging(hedging_config);
+    }
     let search_service = Arc::new(SearchServiceImpl::new(
         metastore,
         storage_resolver,
@@ -280,6 +316,15 @@ pub async fn single_node_search(
     metastore: MetastoreServiceClient,
     storage_resolver: StorageResolver,
 ) -> crate::Result<SearchResponse> {
+    let search_service = single_node_search_service(metastore, storage_resolver);
+    search_service.root_search(search_request).await
+}
+
+/// Creates a search service running on a single-node cluster made of the current node.
+pub(crate) fn single_node_search_service(
+    metastore: MetastoreServiceClient,
+    storage_resolver: StorageResolver,
+) -> Arc<SearchServiceImpl> {
     let socket_addr = SocketAddr::new(Ipv4Addr::new(127, 0, 0, 1).into(), 7280u16);
     let searcher_pool = SearcherPool::default();
     let search_job_placer = SearchJobPlacer::new(searcher_pool.clone());
@@ -287,7 +332,7 @@ pub async fn single_node_search(
     let searcher_config = SearcherConfig::default();
     let searcher_context = Arc::new(SearcherContext::new(searcher_config, None));
     let search_service = Arc::new(SearchServiceImpl::new(
-        metastore.clone(),
+        metastore,
         storage_resolver,
         cluster_client.clone(),
         searcher_context.clone(),
@@ -295,13 +340,7 @@ pub async fn single_node_search(
     let search_service_client =
         SearchServiceClient::from_service(search_service.clone(), socket_addr);
     searcher_pool.insert(socket_addr, search_service_client);
-    root_search(
-        &searcher_context,
-        search_request,
-        metastore,
-        &cluster_client,
-    )
-    .await
+    search_service
 }
 
 /// Creates a tantivy Term from a &str.
@@ -380,6 +380,7 @@ pub async fn leaf_list_terms(
             split_id,
             error: err.to_string(),
             retryable_error: true,
+            timed_out: false,
         })
         .collect();
     let merged_search_response = LeafListTermsResponse {
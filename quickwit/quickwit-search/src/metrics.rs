@@ -21,14 +21,19 @@
 
 use once_cell::sync::Lazy;
 use quickwit_common::metrics::{
-    exponential_buckets, new_counter, new_counter_vec, new_histogram, Histogram, IntCounter,
-    IntCounterVec,
+    exponential_buckets, new_counter, new_counter_vec, new_gauge_vec, new_histogram, Histogram,
+    IntCounter, IntCounterVec, IntGaugeVec,
 };
 
 pub struct SearchMetrics {
     pub leaf_searches_splits_total: IntCounter,
     pub leaf_search_split_duration_secs: Histogram,
     pub job_assigned_total: IntCounterVec<1>,
+    pub leaf_search_hedged_requests_total: IntCounter,
+    pub leaf_search_hedge_wins_total: IntCounter,
+    pub leaf_search_hedges_throttled_total: IntCounter,
+    pub admission_queued_searches: IntGaugeVec<1>,
+    pub admission_rejected_searches_total: IntCounterVec<1>,
 }
 
 impl Default for SearchMetrics {
@@ -54,6 +59,42 @@ impl Default for SearchMetrics {
                 &[],
                 ["affinity"],
             ),
+            leaf_search_hedged_requests_total: new_counter(
+                "leaf_search_hedged_requests_total",
+                "Number of leaf search requests duplicated to another searcher because they were \
+                 slower than most recent requests.",
+                "search",
+                &[],
+            ),
+            leaf_search_hedge_wins_total: new_counter(
+                "leaf_search_hedge_wins_total",
+                "Number of hedged leaf search requests for which the duplicate request responded \
+                 first.",
+                "search",
+                &[],
+            ),
+            leaf_search_hedges_throttled_total: new_counter(
+                "leaf_search_hedges_throttled_total",
+                "Number of slow leaf search requests that were not hedged because the hedge \
+                 budget was exhausted.",
+                "search",
+                &[],
+            ),
+            admission_queued_searches: new_gauge_vec(
+                "admission_queued_searches",
+                "Number of root searches waiting to be admitted, per priority.",
+                "search",
+                &[],
+                ["priority"],
+            ),
+            admission_rejected_searches_total: new_counter_vec(
+                "admission_rejected_searches_total",
+                "Number of root searches rejected because they could not be admitted within the \
+                 maximum queue duration, per priority.",
+                "search",
+                &[],
+                ["priority"],
+            ),
         }
     }
 }
@@ -0,0 +1,1116 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Pipeline aggregations evaluated by the root searcher.
+//!
+//! Pipeline aggregations compute new values from the output of other aggregations rather than
+//! from documents. They are removed from the aggregation request before it is sent to the leaves,
+//! and evaluated over the buckets of their parent aggregation once the aggregation results are
+//! final.
+
+use std::collections::{BTreeMap, HashMap};
+
+use quickwit_proto::search::SearchRequest;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use crate::SearchError;
+
+const PIPELINE_AGGREGATION_TYPES: [&str; 4] =
+    ["bucket_script", "cumulative_sum", "derivative", "moving_fn"];
+
+/// Policy applied when a bucket has no value for a `buckets_path`.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum GapPolicy {
+    /// The bucket is ignored.
+    #[default]
+    Skip,
+    /// The missing value is replaced by zero.
+    InsertZeros,
+    /// Same as `Skip`, since only missing values are gaps.
+    KeepValues,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum ScriptRequest {
+    Source(String),
+    Script {
+        source: String,
+        #[serde(default)]
+        #[allow(dead_code)]
+        lang: Option<String>,
+    },
+}
+
+impl ScriptRequest {
+    fn source(&self) -> &str {
+        match self {
+            ScriptRequest::Source(source) => source,
+            ScriptRequest::Script { source, .. } => source,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PipelineAggregationRequest {
+    BucketScript(BucketScriptRequest),
+    CumulativeSum(CumulativeSumRequest),
+    Derivative(DerivativeRequest),
+    MovingFn(MovingFnRequest),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BucketScriptRequest {
+    buckets_path: BTreeMap<String, String>,
+    script: ScriptRequest,
+    #[serde(default)]
+    gap_policy: GapPolicy,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CumulativeSumRequest {
+    buckets_path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DerivativeRequest {
+    buckets_path: String,
+    #[serde(default)]
+    gap_policy: GapPolicy,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MovingFnRequest {
+    buckets_path: String,
+    #[serde(deserialize_with = "deserialize_usize_lenient")]
+    window: usize,
+    script: ScriptRequest,
+    #[serde(default)]
+    shift: i64,
+    #[serde(default)]
+    gap_policy: GapPolicy,
+}
+
+/// Deserializes an integer that may be sent as a string, as some clients do.
+fn deserialize_usize_lenient<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where D: Deserializer<'de> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum UsizeOrString {
+        Usize(usize),
+        String(String),
+    }
+    match UsizeOrString::deserialize(deserializer)? {
+        UsizeOrString::Usize(value) => Ok(value),
+        UsizeOrString::String(value) => value.trim().parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PipelineAggregation {
+    BucketScript {
+        buckets_path: BTreeMap<String, String>,
+        script: ScriptExpr,
+        gap_policy: GapPolicy,
+    },
+    CumulativeSum {
+        buckets_path: String,
+    },
+    Derivative {
+        buckets_path: String,
+        gap_policy: GapPolicy,
+    },
+    MovingFn {
+        buckets_path: String,
+        window: usize,
+        shift: i64,
+        function: MovingFunction,
+        gap_policy: GapPolicy,
+    },
+}
+
+impl PipelineAggregation {
+    fn parse(name: &str, aggregation: JsonValue) -> crate::Result<Self> {
+        let invalid_request = |reason: String| {
+            SearchError::InvalidAggregationRequest(format!(
+                "invalid pipeline aggregation `{name}`: {reason}"
+            ))
+        };
+        let pipeline_aggregation_request: PipelineAggregationRequest =
+            serde_json::from_value(aggregation)
+                .map_err(|error| invalid_request(error.to_string()))?;
+        let pipeline_aggregation = match pipeline_aggregation_request {
+            PipelineAggregationRequest::BucketScript(request) => {
+                let script = ScriptExpr::parse(request.script.source()).map_err(invalid_request)?;
+                for variable in script.variables() {
+                    if !request.buckets_path.contains_key(variable) {
+                        return Err(invalid_request(format!(
+                            "variable `{variable}` is not defined in `buckets_path`"
+                        )));
+                    }
+                }
+                PipelineAggregation::BucketScript {
+                    buckets_path: request.buckets_path,
+                    script,
+                    gap_policy: request.gap_policy,
+                }
+            }
+            PipelineAggregationRequest::CumulativeSum(request) => {
+                PipelineAggregation::CumulativeSum {
+                    buckets_path: request.buckets_path,
+                }
+            }
+            PipelineAggregationRequest::Derivative(request) => PipelineAggregation::Derivative {
+                buckets_path: request.buckets_path,
+                gap_policy: request.gap_policy,
+            },
+            PipelineAggregationRequest::MovingFn(request) => {
+                if request.window == 0 {
+                    return Err(invalid_request("`window` must be positive".to_string()));
+                }
+                let function =
+                    MovingFunction::parse(request.script.source()).map_err(invalid_request)?;
+                PipelineAggregation::MovingFn {
+                    buckets_path: request.buckets_path,
+                    window: request.window,
+                    shift: request.shift,
+                    function,
+                    gap_policy: request.gap_policy,
+                }
+            }
+        };
+        Ok(pipeline_aggregation)
+    }
+
+    fn buckets_paths(&self) -> Vec<&str> {
+        match self {
+            PipelineAggregation::BucketScript { buckets_path, .. } => {
+                buckets_path.values().map(String::as_str).collect()
+            }
+            PipelineAggregation::CumulativeSum { buckets_path }
+            | PipelineAggregation::Derivative { buckets_path, .. }
+            | PipelineAggregation::MovingFn { buckets_path, .. } => vec![buckets_path.as_str()],
+        }
+    }
+
+    /// Returns true if the aggregation depends on the order of the buckets.
+    fn is_sequential(&self) -> bool {
+        !matches!(self, PipelineAggregation::BucketScript { .. })
+    }
+
+    /// Computes the value of the aggregation for each bucket. `None` means that the bucket has no
+    /// value for the aggregation.
+    fn evaluate(&self, buckets: &[&mut JsonValue]) -> Vec<Option<f64>> {
+        match self {
+            PipelineAggregation::BucketScript {
+                buckets_path,
+                script,
+                gap_policy,
+            } => buckets
+                .iter()
+                .map(|bucket| {
+                    let mut variables = HashMap::with_capacity(buckets_path.len());
+                    for (variable, path) in buckets_path {
+                        let value =
+                            apply_gap_policy(resolve_buckets_path(bucket, path), *gap_policy)?;
+                        variables.insert(variable.as_str(), value);
+                    }
+                    Some(script.eval(&variables))
+                })
+                .collect(),
+            PipelineAggregation::CumulativeSum { buckets_path } => {
+                let mut sum = 0.0;
+                buckets
+                    .iter()
+                    .map(|bucket| {
+                        sum += resolve_buckets_path(bucket, buckets_path).unwrap_or(0.0);
+                        Some(sum)
+                    })
+                    .collect()
+            }
+            PipelineAggregation::Derivative {
+                buckets_path,
+                gap_policy,
+            } => {
+                let mut previous_value_opt: Option<f64> = None;
+                buckets
+                    .iter()
+                    .map(|bucket| {
+                        let value = apply_gap_policy(
+                            resolve_buckets_path(bucket, buckets_path),
+                            *gap_policy,
+                        )?;
+                        let derivative_opt =
+                            previous_value_opt.map(|previous_value| value - previous_value);
+                        previous_value_opt = Some(value);
+                        derivative_opt
+                    })
+                    .collect()
+            }
+            PipelineAggregation::MovingFn {
+                buckets_path,
+                window,
+                shift,
+                function,
+                gap_policy,
+            } => {
+                let values: Vec<Option<f64>> = buckets
+                    .iter()
+                    .map(|bucket| {
+                        apply_gap_policy(resolve_buckets_path(bucket, buckets_path), *gap_policy)
+                    })
+                    .collect();
+                let num_buckets = values.len() as i64;
+                (0..num_buckets)
+                    .map(|bucket_idx| {
+                        let end = (bucket_idx + shift).clamp(0, num_buckets);
+                        let start = (bucket_idx + shift - *window as i64).clamp(0, end);
+                        let window_values: Vec<f64> = values[start as usize..end as usize]
+                            .iter()
+                            .flatten()
+                            .copied()
+                            .collect();
+                        Some(function.eval(&window_values))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn apply_gap_policy(value_opt: Option<f64>, gap_policy: GapPolicy) -> Option<f64> {
+    match (value_opt, gap_policy) {
+        (Some(value), _) if value.is_finite() => Some(value),
+        (_, GapPolicy::InsertZeros) => Some(0.0),
+        _ => None,
+    }
+}
+
+/// Resolves a `buckets_path` relative to a bucket.
+///
+/// The syntax is `AGG_NAME[>AGG_NAME]*[.METRIC]`, where the metric can also be written
+/// `AGG_NAME[METRIC]` (e.g. `load_time_percentiles[99.9]`). The special path `_count` refers to
+/// the document count of the bucket.
+fn resolve_buckets_path(bucket: &JsonValue, buckets_path: &str) -> Option<f64> {
+    let mut segments: Vec<&str> = buckets_path.split('>').collect();
+    let last_segment = segments.pop()?;
+    let mut aggregation_result = bucket;
+    for segment in segments {
+        aggregation_result = aggregation_result.get(segment)?;
+    }
+    if last_segment == "_count" {
+        return aggregation_result.get("doc_count")?.as_f64();
+    }
+    if last_segment == "_key" {
+        return aggregation_result.get("key")?.as_f64();
+    }
+    let (aggregation_name, metric_opt) = split_metric(last_segment);
+    let aggregation_result = aggregation_result.get(aggregation_name)?;
+    let Some(metric) = metric_opt else {
+        return aggregation_result.get("value")?.as_f64();
+    };
+    if let Some(value) = aggregation_result.get(metric) {
+        return value.as_f64();
+    }
+    // Percentiles are returned as a map keyed by percent, or as a list of key/value objects.
+    let metric_key: f64 = metric.parse().ok()?;
+    match aggregation_result.get("values")? {
+        JsonValue::Object(values) => values
+            .iter()
+            .find(|(key, _)| key.parse::<f64>().ok() == Some(metric_key))?
+            .1
+            .as_f64(),
+        JsonValue::Array(values) => values
+            .iter()
+            .find(|value| value.get("key").and_then(JsonValue::as_f64) == Some(metric_key))?
+            .get("value")?
+            .as_f64(),
+        _ => None,
+    }
+}
+
+fn split_metric(segment: &str) -> (&str, Option<&str>) {
+    if let Some((aggregation_name, metric)) = segment.split_once('[') {
+        return (aggregation_name, Some(metric.trim_end_matches(']')));
+    }
+    match segment.split_once('.') {
+        Some((aggregation_name, metric)) => (aggregation_name, Some(metric)),
+        None => (segment, None),
+    }
+}
+
+/// Returns the name of the aggregation a `buckets_path` starts with.
+fn buckets_path_root(buckets_path: &str) -> &str {
+    let first_segment = buckets_path.split('>').next().unwrap_or_default();
+    split_metric(first_segment).0
+}
+
+/// The functions supported in the script of a `moving_fn` aggregation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MovingFunction {
+    Max,
+    Min,
+    Sum,
+    UnweightedAvg,
+    LinearWeightedAvg,
+    StdDev,
+}
+
+impl MovingFunction {
+    fn parse(script: &str) -> Result<Self, String> {
+        let script: String = script.chars().filter(|ch| !ch.is_whitespace()).collect();
+        let script = script.strip_prefix("return").unwrap_or(&script);
+        let moving_function = match script.trim_end_matches(';') {
+            "MovingFunctions.max(values)" => MovingFunction::Max,
+            "MovingFunctions.min(values)" => MovingFunction::Min,
+            "MovingFunctions.sum(values)" => MovingFunction::Sum,
+            "MovingFunctions.unweightedAvg(values)" => MovingFunction::UnweightedAvg,
+            "MovingFunctions.linearWeightedAvg(values)" => MovingFunction::LinearWeightedAvg,
+            "MovingFunctions.stdDev(values,MovingFunctions.unweightedAvg(values))" => {
+                MovingFunction::StdDev
+            }
+            _ => {
+                return Err(format!(
+                    "unsupported script `{script}`, only the `max`, `min`, `sum`, \
+                     `unweightedAvg`, `linearWeightedAvg`, and `stdDev` moving functions are \
+                     supported"
+                ));
+            }
+        };
+        Ok(moving_function)
+    }
+
+    fn eval(&self, values: &[f64]) -> f64 {
+        let num_values = values.len() as f64;
+        let unweighted_avg = || values.iter().sum::<f64>() / num_values;
+        match self {
+            MovingFunction::Max => values.iter().copied().reduce(f64::max).unwrap_or(f64::NAN),
+            MovingFunction::Min => values.iter().copied().reduce(f64::min).unwrap_or(f64::NAN),
+            MovingFunction::Sum => values.iter().sum(),
+            MovingFunction::UnweightedAvg => unweighted_avg(),
+            MovingFunction::LinearWeightedAvg => {
+                // The oldest value has a weight of 1, the most recent a weight of `values.len()`.
+                let weighted_sum: f64 = values
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, value)| (idx + 1) as f64 * value)
+                    .sum();
+                weighted_sum / (num_values * (num_values + 1.0) / 2.0)
+            }
+            MovingFunction::StdDev => {
+                let avg = unweighted_avg();
+                let variance = values
+                    .iter()
+                    .map(|value| (value - avg) * (value - avg))
+                    .sum::<f64>()
+                    / num_values;
+                variance.sqrt()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+/// An arithmetic expression, the subset of the scripting language supported by the
+/// `bucket_script` aggregation. Variables are written either `params.var` or `var`.
+#[derive(Debug, Clone, PartialEq)]
+enum ScriptExpr {
+    Number(f64),
+    Variable(String),
+    Neg(Box<ScriptExpr>),
+    Binary(BinaryOperator, Box<ScriptExpr>, Box<ScriptExpr>),
+}
+
+impl ScriptExpr {
+    fn parse(script: &str) -> Result<Self, String> {
+        let script = script.trim();
+        let script = script.strip_prefix("return ").unwrap_or(script);
+        let script = script.trim_end().trim_end_matches(';');
+        let mut parser = ScriptParser {
+            chars: script.chars().collect(),
+            pos: 0,
+        };
+        let expr = parser.parse_expr()?;
+        parser.skip_whitespace();
+        if parser.pos < parser.chars.len() {
+            return Err(format!(
+                "unexpected character `{}` in script `{script}`",
+                parser.chars[parser.pos]
+            ));
+        }
+        Ok(expr)
+    }
+
+    fn variables(&self) -> Vec<&str> {
+        let mut variables = Vec::new();
+        let mut stack = vec![self];
+        while let Some(expr) = stack.pop() {
+            match expr {
+                ScriptExpr::Number(_) => {}
+                ScriptExpr::Variable(variable) => variables.push(variable.as_str()),
+                ScriptExpr::Neg(operand) => stack.push(operand),
+                ScriptExpr::Binary(_, left, right) => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+        variables
+    }
+
+    fn eval(&self, variables: &HashMap<&str, f64>) -> f64 {
+        match self {
+            ScriptExpr::Number(value) => *value,
+            ScriptExpr::Variable(variable) => variables
+                .get(variable.as_str())
+                .copied()
+                .unwrap_or(f64::NAN),
+            ScriptExpr::Neg(operand) => -operand.eval(variables),
+            ScriptExpr::Binary(operator, left, right) => {
+                let left = left.eval(variables);
+                let right = right.eval(variables);
+                match operator {
+                    BinaryOperator::Add => left + right,
+                    BinaryOperator::Sub => left - right,
+                    BinaryOperator::Mul => left * right,
+                    BinaryOperator::Div => left / right,
+                    BinaryOperator::Rem => left % right,
+                }
+            }
+        }
+    }
+}
+
+struct ScriptParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl ScriptParser {
+    fn skip_whitespace(&mut self) {
+        while self
+            .chars
+            .get(self.pos)
+            .is_some_and(|ch| ch.is_whitespace())
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn parse_expr(&mut self) -> Result<ScriptExpr, String> {
+        let mut expr = self.parse_term()?;
+        loop {
+            let operator = match self.peek() {
+                Some('+') => BinaryOperator::Add,
+                Some('-') => BinaryOperator::Sub,
+                _ => return Ok(expr),
+            };
+            self.pos += 1;
+            let right = self.parse_term()?;
+            expr = ScriptExpr::Binary(operator, Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<ScriptExpr, String> {
+        let mut expr = self.parse_unary()?;
+        loop {
+            let operator = match self.peek() {
+                Some('*') => BinaryOperator::Mul,
+                Some('/') => BinaryOperator::Div,
+                Some('%') => BinaryOperator::Rem,
+                _ => return Ok(expr),
+            };
+            self.pos += 1;
+            let right = self.parse_unary()?;
+            expr = ScriptExpr::Binary(operator, Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<ScriptExpr, String> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(ScriptExpr::Neg(Box::new(self.parse_unary()?)))
+            }
+            Some('+') => {
+                self.pos += 1;
+                self.parse_unary()
+            }
+            _ => self.parse_primary(),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<ScriptExpr, String> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let expr = self.parse_expr()?;
+                if self.peek() != Some(')') {
+                    return Err("missing closing parenthesis in script".to_string());
+                }
+                self.pos += 1;
+                Ok(expr)
+            }
+            Some(ch) if ch.is_ascii_digit() || ch == '.' => {
+                let number = self.take_while(|ch| ch.is_ascii_digit() || ch == '.');
+                let value: f64 = number
+                    .parse()
+                    .map_err(|_| format!("invalid number `{number}` in script"))?;
+                Ok(ScriptExpr::Number(value))
+            }
+            Some(ch) if ch.is_alphabetic() || ch == '_' => {
+                let identifier =
+                    self.take_while(|ch| ch.is_alphanumeric() || ch == '_' || ch == '.');
+                let variable = identifier.strip_prefix("params.").unwrap_or(&identifier);
+                if variable.is_empty() || variable.contains('.') {
+                    return Err(format!("unsupported identifier `{identifier}` in script"));
+                }
+                Ok(ScriptExpr::Variable(variable.to_string()))
+            }
+            Some(ch) => Err(format!("unexpected character `{ch}` in script")),
+            None => Err("unexpected end of script".to_string()),
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|ch| predicate(*ch)) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+}
+
+/// The pipeline aggregations of an aggregation request, organized like the aggregations they are
+/// nested in.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct PipelineAggregations {
+    /// The pipeline aggregations evaluated over the buckets of the parent aggregation, in
+    /// evaluation order.
+    pipeline_aggregations: Vec<(String, PipelineAggregation)>,
+    /// The sub-aggregations containing pipeline aggregations.
+    sub_aggregations: BTreeMap<String, PipelineAggregations>,
+}
+
+impl PipelineAggregations {
+    /// Removes the pipeline aggregations from the aggregation request of a search request.
+    ///
+    /// Returns `None` if the request has no pipeline aggregations. Requests that are not valid
+    /// JSON objects are left untouched and rejected later on by the request validation.
+    pub(crate) fn extract_from_search_request(
+        search_request: &mut SearchRequest,
+    ) -> crate::Result<Option<Self>> {
+        let Some(aggregation_request) = &search_request.aggregation_request else {
+            return Ok(None);
+        };
+        let Ok(mut aggregations) =
+            serde_json::from_str::<JsonMap<String, JsonValue>>(aggregation_request)
+        else {
+            return Ok(None);
+        };
+        let pipeline_aggregations = Self::extract(&mut aggregations, None)?;
+        if pipeline_aggregations.is_empty() {
+            return Ok(None);
+        }
+        search_request.aggregation_request = Some(serde_json::to_string(&aggregations)?);
+        Ok(Some(pipeline_aggregations))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pipeline_aggregations.is_empty() && self.sub_aggregations.is_empty()
+    }
+
+    /// `parent_is_keyed_opt` is `None` for the top-level aggregations, and tells whether the
+    /// buckets of the parent aggregation are keyed otherwise.
+    fn extract(
+        aggregations: &mut JsonMap<String, JsonValue>,
+        parent_is_keyed_opt: Option<bool>,
+    ) -> crate::Result<Self> {
+        let sibling_names: Vec<String> = aggregations.keys().cloned().collect();
+        let pipeline_names: Vec<String> = aggregations
+            .iter()
+            .filter(|(_, aggregation)| is_pipeline_aggregation(aggregation))
+            .map(|(name, _)| name.clone())
+            .collect();
+        let mut pipeline_aggregations = Vec::with_capacity(pipeline_names.len());
+
+        for name in pipeline_names {
+            let aggregation = aggregations
+                .remove(&name)
+                .expect("aggregation should exist");
+            let Some(parent_is_keyed) = parent_is_keyed_opt else {
+                return Err(SearchError::InvalidAggregationRequest(format!(
+                    "pipeline aggregation `{name}` must be nested in a bucket aggregation"
+                )));
+            };
+            let pipeline_aggregation = PipelineAggregation::parse(&name, aggregation)?;
+            if parent_is_keyed && pipeline_aggregation.is_sequential() {
+                return Err(SearchError::InvalidAggregationRequest(format!(
+                    "pipeline aggregation `{name}` cannot be nested in an aggregation with keyed \
+                     buckets"
+                )));
+            }
+            for buckets_path in pipeline_aggregation.buckets_paths() {
+                let root = buckets_path_root(buckets_path);
+                if !matches!(root, "_count" | "_key")
+                    && !sibling_names
+                        .iter()
+                        .any(|sibling_name| sibling_name == root)
+                {
+                    return Err(SearchError::InvalidAggregationRequest(format!(
+                        "invalid `buckets_path` `{buckets_path}` in pipeline aggregation \
+                         `{name}`: no aggregation named `{root}`"
+                    )));
+                }
+            }
+            pipeline_aggregations.push((name, pipeline_aggregation));
+        }
+        let pipeline_aggregations = sort_by_dependencies(pipeline_aggregations)?;
+
+        let mut sub_aggregations = BTreeMap::new();
+        for (name, aggregation) in aggregations.iter_mut() {
+            let Some(aggregation_obj) = aggregation.as_object_mut() else {
+                continue;
+            };
+            // The buckets of the filters aggregation are always keyed.
+            let is_keyed = aggregation_obj.contains_key("filters")
+                || aggregation_obj
+                    .values()
+                    .any(|params| params.get("keyed").and_then(JsonValue::as_bool) == Some(true));
+            for sub_aggregations_key in ["aggs", "aggregations"] {
+                let Some(JsonValue::Object(sub_aggregations_obj)) =
+                    aggregation_obj.get_mut(sub_aggregations_key)
+                else {
+                    continue;
+                };
+                let sub_pipeline_aggregations =
+                    Self::extract(sub_aggregations_obj, Some(is_keyed))?;
+                if sub_aggregations_obj.is_empty() {
+                    aggregation_obj.remove(sub_aggregations_key);
+                }
+                if !sub_pipeline_aggregations.is_empty() {
+                    sub_aggregations.insert(name.clone(), sub_pipeline_aggregations);
+                }
+            }
+        }
+        Ok(Self {
+            pipeline_aggregations,
+            sub_aggregations,
+        })
+    }
+
+    /// Evaluates the pipeline aggregations over the final aggregation results.
+    pub(crate) fn apply(&self, aggregation_results_json: &str) -> crate::Result<String> {
+        let mut aggregation_results: JsonValue = serde_json::from_str(aggregation_results_json)?;
+        self.apply_to_aggregation_results(&mut aggregation_results);
+        Ok(serde_json::to_string(&aggregation_results)?)
+    }
+
+    fn apply_to_aggregation_results(&self, aggregation_results: &mut JsonValue) {
+        for (name, sub_pipeline_aggregations) in &self.sub_aggregations {
+            if let Some(aggregation_result) = aggregation_results.get_mut(name) {
+                sub_pipeline_aggregations.apply_to_buckets(aggregation_result);
+            }
+        }
+    }
+
+    fn apply_to_buckets(&self, aggregation_result: &mut JsonValue) {
+        let mut buckets: Vec<&mut JsonValue> = match aggregation_result.get_mut("buckets") {
+            Some(JsonValue::Array(buckets)) => buckets.iter_mut().collect(),
+            Some(JsonValue::Object(keyed_buckets)) => keyed_buckets.values_mut().collect(),
+            _ => return,
+        };
+        // Pipeline aggregations nested deeper are evaluated first, as they may be referenced by
+        // the pipeline aggregations of this level.
+        for bucket in buckets.iter_mut() {
+            self.apply_to_aggregation_results(bucket);
+        }
+        for (name, pipeline_aggregation) in &self.pipeline_aggregations {
+            let values = pipeline_aggregation.evaluate(&buckets);
+            for (bucket, value_opt) in buckets.iter_mut().zip(values) {
+                let (Some(value), Some(bucket_obj)) = (value_opt, bucket.as_object_mut()) else {
+                    continue;
+                };
+                // NaN and infinite values cannot be represented in JSON.
+                let value_json = serde_json::Number::from_f64(value)
+                    .map(JsonValue::Number)
+                    .unwrap_or(JsonValue::Null);
+                bucket_obj.insert(name.clone(), serde_json::json!({ "value": value_json }));
+            }
+        }
+    }
+}
+
+fn is_pipeline_aggregation(aggregation: &JsonValue) -> bool {
+    aggregation.as_object().is_some_and(|aggregation_obj| {
+        PIPELINE_AGGREGATION_TYPES
+            .iter()
+            .any(|aggregation_type| aggregation_obj.contains_key(*aggregation_type))
+    })
+}
+
+/// Orders the pipeline aggregations so that an aggregation referencing another pipeline
+/// aggregation is evaluated after it.
+fn sort_by_dependencies(
+    mut pipeline_aggregations: Vec<(String, PipelineAggregation)>,
+) -> crate::Result<Vec<(String, PipelineAggregation)>> {
+    let mut sorted_pipeline_aggregations: Vec<(String, PipelineAggregation)> =
+        Vec::with_capacity(pipeline_aggregations.len());
+    while !pipeline_aggregations.is_empty() {
+        let ready_idx = pipeline_aggregations
+            .iter()
+            .position(|(_, pipeline_aggregation)| {
+                pipeline_aggregation
+                    .buckets_paths()
+                    .into_iter()
+                    .all(|buckets_path| {
+                        let root = buckets_path_root(buckets_path);
+                        !pipeline_aggregations.iter().any(|(name, _)| name == root)
+                    })
+            })
+            .ok_or_else(|| {
+                SearchError::InvalidAggregationRequest(
+                    "pipeline aggregations cannot reference each other cyclically".to_string(),
+                )
+            })?;
+        sorted_pipeline_aggregations.push(pipeline_aggregations.remove(ready_idx));
+    }
+    Ok(sorted_pipeline_aggregations)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn search_request_with_aggregations(aggregations: JsonValue) -> SearchRequest {
+        SearchRequest {
+            aggregation_request: Some(aggregations.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_extract_pipeline_aggregations() {
+        let mut search_request = search_request_with_aggregations(json!({
+            "per_minute": {
+                "date_histogram": { "field": "timestamp", "fixed_interval": "1m" },
+                "aggs": {
+                    "errors": { "sum": { "field": "num_errors" } },
+                    "error_rate": {
+                        "bucket_script": {
+                            "buckets_path": { "errors": "errors", "total": "_count" },
+                            "script": "params.errors / params.total * 100"
+                        }
+                    },
+                    "errors_derivative": { "derivative": { "buckets_path": "errors" } }
+                }
+            },
+            "num_requests": { "value_count": { "field": "timestamp" } }
+        }));
+        let pipeline_aggregations =
+            PipelineAggregations::extract_from_search_request(&mut search_request)
+                .unwrap()
+                .unwrap();
+        let aggregations: JsonValue =
+            serde_json::from_str(search_request.aggregation_request.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            aggregations,
+            json!({
+                "per_minute": {
+                    "date_histogram": { "field": "timestamp", "fixed_interval": "1m" },
+                    "aggs": {
+                        "errors": { "sum": { "field": "num_errors" } }
+                    }
+                },
+                "num_requests": { "value_count": { "field": "timestamp" } }
+            })
+        );
+        assert!(pipeline_aggregations.pipeline_aggregations.is_empty());
+        let per_minute_pipeline_aggregations =
+            &pipeline_aggregations.sub_aggregations["per_minute"];
+        assert_eq!(
+            per_minute_pipeline_aggregations.pipeline_aggregations.len(),
+            2
+        );
+
+        let mut search_request = search_request_with_aggregations(json!({
+            "num_requests": { "value_count": { "field": "timestamp" } }
+        }));
+        let pipeline_aggregations_opt =
+            PipelineAggregations::extract_from_search_request(&mut search_request).unwrap();
+        assert!(pipeline_aggregations_opt.is_none());
+    }
+
+    #[test]
+    fn test_extract_pipeline_aggregations_invalid() {
+        for (aggregations, expected_error) in [
+            (
+                json!({ "derivative": { "derivative": { "buckets_path": "_count" } } }),
+                "must be nested in a bucket aggregation",
+            ),
+            (
+                json!({
+                    "per_minute": {
+                        "date_histogram": { "field": "timestamp", "fixed_interval": "1m" },
+                        "aggs": {
+                            "derivative": { "derivative": { "buckets_path": "missing" } }
+                        }
+                    }
+                }),
+                "no aggregation named `missing`",
+            ),
+            (
+                json!({
+                    "per_minute": {
+                        "date_histogram": { "field": "timestamp", "fixed_interval": "1m" },
+                        "aggs": {
+                            "ratio": {
+                                "bucket_script": {
+                                    "buckets_path": { "count": "_count" },
+                                    "script": "params.count / params.total"
+                                }
+                            }
+                        }
+                    }
+                }),
+                "variable `total` is not defined",
+            ),
+            (
+                json!({
+                    "per_minute": {
+                        "date_histogram": { "field": "timestamp", "fixed_interval": "1m" },
+                        "aggs": {
+                            "first": { "cumulative_sum": { "buckets_path": "second" } },
+                            "second": { "cumulative_sum": { "buckets_path": "first" } }
+                        }
+                    }
+                }),
+                "cyclically",
+            ),
+            (
+                json!({
+                    "per_minute": {
+                        "date_histogram": {
+                            "field": "timestamp",
+                            "fixed_interval": "1m",
+                            "keyed": true
+                        },
+                        "aggs": {
+                            "derivative": { "derivative": { "buckets_path": "_count" } }
+                        }
+                    }
+                }),
+                "keyed buckets",
+            ),
+            (
+                json!({
+                    "per_minute": {
+                        "date_histogram": { "field": "timestamp", "fixed_interval": "1m" },
+                        "aggs": {
+                            "moving_avg": {
+                                "moving_fn": {
+                                    "buckets_path": "_count",
+                                    "window": 5,
+                                    "script": "MovingFunctions.ewma(values, 0.3)"
+                                }
+                            }
+                        }
+                    }
+                }),
+                "unsupported script",
+            ),
+        ] {
+            let mut search_request = search_request_with_aggregations(aggregations);
+            let error =
+                PipelineAggregations::extract_from_search_request(&mut search_request).unwrap_err();
+            let SearchError::InvalidAggregationRequest(error_msg) = error else {
+                panic!("expected an invalid aggregation request error, got {error:?}");
+            };
+            assert!(
+                error_msg.contains(expected_error),
+                "`{error_msg}` does not contain `{expected_error}`"
+            );
+        }
+    }
+
+    #[test]
+    fn test_apply_pipeline_aggregations() {
+        let mut search_request = search_request_with_aggregations(json!({
+            "per_minute": {
+                "date_histogram": { "field": "timestamp", "fixed_interval": "1m" },
+                "aggs": {
+                    "errors": { "sum": { "field": "num_errors" } },
+                    "error_rate": {
+                        "bucket_script": {
+                            "buckets_path": { "errors": "errors", "total": "_count" },
+                            "script": "params.errors / params.total"
+                        }
+                    },
+                    "errors_derivative": {
+                        "derivative": { "buckets_path": "errors" }
+                    },
+                    "error_rate_cumulative_sum": {
+                        "cumulative_sum": { "buckets_path": "error_rate" }
+                    },
+                    "errors_moving_max": {
+                        "moving_fn": {
+                            "buckets_path": "errors",
+                            "window": "2",
+                            "script": "MovingFunctions.max(values)"
+                        }
+                    }
+                }
+            }
+        }));
+        let pipeline_aggregations =
+            PipelineAggregations::extract_from_search_request(&mut search_request)
+                .unwrap()
+                .unwrap();
+        let aggregation_results = json!({
+            "per_minute": {
+                "buckets": [
+                    { "key": 0.0, "doc_count": 10, "errors": { "value": 1.0 } },
+                    { "key": 60000.0, "doc_count": 0, "errors": { "value": null } },
+                    { "key": 120000.0, "doc_count": 20, "errors": { "value": 5.0 } }
+                ]
+            }
+        });
+        let aggregation_results_json = pipeline_aggregations
+            .apply(&aggregation_results.to_string())
+            .unwrap();
+        let aggregation_results: JsonValue =
+            serde_json::from_str(&aggregation_results_json).unwrap();
+        assert_eq!(
+            aggregation_results,
+            json!({
+                "per_minute": {
+                    "buckets": [
+                        {
+                            "key": 0.0,
+                            "doc_count": 10,
+                            "errors": { "value": 1.0 },
+                            "error_rate": { "value": 0.1 },
+                            "error_rate_cumulative_sum": { "value": 0.1 },
+                            "errors_moving_max": { "value": null }
+                        },
+                        {
+                            "key": 60000.0,
+                            "doc_count": 0,
+                            "errors": { "value": null },
+                            "error_rate_cumulative_sum": { "value": 0.1 },
+                            "errors_moving_max": { "value": 1.0 }
+                        },
+                        {
+                            "key": 120000.0,
+                            "doc_count": 20,
+                            "errors": { "value": 5.0 },
+                            "error_rate": { "value": 0.25 },
+                            "errors_derivative": { "value": 4.0 },
+                            "error_rate_cumulative_sum": { "value": 0.35 },
+                            "errors_moving_max": { "value": 1.0 }
+                        }
+                    ]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_resolve_buckets_path() {
+        let bucket = json!({
+            "key": 1.0,
+            "doc_count": 3,
+            "latency": { "value": 12.5 },
+            "latency_stats": { "avg": 10.0, "max": 20.0 },
+            "latency_percentiles": { "values": { "50.0": 11.0, "99.0": 19.0 } }
+        });
+        assert_eq!(resolve_buckets_path(&bucket, "_count"), Some(3.0));
+        assert_eq!(resolve_buckets_path(&bucket, "_key"), Some(1.0));
+        assert_eq!(resolve_buckets_path(&bucket, "latency"), Some(12.5));
+        assert_eq!(
+            resolve_buckets_path(&bucket, "latency_stats.max"),
+            Some(20.0)
+        );
+        assert_eq!(
+            resolve_buckets_path(&bucket, "latency_percentiles[99]"),
+            Some(19.0)
+        );
+        assert_eq!(
+            resolve_buckets_path(&bucket, "latency_percentiles.50"),
+            Some(11.0)
+        );
+        assert_eq!(resolve_buckets_path(&bucket, "missing"), None);
+    }
+
+    #[test]
+    fn test_script_expr() {
+        let script = ScriptExpr::parse("return (params.a + b) * -2 / 4 % 3;").unwrap();
+        let mut variables = script.variables();
+        variables.sort();
+        assert_eq!(variables, ["a", "b"]);
+        let variables = HashMap::from([("a", 4.0), ("b", 3.0)]);
+        assert_eq!(script.eval(&variables), -0.5);
+
+        let script = ScriptExpr::parse("1 - 2 - 3").unwrap();
+        assert_eq!(script.eval(&HashMap::new()), -4.0);
+
+        ScriptExpr::parse("params.a +").unwrap_err();
+        ScriptExpr::parse("(1 + 2").unwrap_err();
+        ScriptExpr::parse("Math.log(params.a)").unwrap_err();
+    }
+
+    #[test]
+    fn test_moving_functions() {
+        let values = [1.0, 2.0, 3.0, 6.0];
+        let eval = |script: &str| MovingFunction::parse(script).unwrap().eval(&values);
+        assert_eq!(eval("MovingFunctions.max(values)"), 6.0);
+        assert_eq!(eval("MovingFunctions.min(values)"), 1.0);
+        assert_eq!(eval("return MovingFunctions.sum(values);"), 12.0);
+        assert_eq!(eval("MovingFunctions.unweightedAvg(values)"), 3.0);
+        assert_eq!(eval("MovingFunctions.linearWeightedAvg(values)"), 3.8);
+        assert_eq!(
+            eval("MovingFunctions.stdDev(values, MovingFunctions.unweightedAvg(values))"),
+            3.5f64.sqrt()
+        );
+        assert!(MovingFunction::Max.eval(&[]).is_nan());
+        assert_eq!(MovingFunction::Sum.eval(&[]), 0.0);
+    }
+}
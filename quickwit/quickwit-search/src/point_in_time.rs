@@ -0,0 +1,355 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use base64::Engine;
+use quickwit_doc_mapper::tag_pruning::TagFilterAst;
+use quickwit_metastore::{
+    IndexMetadataResponseExt, ListSplitsRequestExt, MetastoreServiceStreamSplitsExt, PointInTime,
+    SplitMetadata, SplitState,
+};
+use quickwit_proto::metastore::{
+    serde_utils, CreatePointInTimeRequest, DeletePointInTimesRequest, EntityKind,
+    IndexMetadataRequest, ListPointInTimesRequest, ListSplitsRequest, MetastoreError,
+    MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::search::SearchRequest;
+use quickwit_proto::types::{IndexUid, SplitId};
+use serde::{Deserialize, Serialize};
+use tantivy::time::OffsetDateTime;
+use ulid::Ulid;
+
+use crate::{list_relevant_splits, relevant_splits_query, resolve_index_patterns, SearchError};
+
+/// Maximum accepted point-in-time keep alive.
+const MAX_POINT_IN_TIME_KEEP_ALIVE: Duration = Duration::from_secs(24 * 60 * 60); // 24 hours
+
+/// Splits pinned by a point in time, per index.
+pub(crate) type PinnedSplits = HashMap<IndexUid, HashSet<SplitId>>;
+
+/// Identifies a point in time across the indexes it covers. It is handed out to clients as an
+/// opaque string.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PointInTimeId {
+    key: Ulid,
+    index_uids: Vec<IndexUid>,
+}
+
+impl fmt::Display for PointInTimeId {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let payload =
+            serde_json::to_vec(self).expect("serializing a point-in-time ID should never fail");
+        write!(formatter, "{}", BASE64_URL_SAFE_NO_PAD.encode(payload))
+    }
+}
+
+impl FromStr for PointInTimeId {
+    type Err = SearchError;
+
+    fn from_str(point_in_time_id_str: &str) -> Result<Self, Self::Err> {
+        let invalid_point_in_time_id = || {
+            SearchError::InvalidArgument(format!(
+                "invalid point-in-time ID `{point_in_time_id_str}`"
+            ))
+        };
+        let payload = BASE64_URL_SAFE_NO_PAD
+            .decode(point_in_time_id_str)
+            .map_err(|_| invalid_point_in_time_id())?;
+        let point_in_time_id: PointInTimeId =
+            serde_json::from_slice(&payload).map_err(|_| invalid_point_in_time_id())?;
+        if point_in_time_id.index_uids.is_empty() {
+            return Err(invalid_point_in_time_id());
+        }
+        Ok(point_in_time_id)
+    }
+}
+
+fn now_timestamp() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+fn validate_keep_alive(keep_alive: Duration) -> crate::Result<()> {
+    if keep_alive.is_zero() || keep_alive > MAX_POINT_IN_TIME_KEEP_ALIVE {
+        return Err(SearchError::InvalidArgument(format!(
+            "point-in-time keep alive must be between 1 and {} secs",
+            MAX_POINT_IN_TIME_KEEP_ALIVE.as_secs()
+        )));
+    }
+    Ok(())
+}
+
+/// Saves a point in time in the metastore. If `overwrite` is set, the point in time replaces the
+/// existing one with the same key, if any.
+async fn save_point_in_time(
+    point_in_time: &PointInTime,
+    overwrite: bool,
+    metastore: &mut MetastoreServiceClient,
+) -> crate::Result<()> {
+    let create_point_in_time_request = CreatePointInTimeRequest {
+        point_in_time_json: serde_utils::to_json_str(point_in_time)?,
+        overwrite,
+    };
+    metastore
+        .create_point_in_time(create_point_in_time_request)
+        .await?;
+    Ok(())
+}
+
+/// Fetches the point in time associated with `key` from the metastore. Returns `None` if the
+/// point in time does not exist or has expired.
+async fn fetch_point_in_time(
+    key: &str,
+    now: i64,
+    metastore: &mut MetastoreServiceClient,
+) -> crate::Result<Option<PointInTime>> {
+    let list_point_in_times_request = ListPointInTimesRequest {
+        point_in_time_keys: vec![key.to_string()],
+    };
+    let list_point_in_times_response = metastore
+        .list_point_in_times(list_point_in_times_request)
+        .await?;
+    let Some(point_in_time_json) = list_point_in_times_response
+        .point_in_times_json
+        .into_iter()
+        .next()
+    else {
+        return Ok(None);
+    };
+    let point_in_time: PointInTime = serde_utils::from_json_str(&point_in_time_json)?;
+
+    if point_in_time.is_expired(now) {
+        return Ok(None);
+    }
+    Ok(Some(point_in_time))
+}
+
+/// Returns `true` if the index identified by `index_uid` still exists.
+async fn index_exists(
+    index_uid: &IndexUid,
+    metastore: &mut MetastoreServiceClient,
+) -> crate::Result<bool> {
+    let index_metadata_request = IndexMetadataRequest::for_index_uid(index_uid.clone());
+
+    match metastore.index_metadata(index_metadata_request).await {
+        Ok(index_metadata_response) => {
+            let index_metadata = index_metadata_response.deserialize_index_metadata()?;
+            Ok(index_metadata.index_uid == *index_uid)
+        }
+        Err(MetastoreError::NotFound(EntityKind::Index { .. })) => Ok(false),
+        Err(metastore_error) => Err(metastore_error.into()),
+    }
+}
+
+/// Creates a point in time pinning the splits currently published in the indexes matching
+/// `index_id_patterns`.
+pub(crate) async fn create_point_in_time(
+    index_id_patterns: &[String],
+    keep_alive: Duration,
+    metastore: &mut MetastoreServiceClient,
+) -> crate::Result<PointInTimeId> {
+    validate_keep_alive(keep_alive)?;
+
+    let indexes_metadata = resolve_index_patterns(index_id_patterns, metastore).await?;
+
+    if indexes_metadata.is_empty() {
+        return Err(SearchError::IndexesNotFound {
+            index_ids: index_id_patterns.to_vec(),
+        });
+    }
+    let point_in_time_id = PointInTimeId {
+        key: Ulid::new(),
+        index_uids: indexes_metadata
+            .iter()
+            .map(|index_metadata| index_metadata.index_uid.clone())
+            .collect(),
+    };
+    let mut split_ids = BTreeMap::new();
+
+    for index_uid in &point_in_time_id.index_uids {
+        let index_split_ids =
+            list_relevant_splits(vec![index_uid.clone()], None, None, None, metastore)
+                .await?
+                .into_iter()
+                .map(|split_metadata| split_metadata.split_id)
+                .collect();
+        split_ids.insert(index_uid.clone(), index_split_ids);
+    }
+    let point_in_time = PointInTime {
+        point_in_time_key: point_in_time_id.key.to_string(),
+        split_ids,
+        expire_at: now_timestamp() + keep_alive.as_secs() as i64,
+    };
+    save_point_in_time(&point_in_time, false, metastore).await?;
+    Ok(point_in_time_id)
+}
+
+/// Deletes a point in time. Returns `true` if the point in time existed and had not expired yet.
+pub(crate) async fn delete_point_in_time(
+    point_in_time_id_str: &str,
+    metastore: &mut MetastoreServiceClient,
+) -> crate::Result<bool> {
+    let point_in_time_id = PointInTimeId::from_str(point_in_time_id_str)?;
+    let key = point_in_time_id.key.to_string();
+    let now = now_timestamp();
+
+    let deleted = fetch_point_in_time(&key, now, metastore).await?.is_some();
+
+    let delete_point_in_times_request = DeletePointInTimesRequest {
+        point_in_time_keys: vec![key],
+    };
+    metastore
+        .delete_point_in_times(delete_point_in_times_request)
+        .await?;
+    Ok(deleted)
+}
+
+/// Resolves the point in time of a search request, if any.
+///
+/// The index ID patterns of the request are replaced with the IDs of the indexes covered by the
+/// point in time, and the splits pinned by the point in time are returned. If the request asks for
+/// it, the expiration of the point in time is pushed back.
+pub(crate) async fn resolve_point_in_time(
+    search_request: &mut SearchRequest,
+    metastore: &mut MetastoreServiceClient,
+) -> crate::Result<Option<PinnedSplits>> {
+    let Some(point_in_time_request) = &search_request.point_in_time else {
+        return Ok(None);
+    };
+    if !search_request.index_id_patterns.is_empty() {
+        return Err(SearchError::InvalidArgument(
+            "index IDs cannot be specified when searching a point in time".to_string(),
+        ));
+    }
+    let point_in_time_id = PointInTimeId::from_str(&point_in_time_request.id)?;
+    let key = point_in_time_id.key.to_string();
+    let now = now_timestamp();
+
+    let extended_expire_at_opt = point_in_time_request
+        .keep_alive_secs
+        .map(|keep_alive_secs| {
+            let keep_alive = Duration::from_secs(keep_alive_secs as u64);
+            validate_keep_alive(keep_alive)?;
+            Ok::<_, SearchError>(now + keep_alive.as_secs() as i64)
+        })
+        .transpose()?;
+
+    let point_in_time_not_found =
+        || SearchError::PointInTimeNotFound(point_in_time_request.id.clone());
+    let mut point_in_time = fetch_point_in_time(&key, now, metastore)
+        .await?
+        .ok_or_else(point_in_time_not_found)?;
+
+    if let Some(extended_expire_at) = extended_expire_at_opt {
+        if extended_expire_at > point_in_time.expire_at {
+            point_in_time.expire_at = extended_expire_at;
+            save_point_in_time(&point_in_time, true, metastore).await?;
+        }
+    }
+    let mut pinned_splits = PinnedSplits::with_capacity(point_in_time_id.index_uids.len());
+    let mut index_ids = Vec::with_capacity(point_in_time_id.index_uids.len());
+
+    for index_uid in point_in_time_id.index_uids {
+        if !index_exists(&index_uid, metastore).await? {
+            return Err(point_in_time_not_found());
+        }
+        let split_ids = point_in_time
+            .split_ids
+            .remove(&index_uid)
+            .ok_or_else(point_in_time_not_found)?;
+        index_ids.push(index_uid.index_id.clone());
+        pinned_splits.insert(index_uid, split_ids.into_iter().collect());
+    }
+    search_request.index_id_patterns = index_ids;
+    Ok(Some(pinned_splits))
+}
+
+/// Lists the splits pinned by a point in time that match the time range and tags filter.
+///
+/// Splits merged since the point in time was created are marked for deletion, but they remain
+/// searchable as long as the point in time holds them.
+pub(crate) async fn list_pinned_splits(
+    index_uids: Vec<IndexUid>,
+    start_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+    tags_filter_opt: Option<TagFilterAst>,
+    pinned_splits: &PinnedSplits,
+    metastore: &mut MetastoreServiceClient,
+) -> crate::Result<Vec<SplitMetadata>> {
+    let index_uids: Vec<IndexUid> = index_uids
+        .into_iter()
+        .filter(|index_uid| pinned_splits.contains_key(index_uid))
+        .collect();
+    let Some(query) =
+        relevant_splits_query(index_uids, start_timestamp, end_timestamp, tags_filter_opt)
+    else {
+        return Ok(Vec::new());
+    };
+    let query = query.with_split_states([SplitState::Published, SplitState::MarkedForDeletion]);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+    let splits_metadata: Vec<SplitMetadata> = metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_splits_metadata()
+        .await?;
+    let pinned_splits_metadata = splits_metadata
+        .into_iter()
+        .filter(|split_metadata| {
+            pinned_splits
+                .get(&split_metadata.index_uid)
+                .map(|split_ids| split_ids.contains(&split_metadata.split_id))
+                .unwrap_or(false)
+        })
+        .collect();
+    Ok(pinned_splits_metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_in_time_id_serialization() {
+        let point_in_time_id = PointInTimeId {
+            key: Ulid::new(),
+            index_uids: vec![
+                IndexUid::for_test("index-1", 0),
+                IndexUid::for_test("index-2", 1),
+            ],
+        };
+        let point_in_time_id_str = point_in_time_id.to_string();
+        assert_eq!(
+            PointInTimeId::from_str(&point_in_time_id_str).unwrap(),
+            point_in_time_id
+        );
+        let error = PointInTimeId::from_str("not-a-point-in-time-id").unwrap_err();
+        assert!(matches!(error, SearchError::InvalidArgument(_)));
+    }
+
+    #[test]
+    fn test_validate_keep_alive() {
+        validate_keep_alive(Duration::from_secs(60)).unwrap();
+        validate_keep_alive(Duration::ZERO).unwrap_err();
+        validate_keep_alive(MAX_POINT_IN_TIME_KEEP_ALIVE + Duration::from_secs(1)).unwrap_err();
+    }
+}
@@ -26,7 +26,8 @@ use crate::SearchError;
 
 /// Retry policy for LeafSearchRequest.
 /// A retry is made either on an error or if there are some failing splits.
-/// In the last case, a retry request is built on failing splits only.
+/// In the last case, a retry request is built on failing splits only. Splits skipped because
+/// the search timed out are not retried.
 pub struct LeafSearchRetryPolicy {}
 
 impl RetryPolicy<LeafSearchRequest, LeafSearchResponse, SearchError> for LeafSearchRetryPolicy {
@@ -38,14 +39,15 @@ impl RetryPolicy<LeafSearchRequest, LeafSearchResponse, SearchError> for LeafSea
     ) -> Option<LeafSearchRequest> {
         match response_res {
             Ok(response) => {
-                if response.failed_splits.is_empty() {
-                    return None;
-                }
                 let failed_splits_hash_set: HashSet<&str> = response
                     .failed_splits
                     .iter()
+                    .filter(|failed_split| !failed_split.timed_out)
                     .map(|failed_split| failed_split.split_id.as_str())
                     .collect();
+                if failed_splits_hash_set.is_empty() {
+                    return None;
+                }
                 for request in request.leaf_requests.iter_mut() {
                     // Keep only failed splits
                     request.split_offsets.retain(|split_metadata| {
@@ -148,6 +150,7 @@ mod tests {
             error: "error".to_string(),
             split_id: "split_2".to_string(),
             retryable_error: true,
+            timed_out: false,
         };
         let response_res = Ok(LeafSearchResponse {
             num_hits: 0,
@@ -159,4 +162,24 @@ mod tests {
         let retry_request = retry_policy.retry_request(request, &response_res).unwrap();
         assert_eq!(retry_request, expected_retry_request);
     }
+
+    #[test]
+    fn test_should_not_retry_on_timed_out_splits() {
+        let retry_policy = LeafSearchRetryPolicy {};
+        let request = mock_leaf_search_request();
+        let split_error = SplitSearchError {
+            error: "search timed out".to_string(),
+            split_id: "split_2".to_string(),
+            retryable_error: false,
+            timed_out: true,
+        };
+        let response_res = Ok(LeafSearchResponse {
+            num_hits: 0,
+            partial_hits: Vec::new(),
+            failed_splits: vec![split_error],
+            num_attempted_splits: 1,
+            ..Default::default()
+        });
+        assert!(retry_policy.retry_request(request, &response_res).is_none());
+    }
 }
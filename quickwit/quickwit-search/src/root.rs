@@ -29,34 +29,48 @@ use quickwit_common::shared_consts;
 use quickwit_common::uri::Uri;
 use quickwit_config::build_doc_mapper;
 use quickwit_doc_mapper::tag_pruning::extract_tags_from_query;
-use quickwit_doc_mapper::DYNAMIC_FIELD_NAME;
+use quickwit_doc_mapper::{DocMapper, DYNAMIC_FIELD_NAME};
 use quickwit_metastore::{IndexMetadata, ListIndexesMetadataResponseExt, SplitMetadata};
 use quickwit_proto::metastore::{
     ListIndexesMetadataRequest, MetastoreService, MetastoreServiceClient,
 };
 use quickwit_proto::search::{
-    FetchDocsRequest, FetchDocsResponse, Hit, LeafHit, LeafRequestRef, LeafSearchRequest,
-    LeafSearchResponse, PartialHit, SearchPlanResponse, SearchRequest, SearchResponse,
+    Collapse, FetchDocsRequest, FetchDocsResponse, Hit, LeafHit, LeafRequestRef, LeafSearchRequest,
+    LeafSearchResponse, NamedQuery, PartialHit, SearchPlanResponse, SearchRequest, SearchResponse,
     SnippetRequest, SortDatetimeFormat, SortField, SortValue, SplitIdAndFooterOffsets,
+    SplitSearchError,
 };
 use quickwit_proto::types::{IndexUid, SplitId};
 use quickwit_query::query_ast::{
     BoolQuery, QueryAst, QueryAstVisitor, RangeQuery, TermQuery, TermSetQuery,
 };
+use quickwit_storage::StorageResolver;
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use tantivy::aggregation::agg_result::AggregationResults;
 use tantivy::aggregation::intermediate_agg_result::IntermediateAggregationResults;
 use tantivy::collector::Collector;
 use tantivy::schema::{FieldEntry, FieldType, Schema};
 use tantivy::TantivyError;
-use tracing::{debug, error, info, info_span, instrument};
+use tokio::time::Instant;
+use tracing::{debug, error, info, info_span, instrument, warn};
 
+use crate::bloom_filter_pruning::prune_splits_with_bloom_filters;
 use crate::cluster_client::ClusterClient;
 use crate::collector::{make_merge_collector, QuickwitAggregations};
+use crate::completion_suggest::validate_suggesters;
+use crate::extended_aggregations::{
+    has_extended_aggregation, ExtendedAggregations, IntermediateExtendedAggregationResults,
+};
+use crate::field_value_range_pruning::prune_splits_with_field_value_ranges;
+use crate::filters_aggregation::resolve_filters_aggregations;
 use crate::find_trace_ids_collector::Span;
+use crate::pipeline_aggregations::PipelineAggregations;
+use crate::point_in_time::{list_pinned_splits, PinnedSplits};
 use crate::scroll_context::{ScrollContext, ScrollKeyAndStartOffset};
 use crate::search_job_placer::{group_by, group_jobs_by_index_id, Job};
 use crate::search_response_rest::StorageRequestCount;
+use crate::search_result_cache::SearchResultCacheKey;
 use crate::service::SearcherContext;
 use crate::{
     extract_split_and_footer_offsets, list_relevant_splits, SearchError, SearchJobPlacer,
@@ -64,6 +78,9 @@ use crate::{
 };
 
 /// Maximum accepted scroll TTL.
+/// Maximum number of inner hits returned for each collapsed value.
+const MAX_COLLAPSE_INNER_HITS: u32 = 100;
+
 fn max_scroll_ttl() -> Duration {
     static MAX_SCROLL_TTL_LOCK: OnceLock<Duration> = OnceLock::new();
     *MAX_SCROLL_TTL_LOCK.get_or_init(|| {
@@ -168,13 +185,16 @@ pub(crate) type IndexesMetasForLeafSearch = HashMap<IndexUid, IndexMetasForLeafS
 struct RequestMetadata {
     timestamp_field_opt: Option<String>,
     query_ast_resolved: QueryAst,
+    named_queries_resolved: Vec<NamedQuery>,
+    aggregation_request_resolved: Option<String>,
     indexes_meta_for_leaf_search: IndexesMetasForLeafSearch,
     sort_fields_is_datetime: HashMap<String, bool>,
 }
 
 /// Validates request against each index's doc mapper and ensures that:
 /// - timestamp fields (if any) are equal across indexes.
-/// - resolved query ASTs are the same across indexes.
+/// - resolved query ASTs, including the ones of the named queries and of the filters aggregations,
+///   are the same across indexes.
 /// - if a sort field is of type datetime, it must be a datetime field on all indexes. This
 ///   constraint come from the need to support datetime formatting on sort values.
 ///
@@ -192,9 +212,20 @@ fn validate_request_and_build_metadata(
     )?;
     let query_ast: QueryAst = serde_json::from_str(&search_request.query_ast)
         .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
+    let named_query_asts: Vec<(&str, QueryAst)> = search_request
+        .named_queries
+        .iter()
+        .map(|named_query| {
+            let named_query_ast: QueryAst = serde_json::from_str(&named_query.query_ast)
+                .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
+            Ok((named_query.name.as_str(), named_query_ast))
+        })
+        .collect::<crate::Result<_>>()?;
     let mut indexes_meta_for_leaf_search: HashMap<IndexUid, IndexMetasForLeafSearch> =
         HashMap::new();
     let mut query_ast_resolved_opt: Option<QueryAst> = None;
+    let mut named_queries_resolved_opt: Option<Vec<NamedQuery>> = None;
+    let mut aggregation_request_resolved_opt: Option<String> = None;
     let mut timestamp_field_opt: Option<String> = None;
     let mut sort_fields_is_datetime: HashMap<String, bool> = HashMap::new();
 
@@ -225,6 +256,20 @@ fn validate_request_and_build_metadata(
             query_ast_resolved_opt = Some(query_ast_resolved_for_index.clone());
         }
 
+        // Validate uniqueness of resolved named queries.
+        let named_queries_resolved_for_index =
+            resolve_named_queries(&named_query_asts, &*doc_mapper)?;
+
+        if let Some(named_queries_resolved) = &named_queries_resolved_opt {
+            if named_queries_resolved != &named_queries_resolved_for_index {
+                return Err(SearchError::InvalidQuery(
+                    "resolved named queries must be the same across indexes".to_string(),
+                ));
+            }
+        } else {
+            named_queries_resolved_opt = Some(named_queries_resolved_for_index);
+        }
+
         // Validate uniqueness of timestamp field if any.
         if let Some(timestamp_field_for_index) = doc_mapper.timestamp_field_name() {
             match timestamp_field_opt {
@@ -245,6 +290,23 @@ fn validate_request_and_build_metadata(
         let schema = doc_mapper.schema();
         validate_request(&schema, &doc_mapper.timestamp_field_name(), search_request)?;
 
+        // Validate uniqueness of the resolved filters aggregations if any.
+        if let Some(aggregation_request) = &search_request.aggregation_request {
+            let aggregation_request_resolved_for_index =
+                resolve_filters_aggregations(aggregation_request, &*doc_mapper)?;
+
+            if let Some(aggregation_request_resolved) = &aggregation_request_resolved_opt {
+                if aggregation_request_resolved != &aggregation_request_resolved_for_index {
+                    return Err(SearchError::InvalidQuery(
+                        "resolved filters aggregation queries must be the same across indexes"
+                            .to_string(),
+                    ));
+                }
+            } else {
+                aggregation_request_resolved_opt = Some(aggregation_request_resolved_for_index);
+            }
+        }
+
         validate_sort_field_types(
             &schema,
             &search_request.sort_fields,
@@ -275,11 +337,36 @@ fn validate_request_and_build_metadata(
     Ok(RequestMetadata {
         timestamp_field_opt,
         query_ast_resolved,
+        named_queries_resolved: named_queries_resolved_opt.unwrap_or_default(),
+        aggregation_request_resolved: aggregation_request_resolved_opt,
         indexes_meta_for_leaf_search,
         sort_fields_is_datetime,
     })
 }
 
+/// Resolves the user input queries of the named queries with the default search fields of the doc
+/// mapper, and validates the resolved queries against its schema.
+fn resolve_named_queries(
+    named_query_asts: &[(&str, QueryAst)],
+    doc_mapper: &dyn DocMapper,
+) -> crate::Result<Vec<NamedQuery>> {
+    let mut named_queries_resolved = Vec::with_capacity(named_query_asts.len());
+
+    for (name, named_query_ast) in named_query_asts {
+        let named_query_ast_resolved = named_query_ast
+            .clone()
+            .parse_user_query(doc_mapper.default_search_fields())
+            .map_err(|err| SearchError::InvalidQuery(err.to_string()))?;
+        doc_mapper.query(doc_mapper.schema(), &named_query_ast_resolved, true)?;
+
+        named_queries_resolved.push(NamedQuery {
+            name: name.to_string(),
+            query_ast: serde_json::to_string(&named_query_ast_resolved)?,
+        });
+    }
+    Ok(named_queries_resolved)
+}
+
 /// Validate sort field types.
 fn validate_sort_field_types(
     schema: &Schema,
@@ -318,6 +405,10 @@ fn validate_requested_snippet_fields(
     snippet_fields: &[String],
 ) -> anyhow::Result<()> {
     for field_name in snippet_fields {
+        // Field name patterns are resolved on the leaves, against the fields of each split.
+        if field_name.contains('*') {
+            continue;
+        }
         let field_entry = schema
             .get_field(field_name)
             .map(|field| schema.get_field_entry(field))?;
@@ -363,12 +454,27 @@ fn simplify_search_request_for_scroll_api(req: &SearchRequest) -> crate::Result<
         aggregation_request: None,
         // We remove the snippet fields. This feature is not supported for scroll requests.
         snippet_fields: Vec::new(),
+        snippet_options: None,
+        // Scroll requests cannot be combined with a point in time.
+        point_in_time: None,
+        // Scroll requests cannot be combined with a collapse.
+        collapse: None,
         // We remove the scroll ttl parameter. It is irrelevant to process later request
         scroll_ttl_secs: None,
         search_after: None,
         // request is simplified after initial query, and we cache the hit count, so we don't need
         // to recompute it afterward.
         count_hits: quickwit_proto::search::CountHits::Underestimate as i32,
+        named_queries: req.named_queries.clone(),
+        timeout_millis: None,
+        priority: req.priority,
+        api_key_id: req.api_key_id.clone(),
+        // Suggestions are only returned with the first page of results.
+        suggesters: Vec::new(),
+        explain: req.explain,
+        profile: false,
+        docvalue_fields: req.docvalue_fields.clone(),
+        skip_source: req.skip_source,
     })
 }
 
@@ -490,10 +596,26 @@ fn validate_request(
 
     validate_requested_snippet_fields(schema, &search_request.snippet_fields)?;
 
+    if search_request.point_in_time.is_some() && search_request.scroll_ttl_secs.is_some() {
+        return Err(SearchError::InvalidArgument(
+            "scroll cannot be used with a point in time".to_string(),
+        ));
+    }
+
+    if let Some(collapse) = &search_request.collapse {
+        validate_collapse(schema, collapse, search_request)?;
+    }
+
+    validate_suggesters(schema, &search_request.suggesters)?;
+
     if let Some(agg) = search_request.aggregation_request.as_ref() {
         let _aggs: QuickwitAggregations = serde_json::from_str(agg).map_err(|_err| {
-            let err = serde_json::from_str::<tantivy::aggregation::agg_req::Aggregations>(agg)
-                .unwrap_err();
+            let err = if has_extended_aggregation(agg) {
+                serde_json::from_str::<ExtendedAggregations>(agg).unwrap_err()
+            } else {
+                serde_json::from_str::<tantivy::aggregation::agg_req::Aggregations>(agg)
+                    .unwrap_err()
+            };
             SearchError::InvalidAggregationRequest(err.to_string())
         })?;
     };
@@ -515,6 +637,69 @@ fn validate_request(
     Ok(())
 }
 
+/// Validates the collapse field and the options the collapse is combined with.
+fn validate_collapse(
+    schema: &Schema,
+    collapse: &Collapse,
+    search_request: &SearchRequest,
+) -> crate::Result<()> {
+    if search_request.scroll_ttl_secs.is_some() {
+        return Err(SearchError::InvalidArgument(
+            "collapse cannot be used in a scroll context".to_string(),
+        ));
+    }
+    if collapse.inner_hits > MAX_COLLAPSE_INNER_HITS {
+        return Err(SearchError::InvalidArgument(format!(
+            "max value for collapse inner_hits is {MAX_COLLAPSE_INNER_HITS}, but got {}",
+            collapse.inner_hits
+        )));
+    }
+    if search_request.search_after.is_some() {
+        let is_sorted_by_collapse_field = search_request
+            .sort_fields
+            .first()
+            .is_some_and(|sort_field| sort_field.field_name == collapse.field);
+        // Otherwise, the next page could contain a collapsed value of the previous pages.
+        if !is_sorted_by_collapse_field || collapse.inner_hits > 0 {
+            return Err(SearchError::InvalidArgument(
+                "search_after can only be used with collapse if the hits are sorted by the \
+                 collapse field, without inner hits"
+                    .to_string(),
+            ));
+        }
+    }
+    let dynamic_field_opt = schema.get_field(DYNAMIC_FIELD_NAME).ok();
+    let (collapse_field, _json_path) = schema
+        .find_field_with_default(&collapse.field, dynamic_field_opt)
+        .ok_or_else(|| {
+            SearchError::InvalidArgument(format!(
+                "unknown field used in `collapse`: {}",
+                collapse.field
+            ))
+        })?;
+    let collapse_field_entry = schema.get_field_entry(collapse_field);
+    if !collapse_field_entry.is_fast() {
+        return Err(SearchError::InvalidArgument(format!(
+            "collapse field must be a fast field, please add the fast property to your field `{}`",
+            collapse.field
+        )));
+    }
+    match collapse_field_entry.field_type() {
+        FieldType::Str(_)
+        | FieldType::U64(_)
+        | FieldType::I64(_)
+        | FieldType::F64(_)
+        | FieldType::Bool(_)
+        | FieldType::Date(_)
+        | FieldType::JsonObject(_) => Ok(()),
+        _ => Err(SearchError::InvalidArgument(format!(
+            "collapse on field `{}` of type {:?} is not supported",
+            collapse.field,
+            collapse_field_entry.field_type().value_type()
+        ))),
+    }
+}
+
 fn get_scroll_ttl_duration(search_request: &SearchRequest) -> crate::Result<Option<Duration>> {
     let Some(scroll_ttl_secs) = search_request.scroll_ttl_secs else {
         return Ok(None);
@@ -638,6 +823,10 @@ pub fn is_metadata_count_request_with_ast(query_ast: &QueryAst, request: &Search
     if request.aggregation_request.is_some() || !request.snippet_fields.is_empty() {
         return false;
     }
+    // The suggestions are read from the splits.
+    if !request.suggesters.is_empty() {
+        return false;
+    }
     true
 }
 
@@ -651,6 +840,8 @@ pub fn get_count_from_metadata(split_metadatas: &[SplitMetadata]) -> Vec<LeafSea
             failed_splits: Vec::new(),
             num_attempted_splits: 1,
             intermediate_aggregation_result: None,
+            suggestions: Vec::new(),
+            split_profiles: Vec::new(),
         })
         .collect()
 }
@@ -683,7 +874,53 @@ pub(crate) async fn search_partial_hits_phase(
             }
             try_join_all(leaf_request_tasks).await?
         };
+    let leaf_search_response =
+        merge_leaf_search_responses(searcher_context, search_request, leaf_search_responses)
+            .await?;
+    debug!(
+        num_hits = leaf_search_response.num_hits,
+        failed_splits = ?leaf_search_response.failed_splits,
+        num_attempted_splits = leaf_search_response.num_attempted_splits,
+        has_intermediate_aggregation_result = leaf_search_response.intermediate_aggregation_result.is_some(),
+        "Merged leaf search response."
+    );
+    timed_out_split_ids(&leaf_search_response.failed_splits)?;
+    Ok(leaf_search_response)
+}
 
+/// Returns the timeout left before the deadline, which is the timeout sent to the leaves.
+fn remaining_timeout_millis(deadline: Instant) -> u32 {
+    let remaining_timeout = deadline.saturating_duration_since(Instant::now());
+    u32::try_from(remaining_timeout.as_millis()).unwrap_or(u32::MAX)
+}
+
+/// Returns the ids of the splits skipped because the search timed out, or an error if some
+/// splits failed for another reason.
+pub(crate) fn timed_out_split_ids(
+    failed_splits: &[SplitSearchError],
+) -> crate::Result<Vec<String>> {
+    let (timed_out_splits, failed_splits): (Vec<&SplitSearchError>, Vec<&SplitSearchError>) =
+        failed_splits
+            .iter()
+            .partition(|failed_split| failed_split.timed_out);
+    if !failed_splits.is_empty() {
+        error!(failed_splits = ?failed_splits, "leaf search response contains at least one failed split");
+        let errors: String = failed_splits.iter().join(", ");
+        return Err(SearchError::Internal(errors));
+    }
+    let timed_out_split_ids = timed_out_splits
+        .into_iter()
+        .map(|timed_out_split| timed_out_split.split_id.clone())
+        .collect();
+    Ok(timed_out_split_ids)
+}
+
+/// Merges leaf search responses into one.
+pub(crate) async fn merge_leaf_search_responses(
+    searcher_context: &SearcherContext,
+    search_request: &SearchRequest,
+    leaf_search_responses: Vec<LeafSearchResponse>,
+) -> crate::Result<LeafSearchResponse> {
     // Creates a collector which merges responses into one
     let merge_collector =
         make_merge_collector(search_request, &searcher_context.get_aggregation_limits())?;
@@ -703,18 +940,6 @@ pub(crate) async fn search_partial_hits_phase(
         .await
         .context("failed to merge leaf search responses")?
         .map_err(|error: TantivyError| crate::SearchError::Internal(error.to_string()))?;
-    debug!(
-        num_hits = leaf_search_response.num_hits,
-        failed_splits = ?leaf_search_response.failed_splits,
-        num_attempted_splits = leaf_search_response.num_attempted_splits,
-        has_intermediate_aggregation_result = leaf_search_response.intermediate_aggregation_result.is_some(),
-        "Merged leaf search response."
-    );
-    if !leaf_search_response.failed_splits.is_empty() {
-        error!(failed_splits = ?leaf_search_response.failed_splits, "leaf search response contains at least one failed split");
-        let errors: String = leaf_search_response.failed_splits.iter().join(", ");
-        return Err(SearchError::Internal(errors));
-    }
     Ok(leaf_search_response)
 }
 
@@ -725,10 +950,10 @@ pub(crate) fn get_snippet_request(search_request: &SearchRequest) -> Option<Snip
     Some(SnippetRequest {
         snippet_fields: search_request.snippet_fields.clone(),
         query_ast_resolved: search_request.query_ast.clone(),
+        snippet_options: search_request.snippet_options.clone(),
     })
 }
 
-#[instrument(skip_all, fields(partial_hits_num=partial_hits.len()))]
 pub(crate) async fn fetch_docs_phase(
     indexes_metas_for_leaf_search: &IndexesMetasForLeafSearch,
     partial_hits: &[PartialHit],
@@ -736,7 +961,34 @@ pub(crate) async fn fetch_docs_phase(
     search_request: &SearchRequest,
     cluster_client: &ClusterClient,
 ) -> crate::Result<Vec<Hit>> {
+    let (hits, _timed_out_splits) = fetch_docs_phase_until_deadline(
+        indexes_metas_for_leaf_search,
+        partial_hits,
+        split_metadatas,
+        search_request,
+        cluster_client,
+        None,
+    )
+    .await?;
+    Ok(hits)
+}
+
+/// Fetches the documents of the partial hits, like [`fetch_docs_phase`]. The fetch docs requests
+/// still running at the deadline, if any, are abandoned: their hits are left out of the response
+/// and the IDs of their splits are returned along with the hits.
+#[instrument(skip_all, fields(partial_hits_num=partial_hits.len()))]
+async fn fetch_docs_phase_until_deadline(
+    indexes_metas_for_leaf_search: &IndexesMetasForLeafSearch,
+    partial_hits: &[PartialHit],
+    split_metadatas: &[SplitMetadata],
+    search_request: &SearchRequest,
+    cluster_client: &ClusterClient,
+    deadline_opt: Option<Instant>,
+) -> crate::Result<(Vec<Hit>, Vec<String>)> {
     let snippet_request: Option<SnippetRequest> = get_snippet_request(search_request);
+    let explain_query_ast_opt: Option<String> = search_request
+        .explain
+        .then(|| search_request.query_ast.clone());
     let hit_order: HashMap<(String, u32, u32), usize> = partial_hits
         .iter()
         .enumerate()
@@ -761,15 +1013,52 @@ pub(crate) async fn fetch_docs_phase(
     for (client, client_jobs) in assigned_fetch_docs_jobs {
         let fetch_jobs_requests = jobs_to_fetch_docs_requests(
             snippet_request.clone(),
+            &search_request.named_queries,
+            explain_query_ast_opt.clone(),
+            &search_request.docvalue_fields,
+            search_request.skip_source,
             indexes_metas_for_leaf_search,
             client_jobs,
         )?;
         for fetch_docs_request in fetch_jobs_requests {
-            fetch_docs_tasks.push(cluster_client.fetch_docs(fetch_docs_request, client.clone()));
+            let split_ids: Vec<String> = fetch_docs_request
+                .split_offsets
+                .iter()
+                .map(|split_offsets| split_offsets.split_id.clone())
+                .collect();
+            let fetch_docs_fut = cluster_client.fetch_docs(fetch_docs_request, client.clone());
+            fetch_docs_tasks.push(async move {
+                let Some(deadline) = deadline_opt else {
+                    return fetch_docs_fut.await.map(Ok);
+                };
+                if Instant::now() >= deadline {
+                    return Ok(Err(split_ids));
+                }
+                match tokio::time::timeout_at(deadline, fetch_docs_fut).await {
+                    Ok(fetch_docs_res) => fetch_docs_res.map(Ok),
+                    Err(_elapsed) => Ok(Err(split_ids)),
+                }
+            });
         }
     }
-    let fetch_docs_responses: Vec<FetchDocsResponse> = try_join_all(fetch_docs_tasks).await?;
-
+    let fetch_docs_results: Vec<Result<FetchDocsResponse, Vec<String>>> =
+        try_join_all(fetch_docs_tasks).await?;
+    let mut fetch_docs_responses: Vec<FetchDocsResponse> =
+        Vec::with_capacity(fetch_docs_results.len());
+    let mut timed_out_splits: Vec<String> = Vec::new();
+
+    for fetch_docs_result in fetch_docs_results {
+        match fetch_docs_result {
+            Ok(fetch_docs_response) => fetch_docs_responses.push(fetch_docs_response),
+            Err(split_ids) => timed_out_splits.extend(split_ids),
+        }
+    }
+    if !timed_out_splits.is_empty() {
+        warn!(
+            num_timed_out_splits = timed_out_splits.len(),
+            "fetch docs timed out before all the documents were fetched"
+        );
+    }
     // Merge the fetched docs.
     let leaf_hits = fetch_docs_responses
         .into_iter()
@@ -809,7 +1098,40 @@ pub(crate) async fn fetch_docs_phase(
         .map(|(_position, hit)| hit)
         .collect();
 
-    Ok(hits)
+    if let Some(collapse) = &search_request.collapse {
+        return Ok((collapse_hits(hits, collapse), timed_out_splits));
+    }
+    Ok((hits, timed_out_splits))
+}
+
+/// Only keeps the top hit of each collapsed group, and nests the hits of the group in its
+/// `inner_hits` if requested.
+///
+/// The hits of a collapsed group are expected to be contiguous, top hit first.
+fn collapse_hits(hits: Vec<Hit>, collapse: &Collapse) -> Vec<Hit> {
+    fn collapse_value(hit: &Hit) -> Option<&str> {
+        hit.partial_hit
+            .as_ref()
+            .and_then(|partial_hit| partial_hit.collapse_value.as_deref())
+    }
+    let mut top_hits: Vec<Hit> = Vec::new();
+
+    for hit in hits {
+        if let Some(top_hit) = top_hits.last_mut() {
+            if collapse_value(top_hit) == collapse_value(&hit) {
+                top_hit.inner_hits.push(hit);
+                continue;
+            }
+        }
+        if collapse.inner_hits > 0 {
+            let mut top_hit = hit.clone();
+            top_hit.inner_hits.push(hit);
+            top_hits.push(top_hit);
+        } else {
+            top_hits.push(hit);
+        }
+    }
+    top_hits
 }
 
 fn build_hit_with_position(
@@ -859,6 +1181,10 @@ fn build_hit_with_position(
             partial_hit: leaf_hit.partial_hit,
             snippet: leaf_hit.leaf_snippet_json,
             index_id,
+            matched_queries: leaf_hit.matched_queries,
+            inner_hits: Vec::new(),
+            explanation_json: leaf_hit.explanation_json,
+            docvalue_fields_json: leaf_hit.leaf_docvalue_fields_json,
         },
     ))
 }
@@ -889,28 +1215,39 @@ async fn root_search_aux(
     search_request: SearchRequest,
     split_metadatas: Vec<SplitMetadata>,
     cluster_client: &ClusterClient,
+    deadline_opt: Option<Instant>,
 ) -> crate::Result<SearchResponse> {
     debug!(split_metadatas = ?PrettySample::new(&split_metadatas, 5));
+    // The leaves are given the time left before the deadline rather than the original timeout, so
+    // that the time spent resolving the indexes and the splits counts against it.
+    let mut leaf_search_request = search_request.clone();
+
+    if let Some(deadline) = deadline_opt {
+        leaf_search_request.timeout_millis = Some(remaining_timeout_millis(deadline));
+    }
     let (first_phase_result, scroll_key_and_start_offset_opt): (
         LeafSearchResponse,
         Option<ScrollKeyAndStartOffset>,
     ) = search_partial_hits_phase_with_scroll(
         searcher_context,
         indexes_metas_for_leaf_search,
-        search_request.clone(),
+        leaf_search_request,
         &split_metadatas[..],
         cluster_client,
     )
     .await?;
+    let mut timed_out_splits = timed_out_split_ids(&first_phase_result.failed_splits)?;
 
-    let hits = fetch_docs_phase(
+    let (hits, fetch_docs_timed_out_splits) = fetch_docs_phase_until_deadline(
         indexes_metas_for_leaf_search,
         &first_phase_result.partial_hits,
         &split_metadatas[..],
         &search_request,
         cluster_client,
+        deadline_opt,
     )
     .await?;
+    timed_out_splits.extend(fetch_docs_timed_out_splits);
 
     let mut aggregation_result_json_opt = finalize_aggregation_if_any(
         &search_request,
@@ -931,6 +1268,10 @@ async fn root_search_aux(
         scroll_id: scroll_key_and_start_offset_opt
             .as_ref()
             .map(ToString::to_string),
+        timed_out: !timed_out_splits.is_empty(),
+        timed_out_splits,
+        suggestions: first_phase_result.suggestions,
+        split_profiles: first_phase_result.split_profiles,
     })
 }
 
@@ -966,11 +1307,27 @@ fn finalize_aggregation(
                 .into_final_result(aggregations, searcher_context.get_aggregation_limits())?;
             serde_json::to_string(&final_aggregation_results)?
         }
+        QuickwitAggregations::ExtendedAggregations(aggregations) => {
+            let intermediate_aggregation_results =
+                if let Some(intermediate_aggregation_result_bytes) =
+                    intermediate_aggregation_result_bytes_opt
+                {
+                    let intermediate_aggregation_results: IntermediateExtendedAggregationResults =
+                        postcard::from_bytes(&intermediate_aggregation_result_bytes)?;
+                    intermediate_aggregation_results
+                } else {
+                    // Default, to return correct structure
+                    Default::default()
+                };
+            let final_aggregation_results: JsonValue = intermediate_aggregation_results
+                .into_final_result(aggregations, searcher_context.get_aggregation_limits())?;
+            serde_json::to_string(&final_aggregation_results)?
+        }
     };
     Ok(Some(merge_aggregation_result))
 }
 
-fn finalize_aggregation_if_any(
+pub(crate) fn finalize_aggregation_if_any(
     search_request: &SearchRequest,
     intermediate_aggregation_result_bytes_opt: Option<Vec<u8>>,
     searcher_context: &SearcherContext,
@@ -1034,6 +1391,7 @@ async fn refine_and_list_matches(
     query_ast_resolved: QueryAst,
     sort_fields_is_datetime: HashMap<String, bool>,
     timestamp_field_opt: Option<String>,
+    pinned_splits_opt: Option<&PinnedSplits>,
 ) -> crate::Result<Vec<SplitMetadata>> {
     let index_uids = indexes_metadata
         .iter()
@@ -1053,18 +1411,31 @@ async fn refine_and_list_matches(
             &mut search_request.end_timestamp,
         );
     }
-    let tag_filter_ast = extract_tags_from_query(query_ast_resolved);
+    let tag_filter_ast = extract_tags_from_query(query_ast_resolved.clone());
 
     // TODO if search after is set, we sort by timestamp and we don't want to count all results,
     // we can refine more here. Same if we sort by _shard_doc
-    let split_metadatas: Vec<SplitMetadata> = list_relevant_splits(
-        index_uids,
-        search_request.start_timestamp,
-        search_request.end_timestamp,
-        tag_filter_ast,
-        metastore,
-    )
-    .await?;
+    let mut split_metadatas: Vec<SplitMetadata> = if let Some(pinned_splits) = pinned_splits_opt {
+        list_pinned_splits(
+            index_uids,
+            search_request.start_timestamp,
+            search_request.end_timestamp,
+            tag_filter_ast,
+            pinned_splits,
+            metastore,
+        )
+        .await?
+    } else {
+        list_relevant_splits(
+            index_uids,
+            search_request.start_timestamp,
+            search_request.end_timestamp,
+            tag_filter_ast,
+            metastore,
+        )
+        .await?
+    };
+    prune_splits_with_field_value_ranges(&query_ast_resolved, &mut split_metadatas);
     Ok(split_metadatas)
 }
 
@@ -1073,15 +1444,129 @@ async fn refine_and_list_matches(
 /// 2. Merges the search results.
 /// 3. Sends fetch docs requests to multiple leaf nodes.
 /// 4. Builds the response with docs and returns.
-#[instrument(skip_all)]
 pub async fn root_search(
     searcher_context: &SearcherContext,
-    mut search_request: SearchRequest,
+    search_request: SearchRequest,
+    metastore: MetastoreServiceClient,
+    storage_resolver: &StorageResolver,
+    cluster_client: &ClusterClient,
+) -> crate::Result<SearchResponse> {
+    root_search_with_pinned_splits(
+        searcher_context,
+        search_request,
+        metastore,
+        storage_resolver,
+        cluster_client,
+        None,
+    )
+    .await
+}
+
+/// Performs a distributed search, like [`root_search`]. If `pinned_splits_opt` is set, the
+/// search only runs on the splits pinned by a point in time.
+#[instrument(skip_all)]
+pub(crate) async fn root_search_with_pinned_splits(
+    searcher_context: &SearcherContext,
+    search_request: SearchRequest,
     mut metastore: MetastoreServiceClient,
+    storage_resolver: &StorageResolver,
     cluster_client: &ClusterClient,
+    pinned_splits_opt: Option<&PinnedSplits>,
 ) -> crate::Result<SearchResponse> {
     info!(searcher_context = ?searcher_context, search_request = ?search_request);
-    let start_instant = tokio::time::Instant::now();
+    let start_instant = Instant::now();
+    // The deadline is computed once, when the root receives the request, and bounds both the leaf
+    // search and the fetch docs phases.
+    let deadline_opt: Option<Instant> = search_request
+        .timeout_millis
+        .map(|timeout_millis| start_instant + Duration::from_millis(timeout_millis as u64));
+    let PreparedRootSearch {
+        search_request,
+        indexes_metas_for_leaf_search,
+        split_metadatas,
+        pipeline_aggregations_opt,
+    } = prepare_root_search(search_request, &mut metastore, pinned_splits_opt).await?;
+
+    // Dashboards tend to re-issue the exact same requests: as long as the targeted splits did
+    // not change, the response can be served without searching the splits again.
+    let search_result_cache_key_opt =
+        SearchResultCacheKey::from_request_and_splits(&search_request, &split_metadatas);
+    let cached_search_response_opt = search_result_cache_key_opt
+        .as_ref()
+        .and_then(|cache_key| searcher_context.search_result_cache.get(cache_key));
+    let mut search_response = if let Some(cached_search_response) = cached_search_response_opt {
+        cached_search_response
+    } else {
+        // The search is admitted once its indexes are resolved, and responses served from the
+        // cache are not subject to admission control.
+        let _search_admission_permit_opt = match &searcher_context.search_admission_controller_opt {
+            Some(search_admission_controller) => {
+                let index_uids: Vec<IndexUid> =
+                    indexes_metas_for_leaf_search.keys().cloned().collect();
+                let search_admission_permit = search_admission_controller
+                    .acquire(
+                        search_request.priority(),
+                        &index_uids,
+                        search_request.api_key_id.as_deref(),
+                    )
+                    .await?;
+                Some(search_admission_permit)
+            }
+            None => None,
+        };
+        let split_metadatas = prune_splits_with_bloom_filters(
+            searcher_context,
+            storage_resolver,
+            &indexes_metas_for_leaf_search,
+            &search_request,
+            split_metadatas,
+        )
+        .await?;
+        // If no index matched, we still go through root_search_aux instead of directly
+        // returning an empty response to make sure we generate
+        // a (pretty useless) scroll id if requested.
+        let search_response = root_search_aux(
+            searcher_context,
+            &indexes_metas_for_leaf_search,
+            search_request,
+            split_metadatas,
+            cluster_client,
+            deadline_opt,
+        )
+        .await?;
+        if let Some(search_result_cache_key) = search_result_cache_key_opt {
+            searcher_context
+                .search_result_cache
+                .put(search_result_cache_key, &search_response);
+        }
+        search_response
+    };
+    apply_pipeline_aggregations(pipeline_aggregations_opt.as_ref(), &mut search_response)?;
+    search_response.elapsed_time_micros = start_instant.elapsed().as_micros() as u64;
+    Ok(search_response)
+}
+
+/// A root search request resolved against the metastore: the targeted indexes are validated
+/// and the relevant splits are listed, so the request is ready to be dispatched to the leaves.
+pub(crate) struct PreparedRootSearch {
+    pub search_request: SearchRequest,
+    pub indexes_metas_for_leaf_search: IndexesMetasForLeafSearch,
+    pub split_metadatas: Vec<SplitMetadata>,
+    /// Pipeline aggregations are not sent to the leaves: they are evaluated on the final
+    /// aggregation results.
+    pub pipeline_aggregations_opt: Option<PipelineAggregations>,
+}
+
+/// Resolves the indexes and lists the splits targeted by a root search request.
+///
+/// If no index matches the request, the prepared search has no index and no split.
+pub(crate) async fn prepare_root_search(
+    mut search_request: SearchRequest,
+    metastore: &mut MetastoreServiceClient,
+    pinned_splits_opt: Option<&PinnedSplits>,
+) -> crate::Result<PreparedRootSearch> {
+    let pipeline_aggregations_opt =
+        PipelineAggregations::extract_from_search_request(&mut search_request)?;
     let list_indexes_metadatas_request = ListIndexesMetadataRequest {
         index_id_patterns: search_request.index_id_patterns.clone(),
     };
@@ -1094,43 +1579,48 @@ pub async fn root_search(
     check_all_index_metadata_found(&indexes_metadata[..], &search_request.index_id_patterns[..])?;
 
     if indexes_metadata.is_empty() {
-        // We go through root_search_aux instead of directly
-        // returning an empty response to make sure we generate
-        // a (pretty useless) scroll id if requested.
-        let mut search_response = root_search_aux(
-            searcher_context,
-            &HashMap::default(),
+        return Ok(PreparedRootSearch {
             search_request,
-            Vec::new(),
-            cluster_client,
-        )
-        .await?;
-        search_response.elapsed_time_micros = start_instant.elapsed().as_micros() as u64;
-        return Ok(search_response);
+            indexes_metas_for_leaf_search: HashMap::default(),
+            split_metadatas: Vec::new(),
+            pipeline_aggregations_opt,
+        });
     }
 
     let request_metadata = validate_request_and_build_metadata(&indexes_metadata, &search_request)?;
+    search_request.named_queries = request_metadata.named_queries_resolved;
+    search_request.aggregation_request = request_metadata.aggregation_request_resolved;
+
     let split_metadatas = refine_and_list_matches(
-        &mut metastore,
+        metastore,
         &mut search_request,
         indexes_metadata,
         request_metadata.query_ast_resolved,
         request_metadata.sort_fields_is_datetime,
         request_metadata.timestamp_field_opt,
+        pinned_splits_opt,
     )
     .await?;
 
-    let mut search_response = root_search_aux(
-        searcher_context,
-        &request_metadata.indexes_meta_for_leaf_search,
+    Ok(PreparedRootSearch {
         search_request,
+        indexes_metas_for_leaf_search: request_metadata.indexes_meta_for_leaf_search,
         split_metadatas,
-        cluster_client,
-    )
-    .await?;
+        pipeline_aggregations_opt,
+    })
+}
 
-    search_response.elapsed_time_micros = start_instant.elapsed().as_micros() as u64;
-    Ok(search_response)
+/// Evaluates the pipeline aggregations, if any, on the aggregation results of a search response.
+pub(crate) fn apply_pipeline_aggregations(
+    pipeline_aggregations_opt: Option<&PipelineAggregations>,
+    search_response: &mut SearchResponse,
+) -> crate::Result<()> {
+    if let (Some(pipeline_aggregations), Some(aggregation_results_json)) =
+        (pipeline_aggregations_opt, &search_response.aggregation)
+    {
+        search_response.aggregation = Some(pipeline_aggregations.apply(aggregation_results_json)?);
+    }
+    Ok(())
 }
 
 /// Returns details on how a query would be executed
@@ -1138,6 +1628,7 @@ pub async fn search_plan(
     mut search_request: SearchRequest,
     mut metastore: MetastoreServiceClient,
 ) -> crate::Result<SearchPlanResponse> {
+    PipelineAggregations::extract_from_search_request(&mut search_request)?;
     let list_indexes_metadatas_request = ListIndexesMetadataRequest {
         index_id_patterns: search_request.index_id_patterns.clone(),
     };
@@ -1172,6 +1663,7 @@ pub async fn search_plan(
         request_metadata.query_ast_resolved.clone(),
         request_metadata.sort_fields_is_datetime,
         request_metadata.timestamp_field_opt,
+        None,
     )
     .await?;
 
@@ -1618,6 +2110,10 @@ pub fn jobs_to_leaf_request(
 /// Builds a list of [`FetchDocsRequest`], one per index, from a list of [`FetchDocsJob`].
 pub fn jobs_to_fetch_docs_requests(
     snippet_request_opt: Option<SnippetRequest>,
+    named_queries: &[NamedQuery],
+    explain_query_ast_opt: Option<String>,
+    docvalue_fields: &[String],
+    skip_source: bool,
     indexes_metas_for_leaf_search: &IndexesMetasForLeafSearch,
     jobs: Vec<FetchDocsJob>,
 ) -> crate::Result<Vec<FetchDocsRequest>> {
@@ -1650,6 +2146,10 @@ pub fn jobs_to_fetch_docs_requests(
                 index_uri: index_meta.index_uri.to_string(),
                 snippet_request: snippet_request_opt.clone(),
                 doc_mapper: index_meta.doc_mapper_str.clone(),
+                named_queries: named_queries.to_vec(),
+                explain_query_ast: explain_query_ast_opt.clone(),
+                docvalue_fields: docvalue_fields.to_vec(),
+                skip_source,
             };
             fetch_docs_requests.push(fetch_docs_req);
 
@@ -1674,7 +2174,7 @@ mod tests {
         ListIndexesMetadataResponse, ListSplitsResponse, MockMetastoreService,
     };
     use quickwit_proto::search::{
-        ScrollRequest, SortByValue, SortOrder, SortValue, SplitSearchError,
+        ScrollRequest, SortByValue, SortMissing, SortOrder, SortValue, SplitSearchError,
     };
     use quickwit_query::query_ast::{qast_helper, qast_json_helper, query_ast_from_user_text};
     use tantivy::schema::{FAST, STORED, TEXT};
@@ -1713,6 +2213,7 @@ mod tests {
             field_is_not_text_err.to_string(),
             "the snippet field `ip` must be of type `Str`, got `IpAddr`"
         );
+        check_snippet_fields_validation(&["*".to_string(), "de*".to_string()]).unwrap();
     }
 
     #[test]
@@ -1760,6 +2261,7 @@ mod tests {
         let indexing_settings = IndexingSettings::default();
         let search_settings = SearchSettings {
             default_search_fields: vec!["body".to_string()],
+            ..Default::default()
         };
         IndexMetadata::new(IndexConfig {
             index_id: index_id.to_string(),
@@ -1784,11 +2286,13 @@ mod tests {
                     field_name: "timestamp".to_string(),
                     sort_order: SortOrder::Desc as i32,
                     sort_datetime_format: Some(SortDatetimeFormat::UnixTimestampMillis as i32),
+                    missing: SortMissing::Last as i32,
                 },
                 SortField {
                     field_name: "_doc".to_string(),
                     sort_order: SortOrder::Asc as i32,
                     sort_datetime_format: None,
+                    missing: SortMissing::Last as i32,
                 },
             ],
             ..Default::default()
@@ -1900,6 +2404,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_request_and_build_metadatas_resolves_filters_aggregations() {
+        let aggregation_request = r#"{
+            "statuses": {
+                "filters": {
+                    "filters": {
+                        "errors": "error",
+                        "tests": {"type": "term", "field": "body", "value": "test"}
+                    }
+                }
+            }
+        }"#;
+        let search_request = quickwit_proto::search::SearchRequest {
+            index_id_patterns: vec!["test-index".to_string()],
+            query_ast: qast_json_helper("test", &["body"]),
+            max_hits: 10,
+            aggregation_request: Some(aggregation_request.to_string()),
+            ..Default::default()
+        };
+        let index_metadata_1 =
+            index_metadata_for_multi_indexes_test("test-index-1", "ram:///test-index-1");
+        let index_metadata_2 =
+            index_metadata_for_multi_indexes_test("test-index-2", "ram:///test-index-2");
+        let request_metadata = validate_request_and_build_metadata(
+            &[index_metadata_1.clone(), index_metadata_2],
+            &search_request,
+        )
+        .unwrap();
+        let aggregation_request_resolved: JsonValue =
+            serde_json::from_str(&request_metadata.aggregation_request_resolved.unwrap()).unwrap();
+        assert_eq!(
+            aggregation_request_resolved["statuses"]["filters"]["filters"]["errors"],
+            serde_json::to_value(qast_helper("error", &["body"])).unwrap()
+        );
+
+        let index_metadata_3 = IndexMetadata::for_test("test-index-3", "ram:///test-index-3");
+        let search_error = validate_request_and_build_metadata(
+            &[index_metadata_1, index_metadata_3],
+            &search_request,
+        )
+        .unwrap_err();
+        assert_eq!(
+            search_error.to_string(),
+            "resolved filters aggregation queries must be the same across indexes"
+        );
+    }
+
     fn index_metadata_for_multi_indexes_test_with_incompatible_sort_type(
         index_id: &str,
         index_uri: &str,
@@ -1932,6 +2483,7 @@ mod tests {
         let indexing_settings = IndexingSettings::default();
         let search_settings = SearchSettings {
             default_search_fields: vec!["body".to_string()],
+            ..Default::default()
         };
         IndexMetadata::new(IndexConfig {
             index_id: index_id.to_string(),
@@ -1955,6 +2507,7 @@ mod tests {
                 field_name: "response_date".to_string(),
                 sort_order: SortOrder::Desc as i32,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             }],
             ..Default::default()
         };
@@ -2048,11 +2601,13 @@ mod tests {
                 field_name: "_doc".to_string(),
                 sort_order: 0,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             },
             SortField {
                 field_name: "_shard_doc".to_string(),
                 sort_order: 0,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             },
         ];
         let mut schema_builder = Schema::builder();
@@ -2072,11 +2627,13 @@ mod tests {
                 field_name: "timestamp".to_string(),
                 sort_order: 0,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             },
             SortField {
                 field_name: "id".to_string(),
                 sort_order: 0,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             },
         ];
         let mut schema_builder = Schema::builder();
@@ -2096,11 +2653,13 @@ mod tests {
                 field_name: "timestamp".to_string(),
                 sort_order: 0,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             },
             SortField {
                 field_name: "id".to_string(),
                 sort_order: 0,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             },
         ];
         let mut schema_builder = Schema::builder();
@@ -2139,11 +2698,13 @@ mod tests {
                 field_name: "timestamp".to_string(),
                 sort_order: 0,
                 sort_datetime_format: Some(SortDatetimeFormat::UnixTimestampMillis as i32),
+                missing: SortMissing::Last as i32,
             },
             SortField {
                 field_name: "id".to_string(),
                 sort_order: 0,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             },
         ];
         validate_sort_by_fields_and_search_after(&sort_fields, &None).unwrap();
@@ -2156,11 +2717,13 @@ mod tests {
                 field_name: "timestamp".to_string(),
                 sort_order: 0,
                 sort_datetime_format: Some(SortDatetimeFormat::UnixTimestampMillis as i32),
+                missing: SortMissing::Last as i32,
             },
             SortField {
                 field_name: "id".to_string(),
                 sort_order: 0,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             },
         ];
         let partial_hit = PartialHit {
@@ -2173,6 +2736,7 @@ mod tests {
             split_id: "".to_string(),
             segment_ord: 0,
             doc_id: 0,
+            collapse_value: None,
         };
         validate_sort_by_fields_and_search_after(&sort_fields, &Some(partial_hit)).unwrap();
     }
@@ -2184,11 +2748,13 @@ mod tests {
                 field_name: "timestamp".to_string(),
                 sort_order: 0,
                 sort_datetime_format: Some(SortDatetimeFormat::UnixTimestampMillis as i32),
+                missing: SortMissing::Last as i32,
             },
             SortField {
                 field_name: "_doc".to_string(),
                 sort_order: 0,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             },
         ];
         let partial_hit = PartialHit {
@@ -2199,6 +2765,7 @@ mod tests {
             split_id: "split1".to_string(),
             segment_ord: 1,
             doc_id: 1,
+            collapse_value: None,
         };
         validate_sort_by_fields_and_search_after(&sort_fields, &Some(partial_hit)).unwrap();
     }
@@ -2253,11 +2820,13 @@ mod tests {
                 field_name: "timestamp".to_string(),
                 sort_order: 0,
                 sort_datetime_format: Some(SortDatetimeFormat::UnixTimestampMillis as i32),
+                missing: SortMissing::Last as i32,
             },
             SortField {
                 field_name: "id".to_string(),
                 sort_order: 0,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             },
         ];
         let partial_hit = PartialHit {
@@ -2268,6 +2837,7 @@ mod tests {
             split_id: "split1".to_string(),
             segment_ord: 1,
             doc_id: 1,
+            collapse_value: None,
         };
         let error =
             validate_sort_by_fields_and_search_after(&sort_fields, &Some(partial_hit)).unwrap_err();
@@ -2286,11 +2856,13 @@ mod tests {
                 field_name: "timestamp".to_string(),
                 sort_order: 0,
                 sort_datetime_format: Some(SortDatetimeFormat::UnixTimestampMillis as i32),
+                missing: SortMissing::Last as i32,
             },
             SortField {
                 field_name: "_doc".to_string(),
                 sort_order: 0,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             },
         ];
         let partial_hit = PartialHit {
@@ -2301,6 +2873,7 @@ mod tests {
             split_id: "".to_string(),
             segment_ord: 1,
             doc_id: 1,
+            collapse_value: None,
         };
         let error =
             validate_sort_by_fields_and_search_after(&sort_fields, &Some(partial_hit)).unwrap_err();
@@ -2319,11 +2892,13 @@ mod tests {
                 field_name: "timestamp".to_string(),
                 sort_order: 0,
                 sort_datetime_format: Some(SortDatetimeFormat::UnixTimestampMillis as i32),
+                missing: SortMissing::Last as i32,
             },
             SortField {
                 field_name: "id".to_string(),
                 sort_order: 0,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             },
         ];
         let partial_hit = PartialHit {
@@ -2334,6 +2909,7 @@ mod tests {
             split_id: "split1".to_string(),
             segment_ord: 1,
             doc_id: 1,
+            collapse_value: None,
         };
         let error =
             validate_sort_by_fields_and_search_after(&sort_fields, &Some(partial_hit)).unwrap_err();
@@ -2367,16 +2943,19 @@ mod tests {
                 field_name: "timestamp".to_string(),
                 sort_order: 0,
                 sort_datetime_format: Some(SortDatetimeFormat::UnixTimestampMillis as i32),
+                missing: SortMissing::Last as i32,
             },
             SortField {
                 field_name: "timestamp".to_string(),
                 sort_order: 0,
                 sort_datetime_format: Some(SortDatetimeFormat::UnixTimestampMillis as i32),
+                missing: SortMissing::Last as i32,
             },
             SortField {
                 field_name: "timestamp".to_string(),
                 sort_order: 0,
                 sort_datetime_format: Some(SortDatetimeFormat::UnixTimestampMillis as i32),
+                missing: SortMissing::Last as i32,
             },
         ];
         let error = validate_sort_by_fields_and_search_after(&sort_fields, &None).unwrap_err();
@@ -2397,6 +2976,7 @@ mod tests {
             split_id: split_id.to_string(),
             segment_ord: 1,
             doc_id,
+            collapse_value: None,
         }
     }
 
@@ -2411,6 +2991,7 @@ mod tests {
             split_id: split_id.to_string(),
             segment_ord: 1,
             doc_id,
+            collapse_value: None,
         }
     }
 
@@ -2429,6 +3010,9 @@ mod tests {
                 .expect("Json serialization should not fail"),
                 partial_hit: Some(req),
                 leaf_snippet_json: None,
+                matched_queries: Vec::new(),
+                explanation_json: None,
+                leaf_docvalue_fields_json: None,
             })
             .collect()
     }
@@ -2522,6 +3106,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await
@@ -2590,6 +3175,7 @@ mod tests {
             &searcher_context,
             search_request,
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await
@@ -2682,6 +3268,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await
@@ -2737,6 +3324,7 @@ mod tests {
                             error: "some error".to_string(),
                             split_id: "split2".to_string(),
                             retryable_error: true,
+                            timed_out: false,
                         }],
                         num_attempted_splits: 2,
                         ..Default::default()
@@ -2766,6 +3354,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await
@@ -2786,6 +3375,7 @@ mod tests {
                 field_name: "response_date".to_string(),
                 sort_order: SortOrder::Asc.into(),
                 sort_datetime_format: Some(SortDatetimeFormat::UnixTimestampNanos as i32),
+                missing: SortMissing::Last as i32,
             }],
             ..Default::default()
         };
@@ -2825,6 +3415,7 @@ mod tests {
                             split_id: "split1".to_string(),
                             segment_ord: 0,
                             doc_id: 0,
+                            collapse_value: None,
                         },
                         quickwit_proto::search::PartialHit {
                             sort_value: None,
@@ -2832,6 +3423,7 @@ mod tests {
                             split_id: "split1".to_string(),
                             segment_ord: 0,
                             doc_id: 1,
+                            collapse_value: None,
                         },
                     ],
                     failed_splits: Vec::new(),
@@ -2859,6 +3451,7 @@ mod tests {
                             split_id: "split2".to_string(),
                             segment_ord: 0,
                             doc_id: 1,
+                            collapse_value: None,
                         },
                         quickwit_proto::search::PartialHit {
                             sort_value: Some(SortValue::I64(1i64).into()),
@@ -2866,6 +3459,7 @@ mod tests {
                             split_id: "split2".to_string(),
                             segment_ord: 0,
                             doc_id: 0,
+                            collapse_value: None,
                         },
                         quickwit_proto::search::PartialHit {
                             sort_value: None,
@@ -2873,6 +3467,7 @@ mod tests {
                             split_id: "split2".to_string(),
                             segment_ord: 0,
                             doc_id: 2,
+                            collapse_value: None,
                         },
                     ],
                     failed_splits: Vec::new(),
@@ -2898,6 +3493,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request.clone(),
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await?;
@@ -2912,6 +3508,7 @@ mod tests {
                 doc_id: 1,
                 sort_value: Some(SortValue::I64(-1i64).into()),
                 sort_value2: None,
+                collapse_value: None,
             }
         );
         assert_eq!(
@@ -2922,6 +3519,7 @@ mod tests {
                 doc_id: 0,
                 sort_value: Some(SortValue::I64(1i64).into()),
                 sort_value2: None,
+                collapse_value: None,
             }
         );
         assert_eq!(
@@ -2932,6 +3530,7 @@ mod tests {
                 doc_id: 0,
                 sort_value: Some(SortValue::U64(2u64).into()),
                 sort_value2: None,
+                collapse_value: None,
             }
         );
         assert_eq!(
@@ -2942,6 +3541,7 @@ mod tests {
                 doc_id: 1,
                 sort_value: None,
                 sort_value2: None,
+                collapse_value: None,
             }
         );
         assert_eq!(
@@ -2952,6 +3552,7 @@ mod tests {
                 doc_id: 2,
                 sort_value: None,
                 sort_value2: None,
+                collapse_value: None,
             }
         );
         Ok(())
@@ -2968,6 +3569,7 @@ mod tests {
                 field_name: "response_date".to_string(),
                 sort_order: SortOrder::Desc.into(),
                 sort_datetime_format: Some(SortDatetimeFormat::UnixTimestampNanos as i32),
+                missing: SortMissing::Last as i32,
             }],
             ..Default::default()
         };
@@ -3007,6 +3609,7 @@ mod tests {
                             split_id: "split1".to_string(),
                             segment_ord: 0,
                             doc_id: 0,
+                            collapse_value: None,
                         },
                         quickwit_proto::search::PartialHit {
                             sort_value: None,
@@ -3014,6 +3617,7 @@ mod tests {
                             split_id: "split1".to_string(),
                             segment_ord: 0,
                             doc_id: 1,
+                            collapse_value: None,
                         },
                     ],
                     failed_splits: Vec::new(),
@@ -3041,6 +3645,7 @@ mod tests {
                             split_id: "split2".to_string(),
                             segment_ord: 0,
                             doc_id: 0,
+                            collapse_value: None,
                         },
                         quickwit_proto::search::PartialHit {
                             sort_value: Some(SortValue::I64(-1i64).into()),
@@ -3048,6 +3653,7 @@ mod tests {
                             split_id: "split2".to_string(),
                             segment_ord: 0,
                             doc_id: 1,
+                            collapse_value: None,
                         },
                         quickwit_proto::search::PartialHit {
                             sort_value: None,
@@ -3055,6 +3661,7 @@ mod tests {
                             split_id: "split2".to_string(),
                             segment_ord: 0,
                             doc_id: 2,
+                            collapse_value: None,
                         },
                     ],
                     failed_splits: Vec::new(),
@@ -3080,6 +3687,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request.clone(),
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await?;
@@ -3094,6 +3702,7 @@ mod tests {
                 doc_id: 0,
                 sort_value: Some(SortValue::U64(2u64).into()),
                 sort_value2: None,
+                collapse_value: None,
             }
         );
         assert_eq!(
@@ -3104,6 +3713,7 @@ mod tests {
                 doc_id: 0,
                 sort_value: Some(SortValue::I64(1i64).into()),
                 sort_value2: None,
+                collapse_value: None,
             }
         );
         assert_eq!(
@@ -3114,6 +3724,7 @@ mod tests {
                 doc_id: 1,
                 sort_value: Some(SortValue::I64(-1i64).into()),
                 sort_value2: None,
+                collapse_value: None,
             }
         );
         assert_eq!(
@@ -3124,6 +3735,7 @@ mod tests {
                 doc_id: 2,
                 sort_value: None,
                 sort_value2: None,
+                collapse_value: None,
             }
         );
         assert_eq!(
@@ -3134,6 +3746,7 @@ mod tests {
                 doc_id: 1,
                 sort_value: None,
                 sort_value2: None,
+                collapse_value: None,
             }
         );
         Ok(())
@@ -3229,6 +3842,7 @@ mod tests {
                             error: "mock_error".to_string(),
                             split_id: "split2".to_string(),
                             retryable_error: true,
+                            timed_out: false,
                         }],
                         num_attempted_splits: 1,
                         ..Default::default()
@@ -3252,6 +3866,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await
@@ -3309,6 +3924,7 @@ mod tests {
                         error: "mock_error".to_string(),
                         split_id: "split2".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 1,
                     ..Default::default()
@@ -3370,6 +3986,7 @@ mod tests {
                         error: "mock_error".to_string(),
                         split_id: "split1".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 1,
                     ..Default::default()
@@ -3392,6 +4009,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await
@@ -3442,6 +4060,7 @@ mod tests {
                             error: "mock_error".to_string(),
                             split_id: "split1".to_string(),
                             retryable_error: true,
+                            timed_out: false,
                         }],
                         num_attempted_splits: 1,
                         ..Default::default()
@@ -3471,6 +4090,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await
@@ -3480,6 +4100,152 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_root_search_with_timed_out_splits() -> anyhow::Result<()> {
+        let search_request = quickwit_proto::search::SearchRequest {
+            index_id_patterns: vec!["test-index".to_string()],
+            query_ast: qast_json_helper("test", &["body"]),
+            max_hits: 10,
+            timeout_millis: Some(10_000),
+            ..Default::default()
+        };
+        let mut mock_metastore = MockMetastoreService::new();
+        let index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
+        let index_uid = index_metadata.index_uid.clone();
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .returning(move |_index_ids_query| {
+                Ok(ListIndexesMetadataResponse::for_test(vec![
+                    index_metadata.clone()
+                ]))
+            });
+        mock_metastore
+            .expect_list_splits()
+            .returning(move |_list_splits_request| {
+                let splits = vec![
+                    MockSplitBuilder::new("split1")
+                        .with_index_uid(&index_uid)
+                        .build(),
+                    MockSplitBuilder::new("split2")
+                        .with_index_uid(&index_uid)
+                        .build(),
+                ];
+                let splits_response = ListSplitsResponse::try_from_splits(splits).unwrap();
+                Ok(ServiceStream::from(vec![Ok(splits_response)]))
+            });
+        let mut mock_search_service = MockSearchService::new();
+        // Timed out splits are not retried.
+        mock_search_service.expect_leaf_search().times(1).returning(
+            |leaf_search_req: quickwit_proto::search::LeafSearchRequest| {
+                // The leaves are given the time left before the deadline of the root.
+                let timeout_millis = leaf_search_req
+                    .search_request
+                    .unwrap()
+                    .timeout_millis
+                    .unwrap();
+                assert!(timeout_millis > 0 && timeout_millis <= 10_000);
+                Ok(quickwit_proto::search::LeafSearchResponse {
+                    num_hits: 1,
+                    partial_hits: vec![mock_partial_hit("split1", 2, 2)],
+                    failed_splits: vec![SplitSearchError {
+                        error: "search timed out".to_string(),
+                        split_id: "split2".to_string(),
+                        retryable_error: false,
+                        timed_out: true,
+                    }],
+                    num_attempted_splits: 2,
+                    ..Default::default()
+                })
+            },
+        );
+        mock_search_service.expect_fetch_docs().returning(
+            |fetch_docs_req: quickwit_proto::search::FetchDocsRequest| {
+                Ok(quickwit_proto::search::FetchDocsResponse {
+                    hits: get_doc_for_fetch_req(fetch_docs_req),
+                })
+            },
+        );
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service)]);
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+        let cluster_client = ClusterClient::new(search_job_placer.clone());
+        let search_response = root_search(
+            &SearcherContext::for_test(),
+            search_request,
+            MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
+            &cluster_client,
+        )
+        .await
+        .unwrap();
+        assert_eq!(search_response.num_hits, 1);
+        assert_eq!(search_response.hits.len(), 1);
+        assert!(search_response.timed_out);
+        assert_eq!(search_response.timed_out_splits, vec!["split2".to_string()]);
+        assert!(search_response.errors.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_root_search_fetch_docs_bounded_by_deadline() -> anyhow::Result<()> {
+        let search_request = quickwit_proto::search::SearchRequest {
+            index_id_patterns: vec!["test-index".to_string()],
+            query_ast: qast_json_helper("test", &["body"]),
+            max_hits: 10,
+            timeout_millis: Some(50),
+            ..Default::default()
+        };
+        let mut mock_metastore = MockMetastoreService::new();
+        let index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
+        let index_uid = index_metadata.index_uid.clone();
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .returning(move |_index_ids_query| {
+                Ok(ListIndexesMetadataResponse::for_test(vec![
+                    index_metadata.clone()
+                ]))
+            });
+        mock_metastore
+            .expect_list_splits()
+            .returning(move |_list_splits_request| {
+                let splits = vec![MockSplitBuilder::new("split1")
+                    .with_index_uid(&index_uid)
+                    .build()];
+                let splits_response = ListSplitsResponse::try_from_splits(splits).unwrap();
+                Ok(ServiceStream::from(vec![Ok(splits_response)]))
+            });
+        let mut mock_search_service = MockSearchService::new();
+        // The leaf search uses up the whole timeout.
+        mock_search_service.expect_leaf_search().times(1).returning(
+            |_leaf_search_req: quickwit_proto::search::LeafSearchRequest| {
+                std::thread::sleep(Duration::from_millis(100));
+                Ok(quickwit_proto::search::LeafSearchResponse {
+                    num_hits: 1,
+                    partial_hits: vec![mock_partial_hit("split1", 2, 2)],
+                    num_attempted_splits: 1,
+                    ..Default::default()
+                })
+            },
+        );
+        mock_search_service.expect_fetch_docs().times(0);
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service)]);
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+        let cluster_client = ClusterClient::new(search_job_placer.clone());
+        let search_response = root_search(
+            &SearcherContext::for_test(),
+            search_request,
+            MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
+            &cluster_client,
+        )
+        .await
+        .unwrap();
+        assert_eq!(search_response.num_hits, 1);
+        assert!(search_response.hits.is_empty());
+        assert!(search_response.timed_out);
+        assert_eq!(search_response.timed_out_splits, vec!["split1".to_string()]);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_root_search_single_split_retry_single_node_fails() -> anyhow::Result<()> {
         let search_request = quickwit_proto::search::SearchRequest {
@@ -3518,6 +4284,7 @@ mod tests {
                         error: "mock_error".to_string(),
                         split_id: "split1".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 1,
                     ..Default::default()
@@ -3536,6 +4303,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await;
@@ -3603,6 +4371,7 @@ mod tests {
                         error: "mock_error".to_string(),
                         split_id: "split1".to_string(),
                         retryable_error: true,
+                        timed_out: false,
                     }],
                     num_attempted_splits: 1,
                     ..Default::default()
@@ -3624,6 +4393,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await
@@ -3704,6 +4474,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await
@@ -3750,6 +4521,7 @@ mod tests {
                 ..Default::default()
             },
             metastore.clone(),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await
@@ -3764,6 +4536,7 @@ mod tests {
                 ..Default::default()
             },
             metastore,
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await
@@ -3826,6 +4599,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await;
@@ -3873,6 +4647,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             metastore.clone(),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await;
@@ -3893,6 +4668,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             metastore,
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await;
@@ -4348,6 +5124,7 @@ mod tests {
                 &searcher_context,
                 search_request,
                 MetastoreServiceClient::from_mock(mock_metastore),
+                &StorageResolver::for_test(),
                 &cluster_client,
             )
             .await
@@ -4614,6 +5391,7 @@ mod tests {
                 &searcher_context,
                 search_request,
                 MetastoreServiceClient::from_mock(mock_metastore),
+                &StorageResolver::for_test(),
                 &cluster_client,
             )
             .await
@@ -4791,6 +5569,7 @@ mod tests {
             &SearcherContext::for_test(),
             search_request,
             MetastoreServiceClient::from_mock(mock_metastore),
+            &StorageResolver::for_test(),
             &cluster_client,
         )
         .await
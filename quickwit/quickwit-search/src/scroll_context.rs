@@ -254,6 +254,7 @@ mod tests {
             split_id: "split".to_string(),
             segment_ord: 1,
             doc_id: 2,
+            collapse_value: None,
         };
         let scroll = ScrollKeyAndStartOffset::new_with_start_offset(10, 100, partial_hit);
         let scroll_str = scroll.to_string();
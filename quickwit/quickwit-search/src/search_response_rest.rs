@@ -19,8 +19,10 @@
 
 use std::convert::TryFrom;
 
-use quickwit_common::truncate_str;
-use quickwit_proto::search::SearchResponse;
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use base64::Engine;
+use quickwit_common::{is_false, truncate_str};
+use quickwit_proto::search::{PartialHit, SearchResponse, SplitSearchProfile};
 use quickwit_query::query_ast::QueryAst;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -40,6 +42,16 @@ pub struct SearchResponseRest {
     #[schema(value_type = Vec<Object>)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub snippets: Option<Vec<JsonValue>>,
+    /// Inner hits of each hit, if the hits are collapsed with inner hits.
+    #[schema(value_type = Vec<Vec<Object>>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inner_hits: Option<Vec<Vec<JsonValue>>>,
+    /// Explanation of the score of each hit, if requested with `explain`.
+    #[schema(value_type = Vec<Object>)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanations: Option<Vec<JsonValue>>,
     /// Elapsed time.
     pub elapsed_time_micros: u64,
     /// Search errors.
@@ -48,6 +60,60 @@ pub struct SearchResponseRest {
     #[schema(value_type = Object)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub aggregations: Option<JsonValue>,
+    /// Cursor of the last hit. Passing it as the `search_after` parameter of the same request
+    /// returns the hits that follow.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_after: Option<String>,
+    /// True if the search timed out. The hits and aggregations are then computed on the splits
+    /// searched before the timeout only.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub timed_out: bool,
+    /// Splits skipped because the search timed out.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub timed_out_splits: Vec<String>,
+    /// Time spent and bytes downloaded searching each split, if requested with `profile`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub split_profiles: Vec<SplitSearchProfile>,
+}
+
+/// Encodes the sort values and the address of a hit into an opaque cursor.
+///
+/// The address of the hit breaks the ties between hits with the same sort values, so that
+/// paginating with the cursor neither skips nor repeats hits.
+pub fn encode_search_after_cursor(partial_hit: &PartialHit) -> String {
+    let payload =
+        serde_json::to_vec(partial_hit).expect("serializing PartialHit should never fail");
+    BASE64_URL_SAFE_NO_PAD.encode(payload)
+}
+
+/// Decodes a cursor created with [`encode_search_after_cursor`].
+pub fn decode_search_after_cursor(cursor: &str) -> Result<PartialHit, SearchError> {
+    let payload = BASE64_URL_SAFE_NO_PAD
+        .decode(cursor)
+        .map_err(|_| SearchError::InvalidArgument("search_after cursor is invalid".to_string()))?;
+    let partial_hit: PartialHit = serde_json::from_slice(&payload).map_err(|_| {
+        SearchError::InvalidArgument("search_after cursor is malformed".to_string())
+    })?;
+    if partial_hit.split_id.is_empty() {
+        return Err(SearchError::InvalidArgument(
+            "search_after cursor is malformed".to_string(),
+        ));
+    }
+    Ok(partial_hit)
+}
+
+fn parse_hit_json(hit_json: &str) -> Result<JsonValue, SearchError> {
+    serde_json::from_str(hit_json).map_err(|err| {
+        SearchError::Internal(format!(
+            "failed to serialize document `{}` to JSON: `{}`",
+            truncate_str(hit_json, 100),
+            err
+        ))
+    })
 }
 
 impl TryFrom<SearchResponse> for SearchResponseRest {
@@ -56,15 +122,28 @@ impl TryFrom<SearchResponse> for SearchResponseRest {
     fn try_from(search_response: SearchResponse) -> Result<Self, Self::Error> {
         let mut documents = Vec::with_capacity(search_response.hits.len());
         let mut snippets = Vec::new();
+        let mut inner_hits = Vec::new();
+        let mut explanations = Vec::new();
+        let has_inner_hits = search_response
+            .hits
+            .iter()
+            .any(|hit| !hit.inner_hits.is_empty());
+        let search_after_opt = search_response
+            .hits
+            .last()
+            .and_then(|hit| hit.partial_hit.as_ref())
+            .map(encode_search_after_cursor);
         for hit in search_response.hits {
-            let document: JsonValue = serde_json::from_str(&hit.json).map_err(|err| {
-                SearchError::Internal(format!(
-                    "failed to serialize document `{}` to JSON: `{}`",
-                    truncate_str(&hit.json, 100),
-                    err
-                ))
-            })?;
-            documents.push(document);
+            documents.push(parse_hit_json(&hit.json)?);
+
+            if has_inner_hits {
+                let inner_hit_documents: Vec<JsonValue> = hit
+                    .inner_hits
+                    .iter()
+                    .map(|inner_hit| parse_hit_json(&inner_hit.json))
+                    .collect::<Result<_, _>>()?;
+                inner_hits.push(inner_hit_documents);
+            }
 
             if let Some(snippet_json) = hit.snippet {
                 let snippet_opt: JsonValue =
@@ -75,6 +154,16 @@ impl TryFrom<SearchResponse> for SearchResponseRest {
                     })?;
                 snippets.push(snippet_opt);
             }
+
+            if let Some(explanation_json) = hit.explanation_json {
+                let explanation: JsonValue =
+                    serde_json::from_str(&explanation_json).map_err(|err| {
+                        SearchError::Internal(format!(
+                            "failed to deserialize score explanation `{explanation_json}`: `{err}`"
+                        ))
+                    })?;
+                explanations.push(explanation);
+            }
         }
 
         let snippet_opt = if !snippets.is_empty() {
@@ -95,9 +184,15 @@ impl TryFrom<SearchResponse> for SearchResponseRest {
             num_hits: search_response.num_hits,
             hits: documents,
             snippets: snippet_opt,
+            inner_hits: has_inner_hits.then_some(inner_hits),
+            explanations: (!explanations.is_empty()).then_some(explanations),
             elapsed_time_micros: search_response.elapsed_time_micros,
             errors: search_response.errors,
             aggregations: aggregations_opt,
+            search_after: search_after_opt,
+            timed_out: search_response.timed_out,
+            timed_out_splits: search_response.timed_out_splits,
+            split_profiles: search_response.split_profiles,
         })
     }
 }
@@ -0,0 +1,204 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use prost::Message;
+use quickwit_metastore::SplitMetadata;
+use quickwit_proto::search::{SearchPriority, SearchRequest, SearchResponse};
+use quickwit_proto::types::SplitId;
+use quickwit_storage::{MemorySizedCache, OwnedBytes};
+
+/// A cache to memoize `root_search` results.
+///
+/// Entries are keyed on the resolved search request and on the set of splits it targets. When
+/// the relevant splits change (new splits are published, or splits are merged or deleted), the
+/// key changes and the stale entry is simply never hit again, until it gets evicted.
+pub struct SearchResultCache {
+    content: MemorySizedCache<CacheKey>,
+}
+
+impl SearchResultCache {
+    pub fn new(capacity: usize) -> SearchResultCache {
+        SearchResultCache {
+            content: MemorySizedCache::with_capacity_in_bytes(
+                capacity,
+                &quickwit_storage::STORAGE_METRICS.search_result_cache,
+            ),
+        }
+    }
+
+    pub fn get(&self, key: &SearchResultCacheKey) -> Option<SearchResponse> {
+        let encoded_result = self.content.get(key)?;
+        // this should never fail
+        SearchResponse::decode(&*encoded_result).ok()
+    }
+
+    pub fn put(&self, key: SearchResultCacheKey, result: &SearchResponse) {
+        // Responses of searches that partially failed or timed out must not be served again, and
+        // the timings of profiled searches are only valid once.
+        if !result.errors.is_empty() || result.timed_out || !result.split_profiles.is_empty() {
+            return;
+        }
+        let encoded_result = result.encode_to_vec();
+        self.content.put(key, OwnedBytes::new(encoded_result));
+    }
+}
+
+/// A key inside a [`SearchResultCache`].
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub struct SearchResultCacheKey {
+    /// The request this matches, once resolved against the doc mappings of the targeted indexes.
+    request: SearchRequest,
+    /// The sorted ids of the splits targeted by the request. Splits are immutable, so the same
+    /// request on the same splits always yields the same response.
+    split_ids: Vec<SplitId>,
+}
+
+impl SearchResultCacheKey {
+    /// Returns `None` if the request cannot be cached: scroll requests create a scroll context on
+    /// every call.
+    pub fn from_request_and_splits(
+        search_request: &SearchRequest,
+        split_metadatas: &[SplitMetadata],
+    ) -> Option<Self> {
+        if search_request.scroll_ttl_secs.is_some() {
+            return None;
+        }
+        let mut split_ids: Vec<SplitId> = split_metadatas
+            .iter()
+            .map(|split_metadata| split_metadata.split_id.clone())
+            .collect();
+        split_ids.sort_unstable();
+
+        // The admission control parameters do not change the response.
+        let request = SearchRequest {
+            priority: SearchPriority::Interactive as i32,
+            api_key_id: None,
+            ..search_request.clone()
+        };
+        Some(SearchResultCacheKey { request, split_ids })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_metastore::SplitMetadata;
+    use quickwit_proto::search::{SearchPriority, SearchRequest, SearchResponse};
+
+    use super::{SearchResultCache, SearchResultCacheKey};
+
+    fn make_key(search_request: &SearchRequest, split_ids: &[&str]) -> SearchResultCacheKey {
+        let split_metadatas: Vec<SplitMetadata> = split_ids
+            .iter()
+            .map(|split_id| SplitMetadata::for_test(split_id.to_string()))
+            .collect();
+        SearchResultCacheKey::from_request_and_splits(search_request, &split_metadatas).unwrap()
+    }
+
+    #[test]
+    fn test_search_result_cache() {
+        let cache = SearchResultCache::new(64_000_000);
+
+        let search_request = SearchRequest {
+            index_id_patterns: vec!["test-idx".to_string()],
+            query_ast: "test".to_string(),
+            max_hits: 10,
+            ..Default::default()
+        };
+        let other_search_request = SearchRequest {
+            query_ast: "test2".to_string(),
+            ..search_request.clone()
+        };
+        let search_response = SearchResponse {
+            num_hits: 1234,
+            ..Default::default()
+        };
+
+        let key = make_key(&search_request, &["split_1", "split_2"]);
+        assert!(cache.get(&key).is_none());
+        cache.put(key, &search_response);
+
+        let key = make_key(&search_request, &["split_1", "split_2"]);
+        assert_eq!(cache.get(&key).unwrap(), search_response);
+        // The order of the splits does not matter.
+        let key = make_key(&search_request, &["split_2", "split_1"]);
+        assert_eq!(cache.get(&key).unwrap(), search_response);
+        // Neither does the admission control of the request.
+        let batch_search_request = SearchRequest {
+            priority: SearchPriority::Batch as i32,
+            api_key_id: Some("key-1".to_string()),
+            ..search_request.clone()
+        };
+        let key = make_key(&batch_search_request, &["split_1", "split_2"]);
+        assert_eq!(cache.get(&key).unwrap(), search_response);
+
+        let key = make_key(&other_search_request, &["split_1", "split_2"]);
+        assert!(cache.get(&key).is_none());
+        // A new split invalidates the entry.
+        let key = make_key(&search_request, &["split_1", "split_2", "split_3"]);
+        assert!(cache.get(&key).is_none());
+        // So does a merge.
+        let key = make_key(&search_request, &["split_4"]);
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn test_search_result_cache_skips_uncacheable_requests() {
+        let cache = SearchResultCache::new(64_000_000);
+
+        let scroll_request = SearchRequest {
+            index_id_patterns: vec!["test-idx".to_string()],
+            scroll_ttl_secs: Some(60),
+            ..Default::default()
+        };
+        let split_metadatas = vec![SplitMetadata::for_test("split_1".to_string())];
+        assert!(
+            SearchResultCacheKey::from_request_and_splits(&scroll_request, &split_metadatas)
+                .is_none()
+        );
+
+        let search_request = SearchRequest {
+            index_id_patterns: vec!["test-idx".to_string()],
+            ..Default::default()
+        };
+        let failed_search_response = SearchResponse {
+            errors: vec!["split_1: timeout".to_string()],
+            ..Default::default()
+        };
+        cache.put(
+            make_key(&search_request, &["split_1"]),
+            &failed_search_response,
+        );
+        assert!(cache
+            .get(&make_key(&search_request, &["split_1"]))
+            .is_none());
+
+        let timed_out_search_response = SearchResponse {
+            timed_out: true,
+            timed_out_splits: vec!["split_1".to_string()],
+            ..Default::default()
+        };
+        cache.put(
+            make_key(&search_request, &["split_1"]),
+            &timed_out_search_response,
+        );
+        assert!(cache
+            .get(&make_key(&search_request, &["split_1"]))
+            .is_none());
+    }
+}
@@ -0,0 +1,248 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Int64Array, RecordBatch, TimestampMicrosecondArray, UInt64Array};
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef, TimeUnit};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use tantivy::schema::Type;
+
+use crate::SearchError;
+
+/// Size of a value in the ClickHouse RowBinary format streamed by the leaves.
+const ROW_BINARY_VALUE_LEN: usize = 8;
+
+/// Re-encodes the fast field values streamed by the leaves in the ClickHouse RowBinary format as an
+/// Arrow IPC stream, with a single non-nullable column named after the fast field. Dates are
+/// encoded as UTC timestamps in microseconds.
+///
+/// The Arrow IPC stream format is not made of self-contained chunks, so unlike the other output
+/// formats, it cannot be produced by the leaves and concatenated by the root.
+pub(crate) struct ArrowIpcEncoder {
+    fast_field_type: Type,
+    schema: SchemaRef,
+    writer: StreamWriter<Vec<u8>>,
+}
+
+impl ArrowIpcEncoder {
+    pub fn try_new(fast_field_name: &str, fast_field_type: Type) -> crate::Result<Self> {
+        let data_type = match fast_field_type {
+            Type::I64 => DataType::Int64,
+            Type::U64 => DataType::UInt64,
+            Type::Date => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            _ => {
+                return Err(SearchError::InvalidArgument(format!(
+                    "the Arrow IPC output format does not support fast field of type \
+                     `{fast_field_type:?}`"
+                )));
+            }
+        };
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            fast_field_name,
+            data_type,
+            false,
+        )]));
+        let writer =
+            StreamWriter::try_new(Vec::new(), &schema).map_err(arrow_error_to_search_error)?;
+        Ok(Self {
+            fast_field_type,
+            schema,
+            writer,
+        })
+    }
+
+    /// Encodes the values of a leaf response as a record batch message.
+    fn encode_record_batch(&mut self, row_binary: &[u8]) -> crate::Result<Bytes> {
+        let value_chunks = row_binary.chunks_exact(ROW_BINARY_VALUE_LEN);
+        if !value_chunks.remainder().is_empty() {
+            return Err(SearchError::Internal(format!(
+                "leaf search stream response of {} bytes is not a sequence of 64-bit values",
+                row_binary.len()
+            )));
+        }
+        let values = value_chunks.map(|value_bytes| {
+            value_bytes
+                .try_into()
+                .expect("chunk should be 8 bytes long")
+        });
+        let array: ArrayRef = match self.fast_field_type {
+            Type::U64 => Arc::new(UInt64Array::from_iter_values(
+                values.map(u64::from_le_bytes),
+            )),
+            Type::I64 => Arc::new(Int64Array::from_iter_values(values.map(i64::from_le_bytes))),
+            Type::Date => Arc::new(
+                TimestampMicrosecondArray::from_iter_values(values.map(i64::from_le_bytes))
+                    .with_timezone("UTC"),
+            ),
+            _ => unreachable!("the fast field type should have been checked on creation"),
+        };
+        let record_batch = RecordBatch::try_new(self.schema.clone(), vec![array])
+            .map_err(arrow_error_to_search_error)?;
+        self.writer
+            .write(&record_batch)
+            .map_err(arrow_error_to_search_error)?;
+        Ok(self.take_encoded_bytes())
+    }
+
+    /// Encodes the end-of-stream marker.
+    fn finish(mut self) -> crate::Result<Bytes> {
+        self.writer.finish().map_err(arrow_error_to_search_error)?;
+        Ok(self.take_encoded_bytes())
+    }
+
+    fn take_encoded_bytes(&mut self) -> Bytes {
+        Bytes::from(std::mem::take(self.writer.get_mut()))
+    }
+
+    /// Encodes a stream of leaf responses: the schema message comes first, followed by a record
+    /// batch message per leaf response, and the end-of-stream marker.
+    pub fn encode_stream(
+        mut self,
+        row_binary_stream: impl Stream<Item = crate::Result<Bytes>> + Send + Unpin + 'static,
+    ) -> impl Stream<Item = crate::Result<Bytes>> + Send + 'static {
+        let schema_message = self.take_encoded_bytes();
+        let messages = futures::stream::unfold(
+            (Some(self), row_binary_stream),
+            |(encoder_opt, mut row_binary_stream)| async move {
+                let mut encoder = encoder_opt?;
+                match row_binary_stream.next().await {
+                    Some(Ok(row_binary)) => {
+                        let message_res = encoder.encode_record_batch(&row_binary);
+                        Some((message_res, (Some(encoder), row_binary_stream)))
+                    }
+                    Some(Err(error)) => Some((Err(error), (Some(encoder), row_binary_stream))),
+                    None => Some((encoder.finish(), (None, row_binary_stream))),
+                }
+            },
+        );
+        futures::stream::once(async move { Ok(schema_message) }).chain(messages)
+    }
+}
+
+fn arrow_error_to_search_error(error: ArrowError) -> SearchError {
+    SearchError::Internal(format!("failed to encode Arrow IPC stream: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Int64Type, TimestampMicrosecondType, UInt64Type};
+    use arrow_ipc::reader::StreamReader;
+    use futures::TryStreamExt;
+
+    use super::*;
+
+    async fn encode_and_decode(
+        fast_field_type: Type,
+        leaf_responses: Vec<Vec<u8>>,
+    ) -> Vec<RecordBatch> {
+        let encoder = ArrowIpcEncoder::try_new("my_field", fast_field_type).unwrap();
+        let row_binary_stream =
+            futures::stream::iter(leaf_responses.into_iter().map(|data| Ok(Bytes::from(data))));
+        let messages: Vec<Bytes> = encoder
+            .encode_stream(row_binary_stream)
+            .try_collect()
+            .await
+            .unwrap();
+        let ipc_stream: Vec<u8> = messages.concat();
+        let stream_reader = StreamReader::try_new(&ipc_stream[..], None).unwrap();
+        assert_eq!(stream_reader.schema().field(0).name(), "my_field");
+        stream_reader
+            .collect::<Result<Vec<RecordBatch>, ArrowError>>()
+            .unwrap()
+    }
+
+    fn row_binary<T: super::super::ToLittleEndian>(values: &[T]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_arrow_ipc_encoder_u64() {
+        let record_batches = encode_and_decode(
+            Type::U64,
+            vec![
+                row_binary(&[1u64, 2u64]),
+                row_binary::<u64>(&[]),
+                row_binary(&[3u64]),
+            ],
+        )
+        .await;
+        assert_eq!(record_batches.len(), 3);
+        let values: Vec<u64> = record_batches
+            .iter()
+            .flat_map(|record_batch| {
+                record_batch
+                    .column(0)
+                    .as_primitive::<UInt64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        assert_eq!(values, [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_arrow_ipc_encoder_i64_and_date() {
+        let record_batches = encode_and_decode(Type::I64, vec![row_binary(&[-1i64, 2i64])]).await;
+        assert_eq!(
+            record_batches[0]
+                .column(0)
+                .as_primitive::<Int64Type>()
+                .values(),
+            &[-1, 2]
+        );
+        let record_batches =
+            encode_and_decode(Type::Date, vec![row_binary(&[1_700_000_000_000_000i64])]).await;
+        assert_eq!(
+            record_batches[0].schema().field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+        );
+        assert_eq!(
+            record_batches[0]
+                .column(0)
+                .as_primitive::<TimestampMicrosecondType>()
+                .values(),
+            &[1_700_000_000_000_000]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_arrow_ipc_encoder_empty_stream() {
+        let record_batches = encode_and_decode(Type::U64, Vec::new()).await;
+        assert!(record_batches.is_empty());
+    }
+
+    #[test]
+    fn test_arrow_ipc_encoder_errors() {
+        let error = ArrowIpcEncoder::try_new("my_field", Type::Str)
+            .err()
+            .unwrap();
+        assert!(matches!(error, SearchError::InvalidArgument(_)));
+
+        let mut encoder = ArrowIpcEncoder::try_new("my_field", Type::U64).unwrap();
+        let error = encoder.encode_record_batch(&[0u8; 9]).unwrap_err();
+        assert!(matches!(error, SearchError::Internal(_)));
+    }
+}
@@ -146,6 +146,13 @@ async fn leaf_search_stream_single_split(
     let output_format = OutputFormat::from_i32(stream_request.output_format)
         .ok_or_else(|| SearchError::Internal("invalid output format specified".to_string()))?;
 
+    // The root re-encodes the RowBinary output of the leaves as Arrow IPC.
+    if output_format == OutputFormat::ArrowIpc {
+        return Err(SearchError::Internal(
+            "invalid output format specified, ArrowIpc is only supported by the root".to_string(),
+        ));
+    }
+
     if request_fields.partition_by_fast_field.is_some()
         && output_format != OutputFormat::ClickHouseRowBinary
     {
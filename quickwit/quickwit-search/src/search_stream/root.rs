@@ -20,18 +20,23 @@
 use std::collections::HashSet;
 
 use bytes::Bytes;
+use futures::stream::BoxStream;
 use futures::{StreamExt, TryStreamExt};
 use quickwit_common::uri::Uri;
 use quickwit_config::build_doc_mapper;
 use quickwit_doc_mapper::tag_pruning::extract_tags_from_query;
 use quickwit_metastore::IndexMetadataResponseExt;
 use quickwit_proto::metastore::{IndexMetadataRequest, MetastoreService, MetastoreServiceClient};
-use quickwit_proto::search::{LeafSearchStreamRequest, SearchRequest, SearchStreamRequest};
+use quickwit_proto::search::{
+    LeafSearchStreamRequest, OutputFormat, SearchRequest, SearchStreamRequest,
+};
 use quickwit_query::query_ast::QueryAst;
 use tokio_stream::StreamMap;
 use tracing::*;
 
+use super::arrow_ipc::ArrowIpcEncoder;
 use crate::cluster_client::ClusterClient;
+use crate::field_value_range_pruning::prune_splits_with_field_value_ranges;
 use crate::root::{refine_start_end_timestamp_from_ast, SearchJob};
 use crate::{list_relevant_splits, SearchError};
 
@@ -41,7 +46,7 @@ pub async fn root_search_stream(
     mut search_stream_request: SearchStreamRequest,
     mut metastore: MetastoreServiceClient,
     cluster_client: ClusterClient,
-) -> crate::Result<impl futures::Stream<Item = crate::Result<Bytes>>> {
+) -> crate::Result<BoxStream<'static, crate::Result<Bytes>>> {
     // TODO: building a search request should not be necessary for listing splits.
     // This needs some refactoring: relevant splits, metadata_map, jobs...
     let index_metadata_request =
@@ -76,8 +81,27 @@ pub async fn root_search_stream(
     doc_mapper.query(doc_mapper.schema(), &query_ast_resolved, true)?;
     search_stream_request.query_ast = serde_json::to_string(&query_ast_resolved)?;
 
+    let arrow_ipc_encoder_opt =
+        if search_stream_request.output_format == OutputFormat::ArrowIpc as i32 {
+            if search_stream_request.partition_by_field.is_some() {
+                return Err(SearchError::InvalidArgument(
+                    "the Arrow IPC output format does not support `partition_by_field`".to_string(),
+                ));
+            }
+            let schema = doc_mapper.schema();
+            let fast_field = schema.get_field(&search_stream_request.fast_field)?;
+            let fast_field_type = schema.get_field_entry(fast_field).field_type().value_type();
+            let arrow_ipc_encoder =
+                ArrowIpcEncoder::try_new(&search_stream_request.fast_field, fast_field_type)?;
+            // The leaves stream RowBinary values, re-encoded as Arrow IPC below.
+            search_stream_request.output_format = OutputFormat::ClickHouseRowBinary as i32;
+            Some(arrow_ipc_encoder)
+        } else {
+            None
+        };
+
     let search_request = SearchRequest::try_from(search_stream_request.clone())?;
-    let split_metadatas = list_relevant_splits(
+    let mut split_metadatas = list_relevant_splits(
         vec![index_uid],
         search_request.start_timestamp,
         search_request.end_timestamp,
@@ -85,6 +109,7 @@ pub async fn root_search_stream(
         &mut metastore,
     )
     .await?;
+    prune_splits_with_field_value_ranges(&query_ast_resolved, &mut split_metadatas);
 
     let doc_mapper_str = serde_json::to_string(&doc_mapper).map_err(|err| {
         SearchError::Internal(format!("failed to serialize doc mapper: cause {err}"))
@@ -110,9 +135,13 @@ pub async fn root_search_stream(
             .await;
         stream_map.insert(leaf_ord, leaf_stream);
     }
-    Ok(stream_map
+    let data_stream = stream_map
         .map(|(_leaf_ord, result)| result)
-        .map_ok(|leaf_response| Bytes::from(leaf_response.data)))
+        .map_ok(|leaf_response| Bytes::from(leaf_response.data));
+    match arrow_ipc_encoder_opt {
+        Some(arrow_ipc_encoder) => Ok(arrow_ipc_encoder.encode_stream(data_stream).boxed()),
+        None => Ok(data_stream.boxed()),
+    }
 }
 
 fn jobs_to_leaf_request(
@@ -132,6 +161,10 @@ fn jobs_to_leaf_request(
 #[cfg(test)]
 mod tests {
 
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::TimestampMicrosecondType;
+    use arrow_array::RecordBatch;
+    use arrow_ipc::reader::StreamReader;
     use quickwit_common::ServiceStream;
     use quickwit_indexing::MockSplitBuilder;
     use quickwit_metastore::{IndexMetadata, ListSplitsResponseExt};
@@ -202,6 +235,77 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_root_search_stream_arrow_ipc() -> anyhow::Result<()> {
+        let request = quickwit_proto::search::SearchStreamRequest {
+            index_id: "test-index".to_string(),
+            query_ast: qast_json_helper("test", &["body"]),
+            fast_field: "timestamp".to_string(),
+            output_format: OutputFormat::ArrowIpc as i32,
+            ..Default::default()
+        };
+        let mut mock_metastore = MockMetastoreService::new();
+        let index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
+        let index_uid = index_metadata.index_uid.clone();
+        mock_metastore.expect_index_metadata().returning(move |_| {
+            Ok(IndexMetadataResponse::try_from_index_metadata(&index_metadata).unwrap())
+        });
+        mock_metastore.expect_list_splits().returning(move |_| {
+            let splits = vec![MockSplitBuilder::new("split1")
+                .with_index_uid(&index_uid)
+                .build()];
+            let splits = ListSplitsResponse::try_from_splits(splits).unwrap();
+            Ok(ServiceStream::from(vec![Ok(splits)]))
+        });
+        let mut mock_search_service = MockSearchService::new();
+        let (result_sender, result_receiver) = tokio::sync::mpsc::unbounded_channel();
+        result_sender.send(Ok(quickwit_proto::search::LeafSearchStreamResponse {
+            data: [1i64.to_le_bytes(), 2i64.to_le_bytes()].concat(),
+            split_id: "split1".to_string(),
+        }))?;
+        mock_search_service
+            .expect_leaf_search_stream()
+            .withf(|request| {
+                request.request.as_ref().unwrap().output_format
+                    == OutputFormat::ClickHouseRowBinary as i32
+            })
+            .return_once(
+                |_leaf_search_req: quickwit_proto::search::LeafSearchStreamRequest| {
+                    Ok(UnboundedReceiverStream::new(result_receiver))
+                },
+            );
+        // The test will hang on indefinitely if we don't drop the sender.
+        drop(result_sender);
+
+        let searcher_pool = searcher_pool_for_test([("127.0.0.1:1001", mock_search_service)]);
+        let search_job_placer = SearchJobPlacer::new(searcher_pool);
+        let cluster_client = ClusterClient::new(search_job_placer.clone());
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let stream =
+            root_search_stream(request.clone(), metastore.clone(), cluster_client.clone()).await?;
+        let result: Vec<Bytes> = stream.try_collect().await?;
+        let ipc_stream = result.concat();
+        let record_batches: Vec<RecordBatch> =
+            StreamReader::try_new(&ipc_stream[..], None)?.collect::<Result<_, _>>()?;
+        assert_eq!(record_batches.len(), 1);
+        let timestamps = record_batches[0]
+            .column_by_name("timestamp")
+            .unwrap()
+            .as_primitive::<TimestampMicrosecondType>();
+        assert_eq!(timestamps.values(), &[1, 2]);
+
+        let partitioned_request = quickwit_proto::search::SearchStreamRequest {
+            partition_by_field: Some("timestamp".to_string()),
+            ..request
+        };
+        let error = root_search_stream(partitioned_request, metastore, cluster_client)
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(error, SearchError::InvalidArgument(_)));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_root_search_stream_single_split_partitioned() -> anyhow::Result<()> {
         let request = quickwit_proto::search::SearchStreamRequest {
@@ -24,17 +24,19 @@ use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use futures::StreamExt;
 use quickwit_common::uri::Uri;
 use quickwit_config::SearcherConfig;
 use quickwit_doc_mapper::DocMapper;
-use quickwit_proto::metastore::MetastoreServiceClient;
+use quickwit_metastore::IndexMetadataResponseExt;
+use quickwit_proto::metastore::{IndexMetadataRequest, MetastoreService, MetastoreServiceClient};
 use quickwit_proto::search::{
     FetchDocsRequest, FetchDocsResponse, GetKvRequest, Hit, LeafListFieldsRequest,
     LeafListTermsRequest, LeafListTermsResponse, LeafSearchRequest, LeafSearchResponse,
     LeafSearchStreamRequest, LeafSearchStreamResponse, ListFieldsRequest, ListFieldsResponse,
     ListTermsRequest, ListTermsResponse, PutKvRequest, ReportSplitsRequest, ReportSplitsResponse,
-    ScrollRequest, SearchPlanResponse, SearchRequest, SearchResponse, SearchStreamRequest,
-    SnippetRequest,
+    ScrollRequest, SearchPlanResponse, SearchPriority, SearchRequest, SearchResponse,
+    SearchStreamRequest, SnippetRequest,
 };
 use quickwit_storage::{
     MemorySizedCache, QuickwitCache, SplitCache, StorageCache, StorageResolver,
@@ -43,15 +45,20 @@ use tantivy::aggregation::AggregationLimitsGuard;
 use tokio::sync::Semaphore;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
+use crate::admission_control::SearchAdmissionController;
+use crate::async_search::{delete_async_search, get_async_search, submit_async_search};
+use crate::fetch_docs::FetchDocsOptions;
 use crate::leaf::multi_leaf_search;
 use crate::leaf_cache::LeafSearchCache;
 use crate::list_fields::{leaf_list_fields, root_list_fields};
 use crate::list_fields_cache::ListFieldsCache;
 use crate::list_terms::{leaf_list_terms, root_list_terms};
-use crate::root::fetch_docs_phase;
+use crate::point_in_time::{create_point_in_time, delete_point_in_time, resolve_point_in_time};
+use crate::root::{fetch_docs_phase, root_search_with_pinned_splits};
 use crate::scroll_context::{MiniKV, ScrollContext, ScrollKeyAndStartOffset};
+use crate::search_result_cache::SearchResultCache;
 use crate::search_stream::{leaf_search_stream, root_search_stream};
-use crate::{fetch_docs, root_search, search_plan, ClusterClient, SearchError};
+use crate::{fetch_docs, search_plan, AsyncSearchResponse, ClusterClient, SearchError};
 
 #[derive(Clone)]
 /// The search service implementation.
@@ -153,6 +160,47 @@ pub trait SearchService: 'static + Send + Sync {
 
     /// Describe how a search would be processed.
     async fn search_plan(&self, request: SearchRequest) -> crate::Result<SearchPlanResponse>;
+
+    /// Creates a point in time pinning the splits currently published in the indexes matching
+    /// `index_id_patterns`, and returns its ID. Searches on the point in time ignore the splits
+    /// published or merged afterwards.
+    ///
+    /// Unlike the other methods, this is not exposed over gRPC: the point-in-time records are
+    /// stored along with the indexes, so any node can serve it.
+    async fn create_point_in_time(
+        &self,
+        index_id_patterns: Vec<String>,
+        keep_alive: Duration,
+    ) -> crate::Result<String>;
+
+    /// Deletes a point in time, releasing its splits. Returns `true` if the point in time existed
+    /// and had not expired yet.
+    async fn delete_point_in_time(&self, point_in_time_id: String) -> crate::Result<bool>;
+
+    /// Submits a search running in the background, and waits at most
+    /// `wait_for_completion_timeout` for it to complete. The progress and partial results of the
+    /// search can be retrieved with `get_async_search` until `keep_alive` elapses.
+    ///
+    /// Like the point-in-time methods, this is not exposed over gRPC: the state of async
+    /// searches is replicated in the KV store of the searchers, so any node can serve it.
+    async fn submit_async_search(
+        &self,
+        request: SearchRequest,
+        wait_for_completion_timeout: Duration,
+        keep_alive: Duration,
+    ) -> crate::Result<AsyncSearchResponse>;
+
+    /// Returns the state of an async search, waiting at most `wait_for_completion_timeout` for it
+    /// to complete.
+    async fn get_async_search(
+        &self,
+        async_search_id: String,
+        wait_for_completion_timeout: Duration,
+    ) -> crate::Result<AsyncSearchResponse>;
+
+    /// Deletes an async search, stopping it if it is still running. Returns `true` if the async
+    /// search existed and had not expired yet.
+    async fn delete_async_search(&self, async_search_id: String) -> crate::Result<bool>;
 }
 
 impl SearchServiceImpl {
@@ -182,12 +230,19 @@ pub fn deserialize_doc_mapper(doc_mapper_str: &str) -> crate::Result<Arc<dyn Doc
 
 #[async_trait]
 impl SearchService for SearchServiceImpl {
-    async fn root_search(&self, search_request: SearchRequest) -> crate::Result<SearchResponse> {
-        let search_result = root_search(
+    async fn root_search(
+        &self,
+        mut search_request: SearchRequest,
+    ) -> crate::Result<SearchResponse> {
+        let mut metastore = self.metastore.clone();
+        let pinned_splits_opt = resolve_point_in_time(&mut search_request, &mut metastore).await?;
+        let search_result = root_search_with_pinned_splits(
             &self.searcher_context,
             search_request,
-            self.metastore.clone(),
+            metastore,
+            &self.storage_resolver,
             &self.cluster_client,
+            pinned_splits_opt.as_ref(),
         )
         .await?;
         Ok(search_result)
@@ -221,13 +276,20 @@ impl SearchService for SearchServiceImpl {
         let snippet_request_opt: Option<&SnippetRequest> =
             fetch_docs_request.snippet_request.as_ref();
         let doc_mapper = deserialize_doc_mapper(&fetch_docs_request.doc_mapper)?;
+        let fetch_docs_options = FetchDocsOptions {
+            snippet_request_opt,
+            named_queries: &fetch_docs_request.named_queries,
+            explain_query_ast_opt: fetch_docs_request.explain_query_ast.as_deref(),
+            docvalue_fields: &fetch_docs_request.docvalue_fields,
+            skip_source: fetch_docs_request.skip_source,
+        };
         let fetch_docs_response = fetch_docs(
             self.searcher_context.clone(),
             fetch_docs_request.partial_hits,
             storage,
             &fetch_docs_request.split_offsets,
             doc_mapper,
-            snippet_request_opt,
+            fetch_docs_options,
         )
         .await?;
 
@@ -238,12 +300,37 @@ impl SearchService for SearchServiceImpl {
         &self,
         stream_request: SearchStreamRequest,
     ) -> crate::Result<Pin<Box<dyn futures::Stream<Item = crate::Result<Bytes>> + Send>>> {
+        // Exports are batch searches.
+        let search_admission_permit_opt =
+            match &self.searcher_context.search_admission_controller_opt {
+                Some(search_admission_controller) => {
+                    let index_metadata_request =
+                        IndexMetadataRequest::for_index_id(stream_request.index_id.clone());
+                    let index_uid = self
+                        .metastore
+                        .clone()
+                        .index_metadata(index_metadata_request)
+                        .await?
+                        .deserialize_index_metadata()?
+                        .index_uid;
+                    let search_admission_permit = search_admission_controller
+                        .acquire(SearchPriority::Batch, &[index_uid], None)
+                        .await?;
+                    Some(search_admission_permit)
+                }
+                None => None,
+            };
         let data = root_search_stream(
             stream_request,
             self.metastore.clone(),
             self.cluster_client.clone(),
         )
         .await?;
+        // The search is admitted until the stream is dropped.
+        let data = data.map(move |data_res| {
+            let _search_admission_permit_opt = &search_admission_permit_opt;
+            data_res
+        });
         Ok(Box::pin(data))
     }
 
@@ -364,6 +451,59 @@ impl SearchService for SearchServiceImpl {
         let search_plan = search_plan(search_request, self.metastore.clone()).await?;
         Ok(search_plan)
     }
+
+    async fn create_point_in_time(
+        &self,
+        index_id_patterns: Vec<String>,
+        keep_alive: Duration,
+    ) -> crate::Result<String> {
+        let point_in_time_id =
+            create_point_in_time(&index_id_patterns, keep_alive, &mut self.metastore.clone())
+                .await?;
+        Ok(point_in_time_id.to_string())
+    }
+
+    async fn delete_point_in_time(&self, point_in_time_id: String) -> crate::Result<bool> {
+        delete_point_in_time(&point_in_time_id, &mut self.metastore.clone()).await
+    }
+
+    async fn submit_async_search(
+        &self,
+        mut search_request: SearchRequest,
+        wait_for_completion_timeout: Duration,
+        keep_alive: Duration,
+    ) -> crate::Result<AsyncSearchResponse> {
+        let mut metastore = self.metastore.clone();
+        let pinned_splits_opt = resolve_point_in_time(&mut search_request, &mut metastore).await?;
+        submit_async_search(
+            search_request,
+            pinned_splits_opt.as_ref(),
+            wait_for_completion_timeout,
+            keep_alive,
+            self.searcher_context.clone(),
+            metastore,
+            &self.storage_resolver,
+            self.cluster_client.clone(),
+        )
+        .await
+    }
+
+    async fn get_async_search(
+        &self,
+        async_search_id: String,
+        wait_for_completion_timeout: Duration,
+    ) -> crate::Result<AsyncSearchResponse> {
+        get_async_search(
+            &async_search_id,
+            wait_for_completion_timeout,
+            &self.cluster_client,
+        )
+        .await
+    }
+
+    async fn delete_async_search(&self, async_search_id: String) -> crate::Result<bool> {
+        delete_async_search(&async_search_id, &self.cluster_client).await
+    }
 }
 
 pub(crate) async fn scroll(
@@ -436,6 +576,10 @@ pub(crate) async fn scroll(
         scroll_id: Some(next_scroll_id.to_string()),
         errors: Vec::new(),
         aggregation: None,
+        timed_out: false,
+        timed_out_splits: Vec::new(),
+        suggestions: Vec::new(),
+        split_profiles: Vec::new(),
     })
 }
 /// [`SearcherContext`] provides a common set of variables
@@ -450,6 +594,9 @@ pub struct SearcherContext {
     pub leaf_search_split_semaphore: Arc<Semaphore>,
     /// Split footer cache.
     pub split_footer_cache: MemorySizedCache<String>,
+    /// Split Bloom filters cache. Caches the serialized Bloom filters of a split, used by the
+    /// root to prune splits.
+    pub split_bloom_filters_cache: MemorySizedCache<String>,
     /// Counting semaphore to limit concurrent split stream requests.
     pub split_stream_semaphore: Semaphore,
     /// Recent sub-query cache.
@@ -458,6 +605,11 @@ pub struct SearcherContext {
     pub split_cache_opt: Option<Arc<SplitCache>>,
     /// List fields cache. Caches the list fields response for a given split.
     pub list_fields_cache: ListFieldsCache,
+    /// Root search result cache. Caches the response of a search request on a given set of
+    /// splits.
+    pub search_result_cache: SearchResultCache,
+    /// Admission control of the root searches. `None` if no admission control is configured.
+    pub search_admission_controller_opt: Option<SearchAdmissionController>,
     /// The aggregation limits are passed to limit the memory usage.
     pub aggregation_limit: AggregationLimitsGuard,
 }
@@ -489,6 +641,10 @@ impl SearcherContext {
             capacity_in_bytes,
             &quickwit_storage::STORAGE_METRICS.split_footer_cache,
         );
+        let split_bloom_filters_cache = MemorySizedCache::with_capacity_in_bytes(
+            capacity_in_bytes,
+            &quickwit_storage::STORAGE_METRICS.split_bloom_filters_cache,
+        );
         let leaf_search_split_semaphore = Arc::new(Semaphore::new(
             searcher_config.max_num_concurrent_split_searches,
         ));
@@ -500,6 +656,12 @@ impl SearcherContext {
             LeafSearchCache::new(searcher_config.partial_request_cache_capacity.as_u64() as usize);
         let list_fields_cache =
             ListFieldsCache::new(searcher_config.partial_request_cache_capacity.as_u64() as usize);
+        let search_result_cache =
+            SearchResultCache::new(searcher_config.search_result_cache_capacity.as_u64() as usize);
+        let search_admission_controller_opt = searcher_config
+            .admission_control
+            .as_ref()
+            .map(SearchAdmissionController::new);
         let aggregation_limit = AggregationLimitsGuard::new(
             Some(searcher_config.aggregation_memory_limit.as_u64()),
             Some(searcher_config.aggregation_bucket_limit),
@@ -510,9 +672,12 @@ impl SearcherContext {
             fast_fields_cache: storage_long_term_cache,
             leaf_search_split_semaphore,
             split_footer_cache: global_split_footer_cache,
+            split_bloom_filters_cache,
             split_stream_semaphore,
             leaf_search_cache,
             list_fields_cache,
+            search_result_cache,
+            search_admission_controller_opt,
             split_cache_opt,
             aggregation_limit,
         }
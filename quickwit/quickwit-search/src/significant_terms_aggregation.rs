@@ -0,0 +1,456 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! The `significant_terms` aggregation, which returns the terms of a field that are unusually
+//! frequent among the documents matching the search query (the foreground set) compared to all the
+//! documents of the searched splits (the background set).
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use tantivy::columnar::StrColumn;
+use tantivy::{DocId, SegmentReader};
+
+const DEFAULT_SIZE: usize = 10;
+
+const DEFAULT_MIN_DOC_COUNT: u64 = 3;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EmptyParams {}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SignificantTermsAggregationParams {
+    field: String,
+    #[serde(default)]
+    size: Option<usize>,
+    #[serde(default)]
+    shard_size: Option<usize>,
+    #[serde(default)]
+    min_doc_count: Option<u64>,
+    #[serde(default)]
+    jlh: Option<EmptyParams>,
+    #[serde(default)]
+    percentage: Option<EmptyParams>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SignificantTermsAggregationRequest {
+    significant_terms: SignificantTermsAggregationParams,
+}
+
+pub(crate) fn is_significant_terms_aggregation(aggregation: &JsonValue) -> bool {
+    aggregation
+        .as_object()
+        .map(|aggregation_obj| aggregation_obj.contains_key("significant_terms"))
+        .unwrap_or(false)
+}
+
+/// Scores the significance of a term from its document frequency in the foreground set (subset)
+/// and in the background set (superset).
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SignificanceHeuristic {
+    /// The absolute change in popularity multiplied by the relative change in popularity.
+    Jlh,
+    /// The ratio of the foreground and background document frequencies.
+    Percentage,
+}
+
+impl SignificanceHeuristic {
+    fn score(
+        &self,
+        subset_doc_count: u64,
+        subset_size: u64,
+        superset_doc_count: u64,
+        superset_size: u64,
+    ) -> f64 {
+        if subset_size == 0 || superset_doc_count == 0 {
+            return 0.0;
+        }
+        match self {
+            SignificanceHeuristic::Jlh => {
+                let subset_probability = subset_doc_count as f64 / subset_size as f64;
+                let superset_probability = superset_doc_count as f64 / superset_size as f64;
+
+                if subset_probability <= superset_probability {
+                    return 0.0;
+                }
+                let absolute_probability_change = subset_probability - superset_probability;
+                let relative_probability_change = subset_probability / superset_probability;
+                absolute_probability_change * relative_probability_change
+            }
+            SignificanceHeuristic::Percentage => {
+                subset_doc_count as f64 / superset_doc_count as f64
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SignificantTermsAggregation {
+    name: String,
+    field: String,
+    size: usize,
+    shard_size: usize,
+    min_doc_count: u64,
+    heuristic: SignificanceHeuristic,
+}
+
+impl SignificantTermsAggregation {
+    pub fn parse(name: String, aggregation_json: JsonValue) -> Result<Self, String> {
+        let request: SignificantTermsAggregationRequest = serde_json::from_value(aggregation_json)
+            .map_err(|error| format!("invalid significant_terms aggregation `{name}`: {error}"))?;
+        let params = request.significant_terms;
+        let heuristic = match (params.jlh, params.percentage) {
+            (_, None) => SignificanceHeuristic::Jlh,
+            (None, Some(_)) => SignificanceHeuristic::Percentage,
+            (Some(_), Some(_)) => {
+                return Err(format!(
+                    "significant_terms aggregation `{name}` must define at most one significance \
+                     heuristic"
+                ));
+            }
+        };
+        let size = params.size.unwrap_or(DEFAULT_SIZE);
+        if size == 0 {
+            return Err(format!(
+                "size of significant_terms aggregation `{name}` must be strictly positive"
+            ));
+        }
+        // Same default as the terms aggregation.
+        let shard_size = params
+            .shard_size
+            .unwrap_or((size as f64 * 1.5 + 10.0) as usize)
+            .max(size);
+        Ok(Self {
+            name,
+            field: params.field,
+            size,
+            shard_size,
+            min_doc_count: params.min_doc_count.unwrap_or(DEFAULT_MIN_DOC_COUNT),
+            heuristic,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    pub fn segment_collector(
+        &self,
+        segment_reader: &SegmentReader,
+    ) -> tantivy::Result<SignificantTermsSegmentCollector> {
+        let str_column_opt = segment_reader.fast_fields().str(&self.field)?;
+        Ok(SignificantTermsSegmentCollector {
+            str_column_opt,
+            segment_reader: segment_reader.clone(),
+            subset_size: 0,
+            subset_doc_counts: HashMap::new(),
+            shard_size: self.shard_size,
+            heuristic: self.heuristic,
+        })
+    }
+}
+
+/// Counts the documents of the foreground set per term ordinal. The background document counts
+/// are only computed on harvest, for the foreground terms.
+pub(crate) struct SignificantTermsSegmentCollector {
+    str_column_opt: Option<StrColumn>,
+    segment_reader: SegmentReader,
+    subset_size: u64,
+    subset_doc_counts: HashMap<u64, u64>,
+    shard_size: usize,
+    heuristic: SignificanceHeuristic,
+}
+
+impl SignificantTermsSegmentCollector {
+    pub fn collect_block(&mut self, docs: &[DocId]) {
+        self.subset_size += docs.len() as u64;
+
+        let Some(str_column) = &self.str_column_opt else {
+            return;
+        };
+        for doc in docs {
+            let mut previous_term_ord_opt = None;
+
+            for term_ord in str_column.term_ords(*doc) {
+                if previous_term_ord_opt == Some(term_ord) {
+                    continue;
+                }
+                *self.subset_doc_counts.entry(term_ord).or_default() += 1;
+                previous_term_ord_opt = Some(term_ord);
+            }
+        }
+    }
+
+    pub fn harvest(self) -> tantivy::Result<IntermediateSignificantTermsResult> {
+        let superset_size = self.segment_reader.num_docs() as u64;
+        let mut terms = BTreeMap::new();
+
+        let Some(str_column) = self.str_column_opt else {
+            return Ok(IntermediateSignificantTermsResult {
+                subset_size: self.subset_size,
+                superset_size,
+                terms,
+            });
+        };
+        let mut superset_doc_counts: HashMap<u64, u64> = self
+            .subset_doc_counts
+            .keys()
+            .map(|term_ord| (*term_ord, 0))
+            .collect();
+
+        if !superset_doc_counts.is_empty() {
+            for doc in self.segment_reader.doc_ids_alive() {
+                let mut previous_term_ord_opt = None;
+
+                for term_ord in str_column.term_ords(doc) {
+                    if previous_term_ord_opt == Some(term_ord) {
+                        continue;
+                    }
+                    if let Some(superset_doc_count) = superset_doc_counts.get_mut(&term_ord) {
+                        *superset_doc_count += 1;
+                    }
+                    previous_term_ord_opt = Some(term_ord);
+                }
+            }
+        }
+        let mut scored_term_ords: Vec<(u64, f64)> = self
+            .subset_doc_counts
+            .iter()
+            .map(|(term_ord, subset_doc_count)| {
+                let score = self.heuristic.score(
+                    *subset_doc_count,
+                    self.subset_size,
+                    superset_doc_counts[term_ord],
+                    superset_size,
+                );
+                (*term_ord, score)
+            })
+            .collect();
+        if scored_term_ords.len() > self.shard_size {
+            scored_term_ords.sort_unstable_by(|(_, left), (_, right)| right.total_cmp(left));
+            scored_term_ords.truncate(self.shard_size);
+        }
+        let mut term_buffer = String::new();
+
+        for (term_ord, _) in scored_term_ords {
+            term_buffer.clear();
+
+            if !str_column.ord_to_str(term_ord, &mut term_buffer)? {
+                continue;
+            }
+            let significant_term = IntermediateSignificantTerm {
+                subset_doc_count: self.subset_doc_counts[&term_ord],
+                superset_doc_count: superset_doc_counts[&term_ord],
+            };
+            terms.insert(term_buffer.clone(), significant_term);
+        }
+        Ok(IntermediateSignificantTermsResult {
+            subset_size: self.subset_size,
+            superset_size,
+            terms,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct IntermediateSignificantTerm {
+    subset_doc_count: u64,
+    superset_doc_count: u64,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct IntermediateSignificantTermsResult {
+    subset_size: u64,
+    superset_size: u64,
+    terms: BTreeMap<String, IntermediateSignificantTerm>,
+}
+
+impl IntermediateSignificantTermsResult {
+    pub fn merge_fruits(&mut self, other: IntermediateSignificantTermsResult) {
+        self.subset_size += other.subset_size;
+        self.superset_size += other.superset_size;
+
+        for (term, other_significant_term) in other.terms {
+            let significant_term = self.terms.entry(term).or_default();
+            significant_term.subset_doc_count += other_significant_term.subset_doc_count;
+            significant_term.superset_doc_count += other_significant_term.superset_doc_count;
+        }
+    }
+
+    pub fn into_final_result(self, aggregation: &SignificantTermsAggregation) -> JsonValue {
+        let mut scored_terms: Vec<(String, IntermediateSignificantTerm, f64)> = self
+            .terms
+            .into_iter()
+            .filter(|(_, significant_term)| {
+                significant_term.subset_doc_count >= aggregation.min_doc_count
+            })
+            .map(|(term, significant_term)| {
+                let score = aggregation.heuristic.score(
+                    significant_term.subset_doc_count,
+                    self.subset_size,
+                    significant_term.superset_doc_count,
+                    self.superset_size,
+                );
+                (term, significant_term, score)
+            })
+            .filter(|(_, _, score)| *score > 0.0)
+            .collect();
+        // Terms are sorted by decreasing score, and then by key.
+        scored_terms.sort_by(|(left_term, _, left_score), (right_term, _, right_score)| {
+            right_score
+                .total_cmp(left_score)
+                .then_with(|| left_term.cmp(right_term))
+        });
+        scored_terms.truncate(aggregation.size);
+
+        let buckets: Vec<JsonValue> = scored_terms
+            .into_iter()
+            .map(|(term, significant_term, score)| {
+                json!({
+                    "key": term,
+                    "doc_count": significant_term.subset_doc_count,
+                    "score": score,
+                    "bg_count": significant_term.superset_doc_count,
+                })
+            })
+            .collect();
+        json!({
+            "doc_count": self.subset_size,
+            "bg_count": self.superset_size,
+            "buckets": buckets,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_significant_terms_aggregation() {
+        let aggregation = SignificantTermsAggregation::parse(
+            "codes".to_string(),
+            json!({"significant_terms": {"field": "code"}}),
+        )
+        .unwrap();
+        assert_eq!(aggregation.name(), "codes");
+        assert_eq!(aggregation.field(), "code");
+        assert_eq!(aggregation.size, 10);
+        assert_eq!(aggregation.shard_size, 25);
+        assert_eq!(aggregation.min_doc_count, 3);
+        assert_eq!(aggregation.heuristic, SignificanceHeuristic::Jlh);
+
+        let aggregation = SignificantTermsAggregation::parse(
+            "codes".to_string(),
+            json!({
+                "significant_terms": {
+                    "field": "code",
+                    "size": 5,
+                    "shard_size": 2,
+                    "min_doc_count": 1,
+                    "percentage": {}
+                }
+            }),
+        )
+        .unwrap();
+        assert_eq!(aggregation.size, 5);
+        assert_eq!(aggregation.shard_size, 5);
+        assert_eq!(aggregation.min_doc_count, 1);
+        assert_eq!(aggregation.heuristic, SignificanceHeuristic::Percentage);
+
+        for invalid_aggregation_json in [
+            json!({"significant_terms": {"field": "code", "jlh": {}, "percentage": {}}}),
+            json!({"significant_terms": {"field": "code", "size": 0}}),
+            json!({"significant_terms": {"field": "code", "background_filter": {}}}),
+            json!({"significant_terms": {"field": "code"}, "aggs": {}}),
+        ] {
+            SignificantTermsAggregation::parse("codes".to_string(), invalid_aggregation_json)
+                .unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_significance_heuristics() {
+        // 50% of the foreground set against 1% of the background set.
+        let score = SignificanceHeuristic::Jlh.score(50, 100, 100, 10_000);
+        assert!((score - 0.49 * 50.0).abs() < 1e-9);
+        // Less frequent in the foreground set than in the background set.
+        assert_eq!(SignificanceHeuristic::Jlh.score(1, 100, 1_000, 10_000), 0.0);
+        assert_eq!(SignificanceHeuristic::Jlh.score(0, 0, 0, 10_000), 0.0);
+
+        let score = SignificanceHeuristic::Percentage.score(50, 100, 100, 10_000);
+        assert!((score - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_significant_terms_final_result() {
+        let aggregation = SignificantTermsAggregation::parse(
+            "codes".to_string(),
+            json!({"significant_terms": {"field": "code", "size": 2, "min_doc_count": 2}}),
+        )
+        .unwrap();
+        let intermediate_result = |subset_size, superset_size, terms: &[(&str, u64, u64)]| {
+            IntermediateSignificantTermsResult {
+                subset_size,
+                superset_size,
+                terms: terms
+                    .iter()
+                    .map(|(term, subset_doc_count, superset_doc_count)| {
+                        let significant_term = IntermediateSignificantTerm {
+                            subset_doc_count: *subset_doc_count,
+                            superset_doc_count: *superset_doc_count,
+                        };
+                        (term.to_string(), significant_term)
+                    })
+                    .collect(),
+            }
+        };
+        let mut merged_result = IntermediateSignificantTermsResult::default();
+        merged_result.merge_fruits(intermediate_result(
+            60,
+            5_000,
+            &[("timeout", 30, 40), ("ok", 20, 4_000), ("rare", 1, 1)],
+        ));
+        merged_result.merge_fruits(intermediate_result(
+            40,
+            5_000,
+            &[("timeout", 10, 10), ("refused", 10, 100)],
+        ));
+        let final_result = merged_result.into_final_result(&aggregation);
+
+        assert_eq!(final_result["doc_count"], 100);
+        assert_eq!(final_result["bg_count"], 10_000);
+        let buckets = final_result["buckets"].as_array().unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0]["key"], "timeout");
+        assert_eq!(buckets[0]["doc_count"], 40);
+        assert_eq!(buckets[0]["bg_count"], 50);
+        assert_eq!(buckets[1]["key"], "refused");
+        assert!(buckets[0]["score"].as_f64().unwrap() > buckets[1]["score"].as_f64().unwrap());
+    }
+}
@@ -18,6 +18,7 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
 
 use assert_json_diff::{assert_json_eq, assert_json_include};
 use quickwit_config::SearcherConfig;
@@ -25,9 +26,11 @@ use quickwit_doc_mapper::tag_pruning::extract_tags_from_query;
 use quickwit_doc_mapper::DefaultDocMapper;
 use quickwit_indexing::TestSandbox;
 use quickwit_opentelemetry::otlp::TraceId;
+use quickwit_proto::metastore::MarkSplitsForDeletionRequest;
 use quickwit_proto::search::{
-    LeafListTermsResponse, ListTermsRequest, SearchRequest, SortByValue, SortField, SortOrder,
-    SortValue,
+    Collapse, CompletionSuggester, CompletionSuggestion, Hit, LeafListTermsResponse,
+    ListTermsRequest, NamedQuery, PointInTime, SearchRequest, SnippetOptions, SortByValue,
+    SortField, SortMissing, SortOrder, SortValue,
 };
 use quickwit_query::query_ast::{
     qast_helper, qast_json_helper, query_ast_from_user_text, QueryAst,
@@ -42,7 +45,9 @@ use super::*;
 use crate::find_trace_ids_collector::Span;
 use crate::list_terms::leaf_list_terms;
 use crate::service::SearcherContext;
-use crate::single_node_search;
+use crate::{
+    decode_search_after_cursor, single_node_search, single_node_search_service, SearchResponseRest,
+};
 
 #[tokio::test]
 async fn test_single_node_simple() -> anyhow::Result<()> {
@@ -87,6 +92,59 @@ async fn test_single_node_simple() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_single_node_search_timeout() -> anyhow::Result<()> {
+    let index_id = "single-node-search-timeout";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: title
+                type: text
+              - name: body
+                type: text
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    let docs = vec![
+        json!({"title": "snoopy", "body": "Snoopy is an anthropomorphic beagle[5] in the comic strip..."}),
+        json!({"title": "beagle", "body": "The beagle is a breed of small scent hound, similar in appearance to the much larger foxhound."}),
+    ];
+    test_sandbox.add_documents(docs).await?;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("beagle", &["body"]),
+        max_hits: 10,
+        timeout_millis: Some(60_000),
+        ..Default::default()
+    };
+    let search_response = single_node_search(
+        search_request.clone(),
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(search_response.num_hits, 2);
+    assert!(!search_response.timed_out);
+    assert!(search_response.timed_out_splits.is_empty());
+
+    // The deadline is reached before any split is searched.
+    let search_request = SearchRequest {
+        timeout_millis: Some(0),
+        ..search_request
+    };
+    let search_response = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(search_response.num_hits, 0);
+    assert!(search_response.hits.is_empty());
+    assert!(search_response.timed_out);
+    assert_eq!(search_response.timed_out_splits.len(), 1);
+    assert!(search_response.errors.is_empty());
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_single_node_termset() -> anyhow::Result<()> {
     let index_id = "single-node-termset-1";
@@ -183,6 +241,122 @@ async fn test_single_search_with_snippet() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_single_search_with_snippet_options() -> anyhow::Result<()> {
+    let index_id = "single-node-with-snippet-options";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: title
+                type: text
+              - name: body
+                type: text
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    let docs = vec![
+        json!({"title": "snoopy", "body": "Snoopy is an anthropomorphic beagle in the comic strip."}),
+        json!({"title": "beagle", "body": "The beagle is a breed of small scent hound."}),
+    ];
+    test_sandbox.add_documents(docs.clone()).await?;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("beagle", &["title", "body"]),
+        snippet_fields: vec!["ti*".to_string(), "body".to_string()],
+        snippet_options: Some(SnippetOptions {
+            pre_tag: Some("<em>".to_string()),
+            post_tag: Some("</em>".to_string()),
+            fragment_size: Some(20),
+        }),
+        max_hits: 2,
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(single_node_result.hits.len(), 2);
+
+    let highlight_json: JsonValue =
+        serde_json::from_str(single_node_result.hits[0].snippet.as_ref().unwrap())?;
+    let expected_json: JsonValue = json!({
+        "title": ["<em>beagle</em>"],
+        "body": ["The <em>beagle</em> is a"]
+    });
+    assert_json_eq!(highlight_json, expected_json);
+
+    let highlight_json: JsonValue =
+        serde_json::from_str(single_node_result.hits[1].snippet.as_ref().unwrap())?;
+    let expected_json: JsonValue = json!({"title": [], "body": ["<em>beagle</em> in the comic"]});
+    assert_json_eq!(highlight_json, expected_json);
+
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_search_with_named_queries() -> anyhow::Result<()> {
+    let index_id = "single-node-with-named-queries";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: title
+                type: text
+              - name: body
+                type: text
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    let docs = vec![
+        json!({"title": "snoopy", "body": "Snoopy is an anthropomorphic beagle in the comic strip."}),
+        json!({"title": "beagle", "body": "The beagle is a breed of small scent hound."}),
+        json!({"title": "lisa", "body": "Lisa is a character in `The Simpsons` animated tv series."}),
+    ];
+    test_sandbox.add_documents(docs).await?;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: serde_json::to_string(&QueryAst::MatchAll)?,
+        max_hits: 10,
+        named_queries: vec![
+            NamedQuery {
+                name: "beagles".to_string(),
+                query_ast: qast_json_helper("beagle", &["title", "body"]),
+            },
+            NamedQuery {
+                name: "snoopy".to_string(),
+                query_ast: qast_json_helper("title:snoopy", &[]),
+            },
+            // Resolved with the default search fields of the index.
+            NamedQuery {
+                name: "simpsons".to_string(),
+                query_ast: serde_json::to_string(&query_ast_from_user_text("simpsons", None))?,
+            },
+        ],
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(single_node_result.num_hits, 3);
+
+    let matched_queries_per_title: BTreeMap<String, Vec<String>> = single_node_result
+        .hits
+        .into_iter()
+        .map(|hit| {
+            let hit_json: JsonValue = serde_json::from_str(&hit.json).unwrap();
+            let title = hit_json["title"].as_str().unwrap().to_string();
+            (title, hit.matched_queries)
+        })
+        .collect();
+    assert_eq!(matched_queries_per_title["snoopy"], ["beagles", "snoopy"]);
+    assert_eq!(matched_queries_per_title["beagle"], ["beagles"]);
+    assert_eq!(matched_queries_per_title["lisa"], ["simpsons"]);
+
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
 async fn slop_search_and_check(
     test_sandbox: &TestSandbox,
     index_id: &str,
@@ -385,6 +559,7 @@ async fn test_single_node_filtering() -> anyhow::Result<()> {
             field_name: "ts".to_string(),
             sort_order: SortOrder::Desc as i32,
             sort_datetime_format: None,
+            missing: SortMissing::Last as i32,
         }],
         ..Default::default()
     };
@@ -409,6 +584,7 @@ async fn test_single_node_filtering() -> anyhow::Result<()> {
             field_name: "ts".to_string(),
             sort_order: SortOrder::Desc as i32,
             sort_datetime_format: None,
+            missing: SortMissing::Last as i32,
         }],
         ..Default::default()
     };
@@ -432,6 +608,7 @@ async fn test_single_node_filtering() -> anyhow::Result<()> {
             field_name: "ts".to_string(),
             sort_order: SortOrder::Desc as i32,
             sort_datetime_format: None,
+            missing: SortMissing::Last as i32,
         }],
         ..Default::default()
     };
@@ -577,6 +754,7 @@ async fn single_node_search_sort_by_field(
             field_name: sort_by_field.to_string(),
             sort_order: SortOrder::Desc as i32,
             sort_datetime_format: None,
+            missing: SortMissing::Last as i32,
         }],
         ..Default::default()
     };
@@ -618,196 +796,1103 @@ async fn test_single_node_sorting_with_query_fieldnorms_disabled() -> anyhow::Re
 }
 
 #[tokio::test]
-async fn test_sort_bm25() {
-    let index_id = "sort_by_bm25".to_string();
+async fn test_single_node_search_collapse() -> anyhow::Result<()> {
+    let index_id = "single-node-search-collapse";
     let doc_mapping_yaml = r#"
             field_mappings:
-              - name: title
-                type: text
-                record: freq
-                fieldnorms: true
-              - name: body
-                type: text
-                record: freq
-                fieldnorms: true
-              - name: nofreq
-                type: text
-                record: basic
-                fieldnorms: true
-              - name: nofreq_nofieldnorms
+              - name: host
                 type: text
-                fieldnorms: false
+                tokenizer: raw
+                fast: true
+              - name: temperature
+                type: i64
+                fast: true
             "#;
-    let default_search_fields = &["title", "body", "nofreq", "nofreq_nofieldnorms"];
-    let test_sandbox = TestSandbox::create(
-        &index_id,
-        doc_mapping_yaml,
-        "{}",
-        &default_search_fields[..],
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["host"]).await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"host": "a", "temperature": 10}),
+            json!({"host": "b", "temperature": 9}),
+            json!({"host": "a", "temperature": 8}),
+            json!({"host": "c", "temperature": 1}),
+        ])
+        .await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"host": "b", "temperature": 7}),
+            json!({"host": "a", "temperature": 6}),
+            json!({"host": "c", "temperature": 5}),
+        ])
+        .await?;
+
+    let hit_temperatures = |hits: &[Hit]| -> Vec<i64> {
+        hits.iter()
+            .map(|hit| {
+                let hit_json: JsonValue = serde_json::from_str(&hit.json).unwrap();
+                hit_json["temperature"].as_i64().unwrap()
+            })
+            .collect()
+    };
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("*", &[]),
+        max_hits: 2,
+        sort_fields: vec![SortField {
+            field_name: "temperature".to_string(),
+            sort_order: SortOrder::Desc as i32,
+            sort_datetime_format: None,
+            missing: SortMissing::Last as i32,
+        }],
+        collapse: Some(Collapse {
+            field: "host".to_string(),
+            inner_hits: 2,
+            inner_hits_name: None,
+        }),
+        ..Default::default()
+    };
+    let search_response = single_node_search(
+        search_request.clone(),
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
     )
-    .await
-    .unwrap();
-    let docs = vec![
-        json!({"title": "one pad", "nofreq": "two pad"}), // 0
-        json!({"title": "one", "nofreq": "two"}),         // 1
-        json!({"title": "one one", "nofreq": "two two"}), // 2
-    ];
-    test_sandbox.add_documents(docs).await.unwrap();
-    let search_hits = |query: &str| {
-        let query_ast_json = serde_json::to_string(&query_ast_from_user_text(query, None)).unwrap();
-        let search_request = SearchRequest {
-            index_id_patterns: vec![index_id.to_string()],
-            query_ast: query_ast_json,
-            max_hits: 1_000,
-            sort_fields: vec![SortField {
-                field_name: "_score".to_string(),
-                sort_order: SortOrder::Desc as i32,
-                sort_datetime_format: None,
-            }],
-            ..Default::default()
-        };
-        let metastore = test_sandbox.metastore();
-        let storage_resolver = test_sandbox.storage_resolver();
-        async move {
-            single_node_search(search_request, metastore, storage_resolver)
-                .await
-                .unwrap()
-                .hits
-                .into_iter()
-                .map(|hit| {
-                    let partial_hit = hit.partial_hit.unwrap();
-                    let Some(SortByValue {
-                        sort_value: Some(SortValue::F64(score)),
-                    }) = partial_hit.sort_value
-                    else {
-                        panic!()
-                    };
-                    (score as f32, partial_hit.doc_id)
-                })
-                .collect()
-        }
+    .await?;
+    assert_eq!(hit_temperatures(&search_response.hits), [10, 9]);
+    assert_eq!(
+        hit_temperatures(&search_response.hits[0].inner_hits),
+        [10, 8]
+    );
+    assert_eq!(
+        hit_temperatures(&search_response.hits[1].inner_hits),
+        [9, 7]
+    );
+    assert_eq!(
+        search_response.hits[0]
+            .partial_hit
+            .as_ref()
+            .unwrap()
+            .collapse_value
+            .as_deref(),
+        Some("a")
+    );
+
+    let search_request = SearchRequest {
+        start_offset: 1,
+        collapse: Some(Collapse {
+            field: "host".to_string(),
+            inner_hits: 0,
+            inner_hits_name: None,
+        }),
+        ..search_request
     };
-    {
-        let hits: Vec<(f32, u32)> = search_hits("title:one").await;
-        assert_eq!(
-            &hits[..],
-            &[(0.1738279, 2), (0.15965714, 1), (0.12343242, 0)]
-        );
-    }
-    {
-        let hits: Vec<(f32, u32)> = search_hits("nofreq:two").await;
-        assert_eq!(
-            &hits[..],
-            &[(0.15965714, 1), (0.12343242, 2), (0.12343242, 0)]
-        );
-    }
-    {
-        let hits: Vec<(f32, u32)> = search_hits("title:one nofreq:two").await;
-        assert_eq!(
-            &hits[..],
-            &[(0.31931427, 1), (0.2972603, 2), (0.24686484, 0)]
-        );
-    }
+    let search_response = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(hit_temperatures(&search_response.hits), [9, 5]);
+    assert!(search_response.hits[0].inner_hits.is_empty());
     test_sandbox.assert_quit().await;
+    Ok(())
 }
 
 #[tokio::test]
-async fn test_sort_by_static_and_dynamic_field() {
-    let index_id = "sort_by_dynamic_field".to_string();
-    // In this test, we will try sorting docs by several fields.
-    // - static_i64
-    // - static_u64
-    // - dynamic_i64
-    // - dynamic_u64
+async fn test_single_node_search_completion_suggest() -> anyhow::Result<()> {
+    let index_id = "single-node-search-completion-suggest";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: title
+                type: text
+              - name: title_suggest
+                type: completion
+            "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["title"]).await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"title": "Nevermind", "title_suggest": ["Nevermind", "Nirvana"]}),
+            json!({"title": "In Utero", "title_suggest": "In Utero"}),
+        ])
+        .await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"title": "Bleach", "title_suggest": ["Bleach", "NIRVANA"]}),
+            json!({"title": "Nevermore", "title_suggest": "Nevermore"}),
+            json!({"title": "New Day", "title_suggest": "New Day"}),
+        ])
+        .await?;
+
+    let suggester = CompletionSuggester {
+        name: "title-suggestion".to_string(),
+        field: "title_suggest".to_string(),
+        prefix: "Ne".to_string(),
+        size: 2,
+    };
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("*", &[]),
+        max_hits: 0,
+        suggesters: vec![
+            suggester.clone(),
+            CompletionSuggester {
+                name: "nirvana".to_string(),
+                prefix: "nirv".to_string(),
+                size: 5,
+                ..suggester.clone()
+            },
+            CompletionSuggester {
+                name: "no-match".to_string(),
+                prefix: "new d x".to_string(),
+                ..suggester.clone()
+            },
+        ],
+        ..Default::default()
+    };
+    let search_response = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(search_response.num_hits, 5);
+    assert_eq!(
+        search_response.suggestions,
+        vec![
+            CompletionSuggestion {
+                name: "title-suggestion".to_string(),
+                options: vec!["nevermind".to_string(), "nevermore".to_string()],
+            },
+            CompletionSuggestion {
+                name: "nirvana".to_string(),
+                options: vec!["nirvana".to_string()],
+            },
+            CompletionSuggestion {
+                name: "no-match".to_string(),
+                options: Vec::new(),
+            },
+        ]
+    );
+
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("*", &[]),
+        suggesters: vec![CompletionSuggester {
+            field: "unknown".to_string(),
+            ..suggester
+        }],
+        ..Default::default()
+    };
+    let search_error = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await
+    .unwrap_err();
+    assert!(search_error
+        .to_string()
+        .contains("unknown field used in suggester `title-suggestion`: unknown"));
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_node_search_explain() -> anyhow::Result<()> {
+    let index_id = "single-node-search-explain";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: title
+                type: text
+                record: freq
+                fieldnorms: true
+            "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["title"]).await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"title": "one one pad"}),
+            json!({"title": "one"}),
+            json!({"title": "two"}),
+        ])
+        .await?;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("one", &["title"]),
+        max_hits: 10,
+        sort_fields: vec![SortField {
+            field_name: "_score".to_string(),
+            sort_order: SortOrder::Desc as i32,
+            sort_datetime_format: None,
+            missing: SortMissing::Last as i32,
+        }],
+        explain: true,
+        ..Default::default()
+    };
+    let search_response = single_node_search(
+        search_request.clone(),
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(search_response.hits.len(), 2);
+
+    for hit in &search_response.hits {
+        let Some(SortByValue {
+            sort_value: Some(SortValue::F64(score)),
+        }) = hit.partial_hit.as_ref().unwrap().sort_value
+        else {
+            panic!("expected a score");
+        };
+        let explanation: JsonValue = serde_json::from_str(hit.explanation_json.as_ref().unwrap())?;
+        assert!(explanation["details"].is_array());
+        let explained_score = explanation["value"].as_f64().unwrap();
+        assert!((explained_score - score).abs() < 1e-4);
+    }
+    let search_request = SearchRequest {
+        explain: false,
+        ..search_request
+    };
+    let search_response = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert!(search_response
+        .hits
+        .iter()
+        .all(|hit| hit.explanation_json.is_none()));
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_node_search_bloom_filter_pruning() -> anyhow::Result<()> {
+    let index_id = "single-node-search-bloom-filter-pruning";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: trace_id
+                type: text
+                tokenizer: raw
+              - name: body
+                type: text
+            bloom_filter_fields: [trace_id]
+            "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"trace_id": "trace_1", "body": "hello"}),
+            json!({"trace_id": "trace_2", "body": "hello"}),
+        ])
+        .await?;
+    test_sandbox
+        .add_documents(vec![json!({"trace_id": "trace_3", "body": "hello"})])
+        .await?;
+
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("trace_id:trace_3", &[]),
+        max_hits: 10,
+        profile: true,
+        ..Default::default()
+    };
+    let search_response = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(search_response.num_hits, 1);
+    assert_eq!(search_response.split_profiles.len(), 1);
+
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("trace_id:unknown_trace", &[]),
+        max_hits: 10,
+        profile: true,
+        ..Default::default()
+    };
+    let search_response = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(search_response.num_hits, 0);
+    assert!(search_response.split_profiles.is_empty());
+
+    // Queries on other fields cannot be pruned with the Bloom filters.
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("hello", &["body"]),
+        max_hits: 10,
+        profile: true,
+        ..Default::default()
+    };
+    let search_response = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(search_response.num_hits, 3);
+    assert_eq!(search_response.split_profiles.len(), 2);
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_node_search_field_value_range_pruning() -> anyhow::Result<()> {
+    let index_id = "single-node-search-field-value-range-pruning";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: status_code
+                type: u64
+                fast: true
+              - name: body
+                type: text
+            "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["body"]).await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"status_code": 200, "body": "ok"}),
+            json!({"status_code": 302, "body": "redirect"}),
+        ])
+        .await?;
+    test_sandbox
+        .add_documents(vec![json!({"status_code": 503, "body": "unavailable"})])
+        .await?;
+
+    for (query, expected_num_hits, expected_num_splits) in [
+        ("status_code:>=500", 1, 1),
+        ("status_code:302", 1, 1),
+        ("status_code:404", 0, 0),
+        ("status_code:[200 TO 503]", 3, 2),
+    ] {
+        let search_request = SearchRequest {
+            index_id_patterns: vec![index_id.to_string()],
+            query_ast: qast_json_helper(query, &["body"]),
+            max_hits: 10,
+            profile: true,
+            ..Default::default()
+        };
+        let search_response = single_node_search(
+            search_request,
+            test_sandbox.metastore(),
+            test_sandbox.storage_resolver(),
+        )
+        .await?;
+        assert_eq!(search_response.num_hits, expected_num_hits, "{query}");
+        assert_eq!(
+            search_response.split_profiles.len(),
+            expected_num_splits,
+            "{query}"
+        );
+    }
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_node_search_profile() -> anyhow::Result<()> {
+    let index_id = "single-node-search-profile";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: title
+                type: text
+              - name: id
+                type: u64
+                fast: true
+            "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["title"]).await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"title": "one", "id": 1}),
+            json!({"title": "two", "id": 2}),
+        ])
+        .await?;
+    test_sandbox
+        .add_documents(vec![json!({"title": "one", "id": 3})])
+        .await?;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("one", &["title"]),
+        max_hits: 10,
+        aggregation_request: Some(r#"{"max_id": {"max": {"field": "id"}}}"#.to_string()),
+        profile: true,
+        ..Default::default()
+    };
+    let search_response = single_node_search(
+        search_request.clone(),
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(search_response.num_hits, 2);
+    assert_eq!(search_response.split_profiles.len(), 2);
+
+    let split_ids: BTreeSet<String> = search_response
+        .split_profiles
+        .iter()
+        .map(|split_profile| split_profile.split_id.clone())
+        .collect();
+    assert_eq!(split_ids.len(), 2);
+
+    for split_profile in &search_response.split_profiles {
+        assert!(!split_profile.leaf_cache_hit);
+        assert!(split_profile.num_bytes_downloaded > 0);
+        assert!(split_profile.aggregation_micros <= split_profile.search_micros);
+    }
+    let search_request = SearchRequest {
+        profile: false,
+        ..search_request
+    };
+    let search_response = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert!(search_response.split_profiles.is_empty());
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sort_bm25() {
+    let index_id = "sort_by_bm25".to_string();
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: title
+                type: text
+                record: freq
+                fieldnorms: true
+              - name: body
+                type: text
+                record: freq
+                fieldnorms: true
+              - name: nofreq
+                type: text
+                record: basic
+                fieldnorms: true
+              - name: nofreq_nofieldnorms
+                type: text
+                fieldnorms: false
+            "#;
+    let default_search_fields = &["title", "body", "nofreq", "nofreq_nofieldnorms"];
+    let test_sandbox = TestSandbox::create(
+        &index_id,
+        doc_mapping_yaml,
+        "{}",
+        &default_search_fields[..],
+    )
+    .await
+    .unwrap();
+    let docs = vec![
+        json!({"title": "one pad", "nofreq": "two pad"}), // 0
+        json!({"title": "one", "nofreq": "two"}),         // 1
+        json!({"title": "one one", "nofreq": "two two"}), // 2
+    ];
+    test_sandbox.add_documents(docs).await.unwrap();
+    let search_hits = |query: &str| {
+        let query_ast_json = serde_json::to_string(&query_ast_from_user_text(query, None)).unwrap();
+        let search_request = SearchRequest {
+            index_id_patterns: vec![index_id.to_string()],
+            query_ast: query_ast_json,
+            max_hits: 1_000,
+            sort_fields: vec![SortField {
+                field_name: "_score".to_string(),
+                sort_order: SortOrder::Desc as i32,
+                sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
+            }],
+            ..Default::default()
+        };
+        let metastore = test_sandbox.metastore();
+        let storage_resolver = test_sandbox.storage_resolver();
+        async move {
+            single_node_search(search_request, metastore, storage_resolver)
+                .await
+                .unwrap()
+                .hits
+                .into_iter()
+                .map(|hit| {
+                    let partial_hit = hit.partial_hit.unwrap();
+                    let Some(SortByValue {
+                        sort_value: Some(SortValue::F64(score)),
+                    }) = partial_hit.sort_value
+                    else {
+                        panic!()
+                    };
+                    (score as f32, partial_hit.doc_id)
+                })
+                .collect()
+        }
+    };
+    {
+        let hits: Vec<(f32, u32)> = search_hits("title:one").await;
+        assert_eq!(
+            &hits[..],
+            &[(0.1738279, 2), (0.15965714, 1), (0.12343242, 0)]
+        );
+    }
+    {
+        let hits: Vec<(f32, u32)> = search_hits("nofreq:two").await;
+        assert_eq!(
+            &hits[..],
+            &[(0.15965714, 1), (0.12343242, 2), (0.12343242, 0)]
+        );
+    }
+    {
+        let hits: Vec<(f32, u32)> = search_hits("title:one nofreq:two").await;
+        assert_eq!(
+            &hits[..],
+            &[(0.31931427, 1), (0.2972603, 2), (0.24686484, 0)]
+        );
+    }
+    test_sandbox.assert_quit().await;
+}
+
+#[tokio::test]
+async fn test_sort_by_static_and_dynamic_field() {
+    let index_id = "sort_by_dynamic_field".to_string();
+    // In this test, we will try sorting docs by several fields.
+    // - static_i64
+    // - static_u64
+    // - dynamic_i64
+    // - dynamic_u64
+    let doc_mapping_yaml = r#"
+            mode: dynamic
+            field_mappings:
+              - name: static_u64
+                type: u64
+                fast: true
+              - name: static_i64
+                type: i64
+                fast: true
+            dynamic_mapping:
+                fast: true
+                stored: true
+            "#;
+    let test_sandbox = TestSandbox::create(&index_id, doc_mapping_yaml, "{}", &[])
+        .await
+        .unwrap();
+    let docs = vec![
+        // 0
+        json!({"static_u64": 3u64, "dynamic_u64": 3u64, "static_i64": 0i64, "dynamic_i64": 0i64}),
+        // 1
+        json!({"static_u64": 2u64, "dynamic_u64": 2u64, "static_i64": -1i64, "dynamic_i64": -1i64}),
+        // 2
+        json!({}),
+        // 3
+        json!({"static_u64": 4u64, "dynamic_u64": (i64::MAX as u64) + 1, "static_i64": 1i64, "dynamic_i64": 1i64}),
+    ];
+    test_sandbox.add_documents(docs).await.unwrap();
+    let search_hits = |sort_field: &str, order: SortOrder| {
+        let query_ast_json = serde_json::to_string(&QueryAst::MatchAll).unwrap();
+        let search_request = SearchRequest {
+            index_id_patterns: vec![index_id.to_string()],
+            query_ast: query_ast_json,
+            max_hits: 1_000,
+            sort_fields: vec![SortField {
+                field_name: sort_field.to_string(),
+                sort_order: order as i32,
+                sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
+            }],
+            ..Default::default()
+        };
+        let metastore = test_sandbox.metastore();
+        let storage_resolver = test_sandbox.storage_resolver();
+        async move {
+            let search_resp = single_node_search(search_request, metastore, storage_resolver)
+                .await
+                .unwrap();
+            assert_eq!(search_resp.num_hits, 4);
+            search_resp
+                .hits
+                .into_iter()
+                .map(|hit| {
+                    let partial_hit = hit.partial_hit.unwrap();
+                    partial_hit.doc_id
+                })
+                .collect::<Vec<u32>>()
+        }
+    };
+    {
+        let ordered_docs: Vec<u32> = search_hits("static_u64", SortOrder::Desc).await;
+        assert_eq!(&ordered_docs[..], &[3, 0, 1, 2]);
+    }
+    {
+        let ordered_docs: Vec<u32> = search_hits("static_u64", SortOrder::Asc).await;
+        assert_eq!(&ordered_docs[..], &[1, 0, 3, 2]);
+    }
+    {
+        let ordered_docs: Vec<u32> = search_hits("static_i64", SortOrder::Desc).await;
+        assert_eq!(&ordered_docs[..], &[3, 0, 1, 2]);
+    }
+    {
+        let ordered_docs: Vec<u32> = search_hits("static_i64", SortOrder::Asc).await;
+        assert_eq!(&ordered_docs[..], &[1, 0, 3, 2]);
+    }
+    {
+        let ordered_docs: Vec<u32> = search_hits("dynamic_u64", SortOrder::Desc).await;
+        assert_eq!(&ordered_docs[..], &[3, 0, 1, 2]);
+    }
+    {
+        let ordered_docs: Vec<u32> = search_hits("dynamic_u64", SortOrder::Asc).await;
+        assert_eq!(&ordered_docs[..], &[1, 0, 3, 2]);
+    }
+    {
+        let ordered_docs: Vec<u32> = search_hits("dynamic_i64", SortOrder::Desc).await;
+        assert_eq!(&ordered_docs[..], &[3, 0, 1, 2]);
+    }
+    {
+        let ordered_docs: Vec<u32> = search_hits("dynamic_i64", SortOrder::Asc).await;
+        assert_eq!(&ordered_docs[..], &[1, 0, 3, 2]);
+    }
+    test_sandbox.assert_quit().await;
+}
+
+#[tokio::test]
+async fn test_sort_with_missing_values_placement() {
+    let index_id = "sort-with-missing-values-placement";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: id
+                type: u64
+              - name: count
+                type: u64
+                fast: true
+            "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &[])
+        .await
+        .unwrap();
+    // Two splits, so that the missing values placement is also applied when merging the leaf
+    // responses.
+    test_sandbox
+        .add_documents(vec![json!({"id": 0, "count": 3}), json!({"id": 1})])
+        .await
+        .unwrap();
+    test_sandbox
+        .add_documents(vec![
+            json!({"id": 2, "count": 1}),
+            json!({"id": 3, "count": 2}),
+        ])
+        .await
+        .unwrap();
+    let search_ids = |order: SortOrder, missing: SortMissing| {
+        let search_request = SearchRequest {
+            index_id_patterns: vec![index_id.to_string()],
+            query_ast: qast_json_helper("*", &[]),
+            max_hits: 10,
+            sort_fields: vec![SortField {
+                field_name: "count".to_string(),
+                sort_order: order as i32,
+                sort_datetime_format: None,
+                missing: missing as i32,
+            }],
+            ..Default::default()
+        };
+        let metastore = test_sandbox.metastore();
+        let storage_resolver = test_sandbox.storage_resolver();
+        async move {
+            let search_response = single_node_search(search_request, metastore, storage_resolver)
+                .await
+                .unwrap();
+            search_response
+                .hits
+                .into_iter()
+                .map(|hit| serde_json::from_str::<JsonValue>(&hit.json).unwrap()["id"].clone())
+                .map(|id| id.as_u64().unwrap())
+                .collect::<Vec<u64>>()
+        }
+    };
+    assert_eq!(
+        search_ids(SortOrder::Desc, SortMissing::Last).await,
+        [0, 3, 2, 1]
+    );
+    assert_eq!(
+        search_ids(SortOrder::Desc, SortMissing::First).await,
+        [1, 0, 3, 2]
+    );
+    assert_eq!(
+        search_ids(SortOrder::Asc, SortMissing::Last).await,
+        [2, 3, 0, 1]
+    );
+    assert_eq!(
+        search_ids(SortOrder::Asc, SortMissing::First).await,
+        [1, 2, 3, 0]
+    );
+    test_sandbox.assert_quit().await;
+}
+
+#[tokio::test]
+async fn test_docvalue_fields() {
+    let index_id = "docvalue-fields";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: id
+                type: u64
+                fast: true
+              - name: tags
+                type: array<text>
+                tokenizer: raw
+                fast: true
+              - name: ip
+                type: ip
+                fast: true
+              - name: attributes
+                type: json
+                fast: true
+            "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &[])
+        .await
+        .unwrap();
+    test_sandbox
+        .add_documents(vec![
+            json!({"id": 1, "tags": ["b", "a"], "ip": "10.0.0.1", "attributes": {"status": 200}}),
+            json!({"id": 2, "attributes": {"status": "ok"}}),
+        ])
+        .await
+        .unwrap();
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("*", &[]),
+        max_hits: 10,
+        sort_fields: vec![SortField {
+            field_name: "id".to_string(),
+            sort_order: SortOrder::Asc as i32,
+            sort_datetime_format: None,
+            missing: SortMissing::Last as i32,
+        }],
+        docvalue_fields: vec![
+            "id".to_string(),
+            "tags".to_string(),
+            "ip".to_string(),
+            "attributes.status".to_string(),
+            "missing".to_string(),
+        ],
+        skip_source: true,
+        ..Default::default()
+    };
+    let search_response = single_node_search(
+        search_request.clone(),
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(search_response.hits.len(), 2);
+
+    let hit_docvalue_fields: Vec<JsonValue> = search_response
+        .hits
+        .iter()
+        .map(|hit| serde_json::from_str(hit.docvalue_fields_json.as_ref().unwrap()).unwrap())
+        .collect();
+    assert_eq!(
+        hit_docvalue_fields[0],
+        json!({"id": [1], "tags": ["a", "b"], "ip": ["10.0.0.1"], "attributes.status": [200]})
+    );
+    assert_eq!(
+        hit_docvalue_fields[1],
+        json!({"id": [2], "attributes.status": ["ok"]})
+    );
+    // The documents are not read from the doc store.
+    assert!(search_response.hits.iter().all(|hit| hit.json == "{}"));
+
+    let search_request = SearchRequest {
+        skip_source: false,
+        ..search_request
+    };
+    let search_response = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await
+    .unwrap();
+    let hit_json: JsonValue = serde_json::from_str(&search_response.hits[1].json).unwrap();
+    assert_eq!(hit_json, json!({"id": 2, "attributes": {"status": "ok"}}));
+    assert!(search_response.hits[1].docvalue_fields_json.is_some());
+    test_sandbox.assert_quit().await;
+}
+
+#[tokio::test]
+async fn test_search_after_cursor_pagination() {
+    let index_id = "search-after-cursor-pagination";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: id
+                type: u64
+              - name: count
+                type: u64
+                fast: true
+            "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &[])
+        .await
+        .unwrap();
+    // Two splits, with many ties on `count` within and across the splits.
+    test_sandbox
+        .add_documents(vec![
+            json!({"id": 0, "count": 2}),
+            json!({"id": 1, "count": 1}),
+            json!({"id": 2, "count": 2}),
+            json!({"id": 3, "count": 1}),
+        ])
+        .await
+        .unwrap();
+    test_sandbox
+        .add_documents(vec![
+            json!({"id": 4, "count": 2}),
+            json!({"id": 5, "count": 1}),
+            json!({"id": 6, "count": 3}),
+        ])
+        .await
+        .unwrap();
+
+    let mut counts = Vec::new();
+    let mut ids = Vec::new();
+    let mut search_after = None;
+    loop {
+        let search_request = SearchRequest {
+            index_id_patterns: vec![index_id.to_string()],
+            query_ast: qast_json_helper("*", &[]),
+            max_hits: 2,
+            sort_fields: vec![SortField {
+                field_name: "count".to_string(),
+                sort_order: SortOrder::Desc as i32,
+                sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
+            }],
+            search_after: search_after.take(),
+            ..Default::default()
+        };
+        let search_response = single_node_search(
+            search_request,
+            test_sandbox.metastore(),
+            test_sandbox.storage_resolver(),
+        )
+        .await
+        .unwrap();
+        let search_response_rest = SearchResponseRest::try_from(search_response).unwrap();
+        if search_response_rest.hits.is_empty() {
+            break;
+        }
+        for hit in &search_response_rest.hits {
+            counts.push(hit["count"].as_u64().unwrap());
+            ids.push(hit["id"].as_u64().unwrap());
+        }
+        let cursor = search_response_rest.search_after.unwrap();
+        search_after = Some(decode_search_after_cursor(&cursor).unwrap());
+    }
+    assert_eq!(counts, [3, 2, 2, 2, 1, 1, 1]);
+    ids.sort();
+    assert_eq!(ids, [0, 1, 2, 3, 4, 5, 6]);
+    test_sandbox.assert_quit().await;
+}
+
+#[tokio::test]
+async fn test_search_on_point_in_time() {
+    let index_id = "search-on-point-in-time";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: id
+                type: u64
+            "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &[])
+        .await
+        .unwrap();
+    test_sandbox
+        .add_documents(vec![json!({"id": 0}), json!({"id": 1})])
+        .await
+        .unwrap();
+    let mut metastore = test_sandbox.metastore();
+    let storage_resolver = test_sandbox.storage_resolver();
+
+    let point_in_time_id = crate::point_in_time::create_point_in_time(
+        &[index_id.to_string()],
+        Duration::from_secs(60),
+        &mut metastore,
+    )
+    .await
+    .unwrap()
+    .to_string();
+
+    // Splits published after the creation of the point in time are not visible to it, and splits
+    // marked for deletion, e.g. after a merge, remain searchable.
+    let pinned_splits = list_all_splits(vec![test_sandbox.index_uid()], &mut metastore)
+        .await
+        .unwrap();
+    test_sandbox
+        .add_documents(vec![json!({"id": 2})])
+        .await
+        .unwrap();
+    let pinned_split_ids = pinned_splits
+        .into_iter()
+        .map(|split| split.split_id)
+        .collect();
+    metastore
+        .mark_splits_for_deletion(MarkSplitsForDeletionRequest::new(
+            test_sandbox.index_uid(),
+            pinned_split_ids,
+        ))
+        .await
+        .unwrap();
+
+    let point_in_time_search_request = |keep_alive_secs: Option<u32>| SearchRequest {
+        query_ast: qast_json_helper("*", &[]),
+        max_hits: 10,
+        point_in_time: Some(PointInTime {
+            id: point_in_time_id.clone(),
+            keep_alive_secs,
+        }),
+        ..Default::default()
+    };
+    let search_response = single_node_search(
+        point_in_time_search_request(Some(120)),
+        metastore.clone(),
+        storage_resolver.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(search_response.num_hits, 2);
+
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("*", &[]),
+        max_hits: 10,
+        ..Default::default()
+    };
+    let search_response =
+        single_node_search(search_request, metastore.clone(), storage_resolver.clone())
+            .await
+            .unwrap();
+    assert_eq!(search_response.num_hits, 1);
+
+    let mut search_request = point_in_time_search_request(None);
+    search_request.index_id_patterns = vec![index_id.to_string()];
+    let search_error =
+        single_node_search(search_request, metastore.clone(), storage_resolver.clone())
+            .await
+            .unwrap_err();
+    assert!(matches!(search_error, SearchError::InvalidArgument(_)));
+
+    let deleted = crate::point_in_time::delete_point_in_time(&point_in_time_id, &mut metastore)
+        .await
+        .unwrap();
+    assert!(deleted);
+
+    let search_error = single_node_search(
+        point_in_time_search_request(None),
+        metastore.clone(),
+        storage_resolver.clone(),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(search_error, SearchError::PointInTimeNotFound(_)));
+
+    let deleted = crate::point_in_time::delete_point_in_time(&point_in_time_id, &mut metastore)
+        .await
+        .unwrap();
+    assert!(!deleted);
+    test_sandbox.assert_quit().await;
+}
+
+#[tokio::test]
+async fn test_async_search() {
+    let index_id = "async-search";
     let doc_mapping_yaml = r#"
-            mode: dynamic
             field_mappings:
-              - name: static_u64
+              - name: id
                 type: u64
                 fast: true
-              - name: static_i64
-                type: i64
-                fast: true
-            dynamic_mapping:
-                fast: true
-                stored: true
             "#;
-    let test_sandbox = TestSandbox::create(&index_id, doc_mapping_yaml, "{}", &[])
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &[])
         .await
         .unwrap();
-    let docs = vec![
-        // 0
-        json!({"static_u64": 3u64, "dynamic_u64": 3u64, "static_i64": 0i64, "dynamic_i64": 0i64}),
-        // 1
-        json!({"static_u64": 2u64, "dynamic_u64": 2u64, "static_i64": -1i64, "dynamic_i64": -1i64}),
-        // 2
-        json!({}),
-        // 3
-        json!({"static_u64": 4u64, "dynamic_u64": (i64::MAX as u64) + 1, "static_i64": 1i64, "dynamic_i64": 1i64}),
-    ];
-    test_sandbox.add_documents(docs).await.unwrap();
-    let search_hits = |sort_field: &str, order: SortOrder| {
-        let query_ast_json = serde_json::to_string(&QueryAst::MatchAll).unwrap();
-        let search_request = SearchRequest {
-            index_id_patterns: vec![index_id.to_string()],
-            query_ast: query_ast_json,
-            max_hits: 1_000,
-            sort_fields: vec![SortField {
-                field_name: sort_field.to_string(),
-                sort_order: order as i32,
-                sort_datetime_format: None,
-            }],
-            ..Default::default()
-        };
-        let metastore = test_sandbox.metastore();
-        let storage_resolver = test_sandbox.storage_resolver();
-        async move {
-            let search_resp = single_node_search(search_request, metastore, storage_resolver)
-                .await
-                .unwrap();
-            assert_eq!(search_resp.num_hits, 4);
-            search_resp
-                .hits
-                .into_iter()
-                .map(|hit| {
-                    let partial_hit = hit.partial_hit.unwrap();
-                    partial_hit.doc_id
-                })
-                .collect::<Vec<u32>>()
-        }
+    test_sandbox
+        .add_documents(vec![json!({"id": 0}), json!({"id": 1})])
+        .await
+        .unwrap();
+    test_sandbox
+        .add_documents(vec![json!({"id": 2})])
+        .await
+        .unwrap();
+    let search_service =
+        single_node_search_service(test_sandbox.metastore(), test_sandbox.storage_resolver());
+
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("*", &[]),
+        max_hits: 10,
+        aggregation_request: Some(r#"{"max_id": {"max": {"field": "id"}}}"#.to_string()),
+        ..Default::default()
     };
-    {
-        let ordered_docs: Vec<u32> = search_hits("static_u64", SortOrder::Desc).await;
-        assert_eq!(&ordered_docs[..], &[3, 0, 1, 2]);
-    }
-    {
-        let ordered_docs: Vec<u32> = search_hits("static_u64", SortOrder::Asc).await;
-        assert_eq!(&ordered_docs[..], &[1, 0, 3, 2]);
-    }
-    {
-        let ordered_docs: Vec<u32> = search_hits("static_i64", SortOrder::Desc).await;
-        assert_eq!(&ordered_docs[..], &[3, 0, 1, 2]);
-    }
-    {
-        let ordered_docs: Vec<u32> = search_hits("static_i64", SortOrder::Asc).await;
-        assert_eq!(&ordered_docs[..], &[1, 0, 3, 2]);
-    }
-    {
-        let ordered_docs: Vec<u32> = search_hits("dynamic_u64", SortOrder::Desc).await;
-        assert_eq!(&ordered_docs[..], &[3, 0, 1, 2]);
-    }
-    {
-        let ordered_docs: Vec<u32> = search_hits("dynamic_u64", SortOrder::Asc).await;
-        assert_eq!(&ordered_docs[..], &[1, 0, 3, 2]);
-    }
-    {
-        let ordered_docs: Vec<u32> = search_hits("dynamic_i64", SortOrder::Desc).await;
-        assert_eq!(&ordered_docs[..], &[3, 0, 1, 2]);
-    }
-    {
-        let ordered_docs: Vec<u32> = search_hits("dynamic_i64", SortOrder::Asc).await;
-        assert_eq!(&ordered_docs[..], &[1, 0, 3, 2]);
-    }
+    let async_search_response = search_service
+        .submit_async_search(
+            search_request.clone(),
+            Duration::ZERO,
+            Duration::from_secs(60),
+        )
+        .await
+        .unwrap();
+    let async_search_id = async_search_response.id.clone();
+    assert_eq!(async_search_response.num_splits, 2);
+
+    let async_search_response = search_service
+        .get_async_search(async_search_id.clone(), Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert!(!async_search_response.is_running);
+    assert!(!async_search_response.is_partial);
+    assert!(async_search_response.completion_time_millis.is_some());
+    assert_eq!(async_search_response.num_searched_splits, 2);
+    assert!(async_search_response.error.is_none());
+    assert_eq!(async_search_response.response.num_hits, 3);
+    assert_eq!(async_search_response.response.hits.len(), 3);
+    let aggregation: JsonValue =
+        serde_json::from_str(async_search_response.response.aggregation.as_ref().unwrap()).unwrap();
+    assert_eq!(aggregation["max_id"]["value"], 2.0);
+
+    let deleted = search_service
+        .delete_async_search(async_search_id.clone())
+        .await
+        .unwrap();
+    assert!(deleted);
+    let search_error = search_service
+        .get_async_search(async_search_id.clone(), Duration::ZERO)
+        .await
+        .unwrap_err();
+    assert!(matches!(search_error, SearchError::AsyncSearchNotFound(_)));
+    let deleted = search_service
+        .delete_async_search(async_search_id)
+        .await
+        .unwrap();
+    assert!(!deleted);
+
+    // A search completing within the timeout is returned right away.
+    let async_search_response = search_service
+        .submit_async_search(
+            search_request.clone(),
+            Duration::from_secs(10),
+            Duration::from_secs(60),
+        )
+        .await
+        .unwrap();
+    assert!(!async_search_response.is_running);
+    assert_eq!(async_search_response.response.num_hits, 3);
+
+    // Invalid requests are rejected on submission.
+    let mut invalid_search_request = search_request;
+    invalid_search_request.scroll_ttl_secs = Some(60);
+    let search_error = search_service
+        .submit_async_search(
+            invalid_search_request,
+            Duration::ZERO,
+            Duration::from_secs(60),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(search_error, SearchError::InvalidArgument(_)));
     test_sandbox.assert_quit().await;
 }
 
@@ -855,11 +1940,13 @@ async fn test_sort_by_2_field() {
                         field_name: sort_field1.to_string(),
                         sort_order: order1 as i32,
                         sort_datetime_format: None,
+                        missing: SortMissing::Last as i32,
                     },
                     SortField {
                         field_name: sort_field2.to_string(),
                         sort_order: order2 as i32,
                         sort_datetime_format: None,
+                        missing: SortMissing::Last as i32,
                     },
                 ],
                 ..Default::default()
@@ -939,6 +2026,7 @@ async fn test_single_node_invalid_sorting_with_query() {
             field_name: "description".to_string(),
             sort_order: SortOrder::Desc as i32,
             sort_datetime_format: None,
+            missing: SortMissing::Last as i32,
         }],
         ..Default::default()
     };
@@ -1061,6 +2149,7 @@ async fn test_search_util(test_sandbox: &TestSandbox, query: &str) -> Vec<u32> {
         splits_offsets,
         test_sandbox.doc_mapper(),
         agg_limits,
+        None,
     )
     .await
     .unwrap();
@@ -1468,6 +2557,300 @@ async fn test_single_node_aggregation_missing_fast_field() {
     test_sandbox.assert_quit().await;
 }
 
+#[tokio::test]
+async fn test_single_node_filters_aggregation() -> anyhow::Result<()> {
+    let index_id = "single-node-agg-3";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: level
+                type: text
+              - name: latency
+                type: f64
+                fast: true
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["level"]).await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"level": "error", "latency": 100.0}),
+            json!({"level": "warn", "latency": 10.0}),
+            json!({"level": "info", "latency": 1.0}),
+        ])
+        .await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"level": "error", "latency": 300.0}),
+            json!({"level": "info", "latency": 3.0}),
+        ])
+        .await?;
+    let agg_req = r#"
+ {
+   "levels": {
+     "filters": {
+       "filters": {
+         "errors": "level:error",
+         "warnings": {"type": "term", "field": "level", "value": "warn"}
+       },
+       "other_bucket_key": "others"
+     },
+     "aggs": {
+       "avg_latency": {
+         "avg": {
+           "field": "latency"
+         }
+       }
+     }
+   },
+   "max_latency": {
+     "max": {
+       "field": "latency"
+     }
+   }
+ }"#;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("*", &[]),
+        max_hits: 0,
+        aggregation_request: Some(agg_req.to_string()),
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    let agg_res_json: JsonValue = serde_json::from_str(&single_node_result.aggregation.unwrap())?;
+    assert_eq!(agg_res_json["max_latency"]["value"], 300.0);
+
+    let buckets = &agg_res_json["levels"]["buckets"];
+    assert_eq!(buckets["errors"]["doc_count"], 2);
+    assert_eq!(buckets["errors"]["avg_latency"]["value"], 200.0);
+    assert_eq!(buckets["warnings"]["doc_count"], 1);
+    assert_eq!(buckets["warnings"]["avg_latency"]["value"], 10.0);
+    assert_eq!(buckets["others"]["doc_count"], 2);
+    assert_eq!(buckets["others"]["avg_latency"]["value"], 2.0);
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_node_significant_terms_aggregation() -> anyhow::Result<()> {
+    let index_id = "single-node-agg-4";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: level
+                type: text
+              - name: code
+                type: text
+                tokenizer: raw
+                fast: true
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["level"]).await?;
+    let mut docs = Vec::new();
+    docs.extend(std::iter::repeat(json!({"level": "error", "code": "timeout"})).take(3));
+    docs.extend(std::iter::repeat(json!({"level": "info", "code": "ok"})).take(5));
+    docs.push(json!({"level": "error", "code": "ok"}));
+    test_sandbox.add_documents(docs).await?;
+
+    let mut docs = Vec::new();
+    docs.push(json!({"level": "error", "code": "timeout"}));
+    docs.extend(std::iter::repeat(json!({"level": "info", "code": "ok"})).take(4));
+    docs.push(json!({"level": "info", "code": "timeout"}));
+    test_sandbox.add_documents(docs).await?;
+
+    let agg_req = r#"
+ {
+   "codes": {
+     "significant_terms": {
+       "field": "code"
+     }
+   }
+ }"#;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("level:error", &[]),
+        max_hits: 0,
+        aggregation_request: Some(agg_req.to_string()),
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    let agg_res_json: JsonValue = serde_json::from_str(&single_node_result.aggregation.unwrap())?;
+    assert_eq!(agg_res_json["codes"]["doc_count"], 5);
+    assert_eq!(agg_res_json["codes"]["bg_count"], 15);
+
+    let buckets = agg_res_json["codes"]["buckets"].as_array().unwrap();
+    assert_eq!(buckets.len(), 1);
+    assert_eq!(buckets[0]["key"], "timeout");
+    assert_eq!(buckets[0]["doc_count"], 4);
+    assert_eq!(buckets[0]["bg_count"], 5);
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_node_geohash_grid_aggregation() -> anyhow::Result<()> {
+    let index_id = "single-node-agg-5";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: city
+                type: text
+              - name: location
+                type: object
+                field_mappings:
+                  - name: lat
+                    type: f64
+                    fast: true
+                  - name: lon
+                    type: f64
+                    fast: true
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &["city"]).await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"city": "paris", "location": {"lat": 48.8566, "lon": 2.3522}}),
+            json!({"city": "paris", "location": {"lat": 48.8570, "lon": 2.3530}}),
+            json!({"city": "amsterdam", "location": {"lat": 52.374, "lon": 4.9}}),
+            json!({"city": "unknown"}),
+        ])
+        .await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"city": "paris", "location": {"lat": 48.86, "lon": 2.34}}),
+            json!({"city": "new york", "location": {"lat": 40.7128, "lon": -74.006}}),
+        ])
+        .await?;
+    let agg_req = r#"
+ {
+   "cells": {
+     "geohash_grid": {
+       "field": "location",
+       "precision": 3
+     }
+   }
+ }"#;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("*", &[]),
+        max_hits: 0,
+        aggregation_request: Some(agg_req.to_string()),
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    assert_eq!(single_node_result.num_hits, 6);
+
+    let agg_res_json: JsonValue = serde_json::from_str(&single_node_result.aggregation.unwrap())?;
+    assert_eq!(
+        agg_res_json["cells"]["buckets"],
+        json!([
+            {"key": "u09", "doc_count": 3},
+            {"key": "dr5", "doc_count": 1},
+            {"key": "u17", "doc_count": 1},
+        ])
+    );
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_node_ip_range_aggregation() -> anyhow::Result<()> {
+    let index_id = "single-node-agg-6";
+    let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: host
+                type: ip
+                fast: true
+              - name: latency
+                type: f64
+                fast: true
+        "#;
+    let test_sandbox = TestSandbox::create(index_id, doc_mapping_yaml, "{}", &[]).await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"host": "10.0.0.1", "latency": 10.0}),
+            json!({"host": "10.0.0.200", "latency": 20.0}),
+            json!({"host": "192.168.1.1", "latency": 100.0}),
+        ])
+        .await?;
+    test_sandbox
+        .add_documents(vec![
+            json!({"host": "10.0.0.2", "latency": 30.0}),
+            json!({"host": "2001:db8::1", "latency": 1.0}),
+            json!({"latency": 1000.0}),
+        ])
+        .await?;
+    let agg_req = r#"
+ {
+   "networks": {
+     "ip_range": {
+       "field": "host",
+       "ranges": [
+         {"to": "10.0.0.128"},
+         {"mask": "10.0.0.0/24"},
+         {"key": "documentation", "mask": "2001:db8::/32"}
+       ]
+     },
+     "aggs": {
+       "max_latency": {
+         "max": {
+           "field": "latency"
+         }
+       }
+     }
+   }
+ }"#;
+    let search_request = SearchRequest {
+        index_id_patterns: vec![index_id.to_string()],
+        query_ast: qast_json_helper("*", &[]),
+        max_hits: 0,
+        aggregation_request: Some(agg_req.to_string()),
+        ..Default::default()
+    };
+    let single_node_result = single_node_search(
+        search_request,
+        test_sandbox.metastore(),
+        test_sandbox.storage_resolver(),
+    )
+    .await?;
+    let agg_res_json: JsonValue = serde_json::from_str(&single_node_result.aggregation.unwrap())?;
+    assert_eq!(
+        agg_res_json["networks"]["buckets"],
+        json!([
+            {
+                "key": "*-10.0.0.128",
+                "to": "10.0.0.128",
+                "doc_count": 2,
+                "max_latency": {"value": 30.0}
+            },
+            {
+                "key": "10.0.0.0/24",
+                "from": "10.0.0.0",
+                "to": "10.0.1.0",
+                "doc_count": 3,
+                "max_latency": {"value": 30.0}
+            },
+            {
+                "key": "documentation",
+                "from": "2001:db8::",
+                "to": "2001:db9::",
+                "doc_count": 1,
+                "max_latency": {"value": 1.0}
+            },
+        ])
+    );
+    test_sandbox.assert_quit().await;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_single_node_with_ip_field() -> anyhow::Result<()> {
     let index_id = "single-node-with-ip-field";
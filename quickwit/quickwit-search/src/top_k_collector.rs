@@ -198,22 +198,26 @@ pub fn specialized_top_k_segment_collector(
     leaf_max_hits: usize,
     segment_ord: u32,
     search_after_option: Option<PartialHit>,
-    order1: SortOrder,
-    order2: SortOrder,
+    sort_key_mapper: HitSortingMapper,
 ) -> Box<dyn QuickwitSegmentTopKCollector> {
     // TODO: Add support for search_after to the specialized collector.
     // Eventually we may want to remove the generic collector to reduce complexity.
-    if search_after_option.is_some() || score_extractor.is_score() {
+    // The specialized collector relies on missing values being the lowest ones, so it does not
+    // support placing them first either.
+    if search_after_option.is_some()
+        || score_extractor.is_score()
+        || sort_key_mapper.has_missing_first()
+    {
         return Box::new(GenericQuickwitSegmentTopKCollector::new(
             split_id,
             score_extractor,
             leaf_max_hits,
             segment_ord,
             search_after_option,
-            order1,
-            order2,
+            sort_key_mapper,
         ));
     }
+    let HitSortingMapper { order1, order2, .. } = sort_key_mapper;
 
     let sort_first_by_ff = score_extractor.first.is_fast_field();
     let sort_second_by_ff = score_extractor
@@ -634,17 +638,11 @@ impl GenericQuickwitSegmentTopKCollector {
         leaf_max_hits: usize,
         segment_ord: u32,
         search_after_option: Option<PartialHit>,
-        order1: SortOrder,
-        order2: SortOrder,
+        sort_key_mapper: HitSortingMapper,
     ) -> Self {
-        let sort_key_mapper = HitSortingMapper { order1, order2 };
-        let precomp_search_after_order = match &search_after_option {
-            Some(search_after) if !search_after.split_id.is_empty() => order1
-                .compare(&split_id, &search_after.split_id)
-                .then_with(|| order1.compare(&segment_ord, &search_after.segment_ord)),
-            // This value isn't actually used.
-            _ => Ordering::Equal,
-        };
+        let HitSortingMapper { order1, order2, .. } = sort_key_mapper;
+        let precomp_search_after_order =
+            precompute_search_after_order(&split_id, segment_ord, &search_after_option, order1);
         let search_after =
             SearchAfterSegment::new(search_after_option, order1, order2, &score_extractor);
 
@@ -678,29 +676,13 @@ impl GenericQuickwitSegmentTopKCollector {
         top_k_hits: &mut TopK<SegmentPartialHit, SegmentPartialHitSortingKey, HitSortingMapper>,
     ) {
         if let Some(search_after) = &search_after {
-            let search_after_value1 = search_after.sort_value;
-            let search_after_value2 = search_after.sort_value2;
-            let orders = &top_k_hits.sort_key_mapper;
-            let mut cmp_result = orders
-                .order1
-                .compare_opt(&sort_value, &search_after_value1)
-                .then_with(|| {
-                    orders
-                        .order2
-                        .compare_opt(&sort_value2, &search_after_value2)
-                });
-            if search_after.compare_on_equal {
-                // TODO actually it's not first, it should be what's in _shard_doc then first then
-                // default
-                let order = orders.order1;
-                cmp_result = cmp_result
-                    .then(precomp_search_after_order)
-                    // We compare doc_id only if sort_value1, sort_value2, split_id and segment_ord
-                    // are equal.
-                    .then_with(|| order.compare(&doc_id, &search_after.doc_id))
-            }
-
-            if cmp_result != Ordering::Less {
+            if !search_after.should_collect(
+                doc_id,
+                sort_value,
+                sort_value2,
+                &top_k_hits.sort_key_mapper,
+                precomp_search_after_order,
+            ) {
                 return;
             }
         }
@@ -823,6 +805,23 @@ impl QuickwitSegmentTopKCollector for GenericQuickwitSegmentTopKCollector {
     }
 }
 
+/// Precomputes the order of the split and segment of a segment collector relatively to the
+/// search after hit. It is used to break ties between hits sharing the same sort values.
+pub(crate) fn precompute_search_after_order(
+    split_id: &SplitId,
+    segment_ord: u32,
+    search_after_option: &Option<PartialHit>,
+    order1: SortOrder,
+) -> Ordering {
+    match search_after_option {
+        Some(search_after) if !search_after.split_id.is_empty() => order1
+            .compare(split_id, &search_after.split_id)
+            .then_with(|| order1.compare(&segment_ord, &search_after.segment_ord)),
+        // This value isn't actually used.
+        _ => Ordering::Equal,
+    }
+}
+
 /// Search After, but the sort values are converted to the u64 fast field representation.
 pub(crate) struct SearchAfterSegment {
     sort_value: Option<u64>,
@@ -876,4 +875,38 @@ impl SearchAfterSegment {
             doc_id: search_after.doc_id,
         })
     }
+
+    /// Returns true if the given hit comes after the search after hit, i.e. if it should be
+    /// collected.
+    #[inline]
+    pub fn should_collect(
+        &self,
+        doc_id: DocId,
+        sort_value: Option<u64>,
+        sort_value2: Option<u64>,
+        orders: &HitSortingMapper,
+        precomp_search_after_order: Ordering,
+    ) -> bool {
+        let mut cmp_result = orders
+            .order1
+            .compare_opt_with_missing(&sort_value, &self.sort_value, orders.missing1)
+            .then_with(|| {
+                orders.order2.compare_opt_with_missing(
+                    &sort_value2,
+                    &self.sort_value2,
+                    orders.missing2,
+                )
+            });
+        if self.compare_on_equal {
+            // TODO actually it's not first, it should be what's in _shard_doc then first then
+            // default
+            let order = orders.order1;
+            cmp_result = cmp_result
+                .then(precomp_search_after_order)
+                // We compare doc_id only if sort_value1, sort_value2, split_id and segment_ord
+                // are equal.
+                .then_with(|| order.compare(&doc_id, &self.doc_id))
+        }
+        cmp_result == Ordering::Less
+    }
 }
@@ -0,0 +1,814 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Audit logging of the REST API.
+//!
+//! An audit event recording who called which API on which indexes, and the outcome of the call,
+//! is emitted for each ingest, search, and admin request. The events are sent to the sink of the
+//! node config (standard output, file, or dedicated index) by a background task, so that a slow
+//! sink never delays the responses.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use anyhow::{bail, Context as _};
+use hyper::{http, StatusCode};
+use itertools::Itertools;
+use quickwit_common::tower::BoxFuture;
+use quickwit_common::uri::Uri;
+use quickwit_config::{
+    load_index_config_from_user_config, AuditSinkConfig, ConfigFormat, IndexConfig,
+    RestAuditConfig, INGEST_V2_SOURCE_ID,
+};
+use quickwit_ingest::{CommitType, IngestServiceError, JsonDocBatchV2Builder};
+use quickwit_proto::ingest::router::{
+    IngestRequestV2, IngestRouterService, IngestRouterServiceClient, IngestSubrequest,
+};
+use quickwit_proto::types::{DocUidGenerator, NodeId};
+use serde::Serialize;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tower::{Layer, Service};
+use tracing::{error, warn};
+
+use crate::auth_api::{api_key_id_from_authorization, required_access, IndexTarget};
+
+/// Maximum number of audit events waiting to be written to the sink. Beyond that, the events are
+/// dropped and counted by the `audit_events_dropped_total` metric.
+const AUDIT_EVENT_QUEUE_CAPACITY: usize = 10_000;
+
+/// Maximum number of audit events written to the sink at once.
+const AUDIT_EVENT_BATCH_SIZE: usize = 1_000;
+
+const REDACTED: &str = "***redacted***";
+
+const AUDIT_INDEX_CONFIG: &str = r#"
+version: 0.8
+
+index_id: ${INDEX_ID}
+
+doc_mapping:
+  mode: strict
+  field_mappings:
+    - name: timestamp
+      type: datetime
+      input_formats: [rfc3339]
+      output_format: rfc3339
+      fast: true
+      fast_precision: milliseconds
+    - name: node_id
+      type: text
+      tokenizer: raw
+      fast: true
+    - name: api_key_id
+      type: text
+      tokenizer: raw
+      fast: true
+    - name: client_ip
+      type: text
+      tokenizer: raw
+    - name: user_agent
+      type: text
+      tokenizer: default
+    - name: operation
+      type: text
+      tokenizer: raw
+      fast: true
+    - name: method
+      type: text
+      tokenizer: raw
+      fast: true
+    - name: path
+      type: text
+      tokenizer: raw
+    - name: query
+      type: text
+      tokenizer: default
+    - name: index_ids
+      type: array<text>
+      tokenizer: raw
+      fast: true
+    - name: status_code
+      type: u64
+      fast: true
+    - name: outcome
+      type: text
+      tokenizer: raw
+      fast: true
+    - name: elapsed_micros
+      type: u64
+      fast: true
+  timestamp_field: timestamp
+
+search_settings:
+  default_search_fields: [api_key_id, path]
+"#;
+
+/// Returns the config of the index receiving the audit events when the sink is `index`.
+pub(crate) fn audit_index_config(
+    index_id: &str,
+    default_index_root_uri: &Uri,
+) -> anyhow::Result<IndexConfig> {
+    let index_config_str = AUDIT_INDEX_CONFIG.replace("${INDEX_ID}", index_id);
+    load_index_config_from_user_config(
+        ConfigFormat::Yaml,
+        index_config_str.as_bytes(),
+        default_index_root_uri,
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum AuditOutcome {
+    Success,
+    /// The request was rejected because of missing or insufficient credentials.
+    Denied,
+    Failure,
+}
+
+impl From<StatusCode> for AuditOutcome {
+    fn from(status_code: StatusCode) -> Self {
+        match status_code {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => AuditOutcome::Denied,
+            _ if status_code.is_client_error() || status_code.is_server_error() => {
+                AuditOutcome::Failure
+            }
+            _ => AuditOutcome::Success,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct AuditEvent {
+    timestamp: String,
+    node_id: NodeId,
+    /// ID of the API key sent with the request, if any. It is only authenticated when the outcome
+    /// is not `denied`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_ip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_agent: Option<String>,
+    /// `ingest`, `search`, or `admin`.
+    operation: &'static str,
+    method: String,
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<String>,
+    /// Indexes or index ID patterns targeted by the request, when they can be resolved from its
+    /// path.
+    index_ids: Vec<String>,
+    status_code: u16,
+    outcome: AuditOutcome,
+    elapsed_micros: u64,
+}
+
+/// Applies the redaction rules of the audit config.
+#[derive(Debug, Default)]
+struct Redactor {
+    client_ip: bool,
+    user_agent: bool,
+    query: bool,
+    query_params: HashSet<String>,
+}
+
+impl Redactor {
+    fn new(redaction_rules: &[String]) -> Self {
+        let mut redactor = Redactor::default();
+
+        for redaction_rule in redaction_rules {
+            match redaction_rule.as_str() {
+                "client_ip" => redactor.client_ip = true,
+                "user_agent" => redactor.user_agent = true,
+                "query" => redactor.query = true,
+                _ => {
+                    // The rules are validated when the node config is loaded.
+                    if let Some(param_name) = redaction_rule.strip_prefix("query.") {
+                        redactor.query_params.insert(param_name.to_string());
+                    }
+                }
+            }
+        }
+        redactor
+    }
+
+    fn redact_client_ip(&self, client_ip_opt: Option<String>) -> Option<String> {
+        redact_if(self.client_ip, client_ip_opt)
+    }
+
+    fn redact_user_agent(&self, user_agent_opt: Option<String>) -> Option<String> {
+        redact_if(self.user_agent, user_agent_opt)
+    }
+
+    fn redact_query(&self, query: &str) -> String {
+        if self.query {
+            return REDACTED.to_string();
+        }
+        if self.query_params.is_empty() {
+            return query.to_string();
+        }
+        query
+            .split('&')
+            .map(|param| {
+                let param_name = param.split_once('=').map_or(param, |(name, _)| name);
+
+                if self.query_params.contains(param_name) {
+                    format!("{param_name}={REDACTED}")
+                } else {
+                    param.to_string()
+                }
+            })
+            .join("&")
+    }
+}
+
+fn redact_if(redact: bool, value_opt: Option<String>) -> Option<String> {
+    if redact {
+        value_opt.map(|_| REDACTED.to_string())
+    } else {
+        value_opt
+    }
+}
+
+/// Destination of the audit events.
+enum AuditSink {
+    Stdout,
+    File(File),
+    Index {
+        ingest_router: IngestRouterServiceClient,
+        index_id: String,
+    },
+}
+
+impl AuditSink {
+    async fn open(
+        sink_config: &AuditSinkConfig,
+        ingest_router: IngestRouterServiceClient,
+    ) -> anyhow::Result<Self> {
+        let sink = match sink_config {
+            AuditSinkConfig::Stdout => AuditSink::Stdout,
+            AuditSinkConfig::File { path } => AuditSink::File(open_audit_file(path).await?),
+            AuditSinkConfig::Index { index_id } => AuditSink::Index {
+                ingest_router,
+                index_id: index_id.clone(),
+            },
+        };
+        Ok(sink)
+    }
+
+    async fn write(&mut self, events: &[AuditEvent]) -> anyhow::Result<()> {
+        match self {
+            AuditSink::Stdout => {
+                let mut stdout = tokio::io::stdout();
+                stdout.write_all(&to_json_lines(events)?).await?;
+                stdout.flush().await?;
+            }
+            AuditSink::File(file) => {
+                file.write_all(&to_json_lines(events)?).await?;
+                file.flush().await?;
+            }
+            AuditSink::Index {
+                ingest_router,
+                index_id,
+            } => {
+                let mut doc_batch_builder = JsonDocBatchV2Builder::default();
+                let mut doc_uid_generator = DocUidGenerator::default();
+
+                for event in events {
+                    doc_batch_builder.add_doc(doc_uid_generator.next_doc_uid(), event)?;
+                }
+                let subrequest = IngestSubrequest {
+                    subrequest_id: 0,
+                    index_id: index_id.clone(),
+                    source_id: INGEST_V2_SOURCE_ID.to_string(),
+                    doc_batch: Some(doc_batch_builder.build()),
+                };
+                let request = IngestRequestV2 {
+                    commit_type: CommitType::Auto.into(),
+                    subrequests: vec![subrequest],
+                };
+                let mut response = ingest_router.ingest(request).await?;
+
+                if let Some(failure) = response.failures.pop() {
+                    bail!(IngestServiceError::from(failure));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn run(mut self, mut event_rx: mpsc::Receiver<AuditEvent>) {
+        let mut events = Vec::with_capacity(AUDIT_EVENT_BATCH_SIZE);
+
+        while event_rx
+            .recv_many(&mut events, AUDIT_EVENT_BATCH_SIZE)
+            .await
+            > 0
+        {
+            if let Err(error) = self.write(&events).await {
+                error!(error=?error, num_events=events.len(), "failed to write audit events");
+                crate::SERVE_METRICS
+                    .audit_events_dropped_total
+                    .inc_by(events.len() as u64);
+            }
+            events.clear();
+        }
+    }
+}
+
+async fn open_audit_file(path: &Path) -> anyhow::Result<File> {
+    if let Some(parent_dir) = path.parent() {
+        tokio::fs::create_dir_all(parent_dir)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to create audit log directory `{}`",
+                    parent_dir.display()
+                )
+            })?;
+    }
+    File::options()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .with_context(|| format!("failed to open audit log file `{}`", path.display()))
+}
+
+fn to_json_lines(events: &[AuditEvent]) -> serde_json::Result<Vec<u8>> {
+    let mut json_lines = Vec::new();
+
+    for event in events {
+        serde_json::to_writer(&mut json_lines, event)?;
+        json_lines.push(b'\n');
+    }
+    Ok(json_lines)
+}
+
+/// Builds the audit events of the REST requests and queues them for the sink.
+#[derive(Clone)]
+pub(crate) struct AuditLogger {
+    node_id: NodeId,
+    redactor: Arc<Redactor>,
+    event_tx: mpsc::Sender<AuditEvent>,
+}
+
+impl AuditLogger {
+    /// Opens the sink of the audit config and spawns the task writing the events to it.
+    pub async fn spawn(
+        audit_config: &RestAuditConfig,
+        node_id: NodeId,
+        ingest_router: IngestRouterServiceClient,
+    ) -> anyhow::Result<Self> {
+        let sink = AuditSink::open(&audit_config.sink, ingest_router).await?;
+        let (event_tx, event_rx) = mpsc::channel(AUDIT_EVENT_QUEUE_CAPACITY);
+        tokio::spawn(sink.run(event_rx));
+
+        Ok(Self {
+            node_id,
+            redactor: Arc::new(Redactor::new(&audit_config.redact)),
+            event_tx,
+        })
+    }
+
+    /// Captures the audit information of a request, or returns `None` if the request is not
+    /// audited: the UI, the metrics, and the health checks are not.
+    fn capture<B>(&self, request: &http::Request<B>) -> Option<AuditRequest> {
+        let path = request.uri().path();
+
+        if !path.starts_with("/api/") {
+            return None;
+        }
+        let required_access = required_access(request.method(), path)?;
+        let index_ids = match required_access.index_target {
+            IndexTarget::Indexes(index_id_patterns) => index_id_patterns,
+            IndexTarget::Cluster | IndexTarget::AnyIndex => Vec::new(),
+        };
+        let headers = request.headers();
+        let header_value = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|header_value| header_value.to_str().ok())
+        };
+        let api_key_id = header_value("authorization").and_then(api_key_id_from_authorization);
+        // The REST server does not see the address of the clients behind a proxy, so the address
+        // is only recorded when the proxy forwards it.
+        let client_ip = header_value("x-forwarded-for")
+            .and_then(|forwarded_for| forwarded_for.split(',').next())
+            .map(|client_ip| client_ip.trim().to_string());
+        let user_agent = header_value("user-agent").map(str::to_string);
+        let query = request
+            .uri()
+            .query()
+            .filter(|query| !query.is_empty())
+            .map(|query| self.redactor.redact_query(query));
+
+        let audit_request = AuditRequest {
+            api_key_id,
+            client_ip: self.redactor.redact_client_ip(client_ip),
+            user_agent: self.redactor.redact_user_agent(user_agent),
+            operation: required_access.scope.as_str(),
+            method: request.method().to_string(),
+            path: path.to_string(),
+            query,
+            index_ids,
+            start: Instant::now(),
+        };
+        Some(audit_request)
+    }
+
+    fn log(&self, audit_request: AuditRequest, status_code: StatusCode) {
+        let event = audit_request.into_event(self.node_id.clone(), status_code);
+
+        if self.event_tx.try_send(event).is_err() {
+            warn!("audit event queue is full, dropping audit event");
+            crate::SERVE_METRICS.audit_events_dropped_total.inc();
+        }
+    }
+}
+
+/// Audit information captured before the request is handled.
+struct AuditRequest {
+    api_key_id: Option<String>,
+    client_ip: Option<String>,
+    user_agent: Option<String>,
+    operation: &'static str,
+    method: String,
+    path: String,
+    query: Option<String>,
+    index_ids: Vec<String>,
+    start: Instant,
+}
+
+impl AuditRequest {
+    fn into_event(self, node_id: NodeId, status_code: StatusCode) -> AuditEvent {
+        let elapsed = self.start.elapsed();
+        let timestamp = OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .unwrap_or_default();
+
+        AuditEvent {
+            timestamp,
+            node_id,
+            api_key_id: self.api_key_id,
+            client_ip: self.client_ip,
+            user_agent: self.user_agent,
+            operation: self.operation,
+            method: self.method,
+            path: self.path,
+            query: self.query,
+            index_ids: self.index_ids,
+            status_code: status_code.as_u16(),
+            outcome: AuditOutcome::from(status_code),
+            elapsed_micros: elapsed.as_micros() as u64,
+        }
+    }
+}
+
+/// Tower layer emitting an audit event for each audited request once its response is ready.
+#[derive(Clone)]
+pub(crate) struct AuditLayer {
+    audit_logger: AuditLogger,
+}
+
+impl AuditLayer {
+    pub fn new(audit_logger: AuditLogger) -> Self {
+        Self { audit_logger }
+    }
+}
+
+impl<S> Layer<S> for AuditLayer {
+    type Service = AuditService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuditService {
+            inner,
+            audit_logger: self.audit_logger.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct AuditService<S> {
+    inner: S,
+    audit_logger: AuditLogger,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for AuditService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+    ResBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        let audit_request_opt = self.audit_logger.capture(&request);
+        let response_fut = self.inner.call(request);
+
+        let Some(audit_request) = audit_request_opt else {
+            return Box::pin(response_fut);
+        };
+        let audit_logger = self.audit_logger.clone();
+
+        Box::pin(async move {
+            let response_res = response_fut.await;
+            let status_code = match &response_res {
+                Ok(response) => response.status(),
+                Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            audit_logger.log(audit_request, status_code);
+            response_res
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::time::Duration;
+
+    use base64::prelude::{Engine, BASE64_STANDARD};
+    use quickwit_proto::ingest::router::{
+        IngestResponseV2, IngestSuccess, MockIngestRouterService,
+    };
+    use serde_json::{json, Value as JsonValue};
+    use tower::{ServiceBuilder, ServiceExt};
+
+    use super::*;
+
+    async fn call<S>(service: &mut S, request: http::Request<()>) -> StatusCode
+    where S: Service<http::Request<()>, Response = http::Response<()>, Error = Infallible> {
+        service
+            .ready()
+            .await
+            .unwrap()
+            .call(request)
+            .await
+            .unwrap()
+            .status()
+    }
+
+    fn respond(request: http::Request<()>) -> http::Response<()> {
+        let status_code = if request.uri().path() == "/api/v1/indexes" {
+            StatusCode::FORBIDDEN
+        } else {
+            StatusCode::OK
+        };
+        http::Response::builder()
+            .status(status_code)
+            .body(())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_redactor() {
+        let redactor = Redactor::new(&[]);
+        assert_eq!(
+            redactor.redact_query("query=foo&max_hits=10"),
+            "query=foo&max_hits=10"
+        );
+        assert_eq!(
+            redactor.redact_client_ip(Some("10.0.0.1".to_string())),
+            Some("10.0.0.1".to_string())
+        );
+
+        let redactor = Redactor::new(&[
+            "client_ip".to_string(),
+            "query.query".to_string(),
+            "query.token".to_string(),
+        ]);
+        assert_eq!(
+            redactor.redact_query("query=foo&max_hits=10&token"),
+            format!("query={REDACTED}&max_hits=10&token={REDACTED}")
+        );
+        assert_eq!(
+            redactor.redact_client_ip(Some("10.0.0.1".to_string())),
+            Some(REDACTED.to_string())
+        );
+        assert_eq!(redactor.redact_client_ip(None), None);
+        assert_eq!(
+            redactor.redact_user_agent(Some("curl/8.0".to_string())),
+            Some("curl/8.0".to_string())
+        );
+
+        let redactor = Redactor::new(&["query".to_string(), "user_agent".to_string()]);
+        assert_eq!(redactor.redact_query("query=foo&max_hits=10"), REDACTED);
+        assert_eq!(
+            redactor.redact_user_agent(Some("curl/8.0".to_string())),
+            Some(REDACTED.to_string())
+        );
+    }
+
+    #[test]
+    fn test_audit_outcome() {
+        assert_eq!(AuditOutcome::from(StatusCode::OK), AuditOutcome::Success);
+        assert_eq!(
+            AuditOutcome::from(StatusCode::CREATED),
+            AuditOutcome::Success
+        );
+        assert_eq!(
+            AuditOutcome::from(StatusCode::UNAUTHORIZED),
+            AuditOutcome::Denied
+        );
+        assert_eq!(
+            AuditOutcome::from(StatusCode::FORBIDDEN),
+            AuditOutcome::Denied
+        );
+        assert_eq!(
+            AuditOutcome::from(StatusCode::NOT_FOUND),
+            AuditOutcome::Failure
+        );
+        assert_eq!(
+            AuditOutcome::from(StatusCode::INTERNAL_SERVER_ERROR),
+            AuditOutcome::Failure
+        );
+    }
+
+    #[test]
+    fn test_audit_index_config() {
+        let index_config = audit_index_config(
+            RestAuditConfig::DEFAULT_INDEX_ID,
+            &Uri::for_test("ram:///indexes"),
+        )
+        .unwrap();
+        assert_eq!(index_config.index_id, RestAuditConfig::DEFAULT_INDEX_ID);
+        assert_eq!(
+            index_config.doc_mapping.timestamp_field.as_deref(),
+            Some("timestamp")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audit_layer_with_file_sink() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let audit_log_path = temp_dir.path().join("audit").join("audit.log");
+        let audit_config = RestAuditConfig {
+            enabled: true,
+            sink: AuditSinkConfig::File {
+                path: audit_log_path.clone(),
+            },
+            redact: vec!["query.query".to_string()],
+        };
+        let audit_logger = AuditLogger::spawn(
+            &audit_config,
+            NodeId::from("test-node".to_string()),
+            IngestRouterServiceClient::mocked(),
+        )
+        .await
+        .unwrap();
+        let mut service = ServiceBuilder::new()
+            .layer(AuditLayer::new(audit_logger))
+            .service_fn(|request| async move { Ok::<_, Infallible>(respond(request)) });
+
+        let authorization = format!("ApiKey {}", BASE64_STANDARD.encode("my-key:my-secret"));
+        let request = http::Request::get("/api/v1/my-index/search?query=body:foo&max_hits=10")
+            .header("authorization", authorization)
+            .header("x-forwarded-for", "10.0.0.1, 10.0.0.2")
+            .header("user-agent", "curl/8.0")
+            .body(())
+            .unwrap();
+        assert_eq!(call(&mut service, request).await, StatusCode::OK);
+
+        // The UI is not audited.
+        let request = http::Request::get("/ui/search").body(()).unwrap();
+        assert_eq!(call(&mut service, request).await, StatusCode::OK);
+
+        let request = http::Request::post("/api/v1/indexes").body(()).unwrap();
+        assert_eq!(call(&mut service, request).await, StatusCode::FORBIDDEN);
+
+        let mut events: Vec<JsonValue> = Vec::new();
+
+        for _ in 0..100 {
+            let audit_log = tokio::fs::read_to_string(&audit_log_path)
+                .await
+                .unwrap_or_default();
+            events = audit_log
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+            if events.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(events.len(), 2);
+
+        let mut search_event = events[0].clone();
+        assert!(search_event["timestamp"].as_str().unwrap().ends_with('Z'));
+        assert!(search_event["elapsed_micros"].is_u64());
+        search_event["timestamp"].take();
+        search_event["elapsed_micros"].take();
+        assert_eq!(
+            search_event,
+            json!({
+                "timestamp": null,
+                "node_id": "test-node",
+                "api_key_id": "my-key",
+                "client_ip": "10.0.0.1",
+                "user_agent": "curl/8.0",
+                "operation": "search",
+                "method": "GET",
+                "path": "/api/v1/my-index/search",
+                "query": format!("query={REDACTED}&max_hits=10"),
+                "index_ids": ["my-index"],
+                "status_code": 200,
+                "outcome": "success",
+                "elapsed_micros": null,
+            })
+        );
+        let create_index_event = &events[1];
+        assert!(create_index_event.get("api_key_id").is_none());
+        assert_eq!(create_index_event["operation"], "admin");
+        assert_eq!(create_index_event["method"], "POST");
+        assert_eq!(create_index_event["index_ids"], json!([]));
+        assert_eq!(create_index_event["status_code"], 403);
+        assert_eq!(create_index_event["outcome"], "denied");
+    }
+
+    #[tokio::test]
+    async fn test_audit_layer_with_index_sink() {
+        let (ingested_tx, mut ingested_rx) = mpsc::channel(1);
+        let mut mock_ingest_router = MockIngestRouterService::new();
+        mock_ingest_router
+            .expect_ingest()
+            .withf(|request| {
+                request.subrequests.len() == 1
+                    && request.subrequests[0].index_id == RestAuditConfig::DEFAULT_INDEX_ID
+                    && request.subrequests[0].source_id == INGEST_V2_SOURCE_ID
+                    && request.subrequests[0]
+                        .doc_batch
+                        .as_ref()
+                        .unwrap()
+                        .num_docs()
+                        == 1
+            })
+            .returning(move |_| {
+                ingested_tx.try_send(()).unwrap();
+                Ok(IngestResponseV2 {
+                    successes: vec![IngestSuccess {
+                        num_ingested_docs: 1,
+                        ..Default::default()
+                    }],
+                    failures: Vec::new(),
+                })
+            });
+        let audit_config = RestAuditConfig {
+            enabled: true,
+            sink: AuditSinkConfig::Index {
+                index_id: RestAuditConfig::DEFAULT_INDEX_ID.to_string(),
+            },
+            redact: Vec::new(),
+        };
+        let audit_logger = AuditLogger::spawn(
+            &audit_config,
+            NodeId::from("test-node".to_string()),
+            IngestRouterServiceClient::from_mock(mock_ingest_router),
+        )
+        .await
+        .unwrap();
+        let mut service = ServiceBuilder::new()
+            .layer(AuditLayer::new(audit_logger))
+            .service_fn(|request| async move { Ok::<_, Infallible>(respond(request)) });
+
+        let request = http::Request::post("/api/v1/my-index/ingest")
+            .body(())
+            .unwrap();
+        assert_eq!(call(&mut service, request).await, StatusCode::OK);
+
+        tokio::time::timeout(Duration::from_secs(5), ingested_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
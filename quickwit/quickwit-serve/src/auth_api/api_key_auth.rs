@@ -0,0 +1,927 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use hyper::Method;
+use percent_encoding::percent_decode_str;
+use quickwit_config::{
+    ApiKey, ApiKeyId, ApiKeyScope, IndexAliases, IndexPrivilege, RestAuthConfig, Role, RoleId,
+};
+use quickwit_metastore::ListIndexAliasesResponseExt;
+use quickwit_opentelemetry::otlp::{OTEL_LOGS_INDEX_ID, OTEL_TRACES_INDEX_ID};
+use quickwit_proto::metastore::{
+    serde_utils, ListApiKeysRequest, ListIndexAliasesRequest, ListRolesRequest, MetastoreResult,
+    MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::tonic;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tracing::error;
+use warp::path::FullPath;
+use warp::reject::Rejection;
+use warp::Filter;
+
+use crate::rest::InternalError;
+
+/// The API keys, the roles, and the index aliases are cached for a few seconds, so a revoked key
+/// or role may still be honored by the other nodes of the cluster for that long.
+const API_KEYS_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Rejection returned when a request does not carry valid API key credentials.
+#[derive(Debug)]
+pub(crate) struct Unauthorized(pub String);
+
+impl warp::reject::Reject for Unauthorized {}
+
+impl fmt::Display for Unauthorized {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unauthorized: {}", self.0)
+    }
+}
+
+/// Rejection returned when the API key of a request grants neither the scope nor the index
+/// privilege required by the endpoint.
+#[derive(Debug)]
+pub(crate) struct Forbidden(pub String);
+
+impl warp::reject::Reject for Forbidden {}
+
+impl fmt::Display for Forbidden {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "forbidden: {}", self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum AuthError {
+    #[error("unauthorized: {0}")]
+    Unauthorized(String),
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl From<AuthError> for Rejection {
+    fn from(error: AuthError) -> Self {
+        match error {
+            AuthError::Unauthorized(message) => warp::reject::custom(Unauthorized(message)),
+            AuthError::Forbidden(message) => warp::reject::custom(Forbidden(message)),
+            AuthError::Internal(message) => warp::reject::custom(InternalError(message)),
+        }
+    }
+}
+
+impl From<AuthError> for tonic::Status {
+    fn from(error: AuthError) -> Self {
+        match error {
+            AuthError::Unauthorized(message) => tonic::Status::unauthenticated(message),
+            AuthError::Forbidden(message) => tonic::Status::permission_denied(message),
+            AuthError::Internal(message) => tonic::Status::internal(message),
+        }
+    }
+}
+
+/// Indexes that a request reads from, writes to, or administers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum IndexTarget {
+    /// The request is not bound to specific indexes, or may target any of them: only the scopes of
+    /// the API key grant access to it.
+    Cluster,
+    /// The request targets an index that cannot be resolved from the request itself, such as a
+    /// scroll follow-up: any role granting the privilege on some indexes grants access to it.
+    AnyIndex,
+    /// The request targets the indexes matching these index ID patterns.
+    Indexes(Vec<String>),
+}
+
+/// Access required to call an endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RequiredAccess {
+    pub scope: ApiKeyScope,
+    pub index_target: IndexTarget,
+}
+
+impl RequiredAccess {
+    pub fn cluster(scope: ApiKeyScope) -> Self {
+        Self {
+            scope,
+            index_target: IndexTarget::Cluster,
+        }
+    }
+
+    pub fn any_index(scope: ApiKeyScope) -> Self {
+        Self {
+            scope,
+            index_target: IndexTarget::AnyIndex,
+        }
+    }
+
+    pub fn indexes<S: ToString>(
+        scope: ApiKeyScope,
+        index_id_patterns: impl IntoIterator<Item = S>,
+    ) -> Self {
+        let index_id_patterns = index_id_patterns
+            .into_iter()
+            .map(|index_id_pattern| index_id_pattern.to_string())
+            .collect();
+        Self {
+            scope,
+            index_target: IndexTarget::Indexes(index_id_patterns),
+        }
+    }
+}
+
+/// API keys, roles, and index aliases stored in the metastore.
+#[derive(Debug, Default)]
+struct AuthState {
+    api_keys: HashMap<ApiKeyId, ApiKey>,
+    roles: HashMap<RoleId, Role>,
+    index_aliases: IndexAliases,
+}
+
+/// Validates the API keys sent to the REST and gRPC APIs against the keys stored in the metastore
+/// and the root API key defined in the node config, and checks that they grant the access
+/// required by the endpoints, either through their scopes or through their roles.
+#[derive(Clone)]
+pub(crate) struct ApiKeyAuthenticator {
+    metastore: MetastoreServiceClient,
+    root_api_key_hash_opt: Option<String>,
+    cache: Arc<Mutex<Option<(Instant, Arc<AuthState>)>>>,
+}
+
+impl ApiKeyAuthenticator {
+    pub fn new(metastore: MetastoreServiceClient, auth_config: &RestAuthConfig) -> Self {
+        let root_api_key_hash_opt = auth_config.root_api_key.as_deref().map(hash_api_key_secret);
+        Self {
+            metastore,
+            root_api_key_hash_opt,
+            cache: Arc::default(),
+        }
+    }
+
+    /// Checks that the credentials of the `Authorization` header belong to an API key granting
+    /// the `required_access`.
+    pub async fn authorize(
+        &self,
+        authorization_opt: Option<&str>,
+        required_access: &RequiredAccess,
+    ) -> Result<(), AuthError> {
+        let Some(authorization) = authorization_opt else {
+            return Err(AuthError::Unauthorized("missing API key".to_string()));
+        };
+        let Some((api_key_id, api_key_secret)) = parse_credentials(authorization) else {
+            return Err(AuthError::Unauthorized(
+                "malformed `Authorization` header".to_string(),
+            ));
+        };
+        let secret_hash = hash_api_key_secret(&api_key_secret);
+
+        if api_key_id == RestAuthConfig::ROOT_API_KEY_ID {
+            if self.root_api_key_hash_opt.as_ref() == Some(&secret_hash) {
+                return Ok(());
+            }
+            return Err(invalid_api_key());
+        }
+        let auth_state = self.load().await.map_err(|error| {
+            error!(error=?error, "failed to load API keys");
+            AuthError::Internal(format!("failed to load API keys: {error}"))
+        })?;
+        let api_key = match auth_state.api_keys.get(&api_key_id) {
+            Some(api_key) if api_key.secret_hash == secret_hash => api_key,
+            _ => return Err(invalid_api_key()),
+        };
+        if !is_authorized(
+            api_key,
+            &auth_state.roles,
+            &auth_state.index_aliases,
+            required_access,
+        ) {
+            return Err(AuthError::Forbidden(forbidden_message(
+                &api_key_id,
+                required_access,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Drops the cached API keys and roles so that the changes made through this node take effect
+    /// immediately.
+    pub fn invalidate(&self) {
+        *self.cache.lock().unwrap() = None;
+    }
+
+    async fn load(&self) -> MetastoreResult<Arc<AuthState>> {
+        if let Some((loaded_at, auth_state)) = &*self.cache.lock().unwrap() {
+            if loaded_at.elapsed() < API_KEYS_CACHE_TTL {
+                return Ok(auth_state.clone());
+            }
+        }
+        let list_api_keys_response = self.metastore.list_api_keys(ListApiKeysRequest {}).await?;
+        let api_keys: HashMap<ApiKeyId, ApiKey> = list_api_keys_response
+            .api_keys_json
+            .iter()
+            .map(|api_key_json| {
+                serde_utils::from_json_str::<ApiKey>(api_key_json)
+                    .map(|api_key| (api_key.api_key_id.clone(), api_key))
+            })
+            .collect::<MetastoreResult<_>>()?;
+        let list_roles_response = self.metastore.list_roles(ListRolesRequest {}).await?;
+        let roles: HashMap<RoleId, Role> = list_roles_response
+            .roles_json
+            .iter()
+            .map(|role_json| {
+                serde_utils::from_json_str::<Role>(role_json)
+                    .map(|role| (role.role_id.clone(), role))
+            })
+            .collect::<MetastoreResult<_>>()?;
+        let index_aliases = self
+            .metastore
+            .list_index_aliases(ListIndexAliasesRequest {})
+            .await?
+            .deserialize_index_aliases()?;
+        let auth_state = Arc::new(AuthState {
+            api_keys,
+            roles,
+            index_aliases,
+        });
+        *self.cache.lock().unwrap() = Some((Instant::now(), auth_state.clone()));
+        Ok(auth_state)
+    }
+}
+
+fn invalid_api_key() -> AuthError {
+    AuthError::Unauthorized("invalid API key".to_string())
+}
+
+/// Returns whether the scopes or the roles of the API key grant the `required_access`. The
+/// `admin` scope and the `admin` privilege imply all the others.
+///
+/// The privileges are checked against the indexes of the targeted aliases rather than against the
+/// alias names, which would otherwise give access to any index added to an alias the roles
+/// happen to match.
+fn is_authorized(
+    api_key: &ApiKey,
+    roles: &HashMap<RoleId, Role>,
+    index_aliases: &IndexAliases,
+    required_access: &RequiredAccess,
+) -> bool {
+    if api_key.has_scope(required_access.scope) {
+        return true;
+    }
+    let privilege = IndexPrivilege::from(required_access.scope);
+    let api_key_roles: Vec<&Role> = api_key
+        .roles
+        .iter()
+        .filter_map(|role_id| roles.get(role_id))
+        .collect();
+
+    match &required_access.index_target {
+        IndexTarget::Cluster => false,
+        IndexTarget::AnyIndex => api_key_roles
+            .iter()
+            .any(|role| role.has_privilege(privilege)),
+        IndexTarget::Indexes(index_id_patterns) => {
+            // Negative patterns only narrow down the targeted indexes, so they never require a
+            // privilege.
+            let mut positive_patterns = index_id_patterns
+                .iter()
+                .filter(|index_id_pattern| !index_id_pattern.starts_with('-'))
+                .flat_map(
+                    |index_id_pattern| match index_aliases.get(index_id_pattern) {
+                        Some(alias_indexes) => alias_indexes.keys().collect(),
+                        None => vec![index_id_pattern],
+                    },
+                )
+                .peekable();
+            positive_patterns.peek().is_some()
+                && positive_patterns.all(|index_id_pattern| {
+                    api_key_roles
+                        .iter()
+                        .any(|role| role.grants_privilege(index_id_pattern, privilege))
+                })
+        }
+    }
+}
+
+fn forbidden_message(api_key_id: &str, required_access: &RequiredAccess) -> String {
+    let scope = required_access.scope;
+    let privilege = IndexPrivilege::from(scope);
+
+    match &required_access.index_target {
+        IndexTarget::Cluster => {
+            format!(
+                "API key `{api_key_id}` does not have the `{}` scope",
+                scope.as_str()
+            )
+        }
+        IndexTarget::AnyIndex => {
+            format!(
+                "API key `{api_key_id}` has neither the `{}` scope nor the `{}` privilege on any \
+                 index",
+                scope.as_str(),
+                privilege.as_str()
+            )
+        }
+        IndexTarget::Indexes(index_id_patterns) => {
+            format!(
+                "API key `{api_key_id}` has neither the `{}` scope nor the `{}` privilege on \
+                 indexes `{}`",
+                scope.as_str(),
+                privilege.as_str(),
+                index_id_patterns.join(",")
+            )
+        }
+    }
+}
+
+/// Hashes the secret of an API key. Only the hash is stored in the metastore.
+pub(crate) fn hash_api_key_secret(api_key_secret: &str) -> String {
+    hex::encode(Sha256::digest(api_key_secret.as_bytes()))
+}
+
+/// Returns the ID of the API key sent in an `Authorization` header, without authenticating it.
+pub(crate) fn api_key_id_from_authorization(authorization: &str) -> Option<ApiKeyId> {
+    parse_credentials(authorization).map(|(api_key_id, _)| api_key_id)
+}
+
+/// Parses the credentials of an `Authorization: ApiKey <credentials>` header, as sent by
+/// Elasticsearch clients, or of an `Authorization: Basic <credentials>` header, as sent by
+/// browsers. In both cases, `<credentials>` is the base64 encoding of `<id>:<secret>`.
+fn parse_credentials(authorization: &str) -> Option<(ApiKeyId, String)> {
+    let (scheme, credentials) = authorization.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("apikey") && !scheme.eq_ignore_ascii_case("basic") {
+        return None;
+    }
+    let decoded_credentials = BASE64_STANDARD.decode(credentials.trim()).ok()?;
+    let decoded_credentials = String::from_utf8(decoded_credentials).ok()?;
+    let (api_key_id, api_key_secret) = decoded_credentials.split_once(':')?;
+    if api_key_id.is_empty() || api_key_secret.is_empty() {
+        return None;
+    }
+    Some((api_key_id.to_string(), api_key_secret.to_string()))
+}
+
+/// Elasticsearch endpoints that only read data even though they are not called with `GET`.
+const ELASTIC_SEARCH_ENDPOINTS: [&str; 8] = [
+    "_analyze",
+    "_async_search",
+    "_count",
+    "_field_caps",
+    "_msearch",
+    "_pit",
+    "_search",
+    "_terms_enum",
+];
+
+/// Returns the access required to call the endpoint, or `None` if the endpoint is public.
+///
+/// The indexes are resolved from the path only. The endpoints whose body may target any index,
+/// such as `_bulk`, `_msearch`, or `_sql`, and the endpoints listing all the indexes require the
+/// scope of the API key. The path segments are percent-decoded before the index ID patterns are
+/// split on commas, like the handlers do, so that an encoded comma cannot smuggle an index in.
+pub(crate) fn required_access(method: &Method, path: &str) -> Option<RequiredAccess> {
+    if method == Method::OPTIONS || path.starts_with("/health/") {
+        return None;
+    }
+    if path.starts_with("/api/developer") || path.starts_with("/api/v1/auth/") {
+        return Some(RequiredAccess::cluster(ApiKeyScope::Admin));
+    }
+    let is_read = method == Method::GET || method == Method::HEAD;
+    let read_or_admin_scope = if is_read {
+        ApiKeyScope::Search
+    } else {
+        ApiKeyScope::Admin
+    };
+    let decoded_path_segments: Vec<Cow<str>> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| percent_decode_str(segment).decode_utf8_lossy())
+        .collect();
+    let path_segments: Vec<&str> = decoded_path_segments.iter().map(AsRef::as_ref).collect();
+
+    let ["api", "v1", api_v1_path_segments @ ..] = path_segments.as_slice() else {
+        // UI, metrics, and OpenAPI docs.
+        return Some(if is_read {
+            RequiredAccess::any_index(ApiKeyScope::Search)
+        } else {
+            RequiredAccess::cluster(ApiKeyScope::Admin)
+        });
+    };
+    // Index IDs cannot start with an underscore, so they never collide with the Elasticsearch
+    // endpoint names. The index-bound Quickwit endpoints are matched before the other ones so that
+    // an index named like a top-level endpoint, `templates` for instance, is always protected.
+    let access = match api_v1_path_segments {
+        ["indexes"] => RequiredAccess::cluster(read_or_admin_scope),
+        ["indexes", index_id, ..] => RequiredAccess::indexes(read_or_admin_scope, [index_id]),
+        ["_elastic"] => RequiredAccess::cluster(ApiKeyScope::Search),
+        ["_elastic", "_bulk"] | ["_elastic", _, "_bulk"] => {
+            RequiredAccess::cluster(ApiKeyScope::Ingest)
+        }
+        ["_elastic", "_msearch"] => RequiredAccess::cluster(ApiKeyScope::Search),
+        ["_elastic", "_search", "scroll"] | ["_elastic", "_async_search" | "_pit", ..] => {
+            RequiredAccess::any_index(ApiKeyScope::Search)
+        }
+        ["_elastic", "_analyze"] => RequiredAccess::any_index(ApiKeyScope::Search),
+        ["_elastic", endpoint] if ELASTIC_SEARCH_ENDPOINTS.contains(endpoint) => {
+            RequiredAccess::indexes(ApiKeyScope::Search, ["*"])
+        }
+        ["_elastic", endpoint, ..] if endpoint.starts_with('_') => {
+            RequiredAccess::cluster(read_or_admin_scope)
+        }
+        ["_elastic", index_id_patterns, endpoint_path_segments @ ..] => {
+            let scope = match endpoint_path_segments {
+                [endpoint, ..] if ELASTIC_SEARCH_ENDPOINTS.contains(endpoint) => {
+                    ApiKeyScope::Search
+                }
+                _ => read_or_admin_scope,
+            };
+            RequiredAccess::indexes(scope, index_id_patterns.split(','))
+        }
+        [index_id_patterns, "search" | "search-plan" | "tail"]
+        | [index_id_patterns, "search", "stream"]
+        | [index_id_patterns, "jaeger", ..] => {
+            RequiredAccess::indexes(ApiKeyScope::Search, index_id_patterns.split(','))
+        }
+        [index_id, "ingest" | "ingest-v2"] | [index_id, "otlp", "v1", "logs" | "traces"] => {
+            RequiredAccess::indexes(ApiKeyScope::Ingest, [index_id])
+        }
+        [index_id, "delete-tasks"] => RequiredAccess::indexes(read_or_admin_scope, [index_id]),
+        ["otlp", "v1", "logs"] => {
+            RequiredAccess::indexes(ApiKeyScope::Ingest, [OTEL_LOGS_INDEX_ID])
+        }
+        ["otlp", "v1", "traces"] => {
+            RequiredAccess::indexes(ApiKeyScope::Ingest, [OTEL_TRACES_INDEX_ID])
+        }
+        ["_sql"] => RequiredAccess::cluster(ApiKeyScope::Search),
+        ["analyze" | "parse-query"] => RequiredAccess::any_index(ApiKeyScope::Search),
+        // Cluster and node information, index templates, lifecycle policies, etc.
+        _ if is_read => RequiredAccess::any_index(ApiKeyScope::Search),
+        _ => RequiredAccess::cluster(ApiKeyScope::Admin),
+    };
+    Some(access)
+}
+
+/// Rejects the requests that do not carry an API key granting the access required by the
+/// endpoint. When `authenticator_opt` is `None`, authentication is disabled and all the requests
+/// go through.
+pub(crate) fn api_key_auth_filter(
+    authenticator_opt: Option<ApiKeyAuthenticator>,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(
+            move |method: Method, full_path: FullPath, authorization_opt: Option<String>| {
+                let authenticator_opt = authenticator_opt.clone();
+                async move {
+                    let Some(authenticator) = authenticator_opt else {
+                        return Ok(());
+                    };
+                    let Some(required_access) = required_access(&method, full_path.as_str()) else {
+                        return Ok(());
+                    };
+                    authenticator
+                        .authorize(authorization_opt.as_deref(), &required_access)
+                        .await
+                        .map_err(Rejection::from)
+                }
+            },
+        )
+        .untuple_one()
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::{IndexAliasAction, IndexAliasConfig};
+    use quickwit_proto::metastore::{
+        ListApiKeysResponse, ListIndexAliasesResponse, ListRolesResponse, MockMetastoreService,
+    };
+    use warp::http::StatusCode;
+    use warp::Reply;
+
+    use super::*;
+    use crate::rest::recover_fn;
+
+    fn api_key_for_test(api_key_id: &str, secret: &str, scopes: &[ApiKeyScope]) -> ApiKey {
+        let mut api_key = ApiKey::for_test(api_key_id, scopes);
+        api_key.secret_hash = hash_api_key_secret(secret);
+        api_key
+    }
+
+    fn authorization(scheme: &str, api_key_id: &str, secret: &str) -> String {
+        let credentials = BASE64_STANDARD.encode(format!("{api_key_id}:{secret}"));
+        format!("{scheme} {credentials}")
+    }
+
+    #[test]
+    fn test_parse_credentials() {
+        let credentials = BASE64_STANDARD.encode("my-key-id:my-secret");
+        let expected_credentials = Some(("my-key-id".to_string(), "my-secret".to_string()));
+        assert_eq!(
+            parse_credentials(&format!("ApiKey {credentials}")),
+            expected_credentials
+        );
+        assert_eq!(
+            parse_credentials(&format!("Basic {credentials}")),
+            expected_credentials
+        );
+        assert!(parse_credentials(&format!("Bearer {credentials}")).is_none());
+        assert!(parse_credentials("ApiKey not-base64!").is_none());
+
+        let credentials_without_secret = BASE64_STANDARD.encode("my-key-id:");
+        assert!(parse_credentials(&format!("ApiKey {credentials_without_secret}")).is_none());
+    }
+
+    #[test]
+    fn test_required_access() {
+        assert_eq!(required_access(&Method::GET, "/health/livez"), None);
+        assert_eq!(required_access(&Method::OPTIONS, "/api/v1/indexes"), None);
+        assert_eq!(
+            required_access(&Method::GET, "/api/developer/debug"),
+            Some(RequiredAccess::cluster(ApiKeyScope::Admin))
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/api/v1/auth/api_keys"),
+            Some(RequiredAccess::cluster(ApiKeyScope::Admin))
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/ui/search"),
+            Some(RequiredAccess::any_index(ApiKeyScope::Search))
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/api/v1/version"),
+            Some(RequiredAccess::any_index(ApiKeyScope::Search))
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/api/v1/indexes"),
+            Some(RequiredAccess::cluster(ApiKeyScope::Search))
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/api/v1/indexes/my-index"),
+            Some(RequiredAccess::indexes(ApiKeyScope::Search, ["my-index"]))
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/v1/my-index/search"),
+            Some(RequiredAccess::indexes(ApiKeyScope::Search, ["my-index"]))
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/api/v1/logs-*,-logs-secret/search"),
+            Some(RequiredAccess::indexes(
+                ApiKeyScope::Search,
+                ["logs-*", "-logs-secret"]
+            ))
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/api/v1/logs-a%2Clogs-b/search"),
+            Some(RequiredAccess::indexes(
+                ApiKeyScope::Search,
+                ["logs-a", "logs-b"]
+            ))
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/v1/_elastic/logs-a%2csecret/_search"),
+            Some(RequiredAccess::indexes(
+                ApiKeyScope::Search,
+                ["logs-a", "secret"]
+            ))
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/api/v1/otel-traces-v0_7/jaeger/api/services"),
+            Some(RequiredAccess::indexes(
+                ApiKeyScope::Search,
+                ["otel-traces-v0_7"]
+            ))
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/v1/_elastic/my-index/_search"),
+            Some(RequiredAccess::indexes(ApiKeyScope::Search, ["my-index"]))
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/v1/_elastic/_search"),
+            Some(RequiredAccess::indexes(ApiKeyScope::Search, ["*"]))
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/v1/_elastic/_msearch"),
+            Some(RequiredAccess::cluster(ApiKeyScope::Search))
+        );
+        assert_eq!(
+            required_access(&Method::DELETE, "/api/v1/_elastic/_async_search/my-id"),
+            Some(RequiredAccess::any_index(ApiKeyScope::Search))
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/api/v1/_elastic/_cat/indices"),
+            Some(RequiredAccess::cluster(ApiKeyScope::Search))
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/v1/my-index/ingest"),
+            Some(RequiredAccess::indexes(ApiKeyScope::Ingest, ["my-index"]))
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/v1/_elastic/_bulk"),
+            Some(RequiredAccess::cluster(ApiKeyScope::Ingest))
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/v1/_elastic/my-index/_bulk"),
+            Some(RequiredAccess::cluster(ApiKeyScope::Ingest))
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/v1/otlp/v1/logs"),
+            Some(RequiredAccess::indexes(
+                ApiKeyScope::Ingest,
+                [OTEL_LOGS_INDEX_ID]
+            ))
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/v1/indexes"),
+            Some(RequiredAccess::cluster(ApiKeyScope::Admin))
+        );
+        assert_eq!(
+            required_access(&Method::DELETE, "/api/v1/indexes/my-index"),
+            Some(RequiredAccess::indexes(ApiKeyScope::Admin, ["my-index"]))
+        );
+        assert_eq!(
+            required_access(&Method::DELETE, "/api/v1/indexes/search"),
+            Some(RequiredAccess::indexes(ApiKeyScope::Admin, ["search"]))
+        );
+        assert_eq!(
+            required_access(&Method::DELETE, "/api/v1/_elastic/my-index"),
+            Some(RequiredAccess::indexes(ApiKeyScope::Admin, ["my-index"]))
+        );
+        assert_eq!(
+            required_access(&Method::PUT, "/api/v1/_elastic/_index_template/_search"),
+            Some(RequiredAccess::cluster(ApiKeyScope::Admin))
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/v1/templates"),
+            Some(RequiredAccess::cluster(ApiKeyScope::Admin))
+        );
+    }
+
+    #[test]
+    fn test_is_authorized() {
+        let roles: HashMap<RoleId, Role> = [
+            Role::for_test("logs-reader", &["logs-*"], IndexPrivilege::Read),
+            Role::for_test("metrics-admin", &["metrics"], IndexPrivilege::Admin),
+        ]
+        .into_iter()
+        .map(|role| (role.role_id.clone(), role))
+        .collect();
+
+        let mut api_key = ApiKey::for_test("my-key", &[ApiKeyScope::Ingest]);
+        api_key.roles = ["logs-reader".to_string(), "metrics-admin".to_string()]
+            .into_iter()
+            .collect();
+
+        let mut index_aliases = IndexAliases::default();
+        index_aliases
+            .apply_actions(vec![
+                IndexAliasAction::Add {
+                    index_ids: vec!["logs-2024".to_string()],
+                    aliases: vec!["logs-current".to_string()],
+                    config: IndexAliasConfig::default(),
+                },
+                IndexAliasAction::Add {
+                    index_ids: vec!["traces".to_string()],
+                    aliases: vec!["logs-traces".to_string()],
+                    config: IndexAliasConfig::default(),
+                },
+            ])
+            .unwrap();
+
+        let is_authorized_for = |required_access: RequiredAccess| {
+            is_authorized(&api_key, &roles, &index_aliases, &required_access)
+        };
+        assert!(is_authorized_for(RequiredAccess::cluster(
+            ApiKeyScope::Ingest
+        )));
+        assert!(!is_authorized_for(RequiredAccess::cluster(
+            ApiKeyScope::Search
+        )));
+        assert!(is_authorized_for(RequiredAccess::any_index(
+            ApiKeyScope::Search
+        )));
+        assert!(is_authorized_for(RequiredAccess::indexes(
+            ApiKeyScope::Search,
+            ["logs-2024", "metrics"]
+        )));
+        assert!(is_authorized_for(RequiredAccess::indexes(
+            ApiKeyScope::Search,
+            ["logs-*", "-logs-secret"]
+        )));
+        assert!(!is_authorized_for(RequiredAccess::indexes(
+            ApiKeyScope::Search,
+            ["logs-2024", "traces"]
+        )));
+        assert!(!is_authorized_for(RequiredAccess::indexes(
+            ApiKeyScope::Search,
+            ["-logs-secret"]
+        )));
+        assert!(is_authorized_for(RequiredAccess::indexes(
+            ApiKeyScope::Admin,
+            ["metrics"]
+        )));
+        assert!(!is_authorized_for(RequiredAccess::indexes(
+            ApiKeyScope::Admin,
+            ["logs-2024"]
+        )));
+        assert!(!is_authorized_for(RequiredAccess::cluster(
+            ApiKeyScope::Admin
+        )));
+        // Aliases are resolved: `logs-traces` matches the `logs-*` pattern of the role, but the
+        // role does not grant access to the `traces` index the alias points to.
+        assert!(is_authorized_for(RequiredAccess::indexes(
+            ApiKeyScope::Search,
+            ["logs-current"]
+        )));
+        assert!(!is_authorized_for(RequiredAccess::indexes(
+            ApiKeyScope::Search,
+            ["logs-traces"]
+        )));
+
+        // An encoded comma cannot reach an index outside of the roles.
+        for path in [
+            "/api/v1/logs-2024%2Ctraces/search",
+            "/api/v1/_elastic/logs-2024%2ctraces/_search",
+        ] {
+            let encoded_comma_access = required_access(&Method::GET, path).unwrap();
+            assert!(!is_authorized_for(encoded_comma_access));
+        }
+        let encoded_comma_access =
+            required_access(&Method::GET, "/api/v1/logs-2024%2Cmetrics/search").unwrap();
+        assert!(is_authorized_for(encoded_comma_access));
+
+        // Roles that do not exist anymore are ignored.
+        api_key.roles = ["deleted-role".to_string()].into_iter().collect();
+        assert!(!is_authorized(
+            &api_key,
+            &roles,
+            &index_aliases,
+            &RequiredAccess::any_index(ApiKeyScope::Search)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_api_key_auth_filter() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_list_api_keys().return_once(|_| {
+            let search_api_key =
+                api_key_for_test("my-search-key", "my-secret", &[ApiKeyScope::Search]);
+            let mut logs_api_key = api_key_for_test("my-logs-key", "my-secret", &[]);
+            logs_api_key.roles.insert("logs-writer".to_string());
+
+            let api_keys_json = vec![
+                serde_json::to_string(&search_api_key).unwrap(),
+                serde_json::to_string(&logs_api_key).unwrap(),
+            ];
+            Ok(ListApiKeysResponse { api_keys_json })
+        });
+        mock_metastore.expect_list_roles().return_once(|_| {
+            let role = Role::for_test("logs-writer", &["logs-*"], IndexPrivilege::Write);
+            let roles_json = vec![serde_json::to_string(&role).unwrap()];
+            Ok(ListRolesResponse { roles_json })
+        });
+        mock_metastore.expect_list_index_aliases().return_once(|_| {
+            let mut index_aliases = IndexAliases::default();
+            index_aliases
+                .apply_actions(vec![IndexAliasAction::Add {
+                    index_ids: vec!["my-index".to_string()],
+                    aliases: vec!["logs-alias".to_string()],
+                    config: IndexAliasConfig::default(),
+                }])
+                .unwrap();
+            Ok(ListIndexAliasesResponse::try_from_index_aliases(&index_aliases).unwrap())
+        });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let auth_config = RestAuthConfig {
+            enabled: true,
+            root_api_key: Some("my-root-secret-0123".to_string()),
+        };
+        let authenticator = ApiKeyAuthenticator::new(metastore, &auth_config);
+        let handler = api_key_auth_filter(Some(authenticator))
+            .map(|| warp::reply().into_response())
+            .recover(recover_fn);
+
+        let resp = warp::test::request()
+            .path("/health/livez")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = warp::test::request()
+            .path("/api/v1/indexes")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let resp = warp::test::request()
+            .path("/api/v1/indexes")
+            .header(
+                "Authorization",
+                authorization("ApiKey", "my-search-key", "wrong-secret"),
+            )
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let resp = warp::test::request()
+            .path("/api/v1/indexes")
+            .header(
+                "Authorization",
+                authorization("ApiKey", "my-search-key", "my-secret"),
+            )
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = warp::test::request()
+            .path("/api/v1/my-index/ingest")
+            .method("POST")
+            .header(
+                "Authorization",
+                authorization("Basic", "my-search-key", "my-secret"),
+            )
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let resp = warp::test::request()
+            .path("/api/v1/logs-2024/ingest")
+            .method("POST")
+            .header(
+                "Authorization",
+                authorization("ApiKey", "my-logs-key", "my-secret"),
+            )
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = warp::test::request()
+            .path("/api/v1/my-index/ingest")
+            .method("POST")
+            .header(
+                "Authorization",
+                authorization("ApiKey", "my-logs-key", "my-secret"),
+            )
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let resp = warp::test::request()
+            .path("/api/v1/logs-alias/ingest")
+            .method("POST")
+            .header(
+                "Authorization",
+                authorization("ApiKey", "my-logs-key", "my-secret"),
+            )
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let resp = warp::test::request()
+            .path("/api/v1/my-index/ingest")
+            .method("POST")
+            .header(
+                "Authorization",
+                authorization("ApiKey", "root", "my-root-secret-0123"),
+            )
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_auth_filter_disabled() {
+        let handler = api_key_auth_filter(None)
+            .map(|| warp::reply().into_response())
+            .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/api/v1/indexes")
+            .method("POST")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,234 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::convert::Infallible;
+use std::task::{Context, Poll};
+
+use futures::future::BoxFuture;
+use hyper::header::AUTHORIZATION;
+use hyper::{Body, Request, Response};
+use quickwit_config::ApiKeyScope;
+use quickwit_opentelemetry::otlp::{
+    extract_otel_index_id_from_metadata, extract_otel_traces_index_id_patterns_from_metadata,
+    OtelSignal,
+};
+use quickwit_proto::tonic;
+use quickwit_proto::tonic::body::BoxBody;
+use quickwit_proto::tonic::metadata::MetadataMap;
+use quickwit_proto::tonic::server::NamedService;
+use tower::Service;
+
+use super::api_key_auth::{ApiKeyAuthenticator, RequiredAccess};
+
+/// Resolves the access required by a gRPC request from its metadata.
+pub(crate) type RequiredAccessFn = fn(&MetadataMap) -> Result<RequiredAccess, tonic::Status>;
+
+/// Wraps a gRPC service exposed to the clients of the cluster, such as the OTLP or Jaeger
+/// services, and rejects the requests that do not carry an API key granting the access they
+/// require. The gRPC services used for the communication between the nodes are not wrapped.
+#[derive(Clone)]
+pub(crate) struct GrpcApiKeyAuth<S> {
+    inner: S,
+    authenticator_opt: Option<ApiKeyAuthenticator>,
+    required_access_fn: RequiredAccessFn,
+}
+
+impl<S> GrpcApiKeyAuth<S> {
+    /// When `authenticator_opt` is `None`, authentication is disabled and all the requests go
+    /// through.
+    pub fn new(
+        inner: S,
+        authenticator_opt: Option<ApiKeyAuthenticator>,
+        required_access_fn: RequiredAccessFn,
+    ) -> Self {
+        Self {
+            inner,
+            authenticator_opt,
+            required_access_fn,
+        }
+    }
+}
+
+impl<S> NamedService for GrpcApiKeyAuth<S>
+where S: NamedService
+{
+    const NAME: &'static str = S::NAME;
+}
+
+impl<S> Service<Request<Body>> for GrpcApiKeyAuth<S>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>, Error = Infallible>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let Some(authenticator) = self.authenticator_opt.clone() else {
+            return Box::pin(self.inner.call(request));
+        };
+        // The inner service was driven to readiness but its clone may not be, so we keep the
+        // clone and move the ready service into the future.
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
+        let required_access_fn = self.required_access_fn;
+
+        Box::pin(async move {
+            let authorization_opt = request
+                .headers()
+                .get(AUTHORIZATION)
+                .and_then(|authorization| authorization.to_str().ok())
+                .map(|authorization| authorization.to_string());
+            let metadata = MetadataMap::from_headers(request.headers().clone());
+
+            let auth_result = match required_access_fn(&metadata) {
+                Ok(required_access) => authenticator
+                    .authorize(authorization_opt.as_deref(), &required_access)
+                    .await
+                    .map_err(tonic::Status::from),
+                Err(status) => Err(status),
+            };
+            if let Err(status) = auth_result {
+                return Ok(status.to_http());
+            }
+            inner.call(request).await
+        })
+    }
+}
+
+/// OTLP logs are written to the index set in the `qw-otel-logs-index` header.
+pub(crate) fn otlp_logs_required_access(
+    metadata: &MetadataMap,
+) -> Result<RequiredAccess, tonic::Status> {
+    let index_id = extract_otel_index_id_from_metadata(metadata, OtelSignal::Logs)?;
+    Ok(RequiredAccess::indexes(ApiKeyScope::Ingest, [index_id]))
+}
+
+/// OTLP traces are written to the index set in the `qw-otel-traces-index` header.
+pub(crate) fn otlp_traces_required_access(
+    metadata: &MetadataMap,
+) -> Result<RequiredAccess, tonic::Status> {
+    let index_id = extract_otel_index_id_from_metadata(metadata, OtelSignal::Traces)?;
+    Ok(RequiredAccess::indexes(ApiKeyScope::Ingest, [index_id]))
+}
+
+/// Jaeger reads the traces from the indexes matching the patterns set in the
+/// `qw-otel-traces-index` header.
+pub(crate) fn jaeger_required_access(
+    metadata: &MetadataMap,
+) -> Result<RequiredAccess, tonic::Status> {
+    let index_id_patterns = extract_otel_traces_index_id_patterns_from_metadata(metadata)?;
+    Ok(RequiredAccess::indexes(
+        ApiKeyScope::Search,
+        index_id_patterns,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::{ApiKey, IndexAliases, RestAuthConfig};
+    use quickwit_metastore::ListIndexAliasesResponseExt;
+    use quickwit_opentelemetry::otlp::{OTEL_LOGS_INDEX_ID, OTEL_TRACES_INDEX_ID_PATTERN};
+    use quickwit_proto::metastore::{
+        ListApiKeysResponse, ListIndexAliasesResponse, ListRolesResponse, MetastoreServiceClient,
+        MockMetastoreService,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::auth_api::api_key_auth::hash_api_key_secret;
+
+    #[test]
+    fn test_grpc_required_access() {
+        let mut metadata = MetadataMap::new();
+        assert_eq!(
+            otlp_logs_required_access(&metadata).unwrap(),
+            RequiredAccess::indexes(ApiKeyScope::Ingest, [OTEL_LOGS_INDEX_ID])
+        );
+        assert_eq!(
+            jaeger_required_access(&metadata).unwrap(),
+            RequiredAccess::indexes(ApiKeyScope::Search, [OTEL_TRACES_INDEX_ID_PATTERN])
+        );
+        metadata.insert("qw-otel-logs-index", "my-logs".parse().unwrap());
+        metadata.insert(
+            "qw-otel-traces-index",
+            "my-traces,my-spans".parse().unwrap(),
+        );
+        assert_eq!(
+            otlp_logs_required_access(&metadata).unwrap(),
+            RequiredAccess::indexes(ApiKeyScope::Ingest, ["my-logs"])
+        );
+        assert_eq!(
+            jaeger_required_access(&metadata).unwrap(),
+            RequiredAccess::indexes(ApiKeyScope::Search, ["my-traces", "my-spans"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_grpc_api_key_auth() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_list_api_keys().return_once(|_| {
+            let mut api_key = ApiKey::for_test("my-ingest-key", &[ApiKeyScope::Ingest]);
+            api_key.secret_hash = hash_api_key_secret("my-secret");
+            let api_keys_json = vec![serde_json::to_string(&api_key).unwrap()];
+            Ok(ListApiKeysResponse { api_keys_json })
+        });
+        mock_metastore
+            .expect_list_roles()
+            .return_once(|_| Ok(ListRolesResponse::default()));
+        mock_metastore.expect_list_index_aliases().return_once(|_| {
+            let index_aliases = IndexAliases::default();
+            Ok(ListIndexAliasesResponse::try_from_index_aliases(&index_aliases).unwrap())
+        });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let auth_config = RestAuthConfig {
+            enabled: true,
+            root_api_key: None,
+        };
+        let authenticator = ApiKeyAuthenticator::new(metastore, &auth_config);
+
+        let inner = tower::service_fn(|_request: Request<Body>| async {
+            Ok::<_, Infallible>(Response::new(tonic::body::empty_body()))
+        });
+        let grpc_auth = GrpcApiKeyAuth::new(inner, Some(authenticator), otlp_logs_required_access);
+
+        let response = grpc_auth
+            .clone()
+            .oneshot(Request::new(Body::empty()))
+            .await
+            .unwrap();
+        let status = tonic::Status::from_header_map(response.headers()).unwrap();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let request = Request::builder()
+            .header("authorization", "ApiKey bXktaW5nZXN0LWtleTpteS1zZWNyZXQ=")
+            .body(Body::empty())
+            .unwrap();
+        let response = grpc_auth.clone().oneshot(request).await.unwrap();
+        assert!(tonic::Status::from_header_map(response.headers()).is_none());
+    }
+}
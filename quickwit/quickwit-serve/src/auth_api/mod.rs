@@ -0,0 +1,31 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod api_key_auth;
+mod grpc_auth;
+mod rest_handler;
+
+pub(crate) use api_key_auth::{
+    api_key_auth_filter, api_key_id_from_authorization, required_access, ApiKeyAuthenticator,
+    Forbidden, IndexTarget, Unauthorized,
+};
+pub(crate) use grpc_auth::{
+    jaeger_required_access, otlp_logs_required_access, otlp_traces_required_access, GrpcApiKeyAuth,
+};
+pub(crate) use rest_handler::{api_key_api_handlers, ApiKeyApi, RoleApi};
@@ -0,0 +1,771 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeSet;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use quickwit_config::{ApiKey, ApiKeyId, ApiKeyScope, RestAuthConfig, Role, RoleId};
+use quickwit_proto::metastore::{
+    serde_utils, CreateApiKeyRequest, CreateRoleRequest, DeleteApiKeyRequest, DeleteRoleRequest,
+    EntityKind, ListApiKeysRequest, ListRolesRequest, MetastoreError, MetastoreResult,
+    MetastoreService, MetastoreServiceClient,
+};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use time::OffsetDateTime;
+use warp::reject::Rejection;
+use warp::{Filter, Reply};
+
+use super::api_key_auth::{hash_api_key_secret, ApiKeyAuthenticator};
+use crate::format::extract_format_from_qs;
+use crate::rest::recover_fn;
+use crate::rest_api_response::into_rest_api_response;
+use crate::with_arg;
+
+/// Length of the secrets generated for the API keys.
+const API_KEY_SECRET_LEN: usize = 32;
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(create_api_key, list_api_keys, update_api_key_roles, delete_api_key),
+    components(schemas(
+        CreateApiKeyRequestBody,
+        UpdateApiKeyRolesRequestBody,
+        ApiKeyInfo,
+        CreatedApiKey
+    ))
+)]
+pub(crate) struct ApiKeyApi;
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(create_role, update_role, list_roles, delete_role))]
+pub(crate) struct RoleApi;
+
+/// Body of the requests creating an API key.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CreateApiKeyRequestBody {
+    pub api_key_id: ApiKeyId,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Vec<ApiKeyScope>)]
+    pub scopes: BTreeSet<ApiKeyScope>,
+    #[serde(default)]
+    #[schema(value_type = Vec<String>)]
+    pub roles: BTreeSet<RoleId>,
+}
+
+/// Body of the requests replacing the roles assigned to an API key.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateApiKeyRolesRequestBody {
+    #[schema(value_type = Vec<String>)]
+    pub roles: BTreeSet<RoleId>,
+}
+
+/// Describes an API key without its secret.
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct ApiKeyInfo {
+    pub api_key_id: ApiKeyId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[schema(value_type = Vec<ApiKeyScope>)]
+    pub scopes: BTreeSet<ApiKeyScope>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    #[schema(value_type = Vec<String>)]
+    pub roles: BTreeSet<RoleId>,
+    pub create_timestamp: i64,
+}
+
+impl From<ApiKey> for ApiKeyInfo {
+    fn from(api_key: ApiKey) -> Self {
+        ApiKeyInfo {
+            api_key_id: api_key.api_key_id,
+            description: api_key.description,
+            scopes: api_key.scopes,
+            roles: api_key.roles,
+            create_timestamp: api_key.create_timestamp,
+        }
+    }
+}
+
+/// Returned once, when an API key is created: the secret cannot be retrieved afterwards.
+#[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub api_key_info: ApiKeyInfo,
+    /// Secret of the API key.
+    pub api_key: String,
+    /// Base64 encoding of `<api_key_id>:<api_key>`, to send in the `Authorization: ApiKey
+    /// <encoded>` header.
+    pub encoded: String,
+}
+
+pub(crate) fn api_key_api_handlers(
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    create_api_key_handler(metastore.clone(), authenticator.clone())
+        .or(list_api_keys_handler(metastore.clone()))
+        .or(update_api_key_roles_handler(
+            metastore.clone(),
+            authenticator.clone(),
+        ))
+        .or(delete_api_key_handler(
+            metastore.clone(),
+            authenticator.clone(),
+        ))
+        .or(create_role_handler(
+            metastore.clone(),
+            authenticator.clone(),
+        ))
+        .or(update_role_handler(
+            metastore.clone(),
+            authenticator.clone(),
+        ))
+        .or(list_roles_handler(metastore.clone()))
+        .or(delete_role_handler(metastore, authenticator))
+        .recover(recover_fn)
+}
+
+fn create_api_key_handler(
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("auth" / "api_keys")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::json())
+        .and(with_arg(metastore))
+        .and(with_arg(authenticator))
+        .then(create_api_key)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    post,
+    tag = "API Keys",
+    path = "/auth/api_keys",
+    request_body = CreateApiKeyRequestBody,
+    responses(
+        (status = 200, description = "The API key was successfully created.", body = CreatedApiKey),
+        (status = 400, description = "The API key is invalid or one of its roles does not exist."),
+        (status = 409, description = "An API key with the same ID already exists.")
+    ),
+)]
+/// Creates a new API key. The secret of the key is generated by Quickwit and only returned in the
+/// response of this request.
+async fn create_api_key(
+    create_api_key_request_body: CreateApiKeyRequestBody,
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> MetastoreResult<CreatedApiKey> {
+    let CreateApiKeyRequestBody {
+        api_key_id,
+        description,
+        scopes,
+        roles,
+    } = create_api_key_request_body;
+
+    if api_key_id == RestAuthConfig::ROOT_API_KEY_ID {
+        return Err(MetastoreError::InvalidArgument {
+            message: format!("API key ID `{api_key_id}` is reserved"),
+        });
+    }
+    check_roles_exist(&metastore, &roles).await?;
+
+    let api_key_secret: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(API_KEY_SECRET_LEN)
+        .map(char::from)
+        .collect();
+    let api_key = ApiKey {
+        api_key_id,
+        description,
+        scopes,
+        roles,
+        secret_hash: hash_api_key_secret(&api_key_secret),
+        create_timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+    };
+    let create_api_key_request = CreateApiKeyRequest {
+        api_key_json: serde_utils::to_json_str(&api_key)?,
+        overwrite: false,
+    };
+    metastore.create_api_key(create_api_key_request).await?;
+    authenticator.invalidate();
+
+    let encoded = BASE64_STANDARD.encode(format!("{}:{api_key_secret}", api_key.api_key_id));
+    let created_api_key = CreatedApiKey {
+        api_key_info: api_key.into(),
+        api_key: api_key_secret,
+        encoded,
+    };
+    Ok(created_api_key)
+}
+
+fn list_api_keys_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("auth" / "api_keys")
+        .and(warp::get())
+        .and(with_arg(metastore))
+        .then(list_api_keys)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    get,
+    tag = "API Keys",
+    path = "/auth/api_keys",
+    responses(
+        (status = 200, description = "The API keys were successfully retrieved.", body = [ApiKeyInfo]),
+    ),
+)]
+/// Lists the API keys stored in the metastore. The secrets are never returned.
+async fn list_api_keys(metastore: MetastoreServiceClient) -> MetastoreResult<Vec<ApiKeyInfo>> {
+    let api_keys: Vec<ApiKeyInfo> = fetch_api_keys(&metastore)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    Ok(api_keys)
+}
+
+async fn fetch_api_keys(metastore: &MetastoreServiceClient) -> MetastoreResult<Vec<ApiKey>> {
+    let list_api_keys_response = metastore.list_api_keys(ListApiKeysRequest {}).await?;
+    list_api_keys_response
+        .api_keys_json
+        .iter()
+        .map(|api_key_json| serde_utils::from_json_str(api_key_json))
+        .collect()
+}
+
+fn update_api_key_roles_handler(
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("auth" / "api_keys" / String / "roles")
+        .and(warp::put())
+        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::json())
+        .and(with_arg(metastore))
+        .and(with_arg(authenticator))
+        .then(update_api_key_roles)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    put,
+    tag = "API Keys",
+    path = "/auth/api_keys/{api_key_id}/roles",
+    request_body = UpdateApiKeyRolesRequestBody,
+    responses(
+        (status = 200, description = "The roles of the API key were successfully updated.", body = ApiKeyInfo),
+        (status = 400, description = "One of the roles does not exist."),
+        (status = 404, description = "The API key was not found.")
+    ),
+)]
+/// Replaces the roles assigned to the API key identified by `api_key_id`.
+async fn update_api_key_roles(
+    api_key_id: ApiKeyId,
+    update_api_key_roles_request_body: UpdateApiKeyRolesRequestBody,
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> MetastoreResult<ApiKeyInfo> {
+    let roles = update_api_key_roles_request_body.roles;
+    check_roles_exist(&metastore, &roles).await?;
+
+    let Some(mut api_key) = fetch_api_keys(&metastore)
+        .await?
+        .into_iter()
+        .find(|api_key| api_key.api_key_id == api_key_id)
+    else {
+        return Err(MetastoreError::NotFound(EntityKind::ApiKey { api_key_id }));
+    };
+    api_key.roles = roles;
+
+    let create_api_key_request = CreateApiKeyRequest {
+        api_key_json: serde_utils::to_json_str(&api_key)?,
+        overwrite: true,
+    };
+    metastore.create_api_key(create_api_key_request).await?;
+    authenticator.invalidate();
+    Ok(api_key.into())
+}
+
+fn delete_api_key_handler(
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("auth" / "api_keys" / String)
+        .and(warp::delete())
+        .and(with_arg(metastore))
+        .and(with_arg(authenticator))
+        .then(delete_api_key)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    delete,
+    tag = "API Keys",
+    path = "/auth/api_keys/{api_key_id}",
+    responses(
+        (status = 200, description = "The API key was successfully revoked."),
+        (status = 404, description = "The API key was not found.")
+    ),
+)]
+/// Revokes the API key identified by the provided `api_key_id`.
+async fn delete_api_key(
+    api_key_id: ApiKeyId,
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> MetastoreResult<()> {
+    let delete_api_key_request = DeleteApiKeyRequest { api_key_id };
+    metastore.delete_api_key(delete_api_key_request).await?;
+    authenticator.invalidate();
+    Ok(())
+}
+
+async fn fetch_roles(metastore: &MetastoreServiceClient) -> MetastoreResult<Vec<Role>> {
+    let list_roles_response = metastore.list_roles(ListRolesRequest {}).await?;
+    list_roles_response
+        .roles_json
+        .iter()
+        .map(|role_json| serde_utils::from_json_str(role_json))
+        .collect()
+}
+
+/// Returns an error if one of the `role_ids` does not exist.
+async fn check_roles_exist(
+    metastore: &MetastoreServiceClient,
+    role_ids: &BTreeSet<RoleId>,
+) -> MetastoreResult<()> {
+    if role_ids.is_empty() {
+        return Ok(());
+    }
+    let existing_role_ids: BTreeSet<RoleId> = fetch_roles(metastore)
+        .await?
+        .into_iter()
+        .map(|role| role.role_id)
+        .collect();
+
+    if let Some(role_id) = role_ids.difference(&existing_role_ids).next() {
+        return Err(MetastoreError::InvalidArgument {
+            message: format!("role `{role_id}` does not exist"),
+        });
+    }
+    Ok(())
+}
+
+fn create_role_handler(
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("auth" / "roles")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::json())
+        .and(with_arg(metastore))
+        .and(with_arg(authenticator))
+        .then(create_role)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    post,
+    tag = "Roles",
+    path = "/auth/roles",
+    request_body = Role,
+    responses(
+        (status = 200, description = "The role was successfully created.", body = Role),
+        (status = 400, description = "The role is invalid."),
+        (status = 409, description = "A role with the same ID already exists.")
+    ),
+)]
+/// Creates a new role granting privileges on the indexes matching some index ID patterns.
+async fn create_role(
+    role: Role,
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> MetastoreResult<Role> {
+    let create_role_request = CreateRoleRequest {
+        role_json: serde_utils::to_json_str(&role)?,
+        overwrite: false,
+    };
+    metastore.create_role(create_role_request).await?;
+    authenticator.invalidate();
+    Ok(role)
+}
+
+fn update_role_handler(
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("auth" / "roles" / String)
+        .and(warp::put())
+        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::json())
+        .and(with_arg(metastore))
+        .and(with_arg(authenticator))
+        .then(update_role)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    put,
+    tag = "Roles",
+    path = "/auth/roles/{role_id}",
+    request_body = Role,
+    responses(
+        (status = 200, description = "The role was successfully created or updated.", body = Role),
+        (status = 400, description = "The role is invalid.")
+    ),
+)]
+/// Creates or replaces the role identified by `role_id`. The changes apply to all the API keys
+/// the role is assigned to.
+async fn update_role(
+    role_id: RoleId,
+    mut json_value: JsonValue,
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> MetastoreResult<Role> {
+    let Some(role_object) = json_value.as_object_mut() else {
+        return Err(MetastoreError::InvalidArgument {
+            message: "role must be a JSON object".to_string(),
+        });
+    };
+    role_object.insert("role_id".to_string(), JsonValue::String(role_id));
+    let role: Role = serde_utils::from_json_value(json_value)?;
+
+    let create_role_request = CreateRoleRequest {
+        role_json: serde_utils::to_json_str(&role)?,
+        overwrite: true,
+    };
+    metastore.create_role(create_role_request).await?;
+    authenticator.invalidate();
+    Ok(role)
+}
+
+fn list_roles_handler(
+    metastore: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("auth" / "roles")
+        .and(warp::get())
+        .and(with_arg(metastore))
+        .then(list_roles)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    get,
+    tag = "Roles",
+    path = "/auth/roles",
+    responses(
+        (status = 200, description = "The roles were successfully retrieved.", body = [Role]),
+    ),
+)]
+/// Lists the roles stored in the metastore.
+async fn list_roles(metastore: MetastoreServiceClient) -> MetastoreResult<Vec<Role>> {
+    fetch_roles(&metastore).await
+}
+
+fn delete_role_handler(
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("auth" / "roles" / String)
+        .and(warp::delete())
+        .and(with_arg(metastore))
+        .and(with_arg(authenticator))
+        .then(delete_role)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    delete,
+    tag = "Roles",
+    path = "/auth/roles/{role_id}",
+    responses(
+        (status = 200, description = "The role was successfully deleted."),
+        (status = 404, description = "The role was not found.")
+    ),
+)]
+/// Deletes the role identified by `role_id`. The API keys the role was assigned to lose the
+/// privileges it granted.
+async fn delete_role(
+    role_id: RoleId,
+    metastore: MetastoreServiceClient,
+    authenticator: ApiKeyAuthenticator,
+) -> MetastoreResult<()> {
+    let delete_role_request = DeleteRoleRequest { role_id };
+    metastore.delete_role(delete_role_request).await?;
+    authenticator.invalidate();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_config::IndexPrivilege;
+    use quickwit_proto::metastore::{
+        EmptyResponse, ListApiKeysResponse, ListRolesResponse, MockMetastoreService,
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    fn authenticator_for_test() -> ApiKeyAuthenticator {
+        let metastore = MetastoreServiceClient::from_mock(MockMetastoreService::new());
+        ApiKeyAuthenticator::new(metastore, &RestAuthConfig::default())
+    }
+
+    #[tokio::test]
+    async fn test_create_api_key() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_create_api_key()
+            .return_once(|request| {
+                let api_key: ApiKey = serde_json::from_str(&request.api_key_json).unwrap();
+                assert_eq!(api_key.api_key_id, "my-ingest-key");
+                assert_eq!(api_key.scopes, BTreeSet::from([ApiKeyScope::Ingest]));
+                assert_eq!(api_key.secret_hash.len(), 64);
+                assert!(api_key.roles.is_empty());
+                assert!(!request.overwrite);
+                Ok(EmptyResponse {})
+            });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let handler = api_key_api_handlers(metastore, authenticator_for_test());
+
+        let resp = warp::test::request()
+            .path("/auth/api_keys")
+            .method("POST")
+            .json(&json!({
+                "api_key_id": "my-ingest-key",
+                "scopes": ["ingest"],
+            }))
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let created_api_key: CreatedApiKey = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(created_api_key.api_key_info.api_key_id, "my-ingest-key");
+        assert_eq!(created_api_key.api_key.len(), API_KEY_SECRET_LEN);
+
+        let expected_encoded =
+            BASE64_STANDARD.encode(format!("my-ingest-key:{}", created_api_key.api_key));
+        assert_eq!(created_api_key.encoded, expected_encoded);
+
+        let resp = warp::test::request()
+            .path("/auth/api_keys")
+            .method("POST")
+            .json(&json!({
+                "api_key_id": "root",
+                "scopes": ["admin"],
+            }))
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_list_api_keys() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_list_api_keys().return_once(|_| {
+            let api_key = ApiKey::for_test("my-search-key", &[ApiKeyScope::Search]);
+            let api_keys_json = vec![serde_json::to_string(&api_key).unwrap()];
+            Ok(ListApiKeysResponse { api_keys_json })
+        });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let handler = api_key_api_handlers(metastore, authenticator_for_test());
+
+        let resp = warp::test::request()
+            .path("/auth/api_keys")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp_json: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        let expected_resp_json = json!([{
+            "api_key_id": "my-search-key",
+            "scopes": ["search"],
+            "create_timestamp": 0,
+        }]);
+        assert_eq!(resp_json, expected_resp_json);
+    }
+
+    #[tokio::test]
+    async fn test_delete_api_key() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_delete_api_key()
+            .times(2)
+            .returning(|request| {
+                if request.api_key_id == "my-search-key" {
+                    return Ok(EmptyResponse {});
+                }
+                Err(MetastoreError::NotFound(EntityKind::ApiKey {
+                    api_key_id: request.api_key_id,
+                }))
+            });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let handler = api_key_api_handlers(metastore, authenticator_for_test());
+
+        let resp = warp::test::request()
+            .path("/auth/api_keys/my-search-key")
+            .method("DELETE")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = warp::test::request()
+            .path("/auth/api_keys/my-unknown-key")
+            .method("DELETE")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_update_api_key_roles() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_list_roles().times(2).returning(|_| {
+            let role = Role::for_test("logs-reader", &["logs-*"], IndexPrivilege::Read);
+            let roles_json = vec![serde_json::to_string(&role).unwrap()];
+            Ok(ListRolesResponse { roles_json })
+        });
+        mock_metastore.expect_list_api_keys().return_once(|_| {
+            let api_key = ApiKey::for_test("my-search-key", &[ApiKeyScope::Search]);
+            let api_keys_json = vec![serde_json::to_string(&api_key).unwrap()];
+            Ok(ListApiKeysResponse { api_keys_json })
+        });
+        mock_metastore
+            .expect_create_api_key()
+            .return_once(|request| {
+                let api_key: ApiKey = serde_json::from_str(&request.api_key_json).unwrap();
+                assert_eq!(api_key.api_key_id, "my-search-key");
+                assert_eq!(api_key.roles, BTreeSet::from(["logs-reader".to_string()]));
+                assert!(request.overwrite);
+                Ok(EmptyResponse {})
+            });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let handler = api_key_api_handlers(metastore, authenticator_for_test());
+
+        let resp = warp::test::request()
+            .path("/auth/api_keys/my-search-key/roles")
+            .method("PUT")
+            .json(&json!({"roles": ["logs-reader"]}))
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp_json: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        let expected_resp_json = json!({
+            "api_key_id": "my-search-key",
+            "scopes": ["search"],
+            "roles": ["logs-reader"],
+            "create_timestamp": 0,
+        });
+        assert_eq!(resp_json, expected_resp_json);
+
+        let resp = warp::test::request()
+            .path("/auth/api_keys/my-search-key/roles")
+            .method("PUT")
+            .json(&json!({"roles": ["unknown-role"]}))
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_create_and_update_role() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore
+            .expect_create_role()
+            .times(2)
+            .returning(|request| {
+                let role: Role = serde_json::from_str(&request.role_json).unwrap();
+                assert_eq!(role.role_id, "logs-reader");
+
+                if request.overwrite {
+                    return Ok(EmptyResponse {});
+                }
+                Err(MetastoreError::AlreadyExists(EntityKind::Role {
+                    role_id: role.role_id,
+                }))
+            });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let handler = api_key_api_handlers(metastore, authenticator_for_test());
+
+        let resp = warp::test::request()
+            .path("/auth/roles")
+            .method("POST")
+            .json(&json!({
+                "role_id": "logs-reader",
+                "grants": [{"index_id_patterns": ["logs-*"], "privileges": ["read"]}],
+            }))
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 409);
+
+        let resp = warp::test::request()
+            .path("/auth/roles/logs-reader")
+            .method("PUT")
+            .json(&json!({
+                "grants": [{"index_id_patterns": ["logs-*"], "privileges": ["read"]}],
+            }))
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let role: Role = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            role,
+            Role::for_test("logs-reader", &["logs-*"], IndexPrivilege::Read)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_role() {
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_delete_role().return_once(|request| {
+            Err(MetastoreError::NotFound(EntityKind::Role {
+                role_id: request.role_id,
+            }))
+        });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let handler = api_key_api_handlers(metastore, authenticator_for_test());
+
+        let resp = warp::test::request()
+            .path("/auth/roles/my-unknown-role")
+            .method("DELETE")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), 404);
+    }
+}
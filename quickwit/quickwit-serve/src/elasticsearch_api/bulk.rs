@@ -17,7 +17,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
 use hyper::StatusCode;
@@ -31,8 +31,10 @@ use warp::{Filter, Rejection};
 
 use super::bulk_v2::{elastic_bulk_ingest_v2, ElasticBulkResponse};
 use crate::elasticsearch_api::filter::{elastic_bulk_filter, elastic_index_bulk_filter};
-use crate::elasticsearch_api::make_elastic_api_response;
-use crate::elasticsearch_api::model::{BulkAction, ElasticBulkOptions, ElasticsearchError};
+use crate::elasticsearch_api::model::{
+    BulkAction, ElasticBulkOptions, ElasticException, ElasticsearchError,
+};
+use crate::elasticsearch_api::{make_elastic_api_response, IndexAliasStore};
 use crate::format::extract_format_from_qs;
 use crate::ingest_api::lines;
 use crate::rest::recover_fn;
@@ -42,13 +44,24 @@ use crate::{with_arg, Body};
 pub fn es_compat_bulk_handler(
     ingest_service: IngestServiceClient,
     ingest_router: IngestRouterServiceClient,
+    index_alias_store: IndexAliasStore,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     elastic_bulk_filter()
         .and(with_arg(ingest_service))
         .and(with_arg(ingest_router))
-        .then(|body, bulk_options, ingest_service, ingest_router| {
-            elastic_ingest_bulk(None, body, bulk_options, ingest_service, ingest_router)
-        })
+        .and(with_arg(index_alias_store))
+        .then(
+            |body, bulk_options, ingest_service, ingest_router, index_alias_store| {
+                elastic_ingest_bulk(
+                    None,
+                    body,
+                    bulk_options,
+                    ingest_service,
+                    ingest_router,
+                    index_alias_store,
+                )
+            },
+        )
         .and(extract_format_from_qs())
         .map(make_elastic_api_response)
         .recover(recover_fn)
@@ -58,18 +71,21 @@ pub fn es_compat_bulk_handler(
 pub fn es_compat_index_bulk_handler(
     ingest_service: IngestServiceClient,
     ingest_router: IngestRouterServiceClient,
+    index_alias_store: IndexAliasStore,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     elastic_index_bulk_filter()
         .and(with_arg(ingest_service))
         .and(with_arg(ingest_router))
+        .and(with_arg(index_alias_store))
         .then(
-            |index_id, body, bulk_options, ingest_service, ingest_router| {
+            |index_id, body, bulk_options, ingest_service, ingest_router, index_alias_store| {
                 elastic_ingest_bulk(
                     Some(index_id),
                     body,
                     bulk_options,
                     ingest_service,
                     ingest_router,
+                    index_alias_store,
                 )
             },
         )
@@ -84,9 +100,19 @@ async fn elastic_ingest_bulk(
     bulk_options: ElasticBulkOptions,
     ingest_service: IngestServiceClient,
     ingest_router: IngestRouterServiceClient,
+    index_alias_store: IndexAliasStore,
 ) -> Result<ElasticBulkResponse, ElasticsearchError> {
+    let index_aliases = index_alias_store.load().await?;
+
     if enable_ingest_v2() || bulk_options.enable_ingest_v2 {
-        return elastic_bulk_ingest_v2(default_index_id, body, bulk_options, ingest_router).await;
+        return elastic_bulk_ingest_v2(
+            default_index_id,
+            body,
+            bulk_options,
+            ingest_router,
+            index_aliases,
+        )
+        .await;
     }
     if disable_ingest_v1() {
         return Err(ElasticsearchError::new(
@@ -98,6 +124,9 @@ async fn elastic_ingest_bulk(
     let now = Instant::now();
     let mut doc_batch_builders = HashMap::new();
     let mut lines = lines(&body.content).enumerate();
+    // `delete` and `update` actions, and `create` actions for an existing `_id`, are skipped.
+    let mut errors = false;
+    let mut created_es_doc_ids: HashSet<(IndexId, String)> = HashSet::new();
 
     while let Some((line_number, line)) = lines.next() {
         let action = serde_json::from_slice::<BulkAction>(line).map_err(|error| {
@@ -107,6 +136,10 @@ async fn elastic_ingest_bulk(
                 None,
             )
         })?;
+        if !action.has_source() {
+            errors = true;
+            continue;
+        }
         let (_, source) = lines.next().ok_or_else(|| {
             ElasticsearchError::new(
                 StatusCode::BAD_REQUEST,
@@ -114,11 +147,17 @@ async fn elastic_ingest_bulk(
                 None,
             )
         })?;
+        if matches!(action, BulkAction::Update(_)) {
+            errors = true;
+            continue;
+        }
         // when ingesting on /my-index/_bulk, if _index: is set to something else than my-index,
         // ES honors it and create the doc in the requested index. That is, `my-index` is a default
         // value in case _index: is missing, but not a constraint on each sub-action.
-        let index_id = action
-            .into_index_id()
+        let is_create = matches!(action, BulkAction::Create(_));
+        let meta = action.into_meta();
+        let index_id = meta
+            .index_id
             .or_else(|| default_index_id.clone())
             .ok_or_else(|| {
                 ElasticsearchError::new(
@@ -127,6 +166,21 @@ async fn elastic_ingest_bulk(
                     None,
                 )
             })?;
+        let index_id = index_aliases
+            .resolve_write_index(&index_id)
+            .map_err(|reason| {
+                ElasticsearchError::new(
+                    StatusCode::BAD_REQUEST,
+                    reason,
+                    Some(ElasticException::IllegalArgument),
+                )
+            })?;
+        if let Some(es_doc_id) = meta.es_doc_id.filter(|_| is_create) {
+            if !created_es_doc_ids.insert((index_id.clone(), es_doc_id)) {
+                errors = true;
+                continue;
+            }
+        }
         let doc_batch_builder = doc_batch_builders
             .entry(index_id.clone())
             .or_insert(DocBatchBuilder::new(index_id));
@@ -145,7 +199,6 @@ async fn elastic_ingest_bulk(
     ingest_service.ingest(ingest_request).await?;
 
     let took_millis = now.elapsed().as_millis() as u64;
-    let errors = false;
     let bulk_response = ElasticBulkResponse {
         took_millis,
         errors,
@@ -181,8 +234,7 @@ mod tests {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let elastic_api_handlers = elastic_api_handlers(
             config,
             search_service,
@@ -213,8 +265,7 @@ mod tests {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_service(&["my-index-1", "my-index-2"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let elastic_api_handlers = elastic_api_handlers(
             config,
             search_service,
@@ -249,8 +300,7 @@ mod tests {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_service(&["my-index-1"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let elastic_api_handlers = elastic_api_handlers(
             config,
             search_service,
@@ -282,8 +332,7 @@ mod tests {
         let (universe, _temp_dir, ingest_service, _) =
             setup_ingest_service(&["my-index-1", "my-index-2"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let elastic_api_handlers = elastic_api_handlers(
             config,
             search_service,
@@ -318,8 +367,7 @@ mod tests {
         let (universe, _temp_dir, ingest_service, ingest_service_mailbox) =
             setup_ingest_service(&["my-index-1", "my-index-2"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let elastic_api_handlers = elastic_api_handlers(
             config,
             search_service,
@@ -405,8 +453,7 @@ mod tests {
         let (universe, _temp_dir, ingest_service, ingest_service_mailbox) =
             setup_ingest_service(&["my-index-1", "my-index-2"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let elastic_api_handlers = elastic_api_handlers(
             config,
             search_service,
@@ -490,8 +537,7 @@ mod tests {
         let search_service = Arc::new(MockSearchService::new());
         let ingest_service = IngestServiceClient::mocked();
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let elastic_api_handlers = elastic_api_handlers(
             config,
             search_service,
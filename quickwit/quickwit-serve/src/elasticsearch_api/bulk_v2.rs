@@ -17,12 +17,13 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::Instant;
 
 use hyper::StatusCode;
 use quickwit_common::rate_limited_error;
-use quickwit_config::INGEST_V2_SOURCE_ID;
+use quickwit_config::{IndexAliases, INGEST_V2_SOURCE_ID};
 use quickwit_ingest::IngestRequestV2Builder;
 use quickwit_proto::ingest::router::{
     IngestFailureReason, IngestResponseV2, IngestRouterService, IngestRouterServiceClient,
@@ -51,6 +52,10 @@ pub(crate) enum ElasticBulkAction {
     Create(ElasticBulkItem),
     #[serde(rename = "index")]
     Index(ElasticBulkItem),
+    #[serde(rename = "delete")]
+    Delete(ElasticBulkItem),
+    #[serde(rename = "update")]
+    Update(ElasticBulkItem),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,11 +95,16 @@ pub(crate) async fn elastic_bulk_ingest_v2(
     body: Body,
     bulk_options: ElasticBulkOptions,
     ingest_router: IngestRouterServiceClient,
+    index_aliases: Arc<IndexAliases>,
 ) -> Result<ElasticBulkResponse, ElasticsearchError> {
     let now = Instant::now();
     let mut ingest_request_builder = IngestRequestV2Builder::default();
     let mut lines = lines(&body.content).enumerate();
     let mut per_subrequest_doc_handles: HashMap<u32, Vec<DocHandle>> = HashMap::new();
+    // Actions rejected before ingestion, along with their position in the request.
+    let mut rejected_actions: Vec<(usize, ElasticBulkAction)> = Vec::new();
+    // IDs of the documents created by `create` actions, per index.
+    let mut created_es_doc_ids: HashSet<(IndexId, ElasticDocId)> = HashSet::new();
     let mut action_count = 0;
     while let Some((line_no, line)) = lines.next() {
         let action = serde_json::from_slice::<BulkAction>(line).map_err(|error| {
@@ -104,13 +114,21 @@ pub(crate) async fn elastic_bulk_ingest_v2(
                 Some(ElasticException::IllegalArgument),
             )
         })?;
-        let (_, doc) = lines.next().ok_or_else(|| {
-            ElasticsearchError::new(
-                StatusCode::BAD_REQUEST,
-                "Validation Failed: 1: no requests added;".to_string(),
-                Some(ElasticException::ActionRequestValidation),
-            )
-        })?;
+        let doc_opt = if action.has_source() {
+            let (_, doc) = lines.next().ok_or_else(|| {
+                ElasticsearchError::new(
+                    StatusCode::BAD_REQUEST,
+                    "Validation Failed: 1: no requests added;".to_string(),
+                    Some(ElasticException::ActionRequestValidation),
+                )
+            })?;
+            Some(doc)
+        } else {
+            None
+        };
+        let is_create = matches!(action, BulkAction::Create(_));
+        let is_delete = matches!(action, BulkAction::Delete(_));
+        let is_update = matches!(action, BulkAction::Update(_));
         let meta = action.into_meta();
         // When ingesting into `/my-index/_bulk`, if `_index` is set to something other than
         // `my-index`, ES honors it and creates the doc for the requested index. That is,
@@ -126,6 +144,80 @@ pub(crate) async fn elastic_bulk_ingest_v2(
                     Some(ElasticException::ActionRequestValidation),
                 )
             })?;
+        if is_delete || is_update {
+            // Quickwit does not index the `_id` of the documents, so they cannot be looked up
+            // for deletion or partial updates. Deletions have to go through delete tasks, which
+            // delete the documents matching a query.
+            let reason = if is_delete {
+                "`delete` actions are not supported: documents cannot be looked up by `_id`, use \
+                 the delete tasks API to delete the documents matching a query instead"
+            } else {
+                "`update` actions are not supported: documents cannot be looked up by `_id`"
+            };
+            let error = ElasticBulkError {
+                index_id: Some(index_id.clone()),
+                exception: ElasticException::IllegalArgument,
+                reason: reason.to_string(),
+            };
+            let item = ElasticBulkItem {
+                index_id,
+                es_doc_id: meta.es_doc_id,
+                status: StatusCode::BAD_REQUEST,
+                error: Some(error),
+            };
+            let rejected_action = if is_delete {
+                ElasticBulkAction::Delete(item)
+            } else {
+                ElasticBulkAction::Update(item)
+            };
+            rejected_actions.push((action_count, rejected_action));
+            action_count += 1;
+            continue;
+        }
+        let index_id = match index_aliases.resolve_write_index(&index_id) {
+            Ok(write_index_id) => write_index_id,
+            Err(reason) => {
+                let error = ElasticBulkError {
+                    index_id: Some(index_id.clone()),
+                    exception: ElasticException::IllegalArgument,
+                    reason,
+                };
+                let item = ElasticBulkItem {
+                    index_id,
+                    es_doc_id: meta.es_doc_id,
+                    status: StatusCode::BAD_REQUEST,
+                    error: Some(error),
+                };
+                let rejected_action = if is_create {
+                    ElasticBulkAction::Create(item)
+                } else {
+                    ElasticBulkAction::Index(item)
+                };
+                rejected_actions.push((action_count, rejected_action));
+                action_count += 1;
+                continue;
+            }
+        };
+        if let Some(es_doc_id) = meta.es_doc_id.as_ref().filter(|_| is_create) {
+            let is_duplicate = !created_es_doc_ids.insert((index_id.clone(), es_doc_id.clone()));
+            if is_duplicate {
+                let error = ElasticBulkError {
+                    index_id: Some(index_id.clone()),
+                    exception: ElasticException::VersionConflictEngine,
+                    reason: format!("[{es_doc_id}]: version conflict, document already exists"),
+                };
+                let item = ElasticBulkItem {
+                    index_id,
+                    es_doc_id: meta.es_doc_id,
+                    status: StatusCode::CONFLICT,
+                    error: Some(error),
+                };
+                rejected_actions.push((action_count, ElasticBulkAction::Create(item)));
+                action_count += 1;
+                continue;
+            }
+        }
+        let doc = doc_opt.expect("`create` and `index` actions should have a source");
         let (subrequest_id, doc_uid) = ingest_request_builder.add_doc(index_id, doc);
 
         let doc_handle = DocHandle {
@@ -145,7 +237,13 @@ pub(crate) async fn elastic_bulk_ingest_v2(
     let ingest_request_opt = ingest_request_builder.build(INGEST_V2_SOURCE_ID, commit_type);
 
     let Some(ingest_request) = ingest_request_opt else {
-        return Ok(ElasticBulkResponse::default());
+        return make_elastic_bulk_response_v2(
+            IngestResponseV2::default(),
+            per_subrequest_doc_handles,
+            rejected_actions,
+            now,
+            action_count,
+        );
     };
     let ingest_response = ingest_router.ingest(ingest_request).await.map_err(|err| {
         rate_limited_error!(limit_per_min=6, err=?err, "router error");
@@ -154,6 +252,7 @@ pub(crate) async fn elastic_bulk_ingest_v2(
     make_elastic_bulk_response_v2(
         ingest_response,
         per_subrequest_doc_handles,
+        rejected_actions,
         now,
         action_count,
     )
@@ -162,11 +261,13 @@ pub(crate) async fn elastic_bulk_ingest_v2(
 fn make_elastic_bulk_response_v2(
     ingest_response_v2: IngestResponseV2,
     mut per_subrequest_doc_handles: HashMap<u32, Vec<DocHandle>>,
+    rejected_actions: Vec<(usize, ElasticBulkAction)>,
     now: Instant,
     action_count: usize,
 ) -> Result<ElasticBulkResponse, ElasticsearchError> {
-    let mut positioned_actions: Vec<(usize, ElasticBulkAction)> = Vec::with_capacity(action_count);
-    let mut errors = false;
+    let mut errors = !rejected_actions.is_empty();
+    let mut positioned_actions: Vec<(usize, ElasticBulkAction)> = rejected_actions;
+    positioned_actions.reserve(action_count);
 
     // Populate the items for each `IngestSuccess` subresponse. They may be partially successful and
     // contain some parse failures.
@@ -346,59 +447,66 @@ fn remove_doc_handles(
 
 #[cfg(test)]
 mod tests {
+    use quickwit_config::IndexAliasAction;
     use quickwit_proto::ingest::router::{
         IngestFailure, IngestFailureReason, IngestResponseV2, IngestSuccess,
         MockIngestRouterService,
     };
     use quickwit_proto::ingest::{ParseFailure, ParseFailureReason};
     use quickwit_proto::types::{IndexUid, Position, ShardId};
+    use serde_json::json;
     use warp::{Filter, Rejection, Reply};
 
     use super::*;
     use crate::elasticsearch_api::bulk_v2::ElasticBulkResponse;
     use crate::elasticsearch_api::filter::elastic_bulk_filter;
     use crate::elasticsearch_api::make_elastic_api_response;
-    use crate::elasticsearch_api::model::ElasticsearchError;
+    use crate::elasticsearch_api::model::{AliasActionsBody, ElasticsearchError};
     use crate::format::extract_format_from_qs;
     use crate::with_arg;
 
     impl ElasticBulkAction {
-        fn index_id(&self) -> &IndexId {
+        fn item(&self) -> &ElasticBulkItem {
             match self {
-                ElasticBulkAction::Create(item) => &item.index_id,
-                ElasticBulkAction::Index(item) => &item.index_id,
+                ElasticBulkAction::Create(item) => item,
+                ElasticBulkAction::Index(item) => item,
+                ElasticBulkAction::Delete(item) => item,
+                ElasticBulkAction::Update(item) => item,
             }
         }
 
+        fn index_id(&self) -> &IndexId {
+            &self.item().index_id
+        }
+
         fn es_doc_id(&self) -> Option<&str> {
-            match self {
-                ElasticBulkAction::Create(item) => item.es_doc_id.as_deref(),
-                ElasticBulkAction::Index(item) => item.es_doc_id.as_deref(),
-            }
+            self.item().es_doc_id.as_deref()
         }
 
         fn status(&self) -> StatusCode {
-            match self {
-                ElasticBulkAction::Create(item) => item.status,
-                ElasticBulkAction::Index(item) => item.status,
-            }
+            self.item().status
         }
 
         fn error(&self) -> Option<&ElasticBulkError> {
-            match self {
-                ElasticBulkAction::Create(item) => item.error.as_ref(),
-                ElasticBulkAction::Index(item) => item.error.as_ref(),
-            }
+            self.item().error.as_ref()
         }
     }
 
     fn es_compat_bulk_handler_v2(
         ingest_router: IngestRouterServiceClient,
+    ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+        es_compat_bulk_handler_v2_with_aliases(ingest_router, IndexAliases::default())
+    }
+
+    fn es_compat_bulk_handler_v2_with_aliases(
+        ingest_router: IngestRouterServiceClient,
+        index_aliases: IndexAliases,
     ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
         elastic_bulk_filter()
             .and(with_arg(ingest_router))
-            .then(|body, bulk_options, ingest_router| {
-                elastic_bulk_ingest_v2(None, body, bulk_options, ingest_router)
+            .and(with_arg(Arc::new(index_aliases)))
+            .then(|body, bulk_options, ingest_router, index_aliases| {
+                elastic_bulk_ingest_v2(None, body, bulk_options, ingest_router, index_aliases)
             })
             .and(extract_format_from_qs())
             .map(make_elastic_api_response)
@@ -478,10 +586,7 @@ mod tests {
         let mut items = bulk_response
             .actions
             .into_iter()
-            .map(|action| match action {
-                ElasticBulkAction::Create(item) => item,
-                ElasticBulkAction::Index(item) => item,
-            })
+            .map(|action| action.item().clone())
             .collect::<Vec<_>>();
         assert_eq!(items.len(), 3);
 
@@ -503,6 +608,169 @@ mod tests {
         assert_eq!(items[2].status, StatusCode::CREATED);
     }
 
+    #[tokio::test]
+    async fn test_bulk_api_mixed_actions() {
+        let mut mock_ingest_router = MockIngestRouterService::new();
+        mock_ingest_router
+            .expect_ingest()
+            .once()
+            .returning(|ingest_request| {
+                assert_eq!(ingest_request.subrequests.len(), 1);
+
+                let subrequest = &ingest_request.subrequests[0];
+                assert_eq!(subrequest.index_id, "my-index-1");
+                assert_eq!(subrequest.doc_batch.as_ref().unwrap().num_docs(), 2);
+
+                Ok(IngestResponseV2 {
+                    successes: vec![IngestSuccess {
+                        subrequest_id: 0,
+                        index_uid: Some(IndexUid::for_test("my-index-1", 0)),
+                        source_id: INGEST_V2_SOURCE_ID.to_string(),
+                        shard_id: Some(ShardId::from(1)),
+                        replication_position_inclusive: Some(Position::offset(1u64)),
+                        num_ingested_docs: 2,
+                        parse_failures: Vec::new(),
+                    }],
+                    failures: Vec::new(),
+                })
+            });
+        let ingest_router = IngestRouterServiceClient::from_mock(mock_ingest_router);
+        let handler = es_compat_bulk_handler_v2(ingest_router);
+
+        let payload = r#"
+            {"create": {"_index": "my-index-1", "_id" : "1"}}
+            {"ts": 1, "message": "my-message-1"}
+            {"create": {"_index": "my-index-1", "_id" : "1"}}
+            {"ts": 1, "message": "my-message-1"}
+            {"delete": {"_index": "my-index-1", "_id" : "2"}}
+            {"update": {"_index": "my-index-1", "_id" : "3"}}
+            {"doc": {"message": "my-message-3"}}
+            {"index": {"_index": "my-index-1", "_id" : "1"}}
+            {"ts": 4, "message": "my-message-4"}
+        "#;
+        let response = warp::test::request()
+            .path("/_elastic/_bulk")
+            .method("POST")
+            .body(payload)
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let bulk_response: ElasticBulkResponse = serde_json::from_slice(response.body()).unwrap();
+        assert!(bulk_response.errors);
+
+        let actions = bulk_response.actions;
+        assert_eq!(actions.len(), 5);
+
+        assert_eq!(actions[0].status(), StatusCode::CREATED);
+
+        assert!(matches!(actions[1], ElasticBulkAction::Create(_)));
+        assert_eq!(actions[1].es_doc_id(), Some("1"));
+        assert_eq!(actions[1].status(), StatusCode::CONFLICT);
+        let error = actions[1].error().unwrap();
+        assert_eq!(error.exception, ElasticException::VersionConflictEngine);
+        assert_eq!(
+            error.reason,
+            "[1]: version conflict, document already exists"
+        );
+
+        assert!(matches!(actions[2], ElasticBulkAction::Delete(_)));
+        assert_eq!(actions[2].es_doc_id(), Some("2"));
+        assert_eq!(actions[2].status(), StatusCode::BAD_REQUEST);
+        let error = actions[2].error().unwrap();
+        assert_eq!(error.exception, ElasticException::IllegalArgument);
+        assert!(error.reason.contains("delete tasks API"));
+
+        assert!(matches!(actions[3], ElasticBulkAction::Update(_)));
+        assert_eq!(actions[3].es_doc_id(), Some("3"));
+        assert_eq!(actions[3].status(), StatusCode::BAD_REQUEST);
+
+        assert_eq!(actions[4].es_doc_id(), Some("1"));
+        assert_eq!(actions[4].status(), StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_api_write_aliases() {
+        let mut mock_ingest_router = MockIngestRouterService::new();
+        mock_ingest_router
+            .expect_ingest()
+            .once()
+            .returning(|ingest_request| {
+                assert_eq!(ingest_request.subrequests.len(), 1);
+
+                let subrequest = &ingest_request.subrequests[0];
+                assert_eq!(subrequest.index_id, "my-index-2");
+                assert_eq!(subrequest.doc_batch.as_ref().unwrap().num_docs(), 2);
+
+                Ok(IngestResponseV2 {
+                    successes: vec![IngestSuccess {
+                        subrequest_id: 0,
+                        index_uid: Some(IndexUid::for_test("my-index-2", 0)),
+                        source_id: INGEST_V2_SOURCE_ID.to_string(),
+                        shard_id: Some(ShardId::from(1)),
+                        replication_position_inclusive: Some(Position::offset(1u64)),
+                        num_ingested_docs: 2,
+                        parse_failures: Vec::new(),
+                    }],
+                    failures: Vec::new(),
+                })
+            });
+        let ingest_router = IngestRouterServiceClient::from_mock(mock_ingest_router);
+        let alias_actions_body: AliasActionsBody = serde_json::from_value(json!({
+            "actions": [
+                {"add": {"index": "my-index-1", "alias": "my-alias", "is_write_index": false}},
+                {"add": {"index": "my-index-2", "alias": "my-alias", "is_write_index": true}},
+                {"add": {"indices": ["my-index-1", "my-index-2"], "alias": "my-read-alias"}},
+            ]
+        }))
+        .unwrap();
+        let index_alias_actions = alias_actions_body
+            .actions
+            .into_iter()
+            .map(IndexAliasAction::from)
+            .collect();
+        let mut index_aliases = IndexAliases::default();
+        index_aliases.apply_actions(index_alias_actions).unwrap();
+        let handler = es_compat_bulk_handler_v2_with_aliases(ingest_router, index_aliases);
+
+        let payload = r#"
+            {"create": {"_index": "my-alias"}}
+            {"ts": 1, "message": "my-message-1"}
+            {"index": {"_index": "my-read-alias"}}
+            {"ts": 2, "message": "my-message-2"}
+            {"index": {"_index": "my-index-2"}}
+            {"ts": 3, "message": "my-message-3"}
+        "#;
+        let response = warp::test::request()
+            .path("/_elastic/_bulk")
+            .method("POST")
+            .body(payload)
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let bulk_response: ElasticBulkResponse = serde_json::from_slice(response.body()).unwrap();
+        assert!(bulk_response.errors);
+
+        let actions = bulk_response.actions;
+        assert_eq!(actions.len(), 3);
+
+        assert_eq!(actions[0].index_id(), "my-index-2");
+        assert_eq!(actions[0].status(), StatusCode::CREATED);
+
+        assert!(matches!(actions[1], ElasticBulkAction::Index(_)));
+        assert_eq!(actions[1].index_id(), "my-read-alias");
+        assert_eq!(actions[1].status(), StatusCode::BAD_REQUEST);
+        let error = actions[1].error().unwrap();
+        assert_eq!(error.exception, ElasticException::IllegalArgument);
+        assert!(error
+            .reason
+            .starts_with("no write index is defined for alias [my-read-alias]"));
+
+        assert_eq!(actions[2].index_id(), "my-index-2");
+        assert_eq!(actions[2].status(), StatusCode::CREATED);
+    }
+
     #[tokio::test]
     async fn test_bulk_api_accepts_empty_requests() {
         let ingest_router = IngestRouterServiceClient::mocked();
@@ -703,6 +971,7 @@ mod tests {
         let response = make_elastic_bulk_response_v2(
             IngestResponseV2::default(),
             HashMap::new(),
+            Vec::new(),
             Instant::now(),
             0,
         )
@@ -763,6 +1032,7 @@ mod tests {
         let response = make_elastic_bulk_response_v2(
             ingest_response_v2,
             per_request_doc_handles,
+            Vec::new(),
             Instant::now(),
             3,
         )
@@ -24,14 +24,20 @@ use warp::reject::LengthRequired;
 use warp::{Filter, Rejection};
 
 use super::model::{
-    CatIndexQueryParams, DeleteQueryParams, FieldCapabilityQueryParams, FieldCapabilityRequestBody,
-    MultiSearchQueryParams, SearchQueryParamsCount,
+    AliasActionsBody, AnalyzeRequestBody, CatIndexQueryParams, CatQueryParams,
+    ClosePointInTimeBody, DeleteQueryParams, FieldCapabilityQueryParams,
+    FieldCapabilityRequestBody, GetAsyncSearchQueryParams, MultiSearchQueryParams,
+    OpenPointInTimeQueryParams, PutIndexTemplateBody, PutIndexTemplateQueryParams, PutMappingBody,
+    SearchQueryParamsCount, SearchTemplateBody, StoredScriptBody, SubmitAsyncSearchQueryParams,
+    TermsEnumQueryParams, TermsEnumRequestBody,
 };
 use crate::decompression::get_body_bytes;
 use crate::elasticsearch_api::model::{
     ElasticBulkOptions, ScrollQueryParams, SearchBody, SearchQueryParams,
 };
-use crate::search_api::{extract_index_id_patterns, extract_index_id_patterns_default};
+use crate::search_api::{
+    api_key_id_filter, extract_index_id_patterns, extract_index_id_patterns_default,
+};
 use crate::Body;
 
 const BODY_LENGTH_LIMIT: ByteSize = ByteSize::mib(1);
@@ -51,12 +57,27 @@ pub(crate) fn elastic_cluster_info_filter() -> impl Filter<Extract = (), Error =
         .and(warp::path::end())
 }
 
+/// Parses the search query parameters and attaches the ID of the API key passed in the
+/// `Authorization` header, if any.
+fn search_query_params_filter(
+) -> impl Filter<Extract = (SearchQueryParams,), Error = Rejection> + Clone {
+    serde_qs::warp::query(serde_qs::Config::default())
+        .and(api_key_id_filter())
+        .map(
+            |mut search_params: SearchQueryParams, api_key_id_opt: Option<String>| {
+                search_params.api_key_id = api_key_id_opt;
+                search_params
+            },
+        )
+}
+
 #[utoipa::path(get, tag = "Search", path = "/_search")]
 pub(crate) fn elasticsearch_filter(
-) -> impl Filter<Extract = (SearchQueryParams,), Error = Rejection> + Clone {
+) -> impl Filter<Extract = (SearchQueryParams, SearchBody), Error = Rejection> + Clone {
     warp::path!("_elastic" / "_search")
         .and(warp::get().or(warp::post()).unify())
-        .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(search_query_params_filter())
+        .and(json_or_empty())
 }
 
 #[utoipa::path(
@@ -162,6 +183,18 @@ pub(crate) fn elastic_field_capabilities_filter() -> impl Filter<
         .and(json_or_empty())
 }
 
+#[utoipa::path(get, tag = "Search", path = "/{index}/_terms_enum")]
+pub(crate) fn elastic_index_terms_enum_filter() -> impl Filter<
+    Extract = (Vec<String>, TermsEnumQueryParams, TermsEnumRequestBody),
+    Error = Rejection,
+> + Clone {
+    warp::path!("_elastic" / String / "_terms_enum")
+        .and_then(extract_index_id_patterns)
+        .and(warp::get().or(warp::post()).unify())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(json_or_empty())
+}
+
 #[utoipa::path(get, tag = "Metadata", path = "/_resolve/index/{index}")]
 pub(crate) fn elastic_resolve_index_filter(
 ) -> impl Filter<Extract = (Vec<String>,), Error = Rejection> + Clone {
@@ -204,6 +237,66 @@ pub(crate) fn elastic_stats_filter() -> impl Filter<Extract = (), Error = Reject
     warp::path!("_elastic" / "_stats").and(warp::get())
 }
 
+#[utoipa::path(get, tag = "Indexes", path = "/{index}/_mapping")]
+pub(crate) fn elastic_index_mapping_filter(
+) -> impl Filter<Extract = (Vec<String>,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / String / "_mapping")
+        .and_then(extract_index_id_patterns)
+        .and(warp::get())
+}
+
+#[utoipa::path(get, tag = "Indexes", path = "/_mapping")]
+pub(crate) fn elastic_mapping_filter() -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_mapping").and(warp::get())
+}
+
+#[utoipa::path(post, tag = "Indexes", path = "/_analyze")]
+pub(crate) fn elastic_analyze_filter(
+) -> impl Filter<Extract = (AnalyzeRequestBody,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_analyze")
+        .and(warp::get().or(warp::post()).unify())
+        .and(warp::body::content_length_limit(BODY_LENGTH_LIMIT.as_u64()))
+        .and(warp::body::json())
+}
+
+#[utoipa::path(post, tag = "Indexes", path = "/{index}/_analyze")]
+pub(crate) fn elastic_index_analyze_filter(
+) -> impl Filter<Extract = (String, AnalyzeRequestBody), Error = Rejection> + Clone {
+    warp::path!("_elastic" / String / "_analyze")
+        .and(warp::get().or(warp::post()).unify())
+        .and(warp::body::content_length_limit(BODY_LENGTH_LIMIT.as_u64()))
+        .and(warp::body::json())
+}
+
+#[utoipa::path(post, tag = "Indexes", path = "/_aliases")]
+pub(crate) fn elastic_update_aliases_filter(
+) -> impl Filter<Extract = (AliasActionsBody,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_aliases")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(BODY_LENGTH_LIMIT.as_u64()))
+        .and(warp::body::json())
+}
+
+#[utoipa::path(get, tag = "Indexes", path = "/_alias/{alias}")]
+pub(crate) fn elastic_get_aliases_filter(
+) -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_alias")
+        .map(|| None)
+        .or(warp::path!("_elastic" / "_alias" / String).map(Some))
+        .unify()
+        .and(warp::get())
+}
+
+#[utoipa::path(put, tag = "Indexes", path = "/{index}/_mapping")]
+pub(crate) fn elastic_index_put_mapping_filter(
+) -> impl Filter<Extract = (Vec<String>, PutMappingBody), Error = Rejection> + Clone {
+    warp::path!("_elastic" / String / "_mapping")
+        .and_then(extract_index_id_patterns)
+        .and(warp::put().or(warp::post()).unify())
+        .and(warp::body::content_length_limit(BODY_LENGTH_LIMIT.as_u64()))
+        .and(warp::body::json())
+}
+
 #[utoipa::path(get, tag = "Search", path = "/_cat/indices/{index}")]
 pub(crate) fn elastic_index_cat_indices_filter(
 ) -> impl Filter<Extract = (Vec<String>, CatIndexQueryParams), Error = Rejection> + Clone {
@@ -221,6 +314,39 @@ pub(crate) fn elastic_cat_indices_filter(
         .and(serde_qs::warp::query(serde_qs::Config::default()))
 }
 
+#[utoipa::path(get, tag = "Search", path = "/_cat/count/{index}")]
+pub(crate) fn elastic_index_cat_count_filter(
+) -> impl Filter<Extract = (Vec<String>, CatQueryParams), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_cat" / "count" / String)
+        .and_then(extract_index_id_patterns)
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
+#[utoipa::path(get, tag = "Search", path = "/_cat/count")]
+pub(crate) fn elastic_cat_count_filter(
+) -> impl Filter<Extract = (CatQueryParams,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_cat" / "count")
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
+#[utoipa::path(get, tag = "Cluster Info", path = "/_cat/health")]
+pub(crate) fn elastic_cat_health_filter(
+) -> impl Filter<Extract = (CatQueryParams,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_cat" / "health")
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
+#[utoipa::path(get, tag = "Cluster Info", path = "/_cat/nodes")]
+pub(crate) fn elastic_cat_nodes_filter(
+) -> impl Filter<Extract = (CatQueryParams,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_cat" / "nodes")
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
 #[utoipa::path(get, tag = "Search", path = "/{index}/_search")]
 pub(crate) fn elastic_index_search_filter(
 ) -> impl Filter<Extract = (Vec<String>, SearchQueryParams, SearchBody), Error = Rejection> + Clone
@@ -228,10 +354,118 @@ pub(crate) fn elastic_index_search_filter(
     warp::path!("_elastic" / String / "_search")
         .and_then(extract_index_id_patterns)
         .and(warp::get().or(warp::post()).unify())
+        .and(search_query_params_filter())
+        .and(json_or_empty())
+}
+
+#[utoipa::path(get, tag = "Search", path = "/{index}/_search/template")]
+pub(crate) fn elastic_index_search_template_filter(
+) -> impl Filter<Extract = (Vec<String>, SearchQueryParams, SearchTemplateBody), Error = Rejection> + Clone
+{
+    warp::path!("_elastic" / String / "_search" / "template")
+        .and_then(extract_index_id_patterns)
+        .and(warp::get().or(warp::post()).unify())
+        .and(search_query_params_filter())
+        .and(json_or_empty())
+}
+
+#[utoipa::path(put, tag = "Search", path = "/_scripts/{id}")]
+pub(crate) fn elastic_put_stored_script_filter(
+) -> impl Filter<Extract = (String, StoredScriptBody), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_scripts" / String)
+        .and(warp::put().or(warp::post()).unify())
+        .and(warp::body::content_length_limit(BODY_LENGTH_LIMIT.as_u64()))
+        .and(warp::body::json())
+}
+
+#[utoipa::path(get, tag = "Search", path = "/_scripts/{id}")]
+pub(crate) fn elastic_get_stored_script_filter(
+) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_scripts" / String).and(warp::get())
+}
+
+#[utoipa::path(delete, tag = "Search", path = "/_scripts/{id}")]
+pub(crate) fn elastic_delete_stored_script_filter(
+) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_scripts" / String).and(warp::delete())
+}
+
+#[utoipa::path(put, tag = "Indexes", path = "/_index_template/{name}")]
+pub(crate) fn elastic_put_index_template_filter() -> impl Filter<
+    Extract = (String, PutIndexTemplateQueryParams, PutIndexTemplateBody),
+    Error = Rejection,
+> + Clone {
+    warp::path!("_elastic" / "_index_template" / String)
+        .and(warp::put().or(warp::post()).unify())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(warp::body::content_length_limit(BODY_LENGTH_LIMIT.as_u64()))
+        .and(warp::body::json())
+}
+
+#[utoipa::path(get, tag = "Indexes", path = "/_index_template/{name}")]
+pub(crate) fn elastic_get_index_templates_filter(
+) -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_index_template")
+        .map(|| None)
+        .or(warp::path!("_elastic" / "_index_template" / String).map(Some))
+        .unify()
+        .and(warp::get())
+}
+
+#[utoipa::path(delete, tag = "Indexes", path = "/_index_template/{name}")]
+pub(crate) fn elastic_delete_index_template_filter(
+) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_index_template" / String).and(warp::delete())
+}
+
+#[utoipa::path(post, tag = "Search", path = "/{index}/_pit")]
+pub(crate) fn elastic_open_point_in_time_filter(
+) -> impl Filter<Extract = (Vec<String>, OpenPointInTimeQueryParams), Error = Rejection> + Clone {
+    warp::path!("_elastic" / String / "_pit")
+        .and_then(extract_index_id_patterns)
+        .and(warp::post())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
+#[utoipa::path(delete, tag = "Search", path = "/_pit")]
+pub(crate) fn elastic_close_point_in_time_filter(
+) -> impl Filter<Extract = (ClosePointInTimeBody,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_pit")
+        .and(warp::delete())
+        .and(json_or_empty())
+}
+
+#[utoipa::path(post, tag = "Search", path = "/{index}/_async_search")]
+pub(crate) fn elastic_submit_async_search_filter(
+) -> impl Filter<Extract = (Vec<String>, SubmitAsyncSearchQueryParams, SearchBody), Error = Rejection>
+       + Clone {
+    warp::path!("_elastic" / String / "_async_search")
+        .and_then(extract_index_id_patterns)
+        .and(warp::post())
         .and(serde_qs::warp::query(serde_qs::Config::default()))
         .and(json_or_empty())
 }
 
+#[utoipa::path(get, tag = "Search", path = "/_async_search/{id}")]
+pub(crate) fn elastic_get_async_search_filter(
+) -> impl Filter<Extract = (String, GetAsyncSearchQueryParams), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_async_search" / String)
+        .and(warp::get())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+}
+
+#[utoipa::path(get, tag = "Search", path = "/_async_search/status/{id}")]
+pub(crate) fn elastic_async_search_status_filter(
+) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_async_search" / "status" / String).and(warp::get())
+}
+
+#[utoipa::path(delete, tag = "Search", path = "/_async_search/{id}")]
+pub(crate) fn elastic_delete_async_search_filter(
+) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::path!("_elastic" / "_async_search" / String).and(warp::delete())
+}
+
 #[utoipa::path(post, tag = "Search", path = "/_msearch")]
 pub(crate) fn elastic_multi_search_filter(
 ) -> impl Filter<Extract = (Bytes, MultiSearchQueryParams), Error = Rejection> + Clone {
@@ -240,6 +474,16 @@ pub(crate) fn elastic_multi_search_filter(
         .and(warp::body::bytes())
         .and(warp::post())
         .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(api_key_id_filter())
+        .map(
+            |payload: Bytes,
+             mut multi_search_params: MultiSearchQueryParams,
+             api_key_id_opt: Option<String>| {
+                multi_search_params.api_key_id = api_key_id_opt;
+                (payload, multi_search_params)
+            },
+        )
+        .untuple_one()
 }
 
 fn merge_scroll_body_params(
@@ -0,0 +1,668 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Rewrites the parameters of the `histogram` and `date_histogram` aggregations of Elasticsearch
+//! into the format expected by tantivy.
+
+use chrono::{Offset, TimeZone};
+use hyper::StatusCode;
+use quickwit_datetime::{DateMathParser, DateMathRounding};
+use serde_json::{json, Value as JsonValue};
+use time::OffsetDateTime;
+
+use super::model::{ElasticException, ElasticsearchError};
+use super::rest_handler::{parse_aggregation_date, parse_date_time_formats};
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// The Unix epoch is a Thursday, so weeks starting on Monday are shifted by 4 days.
+const MONDAY_WEEK_OFFSET_MILLIS: i64 = 4 * MILLIS_PER_DAY;
+
+/// Hard bounds spanning more than a year necessarily contain the DST transitions of a time zone
+/// observing daylight saving time.
+const MAX_SINGLE_UTC_OFFSET_PERIOD_MILLIS: i64 = 366 * MILLIS_PER_DAY;
+
+/// Rewrites the parameters of the `histogram` and `date_histogram` aggregations into the format
+/// expected by tantivy, which only supports fixed intervals.
+///
+/// Calendar intervals of at most a week are converted into the equivalent fixed intervals, with
+/// weeks starting on Monday. Months, quarters, and years do not have a fixed duration and are
+/// rejected.
+///
+/// The `time_zone` parameter is folded into the `offset` parameter, so that buckets are aligned on
+/// the local time of the zone. A single UTC offset applies to all the buckets, so time zones
+/// observing daylight saving time are only accepted when the buckets do not depend on the UTC
+/// offset in effect. See [`named_time_zone_offset_millis`].
+///
+/// The bounds of date histograms may be expressed as dates, parsed with the `format` parameter if
+/// set, or as date math expressions: they are converted into Unix timestamps in milliseconds.
+pub(super) fn rewrite_histogram_params(
+    aggregations: &mut serde_json::Map<String, JsonValue>,
+) -> Result<(), ElasticsearchError> {
+    let date_math_parser = DateMathParser::new(OffsetDateTime::now_utc());
+    rewrite_histogram_params_inner(aggregations, &date_math_parser)
+}
+
+fn rewrite_histogram_params_inner(
+    aggregations: &mut serde_json::Map<String, JsonValue>,
+    date_math_parser: &DateMathParser,
+) -> Result<(), ElasticsearchError> {
+    for aggregation in aggregations.values_mut() {
+        let Some(aggregation_obj) = aggregation.as_object_mut() else {
+            continue;
+        };
+        if let Some(JsonValue::Object(histogram_params)) = aggregation_obj.get_mut("histogram") {
+            reconcile_histogram_bounds(histogram_params);
+        }
+        if let Some(JsonValue::Object(date_histogram_params)) =
+            aggregation_obj.get_mut("date_histogram")
+        {
+            rewrite_date_histogram(date_histogram_params, date_math_parser)?;
+        }
+        for sub_aggregations_key in ["aggs", "aggregations"] {
+            if let Some(JsonValue::Object(sub_aggregations)) =
+                aggregation_obj.get_mut(sub_aggregations_key)
+            {
+                rewrite_histogram_params_inner(sub_aggregations, date_math_parser)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Adapts the bounds of a histogram to tantivy, which rejects some combinations accepted by
+/// Elasticsearch:
+/// - `extended_bounds` only add empty buckets, which are not returned if `min_doc_count` is
+///   positive, so they are dropped in that case;
+/// - `extended_bounds` are clipped to the `hard_bounds`.
+fn reconcile_histogram_bounds(histogram_params: &mut serde_json::Map<String, JsonValue>) {
+    let min_doc_count = histogram_params
+        .get("min_doc_count")
+        .and_then(JsonValue::as_u64)
+        .unwrap_or(0);
+    if min_doc_count > 0 {
+        histogram_params.remove("extended_bounds");
+        return;
+    }
+    let get_bounds = |bounds_key: &str| -> Option<[(f64, JsonValue); 2]> {
+        let bounds = histogram_params.get(bounds_key)?;
+        let min = bounds.get("min")?;
+        let max = bounds.get("max")?;
+        Some([(min.as_f64()?, min.clone()), (max.as_f64()?, max.clone())])
+    };
+    let (Some([hard_min, hard_max]), Some([extended_min, extended_max])) =
+        (get_bounds("hard_bounds"), get_bounds("extended_bounds"))
+    else {
+        return;
+    };
+    if extended_min.0 >= hard_min.0 && extended_max.0 <= hard_max.0 {
+        return;
+    }
+    let clipped_min = if extended_min.0 < hard_min.0 {
+        hard_min
+    } else {
+        extended_min
+    };
+    let clipped_max = if extended_max.0 > hard_max.0 {
+        hard_max
+    } else {
+        extended_max
+    };
+    if clipped_min.0 > clipped_max.0 {
+        histogram_params.remove("extended_bounds");
+    } else {
+        histogram_params.insert(
+            "extended_bounds".to_string(),
+            json!({ "min": clipped_min.1, "max": clipped_max.1 }),
+        );
+    }
+}
+
+fn rewrite_date_histogram(
+    date_histogram_params: &mut serde_json::Map<String, JsonValue>,
+    date_math_parser: &DateMathParser,
+) -> Result<(), ElasticsearchError> {
+    let date_time_formats = parse_date_time_formats(date_histogram_params.remove("format"))
+        .map_err(|error| invalid_date_histogram_param(&error))?;
+
+    for bounds_key in ["extended_bounds", "hard_bounds"] {
+        let Some(JsonValue::Object(bounds)) = date_histogram_params.get_mut(bounds_key) else {
+            continue;
+        };
+        // Both bounds are inclusive.
+        for (bound_key, rounding) in [
+            ("min", DateMathRounding::Down),
+            ("max", DateMathRounding::Up),
+        ] {
+            let Some(bound) = bounds.get_mut(bound_key) else {
+                continue;
+            };
+            if !bound.is_string() {
+                continue;
+            }
+            let date_time =
+                parse_aggregation_date(bound, &date_time_formats, date_math_parser, rounding)
+                    .map_err(|error| {
+                        invalid_date_histogram_param(&format!(
+                            "invalid `{bounds_key}.{bound_key}`: {error}"
+                        ))
+                    })?;
+            *bound = JsonValue::from(date_time.into_timestamp_millis());
+        }
+    }
+    reconcile_histogram_bounds(date_histogram_params);
+
+    let mut alignment_offset_millis: i64 = 0;
+
+    if let Some(calendar_interval) = date_histogram_params
+        .get("calendar_interval")
+        .and_then(JsonValue::as_str)
+    {
+        let fixed_interval = match calendar_interval {
+            "minute" | "1m" => "1m",
+            "hour" | "1h" => "1h",
+            "day" | "1d" => "1d",
+            "week" | "1w" => {
+                alignment_offset_millis = MONDAY_WEEK_OFFSET_MILLIS;
+                "7d"
+            }
+            "month" | "1M" | "quarter" | "1q" | "year" | "1y" => {
+                return Err(invalid_date_histogram_param(&format!(
+                    "calendar interval `{calendar_interval}` is not supported: months, quarters, \
+                     and years do not have a fixed duration"
+                )));
+            }
+            _ => {
+                return Err(invalid_date_histogram_param(&format!(
+                    "unknown calendar interval `{calendar_interval}`"
+                )));
+            }
+        };
+        date_histogram_params.remove("calendar_interval");
+        date_histogram_params.insert(
+            "fixed_interval".to_string(),
+            JsonValue::String(fixed_interval.to_string()),
+        );
+    }
+    let time_zone_opt = match date_histogram_params.remove("time_zone") {
+        Some(JsonValue::String(time_zone)) => Some(time_zone),
+        Some(_) => {
+            return Err(invalid_date_histogram_param("`time_zone` must be a string"));
+        }
+        None => None,
+    };
+    if alignment_offset_millis == 0 && time_zone_opt.is_none() {
+        return Ok(());
+    }
+    let fixed_interval = date_histogram_params
+        .get("fixed_interval")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default();
+    let interval_millis = parse_interval_millis(fixed_interval)
+        .filter(|interval_millis| *interval_millis > 0)
+        .ok_or_else(|| {
+            invalid_date_histogram_param(
+                "`time_zone` requires a valid `fixed_interval` or `calendar_interval`",
+            )
+        })?;
+    let utc_offset_millis = match time_zone_opt.as_deref().map(parse_time_zone).transpose()? {
+        Some(HistogramTimeZone::UtcOffset(utc_offset_millis)) => utc_offset_millis,
+        Some(HistogramTimeZone::Named(tz)) => {
+            let hard_bounds_opt = date_histogram_params.get("hard_bounds").and_then(|bounds| {
+                let min = bounds.get("min")?.as_f64()? as i64;
+                let max = bounds.get("max")?.as_f64()? as i64;
+                Some((min, max))
+            });
+            named_time_zone_offset_millis(tz, fixed_interval, interval_millis, hard_bounds_opt)?
+        }
+        None => 0,
+    };
+    if alignment_offset_millis == 0 && utc_offset_millis == 0 {
+        return Ok(());
+    }
+    let offset_millis = match date_histogram_params.get("offset") {
+        Some(JsonValue::String(offset)) => parse_interval_millis(offset)
+            .ok_or_else(|| invalid_date_histogram_param("invalid `offset`"))?,
+        Some(_) => return Err(invalid_date_histogram_param("`offset` must be a string")),
+        None => 0,
+    };
+    // Local bucket boundaries satisfy `timestamp + utc_offset = offset (mod interval)`.
+    let aligned_offset_millis =
+        (offset_millis + alignment_offset_millis - utc_offset_millis).rem_euclid(interval_millis);
+    date_histogram_params.insert(
+        "offset".to_string(),
+        JsonValue::String(format!("{aligned_offset_millis}ms")),
+    );
+    Ok(())
+}
+
+/// Parses an interval such as `30d`, `-4h`, or `+15minutes` into milliseconds.
+fn parse_interval_millis(interval: &str) -> Option<i64> {
+    let (sign, interval) = match interval.strip_prefix('-') {
+        Some(interval) => (-1, interval),
+        None => (1, interval.strip_prefix('+').unwrap_or(interval)),
+    };
+    let unit_pos = interval.find(|ch: char| !ch.is_ascii_digit())?;
+    let (value, unit) = interval.split_at(unit_pos);
+    let unit_millis = match unit {
+        "ms" | "milliseconds" => 1,
+        "s" | "seconds" => 1_000,
+        "m" | "minutes" => 60_000,
+        "h" | "hours" => 3_600_000,
+        "d" | "days" => MILLIS_PER_DAY,
+        _ => return None,
+    };
+    let value: i64 = value.parse().ok()?;
+    value.checked_mul(unit_millis).map(|millis| sign * millis)
+}
+
+#[derive(Debug, PartialEq)]
+enum HistogramTimeZone {
+    /// UTC offset in milliseconds.
+    UtcOffset(i64),
+    Named(chrono_tz::Tz),
+}
+
+/// Parses a time zone, which is either a UTC offset such as `+01:00` or `-0530`, or a name of the
+/// TZ database such as `Europe/Paris`.
+fn parse_time_zone(time_zone: &str) -> Result<HistogramTimeZone, ElasticsearchError> {
+    let invalid_time_zone = || {
+        invalid_date_histogram_param(&format!(
+            "unknown time zone `{time_zone}`. time zone must be a UTC offset or a valid name in \
+             the TZ database"
+        ))
+    };
+    if time_zone == "Z" {
+        return Ok(HistogramTimeZone::UtcOffset(0));
+    }
+    if let Some((sign, utc_offset)) = time_zone
+        .strip_prefix('+')
+        .map(|utc_offset| (1, utc_offset))
+        .or_else(|| {
+            time_zone
+                .strip_prefix('-')
+                .map(|utc_offset| (-1, utc_offset))
+        })
+    {
+        let digits: String = utc_offset.chars().filter(|ch| *ch != ':').collect();
+        if !matches!(digits.len(), 2 | 4) || !digits.chars().all(|ch| ch.is_ascii_digit()) {
+            return Err(invalid_time_zone());
+        }
+        let hours: i64 = digits[..2].parse().map_err(|_| invalid_time_zone())?;
+        let minutes: i64 = digits[2..].parse().unwrap_or(0);
+        if hours > 18 || minutes > 59 {
+            return Err(invalid_time_zone());
+        }
+        let utc_offset_millis = sign * (hours * 3_600_000 + minutes * 60_000);
+        return Ok(HistogramTimeZone::UtcOffset(utc_offset_millis));
+    }
+    let tz: chrono_tz::Tz = time_zone.parse().map_err(|_| invalid_time_zone())?;
+    Ok(HistogramTimeZone::Named(tz))
+}
+
+/// Returns the UTC offset in milliseconds of a time zone at the given timestamp.
+fn utc_offset_millis_at(tz: chrono_tz::Tz, timestamp_millis: i64) -> i64 {
+    let Some(date_time) = chrono::DateTime::from_timestamp_millis(timestamp_millis) else {
+        return 0;
+    };
+    let utc_offset_secs = tz
+        .offset_from_utc_datetime(&date_time.naive_utc())
+        .fix()
+        .local_minus_utc();
+    utc_offset_secs as i64 * 1_000
+}
+
+/// Returns the UTC offset of a named time zone to fold into the offset of a date histogram.
+///
+/// Fixed intervals cannot follow the UTC offset changes of the time zones observing daylight
+/// saving time, so such time zones are only accepted when the buckets do not depend on the UTC
+/// offset in effect, that is when:
+/// - the interval divides the differences between the UTC offsets of the zone over the year, for
+///   instance `1h` or `30m` for the usual one hour DST shift;
+/// - or `hard_bounds` restrict the histogram to a period during which the UTC offset of the zone
+///   does not change.
+///
+/// Historical changes of the standard UTC offset of the zone are not taken into account.
+fn named_time_zone_offset_millis(
+    tz: chrono_tz::Tz,
+    fixed_interval: &str,
+    interval_millis: i64,
+    hard_bounds_opt: Option<(i64, i64)>,
+) -> Result<i64, ElasticsearchError> {
+    let reference_timestamp_millis = hard_bounds_opt
+        .map(|(min, _max)| min)
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+    let reference_utc_offset_millis = utc_offset_millis_at(tz, reference_timestamp_millis);
+
+    // DST periods last several months, so sampling the UTC offsets every month over a year
+    // catches the DST shifts.
+    let utc_offsets_are_aligned = (-6..=6)
+        .map(|month| reference_timestamp_millis + month * 30 * MILLIS_PER_DAY)
+        .all(|timestamp_millis| {
+            (utc_offset_millis_at(tz, timestamp_millis) - reference_utc_offset_millis)
+                .rem_euclid(interval_millis)
+                == 0
+        });
+    if utc_offsets_are_aligned {
+        return Ok(reference_utc_offset_millis);
+    }
+    if let Some((min, max)) = hard_bounds_opt {
+        // DST periods last more than a day, so checking the UTC offset every day is enough.
+        if max - min <= MAX_SINGLE_UTC_OFFSET_PERIOD_MILLIS
+            && (min..=max)
+                .step_by(MILLIS_PER_DAY as usize)
+                .chain([max])
+                .all(|timestamp_millis| {
+                    utc_offset_millis_at(tz, timestamp_millis) == reference_utc_offset_millis
+                })
+        {
+            return Ok(reference_utc_offset_millis);
+        }
+    }
+    Err(invalid_date_histogram_param(&format!(
+        "time zone `{tz}` observes daylight saving time, which is not supported with a \
+         `{fixed_interval}` interval because buckets would be misaligned across DST transitions. \
+         use a UTC offset such as `+01:00`, an interval dividing the DST shift such as `1h`, or \
+         `hard_bounds` within a single DST period instead"
+    )))
+}
+
+fn invalid_date_histogram_param(reason: &str) -> ElasticsearchError {
+    ElasticsearchError::new(
+        StatusCode::BAD_REQUEST,
+        format!("invalid date_histogram aggregation: {reason}"),
+        Some(ElasticException::IllegalArgument),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn rewrite(aggregations: JsonValue) -> Result<JsonValue, ElasticsearchError> {
+        let JsonValue::Object(mut aggregations) = aggregations else {
+            panic!("aggregations should be an object");
+        };
+        let date_math_parser = DateMathParser::new(datetime!(2024-07-03 12:00:00 UTC));
+        rewrite_histogram_params_inner(&mut aggregations, &date_math_parser)?;
+        Ok(JsonValue::Object(aggregations))
+    }
+
+    #[test]
+    fn test_rewrite_date_histogram_params() {
+        let aggregations = rewrite(json!({
+            "per_day": {
+                "date_histogram": {
+                    "field": "timestamp",
+                    "calendar_interval": "day",
+                    "time_zone": "Europe/Paris",
+                    "hard_bounds": { "min": 1720000000000u64, "max": 1720100000000u64 }
+                }
+            },
+            "per_host": {
+                "terms": { "field": "host" },
+                "aggs": {
+                    "per_week": {
+                        "date_histogram": {
+                            "field": "timestamp",
+                            "calendar_interval": "1w",
+                            "time_zone": "-05:30"
+                        }
+                    }
+                }
+            },
+            "per_hour": {
+                "date_histogram": {
+                    "field": "timestamp",
+                    "fixed_interval": "1h",
+                    "offset": "-15m",
+                    "time_zone": "UTC"
+                }
+            },
+            "per_half_hour": {
+                "date_histogram": {
+                    "field": "timestamp",
+                    "fixed_interval": "30m",
+                    "time_zone": "Europe/Paris"
+                }
+            },
+            "per_day_in_tokyo": {
+                "date_histogram": {
+                    "field": "timestamp",
+                    "fixed_interval": "1d",
+                    "time_zone": "Asia/Tokyo"
+                }
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            aggregations,
+            json!({
+                "per_day": {
+                    "date_histogram": {
+                        "field": "timestamp",
+                        "fixed_interval": "1d",
+                        "offset": "79200000ms",
+                        "hard_bounds": { "min": 1720000000000u64, "max": 1720100000000u64 }
+                    }
+                },
+                "per_host": {
+                    "terms": { "field": "host" },
+                    "aggs": {
+                        "per_week": {
+                            "date_histogram": {
+                                "field": "timestamp",
+                                "fixed_interval": "7d",
+                                "offset": "365400000ms"
+                            }
+                        }
+                    }
+                },
+                "per_hour": {
+                    "date_histogram": {
+                        "field": "timestamp",
+                        "fixed_interval": "1h",
+                        "offset": "-15m"
+                    }
+                },
+                "per_half_hour": {
+                    "date_histogram": {
+                        "field": "timestamp",
+                        "fixed_interval": "30m",
+                        "offset": "0ms"
+                    }
+                },
+                "per_day_in_tokyo": {
+                    "date_histogram": {
+                        "field": "timestamp",
+                        "fixed_interval": "1d",
+                        "offset": "54000000ms"
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_rewrite_date_histogram_params_rejects_unsupported_params() {
+        for (invalid_date_histogram_params, expected_error) in [
+            (
+                json!({ "field": "timestamp", "fixed_interval": "1d", "time_zone": "Mars/Olympus" }),
+                "unknown time zone `Mars/Olympus`",
+            ),
+            (
+                json!({ "field": "timestamp", "fixed_interval": "1d", "time_zone": "+1" }),
+                "unknown time zone `+1`",
+            ),
+            (
+                json!({ "field": "timestamp", "time_zone": "+01:00" }),
+                "`time_zone` requires a valid `fixed_interval` or `calendar_interval`",
+            ),
+            (
+                json!({ "field": "timestamp", "calendar_interval": "month" }),
+                "calendar interval `month` is not supported",
+            ),
+            (
+                json!({ "field": "timestamp", "calendar_interval": "1y", "time_zone": "UTC" }),
+                "calendar interval `1y` is not supported",
+            ),
+            // Days in Paris last 23 or 25 hours on DST transitions.
+            (
+                json!({ "field": "timestamp", "calendar_interval": "day", "time_zone": "Europe/Paris" }),
+                "time zone `Europe/Paris` observes daylight saving time",
+            ),
+            // The hard bounds contain the DST transition of 2024-10-27.
+            (
+                json!({
+                    "field": "timestamp",
+                    "fixed_interval": "1d",
+                    "time_zone": "Europe/Paris",
+                    "hard_bounds": { "min": 1727740800000u64, "max": 1730419200000u64 }
+                }),
+                "time zone `Europe/Paris` observes daylight saving time",
+            ),
+        ] {
+            let error = rewrite(json!({
+                "per_day": { "date_histogram": invalid_date_histogram_params }
+            }))
+            .unwrap_err();
+            assert_eq!(error.status, StatusCode::BAD_REQUEST);
+            assert!(
+                error
+                    .error
+                    .reason
+                    .as_ref()
+                    .unwrap()
+                    .contains(expected_error),
+                "{:?}",
+                error.error.reason
+            );
+        }
+    }
+
+    #[test]
+    fn test_rewrite_histogram_bounds() {
+        let aggregations = rewrite(json!({
+            "per_day": {
+                "date_histogram": {
+                    "field": "timestamp",
+                    "fixed_interval": "1d",
+                    "format": "yyyy-MM-dd",
+                    "min_doc_count": 0,
+                    "extended_bounds": { "min": "2024-07-01", "max": "2024-07-04" },
+                    "hard_bounds": { "min": "2024-07-03||/d", "max": "2024-07-03||/d" }
+                }
+            },
+            "per_hour": {
+                "date_histogram": {
+                    "field": "timestamp",
+                    "fixed_interval": "1h",
+                    "min_doc_count": 1,
+                    "extended_bounds": { "min": "1720000000000", "max": 1720100000000u64 }
+                }
+            },
+            "per_size": {
+                "histogram": {
+                    "field": "size",
+                    "interval": 10,
+                    "extended_bounds": { "min": 0, "max": 100 },
+                    "hard_bounds": { "min": 200, "max": 300 }
+                }
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            aggregations,
+            json!({
+                "per_day": {
+                    "date_histogram": {
+                        "field": "timestamp",
+                        "fixed_interval": "1d",
+                        "min_doc_count": 0,
+                        "extended_bounds": { "min": 1719964800000u64, "max": 1720051199999u64 },
+                        "hard_bounds": { "min": 1719964800000u64, "max": 1720051199999u64 }
+                    }
+                },
+                "per_hour": {
+                    "date_histogram": {
+                        "field": "timestamp",
+                        "fixed_interval": "1h",
+                        "min_doc_count": 1
+                    }
+                },
+                "per_size": {
+                    "histogram": {
+                        "field": "size",
+                        "interval": 10,
+                        "hard_bounds": { "min": 200, "max": 300 }
+                    }
+                }
+            })
+        );
+
+        let error = rewrite(json!({
+            "per_day": {
+                "date_histogram": {
+                    "field": "timestamp",
+                    "fixed_interval": "1d",
+                    "extended_bounds": { "min": "yesterday", "max": "now" }
+                }
+            }
+        }))
+        .unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_parse_time_zone() {
+        assert_eq!(
+            parse_time_zone("Z").unwrap(),
+            HistogramTimeZone::UtcOffset(0)
+        );
+        assert_eq!(
+            parse_time_zone("+01:00").unwrap(),
+            HistogramTimeZone::UtcOffset(3_600_000)
+        );
+        assert_eq!(
+            parse_time_zone("-0530").unwrap(),
+            HistogramTimeZone::UtcOffset(-19_800_000)
+        );
+        assert_eq!(
+            parse_time_zone("Europe/Paris").unwrap(),
+            HistogramTimeZone::Named(chrono_tz::Europe::Paris)
+        );
+        parse_time_zone("+25:00").unwrap_err();
+        parse_time_zone("+01:60").unwrap_err();
+    }
+
+    #[test]
+    fn test_utc_offset_millis_at() {
+        // 2024-01-15 and 2024-07-03, before and after the DST transition.
+        assert_eq!(
+            utc_offset_millis_at(chrono_tz::Europe::Paris, 1705276800000),
+            3_600_000
+        );
+        assert_eq!(
+            utc_offset_millis_at(chrono_tz::Europe::Paris, 1720000000000),
+            7_200_000
+        );
+    }
+}
@@ -0,0 +1,344 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use hyper::StatusCode;
+use itertools::Itertools;
+use quickwit_config::{parse_alias_filter, IndexAliasAction, IndexAliasConfig, IndexAliases};
+use quickwit_metastore::{ListIndexAliasesResponseExt, UpdateIndexAliasesRequestExt};
+use quickwit_proto::metastore::{
+    EntityKind, ListIndexAliasesRequest, MetastoreError, MetastoreService, MetastoreServiceClient,
+    UpdateIndexAliasesRequest,
+};
+use quickwit_query::query_ast::QueryAst;
+use serde_json::Value as JsonValue;
+
+use super::model::{AliasAction, ElasticException, ElasticsearchError, GetAliasesResponse};
+use super::search_template::internal_error;
+
+/// Updates made through other nodes become visible after at most this long.
+const INDEX_ALIASES_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Caches the index aliases stored in the metastore.
+#[derive(Clone)]
+pub struct IndexAliasStore {
+    metastore: MetastoreServiceClient,
+    cache: Arc<Mutex<Option<(Instant, Arc<IndexAliases>)>>>,
+}
+
+impl IndexAliasStore {
+    pub fn new(metastore: MetastoreServiceClient) -> Self {
+        Self {
+            metastore,
+            cache: Arc::default(),
+        }
+    }
+
+    /// Returns the index aliases, which are cached for a few seconds.
+    pub async fn load(&self) -> Result<Arc<IndexAliases>, ElasticsearchError> {
+        if let Some((loaded_at, index_aliases)) = &*self.cache.lock().unwrap() {
+            if loaded_at.elapsed() < INDEX_ALIASES_CACHE_TTL {
+                return Ok(index_aliases.clone());
+            }
+        }
+        let index_aliases = self
+            .metastore
+            .list_index_aliases(ListIndexAliasesRequest {})
+            .await
+            .and_then(|response| response.deserialize_index_aliases())
+            .map_err(|error| internal_error(format!("failed to fetch index aliases: {error}")))?;
+        let index_aliases = Arc::new(index_aliases);
+        *self.cache.lock().unwrap() = Some((Instant::now(), index_aliases.clone()));
+        Ok(index_aliases)
+    }
+
+    /// Applies the actions of an `_aliases` request. The metastore applies them atomically: if one
+    /// of them is invalid, the aliases are left untouched.
+    pub async fn update(&self, actions: Vec<AliasAction>) -> Result<(), ElasticsearchError> {
+        let index_alias_actions: Vec<IndexAliasAction> =
+            actions.into_iter().map(IndexAliasAction::from).collect();
+        let update_index_aliases_request =
+            UpdateIndexAliasesRequest::try_from_actions(&index_alias_actions)
+                .map_err(|error| internal_error(error.to_string()))?;
+        let update_result = self
+            .metastore
+            .update_index_aliases(update_index_aliases_request)
+            .await;
+        *self.cache.lock().unwrap() = None;
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(MetastoreError::NotFound(EntityKind::IndexAlias { alias })) => {
+                Err(aliases_not_found_error(&alias))
+            }
+            Err(MetastoreError::InvalidArgument { message }) => {
+                Err(illegal_argument_error(message))
+            }
+            Err(error) => Err(internal_error(format!(
+                "failed to update index aliases: {error}"
+            ))),
+        }
+    }
+}
+
+impl From<AliasAction> for IndexAliasAction {
+    fn from(alias_action: AliasAction) -> Self {
+        match alias_action {
+            AliasAction::Add(params) => IndexAliasAction::Add {
+                index_ids: params.index,
+                aliases: params.alias,
+                config: IndexAliasConfig {
+                    filter: params.filter,
+                    is_write_index: params.is_write_index,
+                },
+            },
+            AliasAction::Remove(params) => IndexAliasAction::Remove {
+                index_ids: params.index,
+                aliases: params.alias,
+            },
+        }
+    }
+}
+
+/// Returns the aliases grouped by index, optionally restricted to the alias named `alias_opt`.
+pub fn get_aliases(
+    index_aliases: &IndexAliases,
+    alias_opt: Option<&str>,
+) -> Result<GetAliasesResponse, ElasticsearchError> {
+    let mut get_aliases_response = GetAliasesResponse::new();
+
+    for (alias, alias_indexes) in index_aliases.iter() {
+        if alias_opt.is_some_and(|requested_alias| requested_alias != alias) {
+            continue;
+        }
+        for (index_id, alias_config) in alias_indexes {
+            get_aliases_response
+                .entry(index_id.clone())
+                .or_default()
+                .aliases
+                .insert(alias.clone(), alias_config.clone());
+        }
+    }
+    if let Some(alias) = alias_opt {
+        if get_aliases_response.is_empty() {
+            return Err(aliases_not_found_error(alias));
+        }
+    }
+    Ok(get_aliases_response)
+}
+
+/// Replaces the aliases among `index_id_patterns` with their indexes and returns the filter the
+/// search must be restricted to.
+///
+/// All the targeted indexes must share the same alias filter, since a search request carries a
+/// single query. Indexes targeted directly have no filter.
+pub fn resolve_search_target(
+    index_aliases: &IndexAliases,
+    index_id_patterns: Vec<String>,
+) -> Result<(Vec<String>, Option<QueryAst>), ElasticsearchError> {
+    if index_aliases.is_empty() {
+        return Ok((index_id_patterns, None));
+    }
+    let mut resolved_index_id_patterns = Vec::with_capacity(index_id_patterns.len());
+    let mut filters: Vec<Option<&JsonValue>> = Vec::new();
+
+    for index_id_pattern in index_id_patterns {
+        if let Some(alias_indexes) = index_aliases.get(&index_id_pattern) {
+            for (index_id, alias_config) in alias_indexes {
+                resolved_index_id_patterns.push(index_id.clone());
+                filters.push(alias_config.filter.as_ref());
+            }
+        } else {
+            if !index_id_pattern.starts_with('-') {
+                filters.push(None);
+            }
+            resolved_index_id_patterns.push(index_id_pattern);
+        }
+    }
+    let filter_opt = match filters.into_iter().all_equal_value() {
+        Ok(filter_opt) => filter_opt,
+        Err(None) => None,
+        Err(Some(_)) => {
+            return Err(illegal_argument_error(
+                "the targeted indexes have different alias filters: they cannot be searched in \
+                 the same request"
+                    .to_string(),
+            ));
+        }
+    };
+    let filter_ast_opt = filter_opt
+        .map(parse_alias_filter)
+        .transpose()
+        .map_err(|error| internal_error(format!("failed to parse stored alias filter: {error}")))?;
+    let resolved_index_id_patterns = resolved_index_id_patterns.into_iter().unique().collect();
+    Ok((resolved_index_id_patterns, filter_ast_opt))
+}
+
+fn aliases_not_found_error(alias: &str) -> ElasticsearchError {
+    ElasticsearchError::new(
+        StatusCode::NOT_FOUND,
+        format!("aliases [{alias}] missing"),
+        Some(ElasticException::AliasesNotFound),
+    )
+}
+
+fn illegal_argument_error(reason: String) -> ElasticsearchError {
+    ElasticsearchError::new(
+        StatusCode::BAD_REQUEST,
+        reason,
+        Some(ElasticException::IllegalArgument),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_metastore::metastore_for_test;
+    use serde_json::json;
+
+    use super::*;
+    use crate::elasticsearch_api::model::AliasActionsBody;
+
+    fn alias_actions_for_test(actions: JsonValue) -> Vec<AliasAction> {
+        let body: AliasActionsBody = serde_json::from_value(json!({ "actions": actions })).unwrap();
+        body.actions
+    }
+
+    fn index_aliases_for_test(actions: JsonValue) -> IndexAliases {
+        let index_alias_actions = alias_actions_for_test(actions)
+            .into_iter()
+            .map(IndexAliasAction::from)
+            .collect();
+        let mut index_aliases = IndexAliases::default();
+        index_aliases.apply_actions(index_alias_actions).unwrap();
+        index_aliases
+    }
+
+    #[test]
+    fn test_get_aliases() {
+        let index_aliases = index_aliases_for_test(json!([
+            {"add": {"indices": ["logs-1", "logs-2"], "alias": "logs"}},
+            {"add": {"index": "logs-2", "alias": "logs-write", "is_write_index": true}},
+        ]));
+        let get_aliases_response = get_aliases(&index_aliases, None).unwrap();
+        assert_eq!(
+            serde_json::to_value(&get_aliases_response).unwrap(),
+            json!({
+                "logs-1": {"aliases": {"logs": {}}},
+                "logs-2": {"aliases": {"logs": {}, "logs-write": {"is_write_index": true}}},
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(get_aliases(&index_aliases, Some("logs-write")).unwrap()).unwrap(),
+            json!({
+                "logs-2": {"aliases": {"logs-write": {"is_write_index": true}}},
+            })
+        );
+        let error = get_aliases(&index_aliases, Some("metrics")).unwrap_err();
+        assert_eq!(error.status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_resolve_search_target() {
+        let index_aliases = index_aliases_for_test(json!([
+            {"add": {"indices": ["logs-1", "logs-2"], "alias": "logs"}},
+            {"add": {
+                "indices": ["logs-1", "logs-2"],
+                "alias": "tenant-1",
+                "filter": {"term": {"tenant": "1"}}
+            }},
+            {"add": {
+                "index": "logs-3",
+                "alias": "tenant-1-archive",
+                "filter": {"term": {"tenant": "1"}}
+            }},
+            {"add": {"index": "logs-1", "alias": "tenant-2", "filter": {"term": {"tenant": "2"}}}},
+        ]));
+        let (index_id_patterns, filter_opt) = resolve_search_target(
+            &index_aliases,
+            vec!["logs".to_string(), "logs-2".to_string()],
+        )
+        .unwrap();
+        assert_eq!(index_id_patterns, ["logs-1", "logs-2"]);
+        assert!(filter_opt.is_none());
+
+        let (index_id_patterns, filter_opt) =
+            resolve_search_target(&index_aliases, vec!["other-*".to_string()]).unwrap();
+        assert_eq!(index_id_patterns, ["other-*"]);
+        assert!(filter_opt.is_none());
+
+        let (index_id_patterns, filter_opt) = resolve_search_target(
+            &index_aliases,
+            vec![
+                "tenant-1".to_string(),
+                "tenant-1-archive".to_string(),
+                "-logs-2".to_string(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(index_id_patterns, ["logs-1", "logs-2", "logs-3", "-logs-2"]);
+        let expected_filter_ast: QueryAst =
+            parse_alias_filter(&json!({"term": {"tenant": "1"}})).unwrap();
+        assert_eq!(filter_opt.unwrap(), expected_filter_ast);
+
+        for index_id_patterns in [
+            vec!["tenant-1".to_string(), "tenant-2".to_string()],
+            vec!["tenant-1".to_string(), "logs-3".to_string()],
+        ] {
+            let error = resolve_search_target(&index_aliases, index_id_patterns).unwrap_err();
+            assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_index_alias_store() {
+        let metastore = metastore_for_test();
+        let index_alias_store = IndexAliasStore::new(metastore.clone());
+        assert!(index_alias_store.load().await.unwrap().is_empty());
+
+        let actions =
+            alias_actions_for_test(json!([{"add": {"index": "logs-1", "alias": "logs"}}]));
+        index_alias_store.update(actions).await.unwrap();
+
+        let index_aliases = index_alias_store.load().await.unwrap();
+        assert_eq!(index_aliases.resolve_write_index("logs").unwrap(), "logs-1");
+
+        // A store sharing the same metastore sees the update.
+        let other_index_alias_store = IndexAliasStore::new(metastore);
+        assert_eq!(other_index_alias_store.load().await.unwrap(), index_aliases);
+
+        let actions = alias_actions_for_test(json!([
+            {"remove": {"index": "logs-1", "alias": "logs"}},
+            {"remove": {"index": "logs-1", "alias": "logs"}},
+        ]));
+        let error = index_alias_store.update(actions).await.unwrap_err();
+        assert_eq!(error.status, StatusCode::NOT_FOUND);
+        assert_eq!(error.error.reason.unwrap(), "aliases [logs] missing");
+
+        let actions = alias_actions_for_test(json!([
+            {"add": {"index": "logs-1", "alias": "logs", "filter": {"foo": {}}}}
+        ]));
+        let error = index_alias_store.update(actions).await.unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+
+        // Failed actions leave the aliases untouched.
+        assert_eq!(index_alias_store.load().await.unwrap(), index_aliases);
+    }
+}
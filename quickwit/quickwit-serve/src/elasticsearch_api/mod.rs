@@ -20,14 +20,19 @@
 mod bulk;
 mod bulk_v2;
 mod filter;
+mod histogram;
+mod index_alias;
 mod model;
 mod rest_handler;
+mod search_template;
 
 use std::sync::Arc;
 
 use bulk::{es_compat_bulk_handler, es_compat_index_bulk_handler};
 pub use filter::ElasticCompatibleApi;
 use hyper::StatusCode;
+pub use index_alias::IndexAliasStore;
+use quickwit_cluster::Cluster;
 use quickwit_config::NodeConfig;
 use quickwit_index_management::IndexService;
 use quickwit_ingest::IngestServiceClient;
@@ -35,12 +40,25 @@ use quickwit_proto::ingest::router::IngestRouterServiceClient;
 use quickwit_proto::metastore::MetastoreServiceClient;
 use quickwit_search::SearchService;
 pub use rest_handler::{
-    es_compat_cat_indices_handler, es_compat_cluster_info_handler, es_compat_delete_index_handler,
+    es_compat_analyze_handler, es_compat_async_search_status_handler, es_compat_cat_count_handler,
+    es_compat_cat_health_handler, es_compat_cat_indices_handler, es_compat_cat_nodes_handler,
+    es_compat_close_point_in_time_handler, es_compat_cluster_info_handler,
+    es_compat_delete_async_search_handler, es_compat_delete_index_handler,
+    es_compat_delete_index_template_handler, es_compat_delete_stored_script_handler,
+    es_compat_get_aliases_handler, es_compat_get_async_search_handler,
+    es_compat_get_index_templates_handler, es_compat_get_stored_script_handler,
+    es_compat_index_analyze_handler, es_compat_index_cat_count_handler,
     es_compat_index_cat_indices_handler, es_compat_index_count_handler,
-    es_compat_index_field_capabilities_handler, es_compat_index_multi_search_handler,
-    es_compat_index_search_handler, es_compat_index_stats_handler, es_compat_resolve_index_handler,
-    es_compat_scroll_handler, es_compat_search_handler, es_compat_stats_handler,
+    es_compat_index_field_capabilities_handler, es_compat_index_mapping_handler,
+    es_compat_index_multi_search_handler, es_compat_index_put_mapping_handler,
+    es_compat_index_search_handler, es_compat_index_search_template_handler,
+    es_compat_index_stats_handler, es_compat_index_terms_enum_handler, es_compat_mapping_handler,
+    es_compat_open_point_in_time_handler, es_compat_put_index_template_handler,
+    es_compat_put_stored_script_handler, es_compat_resolve_index_handler, es_compat_scroll_handler,
+    es_compat_search_handler, es_compat_stats_handler, es_compat_submit_async_search_handler,
+    es_compat_update_aliases_handler,
 };
+pub use search_template::StoredScriptStore;
 use serde::{Deserialize, Serialize};
 use warp::{Filter, Rejection};
 
@@ -61,30 +79,102 @@ pub fn elastic_api_handlers(
     metastore: MetastoreServiceClient,
     index_service: IndexService,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    let stored_script_store = StoredScriptStore::new(
+        index_service.storage_resolver(),
+        &node_config.default_index_root_uri,
+    );
+    let index_alias_store = IndexAliasStore::new(metastore.clone());
     es_compat_cluster_info_handler(node_config, BuildInfo::get())
         .or(es_compat_search_handler(search_service.clone()))
         .or(es_compat_bulk_handler(
             ingest_service.clone(),
             ingest_router.clone(),
+            index_alias_store.clone(),
+        ))
+        .or(es_compat_index_bulk_handler(
+            ingest_service,
+            ingest_router,
+            index_alias_store.clone(),
+        ))
+        .or(es_compat_index_search_handler(
+            search_service.clone(),
+            index_alias_store.clone(),
+        ))
+        .or(es_compat_index_search_template_handler(
+            search_service.clone(),
+            stored_script_store.clone(),
+            index_alias_store.clone(),
+        ))
+        .or(es_compat_index_count_handler(
+            search_service.clone(),
+            index_alias_store.clone(),
         ))
-        .or(es_compat_index_bulk_handler(ingest_service, ingest_router))
-        .or(es_compat_index_search_handler(search_service.clone()))
-        .or(es_compat_index_count_handler(search_service.clone()))
         .or(es_compat_scroll_handler(search_service.clone()))
-        .or(es_compat_index_multi_search_handler(search_service.clone()))
+        .or(es_compat_open_point_in_time_handler(search_service.clone()))
+        // Registered before the delete index handler, which would otherwise match `_pit` as an
+        // index ID.
+        .or(es_compat_close_point_in_time_handler(
+            search_service.clone(),
+        ))
+        .or(es_compat_submit_async_search_handler(
+            search_service.clone(),
+        ))
+        .or(es_compat_async_search_status_handler(
+            search_service.clone(),
+        ))
+        .or(es_compat_get_async_search_handler(search_service.clone()))
+        .or(es_compat_delete_async_search_handler(
+            search_service.clone(),
+        ))
+        .or(es_compat_index_multi_search_handler(
+            search_service.clone(),
+            index_alias_store.clone(),
+        ))
         .or(es_compat_index_field_capabilities_handler(
             search_service.clone(),
         ))
+        .or(es_compat_index_terms_enum_handler(search_service.clone()))
         .or(es_compat_index_stats_handler(metastore.clone()))
+        .or(es_compat_index_mapping_handler(metastore.clone()))
+        .or(es_compat_mapping_handler(metastore.clone()))
+        .or(es_compat_index_put_mapping_handler(metastore.clone()))
+        .or(es_compat_analyze_handler())
+        .or(es_compat_index_analyze_handler(metastore.clone()))
+        .or(es_compat_update_aliases_handler(
+            metastore.clone(),
+            index_alias_store.clone(),
+        ))
+        .or(es_compat_get_aliases_handler(index_alias_store.clone()))
+        .or(es_compat_put_index_template_handler(metastore.clone()))
+        .or(es_compat_get_index_templates_handler(metastore.clone()))
+        .or(es_compat_delete_index_template_handler(metastore.clone()))
         .or(es_compat_delete_index_handler(index_service))
+        .or(es_compat_put_stored_script_handler(
+            stored_script_store.clone(),
+        ))
+        .or(es_compat_get_stored_script_handler(
+            stored_script_store.clone(),
+        ))
+        .or(es_compat_delete_stored_script_handler(stored_script_store))
         .or(es_compat_stats_handler(metastore.clone()))
         .or(es_compat_index_cat_indices_handler(metastore.clone()))
         .or(es_compat_cat_indices_handler(metastore.clone()))
+        .or(es_compat_index_cat_count_handler(metastore.clone()))
+        .or(es_compat_cat_count_handler(metastore.clone()))
         .or(es_compat_resolve_index_handler(metastore.clone()))
         .recover(recover_fn)
     // Register newly created handlers here.
 }
 
+/// Setup the Elasticsearch API handlers reporting the state of the cluster.
+pub fn elastic_cluster_api_handlers(
+    cluster: Cluster,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    es_compat_cat_health_handler(cluster.clone())
+        .or(es_compat_cat_nodes_handler(cluster))
+        .recover(recover_fn)
+}
+
 /// Helper type needed by the Elasticsearch endpoints.
 /// Control how the total number of hits should be tracked.
 ///
@@ -130,22 +220,27 @@ mod tests {
     use std::sync::Arc;
 
     use assert_json_diff::assert_json_include;
+    use base64::prelude::{Engine, BASE64_STANDARD};
     use mockall::predicate;
+    use quickwit_common::uri::Uri;
     use quickwit_config::NodeConfig;
     use quickwit_index_management::IndexService;
     use quickwit_ingest::{IngestApiService, IngestServiceClient};
     use quickwit_metastore::metastore_for_test;
     use quickwit_proto::ingest::router::IngestRouterServiceClient;
     use quickwit_proto::metastore::MetastoreServiceClient;
+    use quickwit_proto::search::SearchPriority;
     use quickwit_search::MockSearchService;
     use quickwit_storage::StorageResolver;
     use serde_json::Value as JsonValue;
     use warp::Filter;
 
-    use super::elastic_api_handlers;
-    use super::model::ElasticsearchError;
+    use super::model::{AliasActionsBody, ElasticsearchError};
+    use super::{elastic_api_handlers, IndexAliasStore};
     use crate::elasticsearch_api::model::MultiSearchResponse;
-    use crate::elasticsearch_api::rest_handler::es_compat_cluster_info_handler;
+    use crate::elasticsearch_api::rest_handler::{
+        es_compat_analyze_handler, es_compat_cluster_info_handler,
+    };
     use crate::rest::recover_fn;
     use crate::BuildInfo;
 
@@ -173,8 +268,7 @@ mod tests {
             ))
             .returning(|_| Ok(Default::default()));
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let es_search_api_handler = super::elastic_api_handlers(
             config,
             Arc::new(mock_search_service),
@@ -227,8 +321,7 @@ mod tests {
             });
 
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let es_search_api_handler = super::elastic_api_handlers(
             config,
             Arc::new(mock_search_service),
@@ -269,8 +362,7 @@ mod tests {
         let mock_search_service = MockSearchService::new();
 
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let es_search_api_handler = super::elastic_api_handlers(
             config,
             Arc::new(mock_search_service),
@@ -304,8 +396,7 @@ mod tests {
         let mock_search_service = MockSearchService::new();
 
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let es_search_api_handler = elastic_api_handlers(
             config,
             Arc::new(mock_search_service),
@@ -339,8 +430,7 @@ mod tests {
         let mock_search_service = MockSearchService::new();
 
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let es_search_api_handler = super::elastic_api_handlers(
             config,
             Arc::new(mock_search_service),
@@ -373,8 +463,7 @@ mod tests {
         let mock_search_service = MockSearchService::new();
 
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let es_search_api_handler = super::elastic_api_handlers(
             config,
             Arc::new(mock_search_service),
@@ -419,8 +508,7 @@ mod tests {
                 }
             });
         let ingest_router = IngestRouterServiceClient::mocked();
-        let index_service =
-            IndexService::new(metastore_for_test(), StorageResolver::unconfigured());
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
         let es_search_api_handler = super::elastic_api_handlers(
             config,
             Arc::new(mock_search_service),
@@ -442,6 +530,114 @@ mod tests {
         assert_eq!(resp.status(), 200);
     }
 
+    #[tokio::test]
+    async fn test_search_api_sets_priority_and_api_key_id() {
+        let config = Arc::new(NodeConfig::for_test());
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(
+                |search_request: &quickwit_proto::search::SearchRequest| {
+                    search_request.priority() == SearchPriority::Batch
+                        && search_request.api_key_id.as_deref() == Some("my-key-id")
+                },
+            ))
+            .times(3)
+            .returning(|_| Ok(Default::default()));
+        let ingest_router = IngestRouterServiceClient::mocked();
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
+        let es_search_api_handler = super::elastic_api_handlers(
+            config,
+            Arc::new(mock_search_service),
+            ingest_service_client(),
+            ingest_router,
+            MetastoreServiceClient::mocked(),
+            index_service,
+        );
+        let authorization = format!("ApiKey {}", BASE64_STANDARD.encode("my-key-id:my-secret"));
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_search?priority=batch")
+            .header("Authorization", &authorization)
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let msearch_payload = r#"
+            {"index":"index-1"}
+            {"query":{"query_string":{"query":"test"}}}
+            {"index":"index-2"}
+            {"query":{"query_string":{"query":"test"}}}
+            "#;
+        let resp = warp::test::request()
+            .path("/_elastic/_msearch?priority=batch")
+            .method("POST")
+            .header("Authorization", &authorization)
+            .body(msearch_payload)
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let es_msearch_response: MultiSearchResponse = serde_json::from_slice(resp.body()).unwrap();
+        for response in es_msearch_response.responses {
+            assert_eq!(response.status, 200);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_terms_enum_api() {
+        let config = Arc::new(NodeConfig::for_test());
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_list_terms()
+            .withf(|list_terms_request| {
+                list_terms_request.index_id_patterns == vec!["index-1".to_string()]
+                    && list_terms_request.field == "service"
+                    && list_terms_request.max_hits == Some(5)
+                    && list_terms_request.start_key.as_deref() == Some(b"qui".as_slice())
+                    && list_terms_request.end_key.as_deref() == Some(b"quj".as_slice())
+            })
+            .returning(|_| {
+                let field = tantivy::schema::Field::from_field_id(0);
+                Ok(quickwit_proto::search::ListTermsResponse {
+                    num_hits: 1,
+                    terms: vec![tantivy::Term::from_field_text(field, "quickwit")
+                        .serialized_term()
+                        .to_vec()],
+                    elapsed_time_micros: 0,
+                    errors: Vec::new(),
+                })
+            });
+        let ingest_router = IngestRouterServiceClient::mocked();
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
+        let es_search_api_handler = super::elastic_api_handlers(
+            config,
+            Arc::new(mock_search_service),
+            ingest_service_client(),
+            ingest_router,
+            MetastoreServiceClient::mocked(),
+            index_service,
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_terms_enum")
+            .method("POST")
+            .json(&serde_json::json!({"field": "service", "string": "qui", "size": 5}))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json,
+            serde_json::json!({"terms": ["quickwit"], "complete": true})
+        );
+
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_terms_enum")
+            .method("POST")
+            .json(&serde_json::json!({"string": "qui"}))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+
     #[tokio::test]
     async fn test_es_compat_cluster_info_handler() {
         let build_info = BuildInfo::get();
@@ -480,4 +676,575 @@ mod tests {
             .await;
         assert_eq!(resp.status(), 200);
     }
+
+    #[tokio::test]
+    async fn test_search_template_api() {
+        let mut node_config = NodeConfig::for_test();
+        node_config.default_index_root_uri = Uri::for_test("ram:///indexes");
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(
+                |search_request: &quickwit_proto::search::SearchRequest| {
+                    search_request.index_id_patterns == vec!["index-1".to_string()]
+                        && search_request.max_hits == 3
+                        && search_request.query_ast.contains("hello")
+                },
+            ))
+            .returning(|_| Ok(Default::default()));
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
+        let es_search_api_handler = elastic_api_handlers(
+            Arc::new(node_config),
+            Arc::new(mock_search_service),
+            ingest_service_client(),
+            IngestRouterServiceClient::mocked(),
+            MetastoreServiceClient::mocked(),
+            index_service,
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/_scripts/my-template")
+            .method("PUT")
+            .json(&serde_json::json!({
+                "script": {
+                    "lang": "mustache",
+                    "source": r#"{"query": {"match": {"body": "{{text}}"}}, "size": {{size}}{{^size}}10{{/size}}}"#
+                }
+            }))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_scripts/my-template")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["_id"], "my-template");
+        assert_eq!(resp_json["script"]["lang"], "mustache");
+
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_search/template")
+            .method("POST")
+            .json(&serde_json::json!({
+                "id": "my-template",
+                "params": {"text": "hello", "size": 3}
+            }))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_scripts/my-template")
+            .method("DELETE")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_search/template")
+            .method("POST")
+            .json(&serde_json::json!({"id": "my-template"}))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 404);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["error"]["type"], "resource_not_found_exception");
+    }
+
+    #[tokio::test]
+    async fn test_search_suggest_api() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(
+                |search_request: &quickwit_proto::search::SearchRequest| {
+                    search_request.suggesters
+                        == vec![quickwit_proto::search::CompletionSuggester {
+                            name: "title-suggest".to_string(),
+                            field: "title".to_string(),
+                            prefix: "qui".to_string(),
+                            size: 3,
+                        }]
+                },
+            ))
+            .returning(|_| {
+                Ok(quickwit_proto::search::SearchResponse {
+                    suggestions: vec![quickwit_proto::search::CompletionSuggestion {
+                        name: "title-suggest".to_string(),
+                        options: vec!["quick".to_string(), "quickwit".to_string()],
+                    }],
+                    ..Default::default()
+                })
+            });
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
+        let es_search_api_handler = elastic_api_handlers(
+            Arc::new(NodeConfig::for_test()),
+            Arc::new(mock_search_service),
+            ingest_service_client(),
+            IngestRouterServiceClient::mocked(),
+            MetastoreServiceClient::mocked(),
+            index_service,
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_search")
+            .method("POST")
+            .json(&serde_json::json!({
+                "suggest": {
+                    "title-suggest": {
+                        "prefix": "qui",
+                        "completion": {"field": "title", "size": 3}
+                    }
+                }
+            }))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json["suggest"],
+            serde_json::json!({
+                "title-suggest": [{
+                    "text": "qui",
+                    "offset": 0,
+                    "length": 3,
+                    "options": [{"text": "quick"}, {"text": "quickwit"}]
+                }]
+            })
+        );
+        assert!(resp_json["hits"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_point_in_time_api() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_create_point_in_time()
+            .with(
+                predicate::eq(vec!["index-1".to_string()]),
+                predicate::eq(std::time::Duration::from_secs(60)),
+            )
+            .returning(|_, _| Ok("my-pit".to_string()));
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(
+                |search_request: &quickwit_proto::search::SearchRequest| {
+                    let point_in_time = search_request.point_in_time.as_ref().unwrap();
+                    search_request.index_id_patterns.is_empty()
+                        && point_in_time.id == "my-pit"
+                        && point_in_time.keep_alive_secs == Some(120)
+                },
+            ))
+            .returning(|_| Ok(Default::default()));
+        mock_search_service
+            .expect_delete_point_in_time()
+            .with(predicate::eq("my-pit".to_string()))
+            .returning(|_| Ok(true));
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
+        let es_search_api_handler = elastic_api_handlers(
+            Arc::new(NodeConfig::for_test()),
+            Arc::new(mock_search_service),
+            ingest_service_client(),
+            IngestRouterServiceClient::mocked(),
+            MetastoreServiceClient::mocked(),
+            index_service,
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_pit?keep_alive=1m")
+            .method("POST")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json, serde_json::json!({"id": "my-pit"}));
+
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_pit")
+            .method("POST")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_search")
+            .method("POST")
+            .json(&serde_json::json!({
+                "pit": {"id": "my-pit", "keep_alive": "2m"},
+                "sort": [{"_shard_doc": "asc"}]
+            }))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["pit_id"], "my-pit");
+
+        let resp = warp::test::request()
+            .path("/_elastic/_search")
+            .method("POST")
+            .json(&serde_json::json!({"query": {"match_all": {}}}))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 501);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_pit")
+            .method("DELETE")
+            .json(&serde_json::json!({"id": "my-pit"}))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json,
+            serde_json::json!({"succeeded": true, "num_freed": 1})
+        );
+    }
+
+    fn async_search_response_for_test(
+        async_search_id: &str,
+        is_running: bool,
+    ) -> quickwit_search::AsyncSearchResponse {
+        quickwit_search::AsyncSearchResponse {
+            id: async_search_id.to_string(),
+            is_running,
+            is_partial: is_running,
+            start_time_millis: 1_000,
+            expiration_time_millis: 601_000,
+            completion_time_millis: (!is_running).then_some(2_000),
+            search_request: quickwit_proto::search::SearchRequest {
+                index_id_patterns: vec!["index-1".to_string()],
+                ..Default::default()
+            },
+            num_splits: 4,
+            num_searched_splits: if is_running { 1 } else { 4 },
+            response: quickwit_proto::search::SearchResponse {
+                num_hits: 3,
+                ..Default::default()
+            },
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_search_api() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_submit_async_search()
+            .with(
+                predicate::function(|search_request: &quickwit_proto::search::SearchRequest| {
+                    search_request.index_id_patterns == vec!["index-1".to_string()]
+                }),
+                predicate::always(),
+                predicate::eq(std::time::Duration::from_secs(600)),
+            )
+            .returning(|_, wait_for_completion_timeout, _| {
+                if wait_for_completion_timeout.is_zero() {
+                    Ok(async_search_response_for_test("my-async-search", true))
+                } else {
+                    Ok(async_search_response_for_test(
+                        "completed-async-search",
+                        false,
+                    ))
+                }
+            });
+        mock_search_service
+            .expect_get_async_search()
+            .with(
+                predicate::eq("my-async-search".to_string()),
+                predicate::always(),
+            )
+            .returning(|_, _| Ok(async_search_response_for_test("my-async-search", false)));
+        mock_search_service
+            .expect_get_async_search()
+            .with(predicate::eq("unknown".to_string()), predicate::always())
+            .returning(|async_search_id, _| {
+                Err(quickwit_search::SearchError::AsyncSearchNotFound(
+                    async_search_id,
+                ))
+            });
+        mock_search_service
+            .expect_delete_async_search()
+            .with(predicate::in_iter([
+                "my-async-search".to_string(),
+                "completed-async-search".to_string(),
+            ]))
+            .returning(|_| Ok(true));
+        mock_search_service
+            .expect_delete_async_search()
+            .with(predicate::eq("unknown".to_string()))
+            .returning(|_| Ok(false));
+        let index_service = IndexService::new(metastore_for_test(), StorageResolver::for_test());
+        let es_search_api_handler = elastic_api_handlers(
+            Arc::new(NodeConfig::for_test()),
+            Arc::new(mock_search_service),
+            ingest_service_client(),
+            IngestRouterServiceClient::mocked(),
+            MetastoreServiceClient::mocked(),
+            index_service,
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_async_search?wait_for_completion_timeout=0s&keep_alive=10m")
+            .method("POST")
+            .json(&serde_json::json!({"query": {"match_all": {}}}))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["id"], "my-async-search");
+        assert_eq!(resp_json["is_running"], true);
+        assert_eq!(resp_json["is_partial"], true);
+        assert_eq!(
+            resp_json["_shards"],
+            serde_json::json!({"total": 4, "successful": 1, "skipped": 0, "failed": 0})
+        );
+        assert_eq!(resp_json["response"]["hits"]["total"]["value"], 3);
+
+        // The search completed within the timeout and is not kept.
+        let resp = warp::test::request()
+            .path("/_elastic/index-1/_async_search?keep_alive=10m")
+            .method("POST")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert!(resp_json.get("id").is_none());
+        assert_eq!(resp_json["is_running"], false);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_async_search/my-async-search?wait_for_completion_timeout=1s")
+            .method("GET")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["is_running"], false);
+        assert_eq!(resp_json["completion_time_in_millis"], 2_000);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_async_search/status/my-async-search")
+            .method("GET")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["completion_status"], 200);
+        assert!(resp_json.get("response").is_none());
+
+        let resp = warp::test::request()
+            .path("/_elastic/_async_search/unknown")
+            .method("GET")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 404);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_async_search/my-async-search")
+            .method("DELETE")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json, serde_json::json!({"acknowledged": true}));
+
+        let resp = warp::test::request()
+            .path("/_elastic/_async_search/unknown")
+            .method("DELETE")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_api() {
+        let es_analyze_api_handler = es_compat_analyze_handler();
+
+        let resp = warp::test::request()
+            .path("/_elastic/_analyze")
+            .method("POST")
+            .json(&serde_json::json!({
+                "analyzer": "standard",
+                "text": ["Hello World", "Quickwit"],
+            }))
+            .reply(&es_analyze_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let tokens = resp_json["tokens"].as_array().unwrap();
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[1]["token"], "world");
+        assert_eq!(tokens[1]["start_offset"], 6);
+        assert_eq!(tokens[1]["end_offset"], 11);
+        assert_eq!(tokens[1]["position"], 1);
+        assert_eq!(tokens[2]["position"], 102);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_analyze")
+            .method("POST")
+            .json(&serde_json::json!({
+                "tokenizer": {"type": "ngram", "min_gram": 2, "max_gram": 2},
+                "text": "abc",
+            }))
+            .reply(&es_analyze_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["tokens"][0]["token"], "ab");
+        assert_eq!(resp_json["tokens"][1]["token"], "bc");
+
+        let resp = warp::test::request()
+            .path("/_elastic/_analyze")
+            .method("POST")
+            .json(&serde_json::json!({"tokenizer": "unknown", "text": "abc"}))
+            .reply(&es_analyze_api_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_analyze")
+            .method("POST")
+            .json(&serde_json::json!({"field": "body", "text": "abc"}))
+            .reply(&es_analyze_api_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_index_search_with_filtered_alias() {
+        let metastore = metastore_for_test();
+        let index_alias_store = IndexAliasStore::new(metastore.clone());
+        let alias_actions_body: AliasActionsBody = serde_json::from_value(serde_json::json!({
+            "actions": [{"add": {
+                "indices": ["logs-1", "logs-2"],
+                "alias": "tenant-1",
+                "filter": {"term": {"tenant": "1"}}
+            }}]
+        }))
+        .unwrap();
+        index_alias_store
+            .update(alias_actions_body.actions)
+            .await
+            .unwrap();
+
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .with(predicate::function(
+                |search_request: &quickwit_proto::search::SearchRequest| {
+                    let query_ast: JsonValue =
+                        serde_json::from_str(&search_request.query_ast).unwrap();
+                    search_request.index_id_patterns == ["logs-1", "logs-2"]
+                        && query_ast["type"] == "bool"
+                        && query_ast["filter"][0]["type"] == "term"
+                        && query_ast["filter"][0]["field"] == "tenant"
+                },
+            ))
+            .returning(|_| Ok(Default::default()));
+        let index_service = IndexService::new(metastore.clone(), StorageResolver::for_test());
+        let es_search_api_handler = elastic_api_handlers(
+            Arc::new(NodeConfig::for_test()),
+            Arc::new(mock_search_service),
+            ingest_service_client(),
+            IngestRouterServiceClient::mocked(),
+            metastore,
+            index_service,
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/tenant-1/_search")
+            .method("POST")
+            .json(&serde_json::json!({"query": {"match_all": {}}}))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_alias/tenant-1")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(
+            resp_json,
+            serde_json::json!({
+                "logs-1": {"aliases": {"tenant-1": {"filter": {"term": {"tenant": "1"}}}}},
+                "logs-2": {"aliases": {"tenant-1": {"filter": {"term": {"tenant": "1"}}}}},
+            })
+        );
+
+        let resp = warp::test::request()
+            .path("/_elastic/tenant-1,logs-3/_search")
+            .method("POST")
+            .json(&serde_json::json!({"query": {"match_all": {}}}))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_index_template_api() {
+        let metastore = metastore_for_test();
+        let index_service = IndexService::new(metastore.clone(), StorageResolver::for_test());
+        let es_search_api_handler = elastic_api_handlers(
+            Arc::new(NodeConfig::for_test()),
+            Arc::new(MockSearchService::new()),
+            ingest_service_client(),
+            IngestRouterServiceClient::mocked(),
+            metastore,
+            index_service,
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/_index_template/logs")
+            .method("PUT")
+            .json(&serde_json::json!({
+                "index_patterns": ["logs-*"],
+                "template": {
+                    "mappings": {"properties": {"@timestamp": {"type": "date"}}},
+                    "settings": {"index": {"refresh_interval": "10s"}}
+                }
+            }))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_index_template/logs?create=true")
+            .method("PUT")
+            .json(&serde_json::json!({"index_patterns": ["logs-*"]}))
+            .reply(&es_search_api_handler)
+            .await;
+        assert_ne!(resp.status(), 200);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_index_template/logs")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        assert_json_include!(
+            actual: resp_json,
+            expected: serde_json::json!({
+                "index_templates": [{
+                    "name": "logs",
+                    "index_template": {
+                        "index_patterns": ["logs-*"],
+                        "template": {
+                            "mappings": {"properties": {"@timestamp": {"type": "date"}}},
+                            "settings": {"index": {"refresh_interval": "10s"}}
+                        }
+                    }
+                }]
+            })
+        );
+        let resp = warp::test::request()
+            .path("/_elastic/_index_template/logs")
+            .method("DELETE")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = warp::test::request()
+            .path("/_elastic/_index_template/logs")
+            .reply(&es_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 404);
+    }
 }
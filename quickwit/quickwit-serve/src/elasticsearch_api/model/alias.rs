@@ -0,0 +1,138 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use quickwit_config::IndexAliasConfig;
+use quickwit_proto::types::IndexId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use serde_with::{serde_as, OneOrMany, PreferMany};
+
+/// Body of the `_aliases` API:
+///
+/// {
+///   "actions": [
+///     {"remove": {"index": "logs-1", "alias": "logs"}},
+///     {"add": {"index": "logs-2", "alias": "logs", "is_write_index": true}}
+///   ]
+/// }
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AliasActionsBody {
+    pub actions: Vec<AliasAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AliasAction {
+    Add(AliasActionParams),
+    Remove(AliasActionParams),
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AliasActionParams {
+    #[serde(alias = "indices")]
+    #[serde_as(deserialize_as = "OneOrMany<_, PreferMany>")]
+    pub index: Vec<IndexId>,
+    #[serde(alias = "aliases")]
+    #[serde_as(deserialize_as = "OneOrMany<_, PreferMany>")]
+    pub alias: Vec<String>,
+    /// Query, in the Elasticsearch query DSL, applied to the searches targeting the alias.
+    #[serde(default)]
+    pub filter: Option<JsonValue>,
+    #[serde(default)]
+    pub is_write_index: Option<bool>,
+}
+
+/// Returns JSON in the format:
+///
+/// {
+///   "logs-2": {
+///     "aliases": {
+///       "logs": {"is_write_index": true}
+///     }
+///   }
+/// }
+pub type GetAliasesResponse = BTreeMap<IndexId, IndexAliasesEntry>;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexAliasesEntry {
+    pub aliases: BTreeMap<String, IndexAliasConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AliasActionsResponse {
+    pub acknowledged: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_alias_actions_body_deserialize() {
+        let body: AliasActionsBody = serde_json::from_value(json!({
+            "actions": [
+                {"add": {"indices": ["logs-1", "logs-2"], "alias": "logs"}},
+                {"add": {
+                    "index": "logs-2",
+                    "aliases": ["tenant-1"],
+                    "filter": {"term": {"tenant": "1"}},
+                    "is_write_index": true
+                }},
+                {"remove": {"index": "logs-1", "alias": "logs"}}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            body.actions,
+            vec![
+                AliasAction::Add(AliasActionParams {
+                    index: vec!["logs-1".to_string(), "logs-2".to_string()],
+                    alias: vec!["logs".to_string()],
+                    filter: None,
+                    is_write_index: None,
+                }),
+                AliasAction::Add(AliasActionParams {
+                    index: vec!["logs-2".to_string()],
+                    alias: vec!["tenant-1".to_string()],
+                    filter: Some(json!({"term": {"tenant": "1"}})),
+                    is_write_index: Some(true),
+                }),
+                AliasAction::Remove(AliasActionParams {
+                    index: vec!["logs-1".to_string()],
+                    alias: vec!["logs".to_string()],
+                    filter: None,
+                    is_write_index: None,
+                }),
+            ]
+        );
+
+        let error = serde_json::from_value::<AliasActionsBody>(json!({
+            "actions": [{"add": {"index": "logs-1", "alias": "logs", "routing": "1"}}]
+        }))
+        .unwrap_err();
+        assert!(error.to_string().contains("unknown field `routing`"));
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use quickwit_doc_mapper::TokenizerConfig;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, OneOrMany, PreferMany};
+use tantivy::tokenizer::TextAnalyzer;
+
+/// Elasticsearch adds this many positions between the values of a multi-valued text.
+const POSITION_INCREMENT_GAP: usize = 100;
+
+#[serde_as]
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnalyzeRequestBody {
+    /// Name of a tokenizer registered in the tokenizer manager.
+    #[serde(default)]
+    pub analyzer: Option<String>,
+    /// Name of a registered tokenizer or inline tokenizer configuration.
+    #[serde(default)]
+    pub tokenizer: Option<AnalyzeTokenizer>,
+    /// Field whose tokenizer should be used. Requires an index.
+    #[serde(default)]
+    pub field: Option<String>,
+    #[serde_as(deserialize_as = "OneOrMany<_, PreferMany>")]
+    pub text: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum AnalyzeTokenizer {
+    Name(String),
+    Config(TokenizerConfig),
+}
+
+/// Returns JSON in the format:
+///
+/// {
+///   "tokens": [
+///     {
+///       "token": "hello",
+///       "start_offset": 0,
+///       "end_offset": 5,
+///       "type": "word",
+///       "position": 0
+///     }
+///   ]
+/// }
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct AnalyzeResponse {
+    pub tokens: Vec<AnalyzeToken>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct AnalyzeToken {
+    pub token: String,
+    pub start_offset: usize,
+    pub end_offset: usize,
+    #[serde(rename = "type")]
+    pub token_type: String,
+    pub position: usize,
+    #[serde(rename = "positionLength")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position_length: Option<usize>,
+}
+
+/// Runs the text analyzer over each text. Like Elasticsearch, the offsets and positions of
+/// the tokens of a text continue from those of the previous texts.
+pub fn analyze_texts_for_es_api(
+    text_analyzer: &mut TextAnalyzer,
+    texts: &[String],
+) -> AnalyzeResponse {
+    let mut tokens = Vec::new();
+    let mut offset_base = 0;
+    let mut position_base = 0;
+
+    for text in texts {
+        let mut next_position_base = position_base;
+        let mut token_stream = text_analyzer.token_stream(text);
+        token_stream.process(&mut |token| {
+            let position = position_base + token.position;
+            tokens.push(AnalyzeToken {
+                token: token.text.clone(),
+                start_offset: offset_base + token.offset_from,
+                end_offset: offset_base + token.offset_to,
+                token_type: "word".to_string(),
+                position,
+                position_length: (token.position_length > 1).then_some(token.position_length),
+            });
+            next_position_base = position + 1 + POSITION_INCREMENT_GAP;
+        });
+        offset_base += text.len() + 1;
+        position_base = next_position_base;
+    }
+    AnalyzeResponse { tokens }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_query::create_default_quickwit_tokenizer_manager;
+
+    use super::*;
+
+    #[test]
+    fn test_analyze_request_body_deserialization() {
+        let analyze_request: AnalyzeRequestBody =
+            serde_json::from_str(r#"{"tokenizer": "raw", "text": "Hello"}"#).unwrap();
+        assert!(matches!(
+            analyze_request.tokenizer,
+            Some(AnalyzeTokenizer::Name(ref name)) if name == "raw"
+        ));
+        assert_eq!(analyze_request.text, ["Hello"]);
+
+        let analyze_request: AnalyzeRequestBody = serde_json::from_str(
+            r#"{"tokenizer": {"type": "ngram", "min_gram": 2, "max_gram": 2}, "text": ["a", "b"]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            analyze_request.tokenizer,
+            Some(AnalyzeTokenizer::Config(_))
+        ));
+        assert_eq!(analyze_request.text, ["a", "b"]);
+
+        serde_json::from_str::<AnalyzeRequestBody>(r#"{"text": "a", "explain": true}"#)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_analyze_texts_for_es_api() {
+        let mut text_analyzer = create_default_quickwit_tokenizer_manager()
+            .get_tokenizer("default")
+            .unwrap();
+        let texts = vec!["Hello World".to_string(), "Quickwit".to_string()];
+        let analyze_response = analyze_texts_for_es_api(&mut text_analyzer, &texts);
+        let expected_tokens = vec![
+            AnalyzeToken {
+                token: "hello".to_string(),
+                start_offset: 0,
+                end_offset: 5,
+                token_type: "word".to_string(),
+                position: 0,
+                position_length: None,
+            },
+            AnalyzeToken {
+                token: "world".to_string(),
+                start_offset: 6,
+                end_offset: 11,
+                token_type: "word".to_string(),
+                position: 1,
+                position_length: None,
+            },
+            AnalyzeToken {
+                token: "quickwit".to_string(),
+                start_offset: 12,
+                end_offset: 20,
+                token_type: "word".to_string(),
+                position: 102,
+                position_length: None,
+            },
+        ];
+        assert_eq!(analyze_response.tokens, expected_tokens);
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use elasticsearch_dsl::search::ErrorCause;
+use quickwit_query::BooleanOperand;
+use serde::{Deserialize, Serialize};
+
+use super::{ElasticsearchSuggestResponse, SearchQueryParams};
+use crate::elasticsearch_api::TrackTotalHits;
+use crate::simple_list::{from_simple_list, to_simple_list};
+
+/// Query parameters of the submit async search API.
+#[serde_with::skip_serializing_none]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SubmitAsyncSearchQueryParams {
+    #[serde(serialize_with = "to_simple_list")]
+    #[serde(deserialize_with = "from_simple_list")]
+    #[serde(default)]
+    pub _source_excludes: Option<Vec<String>>,
+    #[serde(serialize_with = "to_simple_list")]
+    #[serde(deserialize_with = "from_simple_list")]
+    #[serde(default)]
+    pub _source_includes: Option<Vec<String>>,
+    #[serde(default)]
+    pub default_operator: Option<BooleanOperand>,
+    #[serde(default)]
+    pub from: Option<u64>,
+    #[serde(default)]
+    pub keep_alive: Option<String>,
+    #[serde(default)]
+    pub keep_on_completion: Option<bool>,
+    #[serde(default)]
+    pub q: Option<String>,
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(serialize_with = "to_simple_list")]
+    #[serde(deserialize_with = "from_simple_list")]
+    #[serde(default)]
+    pub sort: Option<Vec<String>>,
+    #[serde(default)]
+    pub track_total_hits: Option<TrackTotalHits>,
+    #[serde(default)]
+    pub wait_for_completion_timeout: Option<String>,
+}
+
+impl From<SubmitAsyncSearchQueryParams> for SearchQueryParams {
+    fn from(value: SubmitAsyncSearchQueryParams) -> Self {
+        SearchQueryParams {
+            _source_excludes: value._source_excludes,
+            _source_includes: value._source_includes,
+            default_operator: value.default_operator,
+            from: value.from,
+            q: value.q,
+            size: value.size,
+            sort: value.sort,
+            track_total_hits: value.track_total_hits,
+            ..Default::default()
+        }
+    }
+}
+
+/// Query parameters of the get async search API.
+#[derive(Default, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GetAsyncSearchQueryParams {
+    #[serde(default)]
+    pub wait_for_completion_timeout: Option<String>,
+}
+
+/// Progress of an async search, in terms of splits.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AsyncSearchShards {
+    pub total: u64,
+    pub successful: u64,
+    pub skipped: u64,
+    pub failed: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AsyncSearchResponse {
+    /// Not set when the search completed within `wait_for_completion_timeout` and
+    /// `keep_on_completion` is false, as the async search is then discarded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub is_partial: bool,
+    pub is_running: bool,
+    pub start_time_in_millis: i64,
+    pub expiration_time_in_millis: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_time_in_millis: Option<i64>,
+    #[serde(rename = "_shards")]
+    pub shards: AsyncSearchShards,
+    pub response: ElasticsearchSuggestResponse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorCause>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AsyncSearchStatusResponse {
+    pub id: String,
+    pub is_partial: bool,
+    pub is_running: bool,
+    pub start_time_in_millis: i64,
+    pub expiration_time_in_millis: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_time_in_millis: Option<i64>,
+    #[serde(rename = "_shards")]
+    pub shards: AsyncSearchShards,
+    /// HTTP status code of the search, once completed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_status: Option<u16>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteAsyncSearchResponse {
+    pub acknowledged: bool,
+}
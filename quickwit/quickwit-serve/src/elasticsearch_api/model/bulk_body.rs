@@ -25,20 +25,23 @@ use serde::Deserialize;
 pub enum BulkAction {
     Create(BulkActionMeta),
     Index(BulkActionMeta),
+    Delete(BulkActionMeta),
+    Update(BulkActionMeta),
 }
 
 impl BulkAction {
-    pub fn into_index_id(self) -> Option<IndexId> {
-        match self {
-            BulkAction::Index(meta) => meta.index_id,
-            BulkAction::Create(meta) => meta.index_id,
-        }
+    /// Returns whether the action line is followed by a source line. Only `delete` actions
+    /// stand on their own.
+    pub fn has_source(&self) -> bool {
+        !matches!(self, BulkAction::Delete(_))
     }
 
     pub fn into_meta(self) -> BulkActionMeta {
         match self {
             BulkAction::Create(meta) => meta,
             BulkAction::Index(meta) => meta,
+            BulkAction::Delete(meta) => meta,
+            BulkAction::Update(meta) => meta,
         }
     }
 }
@@ -113,6 +116,31 @@ mod tests {
                     "_id": "2"
                 }
             }"#;
+            let bulk_action = serde_json::from_str::<BulkAction>(bulk_action_json).unwrap();
+            assert!(!bulk_action.has_source());
+            assert_eq!(
+                bulk_action,
+                BulkAction::Delete(BulkActionMeta {
+                    index_id: Some("test".to_string()),
+                    es_doc_id: Some("2".to_string()),
+                })
+            );
+        }
+        {
+            let bulk_action_json = r#"{
+                "update": {
+                    "_id": "2"
+                }
+            }"#;
+            let bulk_action = serde_json::from_str::<BulkAction>(bulk_action_json).unwrap();
+            assert!(bulk_action.has_source());
+        }
+        {
+            let bulk_action_json = r#"{
+                "upsert": {
+                    "_id": "2"
+                }
+            }"#;
             serde_json::from_str::<BulkAction>(bulk_action_json).unwrap_err();
         }
     }
@@ -0,0 +1,247 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use hyper::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use super::ElasticsearchError;
+use crate::simple_list::{from_simple_list, to_simple_list};
+
+/// Output format of the `_cat` APIs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatFormat {
+    /// Aligned text columns, the default format of Elasticsearch.
+    Text,
+    /// Array of JSON objects.
+    Json,
+}
+
+impl CatFormat {
+    pub fn from_query_param(format_opt: Option<&str>) -> Result<Self, ElasticsearchError> {
+        match format_opt.map(str::to_lowercase).as_deref() {
+            None | Some("txt") | Some("text") => Ok(CatFormat::Text),
+            Some("json") => Ok(CatFormat::Json),
+            Some(format) => Err(ElasticsearchError::new(
+                StatusCode::BAD_REQUEST,
+                format!("Format {format:?} is not supported. Supported formats are txt and json."),
+                None,
+            )),
+        }
+    }
+}
+
+/// Query parameters of the `_cat` APIs without parameters of their own.
+#[serde_with::skip_serializing_none]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CatQueryParams {
+    /// `txt` (default) or `json`.
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Comma-separated list of column names to display.
+    #[serde(serialize_with = "to_simple_list")]
+    #[serde(deserialize_with = "from_simple_list")]
+    #[serde(default)]
+    pub h: Option<Vec<String>>,
+    /// If true, the text output includes column headings. Defaults to false.
+    #[serde(default)]
+    pub v: Option<bool>,
+}
+
+/// Table returned by the `_cat` APIs.
+#[derive(Debug)]
+pub struct CatResponse {
+    pub format: CatFormat,
+    with_headers: bool,
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl CatResponse {
+    /// Builds the table from rows holding a value for each of the `columns`. The columns
+    /// requested with the `h` parameter, if any, are kept in the requested order.
+    pub fn new(
+        format: CatFormat,
+        with_headers: bool,
+        columns: &[&str],
+        rows: Vec<Vec<String>>,
+        selected_columns_opt: Option<&[String]>,
+    ) -> Result<Self, ElasticsearchError> {
+        let Some(selected_columns) = selected_columns_opt else {
+            return Ok(CatResponse {
+                format,
+                with_headers,
+                columns: columns.iter().map(|column| column.to_string()).collect(),
+                rows,
+            });
+        };
+        let column_ords: Vec<usize> = selected_columns
+            .iter()
+            .map(|selected_column| {
+                columns
+                    .iter()
+                    .position(|column| column == selected_column)
+                    .ok_or_else(|| {
+                        ElasticsearchError::new(
+                            StatusCode::BAD_REQUEST,
+                            format!("Unknown column {selected_column:?}."),
+                            None,
+                        )
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                column_ords
+                    .iter()
+                    .map(|&column_ord| row[column_ord].clone())
+                    .collect()
+            })
+            .collect();
+        Ok(CatResponse {
+            format,
+            with_headers,
+            columns: selected_columns.to_vec(),
+            rows,
+        })
+    }
+
+    /// Returns the rows as JSON objects keyed by column name.
+    pub fn to_json(&self) -> JsonValue {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object: JsonMap<String, JsonValue> = self
+                    .columns
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().cloned().map(JsonValue::String))
+                    .collect();
+                JsonValue::Object(object)
+            })
+            .collect();
+        JsonValue::Array(rows)
+    }
+
+    /// Returns the rows as lines of space-separated columns, padded to the width of the widest
+    /// value of each column.
+    pub fn to_text(&self) -> String {
+        let mut column_widths: Vec<usize> = if self.with_headers {
+            self.columns
+                .iter()
+                .map(|column| column.chars().count())
+                .collect()
+        } else {
+            vec![0; self.columns.len()]
+        };
+        for row in &self.rows {
+            for (column_width, value) in column_widths.iter_mut().zip(row) {
+                *column_width = (*column_width).max(value.chars().count());
+            }
+        }
+        let mut text = String::new();
+        let headers_opt = self.with_headers.then_some(&self.columns);
+
+        for values in headers_opt.into_iter().chain(&self.rows) {
+            let mut line = String::new();
+            for (value, column_width) in values.iter().zip(&column_widths) {
+                line.push_str(value);
+                let padding = column_width - value.chars().count() + 1;
+                line.extend(std::iter::repeat(' ').take(padding));
+            }
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn cat_response(format: CatFormat, with_headers: bool, h: Option<&[String]>) -> CatResponse {
+        CatResponse::new(
+            format,
+            with_headers,
+            &["health", "index", "docs.count"],
+            vec![
+                vec!["green".to_string(), "logs".to_string(), "1000".to_string()],
+                vec![
+                    "green".to_string(),
+                    "gharchive".to_string(),
+                    "5".to_string(),
+                ],
+            ],
+            h,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cat_format_from_query_param() {
+        assert_eq!(CatFormat::from_query_param(None).unwrap(), CatFormat::Text);
+        assert_eq!(
+            CatFormat::from_query_param(Some("txt")).unwrap(),
+            CatFormat::Text
+        );
+        assert_eq!(
+            CatFormat::from_query_param(Some("JSON")).unwrap(),
+            CatFormat::Json
+        );
+        let error = CatFormat::from_query_param(Some("yaml")).unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_cat_response_to_text() {
+        assert_eq!(
+            cat_response(CatFormat::Text, false, None).to_text(),
+            "green logs      1000\ngreen gharchive 5\n"
+        );
+        assert_eq!(
+            cat_response(CatFormat::Text, true, None).to_text(),
+            "health index     docs.count\ngreen  logs      1000\ngreen  gharchive 5\n"
+        );
+    }
+
+    #[test]
+    fn test_cat_response_selected_columns() {
+        let h = vec!["docs.count".to_string(), "index".to_string()];
+        let response = cat_response(CatFormat::Json, false, Some(&h));
+        assert_eq!(
+            response.to_json(),
+            json!([
+                {"docs.count": "1000", "index": "logs"},
+                {"docs.count": "5", "index": "gharchive"},
+            ])
+        );
+        assert_eq!(response.to_text(), "1000 logs\n5    gharchive\n");
+
+        let h = vec!["unknown".to_string()];
+        let error =
+            CatResponse::new(CatFormat::Json, false, &["index"], Vec::new(), Some(&h)).unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+}
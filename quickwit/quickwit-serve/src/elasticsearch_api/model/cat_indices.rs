@@ -17,22 +17,21 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::HashSet;
 use std::ops::AddAssign;
 
 use hyper::StatusCode;
 use quickwit_metastore::{IndexMetadata, SplitMetadata};
 use serde::{Deserialize, Serialize, Serializer};
 
-use super::ElasticsearchError;
+use super::{CatFormat, ElasticsearchError};
 use crate::simple_list::{from_simple_list, to_simple_list};
 
 #[serde_with::skip_serializing_none]
 #[derive(Default, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct CatIndexQueryParams {
+    /// `txt` (default) or `json`.
     #[serde(default)]
-    /// Only JSON supported for now.
     pub format: Option<String>,
     /// Comma-separated list of column names to display.
     #[serde(serialize_with = "to_simple_list")]
@@ -52,31 +51,13 @@ pub struct CatIndexQueryParams {
     #[serde(deserialize_with = "from_simple_list")]
     #[serde(default)]
     pub s: Option<Vec<String>>,
-    /// If true, the response includes column headings. Defaults to false.
-    /// Unsupported for now.
+    /// If true, the text output includes column headings. Defaults to false.
     #[serde(default)]
     pub v: Option<bool>,
 }
 impl CatIndexQueryParams {
-    pub fn validate(&self) -> Result<(), ElasticsearchError> {
-        if let Some(format) = &self.format {
-            if format.to_lowercase() != "json" {
-                return Err(ElasticsearchError::new(
-                    StatusCode::BAD_REQUEST,
-                    format!(
-                        "Format {:?} is not supported. Only format=json is supported.",
-                        format
-                    ),
-                    None,
-                ));
-            }
-        } else {
-            return Err(ElasticsearchError::new(
-                StatusCode::BAD_REQUEST,
-                "Only format=json is supported.".to_string(),
-                None,
-            ));
-        }
+    pub fn validate(&self) -> Result<CatFormat, ElasticsearchError> {
+        let format = CatFormat::from_query_param(self.format.as_deref())?;
         let unsupported_parameter_error = |field: &str| {
             ElasticsearchError::new(
                 StatusCode::BAD_REQUEST,
@@ -87,13 +68,10 @@ impl CatIndexQueryParams {
         if self.bytes.is_some() {
             return Err(unsupported_parameter_error("bytes"));
         }
-        if self.v.is_some() {
-            return Err(unsupported_parameter_error("v"));
-        }
         if self.s.is_some() {
             return Err(unsupported_parameter_error("s"));
         }
-        Ok(())
+        Ok(format)
     }
 }
 
@@ -118,21 +96,33 @@ pub struct ElasticsearchCatIndexResponse {
 }
 
 impl ElasticsearchCatIndexResponse {
-    pub fn serialize_filtered(
-        &self,
-        fields: &Option<Vec<String>>,
-    ) -> serde_json::Result<serde_json::Value> {
-        let mut value = serde_json::to_value(self)?;
+    /// Columns of the `_cat/indices` API, in the order of the fields of the response.
+    pub const COLUMNS: [&'static str; 11] = [
+        "health",
+        "status",
+        "index",
+        "uuid",
+        "pri",
+        "rep",
+        "docs.count",
+        "docs.deleted",
+        "store.size",
+        "pri.store.size",
+        "dataset.size",
+    ];
 
-        if let Some(fields) = fields {
-            let fields: HashSet<String> = fields.iter().cloned().collect();
-            // If fields are specified, retain only those fields
-            if let serde_json::Value::Object(ref mut map) = value {
-                map.retain(|key, _| fields.contains(key));
-            }
-        }
-
-        Ok(value)
+    /// Returns the values of the [`Self::COLUMNS`], formatted as strings.
+    pub fn to_row(&self) -> serde_json::Result<Vec<String>> {
+        let value = serde_json::to_value(self)?;
+        let row = Self::COLUMNS
+            .iter()
+            .map(|column| match value.get(column) {
+                Some(serde_json::Value::String(text)) => text.clone(),
+                Some(other_value) => other_value.to_string(),
+                None => String::new(),
+            })
+            .collect();
+        Ok(row)
     }
 }
 impl AddAssign for ElasticsearchCatIndexResponse {
@@ -263,12 +253,10 @@ impl AddAssign for Status {
 
 #[cfg(test)]
 mod tests {
-    use serde_json::json;
-
     use super::*;
 
     #[test]
-    fn test_serialize_filtered() {
+    fn test_to_row() {
         let response = ElasticsearchCatIndexResponse {
             health: Health::Green,
             status: Status::Open,
@@ -283,33 +271,21 @@ mod tests {
             dataset_size: 1500,
         };
 
-        // Test serialization with all fields
-        let all_fields = response.serialize_filtered(&None).unwrap();
-        let expected_all_fields = json!({
-            "health": "green",
-            "status": "open",
-            "index": "test_index",
-            "uuid": "test_uuid",
-            "pri": "1",
-            "rep": "2",
-            "docs.count": "100",
-            "docs.deleted": "10",
-            "store.size": "1000b",  // Assuming ser_es_format formats size to kb
-            "pri.store.size": "500b", // Example format
-            "dataset.size": "1.5kb", // Example format
-        });
-        assert_eq!(all_fields, expected_all_fields);
-
-        // Test serialization with selected fields
-        let selected_fields = response
-            .serialize_filtered(&Some(vec!["index".to_string(), "uuid".to_string()]))
-            .unwrap();
-        let expected_selected_fields = json!({
-            "index": "test_index",
-            "uuid": "test_uuid"
-        });
-        assert_eq!(selected_fields, expected_selected_fields);
-
-        // Add more test cases as needed
+        assert_eq!(
+            response.to_row().unwrap(),
+            [
+                "green",
+                "open",
+                "test_index",
+                "test_uuid",
+                "1",
+                "2",
+                "100",
+                "10",
+                "1000b",
+                "500b",
+                "1.5kb",
+            ]
+        );
     }
 }
@@ -144,6 +144,8 @@ impl From<IndexServiceError> for ElasticsearchError {
 pub enum ElasticException {
     #[serde(rename = "action_request_validation_exception")]
     ActionRequestValidation,
+    #[serde(rename = "aliases_not_found_exception")]
+    AliasesNotFound,
     #[serde(rename = "document_parsing_exception")]
     DocumentParsing,
     // This is an exception proper to Quickwit.
@@ -156,24 +158,31 @@ pub enum ElasticException {
     // This is an exception proper to Quickwit.
     #[serde(rename = "rate_limited_exception")]
     RateLimited,
+    #[serde(rename = "resource_not_found_exception")]
+    ResourceNotFound,
     // This is an exception proper to Quickwit.
     #[serde(rename = "source_not_found_exception")]
     SourceNotFound,
     #[serde(rename = "timeout_exception")]
     Timeout,
+    #[serde(rename = "version_conflict_engine_exception")]
+    VersionConflictEngine,
 }
 
 impl ElasticException {
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::ActionRequestValidation => "action_request_validation_exception",
+            Self::AliasesNotFound => "aliases_not_found_exception",
             Self::DocumentParsing => "document_parsing_exception",
             Self::Internal => "internal_exception",
             Self::RateLimited => "rate_limited_exception",
             Self::IllegalArgument => "illegal_argument_exception",
             Self::IndexNotFound => "index_not_found_exception",
+            Self::ResourceNotFound => "resource_not_found_exception",
             Self::SourceNotFound => "source_not_found_exception",
             Self::Timeout => "timeout_exception",
+            Self::VersionConflictEngine => "version_conflict_engine_exception",
         }
     }
 }
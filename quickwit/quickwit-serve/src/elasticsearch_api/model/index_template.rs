@@ -0,0 +1,467 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use quickwit_config::{
+    DocMapping, IndexTemplate, IndexingSettings, RetentionPolicy, SearchSettings,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use serde_with::{serde_as, OneOrMany, PreferMany};
+
+use super::{convert_to_es_mapping, merge_es_properties, ElasticsearchMapping};
+
+/// Body of the `PUT _index_template/{name}` API:
+///
+/// {
+///   "index_patterns": ["logs-*"],
+///   "priority": 10,
+///   "template": {
+///     "mappings": {"properties": {"@timestamp": {"type": "date"}}},
+///     "settings": {"index": {"refresh_interval": "5s"}},
+///     "lifecycle": {"data_retention": "30d"}
+///   }
+/// }
+///
+/// Settings that do not have a Quickwit equivalent, such as `number_of_shards`, are ignored.
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct PutIndexTemplateBody {
+    #[serde_as(deserialize_as = "OneOrMany<_, PreferMany>")]
+    pub index_patterns: Vec<String>,
+    #[serde(default)]
+    pub priority: usize,
+    #[serde(default)]
+    pub template: ElasticIndexTemplateSpec,
+    #[serde(default)]
+    pub composed_of: Vec<String>,
+    /// Templates declaring a data stream map the `@timestamp` field, like Elasticsearch does.
+    #[serde(default)]
+    pub data_stream: Option<JsonValue>,
+    #[serde(default)]
+    #[serde(rename = "_meta")]
+    pub meta: Option<JsonMap<String, JsonValue>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ElasticIndexTemplateSpec {
+    #[serde(default)]
+    pub mappings: Option<ElasticIndexTemplateMappings>,
+    #[serde(default)]
+    pub settings: JsonMap<String, JsonValue>,
+    #[serde(default)]
+    pub aliases: JsonMap<String, JsonValue>,
+    #[serde(default)]
+    pub lifecycle: Option<ElasticIndexTemplateLifecycle>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ElasticIndexTemplateMappings {
+    #[serde(default)]
+    pub dynamic: Option<JsonValue>,
+    #[serde(default)]
+    pub properties: JsonMap<String, JsonValue>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ElasticIndexTemplateLifecycle {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_retention: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PutIndexTemplateQueryParams {
+    /// If true, the request fails if a template with the same name already exists.
+    #[serde(default)]
+    pub create: Option<bool>,
+}
+
+/// Returns JSON in the format:
+///
+/// {
+///   "index_templates": [
+///     {
+///       "name": "logs",
+///       "index_template": {
+///         "index_patterns": ["logs-*"],
+///         "priority": 10,
+///         "template": {"mappings": {...}, "settings": {...}}
+///       }
+///     }
+///   ]
+/// }
+#[derive(Debug, Clone, Serialize)]
+pub struct GetIndexTemplatesResponse {
+    pub index_templates: Vec<IndexTemplateEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexTemplateEntry {
+    pub name: String,
+    pub index_template: ElasticIndexTemplate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ElasticIndexTemplate {
+    pub index_patterns: Vec<String>,
+    pub priority: usize,
+    pub template: ElasticIndexTemplateSummary,
+    pub composed_of: Vec<String>,
+    #[serde(rename = "_meta")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<JsonValue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ElasticIndexTemplateSummary {
+    pub mappings: ElasticsearchMapping,
+    pub settings: JsonValue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lifecycle: Option<ElasticIndexTemplateLifecycle>,
+}
+
+/// Converts the body of a `PUT _index_template` request into a Quickwit index template. The
+/// template still needs to be validated.
+pub fn convert_to_quickwit_index_template(
+    template_id: String,
+    put_index_template_body: PutIndexTemplateBody,
+) -> Result<IndexTemplate, String> {
+    if !put_index_template_body.composed_of.is_empty() {
+        return Err("component templates are not supported".to_string());
+    }
+    let template_spec = put_index_template_body.template;
+
+    if !template_spec.aliases.is_empty() {
+        return Err("index template aliases are not supported".to_string());
+    }
+    let mappings = template_spec.mappings.unwrap_or_default();
+
+    let mode = match mappings.dynamic.as_ref() {
+        None | Some(JsonValue::Bool(true)) => "dynamic",
+        Some(JsonValue::Bool(false)) => "lenient",
+        Some(JsonValue::String(dynamic)) => match dynamic.as_str() {
+            "true" => "dynamic",
+            "false" | "runtime" => "lenient",
+            "strict" => "strict",
+            _ => return Err(format!("unknown value `{dynamic}` for `dynamic`")),
+        },
+        Some(dynamic) => return Err(format!("unknown value `{dynamic}` for `dynamic`")),
+    };
+    let mut field_mappings_json = Vec::new();
+    merge_es_properties(&mut field_mappings_json, &mappings.properties)?;
+
+    if put_index_template_body.data_stream.is_some() {
+        let timestamp_properties = json!({"@timestamp": {"type": "date"}});
+        merge_es_properties(
+            &mut field_mappings_json,
+            timestamp_properties
+                .as_object()
+                .expect("properties should be an object"),
+        )?;
+    }
+    let timestamp_field_opt = field_mappings_json
+        .iter()
+        .any(|field_mapping_json| {
+            field_mapping_json["name"] == "@timestamp" && field_mapping_json["type"] == "datetime"
+        })
+        .then(|| "@timestamp".to_string());
+
+    let doc_mapping: DocMapping = serde_json::from_value(json!({
+        "mode": mode,
+        "field_mappings": field_mappings_json,
+        "timestamp_field": timestamp_field_opt,
+    }))
+    .map_err(|serde_error| format!("invalid mappings: {serde_error}"))?;
+
+    let settings = flatten_settings(&template_spec.settings);
+    let mut indexing_settings = IndexingSettings::default();
+
+    if let Some(refresh_interval) = settings.get("refresh_interval").and_then(JsonValue::as_str) {
+        if refresh_interval != "-1" {
+            let refresh_interval = humantime::parse_duration(refresh_interval).map_err(|_| {
+                format!("failed to parse `refresh_interval` value `{refresh_interval}`")
+            })?;
+            indexing_settings.commit_timeout_secs = refresh_interval.as_secs().max(1) as usize;
+        }
+    }
+    let mut search_settings = SearchSettings::default();
+
+    match settings.get("query.default_field") {
+        Some(JsonValue::String(field)) => search_settings.default_search_fields.push(field.clone()),
+        Some(JsonValue::Array(fields)) => search_settings.default_search_fields.extend(
+            fields
+                .iter()
+                .filter_map(JsonValue::as_str)
+                .map(str::to_string),
+        ),
+        _ => {}
+    }
+    // `*` is the Elasticsearch default and has no Quickwit equivalent.
+    search_settings
+        .default_search_fields
+        .retain(|field| field != "*");
+
+    let retention_policy_opt = template_spec
+        .lifecycle
+        .and_then(|lifecycle| lifecycle.data_retention)
+        .map(|retention_period| RetentionPolicy {
+            retention_period: convert_es_time_unit(&retention_period),
+            evaluation_schedule: RetentionPolicy::default_schedule(),
+        });
+    let description = put_index_template_body
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.get("description"))
+        .and_then(JsonValue::as_str)
+        .map(str::to_string);
+
+    Ok(IndexTemplate {
+        template_id,
+        index_id_patterns: put_index_template_body.index_patterns,
+        index_root_uri: None,
+        priority: put_index_template_body.priority,
+        description,
+        doc_mapping,
+        indexing_settings,
+        search_settings,
+        retention_policy_opt,
+    })
+}
+
+/// Converts a Quickwit index template into its Elasticsearch representation.
+pub fn convert_to_es_index_template(
+    index_template: &IndexTemplate,
+) -> serde_json::Result<ElasticIndexTemplate> {
+    let mappings = convert_to_es_mapping(&index_template.doc_mapping)?.mappings;
+    let mut index_settings = json!({
+        "refresh_interval": format!("{}s", index_template.indexing_settings.commit_timeout_secs),
+    });
+    if !index_template
+        .search_settings
+        .default_search_fields
+        .is_empty()
+    {
+        index_settings["query"] = json!({
+            "default_field": index_template.search_settings.default_search_fields,
+        });
+    }
+    let lifecycle = index_template
+        .retention_policy_opt
+        .as_ref()
+        .map(|retention_policy| ElasticIndexTemplateLifecycle {
+            data_retention: Some(retention_policy.retention_period.clone()),
+        });
+    let meta = index_template
+        .description
+        .as_ref()
+        .map(|description| json!({ "description": description }));
+
+    Ok(ElasticIndexTemplate {
+        index_patterns: index_template.index_id_patterns.clone(),
+        priority: index_template.priority,
+        template: ElasticIndexTemplateSummary {
+            mappings,
+            settings: json!({ "index": index_settings }),
+            lifecycle,
+        },
+        composed_of: Vec::new(),
+        meta,
+    })
+}
+
+/// Flattens the nested and dotted forms of Elasticsearch index settings into dotted keys without
+/// the `index.` prefix: `{"index": {"query": {"default_field": "x"}}}` becomes
+/// `{"query.default_field": "x"}`.
+fn flatten_settings(settings: &JsonMap<String, JsonValue>) -> BTreeMap<String, JsonValue> {
+    fn flatten_into(
+        prefix: &str,
+        settings: &JsonMap<String, JsonValue>,
+        flattened_settings: &mut BTreeMap<String, JsonValue>,
+    ) {
+        for (key, value) in settings {
+            let key = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            if let JsonValue::Object(nested_settings) = value {
+                flatten_into(&key, nested_settings, flattened_settings);
+            } else {
+                let key = key
+                    .strip_prefix("index.")
+                    .map(str::to_string)
+                    .unwrap_or(key);
+                flattened_settings.insert(key, value.clone());
+            }
+        }
+    }
+    let mut flattened_settings = BTreeMap::new();
+    flatten_into("", settings, &mut flattened_settings);
+    flattened_settings
+}
+
+/// Converts an Elasticsearch time value (`30d`, `12h`) into a period that Quickwit can parse.
+/// Elasticsearch uses `d` for days, which humantime does not support.
+fn convert_es_time_unit(time_value: &str) -> String {
+    match time_value.strip_suffix('d') {
+        Some(days) if days.parse::<u64>().is_ok() => format!("{days} days"),
+        _ => time_value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_doc_mapper::{Mode, ModeType};
+
+    use super::*;
+
+    #[test]
+    fn test_convert_to_quickwit_index_template() {
+        let put_index_template_body: PutIndexTemplateBody = serde_json::from_value(json!({
+            "index_patterns": "logs-*",
+            "priority": 10,
+            "data_stream": {},
+            "template": {
+                "mappings": {
+                    "dynamic": "strict",
+                    "properties": {
+                        "message": {"type": "text"},
+                        "service": {"type": "keyword"}
+                    }
+                },
+                "settings": {
+                    "number_of_shards": 1,
+                    "index.refresh_interval": "5s",
+                    "index": {"query": {"default_field": ["message"]}}
+                },
+                "lifecycle": {"data_retention": "30d"}
+            },
+            "_meta": {"description": "Application logs."}
+        }))
+        .unwrap();
+        let index_template =
+            convert_to_quickwit_index_template("logs".to_string(), put_index_template_body)
+                .unwrap();
+        index_template.validate().unwrap();
+
+        assert_eq!(index_template.template_id, "logs");
+        assert_eq!(index_template.index_id_patterns, ["logs-*"]);
+        assert_eq!(index_template.priority, 10);
+        assert_eq!(
+            index_template.description.as_deref(),
+            Some("Application logs.")
+        );
+        assert_eq!(index_template.doc_mapping.mode, Mode::Strict);
+        assert_eq!(index_template.doc_mapping.field_mappings.len(), 3);
+        assert_eq!(
+            index_template.doc_mapping.timestamp_field.as_deref(),
+            Some("@timestamp")
+        );
+        assert_eq!(index_template.indexing_settings.commit_timeout_secs, 5);
+        assert_eq!(
+            index_template.search_settings.default_search_fields,
+            ["message"]
+        );
+        let retention_policy = index_template.retention_policy_opt.as_ref().unwrap();
+        assert_eq!(retention_policy.retention_period, "30 days");
+        assert_eq!(retention_policy.evaluation_schedule, "hourly");
+
+        let es_index_template = convert_to_es_index_template(&index_template).unwrap();
+        assert_eq!(
+            serde_json::to_value(&es_index_template).unwrap(),
+            json!({
+                "index_patterns": ["logs-*"],
+                "priority": 10,
+                "template": {
+                    "mappings": {
+                        "dynamic": "strict",
+                        "properties": {
+                            "message": {"type": "text"},
+                            "service": {"type": "keyword"},
+                            "@timestamp": {"type": "date"}
+                        }
+                    },
+                    "settings": {
+                        "index": {
+                            "refresh_interval": "5s",
+                            "query": {"default_field": ["message"]}
+                        }
+                    },
+                    "lifecycle": {"data_retention": "30 days"}
+                },
+                "composed_of": [],
+                "_meta": {"description": "Application logs."}
+            })
+        );
+    }
+
+    #[test]
+    fn test_convert_to_quickwit_index_template_defaults() {
+        let put_index_template_body: PutIndexTemplateBody =
+            serde_json::from_value(json!({"index_patterns": ["metrics-*"]})).unwrap();
+        let index_template =
+            convert_to_quickwit_index_template("metrics".to_string(), put_index_template_body)
+                .unwrap();
+        index_template.validate().unwrap();
+
+        assert_eq!(
+            index_template.doc_mapping.mode.mode_type(),
+            ModeType::Dynamic
+        );
+        assert!(index_template.doc_mapping.field_mappings.is_empty());
+        assert!(index_template.doc_mapping.timestamp_field.is_none());
+        assert_eq!(
+            index_template.indexing_settings,
+            IndexingSettings::default()
+        );
+        assert!(index_template.retention_policy_opt.is_none());
+    }
+
+    #[test]
+    fn test_convert_to_quickwit_index_template_rejects_unsupported_features() {
+        let put_index_template_body: PutIndexTemplateBody = serde_json::from_value(json!({
+            "index_patterns": ["logs-*"],
+            "composed_of": ["base"]
+        }))
+        .unwrap();
+        let error = convert_to_quickwit_index_template("logs".to_string(), put_index_template_body)
+            .unwrap_err();
+        assert_eq!(error, "component templates are not supported");
+
+        let put_index_template_body: PutIndexTemplateBody = serde_json::from_value(json!({
+            "index_patterns": ["logs-*"],
+            "template": {"aliases": {"logs": {}}}
+        }))
+        .unwrap();
+        let error = convert_to_quickwit_index_template("logs".to_string(), put_index_template_body)
+            .unwrap_err();
+        assert_eq!(error, "index template aliases are not supported");
+
+        let put_index_template_body: PutIndexTemplateBody = serde_json::from_value(json!({
+            "index_patterns": ["logs-*"],
+            "template": {"mappings": {"properties": {"geo": {"type": "geo_point"}}}}
+        }))
+        .unwrap();
+        let error = convert_to_quickwit_index_template("logs".to_string(), put_index_template_body)
+            .unwrap_err();
+        assert_eq!(error, "field `geo` has unsupported type `geo_point`");
+    }
+}
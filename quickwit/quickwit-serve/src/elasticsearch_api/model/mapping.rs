@@ -0,0 +1,369 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use quickwit_doc_mapper::{DocMapping, ModeType};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+
+/// Mapping of an index, as returned by the `_mapping` API:
+///
+/// {
+///   "my-index": {
+///     "mappings": {
+///       "dynamic": "true",
+///       "properties": {
+///         "message": {"type": "text"},
+///         "service": {"type": "keyword"}
+///       }
+///     }
+///   }
+/// }
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ElasticsearchIndexMapping {
+    pub mappings: ElasticsearchMapping,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ElasticsearchMapping {
+    pub dynamic: String,
+    pub properties: JsonMap<String, JsonValue>,
+}
+
+/// Body of the `PUT _mapping` API. Only additive changes to `properties` are supported.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PutMappingBody {
+    #[serde(default)]
+    pub properties: JsonMap<String, JsonValue>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct PutMappingResponse {
+    pub acknowledged: bool,
+}
+
+/// Renders a Quickwit doc mapping as an Elasticsearch mapping.
+///
+/// Concatenate fields have no Elasticsearch equivalent and are left out.
+pub fn convert_to_es_mapping(
+    doc_mapping: &DocMapping,
+) -> serde_json::Result<ElasticsearchIndexMapping> {
+    let field_mappings_json = serde_json::to_value(&doc_mapping.field_mappings)?;
+    let properties = es_properties(field_mappings_json.as_array().map(Vec::as_slice));
+    let dynamic = match doc_mapping.mode.mode_type() {
+        ModeType::Dynamic => "true",
+        ModeType::Lenient => "false",
+        ModeType::Strict | ModeType::StrictVerbose => "strict",
+    };
+    Ok(ElasticsearchIndexMapping {
+        mappings: ElasticsearchMapping {
+            dynamic: dynamic.to_string(),
+            properties,
+        },
+    })
+}
+
+fn es_properties(field_mappings_json_opt: Option<&[JsonValue]>) -> JsonMap<String, JsonValue> {
+    let mut properties = JsonMap::new();
+
+    for field_mapping_json in field_mappings_json_opt.unwrap_or_default() {
+        let Some(field_name) = field_mapping_json.get("name").and_then(JsonValue::as_str) else {
+            continue;
+        };
+        if let Some(es_field_mapping) = es_field_mapping(field_mapping_json) {
+            properties.insert(field_name.to_string(), es_field_mapping);
+        }
+    }
+    properties
+}
+
+/// Converts a serialized Quickwit field mapping entry into an Elasticsearch field mapping.
+fn es_field_mapping(field_mapping_json: &JsonValue) -> Option<JsonValue> {
+    let type_id = field_mapping_json.get("type")?.as_str()?;
+    let type_id = type_id
+        .strip_prefix("array<")
+        .and_then(|type_id| type_id.strip_suffix('>'))
+        .unwrap_or(type_id);
+
+    let es_type = match type_id {
+        "text" => {
+            let tokenizer_opt = field_mapping_json
+                .get("tokenizer")
+                .and_then(JsonValue::as_str);
+            if tokenizer_opt == Some("raw") {
+                "keyword"
+            } else {
+                "text"
+            }
+        }
+        "i64" => "long",
+        "u64" => "unsigned_long",
+        "f64" => "double",
+        "bool" => "boolean",
+        "ip" => "ip",
+        "datetime" => "date",
+        "bytes" => "binary",
+        "json" => "object",
+        "completion" => "completion",
+        "object" => {
+            let field_mappings_json_opt = field_mapping_json
+                .get("field_mappings")
+                .and_then(JsonValue::as_array)
+                .map(Vec::as_slice);
+            let properties = es_properties(field_mappings_json_opt);
+            return Some(json!({ "properties": properties }));
+        }
+        _ => return None,
+    };
+    Some(json!({ "type": es_type }))
+}
+
+/// Converts an Elasticsearch field mapping into a serialized Quickwit field mapping entry.
+fn quickwit_field_mapping(
+    field_name: &str,
+    es_field_mapping: &JsonValue,
+) -> Result<JsonValue, String> {
+    let es_type_opt = es_field_mapping.get("type").and_then(JsonValue::as_str);
+
+    if let Some(es_properties) = es_field_mapping.get("properties") {
+        if !matches!(es_type_opt, None | Some("object")) {
+            return Err(format!(
+                "field `{field_name}` of type `{}` cannot have properties",
+                es_type_opt.unwrap_or_default()
+            ));
+        }
+        let es_properties = es_properties
+            .as_object()
+            .ok_or_else(|| format!("properties of field `{field_name}` must be an object"))?;
+        let field_mappings = es_properties
+            .iter()
+            .map(|(name, es_field_mapping)| quickwit_field_mapping(name, es_field_mapping))
+            .collect::<Result<Vec<JsonValue>, String>>()?;
+        return Ok(json!({
+            "name": field_name,
+            "type": "object",
+            "field_mappings": field_mappings,
+        }));
+    }
+    let Some(es_type) = es_type_opt else {
+        return Err(format!("field `{field_name}` is missing a type"));
+    };
+    let field_mapping_json = match es_type {
+        "keyword" | "constant_keyword" | "wildcard" => {
+            json!({ "type": "text", "tokenizer": "raw", "fast": true })
+        }
+        "text" | "match_only_text" => {
+            json!({ "type": "text", "tokenizer": "default", "record": "position" })
+        }
+        "long" | "integer" | "short" | "byte" => json!({ "type": "i64", "fast": true }),
+        "unsigned_long" => json!({ "type": "u64", "fast": true }),
+        "double" | "float" | "half_float" | "scaled_float" => {
+            json!({ "type": "f64", "fast": true })
+        }
+        "boolean" => json!({ "type": "bool", "fast": true }),
+        "ip" => json!({ "type": "ip", "fast": true }),
+        "date" | "date_nanos" => json!({ "type": "datetime", "fast": true }),
+        "binary" => json!({ "type": "bytes" }),
+        "object" | "flattened" => json!({ "type": "json" }),
+        "completion" => json!({ "type": "completion" }),
+        _ => {
+            return Err(format!(
+                "field `{field_name}` has unsupported type `{es_type}`"
+            ));
+        }
+    };
+    let mut field_mapping_json = field_mapping_json;
+    field_mapping_json["name"] = JsonValue::String(field_name.to_string());
+    Ok(field_mapping_json)
+}
+
+/// Adds the fields of an Elasticsearch `properties` object to a list of serialized Quickwit field
+/// mapping entries. Fields that already exist must keep the same type, and object fields are
+/// merged recursively.
+///
+/// Returns whether some fields were added.
+pub fn merge_es_properties(
+    field_mappings_json: &mut Vec<JsonValue>,
+    es_properties: &JsonMap<String, JsonValue>,
+) -> Result<bool, String> {
+    let mut has_changed = false;
+
+    for (field_name, es_field_mapping) in es_properties {
+        let new_field_mapping_json = quickwit_field_mapping(field_name, es_field_mapping)?;
+        let Some(current_field_mapping_json) = field_mappings_json
+            .iter_mut()
+            .find(|field_mapping_json| field_mapping_json["name"] == field_name.as_str())
+        else {
+            field_mappings_json.push(new_field_mapping_json);
+            has_changed = true;
+            continue;
+        };
+        let current_es_field_mapping = es_field_mapping_type(current_field_mapping_json);
+        let new_es_field_mapping = es_field_mapping_type(&new_field_mapping_json);
+
+        if current_es_field_mapping != new_es_field_mapping {
+            return Err(format!(
+                "field `{field_name}` cannot be changed from type `{current_es_field_mapping}` to \
+                 `{new_es_field_mapping}`"
+            ));
+        }
+        if current_es_field_mapping == "object" && es_field_mapping.get("properties").is_some() {
+            let current_field_mappings_json = current_field_mapping_json
+                .get_mut("field_mappings")
+                .and_then(JsonValue::as_array_mut)
+                .ok_or_else(|| {
+                    format!("field `{field_name}` is a JSON field and cannot have properties")
+                })?;
+            let es_properties = es_field_mapping["properties"]
+                .as_object()
+                .ok_or_else(|| format!("properties of field `{field_name}` must be an object"))?;
+            has_changed |= merge_es_properties(current_field_mappings_json, es_properties)?;
+        }
+    }
+    Ok(has_changed)
+}
+
+/// Returns the Elasticsearch type of a serialized Quickwit field mapping entry. Object fields are
+/// reported as `object`.
+fn es_field_mapping_type(field_mapping_json: &JsonValue) -> String {
+    if field_mapping_json["type"] == "object" {
+        return "object".to_string();
+    }
+    es_field_mapping(field_mapping_json)
+        .and_then(|es_field_mapping| {
+            es_field_mapping["type"]
+                .as_str()
+                .map(|es_type| es_type.to_string())
+        })
+        .unwrap_or_else(|| field_mapping_json["type"].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_doc_mapper::FieldMappingEntry;
+
+    use super::*;
+
+    fn doc_mapping_for_test() -> DocMapping {
+        serde_json::from_value(json!({
+            "mode": "strict",
+            "field_mappings": [
+                {"name": "message", "type": "text"},
+                {"name": "service", "type": "text", "tokenizer": "raw", "fast": true},
+                {"name": "latency", "type": "array<f64>"},
+                {"name": "timestamp", "type": "datetime", "fast": true},
+                {"name": "attributes", "type": "json"},
+                {
+                    "name": "resource",
+                    "type": "object",
+                    "field_mappings": [{"name": "host", "type": "ip"}]
+                },
+                {
+                    "name": "all",
+                    "type": "concatenate",
+                    "concatenate_fields": ["message"]
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_convert_to_es_mapping() {
+        let es_mapping = convert_to_es_mapping(&doc_mapping_for_test()).unwrap();
+        assert_eq!(es_mapping.mappings.dynamic, "strict");
+        assert_eq!(
+            JsonValue::Object(es_mapping.mappings.properties),
+            json!({
+                "message": {"type": "text"},
+                "service": {"type": "keyword"},
+                "latency": {"type": "double"},
+                "timestamp": {"type": "date"},
+                "attributes": {"type": "object"},
+                "resource": {"properties": {"host": {"type": "ip"}}},
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge_es_properties() {
+        let doc_mapping = doc_mapping_for_test();
+        let mut field_mappings_json = serde_json::to_value(&doc_mapping.field_mappings)
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .clone();
+        let es_properties = json!({
+            "service": {"type": "keyword"},
+            "status": {"type": "integer"},
+            "resource": {"properties": {"region": {"type": "keyword"}}},
+        });
+        let has_changed =
+            merge_es_properties(&mut field_mappings_json, es_properties.as_object().unwrap())
+                .unwrap();
+        assert!(has_changed);
+
+        let field_mappings: Vec<FieldMappingEntry> =
+            serde_json::from_value(JsonValue::Array(field_mappings_json.clone())).unwrap();
+        assert_eq!(field_mappings.len(), 8);
+        assert_eq!(field_mappings[7].name, "status");
+        assert_eq!(
+            es_field_mapping(&field_mappings_json[7]).unwrap(),
+            json!({"type": "long"})
+        );
+        assert_eq!(
+            es_field_mapping(&field_mappings_json[5]).unwrap(),
+            json!({
+                "properties": {
+                    "host": {"type": "ip"},
+                    "region": {"type": "keyword"},
+                }
+            })
+        );
+        let has_changed =
+            merge_es_properties(&mut field_mappings_json, es_properties.as_object().unwrap())
+                .unwrap();
+        assert!(!has_changed);
+    }
+
+    #[test]
+    fn test_merge_es_properties_rejects_type_changes() {
+        let doc_mapping = doc_mapping_for_test();
+        let mut field_mappings_json = serde_json::to_value(&doc_mapping.field_mappings)
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .clone();
+        let error = merge_es_properties(
+            &mut field_mappings_json,
+            json!({"message": {"type": "keyword"}}).as_object().unwrap(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "field `message` cannot be changed from type `text` to `keyword`"
+        );
+        let error = merge_es_properties(
+            &mut field_mappings_json,
+            json!({"geo": {"type": "geo_point"}}).as_object().unwrap(),
+        )
+        .unwrap_err();
+        assert_eq!(error, "field `geo` has unsupported type `geo_point`");
+    }
+}
@@ -17,19 +17,42 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+mod alias;
+mod analyze;
+mod async_search;
 mod bulk_body;
 mod bulk_query_params;
+mod cat;
 mod cat_indices;
 mod error;
 mod field_capability;
+mod index_template;
+mod mapping;
 mod multi_search;
+mod point_in_time;
+mod profile;
 mod scroll;
 mod search_body;
 mod search_query_params;
+mod search_template;
 mod stats;
+mod suggest;
+mod terms_enum;
 
+pub use alias::{
+    AliasAction, AliasActionParams, AliasActionsBody, AliasActionsResponse, GetAliasesResponse,
+    IndexAliasesEntry,
+};
+pub use analyze::{
+    analyze_texts_for_es_api, AnalyzeRequestBody, AnalyzeResponse, AnalyzeToken, AnalyzeTokenizer,
+};
+pub use async_search::{
+    AsyncSearchResponse, AsyncSearchShards, AsyncSearchStatusResponse, DeleteAsyncSearchResponse,
+    GetAsyncSearchQueryParams, SubmitAsyncSearchQueryParams,
+};
 pub use bulk_body::BulkAction;
 pub use bulk_query_params::ElasticBulkOptions;
+pub use cat::{CatFormat, CatQueryParams, CatResponse};
 pub use cat_indices::{
     CatIndexQueryParams, ElasticsearchCatIndexResponse, ElasticsearchResolveIndexEntryResponse,
     ElasticsearchResolveIndexResponse,
@@ -39,21 +62,69 @@ pub use field_capability::{
     build_list_field_request_for_es_api, convert_to_es_field_capabilities_response,
     FieldCapabilityQueryParams, FieldCapabilityRequestBody, FieldCapabilityResponse,
 };
+pub use index_template::{
+    convert_to_es_index_template, convert_to_quickwit_index_template, ElasticIndexTemplate,
+    ElasticIndexTemplateLifecycle, ElasticIndexTemplateMappings, ElasticIndexTemplateSpec,
+    ElasticIndexTemplateSummary, GetIndexTemplatesResponse, IndexTemplateEntry,
+    PutIndexTemplateBody, PutIndexTemplateQueryParams,
+};
+pub use mapping::{
+    convert_to_es_mapping, merge_es_properties, ElasticsearchIndexMapping, ElasticsearchMapping,
+    PutMappingBody, PutMappingResponse,
+};
 pub use multi_search::{
     MultiSearchHeader, MultiSearchQueryParams, MultiSearchResponse, MultiSearchSingleResponse,
 };
-use quickwit_proto::search::{SortDatetimeFormat, SortOrder};
+pub use point_in_time::{
+    ClosePointInTimeBody, ClosePointInTimeResponse, OpenPointInTimeQueryParams,
+    OpenPointInTimeResponse, PointInTimeBody,
+};
+pub(crate) use profile::convert_to_es_profile;
+pub use profile::{ElasticsearchProfile, ShardProfile};
+use quickwit_proto::search::{SortDatetimeFormat, SortMissing, SortOrder};
 pub use scroll::ScrollQueryParams;
-pub use search_body::SearchBody;
+pub use search_body::{Collapse, CollapseInnerHits, Highlight, SearchBody};
 pub use search_query_params::{DeleteQueryParams, SearchQueryParams, SearchQueryParamsCount};
+pub use search_template::{
+    GetStoredScriptResponse, SearchTemplateBody, StoredScript, StoredScriptBody,
+};
 use serde::{Deserialize, Serialize};
 pub use stats::{ElasticsearchStatsResponse, StatsResponseEntry};
+pub(crate) use suggest::{build_suggesters_for_es_api, convert_to_es_suggest};
+pub use suggest::{
+    CompletionSuggesterBody, ElasticsearchSuggestResponse, SuggestEntry, SuggestOption,
+    SuggesterBody,
+};
+pub use terms_enum::{
+    build_list_terms_request_for_es_api, convert_to_es_terms_enum_response, TermsEnumQueryParams,
+    TermsEnumRequestBody, TermsEnumResponse,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SortField {
     pub field: String,
     pub order: SortOrder,
     pub date_format: Option<ElasticDateFormat>,
+    pub missing: SortMissing,
+}
+
+/// Placement of the documents missing the sort field.
+/// Unlike ES, we do not support replacing missing values with a custom value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ElasticSortMissing {
+    #[serde(rename = "_first")]
+    First,
+    #[serde(rename = "_last")]
+    Last,
+}
+
+impl From<ElasticSortMissing> for SortMissing {
+    fn from(missing: ElasticSortMissing) -> Self {
+        match missing {
+            ElasticSortMissing::First => SortMissing::First,
+            ElasticSortMissing::Last => SortMissing::Last,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -17,15 +17,15 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use elasticsearch_dsl::search::SearchResponse as ElasticsearchResponse;
 use elasticsearch_dsl::ErrorCause;
 use hyper::StatusCode;
+use quickwit_proto::search::SearchPriority;
 use serde::{Deserialize, Serialize};
 use serde_with::formats::PreferMany;
 use serde_with::{serde_as, OneOrMany};
 
 use super::search_query_params::ExpandWildcards;
-use super::ElasticsearchError;
+use super::{ElasticsearchError, ElasticsearchSuggestResponse};
 use crate::simple_list::{from_simple_list, to_simple_list};
 
 // Multi search doc: https://www.elastic.co/guide/en/elasticsearch/reference/current/search-multi-search.html
@@ -61,6 +61,10 @@ pub struct MultiSearchQueryParams {
     pub max_concurrent_shard_requests: Option<i64>,
     #[serde(default)]
     pub pre_filter_shard_size: Option<i64>,
+    /// Priority of the searches: `interactive` (default) or `batch`.
+    /// This is not part of the official Elasticsearch API.
+    #[serde(default)]
+    pub priority: Option<SearchPriority>,
     #[serde(default)]
     pub rest_total_hits_as_int: Option<bool>,
     #[serde(serialize_with = "to_simple_list")]
@@ -81,6 +85,9 @@ pub struct MultiSearchQueryParams {
     pub _source_includes: Option<Vec<String>>,
     #[serde(default)]
     pub typed_keys: Option<bool>,
+    /// ID of the API key passed in the `Authorization` header, if any.
+    #[serde(skip)]
+    pub api_key_id: Option<String>,
 }
 
 #[serde_as]
@@ -117,14 +124,14 @@ pub struct MultiSearchSingleResponse {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(flatten)]
-    pub response: Option<ElasticsearchResponse>,
+    pub response: Option<ElasticsearchSuggestResponse>,
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<ErrorCause>,
 }
 
-impl From<ElasticsearchResponse> for MultiSearchSingleResponse {
-    fn from(response: ElasticsearchResponse) -> Self {
+impl From<ElasticsearchSuggestResponse> for MultiSearchSingleResponse {
+    fn from(response: ElasticsearchSuggestResponse) -> Self {
         MultiSearchSingleResponse {
             status: StatusCode::OK,
             response: Some(response),
@@ -0,0 +1,52 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+
+/// Query parameters of the open point-in-time API.
+#[derive(Deserialize, Default)]
+pub struct OpenPointInTimeQueryParams {
+    pub keep_alive: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OpenPointInTimeResponse {
+    pub id: String,
+}
+
+#[derive(Deserialize, Default)]
+pub struct ClosePointInTimeBody {
+    #[serde(default)]
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ClosePointInTimeResponse {
+    pub succeeded: bool,
+    pub num_freed: u32,
+}
+
+/// Point in time of a search request.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PointInTimeBody {
+    pub id: String,
+    #[serde(default)]
+    pub keep_alive: Option<String>,
+}
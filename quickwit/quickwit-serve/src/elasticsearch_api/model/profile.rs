@@ -0,0 +1,96 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use quickwit_proto::search::SplitSearchProfile;
+use serde::{Deserialize, Serialize};
+
+/// The `profile` section of a search response.
+///
+/// Quickwit does not profile the individual queries and collectors like Elasticsearch. Instead, it
+/// reports the time spent in each phase of the search of a split, each split being reported as a
+/// shard.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ElasticsearchProfile {
+    pub shards: Vec<ShardProfile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShardProfile {
+    /// ID of the split.
+    pub id: String,
+    pub warmup_time_in_nanos: u64,
+    pub search_time_in_nanos: u64,
+    pub aggregation_time_in_nanos: u64,
+    pub downloaded_bytes: u64,
+    pub cache_hit: bool,
+}
+
+/// Converts the split profiles of a search response into the `profile` section of the
+/// Elasticsearch response. Returns `None` if the search was not profiled.
+pub(crate) fn convert_to_es_profile(
+    split_profiles: Vec<SplitSearchProfile>,
+) -> Option<ElasticsearchProfile> {
+    if split_profiles.is_empty() {
+        return None;
+    }
+    let shards = split_profiles
+        .into_iter()
+        .map(|split_profile| ShardProfile {
+            id: split_profile.split_id,
+            warmup_time_in_nanos: split_profile.warmup_micros * 1_000,
+            search_time_in_nanos: split_profile.search_micros * 1_000,
+            aggregation_time_in_nanos: split_profile.aggregation_micros * 1_000,
+            downloaded_bytes: split_profile.num_bytes_downloaded,
+            cache_hit: split_profile.leaf_cache_hit,
+        })
+        .collect();
+    Some(ElasticsearchProfile { shards })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_to_es_profile() {
+        assert!(convert_to_es_profile(Vec::new()).is_none());
+
+        let split_profile = SplitSearchProfile {
+            split_id: "split-1".to_string(),
+            warmup_micros: 3,
+            search_micros: 2,
+            aggregation_micros: 1,
+            num_bytes_downloaded: 1_024,
+            leaf_cache_hit: false,
+        };
+        let es_profile = convert_to_es_profile(vec![split_profile]).unwrap();
+        let es_profile_json = serde_json::to_value(es_profile).unwrap();
+        let expected_es_profile_json = serde_json::json!({
+            "shards": [{
+                "id": "split-1",
+                "warmup_time_in_nanos": 3_000,
+                "search_time_in_nanos": 2_000,
+                "aggregation_time_in_nanos": 1_000,
+                "downloaded_bytes": 1_024,
+                "cache_hit": false,
+            }]
+        });
+        assert_eq!(es_profile_json, expected_es_profile_json);
+    }
+}
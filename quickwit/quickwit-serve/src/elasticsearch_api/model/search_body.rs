@@ -17,15 +17,16 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 
-use quickwit_proto::search::SortOrder;
+use quickwit_proto::search::{SortMissing, SortOrder};
 use quickwit_query::{ElasticQueryDsl, OneFieldMap};
 use serde::de::{MapAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
+use serde_with::{serde_as, OneOrMany, PreferMany};
 
-use super::ElasticDateFormat;
+use super::{ElasticDateFormat, ElasticSortMissing, PointInTimeBody, SuggesterBody};
 use crate::elasticsearch_api::model::{default_elasticsearch_sort_order, SortField};
 use crate::elasticsearch_api::TrackTotalHits;
 
@@ -36,6 +37,7 @@ enum FieldSortParamsForDeser {
     Object {
         order: Option<SortOrder>,
         format: Option<ElasticDateFormat>,
+        missing: Option<ElasticSortMissing>,
     },
     String(SortOrder),
 }
@@ -46,10 +48,16 @@ impl From<FieldSortParamsForDeser> for FieldSortParams {
             FieldSortParamsForDeser::Object {
                 order,
                 format: date_format,
-            } => FieldSortParams { order, date_format },
+                missing,
+            } => FieldSortParams {
+                order,
+                date_format,
+                missing,
+            },
             FieldSortParamsForDeser::String(order) => FieldSortParams {
                 order: Some(order),
                 date_format: None,
+                missing: None,
             },
         }
     }
@@ -64,6 +72,8 @@ struct FieldSortParams {
     #[serde(default)]
     #[serde(rename = "format")]
     pub date_format: Option<ElasticDateFormat>,
+    #[serde(default)]
+    pub missing: Option<ElasticSortMissing>,
 }
 
 #[derive(Debug, Default, Clone, Deserialize, PartialEq)]
@@ -86,18 +96,158 @@ pub struct SearchBody {
     pub stored_fields: Option<BTreeSet<String>>,
     #[serde(default)]
     pub search_after: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub highlight: Option<Highlight>,
+    #[serde(default)]
+    pub pit: Option<PointInTimeBody>,
+    #[serde(default)]
+    pub collapse: Option<Collapse>,
+    #[serde(default)]
+    pub timeout: Option<String>,
+    #[serde(default)]
+    pub suggest: BTreeMap<String, SuggesterBody>,
+    #[serde(default)]
+    pub explain: Option<bool>,
+    #[serde(default)]
+    pub profile: bool,
+    #[serde(default)]
+    pub _source: Option<SourceFilter>,
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_fields")]
+    pub fields: Vec<String>,
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_fields")]
+    pub docvalue_fields: Vec<String>,
 
     // Ignored values, only here for compatibility with OpenSearch Dashboards.
     #[serde(default)]
-    pub _source: serde::de::IgnoredAny,
+    pub script_fields: serde::de::IgnoredAny,
+    #[serde(default)]
+    pub version: serde::de::IgnoredAny,
+}
+
+/// Highlighting parameters of a search request.
+///
+/// A single fragment is returned per field value. Options specific to a field, as well as the
+/// other highlighting parameters (`number_of_fragments`, `type`, ...), are ignored.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct Highlight {
     #[serde(default)]
-    pub docvalue_fields: serde::de::IgnoredAny,
+    #[serde(deserialize_with = "deserialize_highlight_fields")]
+    pub fields: Vec<String>,
     #[serde(default)]
-    pub script_fields: serde::de::IgnoredAny,
+    pub pre_tags: Option<Vec<String>>,
     #[serde(default)]
-    pub highlight: serde::de::IgnoredAny,
+    pub post_tags: Option<Vec<String>>,
     #[serde(default)]
-    pub version: serde::de::IgnoredAny,
+    pub fragment_size: Option<u32>,
+}
+
+/// Field collapsing parameters of a search request.
+///
+/// A single `inner_hits` definition is supported, of which only the `name` and the `size` are
+/// taken into account.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Collapse {
+    pub field: String,
+    #[serde(default)]
+    pub inner_hits: Option<CollapseInnerHits>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CollapseInnerHits {
+    pub name: String,
+    #[serde(default = "default_inner_hits_size")]
+    pub size: u32,
+}
+
+fn default_inner_hits_size() -> u32 {
+    3
+}
+
+/// `_source` parameter of a search request, selecting the parts of the source of the documents
+/// returned with the hits.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum SourceFilter {
+    Enabled(bool),
+    Field(String),
+    Fields(Vec<String>),
+    IncludesExcludes(SourceIncludesExcludes),
+}
+
+#[serde_as]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceIncludesExcludes {
+    #[serde_as(deserialize_as = "Option<OneOrMany<_, PreferMany>>")]
+    #[serde(default)]
+    #[serde(alias = "include")]
+    pub includes: Option<Vec<String>>,
+    #[serde_as(deserialize_as = "Option<OneOrMany<_, PreferMany>>")]
+    #[serde(default)]
+    #[serde(alias = "exclude")]
+    pub excludes: Option<Vec<String>>,
+}
+
+impl SourceFilter {
+    /// Returns the paths to include and the paths to exclude from the source of the documents.
+    ///
+    /// `_source: false` includes no path at all, so that the hits carry an empty source.
+    pub fn into_includes_excludes(self) -> (Option<Vec<String>>, Option<Vec<String>>) {
+        match self {
+            SourceFilter::Enabled(true) => (None, None),
+            SourceFilter::Enabled(false) => (Some(Vec::new()), None),
+            SourceFilter::Field(field) => (Some(vec![field]), None),
+            SourceFilter::Fields(fields) => (Some(fields), None),
+            SourceFilter::IncludesExcludes(includes_excludes) => {
+                (includes_excludes.includes, includes_excludes.excludes)
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FieldAndFormatForDeser {
+    Name(String),
+    Object { field: String },
+}
+
+/// ES accepts the retrieved fields and docvalue fields either as names or as
+/// `{"field": ..., "format": ...}` objects. The format is ignored.
+fn deserialize_fields<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where D: Deserializer<'de> {
+    let field_names = Vec::<FieldAndFormatForDeser>::deserialize(deserializer)?
+        .into_iter()
+        .map(|field_and_format| match field_and_format {
+            FieldAndFormatForDeser::Name(field) | FieldAndFormatForDeser::Object { field } => field,
+        })
+        .collect();
+    Ok(field_names)
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HighlightFieldsForDeser {
+    Object(serde_json::Map<String, serde_json::Value>),
+    Array(Vec<serde_json::Map<String, serde_json::Value>>),
+}
+
+/// ES accepts the highlighted fields either as an object, or as an array of single-field objects
+/// to preserve their order.
+fn deserialize_highlight_fields<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where D: Deserializer<'de> {
+    let field_names = match HighlightFieldsForDeser::deserialize(deserializer)? {
+        HighlightFieldsForDeser::Object(fields) => {
+            fields.into_iter().map(|(name, _)| name).collect()
+        }
+        HighlightFieldsForDeser::Array(fields) => fields
+            .into_iter()
+            .flat_map(|field| field.into_iter().map(|(name, _)| name))
+            .collect(),
+    };
+    Ok(field_names)
 }
 
 struct FieldSortVecVisitor;
@@ -118,6 +268,7 @@ impl From<StringOrMapFieldSort> for SortField {
                     field: field_name,
                     order,
                     date_format: None,
+                    missing: SortMissing::Last,
                 }
             }
             StringOrMapFieldSort::Sort(sort) => {
@@ -129,6 +280,11 @@ impl From<StringOrMapFieldSort> for SortField {
                     field: sort.field,
                     order,
                     date_format: sort.value.date_format,
+                    missing: sort
+                        .value
+                        .missing
+                        .map(SortMissing::from)
+                        .unwrap_or(SortMissing::Last),
                 }
             }
         }
@@ -164,6 +320,10 @@ impl<'de> Visitor<'de> for FieldSortVecVisitor {
                 field: field_sort_key,
                 order: sort_order,
                 date_format: field_sort_params.date_format,
+                missing: field_sort_params
+                    .missing
+                    .map(SortMissing::from)
+                    .unwrap_or(SortMissing::Last),
             });
         }
         Ok(sort_fields)
@@ -261,6 +421,130 @@ mod tests {
         assert_eq!(field_sorts[3].order, SortOrder::Asc);
     }
 
+    #[test]
+    fn test_sort_field_missing() {
+        let json = r#"
+        {
+            "sort": [
+                { "timestamp": { "order": "desc", "missing": "_first" } },
+                { "uid": { "missing": "_last" } },
+                "status"
+            ]
+        }
+        "#;
+        let search_body: SearchBody = serde_json::from_str(json).unwrap();
+        let field_sorts = search_body.sort.unwrap();
+        assert_eq!(field_sorts.len(), 3);
+        assert_eq!(field_sorts[0].missing, SortMissing::First);
+        assert_eq!(field_sorts[1].missing, SortMissing::Last);
+        assert_eq!(field_sorts[2].missing, SortMissing::Last);
+
+        let json = r#"
+        {
+            "sort": [
+                { "timestamp": { "order": "desc", "missing": 0 } }
+            ]
+        }
+        "#;
+        serde_json::from_str::<SearchBody>(json).unwrap_err();
+    }
+
+    #[test]
+    fn test_highlight() {
+        let json = r#"
+        {
+            "highlight": {
+                "pre_tags": ["<em>"],
+                "post_tags": ["</em>"],
+                "fields": { "body": {}, "title": { "number_of_fragments": 1 } },
+                "fragment_size": 50,
+                "require_field_match": false
+            }
+        }
+        "#;
+        let search_body: SearchBody = serde_json::from_str(json).unwrap();
+        let highlight = search_body.highlight.unwrap();
+        assert_eq!(
+            highlight.fields,
+            vec!["body".to_string(), "title".to_string()]
+        );
+        assert_eq!(highlight.pre_tags, Some(vec!["<em>".to_string()]));
+        assert_eq!(highlight.post_tags, Some(vec!["</em>".to_string()]));
+        assert_eq!(highlight.fragment_size, Some(50));
+
+        let json = r#"{ "highlight": { "fields": [{ "title": {} }, { "body": {} }] } }"#;
+        let search_body: SearchBody = serde_json::from_str(json).unwrap();
+        let highlight = search_body.highlight.unwrap();
+        assert_eq!(
+            highlight.fields,
+            vec!["title".to_string(), "body".to_string()]
+        );
+        assert_eq!(highlight.pre_tags, None);
+        assert_eq!(highlight.fragment_size, None);
+    }
+
+    #[test]
+    fn test_collapse() {
+        let json = r#"{ "collapse": { "field": "user", "inner_hits": { "name": "recent" } } }"#;
+        let search_body: SearchBody = serde_json::from_str(json).unwrap();
+        let collapse = search_body.collapse.unwrap();
+        assert_eq!(collapse.field, "user");
+        assert_eq!(
+            collapse.inner_hits,
+            Some(CollapseInnerHits {
+                name: "recent".to_string(),
+                size: 3,
+            })
+        );
+
+        let json = r#"{ "collapse": { "field": "user" } }"#;
+        let search_body: SearchBody = serde_json::from_str(json).unwrap();
+        assert_eq!(search_body.collapse.unwrap().inner_hits, None);
+    }
+
+    #[test]
+    fn test_source_filter() {
+        let search_body: SearchBody = serde_json::from_str(r#"{ "_source": false }"#).unwrap();
+        assert_eq!(
+            search_body._source.unwrap().into_includes_excludes(),
+            (Some(Vec::new()), None)
+        );
+
+        let search_body: SearchBody = serde_json::from_str(r#"{ "_source": "user.*" }"#).unwrap();
+        assert_eq!(
+            search_body._source.unwrap().into_includes_excludes(),
+            (Some(vec!["user.*".to_string()]), None)
+        );
+
+        let json = r#"{ "_source": { "includes": ["user", "title"], "exclude": "user.email" } }"#;
+        let search_body: SearchBody = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            search_body._source.unwrap().into_includes_excludes(),
+            (
+                Some(vec!["user".to_string(), "title".to_string()]),
+                Some(vec!["user.email".to_string()])
+            )
+        );
+
+        let json = r#"{ "_source": { "includes": ["user"], "unknown": [] } }"#;
+        serde_json::from_str::<SearchBody>(json).unwrap_err();
+    }
+
+    #[test]
+    fn test_fields() {
+        let json =
+            r#"{ "fields": ["user.id", { "field": "timestamp", "format": "epoch_millis" }] }"#;
+        let search_body: SearchBody = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            search_body.fields,
+            vec!["user.id".to_string(), "timestamp".to_string()]
+        );
+
+        let json = r#"{ "docvalue_fields": [{ "field": "timestamp", "format": "date_time" }] }"#;
+        let search_body: SearchBody = serde_json::from_str(json).unwrap();
+        assert_eq!(search_body.docvalue_fields, vec!["timestamp".to_string()]);
+    }
+
     #[test]
     fn test_unknown_field_behaviour() {
         let json = r#"
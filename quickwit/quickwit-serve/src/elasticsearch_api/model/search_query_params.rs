@@ -21,7 +21,7 @@ use std::fmt;
 use std::str::FromStr;
 use std::time::Duration;
 
-use quickwit_proto::search::SortOrder;
+use quickwit_proto::search::{SearchPriority, SortMissing, SortOrder};
 use quickwit_query::BooleanOperand;
 use quickwit_search::SearchError;
 use serde::{Deserialize, Serialize};
@@ -108,6 +108,10 @@ pub struct SearchQueryParams {
     pub preference: Option<String>,
     #[serde(default)]
     pub pretty: Option<bool>,
+    /// Priority of the search: `interactive` (default) or `batch`.
+    /// This is not part of the official Elasticsearch API.
+    #[serde(default)]
+    pub priority: Option<SearchPriority>,
     #[serde(default)]
     pub q: Option<String>,
     #[serde(default)]
@@ -154,6 +158,9 @@ pub struct SearchQueryParams {
     pub typed_keys: Option<bool>,
     #[serde(default)]
     pub version: Option<bool>,
+    /// ID of the API key passed in the `Authorization` header, if any.
+    #[serde(skip)]
+    pub api_key_id: Option<String>,
 }
 
 #[serde_with::skip_serializing_none]
@@ -245,6 +252,7 @@ fn parse_sort_field_str(sort_field_str: &str) -> Result<SortField, SearchError>
             field: field.to_string(),
             order,
             date_format: None,
+            missing: SortMissing::Last,
         })
     } else {
         let order = default_elasticsearch_sort_order(sort_field_str);
@@ -252,6 +260,7 @@ fn parse_sort_field_str(sort_field_str: &str) -> Result<SortField, SearchError>
             field: sort_field_str.to_string(),
             order,
             date_format: None,
+            missing: SortMissing::Last,
         })
     }
 }
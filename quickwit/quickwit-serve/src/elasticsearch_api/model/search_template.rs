@@ -0,0 +1,140 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+/// Templates can be provided either as a string or as a JSON object. Both are
+/// kept as a string since the rendered template is only parsed after
+/// substitution.
+fn deserialize_template_source<'de, D>(deserializer: D) -> Result<String, D::Error>
+where D: Deserializer<'de> {
+    match JsonValue::deserialize(deserializer)? {
+        JsonValue::String(source) => Ok(source),
+        source @ JsonValue::Object(_) => Ok(source.to_string()),
+        _ => Err(serde::de::Error::custom(
+            "template source must be a string or an object",
+        )),
+    }
+}
+
+fn deserialize_opt_template_source<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where D: Deserializer<'de> {
+    deserialize_template_source(deserializer).map(Some)
+}
+
+/// Body of the `_scripts/{id}` endpoints.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StoredScriptBody {
+    pub script: StoredScript,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredScript {
+    pub lang: String,
+    #[serde(deserialize_with = "deserialize_template_source")]
+    pub source: String,
+}
+
+/// Returns JSON in the format:
+///
+/// {
+///   "_id": "my-template",
+///   "found": true,
+///   "script": {
+///     "lang": "mustache",
+///     "source": "..."
+///   }
+/// }
+#[derive(Debug, Clone, Serialize)]
+pub struct GetStoredScriptResponse {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub found: bool,
+    pub script: StoredScript,
+}
+
+/// Body of the `{index}/_search/template` endpoint.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SearchTemplateBody {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_opt_template_source")]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub params: JsonMap<String, JsonValue>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_script_body_deserialize() {
+        let stored_script_body: StoredScriptBody = serde_json::from_str(
+            r#"{"script": {"lang": "mustache", "source": "{\"query\": {\"match_all\": {}}}"}}"#,
+        )
+        .unwrap();
+        assert_eq!(stored_script_body.script.lang, "mustache");
+        assert_eq!(
+            stored_script_body.script.source,
+            r#"{"query": {"match_all": {}}}"#
+        );
+
+        let stored_script_body: StoredScriptBody = serde_json::from_str(
+            r#"{"script": {"lang": "mustache", "source": {"query": {"match": {"title": "{{q}}"}}}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            stored_script_body.script.source,
+            r#"{"query":{"match":{"title":"{{q}}"}}}"#
+        );
+
+        let error = serde_json::from_str::<StoredScriptBody>(
+            r#"{"script": {"lang": "mustache", "source": 3}}"#,
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("template source must be a string or an object"));
+    }
+
+    #[test]
+    fn test_search_template_body_deserialize() {
+        let search_template_body: SearchTemplateBody =
+            serde_json::from_str(r#"{"id": "my-template", "params": {"q": "hello"}}"#).unwrap();
+        assert_eq!(search_template_body.id.as_deref(), Some("my-template"));
+        assert!(search_template_body.source.is_none());
+        assert_eq!(search_template_body.params["q"], "hello");
+
+        let search_template_body: SearchTemplateBody =
+            serde_json::from_str(r#"{"source": {"size": 3}}"#).unwrap();
+        assert_eq!(
+            search_template_body.source.as_deref(),
+            Some(r#"{"size":3}"#)
+        );
+        assert!(search_template_body.params.is_empty());
+
+        serde_json::from_str::<SearchTemplateBody>(r#"{"id": "my-template", "explain": true}"#)
+            .unwrap_err();
+    }
+}
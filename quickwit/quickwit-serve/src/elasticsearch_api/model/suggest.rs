@@ -0,0 +1,243 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use elasticsearch_dsl::search::SearchResponse as ElasticsearchResponse;
+use quickwit_proto::search::{CompletionSuggester, CompletionSuggestion};
+use quickwit_search::SearchError;
+use serde::{Deserialize, Serialize};
+
+use super::{ElasticsearchError, ElasticsearchProfile};
+
+/// A suggester of the `suggest` section of a search request.
+///
+/// Only the `completion` suggester is supported.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SuggesterBody {
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Alias of `prefix`.
+    #[serde(default)]
+    pub text: Option<String>,
+    pub completion: CompletionSuggesterBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompletionSuggesterBody {
+    pub field: String,
+    #[serde(default = "default_suggester_size")]
+    pub size: u32,
+    /// The suggestions are always deduplicated.
+    #[serde(default)]
+    pub skip_duplicates: bool,
+}
+
+fn default_suggester_size() -> u32 {
+    5
+}
+
+/// A search response with the suggestions of the `suggest` section of the request, and the
+/// profile of the search if requested.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ElasticsearchSuggestResponse {
+    #[serde(flatten)]
+    pub response: ElasticsearchResponse,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub suggest: BTreeMap<String, Vec<SuggestEntry>>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ElasticsearchProfile>,
+}
+
+impl From<ElasticsearchResponse> for ElasticsearchSuggestResponse {
+    fn from(response: ElasticsearchResponse) -> Self {
+        ElasticsearchSuggestResponse {
+            response,
+            suggest: BTreeMap::new(),
+            profile: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuggestEntry {
+    pub text: String,
+    pub offset: usize,
+    pub length: usize,
+    pub options: Vec<SuggestOption>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuggestOption {
+    pub text: String,
+}
+
+pub(crate) fn build_suggesters_for_es_api(
+    suggest: BTreeMap<String, SuggesterBody>,
+) -> Result<Vec<CompletionSuggester>, ElasticsearchError> {
+    suggest
+        .into_iter()
+        .map(|(name, suggester_body)| {
+            let prefix = suggester_body
+                .prefix
+                .or(suggester_body.text)
+                .ok_or_else(|| {
+                    SearchError::InvalidArgument(format!("suggester `{name}` must have a `prefix`"))
+                })?;
+            Ok(CompletionSuggester {
+                name,
+                field: suggester_body.completion.field,
+                prefix,
+                size: suggester_body.completion.size,
+            })
+        })
+        .collect()
+}
+
+/// Converts the suggestions of a search response. The suggesters are the ones of the request.
+pub(crate) fn convert_to_es_suggest(
+    suggesters: &[CompletionSuggester],
+    suggestions: Vec<CompletionSuggestion>,
+) -> BTreeMap<String, Vec<SuggestEntry>> {
+    let mut options_per_name: BTreeMap<String, Vec<String>> = suggestions
+        .into_iter()
+        .map(|suggestion| (suggestion.name, suggestion.options))
+        .collect();
+    suggesters
+        .iter()
+        .map(|suggester| {
+            let options = options_per_name
+                .remove(&suggester.name)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|text| SuggestOption { text })
+                .collect();
+            let suggest_entry = SuggestEntry {
+                text: suggester.prefix.clone(),
+                offset: 0,
+                length: suggester.prefix.chars().count(),
+                options,
+            };
+            (suggester.name.clone(), vec![suggest_entry])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_build_suggesters_for_es_api() {
+        let suggest: BTreeMap<String, SuggesterBody> = serde_json::from_value(json!({
+            "song-suggest": {
+                "prefix": "nir",
+                "completion": {
+                    "field": "suggest",
+                    "skip_duplicates": true
+                }
+            },
+            "artist-suggest": {
+                "text": "nev",
+                "completion": {
+                    "field": "artist_suggest",
+                    "size": 10
+                }
+            }
+        }))
+        .unwrap();
+        let suggesters = build_suggesters_for_es_api(suggest).unwrap();
+        assert_eq!(
+            suggesters,
+            vec![
+                CompletionSuggester {
+                    name: "artist-suggest".to_string(),
+                    field: "artist_suggest".to_string(),
+                    prefix: "nev".to_string(),
+                    size: 10,
+                },
+                CompletionSuggester {
+                    name: "song-suggest".to_string(),
+                    field: "suggest".to_string(),
+                    prefix: "nir".to_string(),
+                    size: 5,
+                },
+            ]
+        );
+
+        let suggest: BTreeMap<String, SuggesterBody> = serde_json::from_value(json!({
+            "song-suggest": {
+                "completion": {
+                    "field": "suggest"
+                }
+            }
+        }))
+        .unwrap();
+        let error = build_suggesters_for_es_api(suggest).unwrap_err();
+        assert!(error
+            .error
+            .reason
+            .unwrap()
+            .contains("suggester `song-suggest` must have a `prefix`"));
+
+        // Fuzzy completion is not supported.
+        serde_json::from_value::<BTreeMap<String, SuggesterBody>>(json!({
+            "song-suggest": {
+                "prefix": "nir",
+                "completion": {
+                    "field": "suggest",
+                    "fuzzy": {"fuzziness": 2}
+                }
+            }
+        }))
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_convert_to_es_suggest() {
+        let suggesters = vec![CompletionSuggester {
+            name: "song-suggest".to_string(),
+            field: "suggest".to_string(),
+            prefix: "Nir".to_string(),
+            size: 5,
+        }];
+        let suggestions = vec![CompletionSuggestion {
+            name: "song-suggest".to_string(),
+            options: vec!["nirvana".to_string()],
+        }];
+        let suggest = convert_to_es_suggest(&suggesters, suggestions);
+        assert_eq!(
+            serde_json::to_value(suggest).unwrap(),
+            json!({
+                "song-suggest": [{
+                    "text": "Nir",
+                    "offset": 0,
+                    "length": 3,
+                    "options": [{"text": "nirvana"}]
+                }]
+            })
+        );
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use quickwit_proto::search::{ListTermsRequest, ListTermsResponse};
+use quickwit_search::SearchError;
+use serde::{Deserialize, Serialize};
+
+use super::ElasticsearchError;
+
+/// Maximum number of terms that can be requested in a single `_terms_enum` request.
+const MAX_TERMS_ENUM_SIZE: u64 = 10_000;
+
+#[serde_with::skip_serializing_none]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TermsEnumQueryParams {
+    /// Non-ES Parameter. If set, restricts splits to documents with a `time_range.start >=
+    /// start_timestamp`.
+    pub start_timestamp: Option<i64>,
+    /// Non-ES Parameter. If set, restricts splits to documents with a `time_range.end <
+    /// end_timestamp``.
+    pub end_timestamp: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TermsEnumRequestBody {
+    #[serde(default)]
+    pub field: String,
+    /// Prefix of the returned terms.
+    #[serde(default)]
+    pub string: Option<String>,
+    #[serde(default = "default_terms_enum_size")]
+    pub size: u64,
+    /// If set, only the terms strictly greater than this value are returned.
+    #[serde(default)]
+    pub search_after: Option<String>,
+    #[serde(default)]
+    // unsupported currently
+    pub case_insensitive: bool,
+    #[serde(default)]
+    // unsupported currently
+    pub index_filter: serde_json::Value,
+}
+
+fn default_terms_enum_size() -> u64 {
+    10
+}
+
+impl Default for TermsEnumRequestBody {
+    fn default() -> Self {
+        TermsEnumRequestBody {
+            field: String::new(),
+            string: None,
+            size: default_terms_enum_size(),
+            search_after: None,
+            case_insensitive: false,
+            index_filter: serde_json::Value::Null,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TermsEnumResponse {
+    pub terms: Vec<String>,
+    /// Whether all the splits were searched. Failed splits fail the whole request, so this is
+    /// always true.
+    pub complete: bool,
+}
+
+/// Returns the smallest key greater than all the keys starting with `prefix`, or `None` if there
+/// is no such key (e.g. the prefix is empty).
+fn prefix_end_key(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut end_key = prefix.to_vec();
+    while let Some(last_byte) = end_key.pop() {
+        if last_byte < u8::MAX {
+            end_key.push(last_byte + 1);
+            return Some(end_key);
+        }
+    }
+    None
+}
+
+/// Builds the list terms request of a `_terms_enum` request. Returns `None` if no term can match,
+/// i.e. if `search_after` is past all the terms starting with the prefix.
+pub fn build_list_terms_request_for_es_api(
+    index_id_patterns: Vec<String>,
+    query_params: TermsEnumQueryParams,
+    request_body: TermsEnumRequestBody,
+) -> Result<Option<ListTermsRequest>, ElasticsearchError> {
+    if request_body.field.is_empty() {
+        return Err(SearchError::InvalidArgument(
+            "`_terms_enum` request must define a `field`".to_string(),
+        )
+        .into());
+    }
+    if request_body.case_insensitive {
+        return Err(SearchError::InvalidArgument(
+            "`case_insensitive` is not supported in `_terms_enum` requests".to_string(),
+        )
+        .into());
+    }
+    if request_body.size > MAX_TERMS_ENUM_SIZE {
+        return Err(SearchError::InvalidArgument(format!(
+            "`size` must be less than or equal to {MAX_TERMS_ENUM_SIZE}, got {}",
+            request_body.size
+        ))
+        .into());
+    }
+    let prefix: Vec<u8> = request_body.string.unwrap_or_default().into_bytes();
+    let end_key = prefix_end_key(&prefix);
+    let start_key = match request_body.search_after {
+        Some(search_after) if search_after.as_bytes() >= prefix.as_slice() => {
+            // The smallest key strictly greater than `search_after`.
+            let mut start_key = search_after.into_bytes();
+            start_key.push(0u8);
+            start_key
+        }
+        _ => prefix,
+    };
+    if let Some(end_key) = &end_key {
+        if &start_key >= end_key {
+            return Ok(None);
+        }
+    }
+    let list_terms_request = ListTermsRequest {
+        index_id_patterns,
+        field: request_body.field,
+        start_timestamp: query_params.start_timestamp,
+        end_timestamp: query_params.end_timestamp,
+        max_hits: Some(request_body.size),
+        start_key: (!start_key.is_empty()).then_some(start_key),
+        end_key,
+    };
+    Ok(Some(list_terms_request))
+}
+
+pub fn convert_to_es_terms_enum_response(
+    list_terms_response: ListTermsResponse,
+) -> Result<TermsEnumResponse, ElasticsearchError> {
+    let terms = list_terms_response
+        .terms
+        .iter()
+        .map(|term_bytes| {
+            tantivy::Term::wrap(term_bytes)
+                .value()
+                .as_str()
+                .map(|term| term.to_string())
+                .ok_or_else(|| {
+                    SearchError::InvalidArgument(
+                        "`_terms_enum` is only supported on text fields".to_string(),
+                    )
+                })
+        })
+        .collect::<Result<Vec<String>, SearchError>>()?;
+    Ok(TermsEnumResponse {
+        terms,
+        complete: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_terms_request_for_test(
+        string: Option<&str>,
+        search_after: Option<&str>,
+    ) -> Option<ListTermsRequest> {
+        let request_body = TermsEnumRequestBody {
+            field: "service".to_string(),
+            string: string.map(ToString::to_string),
+            search_after: search_after.map(ToString::to_string),
+            ..Default::default()
+        };
+        build_list_terms_request_for_es_api(
+            vec!["my-index".to_string()],
+            TermsEnumQueryParams::default(),
+            request_body,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_prefix_end_key() {
+        assert_eq!(prefix_end_key(b""), None);
+        assert_eq!(prefix_end_key(b"ab"), Some(b"ac".to_vec()));
+        assert_eq!(prefix_end_key(b"a\xff"), Some(b"b".to_vec()));
+        assert_eq!(prefix_end_key(b"\xff\xff"), None);
+    }
+
+    #[test]
+    fn test_build_list_terms_request_for_es_api() {
+        let list_terms_request = list_terms_request_for_test(None, None).unwrap();
+        assert_eq!(list_terms_request.field, "service");
+        assert_eq!(list_terms_request.max_hits, Some(10));
+        assert_eq!(list_terms_request.start_key, None);
+        assert_eq!(list_terms_request.end_key, None);
+
+        let list_terms_request = list_terms_request_for_test(Some("qui"), None).unwrap();
+        assert_eq!(list_terms_request.start_key, Some(b"qui".to_vec()));
+        assert_eq!(list_terms_request.end_key, Some(b"quj".to_vec()));
+
+        let list_terms_request = list_terms_request_for_test(Some("qui"), Some("quick")).unwrap();
+        assert_eq!(list_terms_request.start_key, Some(b"quick\0".to_vec()));
+        assert_eq!(list_terms_request.end_key, Some(b"quj".to_vec()));
+
+        // `search_after` before the prefix.
+        let list_terms_request = list_terms_request_for_test(Some("qui"), Some("a")).unwrap();
+        assert_eq!(list_terms_request.start_key, Some(b"qui".to_vec()));
+
+        // `search_after` past the terms starting with the prefix.
+        assert!(list_terms_request_for_test(Some("qui"), Some("quz")).is_none());
+    }
+
+    #[test]
+    fn test_build_list_terms_request_for_es_api_invalid() {
+        let error = build_list_terms_request_for_es_api(
+            vec!["my-index".to_string()],
+            TermsEnumQueryParams::default(),
+            TermsEnumRequestBody::default(),
+        )
+        .unwrap_err();
+        assert_eq!(error.status, hyper::StatusCode::BAD_REQUEST);
+
+        let request_body = TermsEnumRequestBody {
+            field: "service".to_string(),
+            size: 100_000,
+            ..Default::default()
+        };
+        let error = build_list_terms_request_for_es_api(
+            vec!["my-index".to_string()],
+            TermsEnumQueryParams::default(),
+            request_body,
+        )
+        .unwrap_err();
+        assert_eq!(error.status, hyper::StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_convert_to_es_terms_enum_response() {
+        let field = tantivy::schema::Field::from_field_id(0);
+        let list_terms_response = ListTermsResponse {
+            num_hits: 2,
+            terms: vec![
+                tantivy::Term::from_field_text(field, "quickwit")
+                    .serialized_term()
+                    .to_vec(),
+                tantivy::Term::from_field_text(field, "quickstart")
+                    .serialized_term()
+                    .to_vec(),
+            ],
+            elapsed_time_micros: 0,
+            errors: Vec::new(),
+        };
+        let terms_enum_response = convert_to_es_terms_enum_response(list_terms_response).unwrap();
+        assert_eq!(
+            terms_enum_response,
+            TermsEnumResponse {
+                terms: vec!["quickwit".to_string(), "quickstart".to_string()],
+                complete: true,
+            }
+        );
+    }
+}
@@ -17,7 +17,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::str::from_utf8;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -26,46 +26,91 @@ use bytes::Bytes;
 use elasticsearch_dsl::search::{Hit as ElasticHit, SearchResponse as ElasticsearchResponse};
 use elasticsearch_dsl::{HitsMetadata, Source, TotalHits, TotalHitsRelation};
 use futures_util::StreamExt;
+use hyper::header::CONTENT_TYPE;
 use hyper::StatusCode;
 use itertools::Itertools;
+use quickwit_cluster::Cluster;
 use quickwit_common::truncate_str;
-use quickwit_config::{validate_index_id_pattern, NodeConfig};
-use quickwit_index_management::IndexService;
+use quickwit_config::service::QuickwitService;
+use quickwit_config::{
+    build_doc_mapper, load_index_config_update, validate_index_id_pattern, ConfigFormat,
+    IndexConfig, IndexTemplate, NodeConfig, VersionedIndexConfig,
+};
+use quickwit_datetime::{
+    parse_date_time_str, resolve_java_datetime_format_alias, DateMathParser, DateMathRounding,
+    DateTimeInputFormat, TantivyDateTime,
+};
+use quickwit_index_management::{IndexService, IndexServiceError};
 use quickwit_metastore::*;
-use quickwit_proto::metastore::MetastoreServiceClient;
+use quickwit_proto::metastore::{
+    serde_utils, CreateIndexTemplateRequest, DeleteIndexTemplatesRequest, IndexMetadataRequest,
+    ListIndexTemplatesRequest, MetastoreError, MetastoreService, MetastoreServiceClient,
+    UpdateIndexRequest,
+};
 use quickwit_proto::search::{
-    CountHits, ListFieldsResponse, PartialHit, ScrollRequest, SearchResponse, SortByValue,
-    SortDatetimeFormat,
+    CountHits, ListFieldsResponse, NamedQuery, PartialHit, PointInTime, ScrollRequest,
+    SearchPriority, SearchResponse, SnippetOptions, SortByValue, SortDatetimeFormat,
 };
-use quickwit_proto::types::IndexUid;
+use quickwit_proto::types::{DocMappingUid, IndexUid};
+use quickwit_proto::ServiceError;
 use quickwit_query::query_ast::{BoolQuery, QueryAst, UserInputQuery};
-use quickwit_query::BooleanOperand;
+use quickwit_query::tokenizers::TokenizerManager;
+use quickwit_query::{create_default_quickwit_tokenizer_manager, BooleanOperand, ElasticQueryDsl};
 use quickwit_search::{list_all_splits, resolve_index_patterns, SearchError, SearchService};
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use warp::{Filter, Rejection};
+use serde_json::{json, Value as JsonValue};
+use tantivy::schema::{FieldType, Schema};
+use tantivy::tokenizer::TextAnalyzer;
+use time::OffsetDateTime;
+use warp::{Filter, Rejection, Reply};
 
 use super::filter::{
-    elastic_cat_indices_filter, elastic_cluster_info_filter, elastic_delete_index_filter,
-    elastic_field_capabilities_filter, elastic_index_cat_indices_filter,
+    elastic_analyze_filter, elastic_async_search_status_filter, elastic_cat_count_filter,
+    elastic_cat_health_filter, elastic_cat_indices_filter, elastic_cat_nodes_filter,
+    elastic_close_point_in_time_filter, elastic_cluster_info_filter,
+    elastic_delete_async_search_filter, elastic_delete_index_filter,
+    elastic_delete_index_template_filter, elastic_delete_stored_script_filter,
+    elastic_field_capabilities_filter, elastic_get_aliases_filter, elastic_get_async_search_filter,
+    elastic_get_index_templates_filter, elastic_get_stored_script_filter,
+    elastic_index_analyze_filter, elastic_index_cat_count_filter, elastic_index_cat_indices_filter,
     elastic_index_count_filter, elastic_index_field_capabilities_filter,
-    elastic_index_search_filter, elastic_index_stats_filter, elastic_multi_search_filter,
-    elastic_resolve_index_filter, elastic_scroll_filter, elastic_stats_filter,
+    elastic_index_mapping_filter, elastic_index_put_mapping_filter, elastic_index_search_filter,
+    elastic_index_search_template_filter, elastic_index_stats_filter,
+    elastic_index_terms_enum_filter, elastic_mapping_filter, elastic_multi_search_filter,
+    elastic_open_point_in_time_filter, elastic_put_index_template_filter,
+    elastic_put_stored_script_filter, elastic_resolve_index_filter, elastic_scroll_filter,
+    elastic_stats_filter, elastic_submit_async_search_filter, elastic_update_aliases_filter,
     elasticsearch_filter,
 };
+use super::histogram::rewrite_histogram_params;
+use super::index_alias::{get_aliases, resolve_search_target, IndexAliasStore};
 use super::model::{
-    build_list_field_request_for_es_api, convert_to_es_field_capabilities_response,
-    CatIndexQueryParams, DeleteQueryParams, ElasticsearchCatIndexResponse, ElasticsearchError,
+    analyze_texts_for_es_api, build_list_field_request_for_es_api,
+    build_list_terms_request_for_es_api, build_suggesters_for_es_api,
+    convert_to_es_field_capabilities_response, convert_to_es_index_template, convert_to_es_mapping,
+    convert_to_es_profile, convert_to_es_suggest, convert_to_es_terms_enum_response,
+    convert_to_quickwit_index_template, merge_es_properties, AliasAction, AliasActionsBody,
+    AliasActionsResponse, AnalyzeRequestBody, AnalyzeResponse, AnalyzeTokenizer,
+    AsyncSearchResponse, AsyncSearchShards, AsyncSearchStatusResponse, CatFormat,
+    CatIndexQueryParams, CatQueryParams, CatResponse, ClosePointInTimeBody,
+    ClosePointInTimeResponse, Collapse, DeleteAsyncSearchResponse, DeleteQueryParams,
+    ElasticException, ElasticsearchCatIndexResponse, ElasticsearchError, ElasticsearchIndexMapping,
     ElasticsearchResolveIndexEntryResponse, ElasticsearchResolveIndexResponse,
-    ElasticsearchStatsResponse, FieldCapabilityQueryParams, FieldCapabilityRequestBody,
-    FieldCapabilityResponse, MultiSearchHeader, MultiSearchQueryParams, MultiSearchResponse,
-    MultiSearchSingleResponse, ScrollQueryParams, SearchBody, SearchQueryParams,
-    SearchQueryParamsCount, StatsResponseEntry,
+    ElasticsearchStatsResponse, ElasticsearchSuggestResponse, FieldCapabilityQueryParams,
+    FieldCapabilityRequestBody, FieldCapabilityResponse, GetAliasesResponse,
+    GetAsyncSearchQueryParams, GetIndexTemplatesResponse, GetStoredScriptResponse, Highlight,
+    IndexTemplateEntry, MultiSearchHeader, MultiSearchQueryParams, MultiSearchResponse,
+    MultiSearchSingleResponse, OpenPointInTimeQueryParams, OpenPointInTimeResponse,
+    PointInTimeBody, PutIndexTemplateBody, PutIndexTemplateQueryParams, PutMappingBody,
+    PutMappingResponse, ScrollQueryParams, SearchBody, SearchQueryParams, SearchQueryParamsCount,
+    SearchTemplateBody, StatsResponseEntry, StoredScriptBody, SubmitAsyncSearchQueryParams,
+    TermsEnumQueryParams, TermsEnumRequestBody, TermsEnumResponse,
 };
+use super::search_template::{render_search_template, StoredScriptStore, MUSTACHE_LANG};
 use super::{make_elastic_api_response, TrackTotalHits};
 use crate::format::BodyFormat;
 use crate::rest::recover_fn;
-use crate::rest_api_response::{RestApiError, RestApiResponse};
+use crate::rest_api_response::RestApiResponse;
 use crate::{with_arg, BuildInfo};
 
 /// Elastic compatible cluster info handler.
@@ -93,24 +138,81 @@ pub fn es_compat_cluster_info_handler(
 }
 
 /// GET or POST _elastic/_search
+///
+/// Only searches on a point in time are supported: the indexes are those of the point in time.
 pub fn es_compat_search_handler(
-    _search_service: Arc<dyn SearchService>,
+    search_service: Arc<dyn SearchService>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     elasticsearch_filter()
-        .then(|_params: SearchQueryParams| async move {
-            // TODO
-            let api_error = RestApiError {
-                status_code: StatusCode::NOT_IMPLEMENTED,
-                message: "_elastic/_search is not supported yet. Please try the index search \
-                          endpoint (_elastic/{index}/search)"
-                    .to_string(),
-            };
-            RestApiResponse::new::<(), _>(
-                &Err(api_error),
-                StatusCode::NOT_IMPLEMENTED,
-                BodyFormat::default(),
-            )
-        })
+        .and(with_arg(search_service))
+        .then(es_compat_search)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// POST _elastic/{index}/_pit
+pub fn es_compat_open_point_in_time_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_open_point_in_time_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_open_point_in_time)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// DELETE _elastic/_pit
+pub fn es_compat_close_point_in_time_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_close_point_in_time_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_close_point_in_time)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// POST _elastic/{index}/_async_search
+pub fn es_compat_submit_async_search_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_submit_async_search_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_submit_async_search)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// GET _elastic/_async_search/{id}
+pub fn es_compat_get_async_search_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_get_async_search_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_get_async_search)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// GET _elastic/_async_search/status/{id}
+pub fn es_compat_async_search_status_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_async_search_status_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_async_search_status)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// DELETE _elastic/_async_search/{id}
+pub fn es_compat_delete_async_search_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_delete_async_search_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_delete_async_search)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
         .recover(recover_fn)
 }
 
@@ -127,6 +229,17 @@ pub fn es_compat_index_field_capabilities_handler(
         .recover(recover_fn)
 }
 
+/// GET or POST _elastic/{index}/_terms_enum
+pub fn es_compat_index_terms_enum_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_index_terms_enum_filter()
+        .and(with_arg(search_service))
+        .then(es_compat_index_terms_enum)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
 /// DELETE _elastic/{index}
 pub fn es_compat_delete_index_handler(
     index_service: IndexService,
@@ -159,6 +272,59 @@ pub fn es_compat_index_stats_handler(
         .recover(recover_fn)
 }
 
+/// GET or POST _elastic/_analyze
+pub fn es_compat_analyze_handler(
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_analyze_filter()
+        .then(es_compat_analyze)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// GET or POST _elastic/{index}/_analyze
+pub fn es_compat_index_analyze_handler(
+    metastore_service: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_index_analyze_filter()
+        .and(with_arg(metastore_service))
+        .then(es_compat_index_analyze)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// GET _elastic/_mapping
+pub fn es_compat_mapping_handler(
+    metastore_service: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_mapping_filter()
+        .and(with_arg(metastore_service))
+        .then(es_compat_mapping)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// GET _elastic/{index}/_mapping
+pub fn es_compat_index_mapping_handler(
+    metastore_service: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_index_mapping_filter()
+        .and(with_arg(metastore_service))
+        .then(es_compat_index_mapping)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// PUT _elastic/{index}/_mapping
+pub fn es_compat_index_put_mapping_handler(
+    metastore_service: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_index_put_mapping_filter()
+        .and(with_arg(metastore_service))
+        .then(es_compat_index_put_mapping)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
 /// GET _elastic/_cat/indices
 pub fn es_compat_cat_indices_handler(
     metastore_service: MetastoreServiceClient,
@@ -166,7 +332,7 @@ pub fn es_compat_cat_indices_handler(
     elastic_cat_indices_filter()
         .and(with_arg(metastore_service))
         .then(es_compat_cat_indices)
-        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .map(make_cat_response)
         .recover(recover_fn)
 }
 
@@ -177,7 +343,51 @@ pub fn es_compat_index_cat_indices_handler(
     elastic_index_cat_indices_filter()
         .and(with_arg(metastore_service))
         .then(es_compat_index_cat_indices)
-        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .map(make_cat_response)
+        .recover(recover_fn)
+}
+
+/// GET _elastic/_cat/count
+pub fn es_compat_cat_count_handler(
+    metastore_service: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_cat_count_filter()
+        .and(with_arg(metastore_service))
+        .then(es_compat_cat_count)
+        .map(make_cat_response)
+        .recover(recover_fn)
+}
+
+/// GET _elastic/_cat/count/{index}
+pub fn es_compat_index_cat_count_handler(
+    metastore_service: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_index_cat_count_filter()
+        .and(with_arg(metastore_service))
+        .then(es_compat_index_cat_count)
+        .map(make_cat_response)
+        .recover(recover_fn)
+}
+
+/// GET _elastic/_cat/health
+pub fn es_compat_cat_health_handler(
+    cluster: Cluster,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_cat_health_filter()
+        .and(with_arg(cluster))
+        .then(es_compat_cat_health)
+        .map(make_cat_response)
+        .recover(recover_fn)
+}
+
+/// GET _elastic/_cat/nodes
+pub fn es_compat_cat_nodes_handler(
+    cluster: Cluster,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_cat_nodes_filter()
+        .and(with_arg(cluster))
+        .then(es_compat_cat_nodes)
+        .map(make_cat_response)
         .recover(recover_fn)
 }
 
@@ -194,20 +404,129 @@ pub fn es_compat_resolve_index_handler(
 /// GET or POST _elastic/{index}/_search
 pub fn es_compat_index_search_handler(
     search_service: Arc<dyn SearchService>,
+    index_alias_store: IndexAliasStore,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     elastic_index_search_filter()
         .and(with_arg(search_service))
+        .and(with_arg(index_alias_store))
         .then(es_compat_index_search)
         .map(|result| make_elastic_api_response(result, BodyFormat::default()))
         .recover(recover_fn)
 }
 
+/// GET or POST _elastic/{index}/_search/template
+pub fn es_compat_index_search_template_handler(
+    search_service: Arc<dyn SearchService>,
+    stored_script_store: StoredScriptStore,
+    index_alias_store: IndexAliasStore,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_index_search_template_filter()
+        .and(with_arg(search_service))
+        .and(with_arg(stored_script_store))
+        .and(with_arg(index_alias_store))
+        .then(es_compat_index_search_template)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// POST _elastic/_aliases
+pub fn es_compat_update_aliases_handler(
+    metastore: MetastoreServiceClient,
+    index_alias_store: IndexAliasStore,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_update_aliases_filter()
+        .and(with_arg(metastore))
+        .and(with_arg(index_alias_store))
+        .then(es_compat_update_aliases)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// GET _elastic/_alias or _elastic/_alias/{alias}
+pub fn es_compat_get_aliases_handler(
+    index_alias_store: IndexAliasStore,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_get_aliases_filter()
+        .and(with_arg(index_alias_store))
+        .then(es_compat_get_aliases)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// PUT or POST _elastic/_index_template/{name}
+pub fn es_compat_put_index_template_handler(
+    metastore_service: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_put_index_template_filter()
+        .and(with_arg(metastore_service))
+        .then(es_compat_put_index_template)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// GET _elastic/_index_template and _elastic/_index_template/{name}
+pub fn es_compat_get_index_templates_handler(
+    metastore_service: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_get_index_templates_filter()
+        .and(with_arg(metastore_service))
+        .then(es_compat_get_index_templates)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// DELETE _elastic/_index_template/{name}
+pub fn es_compat_delete_index_template_handler(
+    metastore_service: MetastoreServiceClient,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_delete_index_template_filter()
+        .and(with_arg(metastore_service))
+        .then(es_compat_delete_index_template)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// PUT or POST _elastic/_scripts/{id}
+pub fn es_compat_put_stored_script_handler(
+    stored_script_store: StoredScriptStore,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_put_stored_script_filter()
+        .and(with_arg(stored_script_store))
+        .then(es_compat_put_stored_script)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// GET _elastic/_scripts/{id}
+pub fn es_compat_get_stored_script_handler(
+    stored_script_store: StoredScriptStore,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_get_stored_script_filter()
+        .and(with_arg(stored_script_store))
+        .then(es_compat_get_stored_script)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
+/// DELETE _elastic/_scripts/{id}
+pub fn es_compat_delete_stored_script_handler(
+    stored_script_store: StoredScriptStore,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    elastic_delete_stored_script_filter()
+        .and(with_arg(stored_script_store))
+        .then(es_compat_delete_stored_script)
+        .map(|result| make_elastic_api_response(result, BodyFormat::default()))
+        .recover(recover_fn)
+}
+
 /// GET or POST _elastic/{index}/_count
 pub fn es_compat_index_count_handler(
     search_service: Arc<dyn SearchService>,
+    index_alias_store: IndexAliasStore,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     elastic_index_count_filter()
         .and(with_arg(search_service))
+        .and(with_arg(index_alias_store))
         .then(es_compat_index_count)
         .map(|result| make_elastic_api_response(result, BodyFormat::default()))
         .recover(recover_fn)
@@ -216,9 +535,11 @@ pub fn es_compat_index_count_handler(
 /// POST _elastic/_msearch
 pub fn es_compat_index_multi_search_handler(
     search_service: Arc<dyn SearchService>,
+    index_alias_store: IndexAliasStore,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     elastic_multi_search_filter()
         .and(with_arg(search_service))
+        .and(with_arg(index_alias_store))
         .then(es_compat_index_multi_search)
         .map(|result: Result<MultiSearchResponse, ElasticsearchError>| {
             let status_code = match &result {
@@ -247,6 +568,10 @@ fn build_request_for_es_api(
     search_body: SearchBody,
 ) -> Result<(quickwit_proto::search::SearchRequest, bool), ElasticsearchError> {
     let default_operator = search_params.default_operator.unwrap_or(BooleanOperand::Or);
+    let mut named_queries: Vec<NamedQuery> = Vec::new();
+    let skip_source =
+        !HitFieldsSelection::from_request(&search_params, &search_body).needs_source();
+
     // The query string, if present, takes priority over what can be in the request
     // body.
     let mut query_ast = if let Some(q) = &search_params.q {
@@ -255,9 +580,20 @@ fn build_request_for_es_api(
             default_fields: None,
             default_operator,
             lenient: false,
+            allow_leading_wildcard: false,
         };
         user_text_query.into()
     } else if let Some(query_dsl) = search_body.query {
+        for (name, named_query_ast) in query_dsl
+            .named_queries()
+            .map_err(|err| SearchError::InvalidQuery(err.to_string()))?
+        {
+            named_queries.push(NamedQuery {
+                name,
+                query_ast: serde_json::to_string(&named_query_ast)
+                    .expect("Failed to serialize QueryAst"),
+            });
+        }
         query_dsl
             .try_into()
             .map_err(|err: anyhow::Error| SearchError::InvalidQuery(err.to_string()))?
@@ -274,6 +610,7 @@ fn build_request_for_es_api(
                     default_fields: None,
                     default_operator,
                     lenient: false,
+                    allow_leading_wildcard: false,
                 };
                 QueryAst::UserInput(user_text_query)
             })
@@ -284,13 +621,20 @@ fn build_request_for_es_api(
             must_not: Vec::new(),
             should: Vec::new(),
             filter: queries,
+            minimum_should_match: None,
         });
     }
 
-    let aggregation_request: Option<String> = if search_body.aggs.is_empty() {
+    let mut aggregations = search_body.aggs;
+    strip_percentiles_accuracy_params(&mut aggregations)?;
+    rewrite_top_hits_params(&mut aggregations)?;
+    rewrite_histogram_params(&mut aggregations)?;
+    rewrite_filters_params(&mut aggregations)?;
+    rewrite_date_range_params(&mut aggregations)?;
+    let aggregation_request: Option<String> = if aggregations.is_empty() {
         None
     } else {
-        serde_json::to_string(&search_body.aggs).ok()
+        serde_json::to_string(&aggregations).ok()
     };
 
     let max_hits = search_params.size.or(search_body.size).unwrap_or(10);
@@ -318,6 +662,7 @@ fn build_request_for_es_api(
                 .date_format
                 .clone()
                 .map(|date_format| SortDatetimeFormat::from(date_format) as i32),
+            missing: sort_field.missing as i32,
         })
         .take_while_inclusive(|sort_field| !is_doc_field(sort_field))
         .collect();
@@ -332,6 +677,29 @@ fn build_request_for_es_api(
 
     let has_doc_id_field = sort_fields.iter().any(is_doc_field);
     let search_after = partial_hit_from_search_after_param(search_body.search_after, &sort_fields)?;
+    let (snippet_fields, snippet_options) = match search_body.highlight {
+        Some(highlight) => snippet_fields_and_options_from_highlight(highlight),
+        None => (Vec::new(), None),
+    };
+    let point_in_time = search_body.pit.map(point_in_time_from_body).transpose()?;
+    let collapse = search_body.collapse.map(collapse_from_body);
+    // The `timeout` query parameter, if present, takes priority over the one in the body.
+    let timeout_millis: Option<u32> = search_params
+        .timeout
+        .as_deref()
+        .or(search_body.timeout.as_deref())
+        .map(parse_timeout)
+        .transpose()?
+        .map(|timeout_duration| timeout_duration.as_millis().min(u32::MAX as u128) as u32);
+    let priority = search_params
+        .priority
+        .unwrap_or(SearchPriority::Interactive);
+    let suggesters = build_suggesters_for_es_api(search_body.suggest)?;
+    let explain = search_params
+        .explain
+        .or(search_body.explain)
+        .unwrap_or(false);
+    let profile = search_body.profile;
 
     Ok((
         quickwit_proto::search::SearchRequest {
@@ -343,91 +711,666 @@ fn build_request_for_es_api(
             sort_fields,
             start_timestamp: None,
             end_timestamp: None,
-            snippet_fields: Vec::new(),
+            snippet_fields,
             scroll_ttl_secs,
             search_after,
             count_hits,
+            named_queries,
+            snippet_options,
+            point_in_time,
+            collapse,
+            timeout_millis,
+            priority: priority as i32,
+            api_key_id: search_params.api_key_id,
+            suggesters,
+            explain,
+            profile,
+            docvalue_fields: search_body.docvalue_fields,
+            skip_source,
         },
         has_doc_id_field,
     ))
 }
 
-fn is_doc_field(field: &quickwit_proto::search::SortField) -> bool {
-    field.field_name == "_shard_doc" || field.field_name == "_doc"
+fn parse_keep_alive(keep_alive: &str) -> Result<Duration, ElasticsearchError> {
+    let keep_alive_duration = humantime::parse_duration(keep_alive).map_err(|_| {
+        SearchError::InvalidArgument(format!("invalid keep_alive duration: `{keep_alive}`"))
+    })?;
+    Ok(keep_alive_duration)
 }
 
-fn partial_hit_from_search_after_param(
-    search_after: Vec<serde_json::Value>,
-    sort_order: &[quickwit_proto::search::SortField],
-) -> Result<Option<PartialHit>, ElasticsearchError> {
-    if search_after.is_empty() {
-        return Ok(None);
-    }
-    if search_after.len() != sort_order.len() {
-        return Err(ElasticsearchError::new(
-            StatusCode::BAD_REQUEST,
-            "sort and search_after are of different length".to_string(),
-            None,
-        ));
+fn parse_timeout(timeout: &str) -> Result<Duration, ElasticsearchError> {
+    let timeout_duration = humantime::parse_duration(timeout).map_err(|_| {
+        SearchError::InvalidArgument(format!("invalid timeout duration: `{timeout}`"))
+    })?;
+    Ok(timeout_duration)
+}
+
+fn parse_wait_for_completion_timeout(
+    wait_for_completion_timeout: &str,
+) -> Result<Duration, ElasticsearchError> {
+    let wait_for_completion_timeout_duration =
+        humantime::parse_duration(wait_for_completion_timeout).map_err(|_| {
+            SearchError::InvalidArgument(format!(
+                "invalid wait_for_completion_timeout duration: `{wait_for_completion_timeout}`"
+            ))
+        })?;
+    Ok(wait_for_completion_timeout_duration)
+}
+
+fn point_in_time_from_body(pit: PointInTimeBody) -> Result<PointInTime, ElasticsearchError> {
+    let keep_alive_secs = pit
+        .keep_alive
+        .map(|keep_alive| parse_keep_alive(&keep_alive))
+        .transpose()?
+        .map(|keep_alive_duration| keep_alive_duration.as_secs() as u32);
+    Ok(PointInTime {
+        id: pit.id,
+        keep_alive_secs,
+    })
+}
+
+fn collapse_from_body(collapse: Collapse) -> quickwit_proto::search::Collapse {
+    let (inner_hits, inner_hits_name) = match collapse.inner_hits {
+        Some(inner_hits) => (inner_hits.size, Some(inner_hits.name)),
+        None => (0, None),
+    };
+    quickwit_proto::search::Collapse {
+        field: collapse.field,
+        inner_hits,
+        inner_hits_name,
     }
-    let mut parsed_search_after = PartialHit::default();
-    for (value, field) in search_after.into_iter().zip(sort_order) {
-        if is_doc_field(field) {
-            if let Some(value_str) = value.as_str() {
-                let address: quickwit_search::GlobalDocAddress =
-                    value_str.parse().map_err(|_| {
-                        ElasticsearchError::new(
-                            StatusCode::BAD_REQUEST,
-                            "invalid search_after doc id, must be of form \
-                             `{split_id}:{segment_id: u32}:{doc_id: u32}`"
-                                .to_string(),
-                            None,
-                        )
-                    })?;
-                parsed_search_after.split_id = address.split;
-                parsed_search_after.segment_ord = address.doc_addr.segment_ord;
-                parsed_search_after.doc_id = address.doc_addr.doc_id;
-                return Ok(Some(parsed_search_after));
-            } else {
-                return Err(ElasticsearchError::new(
-                    StatusCode::BAD_REQUEST,
-                    "search_after doc id must be of string type".to_string(),
-                    None,
-                ));
+}
+
+/// Converts the `highlight` parameters of a search request into snippet fields and options.
+///
+/// Elasticsearch cycles through the `pre_tags` and `post_tags` to highlight the different terms:
+/// only the first tags are used.
+fn snippet_fields_and_options_from_highlight(
+    highlight: Highlight,
+) -> (Vec<String>, Option<SnippetOptions>) {
+    let first_tag = |tags_opt: Option<Vec<String>>| tags_opt?.into_iter().next();
+    let snippet_options = SnippetOptions {
+        pre_tag: first_tag(highlight.pre_tags),
+        post_tag: first_tag(highlight.post_tags),
+        fragment_size: highlight.fragment_size,
+    };
+    (highlight.fields, Some(snippet_options))
+}
+
+/// Validates and removes the accuracy parameters of the percentiles aggregations.
+///
+/// Elasticsearch computes percentiles with a t-digest tuned by `tdigest.compression`, or with an
+/// HDR histogram tuned by `hdr.number_of_significant_value_digits`. Quickwit computes them with a
+/// DDSketch, which has a fixed relative accuracy and is merged exactly across splits and leaves,
+/// so these parameters have no equivalent.
+fn strip_percentiles_accuracy_params(
+    aggregations: &mut serde_json::Map<String, JsonValue>,
+) -> Result<(), ElasticsearchError> {
+    for aggregation in aggregations.values_mut() {
+        let Some(aggregation_obj) = aggregation.as_object_mut() else {
+            continue;
+        };
+        if let Some(JsonValue::Object(percentiles_params)) = aggregation_obj.get_mut("percentiles")
+        {
+            let tdigest_opt = percentiles_params.remove("tdigest");
+            let hdr_opt = percentiles_params.remove("hdr");
+            match (tdigest_opt, hdr_opt) {
+                (Some(_), Some(_)) => {
+                    return Err(invalid_percentiles_param(
+                        "`tdigest` and `hdr` cannot be set together",
+                    ));
+                }
+                (Some(tdigest), None) => {
+                    let is_valid_compression = match tdigest.get("compression") {
+                        Some(compression) => compression
+                            .as_f64()
+                            .is_some_and(|compression| compression > 0.0),
+                        None => tdigest.is_object(),
+                    };
+                    if !is_valid_compression {
+                        return Err(invalid_percentiles_param(
+                            "`tdigest.compression` must be a positive number",
+                        ));
+                    }
+                }
+                (None, Some(hdr)) => {
+                    let num_digits_opt = hdr
+                        .get("number_of_significant_value_digits")
+                        .and_then(JsonValue::as_u64);
+                    if !num_digits_opt.is_some_and(|num_digits| num_digits <= 5) {
+                        return Err(invalid_percentiles_param(
+                            "`hdr.number_of_significant_value_digits` must be an integer between \
+                             0 and 5",
+                        ));
+                    }
+                }
+                (None, None) => {}
             }
-        } else {
-            let value = SortByValue::try_from_json(value).ok_or_else(|| {
-                ElasticsearchError::new(
-                    StatusCode::BAD_REQUEST,
-                    "invalid search_after field value, expect bool, number or string".to_string(),
-                    None,
-                )
-            })?;
-            // TODO make cleaner once we support Vec
-            if parsed_search_after.sort_value.is_none() {
-                parsed_search_after.sort_value = Some(value);
-            } else {
-                parsed_search_after.sort_value2 = Some(value);
+        }
+        for sub_aggregations_key in ["aggs", "aggregations"] {
+            if let Some(JsonValue::Object(sub_aggregations)) =
+                aggregation_obj.get_mut(sub_aggregations_key)
+            {
+                strip_percentiles_accuracy_params(sub_aggregations)?;
             }
         }
     }
-    Ok(Some(parsed_search_after))
+    Ok(())
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ElasticsearchCountResponse {
-    count: u64,
+fn invalid_percentiles_param(reason: &str) -> ElasticsearchError {
+    ElasticsearchError::new(
+        StatusCode::BAD_REQUEST,
+        format!("invalid percentiles aggregation: {reason}"),
+        Some(ElasticException::IllegalArgument),
+    )
 }
 
-async fn es_compat_index_count(
-    index_id_patterns: Vec<String>,
+/// Rewrites the parameters of the `top_hits` aggregations into the format expected by tantivy.
+///
+/// Only fast fields can be retrieved from within an aggregation: the fields listed in `_source`
+/// are turned into `docvalue_fields`, and the different sort syntaxes accepted by Elasticsearch
+/// are normalized into a list of `{"field": "order"}` objects.
+fn rewrite_top_hits_params(
+    aggregations: &mut serde_json::Map<String, JsonValue>,
+) -> Result<(), ElasticsearchError> {
+    for aggregation in aggregations.values_mut() {
+        let Some(aggregation_obj) = aggregation.as_object_mut() else {
+            continue;
+        };
+        if let Some(JsonValue::Object(top_hits_params)) = aggregation_obj.get_mut("top_hits") {
+            if let Some(source) = top_hits_params.remove("_source") {
+                let source_fields = top_hits_source_fields(source)?;
+                let docvalue_fields = top_hits_params
+                    .entry("docvalue_fields")
+                    .or_insert_with(|| JsonValue::Array(Vec::new()));
+                let JsonValue::Array(docvalue_fields) = docvalue_fields else {
+                    return Err(invalid_top_hits_param(
+                        "`docvalue_fields` must be an array of field names",
+                    ));
+                };
+                for source_field in source_fields {
+                    if !docvalue_fields.contains(&source_field) {
+                        docvalue_fields.push(source_field);
+                    }
+                }
+            }
+            if let Some(sort) = top_hits_params.get_mut("sort") {
+                *sort = normalize_top_hits_sort(sort.take())?;
+            }
+        }
+        for sub_aggregations_key in ["aggs", "aggregations"] {
+            if let Some(JsonValue::Object(sub_aggregations)) =
+                aggregation_obj.get_mut(sub_aggregations_key)
+            {
+                rewrite_top_hits_params(sub_aggregations)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the fields to retrieve for a `_source` parameter of a `top_hits` aggregation.
+///
+/// The whole source of the documents cannot be retrieved, so `_source: true` and `_source: false`
+/// do not retrieve any field.
+fn top_hits_source_fields(source: JsonValue) -> Result<Vec<JsonValue>, ElasticsearchError> {
+    let includes = match source {
+        JsonValue::Bool(_) => return Ok(Vec::new()),
+        JsonValue::Object(mut source_obj) => {
+            if source_obj.contains_key("excludes") || source_obj.contains_key("exclude") {
+                return Err(invalid_top_hits_param(
+                    "`_source.excludes` is not supported",
+                ));
+            }
+            match source_obj
+                .remove("includes")
+                .or_else(|| source_obj.remove("include"))
+            {
+                Some(includes) => includes,
+                None => return Ok(Vec::new()),
+            }
+        }
+        includes => includes,
+    };
+    match includes {
+        JsonValue::String(_) => Ok(vec![includes]),
+        JsonValue::Array(fields) if fields.iter().all(JsonValue::is_string) => Ok(fields),
+        _ => Err(invalid_top_hits_param(
+            "`_source` must be a boolean, a field name, or a list of field names",
+        )),
+    }
+}
+
+/// Normalizes the `sort` parameter of a `top_hits` aggregation into a list of
+/// `{"field": "order"}` objects.
+fn normalize_top_hits_sort(sort: JsonValue) -> Result<JsonValue, ElasticsearchError> {
+    let sort_items = match sort {
+        JsonValue::Array(sort_items) => sort_items,
+        sort_item => vec![sort_item],
+    };
+    let mut normalized_sort_items = Vec::with_capacity(sort_items.len());
+    for sort_item in sort_items {
+        match sort_item {
+            JsonValue::String(field) => {
+                normalized_sort_items.push(json!({ field: "asc" }));
+            }
+            JsonValue::Object(sort_obj) => {
+                for (field, order) in sort_obj {
+                    let order = match order {
+                        JsonValue::String(_) => order,
+                        JsonValue::Object(mut order_obj) => order_obj
+                            .remove("order")
+                            .unwrap_or_else(|| JsonValue::String("asc".to_string())),
+                        _ => {
+                            return Err(invalid_top_hits_param(
+                                "sort order must be `asc` or `desc`",
+                            ));
+                        }
+                    };
+                    normalized_sort_items.push(json!({ field: order }));
+                }
+            }
+            _ => {
+                return Err(invalid_top_hits_param(
+                    "`sort` must be a field name, an object, or a list of those",
+                ));
+            }
+        }
+    }
+    Ok(JsonValue::Array(normalized_sort_items))
+}
+
+fn invalid_top_hits_param(reason: &str) -> ElasticsearchError {
+    ElasticsearchError::new(
+        StatusCode::BAD_REQUEST,
+        format!("invalid top_hits aggregation: {reason}"),
+        Some(ElasticException::IllegalArgument),
+    )
+}
+
+/// Converts the Elasticsearch query DSL filters of the `filters` aggregations into query ASTs.
+///
+/// Quickwit only supports `filters` aggregations at the top level, with keyed buckets.
+fn rewrite_filters_params(
+    aggregations: &mut serde_json::Map<String, JsonValue>,
+) -> Result<(), ElasticsearchError> {
+    for aggregation in aggregations.values_mut() {
+        let Some(JsonValue::Object(filters_params)) = aggregation.get_mut("filters") else {
+            continue;
+        };
+        match filters_params.remove("keyed") {
+            None | Some(JsonValue::Bool(true)) => {}
+            Some(_) => return Err(invalid_filters_param("only keyed buckets are supported")),
+        }
+        let Some(JsonValue::Object(filters)) = filters_params.get_mut("filters") else {
+            return Err(invalid_filters_param(
+                "`filters` must be an object mapping bucket keys to queries, anonymous filters \
+                 are not supported",
+            ));
+        };
+        for filter in filters.values_mut() {
+            let query_dsl: ElasticQueryDsl = serde_json::from_value(filter.take())
+                .map_err(|err| invalid_filters_param(&err.to_string()))?;
+            let query_ast: QueryAst = query_dsl
+                .try_into()
+                .map_err(|err: anyhow::Error| invalid_filters_param(&err.to_string()))?;
+            *filter = serde_json::to_value(&query_ast).expect("Failed to serialize QueryAst");
+        }
+    }
+    Ok(())
+}
+
+fn invalid_filters_param(reason: &str) -> ElasticsearchError {
+    ElasticsearchError::new(
+        StatusCode::BAD_REQUEST,
+        format!("invalid filters aggregation: {reason}"),
+        Some(ElasticException::IllegalArgument),
+    )
+}
+
+/// Rewrites the `date_range` aggregations into `range` aggregations, whose bounds are expressed in
+/// nanoseconds for datetime fields.
+///
+/// Bounds are dates, parsed with `format` if set, date math expressions such as `now-1d/d`, or
+/// Unix timestamps in milliseconds.
+fn rewrite_date_range_params(
+    aggregations: &mut serde_json::Map<String, JsonValue>,
+) -> Result<(), ElasticsearchError> {
+    let date_math_parser = DateMathParser::new(OffsetDateTime::now_utc());
+
+    for aggregation in aggregations.values_mut() {
+        let Some(aggregation_obj) = aggregation.as_object_mut() else {
+            continue;
+        };
+        if let Some(date_range_params) = aggregation_obj.remove("date_range") {
+            let JsonValue::Object(date_range_params) = date_range_params else {
+                return Err(invalid_date_range_param("parameters must be an object"));
+            };
+            let range_params = rewrite_date_range(date_range_params, &date_math_parser)?;
+            aggregation_obj.insert("range".to_string(), JsonValue::Object(range_params));
+        }
+        for sub_aggregations_key in ["aggs", "aggregations"] {
+            if let Some(JsonValue::Object(sub_aggregations)) =
+                aggregation_obj.get_mut(sub_aggregations_key)
+            {
+                rewrite_date_range_params(sub_aggregations)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolves the `format` parameter of an aggregation into the formats used to parse its dates.
+pub(super) fn parse_date_time_formats(
+    format_opt: Option<JsonValue>,
+) -> Result<Vec<DateTimeInputFormat>, String> {
+    match format_opt {
+        None => Ok(vec![
+            DateTimeInputFormat::Rfc3339,
+            "%Y-%m-%dT%H:%M:%S".parse().expect("format should be valid"),
+            "%Y-%m-%d".parse().expect("format should be valid"),
+        ]),
+        // Elasticsearch accepts several formats separated by `||`.
+        Some(JsonValue::String(java_date_formats)) => java_date_formats
+            .split("||")
+            .map(|java_date_format| resolve_java_datetime_format_alias(java_date_format.trim()))
+            .collect(),
+        Some(_) => Err("`format` must be a string".to_string()),
+    }
+}
+
+/// Parses a date of an aggregation, expressed as a date string, a date math expression, or a
+/// Unix timestamp in milliseconds.
+pub(super) fn parse_aggregation_date(
+    date: &JsonValue,
+    date_time_formats: &[DateTimeInputFormat],
+    date_math_parser: &DateMathParser,
+    rounding: DateMathRounding,
+) -> Result<TantivyDateTime, String> {
+    match date {
+        JsonValue::Number(timestamp_millis) => timestamp_millis
+            .as_i64()
+            .map(TantivyDateTime::from_timestamp_millis)
+            .ok_or_else(|| format!("`{timestamp_millis}` is not a timestamp in milliseconds")),
+        JsonValue::String(date_time_str) => {
+            if DateMathParser::is_date_math_expression(date_time_str) {
+                date_math_parser.parse(date_time_str, date_time_formats, rounding)
+            } else if let Ok(timestamp_millis) = date_time_str.parse::<i64>() {
+                Ok(TantivyDateTime::from_timestamp_millis(timestamp_millis))
+            } else {
+                parse_date_time_str(date_time_str, date_time_formats)
+            }
+        }
+        _ => Err(format!(
+            "`{date}` is not a date or a timestamp in milliseconds"
+        )),
+    }
+}
+
+fn rewrite_date_range(
+    mut date_range_params: serde_json::Map<String, JsonValue>,
+    date_math_parser: &DateMathParser,
+) -> Result<serde_json::Map<String, JsonValue>, ElasticsearchError> {
+    let date_time_formats = parse_date_time_formats(date_range_params.remove("format"))
+        .map_err(|error| invalid_date_range_param(&error))?;
+    let mut range_params = serde_json::Map::new();
+
+    for (param_key, param_value) in date_range_params {
+        match param_key.as_str() {
+            "field" | "keyed" => {
+                range_params.insert(param_key, param_value);
+            }
+            "ranges" => {
+                let JsonValue::Array(ranges) = param_value else {
+                    return Err(invalid_date_range_param("`ranges` must be an array"));
+                };
+                let ranges = ranges
+                    .into_iter()
+                    .map(|range| {
+                        rewrite_date_range_bounds(range, &date_time_formats, date_math_parser)
+                    })
+                    .collect::<Result<_, _>>()?;
+                range_params.insert(param_key, JsonValue::Array(ranges));
+            }
+            _ => {
+                return Err(invalid_date_range_param(&format!(
+                    "unsupported parameter `{param_key}`"
+                )));
+            }
+        }
+    }
+    Ok(range_params)
+}
+
+fn rewrite_date_range_bounds(
+    range: JsonValue,
+    date_time_formats: &[DateTimeInputFormat],
+    date_math_parser: &DateMathParser,
+) -> Result<JsonValue, ElasticsearchError> {
+    let JsonValue::Object(mut range_obj) = range else {
+        return Err(invalid_date_range_param("ranges must be objects"));
+    };
+    for bound_key in ["from", "to"] {
+        let Some(bound) = range_obj.remove(bound_key) else {
+            continue;
+        };
+        if bound.is_null() {
+            continue;
+        }
+        // `from` is inclusive and `to` is exclusive: both round down.
+        let date_time = parse_aggregation_date(
+            &bound,
+            date_time_formats,
+            date_math_parser,
+            DateMathRounding::Down,
+        )
+        .map_err(|error| invalid_date_range_param(&format!("invalid `{bound_key}`: {error}")))?;
+        range_obj.insert(
+            bound_key.to_string(),
+            JsonValue::from(date_time.into_timestamp_nanos() as f64),
+        );
+    }
+    Ok(JsonValue::Object(range_obj))
+}
+
+fn invalid_date_range_param(reason: &str) -> ElasticsearchError {
+    ElasticsearchError::new(
+        StatusCode::BAD_REQUEST,
+        format!("invalid date_range aggregation: {reason}"),
+        Some(ElasticException::IllegalArgument),
+    )
+}
+
+/// Converts the results of the `top_hits` aggregations into the format returned by
+/// Elasticsearch.
+///
+/// The aggregation request is walked alongside the results to locate the `top_hits`
+/// aggregations, whether they are at the top level or nested in bucket aggregations. The
+/// retrieved fields are exposed both in `_source` and in `fields`.
+fn convert_top_hits_results(
+    aggregations_request: &serde_json::Map<String, JsonValue>,
+    aggregations_result: &mut JsonValue,
+) {
+    let Some(aggregations_result_obj) = aggregations_result.as_object_mut() else {
+        return;
+    };
+    for (aggregation_name, aggregation_request) in aggregations_request {
+        let Some(aggregation_result) = aggregations_result_obj.get_mut(aggregation_name) else {
+            continue;
+        };
+        let Some(aggregation_request_obj) = aggregation_request.as_object() else {
+            continue;
+        };
+        if aggregation_request_obj.contains_key("top_hits") {
+            convert_top_hits_result(aggregation_result);
+            continue;
+        }
+        let Some(sub_aggregations_request) = aggregation_request_obj
+            .get("aggs")
+            .or_else(|| aggregation_request_obj.get("aggregations"))
+            .and_then(JsonValue::as_object)
+        else {
+            continue;
+        };
+        match aggregation_result.get_mut("buckets") {
+            Some(JsonValue::Array(buckets)) => {
+                for bucket in buckets {
+                    convert_top_hits_results(sub_aggregations_request, bucket);
+                }
+            }
+            Some(JsonValue::Object(keyed_buckets)) => {
+                for bucket in keyed_buckets.values_mut() {
+                    convert_top_hits_results(sub_aggregations_request, bucket);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn convert_top_hits_result(top_hits_result: &mut JsonValue) {
+    let Some(JsonValue::Array(hits)) = top_hits_result.get_mut("hits") else {
+        return;
+    };
+    let es_hits: Vec<JsonValue> = hits
+        .drain(..)
+        .map(|hit| {
+            let sort = hit.get("sort").cloned().unwrap_or_else(|| json!([]));
+            let source = hit
+                .get("docvalue_fields")
+                .and_then(JsonValue::as_object)
+                .cloned()
+                .unwrap_or_default();
+            let fields: serde_json::Map<String, JsonValue> = source
+                .iter()
+                .map(|(field, value)| {
+                    let values = if value.is_array() {
+                        value.clone()
+                    } else {
+                        JsonValue::Array(vec![value.clone()])
+                    };
+                    (field.clone(), values)
+                })
+                .collect();
+            json!({
+                "_source": source,
+                "fields": fields,
+                "sort": sort,
+            })
+        })
+        .collect();
+    *top_hits_result = json!({
+        "hits": {
+            "max_score": null,
+            "hits": es_hits,
+        }
+    });
+}
+
+/// Converts the aggregation results of a search response, given the aggregation request that
+/// was sent to the root searcher.
+fn convert_aggregation_results(
+    aggregation_request_opt: Option<&str>,
+    search_response: &mut ElasticsearchResponse,
+) {
+    let (Some(aggregation_request), Some(aggregations)) = (
+        aggregation_request_opt,
+        search_response.aggregations.as_mut(),
+    ) else {
+        return;
+    };
+    let Ok(aggregations_request) =
+        serde_json::from_str::<serde_json::Map<String, JsonValue>>(aggregation_request)
+    else {
+        return;
+    };
+    convert_top_hits_results(&aggregations_request, aggregations);
+}
+
+fn is_doc_field(field: &quickwit_proto::search::SortField) -> bool {
+    field.field_name == "_shard_doc" || field.field_name == "_doc"
+}
+
+fn partial_hit_from_search_after_param(
+    search_after: Vec<serde_json::Value>,
+    sort_order: &[quickwit_proto::search::SortField],
+) -> Result<Option<PartialHit>, ElasticsearchError> {
+    if search_after.is_empty() {
+        return Ok(None);
+    }
+    if search_after.len() != sort_order.len() {
+        return Err(ElasticsearchError::new(
+            StatusCode::BAD_REQUEST,
+            "sort and search_after are of different length".to_string(),
+            None,
+        ));
+    }
+    let mut parsed_search_after = PartialHit::default();
+    for (value, field) in search_after.into_iter().zip(sort_order) {
+        if is_doc_field(field) {
+            if let Some(value_str) = value.as_str() {
+                let address: quickwit_search::GlobalDocAddress =
+                    value_str.parse().map_err(|_| {
+                        ElasticsearchError::new(
+                            StatusCode::BAD_REQUEST,
+                            "invalid search_after doc id, must be of form \
+                             `{split_id}:{segment_id: u32}:{doc_id: u32}`"
+                                .to_string(),
+                            None,
+                        )
+                    })?;
+                parsed_search_after.split_id = address.split;
+                parsed_search_after.segment_ord = address.doc_addr.segment_ord;
+                parsed_search_after.doc_id = address.doc_addr.doc_id;
+                return Ok(Some(parsed_search_after));
+            } else {
+                return Err(ElasticsearchError::new(
+                    StatusCode::BAD_REQUEST,
+                    "search_after doc id must be of string type".to_string(),
+                    None,
+                ));
+            }
+        } else {
+            let value = SortByValue::try_from_json(value).ok_or_else(|| {
+                ElasticsearchError::new(
+                    StatusCode::BAD_REQUEST,
+                    "invalid search_after field value, expect bool, number or string".to_string(),
+                    None,
+                )
+            })?;
+            // TODO make cleaner once we support Vec
+            if parsed_search_after.sort_value.is_none() {
+                parsed_search_after.sort_value = Some(value);
+            } else {
+                parsed_search_after.sort_value2 = Some(value);
+            }
+        }
+    }
+    Ok(Some(parsed_search_after))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ElasticsearchCountResponse {
+    count: u64,
+}
+
+async fn es_compat_index_count(
+    index_id_patterns: Vec<String>,
     search_params: SearchQueryParamsCount,
     search_body: SearchBody,
     search_service: Arc<dyn SearchService>,
+    index_alias_store: IndexAliasStore,
 ) -> Result<ElasticsearchCountResponse, ElasticsearchError> {
     let search_params: SearchQueryParams = search_params.into();
-    let (search_request, _append_shard_doc) =
+    let (index_id_patterns, alias_filter_opt) =
+        resolve_search_target(&index_alias_store.load().await?, index_id_patterns)?;
+    let (mut search_request, _append_shard_doc) =
         build_request_for_es_api(index_id_patterns, search_params, search_body)?;
+    if let Some(alias_filter) = alias_filter_opt {
+        add_alias_filter(&mut search_request, alias_filter);
+    }
     let search_response: SearchResponse = search_service.root_search(search_request).await?;
     let search_response_rest: ElasticsearchCountResponse = ElasticsearchCountResponse {
         count: search_response.num_hits,
@@ -435,27 +1378,232 @@ async fn es_compat_index_count(
     Ok(search_response_rest)
 }
 
+async fn es_compat_search(
+    search_params: SearchQueryParams,
+    search_body: SearchBody,
+    search_service: Arc<dyn SearchService>,
+) -> Result<ElasticsearchSuggestResponse, ElasticsearchError> {
+    if search_body.pit.is_none() {
+        return Err(ElasticsearchError::new(
+            StatusCode::NOT_IMPLEMENTED,
+            "_elastic/_search is only supported with a point in time (`pit`). Please try the \
+             index search endpoint (_elastic/{index}/_search)"
+                .to_string(),
+            None,
+        ));
+    }
+    es_compat_search_indexes(Vec::new(), None, search_params, search_body, search_service).await
+}
+
+async fn es_compat_open_point_in_time(
+    index_id_patterns: Vec<String>,
+    query_params: OpenPointInTimeQueryParams,
+    search_service: Arc<dyn SearchService>,
+) -> Result<OpenPointInTimeResponse, ElasticsearchError> {
+    let Some(keep_alive) = query_params.keep_alive else {
+        return Err(SearchError::InvalidArgument("missing keep_alive".to_string()).into());
+    };
+    let keep_alive_duration = parse_keep_alive(&keep_alive)?;
+    let point_in_time_id = search_service
+        .create_point_in_time(index_id_patterns, keep_alive_duration)
+        .await?;
+    Ok(OpenPointInTimeResponse {
+        id: point_in_time_id,
+    })
+}
+
+async fn es_compat_close_point_in_time(
+    close_point_in_time_body: ClosePointInTimeBody,
+    search_service: Arc<dyn SearchService>,
+) -> Result<ClosePointInTimeResponse, ElasticsearchError> {
+    if close_point_in_time_body.id.is_empty() {
+        return Err(SearchError::InvalidArgument("missing point-in-time id".to_string()).into());
+    }
+    let freed = search_service
+        .delete_point_in_time(close_point_in_time_body.id)
+        .await?;
+    Ok(ClosePointInTimeResponse {
+        succeeded: true,
+        num_freed: freed as u32,
+    })
+}
+
 async fn es_compat_index_search(
     index_id_patterns: Vec<String>,
     search_params: SearchQueryParams,
     search_body: SearchBody,
     search_service: Arc<dyn SearchService>,
-) -> Result<ElasticsearchResponse, ElasticsearchError> {
-    let _source_excludes = search_params._source_excludes.clone();
-    let _source_includes = search_params._source_includes.clone();
+    index_alias_store: IndexAliasStore,
+) -> Result<ElasticsearchSuggestResponse, ElasticsearchError> {
+    let (index_id_patterns, alias_filter_opt) =
+        resolve_search_target(&index_alias_store.load().await?, index_id_patterns)?;
+    es_compat_search_indexes(
+        index_id_patterns,
+        alias_filter_opt,
+        search_params,
+        search_body,
+        search_service,
+    )
+    .await
+}
+
+/// Restricts a search request to the documents matching the filter of the targeted alias.
+fn add_alias_filter(
+    search_request: &mut quickwit_proto::search::SearchRequest,
+    alias_filter: QueryAst,
+) {
+    let query_ast: QueryAst =
+        serde_json::from_str(&search_request.query_ast).expect("QueryAst should deserialize");
+    let filtered_query_ast = QueryAst::Bool(BoolQuery {
+        must: vec![query_ast],
+        filter: vec![alias_filter],
+        ..Default::default()
+    });
+    search_request.query_ast =
+        serde_json::to_string(&filtered_query_ast).expect("Failed to serialize QueryAst");
+}
+
+async fn es_compat_search_indexes(
+    index_id_patterns: Vec<String>,
+    alias_filter_opt: Option<QueryAst>,
+    search_params: SearchQueryParams,
+    search_body: SearchBody,
+    search_service: Arc<dyn SearchService>,
+) -> Result<ElasticsearchSuggestResponse, ElasticsearchError> {
+    let hit_fields_selection = HitFieldsSelection::from_request(&search_params, &search_body);
+    let point_in_time_id_opt = search_body.pit.as_ref().map(|pit| pit.id.clone());
     let start_instant = Instant::now();
-    let (search_request, append_shard_doc) =
+    let (mut search_request, append_shard_doc) =
         build_request_for_es_api(index_id_patterns, search_params, search_body)?;
-    let search_response: SearchResponse = search_service.root_search(search_request).await?;
+    if let Some(alias_filter) = alias_filter_opt {
+        add_alias_filter(&mut search_request, alias_filter);
+    }
+    let aggregation_request = search_request.aggregation_request.clone();
+    let collapse_opt = search_request.collapse.clone();
+    let suggesters = search_request.suggesters.clone();
+    let mut search_response: SearchResponse = search_service.root_search(search_request).await?;
     let elapsed = start_instant.elapsed();
+    let suggestions = std::mem::take(&mut search_response.suggestions);
+    let split_profiles = std::mem::take(&mut search_response.split_profiles);
     let mut search_response_rest: ElasticsearchResponse = convert_to_es_search_response(
         search_response,
         append_shard_doc,
-        _source_excludes,
-        _source_includes,
+        collapse_opt.as_ref(),
+        &hit_fields_selection,
     );
+    convert_aggregation_results(aggregation_request.as_deref(), &mut search_response_rest);
     search_response_rest.took = elapsed.as_millis() as u32;
-    Ok(search_response_rest)
+    search_response_rest.pit_id = point_in_time_id_opt;
+    Ok(ElasticsearchSuggestResponse {
+        response: search_response_rest,
+        suggest: convert_to_es_suggest(&suggesters, suggestions),
+        profile: convert_to_es_profile(split_profiles),
+    })
+}
+
+/// Like Elasticsearch, the submit async search API waits one second for the search to complete
+/// by default.
+const DEFAULT_SUBMIT_WAIT_FOR_COMPLETION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Async searches are held in the memory of the searchers, so they are kept for a shorter time
+/// than in Elasticsearch (5 days) by default.
+const DEFAULT_ASYNC_SEARCH_KEEP_ALIVE: Duration = Duration::from_secs(60 * 60);
+
+async fn es_compat_submit_async_search(
+    index_id_patterns: Vec<String>,
+    query_params: SubmitAsyncSearchQueryParams,
+    search_body: SearchBody,
+    search_service: Arc<dyn SearchService>,
+) -> Result<AsyncSearchResponse, ElasticsearchError> {
+    let wait_for_completion_timeout = query_params
+        .wait_for_completion_timeout
+        .as_deref()
+        .map(parse_wait_for_completion_timeout)
+        .transpose()?
+        .unwrap_or(DEFAULT_SUBMIT_WAIT_FOR_COMPLETION_TIMEOUT);
+    let keep_alive = query_params
+        .keep_alive
+        .as_deref()
+        .map(parse_keep_alive)
+        .transpose()?
+        .unwrap_or(DEFAULT_ASYNC_SEARCH_KEEP_ALIVE);
+    let keep_on_completion = query_params.keep_on_completion.unwrap_or(false);
+    let search_params: SearchQueryParams = query_params.into();
+    let hit_fields_selection = HitFieldsSelection::from_request(&search_params, &search_body);
+    let (search_request, _append_shard_doc) =
+        build_request_for_es_api(index_id_patterns, search_params, search_body)?;
+    let async_search_response = search_service
+        .submit_async_search(search_request, wait_for_completion_timeout, keep_alive)
+        .await?;
+    let mut es_async_search_response =
+        convert_to_es_async_search_response(async_search_response, &hit_fields_selection);
+    if !es_async_search_response.is_running && !keep_on_completion {
+        if let Some(async_search_id) = es_async_search_response.id.take() {
+            search_service.delete_async_search(async_search_id).await?;
+        }
+    }
+    Ok(es_async_search_response)
+}
+
+async fn es_compat_get_async_search(
+    async_search_id: String,
+    query_params: GetAsyncSearchQueryParams,
+    search_service: Arc<dyn SearchService>,
+) -> Result<AsyncSearchResponse, ElasticsearchError> {
+    let wait_for_completion_timeout = query_params
+        .wait_for_completion_timeout
+        .as_deref()
+        .map(parse_wait_for_completion_timeout)
+        .transpose()?
+        .unwrap_or_default();
+    let async_search_response = search_service
+        .get_async_search(async_search_id, wait_for_completion_timeout)
+        .await?;
+    // TODO the `_source` filters and the `fields` depend on the submit request, but we don't have
+    // access to them.
+    Ok(convert_to_es_async_search_response(
+        async_search_response,
+        &HitFieldsSelection::default(),
+    ))
+}
+
+async fn es_compat_async_search_status(
+    async_search_id: String,
+    search_service: Arc<dyn SearchService>,
+) -> Result<AsyncSearchStatusResponse, ElasticsearchError> {
+    let async_search_response = search_service
+        .get_async_search(async_search_id, Duration::ZERO)
+        .await?;
+    let completion_status = if async_search_response.is_running {
+        None
+    } else if let Some(search_error) = &async_search_response.error {
+        Some(search_error.error_code().http_status_code().as_u16())
+    } else {
+        Some(StatusCode::OK.as_u16())
+    };
+    Ok(AsyncSearchStatusResponse {
+        id: async_search_response.id,
+        is_partial: async_search_response.is_partial,
+        is_running: async_search_response.is_running,
+        start_time_in_millis: async_search_response.start_time_millis,
+        expiration_time_in_millis: async_search_response.expiration_time_millis,
+        completion_time_in_millis: async_search_response.completion_time_millis,
+        shards: async_search_shards(&async_search_response),
+        completion_status,
+    })
+}
+
+async fn es_compat_delete_async_search(
+    async_search_id: String,
+    search_service: Arc<dyn SearchService>,
+) -> Result<DeleteAsyncSearchResponse, ElasticsearchError> {
+    let deleted = search_service
+        .delete_async_search(async_search_id.clone())
+        .await?;
+    if !deleted {
+        return Err(SearchError::AsyncSearchNotFound(async_search_id).into());
+    }
+    Ok(DeleteAsyncSearchResponse { acknowledged: true })
 }
 
 /// Returns JSON in the format:
@@ -483,6 +1631,260 @@ async fn es_compat_delete_index(
     Ok(ElasticsearchDeleteResponse { acknowledged: true })
 }
 
+async fn es_compat_index_search_template(
+    index_id_patterns: Vec<String>,
+    search_params: SearchQueryParams,
+    search_template_body: SearchTemplateBody,
+    search_service: Arc<dyn SearchService>,
+    stored_script_store: StoredScriptStore,
+    index_alias_store: IndexAliasStore,
+) -> Result<ElasticsearchSuggestResponse, ElasticsearchError> {
+    let template_source = match (search_template_body.id, search_template_body.source) {
+        (Some(script_id), None) => stored_script_store.get(&script_id).await?.source,
+        (None, Some(source)) => source,
+        _ => {
+            return Err(ElasticsearchError::new(
+                StatusCode::BAD_REQUEST,
+                "exactly one of `id` or `source` must be set".to_string(),
+                Some(ElasticException::ActionRequestValidation),
+            ));
+        }
+    };
+    let rendered_template = render_search_template(&template_source, &search_template_body.params)
+        .map_err(|error| {
+            ElasticsearchError::new(
+                StatusCode::BAD_REQUEST,
+                format!("failed to render search template: {error}"),
+                Some(ElasticException::IllegalArgument),
+            )
+        })?;
+    let search_body: SearchBody = serde_json::from_str(&rendered_template).map_err(|error| {
+        ElasticsearchError::new(
+            StatusCode::BAD_REQUEST,
+            format!("rendered search template is not a valid search request: {error}"),
+            Some(ElasticException::IllegalArgument),
+        )
+    })?;
+    es_compat_index_search(
+        index_id_patterns,
+        search_params,
+        search_body,
+        search_service,
+        index_alias_store,
+    )
+    .await
+}
+
+async fn es_compat_update_aliases(
+    alias_actions_body: AliasActionsBody,
+    metastore: MetastoreServiceClient,
+    index_alias_store: IndexAliasStore,
+) -> Result<AliasActionsResponse, ElasticsearchError> {
+    for alias_action in &alias_actions_body.actions {
+        let AliasAction::Add(alias_action_params) = alias_action else {
+            continue;
+        };
+        for index_id in &alias_action_params.index {
+            let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.clone());
+            metastore
+                .index_metadata(index_metadata_request)
+                .await
+                .map_err(IndexServiceError::from)?;
+        }
+        for alias in &alias_action_params.alias {
+            let index_metadata_request = IndexMetadataRequest::for_index_id(alias.clone());
+            match metastore.index_metadata(index_metadata_request).await {
+                Ok(_) => {
+                    return Err(ElasticsearchError::new(
+                        StatusCode::BAD_REQUEST,
+                        format!(
+                            "invalid alias name [{alias}]: an index exists with the same name as \
+                             the alias"
+                        ),
+                        Some(ElasticException::IllegalArgument),
+                    ));
+                }
+                Err(MetastoreError::NotFound(_)) => {}
+                Err(metastore_error) => return Err(IndexServiceError::from(metastore_error).into()),
+            }
+        }
+    }
+    index_alias_store.update(alias_actions_body.actions).await?;
+    Ok(AliasActionsResponse { acknowledged: true })
+}
+
+async fn es_compat_get_aliases(
+    alias_opt: Option<String>,
+    index_alias_store: IndexAliasStore,
+) -> Result<GetAliasesResponse, ElasticsearchError> {
+    get_aliases(&index_alias_store.load().await?, alias_opt.as_deref())
+}
+
+/// Creates or replaces a Quickwit index template. The control plane applies it to the indexes
+/// it auto-creates, for instance when the bulk API targets an index that does not exist yet.
+async fn es_compat_put_index_template(
+    template_id: String,
+    query_params: PutIndexTemplateQueryParams,
+    put_index_template_body: PutIndexTemplateBody,
+    metastore: MetastoreServiceClient,
+) -> Result<ElasticsearchDeleteResponse, ElasticsearchError> {
+    let invalid_template_error = |message: String| {
+        ElasticsearchError::new(
+            StatusCode::BAD_REQUEST,
+            format!("invalid index template `{template_id}`: {message}"),
+            Some(ElasticException::IllegalArgument),
+        )
+    };
+    let index_template =
+        convert_to_quickwit_index_template(template_id.clone(), put_index_template_body)
+            .map_err(invalid_template_error)?;
+    index_template
+        .validate()
+        .map_err(|error| invalid_template_error(error.to_string()))?;
+
+    let index_template_json =
+        serde_utils::to_json_str(&index_template).map_err(IndexServiceError::from)?;
+    let create_index_template_request = CreateIndexTemplateRequest {
+        index_template_json,
+        overwrite: !query_params.create.unwrap_or(false),
+    };
+    metastore
+        .create_index_template(create_index_template_request)
+        .await
+        .map_err(IndexServiceError::from)?;
+    Ok(ElasticsearchDeleteResponse { acknowledged: true })
+}
+
+async fn es_compat_get_index_templates(
+    template_id_opt: Option<String>,
+    metastore: MetastoreServiceClient,
+) -> Result<GetIndexTemplatesResponse, ElasticsearchError> {
+    let mut index_templates = list_index_templates(metastore).await?;
+
+    if let Some(template_id) = &template_id_opt {
+        index_templates.retain(|index_template| &index_template.template_id == template_id);
+
+        if index_templates.is_empty() {
+            return Err(index_template_not_found_error(template_id));
+        }
+    }
+    let index_templates = index_templates
+        .iter()
+        .map(|index_template| {
+            let index_template_entry = IndexTemplateEntry {
+                name: index_template.template_id.clone(),
+                index_template: convert_to_es_index_template(index_template).map_err(
+                    |serde_error| {
+                        ElasticsearchError::new(
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            format!("failed to serialize index template: {serde_error}"),
+                            None,
+                        )
+                    },
+                )?,
+            };
+            Ok(index_template_entry)
+        })
+        .collect::<Result<Vec<_>, ElasticsearchError>>()?;
+    Ok(GetIndexTemplatesResponse { index_templates })
+}
+
+async fn es_compat_delete_index_template(
+    template_id: String,
+    metastore: MetastoreServiceClient,
+) -> Result<ElasticsearchDeleteResponse, ElasticsearchError> {
+    // Deleting templates is idempotent in the metastore, whereas Elasticsearch reports missing
+    // templates.
+    let index_templates = list_index_templates(metastore.clone()).await?;
+
+    if !index_templates
+        .iter()
+        .any(|index_template| index_template.template_id == template_id)
+    {
+        return Err(index_template_not_found_error(&template_id));
+    }
+    let delete_index_templates_request = DeleteIndexTemplatesRequest {
+        template_ids: vec![template_id],
+    };
+    metastore
+        .delete_index_templates(delete_index_templates_request)
+        .await
+        .map_err(IndexServiceError::from)?;
+    Ok(ElasticsearchDeleteResponse { acknowledged: true })
+}
+
+async fn list_index_templates(
+    metastore: MetastoreServiceClient,
+) -> Result<Vec<IndexTemplate>, ElasticsearchError> {
+    let list_index_templates_response = metastore
+        .list_index_templates(ListIndexTemplatesRequest {})
+        .await
+        .map_err(IndexServiceError::from)?;
+    let index_templates = list_index_templates_response
+        .index_templates_json
+        .iter()
+        .map(|index_template_json| serde_utils::from_json_str(index_template_json))
+        .collect::<Result<Vec<IndexTemplate>, MetastoreError>>()
+        .map_err(IndexServiceError::from)?;
+    Ok(index_templates)
+}
+
+fn index_template_not_found_error(template_id: &str) -> ElasticsearchError {
+    ElasticsearchError::new(
+        StatusCode::NOT_FOUND,
+        format!("index template matching [{template_id}] not found"),
+        Some(ElasticException::ResourceNotFound),
+    )
+}
+
+async fn es_compat_put_stored_script(
+    script_id: String,
+    stored_script_body: StoredScriptBody,
+    stored_script_store: StoredScriptStore,
+) -> Result<ElasticsearchDeleteResponse, ElasticsearchError> {
+    let script = stored_script_body.script;
+    if script.lang != MUSTACHE_LANG {
+        return Err(ElasticsearchError::new(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "unsupported script language `{}`, only `{MUSTACHE_LANG}` is supported",
+                script.lang
+            ),
+            Some(ElasticException::IllegalArgument),
+        ));
+    }
+    // Fail early on templates that cannot be parsed.
+    render_search_template(&script.source, &Default::default()).map_err(|error| {
+        ElasticsearchError::new(
+            StatusCode::BAD_REQUEST,
+            format!("invalid search template: {error}"),
+            Some(ElasticException::IllegalArgument),
+        )
+    })?;
+    stored_script_store.put(&script_id, &script).await?;
+    Ok(ElasticsearchDeleteResponse { acknowledged: true })
+}
+
+async fn es_compat_get_stored_script(
+    script_id: String,
+    stored_script_store: StoredScriptStore,
+) -> Result<GetStoredScriptResponse, ElasticsearchError> {
+    let script = stored_script_store.get(&script_id).await?;
+    Ok(GetStoredScriptResponse {
+        id: script_id,
+        found: true,
+        script,
+    })
+}
+
+async fn es_compat_delete_stored_script(
+    script_id: String,
+    stored_script_store: StoredScriptStore,
+) -> Result<ElasticsearchDeleteResponse, ElasticsearchError> {
+    stored_script_store.delete(&script_id).await?;
+    Ok(ElasticsearchDeleteResponse { acknowledged: true })
+}
+
 async fn es_compat_stats(
     metastore: MetastoreServiceClient,
 ) -> Result<ElasticsearchStatsResponse, ElasticsearchError> {
@@ -514,10 +1916,398 @@ async fn es_compat_index_stats(
     Ok(search_response_rest)
 }
 
+async fn es_compat_analyze(
+    analyze_body: AnalyzeRequestBody,
+) -> Result<AnalyzeResponse, ElasticsearchError> {
+    let tokenizer_manager = create_default_quickwit_tokenizer_manager();
+    let mut text_analyzer = resolve_text_analyzer(&analyze_body, &tokenizer_manager, None)?;
+    Ok(analyze_texts_for_es_api(
+        &mut text_analyzer,
+        &analyze_body.text,
+    ))
+}
+
+async fn es_compat_index_analyze(
+    index_id: String,
+    analyze_body: AnalyzeRequestBody,
+    metastore: MetastoreServiceClient,
+) -> Result<AnalyzeResponse, ElasticsearchError> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id);
+    let index_config = metastore
+        .index_metadata(index_metadata_request)
+        .await
+        .and_then(|response| response.deserialize_index_metadata())
+        .map_err(IndexServiceError::from)?
+        .into_index_config();
+    let doc_mapper = build_doc_mapper(&index_config.doc_mapping, &index_config.search_settings)
+        .map_err(IndexServiceError::InvalidConfig)?;
+    let mut text_analyzer = resolve_text_analyzer(
+        &analyze_body,
+        doc_mapper.tokenizer_manager(),
+        Some(&doc_mapper.schema()),
+    )?;
+    Ok(analyze_texts_for_es_api(
+        &mut text_analyzer,
+        &analyze_body.text,
+    ))
+}
+
+/// Resolves the text analyzer of an `_analyze` request from the `field`, the `analyzer`, or the
+/// `tokenizer` parameter. Without any of them, the `default` tokenizer is used.
+fn resolve_text_analyzer(
+    analyze_body: &AnalyzeRequestBody,
+    tokenizer_manager: &TokenizerManager,
+    schema_opt: Option<&Schema>,
+) -> Result<TextAnalyzer, ElasticsearchError> {
+    let bad_request_error = |message: String| {
+        ElasticsearchError::new(
+            StatusCode::BAD_REQUEST,
+            message,
+            Some(ElasticException::IllegalArgument),
+        )
+    };
+    let num_params = [
+        analyze_body.field.is_some(),
+        analyze_body.analyzer.is_some(),
+        analyze_body.tokenizer.is_some(),
+    ]
+    .into_iter()
+    .filter(|is_some| *is_some)
+    .count();
+    if num_params > 1 {
+        return Err(bad_request_error(
+            "only one of `field`, `analyzer`, or `tokenizer` can be set".to_string(),
+        ));
+    }
+    let tokenizer_name = if let Some(field_name) = &analyze_body.field {
+        let Some(schema) = schema_opt else {
+            return Err(bad_request_error(
+                "`field` can only be used when analyzing text of an index".to_string(),
+            ));
+        };
+        let Some((field, _json_path)) = schema.find_field(field_name) else {
+            return Err(bad_request_error(format!(
+                "field `{field_name}` does not exist"
+            )));
+        };
+        let text_indexing_options_opt = match schema.get_field_entry(field).field_type() {
+            FieldType::Str(text_options) => text_options.get_indexing_options(),
+            FieldType::JsonObject(json_options) => json_options.get_text_indexing_options(),
+            _ => None,
+        };
+        let Some(text_indexing_options) = text_indexing_options_opt else {
+            return Err(bad_request_error(format!(
+                "field `{field_name}` is not an indexed text field"
+            )));
+        };
+        text_indexing_options.tokenizer().to_string()
+    } else if let Some(analyzer_name) = &analyze_body.analyzer {
+        analyzer_name.clone()
+    } else {
+        match &analyze_body.tokenizer {
+            Some(AnalyzeTokenizer::Name(tokenizer_name)) => tokenizer_name.clone(),
+            Some(AnalyzeTokenizer::Config(tokenizer_config)) => {
+                return tokenizer_config
+                    .text_analyzer()
+                    .map_err(|error| bad_request_error(format!("invalid tokenizer: {error:#}")));
+            }
+            None => "default".to_string(),
+        }
+    };
+    // Maps the names of the Elasticsearch built-in analyzers to their closest Quickwit equivalent.
+    let tokenizer_name = match tokenizer_name.as_str() {
+        "standard" => "default",
+        "keyword" => "raw",
+        tokenizer_name => tokenizer_name,
+    };
+    tokenizer_manager
+        .get_tokenizer(tokenizer_name)
+        .ok_or_else(|| bad_request_error(format!("unknown tokenizer `{tokenizer_name}`")))
+}
+
+async fn es_compat_mapping(
+    metastore: MetastoreServiceClient,
+) -> Result<HashMap<String, ElasticsearchIndexMapping>, ElasticsearchError> {
+    es_compat_index_mapping(vec!["*".to_string()], metastore).await
+}
+
+async fn es_compat_index_mapping(
+    index_id_patterns: Vec<String>,
+    mut metastore: MetastoreServiceClient,
+) -> Result<HashMap<String, ElasticsearchIndexMapping>, ElasticsearchError> {
+    let indexes_metadata = resolve_index_patterns(&index_id_patterns, &mut metastore).await?;
+    let mut index_mappings = HashMap::with_capacity(indexes_metadata.len());
+
+    for index_metadata in indexes_metadata {
+        let index_mapping = convert_to_es_mapping(&index_metadata.index_config.doc_mapping)
+            .map_err(|serde_error| {
+                ElasticsearchError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("failed to serialize doc mapping: {serde_error}"),
+                    None,
+                )
+            })?;
+        index_mappings.insert(index_metadata.index_id().to_string(), index_mapping);
+    }
+    Ok(index_mappings)
+}
+
+/// Adds the fields of the request to the doc mapping of the target indexes. All the updates are
+/// validated before any of them is applied.
+async fn es_compat_index_put_mapping(
+    index_id_patterns: Vec<String>,
+    put_mapping_body: PutMappingBody,
+    mut metastore: MetastoreServiceClient,
+) -> Result<PutMappingResponse, ElasticsearchError> {
+    let indexes_metadata = resolve_index_patterns(&index_id_patterns, &mut metastore).await?;
+    let mut update_requests = Vec::with_capacity(indexes_metadata.len());
+
+    for index_metadata in indexes_metadata {
+        let index_uid = index_metadata.index_uid.clone();
+        let current_index_config = index_metadata.into_index_config();
+
+        let Some(new_index_config) =
+            build_index_config_mapping_update(&current_index_config, &put_mapping_body)?
+        else {
+            continue;
+        };
+        let update_request = UpdateIndexRequest::try_from_updates(
+            index_uid,
+            &new_index_config.search_settings,
+            &new_index_config.retention_policy_opt,
+            &new_index_config.indexing_settings,
+            &new_index_config.doc_mapping,
+        )
+        .map_err(IndexServiceError::from)?;
+        update_requests.push(update_request);
+    }
+    for update_request in update_requests {
+        metastore
+            .update_index(update_request)
+            .await
+            .map_err(IndexServiceError::from)?;
+    }
+    Ok(PutMappingResponse { acknowledged: true })
+}
+
+/// Returns the index config with the fields of the `PUT _mapping` request added to its doc
+/// mapping, or `None` if the request does not add any field.
+fn build_index_config_mapping_update(
+    current_index_config: &IndexConfig,
+    put_mapping_body: &PutMappingBody,
+) -> Result<Option<IndexConfig>, ElasticsearchError> {
+    let invalid_mapping_error = |message: String| {
+        ElasticsearchError::new(
+            StatusCode::BAD_REQUEST,
+            message,
+            Some(ElasticException::IllegalArgument),
+        )
+    };
+    let field_mappings_json =
+        serde_json::to_value(&current_index_config.doc_mapping.field_mappings)
+            .map_err(|serde_error| invalid_mapping_error(serde_error.to_string()))?;
+    let JsonValue::Array(mut field_mappings_json) = field_mappings_json else {
+        return Err(invalid_mapping_error(
+            "field mappings should be serialized as an array".to_string(),
+        ));
+    };
+    if !merge_es_properties(&mut field_mappings_json, &put_mapping_body.properties)
+        .map_err(invalid_mapping_error)?
+    {
+        return Ok(None);
+    }
+    let mut new_index_config = current_index_config.clone();
+    new_index_config.doc_mapping.field_mappings =
+        serde_json::from_value(JsonValue::Array(field_mappings_json))
+            .map_err(|serde_error| invalid_mapping_error(serde_error.to_string()))?;
+    new_index_config.doc_mapping.doc_mapping_uid = DocMappingUid::random();
+
+    // Goes through the regular index update validation.
+    let new_index_config_json =
+        serde_json::to_vec(&VersionedIndexConfig::from(new_index_config))
+            .map_err(|serde_error| invalid_mapping_error(serde_error.to_string()))?;
+    let new_index_config = load_index_config_update(
+        ConfigFormat::Json,
+        &new_index_config_json,
+        current_index_config,
+    )
+    .map_err(IndexServiceError::InvalidConfig)?;
+    Ok(Some(new_index_config))
+}
+
+fn make_cat_response(result: Result<CatResponse, ElasticsearchError>) -> warp::reply::Response {
+    match result {
+        Ok(cat_response) if cat_response.format == CatFormat::Text => warp::reply::with_header(
+            cat_response.to_text(),
+            CONTENT_TYPE,
+            "text/plain; charset=UTF-8",
+        )
+        .into_response(),
+        Ok(cat_response) => {
+            make_elastic_api_response(Ok(cat_response.to_json()), BodyFormat::default())
+                .into_response()
+        }
+        Err(error) => {
+            make_elastic_api_response::<()>(Err(error), BodyFormat::default()).into_response()
+        }
+    }
+}
+
+/// Returns the `epoch` and `timestamp` columns of the `_cat` APIs.
+fn cat_epoch_and_timestamp() -> [String; 2] {
+    let now = OffsetDateTime::now_utc();
+    let timestamp = format!("{:02}:{:02}:{:02}", now.hour(), now.minute(), now.second());
+    [now.unix_timestamp().to_string(), timestamp]
+}
+
+async fn es_compat_cat_count(
+    query_params: CatQueryParams,
+    metastore: MetastoreServiceClient,
+) -> Result<CatResponse, ElasticsearchError> {
+    es_compat_index_cat_count(vec!["*".to_string()], query_params, metastore).await
+}
+
+/// Counts the documents of the published splits, like the `docs.count` column of
+/// `_cat/indices`.
+async fn es_compat_index_cat_count(
+    index_id_patterns: Vec<String>,
+    query_params: CatQueryParams,
+    mut metastore: MetastoreServiceClient,
+) -> Result<CatResponse, ElasticsearchError> {
+    let format = CatFormat::from_query_param(query_params.format.as_deref())?;
+    let index_uids = resolve_index_patterns(&index_id_patterns, &mut metastore)
+        .await?
+        .into_iter()
+        .map(|index_metadata| index_metadata.index_uid)
+        .collect_vec();
+    let splits_metadata = list_all_splits(index_uids, &mut metastore).await?;
+    let num_docs: usize = splits_metadata
+        .iter()
+        .map(|split_metadata| split_metadata.num_docs)
+        .sum();
+    let [epoch, timestamp] = cat_epoch_and_timestamp();
+    CatResponse::new(
+        format,
+        query_params.v.unwrap_or(false),
+        &["epoch", "timestamp", "count"],
+        vec![vec![epoch, timestamp, num_docs.to_string()]],
+        query_params.h.as_deref(),
+    )
+}
+
+/// The cluster is green if all its nodes are ready, yellow if some are not, and red if none
+/// is. Quickwit has no shards: the shard columns are reported for compatibility only.
+async fn es_compat_cat_health(
+    query_params: CatQueryParams,
+    cluster: Cluster,
+) -> Result<CatResponse, ElasticsearchError> {
+    let format = CatFormat::from_query_param(query_params.format.as_deref())?;
+    let cluster_snapshot = cluster.snapshot().await;
+    let num_ready_nodes = cluster_snapshot.ready_nodes.len();
+    let num_nodes = num_ready_nodes + cluster_snapshot.live_nodes.len();
+    let status = if num_ready_nodes == 0 {
+        "red"
+    } else if num_ready_nodes < num_nodes {
+        "yellow"
+    } else {
+        "green"
+    };
+    let num_data_nodes = cluster
+        .ready_nodes()
+        .await
+        .iter()
+        .filter(|node| {
+            node.enabled_services().contains(&QuickwitService::Indexer)
+                || node.enabled_services().contains(&QuickwitService::Searcher)
+        })
+        .count();
+    let [epoch, timestamp] = cat_epoch_and_timestamp();
+    let row = vec![
+        epoch,
+        timestamp,
+        cluster_snapshot.cluster_id,
+        status.to_string(),
+        num_nodes.to_string(),
+        num_data_nodes.to_string(),
+        "0".to_string(),
+        "0".to_string(),
+        "0".to_string(),
+        "0".to_string(),
+        "0".to_string(),
+        "0".to_string(),
+        "-".to_string(),
+        "100.0%".to_string(),
+    ];
+    CatResponse::new(
+        format,
+        query_params.v.unwrap_or(false),
+        &[
+            "epoch",
+            "timestamp",
+            "cluster",
+            "status",
+            "node.total",
+            "node.data",
+            "shards",
+            "pri",
+            "relo",
+            "init",
+            "unassign",
+            "pending_tasks",
+            "max_task_wait_time",
+            "active_shards_percent",
+        ],
+        vec![row],
+        query_params.h.as_deref(),
+    )
+}
+
+/// Lists the ready nodes of the cluster. The node running the control plane is reported as the
+/// master node.
+async fn es_compat_cat_nodes(
+    query_params: CatQueryParams,
+    cluster: Cluster,
+) -> Result<CatResponse, ElasticsearchError> {
+    let format = CatFormat::from_query_param(query_params.format.as_deref())?;
+    let mut ready_nodes = cluster.ready_nodes().await;
+    ready_nodes.sort_by(|left, right| left.node_id().cmp(right.node_id()));
+
+    let rows = ready_nodes
+        .iter()
+        .map(|node| {
+            let grpc_advertise_addr = node.grpc_advertise_addr();
+            let node_roles = node
+                .enabled_services()
+                .iter()
+                .map(|service| service.as_str())
+                .sorted()
+                .join(",");
+            let is_master = node
+                .enabled_services()
+                .contains(&QuickwitService::ControlPlane);
+            vec![
+                node.node_id().to_string(),
+                grpc_advertise_addr.ip().to_string(),
+                grpc_advertise_addr.port().to_string(),
+                node_roles,
+                if is_master { "*" } else { "-" }.to_string(),
+                node.node_id().to_string(),
+            ]
+        })
+        .collect();
+    CatResponse::new(
+        format,
+        query_params.v.unwrap_or(false),
+        &["id", "ip", "port", "node.role", "master", "name"],
+        rows,
+        query_params.h.as_deref(),
+    )
+}
+
 async fn es_compat_cat_indices(
     query_params: CatIndexQueryParams,
     metastore: MetastoreServiceClient,
-) -> Result<Vec<serde_json::Value>, ElasticsearchError> {
+) -> Result<CatResponse, ElasticsearchError> {
     es_compat_index_cat_indices(vec!["*".to_string()], query_params, metastore).await
 }
 
@@ -525,8 +2315,8 @@ async fn es_compat_index_cat_indices(
     index_id_patterns: Vec<String>,
     query_params: CatIndexQueryParams,
     mut metastore: MetastoreServiceClient,
-) -> Result<Vec<serde_json::Value>, ElasticsearchError> {
-    query_params.validate()?;
+) -> Result<CatResponse, ElasticsearchError> {
+    let format = query_params.validate()?;
     let indexes_metadata = resolve_index_patterns(&index_id_patterns, &mut metastore).await?;
     let mut index_id_to_resp: HashMap<IndexUid, ElasticsearchCatIndexResponse> = indexes_metadata
         .iter()
@@ -546,7 +2336,7 @@ async fn es_compat_index_cat_indices(
     let search_response_rest: Vec<ElasticsearchCatIndexResponse> =
         convert_to_es_cat_indices_response(&mut index_id_to_resp, splits_metadata);
 
-    let search_response_rest = search_response_rest
+    let rows = search_response_rest
         .into_iter()
         .filter(|resp| {
             if let Some(health) = query_params.health {
@@ -555,8 +2345,8 @@ async fn es_compat_index_cat_indices(
                 true
             }
         })
-        .map(|cat_index| cat_index.serialize_filtered(&query_params.h))
-        .collect::<Result<Vec<serde_json::Value>, serde_json::Error>>()
+        .map(|cat_index| cat_index.to_row())
+        .collect::<Result<Vec<Vec<String>>, serde_json::Error>>()
         .map_err(|serde_error| {
             ElasticsearchError::new(
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -565,7 +2355,13 @@ async fn es_compat_index_cat_indices(
             )
         })?;
 
-    Ok(search_response_rest)
+    CatResponse::new(
+        format,
+        query_params.v.unwrap_or(false),
+        &ElasticsearchCatIndexResponse::COLUMNS,
+        rows,
+        query_params.h.as_deref(),
+    )
 }
 
 async fn es_compat_resolve_index(
@@ -580,25 +2376,160 @@ async fn es_compat_resolve_index(
 
     indices.sort_by(|left, right| left.name.cmp(&right.name));
 
-    Ok(ElasticsearchResolveIndexResponse {
-        indices,
-        ..Default::default()
-    })
+    Ok(ElasticsearchResolveIndexResponse {
+        indices,
+        ..Default::default()
+    })
+}
+
+async fn es_compat_index_field_capabilities(
+    index_id_patterns: Vec<String>,
+    search_params: FieldCapabilityQueryParams,
+    search_body: FieldCapabilityRequestBody,
+    search_service: Arc<dyn SearchService>,
+) -> Result<FieldCapabilityResponse, ElasticsearchError> {
+    let search_request =
+        build_list_field_request_for_es_api(index_id_patterns, search_params, search_body)?;
+    let search_response: ListFieldsResponse =
+        search_service.root_list_fields(search_request).await?;
+    let search_response_rest: FieldCapabilityResponse =
+        convert_to_es_field_capabilities_response(search_response);
+    Ok(search_response_rest)
+}
+
+async fn es_compat_index_terms_enum(
+    index_id_patterns: Vec<String>,
+    query_params: TermsEnumQueryParams,
+    request_body: TermsEnumRequestBody,
+    search_service: Arc<dyn SearchService>,
+) -> Result<TermsEnumResponse, ElasticsearchError> {
+    let Some(list_terms_request) =
+        build_list_terms_request_for_es_api(index_id_patterns, query_params, request_body)?
+    else {
+        return Ok(TermsEnumResponse {
+            terms: Vec::new(),
+            complete: true,
+        });
+    };
+    let list_terms_response = search_service.root_list_terms(list_terms_request).await?;
+    convert_to_es_terms_enum_response(list_terms_response)
+}
+
+/// Parts of the documents returned with the hits of a search request: the filters applied to their
+/// `_source`, and the `fields` whose values are retrieved.
+#[derive(Clone, Debug, Default)]
+struct HitFieldsSelection {
+    source_excludes: Option<Vec<String>>,
+    source_includes: Option<Vec<String>>,
+    fields: Vec<String>,
+}
+
+impl HitFieldsSelection {
+    /// The `_source_includes` and `_source_excludes` query parameters take precedence over the
+    /// `_source` query parameter, which takes precedence over the `_source` of the request body.
+    fn from_request(search_params: &SearchQueryParams, search_body: &SearchBody) -> Self {
+        let (mut source_includes, mut source_excludes) = match search_params._source.as_deref() {
+            Some([enabled]) if enabled == "true" => (None, None),
+            Some([enabled]) if enabled == "false" => (Some(Vec::new()), None),
+            Some(source_includes) => (Some(source_includes.to_vec()), None),
+            None => search_body
+                ._source
+                .clone()
+                .map(|source_filter| source_filter.into_includes_excludes())
+                .unwrap_or_default(),
+        };
+        if search_params._source_includes.is_some() {
+            source_includes = search_params._source_includes.clone();
+        }
+        if search_params._source_excludes.is_some() {
+            source_excludes = search_params._source_excludes.clone();
+        }
+        HitFieldsSelection {
+            source_excludes,
+            source_includes,
+            fields: search_body.fields.clone(),
+        }
+    }
+
+    /// Returns whether the documents are needed to build the hits, i.e. whether their source is
+    /// returned or fields are retrieved from it. Otherwise, they are not read from the doc store.
+    fn needs_source(&self) -> bool {
+        let source_disabled = self
+            .source_includes
+            .as_ref()
+            .is_some_and(|source_includes| source_includes.is_empty());
+        !source_disabled || !self.fields.is_empty()
+    }
+}
+
+/// Retrieves the values of the leaf fields of a document whose path matches one of the requested
+/// field patterns. As in Elasticsearch, the values are always returned as arrays.
+fn retrieve_fields(
+    document: &JsonValue,
+    field_patterns: &[String],
+) -> BTreeMap<String, Vec<JsonValue>> {
+    fn collect_leaf_values(
+        value: &JsonValue,
+        path: &str,
+        field_patterns: &[String],
+        values_by_path: &mut BTreeMap<String, Vec<JsonValue>>,
+    ) {
+        match value {
+            JsonValue::Object(map) => {
+                for (key, sub_value) in map {
+                    let sub_path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{path}.{key}")
+                    };
+                    collect_leaf_values(sub_value, &sub_path, field_patterns, values_by_path);
+                }
+            }
+            JsonValue::Array(values) => {
+                for value in values {
+                    collect_leaf_values(value, path, field_patterns, values_by_path);
+                }
+            }
+            JsonValue::Null => {}
+            leaf_value => {
+                if field_patterns
+                    .iter()
+                    .any(|field_pattern| matches_field_pattern(field_pattern, path))
+                {
+                    values_by_path
+                        .entry(path.to_string())
+                        .or_default()
+                        .push(leaf_value.clone());
+                }
+            }
+        }
+    }
+    let mut values_by_path = BTreeMap::new();
+    if !field_patterns.is_empty() {
+        collect_leaf_values(document, "", field_patterns, &mut values_by_path);
+    }
+    values_by_path
 }
 
-async fn es_compat_index_field_capabilities(
-    index_id_patterns: Vec<String>,
-    search_params: FieldCapabilityQueryParams,
-    search_body: FieldCapabilityRequestBody,
-    search_service: Arc<dyn SearchService>,
-) -> Result<FieldCapabilityResponse, ElasticsearchError> {
-    let search_request =
-        build_list_field_request_for_es_api(index_id_patterns, search_params, search_body)?;
-    let search_response: ListFieldsResponse =
-        search_service.root_list_fields(search_request).await?;
-    let search_response_rest: FieldCapabilityResponse =
-        convert_to_es_field_capabilities_response(search_response);
-    Ok(search_response_rest)
+/// Matches a field path against a pattern, in which `*` matches any sequence of characters.
+fn matches_field_pattern(field_pattern: &str, field_path: &str) -> bool {
+    let mut pattern_parts = field_pattern.split('*');
+    let prefix = pattern_parts.next().unwrap_or_default();
+    let Some(mut remaining) = field_path.strip_prefix(prefix) else {
+        return false;
+    };
+    let mut pattern_parts: Vec<&str> = pattern_parts.collect();
+    let Some(suffix) = pattern_parts.pop() else {
+        // The pattern does not contain any wildcard.
+        return remaining.is_empty();
+    };
+    for pattern_part in pattern_parts {
+        let Some(position) = remaining.find(pattern_part) else {
+            return false;
+        };
+        remaining = &remaining[position + pattern_part.len()..];
+    }
+    remaining.ends_with(suffix)
 }
 
 fn filter_source(
@@ -692,17 +2623,31 @@ fn generate_path_variants_with_suffix(input: &str) -> Vec<(&str, Option<&str>)>
 fn convert_hit(
     hit: quickwit_proto::search::Hit,
     append_shard_doc: bool,
-    _source_excludes: &Option<Vec<String>>,
-    _source_includes: &Option<Vec<String>>,
+    collapse_opt: Option<&quickwit_proto::search::Collapse>,
+    hit_fields_selection: &HitFieldsSelection,
 ) -> ElasticHit {
     let mut json: serde_json::Value = serde_json::from_str(&hit.json).unwrap_or(json!({}));
-    filter_source(&mut json, _source_excludes, _source_includes);
+    // The fields are retrieved from the whole source, regardless of the `_source` filters.
+    let mut fields = retrieve_fields(&json, &hit_fields_selection.fields);
+    // The docvalue fields are read from the fast fields by the searchers.
+    if let Some(docvalue_fields_json) = &hit.docvalue_fields_json {
+        let docvalue_fields: BTreeMap<String, Vec<JsonValue>> =
+            serde_json::from_str(docvalue_fields_json).unwrap_or_default();
+        fields.extend(docvalue_fields);
+    }
+    filter_source(
+        &mut json,
+        &hit_fields_selection.source_excludes,
+        &hit_fields_selection.source_includes,
+    );
     let source =
         Source::from_string(serde_json::to_string(&json).unwrap_or_else(|_| "{}".to_string()))
             .unwrap_or_else(|_| Source::from_string("{}".to_string()).unwrap());
 
     let mut sort = Vec::new();
+    let mut collapse_value_opt = None;
     if let Some(partial_hit) = hit.partial_hit {
+        collapse_value_opt = partial_hit.collapse_value.clone();
         if let Some(sort_value) = partial_hit.sort_value {
             sort.push(sort_value.into_json());
         }
@@ -716,17 +2661,67 @@ fn convert_hit(
         }
     }
 
+    // Fields without any highlighted terms are omitted, as in Elasticsearch.
+    let highlight = hit
+        .snippet
+        .and_then(|snippet_json| {
+            serde_json::from_str::<HashMap<String, Vec<String>>>(&snippet_json).ok()
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, fragments)| !fragments.is_empty())
+        .collect();
+
+    // As in Elasticsearch, the value of the collapse field is returned in the `fields` of the hit
+    // and the collapsed hits are returned under the name of the `inner_hits` definition.
+    if let Some((collapse, collapse_value)) = collapse_opt.zip(collapse_value_opt) {
+        fields.insert(
+            collapse.field.clone(),
+            vec![JsonValue::String(collapse_value)],
+        );
+    }
+    let fields = fields
+        .into_iter()
+        .map(|(field_path, values)| (field_path, JsonValue::Array(values)))
+        .collect();
+    let inner_hits = collapse_opt
+        .and_then(|collapse| collapse.inner_hits_name.clone())
+        .filter(|_| !hit.inner_hits.is_empty())
+        .map(|inner_hits_name| {
+            let hits = hit
+                .inner_hits
+                .into_iter()
+                .map(|inner_hit| {
+                    convert_hit(inner_hit, append_shard_doc, None, hit_fields_selection)
+                })
+                .collect();
+            let inner_hits_result = elasticsearch_dsl::InnerHitsResult {
+                hits: HitsMetadata {
+                    total: None,
+                    max_score: None,
+                    hits,
+                },
+            };
+            (inner_hits_name, inner_hits_result)
+        })
+        .into_iter()
+        .collect();
+
+    let explanation = hit
+        .explanation_json
+        .and_then(|explanation_json| serde_json::from_str(&explanation_json).ok());
+
     ElasticHit {
-        fields: Default::default(),
-        explanation: None,
+        fields,
+        explanation,
         index: hit.index_id,
         id: "".to_string(),
         score: None,
         nested: None,
         source,
-        highlight: Default::default(),
-        inner_hits: Default::default(),
-        matched_queries: Vec::default(),
+        highlight,
+        inner_hits,
+        matched_queries: hit.matched_queries,
         sort,
     }
 }
@@ -735,7 +2730,9 @@ async fn es_compat_index_multi_search(
     payload: Bytes,
     multi_search_params: MultiSearchQueryParams,
     search_service: Arc<dyn SearchService>,
+    index_alias_store: IndexAliasStore,
 ) -> Result<MultiSearchResponse, ElasticsearchError> {
+    let index_aliases = index_alias_store.load().await?;
     let mut search_requests = Vec::new();
     let str_payload = from_utf8(&payload)
         .map_err(|err| SearchError::InvalidQuery(format!("invalid UTF-8: {}", err)))?;
@@ -762,7 +2759,8 @@ async fn es_compat_index_multi_search(
                 ))
             })?;
         }
-        let index_ids_patterns = request_header.index.clone();
+        let (index_ids_patterns, alias_filter_opt) =
+            resolve_search_target(&index_aliases, request_header.index.clone())?;
         let search_body = payload_lines
             .next()
             .ok_or_else(|| {
@@ -787,33 +2785,51 @@ async fn es_compat_index_multi_search(
         if let Some(extra_filters) = &multi_search_params.extra_filters {
             search_query_params.extra_filters = Some(extra_filters.to_vec());
         }
-        let es_request =
+        search_query_params.priority = multi_search_params.priority;
+        search_query_params.api_key_id = multi_search_params.api_key_id.clone();
+        let hit_fields_selection =
+            HitFieldsSelection::from_request(&search_query_params, &search_body);
+        let (mut search_request, append_shard_doc) =
             build_request_for_es_api(index_ids_patterns, search_query_params, search_body)?;
-        search_requests.push(es_request);
+        if let Some(alias_filter) = alias_filter_opt {
+            add_alias_filter(&mut search_request, alias_filter);
+        }
+        search_requests.push((search_request, append_shard_doc, hit_fields_selection));
     }
     // TODO: forced to do weird referencing to work around https://github.com/rust-lang/rust/issues/100905
     // otherwise append_shard_doc is captured by ref, and we get lifetime issues
-    let futures = search_requests
-        .into_iter()
-        .map(|(search_request, append_shard_doc)| {
+    let futures = search_requests.into_iter().map(
+        |(search_request, append_shard_doc, hit_fields_selection)| {
             let search_service = &search_service;
-            let _source_excludes = multi_search_params._source_excludes.clone();
-            let _source_includes = multi_search_params._source_includes.clone();
             async move {
                 let start_instant = Instant::now();
-                let search_response: SearchResponse =
+                let aggregation_request = search_request.aggregation_request.clone();
+                let collapse_opt = search_request.collapse.clone();
+                let suggesters = search_request.suggesters.clone();
+                let mut search_response: SearchResponse =
                     search_service.clone().root_search(search_request).await?;
                 let elapsed = start_instant.elapsed();
+                let suggestions = std::mem::take(&mut search_response.suggestions);
+                let split_profiles = std::mem::take(&mut search_response.split_profiles);
                 let mut search_response_rest: ElasticsearchResponse = convert_to_es_search_response(
                     search_response,
                     append_shard_doc,
-                    _source_excludes,
-                    _source_includes,
+                    collapse_opt.as_ref(),
+                    &hit_fields_selection,
+                );
+                convert_aggregation_results(
+                    aggregation_request.as_deref(),
+                    &mut search_response_rest,
                 );
                 search_response_rest.took = elapsed.as_millis() as u32;
-                Ok::<_, ElasticsearchError>(search_response_rest)
+                Ok::<_, ElasticsearchError>(ElasticsearchSuggestResponse {
+                    response: search_response_rest,
+                    suggest: convert_to_es_suggest(&suggesters, suggestions),
+                    profile: convert_to_es_profile(split_profiles),
+                })
             }
-        });
+        },
+    );
     let max_concurrent_searches =
         multi_search_params.max_concurrent_searches.unwrap_or(10) as usize;
     let search_responses = futures::stream::iter(futures)
@@ -853,7 +2869,7 @@ async fn es_scroll(
     let search_response: SearchResponse = search_service.scroll(scroll_request).await?;
     // TODO append_shard_doc depends on the initial request, but we don't have access to it
     let mut search_response_rest: ElasticsearchResponse =
-        convert_to_es_search_response(search_response, false, None, None);
+        convert_to_es_search_response(search_response, false, None, &HitFieldsSelection::default());
     search_response_rest.took = start_instant.elapsed().as_millis() as u32;
     Ok(search_response_rest)
 }
@@ -916,13 +2932,13 @@ fn convert_to_es_stats_response(
 fn convert_to_es_search_response(
     resp: SearchResponse,
     append_shard_doc: bool,
-    _source_excludes: Option<Vec<String>>,
-    _source_includes: Option<Vec<String>>,
+    collapse_opt: Option<&quickwit_proto::search::Collapse>,
+    hit_fields_selection: &HitFieldsSelection,
 ) -> ElasticsearchResponse {
     let hits: Vec<ElasticHit> = resp
         .hits
         .into_iter()
-        .map(|hit| convert_hit(hit, append_shard_doc, &_source_excludes, &_source_includes))
+        .map(|hit| convert_hit(hit, append_shard_doc, collapse_opt, hit_fields_selection))
         .collect();
     let aggregations: Option<serde_json::Value> = if let Some(aggregation_json) = resp.aggregation {
         serde_json::from_str(&aggregation_json).ok()
@@ -930,7 +2946,7 @@ fn convert_to_es_search_response(
         None
     };
     ElasticsearchResponse {
-        timed_out: false,
+        timed_out: resp.timed_out,
         hits: HitsMetadata {
             total: Some(TotalHits {
                 value: resp.num_hits,
@@ -945,6 +2961,78 @@ fn convert_to_es_search_response(
     }
 }
 
+fn async_search_shards(
+    async_search_response: &quickwit_search::AsyncSearchResponse,
+) -> AsyncSearchShards {
+    let failed = if async_search_response.error.is_some() {
+        async_search_response
+            .num_splits
+            .saturating_sub(async_search_response.num_searched_splits)
+    } else {
+        0
+    };
+    AsyncSearchShards {
+        total: async_search_response.num_splits,
+        successful: async_search_response.num_searched_splits,
+        skipped: 0,
+        failed,
+    }
+}
+
+fn convert_to_es_async_search_response(
+    mut async_search_response: quickwit_search::AsyncSearchResponse,
+    hit_fields_selection: &HitFieldsSelection,
+) -> AsyncSearchResponse {
+    let shards = async_search_shards(&async_search_response);
+    let append_shard_doc = async_search_response
+        .search_request
+        .sort_fields
+        .iter()
+        .any(is_doc_field);
+    let took = async_search_response.response.elapsed_time_micros / 1_000;
+    let suggestions = std::mem::take(&mut async_search_response.response.suggestions);
+    let split_profiles = std::mem::take(&mut async_search_response.response.split_profiles);
+    let mut response = convert_to_es_search_response(
+        async_search_response.response,
+        append_shard_doc,
+        async_search_response.search_request.collapse.as_ref(),
+        hit_fields_selection,
+    );
+    convert_aggregation_results(
+        async_search_response
+            .search_request
+            .aggregation_request
+            .as_deref(),
+        &mut response,
+    );
+    response.took = took as u32;
+    response.pit_id = async_search_response
+        .search_request
+        .point_in_time
+        .map(|point_in_time| point_in_time.id);
+    let error = async_search_response
+        .error
+        .map(|search_error| ElasticsearchError::from(search_error).error);
+    AsyncSearchResponse {
+        id: Some(async_search_response.id),
+        is_partial: async_search_response.is_partial,
+        is_running: async_search_response.is_running,
+        start_time_in_millis: async_search_response.start_time_millis,
+        expiration_time_in_millis: async_search_response.expiration_time_millis,
+        completion_time_in_millis: async_search_response.completion_time_millis,
+        shards,
+        response: ElasticsearchSuggestResponse {
+            response,
+            suggest: convert_to_es_suggest(
+                &async_search_response.search_request.suggesters,
+                suggestions,
+            ),
+            profile: convert_to_es_profile(split_profiles),
+        },
+        error,
+    }
+}
+
 pub(crate) fn str_lines(body: &str) -> impl Iterator<Item = &str> {
     body.lines()
         .map(|line| line.trim())
@@ -954,6 +3042,7 @@ pub(crate) fn str_lines(body: &str) -> impl Iterator<Item = &str> {
 #[cfg(test)]
 mod tests {
     use hyper::StatusCode;
+    use quickwit_proto::search::SortMissing;
 
     use super::{partial_hit_from_search_after_param, *};
 
@@ -976,6 +3065,7 @@ mod tests {
             field_name: "field1".to_string(),
             sort_order: 1,
             sort_datetime_format: None,
+            missing: SortMissing::Last as i32,
         }];
         let error = partial_hit_from_search_after_param(search_after, sort_order).unwrap_err();
         assert_eq!(error.status, StatusCode::BAD_REQUEST);
@@ -992,6 +3082,7 @@ mod tests {
             field_name: "_doc".to_string(),
             sort_order: 1,
             sort_datetime_format: None,
+            missing: SortMissing::Last as i32,
         }];
         let error = partial_hit_from_search_after_param(search_after, sort_order).unwrap_err();
         assert_eq!(error.status, StatusCode::BAD_REQUEST);
@@ -1002,6 +3093,590 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_request_for_es_api_with_named_queries() {
+        let search_body: SearchBody = serde_json::from_value(json!({
+            "query": {
+                "bool": {
+                    "should": [
+                        { "term": { "severity": { "value": "ERROR", "_name": "errors" } } },
+                        { "match": { "body": { "query": "timeout", "_name": "timeouts" } } }
+                    ]
+                }
+            }
+        }))
+        .unwrap();
+        let (search_request, _) = build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body,
+        )
+        .unwrap();
+        let named_query_names: Vec<&str> = search_request
+            .named_queries
+            .iter()
+            .map(|named_query| named_query.name.as_str())
+            .collect();
+        assert_eq!(named_query_names, ["errors", "timeouts"]);
+
+        let named_query_ast: QueryAst =
+            serde_json::from_str(&search_request.named_queries[0].query_ast).unwrap();
+        let QueryAst::Term(term_query) = named_query_ast else {
+            panic!("expected a term query, got {named_query_ast:?}");
+        };
+        assert_eq!(term_query.field, "severity");
+        assert_eq!(term_query.value, "ERROR");
+
+        let hit = quickwit_proto::search::Hit {
+            json: r#"{"severity": "ERROR"}"#.to_string(),
+            matched_queries: vec!["errors".to_string()],
+            ..Default::default()
+        };
+        let elastic_hit = convert_hit(hit, false, None, &HitFieldsSelection::default());
+        assert_eq!(elastic_hit.matched_queries, ["errors"]);
+    }
+
+    #[test]
+    fn test_build_request_for_es_api_with_highlight() {
+        let search_body: SearchBody = serde_json::from_value(json!({
+            "query": { "match": { "body": "beagle" } },
+            "highlight": {
+                "pre_tags": ["@kibana-highlighted-field@"],
+                "post_tags": ["@/kibana-highlighted-field@"],
+                "fields": { "*": {} },
+                "fragment_size": 2147483647
+            }
+        }))
+        .unwrap();
+        let (search_request, _) = build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body,
+        )
+        .unwrap();
+        assert_eq!(search_request.snippet_fields, ["*"]);
+        assert_eq!(
+            search_request.snippet_options,
+            Some(SnippetOptions {
+                pre_tag: Some("@kibana-highlighted-field@".to_string()),
+                post_tag: Some("@/kibana-highlighted-field@".to_string()),
+                fragment_size: Some(2147483647),
+            })
+        );
+
+        let hit = quickwit_proto::search::Hit {
+            json: r#"{"title": "snoopy", "body": "Snoopy is a beagle."}"#.to_string(),
+            snippet: Some(r#"{"title": [], "body": ["Snoopy is a <em>beagle</em>"]}"#.to_string()),
+            ..Default::default()
+        };
+        let elastic_hit = convert_hit(hit, false, None, &HitFieldsSelection::default());
+        let elastic_hit_json = serde_json::to_value(&elastic_hit).unwrap();
+        assert_eq!(
+            elastic_hit_json["highlight"],
+            json!({ "body": ["Snoopy is a <em>beagle</em>"] })
+        );
+    }
+
+    #[test]
+    fn test_build_request_for_es_api_with_timeout() {
+        let search_body: SearchBody = serde_json::from_value(json!({ "timeout": "2s" })).unwrap();
+        let (search_request, _) = build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body.clone(),
+        )
+        .unwrap();
+        assert_eq!(search_request.timeout_millis, Some(2_000));
+
+        // The query parameter takes priority over the body.
+        let search_params = SearchQueryParams {
+            timeout: Some("500ms".to_string()),
+            ..Default::default()
+        };
+        let (search_request, _) =
+            build_request_for_es_api(vec!["my-index".to_string()], search_params, search_body)
+                .unwrap();
+        assert_eq!(search_request.timeout_millis, Some(500));
+
+        let search_body: SearchBody =
+            serde_json::from_value(json!({ "timeout": "whenever" })).unwrap();
+        build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body,
+        )
+        .unwrap_err();
+
+        let search_response = SearchResponse {
+            num_hits: 1,
+            timed_out: true,
+            timed_out_splits: vec!["split_1".to_string()],
+            ..Default::default()
+        };
+        let es_response = convert_to_es_search_response(
+            search_response,
+            false,
+            None,
+            &HitFieldsSelection::default(),
+        );
+        assert!(es_response.timed_out);
+    }
+
+    #[test]
+    fn test_build_request_for_es_api_with_explain() {
+        let search_body: SearchBody = serde_json::from_value(json!({ "explain": true })).unwrap();
+        let (search_request, _) = build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body,
+        )
+        .unwrap();
+        assert!(search_request.explain);
+
+        // The `explain` query parameter takes priority over the one in the body.
+        let search_body: SearchBody = serde_json::from_value(json!({ "explain": true })).unwrap();
+        let search_params = SearchQueryParams {
+            explain: Some(false),
+            ..Default::default()
+        };
+        let (search_request, _) =
+            build_request_for_es_api(vec!["my-index".to_string()], search_params, search_body)
+                .unwrap();
+        assert!(!search_request.explain);
+
+        let hit = quickwit_proto::search::Hit {
+            json: r#"{"title": "one"}"#.to_string(),
+            explanation_json: Some(
+                r#"{"value": 0.5, "description": "TermQuery, product of...", "details": []}"#
+                    .to_string(),
+            ),
+            ..Default::default()
+        };
+        let elastic_hit = convert_hit(hit, false, None, &HitFieldsSelection::default());
+        let elastic_hit_json = serde_json::to_value(&elastic_hit).unwrap();
+        assert_eq!(
+            elastic_hit_json["_explanation"]["description"],
+            "TermQuery, product of..."
+        );
+        assert_eq!(elastic_hit_json["_explanation"]["value"], 0.5);
+    }
+
+    #[test]
+    fn test_build_request_for_es_api_with_collapse() {
+        let search_body: SearchBody = serde_json::from_value(json!({
+            "collapse": {
+                "field": "user",
+                "inner_hits": { "name": "most_recent", "size": 2 }
+            }
+        }))
+        .unwrap();
+        let (search_request, _) = build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body,
+        )
+        .unwrap();
+        let collapse = search_request.collapse.unwrap();
+        assert_eq!(
+            collapse,
+            quickwit_proto::search::Collapse {
+                field: "user".to_string(),
+                inner_hits: 2,
+                inner_hits_name: Some("most_recent".to_string()),
+            }
+        );
+
+        let inner_hit = quickwit_proto::search::Hit {
+            json: r#"{"user": "kimchy", "message": "second"}"#.to_string(),
+            ..Default::default()
+        };
+        let hit = quickwit_proto::search::Hit {
+            json: r#"{"user": "kimchy", "message": "first"}"#.to_string(),
+            partial_hit: Some(PartialHit {
+                collapse_value: Some("kimchy".to_string()),
+                ..Default::default()
+            }),
+            inner_hits: vec![inner_hit],
+            ..Default::default()
+        };
+        let elastic_hit = convert_hit(hit, false, Some(&collapse), &HitFieldsSelection::default());
+        let elastic_hit_json = serde_json::to_value(&elastic_hit).unwrap();
+        assert_eq!(elastic_hit_json["fields"], json!({ "user": ["kimchy"] }));
+        assert_eq!(
+            elastic_hit_json["inner_hits"]["most_recent"]["hits"]["hits"][0]["_source"],
+            json!({ "user": "kimchy", "message": "second" })
+        );
+    }
+
+    #[test]
+    fn test_build_request_for_es_api_strips_percentiles_accuracy_params() {
+        let search_body: SearchBody = serde_json::from_value(json!({
+            "aggs": {
+                "latency": {
+                    "percentiles": {
+                        "field": "latency",
+                        "percents": [50, 99],
+                        "tdigest": { "compression": 200 }
+                    }
+                },
+                "per_service": {
+                    "terms": { "field": "service" },
+                    "aggs": {
+                        "latency": {
+                            "percentiles": {
+                                "field": "latency",
+                                "hdr": { "number_of_significant_value_digits": 3 }
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let (search_request, _) = build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body,
+        )
+        .unwrap();
+        let aggregations: JsonValue =
+            serde_json::from_str(&search_request.aggregation_request.unwrap()).unwrap();
+        assert_eq!(
+            aggregations,
+            json!({
+                "latency": {
+                    "percentiles": { "field": "latency", "percents": [50, 99] }
+                },
+                "per_service": {
+                    "terms": { "field": "service" },
+                    "aggs": {
+                        "latency": {
+                            "percentiles": { "field": "latency" }
+                        }
+                    }
+                }
+            })
+        );
+
+        for invalid_percentiles_params in [
+            json!({ "field": "latency", "tdigest": { "compression": -1 } }),
+            json!({ "field": "latency", "hdr": { "number_of_significant_value_digits": 6 } }),
+            json!({ "field": "latency", "tdigest": {}, "hdr": {} }),
+        ] {
+            let search_body: SearchBody = serde_json::from_value(json!({
+                "aggs": { "latency": { "percentiles": invalid_percentiles_params } }
+            }))
+            .unwrap();
+            let error = build_request_for_es_api(
+                vec!["my-index".to_string()],
+                SearchQueryParams::default(),
+                search_body,
+            )
+            .unwrap_err();
+            assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[test]
+    fn test_build_request_for_es_api_rewrites_top_hits_params() {
+        let search_body: SearchBody = serde_json::from_value(json!({
+            "aggs": {
+                "per_host": {
+                    "terms": { "field": "host" },
+                    "aggs": {
+                        "latest": {
+                            "top_hits": {
+                                "size": 1,
+                                "sort": [{ "timestamp": { "order": "desc" } }],
+                                "_source": { "includes": ["message", "level"] }
+                            }
+                        }
+                    }
+                },
+                "first": {
+                    "top_hits": {
+                        "size": 2,
+                        "sort": "timestamp",
+                        "_source": "message",
+                        "docvalue_fields": ["message", "level"]
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let (search_request, _) = build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body,
+        )
+        .unwrap();
+        let aggregations: JsonValue =
+            serde_json::from_str(&search_request.aggregation_request.unwrap()).unwrap();
+        assert_eq!(
+            aggregations,
+            json!({
+                "per_host": {
+                    "terms": { "field": "host" },
+                    "aggs": {
+                        "latest": {
+                            "top_hits": {
+                                "size": 1,
+                                "sort": [{ "timestamp": "desc" }],
+                                "docvalue_fields": ["message", "level"]
+                            }
+                        }
+                    }
+                },
+                "first": {
+                    "top_hits": {
+                        "size": 2,
+                        "sort": [{ "timestamp": "asc" }],
+                        "docvalue_fields": ["message", "level"]
+                    }
+                }
+            })
+        );
+
+        for invalid_top_hits_params in [
+            json!({ "_source": { "excludes": ["message"] } }),
+            json!({ "_source": 1 }),
+            json!({ "sort": [1] }),
+        ] {
+            let search_body: SearchBody = serde_json::from_value(json!({
+                "aggs": { "latest": { "top_hits": invalid_top_hits_params } }
+            }))
+            .unwrap();
+            let error = build_request_for_es_api(
+                vec!["my-index".to_string()],
+                SearchQueryParams::default(),
+                search_body,
+            )
+            .unwrap_err();
+            assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[test]
+    fn test_build_request_for_es_api_rewrites_filters_params() {
+        let search_body: SearchBody = serde_json::from_value(json!({
+            "aggs": {
+                "statuses": {
+                    "filters": {
+                        "filters": {
+                            "errors": { "term": { "status": { "value": "500" } } },
+                            "others": { "match_all": {} }
+                        },
+                        "keyed": true,
+                        "other_bucket": true
+                    },
+                    "aggs": {
+                        "avg_latency": { "avg": { "field": "latency" } }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let (search_request, _) = build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body,
+        )
+        .unwrap();
+        let aggregations: JsonValue =
+            serde_json::from_str(&search_request.aggregation_request.unwrap()).unwrap();
+        assert_eq!(
+            aggregations,
+            json!({
+                "statuses": {
+                    "filters": {
+                        "filters": {
+                            "errors": { "type": "term", "field": "status", "value": "500" },
+                            "others": { "type": "match_all" }
+                        },
+                        "other_bucket": true
+                    },
+                    "aggs": {
+                        "avg_latency": { "avg": { "field": "latency" } }
+                    }
+                }
+            })
+        );
+
+        for invalid_filters_params in [
+            json!({ "filters": [{ "match_all": {} }] }),
+            json!({ "filters": { "errors": { "match_all": {} } }, "keyed": false }),
+            json!({ "filters": { "errors": { "unknown_query": {} } } }),
+        ] {
+            let search_body: SearchBody = serde_json::from_value(json!({
+                "aggs": { "statuses": { "filters": invalid_filters_params } }
+            }))
+            .unwrap();
+            let error = build_request_for_es_api(
+                vec!["my-index".to_string()],
+                SearchQueryParams::default(),
+                search_body,
+            )
+            .unwrap_err();
+            assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[test]
+    fn test_build_request_for_es_api_rewrites_date_range_params() {
+        let search_body: SearchBody = serde_json::from_value(json!({
+            "aggs": {
+                "per_host": {
+                    "terms": { "field": "host" },
+                    "aggs": {
+                        "periods": {
+                            "date_range": {
+                                "field": "timestamp",
+                                "ranges": [
+                                    { "to": "2015-01-01" },
+                                    {
+                                        "key": "january",
+                                        "from": "2015-01-01T00:00:00Z",
+                                        "to": "2015-01-01||+1M"
+                                    },
+                                    { "from": 1422748800000_i64 }
+                                ],
+                                "keyed": true
+                            }
+                        }
+                    }
+                },
+                "recent": {
+                    "date_range": {
+                        "field": "timestamp",
+                        "format": "dd/MM/yyyy",
+                        "ranges": [{ "from": "01/02/2015" }]
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let (search_request, _) = build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body,
+        )
+        .unwrap();
+        let aggregations: JsonValue =
+            serde_json::from_str(&search_request.aggregation_request.unwrap()).unwrap();
+        assert_eq!(
+            aggregations,
+            json!({
+                "per_host": {
+                    "terms": { "field": "host" },
+                    "aggs": {
+                        "periods": {
+                            "range": {
+                                "field": "timestamp",
+                                "ranges": [
+                                    { "to": 1420070400000000000.0 },
+                                    {
+                                        "key": "january",
+                                        "from": 1420070400000000000.0,
+                                        "to": 1422748800000000000.0
+                                    },
+                                    { "from": 1422748800000000000.0 }
+                                ],
+                                "keyed": true
+                            }
+                        }
+                    }
+                },
+                "recent": {
+                    "range": {
+                        "field": "timestamp",
+                        "ranges": [{ "from": 1422748800000000000.0 }]
+                    }
+                }
+            })
+        );
+
+        for invalid_date_range_params in [
+            json!({ "field": "timestamp", "ranges": [{ "from": "yesterday" }] }),
+            json!({ "field": "timestamp", "ranges": [{ "from": true }] }),
+            json!({ "field": "timestamp", "ranges": {} }),
+            json!({ "field": "timestamp", "ranges": [], "time_zone": "+01:00" }),
+        ] {
+            let search_body: SearchBody = serde_json::from_value(json!({
+                "aggs": { "periods": { "date_range": invalid_date_range_params } }
+            }))
+            .unwrap();
+            let error = build_request_for_es_api(
+                vec!["my-index".to_string()],
+                SearchQueryParams::default(),
+                search_body,
+            )
+            .unwrap_err();
+            assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[test]
+    fn test_convert_top_hits_results() {
+        let aggregations_request: serde_json::Map<String, JsonValue> =
+            serde_json::from_value(json!({
+                "per_host": {
+                    "terms": { "field": "host" },
+                    "aggs": {
+                        "latest": { "top_hits": { "size": 1, "sort": [{ "timestamp": "desc" }] } }
+                    }
+                }
+            }))
+            .unwrap();
+        let mut aggregations_result = json!({
+            "per_host": {
+                "buckets": [
+                    {
+                        "key": "host-1",
+                        "doc_count": 3,
+                        "latest": {
+                            "hits": [{
+                                "sort": [1702166400000000000u64],
+                                "docvalue_fields": { "message": "hello", "level": ["info"] }
+                            }]
+                        }
+                    },
+                    { "key": "host-2", "doc_count": 0, "latest": { "hits": [] } }
+                ],
+                "sum_other_doc_count": 0
+            }
+        });
+        convert_top_hits_results(&aggregations_request, &mut aggregations_result);
+        assert_eq!(
+            aggregations_result,
+            json!({
+                "per_host": {
+                    "buckets": [
+                        {
+                            "key": "host-1",
+                            "doc_count": 3,
+                            "latest": {
+                                "hits": {
+                                    "max_score": null,
+                                    "hits": [{
+                                        "_source": { "message": "hello", "level": ["info"] },
+                                        "fields": { "message": ["hello"], "level": ["info"] },
+                                        "sort": [1702166400000000000u64]
+                                    }]
+                                }
+                            }
+                        },
+                        {
+                            "key": "host-2",
+                            "doc_count": 0,
+                            "latest": { "hits": { "max_score": null, "hits": [] } }
+                        }
+                    ],
+                    "sum_other_doc_count": 0
+                }
+            })
+        );
+    }
+
     #[test]
     fn test_single_element() {
         let input = "app";
@@ -1133,4 +3808,150 @@ mod tests {
 
         assert_eq!(fields, expected);
     }
+
+    #[test]
+    fn test_hit_fields_selection_from_request() {
+        let search_body: SearchBody = serde_json::from_value(json!({
+            "_source": { "includes": ["app", "user"], "excludes": "user.id" },
+            "fields": ["app.*"]
+        }))
+        .unwrap();
+        let hit_fields_selection =
+            HitFieldsSelection::from_request(&SearchQueryParams::default(), &search_body);
+        assert_eq!(
+            hit_fields_selection.source_includes,
+            Some(vec!["app".to_string(), "user".to_string()])
+        );
+        assert_eq!(
+            hit_fields_selection.source_excludes,
+            Some(vec!["user.id".to_string()])
+        );
+        assert_eq!(hit_fields_selection.fields, vec!["app.*".to_string()]);
+
+        let search_params = SearchQueryParams {
+            _source: Some(vec!["false".to_string()]),
+            _source_excludes: Some(vec!["app.id".to_string()]),
+            ..Default::default()
+        };
+        let hit_fields_selection = HitFieldsSelection::from_request(&search_params, &search_body);
+        assert_eq!(hit_fields_selection.source_includes, Some(Vec::new()));
+        assert_eq!(
+            hit_fields_selection.source_excludes,
+            Some(vec!["app.id".to_string()])
+        );
+
+        let search_params = SearchQueryParams {
+            _source: Some(vec!["true".to_string()]),
+            ..Default::default()
+        };
+        let hit_fields_selection = HitFieldsSelection::from_request(&search_params, &search_body);
+        assert_eq!(hit_fields_selection.source_includes, None);
+        assert_eq!(hit_fields_selection.source_excludes, None);
+    }
+
+    #[test]
+    fn test_build_request_for_es_api_with_docvalue_fields() {
+        let search_body: SearchBody = serde_json::from_value(json!({
+            "_source": false,
+            "docvalue_fields": ["status", { "field": "timestamp", "format": "date_time" }]
+        }))
+        .unwrap();
+        let (search_request, _) = build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body,
+        )
+        .unwrap();
+        assert_eq!(
+            search_request.docvalue_fields,
+            vec!["status".to_string(), "timestamp".to_string()]
+        );
+        assert!(search_request.skip_source);
+
+        // The documents are still read when fields are retrieved from their source.
+        let search_body: SearchBody = serde_json::from_value(json!({
+            "_source": false,
+            "fields": ["status"],
+            "docvalue_fields": ["status"]
+        }))
+        .unwrap();
+        let (search_request, _) = build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body,
+        )
+        .unwrap();
+        assert!(!search_request.skip_source);
+
+        let search_body: SearchBody =
+            serde_json::from_value(json!({ "docvalue_fields": ["status"] })).unwrap();
+        let (search_request, _) = build_request_for_es_api(
+            vec!["my-index".to_string()],
+            SearchQueryParams::default(),
+            search_body,
+        )
+        .unwrap();
+        assert!(!search_request.skip_source);
+    }
+
+    #[test]
+    fn test_convert_hit_with_docvalue_fields() {
+        let hit = quickwit_proto::search::Hit {
+            json: "{}".to_string(),
+            docvalue_fields_json: Some(r#"{"status": [200], "tags": ["a", "b"]}"#.to_string()),
+            ..Default::default()
+        };
+        let elastic_hit = convert_hit(hit, false, None, &HitFieldsSelection::default());
+        let elastic_hit_json = serde_json::to_value(&elastic_hit).unwrap();
+        assert_eq!(
+            elastic_hit_json["fields"],
+            json!({ "status": [200], "tags": ["a", "b"] })
+        );
+    }
+
+    #[test]
+    fn test_matches_field_pattern() {
+        assert!(matches_field_pattern("app.id", "app.id"));
+        assert!(!matches_field_pattern("app.id", "app.id2"));
+        assert!(!matches_field_pattern("app", "app.id"));
+        assert!(matches_field_pattern("app.*", "app.id"));
+        assert!(matches_field_pattern("*", "app.id"));
+        assert!(matches_field_pattern("*.id", "user.id"));
+        assert!(!matches_field_pattern("*.id", "user.name"));
+        assert!(matches_field_pattern("a*p*d", "app.id"));
+        assert!(!matches_field_pattern("a*b*b", "ab"));
+    }
+
+    #[test]
+    fn test_convert_hit_with_hit_fields_selection() {
+        let hit_fields_selection = HitFieldsSelection {
+            source_excludes: None,
+            source_includes: Some(Vec::new()),
+            fields: vec![
+                "app.*".to_string(),
+                "tags".to_string(),
+                "missing".to_string(),
+            ],
+        };
+        let hit = quickwit_proto::search::Hit {
+            json: json!({
+                "app": { "id": 123, "name": "Blub", "version": null },
+                "tags": ["a", "b"],
+                "user": { "id": 456 }
+            })
+            .to_string(),
+            ..Default::default()
+        };
+        let elastic_hit = convert_hit(hit, false, None, &hit_fields_selection);
+        let elastic_hit_json = serde_json::to_value(&elastic_hit).unwrap();
+        assert_eq!(elastic_hit_json["_source"], json!({}));
+        assert_eq!(
+            elastic_hit_json["fields"],
+            json!({
+                "app.id": [123],
+                "app.name": ["Blub"],
+                "tags": ["a", "b"]
+            })
+        );
+    }
 }
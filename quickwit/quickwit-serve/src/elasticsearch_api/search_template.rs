@@ -0,0 +1,565 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{bail, Context};
+use hyper::StatusCode;
+use quickwit_common::uri::Uri;
+use quickwit_storage::{Storage, StorageErrorKind, StorageResolver};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use super::model::{ElasticException, ElasticsearchError, StoredScript};
+
+/// The only script language supported by search templates.
+pub(crate) const MUSTACHE_LANG: &str = "mustache";
+
+/// Stores search templates as JSON files in the `_scripts` directory of the default index root
+/// URI. Index IDs cannot start with an underscore, so this directory never collides with an
+/// index directory.
+#[derive(Clone)]
+pub struct StoredScriptStore {
+    storage_resolver: StorageResolver,
+    scripts_uri: Uri,
+}
+
+impl StoredScriptStore {
+    pub fn new(storage_resolver: StorageResolver, default_index_root_uri: &Uri) -> Self {
+        let scripts_uri = default_index_root_uri
+            .join("_scripts")
+            .expect("`_scripts` should be a valid relative path");
+        Self {
+            storage_resolver,
+            scripts_uri,
+        }
+    }
+
+    pub async fn put(
+        &self,
+        script_id: &str,
+        script: &StoredScript,
+    ) -> Result<(), ElasticsearchError> {
+        let script_path = script_path(script_id)?;
+        let script_json =
+            serde_json::to_vec(script).expect("stored script should be JSON serializable");
+        self.storage()
+            .await?
+            .put(&script_path, Box::new(script_json))
+            .await
+            .map_err(|error| {
+                internal_error(format!("failed to store script `{script_id}`: {error}"))
+            })
+    }
+
+    pub async fn get(&self, script_id: &str) -> Result<StoredScript, ElasticsearchError> {
+        let script_path = script_path(script_id)?;
+        let script_bytes = match self.storage().await?.get_all(&script_path).await {
+            Ok(script_bytes) => script_bytes,
+            Err(error) if error.kind() == StorageErrorKind::NotFound => {
+                return Err(script_not_found_error(script_id));
+            }
+            Err(error) => {
+                return Err(internal_error(format!(
+                    "failed to fetch script `{script_id}`: {error}"
+                )));
+            }
+        };
+        serde_json::from_slice(script_bytes.as_slice()).map_err(|error| {
+            internal_error(format!(
+                "failed to deserialize script `{script_id}`: {error}"
+            ))
+        })
+    }
+
+    pub async fn delete(&self, script_id: &str) -> Result<(), ElasticsearchError> {
+        let script_path = script_path(script_id)?;
+        let storage = self.storage().await?;
+        let script_exists = storage.exists(&script_path).await.map_err(|error| {
+            internal_error(format!("failed to fetch script `{script_id}`: {error}"))
+        })?;
+        if !script_exists {
+            return Err(script_not_found_error(script_id));
+        }
+        storage.delete(&script_path).await.map_err(|error| {
+            internal_error(format!("failed to delete script `{script_id}`: {error}"))
+        })
+    }
+
+    async fn storage(&self) -> Result<Arc<dyn Storage>, ElasticsearchError> {
+        self.storage_resolver
+            .resolve(&self.scripts_uri)
+            .await
+            .map_err(|error| internal_error(format!("failed to resolve scripts storage: {error}")))
+    }
+}
+
+fn script_path(script_id: &str) -> Result<PathBuf, ElasticsearchError> {
+    let is_valid = !script_id.is_empty()
+        && !script_id.starts_with('.')
+        && script_id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'));
+    if !is_valid {
+        return Err(ElasticsearchError::new(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "invalid script ID `{script_id}`: script IDs may only contain ASCII letters, \
+                 digits, `-`, `_`, and `.`, and cannot start with `.`"
+            ),
+            Some(ElasticException::IllegalArgument),
+        ));
+    }
+    Ok(PathBuf::from(format!("{script_id}.json")))
+}
+
+fn script_not_found_error(script_id: &str) -> ElasticsearchError {
+    ElasticsearchError::new(
+        StatusCode::NOT_FOUND,
+        format!("stored script `{script_id}` does not exist"),
+        Some(ElasticException::ResourceNotFound),
+    )
+}
+
+pub(super) fn internal_error(reason: String) -> ElasticsearchError {
+    ElasticsearchError::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        reason,
+        Some(ElasticException::Internal),
+    )
+}
+
+/// Renders a mustache search template with the given parameters.
+///
+/// The following subset of mustache is supported:
+/// - `{{var}}`: variable, with string values escaped for inclusion in a JSON string;
+/// - `{{{var}}}` and `{{&var}}`: unescaped variable;
+/// - `{{#var}}...{{/var}}`: section, rendered once per item for arrays, once for other truthy
+///   values;
+/// - `{{^var}}...{{/var}}`: inverted section, rendered if the value is missing or falsy;
+/// - `{{#toJson}}var{{/toJson}}`: value serialized as JSON;
+/// - `{{#join}}var{{/join}}`: array items joined with `,` (or with the delimiter given by `{{#join
+///   delimiter=' '}}`);
+/// - `{{! comment }}`.
+///
+/// Variables can be dotted paths (`user.name`, `tags.0`) and `{{.}}` refers to the current item
+/// within a section.
+pub(crate) fn render_search_template(
+    source: &str,
+    params: &JsonMap<String, JsonValue>,
+) -> anyhow::Result<String> {
+    let mut parser = TemplateParser { rest: source };
+    let nodes = parser.parse_nodes(None)?;
+    let root = JsonValue::Object(params.clone());
+    let mut context_stack = vec![&root];
+    let mut output = String::with_capacity(source.len());
+    render_nodes(&nodes, &mut context_stack, &mut output);
+    Ok(output)
+}
+
+#[derive(Debug)]
+enum TemplateNode<'a> {
+    Text(&'a str),
+    Variable {
+        path: &'a str,
+        escape: bool,
+    },
+    Section {
+        path: &'a str,
+        inverted: bool,
+        children: Vec<TemplateNode<'a>>,
+    },
+    ToJson(&'a str),
+    Join {
+        path: &'a str,
+        delimiter: &'a str,
+    },
+}
+
+struct TemplateParser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> TemplateParser<'a> {
+    fn parse_nodes(&mut self, section_opt: Option<&str>) -> anyhow::Result<Vec<TemplateNode<'a>>> {
+        let mut nodes = Vec::new();
+        loop {
+            let rest = self.rest;
+            let Some(tag_start) = rest.find("{{") else {
+                if let Some(section) = section_opt {
+                    bail!("unclosed section `{section}`");
+                }
+                if !rest.is_empty() {
+                    nodes.push(TemplateNode::Text(rest));
+                }
+                self.rest = "";
+                return Ok(nodes);
+            };
+            if tag_start > 0 {
+                nodes.push(TemplateNode::Text(&rest[..tag_start]));
+            }
+            let after_open = &rest[tag_start + 2..];
+
+            if let Some(raw_tag) = after_open.strip_prefix('{') {
+                let tag_end = raw_tag.find("}}}").context("unclosed tag `{{{`")?;
+                nodes.push(TemplateNode::Variable {
+                    path: raw_tag[..tag_end].trim(),
+                    escape: false,
+                });
+                self.rest = &raw_tag[tag_end + 3..];
+                continue;
+            }
+            let tag_end = after_open.find("}}").context("unclosed tag `{{`")?;
+            let tag = after_open[..tag_end].trim();
+            self.rest = &after_open[tag_end + 2..];
+
+            if let Some(name) = tag.strip_prefix('#') {
+                let name = name.trim();
+                if name == "toJson" {
+                    let path = self.take_raw_section("toJson")?;
+                    nodes.push(TemplateNode::ToJson(path));
+                } else if let Some(join_options) = name.strip_prefix("join") {
+                    let delimiter = parse_join_delimiter(join_options)?;
+                    let path = self.take_raw_section("join")?;
+                    nodes.push(TemplateNode::Join { path, delimiter });
+                } else {
+                    let children = self.parse_nodes(Some(name))?;
+                    nodes.push(TemplateNode::Section {
+                        path: name,
+                        inverted: false,
+                        children,
+                    });
+                }
+            } else if let Some(name) = tag.strip_prefix('^') {
+                let name = name.trim();
+                let children = self.parse_nodes(Some(name))?;
+                nodes.push(TemplateNode::Section {
+                    path: name,
+                    inverted: true,
+                    children,
+                });
+            } else if let Some(name) = tag.strip_prefix('/') {
+                let name = name.trim();
+                if section_opt != Some(name) {
+                    bail!("unexpected closing tag `{name}`");
+                }
+                return Ok(nodes);
+            } else if tag.starts_with('!') {
+                // Comments are not rendered.
+            } else if let Some(path) = tag.strip_prefix('&') {
+                nodes.push(TemplateNode::Variable {
+                    path: path.trim(),
+                    escape: false,
+                });
+            } else {
+                nodes.push(TemplateNode::Variable {
+                    path: tag,
+                    escape: true,
+                });
+            }
+        }
+    }
+
+    /// Consumes the content of a `toJson` or `join` section, which is a variable path rather than
+    /// a template.
+    fn take_raw_section(&mut self, name: &str) -> anyhow::Result<&'a str> {
+        let rest = self.rest;
+        let closing_tag = format!("{{{{/{name}");
+        let section_end = rest
+            .find(&closing_tag)
+            .with_context(|| format!("unclosed section `{name}`"))?;
+        let after_closing_tag = &rest[section_end + closing_tag.len()..];
+        let closing_tag_end = after_closing_tag
+            .find("}}")
+            .with_context(|| format!("unclosed section `{name}`"))?;
+        self.rest = &after_closing_tag[closing_tag_end + 2..];
+        Ok(rest[..section_end].trim())
+    }
+}
+
+fn parse_join_delimiter(join_options: &str) -> anyhow::Result<&str> {
+    let join_options = join_options.trim();
+    if join_options.is_empty() {
+        return Ok(",");
+    }
+    let delimiter_opt = join_options
+        .strip_prefix("delimiter=")
+        .and_then(|quoted_delimiter| {
+            quoted_delimiter
+                .strip_prefix('\'')
+                .and_then(|delimiter| delimiter.strip_suffix('\''))
+                .or_else(|| {
+                    quoted_delimiter
+                        .strip_prefix('"')
+                        .and_then(|delimiter| delimiter.strip_suffix('"'))
+                })
+        });
+    match delimiter_opt {
+        Some(delimiter) => Ok(delimiter),
+        None => bail!("invalid join options `{join_options}`, expected `delimiter='...'`"),
+    }
+}
+
+fn render_nodes(nodes: &[TemplateNode], context_stack: &mut Vec<&JsonValue>, output: &mut String) {
+    for node in nodes {
+        match node {
+            TemplateNode::Text(text) => output.push_str(text),
+            TemplateNode::Variable { path, escape } => {
+                if let Some(value) = lookup(context_stack, path) {
+                    write_value(&value_to_string(value), *escape, output);
+                }
+            }
+            TemplateNode::Section {
+                path,
+                inverted,
+                children,
+            } => {
+                let value_opt = lookup(context_stack, path).filter(|value| is_truthy(value));
+                match (value_opt, inverted) {
+                    (None, true) => render_nodes(children, context_stack, output),
+                    (Some(JsonValue::Array(items)), false) => {
+                        for item in items {
+                            context_stack.push(item);
+                            render_nodes(children, context_stack, output);
+                            context_stack.pop();
+                        }
+                    }
+                    (Some(value), false) => {
+                        context_stack.push(value);
+                        render_nodes(children, context_stack, output);
+                        context_stack.pop();
+                    }
+                    _ => {}
+                }
+            }
+            TemplateNode::ToJson(path) => {
+                let value = lookup(context_stack, path).unwrap_or(&JsonValue::Null);
+                output.push_str(&value.to_string());
+            }
+            TemplateNode::Join { path, delimiter } => match lookup(context_stack, path) {
+                Some(JsonValue::Array(items)) => {
+                    let joined = items
+                        .iter()
+                        .map(value_to_string)
+                        .collect::<Vec<String>>()
+                        .join(delimiter);
+                    write_value(&joined, true, output);
+                }
+                Some(value) => write_value(&value_to_string(value), true, output),
+                None => {}
+            },
+        }
+    }
+}
+
+/// Resolves a dotted path, looking up its first key from the innermost context outwards.
+fn lookup<'v>(context_stack: &[&'v JsonValue], path: &str) -> Option<&'v JsonValue> {
+    if path == "." {
+        return context_stack.last().copied();
+    }
+    let mut keys = path.split('.');
+    let first_key = keys.next()?;
+    let mut value = context_stack
+        .iter()
+        .rev()
+        .find_map(|context| child_value(context, first_key))?;
+    for key in keys {
+        value = child_value(value, key)?;
+    }
+    Some(value)
+}
+
+fn child_value<'v>(value: &'v JsonValue, key: &str) -> Option<&'v JsonValue> {
+    match value {
+        JsonValue::Object(object) => object.get(key),
+        JsonValue::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => None,
+    }
+}
+
+fn is_truthy(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Null => false,
+        JsonValue::Bool(boolean) => *boolean,
+        JsonValue::String(text) => !text.is_empty(),
+        JsonValue::Array(items) => !items.is_empty(),
+        JsonValue::Number(_) | JsonValue::Object(_) => true,
+    }
+}
+
+fn value_to_string(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(text) => text.clone(),
+        _ => value.to_string(),
+    }
+}
+
+fn write_value(text: &str, escape: bool, output: &mut String) {
+    if !escape {
+        output.push_str(text);
+        return;
+    }
+    let quoted = serde_json::to_string(text).expect("string should be JSON serializable");
+    output.push_str(&quoted[1..quoted.len() - 1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn render(source: &str, params: JsonValue) -> String {
+        let JsonValue::Object(params) = params else {
+            panic!("params should be an object");
+        };
+        render_search_template(source, &params).unwrap()
+    }
+
+    #[test]
+    fn test_render_search_template_variables() {
+        assert_eq!(
+            render(
+                r#"{"query": {"match": {"title": "{{query.text}}"}}, "size": {{size}}}"#,
+                json!({"query": {"text": "say \"hi\""}, "size": 5}),
+            ),
+            r#"{"query": {"match": {"title": "say \"hi\""}}, "size": 5}"#
+        );
+        assert_eq!(render("[{{missing}}]", json!({})), "[]");
+        assert_eq!(render("{{tags.1}}", json!({"tags": ["a", "b"]})), "b");
+        assert_eq!(
+            render(r#"{{{raw}}}|{{& raw}}"#, json!({"raw": "\"x\""})),
+            r#""x"|"x""#
+        );
+        assert_eq!(render("a{{! comment }}b", json!({})), "ab");
+    }
+
+    #[test]
+    fn test_render_search_template_sections() {
+        let source =
+            r#"{"from": {{from}}{{^from}}0{{/from}}, "size": {{size}}{{^size}}10{{/size}}}"#;
+        assert_eq!(
+            render(source, json!({"size": 20})),
+            r#"{"from": 0, "size": 20}"#
+        );
+        assert_eq!(
+            render(
+                r#"[{{#tags}}"{{.}}",{{/tags}}]"#,
+                json!({"tags": ["a", "b"]})
+            ),
+            r#"["a","b",]"#
+        );
+        assert_eq!(
+            render(
+                "{{#user}}{{name}}-{{suffix}}{{/user}}",
+                json!({"user": {"name": "joe"}, "suffix": "x"})
+            ),
+            "joe-x"
+        );
+        assert_eq!(
+            render(
+                "{{#flag}}yes{{/flag}}{{^flag}}no{{/flag}}",
+                json!({"flag": false})
+            ),
+            "no"
+        );
+        assert_eq!(render("{{#empty}}yes{{/empty}}", json!({"empty": []})), "");
+    }
+
+    #[test]
+    fn test_render_search_template_to_json_and_join() {
+        assert_eq!(
+            render(
+                r#"{"terms": {"tag": {{#toJson}}tags{{/toJson}}}}"#,
+                json!({"tags": ["a", "b"]})
+            ),
+            r#"{"terms": {"tag": ["a","b"]}}"#
+        );
+        assert_eq!(
+            render(r#""{{#join}}tags{{/join}}""#, json!({"tags": ["a", "b"]})),
+            r#""a,b""#
+        );
+        assert_eq!(
+            render(
+                r#""{{#join delimiter=' OR '}}tags{{/join delimiter=' OR '}}""#,
+                json!({"tags": ["a", "b"]})
+            ),
+            r#""a OR b""#
+        );
+    }
+
+    #[test]
+    fn test_render_search_template_invalid() {
+        let params = JsonMap::new();
+        let error = render_search_template("{{#tags}}", &params).unwrap_err();
+        assert_eq!(error.to_string(), "unclosed section `tags`");
+
+        let error = render_search_template("{{#tags}}{{/other}}", &params).unwrap_err();
+        assert_eq!(error.to_string(), "unexpected closing tag `other`");
+
+        let error = render_search_template("{{size", &params).unwrap_err();
+        assert_eq!(error.to_string(), "unclosed tag `{{`");
+
+        let error = render_search_template("{{#join delimiter}}a{{/join}}", &params).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid join options `delimiter`, expected `delimiter='...'`"
+        );
+    }
+
+    #[test]
+    fn test_script_path() {
+        assert_eq!(
+            script_path("my-template_1.v2").unwrap(),
+            PathBuf::from("my-template_1.v2.json")
+        );
+        for invalid_script_id in ["", ".hidden", "../escape", "a/b", "a b"] {
+            let error = script_path(invalid_script_id).unwrap_err();
+            assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stored_script_store() {
+        let stored_script_store = StoredScriptStore::new(
+            StorageResolver::for_test(),
+            &Uri::for_test("ram:///indexes"),
+        );
+        let error = stored_script_store.get("my-template").await.unwrap_err();
+        assert_eq!(error.status, StatusCode::NOT_FOUND);
+
+        let script = StoredScript {
+            lang: MUSTACHE_LANG.to_string(),
+            source: r#"{"query": {"match_all": {}}}"#.to_string(),
+        };
+        stored_script_store
+            .put("my-template", &script)
+            .await
+            .unwrap();
+        assert_eq!(
+            stored_script_store.get("my-template").await.unwrap(),
+            script
+        );
+
+        stored_script_store.delete("my-template").await.unwrap();
+        let error = stored_script_store.delete("my-template").await.unwrap_err();
+        assert_eq!(error.status, StatusCode::NOT_FOUND);
+    }
+}
@@ -19,12 +19,15 @@
 
 use std::fmt;
 
-use hyper::header::CONTENT_TYPE;
+use hyper::header::{ACCEPT, CONTENT_TYPE};
 use quickwit_config::ConfigFormat;
 use serde::{self, Deserialize, Serialize, Serializer};
+use serde_json::Value as JsonValue;
 use thiserror::Error;
 use warp::{Filter, Rejection};
 
+use crate::sql_api::SqlResponse;
+
 /// Body output format used for the REST API.
 #[derive(Deserialize, Clone, Debug, Eq, PartialEq, Copy, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
@@ -33,6 +36,14 @@ pub enum BodyFormat {
     Json,
     #[default]
     PrettyJson,
+    /// Binary MessagePack encoding, more compact than JSON for large responses.
+    Msgpack,
+    /// Binary CBOR encoding, more compact than JSON for large responses.
+    Cbor,
+    /// Newline-delimited JSON. The search endpoints write their hits one per line.
+    Ndjson,
+    /// Comma-separated values, with a row per hit for the search endpoints.
+    Csv,
 }
 
 impl BodyFormat {
@@ -47,13 +58,88 @@ impl BodyFormat {
     }
 
     fn value_to_vec(&self, value: &impl serde::Serialize) -> Result<Vec<u8>, ()> {
+        let serialization_result = match &self {
+            Self::Json => serde_json::to_vec(value).map_err(|error| error.to_string()),
+            Self::Ndjson => serde_json::to_vec(value)
+                .map(|mut line| {
+                    line.push(b'\n');
+                    line
+                })
+                .map_err(|error| error.to_string()),
+            Self::PrettyJson => serde_json::to_vec_pretty(value).map_err(|error| error.to_string()),
+            // Structs are serialized as maps so that the responses are self-describing, like JSON.
+            Self::Msgpack => rmp_serde::to_vec_named(value).map_err(|error| error.to_string()),
+            // Responses other than the search hits are written as a single row, or a row per
+            // element if they are an array.
+            Self::Csv => serde_json::to_value(value)
+                .map(|json_value| {
+                    let documents = match json_value {
+                        JsonValue::Array(documents) => documents,
+                        document => vec![document],
+                    };
+                    SqlResponse::from_documents(&documents, None)
+                        .to_csv()
+                        .into_bytes()
+                })
+                .map_err(|error| error.to_string()),
+            Self::Cbor => {
+                let mut buffer = Vec::new();
+                ciborium::into_writer(value, &mut buffer)
+                    .map(|_| buffer)
+                    .map_err(|error| error.to_string())
+            }
+        };
+        serialization_result.map_err(|error| {
+            tracing::error!(error=%error, "response serialization failed");
+        })
+    }
+
+    /// Returns the media type of the responses serialized in this format.
+    pub(crate) fn content_type(&self) -> &'static str {
         match &self {
-            Self::Json => serde_json::to_vec(value),
-            Self::PrettyJson => serde_json::to_vec_pretty(value),
+            Self::Json | Self::PrettyJson => "application/json",
+            Self::Msgpack => "application/msgpack",
+            Self::Cbor => "application/cbor",
+            Self::Ndjson => "application/x-ndjson",
+            Self::Csv => "text/csv",
         }
-        .map_err(|_| {
-            tracing::error!("response serialization failed");
-        })
+    }
+
+    /// Returns the format preferred by an `Accept` header, if it accepts any. The media types are
+    /// ranked by quality value, then by order of appearance. `application/json` stands for the
+    /// default JSON format.
+    fn from_accept_header(accept: &str) -> Option<BodyFormat> {
+        let mut best_format_opt: Option<(BodyFormat, f32)> = None;
+
+        for media_range in accept.split(',') {
+            let mut media_range_parts = media_range.split(';');
+            let media_type = media_range_parts
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase();
+            let format = match media_type.as_str() {
+                "application/json" => Self::default(),
+                "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                    Self::Msgpack
+                }
+                "application/cbor" => Self::Cbor,
+                "application/x-ndjson" | "application/ndjson" => Self::Ndjson,
+                "text/csv" => Self::Csv,
+                _ => continue,
+            };
+            let quality = media_range_parts
+                .filter_map(|parameter| parameter.trim().strip_prefix("q="))
+                .find_map(|quality| quality.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality <= 0.0 {
+                continue;
+            }
+            if best_format_opt.map_or(true, |(_, best_quality)| quality > best_quality) {
+                best_format_opt = Some((format, quality));
+            }
+        }
+        best_format_opt.map(|(format, _)| format)
     }
 }
 
@@ -62,6 +148,10 @@ impl fmt::Display for BodyFormat {
         match &self {
             Self::Json => write!(formatter, "json"),
             Self::PrettyJson => write!(formatter, "pretty_json"),
+            Self::Msgpack => write!(formatter, "msgpack"),
+            Self::Cbor => write!(formatter, "cbor"),
+            Self::Ndjson => write!(formatter, "ndjson"),
+            Self::Csv => write!(formatter, "csv"),
         }
     }
 }
@@ -80,13 +170,30 @@ impl Serialize for BodyFormat {
 struct FormatQueryString {
     /// The output format requested.
     #[serde(default)]
-    pub format: BodyFormat,
+    pub format: Option<BodyFormat>,
 }
 
+/// Extracts the output format requested with the `format` query string parameter or, failing
+/// that, with the `Accept` header.
 pub(crate) fn extract_format_from_qs(
 ) -> impl Filter<Extract = (BodyFormat,), Error = Rejection> + Clone {
     serde_qs::warp::query::<FormatQueryString>(serde_qs::Config::default())
-        .map(|format_qs: FormatQueryString| format_qs.format)
+        .and(extract_format_from_accept_header())
+        .map(
+            |format_qs: FormatQueryString, accept_format_opt: Option<BodyFormat>| {
+                format_qs.format.or(accept_format_opt).unwrap_or_default()
+            },
+        )
+}
+
+/// Extracts the output format requested with the `Accept` header, if any.
+pub(crate) fn extract_format_from_accept_header(
+) -> impl Filter<Extract = (Option<BodyFormat>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(ACCEPT.as_str()).map(|accept_opt: Option<String>| {
+        accept_opt
+            .as_deref()
+            .and_then(BodyFormat::from_accept_header)
+    })
 }
 
 #[derive(Debug, Error)]
@@ -117,3 +224,109 @@ pub(crate) fn extract_config_format(
         },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_format_from_accept_header() {
+        assert_eq!(
+            BodyFormat::from_accept_header("application/msgpack"),
+            Some(BodyFormat::Msgpack)
+        );
+        assert_eq!(
+            BodyFormat::from_accept_header("application/json;q=0.5, application/cbor"),
+            Some(BodyFormat::Cbor)
+        );
+        assert_eq!(
+            BodyFormat::from_accept_header("application/x-ndjson"),
+            Some(BodyFormat::Ndjson)
+        );
+        assert_eq!(
+            BodyFormat::from_accept_header("text/csv"),
+            Some(BodyFormat::Csv)
+        );
+        assert_eq!(
+            BodyFormat::from_accept_header("Application/MsgPack"),
+            Some(BodyFormat::Msgpack)
+        );
+        assert_eq!(
+            BodyFormat::from_accept_header("application/json"),
+            Some(BodyFormat::PrettyJson)
+        );
+        assert_eq!(
+            BodyFormat::from_accept_header("application/json, application/msgpack;q=0.9"),
+            Some(BodyFormat::PrettyJson)
+        );
+        assert_eq!(
+            BodyFormat::from_accept_header("application/cbor;q=0, application/json;q=0.1"),
+            Some(BodyFormat::PrettyJson)
+        );
+        assert_eq!(
+            BodyFormat::from_accept_header("text/html, text/csv;q=0.8, */*;q=0.5"),
+            Some(BodyFormat::Csv)
+        );
+        assert_eq!(BodyFormat::from_accept_header("text/html, */*"), None);
+        assert_eq!(BodyFormat::from_accept_header(""), None);
+    }
+
+    #[test]
+    fn test_body_format_binary_serialization() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Response {
+            num_hits: u64,
+            hits: Vec<String>,
+        }
+        let response = Response {
+            num_hits: 2,
+            hits: vec!["foo".to_string(), "bar".to_string()],
+        };
+        let msgpack_bytes = BodyFormat::Msgpack
+            .result_to_vec::<_, ()>(&Ok(&response))
+            .unwrap();
+        let msgpack_response: Response = rmp_serde::from_slice(&msgpack_bytes).unwrap();
+        assert_eq!(msgpack_response, response);
+
+        let cbor_bytes = BodyFormat::Cbor
+            .result_to_vec::<_, ()>(&Ok(&response))
+            .unwrap();
+        let cbor_response: Response = ciborium::from_reader(&cbor_bytes[..]).unwrap();
+        assert_eq!(cbor_response, response);
+
+        let csv_bytes = BodyFormat::Csv
+            .result_to_vec::<_, ()>(&Ok(&response))
+            .unwrap();
+        assert_eq!(
+            csv_bytes,
+            b"num_hits,hits\n2,\"[\"\"foo\"\",\"\"bar\"\"]\"\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_format_from_qs() {
+        let filter = extract_format_from_qs();
+        let body_format = warp::test::request()
+            .path("/?format=json")
+            .header("accept", "application/msgpack")
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(body_format, BodyFormat::Json);
+
+        let body_format = warp::test::request()
+            .path("/")
+            .header("accept", "application/msgpack")
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(body_format, BodyFormat::Msgpack);
+
+        let body_format = warp::test::request()
+            .path("/")
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(body_format, BodyFormat::PrettyJson);
+    }
+}
@@ -22,9 +22,12 @@ use std::error::Error;
 use std::sync::Arc;
 
 use bytesize::ByteSize;
+use futures::FutureExt;
 use quickwit_cluster::cluster_grpc_server;
+use quickwit_common::tls::{make_client_cert_verifier, make_server_config, ReloadingCertResolver};
 use quickwit_common::tower::BoxFutureInfaillible;
 use quickwit_config::service::QuickwitService;
+use quickwit_config::MtlsMode;
 use quickwit_proto::developer::DeveloperServiceClient;
 use quickwit_proto::indexing::IndexingServiceClient;
 use quickwit_proto::jaeger::storage::v1::span_reader_plugin_server::SpanReaderPluginServer;
@@ -35,10 +38,16 @@ use quickwit_proto::tonic::codegen::CompressionEncoding;
 use quickwit_proto::tonic::transport::server::TcpIncoming;
 use quickwit_proto::tonic::transport::Server;
 use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
 use tracing::*;
 
+use crate::auth_api::{
+    jaeger_required_access, otlp_logs_required_access, otlp_traces_required_access,
+    ApiKeyAuthenticator, GrpcApiKeyAuth,
+};
 use crate::developer_api::DeveloperApiServer;
 use crate::search_api::GrpcSearchAdapter;
+use crate::tls::tls_incoming;
 use crate::{QuickwitServices, INDEXING_GRPC_SERVER_METRICS_LAYER};
 
 /// Starts and binds gRPC services to `grpc_listen_addr`.
@@ -123,13 +132,25 @@ pub(crate) async fn start_grpc_server(
     } else {
         None
     };
+    // The OTLP and Jaeger services are called by clients outside of the cluster, so they check
+    // the API keys of the requests when authentication is enabled.
+    let api_key_authenticator_opt = services.node_config.rest_config.auth.enabled.then(|| {
+        ApiKeyAuthenticator::new(
+            services.metastore_client.clone(),
+            &services.node_config.rest_config.auth,
+        )
+    });
     // Mount gRPC OpenTelemetry OTLP services if present.
     let otlp_trace_grpc_service =
         if let Some(otlp_traces_service) = services.otlp_traces_service_opt.clone() {
             enabled_grpc_services.insert("otlp-traces");
             let trace_service = TraceServiceServer::new(otlp_traces_service)
                 .accept_compressed(CompressionEncoding::Gzip);
-            Some(trace_service)
+            Some(GrpcApiKeyAuth::new(
+                trace_service,
+                api_key_authenticator_opt.clone(),
+                otlp_traces_required_access,
+            ))
         } else {
             None
         };
@@ -138,7 +159,11 @@ pub(crate) async fn start_grpc_server(
             enabled_grpc_services.insert("otlp-logs");
             let logs_service = LogsServiceServer::new(otlp_logs_service)
                 .accept_compressed(CompressionEncoding::Gzip);
-            Some(logs_service)
+            Some(GrpcApiKeyAuth::new(
+                logs_service,
+                api_key_authenticator_opt.clone(),
+                otlp_logs_required_access,
+            ))
         } else {
             None
         };
@@ -162,7 +187,11 @@ pub(crate) async fn start_grpc_server(
     // Mount gRPC jaeger service if present.
     let jaeger_grpc_service = if let Some(jaeger_service) = services.jaeger_service_opt.clone() {
         enabled_grpc_services.insert("jaeger");
-        Some(SpanReaderPluginServer::new(jaeger_service))
+        Some(GrpcApiKeyAuth::new(
+            SpanReaderPluginServer::new(jaeger_service),
+            api_key_authenticator_opt,
+            jaeger_required_access,
+        ))
     } else {
         None
     };
@@ -194,10 +223,33 @@ pub(crate) async fn start_grpc_server(
         grpc_listen_addr=?grpc_listen_addr,
         "Starting gRPC server listening on {grpc_listen_addr}."
     );
-    // nodelay=true and keepalive=None are the default values for Server::builder()
-    let tcp_incoming = TcpIncoming::from_listener(tcp_listener, true, None)
-        .map_err(|err: Box<dyn Error + Send + Sync>| anyhow::anyhow!(err))?;
-    let serve_fut = server_router.serve_with_incoming_shutdown(tcp_incoming, shutdown_signal);
+    let serve_fut = if let Some(tls_config) = &services.node_config.grpc_config.tls {
+        let cert_resolver =
+            ReloadingCertResolver::load(&tls_config.cert_path, &tls_config.key_path)?;
+        cert_resolver.spawn_reload_task();
+        // The CA path is required by the config validation when mutual TLS is enabled.
+        let client_cert_verifier_opt = match (tls_config.mtls, &tls_config.ca_path) {
+            (MtlsMode::Permissive, Some(ca_path)) => {
+                Some(make_client_cert_verifier(ca_path, true)?)
+            }
+            (MtlsMode::Strict, Some(ca_path)) => Some(make_client_cert_verifier(ca_path, false)?),
+            _ => None,
+        };
+        let server_config = make_server_config(cert_resolver, client_cert_verifier_opt, &[b"h2"]);
+        let tls_acceptor = TlsAcceptor::from(server_config);
+        let allow_plaintext = tls_config.mtls == MtlsMode::Permissive;
+        let tls_incoming = tls_incoming(tcp_listener, tls_acceptor, allow_plaintext);
+        server_router
+            .serve_with_incoming_shutdown(tls_incoming, shutdown_signal)
+            .boxed()
+    } else {
+        // nodelay=true and keepalive=None are the default values for Server::builder()
+        let tcp_incoming = TcpIncoming::from_listener(tcp_listener, true, None)
+            .map_err(|err: Box<dyn Error + Send + Sync>| anyhow::anyhow!(err))?;
+        server_router
+            .serve_with_incoming_shutdown(tcp_incoming, shutdown_signal)
+            .boxed()
+    };
     let (serve_res, _trigger_res) = tokio::join!(serve_fut, readiness_trigger);
     serve_res?;
     Ok(())
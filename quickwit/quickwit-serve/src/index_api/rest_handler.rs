@@ -1429,6 +1429,10 @@ mod tests {
         mock_metastore
             .expect_delete_index()
             .return_once(|_| Ok(EmptyResponse {}));
+        mock_metastore
+            .expect_delete_index_aliases()
+            .withf(|request| request.index_ids == ["quickwit-demo-index"])
+            .return_once(|_| Ok(EmptyResponse {}));
         let index_service = IndexService::new(
             MetastoreServiceClient::from_mock(mock_metastore),
             StorageResolver::unconfigured(),
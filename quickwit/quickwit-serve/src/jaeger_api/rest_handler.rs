@@ -463,6 +463,10 @@ mod tests {
                     errors: Vec::new(),
                     aggregation: None,
                     scroll_id: None,
+                    timed_out: false,
+                    timed_out_splits: Vec::new(),
+                    suggestions: Vec::new(),
+                    split_profiles: Vec::new(),
                 })
             });
         let mock_search_service = Arc::new(mock_search_service);
@@ -494,6 +498,10 @@ mod tests {
                     errors: Vec::new(),
                     aggregation: None,
                     scroll_id: None,
+                    timed_out: false,
+                    timed_out_splits: Vec::new(),
+                    suggestions: Vec::new(),
+                    split_profiles: Vec::new(),
                 })
             });
         let mock_search_service = Arc::new(mock_search_service);
@@ -19,6 +19,8 @@
 
 #![recursion_limit = "256"]
 
+mod audit;
+mod auth_api;
 mod build_info;
 mod cluster_api;
 mod decompression;
@@ -32,19 +34,24 @@ mod index_api;
 mod indexing_api;
 mod ingest_api;
 mod jaeger_api;
+mod lifecycle_api;
 mod load_shield;
 mod metrics;
 mod metrics_api;
 mod node_info_handler;
 mod openapi;
 mod otlp_api;
+mod pg_wire;
 mod rate_modulator;
+mod request_rate_limiter;
 mod rest;
 mod rest_api_response;
 mod search_api;
 pub(crate) mod simple_list;
+mod sql_api;
 pub mod tcp_listener;
 mod template_api;
+mod tls;
 mod ui_handler;
 
 use std::collections::{HashMap, HashSet};
@@ -71,15 +78,18 @@ use quickwit_common::pubsub::{EventBroker, EventSubscriptionHandle};
 use quickwit_common::rate_limiter::RateLimiterSettings;
 use quickwit_common::retry::RetryParams;
 use quickwit_common::runtimes::RuntimesConfig;
+use quickwit_common::tls::{make_grpc_client_config, GrpcTlsConnector, ReloadingCertResolver};
 use quickwit_common::tower::{
-    BalanceChannel, BoxFutureInfaillible, BufferLayer, Change, CircuitBreakerEvaluator,
-    ConstantRate, EstimateRateLayer, EventListenerLayer, GrpcMetricsLayer, LoadShedLayer,
-    RateLimitLayer, RetryLayer, RetryPolicy, SmaRateEstimator, TimeoutLayer,
+    set_grpc_tls_connector, BalanceChannel, BoxFutureInfaillible, BufferLayer, Change,
+    CircuitBreakerEvaluator, ConstantRate, EstimateRateLayer, EventListenerLayer, GrpcMetricsLayer,
+    LoadShedLayer, RateLimitLayer, RetryLayer, RetryPolicy, SmaRateEstimator, TimeoutLayer,
 };
 use quickwit_common::uri::Uri;
 use quickwit_common::{get_bool_from_env, spawn_named_task};
 use quickwit_config::service::QuickwitService;
-use quickwit_config::{ClusterConfig, IngestApiConfig, NodeConfig};
+use quickwit_config::{
+    AuditSinkConfig, ClusterConfig, GossipEncryptionMode, IngestApiConfig, MtlsMode, NodeConfig,
+};
 use quickwit_control_plane::control_plane::{ControlPlane, ControlPlaneEventSubscriber};
 use quickwit_control_plane::{IndexerNodeInfo, IndexerPool};
 use quickwit_index_management::{IndexService as IndexManager, IndexServiceError};
@@ -130,6 +140,7 @@ use crate::rate_modulator::RateModulator;
 #[cfg(test)]
 use crate::rest::recover_fn;
 pub use crate::search_api::{search_request_from_api_request, SearchRequestQueryString, SortBy};
+pub use crate::sql_api::{SqlColumn, SqlRequest, SqlResponse, SqlResponseFormat};
 
 const READINESS_REPORTING_INTERVAL: Duration = if cfg!(any(test, feature = "testsuite")) {
     Duration::from_millis(25)
@@ -396,6 +407,37 @@ pub async fn serve_quickwit(
     shutdown_signal: BoxFutureInfaillible<()>,
     env_filter_reload_fn: EnvFilterReloadFn,
 ) -> anyhow::Result<HashMap<String, ActorExitStatus>> {
+    // When TLS is enabled on the gRPC server, the other nodes of the cluster are expected to serve
+    // gRPC over TLS as well, so the channels to them must be configured before they get created.
+    let grpc_tls_connector_opt = if let Some(tls_config) = &node_config.grpc_config.tls {
+        let client_cert_resolver_opt = if tls_config.mtls != MtlsMode::Disabled {
+            if node_config.gossip_config.encryption != GossipEncryptionMode::Strict {
+                warn!(
+                    gossip_listen_addr=%node_config.gossip_listen_addr,
+                    "mutual TLS only covers gRPC: enable strict gossip encryption \
+                     (`gossip.encryption: strict`) so that the gossip messages are encrypted and \
+                     authenticated as well"
+                );
+            }
+            let client_cert_resolver =
+                ReloadingCertResolver::load(&tls_config.cert_path, &tls_config.key_path)?;
+            client_cert_resolver.spawn_reload_task();
+            Some(client_cert_resolver)
+        } else {
+            None
+        };
+        let client_config =
+            make_grpc_client_config(tls_config.ca_path.as_deref(), client_cert_resolver_opt)
+                .context("failed to build gRPC TLS client config")?;
+        let tls_connector =
+            GrpcTlsConnector::new(client_config, tls_config.server_name.as_deref())?
+                .with_plaintext_fallback(tls_config.mtls == MtlsMode::Permissive);
+        Some(tls_connector)
+    } else {
+        None
+    };
+    set_grpc_tls_connector(grpc_tls_connector_opt);
+
     let cluster = start_cluster_service(&node_config)
         .await
         .context("failed to start cluster service")?;
@@ -581,6 +623,21 @@ pub async fn serve_quickwit(
             }
         }
     }
+    let audit_config = &node_config.rest_config.audit;
+
+    if let (true, AuditSinkConfig::Index { index_id }) = (audit_config.enabled, &audit_config.sink)
+    {
+        let audit_index_config =
+            audit::audit_index_config(index_id, &node_config.default_index_root_uri)
+                .context("failed to load audit index config")?;
+        match index_manager.create_index(audit_index_config, false).await {
+            Ok(_)
+            | Err(IndexServiceError::Metastore(MetastoreError::AlreadyExists(
+                EntityKind::Index { .. },
+            ))) => {}
+            Err(error) => bail!("failed to create audit index: {error}"),
+        };
+    }
     let split_cache_root_directory: PathBuf =
         node_config.data_dir_path.join("searcher-split-cache");
     let split_cache_opt: Option<Arc<SplitCache>> =
@@ -682,6 +739,8 @@ pub async fn serve_quickwit(
 
     let grpc_listen_addr = node_config.grpc_listen_addr;
     let rest_listen_addr = node_config.rest_config.listen_addr;
+    let pg_wire_config = node_config.pg_wire_config.clone();
+    let pg_wire_search_service = search_service.clone();
     let quickwit_services: Arc<QuickwitServices> = Arc::new(QuickwitServices {
         node_config: Arc::new(node_config),
         cluster: cluster.clone(),
@@ -744,6 +803,26 @@ pub async fn serve_quickwit(
         rest_shutdown_signal,
     );
 
+    // Setup and start the PostgreSQL wire protocol server, if enabled.
+    let (pg_wire_shutdown_trigger_tx, pg_wire_shutdown_signal_rx) = oneshot::channel::<()>();
+    let pg_wire_join_handle_opt = if pg_wire_config.enable_endpoint {
+        let pg_wire_listen_addr =
+            SocketAddr::new(rest_listen_addr.ip(), pg_wire_config.listen_port);
+        let pg_wire_shutdown_signal = Box::pin(async move {
+            if pg_wire_shutdown_signal_rx.await.is_err() {
+                debug!("PostgreSQL wire protocol server shutdown trigger sender was dropped");
+            }
+        });
+        let pg_wire_server = pg_wire::start_pg_wire_server(
+            tcp_listener_resolver.resolve(pg_wire_listen_addr).await?,
+            pg_wire_search_service,
+            pg_wire_shutdown_signal,
+        );
+        Some(spawn_named_task(pg_wire_server, "pg_wire_server"))
+    } else {
+        None
+    };
+
     // Node readiness indicates that the server is ready to receive requests.
     // Thus readiness task is started once gRPC and REST servers are started.
     spawn_named_task(
@@ -775,6 +854,8 @@ pub async fn serve_quickwit(
         if rest_shutdown_trigger_tx.send(()).is_err() {
             debug!("REST server shutdown signal receiver was dropped");
         }
+        // The receiver is dropped if the PostgreSQL wire protocol server is disabled.
+        let _ = pg_wire_shutdown_trigger_tx.send(());
         actor_exit_statuses
     });
     let grpc_join_handle = spawn_named_task(grpc_server, "grpc_server");
@@ -789,6 +870,17 @@ pub async fn serve_quickwit(
     if let Err(rest_err) = rest_res {
         error!("REST server failed: {:?}", rest_err);
     }
+    if let Some(pg_wire_join_handle) = pg_wire_join_handle_opt {
+        match pg_wire_join_handle.await {
+            Ok(Err(pg_wire_err)) => {
+                error!("PostgreSQL wire protocol server failed: {:?}", pg_wire_err)
+            }
+            Err(join_error) => {
+                error!("PostgreSQL wire protocol server panicked: {:?}", join_error)
+            }
+            Ok(Ok(())) => {}
+        }
+    }
     let actor_exit_statuses = shutdown_handle
         .await
         .context("failed to gracefully shutdown services")?;
@@ -1299,11 +1391,14 @@ async fn check_cluster_configuration(
 
 pub mod lambda_search_api {
     pub use crate::elasticsearch_api::{
-        es_compat_cat_indices_handler, es_compat_index_cat_indices_handler,
+        es_compat_analyze_handler, es_compat_cat_count_handler, es_compat_cat_indices_handler,
+        es_compat_get_aliases_handler, es_compat_index_analyze_handler,
+        es_compat_index_cat_count_handler, es_compat_index_cat_indices_handler,
         es_compat_index_count_handler, es_compat_index_field_capabilities_handler,
-        es_compat_index_multi_search_handler, es_compat_index_search_handler,
-        es_compat_index_stats_handler, es_compat_resolve_index_handler, es_compat_scroll_handler,
-        es_compat_search_handler, es_compat_stats_handler,
+        es_compat_index_mapping_handler, es_compat_index_multi_search_handler,
+        es_compat_index_search_handler, es_compat_index_stats_handler, es_compat_mapping_handler,
+        es_compat_resolve_index_handler, es_compat_scroll_handler, es_compat_search_handler,
+        es_compat_stats_handler, IndexAliasStore,
     };
     pub use crate::index_api::get_index_metadata_handler;
     pub use crate::rest::recover_fn;
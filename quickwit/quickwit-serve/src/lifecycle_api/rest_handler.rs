@@ -0,0 +1,489 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use bytes::Bytes;
+use quickwit_config::{ConfigFormat, LifecyclePolicy, LifecyclePolicyId};
+use quickwit_janitor::error::JanitorError;
+use quickwit_janitor::{
+    compute_index_lifecycle_status, IndexLifecycleStatus, LifecyclePolicyStore,
+};
+use quickwit_metastore::{
+    IndexMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt,
+    MetastoreServiceStreamSplitsExt, SplitState,
+};
+use quickwit_proto::metastore::{
+    IndexMetadataRequest, ListSplitsRequest, MetastoreService, MetastoreServiceClient,
+};
+use quickwit_proto::types::IndexId;
+use serde_json::Value as JsonValue;
+use time::OffsetDateTime;
+use warp::reject::Rejection;
+use warp::{Filter, Reply};
+
+use crate::format::{extract_config_format, extract_format_from_qs};
+use crate::rest::recover_fn;
+use crate::rest_api_response::into_rest_api_response;
+use crate::with_arg;
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        create_lifecycle_policy,
+        get_lifecycle_policy,
+        update_lifecycle_policy,
+        delete_lifecycle_policy,
+        list_lifecycle_policies,
+        get_index_lifecycle_status,
+    ),
+    components(schemas(LifecyclePolicy, IndexLifecycleStatus))
+)]
+pub(crate) struct LifecyclePolicyApi;
+
+pub(crate) fn lifecycle_policy_api_handlers(
+    metastore: MetastoreServiceClient,
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    create_lifecycle_policy_handler(lifecycle_policy_store.clone())
+        .or(get_lifecycle_policy_handler(lifecycle_policy_store.clone()))
+        .or(update_lifecycle_policy_handler(
+            lifecycle_policy_store.clone(),
+        ))
+        .or(delete_lifecycle_policy_handler(
+            lifecycle_policy_store.clone(),
+        ))
+        .or(list_lifecycle_policies_handler(
+            lifecycle_policy_store.clone(),
+        ))
+        .or(get_index_lifecycle_status_handler(
+            metastore,
+            lifecycle_policy_store,
+        ))
+        .recover(recover_fn)
+}
+
+fn parse_lifecycle_policy(
+    body: &Bytes,
+    config_format: ConfigFormat,
+    policy_id_opt: Option<LifecyclePolicyId>,
+) -> Result<LifecyclePolicy, JanitorError> {
+    let mut json_value: JsonValue = config_format
+        .parse(body)
+        .map_err(|error| JanitorError::InvalidLifecyclePolicy(error.to_string()))?;
+
+    if let Some(policy_id) = policy_id_opt {
+        json_value["policy_id"] = JsonValue::String(policy_id);
+    }
+    serde_json::from_value(json_value)
+        .map_err(|error| JanitorError::InvalidLifecyclePolicy(error.to_string()))
+}
+
+fn create_lifecycle_policy_handler(
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("lifecycle" / "policies")
+        .and(warp::post())
+        .and(warp::filters::body::bytes())
+        .and(extract_config_format())
+        .and(with_arg(lifecycle_policy_store))
+        .then(create_lifecycle_policy)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    post,
+    tag = "Lifecycle Policies",
+    path = "/lifecycle/policies",
+    request_body = LifecyclePolicy,
+    responses(
+        (status = 200, description = "The lifecycle policy was successfully created.", body = LifecyclePolicy),
+        (status = 400, description = "The lifecycle policy is invalid."),
+        (status = 409, description = "A lifecycle policy with the same ID already exists.")
+    ),
+)]
+/// Creates a new lifecycle policy.
+async fn create_lifecycle_policy(
+    body: Bytes,
+    config_format: ConfigFormat,
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> Result<LifecyclePolicy, JanitorError> {
+    let lifecycle_policy = parse_lifecycle_policy(&body, config_format, None)?;
+    lifecycle_policy_store
+        .put_policy(lifecycle_policy.clone(), false)
+        .await?;
+    Ok(lifecycle_policy)
+}
+
+fn get_lifecycle_policy_handler(
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("lifecycle" / "policies" / String)
+        .and(warp::get())
+        .and(with_arg(lifecycle_policy_store))
+        .then(get_lifecycle_policy)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    get,
+    tag = "Lifecycle Policies",
+    path = "/lifecycle/policies/{policy_id}",
+    responses(
+        (status = 200, description = "The lifecycle policy was successfully retrieved.", body = LifecyclePolicy),
+        (status = 404, description = "The lifecycle policy was not found.")
+    ),
+)]
+/// Retrieves the lifecycle policy identified by `policy_id`.
+async fn get_lifecycle_policy(
+    policy_id: LifecyclePolicyId,
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> Result<LifecyclePolicy, JanitorError> {
+    lifecycle_policy_store.get_policy(&policy_id).await
+}
+
+fn update_lifecycle_policy_handler(
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("lifecycle" / "policies" / String)
+        .and(warp::put())
+        .and(warp::filters::body::bytes())
+        .and(extract_config_format())
+        .and(with_arg(lifecycle_policy_store))
+        .then(update_lifecycle_policy)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    put,
+    tag = "Lifecycle Policies",
+    path = "/lifecycle/policies/{policy_id}",
+    request_body = LifecyclePolicy,
+    responses(
+        (status = 200, description = "The lifecycle policy was successfully created or updated.", body = LifecyclePolicy),
+        (status = 400, description = "The lifecycle policy is invalid.")
+    ),
+)]
+/// Creates or replaces the lifecycle policy identified by `policy_id`.
+async fn update_lifecycle_policy(
+    policy_id: LifecyclePolicyId,
+    body: Bytes,
+    config_format: ConfigFormat,
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> Result<LifecyclePolicy, JanitorError> {
+    let lifecycle_policy = parse_lifecycle_policy(&body, config_format, Some(policy_id))?;
+    lifecycle_policy_store
+        .put_policy(lifecycle_policy.clone(), true)
+        .await?;
+    Ok(lifecycle_policy)
+}
+
+fn delete_lifecycle_policy_handler(
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("lifecycle" / "policies" / String)
+        .and(warp::delete())
+        .and(with_arg(lifecycle_policy_store))
+        .then(delete_lifecycle_policy)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    delete,
+    tag = "Lifecycle Policies",
+    path = "/lifecycle/policies/{policy_id}",
+    responses(
+        (status = 200, description = "The lifecycle policy was successfully deleted."),
+        (status = 404, description = "The lifecycle policy was not found.")
+    ),
+)]
+/// Deletes the lifecycle policy identified by `policy_id`.
+async fn delete_lifecycle_policy(
+    policy_id: LifecyclePolicyId,
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> Result<(), JanitorError> {
+    lifecycle_policy_store.delete_policy(&policy_id).await
+}
+
+fn list_lifecycle_policies_handler(
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("lifecycle" / "policies")
+        .and(warp::get())
+        .and(with_arg(lifecycle_policy_store))
+        .then(list_lifecycle_policies)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    get,
+    tag = "Lifecycle Policies",
+    path = "/lifecycle/policies",
+    responses(
+        (status = 200, description = "The lifecycle policies were successfully retrieved.", body = [LifecyclePolicy]),
+    ),
+)]
+/// Retrieves all the lifecycle policies.
+async fn list_lifecycle_policies(
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> Result<Vec<LifecyclePolicy>, JanitorError> {
+    lifecycle_policy_store.list_policies().await
+}
+
+fn get_index_lifecycle_status_handler(
+    metastore: MetastoreServiceClient,
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("indexes" / String / "lifecycle")
+        .and(warp::get())
+        .and(with_arg(metastore))
+        .and(with_arg(lifecycle_policy_store))
+        .then(get_index_lifecycle_status)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    get,
+    tag = "Lifecycle Policies",
+    path = "/indexes/{index_id}/lifecycle",
+    responses(
+        (status = 200, description = "The lifecycle status of the index was successfully computed.", body = IndexLifecycleStatus),
+        (status = 404, description = "The index was not found.")
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to retrieve the lifecycle status for."),
+    )
+)]
+/// Returns the lifecycle policy that applies to the index and how its published splits are
+/// distributed across the phases of the policy.
+async fn get_index_lifecycle_status(
+    index_id: IndexId,
+    metastore: MetastoreServiceClient,
+    lifecycle_policy_store: LifecyclePolicyStore,
+) -> Result<IndexLifecycleStatus, JanitorError> {
+    let index_metadata_request = IndexMetadataRequest::for_index_id(index_id.clone());
+    let index_uid = metastore
+        .index_metadata(index_metadata_request)
+        .await?
+        .deserialize_index_metadata()?
+        .index_uid;
+    let query = ListSplitsQuery::for_index(index_uid).with_split_state(SplitState::Published);
+    let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+    let published_splits = metastore
+        .list_splits(list_splits_request)
+        .await?
+        .collect_splits_metadata()
+        .await?;
+    let lifecycle_policies = lifecycle_policy_store.list_policies().await?;
+    let lifecycle_policy_opt =
+        LifecyclePolicy::find_matching_policy(&lifecycle_policies, &index_id);
+    let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+
+    compute_index_lifecycle_status(
+        index_id,
+        lifecycle_policy_opt,
+        &published_splits,
+        now_timestamp,
+    )
+    .map_err(|error| JanitorError::Internal(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_common::ServiceStream;
+    use quickwit_config::IndexConfig;
+    use quickwit_janitor::LifecyclePhaseName;
+    use quickwit_metastore::{
+        metastore_for_test, IndexMetadata, ListSplitsResponseExt, Split, SplitMetadata,
+    };
+    use quickwit_proto::metastore::{
+        IndexMetadataResponse, ListSplitsResponse, MockMetastoreService,
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    fn lifecycle_policy_store_for_test() -> LifecyclePolicyStore {
+        LifecyclePolicyStore::new(metastore_for_test())
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_policy_crud() {
+        let lifecycle_policy_store = lifecycle_policy_store_for_test();
+        let metastore = MetastoreServiceClient::from_mock(MockMetastoreService::new());
+        let handler = lifecycle_policy_api_handlers(metastore, lifecycle_policy_store);
+
+        let policy_json = json!({
+            "policy_id": "logs",
+            "index_id_patterns": ["logs-*"],
+            "phases": {
+                "force_merge": {"min_age": "1 day"},
+                "delete": {"min_age": "30 days"},
+            }
+        });
+        let response = warp::test::request()
+            .path("/lifecycle/policies")
+            .method("POST")
+            .json(&policy_json)
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let response = warp::test::request()
+            .path("/lifecycle/policies")
+            .method("POST")
+            .json(&policy_json)
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 409);
+
+        let response = warp::test::request()
+            .path("/lifecycle/policies")
+            .method("POST")
+            .json(&json!({
+                "policy_id": "invalid",
+                "index_id_patterns": ["logs-*"],
+                "phases": {
+                    "force_merge": {"min_age": "30 days"},
+                    "delete": {"min_age": "1 day"},
+                }
+            }))
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 400);
+
+        let response = warp::test::request()
+            .path("/lifecycle/policies/logs")
+            .method("PUT")
+            .json(&json!({
+                "index_id_patterns": ["logs-*"],
+                "phases": {
+                    "delete": {"min_age": "7 days"},
+                }
+            }))
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let response = warp::test::request()
+            .path("/lifecycle/policies/logs")
+            .method("GET")
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 200);
+        let lifecycle_policy: LifecyclePolicy = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(lifecycle_policy.policy_id, "logs");
+        assert!(lifecycle_policy.phases.force_merge.is_none());
+        assert_eq!(lifecycle_policy.phases.delete.unwrap().min_age, "7 days");
+
+        let response = warp::test::request()
+            .path("/lifecycle/policies")
+            .method("GET")
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 200);
+        let lifecycle_policies: Vec<LifecyclePolicy> =
+            serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(lifecycle_policies.len(), 1);
+
+        let response = warp::test::request()
+            .path("/lifecycle/policies/logs")
+            .method("DELETE")
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let response = warp::test::request()
+            .path("/lifecycle/policies/logs")
+            .method("GET")
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_get_index_lifecycle_status() {
+        let lifecycle_policy_store = lifecycle_policy_store_for_test();
+        lifecycle_policy_store
+            .put_policy(LifecyclePolicy::for_test("logs", &["logs-*"]), false)
+            .await
+            .unwrap();
+
+        let mut mock_metastore = MockMetastoreService::new();
+        mock_metastore.expect_index_metadata().return_once(|_| {
+            let index_metadata =
+                IndexMetadata::new(IndexConfig::for_test("logs-1", "ram:///indexes/logs-1"));
+            Ok(IndexMetadataResponse::try_from_index_metadata(&index_metadata).unwrap())
+        });
+        mock_metastore.expect_list_splits().return_once(|_| {
+            let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+            let splits = [
+                ("split-1", now_timestamp),
+                ("split-2", now_timestamp - 2 * 24 * 3600),
+                ("split-3", now_timestamp - 60 * 24 * 3600),
+            ]
+            .into_iter()
+            .map(|(split_id, end_timestamp)| Split {
+                split_metadata: SplitMetadata {
+                    split_id: split_id.to_string(),
+                    num_docs: 10,
+                    time_range: Some(end_timestamp - 60..=end_timestamp),
+                    ..Default::default()
+                },
+                split_state: SplitState::Published,
+                update_timestamp: 0,
+                publish_timestamp: None,
+            })
+            .collect();
+            let splits_response = ListSplitsResponse::try_from_splits(splits).unwrap();
+            Ok(ServiceStream::from(vec![Ok(splits_response)]))
+        });
+        let metastore = MetastoreServiceClient::from_mock(mock_metastore);
+        let handler = lifecycle_policy_api_handlers(metastore, lifecycle_policy_store);
+
+        let response = warp::test::request()
+            .path("/indexes/logs-1/lifecycle")
+            .method("GET")
+            .reply(&handler)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let status: IndexLifecycleStatus = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(status.index_id, "logs-1");
+        assert_eq!(status.policy_id.as_deref(), Some("logs"));
+
+        let phase_names: Vec<LifecyclePhaseName> =
+            status.phases.iter().map(|phase| phase.phase).collect();
+        assert_eq!(
+            phase_names,
+            [
+                LifecyclePhaseName::Hot,
+                LifecyclePhaseName::ForceMerge,
+                LifecyclePhaseName::Delete
+            ]
+        );
+        for phase in &status.phases {
+            assert_eq!(phase.num_splits, 1);
+        }
+    }
+}
@@ -25,10 +25,12 @@ use quickwit_common::metrics::{
 
 pub struct ServeMetrics {
     pub http_requests_total: IntCounterVec<2>,
+    pub http_compressed_responses_total: IntCounterVec<1>,
     pub request_duration_secs: HistogramVec<2>,
     pub ongoing_requests: IntGaugeVec<1>,
     pub pending_requests: IntGaugeVec<1>,
     pub circuit_break_total: IntCounter,
+    pub audit_events_dropped_total: IntCounter,
 }
 
 impl Default for ServeMetrics {
@@ -47,6 +49,13 @@ impl Default for ServeMetrics {
                 &[],
                 ["method", "status_code"],
             ),
+            http_compressed_responses_total: new_counter_vec(
+                "http_compressed_responses_total",
+                "Total number of HTTP responses compressed, per content encoding.",
+                "",
+                &[],
+                ["encoding"],
+            ),
             request_duration_secs: new_histogram_vec(
                 "request_duration_secs",
                 "Response time in seconds",
@@ -70,6 +79,12 @@ impl Default for ServeMetrics {
                 ["endpoint_group"],
             ),
             circuit_break_total,
+            audit_events_dropped_total: new_counter(
+                "audit_events_dropped_total",
+                "Number of audit events dropped because the audit sink was lagging or failing.",
+                "",
+                &[],
+            ),
         }
     }
 }
@@ -27,6 +27,7 @@ use quickwit_metastore::MetastoreApiSchemas;
 use utoipa::openapi::Tag;
 use utoipa::OpenApi;
 
+use crate::auth_api::{ApiKeyApi, RoleApi};
 use crate::cluster_api::ClusterApi;
 use crate::delete_task_api::DeleteTaskApi;
 use crate::developer_api::DeveloperApi;
@@ -36,10 +37,12 @@ use crate::index_api::IndexApi;
 use crate::indexing_api::IndexingApi;
 use crate::ingest_api::{IngestApi, IngestApiSchemas};
 use crate::jaeger_api::JaegerApi;
+use crate::lifecycle_api::LifecyclePolicyApi;
 use crate::metrics_api::MetricsApi;
 use crate::node_info_handler::NodeInfoApi;
 use crate::otlp_api::OtlpApi;
 use crate::search_api::SearchApi;
+use crate::sql_api::SqlApi;
 use crate::template_api::IndexTemplateApi;
 
 /// Builds the OpenApi docs structure using the registered/merged docs.
@@ -71,6 +74,7 @@ pub fn build_docs() -> utoipa::openapi::OpenApi {
         Tag::new("Indexes"),
         Tag::new("Ingest"),
         Tag::new("Delete Tasks"),
+        Tag::new("Lifecycle Policies"),
         Tag::new("Node Health"),
         Tag::new("Sources"),
         Tag::new("Get Metrics"),
@@ -81,10 +85,13 @@ pub fn build_docs() -> utoipa::openapi::OpenApi {
         Tag::new("Jaeger"),
         Tag::new("Open Telemetry"),
         Tag::new("Debug"),
+        Tag::new("API Keys"),
+        Tag::new("Roles"),
     ];
     docs_base.tags = Some(tags);
 
     // Routing
+    docs_base.merge_components_and_paths(ApiKeyApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(ClusterApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(DeleteTaskApi::openapi().with_path_prefix("/api/v1"));
     docs_base
@@ -98,9 +105,12 @@ pub fn build_docs() -> utoipa::openapi::OpenApi {
     docs_base.merge_components_and_paths(IndexTemplateApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(IngestApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(JaegerApi::openapi().with_path_prefix("/api/v1"));
+    docs_base.merge_components_and_paths(LifecyclePolicyApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(MetricsApi::openapi().with_path_prefix("/metrics"));
     docs_base.merge_components_and_paths(NodeInfoApi::openapi().with_path_prefix("/api/v1"));
+    docs_base.merge_components_and_paths(RoleApi::openapi().with_path_prefix("/api/v1"));
     docs_base.merge_components_and_paths(SearchApi::openapi().with_path_prefix("/api/v1"));
+    docs_base.merge_components_and_paths(SqlApi::openapi().with_path_prefix("/api/v1"));
 
     // Schemas
     docs_base.merge_components_and_paths(MetastoreApiSchemas::openapi());
@@ -0,0 +1,516 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Encoding and decoding of the PostgreSQL frontend/backend protocol (version 3.0) messages.
+//!
+//! See <https://www.postgresql.org/docs/current/protocol-message-formats.html>.
+
+use std::collections::HashMap;
+use std::io;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+const PROTOCOL_VERSION_3: i32 = 196_608;
+const SSL_REQUEST_CODE: i32 = 80_877_103;
+const GSSENC_REQUEST_CODE: i32 = 80_877_104;
+const CANCEL_REQUEST_CODE: i32 = 80_877_102;
+
+/// Maximum length of a frontend message, including its length field.
+const MAX_MESSAGE_LEN: usize = 16 * 1024 * 1024;
+
+pub(super) const BOOL_OID: i32 = 16;
+pub(super) const INT8_OID: i32 = 20;
+pub(super) const TEXT_OID: i32 = 25;
+pub(super) const JSON_OID: i32 = 114;
+pub(super) const FLOAT8_OID: i32 = 701;
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// First message sent by a client on a new connection.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum StartupMessage {
+    SslRequest,
+    GssEncRequest,
+    CancelRequest,
+    Startup { parameters: HashMap<String, String> },
+}
+
+/// Messages sent by a client once the connection is established.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum FrontendMessage {
+    Query {
+        query: String,
+    },
+    Parse {
+        statement_name: String,
+        query: String,
+    },
+    Bind {
+        portal_name: String,
+        statement_name: String,
+        parameter_format_codes: Vec<i16>,
+        parameters: Vec<Option<Bytes>>,
+        result_format_codes: Vec<i16>,
+    },
+    Describe {
+        target: DescribeTarget,
+        name: String,
+    },
+    Execute {
+        portal_name: String,
+    },
+    Close {
+        target: DescribeTarget,
+        name: String,
+    },
+    Sync,
+    Flush,
+    Terminate,
+    Unsupported {
+        tag: u8,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum DescribeTarget {
+    Statement,
+    Portal,
+}
+
+struct MessageBody {
+    bytes: Bytes,
+}
+
+impl MessageBody {
+    fn ensure_remaining(&self, num_bytes: usize) -> io::Result<()> {
+        if self.bytes.remaining() < num_bytes {
+            return Err(invalid_data("truncated message"));
+        }
+        Ok(())
+    }
+
+    fn get_u8(&mut self) -> io::Result<u8> {
+        self.ensure_remaining(1)?;
+        Ok(self.bytes.get_u8())
+    }
+
+    fn get_i16(&mut self) -> io::Result<i16> {
+        self.ensure_remaining(2)?;
+        Ok(self.bytes.get_i16())
+    }
+
+    fn get_i32(&mut self) -> io::Result<i32> {
+        self.ensure_remaining(4)?;
+        Ok(self.bytes.get_i32())
+    }
+
+    fn get_bytes(&mut self, len: usize) -> io::Result<Bytes> {
+        self.ensure_remaining(len)?;
+        Ok(self.bytes.split_to(len))
+    }
+
+    fn get_cstring(&mut self) -> io::Result<String> {
+        let Some(nul_pos) = self.bytes.iter().position(|byte| *byte == 0) else {
+            return Err(invalid_data("unterminated string in message"));
+        };
+        let string_bytes = self.bytes.split_to(nul_pos);
+        self.bytes.advance(1);
+        String::from_utf8(string_bytes.to_vec()).map_err(|_| invalid_data("invalid UTF-8 string"))
+    }
+
+    fn get_i16_array(&mut self) -> io::Result<Vec<i16>> {
+        let len = self.get_i16()?;
+        (0..len).map(|_| self.get_i16()).collect()
+    }
+}
+
+async fn read_body<R: AsyncRead + Unpin>(reader: &mut R, len: i32) -> io::Result<MessageBody> {
+    let len = usize::try_from(len).map_err(|_| invalid_data("invalid message length"))?;
+    if !(4..=MAX_MESSAGE_LEN).contains(&len) {
+        return Err(invalid_data(format!("invalid message length `{len}`")));
+    }
+    let mut body = vec![0u8; len - 4];
+    reader.read_exact(&mut body).await?;
+    Ok(MessageBody {
+        bytes: Bytes::from(body),
+    })
+}
+
+/// Reads the first message of a connection, which unlike the other messages has no type byte.
+pub(super) async fn read_startup_message<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> io::Result<StartupMessage> {
+    let len = reader.read_i32().await?;
+    let mut body = read_body(reader, len).await?;
+    let code = body.get_i32()?;
+
+    match code {
+        SSL_REQUEST_CODE => Ok(StartupMessage::SslRequest),
+        GSSENC_REQUEST_CODE => Ok(StartupMessage::GssEncRequest),
+        CANCEL_REQUEST_CODE => Ok(StartupMessage::CancelRequest),
+        PROTOCOL_VERSION_3 => {
+            let mut parameters = HashMap::new();
+            loop {
+                let name = body.get_cstring()?;
+                if name.is_empty() {
+                    break;
+                }
+                let value = body.get_cstring()?;
+                parameters.insert(name, value);
+            }
+            Ok(StartupMessage::Startup { parameters })
+        }
+        _ => Err(invalid_data(format!(
+            "unsupported protocol version `{}.{}`",
+            code >> 16,
+            code & 0xffff
+        ))),
+    }
+}
+
+fn parse_describe_target(target: u8) -> io::Result<DescribeTarget> {
+    match target {
+        b'S' => Ok(DescribeTarget::Statement),
+        b'P' => Ok(DescribeTarget::Portal),
+        _ => Err(invalid_data(format!(
+            "invalid describe target `{}`",
+            target as char
+        ))),
+    }
+}
+
+/// Reads a frontend message. Returns `None` if the client closed the connection.
+pub(super) async fn read_frontend_message<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> io::Result<Option<FrontendMessage>> {
+    let tag = match reader.read_u8().await {
+        Ok(tag) => tag,
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    };
+    let len = reader.read_i32().await?;
+    let mut body = read_body(reader, len).await?;
+
+    let message = match tag {
+        b'Q' => FrontendMessage::Query {
+            query: body.get_cstring()?,
+        },
+        b'P' => {
+            let statement_name = body.get_cstring()?;
+            let query = body.get_cstring()?;
+            // The parameter types are inferred from the values bound to the statement.
+            body.get_i16_array()?;
+            FrontendMessage::Parse {
+                statement_name,
+                query,
+            }
+        }
+        b'B' => {
+            let portal_name = body.get_cstring()?;
+            let statement_name = body.get_cstring()?;
+            let parameter_format_codes = body.get_i16_array()?;
+            let num_parameters = body.get_i16()?;
+            let mut parameters = Vec::with_capacity(num_parameters.max(0) as usize);
+
+            for _ in 0..num_parameters {
+                let parameter_len = body.get_i32()?;
+                let parameter_opt = if parameter_len < 0 {
+                    None
+                } else {
+                    Some(body.get_bytes(parameter_len as usize)?)
+                };
+                parameters.push(parameter_opt);
+            }
+            let result_format_codes = body.get_i16_array()?;
+            FrontendMessage::Bind {
+                portal_name,
+                statement_name,
+                parameter_format_codes,
+                parameters,
+                result_format_codes,
+            }
+        }
+        b'D' => {
+            let target = parse_describe_target(body.get_u8()?)?;
+            let name = body.get_cstring()?;
+            FrontendMessage::Describe { target, name }
+        }
+        b'E' => {
+            let portal_name = body.get_cstring()?;
+            // The maximum number of rows is not supported: all the rows are always returned.
+            body.get_i32()?;
+            FrontendMessage::Execute { portal_name }
+        }
+        b'C' => {
+            let target = parse_describe_target(body.get_u8()?)?;
+            let name = body.get_cstring()?;
+            FrontendMessage::Close { target, name }
+        }
+        b'S' => FrontendMessage::Sync,
+        b'H' => FrontendMessage::Flush,
+        b'X' => FrontendMessage::Terminate,
+        _ => FrontendMessage::Unsupported { tag },
+    };
+    Ok(Some(message))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct FieldDescription {
+    pub name: String,
+    pub type_oid: i32,
+}
+
+/// Messages sent by the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum BackendMessage {
+    AuthenticationOk,
+    ParameterStatus { name: String, value: String },
+    BackendKeyData { process_id: i32, secret_key: i32 },
+    ReadyForQuery,
+    RowDescription { fields: Vec<FieldDescription> },
+    DataRow { values: Vec<Option<String>> },
+    CommandComplete { tag: String },
+    EmptyQueryResponse,
+    ErrorResponse { code: &'static str, message: String },
+    ParseComplete,
+    BindComplete,
+    CloseComplete,
+    ParameterDescription { type_oids: Vec<i32> },
+    NoData,
+}
+
+fn put_cstring(buffer: &mut BytesMut, string: &str) {
+    buffer.put_slice(string.as_bytes());
+    buffer.put_u8(0);
+}
+
+impl BackendMessage {
+    fn tag(&self) -> u8 {
+        match self {
+            Self::AuthenticationOk => b'R',
+            Self::ParameterStatus { .. } => b'S',
+            Self::BackendKeyData { .. } => b'K',
+            Self::ReadyForQuery => b'Z',
+            Self::RowDescription { .. } => b'T',
+            Self::DataRow { .. } => b'D',
+            Self::CommandComplete { .. } => b'C',
+            Self::EmptyQueryResponse => b'I',
+            Self::ErrorResponse { .. } => b'E',
+            Self::ParseComplete => b'1',
+            Self::BindComplete => b'2',
+            Self::CloseComplete => b'3',
+            Self::ParameterDescription { .. } => b't',
+            Self::NoData => b'n',
+        }
+    }
+
+    /// Appends the message to the buffer.
+    pub fn encode(&self, buffer: &mut BytesMut) {
+        buffer.put_u8(self.tag());
+        let len_pos = buffer.len();
+        // The length is patched once the body is written.
+        buffer.put_i32(0);
+
+        match self {
+            Self::AuthenticationOk => buffer.put_i32(0),
+            Self::ParameterStatus { name, value } => {
+                put_cstring(buffer, name);
+                put_cstring(buffer, value);
+            }
+            Self::BackendKeyData {
+                process_id,
+                secret_key,
+            } => {
+                buffer.put_i32(*process_id);
+                buffer.put_i32(*secret_key);
+            }
+            // We never open transactions: the server is always idle.
+            Self::ReadyForQuery => buffer.put_u8(b'I'),
+            Self::RowDescription { fields } => {
+                buffer.put_i16(fields.len() as i16);
+                for field in fields {
+                    put_cstring(buffer, &field.name);
+                    // Table OID and column attribute number.
+                    buffer.put_i32(0);
+                    buffer.put_i16(0);
+                    buffer.put_i32(field.type_oid);
+                    // Variable type size and no type modifier.
+                    buffer.put_i16(-1);
+                    buffer.put_i32(-1);
+                    // Text format.
+                    buffer.put_i16(0);
+                }
+            }
+            Self::DataRow { values } => {
+                buffer.put_i16(values.len() as i16);
+                for value_opt in values {
+                    if let Some(value) = value_opt {
+                        buffer.put_i32(value.len() as i32);
+                        buffer.put_slice(value.as_bytes());
+                    } else {
+                        buffer.put_i32(-1);
+                    }
+                }
+            }
+            Self::CommandComplete { tag } => put_cstring(buffer, tag),
+            Self::ErrorResponse { code, message } => {
+                for (field_type, field_value) in [
+                    (b'S', "ERROR"),
+                    (b'V', "ERROR"),
+                    (b'C', code),
+                    (b'M', message.as_str()),
+                ] {
+                    buffer.put_u8(field_type);
+                    put_cstring(buffer, field_value);
+                }
+                buffer.put_u8(0);
+            }
+            Self::ParameterDescription { type_oids } => {
+                buffer.put_i16(type_oids.len() as i16);
+                for type_oid in type_oids {
+                    buffer.put_i32(*type_oid);
+                }
+            }
+            Self::EmptyQueryResponse
+            | Self::ParseComplete
+            | Self::BindComplete
+            | Self::CloseComplete
+            | Self::NoData => {}
+        }
+        let len = (buffer.len() - len_pos) as i32;
+        buffer[len_pos..len_pos + 4].copy_from_slice(&len.to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frontend_message_bytes(tag: u8, body: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![tag];
+        bytes.extend_from_slice(&(body.len() as i32 + 4).to_be_bytes());
+        bytes.extend_from_slice(body);
+        bytes
+    }
+
+    #[tokio::test]
+    async fn test_read_startup_message() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&PROTOCOL_VERSION_3.to_be_bytes());
+        body.extend_from_slice(b"user\0quickwit\0database\0logs\0\0");
+        let mut bytes = (body.len() as i32 + 4).to_be_bytes().to_vec();
+        bytes.extend_from_slice(&body);
+
+        let startup_message = read_startup_message(&mut &bytes[..]).await.unwrap();
+        let expected_parameters = HashMap::from([
+            ("user".to_string(), "quickwit".to_string()),
+            ("database".to_string(), "logs".to_string()),
+        ]);
+        assert_eq!(
+            startup_message,
+            StartupMessage::Startup {
+                parameters: expected_parameters
+            }
+        );
+        let mut bytes = 8i32.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&SSL_REQUEST_CODE.to_be_bytes());
+        let startup_message = read_startup_message(&mut &bytes[..]).await.unwrap();
+        assert_eq!(startup_message, StartupMessage::SslRequest);
+
+        let mut bytes = 8i32.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&0x0002_0000i32.to_be_bytes());
+        let error = read_startup_message(&mut &bytes[..]).await.unwrap_err();
+        assert_eq!(error.to_string(), "unsupported protocol version `2.0`");
+    }
+
+    #[tokio::test]
+    async fn test_read_frontend_message() {
+        let bytes = frontend_message_bytes(b'Q', b"SELECT * FROM logs\0");
+        let message = read_frontend_message(&mut &bytes[..]).await.unwrap();
+        assert_eq!(
+            message,
+            Some(FrontendMessage::Query {
+                query: "SELECT * FROM logs".to_string()
+            })
+        );
+        let mut body = Vec::new();
+        body.extend_from_slice(b"\0stmt\0");
+        body.extend_from_slice(&1i16.to_be_bytes());
+        body.extend_from_slice(&0i16.to_be_bytes());
+        body.extend_from_slice(&2i16.to_be_bytes());
+        body.extend_from_slice(&3i32.to_be_bytes());
+        body.extend_from_slice(b"api");
+        body.extend_from_slice(&(-1i32).to_be_bytes());
+        body.extend_from_slice(&0i16.to_be_bytes());
+        let bytes = frontend_message_bytes(b'B', &body);
+        let message = read_frontend_message(&mut &bytes[..]).await.unwrap();
+        assert_eq!(
+            message,
+            Some(FrontendMessage::Bind {
+                portal_name: String::new(),
+                statement_name: "stmt".to_string(),
+                parameter_format_codes: vec![0],
+                parameters: vec![Some(Bytes::from_static(b"api")), None],
+                result_format_codes: Vec::new(),
+            })
+        );
+        let bytes = frontend_message_bytes(b'D', b"Pportal\0");
+        let message = read_frontend_message(&mut &bytes[..]).await.unwrap();
+        assert_eq!(
+            message,
+            Some(FrontendMessage::Describe {
+                target: DescribeTarget::Portal,
+                name: "portal".to_string(),
+            })
+        );
+        let message = read_frontend_message(&mut &b""[..]).await.unwrap();
+        assert_eq!(message, None);
+
+        let bytes = frontend_message_bytes(b'Q', b"SELECT");
+        let error = read_frontend_message(&mut &bytes[..]).await.unwrap_err();
+        assert_eq!(error.to_string(), "unterminated string in message");
+    }
+
+    #[test]
+    fn test_encode_backend_messages() {
+        let mut buffer = BytesMut::new();
+        BackendMessage::ReadyForQuery.encode(&mut buffer);
+        assert_eq!(&buffer[..], b"Z\0\0\0\x05I");
+
+        let mut buffer = BytesMut::new();
+        BackendMessage::DataRow {
+            values: vec![Some("ab".to_string()), None],
+        }
+        .encode(&mut buffer);
+        assert_eq!(
+            &buffer[..],
+            b"D\0\0\0\x10\0\x02\0\0\0\x02ab\xff\xff\xff\xff"
+        );
+        let mut buffer = BytesMut::new();
+        BackendMessage::ErrorResponse {
+            code: "42601",
+            message: "oops".to_string(),
+        }
+        .encode(&mut buffer);
+        assert_eq!(&buffer[..], b"E\0\0\0\x20SERROR\0VERROR\0C42601\0Moops\0\0");
+    }
+}
@@ -0,0 +1,940 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Minimal PostgreSQL wire protocol server exposing the read-only SQL API, so that standard
+//! PostgreSQL clients (`psql`, JDBC/ODBC drivers, BI tools) can query Quickwit indexes.
+//!
+//! Authentication is not supported: the listener should only be exposed on trusted networks, and
+//! the node refuses to start it when API key authentication is enabled.
+
+mod messages;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bytes::{Bytes, BytesMut};
+use quickwit_common::tower::BoxFutureInfaillible;
+use quickwit_search::{SearchError, SearchService};
+use serde_json::Value as JsonValue;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tracing::{debug, info, warn};
+
+use self::messages::{
+    read_frontend_message, read_startup_message, BackendMessage, DescribeTarget, FieldDescription,
+    FrontendMessage, StartupMessage, BOOL_OID, FLOAT8_OID, INT8_OID, JSON_OID, TEXT_OID,
+};
+use crate::sql_api::{execute_sql_query, SqlResponse};
+
+const SERVER_VERSION: &str = "14.0";
+
+/// Parameters reported to the client on startup and returned by `SHOW` statements.
+const PARAMETER_STATUSES: [(&str, &str); 7] = [
+    ("server_version", SERVER_VERSION),
+    ("server_encoding", "UTF8"),
+    ("client_encoding", "UTF8"),
+    ("DateStyle", "ISO, MDY"),
+    ("TimeZone", "UTC"),
+    ("integer_datetimes", "on"),
+    ("standard_conforming_strings", "on"),
+];
+
+/// Starts the PostgreSQL wire protocol server and accepts connections until the shutdown signal
+/// is received.
+pub(crate) async fn start_pg_wire_server(
+    tcp_listener: TcpListener,
+    search_service: Arc<dyn SearchService>,
+    mut shutdown_signal: BoxFutureInfaillible<()>,
+) -> anyhow::Result<()> {
+    info!(
+        listen_addr=?tcp_listener.local_addr()?,
+        "starting PostgreSQL wire protocol server"
+    );
+    let mut next_process_id: i32 = 1;
+
+    loop {
+        let (tcp_stream, peer_addr) = tokio::select! {
+            _ = &mut shutdown_signal => break,
+            accept_res = tcp_listener.accept() => match accept_res {
+                Ok(accepted) => accepted,
+                Err(error) => {
+                    warn!(%error, "failed to accept PostgreSQL connection");
+                    continue;
+                }
+            },
+        };
+        let search_service = search_service.clone();
+        let process_id = next_process_id;
+        next_process_id = next_process_id.wrapping_add(1).max(1);
+
+        tokio::spawn(async move {
+            if let Err(error) = handle_connection(tcp_stream, search_service, process_id).await {
+                debug!(%peer_addr, %error, "PostgreSQL connection closed with an error");
+            }
+        });
+    }
+    info!("PostgreSQL wire protocol server shut down");
+    Ok(())
+}
+
+/// Error reported to the client in an `ErrorResponse` message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PgError {
+    /// SQLSTATE code.
+    code: &'static str,
+    message: String,
+}
+
+impl PgError {
+    fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn feature_not_supported(message: impl Into<String>) -> Self {
+        Self::new("0A000", message)
+    }
+}
+
+impl From<SearchError> for PgError {
+    fn from(search_error: SearchError) -> Self {
+        let code = match &search_error {
+            SearchError::InvalidQuery(_) => "42601",
+            SearchError::IndexesNotFound { .. } => "42P01",
+            SearchError::AsyncSearchNotFound(_) | SearchError::PointInTimeNotFound(_) => "42704",
+            SearchError::InvalidAggregationRequest(_) | SearchError::InvalidArgument(_) => "22023",
+            SearchError::Timeout(_) => "57014",
+            SearchError::TooManyRequests | SearchError::Unavailable(_) => "53000",
+            SearchError::Internal(_) | SearchError::StorageResolver(_) => "XX000",
+        };
+        Self::new(code, search_error.to_string())
+    }
+}
+
+impl From<PgError> for BackendMessage {
+    fn from(error: PgError) -> Self {
+        BackendMessage::ErrorResponse {
+            code: error.code,
+            message: error.message,
+        }
+    }
+}
+
+/// Result of a statement, with all its values encoded in the text format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct QueryResult {
+    /// Empty for statements that do not return rows.
+    fields: Vec<FieldDescription>,
+    rows: Vec<Vec<Option<String>>>,
+    command_tag: String,
+}
+
+impl QueryResult {
+    fn command(command_tag: &str) -> Self {
+        Self {
+            command_tag: command_tag.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn single_value(column: &str, type_oid: i32, value: String) -> Self {
+        Self {
+            fields: vec![FieldDescription {
+                name: column.to_string(),
+                type_oid,
+            }],
+            rows: vec![vec![Some(value)]],
+            command_tag: "SELECT 1".to_string(),
+        }
+    }
+
+    fn row_description(&self) -> BackendMessage {
+        if self.fields.is_empty() {
+            BackendMessage::NoData
+        } else {
+            BackendMessage::RowDescription {
+                fields: self.fields.clone(),
+            }
+        }
+    }
+
+    fn encode_rows(&self, buffer: &mut BytesMut) {
+        for row in &self.rows {
+            BackendMessage::DataRow {
+                values: row.clone(),
+            }
+            .encode(buffer);
+        }
+        BackendMessage::CommandComplete {
+            tag: self.command_tag.clone(),
+        }
+        .encode(buffer);
+    }
+}
+
+fn value_type_oid(value: &JsonValue) -> Option<i32> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::Bool(_) => Some(BOOL_OID),
+        JsonValue::Number(number) if number.is_i64() => Some(INT8_OID),
+        JsonValue::Number(_) => Some(FLOAT8_OID),
+        JsonValue::String(_) => Some(TEXT_OID),
+        JsonValue::Array(_) | JsonValue::Object(_) => Some(JSON_OID),
+    }
+}
+
+/// Infers the type of a column from its values, falling back to `text` for columns holding
+/// values of different types.
+fn column_type_oid(rows: &[Vec<JsonValue>], column_idx: usize) -> i32 {
+    let mut column_type_oid_opt = None;
+
+    for value_type_oid in rows
+        .iter()
+        .filter_map(|row| row.get(column_idx).and_then(value_type_oid))
+    {
+        column_type_oid_opt = match column_type_oid_opt {
+            None => Some(value_type_oid),
+            Some(type_oid) if type_oid == value_type_oid => Some(type_oid),
+            Some(INT8_OID | FLOAT8_OID) if matches!(value_type_oid, INT8_OID | FLOAT8_OID) => {
+                Some(FLOAT8_OID)
+            }
+            Some(_) => return TEXT_OID,
+        };
+    }
+    column_type_oid_opt.unwrap_or(TEXT_OID)
+}
+
+fn encode_text_value(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::Bool(true) => Some("t".to_string()),
+        JsonValue::Bool(false) => Some("f".to_string()),
+        JsonValue::String(text) => Some(text.clone()),
+        _ => Some(value.to_string()),
+    }
+}
+
+impl From<SqlResponse> for QueryResult {
+    fn from(sql_response: SqlResponse) -> Self {
+        let fields = sql_response
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(column_idx, column)| FieldDescription {
+                name: column.name.clone(),
+                type_oid: column_type_oid(&sql_response.rows, column_idx),
+            })
+            .collect();
+        let rows: Vec<Vec<Option<String>>> = sql_response
+            .rows
+            .iter()
+            .map(|row| row.iter().map(encode_text_value).collect())
+            .collect();
+        let command_tag = format!("SELECT {}", rows.len());
+        Self {
+            fields,
+            rows,
+            command_tag,
+        }
+    }
+}
+
+/// Splits a query string into its statements, ignoring the semicolons within quotes.
+fn split_statements(query: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut quote_opt: Option<char> = None;
+    let mut start = 0;
+
+    for (idx, ch) in query.char_indices() {
+        match (quote_opt, ch) {
+            (None, '\'' | '"' | '`') => quote_opt = Some(ch),
+            (Some(quote), _) if quote == ch => quote_opt = None,
+            (None, ';') => {
+                statements.push(&query[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(&query[start..]);
+    statements
+        .into_iter()
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+/// Replaces the `$n` placeholders located outside quotes with the corresponding parameters.
+fn substitute_parameters(query: &str, parameters: &[Option<String>]) -> Result<String, PgError> {
+    let mut substituted = String::with_capacity(query.len());
+    let mut quote_opt: Option<char> = None;
+    let mut chars = query.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match (quote_opt, ch) {
+            (None, '\'' | '"' | '`') => quote_opt = Some(ch),
+            (Some(quote), _) if quote == ch => quote_opt = None,
+            (None, '$') if chars.peek().is_some_and(char::is_ascii_digit) => {
+                let mut position = String::new();
+                while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                    position.push(digit);
+                }
+                let parameter_opt = position
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|position| position.checked_sub(1))
+                    .and_then(|parameter_idx| parameters.get(parameter_idx));
+                let Some(parameter) = parameter_opt else {
+                    return Err(PgError::new(
+                        "08P01",
+                        format!("no value bound for parameter `${position}`"),
+                    ));
+                };
+                match parameter {
+                    None => substituted.push_str("NULL"),
+                    Some(value) if value.parse::<f64>().is_ok_and(f64::is_finite) => {
+                        substituted.push_str(value)
+                    }
+                    Some(value) => {
+                        substituted.push('\'');
+                        substituted.push_str(&value.replace('\'', "''"));
+                        substituted.push('\'');
+                    }
+                }
+                continue;
+            }
+            _ => {}
+        }
+        substituted.push(ch);
+    }
+    Ok(substituted)
+}
+
+/// Returns the number of parameters of a query, i.e. the highest `$n` placeholder position.
+fn num_parameters(query: &str) -> usize {
+    let mut num_parameters = 0;
+    let mut quote_opt: Option<char> = None;
+    let mut chars = query.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match (quote_opt, ch) {
+            (None, '\'' | '"' | '`') => quote_opt = Some(ch),
+            (Some(quote), _) if quote == ch => quote_opt = None,
+            (None, '$') => {
+                let mut position = String::new();
+                while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                    position.push(digit);
+                }
+                if let Ok(position) = position.parse::<usize>() {
+                    num_parameters = num_parameters.max(position);
+                }
+            }
+            _ => {}
+        }
+    }
+    num_parameters
+}
+
+/// Executes a single statement.
+async fn execute_statement(
+    statement: &str,
+    search_service: &dyn SearchService,
+) -> Result<QueryResult, PgError> {
+    let statement = statement.trim().trim_end_matches(';').trim();
+    let (keyword, rest) = statement
+        .split_once(char::is_whitespace)
+        .unwrap_or((statement, ""));
+    let rest = rest.trim();
+
+    match keyword.to_ascii_lowercase().as_str() {
+        "select" => {
+            // Clients commonly probe the server with constant queries that have no `FROM` clause.
+            if rest.eq_ignore_ascii_case("version()") {
+                let version = format!("PostgreSQL {SERVER_VERSION} (Quickwit)");
+                return Ok(QueryResult::single_value("version", TEXT_OID, version));
+            }
+            if let Ok(number) = rest.parse::<i64>() {
+                return Ok(QueryResult::single_value(
+                    "?column?",
+                    INT8_OID,
+                    number.to_string(),
+                ));
+            }
+            let sql_response = execute_sql_query(statement, search_service).await?;
+            Ok(sql_response.into())
+        }
+        "show" => {
+            let parameter_value_opt = PARAMETER_STATUSES
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(rest))
+                .map(|(_, value)| value.to_string());
+            let Some(parameter_value) = parameter_value_opt else {
+                return Err(PgError::new(
+                    "42704",
+                    format!("unrecognized configuration parameter `{rest}`"),
+                ));
+            };
+            Ok(QueryResult {
+                command_tag: "SHOW".to_string(),
+                ..QueryResult::single_value(rest, TEXT_OID, parameter_value)
+            })
+        }
+        // Session settings and transactions are accepted but have no effect.
+        "set" => Ok(QueryResult::command("SET")),
+        "reset" => Ok(QueryResult::command("RESET")),
+        "discard" => Ok(QueryResult::command("DISCARD ALL")),
+        "begin" => Ok(QueryResult::command("BEGIN")),
+        "start" => Ok(QueryResult::command("START TRANSACTION")),
+        "commit" | "end" => Ok(QueryResult::command("COMMIT")),
+        "rollback" | "abort" => Ok(QueryResult::command("ROLLBACK")),
+        _ => Err(PgError::feature_not_supported(format!(
+            "unsupported statement `{keyword}`: only read-only `SELECT` queries are supported"
+        ))),
+    }
+}
+
+/// Statement bound to its parameters by a `Bind` message.
+struct Portal {
+    query: String,
+    /// Result computed when the portal is described, reused by the following `Execute` message.
+    result_opt: Option<QueryResult>,
+}
+
+async fn handle_connection<S>(
+    stream: S,
+    search_service: Arc<dyn SearchService>,
+    process_id: i32,
+) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+    let mut buffer = BytesMut::new();
+
+    loop {
+        match read_startup_message(&mut reader).await? {
+            // TLS and GSSAPI encryption are not supported: the client may proceed unencrypted.
+            StartupMessage::SslRequest | StartupMessage::GssEncRequest => {
+                writer.write_all(b"N").await?;
+                writer.flush().await?;
+            }
+            // Queries run to completion and cannot be cancelled.
+            StartupMessage::CancelRequest => return Ok(()),
+            StartupMessage::Startup { parameters } => {
+                debug!(
+                    user=?parameters.get("user"),
+                    database=?parameters.get("database"),
+                    "accepted PostgreSQL connection"
+                );
+                break;
+            }
+        }
+    }
+    BackendMessage::AuthenticationOk.encode(&mut buffer);
+
+    for (name, value) in PARAMETER_STATUSES {
+        BackendMessage::ParameterStatus {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+        .encode(&mut buffer);
+    }
+    BackendMessage::BackendKeyData {
+        process_id,
+        secret_key: 0,
+    }
+    .encode(&mut buffer);
+    BackendMessage::ReadyForQuery.encode(&mut buffer);
+    writer.write_all(&buffer.split()).await?;
+    writer.flush().await?;
+
+    let mut statements: HashMap<String, String> = HashMap::new();
+    let mut portals: HashMap<String, Portal> = HashMap::new();
+    // After an error in the extended query protocol, messages are discarded until `Sync`.
+    let mut skip_until_sync = false;
+
+    while let Some(message) = read_frontend_message(&mut reader).await? {
+        if skip_until_sync
+            && !matches!(
+                message,
+                FrontendMessage::Sync | FrontendMessage::Query { .. } | FrontendMessage::Terminate
+            )
+        {
+            continue;
+        }
+        match message {
+            FrontendMessage::Query { query } => {
+                skip_until_sync = false;
+                let statements = split_statements(&query);
+
+                if statements.is_empty() {
+                    BackendMessage::EmptyQueryResponse.encode(&mut buffer);
+                }
+                for statement in statements {
+                    match execute_statement(statement, &*search_service).await {
+                        Ok(query_result) => {
+                            if !query_result.fields.is_empty() {
+                                query_result.row_description().encode(&mut buffer);
+                            }
+                            query_result.encode_rows(&mut buffer);
+                        }
+                        Err(error) => {
+                            BackendMessage::from(error).encode(&mut buffer);
+                            break;
+                        }
+                    }
+                }
+                BackendMessage::ReadyForQuery.encode(&mut buffer);
+                writer.write_all(&buffer.split()).await?;
+                writer.flush().await?;
+            }
+            FrontendMessage::Parse {
+                statement_name,
+                query,
+            } => {
+                if split_statements(&query).len() > 1 {
+                    let error = PgError::new(
+                        "42601",
+                        "cannot insert multiple commands into a prepared statement",
+                    );
+                    BackendMessage::from(error).encode(&mut buffer);
+                    skip_until_sync = true;
+                    continue;
+                }
+                statements.insert(statement_name, query);
+                BackendMessage::ParseComplete.encode(&mut buffer);
+            }
+            FrontendMessage::Bind {
+                portal_name,
+                statement_name,
+                parameter_format_codes,
+                parameters,
+                result_format_codes,
+            } => {
+                match bind_portal(
+                    &statements,
+                    &statement_name,
+                    &parameter_format_codes,
+                    parameters,
+                    &result_format_codes,
+                ) {
+                    Ok(portal) => {
+                        portals.insert(portal_name, portal);
+                        BackendMessage::BindComplete.encode(&mut buffer);
+                    }
+                    Err(error) => {
+                        BackendMessage::from(error).encode(&mut buffer);
+                        skip_until_sync = true;
+                    }
+                }
+            }
+            FrontendMessage::Describe { target, name } => {
+                match describe(&statements, &mut portals, target, &name, &*search_service).await {
+                    Ok(messages) => {
+                        for message in messages {
+                            message.encode(&mut buffer);
+                        }
+                    }
+                    Err(error) => {
+                        BackendMessage::from(error).encode(&mut buffer);
+                        skip_until_sync = true;
+                    }
+                }
+            }
+            FrontendMessage::Execute { portal_name } => {
+                let query_result_res = match portals.get_mut(&portal_name) {
+                    Some(portal) => match portal.result_opt.take() {
+                        Some(query_result) => Ok(query_result),
+                        None => execute_portal_query(&portal.query, &*search_service).await,
+                    },
+                    None => Err(PgError::new(
+                        "34000",
+                        format!("portal `{portal_name}` does not exist"),
+                    )),
+                };
+                match query_result_res {
+                    Ok(query_result) => query_result.encode_rows(&mut buffer),
+                    Err(error) => {
+                        BackendMessage::from(error).encode(&mut buffer);
+                        skip_until_sync = true;
+                    }
+                }
+            }
+            FrontendMessage::Close { target, name } => {
+                match target {
+                    DescribeTarget::Statement => statements.remove(&name),
+                    DescribeTarget::Portal => portals.remove(&name).map(|portal| portal.query),
+                };
+                BackendMessage::CloseComplete.encode(&mut buffer);
+            }
+            FrontendMessage::Sync => {
+                skip_until_sync = false;
+                // The unnamed portal only lives until the end of the transaction.
+                portals.remove("");
+                BackendMessage::ReadyForQuery.encode(&mut buffer);
+                writer.write_all(&buffer.split()).await?;
+                writer.flush().await?;
+            }
+            FrontendMessage::Flush => {
+                writer.write_all(&buffer.split()).await?;
+                writer.flush().await?;
+            }
+            FrontendMessage::Terminate => break,
+            FrontendMessage::Unsupported { tag } => {
+                let error = PgError::feature_not_supported(format!(
+                    "unsupported message type `{}`",
+                    tag as char
+                ));
+                BackendMessage::from(error).encode(&mut buffer);
+                skip_until_sync = true;
+            }
+        }
+    }
+    if !buffer.is_empty() {
+        writer.write_all(&buffer).await?;
+    }
+    writer.shutdown().await?;
+    Ok(())
+}
+
+fn bind_portal(
+    statements: &HashMap<String, String>,
+    statement_name: &str,
+    parameter_format_codes: &[i16],
+    parameters: Vec<Option<Bytes>>,
+    result_format_codes: &[i16],
+) -> Result<Portal, PgError> {
+    let Some(query) = statements.get(statement_name) else {
+        return Err(PgError::new(
+            "26000",
+            format!("prepared statement `{statement_name}` does not exist"),
+        ));
+    };
+    if parameter_format_codes
+        .iter()
+        .any(|format_code| *format_code != 0)
+    {
+        return Err(PgError::feature_not_supported(
+            "binary parameter format is not supported",
+        ));
+    }
+    if result_format_codes
+        .iter()
+        .any(|format_code| *format_code != 0)
+    {
+        return Err(PgError::feature_not_supported(
+            "binary result format is not supported",
+        ));
+    }
+    let parameters: Vec<Option<String>> = parameters
+        .into_iter()
+        .map(|parameter_opt| {
+            parameter_opt
+                .map(|parameter| {
+                    String::from_utf8(parameter.to_vec())
+                        .map_err(|_| PgError::new("22021", "invalid UTF-8 parameter value"))
+                })
+                .transpose()
+        })
+        .collect::<Result<_, _>>()?;
+    let query = substitute_parameters(query, &parameters)?;
+    Ok(Portal {
+        query,
+        result_opt: None,
+    })
+}
+
+async fn execute_portal_query(
+    query: &str,
+    search_service: &dyn SearchService,
+) -> Result<QueryResult, PgError> {
+    match split_statements(query).first() {
+        Some(statement) => execute_statement(statement, search_service).await,
+        None => Ok(QueryResult::command("")),
+    }
+}
+
+/// Returns the messages describing a prepared statement or a portal.
+///
+/// Result columns are inferred from the values they hold, so describing a statement or a portal
+/// requires running its query.
+async fn describe(
+    statements: &HashMap<String, String>,
+    portals: &mut HashMap<String, Portal>,
+    target: DescribeTarget,
+    name: &str,
+    search_service: &dyn SearchService,
+) -> Result<Vec<BackendMessage>, PgError> {
+    match target {
+        DescribeTarget::Statement => {
+            let Some(query) = statements.get(name) else {
+                return Err(PgError::new(
+                    "26000",
+                    format!("prepared statement `{name}` does not exist"),
+                ));
+            };
+            let num_parameters = num_parameters(query);
+            let parameter_description = BackendMessage::ParameterDescription {
+                type_oids: vec![TEXT_OID; num_parameters],
+            };
+            // The columns of a parameterized query are only known once its parameters are bound.
+            if num_parameters > 0 {
+                return Ok(vec![parameter_description, BackendMessage::NoData]);
+            }
+            let query_result = execute_portal_query(query, search_service).await?;
+            Ok(vec![parameter_description, query_result.row_description()])
+        }
+        DescribeTarget::Portal => {
+            let Some(portal) = portals.get_mut(name) else {
+                return Err(PgError::new(
+                    "34000",
+                    format!("portal `{name}` does not exist"),
+                ));
+            };
+            if portal.result_opt.is_none() {
+                portal.result_opt =
+                    Some(execute_portal_query(&portal.query, search_service).await?);
+            }
+            let row_description = portal
+                .result_opt
+                .as_ref()
+                .map(QueryResult::row_description)
+                .unwrap_or(BackendMessage::NoData);
+            Ok(vec![row_description])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_proto::search::{Hit, SearchResponse};
+    use quickwit_search::MockSearchService;
+    use serde_json::json;
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+    use crate::sql_api::SqlColumn;
+
+    #[test]
+    fn test_split_statements() {
+        assert_eq!(split_statements(""), Vec::<&str>::new());
+        assert_eq!(split_statements(" ; ;"), Vec::<&str>::new());
+        assert_eq!(
+            split_statements("SET x = 1; SELECT * FROM logs WHERE body = 'a;b';"),
+            vec!["SET x = 1", "SELECT * FROM logs WHERE body = 'a;b'"]
+        );
+    }
+
+    #[test]
+    fn test_substitute_parameters() {
+        let parameters = vec![Some("it's".to_string()), Some("10".to_string()), None];
+        assert_eq!(
+            substitute_parameters(
+                "SELECT * FROM logs WHERE body = $1 AND tag = '$2' AND x = $3 LIMIT $2",
+                &parameters
+            )
+            .unwrap(),
+            "SELECT * FROM logs WHERE body = 'it''s' AND tag = '$2' AND x = NULL LIMIT 10"
+        );
+        let error = substitute_parameters("SELECT * FROM logs LIMIT $4", &parameters).unwrap_err();
+        assert_eq!(error.code, "08P01");
+        assert_eq!(error.message, "no value bound for parameter `$4`");
+
+        assert_eq!(num_parameters("SELECT * FROM logs"), 0);
+        assert_eq!(
+            num_parameters("SELECT * FROM logs WHERE a = $2 AND b = '$3' LIMIT $1"),
+            2
+        );
+    }
+
+    #[test]
+    fn test_query_result_from_sql_response() {
+        let sql_response = SqlResponse {
+            columns: ["count", "avg", "level", "flag", "mixed", "nulls"]
+                .into_iter()
+                .map(|name| SqlColumn {
+                    name: name.to_string(),
+                })
+                .collect(),
+            rows: vec![
+                vec![
+                    json!(1),
+                    json!(1),
+                    json!("INFO"),
+                    json!(true),
+                    json!(1),
+                    json!(null),
+                ],
+                vec![
+                    json!(2),
+                    json!(1.5),
+                    json!(null),
+                    json!(false),
+                    json!("a"),
+                    json!(null),
+                ],
+            ],
+        };
+        let query_result = QueryResult::from(sql_response);
+        let type_oids: Vec<i32> = query_result
+            .fields
+            .iter()
+            .map(|field| field.type_oid)
+            .collect();
+        assert_eq!(
+            type_oids,
+            [INT8_OID, FLOAT8_OID, TEXT_OID, BOOL_OID, TEXT_OID, TEXT_OID]
+        );
+        assert_eq!(
+            query_result.rows[1],
+            [
+                Some("2".to_string()),
+                Some("1.5".to_string()),
+                None,
+                Some("f".to_string()),
+                Some("a".to_string()),
+                None
+            ]
+        );
+        assert_eq!(query_result.command_tag, "SELECT 2");
+    }
+
+    #[tokio::test]
+    async fn test_execute_statement() {
+        let search_service = MockSearchService::new();
+
+        let query_result = execute_statement("select 1;", &search_service)
+            .await
+            .unwrap();
+        assert_eq!(query_result.rows, [[Some("1".to_string())]]);
+
+        let query_result = execute_statement("SHOW server_version", &search_service)
+            .await
+            .unwrap();
+        assert_eq!(query_result.rows, [[Some(SERVER_VERSION.to_string())]]);
+        assert_eq!(query_result.command_tag, "SHOW");
+
+        let query_result = execute_statement("SET extra_float_digits = 3", &search_service)
+            .await
+            .unwrap();
+        assert!(query_result.fields.is_empty());
+        assert_eq!(query_result.command_tag, "SET");
+
+        let error = execute_statement("DELETE FROM logs", &search_service)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, "0A000");
+
+        let error = execute_statement("SELECT FROM", &search_service)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, "42601");
+    }
+
+    fn encode_frontend_message(tag: u8, body: &[u8], buffer: &mut Vec<u8>) {
+        buffer.push(tag);
+        buffer.extend_from_slice(&(body.len() as i32 + 4).to_be_bytes());
+        buffer.extend_from_slice(body);
+    }
+
+    /// Splits the server output into its `(tag, body)` messages.
+    fn decode_backend_messages(mut bytes: &[u8]) -> Vec<(u8, Vec<u8>)> {
+        let mut messages = Vec::new();
+
+        while !bytes.is_empty() {
+            let tag = bytes[0];
+            let len = i32::from_be_bytes(bytes[1..5].try_into().unwrap()) as usize;
+            messages.push((tag, bytes[5..1 + len].to_vec()));
+            bytes = &bytes[1 + len..];
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn test_pg_wire_simple_and_extended_queries() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .times(2)
+            .returning(|search_request| {
+                assert_eq!(search_request.index_id_patterns, vec!["logs"]);
+                Ok(SearchResponse {
+                    num_hits: 1,
+                    hits: vec![Hit {
+                        json: r#"{"severity_text": "ERROR", "status": 500}"#.to_string(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                })
+            });
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let connection_handle =
+            tokio::spawn(handle_connection(server, Arc::new(mock_search_service), 1));
+        let mut request = Vec::new();
+        let mut startup_body = 196_608i32.to_be_bytes().to_vec();
+        startup_body.extend_from_slice(b"user\0quickwit\0\0");
+        request.extend_from_slice(&(startup_body.len() as i32 + 4).to_be_bytes());
+        request.extend_from_slice(&startup_body);
+
+        encode_frontend_message(
+            b'Q',
+            b"SELECT severity_text, status FROM logs;\0",
+            &mut request,
+        );
+        encode_frontend_message(
+            b'P',
+            b"\0SELECT status FROM logs WHERE severity_text = $1\0\0\0",
+            &mut request,
+        );
+        let mut bind_body = b"\0\0\0\0\0\x01".to_vec();
+        bind_body.extend_from_slice(&5i32.to_be_bytes());
+        bind_body.extend_from_slice(b"ERROR\0\0");
+        encode_frontend_message(b'B', &bind_body, &mut request);
+        encode_frontend_message(b'D', b"P\0", &mut request);
+        encode_frontend_message(b'E', b"\0\0\0\0\0", &mut request);
+        encode_frontend_message(b'S', b"", &mut request);
+        encode_frontend_message(b'Q', b"DROP INDEX logs\0", &mut request);
+        encode_frontend_message(b'X', b"", &mut request);
+        client.write_all(&request).await.unwrap();
+
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).await.unwrap();
+        connection_handle.await.unwrap().unwrap();
+
+        let messages = decode_backend_messages(&response);
+        let tags: String = messages.iter().map(|(tag, _)| *tag as char).collect();
+        // Startup, simple query, extended query, and failed simple query.
+        assert_eq!(tags, "RSSSSSSSKZTDCZ12TDCZEZ");
+
+        let data_rows: Vec<&[u8]> = messages
+            .iter()
+            .filter(|(tag, _)| *tag == b'D')
+            .map(|(_, body)| body.as_slice())
+            .collect();
+        assert_eq!(data_rows[0], b"\0\x02\0\0\0\x05ERROR\0\0\0\x03500");
+        assert_eq!(data_rows[1], b"\0\x01\0\0\0\x03500");
+
+        let (_, error_body) = &messages[messages.len() - 2];
+        let error_message = String::from_utf8_lossy(error_body);
+        assert!(error_message.contains("C0A000"));
+    }
+}
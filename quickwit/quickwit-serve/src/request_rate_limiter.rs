@@ -0,0 +1,610 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use hyper::Method;
+use quickwit_common::rate_limiter::{RateLimiter, RateLimiterSettings};
+use quickwit_common::tower::ConstantRate;
+use quickwit_config::{ApiKeyScope, RequestRateLimits, RestRateLimitsConfig};
+use warp::path::FullPath;
+use warp::reject::Rejection;
+use warp::Filter;
+
+use crate::auth_api::{api_key_id_from_authorization, required_access, IndexTarget};
+
+/// The rate limiters idle for longer than this are evicted once the number of rate limiters
+/// exceeds `MAX_NUM_RATE_LIMITERS`.
+const RATE_LIMITER_IDLE_TTL: Duration = Duration::from_secs(60);
+
+const MAX_NUM_RATE_LIMITERS: usize = 10_000;
+
+/// Rejection returned when a request exceeds the rate limits of one of its indexes or of its API
+/// key.
+#[derive(Debug)]
+pub(crate) struct RateLimited {
+    pub retry_after: Duration,
+    pub message: String,
+}
+
+impl warp::reject::Reject for RateLimited {}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "too many requests: {}", self.message)
+    }
+}
+
+/// Rejection returned when the size of an ingest request cannot be charged to the ingest rate
+/// limits of its indexes or of its API key.
+#[derive(Debug)]
+pub(crate) enum UnchargeableRequest {
+    /// The request does not declare the size of its body, for instance because it is chunked.
+    MissingContentLength,
+    /// The request is larger than the bucket of one of its indexes or of its API key, so it would
+    /// never be admitted.
+    TooLarge { message: String },
+}
+
+impl warp::reject::Reject for UnchargeableRequest {}
+
+impl fmt::Display for UnchargeableRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingContentLength => write!(
+                f,
+                "ingest requests subject to a rate limit must have a `content-length` header"
+            ),
+            Self::TooLarge { message } => write!(f, "payload too large: {message}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+enum LimitKind {
+    IngestBytes,
+    SearchQueries,
+}
+
+impl LimitKind {
+    fn from_scope(scope: ApiKeyScope) -> Option<Self> {
+        match scope {
+            ApiKeyScope::Ingest => Some(Self::IngestBytes),
+            ApiKeyScope::Search => Some(Self::SearchQueries),
+            ApiKeyScope::Admin => None,
+        }
+    }
+
+    fn permits_per_sec(&self, limits: &RequestRateLimits) -> Option<u64> {
+        match self {
+            Self::IngestBytes => limits
+                .ingest_bytes_per_sec
+                .map(|ingest_bytes_per_sec| ingest_bytes_per_sec.as_u64()),
+            Self::SearchQueries => limits.search_qps.map(|search_qps| search_qps.get() as u64),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+enum Subject {
+    Index(String),
+    ApiKey(String),
+}
+
+impl fmt::Display for Subject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Index(index_id) => write!(f, "index `{index_id}`"),
+            Self::ApiKey(api_key_id) => write!(f, "API key `{api_key_id}`"),
+        }
+    }
+}
+
+struct RateLimiterEntry {
+    rate_limiter: RateLimiter,
+    last_used_at: Instant,
+}
+
+/// Enforces the ingest and search rate limits of the indexes and of the API keys with one token
+/// bucket per index or API key and per kind of limit. The buckets are created lazily.
+#[derive(Clone)]
+pub(crate) struct RequestRateLimiter {
+    config: Arc<RestRateLimitsConfig>,
+    rate_limiters: Arc<Mutex<HashMap<(Subject, LimitKind), RateLimiterEntry>>>,
+}
+
+impl RequestRateLimiter {
+    pub fn new(config: RestRateLimitsConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            rate_limiters: Arc::default(),
+        }
+    }
+
+    /// Acquires the permits required by a request from the buckets of its indexes and of its API
+    /// key. Ingest requests are charged the size of their body, which hyper guarantees to match
+    /// their `content-length` header. If one of the buckets does not hold enough permits, the
+    /// permits already acquired are given back and the request is rejected.
+    fn acquire(
+        &self,
+        method: &Method,
+        path: &str,
+        authorization_opt: Option<&str>,
+        content_length_opt: Option<u64>,
+    ) -> Result<(), Rejection> {
+        // The index management API and the UI are not rate limited.
+        let Some(api_v1_path) = path.strip_prefix("/api/v1/") else {
+            return Ok(());
+        };
+        if api_v1_path.split('/').next() == Some("indexes") {
+            return Ok(());
+        }
+        let Some(required_access) = required_access(method, path) else {
+            return Ok(());
+        };
+        let Some(limit_kind) = LimitKind::from_scope(required_access.scope) else {
+            return Ok(());
+        };
+        let mut subjects: Vec<Subject> = Vec::new();
+
+        if let IndexTarget::Indexes(index_id_patterns) = required_access.index_target {
+            for index_id_pattern in index_id_patterns {
+                // Negative patterns only narrow down the targeted indexes.
+                if index_id_pattern.starts_with('-') {
+                    continue;
+                }
+                // The limits are set on index IDs, so a pattern is charged to every index with
+                // dedicated limits it matches, and to a bucket of its own holding the default
+                // limits for the other indexes it may match.
+                if index_id_pattern.contains('*') {
+                    for index_id in self.config.limited_index_ids_matching(&index_id_pattern) {
+                        let subject = Subject::Index(index_id.to_string());
+
+                        if !subjects.contains(&subject) {
+                            subjects.push(subject);
+                        }
+                    }
+                }
+                let subject = Subject::Index(index_id_pattern);
+
+                if !subjects.contains(&subject) {
+                    subjects.push(subject);
+                }
+            }
+        }
+        if let Some(api_key_id) = authorization_opt.and_then(api_key_id_from_authorization) {
+            subjects.push(Subject::ApiKey(api_key_id));
+        }
+        let mut acquired: Vec<(Subject, u64)> = Vec::with_capacity(subjects.len());
+        let mut rate_limiters = self.rate_limiters.lock().unwrap();
+        let now = Instant::now();
+
+        for subject in subjects {
+            let limits = match &subject {
+                Subject::Index(index_id) => self.config.index_limits(index_id),
+                Subject::ApiKey(api_key_id) => self.config.api_key_limits(api_key_id),
+            };
+            let Some(permits_per_sec) = limit_kind.permits_per_sec(&limits) else {
+                continue;
+            };
+            let num_permits = match limit_kind {
+                LimitKind::IngestBytes => {
+                    let Some(content_length) = content_length_opt else {
+                        release_permits(&mut rate_limiters, acquired, limit_kind);
+                        return Err(warp::reject::custom(
+                            UnchargeableRequest::MissingContentLength,
+                        ));
+                    };
+                    if content_length > permits_per_sec {
+                        release_permits(&mut rate_limiters, acquired, limit_kind);
+                        let message = format!(
+                            "request of {content_length} bytes exceeds the ingest rate limit of \
+                             {subject} ({permits_per_sec} bytes per second)"
+                        );
+                        return Err(warp::reject::custom(UnchargeableRequest::TooLarge {
+                            message,
+                        }));
+                    }
+                    content_length
+                }
+                LimitKind::SearchQueries => 1,
+            };
+            let entry = rate_limiters
+                .entry((subject.clone(), limit_kind))
+                .or_insert_with(|| RateLimiterEntry {
+                    rate_limiter: RateLimiter::from_settings(rate_limiter_settings(
+                        permits_per_sec,
+                    )),
+                    last_used_at: now,
+                });
+            entry.last_used_at = now;
+
+            if let Err(retry_after) = entry.rate_limiter.acquire_with_duration(num_permits) {
+                release_permits(&mut rate_limiters, acquired, limit_kind);
+                let message = match limit_kind {
+                    LimitKind::IngestBytes => format!("ingest rate limit of {subject} exceeded"),
+                    LimitKind::SearchQueries => format!("search rate limit of {subject} exceeded"),
+                };
+                return Err(warp::reject::custom(RateLimited {
+                    retry_after,
+                    message,
+                }));
+            }
+            acquired.push((subject, num_permits));
+        }
+        if rate_limiters.len() > MAX_NUM_RATE_LIMITERS {
+            rate_limiters
+                .retain(|_, entry| now.duration_since(entry.last_used_at) < RATE_LIMITER_IDLE_TTL);
+        }
+        Ok(())
+    }
+}
+
+/// Gives back the permits acquired from the buckets of the subjects of a rejected request.
+fn release_permits(
+    rate_limiters: &mut HashMap<(Subject, LimitKind), RateLimiterEntry>,
+    acquired: Vec<(Subject, u64)>,
+    limit_kind: LimitKind,
+) {
+    for (subject, num_permits) in acquired {
+        if let Some(entry) = rate_limiters.get_mut(&(subject, limit_kind)) {
+            entry.rate_limiter.release(num_permits);
+        }
+    }
+}
+
+/// The bucket holds one second worth of permits. It is refilled every 100ms when the rate can be
+/// split evenly into tenths of seconds, and every second otherwise.
+fn rate_limiter_settings(permits_per_sec: u64) -> RateLimiterSettings {
+    let refill_period = if permits_per_sec >= 10 && permits_per_sec % 10 == 0 {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(1)
+    };
+    RateLimiterSettings {
+        burst_limit: permits_per_sec,
+        rate_limit: ConstantRate::new(permits_per_sec, Duration::from_secs(1)),
+        refill_period,
+    }
+}
+
+/// Rejects the requests exceeding the rate limits of their indexes or of their API key. When
+/// `rate_limiter_opt` is `None`, rate limiting is disabled and all the requests go through.
+pub(crate) fn request_rate_limit_filter(
+    rate_limiter_opt: Option<RequestRateLimiter>,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::header::optional::<u64>("content-length"))
+        .and_then(
+            move |method: Method,
+                  full_path: FullPath,
+                  authorization_opt: Option<String>,
+                  content_length_opt: Option<u64>| {
+                let rate_limiter_opt = rate_limiter_opt.clone();
+                async move {
+                    let Some(rate_limiter) = rate_limiter_opt else {
+                        return Ok(());
+                    };
+                    rate_limiter.acquire(
+                        &method,
+                        full_path.as_str(),
+                        authorization_opt.as_deref(),
+                        content_length_opt,
+                    )
+                }
+            },
+        )
+        .untuple_one()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use base64::prelude::{Engine, BASE64_STANDARD};
+    use bytesize::ByteSize;
+    use warp::http::StatusCode;
+    use warp::Reply;
+
+    use super::*;
+    use crate::rest::recover_fn_final;
+
+    fn authorization(api_key_id: &str) -> String {
+        let credentials = BASE64_STANDARD.encode(format!("{api_key_id}:my-secret"));
+        format!("ApiKey {credentials}")
+    }
+
+    #[test]
+    fn test_request_rate_limiter_search_qps() {
+        let mut config = RestRateLimitsConfig::default();
+        config.default_index.search_qps = NonZeroU32::new(2);
+        config.indexes.insert(
+            "noisy-index".to_string(),
+            RequestRateLimits {
+                ingest_bytes_per_sec: None,
+                search_qps: NonZeroU32::new(1),
+            },
+        );
+        let rate_limiter = RequestRateLimiter::new(config);
+
+        let search = |path: &str| rate_limiter.acquire(&Method::GET, path, None, None);
+
+        search("/api/v1/noisy-index/search").unwrap();
+        let rejection = search("/api/v1/noisy-index/search").unwrap_err();
+        let rate_limited = rejection.find::<RateLimited>().unwrap();
+        assert!(rate_limited.retry_after > Duration::ZERO);
+        assert!(rate_limited.retry_after <= Duration::from_secs(1));
+        assert_eq!(
+            rate_limited.message,
+            "search rate limit of index `noisy-index` exceeded"
+        );
+        search("/api/v1/my-index/search").unwrap();
+        search("/api/v1/my-index/search").unwrap();
+        search("/api/v1/my-index/search").unwrap_err();
+
+        // Ingest requests and index management requests are not limited by the search limits.
+        rate_limiter
+            .acquire(
+                &Method::POST,
+                "/api/v1/my-index/ingest",
+                None,
+                Some(1_000_000),
+            )
+            .unwrap();
+        search("/api/v1/indexes/my-index").unwrap();
+        search("/api/v1/indexes").unwrap();
+
+        // Indexes whose ID starts with `indexes` are limited.
+        search("/api/v1/indexes-archive/search").unwrap();
+        search("/api/v1/indexes-archive/search").unwrap();
+        search("/api/v1/indexes-archive/search").unwrap_err();
+
+        // Requests targeting indexes with a pattern are limited by the default limits.
+        search("/api/v1/my-*/search").unwrap();
+        search("/api/v1/my-*/search").unwrap();
+        search("/api/v1/my-*/search").unwrap_err();
+    }
+
+    #[test]
+    fn test_request_rate_limiter_index_patterns() {
+        let mut config = RestRateLimitsConfig::default();
+        config.default_index.search_qps = NonZeroU32::new(10);
+        config.indexes.insert(
+            "logs".to_string(),
+            RequestRateLimits {
+                ingest_bytes_per_sec: None,
+                search_qps: NonZeroU32::new(1),
+            },
+        );
+        let rate_limiter = RequestRateLimiter::new(config);
+
+        let search = |path: &str| rate_limiter.acquire(&Method::GET, path, None, None);
+
+        // Patterns are charged to the indexes with dedicated limits they match.
+        search("/api/v1/logs*/search").unwrap();
+        let rejection = search("/api/v1/logs/search").unwrap_err();
+        assert_eq!(
+            rejection.find::<RateLimited>().unwrap().message,
+            "search rate limit of index `logs` exceeded"
+        );
+        let rejection = search("/api/v1/l*,-traces/search").unwrap_err();
+        assert_eq!(
+            rejection.find::<RateLimited>().unwrap().message,
+            "search rate limit of index `logs` exceeded"
+        );
+        let rejection = search("/api/v1/_elastic/*/_search").unwrap_err();
+        assert_eq!(
+            rejection.find::<RateLimited>().unwrap().message,
+            "search rate limit of index `logs` exceeded"
+        );
+        search("/api/v1/traces*/search").unwrap();
+
+        // Encoded commas cannot hide an index from its limits.
+        let rejection = search("/api/v1/other%2Clogs/search").unwrap_err();
+        assert_eq!(
+            rejection.find::<RateLimited>().unwrap().message,
+            "search rate limit of index `logs` exceeded"
+        );
+        let rejection = search("/api/v1/_elastic/other%2clogs/_search").unwrap_err();
+        assert_eq!(
+            rejection.find::<RateLimited>().unwrap().message,
+            "search rate limit of index `logs` exceeded"
+        );
+    }
+
+    #[test]
+    fn test_request_rate_limiter_ingest_bytes() {
+        let mut config = RestRateLimitsConfig::default();
+        config.default_api_key.ingest_bytes_per_sec = Some(ByteSize::b(1_001));
+        config.api_keys.insert(
+            "my-bulk-key".to_string(),
+            RequestRateLimits {
+                ingest_bytes_per_sec: Some(ByteSize::b(10_001)),
+                search_qps: None,
+            },
+        );
+        let rate_limiter = RequestRateLimiter::new(config);
+
+        let my_key = authorization("my-key");
+        let ingest = |path: &str, api_key: &str, num_bytes: u64| {
+            rate_limiter.acquire(&Method::POST, path, Some(api_key), Some(num_bytes))
+        };
+        ingest("/api/v1/my-index/ingest", &my_key, 600).unwrap();
+        ingest("/api/v1/_elastic/_bulk", &my_key, 600).unwrap_err();
+        ingest("/api/v1/my-index/ingest", &my_key, 401).unwrap();
+        ingest("/api/v1/my-index/ingest", &my_key, 1).unwrap_err();
+
+        // Requests larger than the bucket are rejected without consuming any permits.
+        let my_bulk_key = authorization("my-bulk-key");
+        let rejection = ingest("/api/v1/_elastic/_bulk", &my_bulk_key, 1_000_000).unwrap_err();
+        let UnchargeableRequest::TooLarge { message } =
+            rejection.find::<UnchargeableRequest>().unwrap()
+        else {
+            panic!("expected a `TooLarge` rejection");
+        };
+        assert_eq!(
+            message,
+            "request of 1000000 bytes exceeds the ingest rate limit of API key `my-bulk-key` \
+             (10001 bytes per second)"
+        );
+        ingest("/api/v1/_elastic/_bulk", &my_bulk_key, 10_001).unwrap();
+        ingest("/api/v1/_elastic/_bulk", &my_bulk_key, 1).unwrap_err();
+
+        // Requests without content length, such as chunked requests, cannot be charged.
+        let my_other_key = authorization("my-other-key");
+        let rejection = rate_limiter
+            .acquire(
+                &Method::POST,
+                "/api/v1/my-index/ingest",
+                Some(&my_other_key),
+                None,
+            )
+            .unwrap_err();
+        assert!(matches!(
+            rejection.find::<UnchargeableRequest>().unwrap(),
+            UnchargeableRequest::MissingContentLength
+        ));
+
+        // Requests without API key are not limited by the API key limits.
+        rate_limiter
+            .acquire(
+                &Method::POST,
+                "/api/v1/my-index/ingest",
+                None,
+                Some(1_000_000),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_request_rate_limiter_releases_permits() {
+        let mut config = RestRateLimitsConfig::default();
+        config.default_index.search_qps = NonZeroU32::new(1);
+        config.default_api_key.search_qps = NonZeroU32::new(1);
+        let rate_limiter = RequestRateLimiter::new(config);
+
+        let search = |path: &str, api_key_id: &str| {
+            let authorization = authorization(api_key_id);
+            rate_limiter.acquire(&Method::GET, path, Some(&authorization), None)
+        };
+        search("/api/v1/index-1/search", "key-1").unwrap();
+
+        let rejection = search("/api/v1/index-1/search", "key-2").unwrap_err();
+        let rate_limited = rejection.find::<RateLimited>().unwrap();
+        assert_eq!(
+            rate_limited.message,
+            "search rate limit of index `index-1` exceeded"
+        );
+        let rejection = search("/api/v1/index-2/search", "key-1").unwrap_err();
+        let rate_limited = rejection.find::<RateLimited>().unwrap();
+        assert_eq!(
+            rate_limited.message,
+            "search rate limit of API key `key-1` exceeded"
+        );
+        // The permit of `index-2` was given back when the bucket of `key-1` was found empty.
+        search("/api/v1/index-2/search", "key-2").unwrap();
+    }
+
+    #[test]
+    fn test_rate_limiter_settings() {
+        let settings = rate_limiter_settings(1);
+        assert_eq!(settings.burst_limit, 1);
+        assert_eq!(settings.refill_period, Duration::from_secs(1));
+
+        let settings = rate_limiter_settings(15);
+        assert_eq!(settings.burst_limit, 15);
+        assert_eq!(settings.refill_period, Duration::from_secs(1));
+
+        let settings = rate_limiter_settings(1_000);
+        assert_eq!(settings.burst_limit, 1_000);
+        assert_eq!(settings.refill_period, Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_request_rate_limit_filter() {
+        let mut config = RestRateLimitsConfig::default();
+        config.default_index.search_qps = NonZeroU32::new(1);
+        let rate_limiter = RequestRateLimiter::new(config);
+
+        let handler = request_rate_limit_filter(Some(rate_limiter))
+            .map(|| warp::reply().into_response())
+            .recover(recover_fn_final);
+
+        let resp = warp::test::request()
+            .path("/api/v1/my-index/search?query=*")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = warp::test::request()
+            .path("/api/v1/my-index/search?query=*")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resp.headers().get("retry-after").unwrap(), "1");
+
+        let mut config = RestRateLimitsConfig::default();
+        config.default_index.ingest_bytes_per_sec = Some(ByteSize::b(10));
+        let rate_limiter = RequestRateLimiter::new(config);
+
+        let handler = request_rate_limit_filter(Some(rate_limiter))
+            .map(|| warp::reply().into_response())
+            .recover(recover_fn_final);
+
+        let resp = warp::test::request()
+            .path("/api/v1/my-index/ingest")
+            .method("POST")
+            .body("01234567890")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let resp = warp::test::request()
+            .path("/api/v1/my-index/ingest")
+            .method("POST")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::LENGTH_REQUIRED);
+
+        let resp = warp::test::request()
+            .path("/api/v1/my-index/ingest")
+            .method("POST")
+            .body("0123456789")
+            .reply(&handler)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let handler = request_rate_limit_filter(None)
+            .map(|| warp::reply().into_response())
+            .recover(recover_fn_final);
+
+        for _ in 0..3 {
+            let resp = warp::test::request()
+                .path("/api/v1/my-index/search?query=*")
+                .reply(&handler)
+                .await;
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+    }
+}
@@ -20,11 +20,16 @@
 use std::fmt::Formatter;
 use std::sync::Arc;
 
+use anyhow::Context;
 use hyper::http::HeaderValue;
 use hyper::{http, Method, StatusCode};
+use quickwit_common::tls::{make_server_config, ReloadingCertResolver};
 use quickwit_common::tower::BoxFutureInfaillible;
+use quickwit_config::RestConfig;
+use quickwit_janitor::LifecyclePolicyStore;
 use quickwit_search::SearchService;
 use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
 use tower::make::Shared;
 use tower::ServiceBuilder;
 use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
@@ -34,25 +39,35 @@ use tracing::{error, info};
 use warp::filters::log::Info;
 use warp::{redirect, Filter, Rejection, Reply};
 
+use crate::audit::{AuditLayer, AuditLogger};
+use crate::auth_api::{
+    api_key_api_handlers, api_key_auth_filter, ApiKeyAuthenticator, Forbidden, Unauthorized,
+};
 use crate::cluster_api::cluster_handler;
 use crate::decompression::{CorruptedData, UnsupportedEncoding};
 use crate::delete_task_api::delete_task_api_handlers;
 use crate::developer_api::developer_api_routes;
-use crate::elasticsearch_api::elastic_api_handlers;
+use crate::elasticsearch_api::{elastic_api_handlers, elastic_cluster_api_handlers};
 use crate::health_check_api::health_check_handlers;
 use crate::index_api::index_management_handlers;
 use crate::indexing_api::indexing_get_handler;
 use crate::ingest_api::ingest_api_handlers;
 use crate::jaeger_api::jaeger_api_handlers;
+use crate::lifecycle_api::lifecycle_policy_api_handlers;
 use crate::metrics_api::metrics_handler;
 use crate::node_info_handler::node_info_handler;
 use crate::otlp_api::otlp_ingest_api_handlers;
+use crate::request_rate_limiter::{
+    request_rate_limit_filter, RateLimited, RequestRateLimiter, UnchargeableRequest,
+};
 use crate::rest_api_response::{RestApiError, RestApiResponse};
 use crate::search_api::{
     search_get_handler, search_plan_get_handler, search_plan_post_handler, search_post_handler,
     search_stream_handler,
 };
+use crate::sql_api::sql_api_handlers;
 use crate::template_api::index_template_api_handlers;
+use crate::tls::tls_incoming;
 use crate::ui_handler::ui_handler;
 use crate::{BodyFormat, BuildInfo, QuickwitServices, RuntimeInfo};
 
@@ -89,7 +104,7 @@ impl std::fmt::Display for InternalError {
 }
 
 /// Env variable key to define the minimum size above which a response should be compressed.
-/// If unset, no compression is applied.
+/// If set, it overrides the `rest.compression_min_size` setting of the node config.
 const QW_MINIMUM_COMPRESSION_SIZE_KEY: &str = "QW_MINIMUM_COMPRESSION_SIZE";
 
 #[derive(Clone, Copy)]
@@ -98,13 +113,17 @@ struct CompressionPredicate {
 }
 
 impl CompressionPredicate {
-    fn from_env() -> CompressionPredicate {
-        let minimum_compression_size_opt: Option<u16> = quickwit_common::get_from_env_opt::<usize>(
-            QW_MINIMUM_COMPRESSION_SIZE_KEY,
-        )
-        .map(|minimum_compression_size: usize| {
-            u16::try_from(minimum_compression_size).unwrap_or(u16::MAX)
-        });
+    fn from_rest_config(rest_config: &RestConfig) -> CompressionPredicate {
+        let minimum_compression_size_opt: Option<u16> =
+            quickwit_common::get_from_env_opt::<usize>(QW_MINIMUM_COMPRESSION_SIZE_KEY)
+                .or_else(|| {
+                    rest_config
+                        .compression_min_size
+                        .map(|compression_min_size| compression_min_size.as_u64() as usize)
+                })
+                .map(|minimum_compression_size: usize| {
+                    u16::try_from(minimum_compression_size).unwrap_or(u16::MAX)
+                });
         let size_above_opt = minimum_compression_size_opt.map(SizeAbove::new);
         CompressionPredicate { size_above_opt }
     }
@@ -121,6 +140,21 @@ impl Predicate for CompressionPredicate {
     }
 }
 
+/// Counts the responses compressed by the compression layer, per encoding.
+fn record_response_compression<B>(response: http::Response<B>) -> http::Response<B> {
+    if let Some(content_encoding) = response
+        .headers()
+        .get(http::header::CONTENT_ENCODING)
+        .and_then(|content_encoding| content_encoding.to_str().ok())
+    {
+        crate::SERVE_METRICS
+            .http_compressed_responses_total
+            .with_label_values([content_encoding])
+            .inc();
+    }
+    response
+}
+
 /// Starts REST services.
 pub(crate) async fn start_rest_server(
     tcp_listener: TcpListener,
@@ -165,8 +199,28 @@ pub(crate) async fn start_rest_server(
         quickwit_services.cluster.clone(),
         quickwit_services.env_filter_reload_fn.clone(),
     );
+    let api_key_authenticator = ApiKeyAuthenticator::new(
+        quickwit_services.metastore_client.clone(),
+        &quickwit_services.node_config.rest_config.auth,
+    );
+    // Checks the API key of the requests when authentication is enabled.
+    let api_key_auth = api_key_auth_filter(
+        quickwit_services
+            .node_config
+            .rest_config
+            .auth
+            .enabled
+            .then(|| api_key_authenticator.clone()),
+    );
+    // Enforces the per-index and per-API key rate limits, once the API key has been checked.
+    let rate_limits_config = &quickwit_services.node_config.rest_config.rate_limits;
+    let request_rate_limit = request_rate_limit_filter(
+        rate_limits_config
+            .is_enabled()
+            .then(|| RequestRateLimiter::new(rate_limits_config.clone())),
+    );
     // `/api/v1/*` routes.
-    let api_v1_root_route = api_v1_routes(quickwit_services.clone());
+    let api_v1_root_route = api_v1_routes(quickwit_services.clone(), api_key_authenticator);
 
     let redirect_root_to_ui_route = warp::path::end()
         .and(warp::get())
@@ -182,23 +236,43 @@ pub(crate) async fn start_rest_server(
     );
 
     // Combine all the routes together.
-    let rest_routes = api_v1_root_route
-        .or(api_doc)
-        .or(redirect_root_to_ui_route)
-        .or(ui_handler())
-        .or(health_check_routes)
-        .or(metrics_routes)
-        .or(developer_routes)
+    let rest_routes = api_key_auth
+        .and(request_rate_limit)
+        .and(
+            api_v1_root_route
+                .or(api_doc)
+                .or(redirect_root_to_ui_route)
+                .or(ui_handler())
+                .or(health_check_routes)
+                .or(metrics_routes)
+                .or(developer_routes),
+        )
         .with(request_counter)
         .recover(recover_fn_final)
         .with(extra_headers)
         .boxed();
 
     let warp_service = warp::service(rest_routes);
-    let compression_predicate = CompressionPredicate::from_env().and(NotForContentType::IMAGES);
+    let compression_predicate =
+        CompressionPredicate::from_rest_config(&quickwit_services.node_config.rest_config)
+            .and(NotForContentType::IMAGES);
     let cors = build_cors(&quickwit_services.node_config.rest_config.cors_allow_origins);
 
+    let audit_config = &quickwit_services.node_config.rest_config.audit;
+    let audit_layer_opt = if audit_config.enabled {
+        let audit_logger = AuditLogger::spawn(
+            audit_config,
+            quickwit_services.node_config.node_id.clone(),
+            quickwit_services.ingest_router_service.clone(),
+        )
+        .await
+        .context("failed to start audit logger")?;
+        Some(AuditLayer::new(audit_logger))
+    } else {
+        None
+    };
     let service = ServiceBuilder::new()
+        .map_response(record_response_compression)
         .layer(
             CompressionLayer::new()
                 .zstd(true)
@@ -207,15 +281,26 @@ pub(crate) async fn start_rest_server(
                 .compress_when(compression_predicate),
         )
         .layer(cors)
+        .option_layer(audit_layer_opt)
         .service(warp_service);
 
     let rest_listen_addr = tcp_listener.local_addr()?;
+    let tls_acceptor_opt = if let Some(tls_config) = &quickwit_services.node_config.rest_config.tls
+    {
+        let cert_resolver =
+            ReloadingCertResolver::load(&tls_config.cert_path, &tls_config.key_path)?;
+        cert_resolver.spawn_reload_task();
+        let server_config = make_server_config(cert_resolver, None, &[b"h2", b"http/1.1"]);
+        Some(TlsAcceptor::from(server_config))
+    } else {
+        None
+    };
     info!(
         rest_listen_addr=?rest_listen_addr,
+        tls_enabled=tls_acceptor_opt.is_some(),
         "Starting REST server listening on {rest_listen_addr}."
     );
 
-    let rest_listener_std = tcp_listener.into_std()?;
     // `graceful_shutdown()` seems to be blocking in presence of existing connections.
     // The following approach of dropping the serve supposedly is not bullet proof, but it seems to
     // work in our unit test.
@@ -224,9 +309,20 @@ pub(crate) async fn start_rest_server(
     // https://github.com/hyperium/hyper/issues/2386
 
     let serve_fut = async move {
-        tokio::select! {
-             res = hyper::Server::from_tcp(rest_listener_std)?.serve(Shared::new(service)) => { res }
-             _ = shutdown_signal => { Ok(()) }
+        if let Some(tls_acceptor) = tls_acceptor_opt {
+            let incoming =
+                hyper::server::accept::from_stream(tls_incoming(tcp_listener, tls_acceptor, false));
+            tokio::select! {
+                 res = hyper::Server::builder(incoming).serve(Shared::new(service)) => { res }
+                 _ = shutdown_signal => { Ok(()) }
+            }
+        } else {
+            let rest_listener_std = tcp_listener.into_std()?;
+            let server = hyper::Server::from_tcp(rest_listener_std)?;
+            tokio::select! {
+                 res = server.serve(Shared::new(service)) => { res }
+                 _ = shutdown_signal => { Ok(()) }
+            }
         }
     };
     let (serve_res, _trigger_res) = tokio::join!(serve_fut, readiness_trigger);
@@ -247,6 +343,7 @@ fn search_routes(
 
 fn api_v1_routes(
     quickwit_services: Arc<QuickwitServices>,
+    api_key_authenticator: ApiKeyAuthenticator,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     let api_v1_root_url = warp::path!("api" / "v1" / ..);
     api_v1_root_url.and(
@@ -258,6 +355,9 @@ fn api_v1_routes(
             quickwit_services.metastore_client.clone(),
             quickwit_services.index_manager.clone(),
         )
+        .or(elastic_cluster_api_handlers(
+            quickwit_services.cluster.clone(),
+        ))
         .or(cluster_handler(quickwit_services.cluster.clone()))
         .or(node_info_handler(
             BuildInfo::get(),
@@ -268,6 +368,7 @@ fn api_v1_routes(
             quickwit_services.indexing_service_opt.clone(),
         ))
         .or(search_routes(quickwit_services.search_service.clone()))
+        .or(sql_api_handlers(quickwit_services.search_service.clone()))
         .or(ingest_api_handlers(
             quickwit_services.ingest_router_service.clone(),
             quickwit_services.ingest_service.clone(),
@@ -289,6 +390,14 @@ fn api_v1_routes(
         ))
         .or(index_template_api_handlers(
             quickwit_services.metastore_client.clone(),
+        ))
+        .or(lifecycle_policy_api_handlers(
+            quickwit_services.metastore_client.clone(),
+            LifecyclePolicyStore::new(quickwit_services.metastore_client.clone()),
+        ))
+        .or(api_key_api_handlers(
+            quickwit_services.metastore_client.clone(),
+            api_key_authenticator,
         )),
     )
 }
@@ -315,6 +424,9 @@ pub async fn recover_fn(rejection: Rejection) -> Result<impl Reply, Rejection> {
 }
 
 pub async fn recover_fn_final(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    let retry_after_opt = rejection
+        .find::<RateLimited>()
+        .map(|rate_limited| rate_limited.retry_after);
     let error = get_status_with_error(rejection).unwrap_or_else(|rejection: Rejection| {
         if rejection.is_not_found() {
             RestApiError {
@@ -330,11 +442,25 @@ pub async fn recover_fn_final(rejection: Rejection) -> Result<impl Reply, Reject
         }
     });
     let status_code = error.status_code;
-    Ok(RestApiResponse::new::<(), _>(
-        &Err(error),
-        status_code,
-        BodyFormat::default(),
-    ))
+    let mut response =
+        RestApiResponse::new::<(), _>(&Err(error), status_code, BodyFormat::default())
+            .into_response();
+    // Lets browsers prompt for the API key ID and secret.
+    if status_code == StatusCode::UNAUTHORIZED {
+        response.headers_mut().insert(
+            http::header::WWW_AUTHENTICATE,
+            HeaderValue::from_static("Basic realm=\"quickwit\""),
+        );
+    }
+    if let Some(retry_after) = retry_after_opt {
+        // `Retry-After` is expressed in whole seconds.
+        let retry_after_secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+        response.headers_mut().insert(
+            http::header::RETRY_AFTER,
+            HeaderValue::from(retry_after_secs),
+        );
+    }
+    Ok(response)
 }
 
 fn get_status_with_error(rejection: Rejection) -> Result<RestApiError, Rejection> {
@@ -405,6 +531,30 @@ fn get_status_with_error(rejection: Rejection) -> Result<RestApiError, Rejection
             status_code: StatusCode::TOO_MANY_REQUESTS,
             message: err.to_string(),
         })
+    } else if let Some(error) = rejection.find::<RateLimited>() {
+        Ok(RestApiError {
+            status_code: StatusCode::TOO_MANY_REQUESTS,
+            message: error.to_string(),
+        })
+    } else if let Some(error) = rejection.find::<UnchargeableRequest>() {
+        let status_code = match error {
+            UnchargeableRequest::MissingContentLength => StatusCode::LENGTH_REQUIRED,
+            UnchargeableRequest::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+        };
+        Ok(RestApiError {
+            status_code,
+            message: error.to_string(),
+        })
+    } else if let Some(error) = rejection.find::<Unauthorized>() {
+        Ok(RestApiError {
+            status_code: StatusCode::UNAUTHORIZED,
+            message: error.to_string(),
+        })
+    } else if let Some(error) = rejection.find::<Forbidden>() {
+        Ok(RestApiError {
+            status_code: StatusCode::FORBIDDEN,
+            message: error.to_string(),
+        })
     } else if let Some(error) = rejection.find::<InvalidArgument>() {
         // Happens when the url path or request body contains invalid argument(s).
         Ok(RestApiError {
@@ -702,6 +852,8 @@ mod tests {
         let metastore_client = MetastoreServiceClient::mocked();
         let index_service =
             IndexService::new(metastore_client.clone(), StorageResolver::unconfigured());
+        let api_key_authenticator =
+            ApiKeyAuthenticator::new(metastore_client.clone(), &node_config.rest_config.auth);
         let control_plane_client = ControlPlaneServiceClient::mocked();
         let transport = ChannelTransport::default();
         let cluster = create_cluster_for_test(Vec::new(), &[], &transport, false)
@@ -730,7 +882,7 @@ mod tests {
             env_filter_reload_fn: crate::do_nothing_env_filter_reload_fn(),
         };
 
-        let handler = api_v1_routes(Arc::new(quickwit_services))
+        let handler = api_v1_routes(Arc::new(quickwit_services), api_key_authenticator)
             .recover(recover_fn_final)
             .with(warp::reply::with::headers(
                 node_config.rest_config.extra_headers.clone(),
@@ -766,4 +918,22 @@ mod tests {
             "custom-value-2"
         );
     }
+
+    #[tokio::test]
+    async fn test_recover_fn_final_unauthorized() {
+        let handler = warp::any()
+            .and_then(|| async {
+                Err::<String, _>(warp::reject::custom(Unauthorized(
+                    "missing API key".to_string(),
+                )))
+            })
+            .recover(recover_fn_final);
+
+        let resp = warp::test::request().path("/").reply(&handler).await;
+        assert_eq!(resp.status(), 401);
+        assert_eq!(
+            resp.headers().get("www-authenticate").unwrap(),
+            "Basic realm=\"quickwit\""
+        );
+    }
 }
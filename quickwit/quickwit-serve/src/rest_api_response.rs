@@ -55,9 +55,10 @@ pub(crate) fn into_rest_api_response<T: serde::Serialize, E: ServiceError>(
     RestApiResponse::new(&rest_api_result, status_code, body_format)
 }
 
-/// A JSON reply for the REST API.
+/// A reply for the REST API, serialized in the requested body format.
 pub struct RestApiResponse {
     status_code: StatusCode,
+    content_type: &'static str,
     inner: Result<Vec<u8>, ()>,
 }
 
@@ -68,7 +69,11 @@ impl RestApiResponse {
         body_format: BodyFormat,
     ) -> Self {
         let inner = body_format.result_to_vec(result);
-        RestApiResponse { status_code, inner }
+        RestApiResponse {
+            status_code,
+            content_type: body_format.content_type(),
+            inner,
+        }
     }
 }
 
@@ -80,7 +85,7 @@ impl Reply for RestApiResponse {
                 let mut response = Response::new(body.into());
                 response
                     .headers_mut()
-                    .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+                    .insert(CONTENT_TYPE, HeaderValue::from_static(self.content_type));
                 *response.status_mut() = self.status_code;
                 response
             }
@@ -21,7 +21,9 @@ mod grpc_adapter;
 mod rest_handler;
 
 pub use self::grpc_adapter::GrpcSearchAdapter;
-pub(crate) use self::rest_handler::{extract_index_id_patterns, extract_index_id_patterns_default};
+pub(crate) use self::rest_handler::{
+    api_key_id_filter, extract_index_id_patterns, extract_index_id_patterns_default,
+};
 pub use self::rest_handler::{
     search_get_handler, search_plan_get_handler, search_plan_post_handler, search_post_handler,
     search_request_from_api_request, search_stream_handler, SearchApi, SearchRequestQueryString,
@@ -24,12 +24,19 @@ use futures::stream::StreamExt;
 use hyper::header::HeaderValue;
 use hyper::HeaderMap;
 use percent_encoding::percent_decode_str;
+use quickwit_common::is_false;
 use quickwit_config::validate_index_id_pattern;
-use quickwit_proto::search::{CountHits, OutputFormat, SortField, SortOrder};
+use quickwit_proto::search::{
+    Collapse, CountHits, OutputFormat, SearchPriority, SnippetOptions, SortField, SortMissing,
+    SortOrder,
+};
 use quickwit_proto::types::IndexId;
 use quickwit_proto::ServiceError;
-use quickwit_query::query_ast::query_ast_from_user_text;
-use quickwit_search::{SearchError, SearchPlanResponseRest, SearchResponseRest, SearchService};
+use quickwit_query::query_ast::{query_ast_from_user_text, QueryAst};
+use quickwit_search::{
+    decode_search_after_cursor, SearchError, SearchPlanResponseRest, SearchResponseRest,
+    SearchService,
+};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value as JsonValue;
 use tracing::info;
@@ -37,8 +44,11 @@ use warp::hyper::header::CONTENT_TYPE;
 use warp::hyper::StatusCode;
 use warp::{reply, Filter, Rejection, Reply};
 
+use crate::auth_api::api_key_id_from_authorization;
+use crate::format::extract_format_from_accept_header;
 use crate::rest_api_response::into_rest_api_response;
 use crate::simple_list::{from_simple_list, to_simple_list};
+use crate::sql_api::SqlResponse;
 use crate::{with_arg, BodyFormat};
 
 #[derive(utoipa::OpenApi)]
@@ -125,6 +135,7 @@ impl From<String> for SortBy {
                 field_name,
                 sort_order: sort_order as i32,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             };
             sort_fields.push(sort_field);
         }
@@ -206,6 +217,18 @@ pub struct SearchRequestQueryString {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(serialize_with = "to_simple_list")]
     pub snippet_fields: Option<Vec<String>>,
+    /// Tag inserted before the highlighted terms of the snippets (by default `<b>`).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet_pre_tag: Option<String>,
+    /// Tag inserted after the highlighted terms of the snippets (by default `</b>`).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet_post_tag: Option<String>,
+    /// Maximum number of characters of the snippets (by default 150).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet_fragment_size: Option<u32>,
     /// If set, restrict search to documents with a `timestamp >= start_timestamp`.
     /// This timestamp is expressed in seconds.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -227,6 +250,13 @@ pub struct SearchRequestQueryString {
     /// The output format.
     #[serde(default)]
     pub format: BodyFormat,
+    /// Fields written as the columns of the `csv` output format. By default, the columns are the
+    /// top-level fields of the hits.
+    #[serde(default)]
+    #[serde(deserialize_with = "from_simple_list")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "to_simple_list")]
+    pub csv_fields: Option<Vec<String>>,
     /// Specifies how documents are sorted.
     #[serde(alias = "sort_by_field")]
     #[serde(deserialize_with = "sort_by_mini_dsl")]
@@ -234,11 +264,49 @@ pub struct SearchRequestQueryString {
     #[serde(skip_serializing_if = "SortBy::is_empty")]
     #[param(value_type = String)]
     pub sort_by: SortBy,
+    /// Cursor returned as `search_after` by a previous search with the same query and sort
+    /// order. If set, only the hits following this cursor are returned.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_after: Option<String>,
+    /// If set, only the top hit of each distinct value of this fast field is returned.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collapse_field: Option<String>,
+    /// Number of best hits returned as inner hits of each top hit when collapsing, including
+    /// the top hit itself.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collapse_inner_hits: Option<u32>,
+    /// If set, the search returns the hits and aggregations collected within this duration
+    /// (e.g. `500ms`, `10s`) and the response is marked as `timed_out`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+    /// Priority of the search: `interactive` (default) or `batch`. Batch searches are admitted
+    /// in a separate, smaller pool so they do not delay interactive searches.
+    #[param(value_type = String)]
+    #[schema(value_type = String)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<SearchPriority>,
     #[param(value_type = bool)]
     #[schema(value_type = bool)]
     #[serde(with = "count_hits_from_bool")]
     #[serde(default = "count_hits_from_bool::default")]
     pub count_all: CountHits,
+    /// Allows wildcard queries starting with a wildcard (e.g. `*bond`).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub allow_leading_wildcard: bool,
+    /// If set, the explanation of the score of each hit is returned.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub explain: bool,
+    /// If set, the time spent and the bytes downloaded searching each split are returned.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub profile: bool,
 }
 
 mod count_hits_from_bool {
@@ -276,8 +344,46 @@ pub fn search_request_from_api_request(
     // The query ast below may still contain user input query. The actual
     // parsing of the user query will happen in the root service, and might require
     // the user of the docmapper default fields (which we do not have at this point).
-    let query_ast = query_ast_from_user_text(&search_request.query, search_request.search_fields);
+    let mut query_ast =
+        query_ast_from_user_text(&search_request.query, search_request.search_fields);
+    if let QueryAst::UserInput(user_input_query) = &mut query_ast {
+        user_input_query.allow_leading_wildcard = search_request.allow_leading_wildcard;
+    }
     let query_ast_json = serde_json::to_string(&query_ast)?;
+    let search_after = search_request
+        .search_after
+        .as_deref()
+        .map(decode_search_after_cursor)
+        .transpose()?;
+    let snippet_options = if search_request.snippet_pre_tag.is_some()
+        || search_request.snippet_post_tag.is_some()
+        || search_request.snippet_fragment_size.is_some()
+    {
+        Some(SnippetOptions {
+            pre_tag: search_request.snippet_pre_tag,
+            post_tag: search_request.snippet_post_tag,
+            fragment_size: search_request.snippet_fragment_size,
+        })
+    } else {
+        None
+    };
+    let collapse = search_request
+        .collapse_field
+        .map(|collapse_field| Collapse {
+            field: collapse_field,
+            inner_hits: search_request.collapse_inner_hits.unwrap_or_default(),
+            inner_hits_name: None,
+        });
+    let timeout_millis = search_request
+        .timeout
+        .as_deref()
+        .map(|timeout| {
+            humantime::parse_duration(timeout).map_err(|_| {
+                SearchError::InvalidArgument(format!("invalid timeout duration: `{timeout}`"))
+            })
+        })
+        .transpose()?
+        .map(|timeout_duration| timeout_duration.as_millis().min(u32::MAX as u128) as u32);
     let search_request = quickwit_proto::search::SearchRequest {
         index_id_patterns,
         query_ast: query_ast_json,
@@ -291,23 +397,75 @@ pub fn search_request_from_api_request(
             .map(|agg| serde_json::to_string(&agg).expect("could not serialize JsonValue")),
         sort_fields: search_request.sort_by.sort_fields,
         scroll_ttl_secs: None,
-        search_after: None,
+        search_after,
         count_hits: search_request.count_all.into(),
+        named_queries: Vec::new(),
+        snippet_options,
+        point_in_time: None,
+        collapse,
+        timeout_millis,
+        priority: search_request
+            .priority
+            .unwrap_or(SearchPriority::Interactive) as i32,
+        api_key_id: None,
+        suggesters: Vec::new(),
+        explain: search_request.explain,
+        profile: search_request.profile,
+        docvalue_fields: Vec::new(),
+        skip_source: false,
     };
     Ok(search_request)
 }
 
+/// Extracts the ID of the API key sent in the `Authorization` header, with the same parsing as
+/// the API key authentication. It is used to apply per-API-key search concurrency limits.
+pub(crate) fn api_key_id_filter(
+) -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization").map(|authorization_opt: Option<String>| {
+        authorization_opt
+            .as_deref()
+            .and_then(api_key_id_from_authorization)
+    })
+}
+
 async fn search_endpoint(
     index_id_patterns: Vec<String>,
     search_request: SearchRequestQueryString,
+    api_key_id_opt: Option<String>,
     search_service: &dyn SearchService,
 ) -> Result<SearchResponseRest, SearchError> {
-    let search_request = search_request_from_api_request(index_id_patterns, search_request)?;
+    let mut search_request = search_request_from_api_request(index_id_patterns, search_request)?;
+    search_request.api_key_id = api_key_id_opt;
     let search_response = search_service.root_search(search_request).await?;
     let search_response_rest = SearchResponseRest::try_from(search_response)?;
     Ok(search_response_rest)
 }
 
+/// Runs the search and writes the documents of the hits, one per line. The documents are not
+/// parsed and re-serialized, but written to the body as is. Like for the other formats, the root
+/// collects all the hits before the body is written.
+async fn search_ndjson_endpoint(
+    index_id_patterns: Vec<String>,
+    search_request: SearchRequestQueryString,
+    api_key_id_opt: Option<String>,
+    search_service: &dyn SearchService,
+) -> Result<Vec<u8>, SearchError> {
+    let mut search_request = search_request_from_api_request(index_id_patterns, search_request)?;
+    search_request.api_key_id = api_key_id_opt;
+    let search_response = search_service.root_search(search_request).await?;
+    let body_len = search_response
+        .hits
+        .iter()
+        .map(|hit| hit.json.len() + 1)
+        .sum();
+    let mut body = Vec::with_capacity(body_len);
+    for hit in search_response.hits {
+        body.extend_from_slice(hit.json.as_bytes());
+        body.push(b'\n');
+    }
+    Ok(body)
+}
+
 fn search_get_filter(
 ) -> impl Filter<Extract = (Vec<String>, SearchRequestQueryString), Error = Rejection> + Clone {
     warp::path!(String / "search")
@@ -342,23 +500,78 @@ fn search_plan_post_filter(
         .and(warp::body::json())
 }
 
+/// Returns the output format of a search request. The formats requested with the `Accept` header
+/// are only taken into account if the `format` parameter is not set.
+fn search_body_format(
+    search_request: &SearchRequestQueryString,
+    accept_format_opt: Option<BodyFormat>,
+) -> BodyFormat {
+    if search_request.format == BodyFormat::default() {
+        accept_format_opt.unwrap_or_default()
+    } else {
+        search_request.format
+    }
+}
+
 async fn search(
     index_id_patterns: Vec<String>,
-    search_request: SearchRequestQueryString,
+    mut search_request: SearchRequestQueryString,
+    accept_format_opt: Option<BodyFormat>,
+    api_key_id_opt: Option<String>,
     search_service: Arc<dyn SearchService>,
 ) -> impl warp::Reply {
     info!(request =? search_request, "search");
-    let body_format = search_request.format;
-    let result = search_endpoint(index_id_patterns, search_request, &*search_service).await;
-    into_rest_api_response(result, body_format)
+    let body_format = search_body_format(&search_request, accept_format_opt);
+    if body_format == BodyFormat::Ndjson {
+        let result = search_ndjson_endpoint(
+            index_id_patterns,
+            search_request,
+            api_key_id_opt,
+            &*search_service,
+        )
+        .await;
+        return match result {
+            Ok(body) => {
+                reply::with_header(body, CONTENT_TYPE, body_format.content_type()).into_response()
+            }
+            Err(error) => into_rest_api_response::<(), _>(Err(error), body_format).into_response(),
+        };
+    }
+    if body_format == BodyFormat::Csv {
+        let csv_fields_opt = search_request.csv_fields.take();
+        let result = search_endpoint(
+            index_id_patterns,
+            search_request,
+            api_key_id_opt,
+            &*search_service,
+        )
+        .await;
+        return match result {
+            Ok(search_response) => {
+                let csv =
+                    SqlResponse::from_documents(&search_response.hits, csv_fields_opt).to_csv();
+                reply::with_header(csv, CONTENT_TYPE, body_format.content_type()).into_response()
+            }
+            Err(error) => into_rest_api_response::<(), _>(Err(error), body_format).into_response(),
+        };
+    }
+    let result = search_endpoint(
+        index_id_patterns,
+        search_request,
+        api_key_id_opt,
+        &*search_service,
+    )
+    .await;
+    into_rest_api_response(result, body_format).into_response()
 }
 
 async fn search_plan(
     index_id_patterns: Vec<String>,
     search_request: SearchRequestQueryString,
+    accept_format_opt: Option<BodyFormat>,
     search_service: Arc<dyn SearchService>,
 ) -> impl warp::Reply {
-    let body_format = search_request.format;
+    let body_format = search_body_format(&search_request, accept_format_opt);
     let result: Result<SearchPlanResponseRest, SearchError> = async {
         let plan_request = search_request_from_api_request(index_id_patterns, search_request)?;
         let plan_response = search_service.search_plan(plan_request).await?;
@@ -388,6 +601,8 @@ pub fn search_get_handler(
     search_service: Arc<dyn SearchService>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     search_get_filter()
+        .and(extract_format_from_accept_header())
+        .and(api_key_id_filter())
         .and(with_arg(search_service))
         .then(search)
 }
@@ -413,6 +628,8 @@ pub fn search_post_handler(
     search_service: Arc<dyn SearchService>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     search_post_filter()
+        .and(extract_format_from_accept_header())
+        .and(api_key_id_filter())
         .and(with_arg(search_service))
         .then(search)
 }
@@ -457,6 +674,7 @@ pub fn search_plan_get_handler(
     search_service: Arc<dyn SearchService>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     search_plan_get_filter()
+        .and(extract_format_from_accept_header())
         .and(with_arg(search_service))
         .then(search_plan)
 }
@@ -480,6 +698,7 @@ pub fn search_plan_post_handler(
     search_service: Arc<dyn SearchService>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
     search_plan_post_filter()
+        .and(extract_format_from_accept_header())
         .and(with_arg(search_service))
         .then(search_plan)
 }
@@ -593,6 +812,7 @@ async fn search_stream(
     let content_type = match request.output_format {
         OutputFormat::ClickHouseRowBinary => "application/octet-stream",
         OutputFormat::Csv => "text/csv",
+        OutputFormat::ArrowIpc => "application/vnd.apache.arrow.stream",
     };
     let reply =
         make_streaming_reply(search_stream_endpoint(index_id, request, &*search_service).await);
@@ -609,9 +829,11 @@ fn search_stream_filter(
 #[cfg(test)]
 mod tests {
     use assert_json_diff::{assert_json_eq, assert_json_include};
+    use base64::prelude::{Engine, BASE64_STANDARD};
     use bytes::Bytes;
     use mockall::predicate;
-    use quickwit_search::{MockSearchService, SearchError};
+    use quickwit_proto::search::{PartialHit, SortByValue, SortValue};
+    use quickwit_search::{encode_search_after_cursor, MockSearchService, SearchError};
     use serde_json::{json, Value as JsonValue};
 
     use super::*;
@@ -658,9 +880,15 @@ mod tests {
             num_hits: 55,
             hits: Vec::new(),
             snippets: None,
+            inner_hits: None,
+            explanations: None,
             elapsed_time_micros: 0u64,
             errors: Vec::new(),
             aggregations: None,
+            search_after: None,
+            timed_out: false,
+            timed_out_splits: Vec::new(),
+            split_profiles: Vec::new(),
         };
         let search_response_json: JsonValue = serde_json::to_value(search_response)?;
         let expected_search_response_json: JsonValue = json!({
@@ -826,6 +1054,174 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_rest_search_api_route_allow_leading_wildcard() {
+        let rest_search_api_filter = search_get_filter();
+        let (indexes, req) = warp::test::request()
+            .path("/quickwit-demo-index/search?query=body:*ond&allow_leading_wildcard=true")
+            .filter(&rest_search_api_filter)
+            .await
+            .unwrap();
+        assert!(req.allow_leading_wildcard);
+        let search_request = search_request_from_api_request(indexes, req).unwrap();
+        let QueryAst::UserInput(user_input_query) =
+            serde_json::from_str(&search_request.query_ast).unwrap()
+        else {
+            panic!("expected a user input query");
+        };
+        assert!(user_input_query.allow_leading_wildcard);
+    }
+
+    #[tokio::test]
+    async fn test_rest_search_api_route_snippet_options() {
+        let rest_search_api_filter = search_get_filter();
+        let (indexes, req) = warp::test::request()
+            .path(
+                "/quickwit-demo-index/search?query=body:bar&snippet_fields=body&snippet_pre_tag=%\
+                 3Cem%3E&snippet_post_tag=%3C/em%3E&snippet_fragment_size=50",
+            )
+            .filter(&rest_search_api_filter)
+            .await
+            .unwrap();
+        let search_request = search_request_from_api_request(indexes, req).unwrap();
+        assert_eq!(search_request.snippet_fields, vec!["body".to_string()]);
+        assert_eq!(
+            search_request.snippet_options,
+            Some(SnippetOptions {
+                pre_tag: Some("<em>".to_string()),
+                post_tag: Some("</em>".to_string()),
+                fragment_size: Some(50),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rest_search_api_route_collapse() {
+        let rest_search_api_filter = search_get_filter();
+        let (indexes, req) = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*&collapse_field=host&collapse_inner_hits=3")
+            .filter(&rest_search_api_filter)
+            .await
+            .unwrap();
+        let search_request = search_request_from_api_request(indexes, req).unwrap();
+        assert_eq!(
+            search_request.collapse,
+            Some(Collapse {
+                field: "host".to_string(),
+                inner_hits: 3,
+                inner_hits_name: None,
+            })
+        );
+        let (indexes, req) = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*")
+            .filter(&rest_search_api_filter)
+            .await
+            .unwrap();
+        let search_request = search_request_from_api_request(indexes, req).unwrap();
+        assert!(search_request.collapse.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rest_search_api_route_timeout() {
+        let rest_search_api_filter = search_get_filter();
+        let (indexes, req) = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*&timeout=1500ms")
+            .filter(&rest_search_api_filter)
+            .await
+            .unwrap();
+        let search_request = search_request_from_api_request(indexes, req).unwrap();
+        assert_eq!(search_request.timeout_millis, Some(1_500));
+
+        let (indexes, req) = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*&timeout=soon")
+            .filter(&rest_search_api_filter)
+            .await
+            .unwrap();
+        let search_error = search_request_from_api_request(indexes, req).unwrap_err();
+        assert!(matches!(search_error, SearchError::InvalidArgument(_)));
+    }
+
+    #[tokio::test]
+    async fn test_rest_search_api_route_priority() {
+        let rest_search_api_filter = search_get_filter();
+        let (indexes, req) = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*&priority=batch")
+            .filter(&rest_search_api_filter)
+            .await
+            .unwrap();
+        let search_request = search_request_from_api_request(indexes, req).unwrap();
+        assert_eq!(search_request.priority(), SearchPriority::Batch);
+
+        let (indexes, req) = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*")
+            .filter(&rest_search_api_filter)
+            .await
+            .unwrap();
+        let search_request = search_request_from_api_request(indexes, req).unwrap();
+        assert_eq!(search_request.priority(), SearchPriority::Interactive);
+
+        let rejection = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*&priority=urgent")
+            .filter(&rest_search_api_filter)
+            .await
+            .unwrap_err();
+        assert!(rejection.find::<serde_qs::Error>().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_rest_search_api_sets_api_key_id() {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .withf(|search_request| search_request.api_key_id.as_deref() == Some("my-key-id"))
+            .returning(|_| Ok(Default::default()));
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let credentials = BASE64_STANDARD.encode("my-key-id:my-secret");
+        // Browsers send the same credentials with the `Basic` scheme.
+        for scheme in ["ApiKey", "Basic"] {
+            let resp = warp::test::request()
+                .path("/quickwit-demo-index/search?query=*")
+                .header("Authorization", format!("{scheme} {credentials}"))
+                .reply(&rest_search_api_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rest_search_api_route_search_after() {
+        let partial_hit = PartialHit {
+            sort_value: Some(SortByValue {
+                sort_value: Some(SortValue::I64(1_700_000_000_000)),
+            }),
+            sort_value2: None,
+            split_id: "split1".to_string(),
+            segment_ord: 1,
+            doc_id: 42,
+            collapse_value: None,
+        };
+        let cursor = encode_search_after_cursor(&partial_hit);
+        let rest_search_api_filter = search_get_filter();
+        let (indexes, req) = warp::test::request()
+            .path(&format!(
+                "/quickwit-demo-index/search?query=*&sort_by=timestamp&search_after={cursor}"
+            ))
+            .filter(&rest_search_api_filter)
+            .await
+            .unwrap();
+        assert_eq!(req.search_after.as_deref(), Some(cursor.as_str()));
+        let search_request = search_request_from_api_request(indexes.clone(), req).unwrap();
+        assert_eq!(search_request.search_after, Some(partial_hit));
+
+        let req = SearchRequestQueryString {
+            query: "*".to_string(),
+            search_after: Some("not-a-cursor".to_string()),
+            ..Default::default()
+        };
+        let error = search_request_from_api_request(indexes, req).unwrap_err();
+        assert!(matches!(error, SearchError::InvalidArgument(_)));
+    }
+
     #[tokio::test]
     async fn test_rest_search_api_route_simple_default_num_hits_default_offset() {
         let rest_search_api_filter = search_get_filter();
@@ -890,6 +1286,7 @@ mod tests {
                     field_name: "field1".to_string(),
                     sort_order: SortOrder::Desc as i32,
                     sort_datetime_format: None,
+                    missing: SortMissing::Last as i32,
                 }],
             ),
             (
@@ -898,6 +1295,7 @@ mod tests {
                     field_name: "field1".to_string(),
                     sort_order: SortOrder::Desc as i32,
                     sort_datetime_format: None,
+                    missing: SortMissing::Last as i32,
                 }],
             ),
             (
@@ -906,6 +1304,7 @@ mod tests {
                     field_name: "field1".to_string(),
                     sort_order: SortOrder::Asc as i32,
                     sort_datetime_format: None,
+                    missing: SortMissing::Last as i32,
                 }],
             ),
             (
@@ -914,6 +1313,7 @@ mod tests {
                     field_name: "_score".to_string(),
                     sort_order: SortOrder::Desc as i32,
                     sort_datetime_format: None,
+                    missing: SortMissing::Last as i32,
                 }],
             ),
             (
@@ -922,6 +1322,7 @@ mod tests {
                     field_name: "_score".to_string(),
                     sort_order: SortOrder::Asc as i32,
                     sort_datetime_format: None,
+                    missing: SortMissing::Last as i32,
                 }],
             ),
             (
@@ -930,6 +1331,7 @@ mod tests {
                     field_name: "_score".to_string(),
                     sort_order: SortOrder::Desc as i32,
                     sort_datetime_format: None,
+                    missing: SortMissing::Last as i32,
                 }],
             ),
             (
@@ -939,11 +1341,13 @@ mod tests {
                         field_name: "field1".to_string(),
                         sort_order: SortOrder::Desc as i32,
                         sort_datetime_format: None,
+                        missing: SortMissing::Last as i32,
                     },
                     SortField {
                         field_name: "field2".to_string(),
                         sort_order: SortOrder::Desc as i32,
                         sort_datetime_format: None,
+                        missing: SortMissing::Last as i32,
                     },
                 ],
             ),
@@ -954,11 +1358,13 @@ mod tests {
                         field_name: "field1".to_string(),
                         sort_order: SortOrder::Desc as i32,
                         sort_datetime_format: None,
+                        missing: SortMissing::Last as i32,
                     },
                     SortField {
                         field_name: "field2".to_string(),
                         sort_order: SortOrder::Asc as i32,
                         sort_datetime_format: None,
+                        missing: SortMissing::Last as i32,
                     },
                 ],
             ),
@@ -969,11 +1375,13 @@ mod tests {
                         field_name: "field1".to_string(),
                         sort_order: SortOrder::Asc as i32,
                         sort_datetime_format: None,
+                        missing: SortMissing::Last as i32,
                     },
                     SortField {
                         field_name: "field2".to_string(),
                         sort_order: SortOrder::Desc as i32,
                         sort_datetime_format: None,
+                        missing: SortMissing::Last as i32,
                     },
                 ],
             ),
@@ -1009,6 +1417,7 @@ mod tests {
                 field_name: "fiel1".to_string(),
                 sort_order: SortOrder::Desc as i32,
                 sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
             }],
         );
     }
@@ -1072,6 +1481,117 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_rest_search_api_route_serialize_msgpack() -> anyhow::Result<()> {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service.expect_root_search().returning(|_| {
+            Ok(quickwit_proto::search::SearchResponse {
+                num_hits: 10,
+                ..Default::default()
+            })
+        });
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*")
+            .header("accept", "application/msgpack")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "application/msgpack"
+        );
+        let resp_json: JsonValue = rmp_serde::from_slice(resp.body())?;
+        assert_eq!(resp_json["num_hits"], 10);
+
+        // The `format` parameter takes priority over the `Accept` header.
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .returning(|_| Ok(Default::default()));
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*&format=json")
+            .header("accept", "application/msgpack")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rest_search_api_route_serialize_ndjson() -> anyhow::Result<()> {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service.expect_root_search().returning(|_| {
+            Ok(quickwit_proto::search::SearchResponse {
+                hits: vec![
+                    quickwit_proto::search::Hit {
+                        json: r#"{"title":"foo"}"#.to_string(),
+                        ..Default::default()
+                    },
+                    quickwit_proto::search::Hit {
+                        json: r#"{"title":"bar"}"#.to_string(),
+                        ..Default::default()
+                    },
+                ],
+                num_hits: 2,
+                ..Default::default()
+            })
+        });
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*&format=ndjson")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "application/x-ndjson"
+        );
+        assert_eq!(
+            String::from_utf8_lossy(resp.body()),
+            "{\"title\":\"foo\"}\n{\"title\":\"bar\"}\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rest_search_api_route_serialize_csv() -> anyhow::Result<()> {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service.expect_root_search().returning(|_| {
+            Ok(quickwit_proto::search::SearchResponse {
+                hits: vec![
+                    quickwit_proto::search::Hit {
+                        json: r#"{"title":"foo, bar","attributes":{"host":"node-1"}}"#.to_string(),
+                        ..Default::default()
+                    },
+                    quickwit_proto::search::Hit {
+                        json: r#"{"title":"baz"}"#.to_string(),
+                        ..Default::default()
+                    },
+                ],
+                num_hits: 2,
+                ..Default::default()
+            })
+        });
+        let rest_search_api_handler = search_handler(mock_search_service);
+        let resp = warp::test::request()
+            .path("/quickwit-demo-index/search?query=*&format=csv&csv_fields=title,attributes.host")
+            .reply(&rest_search_api_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers().get("content-type").unwrap(), "text/csv");
+        assert_eq!(
+            String::from_utf8_lossy(resp.body()),
+            "title,attributes.host\n\"foo, bar\",node-1\nbaz,\n"
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_rest_search_api_start_offset_and_num_hits_parameter() -> anyhow::Result<()> {
         let mut mock_search_service = MockSearchService::new();
@@ -1223,6 +1743,19 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_rest_search_stream_api_arrow_ipc() {
+        let (_index, req) = warp::test::request()
+            .path(
+                "/my-index/search/stream?query=obama&fast_field=external_id&\
+                 output_format=arrow_ipc",
+            )
+            .filter(&super::search_stream_filter())
+            .await
+            .unwrap();
+        assert_eq!(req.output_format, OutputFormat::ArrowIpc);
+    }
+
     #[tokio::test]
     async fn test_rest_search_stream_api_error() {
         let rejection = warp::test::request()
@@ -1236,7 +1769,8 @@ mod tests {
         let parse_error = rejection.find::<serde_qs::Error>().unwrap();
         assert_eq!(
             parse_error.to_string(),
-            "unknown variant `ClickHouseRowBinary`, expected `csv` or `click_house_row_binary`"
+            "unknown variant `ClickHouseRowBinary`, expected one of `csv`, \
+             `click_house_row_binary`, `arrow_ipc`"
         );
     }
 
@@ -1264,6 +1798,10 @@ mod tests {
                     partial_hit: None,
                     snippet: Some(r#"{"title": [], "body": ["foo <em>bar</em> baz"]}"#.to_string()),
                     index_id: "quickwit-demo-index".to_string(),
+                    matched_queries: Vec::new(),
+                    inner_hits: Vec::new(),
+                    explanation_json: None,
+                    docvalue_fields_json: None,
                 }],
                 num_hits: 1,
                 elapsed_time_micros: 16,
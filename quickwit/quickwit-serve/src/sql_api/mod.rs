@@ -0,0 +1,205 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod parser;
+mod planner;
+mod rest_handler;
+
+use std::collections::HashSet;
+
+use quickwit_search::{SearchError, SearchResponseRest, SearchService};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+pub(crate) use self::rest_handler::{sql_api_handlers, SqlApi};
+
+/// Output format of the SQL API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlResponseFormat {
+    Json,
+    #[default]
+    PrettyJson,
+    Csv,
+}
+
+/// Body of a SQL API request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SqlRequest {
+    /// SQL `SELECT` statement, e.g. `SELECT service, COUNT(*) FROM logs GROUP BY service`.
+    pub query: String,
+    /// The output format: `json`, `pretty_json` or `csv`.
+    #[serde(default)]
+    pub format: SqlResponseFormat,
+}
+
+/// Column of a SQL result set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SqlColumn {
+    pub name: String,
+}
+
+/// Result set of a SQL query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SqlResponse {
+    pub columns: Vec<SqlColumn>,
+    #[schema(value_type = Vec<Vec<Object>>)]
+    pub rows: Vec<Vec<JsonValue>>,
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn csv_field(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(text) => csv_escape(text),
+        _ => csv_escape(&value.to_string()),
+    }
+}
+
+impl SqlResponse {
+    /// Builds a result set with a row per document and a column per field. Without fields, the
+    /// columns are the top-level keys of the documents, in order of appearance.
+    pub(crate) fn from_documents(documents: &[JsonValue], fields_opt: Option<Vec<String>>) -> Self {
+        let fields = fields_opt.unwrap_or_else(|| {
+            let mut seen_keys: HashSet<&str> = HashSet::new();
+            let mut fields = Vec::new();
+            for document in documents.iter().filter_map(JsonValue::as_object) {
+                for key in document.keys() {
+                    if seen_keys.insert(key) {
+                        fields.push(key.clone());
+                    }
+                }
+            }
+            fields
+        });
+        let rows = documents
+            .iter()
+            .map(|document| {
+                fields
+                    .iter()
+                    .map(|field| {
+                        planner::lookup_column(document, field)
+                            .cloned()
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .collect();
+        let columns = fields.into_iter().map(|name| SqlColumn { name }).collect();
+        SqlResponse { columns, rows }
+    }
+
+    /// Formats the result set as CSV, with a header line holding the column names.
+    ///
+    /// Nulls are written as empty fields, and arrays and objects as JSON.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        let header: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| csv_escape(&column.name))
+            .collect();
+        csv.push_str(&header.join(","));
+        csv.push('\n');
+
+        for row in &self.rows {
+            let fields: Vec<String> = row.iter().map(csv_field).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Parses a SQL query, runs the corresponding search, and returns the result set.
+pub(crate) async fn execute_sql_query(
+    sql: &str,
+    search_service: &dyn SearchService,
+) -> Result<SqlResponse, SearchError> {
+    let statement = parser::parse_sql(sql).map_err(|error| {
+        SearchError::InvalidQuery(format!("failed to parse SQL query: {error}"))
+    })?;
+    let sql_plan = planner::plan_sql_statement(statement)?;
+    let search_response = search_service
+        .root_search(sql_plan.search_request.clone())
+        .await?;
+    let search_response_rest = SearchResponseRest::try_from(search_response)?;
+    sql_plan.into_sql_response(search_response_rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_sql_response_from_documents() {
+        let documents = vec![
+            json!({"attributes": {"host": "node-1"}, "service": "api"}),
+            json!({"level": "WARN", "service": "db"}),
+        ];
+        let sql_response = SqlResponse::from_documents(&documents, None);
+        assert_eq!(
+            sql_response.to_csv(),
+            "attributes,service,level\n\"{\"\"host\"\":\"\"node-1\"\"}\",api,\n,db,WARN\n"
+        );
+        let sql_response = SqlResponse::from_documents(
+            &documents,
+            Some(vec!["attributes.host".to_string(), "service".to_string()]),
+        );
+        assert_eq!(
+            sql_response.to_csv(),
+            "attributes.host,service\nnode-1,api\n,db\n"
+        );
+    }
+
+    #[test]
+    fn test_sql_response_to_csv() {
+        let sql_response = SqlResponse {
+            columns: vec![
+                SqlColumn {
+                    name: "service".to_string(),
+                },
+                SqlColumn {
+                    name: "count(*)".to_string(),
+                },
+                SqlColumn {
+                    name: "tags".to_string(),
+                },
+            ],
+            rows: vec![
+                vec![json!("api"), json!(3), json!(["a", "b"])],
+                vec![json!("say \"hi\""), json!(1.5), JsonValue::Null],
+            ],
+        };
+        assert_eq!(
+            sql_response.to_csv(),
+            "service,count(*),tags\napi,3,\"[\"\"a\"\",\"\"b\"\"]\"\n\"say \"\"hi\"\"\",1.5,\n"
+        );
+    }
+}
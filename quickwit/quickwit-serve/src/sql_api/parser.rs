@@ -0,0 +1,911 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Parser for the subset of SQL supported by Quickwit:
+//!
+//! ```sql
+//! SELECT <select_item> [, ...]
+//! FROM <index_id>
+//! [WHERE <condition>]
+//! [GROUP BY <column> [, ...]]
+//! [ORDER BY <column> [ASC | DESC] [, ...]]
+//! [LIMIT <count>]
+//! [OFFSET <count>]
+//! ```
+
+use std::fmt;
+
+/// Aggregate functions supported in the select list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFunction {
+    fn from_name(name: &str) -> Option<Self> {
+        let aggregate_function = match name.to_ascii_lowercase().as_str() {
+            "count" => Self::Count,
+            "sum" => Self::Sum,
+            "avg" => Self::Avg,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            _ => return None,
+        };
+        Some(aggregate_function)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Count => "count",
+            Self::Sum => "sum",
+            Self::Avg => "avg",
+            Self::Min => "min",
+            Self::Max => "max",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AggregateCall {
+    pub function: AggregateFunction,
+    /// `None` stands for `*`, as in `COUNT(*)`.
+    pub column: Option<String>,
+}
+
+impl fmt::Display for AggregateCall {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let column = self.column.as_deref().unwrap_or("*");
+        write!(formatter, "{}({column})", self.function.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SelectItem {
+    /// `*`
+    Wildcard,
+    Column {
+        column: String,
+        alias: Option<String>,
+    },
+    Aggregate {
+        aggregate: AggregateCall,
+        alias: Option<String>,
+    },
+}
+
+impl SelectItem {
+    /// Name of the column in the result set.
+    pub fn output_name(&self) -> String {
+        match self {
+            Self::Wildcard => "*".to_string(),
+            Self::Column { column, alias } => alias.clone().unwrap_or_else(|| column.clone()),
+            Self::Aggregate { aggregate, alias } => {
+                alias.clone().unwrap_or_else(|| aggregate.to_string())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Literal {
+    String(String),
+    /// Numbers are kept in their textual form and interpreted against the field type later.
+    Number(String),
+    Bool(bool),
+}
+
+impl Literal {
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::String(text) | Self::Number(text) => text.clone(),
+            Self::Bool(value) => value.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ComparisonOperator {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Condition {
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+    Comparison {
+        column: String,
+        operator: ComparisonOperator,
+        value: Literal,
+    },
+    Between {
+        column: String,
+        low: Literal,
+        high: Literal,
+        negated: bool,
+    },
+    InList {
+        column: String,
+        values: Vec<Literal>,
+        negated: bool,
+    },
+    IsNull {
+        column: String,
+        negated: bool,
+    },
+    Like {
+        column: String,
+        pattern: String,
+        negated: bool,
+    },
+    /// `MATCH(column, 'text')`: full-text search on a column.
+    Match {
+        column: String,
+        text: String,
+    },
+    /// `QUERY('user query')`: query expressed in Quickwit's query language.
+    Query {
+        user_text: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OrderByItem {
+    /// Column, alias, or canonical aggregate name (e.g. `count(*)`).
+    pub key: String,
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SelectStatement {
+    pub select_items: Vec<SelectItem>,
+    pub index_id: String,
+    pub condition_opt: Option<Condition>,
+    pub group_by: Vec<String>,
+    pub order_by: Vec<OrderByItem>,
+    pub limit_opt: Option<u64>,
+    pub offset_opt: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Identifier(String),
+    QuotedIdentifier(String),
+    String(String),
+    Number(String),
+    Star,
+    Comma,
+    LeftParen,
+    RightParen,
+    Semicolon,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Identifier(identifier) => write!(formatter, "{identifier}"),
+            Self::QuotedIdentifier(identifier) => write!(formatter, "\"{identifier}\""),
+            Self::String(text) => write!(formatter, "'{text}'"),
+            Self::Number(number) => write!(formatter, "{number}"),
+            Self::Star => write!(formatter, "*"),
+            Self::Comma => write!(formatter, ","),
+            Self::LeftParen => write!(formatter, "("),
+            Self::RightParen => write!(formatter, ")"),
+            Self::Semicolon => write!(formatter, ";"),
+            Self::Eq => write!(formatter, "="),
+            Self::NotEq => write!(formatter, "!="),
+            Self::Lt => write!(formatter, "<"),
+            Self::LtEq => write!(formatter, "<="),
+            Self::Gt => write!(formatter, ">"),
+            Self::GtEq => write!(formatter, ">="),
+        }
+    }
+}
+
+fn is_identifier_start(ch: char) -> bool {
+    ch.is_ascii_alphabetic() || ch == '_'
+}
+
+fn is_identifier_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || matches!(ch, '_' | '.' | '-' | '@')
+}
+
+fn tokenize(sql: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if is_identifier_start(ch) {
+            let mut identifier = String::new();
+            while let Some(&ch) = chars.peek() {
+                if !is_identifier_char(ch) {
+                    break;
+                }
+                identifier.push(ch);
+                chars.next();
+            }
+            tokens.push(Token::Identifier(identifier));
+            continue;
+        }
+        let is_negative_number =
+            ch == '-' && matches!(chars.clone().nth(1), Some(next_ch) if next_ch.is_ascii_digit());
+        if ch.is_ascii_digit() || is_negative_number {
+            let mut number = String::new();
+            number.push(ch);
+            chars.next();
+            while let Some(&ch) = chars.peek() {
+                if !(ch.is_ascii_alphanumeric() || matches!(ch, '.' | '+' | '-')) {
+                    break;
+                }
+                // Only accept a sign right after an exponent marker.
+                if matches!(ch, '+' | '-') && !number.ends_with(['e', 'E']) {
+                    break;
+                }
+                number.push(ch);
+                chars.next();
+            }
+            tokens.push(Token::Number(number));
+            continue;
+        }
+        chars.next();
+        let token = match ch {
+            '\'' | '"' | '`' => {
+                let closing_quote = ch;
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        // A doubled quote is an escaped quote.
+                        Some(ch) if ch == closing_quote => {
+                            if chars.peek() == Some(&closing_quote) {
+                                text.push(closing_quote);
+                                chars.next();
+                            } else {
+                                break;
+                            }
+                        }
+                        Some(ch) => text.push(ch),
+                        None => return Err(format!("unterminated quoted string `{text}`")),
+                    }
+                }
+                if closing_quote == '\'' {
+                    Token::String(text)
+                } else {
+                    Token::QuotedIdentifier(text)
+                }
+            }
+            '*' => Token::Star,
+            ',' => Token::Comma,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            ';' => Token::Semicolon,
+            '=' => Token::Eq,
+            '!' if chars.peek() == Some(&'=') => {
+                chars.next();
+                Token::NotEq
+            }
+            '<' => match chars.peek() {
+                Some('=') => {
+                    chars.next();
+                    Token::LtEq
+                }
+                Some('>') => {
+                    chars.next();
+                    Token::NotEq
+                }
+                _ => Token::Lt,
+            },
+            '>' => {
+                if chars.peek() == Some(&'=') {
+                    chars.next();
+                    Token::GtEq
+                } else {
+                    Token::Gt
+                }
+            }
+            _ => return Err(format!("unexpected character `{ch}`")),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+const RESERVED_KEYWORDS: [&str; 19] = [
+    "and", "as", "asc", "between", "by", "desc", "false", "from", "group", "in", "is", "like",
+    "limit", "not", "null", "offset", "or", "order", "where",
+];
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_nth(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.position + offset)
+    }
+
+    fn next_token(&mut self) -> Result<Token, String> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or_else(|| "unexpected end of query".to_string())?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Identifier(identifier)) if identifier.eq_ignore_ascii_case(keyword))
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), String> {
+        if self.consume_keyword(keyword) {
+            return Ok(());
+        }
+        match self.peek() {
+            Some(token) => Err(format!(
+                "expected `{}`, found `{token}`",
+                keyword.to_ascii_uppercase()
+            )),
+            None => Err(format!(
+                "expected `{}`, found end of query",
+                keyword.to_ascii_uppercase()
+            )),
+        }
+    }
+
+    fn consume_token(&mut self, expected_token: &Token) -> bool {
+        if self.peek() == Some(expected_token) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect_token(&mut self, expected_token: &Token) -> Result<(), String> {
+        if self.consume_token(expected_token) {
+            return Ok(());
+        }
+        match self.peek() {
+            Some(token) => Err(format!("expected `{expected_token}`, found `{token}`")),
+            None => Err(format!("expected `{expected_token}`, found end of query")),
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<String, String> {
+        match self.next_token()? {
+            Token::Identifier(identifier) => {
+                if RESERVED_KEYWORDS.contains(&identifier.to_ascii_lowercase().as_str()) {
+                    return Err(format!(
+                        "expected identifier, found keyword `{}`",
+                        identifier.to_ascii_uppercase()
+                    ));
+                }
+                Ok(identifier)
+            }
+            Token::QuotedIdentifier(identifier) => Ok(identifier),
+            token => Err(format!("expected identifier, found `{token}`")),
+        }
+    }
+
+    fn parse_unsigned_integer(&mut self, clause: &str) -> Result<u64, String> {
+        match self.next_token()? {
+            Token::Number(number) => number
+                .parse::<u64>()
+                .map_err(|_| format!("invalid {clause} value `{number}`")),
+            token => Err(format!("expected {clause} value, found `{token}`")),
+        }
+    }
+
+    fn parse_literal(&mut self) -> Result<Literal, String> {
+        match self.next_token()? {
+            Token::String(text) => Ok(Literal::String(text)),
+            Token::Number(number) => Ok(Literal::Number(number)),
+            Token::Identifier(identifier) if identifier.eq_ignore_ascii_case("true") => {
+                Ok(Literal::Bool(true))
+            }
+            Token::Identifier(identifier) if identifier.eq_ignore_ascii_case("false") => {
+                Ok(Literal::Bool(false))
+            }
+            token => Err(format!("expected literal value, found `{token}`")),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        match self.next_token()? {
+            Token::String(text) => Ok(text),
+            token => Err(format!("expected string literal, found `{token}`")),
+        }
+    }
+
+    /// Parses an aggregate call if the next tokens are `<function> (`.
+    fn parse_aggregate_call_opt(&mut self) -> Result<Option<AggregateCall>, String> {
+        let Some(Token::Identifier(name)) = self.peek() else {
+            return Ok(None);
+        };
+        if self.peek_nth(1) != Some(&Token::LeftParen) {
+            return Ok(None);
+        }
+        let Some(function) = AggregateFunction::from_name(name) else {
+            return Err(format!("unsupported function `{name}`"));
+        };
+        self.position += 2;
+        let column = if self.consume_token(&Token::Star) {
+            if function != AggregateFunction::Count {
+                return Err(format!(
+                    "`*` is only supported as argument of `COUNT`, not `{}`",
+                    function.name().to_ascii_uppercase()
+                ));
+            }
+            None
+        } else {
+            Some(self.parse_identifier()?)
+        };
+        self.expect_token(&Token::RightParen)?;
+        Ok(Some(AggregateCall { function, column }))
+    }
+
+    fn parse_alias_opt(&mut self) -> Result<Option<String>, String> {
+        if self.consume_keyword("as") {
+            return self.parse_identifier().map(Some);
+        }
+        match self.peek() {
+            Some(Token::QuotedIdentifier(_)) => self.parse_identifier().map(Some),
+            Some(Token::Identifier(identifier))
+                if !RESERVED_KEYWORDS.contains(&identifier.to_ascii_lowercase().as_str()) =>
+            {
+                self.parse_identifier().map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_select_item(&mut self) -> Result<SelectItem, String> {
+        if self.consume_token(&Token::Star) {
+            return Ok(SelectItem::Wildcard);
+        }
+        if let Some(aggregate) = self.parse_aggregate_call_opt()? {
+            let alias = self.parse_alias_opt()?;
+            return Ok(SelectItem::Aggregate { aggregate, alias });
+        }
+        let column = self.parse_identifier()?;
+        let alias = self.parse_alias_opt()?;
+        Ok(SelectItem::Column { column, alias })
+    }
+
+    fn parse_condition(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_conjunction()?;
+        while self.consume_keyword("or") {
+            let right = self.parse_conjunction()?;
+            condition = Condition::Or(Box::new(condition), Box::new(right));
+        }
+        Ok(condition)
+    }
+
+    fn parse_conjunction(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_negation()?;
+        while self.consume_keyword("and") {
+            let right = self.parse_negation()?;
+            condition = Condition::And(Box::new(condition), Box::new(right));
+        }
+        Ok(condition)
+    }
+
+    fn parse_negation(&mut self) -> Result<Condition, String> {
+        if self.consume_keyword("not") {
+            let condition = self.parse_negation()?;
+            return Ok(Condition::Not(Box::new(condition)));
+        }
+        self.parse_predicate()
+    }
+
+    fn parse_predicate(&mut self) -> Result<Condition, String> {
+        if self.consume_token(&Token::LeftParen) {
+            let condition = self.parse_condition()?;
+            self.expect_token(&Token::RightParen)?;
+            return Ok(condition);
+        }
+        if let (Some(Token::Identifier(name)), Some(Token::LeftParen)) =
+            (self.peek(), self.peek_nth(1))
+        {
+            let name = name.to_ascii_lowercase();
+            match name.as_str() {
+                "match" => {
+                    self.position += 2;
+                    let column = self.parse_identifier()?;
+                    self.expect_token(&Token::Comma)?;
+                    let text = self.parse_string()?;
+                    self.expect_token(&Token::RightParen)?;
+                    return Ok(Condition::Match { column, text });
+                }
+                "query" => {
+                    self.position += 2;
+                    let user_text = self.parse_string()?;
+                    self.expect_token(&Token::RightParen)?;
+                    return Ok(Condition::Query { user_text });
+                }
+                _ => return Err(format!("unsupported function `{name}` in WHERE clause")),
+            }
+        }
+        let column = self.parse_identifier()?;
+
+        if self.consume_keyword("is") {
+            let negated = self.consume_keyword("not");
+            self.expect_keyword("null")?;
+            return Ok(Condition::IsNull { column, negated });
+        }
+        let negated = self.consume_keyword("not");
+
+        if self.consume_keyword("in") {
+            self.expect_token(&Token::LeftParen)?;
+            let mut values = vec![self.parse_literal()?];
+            while self.consume_token(&Token::Comma) {
+                values.push(self.parse_literal()?);
+            }
+            self.expect_token(&Token::RightParen)?;
+            return Ok(Condition::InList {
+                column,
+                values,
+                negated,
+            });
+        }
+        if self.consume_keyword("like") {
+            let pattern = self.parse_string()?;
+            return Ok(Condition::Like {
+                column,
+                pattern,
+                negated,
+            });
+        }
+        if self.consume_keyword("between") {
+            let low = self.parse_literal()?;
+            self.expect_keyword("and")?;
+            let high = self.parse_literal()?;
+            return Ok(Condition::Between {
+                column,
+                low,
+                high,
+                negated,
+            });
+        }
+        if negated {
+            return Err("expected `IN`, `LIKE` or `BETWEEN` after `NOT`".to_string());
+        }
+        let operator = match self.next_token()? {
+            Token::Eq => ComparisonOperator::Eq,
+            Token::NotEq => ComparisonOperator::NotEq,
+            Token::Lt => ComparisonOperator::Lt,
+            Token::LtEq => ComparisonOperator::LtEq,
+            Token::Gt => ComparisonOperator::Gt,
+            Token::GtEq => ComparisonOperator::GtEq,
+            token => return Err(format!("expected comparison operator, found `{token}`")),
+        };
+        let value = self.parse_literal()?;
+        Ok(Condition::Comparison {
+            column,
+            operator,
+            value,
+        })
+    }
+
+    fn parse_order_by_item(&mut self) -> Result<OrderByItem, String> {
+        let key = if let Some(aggregate) = self.parse_aggregate_call_opt()? {
+            aggregate.to_string()
+        } else {
+            self.parse_identifier()?
+        };
+        let descending = if self.consume_keyword("desc") {
+            true
+        } else {
+            self.consume_keyword("asc");
+            false
+        };
+        Ok(OrderByItem { key, descending })
+    }
+
+    fn parse_select_statement(&mut self) -> Result<SelectStatement, String> {
+        self.expect_keyword("select")?;
+        let mut select_items = vec![self.parse_select_item()?];
+        while self.consume_token(&Token::Comma) {
+            select_items.push(self.parse_select_item()?);
+        }
+        self.expect_keyword("from")?;
+        let index_id = self.parse_identifier()?;
+
+        let condition_opt = if self.consume_keyword("where") {
+            Some(self.parse_condition()?)
+        } else {
+            None
+        };
+        let mut group_by = Vec::new();
+        if self.consume_keyword("group") {
+            self.expect_keyword("by")?;
+            group_by.push(self.parse_identifier()?);
+            while self.consume_token(&Token::Comma) {
+                group_by.push(self.parse_identifier()?);
+            }
+        }
+        let mut order_by = Vec::new();
+        if self.consume_keyword("order") {
+            self.expect_keyword("by")?;
+            order_by.push(self.parse_order_by_item()?);
+            while self.consume_token(&Token::Comma) {
+                order_by.push(self.parse_order_by_item()?);
+            }
+        }
+        let limit_opt = if self.consume_keyword("limit") {
+            Some(self.parse_unsigned_integer("LIMIT")?)
+        } else {
+            None
+        };
+        let offset_opt = if self.consume_keyword("offset") {
+            Some(self.parse_unsigned_integer("OFFSET")?)
+        } else {
+            None
+        };
+        self.consume_token(&Token::Semicolon);
+
+        if let Some(token) = self.peek() {
+            return Err(format!("unexpected token `{token}`"));
+        }
+        Ok(SelectStatement {
+            select_items,
+            index_id,
+            condition_opt,
+            group_by,
+            order_by,
+            limit_opt,
+            offset_opt,
+        })
+    }
+}
+
+/// Parses a SQL `SELECT` statement.
+pub(crate) fn parse_sql(sql: &str) -> Result<SelectStatement, String> {
+    let tokens = tokenize(sql)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+    parser.parse_select_statement()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str) -> SelectItem {
+        SelectItem::Column {
+            column: name.to_string(),
+            alias: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_sql_simple_select() {
+        let statement = parse_sql("SELECT * FROM hdfs-logs").unwrap();
+        assert_eq!(
+            statement,
+            SelectStatement {
+                select_items: vec![SelectItem::Wildcard],
+                index_id: "hdfs-logs".to_string(),
+                condition_opt: None,
+                group_by: Vec::new(),
+                order_by: Vec::new(),
+                limit_opt: None,
+                offset_opt: None,
+            }
+        );
+        let statement =
+            parse_sql("select severity_text, body AS message from \"my-index\";").unwrap();
+        assert_eq!(
+            statement.select_items,
+            vec![
+                column("severity_text"),
+                SelectItem::Column {
+                    column: "body".to_string(),
+                    alias: Some("message".to_string())
+                }
+            ]
+        );
+        assert_eq!(statement.index_id, "my-index");
+    }
+
+    #[test]
+    fn test_parse_sql_where() {
+        let statement = parse_sql(
+            "SELECT * FROM logs WHERE status >= 500 AND (service IN ('api', 'web') OR NOT \
+             host.name LIKE 'db-%') AND trace_id IS NOT NULL",
+        )
+        .unwrap();
+        let expected_condition = Condition::And(
+            Box::new(Condition::And(
+                Box::new(Condition::Comparison {
+                    column: "status".to_string(),
+                    operator: ComparisonOperator::GtEq,
+                    value: Literal::Number("500".to_string()),
+                }),
+                Box::new(Condition::Or(
+                    Box::new(Condition::InList {
+                        column: "service".to_string(),
+                        values: vec![
+                            Literal::String("api".to_string()),
+                            Literal::String("web".to_string()),
+                        ],
+                        negated: false,
+                    }),
+                    Box::new(Condition::Not(Box::new(Condition::Like {
+                        column: "host.name".to_string(),
+                        pattern: "db-%".to_string(),
+                        negated: false,
+                    }))),
+                )),
+            )),
+            Box::new(Condition::IsNull {
+                column: "trace_id".to_string(),
+                negated: true,
+            }),
+        );
+        assert_eq!(statement.condition_opt, Some(expected_condition));
+
+        let statement = parse_sql(
+            "SELECT * FROM logs WHERE latency NOT BETWEEN -1.5 AND 2e3 AND MATCH(body, 'it''s \
+             broken') AND QUERY('severity_text:ERROR') AND status <> 200",
+        )
+        .unwrap();
+        let expected_condition = Condition::And(
+            Box::new(Condition::And(
+                Box::new(Condition::And(
+                    Box::new(Condition::Between {
+                        column: "latency".to_string(),
+                        low: Literal::Number("-1.5".to_string()),
+                        high: Literal::Number("2e3".to_string()),
+                        negated: true,
+                    }),
+                    Box::new(Condition::Match {
+                        column: "body".to_string(),
+                        text: "it's broken".to_string(),
+                    }),
+                )),
+                Box::new(Condition::Query {
+                    user_text: "severity_text:ERROR".to_string(),
+                }),
+            )),
+            Box::new(Condition::Comparison {
+                column: "status".to_string(),
+                operator: ComparisonOperator::NotEq,
+                value: Literal::Number("200".to_string()),
+            }),
+        );
+        assert_eq!(statement.condition_opt, Some(expected_condition));
+    }
+
+    #[test]
+    fn test_parse_sql_aggregation() {
+        let statement = parse_sql(
+            "SELECT service, COUNT(*) AS hits, avg(latency) FROM logs GROUP BY service ORDER BY \
+             hits DESC, avg(latency) LIMIT 10 OFFSET 5",
+        )
+        .unwrap();
+        assert_eq!(
+            statement.select_items,
+            vec![
+                column("service"),
+                SelectItem::Aggregate {
+                    aggregate: AggregateCall {
+                        function: AggregateFunction::Count,
+                        column: None,
+                    },
+                    alias: Some("hits".to_string()),
+                },
+                SelectItem::Aggregate {
+                    aggregate: AggregateCall {
+                        function: AggregateFunction::Avg,
+                        column: Some("latency".to_string()),
+                    },
+                    alias: None,
+                },
+            ]
+        );
+        assert_eq!(statement.select_items[2].output_name(), "avg(latency)");
+        assert_eq!(statement.group_by, vec!["service".to_string()]);
+        assert_eq!(
+            statement.order_by,
+            vec![
+                OrderByItem {
+                    key: "hits".to_string(),
+                    descending: true,
+                },
+                OrderByItem {
+                    key: "avg(latency)".to_string(),
+                    descending: false,
+                },
+            ]
+        );
+        assert_eq!(statement.limit_opt, Some(10));
+        assert_eq!(statement.offset_opt, Some(5));
+    }
+
+    #[test]
+    fn test_parse_sql_errors() {
+        assert_eq!(
+            parse_sql("SELECT * logs").unwrap_err(),
+            "expected `FROM`, found `logs`"
+        );
+        assert_eq!(
+            parse_sql("SELECT * FROM logs WHERE").unwrap_err(),
+            "unexpected end of query"
+        );
+        assert_eq!(
+            parse_sql("SELECT * FROM logs WHERE body = 'abc").unwrap_err(),
+            "unterminated quoted string `abc`"
+        );
+        assert_eq!(
+            parse_sql("SELECT sum(*) FROM logs").unwrap_err(),
+            "`*` is only supported as argument of `COUNT`, not `SUM`"
+        );
+        assert_eq!(
+            parse_sql("SELECT lower(body) FROM logs").unwrap_err(),
+            "unsupported function `lower`"
+        );
+        assert_eq!(
+            parse_sql("SELECT * FROM logs LIMIT 10 foo").unwrap_err(),
+            "unexpected token `foo`"
+        );
+        assert_eq!(
+            parse_sql("SELECT * FROM logs WHERE status NOT 200").unwrap_err(),
+            "expected `IN`, `LIKE` or `BETWEEN` after `NOT`"
+        );
+        assert_eq!(
+            parse_sql("SELECT * FROM logs LIMIT -1").unwrap_err(),
+            "invalid LIMIT value `-1`"
+        );
+    }
+}
@@ -0,0 +1,1048 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Translates a parsed SQL statement into a Quickwit search request, and the search response
+//! back into rows.
+//!
+//! Statements are executed in one of three modes:
+//! - without aggregate functions nor `GROUP BY`, the statement fetches hits, and `ORDER BY`,
+//!   `LIMIT` and `OFFSET` are pushed down to the search request.
+//! - with aggregate functions but no `GROUP BY`, the statement returns a single row computed from
+//!   metric aggregations.
+//! - with `GROUP BY`, the statement runs nested terms aggregations, one row per leaf bucket. `ORDER
+//!   BY`, `LIMIT` and `OFFSET` are then applied to the rows in memory.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::Bound;
+
+use quickwit_proto::search::{CountHits, SearchRequest, SortField, SortMissing, SortOrder};
+use quickwit_query::query_ast::{
+    BoolQuery, FieldPresenceQuery, FullTextMode, FullTextParams, FullTextQuery, QueryAst,
+    RangeQuery, TermQuery, TermSetQuery, UserInputQuery, WildcardQuery,
+};
+use quickwit_query::{BooleanOperand, JsonLiteral, MatchAllOrNone};
+use quickwit_search::{SearchError, SearchResponseRest};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+
+use super::parser::{
+    AggregateCall, AggregateFunction, ComparisonOperator, Condition, Literal, OrderByItem,
+    SelectItem, SelectStatement,
+};
+use super::{SqlColumn, SqlResponse};
+
+/// Number of hits returned by statements without a `LIMIT` clause.
+pub(crate) const DEFAULT_SQL_LIMIT: u64 = 100;
+
+/// Maximum number of buckets requested for each `GROUP BY` column.
+pub(crate) const MAX_GROUP_BY_BUCKETS: u32 = 10_000;
+
+fn invalid_query(message: impl Into<String>) -> SearchError {
+    SearchError::InvalidQuery(message.into())
+}
+
+fn literal_to_json_literal(literal: &Literal) -> Result<JsonLiteral, SearchError> {
+    match literal {
+        Literal::String(text) => Ok(JsonLiteral::String(text.clone())),
+        Literal::Number(number) => serde_json::from_str(number)
+            .map(JsonLiteral::Number)
+            .map_err(|_| invalid_query(format!("invalid number `{number}`"))),
+        Literal::Bool(value) => Ok(JsonLiteral::Bool(*value)),
+    }
+}
+
+fn negate(query_ast: QueryAst) -> QueryAst {
+    BoolQuery {
+        must_not: vec![query_ast],
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Converts a SQL `LIKE` pattern into a wildcard pattern: `%` and `_` become `*` and `?`, and
+/// backslash-escaped characters are kept literal.
+///
+/// Returns `None` if the pattern does not contain any wildcard.
+fn like_pattern_to_wildcard(pattern: &str) -> Option<String> {
+    let mut wildcard_pattern = String::with_capacity(pattern.len());
+    let mut has_wildcard = false;
+    let mut chars = pattern.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '%' => {
+                has_wildcard = true;
+                wildcard_pattern.push('*');
+            }
+            '_' => {
+                has_wildcard = true;
+                wildcard_pattern.push('?');
+            }
+            '\\' | '*' | '?' => {
+                let literal_ch = if ch == '\\' {
+                    chars.next().unwrap_or('\\')
+                } else {
+                    ch
+                };
+                if matches!(literal_ch, '\\' | '*' | '?') {
+                    wildcard_pattern.push('\\');
+                }
+                wildcard_pattern.push(literal_ch);
+            }
+            _ => wildcard_pattern.push(ch),
+        }
+    }
+    has_wildcard.then_some(wildcard_pattern)
+}
+
+/// Unescapes a `LIKE` pattern without any wildcard.
+fn like_pattern_to_term(pattern: &str) -> String {
+    let mut term = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            term.push(chars.next().unwrap_or('\\'));
+        } else {
+            term.push(ch);
+        }
+    }
+    term
+}
+
+fn condition_to_query_ast(condition: &Condition) -> Result<QueryAst, SearchError> {
+    let query_ast = match condition {
+        Condition::And(left, right) => BoolQuery {
+            must: vec![
+                condition_to_query_ast(left)?,
+                condition_to_query_ast(right)?,
+            ],
+            ..Default::default()
+        }
+        .into(),
+        Condition::Or(left, right) => BoolQuery {
+            should: vec![
+                condition_to_query_ast(left)?,
+                condition_to_query_ast(right)?,
+            ],
+            ..Default::default()
+        }
+        .into(),
+        Condition::Not(condition) => negate(condition_to_query_ast(condition)?),
+        Condition::Comparison {
+            column,
+            operator,
+            value,
+        } => {
+            let (lower_bound, upper_bound) = match operator {
+                ComparisonOperator::Eq | ComparisonOperator::NotEq => {
+                    let term_query: QueryAst = TermQuery {
+                        field: column.clone(),
+                        value: value.to_text(),
+                    }
+                    .into();
+                    if *operator == ComparisonOperator::NotEq {
+                        return Ok(negate(term_query));
+                    }
+                    return Ok(term_query);
+                }
+                ComparisonOperator::Lt => (
+                    Bound::Unbounded,
+                    Bound::Excluded(literal_to_json_literal(value)?),
+                ),
+                ComparisonOperator::LtEq => (
+                    Bound::Unbounded,
+                    Bound::Included(literal_to_json_literal(value)?),
+                ),
+                ComparisonOperator::Gt => (
+                    Bound::Excluded(literal_to_json_literal(value)?),
+                    Bound::Unbounded,
+                ),
+                ComparisonOperator::GtEq => (
+                    Bound::Included(literal_to_json_literal(value)?),
+                    Bound::Unbounded,
+                ),
+            };
+            RangeQuery {
+                field: column.clone(),
+                lower_bound,
+                upper_bound,
+            }
+            .into()
+        }
+        Condition::Between {
+            column,
+            low,
+            high,
+            negated,
+        } => {
+            let range_query: QueryAst = RangeQuery {
+                field: column.clone(),
+                lower_bound: Bound::Included(literal_to_json_literal(low)?),
+                upper_bound: Bound::Included(literal_to_json_literal(high)?),
+            }
+            .into();
+            if *negated {
+                negate(range_query)
+            } else {
+                range_query
+            }
+        }
+        Condition::InList {
+            column,
+            values,
+            negated,
+        } => {
+            let terms: BTreeSet<String> = values.iter().map(Literal::to_text).collect();
+            let terms_per_field = HashMap::from([(column.clone(), terms)]);
+            let term_set_query: QueryAst = TermSetQuery { terms_per_field }.into();
+            if *negated {
+                negate(term_set_query)
+            } else {
+                term_set_query
+            }
+        }
+        Condition::IsNull { column, negated } => {
+            let field_presence_query: QueryAst = FieldPresenceQuery {
+                field: column.clone(),
+            }
+            .into();
+            if *negated {
+                field_presence_query
+            } else {
+                negate(field_presence_query)
+            }
+        }
+        Condition::Like {
+            column,
+            pattern,
+            negated,
+        } => {
+            let like_query: QueryAst = if let Some(value) = like_pattern_to_wildcard(pattern) {
+                WildcardQuery {
+                    field: column.clone(),
+                    value,
+                    allow_leading_wildcard: false,
+                }
+                .into()
+            } else {
+                TermQuery {
+                    field: column.clone(),
+                    value: like_pattern_to_term(pattern),
+                }
+                .into()
+            };
+            if *negated {
+                negate(like_query)
+            } else {
+                like_query
+            }
+        }
+        Condition::Match { column, text } => FullTextQuery {
+            field: column.clone(),
+            text: text.clone(),
+            params: FullTextParams {
+                tokenizer: None,
+                mode: FullTextMode::Bool {
+                    operator: BooleanOperand::Or,
+                    minimum_should_match: None,
+                },
+                zero_terms_query: MatchAllOrNone::MatchNone,
+            },
+            lenient: false,
+        }
+        .into(),
+        Condition::Query { user_text } => UserInputQuery {
+            user_text: user_text.clone(),
+            default_fields: None,
+            default_operator: BooleanOperand::And,
+            lenient: false,
+            allow_leading_wildcard: false,
+        }
+        .into(),
+    };
+    Ok(query_ast)
+}
+
+/// Describes how to turn the search response into rows.
+#[derive(Debug)]
+enum Projection {
+    Hits {
+        select_items: Vec<SelectItem>,
+    },
+    Aggregates {
+        select_items: Vec<SelectItem>,
+        order_by: Vec<OrderByItem>,
+        limit_opt: Option<u64>,
+        offset: u64,
+    },
+    Groups {
+        group_by: Vec<String>,
+        select_items: Vec<SelectItem>,
+        order_by: Vec<OrderByItem>,
+        limit_opt: Option<u64>,
+        offset: u64,
+    },
+}
+
+/// A SQL statement translated into a search request.
+#[derive(Debug)]
+pub(crate) struct SqlPlan {
+    pub search_request: SearchRequest,
+    projection: Projection,
+}
+
+fn aggregate_key(select_item_ord: usize) -> String {
+    format!("agg_{select_item_ord}")
+}
+
+fn group_key(group_ord: usize) -> String {
+    format!("group_{group_ord}")
+}
+
+/// Builds the metric aggregations computed for each select item, `COUNT(*)` excepted as it is
+/// given by the number of hits or the bucket document count.
+fn metric_aggregations(select_items: &[SelectItem]) -> JsonMap<String, JsonValue> {
+    let mut aggregations = JsonMap::new();
+
+    for (select_item_ord, select_item) in select_items.iter().enumerate() {
+        let SelectItem::Aggregate {
+            aggregate:
+                AggregateCall {
+                    function,
+                    column: Some(column),
+                },
+            ..
+        } = select_item
+        else {
+            continue;
+        };
+        let aggregation_type = match function {
+            AggregateFunction::Count => "value_count",
+            AggregateFunction::Sum => "sum",
+            AggregateFunction::Avg => "avg",
+            AggregateFunction::Min => "min",
+            AggregateFunction::Max => "max",
+        };
+        aggregations.insert(
+            aggregate_key(select_item_ord),
+            json!({ aggregation_type: { "field": column } }),
+        );
+    }
+    aggregations
+}
+
+/// Resolves the `ORDER BY` keys of a statement returning hits into sort fields.
+fn hits_sort_fields(select_items: &[SelectItem], order_by: &[OrderByItem]) -> Vec<SortField> {
+    let mut sort_fields = Vec::with_capacity(order_by.len());
+
+    for order_by_item in order_by {
+        let field_name = select_items
+            .iter()
+            .find_map(|select_item| match select_item {
+                SelectItem::Column {
+                    column,
+                    alias: Some(alias),
+                } if *alias == order_by_item.key => Some(column.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| order_by_item.key.clone());
+        let sort_order = if order_by_item.descending {
+            SortOrder::Desc
+        } else {
+            SortOrder::Asc
+        };
+        sort_fields.push(SortField {
+            field_name,
+            sort_order: sort_order as i32,
+            sort_datetime_format: None,
+            missing: SortMissing::Last as i32,
+        });
+    }
+    sort_fields
+}
+
+/// Translates a SQL statement into a search request.
+pub(crate) fn plan_sql_statement(statement: SelectStatement) -> Result<SqlPlan, SearchError> {
+    let SelectStatement {
+        select_items,
+        index_id,
+        condition_opt,
+        group_by,
+        order_by,
+        limit_opt,
+        offset_opt,
+    } = statement;
+
+    let query_ast = match &condition_opt {
+        Some(condition) => condition_to_query_ast(condition)?,
+        None => QueryAst::MatchAll,
+    };
+    let query_ast_json = serde_json::to_string(&query_ast)?;
+    let mut search_request = SearchRequest {
+        index_id_patterns: vec![index_id],
+        query_ast: query_ast_json,
+        ..Default::default()
+    };
+    let has_aggregates = select_items
+        .iter()
+        .any(|select_item| matches!(select_item, SelectItem::Aggregate { .. }));
+
+    if !has_aggregates && group_by.is_empty() {
+        search_request.max_hits = limit_opt.unwrap_or(DEFAULT_SQL_LIMIT);
+        search_request.start_offset = offset_opt.unwrap_or(0);
+        search_request.sort_fields = hits_sort_fields(&select_items, &order_by);
+        search_request.count_hits = CountHits::Underestimate as i32;
+        let projection = Projection::Hits { select_items };
+        return Ok(SqlPlan {
+            search_request,
+            projection,
+        });
+    }
+    for select_item in &select_items {
+        match select_item {
+            SelectItem::Wildcard => {
+                return Err(invalid_query(
+                    "`*` cannot be selected in a query with aggregate functions or a `GROUP BY` \
+                     clause",
+                ));
+            }
+            SelectItem::Column { column, .. } if !group_by.contains(column) => {
+                return Err(invalid_query(format!(
+                    "column `{column}` must appear in the `GROUP BY` clause or be used in an \
+                     aggregate function"
+                )));
+            }
+            _ => {}
+        }
+    }
+    for order_by_item in &order_by {
+        resolve_output_column(&select_items, &order_by_item.key)?;
+    }
+    search_request.max_hits = 0;
+    search_request.count_hits = CountHits::CountAll as i32;
+    let metric_aggregations = metric_aggregations(&select_items);
+
+    if group_by.is_empty() {
+        if !metric_aggregations.is_empty() {
+            search_request.aggregation_request = Some(serde_json::to_string(&metric_aggregations)?);
+        }
+        let projection = Projection::Aggregates {
+            select_items,
+            order_by,
+            limit_opt,
+            offset: offset_opt.unwrap_or(0),
+        };
+        return Ok(SqlPlan {
+            search_request,
+            projection,
+        });
+    }
+    let mut aggregations = metric_aggregations;
+
+    for (group_ord, column) in group_by.iter().enumerate().rev() {
+        let mut terms_aggregation = json!({
+            "terms": {
+                "field": column,
+                "size": MAX_GROUP_BY_BUCKETS,
+            }
+        });
+        if !aggregations.is_empty() {
+            terms_aggregation["aggs"] = JsonValue::Object(aggregations);
+        }
+        aggregations = JsonMap::from_iter([(group_key(group_ord), terms_aggregation)]);
+    }
+    search_request.aggregation_request = Some(serde_json::to_string(&aggregations)?);
+
+    let projection = Projection::Groups {
+        group_by,
+        select_items,
+        order_by,
+        limit_opt,
+        offset: offset_opt.unwrap_or(0),
+    };
+    Ok(SqlPlan {
+        search_request,
+        projection,
+    })
+}
+
+/// Returns the position of the result set column matching an `ORDER BY` key.
+fn resolve_output_column(select_items: &[SelectItem], key: &str) -> Result<usize, SearchError> {
+    let position_opt = select_items
+        .iter()
+        .position(|select_item| select_item.output_name() == key)
+        .or_else(|| {
+            select_items
+                .iter()
+                .position(|select_item| match select_item {
+                    SelectItem::Column { column, .. } => column == key,
+                    SelectItem::Aggregate { aggregate, .. } => aggregate.to_string() == key,
+                    SelectItem::Wildcard => false,
+                })
+        });
+    position_opt.ok_or_else(|| {
+        invalid_query(format!(
+            "`ORDER BY` key `{key}` must be a column of the result set"
+        ))
+    })
+}
+
+/// Looks up a column in a document, first as a key, then as a path of nested keys.
+pub(super) fn lookup_column<'a>(document: &'a JsonValue, column: &str) -> Option<&'a JsonValue> {
+    if let Some(value) = document.get(column) {
+        return Some(value);
+    }
+    let mut value = document;
+    for key in column.split('.') {
+        value = value.get(key)?;
+    }
+    Some(value)
+}
+
+fn metric_value(aggregations: &JsonValue, select_item_ord: usize) -> JsonValue {
+    aggregations
+        .get(aggregate_key(select_item_ord))
+        .and_then(|metric| metric.get("value"))
+        .cloned()
+        .unwrap_or(JsonValue::Null)
+}
+
+fn type_rank(value: &JsonValue) -> u8 {
+    match value {
+        JsonValue::Bool(_) => 0,
+        JsonValue::Number(_) => 1,
+        JsonValue::String(_) => 2,
+        JsonValue::Array(_) => 3,
+        JsonValue::Object(_) => 4,
+        JsonValue::Null => 5,
+    }
+}
+
+/// Orders values by type, then by value. Nulls come last.
+fn compare_json_values(left: &JsonValue, right: &JsonValue) -> Ordering {
+    match (left, right) {
+        (JsonValue::Bool(left), JsonValue::Bool(right)) => left.cmp(right),
+        (JsonValue::Number(left), JsonValue::Number(right)) => {
+            let left = left.as_f64().unwrap_or(f64::NAN);
+            let right = right.as_f64().unwrap_or(f64::NAN);
+            left.total_cmp(&right)
+        }
+        (JsonValue::String(left), JsonValue::String(right)) => left.cmp(right),
+        _ => type_rank(left).cmp(&type_rank(right)),
+    }
+}
+
+fn sort_and_paginate_rows(
+    rows: &mut Vec<Vec<JsonValue>>,
+    select_items: &[SelectItem],
+    order_by: &[OrderByItem],
+    limit_opt: Option<u64>,
+    offset: u64,
+) -> Result<(), SearchError> {
+    let mut sort_keys = Vec::with_capacity(order_by.len());
+
+    for order_by_item in order_by {
+        let position = resolve_output_column(select_items, &order_by_item.key)?;
+        sort_keys.push((position, order_by_item.descending));
+    }
+    if !sort_keys.is_empty() {
+        rows.sort_by(|left_row, right_row| {
+            for &(position, descending) in &sort_keys {
+                let ordering = compare_json_values(&left_row[position], &right_row[position]);
+                let ordering = if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                };
+                if ordering.is_ne() {
+                    return ordering;
+                }
+            }
+            Ordering::Equal
+        });
+    }
+    let offset = (offset as usize).min(rows.len());
+    rows.drain(..offset);
+
+    if let Some(limit) = limit_opt {
+        rows.truncate(limit as usize);
+    }
+    Ok(())
+}
+
+/// Walks the nested terms aggregation buckets, emitting one row per leaf bucket.
+fn collect_group_rows(
+    aggregations: &JsonValue,
+    group_ord: usize,
+    group_by: &[String],
+    group_values: &mut Vec<JsonValue>,
+    select_items: &[SelectItem],
+    rows: &mut Vec<Vec<JsonValue>>,
+) {
+    let Some(buckets) = aggregations
+        .get(group_key(group_ord))
+        .and_then(|terms_aggregation| terms_aggregation.get("buckets"))
+        .and_then(JsonValue::as_array)
+    else {
+        return;
+    };
+    for bucket in buckets {
+        let group_value = bucket
+            .get("key_as_string")
+            .or_else(|| bucket.get("key"))
+            .cloned()
+            .unwrap_or(JsonValue::Null);
+        group_values.push(group_value);
+
+        if group_ord + 1 < group_by.len() {
+            collect_group_rows(
+                bucket,
+                group_ord + 1,
+                group_by,
+                group_values,
+                select_items,
+                rows,
+            );
+        } else {
+            let doc_count = bucket.get("doc_count").cloned().unwrap_or(json!(0));
+            let row = select_items
+                .iter()
+                .enumerate()
+                .map(|(select_item_ord, select_item)| match select_item {
+                    SelectItem::Column { column, .. } => {
+                        let group_ord = group_by
+                            .iter()
+                            .position(|group_column| group_column == column)
+                            .expect("selected columns should be part of the `GROUP BY` clause");
+                        group_values[group_ord].clone()
+                    }
+                    SelectItem::Aggregate { aggregate, .. } if aggregate.column.is_none() => {
+                        doc_count.clone()
+                    }
+                    SelectItem::Aggregate { .. } => metric_value(bucket, select_item_ord),
+                    SelectItem::Wildcard => JsonValue::Null,
+                })
+                .collect();
+            rows.push(row);
+        }
+        group_values.pop();
+    }
+}
+
+fn output_columns(select_items: &[SelectItem]) -> Vec<SqlColumn> {
+    select_items
+        .iter()
+        .map(|select_item| SqlColumn {
+            name: select_item.output_name(),
+        })
+        .collect()
+}
+
+impl SqlPlan {
+    /// Turns the response of the search request into the result set of the statement.
+    pub fn into_sql_response(
+        self,
+        search_response: SearchResponseRest,
+    ) -> Result<SqlResponse, SearchError> {
+        match self.projection {
+            Projection::Hits { select_items } => {
+                let mut column_names: Vec<(String, String)> = Vec::new();
+
+                for select_item in &select_items {
+                    match select_item {
+                        SelectItem::Column { column, .. } => {
+                            column_names.push((column.clone(), select_item.output_name()));
+                        }
+                        SelectItem::Wildcard => {
+                            let mut seen_keys: HashSet<&str> = HashSet::new();
+                            for hit in &search_response.hits {
+                                let Some(document) = hit.as_object() else {
+                                    continue;
+                                };
+                                for key in document.keys() {
+                                    if seen_keys.insert(key) {
+                                        column_names.push((key.clone(), key.clone()));
+                                    }
+                                }
+                            }
+                        }
+                        SelectItem::Aggregate { .. } => {
+                            unreachable!("hits are only fetched for statements without aggregates")
+                        }
+                    }
+                }
+                let rows = search_response
+                    .hits
+                    .iter()
+                    .map(|hit| {
+                        column_names
+                            .iter()
+                            .map(|(column, _)| {
+                                lookup_column(hit, column).cloned().unwrap_or_default()
+                            })
+                            .collect()
+                    })
+                    .collect();
+                let columns = column_names
+                    .into_iter()
+                    .map(|(_, name)| SqlColumn { name })
+                    .collect();
+                Ok(SqlResponse { columns, rows })
+            }
+            Projection::Aggregates {
+                select_items,
+                order_by,
+                limit_opt,
+                offset,
+            } => {
+                let aggregations = search_response.aggregations.unwrap_or_default();
+                let row = select_items
+                    .iter()
+                    .enumerate()
+                    .map(|(select_item_ord, select_item)| match select_item {
+                        SelectItem::Aggregate { aggregate, .. } if aggregate.column.is_none() => {
+                            json!(search_response.num_hits)
+                        }
+                        _ => metric_value(&aggregations, select_item_ord),
+                    })
+                    .collect();
+                let mut rows = vec![row];
+                sort_and_paginate_rows(&mut rows, &select_items, &order_by, limit_opt, offset)?;
+                let columns = output_columns(&select_items);
+                Ok(SqlResponse { columns, rows })
+            }
+            Projection::Groups {
+                group_by,
+                select_items,
+                order_by,
+                limit_opt,
+                offset,
+            } => {
+                let aggregations = search_response.aggregations.unwrap_or_default();
+                let mut rows = Vec::new();
+                collect_group_rows(
+                    &aggregations,
+                    0,
+                    &group_by,
+                    &mut Vec::with_capacity(group_by.len()),
+                    &select_items,
+                    &mut rows,
+                );
+                sort_and_paginate_rows(&mut rows, &select_items, &order_by, limit_opt, offset)?;
+                let columns = output_columns(&select_items);
+                Ok(SqlResponse { columns, rows })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql_api::parser::parse_sql;
+
+    fn plan(sql: &str) -> SqlPlan {
+        let statement = parse_sql(sql).unwrap();
+        plan_sql_statement(statement).unwrap()
+    }
+
+    fn search_response(
+        num_hits: u64,
+        hits: Vec<JsonValue>,
+        aggregations: Option<JsonValue>,
+    ) -> SearchResponseRest {
+        SearchResponseRest {
+            num_hits,
+            hits,
+            snippets: None,
+            inner_hits: None,
+            explanations: None,
+            elapsed_time_micros: 0,
+            errors: Vec::new(),
+            aggregations,
+            search_after: None,
+            timed_out: false,
+            timed_out_splits: Vec::new(),
+            split_profiles: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_like_pattern_to_wildcard() {
+        assert_eq!(like_pattern_to_wildcard("quick"), None);
+        assert_eq!(like_pattern_to_wildcard("quick%").unwrap(), "quick*");
+        assert_eq!(like_pattern_to_wildcard("%ick_it").unwrap(), "*ick?it");
+        assert_eq!(like_pattern_to_wildcard("100\\%%").unwrap(), "100%*");
+        assert_eq!(like_pattern_to_wildcard("what?%").unwrap(), "what\\?*");
+        assert_eq!(like_pattern_to_term("100\\%"), "100%");
+    }
+
+    #[test]
+    fn test_plan_sql_statement_where() {
+        let sql_plan = plan(
+            "SELECT * FROM logs WHERE severity_text = 'ERROR' AND status >= 500 AND service NOT \
+             IN ('api') AND trace_id IS NULL AND host LIKE 'db-%' AND MATCH(body, 'timeout') OR \
+             QUERY('tenant:acme')",
+        );
+        let query_ast: QueryAst = serde_json::from_str(&sql_plan.search_request.query_ast).unwrap();
+        let term_query: QueryAst = TermQuery {
+            field: "severity_text".to_string(),
+            value: "ERROR".to_string(),
+        }
+        .into();
+        let range_query: QueryAst = RangeQuery {
+            field: "status".to_string(),
+            lower_bound: Bound::Included(JsonLiteral::Number(500.into())),
+            upper_bound: Bound::Unbounded,
+        }
+        .into();
+        let term_set_query: QueryAst = TermSetQuery {
+            terms_per_field: HashMap::from([(
+                "service".to_string(),
+                BTreeSet::from(["api".to_string()]),
+            )]),
+        }
+        .into();
+        let field_presence_query: QueryAst = FieldPresenceQuery {
+            field: "trace_id".to_string(),
+        }
+        .into();
+        let wildcard_query: QueryAst = WildcardQuery {
+            field: "host".to_string(),
+            value: "db-*".to_string(),
+            allow_leading_wildcard: false,
+        }
+        .into();
+        let QueryAst::Bool(BoolQuery { should, .. }) = query_ast else {
+            panic!("expected a bool query");
+        };
+        assert_eq!(should.len(), 2);
+        assert!(
+            matches!(&should[1], QueryAst::UserInput(user_input_query) if user_input_query.user_text == "tenant:acme")
+        );
+
+        let mut conjunction = &should[0];
+        let mut clauses = Vec::new();
+        while let QueryAst::Bool(BoolQuery { must, .. }) = conjunction {
+            if must.len() != 2 {
+                break;
+            }
+            clauses.push(&must[1]);
+            conjunction = &must[0];
+        }
+        clauses.push(conjunction);
+        clauses.reverse();
+
+        assert_eq!(clauses.len(), 6);
+        assert_eq!(clauses[0], &term_query);
+        assert_eq!(clauses[1], &range_query);
+        assert_eq!(clauses[2], &negate(term_set_query));
+        assert_eq!(clauses[3], &negate(field_presence_query));
+        assert_eq!(clauses[4], &wildcard_query);
+        assert!(
+            matches!(clauses[5], QueryAst::FullText(full_text_query) if full_text_query.field == "body" && full_text_query.text == "timeout")
+        );
+    }
+
+    #[test]
+    fn test_plan_sql_statement_hits() {
+        let sql_plan = plan(
+            "SELECT timestamp AS ts, body, resource.service FROM logs ORDER BY ts DESC LIMIT 2 \
+             OFFSET 10",
+        );
+        assert_eq!(sql_plan.search_request.index_id_patterns, vec!["logs"]);
+        assert_eq!(sql_plan.search_request.max_hits, 2);
+        assert_eq!(sql_plan.search_request.start_offset, 10);
+        assert_eq!(sql_plan.search_request.aggregation_request, None);
+        assert_eq!(
+            sql_plan.search_request.sort_fields,
+            vec![SortField {
+                field_name: "timestamp".to_string(),
+                sort_order: SortOrder::Desc as i32,
+                sort_datetime_format: None,
+                missing: SortMissing::Last as i32,
+            }]
+        );
+        let hits = vec![
+            json!({"timestamp": 2, "body": "foo", "resource": {"service": "api"}}),
+            json!({"timestamp": 1}),
+        ];
+        let sql_response = sql_plan
+            .into_sql_response(search_response(100, hits, None))
+            .unwrap();
+        let column_names: Vec<&str> = sql_response
+            .columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect();
+        assert_eq!(column_names, ["ts", "body", "resource.service"]);
+        assert_eq!(
+            sql_response.rows,
+            vec![
+                vec![json!(2), json!("foo"), json!("api")],
+                vec![json!(1), JsonValue::Null, JsonValue::Null],
+            ]
+        );
+
+        let sql_plan = plan("SELECT * FROM logs");
+        assert_eq!(sql_plan.search_request.max_hits, DEFAULT_SQL_LIMIT);
+        let hits = vec![json!({"a": 1, "b": 2}), json!({"b": 3, "c": 4})];
+        let sql_response = sql_plan
+            .into_sql_response(search_response(2, hits, None))
+            .unwrap();
+        let column_names: Vec<&str> = sql_response
+            .columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect();
+        assert_eq!(column_names, ["a", "b", "c"]);
+        assert_eq!(
+            sql_response.rows,
+            vec![
+                vec![json!(1), json!(2), JsonValue::Null],
+                vec![JsonValue::Null, json!(3), json!(4)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_sql_statement_aggregates() {
+        let sql_plan = plan("SELECT COUNT(*), avg(latency) AS avg_latency FROM logs");
+        assert_eq!(sql_plan.search_request.max_hits, 0);
+        let aggregation_request: JsonValue = serde_json::from_str(
+            sql_plan
+                .search_request
+                .aggregation_request
+                .as_ref()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            aggregation_request,
+            json!({"agg_1": {"avg": {"field": "latency"}}})
+        );
+        let aggregations = json!({"agg_1": {"value": 12.5}});
+        let sql_response = sql_plan
+            .into_sql_response(search_response(42, Vec::new(), Some(aggregations)))
+            .unwrap();
+        assert_eq!(sql_response.columns[0].name, "count(*)");
+        assert_eq!(sql_response.columns[1].name, "avg_latency");
+        assert_eq!(sql_response.rows, vec![vec![json!(42), json!(12.5)]]);
+    }
+
+    #[test]
+    fn test_plan_sql_statement_group_by() {
+        let sql_plan = plan(
+            "SELECT service, level, COUNT(*) AS hits, max(latency) FROM logs GROUP BY service, \
+             level ORDER BY hits DESC, service LIMIT 3",
+        );
+        let aggregation_request: JsonValue = serde_json::from_str(
+            sql_plan
+                .search_request
+                .aggregation_request
+                .as_ref()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            aggregation_request,
+            json!({
+                "group_0": {
+                    "terms": {"field": "service", "size": MAX_GROUP_BY_BUCKETS},
+                    "aggs": {
+                        "group_1": {
+                            "terms": {"field": "level", "size": MAX_GROUP_BY_BUCKETS},
+                            "aggs": {
+                                "agg_3": {"max": {"field": "latency"}}
+                            }
+                        }
+                    }
+                }
+            })
+        );
+        let aggregations = json!({
+            "group_0": {
+                "buckets": [
+                    {
+                        "key": "api",
+                        "doc_count": 5,
+                        "group_1": {
+                            "buckets": [
+                                {"key": "info", "doc_count": 3, "agg_3": {"value": 10.0}},
+                                {"key": "error", "doc_count": 2, "agg_3": {"value": 30.0}},
+                            ]
+                        }
+                    },
+                    {
+                        "key": "web",
+                        "doc_count": 4,
+                        "group_1": {
+                            "buckets": [
+                                {"key": "info", "doc_count": 4, "agg_3": {"value": 20.0}},
+                            ]
+                        }
+                    }
+                ]
+            }
+        });
+        let sql_response = sql_plan
+            .into_sql_response(search_response(9, Vec::new(), Some(aggregations)))
+            .unwrap();
+        let column_names: Vec<&str> = sql_response
+            .columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect();
+        assert_eq!(column_names, ["service", "level", "hits", "max(latency)"]);
+        assert_eq!(
+            sql_response.rows,
+            vec![
+                vec![json!("web"), json!("info"), json!(4), json!(20.0)],
+                vec![json!("api"), json!("info"), json!(3), json!(10.0)],
+                vec![json!("api"), json!("error"), json!(2), json!(30.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_sql_statement_errors() {
+        let plan_error = |sql: &str| {
+            let statement = parse_sql(sql).unwrap();
+            plan_sql_statement(statement).unwrap_err().to_string()
+        };
+        assert_eq!(
+            plan_error("SELECT service, COUNT(*) FROM logs"),
+            "column `service` must appear in the `GROUP BY` clause or be used in an aggregate \
+             function"
+        );
+        assert_eq!(
+            plan_error("SELECT *, COUNT(*) FROM logs"),
+            "`*` cannot be selected in a query with aggregate functions or a `GROUP BY` clause"
+        );
+        assert_eq!(
+            plan_error("SELECT service FROM logs GROUP BY service ORDER BY latency"),
+            "`ORDER BY` key `latency` must be a column of the result set"
+        );
+        assert_eq!(
+            plan_error("SELECT * FROM logs WHERE latency > 1x"),
+            "invalid number `1x`"
+        );
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use quickwit_search::{SearchError, SearchService};
+use tracing::info;
+use warp::hyper::header::CONTENT_TYPE;
+use warp::reply::Response;
+use warp::{Filter, Rejection, Reply};
+
+use super::{execute_sql_query, SqlRequest, SqlResponse, SqlResponseFormat};
+use crate::rest::recover_fn;
+use crate::rest_api_response::into_rest_api_response;
+use crate::{with_arg, BodyFormat};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(sql_query_handler),
+    components(schemas(SqlRequest, SqlResponse, SqlResponseFormat, super::SqlColumn))
+)]
+pub(crate) struct SqlApi;
+
+pub(crate) fn sql_api_handlers(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    sql_query_handler(search_service).recover(recover_fn)
+}
+
+fn sql_query_filter() -> impl Filter<Extract = (SqlRequest,), Error = Rejection> + Clone {
+    warp::path!("_sql")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::json())
+}
+
+#[utoipa::path(
+    post,
+    tag = "Search",
+    path = "/_sql",
+    request_body = SqlRequest,
+    responses(
+        (status = 200, description = "Successfully executed the SQL query.", body = SqlResponse)
+    ),
+)]
+/// SQL Query
+///
+/// Runs a SQL `SELECT` statement against an index and returns the result set as JSON or CSV.
+pub(crate) fn sql_query_handler(
+    search_service: Arc<dyn SearchService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    sql_query_filter()
+        .and(with_arg(search_service))
+        .then(sql_query)
+}
+
+async fn sql_query(sql_request: SqlRequest, search_service: Arc<dyn SearchService>) -> Response {
+    info!(query=%sql_request.query, "sql");
+    let result: Result<SqlResponse, SearchError> =
+        execute_sql_query(&sql_request.query, &*search_service).await;
+    let body_format = match sql_request.format {
+        SqlResponseFormat::Json => BodyFormat::Json,
+        SqlResponseFormat::PrettyJson | SqlResponseFormat::Csv => BodyFormat::PrettyJson,
+    };
+    match result {
+        Ok(sql_response) if sql_request.format == SqlResponseFormat::Csv => {
+            warp::reply::with_header(sql_response.to_csv(), CONTENT_TYPE, "text/csv")
+                .into_response()
+        }
+        result => into_rest_api_response(result, body_format).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_proto::search::{Hit, SearchResponse};
+    use quickwit_search::MockSearchService;
+    use serde_json::{json, Value as JsonValue};
+
+    use super::*;
+
+    fn mock_search_service() -> MockSearchService {
+        let mut mock_search_service = MockSearchService::new();
+        mock_search_service
+            .expect_root_search()
+            .returning(|search_request| {
+                assert_eq!(search_request.index_id_patterns, vec!["logs"]);
+                assert_eq!(search_request.max_hits, 10);
+                Ok(SearchResponse {
+                    num_hits: 2,
+                    hits: vec![
+                        Hit {
+                            json: r#"{"severity_text": "ERROR", "body": "foo, bar"}"#.to_string(),
+                            ..Default::default()
+                        },
+                        Hit {
+                            json: r#"{"severity_text": "WARN"}"#.to_string(),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                })
+            });
+        mock_search_service
+    }
+
+    #[tokio::test]
+    async fn test_sql_api_json() {
+        let sql_handler = sql_api_handlers(Arc::new(mock_search_service()));
+        let response = warp::test::request()
+            .path("/_sql")
+            .method("POST")
+            .json(&json!({
+                "query": "SELECT severity_text, body FROM logs WHERE severity_text IN ('ERROR', \
+                          'WARN') LIMIT 10",
+            }))
+            .reply(&sql_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+        let sql_response: JsonValue = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(
+            sql_response,
+            json!({
+                "columns": [{"name": "severity_text"}, {"name": "body"}],
+                "rows": [["ERROR", "foo, bar"], ["WARN", null]],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sql_api_csv() {
+        let sql_handler = sql_api_handlers(Arc::new(mock_search_service()));
+        let response = warp::test::request()
+            .path("/_sql")
+            .method("POST")
+            .json(&json!({
+                "query": "SELECT severity_text, body FROM logs LIMIT 10",
+                "format": "csv",
+            }))
+            .reply(&sql_handler)
+            .await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()[CONTENT_TYPE], "text/csv");
+        assert_eq!(
+            response.body(),
+            "severity_text,body\nERROR,\"foo, bar\"\nWARN,\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sql_api_invalid_query() {
+        let sql_handler = sql_api_handlers(Arc::new(MockSearchService::new()));
+        let response = warp::test::request()
+            .path("/_sql")
+            .method("POST")
+            .json(&json!({"query": "SELECT FROM logs"}))
+            .reply(&sql_handler)
+            .await;
+        assert_eq!(response.status(), 400);
+        let error_response: JsonValue = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(
+            error_response,
+            json!({
+                "message": "failed to parse SQL query: expected identifier, found keyword `FROM`"
+            })
+        );
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::Stream;
+use quickwit_proto::tonic::transport::server::{Connected, TcpConnectInfo};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, error};
+
+/// Maximum amount of time a client has to complete the TLS handshake.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Content type of the TLS records carrying handshake messages. A TLS client always starts the
+/// connection with such a record, whereas plaintext HTTP/1.1 and HTTP/2 requests start with an
+/// ASCII letter.
+const TLS_HANDSHAKE_CONTENT_TYPE: u8 = 0x16;
+
+/// Accepts TCP connections from `tcp_listener` and performs the TLS handshake for each of them.
+/// If `allow_plaintext` is true, the connections that do not start with a TLS handshake are
+/// accepted as plaintext connections.
+///
+/// Handshakes run concurrently so that a slow or malicious client cannot stall the accept loop.
+/// Failed handshakes are logged and dropped: they never surface as errors in the returned stream,
+/// which would otherwise shut down the server consuming it.
+pub(crate) fn tls_incoming(
+    tcp_listener: TcpListener,
+    tls_acceptor: TlsAcceptor,
+    allow_plaintext: bool,
+) -> impl Stream<Item = io::Result<MaybeTlsStream>> {
+    let (stream_tx, stream_rx) = mpsc::channel(128);
+
+    tokio::spawn(async move {
+        loop {
+            let accept_res = tokio::select! {
+                accept_res = tcp_listener.accept() => accept_res,
+                _ = stream_tx.closed() => break,
+            };
+            let (tcp_stream, peer_addr) = match accept_res {
+                Ok(accepted) => accepted,
+                Err(error) => {
+                    error!(error=?error, "failed to accept TCP connection");
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            };
+            if let Err(error) = tcp_stream.set_nodelay(true) {
+                debug!(error=?error, peer_addr=%peer_addr, "failed to set TCP_NODELAY");
+            }
+            let tls_acceptor = tls_acceptor.clone();
+            let stream_tx = stream_tx.clone();
+
+            tokio::spawn(async move {
+                let handshake_fut = async move {
+                    if allow_plaintext && !starts_with_tls_handshake(&tcp_stream).await? {
+                        return Ok(MaybeTlsStream::Plain(tcp_stream));
+                    }
+                    let tls_stream = tls_acceptor.accept(tcp_stream).await?;
+                    Ok::<_, io::Error>(MaybeTlsStream::Tls(tls_stream))
+                };
+                match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, handshake_fut).await {
+                    Ok(Ok(stream)) => {
+                        let _ = stream_tx.send(Ok(stream)).await;
+                    }
+                    Ok(Err(error)) => {
+                        debug!(error=?error, peer_addr=%peer_addr, "TLS handshake failed");
+                    }
+                    Err(_) => {
+                        debug!(peer_addr=%peer_addr, "TLS handshake timed out");
+                    }
+                }
+            });
+        }
+    });
+    ReceiverStream::new(stream_rx)
+}
+
+/// Peeks at the first byte sent by the client, without consuming it, to tell TLS connections from
+/// plaintext ones.
+async fn starts_with_tls_handshake(tcp_stream: &TcpStream) -> io::Result<bool> {
+    let mut first_byte = [0u8; 1];
+
+    if tcp_stream.peek(&mut first_byte).await? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(first_byte[0] == TLS_HANDSHAKE_CONTENT_TYPE)
+}
+
+/// Connection accepted by [`tls_incoming`], which can be served by hyper and tonic.
+pub(crate) enum MaybeTlsStream {
+    Plain(TcpStream),
+    Tls(TlsStream<TcpStream>),
+}
+
+impl Connected for MaybeTlsStream {
+    type ConnectInfo = TcpConnectInfo;
+
+    fn connect_info(&self) -> Self::ConnectInfo {
+        match self {
+            Self::Plain(tcp_stream) => tcp_stream.connect_info(),
+            Self::Tls(tls_stream) => tls_stream.get_ref().0.connect_info(),
+        }
+    }
+}
+
+impl AsyncRead for MaybeTlsStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(tcp_stream) => Pin::new(tcp_stream).poll_read(cx, buf),
+            Self::Tls(tls_stream) => Pin::new(tls_stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for MaybeTlsStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Plain(tcp_stream) => Pin::new(tcp_stream).poll_write(cx, buf),
+            Self::Tls(tls_stream) => Pin::new(tls_stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Plain(tcp_stream) => Pin::new(tcp_stream).poll_write_vectored(cx, bufs),
+            Self::Tls(tls_stream) => Pin::new(tls_stream).poll_write_vectored(cx, bufs),
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Self::Plain(tcp_stream) => tcp_stream.is_write_vectored(),
+            Self::Tls(tls_stream) => tls_stream.is_write_vectored(),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(tcp_stream) => Pin::new(tcp_stream).poll_flush(cx),
+            Self::Tls(tls_stream) => Pin::new(tls_stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(tcp_stream) => Pin::new(tcp_stream).poll_shutdown(cx),
+            Self::Tls(tls_stream) => Pin::new(tls_stream).poll_shutdown(cx),
+        }
+    }
+}
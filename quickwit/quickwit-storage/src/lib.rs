@@ -56,6 +56,7 @@ mod split;
 mod split_cache;
 mod storage_factory;
 mod storage_resolver;
+mod storage_with_download_counter;
 mod versioned_component;
 
 use quickwit_common::uri::Uri;
@@ -87,6 +88,7 @@ pub use self::storage::MockStorage;
 pub use self::storage_factory::MockStorageFactory;
 pub use self::storage_factory::{StorageFactory, UnsupportedStorage};
 pub use self::storage_resolver::StorageResolver;
+pub use self::storage_with_download_counter::wrap_storage_with_download_counter;
 #[cfg(feature = "integration-testsuite")]
 pub use self::test_suite::{
     storage_test_multi_part_upload, storage_test_single_part_upload, storage_test_suite,
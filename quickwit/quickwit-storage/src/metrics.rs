@@ -31,7 +31,9 @@ pub struct StorageMetrics {
     pub fd_cache_metrics: CacheMetrics,
     pub fast_field_cache: CacheMetrics,
     pub split_footer_cache: CacheMetrics,
+    pub split_bloom_filters_cache: CacheMetrics,
     pub searcher_split_cache: CacheMetrics,
+    pub search_result_cache: CacheMetrics,
     pub object_storage_get_total: IntCounter,
     pub object_storage_put_total: IntCounter,
     pub object_storage_put_parts: IntCounter,
@@ -46,8 +48,10 @@ impl Default for StorageMetrics {
             fd_cache_metrics: CacheMetrics::for_component("fd"),
             partial_request_cache: CacheMetrics::for_component("partial_request"),
             searcher_split_cache: CacheMetrics::for_component("searcher_split"),
+            search_result_cache: CacheMetrics::for_component("search_result"),
             shortlived_cache: CacheMetrics::for_component("shortlived"),
             split_footer_cache: CacheMetrics::for_component("splitfooter"),
+            split_bloom_filters_cache: CacheMetrics::for_component("split_bloom_filters"),
 
             object_storage_get_total: new_counter(
                 "object_storage_gets_total",
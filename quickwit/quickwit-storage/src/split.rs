@@ -28,7 +28,7 @@ use aws_sdk_s3::primitives::{ByteStream, SdkBody};
 use futures::{stream, Stream, StreamExt};
 use hyper::body::{Body, Bytes};
 use pin_project::pin_project;
-use quickwit_common::shared_consts::SPLIT_FIELDS_FILE_NAME;
+use quickwit_common::shared_consts::{SPLIT_BLOOM_FILTERS_FILE_NAME, SPLIT_FIELDS_FILE_NAME};
 use tokio::io::{AsyncReadExt, AsyncSeekExt};
 use tokio_util::io::ReaderStream;
 
@@ -145,10 +145,11 @@ pub struct SplitPayloadBuilder {
 }
 
 impl SplitPayloadBuilder {
-    /// Creates a new SplitPayloadBuilder for given files and hotcache.
+    /// Creates a new SplitPayloadBuilder for given files, Bloom filters, and hotcache.
     pub fn get_split_payload(
         split_files: &[PathBuf],
         serialized_split_fields: &[u8],
+        serialized_split_bloom_filters: &[u8],
         hotcache: &[u8],
     ) -> anyhow::Result<SplitPayload> {
         let mut split_payload_builder = SplitPayloadBuilder::default();
@@ -159,6 +160,13 @@ impl SplitPayloadBuilder {
             SPLIT_FIELDS_FILE_NAME.to_string(),
             Box::new(serialized_split_fields.to_vec()),
         );
+        // Splits of indexes without Bloom filter fields do not carry the file at all.
+        if !serialized_split_bloom_filters.is_empty() {
+            split_payload_builder.add_payload(
+                SPLIT_BLOOM_FILTERS_FILE_NAME.to_string(),
+                Box::new(serialized_split_bloom_filters.to_vec()),
+            );
+        }
         let offsets = split_payload_builder.finalize(hotcache)?;
         Ok(offsets)
     }
@@ -296,8 +304,12 @@ mod tests {
         let mut file2 = File::create(&test_filepath2)?;
         file2.write_all(b"world")?;
 
-        let split_payload =
-            SplitPayloadBuilder::get_split_payload(&[test_filepath1, test_filepath2], &[], b"abc")?;
+        let split_payload = SplitPayloadBuilder::get_split_payload(
+            &[test_filepath1, test_filepath2],
+            &[],
+            &[],
+            b"abc",
+        )?;
 
         assert_eq!(split_payload.len(), 128);
 
@@ -431,6 +443,7 @@ mod tests {
         let split_streamer = SplitPayloadBuilder::get_split_payload(
             &[test_filepath1.clone(), test_filepath2.clone()],
             &[],
+            &[],
             &[1, 2, 3],
         )?;
 
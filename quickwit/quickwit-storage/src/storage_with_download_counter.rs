@@ -0,0 +1,153 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use quickwit_common::uri::Uri;
+use tokio::io::AsyncRead;
+
+use crate::storage::SendableAsync;
+use crate::{BulkDeleteError, OwnedBytes, Storage, StorageResult};
+
+/// This storage acts as a proxy to another storage and counts the number of bytes read from it
+/// with `get_slice` and `get_all`.
+struct StorageWithDownloadCounter {
+    storage: Arc<dyn Storage>,
+    num_bytes_downloaded: Arc<AtomicU64>,
+}
+
+impl fmt::Debug for StorageWithDownloadCounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StorageWithDownloadCounter")
+            .field("uri", self.storage.uri())
+            .finish()
+    }
+}
+
+impl StorageWithDownloadCounter {
+    fn record_download(&self, bytes: &OwnedBytes) {
+        self.num_bytes_downloaded
+            .fetch_add(bytes.len() as u64, Ordering::Relaxed);
+    }
+}
+
+#[async_trait]
+impl Storage for StorageWithDownloadCounter {
+    async fn check_connectivity(&self) -> anyhow::Result<()> {
+        self.storage.check_connectivity().await
+    }
+
+    async fn put(&self, path: &Path, payload: Box<dyn crate::PutPayload>) -> StorageResult<()> {
+        self.storage.put(path, payload).await
+    }
+
+    async fn copy_to(&self, path: &Path, output: &mut dyn SendableAsync) -> StorageResult<()> {
+        self.storage.copy_to(path, output).await
+    }
+
+    async fn get_slice(&self, path: &Path, range: Range<usize>) -> StorageResult<OwnedBytes> {
+        let bytes = self.storage.get_slice(path, range).await?;
+        self.record_download(&bytes);
+        Ok(bytes)
+    }
+
+    async fn get_slice_stream(
+        &self,
+        path: &Path,
+        range: Range<usize>,
+    ) -> StorageResult<Box<dyn AsyncRead + Send + Unpin>> {
+        self.storage.get_slice_stream(path, range).await
+    }
+
+    async fn get_all(&self, path: &Path) -> StorageResult<OwnedBytes> {
+        let bytes = self.storage.get_all(path).await?;
+        self.record_download(&bytes);
+        Ok(bytes)
+    }
+
+    async fn delete(&self, path: &Path) -> StorageResult<()> {
+        self.storage.delete(path).await
+    }
+
+    async fn bulk_delete<'a>(&self, paths: &[&'a Path]) -> Result<(), BulkDeleteError> {
+        self.storage.bulk_delete(paths).await
+    }
+
+    async fn exists(&self, path: &Path) -> StorageResult<bool> {
+        self.storage.exists(path).await
+    }
+
+    async fn file_num_bytes(&self, path: &Path) -> StorageResult<u64> {
+        self.storage.file_num_bytes(path).await
+    }
+
+    fn uri(&self) -> &Uri {
+        self.storage.uri()
+    }
+}
+
+/// Wraps a storage so that the number of bytes read from it with `get_slice` and `get_all` is
+/// added to `num_bytes_downloaded`. The streamed reads are not counted.
+pub fn wrap_storage_with_download_counter(
+    storage: Arc<dyn Storage>,
+    num_bytes_downloaded: Arc<AtomicU64>,
+) -> Arc<dyn Storage> {
+    Arc::new(StorageWithDownloadCounter {
+        storage,
+        num_bytes_downloaded,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RamStorage;
+
+    #[tokio::test]
+    async fn test_storage_with_download_counter() {
+        let ram_storage = RamStorage::builder()
+            .put("split.split", b"split_payload")
+            .build();
+        let num_bytes_downloaded = Arc::new(AtomicU64::new(0));
+        let storage =
+            wrap_storage_with_download_counter(Arc::new(ram_storage), num_bytes_downloaded.clone());
+        let path = Path::new("split.split");
+
+        let bytes = storage.get_slice(path, 0..5).await.unwrap();
+        assert_eq!(&bytes[..], b"split");
+        assert_eq!(num_bytes_downloaded.load(Ordering::Relaxed), 5);
+
+        storage.get_all(path).await.unwrap();
+        assert_eq!(num_bytes_downloaded.load(Ordering::Relaxed), 18);
+
+        storage
+            .get_slice(Path::new("missing"), 0..5)
+            .await
+            .unwrap_err();
+        assert_eq!(num_bytes_downloaded.load(Ordering::Relaxed), 18);
+
+        assert_eq!(storage.file_num_bytes(path).await.unwrap(), 13);
+        assert_eq!(num_bytes_downloaded.load(Ordering::Relaxed), 18);
+    }
+}